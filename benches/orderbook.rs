@@ -0,0 +1,111 @@
+//! Order book update throughput
+//!
+//! Replays a pre-generated stream of price changes for one token the way the
+//! streaming service applies them (change, crossed-book check, read the
+//! touch) and reports updates per second. Full-market streaming needs each
+//! token's book to keep up with 100k updates/sec; the run fails if any
+//! scenario falls below that.
+//!
+//! Run with `cargo bench --bench orderbook`.
+
+use std::time::{Duration, Instant};
+
+use polybot::core::types::common::Side;
+use polybot::core::types::market::PriceLevel;
+use polybot::core::ws::OrderBook;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+
+/// Updates per second one token's book must sustain
+const TARGET_UPDATES_PER_SEC: f64 = 100_000.0;
+const UPDATES: usize = 1_000_000;
+const SNAPSHOTS: usize = 20_000;
+const ASSET_ID: &str = "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+
+/// Price changes clustered around the touch, a fifth of them removals
+fn changes(rng: &mut StdRng, scale: u32, count: usize) -> Vec<(Side, Decimal, Decimal)> {
+    let ticks = 10i64.pow(scale);
+    let mid = ticks / 2;
+    let spread = ticks / 100;
+    (0..count)
+        .map(|_| {
+            let depth = rng.random_range(0..spread * 10);
+            let (side, price) = if rng.random_bool(0.5) {
+                (Side::Buy, mid - spread - depth)
+            } else {
+                (Side::Sell, mid + spread + depth)
+            };
+            let size = if rng.random_bool(0.2) {
+                Decimal::ZERO
+            } else {
+                Decimal::new(rng.random_range(100..1_000_000), 2)
+            };
+            (side, Decimal::new(price, scale), size)
+        })
+        .collect()
+}
+
+fn snapshot_side(rng: &mut StdRng, from: i64, step: i64) -> Vec<PriceLevel> {
+    (0..40)
+        .map(|i| PriceLevel::new(Decimal::new(from + step * i, 2), Decimal::new(rng.random_range(100..1_000_000), 2)))
+        .collect()
+}
+
+fn bench_changes(name: &str, tick: Decimal, updates: &[(Side, Decimal, Decimal)]) -> bool {
+    let mut book = OrderBook::new(ASSET_ID.to_string());
+    book.set_tick_size(tick);
+    let start = Instant::now();
+    let mut touched = 0usize;
+    for (side, price, size) in updates {
+        book.apply_price_change_no_hash(*side, *price, *size);
+        book.validate_and_clean();
+        touched += book.best_bid().is_some() as usize + book.best_ask().is_some() as usize;
+    }
+    report(name, updates.len(), start.elapsed(), touched)
+}
+
+fn bench_snapshots(rng: &mut StdRng) -> bool {
+    let snapshots: Vec<_> = (0..SNAPSHOTS)
+        .map(|_| (snapshot_side(rng, 49, -1), snapshot_side(rng, 51, 1)))
+        .collect();
+    let mut book = OrderBook::new(ASSET_ID.to_string());
+    let start = Instant::now();
+    let mut touched = 0usize;
+    for (timestamp, (bids, asks)) in snapshots.into_iter().enumerate() {
+        book.replace_with_snapshot_no_hash(String::new(), timestamp as u64 + 1, bids, asks);
+        touched += book.best_bid().is_some() as usize;
+    }
+    report("snapshots (40 levels a side)", SNAPSHOTS, start.elapsed(), touched)
+}
+
+fn report(name: &str, count: usize, elapsed: Duration, touched: usize) -> bool {
+    let rate = count as f64 / elapsed.as_secs_f64();
+    let ok = rate >= TARGET_UPDATES_PER_SEC;
+    println!(
+        "{:<32} {:>9} updates in {:>8.2?}  {:>12.0} /s  {}  (touch reads: {})",
+        name,
+        count,
+        elapsed,
+        rate,
+        if ok { "ok" } else { "BELOW TARGET" },
+        touched
+    );
+    ok
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(4175);
+    let cents = changes(&mut rng, 2, UPDATES);
+    let mills = changes(&mut rng, 3, UPDATES);
+
+    let results = [
+        bench_changes("changes (0.01 tick)", Decimal::new(1, 2), &cents),
+        bench_changes("changes (0.001 tick)", Decimal::new(1, 3), &mills),
+        bench_snapshots(&mut rng),
+    ];
+    if results.contains(&false) {
+        eprintln!("Order book throughput below {} updates/sec", TARGET_UPDATES_PER_SEC);
+        std::process::exit(1);
+    }
+}
@@ -68,6 +68,12 @@ pub enum AddressSubcommand {
     
     /// Add tags to multiple addresses
     Tag(TagAddressesArgs),
+    
+    /// Manage address groups
+    Group(GroupCommandArgs),
+    
+    /// Follow new activity of addresses
+    Tail(TailAddressArgs),
 }
 
 #[derive(Debug, Parser)]
@@ -106,6 +112,10 @@ pub struct ListAddressesArgs {
     #[arg(long)]
     pub watched: bool,
     
+    /// Show only addresses in this group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
     /// Show detailed information
     #[arg(short, long)]
     pub detailed: bool,
@@ -208,6 +218,10 @@ pub struct QueryAddressArgs {
     #[arg(long)]
     pub tags: Option<String>,
     
+    /// Filter by group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
     /// Show only active addresses
     #[arg(long)]
     pub active_only: bool,
@@ -245,12 +259,28 @@ pub struct SyncAddressArgs {
 pub struct ImportAddressArgs {
     /// CSV file path
     pub path: String,
+    
+    /// Add every imported address to this group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
+    /// Merge rows into addresses already in the book instead of skipping them
+    #[arg(long)]
+    pub update: bool,
 }
 
 #[derive(Debug, Parser)]
 pub struct ExportAddressArgs {
     /// Output CSV file path
     pub path: String,
+    
+    /// Only export addresses in this group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
+    /// Only export addresses with these tags (comma-separated)
+    #[arg(long)]
+    pub tags: Option<String>,
 }
 
 #[derive(Debug, Parser)]
@@ -259,6 +289,10 @@ pub struct SyncAllAddressArgs {
     #[arg(long)]
     pub active_only: bool,
     
+    /// Only sync addresses in this group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
     /// Maximum number of addresses to sync
     #[arg(short, long)]
     pub limit: Option<usize>,
@@ -307,6 +341,66 @@ pub struct TagAddressesArgs {
     pub address_type: Option<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct GroupCommandArgs {
+    #[command(subcommand)]
+    pub command: GroupSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GroupSubcommand {
+    /// List groups with their member counts
+    List,
+    
+    /// Add addresses to a group (created on first use)
+    Add {
+        /// Group name
+        group: String,
+        
+        /// Addresses or labels to add
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    
+    /// Remove addresses from a group
+    Remove {
+        /// Group name
+        group: String,
+        
+        /// Addresses or labels to remove
+        #[arg(required = true)]
+        members: Vec<String>,
+    },
+    
+    /// Delete a group, taking every address out of it
+    Delete {
+        /// Group name
+        group: String,
+    },
+}
+
+#[derive(Debug, Parser)]
+pub struct TailAddressArgs {
+    /// Addresses or labels to follow
+    pub addresses: Vec<String>,
+    
+    /// Follow every address in this group
+    #[arg(short, long)]
+    pub group: Option<String>,
+    
+    /// Follow every address with these tags (comma-separated)
+    #[arg(long)]
+    pub tags: Option<String>,
+    
+    /// Seconds between polls
+    #[arg(short, long, default_value = "15")]
+    pub interval: u64,
+    
+    /// Recent events shown per address on start
+    #[arg(short, long, default_value = "5")]
+    pub limit: usize,
+}
+
 impl AddressCommand {
     pub async fn execute(self, _host: &str, data_paths: DataPaths) -> Result<()> {
         // Get address service (Gamma client will be created internally)
@@ -371,6 +465,12 @@ impl AddressCommand {
             AddressSubcommand::Tag(args) => {
                 tag_addresses(args, &address_service).await?;
             }
+            AddressSubcommand::Group(args) => {
+                manage_groups(args, &address_service).await?;
+            }
+            AddressSubcommand::Tail(args) => {
+                tail_addresses(args, &address_service).await?;
+            }
         }
         
         Ok(())
@@ -417,6 +517,10 @@ async fn list_addresses(args: ListAddressesArgs, service: &AddressBookServiceHan
         entries.retain(|e| e.address_type == AddressType::Watched);
     }
     
+    if let Some(ref group) = args.group {
+        entries.retain(|e| e.in_group(group));
+    }
+    
     if entries.is_empty() {
         println!("No addresses found");
         return Ok(());
@@ -643,6 +747,7 @@ async fn query_addresses(args: QueryAddressArgs, service: &AddressBookServiceHan
         search: args.search,
         address_type,
         tags,
+        group: args.group,
         active_only: args.active_only,
         sort_by,
         ascending: args.ascending,
@@ -832,12 +937,21 @@ async fn import_addresses(args: ImportAddressArgs, service: &AddressBookServiceH
     let (tx, rx) = oneshot::channel();
     service.send(AddressBookCommand::ImportCsv {
         path: args.path,
+        group: args.group.clone(),
+        update_existing: args.update,
         response: tx,
     }).await?;
     
-    let count = rx.await??;
+    let summary = rx.await??;
     
-    println!("✅ Imported {} addresses", count);
+    println!("✅ Imported addresses: {} added, {} updated, {} already in the book",
+        summary.added, summary.updated, summary.skipped);
+    if let Some(group) = args.group {
+        println!("   Group: {}", types::normalize_group(&group));
+    }
+    if summary.skipped > 0 {
+        println!("💡 Use --update to merge rows into existing addresses");
+    }
     
     Ok(())
 }
@@ -846,15 +960,22 @@ async fn import_addresses(args: ImportAddressArgs, service: &AddressBookServiceH
 async fn export_addresses(args: ExportAddressArgs, service: &AddressBookServiceHandle) -> Result<()> {
     println!("📤 Exporting addresses to {}...", args.path);
     
+    let query = AddressQuery {
+        tags: parse_tags(args.tags.as_deref()),
+        group: args.group,
+        ..Default::default()
+    };
+    
     let (tx, rx) = oneshot::channel();
     service.send(AddressBookCommand::ExportCsv {
         path: args.path.clone(),
+        query,
         response: tx,
     }).await?;
     
-    rx.await??;
+    let count = rx.await??;
     
-    println!("✅ Exported addresses to {}", args.path);
+    println!("✅ Exported {} addresses to {}", count, args.path);
     
     Ok(())
 }
@@ -892,6 +1013,10 @@ fn print_address_entry(entry: &AddressEntry, detailed: bool) {
         println!("   Tags: {}", entry.tags.join(", "));
     }
     
+    if !entry.groups.is_empty() {
+        println!("   Groups: {}", entry.groups.join(", "));
+    }
+    
     if detailed {
         println!("   Active: {}", if entry.is_active { "Yes" } else { "No" });
         println!("   Added: {}", entry.metadata.added_at.format("%Y-%m-%d %H:%M:%S"));
@@ -930,15 +1055,7 @@ fn print_address_entry(entry: &AddressEntry, detailed: bool) {
 
 /// Parse address type from string
 fn parse_address_type(s: &str) -> Result<AddressType> {
-    match s.to_lowercase().as_str() {
-        "own" => Ok(AddressType::Own),
-        "watched" => Ok(AddressType::Watched),
-        "contract" => Ok(AddressType::Contract),
-        "exchange" => Ok(AddressType::Exchange),
-        "market-maker" | "mm" => Ok(AddressType::MarketMaker),
-        "other" => Ok(AddressType::Other),
-        _ => Err(anyhow::anyhow!("Invalid address type: {}", s)),
-    }
+    s.parse()
 }
 
 /// Parse comma-separated tags
@@ -1001,7 +1118,7 @@ async fn sync_all_addresses(args: SyncAllAddressArgs, service: &AddressBookServi
     // Get all addresses
     let (tx, rx) = oneshot::channel();
     service.send(AddressBookCommand::ListAddresses {
-        limit: args.limit,
+        limit: if args.group.is_some() { None } else { args.limit },
         response: tx,
     }).await?;
     
@@ -1012,6 +1129,13 @@ async fn sync_all_addresses(args: SyncAllAddressArgs, service: &AddressBookServi
         addresses.retain(|e| e.is_active);
     }
     
+    if let Some(ref group) = args.group {
+        addresses.retain(|e| e.in_group(group));
+        if let Some(limit) = args.limit {
+            addresses.truncate(limit);
+        }
+    }
+    
     if addresses.is_empty() {
         println!("No addresses to sync");
         return Ok(());
@@ -1244,6 +1368,139 @@ async fn tag_addresses(args: TagAddressesArgs, service: &AddressBookServiceHandl
     Ok(())
 }
 
+/// Manage address groups
+async fn manage_groups(args: GroupCommandArgs, service: &AddressBookServiceHandle) -> Result<()> {
+    match args.command {
+        GroupSubcommand::List => {
+            let (tx, rx) = oneshot::channel();
+            service.send(AddressBookCommand::ListGroups {
+                response: tx,
+            }).await?;
+            
+            let groups = rx.await??;
+            
+            if groups.is_empty() {
+                println!("No groups yet");
+                println!("💡 Create one with: address group add <group> <address-or-label>...");
+                return Ok(());
+            }
+            
+            println!("👥 Address Groups ({})", groups.len());
+            println!("{:<30} {:>8}", "Group", "Members");
+            println!("{}", "-".repeat(39));
+            for (group, count) in groups {
+                println!("{:<30} {:>8}", group, count);
+            }
+        }
+        GroupSubcommand::Add { group, members } => {
+            update_group(service, group, members, false).await?;
+        }
+        GroupSubcommand::Remove { group, members } => {
+            update_group(service, group, members, true).await?;
+        }
+        GroupSubcommand::Delete { group } => {
+            let (tx, rx) = oneshot::channel();
+            service.send(AddressBookCommand::DeleteGroup {
+                group: group.clone(),
+                response: tx,
+            }).await?;
+            
+            let removed = rx.await??;
+            
+            println!("🗑️  Deleted group {} ({} addresses)", types::normalize_group(&group), removed);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Add addresses to a group or remove them from it
+async fn update_group(
+    service: &AddressBookServiceHandle,
+    group: String,
+    members: Vec<String>,
+    remove: bool,
+) -> Result<()> {
+    let requested = members.len();
+    let (tx, rx) = oneshot::channel();
+    service.send(AddressBookCommand::UpdateGroup {
+        group: group.clone(),
+        members,
+        remove,
+        response: tx,
+    }).await?;
+    
+    let changed = rx.await??;
+    let group = types::normalize_group(&group);
+    
+    if remove {
+        println!("✅ Removed {} of {} addresses from {}", changed, requested, group);
+    } else {
+        println!("✅ Added {} of {} addresses to {}", changed, requested, group);
+    }
+    
+    Ok(())
+}
+
+/// Follow new activity of addresses
+async fn tail_addresses(args: TailAddressArgs, service: &AddressBookServiceHandle) -> Result<()> {
+    let mut entries = Vec::new();
+    
+    for address_or_label in &args.addresses {
+        let (tx, rx) = oneshot::channel();
+        service.send(AddressBookCommand::GetAddress {
+            address_or_label: address_or_label.clone(),
+            response: tx,
+        }).await?;
+        
+        match rx.await?? {
+            Some(entry) => entries.push(entry),
+            None => {
+                // Addresses outside the book can still be followed
+                let address = storage::checksum_address(&storage::validate_address(address_or_label)?);
+                entries.push(AddressEntry::new(address, AddressType::Watched));
+            }
+        }
+    }
+    
+    if args.group.is_some() || args.tags.is_some() {
+        let (tx, rx) = oneshot::channel();
+        service.send(AddressBookCommand::QueryAddresses {
+            query: AddressQuery {
+                tags: parse_tags(args.tags.as_deref()),
+                group: args.group.clone(),
+                ..Default::default()
+            },
+            response: tx,
+        }).await?;
+        entries.extend(rx.await??);
+    }
+    
+    let mut seen = std::collections::HashSet::new();
+    let targets: Vec<tail::TailTarget> = entries
+        .into_iter()
+        .filter(|e| seen.insert(e.address.clone()))
+        .map(|e| tail::TailTarget {
+            name: e.display_name(),
+            address: e.address,
+        })
+        .collect();
+    
+    if targets.is_empty() {
+        println!("No addresses to follow");
+        println!("💡 Pass addresses or labels, or use --group / --tags");
+        return Ok(());
+    }
+    
+    println!("👀 Following {} addresses every {}s (Ctrl-C to stop)", targets.len(), args.interval);
+    for target in &targets {
+        println!("   • {} ({})", target.name, target.address);
+    }
+    println!();
+    
+    tail::run(&targets, std::time::Duration::from_secs(args.interval.max(1)), args.limit).await
+}
+
 // Re-export command types
 // pub use AddAddressArgs as AddAddressCommand;
 // pub use ListAddressesArgs as ListAddressesCommand;
@@ -0,0 +1,355 @@
+//! Shared address display for every view that shows a wallet
+//!
+//! Addresses are shown as their address book label when there is one,
+//! otherwise as their ENS primary name, otherwise as shortened hex. ENS names
+//! come from reverse resolution on Ethereum mainnet and are only trusted when
+//! the name resolves back to the same address. Results, including "no name",
+//! are cached on disk so renderers never wait on the network: they read the
+//! cache synchronously and callers warm it with [`AddressDisplay::resolve`]
+//! before drawing.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use ethers_core::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock, RwLock};
+use tracing::{debug, warn};
+
+use super::storage::AddressBookStorage;
+use crate::data::DataPaths;
+use crate::ethereum_utils::JsonRpcClient;
+
+/// Public mainnet endpoint used for ENS lookups
+pub const DEFAULT_ENS_RPC_URL: &str = "https://cloudflare-eth.com";
+
+const ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+/// `resolver(bytes32)`
+const RESOLVER_SELECTOR: &str = "0178b8bf";
+/// `name(bytes32)`
+const NAME_SELECTOR: &str = "691f3431";
+/// `addr(bytes32)`
+const ADDR_SELECTOR: &str = "3b3b57de";
+
+const ENS_CACHE_FILE: &str = "ens_cache.json";
+const ENS_CACHE_TTL_HOURS: i64 = 24;
+
+static ADDRESS_DISPLAY: OnceLock<Arc<AddressDisplay>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedName {
+    name: Option<String>,
+    resolved_at: DateTime<Utc>,
+}
+
+pub struct AddressDisplay {
+    /// Lowercase address -> address book label
+    labels: HashMap<String, String>,
+    /// Lowercase address -> ENS lookup result
+    ens: RwLock<HashMap<String, CachedName>>,
+    cache_path: PathBuf,
+    rpc: Option<JsonRpcClient>,
+}
+
+impl AddressDisplay {
+    /// Labels from the address book plus the ENS cache; never fails, a missing
+    /// or unreadable file just means fewer names
+    pub async fn load(data_paths: &DataPaths) -> Self {
+        let storage_dir = data_paths.root().join("address_book");
+        let labels = match AddressBookStorage::new(&storage_dir).load().await {
+            Ok(book) => book
+                .entries
+                .values()
+                .filter_map(|entry| {
+                    let label = entry.label.clone()?;
+                    Some((entry.address.to_lowercase(), label))
+                })
+                .collect(),
+            Err(e) => {
+                debug!("Address book unavailable for display labels: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let cache_path = storage_dir.join(ENS_CACHE_FILE);
+        let ens = std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let rpc_url = std::env::var("POLYBOT_ENS_RPC_URL").unwrap_or_else(|_| DEFAULT_ENS_RPC_URL.to_string());
+        let rpc = JsonRpcClient::new(&rpc_url)
+            .map_err(|e| warn!("ENS lookups disabled: {}", e))
+            .ok();
+
+        Self {
+            labels,
+            ens: RwLock::new(ens),
+            cache_path,
+            rpc,
+        }
+    }
+
+    /// Label or ENS name for `address`, if known
+    pub fn name(&self, address: &str) -> Option<String> {
+        let key = address.to_lowercase();
+        if let Some(label) = self.labels.get(&key) {
+            return Some(label.clone());
+        }
+        self.ens
+            .read()
+            .ok()?
+            .get(&key)
+            .and_then(|cached| cached.name.clone())
+    }
+
+    /// `name (0x12ab…cdef)` when a name is known, the short address otherwise
+    pub fn display(&self, address: &str) -> String {
+        self.display_or(address, None)
+    }
+
+    /// Like [`Self::display`], using `fallback` (e.g. a Polymarket profile
+    /// name) when neither a label nor an ENS name is known
+    pub fn display_or(&self, address: &str, fallback: Option<&str>) -> String {
+        let fallback = fallback.filter(|name| !name.is_empty()).map(str::to_string);
+        match self.name(address).or(fallback) {
+            Some(name) => format!("{} ({})", name, short_address(address)),
+            None => short_address(address),
+        }
+    }
+
+    /// The process-wide instance, if [`address_display`] has loaded it
+    pub fn global() -> Option<Arc<AddressDisplay>> {
+        ADDRESS_DISPLAY.get().cloned()
+    }
+
+    /// Look up ENS names for addresses without a label or a fresh cache entry
+    pub async fn resolve<'a>(&self, addresses: impl IntoIterator<Item = &'a str>) {
+        let Some(rpc) = &self.rpc else {
+            return;
+        };
+        let now = Utc::now();
+        let stale: Vec<String> = {
+            let Ok(cache) = self.ens.read() else {
+                return;
+            };
+            let mut stale: Vec<String> = addresses
+                .into_iter()
+                .map(str::to_lowercase)
+                .filter(|address| is_hex_address(address) && !self.labels.contains_key(address))
+                .filter(|address| {
+                    cache.get(address).is_none_or(|cached| {
+                        now - cached.resolved_at > Duration::hours(ENS_CACHE_TTL_HOURS)
+                    })
+                })
+                .collect();
+            stale.sort();
+            stale.dedup();
+            stale
+        };
+        if stale.is_empty() {
+            return;
+        }
+
+        let mut resolved = Vec::with_capacity(stale.len());
+        for address in stale {
+            match reverse_resolve(rpc, &address).await {
+                Ok(name) => resolved.push((address, name)),
+                // Leave the entry alone so the next call retries
+                Err(e) => debug!("ENS lookup for {} failed: {}", address, e),
+            }
+        }
+
+        if let Ok(mut cache) = self.ens.write() {
+            for (address, name) in resolved {
+                cache.insert(address, CachedName { name, resolved_at: now });
+            }
+            if let Err(e) = self.save_cache(&cache) {
+                warn!("Failed to save ENS cache {}: {}", self.cache_path.display(), e);
+            }
+        }
+    }
+
+    fn save_cache(&self, cache: &HashMap<String, CachedName>) -> Result<()> {
+        if let Some(parent) = self.cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.cache_path, serde_json::to_string_pretty(cache)?)?;
+        Ok(())
+    }
+}
+
+/// Process-wide display service, loaded on first use
+pub async fn address_display(data_paths: &DataPaths) -> Arc<AddressDisplay> {
+    if let Some(display) = ADDRESS_DISPLAY.get() {
+        return display.clone();
+    }
+    let display = Arc::new(AddressDisplay::load(data_paths).await);
+    ADDRESS_DISPLAY.get_or_init(|| display).clone()
+}
+
+/// Display string for renderers that can't await; falls back to the short
+/// address until [`address_display`] has been loaded
+pub fn display_address(address: &str) -> String {
+    display_address_or(address, None)
+}
+
+/// [`display_address`] with a fallback name, see [`AddressDisplay::display_or`]
+pub fn display_address_or(address: &str, fallback: Option<&str>) -> String {
+    match ADDRESS_DISPLAY.get() {
+        Some(display) => display.display_or(address, fallback),
+        None => match fallback.filter(|name| !name.is_empty()) {
+            Some(name) => format!("{} ({})", name, short_address(address)),
+            None => short_address(address),
+        },
+    }
+}
+
+/// `0x12ab…cdef`
+pub fn short_address(address: &str) -> String {
+    if address.len() > 12 && address.is_char_boundary(6) && address.is_char_boundary(address.len() - 4) {
+        format!("{}…{}", &address[..6], &address[address.len() - 4..])
+    } else {
+        address.to_string()
+    }
+}
+
+fn is_hex_address(address: &str) -> bool {
+    address.len() == 42 && address.starts_with("0x") && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// ENS namehash of a dot-separated name
+pub fn namehash(name: &str) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    if name.is_empty() {
+        return node;
+    }
+    for label in name.rsplit('.') {
+        let mut buf = [0u8; 64];
+        buf[..32].copy_from_slice(&node);
+        buf[32..].copy_from_slice(&keccak256(label.as_bytes()));
+        node = keccak256(buf);
+    }
+    node
+}
+
+/// Primary ENS name of `address`, verified by forward resolution
+async fn reverse_resolve(rpc: &JsonRpcClient, address: &str) -> Result<Option<String>> {
+    let reverse_node = namehash(&format!("{}.addr.reverse", address.trim_start_matches("0x")));
+    let Some(resolver) = lookup_resolver(rpc, &reverse_node).await? else {
+        return Ok(None);
+    };
+    let name = decode_abi_string(&eth_call(rpc, &resolver, NAME_SELECTOR, &reverse_node).await?)?;
+    if name.is_empty() {
+        return Ok(None);
+    }
+
+    // Anyone can set any reverse record; only keep names that point back
+    let forward_node = namehash(&name);
+    let Some(forward_resolver) = lookup_resolver(rpc, &forward_node).await? else {
+        return Ok(None);
+    };
+    let resolved = decode_abi_address(&eth_call(rpc, &forward_resolver, ADDR_SELECTOR, &forward_node).await?);
+    Ok(resolved
+        .filter(|resolved| resolved.eq_ignore_ascii_case(address))
+        .map(|_| name))
+}
+
+async fn lookup_resolver(rpc: &JsonRpcClient, node: &[u8; 32]) -> Result<Option<String>> {
+    let result = eth_call(rpc, ENS_REGISTRY, RESOLVER_SELECTOR, node).await?;
+    Ok(decode_abi_address(&result))
+}
+
+async fn eth_call(rpc: &JsonRpcClient, to: &str, selector: &str, node: &[u8; 32]) -> Result<Vec<u8>> {
+    let data = format!("0x{}{}", selector, hex::encode(node));
+    let result = rpc
+        .call("eth_call", json!([{ "to": to, "data": data }, "latest"]))
+        .await?;
+    let result = result.as_str().ok_or_else(|| anyhow!("eth_call returned no data"))?;
+    Ok(hex::decode(result.trim_start_matches("0x"))?)
+}
+
+/// Last 20 bytes of an ABI word, `None` for the zero address
+fn decode_abi_address(data: &[u8]) -> Option<String> {
+    let word = data.get(..32)?;
+    let address = &word[12..];
+    if address.iter().all(|b| *b == 0) {
+        return None;
+    }
+    Some(format!("0x{}", hex::encode(address)))
+}
+
+/// ABI-encoded dynamic `string` return value
+fn decode_abi_string(data: &[u8]) -> Result<String> {
+    let word = |at: usize| -> Result<usize> {
+        let bytes = data
+            .get(at..at.saturating_add(32))
+            .ok_or_else(|| anyhow!("ABI string truncated"))?;
+        Ok(u64::from_be_bytes(bytes[24..].try_into()?) as usize)
+    };
+    if data.is_empty() {
+        return Ok(String::new());
+    }
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.saturating_add(32);
+    let bytes = data
+        .get(start..start.saturating_add(len))
+        .ok_or_else(|| anyhow!("ABI string truncated"))?;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namehash_matches_eip137_vectors() {
+        assert_eq!(namehash(""), [0u8; 32]);
+        assert_eq!(
+            hex::encode(namehash("eth")),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            hex::encode(namehash("foo.eth")),
+            "de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+    }
+
+    #[test]
+    fn decodes_abi_values() {
+        let mut data = vec![0u8; 96];
+        data[31] = 0x20;
+        data[63] = 8;
+        data[64..72].copy_from_slice(b"vitalik.");
+        assert_eq!(decode_abi_string(&data).unwrap(), "vitalik.");
+
+        let mut word = vec![0u8; 32];
+        assert_eq!(decode_abi_address(&word), None);
+        word[31] = 1;
+        assert_eq!(
+            decode_abi_address(&word).as_deref(),
+            Some("0x0000000000000000000000000000000000000001")
+        );
+    }
+
+    #[test]
+    fn labels_take_precedence_over_ens() {
+        let address = "0xAbCdEf0123456789abcdef0123456789ABCDEF01";
+        let display = AddressDisplay {
+            labels: HashMap::from([(address.to_lowercase(), "desk".to_string())]),
+            ens: RwLock::new(HashMap::new()),
+            cache_path: PathBuf::new(),
+            rpc: None,
+        };
+        assert_eq!(display.display(address), "desk (0xAbCd…EF01)");
+        assert_eq!(display.display("0x1111111111111111111111111111111111111111"), "0x1111…1111");
+        assert_eq!(display.display_or(address, Some("whale")), "desk (0xAbCd…EF01)");
+        assert_eq!(
+            display.display_or("0x1111111111111111111111111111111111111111", Some("whale")),
+            "whale (0x1111…1111)"
+        );
+    }
+}
@@ -8,6 +8,8 @@ pub mod storage;
 pub mod service;
 pub mod commands;
 pub mod db;
+pub mod display;
+pub mod tail;
 
 // Re-export core types
 pub use types::{
@@ -22,6 +24,9 @@ pub use service::{
     AddressBookServiceHandle, AddressBookCommand,
 };
 
+// Re-export display helpers
+pub use display::{address_display, display_address, display_address_or, AddressDisplay};
+
 // Re-export commands
 pub use commands::{
     AddressCommand,
@@ -13,6 +13,7 @@ use crate::address_book::storage::*;
 use crate::core::portfolio::PortfolioServiceHandle;
 use reqwest::Client;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use crate::data::DataPaths;
 
@@ -84,13 +85,38 @@ pub enum AddressBookCommand {
     /// Import addresses from CSV
     ImportCsv {
         path: String,
-        response: oneshot::Sender<Result<usize>>,
+        /// Group every imported address joins
+        group: Option<String>,
+        /// Merge rows into addresses already in the book instead of skipping them
+        update_existing: bool,
+        response: oneshot::Sender<Result<CsvImportSummary>>,
     },
     
-    /// Export addresses to CSV
+    /// Export the addresses matching a query to CSV
     ExportCsv {
         path: String,
-        response: oneshot::Sender<Result<()>>,
+        query: AddressQuery,
+        response: oneshot::Sender<Result<usize>>,
+    },
+    
+    /// Add addresses to a group, or remove them from it
+    UpdateGroup {
+        group: String,
+        /// Addresses or labels
+        members: Vec<String>,
+        remove: bool,
+        response: oneshot::Sender<Result<usize>>,
+    },
+    
+    /// Remove a group from every address in it
+    DeleteGroup {
+        group: String,
+        response: oneshot::Sender<Result<usize>>,
+    },
+    
+    /// List groups with their member counts
+    ListGroups {
+        response: oneshot::Sender<Result<BTreeMap<String, usize>>>,
     },
     
     /// Get address book stats
@@ -99,6 +125,15 @@ pub enum AddressBookCommand {
     },
 }
 
+/// Outcome of a CSV import
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    /// Rows for addresses already in the book, when not updating
+    pub skipped: usize,
+}
+
 /// Address book service actor
 pub struct AddressBookService {
     /// Storage backend
@@ -314,16 +349,31 @@ impl AddressBookService {
                     let _ = response.send(result);
                 }
                 
-                AddressBookCommand::ImportCsv { path, response } => {
-                    let result = self.handle_import_csv(path).await;
+                AddressBookCommand::ImportCsv { path, group, update_existing, response } => {
+                    let result = self.handle_import_csv(path, group, update_existing).await;
                     let _ = response.send(result);
                 }
                 
-                AddressBookCommand::ExportCsv { path, response } => {
-                    let result = self.handle_export_csv(path).await;
+                AddressBookCommand::ExportCsv { path, query, response } => {
+                    let result = self.handle_export_csv(path, query).await;
                     let _ = response.send(result);
                 }
                 
+                AddressBookCommand::UpdateGroup { group, members, remove, response } => {
+                    let result = self.handle_update_group(group, members, remove).await;
+                    let _ = response.send(result);
+                }
+                
+                AddressBookCommand::DeleteGroup { group, response } => {
+                    let result = self.handle_delete_group(group).await;
+                    let _ = response.send(result);
+                }
+                
+                AddressBookCommand::ListGroups { response } => {
+                    let book = self.address_book.read().await;
+                    let _ = response.send(Ok(book.groups()));
+                }
+                
                 AddressBookCommand::GetStats { response } => {
                     let result = self.handle_get_stats().await;
                     let _ = response.send(result);
@@ -621,36 +671,167 @@ impl AddressBookService {
     }
     
     /// Handle import CSV command
-    async fn handle_import_csv(&mut self, path: String) -> Result<usize> {
+    async fn handle_import_csv(
+        &mut self,
+        path: String,
+        group: Option<String>,
+        update_existing: bool,
+    ) -> Result<CsvImportSummary> {
         let path = std::path::Path::new(&path);
         let entries = self.storage.import_csv(path).await?;
         
         let mut book = self.address_book.write().await;
-        let mut imported = 0;
+        let mut summary = CsvImportSummary::default();
         
-        for entry in entries {
-            if !book.entries.contains_key(&entry.address) {
-                book.upsert_entry(entry);
-                imported += 1;
+        for mut entry in entries {
+            if let Some(ref group) = group {
+                entry.join_group(group);
+            }
+            
+            // A label can only point at one address
+            let label_taken = entry.label.as_ref().is_some_and(|label| {
+                book.labels.get(label).is_some_and(|owner| *owner != entry.address)
+            });
+            if label_taken {
+                warn!(
+                    "Label {:?} already belongs to another address, not applied to {}",
+                    entry.label, entry.address
+                );
+            }
+            
+            match book.entries.get(&entry.address).cloned() {
+                None => {
+                    if label_taken {
+                        entry.label = None;
+                    }
+                    book.upsert_entry(entry);
+                    summary.added += 1;
+                }
+                Some(_) if !update_existing => {
+                    summary.skipped += 1;
+                }
+                Some(mut existing) => {
+                    let old_label = existing.label.clone();
+                    if !label_taken && entry.label.is_some() {
+                        existing.label = entry.label;
+                    }
+                    if entry.description.is_some() {
+                        existing.description = entry.description;
+                    }
+                    if entry.notes.is_some() {
+                        existing.notes = entry.notes;
+                    }
+                    existing.address_type = entry.address_type;
+                    existing.is_active = entry.is_active;
+                    for tag in entry.tags {
+                        if !existing.tags.contains(&tag) {
+                            existing.tags.push(tag);
+                        }
+                    }
+                    for group in &entry.groups {
+                        existing.join_group(group);
+                    }
+                    existing.metadata.updated_at = chrono::Utc::now();
+                    
+                    if let Some(old) = old_label {
+                        if existing.label.as_ref() != Some(&old) {
+                            book.labels.remove(&old);
+                        }
+                    }
+                    book.upsert_entry(existing);
+                    summary.updated += 1;
+                }
             }
         }
         
         // Save to disk
         self.storage.save(&book).await?;
         
-        info!("Imported {} addresses from CSV", imported);
-        Ok(imported)
+        info!(
+            "Imported addresses from CSV: {} added, {} updated, {} skipped",
+            summary.added, summary.updated, summary.skipped
+        );
+        Ok(summary)
     }
     
     /// Handle export CSV command
-    async fn handle_export_csv(&self, path: String) -> Result<()> {
+    async fn handle_export_csv(&self, path: String, query: AddressQuery) -> Result<usize> {
         let book = self.address_book.read().await;
         let path = std::path::Path::new(&path);
         
-        self.storage.export_csv(path, &book).await?;
+        let mut entries = book.query(&query);
+        entries.sort_by_key(|e| e.display_name());
+        self.storage.export_csv(path, &entries).await?;
         
-        info!("Exported {} addresses to CSV", book.entries.len());
-        Ok(())
+        info!("Exported {} addresses to CSV", entries.len());
+        Ok(entries.len())
+    }
+    
+    /// Handle update group command
+    async fn handle_update_group(
+        &mut self,
+        group: String,
+        members: Vec<String>,
+        remove: bool,
+    ) -> Result<usize> {
+        let group = normalize_group(&group);
+        if group.is_empty() {
+            return Err(anyhow::anyhow!("Group name cannot be empty"));
+        }
+        
+        let mut book = self.address_book.write().await;
+        let mut changed = 0;
+        
+        for member in members {
+            let address = match book.get_entry(&member) {
+                Some(entry) => entry.address.clone(),
+                None => checksum_address(&validate_address(&member)?),
+            };
+            let entry = book.entries.get_mut(&address)
+                .ok_or_else(|| AddressBookError::AddressNotFound(member.clone()))?;
+            
+            let updated = if remove {
+                entry.leave_group(&group)
+            } else {
+                entry.join_group(&group)
+            };
+            if updated {
+                entry.metadata.updated_at = chrono::Utc::now();
+                changed += 1;
+            }
+        }
+        
+        // Save to disk
+        self.storage.save(&book).await?;
+        
+        info!("{} {} addresses {} group {}", if remove { "Removed" } else { "Added" },
+            changed, if remove { "from" } else { "to" }, group);
+        Ok(changed)
+    }
+    
+    /// Handle delete group command
+    async fn handle_delete_group(&mut self, group: String) -> Result<usize> {
+        let group = normalize_group(&group);
+        let mut book = self.address_book.write().await;
+        
+        let now = chrono::Utc::now();
+        let mut removed = 0;
+        for entry in book.entries.values_mut() {
+            if entry.leave_group(&group) {
+                entry.metadata.updated_at = now;
+                removed += 1;
+            }
+        }
+        
+        if removed == 0 {
+            return Err(anyhow::anyhow!("Group '{}' has no members", group));
+        }
+        
+        // Save to disk
+        self.storage.save(&book).await?;
+        
+        info!("Deleted group {} from {} addresses", group, removed);
+        Ok(removed)
     }
     
     /// Handle get stats command
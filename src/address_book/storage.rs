@@ -157,109 +157,119 @@ impl AddressBookStorage {
         Ok(())
     }
 
-    /// Export address book to CSV
-    pub async fn export_csv(&self, path: &Path, address_book: &AddressBook) -> Result<()> {
-        use std::io::Write;
-        
-        let mut file = std::fs::File::create(path)?;
-        
-        // Write header
-        writeln!(file, "Address,Label,Type,Description,Tags,Added At,Last Synced,Total Value,Active")?;
-        
-        // Write entries
-        for entry in address_book.entries.values() {
-            writeln!(
-                file,
-                "{},{},{},{},{},{},{},{},{}",
-                entry.address,
-                entry.label.as_deref().unwrap_or(""),
-                entry.address_type,
-                entry.description.as_deref().unwrap_or(""),
-                entry.tags.join(";"),
-                entry.metadata.added_at.format("%Y-%m-%d %H:%M:%S"),
-                entry.metadata.last_synced
-                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or_default(),
-                entry.stats.as_ref()
-                    .map(|s| s.total_value.to_string())
-                    .unwrap_or_default(),
-                entry.is_active
-            )?;
-        }
-        
-        info!("Exported {} addresses to CSV", address_book.entries.len());
+    /// Export entries to CSV
+    pub async fn export_csv(&self, path: &Path, entries: &[&AddressEntry]) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        write_csv(file, entries)?;
+        info!("Exported {} addresses to CSV", entries.len());
         Ok(())
     }
 
     /// Import addresses from CSV
     pub async fn import_csv(&self, path: &Path) -> Result<Vec<AddressEntry>> {
-        use std::io::{BufRead, BufReader};
-        
-        let file = std::fs::File::open(path)?;
-        let reader = BufReader::new(file);
-        let mut entries = Vec::new();
-        let mut line_num = 0;
-        
-        for line in reader.lines() {
-            line_num += 1;
-            
-            // Skip header
-            if line_num == 1 {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let entries = read_csv(file)?;
+        info!("Read {} addresses from CSV", entries.len());
+        Ok(entries)
+    }
+}
+
+/// CSV columns, in export order; lists within a cell are `;`-separated
+pub const CSV_COLUMNS: &[&str] = &[
+    "Address", "Label", "Type", "Description", "Tags", "Groups", "Notes",
+    "Added At", "Last Synced", "Total Value", "Active",
+];
+
+/// Write entries as CSV with a header row
+pub fn write_csv<W: std::io::Write>(writer: W, entries: &[&AddressEntry]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(CSV_COLUMNS)?;
+    for entry in entries {
+        wtr.write_record([
+            entry.address.clone(),
+            entry.label.clone().unwrap_or_default(),
+            entry.address_type.to_string(),
+            entry.description.clone().unwrap_or_default(),
+            entry.tags.join(";"),
+            entry.groups.join(";"),
+            entry.notes.clone().unwrap_or_default(),
+            entry.metadata.added_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            entry.metadata.last_synced
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default(),
+            entry.stats.as_ref()
+                .map(|s| s.total_value.to_string())
+                .unwrap_or_default(),
+            entry.is_active.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Read entries from CSV, matching columns by header name
+///
+/// Only `Address` is required, so hand-written files and exports from before
+/// the `Groups`/`Notes` columns both import. Rows with a bad address or type
+/// are skipped with a warning.
+pub fn read_csv<R: std::io::Read>(reader: R) -> Result<Vec<AddressEntry>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    let headers = rdr.headers().context("Failed to read CSV header")?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let address_col = column("Address")
+        .ok_or_else(|| anyhow::anyhow!("CSV has no Address column"))?;
+    let (label_col, type_col, description_col) = (column("Label"), column("Type"), column("Description"));
+    let (tags_col, groups_col, notes_col) = (column("Tags"), column("Groups"), column("Notes"));
+    let active_col = column("Active");
+
+    let mut entries = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        let line = i + 2;
+        let record = record.with_context(|| format!("Failed to read CSV line {}", line))?;
+        let cell = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let list = |col: Option<usize>| -> Vec<String> {
+            cell(col)
+                .map(|v| v.split(';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+                .unwrap_or_default()
+        };
+
+        let address = match validate_address(record.get(address_col).unwrap_or("")) {
+            Ok(address) => checksum_address(&address),
+            Err(e) => {
+                warn!("Skipping CSV line {}: {}", line, e);
                 continue;
             }
-            
-            let line = line?;
-            let parts: Vec<&str> = line.split(',').collect();
-            
-            if parts.len() < 9 {
-                warn!("Skipping invalid line {}: insufficient columns", line_num);
+        };
+        let address_type = match cell(type_col).map(|t| t.parse::<AddressType>()).transpose() {
+            Ok(address_type) => address_type.unwrap_or(AddressType::Watched),
+            Err(e) => {
+                warn!("Skipping CSV line {}: {}", line, e);
                 continue;
             }
-            
-            let address = parts[0].trim().to_string();
-            let label = if parts[1].trim().is_empty() {
-                None
-            } else {
-                Some(parts[1].trim().to_string())
-            };
-            
-            let address_type = match parts[2].trim() {
-                "Own" => AddressType::Own,
-                "Watched" => AddressType::Watched,
-                "Contract" => AddressType::Contract,
-                "Exchange" => AddressType::Exchange,
-                "Market Maker" => AddressType::MarketMaker,
-                _ => AddressType::Other,
-            };
-            
-            let description = if parts[3].trim().is_empty() {
-                None
-            } else {
-                Some(parts[3].trim().to_string())
-            };
-            
-            let tags: Vec<String> = if parts[4].trim().is_empty() {
-                Vec::new()
-            } else {
-                parts[4].split(';').map(|s| s.trim().to_string()).collect()
-            };
-            
-            let is_active = parts[8].trim() == "true";
-            
-            let mut entry = AddressEntry::new(address, address_type);
-            entry.label = label;
-            entry.description = description;
-            entry.tags = tags;
-            entry.is_active = is_active;
-            
-            entries.push(entry);
+        };
+
+        let mut entry = AddressEntry::new(address, address_type);
+        entry.label = cell(label_col);
+        entry.description = cell(description_col);
+        entry.tags = list(tags_col);
+        for group in list(groups_col) {
+            entry.join_group(&group);
         }
-        
-        info!("Imported {} addresses from CSV", entries.len());
-        Ok(entries)
+        entry.notes = cell(notes_col);
+        entry.is_active = cell(active_col)
+            .map_or(true, |v| !matches!(v.to_ascii_lowercase().as_str(), "false" | "no" | "0"));
+        entries.push(entry);
     }
-
-
+    Ok(entries)
 }
 
 /// Validate Ethereum address format
@@ -296,4 +306,43 @@ pub fn checksum_address(address: &str) -> String {
     // A proper implementation would require adding sha3/keccak256 dependency
     let address = address.trim_start_matches("0x").trim_start_matches("0X");
     format!("0x{}", address.to_lowercase())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trip_keeps_tags_and_groups() {
+        let mut whale = AddressEntry::new(
+            "0xabc0000000000000000000000000000000000001".to_string(),
+            AddressType::MarketMaker,
+        );
+        whale.label = Some("big, fish".to_string());
+        whale.tags = vec!["whale".to_string(), "sports".to_string()];
+        whale.join_group("Whales");
+        whale.is_active = false;
+
+        let mut buf = Vec::new();
+        write_csv(&mut buf, &[&whale]).unwrap();
+        let entries = read_csv(buf.as_slice()).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.address, whale.address);
+        assert_eq!(entry.label.as_deref(), Some("big, fish"));
+        assert_eq!(entry.address_type, AddressType::MarketMaker);
+        assert_eq!(entry.tags, whale.tags);
+        assert_eq!(entry.groups, ["whales"]);
+        assert!(!entry.is_active);
+
+        // Hand-written files only need an Address column, in any case and order
+        let csv = "label,ADDRESS,groups\n\
+                   mine,0x0000000000000000000000000000000000000002,my wallets\n\
+                   bad,0x12,\n";
+        let entries = read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].address_type, AddressType::Watched);
+        assert_eq!(entries[0].groups, ["my-wallets"]);
+        assert!(entries[0].is_active);
+    }
+}
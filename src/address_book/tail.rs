@@ -0,0 +1,250 @@
+//! Follow the trading activity of address book entries
+//!
+//! `polybot address tail` polls the Polymarket data API activity feed of each
+//! followed address and prints the events it hasn't shown yet, until Ctrl-C.
+//! An event is new when it wasn't in the previous response for its address
+//! and isn't older than the newest event already seen.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::warn;
+
+/// Activity feed of the Polymarket data API
+pub const ACTIVITY_URL: &str = "https://data-api.polymarket.com/activity";
+
+/// Events requested per address and poll
+const FETCH_LIMIT: usize = 100;
+
+/// An address to follow
+#[derive(Debug, Clone)]
+pub struct TailTarget {
+    pub address: String,
+    /// Label or shortened address, for output
+    pub name: String,
+}
+
+/// One entry of an address's activity feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityEvent {
+    /// Identity of the event within the feed
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Token traded, when the event concerns one
+    pub asset: Option<String>,
+    /// TRADE, SPLIT, MERGE, REDEEM, ...
+    pub kind: String,
+    pub side: Option<String>,
+    pub size: Option<f64>,
+    pub price: Option<f64>,
+    pub usdc_size: Option<f64>,
+    pub title: Option<String>,
+    pub outcome: Option<String>,
+}
+
+impl ActivityEvent {
+    /// Parse a feed entry; entries without a timestamp are ignored
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let timestamp = Utc
+            .timestamp_opt(value.get("timestamp")?.as_i64()?, 0)
+            .single()?;
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+        let number = |key: &str| {
+            value.get(key).and_then(|v| {
+                v.as_f64()
+                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+            })
+        };
+        let kind = text("type").unwrap_or_else(|| "TRADE".to_string());
+        let side = text("side").filter(|s| !s.is_empty());
+        let size = number("size");
+        let asset = text("asset").filter(|a| !a.is_empty());
+        // A transaction can hold several fills, so the hash alone isn't unique
+        let id = format!(
+            "{}:{}:{}:{}:{}",
+            text("transactionHash").unwrap_or_default(),
+            asset.as_deref().unwrap_or_default(),
+            kind,
+            side.as_deref().unwrap_or_default(),
+            size.unwrap_or_default()
+        );
+        Some(Self {
+            id,
+            timestamp,
+            asset,
+            kind,
+            side,
+            size,
+            price: number("price"),
+            usdc_size: number("usdcSize"),
+            title: text("title"),
+            outcome: text("outcome"),
+        })
+    }
+
+    /// One-line summary, without the time and address
+    pub fn summary(&self) -> String {
+        let mut line = match &self.side {
+            Some(side) => format!("{} {}", self.kind, side),
+            None => self.kind.clone(),
+        };
+        if let Some(size) = self.size {
+            line.push_str(&format!(" {:.2}", size));
+        }
+        if let Some(outcome) = &self.outcome {
+            line.push_str(&format!(" {}", outcome));
+        }
+        if let Some(price) = self.price.filter(|p| *p > 0.0) {
+            line.push_str(&format!(" @ {:.3}", price));
+        }
+        if let Some(usdc) = self.usdc_size {
+            line.push_str(&format!(" (${:.2})", usdc));
+        }
+        if let Some(title) = &self.title {
+            line.push_str(&format!("  {}", title));
+        }
+        line
+    }
+}
+
+/// Tracks which events of one address were already shown
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+    seen: HashSet<String>,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl ActivityTracker {
+    /// Events of `events` not shown before, oldest first
+    pub fn new_events(&mut self, mut events: Vec<ActivityEvent>) -> Vec<ActivityEvent> {
+        let seen: HashSet<String> = events.iter().map(|e| e.id.clone()).collect();
+        events.retain(|e| {
+            !self.seen.contains(&e.id) && self.latest.map_or(true, |latest| e.timestamp >= latest)
+        });
+        events.sort_by_key(|e| e.timestamp);
+
+        self.seen = seen;
+        if let Some(last) = events.last() {
+            self.latest = Some(
+                self.latest
+                    .map_or(last.timestamp, |l| l.max(last.timestamp)),
+            );
+        }
+        events
+    }
+}
+
+/// Fetch the most recent activity of `address`
+pub async fn fetch_activity(
+    client: &Client,
+    address: &str,
+    limit: usize,
+) -> Result<Vec<ActivityEvent>> {
+    let response = client
+        .get(ACTIVITY_URL)
+        .query(&[("user", address), ("limit", &limit.to_string())])
+        .send()
+        .await
+        .context("Failed to fetch activity")?
+        .error_for_status()
+        .context("Activity request failed")?;
+    let body: Value = response.json().await.context("Failed to parse activity")?;
+    Ok(body
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(ActivityEvent::from_value)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Print the last `backlog` events of each target, then new events every
+/// `interval` until Ctrl-C
+pub async fn run(targets: &[TailTarget], interval: Duration, backlog: usize) -> Result<()> {
+    let client = Client::new();
+    let mut trackers: HashMap<String, ActivityTracker> = HashMap::new();
+    let mut first = true;
+
+    loop {
+        for target in targets {
+            let events = match fetch_activity(&client, &target.address, FETCH_LIMIT).await {
+                Ok(events) => events,
+                Err(e) => {
+                    warn!("Activity poll failed for {}: {:#}", target.name, e);
+                    continue;
+                }
+            };
+            let tracker = trackers.entry(target.address.clone()).or_default();
+            let mut new_events = tracker.new_events(events);
+            if first {
+                let skip = new_events.len().saturating_sub(backlog);
+                new_events.drain(..skip);
+            }
+            for event in new_events {
+                println!(
+                    "{}  {:<20} {}",
+                    event
+                        .timestamp
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    target.name,
+                    event.summary()
+                );
+            }
+        }
+        first = false;
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(hash: &str, timestamp: i64) -> ActivityEvent {
+        ActivityEvent::from_value(&json!({
+            "transactionHash": hash,
+            "timestamp": timestamp,
+            "type": "TRADE",
+            "side": "BUY",
+            "size": 120.0,
+            "price": "0.42",
+            "usdcSize": 50.4,
+            "title": "Will it rain?",
+            "outcome": "Yes",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_unseen_events_are_new() {
+        let mut tracker = ActivityTracker::default();
+        let first = tracker.new_events(vec![event("0xb", 200), event("0xa", 100)]);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].timestamp.timestamp(), 100);
+        assert_eq!(first[0].price, Some(0.42));
+        assert_eq!(
+            first[0].summary(),
+            "TRADE BUY 120.00 Yes @ 0.420 ($50.40)  Will it rain?"
+        );
+
+        // The newest event shows once; an old event that reappears doesn't
+        let next = tracker.new_events(vec![event("0xc", 300), event("0xb", 200), event("0xz", 50)]);
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].timestamp.timestamp(), 300);
+        assert!(tracker.new_events(vec![event("0xc", 300)]).is_empty());
+
+        assert!(ActivityEvent::from_value(&json!({ "type": "TRADE" })).is_none());
+    }
+}
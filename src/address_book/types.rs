@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Address entry in the address book
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +23,10 @@ pub struct AddressEntry {
     /// Custom tags for organization
     pub tags: Vec<String>,
     
+    /// Named groups the address belongs to (e.g. "whales", "my-wallets")
+    #[serde(default)]
+    pub groups: Vec<String>,
+    
     /// Metadata about the address
     pub metadata: AddressMetadata,
     
@@ -53,6 +57,23 @@ pub enum AddressType {
     Other,
 }
 
+impl std::str::FromStr for AddressType {
+    type Err = anyhow::Error;
+
+    /// Parse a CLI name (`own`, `market-maker`, `mm`) or a display name (`Market Maker`)
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().replace(' ', "-").as_str() {
+            "own" => Ok(AddressType::Own),
+            "watched" => Ok(AddressType::Watched),
+            "contract" => Ok(AddressType::Contract),
+            "exchange" => Ok(AddressType::Exchange),
+            "market-maker" | "mm" => Ok(AddressType::MarketMaker),
+            "other" => Ok(AddressType::Other),
+            _ => Err(anyhow::anyhow!("Invalid address type: {}", s)),
+        }
+    }
+}
+
 impl std::fmt::Display for AddressType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -201,6 +222,9 @@ pub struct AddressQuery {
     /// Filter by tags
     pub tags: Vec<String>,
     
+    /// Only addresses in this group
+    pub group: Option<String>,
+    
     /// Only show active addresses
     pub active_only: bool,
     
@@ -263,6 +287,7 @@ impl AddressEntry {
             description: None,
             address_type,
             tags: Vec::new(),
+            groups: Vec::new(),
             metadata: AddressMetadata {
                 added_at: now,
                 updated_at: now,
@@ -329,6 +354,31 @@ impl AddressEntry {
         false
     }
 
+    /// Whether the address is in `group` (names compare normalized)
+    pub fn in_group(&self, group: &str) -> bool {
+        let group = normalize_group(group);
+        self.groups.iter().any(|g| *g == group)
+    }
+
+    /// Add the address to `group`; returns false if it was already in it
+    pub fn join_group(&mut self, group: &str) -> bool {
+        let group = normalize_group(group);
+        if group.is_empty() || self.groups.contains(&group) {
+            return false;
+        }
+        self.groups.push(group);
+        self.groups.sort();
+        true
+    }
+
+    /// Take the address out of `group`; returns false if it wasn't in it
+    pub fn leave_group(&mut self, group: &str) -> bool {
+        let group = normalize_group(group);
+        let before = self.groups.len();
+        self.groups.retain(|g| *g != group);
+        self.groups.len() != before
+    }
+
     /// Update query metadata
     pub fn record_query(&mut self) {
         self.metadata.query_count += 1;
@@ -431,6 +481,17 @@ impl AddressBook {
         Ok(())
     }
 
+    /// Groups in use, with how many addresses each has
+    pub fn groups(&self) -> BTreeMap<String, usize> {
+        let mut groups = BTreeMap::new();
+        for entry in self.entries.values() {
+            for group in &entry.groups {
+                *groups.entry(group.clone()).or_insert(0) += 1;
+            }
+        }
+        groups
+    }
+
     /// Update address type counts
     fn update_counts(&mut self) {
         self.metadata.own_addresses = self.entries.values()
@@ -469,6 +530,13 @@ impl AddressBook {
                     }
                 }
                 
+                // Apply group filter
+                if let Some(group) = &query.group {
+                    if !entry.in_group(group) {
+                        return false;
+                    }
+                }
+                
                 // Apply active filter
                 if query.active_only && !entry.is_active {
                     return false;
@@ -517,4 +585,9 @@ impl AddressBook {
         
         results
     }
-}
\ No newline at end of file
+}
+
+/// Canonical form of a group name: trimmed, lowercase, spaces as dashes
+pub fn normalize_group(name: &str) -> String {
+    name.trim().to_lowercase().split_whitespace().collect::<Vec<_>>().join("-")
+}
@@ -0,0 +1,86 @@
+//! Read-only (watch) profiles
+//!
+//! A read-only profile stores only the address it monitors: no private key,
+//! no API credentials. Everything that reads public data works as usual,
+//! while anything that would place, cancel or sign is refused. The CLI marks
+//! the process read-only once at startup from the active profile, and every
+//! mutating path (command dispatch, order placement and cancels, on-chain
+//! sends, signer connection) checks [`ensure_writable`] before acting.
+
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::signer::SignerBackend;
+use crate::config;
+use crate::data_paths::DataPaths;
+use crate::ethereum_utils::derive_address_from_private_key;
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Mark the process read-only when the active profile is
+pub fn activate(data_paths: &DataPaths) -> Result<bool> {
+    let read_only = SignerBackend::load(data_paths)?.is_read_only();
+    set_read_only(read_only);
+    Ok(read_only)
+}
+
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::SeqCst);
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::SeqCst)
+}
+
+/// Refuse `action` in a read-only session
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_read_only() {
+        return Err(read_only_error(action));
+    }
+    Ok(())
+}
+
+pub(crate) fn read_only_error(action: &str) -> anyhow::Error {
+    anyhow!(
+        "Cannot {}: this profile is read-only (address only). \
+         Use a profile with a key, or run 'polybot init --pk <key>' / 'polybot init --ledger'",
+        action
+    )
+}
+
+/// Address of the profile's account, without touching a hardware wallet
+/// unless the profile signs with one
+pub async fn account_address(data_paths: &DataPaths) -> Result<String> {
+    match SignerBackend::load(data_paths)? {
+        SignerBackend::ReadOnly { address } => Ok(address),
+        SignerBackend::Local => {
+            let private_key = config::load_private_key(data_paths)
+                .await
+                .map_err(|e| anyhow!("No private key found. Run 'polybot init' first: {}", e))?;
+            derive_address_from_private_key(&private_key)
+        }
+        backend => Ok(backend.connect(data_paths).await?.address_hex()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_only_profiles_expose_their_address_and_block_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let address = "0x8ba1f109551bd432803012645ac136ddd64dba72";
+        SignerBackend::read_only(address).unwrap().save(&data_paths).unwrap();
+
+        // The process-wide flag is left alone so parallel tests aren't affected
+        let backend = SignerBackend::load(&data_paths).unwrap();
+        assert!(backend.is_read_only());
+        assert_eq!(account_address(&data_paths).await.unwrap(), address);
+        let error = backend.connect(&data_paths).await.err().unwrap();
+        assert!(error.to_string().contains("read-only"));
+
+        assert!(SignerBackend::read_only("my-wallet").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+//! CLOB requests signed through a [`Signer`]
+//!
+//! `polymarket_rs_client::ClobClient` only signs with an in-process private
+//! key. For other backends this module builds the two EIP-712 messages the
+//! CLOB needs itself: the `ClobAuth` attestation used to create or derive API
+//! credentials, and the CTF Exchange `Order` every limit order carries. The
+//! signed order is posted with the usual L2 HMAC headers, as are cancels.
+
+use anyhow::{anyhow, Context, Result};
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::{Address, Signature, U256};
+use ethers_core::utils::keccak256;
+use polymarket_rs_client::{ApiCreds, Side};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::signer::{OrderFunder, SignatureType, Signer};
+use crate::auth_env::build_l2_headers;
+
+pub const CTF_EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+pub const NEG_RISK_CTF_EXCHANGE_ADDRESS: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+
+const EXCHANGE_DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const CLOB_AUTH_DOMAIN_NAME: &str = "ClobAuthDomain";
+const DOMAIN_VERSION: &str = "1";
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const AUTH_DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId)";
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+const CLOB_AUTH_TYPE: &str = "ClobAuth(address address,string timestamp,uint256 nonce,string message)";
+
+/// Collateral and outcome tokens both have 6 decimals
+const TOKEN_DECIMALS: u32 = 6;
+
+fn string_hash(value: &str) -> Token {
+    Token::FixedBytes(keccak256(value.as_bytes()).to_vec())
+}
+
+/// Domain separator of the (neg risk) CTF Exchange
+pub fn exchange_domain_separator(chain_id: u64, neg_risk: bool) -> Result<[u8; 32]> {
+    let contract = if neg_risk {
+        NEG_RISK_CTF_EXCHANGE_ADDRESS
+    } else {
+        CTF_EXCHANGE_ADDRESS
+    };
+    Ok(keccak256(encode(&[
+        string_hash(DOMAIN_TYPE),
+        string_hash(EXCHANGE_DOMAIN_NAME),
+        string_hash(DOMAIN_VERSION),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(contract.parse()?),
+    ])))
+}
+
+fn clob_auth_domain_separator(chain_id: u64) -> [u8; 32] {
+    keccak256(encode(&[
+        string_hash(AUTH_DOMAIN_TYPE),
+        string_hash(CLOB_AUTH_DOMAIN_NAME),
+        string_hash(DOMAIN_VERSION),
+        Token::Uint(U256::from(chain_id)),
+    ]))
+}
+
+fn signature_hex(signature: &Signature) -> String {
+    format!("0x{}", hex::encode(signature.to_vec()))
+}
+
+/// Decimal amount to 6 decimal base units
+fn to_base_units(amount: Decimal) -> Result<U256> {
+    let units = (amount * Decimal::from(10u64.pow(TOKEN_DECIMALS)))
+        .trunc()
+        .to_u128()
+        .ok_or_else(|| anyhow!("Amount out of range: {}", amount))?;
+    Ok(U256::from(units))
+}
+
+/// Who signs an order and whose funds it trades
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderMaker {
+    pub maker: Address,
+    pub signer: Address,
+    pub signature_type: SignatureType,
+}
+
+impl OrderMaker {
+    /// The signer trades from its own address
+    pub fn eoa(address: Address) -> Self {
+        Self {
+            maker: address,
+            signer: address,
+            signature_type: SignatureType::Eoa,
+        }
+    }
+
+    /// Maker for a profile: its funder wallet when one is configured
+    pub fn for_signer(signer: Address, funder: Option<&OrderFunder>) -> Result<Self> {
+        let Some(funder) = funder else {
+            return Ok(Self::eoa(signer));
+        };
+        Ok(Self {
+            maker: funder.address.parse().context("Invalid funder address")?,
+            signer,
+            signature_type: funder.signature_type,
+        })
+    }
+}
+
+/// CTF Exchange order, as hashed and signed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeOrder {
+    pub salt: u64,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub expiration: U256,
+    pub nonce: U256,
+    pub fee_rate_bps: U256,
+    /// 0 for a buy, 1 for a sell
+    pub side: u8,
+    pub signature_type: u8,
+}
+
+impl ExchangeOrder {
+    /// GTC limit order: a buy gives USDC for shares, a sell the reverse.
+    /// `fee_rate_bps` must match the market's fee rate or the exchange rejects the order
+    pub fn limit(
+        maker: OrderMaker,
+        token_id: &str,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+        fee_rate_bps: u32,
+    ) -> Result<Self> {
+        if price <= Decimal::ZERO || price >= Decimal::ONE {
+            return Err(anyhow!("Price must be between 0 and 1, got {}", price));
+        }
+        let shares = size.round_dp_with_strategy(2, RoundingStrategy::ToZero);
+        let usdc = (shares * price).round_dp_with_strategy(4, RoundingStrategy::ToZero);
+        if shares.is_zero() || usdc.is_zero() {
+            return Err(anyhow!("Order size {} at {} rounds to nothing", size, price));
+        }
+        let (maker_amount, taker_amount, side) = match side {
+            Side::BUY => (usdc, shares, 0),
+            Side::SELL => (shares, usdc, 1),
+        };
+        Ok(Self {
+            salt: rand::random::<u32>() as u64,
+            maker: maker.maker,
+            signer: maker.signer,
+            taker: Address::zero(),
+            token_id: U256::from_dec_str(token_id).context("Token id must be a decimal number")?,
+            maker_amount: to_base_units(maker_amount)?,
+            taker_amount: to_base_units(taker_amount)?,
+            expiration: U256::zero(),
+            nonce: U256::zero(),
+            fee_rate_bps: U256::from(fee_rate_bps),
+            side,
+            signature_type: maker.signature_type.code(),
+        })
+    }
+
+    pub fn struct_hash(&self) -> [u8; 32] {
+        keccak256(encode(&[
+            string_hash(ORDER_TYPE),
+            Token::Uint(U256::from(self.salt)),
+            Token::Address(self.maker),
+            Token::Address(self.signer),
+            Token::Address(self.taker),
+            Token::Uint(self.token_id),
+            Token::Uint(self.maker_amount),
+            Token::Uint(self.taker_amount),
+            Token::Uint(self.expiration),
+            Token::Uint(self.nonce),
+            Token::Uint(self.fee_rate_bps),
+            Token::Uint(U256::from(self.side)),
+            Token::Uint(U256::from(self.signature_type)),
+        ]))
+    }
+
+    /// `order` object of the POST /order body
+    pub fn to_json(&self, signature: &Signature) -> Value {
+        json!({
+            "salt": self.salt,
+            "maker": format!("{:?}", self.maker),
+            "signer": format!("{:?}", self.signer),
+            "taker": format!("{:?}", self.taker),
+            "tokenId": self.token_id.to_string(),
+            "makerAmount": self.maker_amount.to_string(),
+            "takerAmount": self.taker_amount.to_string(),
+            "expiration": self.expiration.to_string(),
+            "nonce": self.nonce.to_string(),
+            "feeRateBps": self.fee_rate_bps.to_string(),
+            "side": if self.side == 0 { "BUY" } else { "SELL" },
+            "signatureType": self.signature_type,
+            "signature": signature_hex(signature),
+        })
+    }
+}
+
+/// L1 headers for the API key endpoints, signed by the account's key
+#[derive(Debug, Clone)]
+pub struct L1Headers {
+    pub address: String,
+    pub signature: String,
+    pub timestamp: String,
+    pub nonce: u64,
+}
+
+impl L1Headers {
+    pub async fn sign(signer: &dyn Signer, chain_id: u64, nonce: u64) -> Result<Self> {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let struct_hash = keccak256(encode(&[
+            string_hash(CLOB_AUTH_TYPE),
+            Token::Address(signer.address()),
+            string_hash(&timestamp),
+            Token::Uint(U256::from(nonce)),
+            string_hash(CLOB_AUTH_MESSAGE),
+        ]));
+        let signature = signer
+            .sign_typed_data(clob_auth_domain_separator(chain_id), struct_hash)
+            .await?;
+        Ok(Self {
+            address: signer.address_hex(),
+            signature: signature_hex(&signature),
+            timestamp,
+            nonce,
+        })
+    }
+
+    pub fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request
+            .header("POLY_ADDRESS", &self.address)
+            .header("POLY_SIGNATURE", &self.signature)
+            .header("POLY_TIMESTAMP", &self.timestamp)
+            .header("POLY_NONCE", self.nonce.to_string())
+    }
+}
+
+/// Create API credentials for the signer's address, or derive the existing ones
+pub async fn create_or_derive_api_key(host: &str, signer: &dyn Signer, chain_id: u64, nonce: u64) -> Result<ApiCreds> {
+    let http = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+    let host = host.trim_end_matches('/');
+
+    // One signature serves both attempts, so a Ledger asks for approval once
+    let headers = L1Headers::sign(signer, chain_id, nonce).await?;
+
+    let response = headers.apply(http.post(format!("{}/auth/api-key", host))).send().await?;
+    if response.status().is_success() {
+        return response.json().await.context("Unexpected API key response");
+    }
+
+    // Creation fails when a key already exists for this nonce; derive it instead
+    headers
+        .apply(http.get(format!("{}/auth/derive-api-key", host)))
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to derive API key")?
+        .json()
+        .await
+        .context("Unexpected API key response")
+}
+
+/// Public per-market settings of the CLOB that orders must match
+pub struct MarketSettings {
+    http: reqwest::Client,
+    host: String,
+}
+
+impl MarketSettings {
+    pub fn new(host: &str) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?,
+            host: host.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Whether the token trades on the neg risk exchange
+    pub async fn neg_risk(&self, token_id: &str) -> Result<bool> {
+        let response: Value = self
+            .http
+            .get(format!("{}/neg-risk", self.host))
+            .query(&[("token_id", token_id)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.get("neg_risk").and_then(|v| v.as_bool()).unwrap_or(false))
+    }
+
+    /// Fee rate the token's market charges takers, which signed orders must carry
+    pub async fn fee_rate_bps(&self, token_id: &str) -> Result<u32> {
+        let response: Value = self
+            .http
+            .get(format!("{}/fee-rate", self.host))
+            .query(&[("token_id", token_id)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let fee_rate = response
+            .get("base_fee")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| anyhow!("Fee rate response has no base_fee: {}", response))?;
+        u32::try_from(fee_rate).context("Fee rate out of range")
+    }
+}
+
+/// Places orders signed by a [`Signer`] instead of the CLOB client's key
+pub struct SignedOrderClient {
+    settings: MarketSettings,
+    chain_id: u64,
+    api_creds: ApiCreds,
+    signer: Arc<dyn Signer>,
+    maker: OrderMaker,
+}
+
+impl SignedOrderClient {
+    pub fn new(
+        host: &str,
+        chain_id: u64,
+        api_creds: ApiCreds,
+        signer: Arc<dyn Signer>,
+        funder: Option<OrderFunder>,
+    ) -> Result<Self> {
+        let maker = OrderMaker::for_signer(signer.address(), funder.as_ref())?;
+        Ok(Self {
+            settings: MarketSettings::new(host)?,
+            chain_id,
+            api_creds,
+            signer,
+            maker,
+        })
+    }
+
+    pub fn signer(&self) -> &Arc<dyn Signer> {
+        &self.signer
+    }
+
+    /// Request to `path` carrying the L2 HMAC headers
+    fn authenticated(&self, method: reqwest::Method, path: &str, body: String) -> Result<reqwest::RequestBuilder> {
+        let headers = build_l2_headers(
+            &self.api_creds,
+            &self.signer.address_hex(),
+            method.as_str(),
+            path,
+            Some(&body).filter(|b| !b.is_empty()).map(String::as_str),
+        )?;
+        let mut request = self
+            .settings
+            .http
+            .request(method, format!("{}{}", self.settings.host, path))
+            .header("Content-Type", "application/json")
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+
+    /// Sign and post a GTC limit order; returns the raw /order response
+    pub async fn post_limit_order(&self, token_id: &str, side: Side, price: Decimal, size: Decimal) -> Result<Value> {
+        let (neg_risk, fee_rate_bps) = tokio::try_join!(
+            self.settings.neg_risk(token_id),
+            self.settings.fee_rate_bps(token_id)
+        )?;
+        let order = ExchangeOrder::limit(self.maker, token_id, side, price, size, fee_rate_bps)?;
+        let domain_separator = exchange_domain_separator(self.chain_id, neg_risk)?;
+        let signature = self
+            .signer
+            .sign_typed_data(domain_separator, order.struct_hash())
+            .await?;
+
+        let body = json!({
+            "order": order.to_json(&signature),
+            "owner": self.api_creds.api_key,
+            "orderType": "GTC",
+        })
+        .to_string();
+        let request = self.authenticated(reqwest::Method::POST, "/order", body)?;
+        // Rejections come back as JSON with `success: false`, which the caller reports
+        Ok(request.send().await?.json().await?)
+    }
+
+    /// Cancel one order; returns the raw DELETE /order response listing
+    /// `canceled` and `not_canceled` orders
+    pub async fn cancel_order(&self, order_id: &str) -> Result<Value> {
+        let body = json!({ "orderID": order_id }).to_string();
+        let request = self.authenticated(reqwest::Method::DELETE, "/order", body)?;
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::signer::{eip712_digest, LocalSigner};
+    use ethers_core::types::H256;
+    use rust_decimal_macros::dec;
+
+    const TEST_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[test]
+    fn limit_order_amounts() {
+        let maker = OrderMaker::eoa(Address::repeat_byte(1));
+        let buy = ExchangeOrder::limit(maker, "123", Side::BUY, dec!(0.55), dec!(10.009), 0).unwrap();
+        assert_eq!(buy.maker_amount, U256::from(5_500_000u64));
+        assert_eq!(buy.taker_amount, U256::from(10_000_000u64));
+
+        let sell = ExchangeOrder::limit(maker, "123", Side::SELL, dec!(0.55), dec!(10), 0).unwrap();
+        assert_eq!(sell.maker_amount, U256::from(10_000_000u64));
+        assert_eq!(sell.taker_amount, U256::from(5_500_000u64));
+
+        assert!(ExchangeOrder::limit(maker, "123", Side::BUY, dec!(1.2), dec!(10), 0).is_err());
+        assert!(ExchangeOrder::limit(maker, "not-a-number", Side::BUY, dec!(0.5), dec!(10), 0).is_err());
+    }
+
+    #[test]
+    fn funder_orders_carry_profile_signature_type_and_fee() {
+        let signer = Address::repeat_byte(1);
+        let funder = OrderFunder::new("0x0202020202020202020202020202020202020202", SignatureType::PolyGnosisSafe).unwrap();
+        let maker = OrderMaker::for_signer(signer, Some(&funder)).unwrap();
+        let order = ExchangeOrder::limit(maker, "123", Side::BUY, dec!(0.5), dec!(10), 100).unwrap();
+        assert_eq!(order.maker, Address::repeat_byte(2));
+        assert_eq!(order.signer, signer);
+        assert_eq!(order.signature_type, 2);
+        assert_eq!(order.fee_rate_bps, U256::from(100));
+        assert_eq!(OrderMaker::for_signer(signer, None).unwrap(), OrderMaker::eoa(signer));
+    }
+
+    #[tokio::test]
+    async fn signed_order_recovers_to_maker() {
+        let signer = LocalSigner::from_hex(TEST_KEY).unwrap();
+        let order = ExchangeOrder::limit(OrderMaker::eoa(signer.address()), "42", Side::BUY, dec!(0.5), dec!(2), 0).unwrap();
+        let domain = exchange_domain_separator(137, false).unwrap();
+        assert_ne!(domain, exchange_domain_separator(137, true).unwrap());
+
+        let signature = signer.sign_typed_data(domain, order.struct_hash()).await.unwrap();
+        let digest = H256::from(eip712_digest(domain, order.struct_hash()));
+        assert_eq!(signature.recover(digest).unwrap(), order.maker);
+
+        let json = order.to_json(&signature);
+        assert_eq!(json["side"], "BUY");
+        assert_eq!(json["makerAmount"], "1000000");
+        assert_eq!(json["signature"].as_str().unwrap().len(), 132);
+    }
+}
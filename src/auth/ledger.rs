@@ -0,0 +1,393 @@
+//! Ledger hardware wallet signer
+//!
+//! Talks to the Ledger Ethereum app over USB HID. APDUs are split into 64 byte
+//! HID packets (channel, tag, sequence number, then payload), and replies end
+//! in a two byte status word. The device only ever returns signatures; the key
+//! stays on it and each signature has to be approved on its screen.
+//!
+//! The HID transport needs the `ledger` cargo feature (it links hidapi). The
+//! framing and APDU encoding below are always built so they can be tested
+//! without a device.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Signature, U256};
+use std::sync::{Arc, Mutex};
+
+use super::signer::Signer;
+
+/// First account of the standard Ethereum derivation
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+
+const CLA: u8 = 0xe0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+const INS_SIGN_EIP712_HASHED: u8 = 0x0c;
+const P1_FIRST_CHUNK: u8 = 0x00;
+const P1_MORE_CHUNKS: u8 = 0x80;
+const MAX_APDU_DATA: usize = 255;
+
+const SW_OK: u16 = 0x9000;
+const SW_REJECTED: u16 = 0x6985;
+const SW_APP_NOT_OPEN: u16 = 0x6e00;
+const SW_LOCKED: u16 = 0x5515;
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// `m/44'/60'/0'/0/0` to its BIP-32 indices
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>> {
+    let components = path
+        .strip_prefix("m/")
+        .ok_or_else(|| anyhow!("Derivation path must start with m/: {}", path))?;
+    let indices = components
+        .split('/')
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index: u32 = index
+                .parse()
+                .with_context(|| format!("Invalid derivation path component '{}'", component))?;
+            if index >= HARDENED {
+                return Err(anyhow!("Derivation index out of range: {}", component));
+            }
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect::<Result<Vec<u32>>>()?;
+    if indices.is_empty() || indices.len() > 10 {
+        return Err(anyhow!("Derivation path must have 1 to 10 components: {}", path));
+    }
+    Ok(indices)
+}
+
+fn encode_path(indices: &[u32]) -> Vec<u8> {
+    let mut data = vec![indices.len() as u8];
+    for index in indices {
+        data.extend_from_slice(&index.to_be_bytes());
+    }
+    data
+}
+
+fn apdu(ins: u8, p1: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = vec![CLA, ins, p1, 0x00, data.len() as u8];
+    apdu.extend_from_slice(data);
+    apdu
+}
+
+/// Ask for the address at `path` without showing it on the device
+pub fn get_address_apdu(path: &[u32]) -> Vec<u8> {
+    apdu(INS_GET_ADDRESS, 0x00, &encode_path(path))
+}
+
+/// Sign an already hashed EIP-712 message
+pub fn sign_eip712_hashed_apdu(path: &[u32], domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Vec<u8> {
+    let mut data = encode_path(path);
+    data.extend_from_slice(&domain_separator);
+    data.extend_from_slice(&struct_hash);
+    apdu(INS_SIGN_EIP712_HASHED, 0x00, &data)
+}
+
+/// Sign an unsigned transaction encoding, split across as many APDUs as needed
+pub fn sign_transaction_apdus(path: &[u32], unsigned_tx: &[u8]) -> Vec<Vec<u8>> {
+    let mut payload = encode_path(path);
+    payload.extend_from_slice(unsigned_tx);
+    payload
+        .chunks(MAX_APDU_DATA)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let p1 = if i == 0 { P1_FIRST_CHUNK } else { P1_MORE_CHUNKS };
+            apdu(INS_SIGN_TRANSACTION, p1, chunk)
+        })
+        .collect()
+}
+
+/// Split an APDU into HID packets
+pub fn wrap_apdu(apdu: &[u8]) -> Vec<[u8; HID_PACKET_SIZE]> {
+    let mut payload = (apdu.len() as u16).to_be_bytes().to_vec();
+    payload.extend_from_slice(apdu);
+
+    let mut packets = Vec::new();
+    let mut remaining = payload.as_slice();
+    let mut sequence: u16 = 0;
+    while !remaining.is_empty() || packets.is_empty() {
+        let mut packet = [0u8; HID_PACKET_SIZE];
+        packet[..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+        packet[2] = HID_TAG_APDU;
+        packet[3..5].copy_from_slice(&sequence.to_be_bytes());
+        let take = remaining.len().min(HID_PACKET_SIZE - 5);
+        packet[5..5 + take].copy_from_slice(&remaining[..take]);
+        remaining = &remaining[take..];
+        packets.push(packet);
+        sequence += 1;
+    }
+    packets
+}
+
+/// Reassembles a reply from HID packets
+#[derive(Debug, Default)]
+pub struct ResponseReader {
+    expected_len: Option<usize>,
+    data: Vec<u8>,
+    sequence: u16,
+}
+
+impl ResponseReader {
+    /// Feed the next packet; returns the reply once it is complete
+    pub fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>> {
+        if packet.len() < 5 {
+            return Err(anyhow!("Short HID packet from Ledger"));
+        }
+        let channel = u16::from_be_bytes([packet[0], packet[1]]);
+        let sequence = u16::from_be_bytes([packet[3], packet[4]]);
+        if channel != HID_CHANNEL || packet[2] != HID_TAG_APDU {
+            return Err(anyhow!("Unexpected HID packet from Ledger"));
+        }
+        if sequence != self.sequence {
+            return Err(anyhow!("Out of order HID packet from Ledger"));
+        }
+        self.sequence += 1;
+
+        let mut body = &packet[5..];
+        if self.expected_len.is_none() {
+            if body.len() < 2 {
+                return Err(anyhow!("Short HID packet from Ledger"));
+            }
+            self.expected_len = Some(u16::from_be_bytes([body[0], body[1]]) as usize);
+            body = &body[2..];
+        }
+        let expected = self.expected_len.unwrap_or_default();
+        let take = body.len().min(expected - self.data.len());
+        self.data.extend_from_slice(&body[..take]);
+        Ok((self.data.len() == expected).then(|| std::mem::take(&mut self.data)))
+    }
+}
+
+/// Strip the status word from a reply, turning failures into readable errors
+pub fn check_status(response: &[u8]) -> Result<&[u8]> {
+    if response.len() < 2 {
+        return Err(anyhow!("Empty reply from Ledger"));
+    }
+    let (data, status) = response.split_at(response.len() - 2);
+    match u16::from_be_bytes([status[0], status[1]]) {
+        SW_OK => Ok(data),
+        SW_REJECTED => Err(anyhow!("Rejected on the Ledger device")),
+        SW_APP_NOT_OPEN => Err(anyhow!("Open the Ethereum app on the Ledger device")),
+        SW_LOCKED => Err(anyhow!("Unlock the Ledger device")),
+        other => Err(anyhow!("Ledger returned status {:#06x}", other)),
+    }
+}
+
+/// `v ‖ r ‖ s` reply of the signing APDUs, with `v` normalized to 27 or 28
+pub fn parse_signature(data: &[u8]) -> Result<Signature> {
+    if data.len() < 65 {
+        return Err(anyhow!("Ledger signature reply is {} bytes, expected 65", data.len()));
+    }
+    let parity = data[0].checked_sub(27).unwrap_or(data[0]) & 1;
+    Ok(Signature {
+        r: U256::from_big_endian(&data[1..33]),
+        s: U256::from_big_endian(&data[33..65]),
+        v: 27 + parity as u64,
+    })
+}
+
+/// Address from a GET_ADDRESS reply (public key, then the address as ASCII hex)
+pub fn parse_address(data: &[u8]) -> Result<Address> {
+    let key_len = *data.first().ok_or_else(|| anyhow!("Empty address reply from Ledger"))? as usize;
+    let address_len = *data
+        .get(1 + key_len)
+        .ok_or_else(|| anyhow!("Truncated address reply from Ledger"))? as usize;
+    let address = data
+        .get(2 + key_len..2 + key_len + address_len)
+        .ok_or_else(|| anyhow!("Truncated address reply from Ledger"))?;
+    let address = std::str::from_utf8(address).context("Ledger address is not ASCII")?;
+    format!("0x{}", address.trim_start_matches("0x"))
+        .parse()
+        .context("Ledger returned an invalid address")
+}
+
+#[cfg(feature = "ledger")]
+mod transport {
+    use super::*;
+
+    /// Open HID connection to the Ledger
+    pub struct HidTransport {
+        device: hidapi::HidDevice,
+    }
+
+    impl HidTransport {
+        pub fn open() -> Result<Self> {
+            let api = hidapi::HidApi::new().context("Failed to initialize HID")?;
+            let info = api
+                .device_list()
+                .find(|d| d.vendor_id() == LEDGER_VENDOR_ID && (d.interface_number() == 0 || d.usage_page() == 0xffa0))
+                .ok_or_else(|| anyhow!("No Ledger device found. Connect and unlock it, then open the Ethereum app"))?;
+            let device = info.open_device(&api).context("Failed to open the Ledger device")?;
+            Ok(Self { device })
+        }
+
+        pub fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>> {
+            for packet in wrap_apdu(apdu) {
+                // hidapi expects the report id in front of the packet
+                let mut report = vec![0u8];
+                report.extend_from_slice(&packet);
+                self.device.write(&report).context("Failed to write to the Ledger")?;
+            }
+
+            let mut reader = ResponseReader::default();
+            loop {
+                let mut packet = [0u8; HID_PACKET_SIZE];
+                let read = self.device.read(&mut packet).context("Failed to read from the Ledger")?;
+                if let Some(response) = reader.push(&packet[..read])? {
+                    return Ok(check_status(&response)?.to_vec());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "ledger"))]
+mod transport {
+    use super::*;
+
+    /// Uninhabited: without the feature a transport can never be opened
+    pub enum HidTransport {}
+
+    impl HidTransport {
+        pub fn open() -> Result<Self> {
+            Err(anyhow!(
+                "Ledger support is not compiled in. Rebuild with `cargo build --features ledger`"
+            ))
+        }
+
+        pub fn exchange(&self, _apdu: &[u8]) -> Result<Vec<u8>> {
+            match *self {}
+        }
+    }
+}
+
+use transport::HidTransport;
+
+/// Signs on a Ledger device; every signature needs approval on the device
+pub struct LedgerSigner {
+    transport: Arc<Mutex<HidTransport>>,
+    path: Vec<u32>,
+    derivation_path: String,
+    address: Address,
+}
+
+impl LedgerSigner {
+    /// Open the device and read the address at `derivation_path` (blocking)
+    pub fn connect(derivation_path: &str) -> Result<Self> {
+        let path = parse_derivation_path(derivation_path)?;
+        let transport = HidTransport::open()?;
+        let address = parse_address(&transport.exchange(&get_address_apdu(&path))?)?;
+        Ok(Self {
+            transport: Arc::new(Mutex::new(transport)),
+            path,
+            derivation_path: derivation_path.to_string(),
+            address,
+        })
+    }
+
+    /// Run APDUs on a blocking thread, since the device waits for the user
+    async fn exchange(&self, apdus: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+        let transport = self.transport.clone();
+        tokio::task::spawn_blocking(move || {
+            let transport = transport.lock().map_err(|_| anyhow!("Ledger transport poisoned"))?;
+            let mut response = Vec::new();
+            for apdu in &apdus {
+                response = transport.exchange(apdu)?;
+            }
+            Ok(response)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Signer for LedgerSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn describe(&self) -> String {
+        format!("Ledger ({})", self.derivation_path)
+    }
+
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Signature> {
+        let apdu = sign_eip712_hashed_apdu(&self.path, domain_separator, struct_hash);
+        parse_signature(&self.exchange(vec![apdu]).await?)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        let apdus = sign_transaction_apdus(&self.path, &tx.rlp());
+        parse_signature(&self.exchange(apdus).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_derivation_paths() {
+        assert_eq!(
+            parse_derivation_path(DEFAULT_DERIVATION_PATH).unwrap(),
+            vec![44 | HARDENED, 60 | HARDENED, HARDENED, 0, 0]
+        );
+        assert!(parse_derivation_path("44'/60'").is_err());
+        assert!(parse_derivation_path("m/44'/x").is_err());
+    }
+
+    #[test]
+    fn hid_framing_round_trips_long_apdus() {
+        let path = parse_derivation_path(DEFAULT_DERIVATION_PATH).unwrap();
+        let apdus = sign_transaction_apdus(&path, &[0xab; 400]);
+        assert_eq!(apdus.len(), 2);
+        assert_eq!(&apdus[0][..5], &[CLA, INS_SIGN_TRANSACTION, P1_FIRST_CHUNK, 0, 255]);
+        assert_eq!(apdus[1][2], P1_MORE_CHUNKS);
+
+        let packets = wrap_apdu(&apdus[0]);
+        assert_eq!(packets.len(), 5);
+        assert_eq!(&packets[1][..5], &[0x01, 0x01, 0x05, 0x00, 0x01]);
+
+        // The device frames its replies the same way
+        let mut reader = ResponseReader::default();
+        let mut result = None;
+        for packet in &packets {
+            assert!(result.is_none());
+            result = reader.push(packet).unwrap();
+        }
+        assert_eq!(result.unwrap(), apdus[0]);
+    }
+
+    #[test]
+    fn decodes_replies() {
+        assert!(check_status(&[0x69, 0x85]).unwrap_err().to_string().contains("Rejected"));
+
+        let mut reply = vec![0x01];
+        reply.extend_from_slice(&[0x11; 32]);
+        reply.extend_from_slice(&[0x22; 32]);
+        reply.extend_from_slice(&[0x90, 0x00]);
+        let signature = parse_signature(check_status(&reply).unwrap()).unwrap();
+        assert_eq!(signature.v, 28);
+        assert_eq!(signature.r, U256::from_big_endian(&[0x11; 32]));
+
+        let mut address_reply = vec![65];
+        address_reply.extend_from_slice(&[0x04; 65]);
+        address_reply.push(40);
+        address_reply.extend_from_slice(b"8ba1f109551bD432803012645Ac136ddd64DBA72");
+        assert_eq!(
+            format!("{:?}", parse_address(&address_reply).unwrap()),
+            "0x8ba1f109551bd432803012645ac136ddd64dba72"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+pub mod access;
+pub mod clob;
+pub mod ledger;
+pub mod signer;
+
+pub use access::{account_address, ensure_writable, is_read_only};
+pub use clob::{MarketSettings, SignedOrderClient};
+pub use signer::{load_signer, LocalSigner, OrderFunder, SignatureType, Signer, SignerBackend};
+
+use anyhow::{anyhow, Result};
+use polymarket_rs_client::ClobClient;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config;
+use crate::data_paths::DataPaths;
+
+fn chain_id_for(host: &str) -> u64 {
+    if host.contains("mumbai") {
+        80001
+    } else {
+        137
+    }
+}
+
+/// Initialize authentication with L1 wallet and save L2 API credentials
+pub async fn init_auth(
+    host: &str,
+    data_paths: &DataPaths,
+    private_key_hex: &str,
+    nonce: u64,
+) -> Result<()> {
+    // Remove 0x prefix if present
+    let private_key_hex = private_key_hex.trim_start_matches("0x");
+
+    // Create client with L1 headers (this automatically adds POLY_* headers)
+    let client = ClobClient::with_l1_headers(host, private_key_hex, 137);
+
+    info!("📡 Performing L1 authentication...");
+
+    // Create or derive API key (L2 credentials)
+    // Convert nonce to U256
+    let nonce_u256 = if nonce == 0 {
+        None
+    } else {
+        Some(polymarket_rs_client::U256::from(nonce))
+    };
+
+    let api_creds = client
+        .create_or_derive_api_key(nonce_u256)
+        .await
+        .map_err(|e| anyhow!("Failed to create/derive API key: {}", e))?;
+
+    info!("🔑 API credentials obtained successfully");
+
+    // Save credentials to encrypted file
+    config::save_credentials(data_paths, &api_creds).await?;
+
+    // Also save the private key for future use
+    config::save_private_key(data_paths, private_key_hex).await?;
+
+    info!("💾 Credentials saved to encrypted storage");
+
+    SignerBackend::Local.save(data_paths)?;
+
+    Ok(())
+}
+
+/// Initialize authentication with an external signer; no private key is stored
+pub async fn init_auth_with_signer(
+    host: &str,
+    data_paths: &DataPaths,
+    backend: SignerBackend,
+    nonce: u64,
+) -> Result<Arc<dyn Signer>> {
+    let signer = backend.connect(data_paths).await?;
+    info!("📡 Performing L1 authentication with {} ({})...", signer.describe(), signer.address_hex());
+
+    let api_creds = clob::create_or_derive_api_key(host, signer.as_ref(), chain_id_for(host), nonce)
+        .await
+        .map_err(|e| anyhow!("Failed to create/derive API key: {}", e))?;
+    info!("🔑 API credentials obtained successfully");
+
+    config::save_credentials(data_paths, &api_creds).await?;
+    backend.save(data_paths)?;
+    info!("💾 Credentials saved to encrypted storage");
+
+    Ok(signer)
+}
+
+/// Order client for profiles whose key is not available to the CLOB client
+///
+/// `None` for local key profiles, which keep placing orders through `ClobClient`.
+pub async fn order_signer(host: &str, data_paths: &DataPaths) -> Result<Option<Arc<SignedOrderClient>>> {
+    let backend = SignerBackend::load(data_paths)?;
+    if !backend.is_ledger() {
+        return Ok(None);
+    }
+    let api_creds = config::load_credentials(data_paths).await?;
+    let signer = backend.connect(data_paths).await?;
+    Ok(Some(Arc::new(SignedOrderClient::new(
+        host,
+        chain_id_for(host),
+        api_creds,
+        signer,
+        backend.funder().cloned(),
+    )?)))
+}
+
+/// Get an authenticated client with saved credentials
+pub async fn get_authenticated_client(host: &str, data_paths: &DataPaths) -> Result<ClobClient> {
+    info!("Creating authenticated client for host: {}", host);
+
+    let backend = SignerBackend::load(data_paths)?;
+    if backend.is_read_only() {
+        // No credentials at all; public endpoints still work
+        info!("Read-only profile: using an unauthenticated client");
+        return Ok(ClobClient::new(host));
+    }
+
+    // Load saved credentials
+    let api_creds = config::load_credentials(data_paths).await.map_err(|e| {
+        anyhow!(
+            "Failed to load credentials. Run 'polybot init' first: {}",
+            e
+        )
+    })?;
+
+    info!("Loaded API credentials successfully");
+
+    if backend.is_ledger() {
+        // The key lives on the device, so the client can only make public calls;
+        // orders go through `order_signer` instead
+        warn!("Ledger profile: CLOB client has no signing key, authenticated calls are unavailable");
+        let mut client = ClobClient::new(host);
+        client.set_api_creds(api_creds);
+        return Ok(client);
+    }
+
+    // Load private key
+    let private_key = config::load_private_key(data_paths).await.map_err(|e| {
+        anyhow!(
+            "Failed to load private key. Run 'polybot init' first: {}",
+            e
+        )
+    })?;
+
+    info!("Loaded private key successfully");
+
+    // Determine chain ID based on host
+    let chain_id = chain_id_for(host);
+    info!("Using chain ID: {}", chain_id);
+
+    // Create client with L1 headers (this sets up the signer)
+    let mut client = ClobClient::with_l1_headers(host, &private_key, chain_id);
+
+    // Set the API credentials for L2 authentication
+    client.set_api_creds(api_creds);
+
+    info!("Client created successfully");
+
+    Ok(client)
+}
@@ -0,0 +1,288 @@
+//! Signing backends for the trading account
+//!
+//! Orders are EIP-712 typed data and on-chain operations are EIP-1559
+//! transactions; both reduce to signing a 32 byte digest. The `Signer` trait
+//! hides where that happens: in process with the stored private key, or on a
+//! Ledger device so the key never touches the trading machine. Which backend a
+//! profile uses is recorded next to its credentials in `signer.json`.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Signature};
+use ethers_core::utils::{keccak256, secret_key_to_address};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::access::read_only_error;
+use super::ledger::{LedgerSigner, DEFAULT_DERIVATION_PATH};
+use crate::config;
+use crate::core::audit::{self, AuditAction};
+use crate::core::funds::is_evm_address;
+use crate::data_paths::DataPaths;
+use crate::ethereum_utils::{sign_digest, signing_key_from_hex};
+
+const SIGNER_CONFIG_FILE: &str = "signer.json";
+
+/// Produces the signatures the bot needs from the account's key
+#[async_trait]
+pub trait Signer: Send + Sync {
+    fn address(&self) -> Address;
+
+    /// Short description for status output
+    fn describe(&self) -> String;
+
+    /// Sign EIP-712 typed data given its domain separator and struct hash
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Signature>;
+
+    /// Sign a transaction; `v` is 27 or 28
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature>;
+
+    /// 0x-prefixed checksum-free address, as the API expects it
+    fn address_hex(&self) -> String {
+        format!("{:?}", self.address())
+    }
+}
+
+/// `keccak256(0x1901 ‖ domainSeparator ‖ hashStruct(message))`
+pub fn eip712_digest(domain_separator: [u8; 32], struct_hash: [u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 66];
+    buf[0] = 0x19;
+    buf[1] = 0x01;
+    buf[2..34].copy_from_slice(&domain_separator);
+    buf[34..].copy_from_slice(&struct_hash);
+    keccak256(buf)
+}
+
+/// Signs with the private key saved by `polybot init`
+pub struct LocalSigner {
+    signing_key: SigningKey,
+    address: Address,
+}
+
+impl LocalSigner {
+    pub fn from_hex(private_key_hex: &str) -> Result<Self> {
+        let signing_key = signing_key_from_hex(private_key_hex)?;
+        let address = secret_key_to_address(&signing_key);
+        Ok(Self { signing_key, address })
+    }
+}
+
+#[async_trait]
+impl Signer for LocalSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn describe(&self) -> String {
+        "local private key".to_string()
+    }
+
+    async fn sign_typed_data(&self, domain_separator: [u8; 32], struct_hash: [u8; 32]) -> Result<Signature> {
+        sign_digest(&self.signing_key, eip712_digest(domain_separator, struct_hash))
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        sign_digest(&self.signing_key, tx.sighash().to_fixed_bytes())
+    }
+}
+
+/// Where a profile's signatures come from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum SignerBackend {
+    /// Encrypted private key in the profile's credentials
+    #[default]
+    Local,
+    /// Ledger Ethereum app over USB HID
+    Ledger {
+        #[serde(default = "default_derivation_path")]
+        derivation_path: String,
+        /// Wallet the orders trade from, when it isn't the device address
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        funder: Option<OrderFunder>,
+    },
+    /// No key at all: the profile only monitors `address`
+    ReadOnly { address: String },
+}
+
+fn default_derivation_path() -> String {
+    DEFAULT_DERIVATION_PATH.to_string()
+}
+
+/// How the exchange verifies an order's signature against its maker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureType {
+    /// Signed by the maker itself
+    #[default]
+    Eoa,
+    /// Maker is a Polymarket proxy wallet owned by the signer
+    PolyProxy,
+    /// Maker is a Gnosis Safe owned by the signer
+    PolyGnosisSafe,
+}
+
+impl SignatureType {
+    /// `signatureType` field of the exchange order
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Eoa => 0,
+            Self::PolyProxy => 1,
+            Self::PolyGnosisSafe => 2,
+        }
+    }
+}
+
+/// Proxy or Safe wallet that holds the funds orders trade with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderFunder {
+    pub address: String,
+    pub signature_type: SignatureType,
+}
+
+impl OrderFunder {
+    pub fn new(address: &str, signature_type: SignatureType) -> Result<Self> {
+        if !is_evm_address(address) {
+            return Err(anyhow!("Not an EVM address: {}", address));
+        }
+        if signature_type == SignatureType::Eoa {
+            return Err(anyhow!("A funder wallet needs a proxy or Safe signature type"));
+        }
+        Ok(Self {
+            address: address.to_lowercase(),
+            signature_type,
+        })
+    }
+}
+
+impl SignerBackend {
+    pub fn ledger(derivation_path: Option<String>, funder: Option<OrderFunder>) -> Self {
+        Self::Ledger {
+            derivation_path: derivation_path.unwrap_or_else(default_derivation_path),
+            funder,
+        }
+    }
+
+    /// Funder wallet orders are made from; `None` when the signer trades for itself
+    pub fn funder(&self) -> Option<&OrderFunder> {
+        match self {
+            Self::Ledger { funder, .. } => funder.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn read_only(address: &str) -> Result<Self> {
+        if !is_evm_address(address) {
+            return Err(anyhow!("Not an EVM address: {}", address));
+        }
+        Ok(Self::ReadOnly {
+            address: address.to_lowercase(),
+        })
+    }
+
+    pub fn is_ledger(&self) -> bool {
+        matches!(self, Self::Ledger { .. })
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Self::ReadOnly { .. })
+    }
+
+    fn path(data_paths: &DataPaths) -> PathBuf {
+        data_paths.auth().join(SIGNER_CONFIG_FILE)
+    }
+
+    /// Backend of the profile, local when none was chosen
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = Self::path(data_paths);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, data_paths: &DataPaths) -> Result<()> {
+        let path = Self::path(data_paths);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        audit::record(
+            AuditAction::CredentialsChanged,
+            "Signing backend set",
+            serde_json::to_value(self)?,
+            Ok(()),
+        );
+        Ok(())
+    }
+
+    /// Open the backend, connecting to the device for Ledger profiles
+    pub async fn connect(&self, data_paths: &DataPaths) -> Result<Arc<dyn Signer>> {
+        match self {
+            Self::Local => {
+                let private_key = config::load_private_key(data_paths).await?;
+                Ok(Arc::new(LocalSigner::from_hex(&private_key)?))
+            }
+            Self::Ledger { derivation_path, .. } => {
+                let path = derivation_path.clone();
+                let signer = tokio::task::spawn_blocking(move || LedgerSigner::connect(&path)).await??;
+                Ok(Arc::new(signer))
+            }
+            Self::ReadOnly { .. } => Err(read_only_error("sign")),
+        }
+    }
+}
+
+/// Signer of the profile behind `data_paths`
+pub async fn load_signer(data_paths: &DataPaths) -> Result<Arc<dyn Signer>> {
+    SignerBackend::load(data_paths)?.connect(data_paths).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::H256;
+
+    const TEST_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[tokio::test]
+    async fn local_signer_signatures_recover_to_its_address() {
+        let signer = LocalSigner::from_hex(TEST_KEY).unwrap();
+        let domain = keccak256(b"domain");
+        let message = keccak256(b"message");
+        let signature = signer.sign_typed_data(domain, message).await.unwrap();
+        assert!(signature.v == 27 || signature.v == 28);
+        let recovered = signature
+            .recover(H256::from(eip712_digest(domain, message)))
+            .unwrap();
+        assert_eq!(recovered, signer.address());
+    }
+
+    #[test]
+    fn backend_config_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        assert_eq!(SignerBackend::load(&data_paths).unwrap(), SignerBackend::Local);
+
+        SignerBackend::ledger(None, None).save(&data_paths).unwrap();
+        assert_eq!(
+            SignerBackend::load(&data_paths).unwrap(),
+            SignerBackend::Ledger {
+                derivation_path: DEFAULT_DERIVATION_PATH.to_string(),
+                funder: None,
+            }
+        );
+
+        let funder = OrderFunder::new("0xABCDEF0123456789abcdef0123456789abcdef01", SignatureType::PolyProxy).unwrap();
+        SignerBackend::ledger(None, Some(funder.clone())).save(&data_paths).unwrap();
+        let loaded = SignerBackend::load(&data_paths).unwrap();
+        assert_eq!(loaded.funder(), Some(&funder));
+        assert_eq!(funder.address, "0xabcdef0123456789abcdef0123456789abcdef01");
+        assert!(OrderFunder::new("0xabcdef0123456789abcdef0123456789abcdef01", SignatureType::Eoa).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+//! User-defined command aliases
+//!
+//! `aliases.yaml` in the base data directory maps a name to a command line,
+//! e.g. `daily: pipeline daily --param date=${date}`, and
+//! `polybot daily` runs that command. Expansion happens on the raw arguments
+//! before clap parses them:
+//!
+//! - `${date}`, `${datetime}`, `${timestamp}`, `${year}`, `${month}` and
+//!   `${day}` are the local time, as in pipeline parameters
+//! - `${1}`, `${2}`, ... take the arguments given after the alias name; the
+//!   arguments no placeholder used are appended unchanged
+//! - other `${...}` placeholders are passed through untouched
+//!
+//! An alias may start with another alias, which adds arguments to it but
+//! can't override a flag the inner alias already sets: the flag ends up on
+//! the command line twice and clap rejects it. Names of built-in commands
+//! can't be aliased, so an alias never changes what an existing command does.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::data_paths::DEFAULT_DATA_DIR;
+
+/// Name of the alias file inside the base data directory
+pub const ALIASES_FILE: &str = "aliases.yaml";
+
+/// How many aliases may expand into one another before giving up
+const MAX_EXPANSION_DEPTH: usize = 8;
+
+/// Global flags that take a value and may come before the command name
+const VALUE_FLAGS: [&str; 2] = ["--data-dir", "--profile"];
+
+/// Aliases kept as YAML in the base data directory, shared by all profiles
+pub struct AliasStore {
+    path: PathBuf,
+}
+
+impl AliasStore {
+    pub fn new(base_dir: &Path) -> Self {
+        Self {
+            path: base_dir.join(ALIASES_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Aliases> {
+        if !self.path.exists() {
+            return Ok(Aliases::default());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        if content.trim().is_empty() {
+            return Ok(Aliases::default());
+        }
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid aliases file {}", self.path.display()))
+    }
+
+    pub fn save(&self, aliases: &Aliases) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("yaml.tmp");
+        std::fs::write(&tmp, serde_yaml::to_string(aliases)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// One alias as listed
+pub struct Alias<'a> {
+    pub name: &'a str,
+    pub command: &'a str,
+}
+
+/// Command lines by alias name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Aliases {
+    commands: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(name)
+    }
+
+    /// Aliases in name order
+    pub fn iter(&self) -> impl Iterator<Item = Alias<'_>> {
+        self.commands.iter().map(|(name, command)| Alias { name, command })
+    }
+
+    pub fn insert(&mut self, name: String, command: String) {
+        self.commands.insert(name, command);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.commands.remove(name)
+    }
+
+    /// Alias names are typed as a command, so keep them to one simple word
+    pub fn validate_name(name: &str, builtins: &[String]) -> Result<()> {
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && !name.starts_with('-')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!(
+                "Invalid alias name '{}': use letters, digits, '-' or '_'",
+                name
+            );
+        }
+        if builtins.iter().any(|builtin| builtin == name) {
+            bail!("'{}' is a built-in command and can't be aliased", name);
+        }
+        Ok(())
+    }
+
+    /// Expand the process arguments with the aliases of the `--data-dir` they name
+    ///
+    /// A broken alias file is reported and ignored, so it can't lock the user out
+    /// of `polybot alias` itself.
+    pub fn expand_command_line(args: Vec<OsString>, builtins: &[String]) -> Result<Vec<OsString>> {
+        let store = AliasStore::new(&Self::data_dir_arg(&args));
+        let aliases = match store.load() {
+            Ok(aliases) => aliases,
+            Err(e) => {
+                eprintln!("⚠️  Ignoring aliases: {:#}", e);
+                return Ok(args);
+            }
+        };
+        if aliases.is_empty() {
+            return Ok(args);
+        }
+        aliases.expand(args, builtins)
+    }
+
+    /// Expand an alias at the command position of `args` (the full argument list,
+    /// program name first); arguments without an alias are returned unchanged
+    pub fn expand(&self, args: Vec<OsString>, builtins: &[String]) -> Result<Vec<OsString>> {
+        let mut args = args;
+        let mut seen = Vec::new();
+        loop {
+            let Some(index) = Self::command_index(&args) else {
+                return Ok(args);
+            };
+            // Alias names are plain words, so a name that isn't UTF-8 is never one
+            let Some(name) = args[index].to_str().map(str::to_string) else {
+                return Ok(args);
+            };
+            if builtins.contains(&name) {
+                return Ok(args);
+            }
+            let Some(command) = self.commands.get(&name) else {
+                return Ok(args);
+            };
+            if seen.contains(&name) {
+                bail!(
+                    "Alias '{}' expands into itself ({})",
+                    name,
+                    seen.join(" -> ")
+                );
+            }
+            if seen.len() >= MAX_EXPANSION_DEPTH {
+                bail!("Aliases nest deeper than {} levels", MAX_EXPANSION_DEPTH);
+            }
+            seen.push(name.clone());
+
+            let rest = args.split_off(index + 1);
+            let words = Self::split_words(command)
+                .with_context(|| format!("Alias '{}' is not a valid command line", name))?;
+            let expanded = Self::substitute(&words, &rest)
+                .with_context(|| format!("Alias '{}' ({})", name, command))?;
+
+            args.truncate(index);
+            args.extend(expanded);
+        }
+    }
+
+    /// Quote words that need it so `split_words` reads them back unchanged
+    pub fn join_words(words: &[String]) -> String {
+        words
+            .iter()
+            .map(|word| {
+                let plain = !word.is_empty()
+                    && !word
+                        .chars()
+                        .any(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\'));
+                if plain {
+                    word.clone()
+                } else {
+                    format!("'{}'", word.replace('\'', r"'\''"))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Split a command line into words like a POSIX shell: whitespace separates
+    /// words, single quotes keep everything literal, double quotes and backslashes
+    /// escape
+    fn split_words(line: &str) -> Result<Vec<String>> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut in_word = false;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    in_word = true;
+                    loop {
+                        match chars.next() {
+                            Some('\'') => break,
+                            Some(c) => word.push(c),
+                            None => bail!("unclosed single quote"),
+                        }
+                    }
+                }
+                '"' => {
+                    in_word = true;
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(c) => word.push(c),
+                                None => bail!("unclosed double quote"),
+                            },
+                            Some(c) => word.push(c),
+                            None => bail!("unclosed double quote"),
+                        }
+                    }
+                }
+                '\\' => {
+                    in_word = true;
+                    if let Some(c) = chars.next() {
+                        word.push(c);
+                    }
+                }
+                c if c.is_whitespace() => {
+                    if in_word {
+                        words.push(std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                }
+                c => {
+                    in_word = true;
+                    word.push(c);
+                }
+            }
+        }
+        if in_word {
+            words.push(word);
+        }
+        Ok(words)
+    }
+
+    /// Fill the placeholders of an alias's words, then append the arguments no
+    /// positional placeholder took
+    ///
+    /// Arguments that are a whole word or appended are kept as given; only one
+    /// spliced into a longer word has to be UTF-8.
+    fn substitute(words: &[String], args: &[OsString]) -> Result<Vec<OsString>> {
+        let time = TimePlaceholders::now();
+        let mut used = vec![false; args.len()];
+        let mut expanded = Vec::with_capacity(words.len() + args.len());
+        let argument = |position: usize| {
+            position.checked_sub(1).filter(|&i| i < args.len()).ok_or_else(|| {
+                anyhow!(
+                    "needs at least {} argument(s), got {}",
+                    position.max(1),
+                    args.len()
+                )
+            })
+        };
+        for word in words {
+            let whole = word
+                .strip_prefix("${")
+                .and_then(|key| key.strip_suffix('}'))
+                .and_then(|key| key.parse::<usize>().ok());
+            if let Some(position) = whole {
+                let i = argument(position)?;
+                used[i] = true;
+                expanded.push(args[i].clone());
+                continue;
+            }
+
+            let mut out = String::new();
+            let mut rest = word.as_str();
+            while let Some(start) = rest.find("${") {
+                out.push_str(&rest[..start]);
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                let key = &rest[start + 2..start + len];
+                let placeholder = &rest[start..=start + len];
+                if let Ok(position) = key.parse::<usize>() {
+                    let i = argument(position)?;
+                    let value = args[i]
+                        .to_str()
+                        .ok_or_else(|| anyhow!("argument {} is not valid UTF-8", position))?;
+                    used[i] = true;
+                    out.push_str(value);
+                } else if let Some(value) = time.get(key) {
+                    out.push_str(value);
+                } else {
+                    out.push_str(placeholder);
+                }
+                rest = &rest[start + len + 1..];
+            }
+            out.push_str(rest);
+            expanded.push(OsString::from(out));
+        }
+        expanded.extend(
+            args.iter()
+                .zip(used)
+                .filter(|(_, used)| !used)
+                .map(|(arg, _)| arg.clone()),
+        );
+        Ok(expanded)
+    }
+
+    /// Index of the command name: the first argument after the program name that
+    /// isn't a global flag or a global flag's value
+    fn command_index(args: &[OsString]) -> Option<usize> {
+        let mut index = 1;
+        while index < args.len() {
+            let arg = args[index].to_string_lossy();
+            if VALUE_FLAGS.contains(&arg.as_ref()) {
+                index += 2;
+            } else if arg.starts_with('-') {
+                index += 1;
+            } else {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    /// The `--data-dir` given on the command line, or the default
+    fn data_dir_arg(args: &[OsString]) -> PathBuf {
+        let mut data_dir = PathBuf::from(DEFAULT_DATA_DIR);
+        let end = Self::command_index(args).unwrap_or(args.len());
+        let mut index = 1;
+        while index < end {
+            let arg = args[index].to_string_lossy();
+            if arg == "--data-dir" {
+                if let Some(value) = args.get(index + 1) {
+                    data_dir = PathBuf::from(value);
+                }
+                index += 2;
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--data-dir=") {
+                data_dir = PathBuf::from(value);
+            }
+            index += 1;
+        }
+        data_dir
+    }
+}
+
+/// `${date}`, `${datetime}`, ... and their values at one local time
+pub struct TimePlaceholders {
+    date: String,
+    datetime: String,
+    timestamp: String,
+    year: String,
+    month: String,
+    day: String,
+}
+
+impl TimePlaceholders {
+    pub fn now() -> Self {
+        let now = Local::now();
+        Self {
+            date: now.format("%Y-%m-%d").to_string(),
+            datetime: now.format("%Y-%m-%d_%H-%M-%S").to_string(),
+            timestamp: now.timestamp().to_string(),
+            year: now.format("%Y").to_string(),
+            month: now.format("%m").to_string(),
+            day: now.format("%d").to_string(),
+        }
+    }
+
+    /// Value of the placeholder `${key}`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "date" => Some(&self.date),
+            "datetime" => Some(&self.datetime),
+            "timestamp" => Some(&self.timestamp),
+            "year" => Some(&self.year),
+            "month" => Some(&self.month),
+            "day" => Some(&self.day),
+            _ => None,
+        }
+    }
+
+    /// `template` with every time placeholder replaced; others are kept
+    pub fn fill(&self, template: &str) -> String {
+        ["date", "datetime", "timestamp", "year", "month", "day"]
+            .into_iter()
+            .fold(template.to_string(), |filled, key| match self.get(key) {
+                Some(value) => filled.replace(&format!("${{{}}}", key), value),
+                None => filled,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    fn args(line: &str) -> Vec<OsString> {
+        Aliases::split_words(line)
+            .unwrap()
+            .into_iter()
+            .map(OsString::from)
+            .collect()
+    }
+
+    #[test]
+    fn aliases_expand_before_parsing() {
+        let builtins = vec!["pipeline".to_string(), "book".to_string()];
+        let mut aliases = Aliases::default();
+        aliases.insert(
+            "daily".to_string(),
+            "pipeline daily --param date=${date} --param note=${note}".to_string(),
+        );
+        aliases.insert("ob".to_string(), "book ${1} --depth 5".to_string());
+        aliases.insert("obw".to_string(), "ob ${1} --watch".to_string());
+        aliases.insert("loop".to_string(), "loop".to_string());
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let expanded = aliases
+            .expand(args("polybot -v daily --dry-run"), &builtins)
+            .unwrap();
+        assert_eq!(
+            expanded,
+            args(&format!(
+                "polybot -v pipeline daily --param date={} \
+                 --param note=${{note}} --dry-run",
+                today
+            ))
+        );
+
+        // Positional arguments, nested aliases, global flags with values
+        let expanded = aliases
+            .expand(args("polybot --data-dir /tmp/x obw 123"), &builtins)
+            .unwrap();
+        assert_eq!(
+            expanded,
+            args("polybot --data-dir /tmp/x book 123 --depth 5 --watch")
+        );
+        match Cli::try_parse_from(&expanded).unwrap().command {
+            Commands::Book(book) => {
+                assert_eq!(book.token_id, "123");
+                assert_eq!(book.depth, 5);
+                assert!(book.watch);
+            }
+            _ => panic!("expected the book command"),
+        }
+        assert!(aliases.expand(args("polybot ob"), &builtins).is_err());
+        assert!(aliases.expand(args("polybot loop"), &builtins).is_err());
+
+        // Built-ins and unknown commands are left alone
+        let plain = args("polybot book 123");
+        assert_eq!(aliases.expand(plain.clone(), &builtins).unwrap(), plain);
+        assert!(Aliases::validate_name("book", &builtins).is_err());
+        assert!(Aliases::validate_name("my-daily", &builtins).is_ok());
+
+        let words = vec!["--param".to_string(), "q=it's here".to_string()];
+        assert_eq!(Aliases::split_words(&Aliases::join_words(&words)).unwrap(), words);
+        assert_eq!(
+            Aliases::data_dir_arg(&args("polybot --data-dir=/srv/data daily")),
+            PathBuf::from("/srv/data")
+        );
+        assert_eq!(
+            TimePlaceholders::now().fill("book_${date}_${other}"),
+            format!("book_{}_${{other}}", today)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn arguments_that_are_not_utf8_are_kept() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let builtins = vec!["book".to_string(), "export".to_string()];
+        let mut aliases = Aliases::default();
+        aliases.insert("ob".to_string(), "book ${1} --depth 5".to_string());
+        aliases.insert("out".to_string(), "export --output=${1}".to_string());
+        let path = OsString::from_vec(b"/tmp/caf\xe9".to_vec());
+
+        let mut line = args("polybot ob");
+        line.push(path.clone());
+        let mut expected = args("polybot book");
+        expected.push(path.clone());
+        expected.extend(args("--depth 5"));
+        assert_eq!(aliases.expand(line, &builtins).unwrap(), expected);
+
+        let mut line = args("polybot ob 123");
+        line.push(path.clone());
+        assert_eq!(aliases.expand(line, &builtins).unwrap().last(), Some(&path));
+
+        // Splicing into a longer word needs text, so it fails instead of mangling
+        let mut line = args("polybot out");
+        line.push(path);
+        assert!(aliases.expand(line, &builtins).is_err());
+    }
+}
@@ -10,3 +10,46 @@ pub fn parse_percentage(s: &str) -> Result<f64, String> {
 
     Ok(value / 100.0)
 }
+
+/// Parse a duration like `90s`, `30m`, `6h` or `2d` (plain numbers are seconds)
+pub fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid duration (e.g. 90s, 30m, 6h, 2d)", s))?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("Unknown duration unit '{}' (use s, m, h or d)", unit)),
+    };
+    let secs = value
+        .checked_mul(unit_secs)
+        .ok_or_else(|| format!("Duration '{}' is too long", s))?;
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_duration_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(2 * 24 * 60 * 60)));
+        assert!(parse_duration("3w").is_err());
+    }
+
+    #[test]
+    fn overflowing_duration_is_an_error() {
+        assert!(parse_duration(&format!("{}d", u64::MAX / 10)).is_err());
+        assert!(parse_duration(&format!("{}s", u64::MAX)).is_ok());
+    }
+}
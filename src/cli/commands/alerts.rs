@@ -0,0 +1,230 @@
+//! Alerts command: list, acknowledge and follow up on routed alerts
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::{Args, Subcommand};
+use std::time::Duration;
+use tokio::signal;
+use tracing::warn;
+
+use crate::core::alerts::{
+    Alert, AlertChannel, AlertRouter, AlertRouting, AlertStore, Severity, ALERTS_CONFIG_FILE,
+};
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct AlertsArgs {
+    #[command(subcommand)]
+    pub action: AlertsAction,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum AlertsAction {
+    /// List open alerts
+    List {
+        /// Also list acknowledged alerts
+        #[arg(long)]
+        all: bool,
+        /// Print the alerts as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Acknowledge an open alert, stopping its repeats and escalation
+    Ack {
+        /// Alert id (or id prefix)
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+        /// Acknowledge every open alert
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+    },
+    /// Show where each severity is routed and how it's followed up
+    Routes,
+    /// Raise a test alert through the routing
+    Test {
+        #[arg(long, value_enum, default_value_t = Severity::Warning)]
+        severity: Severity,
+        #[arg(long, default_value = "Test alert from polybot")]
+        message: String,
+    },
+    /// Keep repeating and escalating open alerts until Ctrl+C, for alerts
+    /// raised by commands that have since exited
+    Run {
+        /// Seconds between follow-ups
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
+}
+
+pub struct AlertsCommand {
+    args: AlertsArgs,
+}
+
+impl AlertsCommand {
+    pub fn new(args: AlertsArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let store = AlertStore::new(&data_paths);
+        match &self.args.action {
+            AlertsAction::List { all, json } => {
+                let alerts: Vec<Alert> = store
+                    .load()?
+                    .into_iter()
+                    .filter(|alert| *all || alert.is_open())
+                    .collect();
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&alerts)?);
+                    return Ok(());
+                }
+                if alerts.is_empty() {
+                    println!("No {}alerts", if *all { "" } else { "open " });
+                    return Ok(());
+                }
+                for alert in &alerts {
+                    print_alert(alert);
+                }
+            }
+            AlertsAction::Ack { id, all: _ } => {
+                let acknowledged = store.acknowledge(id.as_deref(), "cli", Utc::now())?;
+                match (id, acknowledged.len()) {
+                    (Some(id), 0) => return Err(anyhow!("No open alert with id '{}'", id)),
+                    (None, 0) => println!("No open alerts"),
+                    _ => {
+                        for alert in &acknowledged {
+                            println!("✅ Acknowledged {} {}", alert.id, alert.message);
+                        }
+                    }
+                }
+            }
+            AlertsAction::Routes => print_routes(&AlertRouting::load(&data_paths)?),
+            AlertsAction::Test { severity, message } => {
+                let router = AlertRouter::new(&data_paths)?;
+                let alert = router
+                    .raise(*severity, "test", message.clone(), None)
+                    .await?;
+                println!(
+                    "{} Raised {} (acknowledge with `polybot alerts ack {}`)",
+                    alert.severity.icon(),
+                    alert.id,
+                    alert.id
+                );
+                let channels = &router.routing().policy(*severity).channels;
+                println!(
+                    "   Sent to: {} (failures are logged)",
+                    channels
+                        .iter()
+                        .map(|channel| channel.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            AlertsAction::Run { interval } => {
+                let router = AlertRouter::new(&data_paths)?;
+                println!(
+                    "⏰ Following up on open alerts every {}s (Ctrl+C to stop)",
+                    interval
+                );
+                let mut ticker = tokio::time::interval(Duration::from_secs((*interval).max(1)));
+                let mut shutdown = Box::pin(signal::ctrl_c());
+                loop {
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = ticker.tick() => match router.follow_up(Utc::now()).await {
+                            Ok(sent) => sent.iter().for_each(print_alert),
+                            Err(e) => warn!("Failed to follow up on alerts: {:#}", e),
+                        },
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_alert(alert: &Alert) {
+    let status = match (alert.acknowledged_at, &alert.acknowledged_by) {
+        (Some(at), by) => format!(
+            "acknowledged {}{}",
+            at.format("%m-%d %H:%M"),
+            by.as_deref()
+                .map(|by| format!(" from {}", by))
+                .unwrap_or_default()
+        ),
+        (None, _) if alert.repeats > 0 => format!("open, sent {} more times", alert.repeats),
+        (None, _) => "open".to_string(),
+    };
+    println!(
+        "{} {}  {}  {:<8}  {:<12}  {}",
+        alert.severity.icon(),
+        alert.id,
+        alert.raised_at.format("%Y-%m-%d %H:%M:%S"),
+        alert.severity,
+        alert.source,
+        alert.message
+    );
+    let escalated = if alert.severity != alert.raised_severity {
+        format!(", escalated from {}", alert.raised_severity)
+    } else {
+        String::new()
+    };
+    println!("   {}{}", status, escalated);
+}
+
+fn print_routes(routing: &AlertRouting) {
+    for severity in Severity::ALL {
+        let policy = routing.policy(severity);
+        let channels: Vec<String> = policy
+            .channels
+            .iter()
+            .map(|channel| match routing.is_configured(*channel) {
+                true => channel.to_string(),
+                false => format!("{} (not set up)", channel),
+            })
+            .collect();
+        println!(
+            "{} {:<8} → {}",
+            severity.icon(),
+            severity,
+            if channels.is_empty() {
+                "nowhere".to_string()
+            } else {
+                channels.join(", ")
+            }
+        );
+        if let Some(every) = policy.repeat_secs.filter(|_| policy.max_repeats > 0) {
+            println!(
+                "   Repeats every {}s, up to {} times, until acknowledged",
+                every, policy.max_repeats
+            );
+        }
+        if let Some(after) = policy
+            .escalate_after_secs
+            .filter(|_| severity != Severity::Critical)
+        {
+            println!(
+                "   Escalates to {} after {}s unacknowledged",
+                severity.escalated(),
+                after
+            );
+        }
+    }
+    let unset: Vec<String> = [
+        AlertChannel::Telegram,
+        AlertChannel::Webhook,
+        AlertChannel::Phone,
+    ]
+    .into_iter()
+    .filter(|channel| !routing.is_configured(*channel))
+    .map(|channel| channel.to_string())
+    .collect();
+    if !unset.is_empty() {
+        println!();
+        println!(
+            "Set up {} in {} in the data directory",
+            unset.join(", "),
+            ALERTS_CONFIG_FILE
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Alias command: manage user-defined command aliases
+//!
+//! Aliases live in `aliases.yaml` in the base data directory and are expanded
+//! before the command line is parsed (see `cli::aliases`).
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::cli::aliases::{AliasStore, Aliases};
+use crate::cli::builtin_commands;
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct AliasArgs {
+    /// Alias subcommand
+    #[command(subcommand)]
+    pub command: AliasSubcommand,
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum AliasSubcommand {
+    /// List aliases and the commands they run
+    List,
+
+    /// Add an alias, e.g. `alias add daily pipeline daily --param 'date=${date}'`
+    Add {
+        /// Alias name (letters, digits, '-' or '_'; not a built-in command)
+        name: String,
+
+        /// Command it runs, without `polybot`; `${1}`.. take the alias's arguments
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+
+        /// Replace an existing alias of the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        name: String,
+    },
+}
+
+pub struct AliasCommand {
+    args: AliasArgs,
+}
+
+impl AliasCommand {
+    pub fn new(args: AliasArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let store = AliasStore::new(data_paths.base());
+        let mut stored = store.load()?;
+
+        match &self.args.command {
+            AliasSubcommand::List => {
+                if stored.is_empty() {
+                    println!(
+                        "No aliases yet. Add one with 'polybot alias add <name> <command...>'"
+                    );
+                    return Ok(());
+                }
+                println!("{}", "Aliases".bright_cyan().bold());
+                let width = stored.iter().map(|alias| alias.name.len()).max().unwrap_or(0);
+                for alias in stored.iter() {
+                    println!("  {:<width$}  polybot {}", alias.name.bright_green(), alias.command);
+                }
+                println!();
+                println!("{}", store.path().display().dimmed());
+            }
+            AliasSubcommand::Add {
+                name,
+                command,
+                force,
+            } => {
+                Aliases::validate_name(name, &builtin_commands())?;
+                if stored.contains(name) && !force {
+                    return Err(anyhow!(
+                        "Alias '{}' already exists; use --force to replace it",
+                        name
+                    ));
+                }
+                // A single quoted argument is the whole command line already
+                let line = match command.as_slice() {
+                    [line] if line.contains(char::is_whitespace) => line.clone(),
+                    words => Aliases::join_words(words),
+                };
+                stored.insert(name.clone(), line.clone());
+                store.save(&stored)?;
+                println!("✅ polybot {} = polybot {}", name, line);
+            }
+            AliasSubcommand::Remove { name } => {
+                if stored.remove(name).is_none() {
+                    return Err(anyhow!("No alias named '{}'", name));
+                }
+                store.save(&stored)?;
+                println!("🗑️  Removed alias '{}'", name);
+            }
+        }
+        Ok(())
+    }
+}
@@ -87,6 +87,10 @@ pub struct AnalyzeArgs {
     #[arg(long)]
     pub text_search: Option<String>,
 
+    /// Filter: drop markets with a token not enriched within this age (e.g. 6h)
+    #[arg(long, value_parser = crate::cli::parse_duration)]
+    pub max_enrichment_age: Option<std::time::Duration>,
+
     /// Include detailed analysis in output
     #[arg(long)]
     pub detailed: bool,
@@ -94,6 +98,10 @@ pub struct AnalyzeArgs {
     /// Show analysis summary
     #[arg(long)]
     pub summary: bool,
+
+    /// Memory limit before filtered markets spill to disk (e.g. 4G; default: 75% of available memory)
+    #[arg(long, value_parser = crate::core::memory::parse_byte_size)]
+    pub memory_limit: Option<u64>,
 }
 
 pub struct AnalyzeCommand {
@@ -0,0 +1,112 @@
+//! Audit command: inspect and verify the hash-chained audit log
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+
+use crate::core::audit::{AuditAction, AuditLog};
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum AuditSubcommand {
+    /// Print the most recent audit entries
+    Show {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value = "50")]
+        limit: usize,
+
+        /// Only show this action (order_placed, order_cancelled, transaction,
+        /// credentials_changed, config_changed)
+        #[arg(long)]
+        action: Option<String>,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check every entry's hash and link in the chain
+    Verify {
+        /// Head hash noted earlier; verification fails if it is no longer in the log
+        #[arg(long)]
+        head: Option<String>,
+    },
+}
+
+pub struct AuditCommand {
+    args: AuditArgs,
+}
+
+impl AuditCommand {
+    pub fn new(args: AuditArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let log = AuditLog::new(&data_paths);
+        match &self.args.command {
+            AuditSubcommand::Show { limit, action, json } => {
+                let action = action.as_deref().map(parse_action).transpose()?;
+                let entries: Vec<_> = log
+                    .entries()?
+                    .into_iter()
+                    .filter(|e| action.is_none_or(|a| e.action == a))
+                    .collect();
+                if entries.is_empty() {
+                    println!("No audit entries in {}", log.path().display());
+                    return Ok(());
+                }
+                for entry in &entries[entries.len().saturating_sub(*limit)..] {
+                    if *json {
+                        println!("{}", serde_json::to_string(entry)?);
+                        continue;
+                    }
+                    let status = match &entry.error {
+                        None if entry.success => "ok".to_string(),
+                        None => "failed".to_string(),
+                        Some(error) => format!("failed: {}", error),
+                    };
+                    println!(
+                        "#{:<5} {}  {:<20} {}  [{}]",
+                        entry.seq,
+                        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                        entry.action,
+                        entry.summary,
+                        status
+                    );
+                    println!("       by {}", entry.context);
+                }
+            }
+            AuditSubcommand::Verify { head } => {
+                let report = log.verify(head.as_deref())?;
+                if let Some(broken) = &report.broken {
+                    return Err(anyhow!(
+                        "❌ Audit log broken at entry {}: {} ({} entries verified before it)",
+                        broken.seq,
+                        broken.problem,
+                        report.entries
+                    ));
+                }
+                if report.head_found == Some(false) {
+                    return Err(anyhow!(
+                        "❌ Head {} is not in the audit log; entries were removed",
+                        head.as_deref().unwrap_or_default()
+                    ));
+                }
+                println!("✅ {} audit entries verified", report.entries);
+                println!("   Head: {}", report.head);
+                println!("   Note the head elsewhere to detect truncation later (audit verify --head)");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_action(name: &str) -> Result<AuditAction> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| anyhow!("Unknown audit action '{}'", name))
+}
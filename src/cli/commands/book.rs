@@ -1,3 +1,4 @@
+use crate::core::execution::book_view::PriceAggregation;
 use crate::data_paths::DataPaths;
 use anyhow::Result;
 use clap::Args;
@@ -10,6 +11,28 @@ pub struct BookArgs {
     /// Number of levels to show
     #[arg(long, default_value = "5")]
     pub depth: usize,
+
+    /// Subscribe via WebSocket and keep re-rendering the book in the terminal
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Minimum milliseconds between redraws in watch mode
+    #[arg(long, default_value = "100")]
+    pub refresh_ms: u64,
+
+    /// Group watch mode levels into price bins of this many cents (1, 2, 5 or 10)
+    #[arg(long, value_parser = parse_bin_cents)]
+    pub bin: Option<PriceAggregation>,
+}
+
+fn parse_bin_cents(value: &str) -> std::result::Result<PriceAggregation, String> {
+    match value.trim_end_matches(['c', '¢']) {
+        "1" => Ok(PriceAggregation::OneCent),
+        "2" => Ok(PriceAggregation::TwoCents),
+        "5" => Ok(PriceAggregation::FiveCents),
+        "10" => Ok(PriceAggregation::TenCents),
+        _ => Err(format!("unsupported bin '{}': expected 1, 2, 5 or 10", value)),
+    }
 }
 
 pub struct BookCommand {
@@ -22,8 +45,334 @@ impl BookCommand {
     }
 
     pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        if self.args.watch {
+            // The market feed is public, so watch mode needs no credentials
+            #[cfg(feature = "tui")]
+            return watch::run(&self.args, &data_paths).await;
+            #[cfg(not(feature = "tui"))]
+            return Err(super::unavailable::missing_feature("book --watch", "tui"));
+        }
+
         let client = crate::auth::get_authenticated_client(host, &data_paths).await?;
         crate::markets::show_orderbook(client, &self.args.token_id, self.args.depth).await?;
         Ok(())
     }
 }
+
+/// Live terminal view of the book for `--watch`
+#[cfg(feature = "tui")]
+mod watch {
+    use super::BookArgs;
+    use crate::client::MarketStream;
+    use crate::core::execution::book_view::{BookView, PriceAggregation};
+    use crate::core::types::common::Side;
+    use crate::core::ws::{OrderBook, PolyEvent};
+    use crate::data_paths::DataPaths;
+    use crate::number_format;
+    use crate::tui::widgets::order_book::render_order_book;
+    use anyhow::Result;
+    use crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+    use ratatui::{
+        backend::CrosstermBackend,
+        layout::{Constraint, Direction, Layout},
+        style::{Color, Modifier, Style},
+        text::{Line, Span},
+        widgets::{Block, Borders, Paragraph},
+        Terminal,
+    };
+    use rust_decimal::Decimal;
+    use std::cell::Cell;
+    use std::io::{self, Stdout};
+    use std::time::{Duration, Instant};
+
+    pub(super) async fn run(args: &BookArgs, data_paths: &DataPaths) -> Result<()> {
+        crate::theme::init(data_paths, None);
+        let mut stream = MarketStream::subscribe(vec![args.token_id.clone()]).await?;
+
+        let mut terminal = setup_terminal()?;
+        let mut state = WatchState::new(&args.token_id);
+        state.aggregation = args.bin.unwrap_or_default();
+        let mut redraw = tokio::time::interval(Duration::from_millis(args.refresh_ms.max(16)));
+
+        let result: Result<()> = loop {
+            tokio::select! {
+                event = stream.next() => match event {
+                    Some(event) => {
+                        state.apply(event);
+                        state.dropped = stream.dropped();
+                    }
+                    None => break Err(anyhow::anyhow!("Market feed closed")),
+                },
+                _ = redraw.tick() => {
+                    match handle_keys(&mut state) {
+                        Ok(true) => break Ok(()),
+                        Ok(false) => {}
+                        Err(e) => break Err(e),
+                    }
+                    if let Err(e) = terminal.draw(|frame| state.render(frame)) {
+                        break Err(e.into());
+                    }
+                }
+            }
+        };
+
+        drop(stream);
+        restore_terminal(&mut terminal)?;
+        result
+    }
+
+    /// Most recent trade on the token; `size` is zero when only its price is known
+    #[derive(Debug, Clone, Copy)]
+    struct LastTrade {
+        price: Decimal,
+        size: Decimal,
+        side: Side,
+    }
+
+    /// Book and ticker state maintained from the market feed
+    struct WatchState {
+        token_id: String,
+        book: OrderBook,
+        has_snapshot: bool,
+        last_trade: Option<LastTrade>,
+        last_update: Option<Instant>,
+        updates: u64,
+        dropped: u64,
+        /// None keeps the view centred on the mid price
+        scroll: Option<usize>,
+        /// Scroll offset used by the last draw, the base for manual scrolling
+        rendered_scroll: Cell<usize>,
+        aggregation: PriceAggregation,
+    }
+
+    impl WatchState {
+        fn new(token_id: &str) -> Self {
+            Self {
+                token_id: token_id.to_string(),
+                book: OrderBook::new(token_id.to_string()),
+                has_snapshot: false,
+                last_trade: None,
+                last_update: None,
+                updates: 0,
+                dropped: 0,
+                scroll: None,
+                rendered_scroll: Cell::new(0),
+                aggregation: PriceAggregation::None,
+            }
+        }
+
+        fn apply(&mut self, event: PolyEvent) {
+            match event {
+                PolyEvent::Book {
+                    asset_id,
+                    market,
+                    timestamp,
+                    bids,
+                    asks,
+                    ..
+                } if asset_id == self.token_id => {
+                    self.book.replace_with_snapshot_no_hash(market, timestamp, bids, asks);
+                    self.has_snapshot = true;
+                }
+                PolyEvent::PriceChange {
+                    asset_id,
+                    side,
+                    price,
+                    size,
+                    ..
+                } if asset_id == self.token_id => {
+                    self.book.apply_price_change_no_hash(side, price, size);
+                }
+                PolyEvent::Trade {
+                    asset_id,
+                    price,
+                    size,
+                    side,
+                } if asset_id == self.token_id => {
+                    self.last_trade = Some(LastTrade { price, size, side });
+                }
+                PolyEvent::LastTradePrice { asset_id, price, .. } if asset_id == self.token_id => {
+                    if self.last_trade.is_none() {
+                        self.last_trade = Some(LastTrade {
+                            price,
+                            size: Decimal::ZERO,
+                            side: Side::Buy,
+                        });
+                    }
+                }
+                _ => return,
+            }
+            self.updates += 1;
+            self.last_update = Some(Instant::now());
+        }
+
+        fn render(&self, frame: &mut ratatui::Frame) {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Ticker
+                    Constraint::Min(0),    // Order book
+                    Constraint::Length(1), // Help
+                ])
+                .split(frame.area());
+
+            frame.render_widget(self.ticker(), chunks[0]);
+
+            if self.has_snapshot {
+                let bids = self.book.get_bids();
+                let asks = self.book.get_asks();
+                // Centre on the mid row: asks are drawn above it, highest first
+                let displayed_asks = BookView::new(&[], &asks, self.aggregation).asks.len();
+                let visible = chunks[1].height.saturating_sub(6) as usize;
+                let scroll = self
+                    .scroll
+                    .unwrap_or_else(|| displayed_asks.saturating_sub(visible / 2));
+                self.rendered_scroll.set(scroll);
+                render_order_book(
+                    frame,
+                    chunks[1],
+                    &bids,
+                    &asks,
+                    &self.token_id,
+                    scroll,
+                    self.aggregation,
+                );
+            } else {
+                let waiting = Paragraph::new("Waiting for order book snapshot...")
+                    .style(Style::default().fg(Color::Yellow))
+                    .block(Block::default().borders(Borders::ALL).title(" Order Book "));
+                frame.render_widget(waiting, chunks[1]);
+            }
+
+            let help = Paragraph::new("q/Esc: quit  ↑/↓ PgUp/PgDn: scroll  c: re-centre  a: price bins")
+                .style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(help, chunks[2]);
+        }
+
+        fn ticker(&self) -> Paragraph<'static> {
+            let label = Style::default().fg(Color::Gray);
+            let value = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+            let mut spans = Vec::new();
+
+            let palette = crate::theme::palette();
+            let best_bid = self.book.best_bid().map(|l| l.price);
+            let best_ask = self.book.best_ask().map(|l| l.price);
+
+            spans.push(Span::styled("Bid ", label));
+            spans.push(Span::styled(
+                best_bid.map(number_format::price).unwrap_or_else(|| "-".to_string()),
+                Style::default().fg(palette.bid.into()).add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled("  Ask ", label));
+            spans.push(Span::styled(
+                best_ask.map(number_format::price).unwrap_or_else(|| "-".to_string()),
+                Style::default().fg(palette.ask.into()).add_modifier(Modifier::BOLD),
+            ));
+
+            if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
+                let spread = ask - bid;
+                let mid = (ask + bid) / Decimal::from(2);
+                let spread_style = if spread < Decimal::ZERO {
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                } else {
+                    value
+                };
+                spans.push(Span::styled("  Spread ", label));
+                spans.push(Span::styled(number_format::price(spread), spread_style));
+                if !mid.is_zero() {
+                    spans.push(Span::styled(
+                        format!(" ({})", number_format::percent(spread / mid * Decimal::from(100))),
+                        label,
+                    ));
+                }
+                spans.push(Span::styled("  Mid ", label));
+                spans.push(Span::styled(number_format::price(mid), value));
+            }
+
+            spans.push(Span::styled("  Last ", label));
+            match self.last_trade {
+                Some(LastTrade { price, size, side }) if !size.is_zero() => {
+                    let color = Color::from(match side {
+                        Side::Buy => palette.bid,
+                        Side::Sell => palette.ask,
+                    });
+                    spans.push(Span::styled(
+                        format!(
+                            "{} × {} {}",
+                            number_format::price(price),
+                            number_format::size(size),
+                            side
+                        ),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ));
+                }
+                Some(LastTrade { price, .. }) => {
+                    spans.push(Span::styled(number_format::price(price), value))
+                }
+                None => spans.push(Span::styled("-", label)),
+            }
+
+            let age = self
+                .last_update
+                .map(|t| format!("{:.1}s ago", t.elapsed().as_secs_f64()))
+                .unwrap_or_else(|| "never".to_string());
+            let mut status = format!(" {} updates · last {} ", self.updates, age);
+            if self.dropped > 0 {
+                status.push_str(&format!("· {} dropped ", self.dropped));
+            }
+
+            Paragraph::new(Line::from(spans)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" 📖 Live Book ")
+                    .title_bottom(Line::from(status).right_aligned()),
+            )
+        }
+    }
+
+    /// Drain pending key presses; returns true when the user asked to quit
+    fn handle_keys(state: &mut WatchState) -> Result<bool> {
+        while event::poll(Duration::ZERO)? {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let current = state.scroll.unwrap_or_else(|| state.rendered_scroll.get());
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+                KeyCode::Char('c') => state.scroll = None,
+                KeyCode::Char('a') => {
+                    state.aggregation = state.aggregation.next();
+                    state.scroll = None;
+                }
+                KeyCode::Up => state.scroll = Some(current.saturating_sub(1)),
+                KeyCode::Down => state.scroll = Some(current + 1),
+                KeyCode::PageUp => state.scroll = Some(current.saturating_sub(10)),
+                KeyCode::PageDown => state.scroll = Some(current + 10),
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
+    fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+    }
+
+    fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,399 @@
+//! Book-snapshots command: capture order books on a schedule and inspect what was stored
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+
+use crate::core::portfolio::api::PortfolioState;
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::{OrderSide, PositionSide, PositionStatus};
+use crate::core::progress::{ctrl_c_token, ProgressReporter};
+use crate::data_paths::DataPaths;
+use crate::markets::clob::book_snapshots::{
+    resolve_targets, run_scheduler, RoundResult, SnapshotConfig, SnapshotTarget,
+};
+use crate::markets::clob::book_watch::{BookWatcher, WatchConfig};
+use crate::typed_store::book_snapshots;
+use crate::typed_store::freshness::default_db_path;
+
+#[derive(Args, Clone)]
+pub struct BookSnapshotsArgs {
+    #[command(subcommand)]
+    pub command: BookSnapshotsSubcommand,
+
+    /// Typed store to write to and read from (default: the profile's database/rocksdb)
+    #[arg(long, global = true)]
+    pub db_path: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum BookSnapshotsSubcommand {
+    /// Capture the books of the configured markets and tokens every interval
+    Run {
+        /// Markets (condition IDs) to add to snapshots.yaml's, all outcome tokens each
+        #[arg(long, value_delimiter = ',')]
+        markets: Vec<String>,
+
+        /// Tokens to add to snapshots.yaml's
+        #[arg(long, value_delimiter = ',')]
+        tokens: Vec<String>,
+
+        /// Time between rounds, overriding snapshots.yaml (e.g. 5m)
+        #[arg(long, value_parser = crate::cli::parse_duration)]
+        interval: Option<Duration>,
+
+        /// Stop after this many rounds (default: run until Ctrl-C)
+        #[arg(long)]
+        rounds: Option<usize>,
+    },
+
+    /// Snapshot the books of held tokens and alert when spreads widen or depth collapses
+    Watch {
+        /// Tokens to watch besides those with an open position or resting order
+        #[arg(long, value_delimiter = ',')]
+        tokens: Vec<String>,
+
+        /// Time between rounds, overriding snapshots.yaml's watch section (e.g. 1m)
+        #[arg(long, value_parser = crate::cli::parse_duration)]
+        interval: Option<Duration>,
+
+        /// Stop after this many rounds (default: run until Ctrl-C)
+        #[arg(long)]
+        rounds: Option<usize>,
+
+        /// Alert when the spread is wider than this (e.g. 0.05)
+        #[arg(long)]
+        max_spread: Option<Decimal>,
+
+        /// Alert when the spread reaches this multiple of its recent median
+        #[arg(long)]
+        widen_factor: Option<Decimal>,
+
+        /// Alert when a side's depth falls this many percent below its recent median
+        #[arg(long)]
+        depth_drop_pct: Option<Decimal>,
+
+        /// Ring the terminal bell when an alert is raised
+        #[arg(long)]
+        bell: bool,
+    },
+
+    /// Stored snapshots per token
+    List,
+
+    /// Best bid, best ask and depth of a token's stored snapshots
+    Show {
+        /// Token ID
+        token_id: String,
+
+        /// Only snapshots captured in the last period (e.g. 6h, 2d)
+        #[arg(long, value_parser = crate::cli::parse_duration)]
+        last: Option<Duration>,
+
+        /// Print the full snapshots as JSON lines instead
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub struct BookSnapshotsCommand {
+    args: BookSnapshotsArgs,
+}
+
+impl BookSnapshotsCommand {
+    pub fn new(args: BookSnapshotsArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let db_path = self
+            .args
+            .db_path
+            .clone()
+            .unwrap_or_else(|| default_db_path(&data_paths));
+
+        match &self.args.command {
+            BookSnapshotsSubcommand::Run {
+                markets,
+                tokens,
+                interval,
+                rounds,
+            } => {
+                let mut config = SnapshotConfig::load(&data_paths)?;
+                config.markets.extend(markets.iter().cloned());
+                config.tokens.extend(tokens.iter().cloned());
+                if let Some(interval) = interval {
+                    config.interval_secs = interval.as_secs();
+                }
+                let interval = config.interval()?;
+                if config.markets.is_empty() && config.tokens.is_empty() {
+                    bail!("Nothing to snapshot: pass --markets/--tokens or list them in snapshots.yaml");
+                }
+
+                // The book endpoint is public, so no credentials are needed
+                let client = ClobClient::new(host);
+                let targets = resolve_targets(&client, &config, &db_path).await?;
+                println!(
+                    "📸 Capturing {} books every {}s into {} (Ctrl-C to stop)",
+                    targets.len(),
+                    interval.as_secs(),
+                    db_path.display()
+                );
+
+                let reporter = ProgressReporter::terminal().with_cancellation(ctrl_c_token());
+                let progress = reporter.task("Rounds", rounds.map(|r| r as u64));
+                let report =
+                    run_scheduler(&client, &targets, &db_path, interval, *rounds, &progress)
+                        .await?;
+                progress.finish("stopped");
+
+                println!(
+                    "✅ {} rounds, {} snapshots stored, {} books failed",
+                    report.rounds, report.snapshots, report.failed
+                );
+            }
+            BookSnapshotsSubcommand::Watch {
+                tokens,
+                interval,
+                rounds,
+                max_spread,
+                widen_factor,
+                depth_drop_pct,
+                bell,
+            } => {
+                let mut config = SnapshotConfig::load(&data_paths)?.watch;
+                if let Some(interval) = interval {
+                    config.interval_secs = interval.as_secs();
+                }
+                if max_spread.is_some() {
+                    config.max_spread = *max_spread;
+                }
+                if widen_factor.is_some() {
+                    config.widen_factor = *widen_factor;
+                }
+                if depth_drop_pct.is_some() {
+                    config.depth_drop_pct = *depth_drop_pct;
+                }
+                watch(host, data_paths, &db_path, config, tokens, *rounds, *bell).await?;
+            }
+            BookSnapshotsSubcommand::List => {
+                let tokens = book_snapshots::summary(&db_path)?;
+                if tokens.is_empty() {
+                    println!("No book snapshots stored. Start capturing with 'polybot book-snapshots run'.");
+                    return Ok(());
+                }
+                println!(
+                    "{:<80} {:>8}  {:<20} {:<20}",
+                    "Token", "Count", "First", "Last"
+                );
+                for token in tokens {
+                    println!(
+                        "{:<80} {:>8}  {:<20} {:<20}",
+                        token.token_id,
+                        token.count,
+                        token.first.format("%Y-%m-%d %H:%M:%S"),
+                        token.last.format("%Y-%m-%d %H:%M:%S")
+                    );
+                }
+            }
+            BookSnapshotsSubcommand::Show {
+                token_id,
+                last,
+                json,
+            } => {
+                let since: Option<DateTime<Utc>> = match last {
+                    Some(last) => Some(Utc::now() - chrono::Duration::from_std(*last)?),
+                    None => None,
+                };
+                let snapshots = book_snapshots::load(&db_path, token_id, since)?;
+                if *json {
+                    for snapshot in &snapshots {
+                        println!("{}", serde_json::to_string(snapshot)?);
+                    }
+                    return Ok(());
+                }
+                if snapshots.is_empty() {
+                    println!("No snapshots of {} stored.", token_id);
+                    return Ok(());
+                }
+
+                println!(
+                    "{:<20} {:>8} {:>8} {:>8} {:>12} {:>12}",
+                    "Captured", "Bid", "Ask", "Spread", "Bid depth", "Ask depth"
+                );
+                let price = |p: Option<rust_decimal::Decimal>| {
+                    p.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string())
+                };
+                for snapshot in &snapshots {
+                    let bid_depth: rust_decimal::Decimal =
+                        snapshot.bids.iter().map(|l| l.size).sum();
+                    let ask_depth: rust_decimal::Decimal =
+                        snapshot.asks.iter().map(|l| l.size).sum();
+                    println!(
+                        "{:<20} {:>8} {:>8} {:>8} {:>12.2} {:>12.2}",
+                        snapshot.captured_at.format("%Y-%m-%d %H:%M:%S"),
+                        price(snapshot.best_bid()),
+                        price(snapshot.best_ask()),
+                        price(snapshot.spread()),
+                        bid_depth,
+                        ask_depth
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Open positions and resting orders of a token, for alert lines
+#[derive(Debug, Default)]
+struct Holding {
+    condition_id: Option<String>,
+    label: Option<String>,
+    details: Vec<String>,
+}
+
+/// Tokens with an open position or a resting order, plus the extra ones
+fn held_tokens(state: &PortfolioState, extra: &[String]) -> BTreeMap<String, Holding> {
+    let mut held: BTreeMap<String, Holding> = BTreeMap::new();
+    for position in state
+        .positions
+        .iter()
+        .filter(|p| p.status == PositionStatus::Open)
+    {
+        let holding = held.entry(position.token_id.clone()).or_default();
+        holding.condition_id = Some(position.market_id.clone());
+        holding.label = Some(match &position.market_question {
+            Some(question) => format!("{} ({})", question, position.outcome),
+            None => position.outcome.clone(),
+        });
+        let side = match position.side {
+            PositionSide::Long => "long",
+            PositionSide::Short => "short",
+        };
+        holding.details.push(format!(
+            "{} {} @ {}",
+            side,
+            position.size.normalize(),
+            position.average_price.normalize()
+        ));
+    }
+    for order in &state.active_orders {
+        let holding = held.entry(order.token_id.clone()).or_default();
+        holding
+            .condition_id
+            .get_or_insert_with(|| order.market_id.clone());
+        let side = match order.side {
+            OrderSide::Buy => "bid",
+            OrderSide::Sell => "offer",
+        };
+        holding.details.push(format!(
+            "{} {} @ {}",
+            side,
+            order.remaining_size.normalize(),
+            order.price.normalize()
+        ));
+    }
+    for token_id in extra {
+        held.entry(token_id.clone()).or_default();
+    }
+    held
+}
+
+/// Re-read the portfolio every round, snapshot the held books and print the
+/// alerts the watcher raises or clears
+async fn watch(
+    host: &str,
+    data_paths: DataPaths,
+    db_path: &Path,
+    config: WatchConfig,
+    extra_tokens: &[String],
+    rounds: Option<usize>,
+    bell: bool,
+) -> Result<()> {
+    let interval = config.interval()?;
+    let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+    let client = ClobClient::new(host);
+    // Baselines start from the snapshots stored over the last baseline window
+    let history_window = chrono::Duration::from_std(interval * config.baseline_snapshots as u32)?;
+    let mut watcher = BookWatcher::new(config);
+
+    println!(
+        "👀 Watching the books of {} every {}s (Ctrl-C to stop)",
+        handlers.get_address(),
+        interval.as_secs()
+    );
+    let cancel = ctrl_c_token();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let (mut done, mut raised) = (0usize, 0usize);
+
+    while rounds.map_or(true, |rounds| done < rounds) {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = cancel.cancelled() => break,
+        }
+
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, using cached positions: {}", e);
+        }
+        let held = held_tokens(&handlers.get_portfolio_state().await?, extra_tokens);
+        let token_ids: BTreeSet<String> = held.keys().cloned().collect();
+        watcher.retain(&token_ids);
+        for token_id in &token_ids {
+            if !watcher.is_tracking(token_id) {
+                let since = Utc::now() - history_window;
+                let history =
+                    book_snapshots::load(db_path, token_id, Some(since)).unwrap_or_else(|e| {
+                        warn!("Failed to load stored snapshots of {}: {}", token_id, e);
+                        Vec::new()
+                    });
+                watcher.seed(token_id, history);
+            }
+        }
+        if done == 0 {
+            println!("   {} tokens held", held.len());
+        }
+
+        let targets: Vec<SnapshotTarget> = held
+            .iter()
+            .map(|(token_id, holding)| SnapshotTarget {
+                token_id: token_id.clone(),
+                condition_id: holding.condition_id.clone(),
+            })
+            .collect();
+        let snapshots = RoundResult::capture(&client, &targets, Utc::now()).await.snapshots;
+        if let Err(e) = book_snapshots::record(db_path, &snapshots) {
+            warn!("Failed to store {} snapshots: {}", snapshots.len(), e);
+        }
+
+        for snapshot in snapshots {
+            for alert in watcher.observe(snapshot) {
+                let holding = &held[&alert.token_id];
+                let name = holding.label.as_deref().unwrap_or(&alert.token_id);
+                let time = Utc::now().format("%H:%M:%S");
+                if alert.raised {
+                    raised += 1;
+                    let cue = if bell { "\x07" } else { "" };
+                    println!("{}⚠️  {} {}: {}", cue, time, name, alert.message);
+                    if !holding.details.is_empty() {
+                        println!("   holding: {}", holding.details.join(", "));
+                    }
+                } else {
+                    println!("✅ {} {}: {}", time, name, alert.message);
+                }
+            }
+        }
+        done += 1;
+    }
+
+    println!("Stopped after {} rounds, {} alerts raised", done, raised);
+    Ok(())
+}
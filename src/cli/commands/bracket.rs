@@ -0,0 +1,240 @@
+//! Bracket command: place buy entries with linked stop-loss and take-profit exits
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Subcommand};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::time::Duration;
+use tokio::signal;
+use tracing::warn;
+
+use crate::client::PolybotClient;
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+use crate::strategy::brackets::{BracketExits, BracketPass, BracketStore, NewBracket};
+
+#[derive(Args, Clone)]
+pub struct BracketArgs {
+    #[command(subcommand)]
+    pub command: BracketSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum BracketSubcommand {
+    /// Buy with a limit entry and arm a stop and a target once it fills
+    Place {
+        /// Token ID
+        token_id: String,
+
+        /// Entry price (e.g., 0.48)
+        #[arg(long)]
+        price: Decimal,
+
+        /// Shares to buy
+        #[arg(long)]
+        size: Decimal,
+
+        /// Sell what's left at the best bid once the bid falls to this price
+        #[arg(long)]
+        stop: Decimal,
+
+        /// Take profit with a resting sell at this price
+        #[arg(long)]
+        target: Decimal,
+
+        /// Market ID (optional, will use token_id if not provided)
+        #[arg(long)]
+        market_id: Option<String>,
+
+        /// Confirm order placement (required unless RUST_ENV=production)
+        #[arg(long)]
+        yes: bool,
+
+        /// Confirm entries above the large order notional threshold without prompting
+        #[arg(long)]
+        yes_large: bool,
+    },
+
+    /// Brackets and their state
+    List {
+        /// Include closed and cancelled brackets
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Cancel a bracket's resting entry or target; bought shares are kept
+    Cancel {
+        /// Bracket ID from 'bracket list'
+        id: u64,
+    },
+
+    /// Watch the open brackets and fire their exits (the daemon does this too)
+    Run {
+        /// Time between passes (e.g. 10s)
+        #[arg(long, value_parser = crate::cli::parse_duration, default_value = "10s")]
+        interval: Duration,
+    },
+}
+
+impl BracketSubcommand {
+    /// Places or cancels orders (refused on read-only profiles)
+    pub fn is_mutating(&self) -> bool {
+        !matches!(self, Self::List { .. })
+    }
+}
+
+pub struct BracketCommand {
+    args: BracketArgs,
+}
+
+impl BracketCommand {
+    pub fn new(args: BracketArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let store = BracketStore::new(&data_paths);
+        match &self.args.command {
+            BracketSubcommand::Place {
+                token_id,
+                price,
+                size,
+                stop,
+                target,
+                market_id,
+                yes,
+                yes_large,
+            } => {
+                let exits = BracketExits {
+                    stop: *stop,
+                    target: *target,
+                };
+                exits.validate(*price)?;
+                if !yes && std::env::var("RUST_ENV").unwrap_or_default() != "production" {
+                    warn!("⚠️  Order confirmation required. Use --yes to confirm.");
+                    return Ok(());
+                }
+                let risk_config = RiskConfig::load(&data_paths)?;
+                if let Some(large) =
+                    risk_config
+                        .large_orders
+                        .check(token_id, Side::Buy, *price, *size)
+                {
+                    let journal = RiskJournal::new(&data_paths);
+                    if !confirm_large_order_cli(&large, *yes_large, &journal)? {
+                        warn!("❌ Large order not confirmed, nothing was placed.");
+                        return Ok(());
+                    }
+                }
+
+                let client = PolybotClient::connect_to(host, data_paths).await?;
+                let entry_order_id = client
+                    .route()
+                    .place(Side::Buy, token_id, *price, *size)
+                    .await?;
+                let bracket = store.add(NewBracket {
+                    token_id: token_id.clone(),
+                    market_id: market_id.clone(),
+                    entry_order_id: entry_order_id.clone(),
+                    entry_price: *price,
+                    size: *size,
+                    exits,
+                })?;
+                println!(
+                    "🎯 Bracket {}: buying {} @ ${} (order {}), stop ${}, target ${}",
+                    bracket.id, size, price, entry_order_id, stop, target
+                );
+                println!("   Exits arm once the entry fills, while 'polybot daemon' or 'polybot bracket run' is running");
+            }
+            BracketSubcommand::List { all } => {
+                let brackets: Vec<_> = store
+                    .load()?
+                    .into_iter()
+                    .filter(|b| *all || b.is_open())
+                    .collect();
+                if brackets.is_empty() {
+                    println!("No open brackets. Place one with 'polybot bracket place'.");
+                    return Ok(());
+                }
+                println!(
+                    "{:>4} {:<20} {:>8} {:>8} {:>8} {:>8}  {:<22} {:<16}",
+                    "ID", "Token", "Size", "Entry", "Stop", "Target", "State", "Updated"
+                );
+                for bracket in brackets {
+                    let token: String = bracket.token_id.chars().take(20).collect();
+                    println!(
+                        "{:>4} {:<20} {:>8} {:>8} {:>8} {:>8}  {:<22} {:<16}",
+                        bracket.id,
+                        token,
+                        bracket.size,
+                        bracket.entry_price,
+                        bracket.stop_price,
+                        bracket.target_price,
+                        bracket.state_label(),
+                        bracket.updated_at.format("%Y-%m-%d %H:%M")
+                    );
+                    if let Some(error) = &bracket.last_error {
+                        println!("     ⚠️  {}", error);
+                    }
+                }
+            }
+            BracketSubcommand::Cancel { id } => {
+                let mut bracket = store
+                    .load()?
+                    .into_iter()
+                    .find(|b| b.id == *id)
+                    .ok_or_else(|| anyhow!("No bracket {}", id))?;
+                let client = PolybotClient::connect_to(host, data_paths).await?;
+                bracket.cancel(&client.route()).await?;
+                store.update(std::slice::from_ref(&bracket))?;
+                println!("✅ Bracket {} cancelled", id);
+            }
+            BracketSubcommand::Run { interval } => {
+                if interval.is_zero() {
+                    bail!("--interval must be positive");
+                }
+                let trading = PolybotClient::connect_to(host, data_paths.clone()).await?;
+                let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+                // Books are public, so no credentials are needed for the bids
+                let client = ClobClient::new(host);
+                println!(
+                    "🎯 Watching brackets every {}s (Ctrl-C to stop)",
+                    interval.as_secs()
+                );
+                let mut timer = tokio::time::interval(*interval);
+                loop {
+                    tokio::select! {
+                        _ = timer.tick() => {
+                            if let Err(e) = run_pass(&handlers, &trading, &client, &store).await {
+                                warn!("Bracket pass failed: {}", e);
+                            }
+                        }
+                        _ = signal::ctrl_c() => break,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn run_pass(
+    handlers: &PortfolioCommandHandlers,
+    trading: &PolybotClient,
+    client: &ClobClient,
+    store: &BracketStore,
+) -> Result<()> {
+    let open: Vec<_> = store.load()?.into_iter().filter(|b| b.is_open()).collect();
+    if open.is_empty() {
+        return Ok(());
+    }
+    handlers.refresh_data().await?;
+    let best_bids = BracketPass::fetch_best_bids(client, &BracketPass::watched_tokens(&open)).await;
+    let pass = BracketPass::run(handlers, &trading.route(), &open, &best_bids).await?;
+    for message in pass.messages {
+        println!("🎯 {}", message);
+    }
+    store.update(&pass.changed)
+}
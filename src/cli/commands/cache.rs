@@ -0,0 +1,117 @@
+//! Cache command: statistics and clearing of the REST response cache
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+
+use crate::core::http_cache::{self, Endpoint};
+use crate::data_paths::DataPaths;
+use crate::markets::datasets::format_bytes;
+
+#[derive(Args, Clone)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: Option<CacheAction>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum CacheAction {
+    /// Show entries, TTLs and hit rates per endpoint (the default)
+    Stats,
+    /// Delete cached responses
+    Clear {
+        /// Only this endpoint (markets, events, trades, price-history)
+        #[arg(long)]
+        endpoint: Option<String>,
+
+        /// Only entries past their TTL
+        #[arg(long)]
+        expired: bool,
+
+        /// Also forget the hit and miss counts
+        #[arg(long)]
+        reset_stats: bool,
+    },
+}
+
+pub struct CacheCommand {
+    args: CacheArgs,
+}
+
+impl CacheCommand {
+    pub fn new(args: CacheArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, _data_paths: DataPaths) -> Result<()> {
+        let cache = http_cache::global();
+        match &self.args.action {
+            None | Some(CacheAction::Stats) => {
+                println!("📦 Response cache at {}", cache.dir().display());
+                if !cache.is_enabled() {
+                    println!("   Disabled for this command (--no-cache)");
+                }
+                let counts = cache.saved_counts();
+                println!(
+                    "\n{:<15} {:>8} {:>8} {:>10} {:>6} {:>8} {:>8} {:>9}",
+                    "Endpoint", "Entries", "Expired", "Size", "TTL", "Hits", "Misses", "Hit rate"
+                );
+                for endpoint in Endpoint::ALL {
+                    let usage = cache.usage(endpoint);
+                    let endpoint_counts = counts
+                        .endpoints
+                        .get(endpoint.name())
+                        .copied()
+                        .unwrap_or_default();
+                    let hit_rate = endpoint_counts
+                        .hit_rate()
+                        .map(|rate| format!("{:.0}%", rate * 100.0))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!(
+                        "{:<15} {:>8} {:>8} {:>10} {:>5}m {:>8} {:>8} {:>9}",
+                        endpoint.name(),
+                        usage.entries,
+                        usage.expired,
+                        format_bytes(usage.bytes),
+                        endpoint.ttl().num_minutes(),
+                        endpoint_counts.hits,
+                        endpoint_counts.misses,
+                        hit_rate
+                    );
+                }
+                if let Some(since) = counts.since {
+                    println!(
+                        "\nHits and misses since {}",
+                        since.format("%Y-%m-%d %H:%M UTC")
+                    );
+                }
+            }
+            CacheAction::Clear {
+                endpoint,
+                expired,
+                reset_stats,
+            } => {
+                let endpoints = match endpoint {
+                    Some(name) => vec![Endpoint::from_name(name).ok_or_else(|| {
+                        anyhow!(
+                            "Unknown endpoint '{}' (expected one of: {})",
+                            name,
+                            Endpoint::ALL.map(|e| e.name()).join(", ")
+                        )
+                    })?],
+                    None => Endpoint::ALL.to_vec(),
+                };
+                let mut removed = 0;
+                for endpoint in endpoints {
+                    removed += cache.clear(endpoint, *expired)?;
+                }
+                let kind = if *expired { "expired " } else { "" };
+                println!("🗑️  Deleted {} {}cached responses", removed, kind);
+                if *reset_stats {
+                    cache.reset_counts()?;
+                    println!("   Hit and miss counts reset");
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,21 +2,36 @@
 
 use anyhow::Result;
 use clap::Args;
+use std::sync::Arc;
 use tracing::info;
 
 use crate::data_paths::DataPaths;
 use crate::logging::{init_logging, LogMode, LoggingConfig};
+use crate::markets::datasets::replay::{parse_speed, resolve_capture, CaptureReplay};
+use crate::theme::{self, ThemePreset};
 
 #[derive(Args, Clone)]
 pub struct CanvasArgs {
-    /// Window width
+    /// Window width, used when no monitor can be detected
     #[arg(long, default_value = "1200")]
     pub width: u32,
 
-    /// Window height
+    /// Window height, used when no monitor can be detected
     #[arg(long, default_value = "800")]
     pub height: u32,
 
+    /// Monitor to open on, by index as logged at startup (default: primary)
+    #[arg(long)]
+    pub monitor: Option<usize>,
+
+    /// Most repaints per second for a pane receiving market data
+    #[arg(long, default_value_t = crate::gui::repaint::DEFAULT_MAX_FPS)]
+    pub max_fps: u32,
+
+    /// Port of the daemon's strategy control API
+    #[arg(long, default_value_t = crate::strategy::control::DEFAULT_CONTROL_PORT)]
+    pub control_port: u16,
+
     /// Enable dark mode
     #[arg(long)]
     pub dark_mode: bool,
@@ -24,6 +39,26 @@ pub struct CanvasArgs {
     /// Window title
     #[arg(long, default_value = "Polybot Trading Canvas")]
     pub title: String,
+
+    /// Color palette, overriding the preset in theme.yaml
+    #[arg(long, value_enum)]
+    pub theme: Option<ThemePreset>,
+
+    /// Replay a `stream --record` capture (name or path) instead of streaming live
+    #[arg(long)]
+    pub replay: Option<String>,
+
+    /// Start of the replayed window (HH:MM[:SS], 'YYYY-MM-DD HH:MM[:SS]' or RFC 3339)
+    #[arg(long, requires = "replay")]
+    pub from: Option<String>,
+
+    /// End of the replayed window
+    #[arg(long, requires = "replay")]
+    pub to: Option<String>,
+
+    /// Replay speed multiplier
+    #[arg(long, default_value_t = 1.0, value_parser = parse_speed, requires = "replay")]
+    pub replay_speed: f64,
 }
 
 pub struct CanvasCommand {
@@ -39,19 +74,39 @@ impl CanvasCommand {
         // Initialize logging with console and file output for GUI mode
         let log_config = LoggingConfig::new(LogMode::ConsoleAndFile, data_paths.clone());
         init_logging(log_config)?;
+        crate::core::audit::set_context(crate::core::audit::AuditContext::Gui);
 
         info!("🎨 Starting Polybot Trading Canvas");
         info!("🌐 API Host: {}", host);
         info!("📁 Data Directory: {}", data_paths.root().display());
+        theme::init(&data_paths, self.args.theme);
+
+        // Load the window up front so a bad dataset or range fails before a window opens
+        let replay = match &self.args.replay {
+            Some(dataset) => {
+                let replay = CaptureReplay::load(
+                    &resolve_capture(&data_paths, dataset),
+                    self.args.from.as_deref(),
+                    self.args.to.as_deref(),
+                )?;
+                info!("⏪ Replaying {}", replay.describe());
+                Some((Arc::new(replay), self.args.replay_speed))
+            }
+            None => None,
+        };
 
         // Call the GUI launcher function from the library
         crate::gui::launch_trading_canvas(
             self.args.width,
             self.args.height,
+            self.args.monitor,
+            self.args.max_fps,
+            self.args.control_port,
             self.args.dark_mode,
             &self.args.title,
             host,
             data_paths,
+            replay,
         )
         .await
     }
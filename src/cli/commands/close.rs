@@ -0,0 +1,199 @@
+//! Close command: close a percentage of a position and record it in the lot history
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::Args;
+use rust_decimal::Decimal;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::client::PolybotClient;
+use crate::core::portfolio::api::orders::fetch_order;
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::close::{ClosePlan, ClosePrice};
+use crate::core::portfolio::import::IMPORTED_TRADE_PREFIX;
+use crate::core::portfolio::storage::{LotEntry, PortfolioStorage};
+use crate::core::portfolio::{OrderSide, PositionSide, PositionStatus};
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+/// How long to wait for a crossing close to be matched on the exchange
+const FILL_LINK_ATTEMPTS: usize = 3;
+const FILL_LINK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Args, Clone)]
+pub struct CloseArgs {
+    /// Token ID of the position
+    pub token_id: String,
+
+    /// Percentage of the position to close
+    #[arg(long, default_value = "100")]
+    pub pct: Decimal,
+
+    /// Limit price (defaults to the position's average price)
+    #[arg(long, conflicts_with = "market")]
+    pub limit: Option<Decimal>,
+
+    /// Cross the spread at the best bid (longs) or best ask (shorts)
+    #[arg(long)]
+    pub market: bool,
+
+    /// Confirm order placement (required unless RUST_ENV=production)
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Confirm orders above the large order notional threshold without prompting
+    #[arg(long)]
+    pub yes_large: bool,
+}
+
+pub struct CloseCommand {
+    args: CloseArgs,
+}
+
+impl CloseCommand {
+    pub fn new(args: CloseArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, using cached positions: {}", e);
+        }
+        let storage = PortfolioStorage::new(data_paths.root(), handlers.get_address());
+
+        // Catch up on fills of earlier closes before adding a new one
+        self.link_fills(host, &data_paths, handlers.get_address(), &storage).await?;
+
+        let position = handlers
+            .get_portfolio_state()
+            .await?
+            .positions
+            .into_iter()
+            .find(|p| p.token_id == self.args.token_id && p.status == PositionStatus::Open)
+            .ok_or_else(|| anyhow!("No open position in token {}", self.args.token_id))?;
+
+        let client = PolybotClient::connect_to(host, data_paths.clone()).await?;
+        let price = if self.args.market {
+            let book = client.order_book(&self.args.token_id).await?;
+            let touch = match position.side {
+                PositionSide::Long => book.best_bid(),
+                PositionSide::Short => book.best_ask(),
+            };
+            ClosePrice::Market(
+                touch
+                    .ok_or_else(|| anyhow!("Book is empty on the closing side"))?
+                    .price,
+            )
+        } else {
+            self.args.limit.map(ClosePrice::Limit).unwrap_or(ClosePrice::AveragePrice)
+        };
+        let plan = ClosePlan::new(&position, self.args.pct, price)?;
+
+        println!(
+            "📉 Closing {}% of {} {} (avg ${}): {:?} {} @ ${}, {} left",
+            self.args.pct,
+            position.size,
+            position.outcome,
+            position.average_price,
+            plan.side,
+            plan.size,
+            plan.price,
+            plan.remaining
+        );
+
+        // Check confirmation in non-production environments
+        if !self.args.yes && std::env::var("RUST_ENV").unwrap_or_default() != "production" {
+            warn!("⚠️  Order confirmation required. Use --yes to confirm.");
+            return Ok(());
+        }
+
+        let side = match plan.side {
+            OrderSide::Buy => Side::Buy,
+            OrderSide::Sell => Side::Sell,
+        };
+        let risk_config = RiskConfig::load(&data_paths)?;
+        if let Some(large) = risk_config.large_orders.check(&self.args.token_id, side, plan.price, plan.size) {
+            let journal = RiskJournal::new(&data_paths);
+            if !confirm_large_order_cli(&large, self.args.yes_large, &journal)? {
+                warn!("❌ Large order not confirmed, nothing was placed.");
+                return Ok(());
+            }
+        }
+
+        let response = match plan.side {
+            OrderSide::Sell => client.sell(&self.args.token_id, plan.price, plan.size).await?,
+            OrderSide::Buy => client.buy(&self.args.token_id, plan.price, plan.size).await?,
+        };
+        let order_id = response.into_order_id()?;
+        println!("📋 Order ID: {}", order_id);
+
+        storage
+            .record_lot_entry(&LotEntry {
+                order_id: order_id.clone(),
+                market_id: position.market_id.clone(),
+                token_id: self.args.token_id.clone(),
+                side: plan.side,
+                price: plan.price,
+                size: plan.size,
+                position_size_before: position.size,
+                average_price: position.average_price,
+                created_at: Utc::now(),
+                fills: Vec::new(),
+            })
+            .await?;
+
+        // Crossing orders fill right away; resting ones are linked on a later run
+        if self.args.market {
+            for _ in 0..FILL_LINK_ATTEMPTS {
+                tokio::time::sleep(FILL_LINK_INTERVAL).await;
+                let linked = self
+                    .link_fills(host, &data_paths, handlers.get_address(), &storage)
+                    .await?;
+                if linked > Decimal::ZERO {
+                    break;
+                }
+            }
+        }
+
+        let history = storage.load_lot_history(&self.args.token_id).await?;
+        println!("\n🧾 Lot history for {}:", self.args.token_id);
+        for entry in &history {
+            println!(
+                "  {} {:?} {} @ ${} | filled {} | realized ${}",
+                entry.created_at.format("%Y-%m-%d %H:%M"),
+                entry.side,
+                entry.size,
+                entry.price,
+                entry.filled_size(),
+                entry.realized_pnl().round_dp(2)
+            );
+        }
+        Ok(())
+    }
+    /// Link what the exchange matched on this token's unfilled closes, looked
+    /// up by their exchange order ID. Imported lots are their own fills.
+    async fn link_fills(
+        &self,
+        host: &str,
+        data_paths: &DataPaths,
+        address: &str,
+        storage: &PortfolioStorage,
+    ) -> Result<Decimal> {
+        let token_id = &self.args.token_id;
+        let mut linked = Decimal::ZERO;
+        for entry in storage.load_lot_history(token_id).await? {
+            if entry.order_id.starts_with(IMPORTED_TRADE_PREFIX) || entry.filled_size() >= entry.size {
+                continue;
+            }
+            match fetch_order(host, data_paths, address, &entry.order_id).await {
+                Ok(Some(order)) => linked += storage.link_order_fills(token_id, &order).await?,
+                Ok(None) => warn!("Exchange doesn't know close order {}", entry.order_id),
+                Err(e) => warn!("Failed to look up close order {}: {}", entry.order_id, e),
+            }
+        }
+        Ok(linked)
+    }
+}
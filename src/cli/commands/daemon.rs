@@ -1,4 +1,10 @@
 //! Daemon command for long-running WebSocket streaming with sample strategy
+//!
+//! The daemon also hosts strategies started through its control API (see
+//! [`crate::strategy::control`]), which the GUI's strategy panel talks to.
+//! With `--failover` several daemons elect a leader (see
+//! [`crate::core::failover`]): only the leader runs strategies and serves the
+//! control API, while standbys keep streaming and take over if it dies.
 
 use anyhow::Result;
 use clap::Args;
@@ -6,13 +12,22 @@ use owo_colors::OwoColorize;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::signal;
-use tracing::{info, warn};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
 
 use crate::data_paths::DataPaths;
+use crate::core::diagnostics::{record_event, spawn_recording, Subsystem};
+use crate::core::execution::trade_flow::CLOSE_CHECK_INTERVAL;
+use crate::core::failover::{Election, Role, DEFAULT_GROUP};
 use crate::core::services::{Streamer, StreamerConfig};
 use crate::core::ws::{AuthPayload, PolyEvent, WsConfig};
+use crate::strategy::control::{self, ControlCall, ControlToken, DEFAULT_CONTROL_PORT};
+use crate::strategy::flatten::FlattenSchedule;
+use crate::strategy::host::StrategyHost;
+use crate::strategy::stale_orders::StaleOrderRule;
 
 #[derive(Args, Clone)]
 pub struct DaemonArgs {
@@ -47,6 +62,47 @@ pub struct DaemonArgs {
     /// Use sandbox environment
     #[arg(long)]
     pub sandbox: bool,
+
+    /// Local port for the strategy control API
+    #[arg(long, default_value_t = DEFAULT_CONTROL_PORT)]
+    pub control_port: u16,
+
+    /// Do not serve the strategy control API
+    #[arg(long)]
+    pub no_control: bool,
+
+    /// UTC times (HH:MM, comma-separated) to flatten strategies with auto_flatten on
+    #[arg(long, value_delimiter = ',')]
+    pub flatten_at: Vec<String>,
+
+    /// Flatten strategies with auto_flatten on this many minutes before their market ends
+    #[arg(long)]
+    pub flatten_before_end: Option<i64>,
+
+    /// Cancel resting orders on the streamed tokens older than this (e.g. 30m)
+    #[arg(long, value_parser = crate::cli::parse_duration)]
+    pub cancel_stale_after: Option<Duration>,
+
+    /// Cancel resting orders on the streamed tokens more than this many ticks from the mid
+    #[arg(long)]
+    pub cancel_drift_ticks: Option<u32>,
+
+    /// Only report the orders the stale order limits would cancel
+    #[arg(long)]
+    pub stale_dry_run: bool,
+
+    /// Elect a leader among daemons sharing this data directory; standbys keep
+    /// streaming and take over strategies and open orders if the leader dies
+    #[arg(long)]
+    pub failover: bool,
+
+    /// Failover group: daemons in the same group elect one leader
+    #[arg(long, default_value = DEFAULT_GROUP)]
+    pub failover_group: String,
+
+    /// Take over when the leader hasn't renewed its lease for this long
+    #[arg(long, default_value = "15s", value_parser = crate::cli::parse_duration)]
+    pub lease_ttl: Duration,
 }
 
 pub struct DaemonCommand {
@@ -110,12 +166,79 @@ impl DaemonCommand {
         // Create and start streamer
         let mut streamer = Streamer::new(streamer_config);
         streamer.start(host, &data_paths).await?;
+        let _recording = spawn_recording(&data_paths, "daemon");
 
         // Set up event handling for sample strategy
         let mut events = streamer.events();
         let mut summary_timer =
             tokio::time::interval(Duration::from_secs(self.args.summary_interval));
 
+        // Strategies started through the control API
+        let flatten_schedule =
+            FlattenSchedule::new(&self.args.flatten_at, self.args.flatten_before_end)?;
+        if !flatten_schedule.is_empty() {
+            info!(
+                "📉 Auto-flatten: {:?} UTC, {:?} minutes before market end",
+                self.args.flatten_at, self.args.flatten_before_end
+            );
+        }
+        let stale_rule = StaleOrderRule {
+            max_age: self
+                .args
+                .cancel_stale_after
+                .map(chrono::Duration::from_std)
+                .transpose()?,
+            max_drift_ticks: self.args.cancel_drift_ticks,
+        };
+        if !stale_rule.is_empty() {
+            info!(
+                "🧹 Stale order cancel{}: older than {:?}, more than {:?} ticks from mid",
+                if self.args.stale_dry_run { " (dry run)" } else { "" },
+                self.args.cancel_stale_after,
+                self.args.cancel_drift_ticks
+            );
+        }
+        let mut strategy_host = StrategyHost::new(host, data_paths.clone())
+            .with_flatten_schedule(flatten_schedule)
+            .with_stale_order_rule(stale_rule, self.args.stale_dry_run);
+        let (control_tx, mut control_rx) = mpsc::channel::<ControlCall>(32);
+
+        // Only the leader of a failover group trades; standbys keep the stream warm
+        let mut election = self
+            .args
+            .failover
+            .then(|| Election::new(&data_paths, &self.args.failover_group, self.args.lease_ttl))
+            .transpose()?;
+        let mut leader = true;
+        if let Some(election) = &mut election {
+            leader = self.try_lead(election, &mut strategy_host).await?;
+            if !leader {
+                info!(
+                    "🕰️ Standing by in failover group '{}' as {}",
+                    election.group(),
+                    election.instance().describe()
+                );
+            }
+        }
+        let mut last_renewed = Instant::now();
+        let mut failover_timer = tokio::time::interval(
+            election
+                .as_ref()
+                .map(Election::heartbeat_interval)
+                .unwrap_or(Duration::from_secs(5)),
+        );
+        let mut control_server = if leader {
+            self.serve_control(&data_paths, &control_tx).await?
+        } else {
+            None
+        };
+        let mut order_timer = tokio::time::interval(Duration::from_secs(1));
+        let mut flatten_timer = tokio::time::interval(Duration::from_secs(15));
+        let mut stale_timer = tokio::time::interval(Duration::from_secs(30));
+        let mut bracket_timer = tokio::time::interval(Duration::from_secs(10));
+        let mut schedule_timer = tokio::time::interval(Duration::from_secs(1));
+        let mut trade_flow_timer = tokio::time::interval(CLOSE_CHECK_INTERVAL);
+
         info!(
             "{}",
             "✅ Daemon started. Press Ctrl+C to stop.".bright_green()
@@ -132,6 +255,9 @@ impl DaemonCommand {
                 result = events.recv() => {
                     match result {
                         Ok(event) => {
+                            if leader {
+                                strategy_host.on_event(&event, &streamer).await;
+                            }
                             self.handle_strategy_event(event).await;
                         }
                         Err(e) => {
@@ -145,6 +271,98 @@ impl DaemonCommand {
                     self.execute_sample_strategy(&streamer).await;
                 }
 
+                // Strategy control API requests
+                Some(call) = control_rx.recv() => {
+                    let response = strategy_host.handle(call.request, &self.args.assets).await;
+                    let _ = call.reply.send(response);
+                }
+
+                // Orders proposed by hosted strategies
+                _ = order_timer.tick(), if leader => {
+                    strategy_host.process_pending_orders().await;
+                }
+
+                // Scheduled and pre-resolution flattens
+                _ = flatten_timer.tick(), if leader => {
+                    strategy_host.run_flatten_schedule(&streamer).await;
+                }
+
+                // Stale resting orders
+                _ = stale_timer.tick(), if leader => {
+                    strategy_host.run_stale_order_check(&streamer, &self.args.assets).await;
+                }
+
+                // Bracket order exits
+                _ = bracket_timer.tick(), if leader => {
+                    strategy_host.run_brackets(&streamer).await;
+                }
+
+                // Sweeps that closed without a later trade
+                _ = trade_flow_timer.tick(), if leader => {
+                    strategy_host.close_trade_flow().await;
+                }
+
+                // Scheduled orders that fell due
+                _ = schedule_timer.tick(), if leader => {
+                    strategy_host.run_scheduled_orders().await;
+                }
+
+                // Lease renewal by the leader, takeover by a standby
+                _ = failover_timer.tick(), if election.is_some() => {
+                    let election = election.as_mut().expect("guarded by the select");
+                    if !leader {
+                        match self.try_lead(election, &mut strategy_host).await {
+                            Ok(true) => {
+                                leader = true;
+                                last_renewed = Instant::now();
+                                control_server = self.serve_control(&data_paths, &control_tx).await?;
+                            }
+                            Ok(false) => {}
+                            Err(e) => warn!("Failed to check the failover lease: {}", e),
+                        }
+                        continue;
+                    }
+                    let lost = match election.renew() {
+                        Ok(true) => {
+                            last_renewed = Instant::now();
+                            let state = strategy_host.handover_state().await;
+                            // The lease is checked again under the lock the handover is written in
+                            match election.save_handover(&state) {
+                                Ok(saved) => !saved,
+                                Err(e) => {
+                                    warn!("Failed to save the failover handover: {}", e);
+                                    false
+                                }
+                            }
+                        }
+                        Ok(false) => true,
+                        // A standby takes over once the lease expires, renewed or not
+                        Err(e) => {
+                            warn!("Failed to renew the failover lease: {}", e);
+                            last_renewed.elapsed() >= self.args.lease_ttl
+                        }
+                    };
+                    if lost {
+                        warn!(
+                            "{}",
+                            format!(
+                                "⚠️ Lost leadership of failover group '{}', standing by",
+                                election.group()
+                            )
+                            .bright_yellow()
+                        );
+                        record_event(
+                            Subsystem::Strategy,
+                            format!("lost leadership of failover group {}", election.group()),
+                        );
+                        leader = false;
+                        if let Some(server) = control_server.take() {
+                            server.abort();
+                        }
+                        strategy_host.shutdown().await;
+                    }
+                }
+
                 // Handle shutdown signal
                 _ = signal::ctrl_c() => {
                     info!("\n{}", "🛑 Shutdown signal received...".bright_yellow());
@@ -153,6 +371,24 @@ impl DaemonCommand {
             }
         }
 
+        // Hand strategies and open orders to a standby before stopping them
+        if let Some(election) = &mut election {
+            if election.is_leader() {
+                let state = strategy_host.handover_state().await;
+                match election.release(&state) {
+                    Ok(()) => info!("🤝 Released the failover lease to the standbys"),
+                    Err(e) => error!("Failed to release the failover lease: {}", e),
+                }
+            }
+            election.leave();
+        }
+
+        // Stop hosted strategies before the stream they trade on
+        if let Some(server) = control_server {
+            server.abort();
+        }
+        strategy_host.shutdown().await;
+
         // Stop streamer
         info!("Stopping streaming daemon");
         streamer.stop().await;
@@ -161,6 +397,83 @@ impl DaemonCommand {
         Ok(())
     }
 
+    /// Serve the strategy control API unless disabled
+    async fn serve_control(
+        &self,
+        data_paths: &DataPaths,
+        calls: &mpsc::Sender<ControlCall>,
+    ) -> Result<Option<JoinHandle<()>>> {
+        if self.args.no_control {
+            return Ok(None);
+        }
+        let token = ControlToken::load_or_create(data_paths)?;
+        Ok(Some(
+            control::serve(self.args.control_port, token, calls.clone()).await?,
+        ))
+    }
+
+    /// Try to lead the failover group, taking over the strategies and open
+    /// orders of the previous leader; false while another instance leads
+    async fn try_lead(
+        &self,
+        election: &mut Election,
+        strategy_host: &mut StrategyHost,
+    ) -> Result<bool> {
+        let Role::Leader { term, previous } = election.try_acquire()? else {
+            election.heartbeat_standby()?;
+            return Ok(false);
+        };
+        info!(
+            "{}",
+            format!(
+                "👑 Leading failover group '{}' (term {}) as {}",
+                election.group(),
+                term,
+                election.instance().describe()
+            )
+            .bright_green()
+        );
+        if let Some(lease) = previous
+            .as_ref()
+            .filter(|lease| lease.holder != *election.instance())
+        {
+            let how = if lease.released {
+                "handed over"
+            } else {
+                "lease expired"
+            };
+            info!("Took over from {} ({})", lease.holder.describe(), how);
+        }
+        record_event(
+            Subsystem::Strategy,
+            format!(
+                "leading failover group {} (term {})",
+                election.group(),
+                term
+            ),
+        );
+
+        match election.handover()? {
+            Some(handover) if !handover.is_of(previous.as_ref()) => warn!(
+                "Ignoring the handover of term {}, it isn't of the lease taken over",
+                handover.term
+            ),
+            Some(handover) => {
+                info!(
+                    "📦 Handover from {}: {} strategies, {} open orders",
+                    handover.from.describe(),
+                    handover.state.strategies.len(),
+                    handover.state.open_orders.len()
+                );
+                if let Err(e) = strategy_host.take_over(handover.state).await {
+                    error!("Failed to take over the handover: {}", e);
+                }
+            }
+            None => {}
+        }
+        Ok(true)
+    }
+
     /// Handle individual streaming events for strategy
     async fn handle_strategy_event(&self, event: PolyEvent) {
         match event {
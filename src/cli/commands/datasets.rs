@@ -1,17 +1,31 @@
 //! Dataset management command for listing, deleting, and managing pipeline outputs
 
 use anyhow::Result;
-use clap::Args;
+use chrono::Local;
+use clap::{Args, Subcommand};
+use rust_decimal::Decimal;
 use std::path::PathBuf;
 use tracing::{error, info, warn};
 
 use crate::data_paths::{DataPaths, DATASETS_DIR, DEFAULT_DATASETS_DIR};
-use crate::markets::datasets::{
-    format_bytes, DatasetManager, DatasetManagerConfig, DatasetTui, DatasetType,
-};
+use crate::markets::datasets::backfill::{backfill_capture, BackfillSummary};
+use crate::markets::datasets::capture::import_capture;
+use crate::markets::datasets::from_search::{self, MarketSearchQuery};
+use crate::markets::datasets::replay::resolve_capture;
+use crate::markets::datasets::{format_bytes, DatasetManager, DatasetManagerConfig, DatasetType};
+#[cfg(feature = "tui")]
+use crate::markets::datasets::DatasetTui;
+use crate::markets::suppression::SUPPRESSION_CONFIG_FILE;
+use crate::number_format;
+use crate::typed_store::candles;
+use crate::typed_store::freshness::default_db_path;
+use crate::typed_store::models::CandleInterval;
 
 #[derive(Args, Clone)]
 pub struct DatasetsArgs {
+    #[command(subcommand)]
+    pub command: Option<DatasetsSubcommand>,
+
     /// Base directory to scan for datasets
     #[arg(long, default_value = DEFAULT_DATASETS_DIR)]
     pub base_dir: String,
@@ -53,6 +67,85 @@ pub struct DatasetsArgs {
     pub details: bool,
 }
 
+#[derive(Subcommand, Clone)]
+pub enum DatasetsSubcommand {
+    /// Write the open markets matching a search of the local Gamma store as a dataset
+    CreateFromSearch(CreateFromSearchArgs),
+    /// Import a stream capture recorded elsewhere and backfill candles from it
+    Import(ImportArgs),
+    /// Backfill candles and rolling statistics from stream captures
+    Backfill(BackfillArgs),
+    /// Show a token's candles with their rolling statistics
+    Candles(CandlesArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct ImportArgs {
+    /// Capture directory, or its events.jsonl file
+    pub path: PathBuf,
+
+    /// Dataset name (default: captures/<capture directory name>)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Only copy the capture, without backfilling candles
+    #[arg(long)]
+    pub no_backfill: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct BackfillArgs {
+    /// Capture dataset names or directories (default: every stream capture)
+    pub captures: Vec<String>,
+}
+
+#[derive(Args, Clone)]
+pub struct CandlesArgs {
+    /// Token ID
+    pub token_id: String,
+
+    #[arg(long, value_enum, default_value_t = CandleInterval::OneMinute)]
+    pub interval: CandleInterval,
+
+    /// Most recent candles to show (0 shows all)
+    #[arg(long, default_value = "30")]
+    pub limit: usize,
+}
+
+#[derive(Args, Clone)]
+pub struct CreateFromSearchArgs {
+    /// Text to find in market questions, descriptions or outcomes
+    pub query: Option<String>,
+
+    /// Dataset name (default: search_<query>)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Only markets with any of these tags (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub tags: Vec<String>,
+
+    /// Only markets in this category
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// Minimum total volume in USD
+    #[arg(long)]
+    pub min_volume: Option<Decimal>,
+
+    /// Minimum liquidity in USD
+    #[arg(long)]
+    pub min_liquidity: Option<Decimal>,
+
+    /// Most markets to keep, highest volume first (0 keeps all)
+    #[arg(long, default_value = "50")]
+    pub limit: usize,
+
+    /// Replace an existing dataset of the same name
+    #[arg(long)]
+    pub force: bool,
+}
+
 pub struct DatasetsCommand {
     args: DatasetsArgs,
 }
@@ -62,7 +155,17 @@ impl DatasetsCommand {
         Self { args }
     }
 
-    pub async fn execute(&self, _host: &str, _data_paths: DataPaths) -> Result<()> {
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            Some(DatasetsSubcommand::CreateFromSearch(args)) => {
+                return self.create_from_search(args, &data_paths);
+            }
+            Some(DatasetsSubcommand::Import(args)) => return self.import(args, &data_paths),
+            Some(DatasetsSubcommand::Backfill(args)) => return self.backfill(args, &data_paths),
+            Some(DatasetsSubcommand::Candles(args)) => return self.candles(args, &data_paths),
+            None => {}
+        }
+
         let config = self.create_config()?;
 
         // Launch interactive TUI if requested or no specific action provided
@@ -94,6 +197,181 @@ impl DatasetsCommand {
         self.list_datasets(&manager).await
     }
 
+    /// Materialize a search of the local Gamma store as a dataset
+    fn create_from_search(
+        &self,
+        args: &CreateFromSearchArgs,
+        data_paths: &DataPaths,
+    ) -> Result<()> {
+        let query = MarketSearchQuery {
+            keyword: args.query.clone(),
+            tags: args.tags.clone(),
+            category: args.category.clone(),
+            min_volume: args.min_volume,
+            min_liquidity: args.min_liquidity,
+            limit: args.limit,
+        };
+        if query.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Give a search query or at least one of --tags, --category, --min-volume, --min-liquidity"
+            ));
+        }
+        let name = args.name.clone().unwrap_or_else(|| query.default_name());
+
+        info!("🔍 Searching local Gamma data...");
+        let dataset = from_search::create_from_search(data_paths, &name, &query, args.force)?;
+        info!(
+            "✅ Created dataset {} with {} markets ({} tokens)",
+            dataset.name, dataset.markets, dataset.tokens
+        );
+        if dataset.suppressed > 0 {
+            info!(
+                "   {} matching markets left out by {}",
+                dataset.suppressed, SUPPRESSION_CONFIG_FILE
+            );
+        }
+        info!("   {}", dataset.path.display());
+        info!("Pick it in the dataset selector of `polybot canvas` or `polybot stream`.");
+        Ok(())
+    }
+
+    fn import(&self, args: &ImportArgs, data_paths: &DataPaths) -> Result<()> {
+        let name = match &args.name {
+            Some(name) => name.clone(),
+            None => {
+                let dir = if args.path.is_dir() {
+                    args.path.as_path()
+                } else {
+                    args.path.parent().unwrap_or(&args.path)
+                };
+                let dir_name = dir
+                    .canonicalize()
+                    .ok()
+                    .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .unwrap_or_else(|| "imported".to_string());
+                format!("captures/{}", dir_name)
+            }
+        };
+
+        let summary = import_capture(&data_paths.datasets(), &args.path, &name)?;
+        info!(
+            "📼 Imported {} events for {} tokens into dataset '{}'",
+            summary.events, summary.tokens, summary.name
+        );
+        if let (Some(from), Some(to)) = (summary.started_at, summary.finished_at) {
+            info!(
+                "   Recorded {} to {}",
+                from.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                to.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+            );
+        }
+        if args.no_backfill {
+            info!(
+                "Backfill candles later with `polybot datasets backfill {}`",
+                summary.name
+            );
+            return Ok(());
+        }
+        let backfill = backfill_capture(&summary.path, &default_db_path(data_paths))?;
+        report_backfill(&backfill);
+        Ok(())
+    }
+
+    fn backfill(&self, args: &BackfillArgs, data_paths: &DataPaths) -> Result<()> {
+        let captures: Vec<PathBuf> = if args.captures.is_empty() {
+            let mut manager = DatasetManager::new(DatasetManagerConfig {
+                base_dir: data_paths.datasets(),
+                scan_dirs: Vec::new(),
+                recursive: true,
+                max_depth: self.args.max_depth,
+            });
+            manager.scan_datasets()?;
+            manager
+                .get_datasets()
+                .iter()
+                .filter(|dataset| matches!(dataset.dataset_type, DatasetType::StreamCapture { .. }))
+                .map(|dataset| dataset.path.clone())
+                .collect()
+        } else {
+            args.captures
+                .iter()
+                .map(|capture| resolve_capture(data_paths, capture))
+                .collect()
+        };
+        if captures.is_empty() {
+            info!("No stream captures to backfill (record one with `polybot stream --record`)");
+            return Ok(());
+        }
+
+        let db_path = default_db_path(data_paths);
+        let mut failed = 0;
+        for capture in &captures {
+            match backfill_capture(capture, &db_path) {
+                Ok(backfill) => report_backfill(&backfill),
+                Err(e) => {
+                    error!("❌ {}: {:#}", capture.display(), e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "{} of {} captures failed to backfill",
+                failed,
+                captures.len()
+            ));
+        }
+        Ok(())
+    }
+
+    fn candles(&self, args: &CandlesArgs, data_paths: &DataPaths) -> Result<()> {
+        let series = candles::load(
+            &default_db_path(data_paths),
+            &args.token_id,
+            args.interval,
+            None,
+            None,
+        )?;
+        if series.is_empty() {
+            info!(
+                "No {} candles for {} (backfill them with `polybot datasets backfill`)",
+                args.interval, args.token_id
+            );
+            return Ok(());
+        }
+
+        let skip = match args.limit {
+            0 => 0,
+            limit => series.len().saturating_sub(limit),
+        };
+        println!(
+            "{:<16}  {:>7} {:>7} {:>7} {:>7}  {:>10} {:>6}  {:>7} {:>7} {:>7}",
+            "START", "OPEN", "HIGH", "LOW", "CLOSE", "VOLUME", "TRADES", "SMA", "VWAP", "VOL"
+        );
+        for (candle, stats) in series.iter().skip(skip) {
+            let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            let stats = stats.as_ref();
+            println!(
+                "{:<16}  {:>7} {:>7} {:>7} {:>7}  {:>10} {:>6}  {:>7} {:>7} {:>7}",
+                candle.start.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                number_format::price(candle.open),
+                number_format::price(candle.high),
+                number_format::price(candle.low),
+                number_format::price(candle.close),
+                number_format::size(candle.volume),
+                candle.trades,
+                optional(stats.map(|s| number_format::price(s.sma))),
+                optional(stats.and_then(|s| s.vwap).map(number_format::price)),
+                optional(
+                    stats
+                        .and_then(|s| s.volatility)
+                        .map(|v| number_format::percent(v * 100.0))
+                ),
+            );
+        }
+        Ok(())
+    }
+
     /// Create dataset manager configuration from CLI arguments
     fn create_config(&self) -> Result<DatasetManagerConfig> {
         let mut scan_dirs = vec![
@@ -119,6 +397,7 @@ impl DatasetsCommand {
     }
 
     /// Launch the interactive TUI interface
+    #[cfg(feature = "tui")]
     async fn launch_interactive_tui(&self, config: DatasetManagerConfig) -> Result<()> {
         info!("🚀 Launching interactive dataset manager...");
 
@@ -129,6 +408,11 @@ impl DatasetsCommand {
         Ok(())
     }
 
+    #[cfg(not(feature = "tui"))]
+    async fn launch_interactive_tui(&self, _config: DatasetManagerConfig) -> Result<()> {
+        Err(super::unavailable::missing_feature("datasets (interactive)", "tui"))
+    }
+
     /// Handle dataset deletion
     async fn handle_delete_datasets(&self, manager: &mut DatasetManager) -> Result<()> {
         if self.args.delete.is_empty() {
@@ -404,3 +688,18 @@ impl DatasetsCommand {
         }
     }
 }
+
+fn report_backfill(backfill: &BackfillSummary) {
+    match (backfill.from, backfill.to) {
+        (Some(from), Some(to)) => info!(
+            "🕯️  {}: {} candles for {} tokens from {} to {} ({} rolling stats updated)",
+            backfill.capture,
+            backfill.candles,
+            backfill.tokens,
+            from.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            to.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            backfill.stats
+        ),
+        _ => info!("🕯️  {}: no trades to build candles from", backfill.capture),
+    }
+}
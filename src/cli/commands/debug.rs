@@ -0,0 +1,134 @@
+//! Debug command: collect diagnostics for bug reports
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use crate::core::diagnostics::{DebugBundle, RecentEvent, SessionState, Subsystem};
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct DebugArgs {
+    #[command(subcommand)]
+    pub command: DebugSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum DebugSubcommand {
+    /// Zip session state, recent events, portfolio snapshots, redacted config
+    /// and log tails for attaching to a bug report
+    Dump {
+        /// Where to write the zip (default: <data_dir>/diagnostics/polybot-debug-<time>.zip)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Leave log files out of the bundle
+        #[arg(long)]
+        no_logs: bool,
+    },
+
+    /// Print the recent events buffered by running sessions
+    Events {
+        /// Only this subsystem's buffer
+        #[arg(long, value_enum)]
+        subsystem: Option<Subsystem>,
+
+        /// Number of most recent events to print
+        #[arg(long, default_value = "100")]
+        last: usize,
+
+        /// Only the session with this source (gui, daemon, strategy:<name>)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Print events as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub struct DebugCommand {
+    args: DebugArgs,
+}
+
+impl DebugCommand {
+    pub fn new(args: DebugArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            DebugSubcommand::Dump { output, no_logs } => {
+                let bundle = DebugBundle::collect(&data_paths, None, !no_logs).await?;
+                let path = output.clone().unwrap_or_else(|| bundle.default_path(&data_paths));
+                bundle.write_zip(&path)?;
+
+                println!("🐞 Debug bundle written to {}", path.display());
+                match &bundle.session {
+                    Some(session) => println!(
+                        "   Session: {} (pid {}) as of {}, {} workers, {} recent events",
+                        session.source,
+                        session.pid,
+                        session.captured_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                        session.workers.len(),
+                        session.events.len()
+                    ),
+                    None => println!("   Session: none recorded (start the GUI to record one)"),
+                }
+                println!(
+                    "   Portfolio snapshots: {}, config files: {}, log files: {}",
+                    bundle.portfolio.len(),
+                    bundle.config.len(),
+                    bundle.logs.len()
+                );
+                println!("   Secrets in config are redacted; review the logs before sharing.");
+            }
+            DebugSubcommand::Events { subsystem, last, source, json } => {
+                let sessions: Vec<_> = SessionState::load_recorded(&data_paths)?
+                    .into_iter()
+                    .filter(|s| source.as_ref().is_none_or(|source| &s.source == source))
+                    .collect();
+
+                let mut events: Vec<(String, RecentEvent)> = sessions
+                    .iter()
+                    .flat_map(|s| s.events.iter().map(|e| (s.source.clone(), e.clone())))
+                    .filter(|(_, e)| subsystem.is_none_or(|s| e.subsystem == s))
+                    .collect();
+                events.sort_by_key(|(_, e)| e.timestamp);
+
+                if events.is_empty() {
+                    println!("No buffered events. Running GUI, daemon and run-strategy sessions record");
+                    println!("their event buffers every 30 seconds; check diagnostics.yaml if a subsystem is off.");
+                    return Ok(());
+                }
+
+                if !*json {
+                    for session in &sessions {
+                        println!(
+                            "📼 {} (pid {}) recorded {}",
+                            session.source,
+                            session.pid,
+                            session.captured_at.format("%Y-%m-%d %H:%M:%S UTC")
+                        );
+                    }
+                }
+                for (source, event) in &events[events.len().saturating_sub(*last)..] {
+                    if *json {
+                        let mut value = serde_json::to_value(event)?;
+                        value["source"] = serde_json::Value::String(source.clone());
+                        println!("{}", value);
+                    } else {
+                        println!(
+                            "{}  {:<16} {:<8} {}",
+                            event.timestamp.format("%H:%M:%S%.3f"),
+                            source,
+                            event.subsystem,
+                            event.message
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,508 @@
+//! Doctor command: aggregated health check across all polybot subsystems
+//!
+//! Runs a series of independent checks (credentials, API reachability, clock
+//! skew, data directory permissions, pending data migrations, RocksDB stores,
+//! search index freshness and WebSocket connectivity) and prints a pass/warn/fail report with remediation
+//! hints. Exits with an error when any check fails so it can gate scripts.
+
+use anyhow::Result;
+use clap::Args;
+use owo_colors::OwoColorize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+use crate::core::ws::WsConfig;
+use crate::data_paths::DataPaths;
+use crate::markets::datasets::format_bytes;
+use crate::markets::gamma::fast_search::FAST_SEARCH_INDEX_FILE;
+use crate::markets::gamma::{get_index_path, GammaStoreConfig};
+use crate::typed_store::freshness;
+use crate::migrations::MigrationRunner;
+
+/// Gamma API base used for reachability checks
+const GAMMA_API_URL: &str = "https://gamma-api.polymarket.com";
+
+#[derive(Args, Clone)]
+pub struct DoctorArgs {
+    /// Skip all checks that require network access
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Skip credential decryption and the authenticated API call
+    #[arg(long)]
+    pub skip_auth: bool,
+
+    /// Clock skew (seconds) above which the check warns; fails at 5x this value
+    #[arg(long, default_value = "2")]
+    pub max_clock_skew_secs: u64,
+
+    /// Age (hours) after which the search index is reported stale
+    #[arg(long, default_value = "24")]
+    pub index_max_age_hours: u64,
+
+    /// Timeout in seconds for each network probe
+    #[arg(long, default_value = "10")]
+    pub timeout_secs: u64,
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    fn label(&self) -> String {
+        match self {
+            CheckStatus::Pass => "PASS".bright_green().bold().to_string(),
+            CheckStatus::Warn => "WARN".bright_yellow().bold().to_string(),
+            CheckStatus::Fail => "FAIL".bright_red().bold().to_string(),
+            CheckStatus::Skip => "SKIP".bright_black().bold().to_string(),
+        }
+    }
+}
+
+/// Result of a single subsystem check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub remediation: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, remediation: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            remediation: Some(remediation.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, remediation: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            remediation: Some(remediation.into()),
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+}
+
+/// Collected results of a doctor run
+#[derive(Debug, Default)]
+pub struct DoctorReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn push(&mut self, result: CheckResult) {
+        match result.status {
+            CheckStatus::Fail => warn!("doctor: {} failed: {}", result.name, result.detail),
+            CheckStatus::Warn => warn!("doctor: {} warning: {}", result.name, result.detail),
+            _ => info!("doctor: {} {:?}: {}", result.name, result.status, result.detail),
+        }
+        self.results.push(result);
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.results.iter().filter(|r| r.status == status).count()
+    }
+
+    fn print(&self) {
+        println!("\n{}\n", "🩺 Polybot Doctor".bright_blue().bold());
+
+        for result in &self.results {
+            println!("  [{}] {:<22} {}", result.status.label(), result.name, result.detail);
+            if let Some(hint) = &result.remediation {
+                println!("         {} {}", "↳".bright_black(), hint.bright_black());
+            }
+        }
+
+        println!(
+            "\n  {} passed, {} warnings, {} failed, {} skipped\n",
+            self.count(CheckStatus::Pass).to_string().bright_green(),
+            self.count(CheckStatus::Warn).to_string().bright_yellow(),
+            self.count(CheckStatus::Fail).to_string().bright_red(),
+            self.count(CheckStatus::Skip).to_string().bright_black()
+        );
+    }
+}
+
+pub struct DoctorCommand {
+    args: DoctorArgs,
+}
+
+impl DoctorCommand {
+    pub fn new(args: DoctorArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        info!("Running doctor checks against {}", host);
+
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.args.timeout_secs))
+            .build()?;
+
+        let mut report = DoctorReport::default();
+        report.push(self.check_data_directory(&data_paths));
+        report.push(self.check_migrations(&data_paths));
+        report.push(self.check_credentials(host, &data_paths).await);
+        report.push(self.check_api_reachability(&http, "CLOB API", host).await);
+        report.push(self.check_api_reachability(&http, "Gamma API", GAMMA_API_URL).await);
+        report.push(self.check_clock_skew(&http, host).await);
+        report.push(self.check_rocksdb("Market index DB", &freshness::default_db_path(&data_paths)));
+        report.push(self.check_gamma_db(&data_paths));
+        report.push(self.check_search_index(&data_paths));
+        report.push(self.check_streaming().await);
+
+        report.print();
+
+        let failures = report.count(CheckStatus::Fail);
+        if failures > 0 {
+            return Err(anyhow::anyhow!("{} doctor check(s) failed", failures));
+        }
+        Ok(())
+    }
+
+    /// Verify every data directory exists and is writable
+    fn check_data_directory(&self, data_paths: &DataPaths) -> CheckResult {
+        const NAME: &str = "Data directory";
+        let dirs = [
+            data_paths.root().clone(),
+            data_paths.auth(),
+            data_paths.datasets(),
+            data_paths.runs(),
+            data_paths.logs(),
+        ];
+
+        for dir in &dirs {
+            if !dir.is_dir() {
+                return CheckResult::fail(
+                    NAME,
+                    format!("{} is missing", dir.display()),
+                    "Re-run any polybot command to recreate the layout, or pass --data-dir",
+                );
+            }
+            let probe = dir.join(".doctor-write-probe");
+            if let Err(e) = std::fs::write(&probe, b"ok").and_then(|_| std::fs::remove_file(&probe)) {
+                return CheckResult::fail(
+                    NAME,
+                    format!("{} is not writable: {}", dir.display(), e),
+                    format!("Fix ownership/permissions of {}", dir.display()),
+                );
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(metadata) = std::fs::metadata(data_paths.auth()) {
+                let mode = metadata.permissions().mode();
+                if mode & 0o077 != 0 {
+                    return CheckResult::warn(
+                        NAME,
+                        format!("auth directory is accessible by other users (mode {:o})", mode & 0o777),
+                        format!("chmod 700 {}", data_paths.auth().display()),
+                    );
+                }
+            }
+        }
+
+        CheckResult::pass(NAME, format!("{} is writable", data_paths.root().display()))
+    }
+
+    /// Report format migrations the data directory still needs, without running them
+    fn check_migrations(&self, data_paths: &DataPaths) -> CheckResult {
+        const NAME: &str = "Data migrations";
+        let pending = match MigrationRunner::new(data_paths).pending() {
+            Ok(pending) => pending,
+            Err(e) => {
+                return CheckResult::fail(
+                    NAME,
+                    format!("cannot migrate {}: {}", data_paths.root().display(), e),
+                    "Use a polybot build that supports this data directory, or restore it from backups/migrations",
+                )
+            }
+        };
+
+        let Some(next) = pending.first() else {
+            return CheckResult::pass(NAME, "data formats are current");
+        };
+        let steps = pending.iter().map(|m| m.name).collect::<Vec<_>>().join(", ");
+        let remediation = if pending.iter().all(|m| m.runs_at_startup) {
+            "Run any polybot command to apply them"
+        } else {
+            "Run 'polybot index' to apply them"
+        };
+        CheckResult::warn(
+            NAME,
+            format!(
+                "{} pending, starting at {} v{}: {}",
+                pending.len(),
+                next.component,
+                next.from_version,
+                steps
+            ),
+            remediation,
+        )
+    }
+
+    /// Decrypt stored credentials and make an authenticated API call
+    async fn check_credentials(&self, host: &str, data_paths: &DataPaths) -> CheckResult {
+        const NAME: &str = "Credentials";
+        let creds_path = data_paths.auth().join("creds.json.enc");
+        if !creds_path.exists() {
+            return CheckResult::fail(
+                NAME,
+                "no stored credentials found",
+                "Run 'polybot init' to create API credentials",
+            );
+        }
+        if self.args.skip_auth {
+            return CheckResult::skip(NAME, "credential file present, decryption skipped (--skip-auth)");
+        }
+
+        let client = match crate::auth::get_authenticated_client(host, data_paths).await {
+            Ok(client) => client,
+            Err(e) => {
+                return CheckResult::fail(
+                    NAME,
+                    format!("could not load credentials: {}", e),
+                    "Check POLYBOT_PASSPHRASE or re-run 'polybot init'",
+                )
+            }
+        };
+
+        if self.args.offline {
+            return CheckResult::pass(NAME, "credentials decrypted (authenticated call skipped: --offline)");
+        }
+
+        match client.get_orders(None, None).await {
+            Ok(orders) => CheckResult::pass(
+                NAME,
+                format!("authenticated API call succeeded ({} open orders)", orders.len()),
+            ),
+            Err(e) => CheckResult::fail(
+                NAME,
+                format!("API rejected credentials: {}", e),
+                "Re-derive API keys with 'polybot init'",
+            ),
+        }
+    }
+
+    /// Check that an HTTP API answers
+    async fn check_api_reachability(
+        &self,
+        http: &reqwest::Client,
+        name: &'static str,
+        base_url: &str,
+    ) -> CheckResult {
+        if self.args.offline {
+            return CheckResult::skip(name, "skipped (--offline)");
+        }
+
+        let started = std::time::Instant::now();
+        match http.get(base_url).send().await {
+            Ok(response) if response.status().is_server_error() => CheckResult::fail(
+                name,
+                format!("{} returned {}", base_url, response.status()),
+                "The service may be degraded; retry later",
+            ),
+            Ok(response) => {
+                let latency = started.elapsed().as_millis();
+                if latency > 2000 {
+                    CheckResult::warn(
+                        name,
+                        format!("reachable but slow ({} ms)", latency),
+                        "Check network latency or proxy settings",
+                    )
+                } else {
+                    debug!("{} answered {} in {} ms", base_url, response.status(), latency);
+                    CheckResult::pass(name, format!("reachable ({} ms)", latency))
+                }
+            }
+            Err(e) => CheckResult::fail(
+                name,
+                format!("{} unreachable: {}", base_url, e),
+                "Check network connectivity, DNS and firewall rules",
+            ),
+        }
+    }
+
+    /// Compare local time against the CLOB server clock
+    async fn check_clock_skew(&self, http: &reqwest::Client, host: &str) -> CheckResult {
+        const NAME: &str = "Clock skew";
+        if self.args.offline {
+            return CheckResult::skip(NAME, "skipped (--offline)");
+        }
+
+        let url = format!("{}/time", host.trim_end_matches('/'));
+        let server_secs = match http.get(&url).send().await {
+            Ok(response) => match response.text().await {
+                Ok(body) => body.trim().trim_matches('"').parse::<i64>().ok(),
+                Err(_) => None,
+            },
+            Err(_) => None,
+        };
+
+        let Some(server_secs) = server_secs else {
+            return CheckResult::warn(
+                NAME,
+                format!("could not read server time from {}", url),
+                "Ensure the CLOB API is reachable",
+            );
+        };
+
+        let local_secs = chrono::Utc::now().timestamp();
+        let skew = (local_secs - server_secs).unsigned_abs();
+        let detail = format!("local clock differs from server by {}s", skew);
+
+        if skew > self.args.max_clock_skew_secs * 5 {
+            CheckResult::fail(NAME, detail, "Enable NTP time sync; signed requests will be rejected")
+        } else if skew > self.args.max_clock_skew_secs {
+            CheckResult::warn(NAME, detail, "Enable NTP time sync to avoid signature expiry errors")
+        } else {
+            CheckResult::pass(NAME, detail)
+        }
+    }
+
+    /// Inspect a RocksDB directory without taking its lock
+    fn check_rocksdb(&self, name: &'static str, path: &Path) -> CheckResult {
+        if !path.exists() {
+            return CheckResult::skip(name, format!("{} not created yet", path.display()));
+        }
+
+        match rocksdb::DB::list_cf(&rocksdb::Options::default(), path) {
+            Ok(column_families) => CheckResult::pass(
+                name,
+                format!(
+                    "{} column families, {}",
+                    column_families.len(),
+                    format_bytes(Self::directory_size(path))
+                ),
+            ),
+            Err(e) => CheckResult::fail(
+                name,
+                format!("{} is unreadable: {}", path.display(), e),
+                format!("Restore from backup or rebuild (e.g. 'polybot index --rocksdb --clear') at {}", path.display()),
+            ),
+        }
+    }
+
+    /// Inspect the store of the Gamma backend `gamma.yaml` selects
+    fn check_gamma_db(&self, data_paths: &DataPaths) -> CheckResult {
+        const NAME: &str = "Gamma DB";
+        let root = data_paths.root();
+        match GammaStoreConfig::load(root) {
+            Ok(config) => self.check_rocksdb(NAME, &config.backend.db_path(root)),
+            Err(e) => CheckResult::fail(
+                NAME,
+                format!("{:#}", e),
+                "Fix the backend in gamma.yaml or delete it to use the default",
+            ),
+        }
+    }
+
+    /// Check that the fast search index exists and is recent
+    fn check_search_index(&self, data_paths: &DataPaths) -> CheckResult {
+        const NAME: &str = "Search index";
+        let index_file = get_index_path(data_paths.root()).join(FAST_SEARCH_INDEX_FILE);
+
+        let modified = match std::fs::metadata(&index_file).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => {
+                return CheckResult::warn(
+                    NAME,
+                    format!("{} not found", index_file.display()),
+                    "Build it with 'polybot gamma search <query>' after importing markets",
+                )
+            }
+        };
+
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        let age_hours = age.as_secs() / 3600;
+
+        if age_hours > self.args.index_max_age_hours {
+            CheckResult::warn(
+                NAME,
+                format!("index is {}h old", age_hours),
+                "Rebuild the search index after refreshing the Gamma database",
+            )
+        } else {
+            CheckResult::pass(NAME, format!("index updated {}h ago", age_hours))
+        }
+    }
+
+    /// Open and close a WebSocket connection to the market feed
+    async fn check_streaming(&self) -> CheckResult {
+        const NAME: &str = "Streaming";
+        if self.args.offline {
+            return CheckResult::skip(NAME, "skipped (--offline)");
+        }
+
+        let url = WsConfig::default().market_url;
+        let timeout = Duration::from_secs(self.args.timeout_secs);
+        match tokio::time::timeout(timeout, tokio_tungstenite::connect_async(url.as_str())).await {
+            Ok(Ok((mut stream, _response))) => {
+                let _ = stream.close(None).await;
+                CheckResult::pass(NAME, format!("connected to {}", url))
+            }
+            Ok(Err(e)) => CheckResult::fail(
+                NAME,
+                format!("WebSocket handshake failed: {}", e),
+                "Check that outbound WSS (port 443) is allowed",
+            ),
+            Err(_) => CheckResult::fail(
+                NAME,
+                format!("WebSocket connect timed out after {}s", self.args.timeout_secs),
+                "Check network connectivity or raise --timeout-secs",
+            ),
+        }
+    }
+
+    fn directory_size(path: &Path) -> u64 {
+        let mut total = 0;
+        let mut stack: Vec<PathBuf> = vec![path.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => stack.push(entry.path()),
+                    Ok(metadata) => total += metadata.len(),
+                    Err(_) => {}
+                }
+            }
+        }
+        total
+    }
+}
@@ -41,6 +41,17 @@ pub struct EnrichArgs {
     /// Continue from a specific market index (for resuming)
     #[arg(long)]
     pub start_from: Option<usize>,
+
+    /// Only enrich markets with a token whose last enrichment is older than --max-age
+    #[arg(long)]
+    pub stale_only: bool,
+
+    /// Age after which enrichment data is stale, e.g. 30m, 6h, 2d
+    #[arg(long, default_value = "6h", value_parser = crate::cli::parse_duration)]
+    pub max_age: std::time::Duration,
+    /// Memory limit before enriched markets spill to disk (e.g. 4G; default: 75% of available memory)
+    #[arg(long, value_parser = crate::core::memory::parse_byte_size)]
+    pub memory_limit: Option<u64>,
 }
 
 pub struct EnrichCommand {
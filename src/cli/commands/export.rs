@@ -0,0 +1,559 @@
+//! Export command: consolidate market data into formats other tools can open
+//!
+//! `export sqlite` writes the typed RocksDB store and the datasets' market and
+//! enrichment files into one normalized SQLite database, so the data can be
+//! queried from any SQL tool without going through polybot.
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Subcommand};
+use rusqlite::{params, Connection, Transaction};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::data_paths::DataPaths;
+use crate::markets::clob::enrich::{EnrichedMarket, Market as DatasetMarket};
+use crate::typed_store::{
+    MarketCf, RocksDbMarket, Token, TokenCf, TypedDbContext, ALL_COLUMN_FAMILIES,
+};
+
+/// Tables of the exported database; see the `export` section of the commands README
+const SCHEMA: &str = "
+CREATE TABLE markets (
+    condition_id TEXT PRIMARY KEY,
+    market_id TEXT,
+    question TEXT NOT NULL,
+    description TEXT,
+    category TEXT,
+    slug TEXT,
+    active INTEGER,
+    closed INTEGER,
+    archived INTEGER,
+    accepting_orders INTEGER,
+    minimum_order_size REAL,
+    minimum_tick_size REAL,
+    end_date TEXT,
+    created_at TEXT,
+    updated_at TEXT,
+    volume REAL,
+    volume_24hr REAL,
+    liquidity REAL,
+    fee_rate REAL,
+    source TEXT NOT NULL
+);
+CREATE TABLE market_tags (
+    condition_id TEXT NOT NULL REFERENCES markets(condition_id),
+    tag TEXT NOT NULL,
+    PRIMARY KEY (condition_id, tag)
+);
+CREATE TABLE tokens (
+    token_id TEXT PRIMARY KEY,
+    condition_id TEXT REFERENCES markets(condition_id),
+    outcome TEXT,
+    winner INTEGER,
+    volume REAL,
+    volume_24hr REAL,
+    supply REAL,
+    market_cap REAL
+);
+CREATE TABLE prices (
+    token_id TEXT NOT NULL REFERENCES tokens(token_id),
+    price REAL NOT NULL,
+    observed_at TEXT,
+    source TEXT NOT NULL,
+    PRIMARY KEY (token_id, source)
+);
+CREATE TABLE enrichment (
+    condition_id TEXT NOT NULL REFERENCES markets(condition_id),
+    dataset TEXT NOT NULL,
+    enriched_at TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    error TEXT,
+    best_bid REAL,
+    best_ask REAL,
+    mid_price REAL,
+    spread REAL,
+    spread_percentage REAL,
+    bid_levels INTEGER,
+    ask_levels INTEGER,
+    total_bid_size REAL,
+    total_ask_size REAL,
+    bid_orders_count INTEGER,
+    ask_orders_count INTEGER,
+    liquidity_ratio REAL,
+    market_depth_score REAL,
+    volume_24hr REAL,
+    volume_total REAL,
+    trade_count_24hr INTEGER,
+    last_trade_time TEXT,
+    PRIMARY KEY (condition_id, dataset)
+);
+CREATE INDEX tokens_by_condition ON tokens(condition_id);
+CREATE INDEX enrichment_by_dataset ON enrichment(dataset);
+";
+
+/// Source name of rows read from the typed RocksDB store
+const STORE_SOURCE: &str = "store";
+
+#[derive(Args, Clone)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub command: ExportSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ExportSubcommand {
+    /// Write markets, tokens, prices and enrichment metrics to a SQLite database
+    Sqlite {
+        /// Database file to create
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Replace the database if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Typed RocksDB store to read (default: <data_dir>/database/rocksdb)
+        #[arg(long)]
+        db_path: Option<PathBuf>,
+
+        /// Datasets to include, by name or path (repeatable; default: every dataset
+        /// with a markets.json or enriched_markets.json)
+        #[arg(long = "dataset")]
+        datasets: Vec<String>,
+
+        /// Leave out the typed RocksDB store
+        #[arg(long)]
+        no_store: bool,
+    },
+}
+
+pub struct ExportCommand {
+    args: ExportArgs,
+}
+
+impl ExportCommand {
+    pub fn new(args: ExportArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            ExportSubcommand::Sqlite {
+                out,
+                force,
+                db_path,
+                datasets,
+                no_store,
+            } => {
+                if out.exists() {
+                    if !force {
+                        return Err(anyhow!(
+                            "{} already exists (use --force to replace it)",
+                            out.display()
+                        ));
+                    }
+                    fs::remove_file(out)
+                        .with_context(|| format!("Failed to remove {}", out.display()))?;
+                }
+
+                let explicit_store = db_path.is_some();
+                let store = if *no_store {
+                    None
+                } else {
+                    let db_path = db_path
+                        .clone()
+                        .unwrap_or_else(|| data_paths.root().join("database").join("rocksdb"));
+                    if db_path.exists() {
+                        Some(db_path)
+                    } else if explicit_store {
+                        return Err(anyhow!("No RocksDB store at {}", db_path.display()));
+                    } else {
+                        warn!(
+                            "No RocksDB store at {}, exporting datasets only",
+                            db_path.display()
+                        );
+                        None
+                    }
+                };
+                let datasets = resolve_datasets(&data_paths, datasets)?;
+
+                let summary = export_sqlite(out, store.as_deref(), &datasets)?;
+                println!("✅ Exported to {}", out.display());
+                println!("   • Markets: {}", summary.markets);
+                println!("   • Tokens: {}", summary.tokens);
+                println!("   • Prices: {}", summary.prices);
+                println!("   • Enrichment rows: {}", summary.enrichment);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Rows written by [`export_sqlite`]
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportSummary {
+    pub markets: usize,
+    pub tokens: usize,
+    pub prices: usize,
+    pub enrichment: usize,
+}
+
+/// Dataset directories to export, named by `requested` or found in the datasets directory
+fn resolve_datasets(data_paths: &DataPaths, requested: &[String]) -> Result<Vec<PathBuf>> {
+    if !requested.is_empty() {
+        return requested
+            .iter()
+            .map(|name| {
+                let path = if Path::new(name).is_absolute() || name.starts_with("./") {
+                    PathBuf::from(name)
+                } else {
+                    data_paths.datasets().join(name)
+                };
+                if path.is_dir() {
+                    Ok(path)
+                } else {
+                    Err(anyhow!("Dataset not found: {}", path.display()))
+                }
+            })
+            .collect();
+    }
+
+    let Ok(entries) = fs::read_dir(data_paths.datasets()) else {
+        return Ok(Vec::new());
+    };
+    let mut datasets: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.join("markets.json").is_file() || path.join("enriched_markets.json").is_file()
+        })
+        .collect();
+    datasets.sort();
+    Ok(datasets)
+}
+
+/// Create the database at `out` from the store at `store` and the `datasets`
+///
+/// Markets and tokens are keyed by condition and token id. The store is
+/// written first and wins over datasets describing the same market; each
+/// source keeps its own row in `prices` and each dataset in `enrichment`.
+pub fn export_sqlite(
+    out: &Path,
+    store: Option<&Path>,
+    datasets: &[PathBuf],
+) -> Result<ExportSummary> {
+    let mut conn =
+        Connection::open(out).with_context(|| format!("Failed to create {}", out.display()))?;
+    conn.execute_batch(SCHEMA)?;
+
+    let tx = conn.transaction()?;
+    if let Some(store) = store {
+        info!("📤 Exporting RocksDB store {}", store.display());
+        let ctx = TypedDbContext::open(store, ALL_COLUMN_FAMILIES.to_vec())?;
+        let markets: Vec<RocksDbMarket> = ctx
+            .scan::<MarketCf>()?
+            .into_iter()
+            .map(|(_, m)| m)
+            .collect();
+        let tokens: Vec<Token> = ctx.scan::<TokenCf>()?.into_iter().map(|(_, t)| t).collect();
+        write_store(&tx, &markets, &tokens)?;
+    }
+    for dataset in datasets {
+        info!("📤 Exporting dataset {}", dataset.display());
+        write_dataset(&tx, dataset)?;
+    }
+    tx.commit()?;
+
+    let count = |table: &str| -> Result<usize> {
+        let rows: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })?;
+        Ok(rows as usize)
+    };
+    Ok(ExportSummary {
+        markets: count("markets")?,
+        tokens: count("tokens")?,
+        prices: count("prices")?,
+        enrichment: count("enrichment")?,
+    })
+}
+
+fn write_store(tx: &Transaction, markets: &[RocksDbMarket], tokens: &[Token]) -> Result<()> {
+    let mut insert_market = tx.prepare(
+        "INSERT OR REPLACE INTO markets VALUES
+         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+    )?;
+    let mut insert_tag = tx.prepare("INSERT OR IGNORE INTO market_tags VALUES (?1, ?2)")?;
+    for market in markets {
+        let Some(condition_id) = &market.condition_id else {
+            continue;
+        };
+        insert_market.execute(params![
+            condition_id,
+            market.id,
+            market.question,
+            market.description,
+            market.category,
+            market.market_slug,
+            market.active,
+            market.closed,
+            market.archived,
+            market.accepting_orders,
+            market.minimum_order_size,
+            market.minimum_tick_size,
+            market.end_date_iso,
+            market.created_at,
+            market.updated_at,
+            market.volume,
+            market.volume_24hr,
+            market.liquidity,
+            market.fee_rate,
+            STORE_SOURCE,
+        ])?;
+        for tag in market.tags.iter().flatten() {
+            insert_tag.execute(params![condition_id, tag])?;
+        }
+    }
+
+    let mut insert_token =
+        tx.prepare("INSERT OR REPLACE INTO tokens VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+    let mut insert_price = tx.prepare("INSERT OR REPLACE INTO prices VALUES (?1, ?2, ?3, ?4)")?;
+    for token in tokens {
+        insert_token.execute(params![
+            token.id,
+            token.condition_id,
+            token.outcome,
+            token.winner,
+            token.volume,
+            token.volume_24hr,
+            token.supply,
+            token.market_cap,
+        ])?;
+        insert_price.execute(params![
+            token.id,
+            token.current_price,
+            token.last_updated,
+            STORE_SOURCE
+        ])?;
+    }
+    Ok(())
+}
+
+fn write_dataset(tx: &Transaction, dir: &Path) -> Result<()> {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    let source = format!("dataset:{}", name);
+
+    let markets_file = dir.join("markets.json");
+    if markets_file.is_file() {
+        match read_json::<Vec<DatasetMarket>>(&markets_file) {
+            Ok(markets) => {
+                for market in &markets {
+                    write_dataset_market(tx, market, &source, None)?;
+                }
+            }
+            Err(e) => warn!("Skipping {}: {}", markets_file.display(), e),
+        }
+    }
+
+    let enriched_file = dir.join("enriched_markets.json");
+    if enriched_file.is_file() {
+        match read_json::<Vec<EnrichedMarket>>(&enriched_file) {
+            Ok(markets) => {
+                for enriched in &markets {
+                    let observed_at = enriched.enriched_at.to_rfc3339();
+                    write_dataset_market(tx, &enriched.market, &source, Some(&observed_at))?;
+                    if let Some(condition_id) = &enriched.market.condition_id {
+                        write_enrichment(tx, condition_id, &name, enriched)?;
+                    }
+                }
+            }
+            Err(e) => warn!("Skipping {}: {}", enriched_file.display(), e),
+        }
+    }
+    Ok(())
+}
+
+/// Add a dataset market and its tokens unless an earlier source already has them
+fn write_dataset_market(
+    tx: &Transaction,
+    market: &DatasetMarket,
+    source: &str,
+    observed_at: Option<&str>,
+) -> Result<()> {
+    let Some(condition_id) = &market.condition_id else {
+        return Ok(());
+    };
+    tx.prepare_cached(
+        "INSERT OR IGNORE INTO markets
+         (condition_id, market_id, question, description, category, active, closed, archived,
+          accepting_orders, minimum_order_size, minimum_tick_size, end_date, created_at,
+          updated_at, source)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+    )?
+    .execute(params![
+        condition_id,
+        market.id,
+        market.question.as_deref().unwrap_or_default(),
+        market.description,
+        market.category,
+        market.active,
+        market.closed,
+        market.archived,
+        market.accepting_orders,
+        market.minimum_order_size,
+        market.minimum_tick_size,
+        market.end_date_iso,
+        market.created_at,
+        market.updated_at,
+        source,
+    ])?;
+    for tag in market.tags.iter().flatten() {
+        tx.prepare_cached("INSERT OR IGNORE INTO market_tags VALUES (?1, ?2)")?
+            .execute(params![condition_id, tag])?;
+    }
+
+    for token in &market.tokens {
+        tx.prepare_cached(
+            "INSERT OR IGNORE INTO tokens (token_id, condition_id, outcome, winner)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?
+        .execute(params![
+            token.token_id,
+            condition_id,
+            token.outcome,
+            token.winner
+        ])?;
+        if let Some(price) = token.price {
+            tx.prepare_cached("INSERT OR REPLACE INTO prices VALUES (?1, ?2, ?3, ?4)")?
+                .execute(params![token.token_id, price, observed_at, source])?;
+        }
+    }
+    Ok(())
+}
+
+fn write_enrichment(
+    tx: &Transaction,
+    condition_id: &str,
+    dataset: &str,
+    enriched: &EnrichedMarket,
+) -> Result<()> {
+    let enrichment = &enriched.enrichment;
+    let orderbook = enrichment.orderbook.as_ref();
+    let liquidity = enrichment.liquidity.as_ref();
+    let volume = enrichment.volume.as_ref();
+    tx.prepare_cached(
+        "INSERT OR REPLACE INTO enrichment VALUES
+         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19,
+          ?20, ?21, ?22)",
+    )?
+    .execute(params![
+        condition_id,
+        dataset,
+        enriched.enriched_at.to_rfc3339(),
+        enrichment.status.success,
+        enrichment.status.error,
+        orderbook.map(|o| o.best_bid),
+        orderbook.map(|o| o.best_ask),
+        orderbook.map(|o| o.mid_price),
+        orderbook.map(|o| o.spread),
+        orderbook.map(|o| o.spread_percentage),
+        orderbook.map(|o| o.bid_levels as i64),
+        orderbook.map(|o| o.ask_levels as i64),
+        liquidity.map(|l| l.total_bid_size),
+        liquidity.map(|l| l.total_ask_size),
+        liquidity.map(|l| l.bid_orders_count as i64),
+        liquidity.map(|l| l.ask_orders_count as i64),
+        liquidity.map(|l| l.liquidity_ratio),
+        liquidity.map(|l| l.market_depth_score),
+        volume.and_then(|v| v.volume_24hr),
+        volume.and_then(|v| v.volume_total),
+        volume.and_then(|v| v.trade_count_24hr).map(|n| n as i64),
+        volume.and_then(|v| v.last_trade_time.clone()),
+    ])?;
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn datasets_export_to_normalized_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let dataset = dir.path().join("enriched");
+        fs::create_dir_all(&dataset).unwrap();
+
+        let market = json!({
+            "condition_id": "0xabc",
+            "question": "Will it rain?",
+            "tags": ["weather"],
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes", "price": 0.4},
+                {"token_id": "2", "outcome": "No", "price": 0.6}
+            ],
+            "active": true,
+            "closed": false
+        });
+        let mut enriched = market.clone();
+        enriched["enriched_at"] = json!("2025-01-01T00:00:00Z");
+        enriched["enrichment"] = json!({
+            "orderbook": {
+                "best_bid": 0.39, "best_ask": 0.41, "mid_price": 0.4, "spread": 0.02,
+                "spread_percentage": 5.0, "bid_levels": 3, "ask_levels": 4
+            },
+            "status": {"success": true, "has_orderbook": true, "processing_time_ms": 12}
+        });
+        fs::write(dataset.join("markets.json"), json!([market]).to_string()).unwrap();
+        fs::write(
+            dataset.join("enriched_markets.json"),
+            json!([enriched]).to_string(),
+        )
+        .unwrap();
+
+        let out = dir.path().join("markets.db");
+        let summary = export_sqlite(&out, None, &[dataset]).unwrap();
+        assert_eq!(
+            summary,
+            ExportSummary {
+                markets: 1,
+                tokens: 2,
+                prices: 2,
+                enrichment: 1,
+            }
+        );
+
+        let conn = Connection::open(&out).unwrap();
+        let (question, tag): (String, String) = conn
+            .query_row(
+                "SELECT question, tag FROM markets JOIN market_tags USING (condition_id)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (question.as_str(), tag.as_str()),
+            ("Will it rain?", "weather")
+        );
+        let (spread, observed_at): (f64, String) = conn
+            .query_row(
+                "SELECT e.spread, p.observed_at FROM enrichment e
+                 JOIN tokens t USING (condition_id)
+                 JOIN prices p USING (token_id)
+                 WHERE t.outcome = 'Yes'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(spread, 0.02);
+        assert!(observed_at.starts_with("2025-01-01"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Failover command: leader, standbys and handover of daemon failover groups
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::{Args, Subcommand};
+
+use crate::core::failover::{self, GroupStatus};
+use crate::data_paths::DataPaths;
+use crate::typed_store::freshness::format_age;
+
+#[derive(Args, Clone)]
+pub struct FailoverArgs {
+    #[command(subcommand)]
+    pub action: Option<FailoverAction>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum FailoverAction {
+    /// Show the current leader of each group (the default)
+    Status {
+        /// Only this group
+        #[arg(long)]
+        group: Option<String>,
+    },
+}
+
+pub struct FailoverCommand {
+    args: FailoverArgs,
+}
+
+impl FailoverCommand {
+    pub fn new(args: FailoverArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let group = match &self.args.action {
+            None => None,
+            Some(FailoverAction::Status { group }) => group.clone(),
+        };
+        let groups = match group {
+            Some(group) => vec![group],
+            None => failover::groups(&data_paths),
+        };
+        if groups.is_empty() {
+            println!("No failover groups yet (start daemons with --failover)");
+            return Ok(());
+        }
+        for (index, group) in groups.iter().enumerate() {
+            if index > 0 {
+                println!();
+            }
+            print_status(&failover::status(&data_paths, group)?);
+        }
+        Ok(())
+    }
+}
+
+fn print_status(status: &GroupStatus) {
+    let now = Utc::now();
+    println!("🔁 Failover group '{}'", status.group);
+    match &status.lease {
+        None => println!("   Leader:   none"),
+        Some(lease) => {
+            let health = if lease.released {
+                "released, a standby takes over".to_string()
+            } else if lease.is_live(now) {
+                format!(
+                    "healthy, expires in {}",
+                    format_age(lease.expires_at() - now)
+                )
+            } else {
+                format!(
+                    "EXPIRED {} ago, a standby takes over",
+                    format_age(now - lease.expires_at())
+                )
+            };
+            println!(
+                "   Leader:   {} (term {}, leading for {})",
+                lease.holder.describe(),
+                lease.term,
+                format_age(now - lease.acquired_at)
+            );
+            println!(
+                "   Lease:    renewed {} ago, {}",
+                format_age(now - lease.renewed_at),
+                health
+            );
+        }
+    }
+
+    if status.standbys.is_empty() {
+        println!("   Standbys: none");
+    }
+    for (index, standby) in status.standbys.iter().enumerate() {
+        println!(
+            "   {} {} (seen {} ago)",
+            if index == 0 { "Standbys:" } else { "         " },
+            standby.instance.describe(),
+            format_age(now - standby.seen_at)
+        );
+    }
+
+    if let Some(handover) = &status.handover {
+        let running = handover
+            .state
+            .strategies
+            .iter()
+            .filter(|strategy| strategy.running)
+            .count();
+        println!(
+            "   Handover: {} strategies ({} running), {} open orders owned by {} (term {}, {} {} ago)",
+            handover.state.strategies.len(),
+            running,
+            handover.state.open_orders.len(),
+            handover.from.id,
+            handover.term,
+            if handover.graceful { "released" } else { "written" },
+            format_age(now - handover.written_at)
+        );
+    }
+}
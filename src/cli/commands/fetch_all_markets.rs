@@ -1,5 +1,6 @@
+use crate::core::progress::ctrl_c_token;
 use crate::data_paths::DataPaths;
-use crate::markets::datasets::save_command_metadata;
+use crate::markets::datasets::report_dataset;
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use clap::Args;
@@ -121,6 +122,10 @@ impl FetchAllMarketsCommand {
 
         info!("📁 Dataset directory: {}", output_dir.display());
 
+        // Ctrl-C saves the chunk in progress and the fetch state, so rerunning
+        // the command resumes; the dataset only counts as cached once complete
+        let cancel = ctrl_c_token();
+
         // Execute the appropriate fetch method
         if self.args.use_gamma {
             info!("🌐 Using Gamma API for market data...");
@@ -129,6 +134,7 @@ impl FetchAllMarketsCommand {
                 verbose,
                 self.args.clear_state,
                 self.args.chunk_size_mb,
+                cancel,
             )
             .await?;
         } else {
@@ -139,38 +145,11 @@ impl FetchAllMarketsCommand {
                 verbose,
                 self.args.clear_state,
                 self.args.chunk_size_mb,
+                cancel,
             )
             .await?;
         }
 
-        // Save command metadata
-        let mut command_args = vec!["--dataset-name".to_string(), dataset_name.clone()];
-
-        if verbose {
-            command_args.push("--verbose".to_string());
-        }
-
-        if self.args.clear_state {
-            command_args.push("--clear-state".to_string());
-        }
-
-        if self.args.use_gamma {
-            command_args.push("--use-gamma".to_string());
-        }
-
-        command_args.extend_from_slice(&[
-            "--chunk-size-mb".to_string(),
-            self.args.chunk_size_mb.to_string(),
-            "--cache-resolution".to_string(),
-            self.args.cache_resolution.clone(),
-            "--cache-duration".to_string(),
-            self.args.cache_duration.to_string(),
-        ]);
-
-        if self.args.force_refresh {
-            command_args.push("--force-refresh".to_string());
-        }
-
         // Enhanced metadata with cache information
         let mut additional_info = HashMap::new();
         additional_info.insert("dataset_name".to_string(), serde_json::json!(dataset_name));
@@ -195,12 +174,8 @@ impl FetchAllMarketsCommand {
             serde_json::json!(self.args.force_refresh),
         );
 
-        save_command_metadata(
-            &output_dir,
-            "fetch-all-markets",
-            &command_args,
-            Some(additional_info),
-        )?;
+        // The CLI middleware writes dataset.yaml once the command succeeds
+        report_dataset(&output_dir, additional_info);
 
         info!("✅ Successfully created dataset: {}", dataset_name);
         Ok(())
@@ -0,0 +1,206 @@
+//! Flatten command: cancel resting orders and close every position for emergency de-risking
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use clap::Args;
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::io::{BufRead, IsTerminal, Write};
+use tracing::warn;
+
+use crate::client::PolybotClient;
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::flatten::prepare_flatten;
+use crate::core::portfolio::storage::{LotEntry, PortfolioStorage};
+use crate::core::portfolio::OrderSide;
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct FlattenArgs {
+    /// Flatten every market (required unless --market is given)
+    #[arg(long, required_unless_present = "market")]
+    pub all: bool,
+
+    /// Only flatten this market (condition ID)
+    #[arg(long)]
+    pub market: Option<String>,
+
+    /// Furthest each close may trade from the touch, e.g. 2% (unfilled shares rest at that limit)
+    #[arg(long, default_value = "2%", value_parser = parse_slippage)]
+    pub max_slippage: Decimal,
+
+    /// Skip the typed confirmation after the preview
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Confirm orders above the large order notional threshold without prompting
+    #[arg(long)]
+    pub yes_large: bool,
+}
+
+/// `2%` or `2` → 0.02
+fn parse_slippage(s: &str) -> Result<Decimal, String> {
+    let value: Decimal = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("'{}' is not a percentage", s))?;
+    Ok(value / Decimal::ONE_HUNDRED)
+}
+
+pub struct FlattenCommand {
+    args: FlattenArgs,
+}
+
+impl FlattenCommand {
+    pub fn new(args: FlattenArgs) -> Self {
+        Self { args }
+    }
+
+    fn side(side: OrderSide) -> Side {
+        match side {
+            OrderSide::Buy => Side::Buy,
+            OrderSide::Sell => Side::Sell,
+        }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, using cached positions: {}", e);
+        }
+        let state = handlers.get_portfolio_state().await?;
+
+        // Books are public, so no credentials are needed to price the closes
+        let books = ClobClient::new(host);
+        let plan = prepare_flatten(
+            &books,
+            &state.positions,
+            &state.active_orders,
+            self.args.market.as_deref(),
+            self.args.max_slippage,
+        )
+        .await?;
+
+        let scope = self.args.market.as_deref().unwrap_or("all markets");
+        println!(
+            "🧯 Flatten {} (max slippage {}%)",
+            scope,
+            (self.args.max_slippage * Decimal::ONE_HUNDRED).normalize()
+        );
+        println!("{}", plan.summary());
+        if plan.is_empty() {
+            println!("Nothing to flatten.");
+            return Ok(());
+        }
+
+        if !self.args.yes {
+            if !std::io::stdin().is_terminal() {
+                println!("\nDry run. Use --yes to cancel and close.");
+                return Ok(());
+            }
+            print!("\nType 'flatten' to cancel these orders and close these positions: ");
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)?;
+            if answer.trim() != "flatten" {
+                println!("Aborted, nothing was cancelled or placed.");
+                return Ok(());
+            }
+        }
+
+        // Every large order is confirmed up front so a declined one doesn't leave a half-flattened book
+        let risk_config = RiskConfig::load(&data_paths)?;
+        let journal = RiskJournal::new(&data_paths);
+        for order in &plan.orders {
+            let side = Self::side(order.side);
+            let token_id = &order.position.token_id;
+            if let Some(large) =
+                risk_config
+                    .large_orders
+                    .check(token_id, side, order.limit, order.size)
+            {
+                if !confirm_large_order_cli(&large, self.args.yes_large, &journal)? {
+                    warn!("❌ Large order not confirmed, nothing was cancelled or placed.");
+                    return Ok(());
+                }
+            }
+        }
+
+        // Cancels and closes go through the order manager, like `polybot buy`/`sell`
+        let trading = PolybotClient::connect_to(host, data_paths.clone()).await?;
+        let route = trading.route();
+        let mut failures = 0;
+        for order in &plan.cancels {
+            match route.cancel(&order.order_id).await {
+                Ok(()) => println!("🗑️  Cancelled {}", order.order_id),
+                Err(e) => {
+                    warn!("Failed to cancel {}: {}", order.order_id, e);
+                    failures += 1;
+                }
+            }
+        }
+
+        let storage = PortfolioStorage::new(data_paths.root(), handlers.get_address());
+        for order in &plan.orders {
+            let position = &order.position;
+            let placed = route
+                .place(Self::side(order.side), &position.token_id, order.limit, order.size)
+                .await;
+            let order_id = match placed {
+                Ok(order_id) => order_id,
+                Err(e) => {
+                    warn!(
+                        "Failed to close {} {}: {}",
+                        position.outcome, position.token_id, e
+                    );
+                    failures += 1;
+                    continue;
+                }
+            };
+            println!(
+                "✅ {:?} {} {} @ {} — order {}",
+                order.side, order.size, position.outcome, order.limit, order_id
+            );
+
+            let entry = LotEntry {
+                order_id,
+                market_id: position.market_id.clone(),
+                token_id: position.token_id.clone(),
+                side: order.side,
+                price: order.limit,
+                size: order.size,
+                position_size_before: position.size,
+                average_price: position.average_price,
+                created_at: Utc::now(),
+                fills: Vec::new(),
+            };
+            if let Err(e) = storage.record_lot_entry(&entry).await {
+                warn!(
+                    "Failed to record the close of {} in the lot history: {}",
+                    position.token_id, e
+                );
+            }
+        }
+
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Failed to refresh portfolio data after flattening: {}", e);
+        }
+
+        if failures > 0 {
+            bail!(
+                "{} cancels or closes failed; check 'polybot orders' and re-run",
+                failures
+            );
+        }
+        if !plan.skipped.is_empty() {
+            println!(
+                "⚠️  {} positions were skipped and are still open",
+                plan.skipped.len()
+            );
+        }
+        Ok(())
+    }
+}
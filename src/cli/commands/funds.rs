@@ -0,0 +1,404 @@
+//! Funds command: deposit addresses, pending bridge deposits, wallet USDC balance, gas costs
+//! and stuck transaction handling
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, TimeZone, Utc};
+use clap::{Args, Subcommand};
+use rust_decimal::Decimal;
+use tokio::sync::oneshot;
+
+use crate::address_book::{service, AddressBookCommand};
+use crate::auth;
+use crate::core::funds::{is_evm_address, BridgeDeposit, FundsClient, DEFAULT_POLYGON_RPC_URL};
+use crate::core::onchain::usdc::EXCHANGE_SPENDERS;
+use crate::core::onchain::{OnChainClient, TxRequest};
+use crate::data_paths::DataPaths;
+use crate::ethereum_utils::{wei_to_gwei, GasOracle, OnChainAction, ON_CHAIN_ACTIONS};
+use crate::number_format;
+
+#[derive(Args, Clone)]
+pub struct FundsArgs {
+    #[command(subcommand)]
+    pub command: FundsSubcommand,
+
+    /// Wallet address or address book label (defaults to the configured wallet)
+    #[arg(long, global = true)]
+    pub address: Option<String>,
+
+    /// Polygon JSON-RPC endpoint used for balance and gas lookups
+    #[arg(long, global = true)]
+    pub rpc_url: Option<String>,
+
+    /// Fail if the current max fee per gas is above this many gwei
+    #[arg(long, global = true)]
+    pub max_fee: Option<Decimal>,
+}
+
+/// Pending transactions older than this are flagged as stuck
+const STUCK_AFTER_MINUTES: i64 = 5;
+
+#[derive(Subcommand, Clone)]
+pub enum FundsSubcommand {
+    /// Show the wallet address and its bridge deposit addresses
+    Address,
+    /// List deposits the bridge is still processing
+    Deposits {
+        /// Include completed and failed deposits
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show the on-chain USDC.e balance and pending deposits
+    Balance,
+    /// Show current gas fees and the worst case cost of on-chain actions
+    Gas,
+    /// Let the CLOB exchanges spend the signing wallet's USDC.e
+    Approve {
+        /// Allowance in USDC (default: unlimited)
+        #[arg(long)]
+        amount: Option<Decimal>,
+        /// Send the approvals (without it only the cost is shown)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Move USDC.e from the signing wallet into a trading wallet, e.g. a proxy wallet
+    Deposit {
+        /// Trading wallet address or address book label
+        to: String,
+        /// Amount in USDC
+        amount: Decimal,
+        /// Send the transfer (without it only the cost is shown)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Send USDC.e from the signing wallet to another address
+    Withdraw {
+        /// Destination address or address book label
+        to: String,
+        /// Amount in USDC
+        amount: Decimal,
+        /// Send the transfer (without it only the cost is shown)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// List the wallet's tracked transactions and flag stuck ones
+    Txs,
+    /// Resubmit a stuck transaction with higher fees
+    SpeedUp {
+        nonce: u64,
+        /// Send the replacement (without it only the new fees are shown)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Cancel a stuck transaction with a zero value self-transfer at its nonce
+    CancelTx {
+        nonce: u64,
+        /// Send the cancellation (without it only the new fees are shown)
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+impl FundsSubcommand {
+    /// Sends a transaction (refused on read-only profiles)
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Self::Approve { yes: true, .. }
+                | Self::Deposit { yes: true, .. }
+                | Self::Withdraw { yes: true, .. }
+                | Self::SpeedUp { yes: true, .. }
+                | Self::CancelTx { yes: true, .. }
+        )
+    }
+}
+
+/// Wallet a funds subcommand reports on
+struct WalletTarget {
+    address: String,
+    /// Address book label, when the wallet was picked by label
+    label: Option<String>,
+}
+
+impl WalletTarget {
+    fn display_name(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} ({})", label, self.address),
+            None => self.address.clone(),
+        }
+    }
+}
+
+pub struct FundsCommand {
+    args: FundsArgs,
+}
+
+impl FundsCommand {
+    pub fn new(args: FundsArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            FundsSubcommand::Address => self.show_addresses(&data_paths).await,
+            FundsSubcommand::Deposits { all } => self.show_deposits(&data_paths, *all).await,
+            FundsSubcommand::Balance => self.show_balance(&data_paths).await,
+            FundsSubcommand::Gas => self.show_gas().await,
+            FundsSubcommand::Approve { amount, yes } => self.approve(&data_paths, *amount, *yes).await,
+            FundsSubcommand::Deposit { to, amount, yes } => {
+                self.transfer(&data_paths, OnChainAction::DEPOSIT, to, *amount, *yes).await
+            }
+            FundsSubcommand::Withdraw { to, amount, yes } => {
+                self.transfer(&data_paths, OnChainAction::WITHDRAW, to, *amount, *yes).await
+            }
+            FundsSubcommand::Txs => self.list_transactions(&data_paths).await,
+            FundsSubcommand::SpeedUp { nonce, yes } => {
+                self.replace_transaction(&data_paths, *nonce, *yes, false).await
+            }
+            FundsSubcommand::CancelTx { nonce, yes } => {
+                self.replace_transaction(&data_paths, *nonce, *yes, true).await
+            }
+        }
+    }
+
+    async fn show_addresses(&self, data_paths: &DataPaths) -> Result<()> {
+        let target = self.resolve_wallet(data_paths).await?;
+        let client = FundsClient::new(self.args.rpc_url.clone())?;
+        println!("👛 Wallet: {}", target.display_name());
+        println!("   Send USDC.e on Polygon here directly, or use a bridge address below.\n");
+        let addresses = client.deposit_addresses(&target.address).await?;
+        println!("🌉 Bridge deposit addresses:");
+        for entry in addresses.entries() {
+            println!("  {:<8} {}", entry.chain, entry.address);
+        }
+        Ok(())
+    }
+
+    async fn show_deposits(&self, data_paths: &DataPaths, all: bool) -> Result<()> {
+        let target = self.resolve_wallet(data_paths).await?;
+        let client = FundsClient::new(self.args.rpc_url.clone())?;
+        let addresses = client.deposit_addresses(&target.address).await?;
+        let mut shown = 0;
+        for entry in addresses.entries() {
+            let deposits: Vec<BridgeDeposit> = client
+                .deposit_status(entry.address)
+                .await?
+                .into_iter()
+                .filter(|d| all || d.is_pending())
+                .collect();
+            for deposit in &deposits {
+                print_deposit(entry.chain, deposit);
+            }
+            shown += deposits.len();
+        }
+        if shown == 0 {
+            let kind = if all { "" } else { "pending " };
+            println!("No {}bridge deposits for {}", kind, target.display_name());
+        }
+        Ok(())
+    }
+
+    async fn show_balance(&self, data_paths: &DataPaths) -> Result<()> {
+        let target = self.resolve_wallet(data_paths).await?;
+        let client = FundsClient::new(self.args.rpc_url.clone())?;
+        let addresses = client.deposit_addresses(&target.address).await?;
+        let status = client.funding_status(&target.address, &addresses).await?;
+        println!("💰 {}", target.display_name());
+        println!("  Wallet USDC.e:    {}", number_format::usd(status.wallet_balance));
+        println!("  Pending deposits: {}", status.pending_deposits.len());
+        for deposit in &status.pending_deposits {
+            print_deposit("", deposit);
+        }
+        Ok(())
+    }
+
+    fn rpc_url(&self) -> &str {
+        self.args.rpc_url.as_deref().unwrap_or(DEFAULT_POLYGON_RPC_URL)
+    }
+
+    async fn show_gas(&self) -> Result<()> {
+        let rpc_url = self.rpc_url();
+        let fees = GasOracle::new(rpc_url)?.fees().await?;
+
+        println!("⛽ Gas on {}", rpc_url);
+        if fees.eip1559 {
+            println!("  Base fee:     {} gwei", wei_to_gwei(fees.base_fee).round_dp(2));
+            println!("  Priority fee: {} gwei", wei_to_gwei(fees.priority_fee).round_dp(2));
+        }
+        println!("  Max fee:      {} gwei\n", wei_to_gwei(fees.max_fee_per_gas()).round_dp(2));
+        for action in ON_CHAIN_ACTIONS {
+            println!("  {}", fees.preview(action.name, action.gas_limit));
+        }
+        fees.ensure_below(self.args.max_fee)
+    }
+
+    async fn onchain_client(&self, data_paths: &DataPaths) -> Result<OnChainClient> {
+        let signer = auth::load_signer(data_paths).await?;
+        OnChainClient::new(data_paths, signer, self.rpc_url(), self.args.max_fee)
+    }
+
+    async fn list_transactions(&self, data_paths: &DataPaths) -> Result<()> {
+        let client = self.onchain_client(data_paths).await?;
+        let transactions = client.sync().await?;
+        let confirmed_nonce = client.confirmed_nonce().await?;
+        if transactions.is_empty() {
+            println!("No transactions tracked for {}", client.address());
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let stuck_after = Duration::minutes(STUCK_AFTER_MINUTES);
+        println!("🧾 Transactions for {} (confirmed nonce {})", client.address(), confirmed_nonce);
+        for tx in &transactions {
+            let stuck = if tx.is_stuck(confirmed_nonce, now, stuck_after) {
+                " ⚠️  STUCK"
+            } else {
+                ""
+            };
+            let status = format!("{:?}", tx.status);
+            println!(
+                "  #{:<5} {:<16} {:<10} {} gwei  {}  {}{}",
+                tx.nonce,
+                tx.action,
+                status,
+                wei_to_gwei(tx.max_fee_per_gas).round_dp(2),
+                tx.submitted_at.format("%Y-%m-%d %H:%M"),
+                tx.hash,
+                stuck
+            );
+        }
+        if transactions
+            .iter()
+            .any(|tx| tx.is_stuck(confirmed_nonce, now, stuck_after))
+        {
+            println!("\nUse `funds speed-up <nonce>` or `funds cancel-tx <nonce>` to unstick them.");
+        }
+        Ok(())
+    }
+
+    /// Approve both exchanges; each approval is journaled like any other send
+    async fn approve(&self, data_paths: &DataPaths, amount: Option<Decimal>, yes: bool) -> Result<()> {
+        let requests = EXCHANGE_SPENDERS
+            .iter()
+            .map(|spender| TxRequest::usdc_approve(spender, amount))
+            .collect::<Result<Vec<_>>>()?;
+        let allowance = amount
+            .map(|a| format!("{} USDC", a))
+            .unwrap_or_else(|| "unlimited USDC".to_string());
+        println!("🔓 Approve {} for {}", allowance, EXCHANGE_SPENDERS.join(" and "));
+        self.send_all(data_paths, &requests, yes).await
+    }
+
+    async fn transfer(
+        &self,
+        data_paths: &DataPaths,
+        action: OnChainAction,
+        to: &str,
+        amount: Decimal,
+        yes: bool,
+    ) -> Result<()> {
+        let target = self.resolve_address(data_paths, to).await?;
+        let request = TxRequest::usdc_transfer(action, &target.address, amount)?;
+        println!("💸 {} {} USDC.e to {}", action.name, amount, target.display_name());
+        self.send_all(data_paths, &[request], yes).await
+    }
+
+    /// Preview the cost of `requests`, then send them in order when `yes` is set
+    async fn send_all(&self, data_paths: &DataPaths, requests: &[TxRequest], yes: bool) -> Result<()> {
+        let client = self.onchain_client(data_paths).await?;
+        let fees = client.current_fees().await?;
+        for request in requests {
+            println!("  {}", fees.preview(&request.action, request.gas_limit));
+        }
+        if !yes {
+            println!("\nDry run. Use --yes to send from {}.", client.address());
+            return Ok(());
+        }
+        for request in requests {
+            let tx = client.send(request).await?;
+            println!("✅ Sent {} {} at nonce {}", tx.action, tx.hash, tx.nonce);
+        }
+        println!("Track them with `funds txs`.");
+        Ok(())
+    }
+
+    async fn replace_transaction(&self, data_paths: &DataPaths, nonce: u64, yes: bool, cancel: bool) -> Result<()> {
+        let client = self.onchain_client(data_paths).await?;
+        client.sync().await?;
+        let preview = client.replacement_preview(nonce).await?;
+        let original = &preview.original;
+        let verb = if cancel { "Cancel" } else { "Speed up" };
+        println!(
+            "{} {} #{} ({}): max fee {} → {} gwei, tip {} → {} gwei",
+            verb,
+            original.action,
+            nonce,
+            original.hash,
+            wei_to_gwei(original.max_fee_per_gas).round_dp(2),
+            wei_to_gwei(preview.fees.max_fee_per_gas).round_dp(2),
+            wei_to_gwei(original.max_priority_fee_per_gas).round_dp(2),
+            wei_to_gwei(preview.fees.max_priority_fee_per_gas).round_dp(2)
+        );
+        if !yes {
+            println!("\nDry run. Use --yes to send the replacement.");
+            return Ok(());
+        }
+
+        let replacement = if cancel {
+            client.cancel(nonce).await?
+        } else {
+            client.speed_up(nonce).await?
+        };
+        println!("✅ Sent {} at nonce {}", replacement.hash, replacement.nonce);
+        Ok(())
+    }
+
+    /// Wallet to act on plus its address book label, if any
+    async fn resolve_wallet(&self, data_paths: &DataPaths) -> Result<WalletTarget> {
+        match &self.args.address {
+            Some(address_or_label) => self.resolve_address(data_paths, address_or_label).await,
+            None => Ok(WalletTarget {
+                address: auth::account_address(data_paths).await?,
+                label: None,
+            }),
+        }
+    }
+
+    /// Address book entry for a label or address, or the address itself
+    async fn resolve_address(&self, data_paths: &DataPaths, address_or_label: &str) -> Result<WalletTarget> {
+        let book = service::get_address_book_service(data_paths.clone(), None, None).await?;
+        let (tx, rx) = oneshot::channel();
+        book.send(AddressBookCommand::GetAddress {
+            address_or_label: address_or_label.to_string(),
+            response: tx,
+        })
+        .await?;
+        match rx.await?? {
+            Some(entry) => Ok(WalletTarget {
+                address: entry.address,
+                label: entry.label,
+            }),
+            None if is_evm_address(address_or_label) => Ok(WalletTarget {
+                address: address_or_label.to_string(),
+                label: None,
+            }),
+            None => Err(anyhow!("'{}' is not an address or address book label", address_or_label)),
+        }
+    }
+}
+
+fn print_deposit(chain: &str, deposit: &BridgeDeposit) {
+    let created = deposit
+        .created_time_ms
+        .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "  {} {:<8} {:<20} amount {} (chain {}) tx {}",
+        created,
+        chain,
+        deposit.status,
+        deposit.from_amount_base_unit.as_deref().unwrap_or("?"),
+        deposit.from_chain_id.as_deref().unwrap_or("?"),
+        deposit.tx_hash.as_deref().unwrap_or("-")
+    );
+}
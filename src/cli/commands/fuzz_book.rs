@@ -0,0 +1,77 @@
+//! Fuzz-book command: replay generated feed sequences through the order book
+//!
+//! Development tool for hardening `core::ws::state` against malformed
+//! exchange data. Generates valid and adversarial update sequences with the
+//! generators in `core::ws::fuzz`, replays each into a fresh book and reports
+//! the first invariant violation, shrunk to a minimal sequence.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::core::ws::fuzz::{self, SequenceKind};
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct FuzzBookArgs {
+    /// Sequence kind to generate (default: both)
+    #[arg(long, value_enum)]
+    pub kind: Option<SequenceKind>,
+
+    /// Sequences to replay per kind
+    #[arg(long, default_value = "1000")]
+    pub cases: u32,
+
+    /// Maximum updates per sequence
+    #[arg(long, default_value = "64")]
+    pub max_len: usize,
+
+    /// Seed for reproducing a run (default: random, printed)
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+pub struct FuzzBookCommand {
+    args: FuzzBookArgs,
+}
+
+impl FuzzBookCommand {
+    pub fn new(args: FuzzBookArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, _data_paths: DataPaths) -> Result<()> {
+        let seed = self.args.seed.unwrap_or_else(rand::random);
+        let kinds = match self.args.kind {
+            Some(kind) => vec![kind],
+            None => vec![SequenceKind::Valid, SequenceKind::Adversarial],
+        };
+
+        println!(
+            "{} {} cases per kind, up to {} updates, seed {}",
+            "🧪 Fuzzing order book:".bright_blue().bold(),
+            self.args.cases,
+            self.args.max_len,
+            seed
+        );
+
+        for kind in kinds {
+            match fuzz::fuzz(kind, self.args.cases, self.args.max_len, seed)? {
+                None => println!("  {} {}: {} sequences kept the book sane", "✅".green(), kind, self.args.cases),
+                Some(failure) => {
+                    println!("  {} {}: {}", "❌".red(), kind, failure.reason);
+                    println!("  Minimal sequence ({} updates):", failure.updates.len());
+                    for (step, update) in failure.updates.iter().enumerate() {
+                        println!("    {:>3}. {}", step, update);
+                    }
+                    println!(
+                        "  Reproduce with: polybot fuzz-book --kind {} --cases {} --max-len {} --seed {}",
+                        kind, self.args.cases, self.args.max_len, seed
+                    );
+                    return Err(anyhow!("Order book invariant violated by {} sequence", kind));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -8,35 +8,31 @@
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
 use std::time::Duration;
 use tracing::{info, debug, error};
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 
-// TUI and crossterm imports
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, DisableMouseCapture, EnableMouseCapture, KeyEvent, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{
-        Block, Borders, List, ListItem, ListState, Paragraph, Wrap,
-    },
-    Frame, Terminal,
-};
-use rust_decimal::prelude::ToPrimitive;
 
+use crate::core::progress::{ctrl_c_token, Cancelled, ProgressReporter};
 use crate::markets::gamma::*;
+use crate::markets::gamma::{session_import, store};
 use crate::data_paths::DataPaths;
+use crate::number_format;
+use crate::typed_store::context::BULK_BATCH_SIZE;
+
+#[cfg(feature = "tui")]
+mod browser;
+
+#[cfg(feature = "tui")]
+use browser::{InteractiveSearchArgs, QuitKey, TuiArgs};
+#[cfg(not(feature = "tui"))]
+use super::unavailable::{missing_feature, UnavailableArgs as InteractiveSearchArgs, UnavailableArgs as TuiArgs};
 
 /// Gamma API command structure
 #[derive(Debug, Args)]
@@ -72,7 +68,7 @@ pub enum GammaCommand {
     ProcessRaw(ProcessRawArgs),
     /// Clear/reset the cache
     ClearCache(ClearCacheArgs),
-    /// Import raw session data into SurrealDB database
+    /// Import raw session data into the local gamma database
     ImportSession(ImportSessionArgs),
     /// Database operations (stats, search, export, etc.)
     Db(DbArgs),
@@ -82,13 +78,6 @@ pub enum GammaCommand {
     SearchStatus(SearchStatusArgs),
 }
 
-/// TUI arguments
-#[derive(Debug, Args)]
-pub struct TuiArgs {
-    /// Custom data directory
-    #[arg(long)]
-    data_dir: Option<PathBuf>,
-}
 
 /// Market fetching arguments
 #[derive(Debug, Args)]
@@ -117,21 +106,41 @@ pub struct MarketsArgs {
     #[arg(long)]
     tag: Option<String>,
     
+    /// Filter by tag ID (API only: stored markets don't keep tag ids)
+    #[arg(long, conflicts_with = "from_db")]
+    tag_id: Option<u64>,
+    
+    /// Also include markets with tags related to --tag-id
+    #[arg(long, requires = "tag_id", conflicts_with = "from_db")]
+    related_tags: bool,
+    
+    /// Only markets of this event
+    #[arg(long)]
+    event_id: Option<u64>,
+    
     /// Filter by category
     #[arg(long)]
     category: Option<String>,
     
     /// Minimum volume filter
     #[arg(long)]
-    min_volume: Option<f64>,
+    min_volume: Option<rust_decimal::Decimal>,
     
     /// Maximum volume filter
     #[arg(long)]
-    max_volume: Option<f64>,
+    max_volume: Option<rust_decimal::Decimal>,
+    
+    /// Minimum liquidity filter
+    #[arg(long)]
+    min_liquidity: Option<rust_decimal::Decimal>,
+    
+    /// Maximum liquidity filter
+    #[arg(long)]
+    max_liquidity: Option<rust_decimal::Decimal>,
     
-    /// Sort by field (volume, liquidity, start_date, end_date)
+    /// Sort by field (volume, volume24hr, liquidity, start_date, end_date, created_at, id)
     #[arg(long, default_value = "volume")]
-    sort_by: String,
+    sort_by: MarketOrder,
     
     /// Sort in ascending order
     #[arg(long)]
@@ -243,7 +252,7 @@ pub struct PositionsArgs {
     
     /// Sort by field (size, current_value, pnl)
     #[arg(long, default_value = "current_value")]
-    sort_by: String,
+    sort_by: PositionOrder,
     
     /// Show detailed information
     #[arg(long, short)]
@@ -509,9 +518,9 @@ pub struct DbListArgs {
     #[arg(long, default_value = "0")]
     offset: u64,
     
-    /// Sort by field (volume, liquidity, created_at, updated_at)
+    /// Sort by field (volume, volume_24hr, liquidity, created_at, updated_at, end_date, question)
     #[arg(long, default_value = "volume")]
-    sort_by: String,
+    sort_by: MarketListOrder,
     
     /// Sort order (asc, desc)
     #[arg(long, default_value = "desc")]
@@ -633,13 +642,6 @@ pub struct BuildIndexArgs {
     force: bool,
 }
 
-/// Interactive search arguments
-#[derive(Debug, Args)]
-pub struct InteractiveSearchArgs {
-    /// Show search statistics
-    #[arg(long)]
-    show_stats: bool,
-}
 
 /// Search status arguments
 #[derive(Debug, Args)]
@@ -677,13 +679,49 @@ pub struct ImportSessionArgs {
     #[arg(long, default_value = "100")]
     batch_size: usize,
     
+    /// Sessions read at the same time
+    #[arg(long, default_value = "4")]
+    concurrency: usize,
+    
+    /// Load in bulk (typed RocksDB backend): no write-ahead log, batches of at
+    /// least 10000 markets, one compaction at the end. Nothing else may use
+    /// the database during the import
+    #[arg(long)]
+    bulk_load: bool,
+    
+    /// Print progress bars instead of the full-screen progress view
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    no_tui: bool,
+    
     /// Confirm the operation without prompting
     #[arg(long, short)]
     yes: bool,
 }
 
+/// Headless builds have no raw mode; Ctrl+C still ends a listing
+#[cfg(not(feature = "tui"))]
+struct QuitKey;
+
+#[cfg(not(feature = "tui"))]
+impl QuitKey {
+    fn listen() -> Self {
+        Self
+    }
+
+    fn pressed(&self) -> bool {
+        false
+    }
+
+    fn stop(self) {}
+}
+
 /// Execute Gamma command
-pub async fn execute_gamma_command(args: GammaArgs, verbose: bool) -> Result<()> {
+pub async fn execute_gamma_command(
+    args: GammaArgs,
+    verbose: bool,
+    data_paths: DataPaths,
+) -> Result<()> {
     // Set log level BEFORE any logging initialization to ensure it takes effect
     // This suppresses verbose SurrealDB and RocksDB initialization logs that would otherwise
     // clutter the output with internal database operations
@@ -697,56 +735,64 @@ pub async fn execute_gamma_command(args: GammaArgs, verbose: bool) -> Result<()>
     let uses_tui = matches!(args.command, GammaCommand::Tui(_) | GammaCommand::Db(_));
     
     // Initialize logging
-    let data_paths = crate::data_paths::DataPaths::new("./data");
+    let root = data_paths.root().clone();
+    let data_root = root.as_path();
     let log_mode = if uses_tui {
         crate::logging::LogMode::FileOnly
     } else {
         crate::logging::LogMode::ConsoleAndFile
     };
+    let display_paths = data_paths.clone();
     let log_config = crate::logging::LoggingConfig::new(log_mode, data_paths);
     
     crate::logging::init_logging(log_config)?;
+
+    // Address book labels and cached ENS names for every wallet shown below
+    crate::address_book::address_display(&display_paths).await;
     
     info!("Starting Gamma command");
     
     match args.command {
-        GammaCommand::Tui(tui_args) => execute_tui(tui_args).await,
-        GammaCommand::Markets(market_args) => execute_markets(market_args, verbose).await,
+        #[cfg(feature = "tui")]
+        GammaCommand::Tui(tui_args) => browser::execute_tui(tui_args, data_root).await,
+        #[cfg(not(feature = "tui"))]
+        GammaCommand::Tui(_) => Err(missing_feature("gamma tui", "tui")),
+        GammaCommand::Markets(market_args) => {
+            execute_markets(market_args, verbose, data_root).await
+        }
         GammaCommand::Events(event_args) => execute_events(event_args, verbose).await,
         GammaCommand::Trades(trade_args) => execute_trades(trade_args, verbose).await,
         GammaCommand::Positions(position_args) => execute_positions(position_args, verbose).await,
         GammaCommand::Search(search_args) => execute_search(search_args).await,
-        GammaCommand::InteractiveSearch(args) => execute_interactive_search(args, verbose).await,
+        #[cfg(feature = "tui")]
+        GammaCommand::InteractiveSearch(args) => {
+            browser::execute_interactive_search(args, verbose, data_root).await
+        }
+        #[cfg(not(feature = "tui"))]
+        GammaCommand::InteractiveSearch(_) => {
+            Err(missing_feature("gamma interactive-search", "tui"))
+        }
         GammaCommand::Analytics(analytics_args) => execute_analytics(analytics_args).await,
         GammaCommand::Sync(sync_args) => execute_sync(sync_args, verbose).await,
-        GammaCommand::Export(export_args) => execute_export(export_args).await,
+        GammaCommand::Export(export_args) => execute_export(export_args, data_root).await,
         GammaCommand::ProcessRaw(process_args) => execute_process_raw(process_args).await,
         GammaCommand::ClearCache(clear_args) => execute_clear_cache(clear_args).await,
-        GammaCommand::ImportSession(import_args) => execute_import_session(import_args, verbose).await,
-        GammaCommand::Db(db_args) => execute_db_command(db_args, verbose).await,
-        GammaCommand::BuildIndex(build_args) => execute_build_index(build_args, verbose).await,
-        GammaCommand::SearchStatus(status_args) => execute_search_status(status_args, verbose).await,
+        GammaCommand::ImportSession(import_args) => {
+            execute_import_session(import_args, verbose, data_root).await
+        }
+        GammaCommand::Db(db_args) => execute_db_command(db_args, verbose, data_root).await,
+        GammaCommand::BuildIndex(build_args) => {
+            execute_build_index(build_args, verbose, data_root).await
+        }
+        GammaCommand::SearchStatus(status_args) => {
+            execute_search_status(status_args, verbose, data_root).await
+        }
     }
 }
 
-/// Execute TUI command
-async fn execute_tui(args: TuiArgs) -> Result<()> {
-    println!("{}", "🚀 Starting Gamma Explorer TUI...".bright_green());
-    
-    let data_dir = args.data_dir
-        .unwrap_or_else(|| DataPaths::new(std::env::current_dir().unwrap()).root().join("database/gamma"));
-    
-    let storage = GammaStorage::new(&data_dir)
-        .context("Failed to open Gamma storage")?;
-    
-    let mut tui = GammaTui::new(storage);
-    tui.run().context("TUI execution failed")?;
-    
-    Ok(())
-}
 
 /// Execute markets command with session-based persistent storage and search service integration
-async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
+async fn execute_markets(args: MarketsArgs, verbose: bool, data_root: &Path) -> Result<()> {
     // Check if loading from database
     if args.from_db {
         println!("{}", "📊 Loading markets from database with search service integration...".bright_blue());
@@ -762,10 +808,10 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
             keyword: None, // Markets command doesn't have keyword search, use search subcommand instead
             tags: args.tag.as_ref().map(|t| vec![t.clone()]).unwrap_or_default(),
             category: args.category.clone(),
-            min_volume: args.min_volume.map(|v| rust_decimal::Decimal::try_from(v).unwrap()),
-            max_volume: args.max_volume.map(|v| rust_decimal::Decimal::try_from(v).unwrap()),
-            min_liquidity: None,
-            max_liquidity: None,
+            min_volume: args.min_volume,
+            max_volume: args.max_volume,
+            min_liquidity: args.min_liquidity,
+            max_liquidity: args.max_liquidity,
             active_only: args.active_only,
             closed_only: args.closed_only,
             archived_only: args.archived,
@@ -777,6 +823,8 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
         let has_filters = search_filters.category.is_some() || 
                           search_filters.min_volume.is_some() || 
                           search_filters.max_volume.is_some() ||
+                          search_filters.min_liquidity.is_some() ||
+                          search_filters.max_liquidity.is_some() ||
                           !search_filters.tags.is_empty() ||
                           search_filters.active_only || 
                           search_filters.closed_only || 
@@ -788,7 +836,7 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
                 .context("Failed to search markets with filters")?
         } else {
             // Fallback to loading all markets from database
-            let all_markets = load_markets_from_database().await
+            let all_markets = load_markets_from_database(data_root).await
                 .context("Failed to load markets from database")?;
             
             if all_markets.is_empty() {
@@ -800,40 +848,22 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
             all_markets
         };
         
-        // Sort markets (search engine provides basic sorting by volume)
+        // Stored markets keep the events they belong to, so --event-id works here too
         let mut final_markets = filtered_markets;
-        match args.sort_by.as_str() {
-            "volume" => {
-                if args.ascending {
-                    final_markets.sort_by_key(|m| m.volume());
-                } else {
-                    final_markets.sort_by_key(|m| std::cmp::Reverse(m.volume()));
-                }
-            },
-            "liquidity" => {
-                if args.ascending {
-                    final_markets.sort_by_key(|m| m.liquidity.unwrap_or_default());
-                } else {
-                    final_markets.sort_by_key(|m| std::cmp::Reverse(m.liquidity.unwrap_or_default()));
-                }
-            },
-            "start_date" => {
-                if args.ascending {
-                    final_markets.sort_by_key(|m| m.start_date);
-                } else {
-                    final_markets.sort_by_key(|m| std::cmp::Reverse(m.start_date));
-                }
-            },
-            "end_date" => {
-                if args.ascending {
-                    final_markets.sort_by_key(|m| m.end_date);
-                } else {
-                    final_markets.sort_by_key(|m| std::cmp::Reverse(m.end_date));
-                }
-            },
-            _ => {} // Default is already sorted by volume desc
+        if let Some(event_id) = args.event_id {
+            final_markets.retain(|m| {
+                m.events
+                    .iter()
+                    .flatten()
+                    .any(|event| event.id.0 == event_id)
+            });
         }
         
+        final_markets.sort_by(|a, b| {
+            let order = args.sort_by.compare(a, b);
+            if args.ascending { order } else { order.reverse() }
+        });
+        
         // Apply limit if not --all
         if !args.all && final_markets.len() > args.limit as usize {
             final_markets.truncate(args.limit as usize);
@@ -854,19 +884,25 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
         .context("Failed to create session manager")?;
     
     // Build query from arguments
-    let query = MarketQuery {
-        limit: Some(500), // Always use batch size of 500 for optimal API performance
-        offset: Some(args.offset),
-        archived: if args.archived { Some(true) } else { None },
-        active: if args.active_only { Some(true) } else { None },
-        closed: if args.closed_only { Some(true) } else { None },
-        tags: args.tag.clone().map(|t| vec![t]).unwrap_or_default(),
-        order: Some(args.sort_by.clone()),
-        ascending: Some(args.ascending),
-        volume_min: args.min_volume.map(|v| rust_decimal::Decimal::try_from(v).unwrap()),
-        volume_max: args.max_volume.map(|v| rust_decimal::Decimal::try_from(v).unwrap()),
-        ..Default::default()
-    };
+    let mut query = MarketQuery::builder()
+        .limit(500) // Always use batch size of 500 for optimal API performance
+        .offset(args.offset)
+        .archived(args.archived.then_some(true))
+        .active(args.active_only.then_some(true))
+        .closed(args.closed_only.then_some(true))
+        .tag(args.tag.clone())
+        .tag_id(args.tag_id.map(TagId))
+        .related_tags(args.related_tags.then_some(true))
+        .order(args.sort_by)
+        .ascending(args.ascending)
+        .volume_min(args.min_volume)
+        .volume_max(args.max_volume)
+        .liquidity_min(args.min_liquidity)
+        .liquidity_max(args.max_liquidity);
+    if let Some(event_id) = args.event_id {
+        query = query.event_id(EventId(event_id));
+    }
+    let query = query.build().context("Invalid market filters")?;
     
     info!("Starting session-based market fetching with query: {:?}", query);
     
@@ -915,8 +951,7 @@ async fn execute_markets(args: MarketsArgs, verbose: bool) -> Result<()> {
     }).context("Failed to set Ctrl+C handler")?;
     
     // Initialize database connection for session storage
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     // Fetch markets with session-based storage
@@ -1119,6 +1154,12 @@ async fn execute_trades(args: TradesArgs, verbose: bool) -> Result<()> {
         .context("Failed to fetch trades")?;
     
     println!("Found {} trades:", response.data.len());
+
+    if let Some(display) = crate::address_book::AddressDisplay::global() {
+        display
+            .resolve(response.data.iter().map(|trade| trade.proxy_wallet.0.as_str()))
+            .await;
+    }
     
     for (i, trade) in response.data.iter().enumerate() {
         if args.detailed || verbose {
@@ -1240,41 +1281,102 @@ async fn execute_sync(args: SyncArgs, verbose: bool) -> Result<()> {
     }
     
     let client = GammaClient::new();
+    let verbose = args.detailed || verbose;
+    
+    // Ctrl-C stops after the batch being stored; the checkpoint lets the next
+    // sync continue from there instead of offset 0
+    let cancel = ctrl_c_token();
+    let mut checkpoint = if args.clear {
+        SyncCheckpoint::default()
+    } else {
+        SyncCheckpoint::load(&data_dir)
+    };
     
     if args.markets {
         println!("{}", "📈 Syncing markets...".bright_blue());
-        sync_markets(&client, &storage, args.batch_size, args.detailed || verbose).await?;
+        checkpoint.markets = sync_markets(&client, &storage, args.batch_size, verbose, checkpoint.markets.unwrap_or(0), &cancel).await?;
+        if checkpoint.markets.is_some() {
+            return sync_interrupted(&checkpoint, &data_dir);
+        }
     }
     
     if args.events {
         println!("{}", "📅 Syncing events...".bright_blue());
-        sync_events(&client, &storage, args.batch_size, args.detailed || verbose).await?;
+        checkpoint.events = sync_events(&client, &storage, args.batch_size, verbose, checkpoint.events.unwrap_or(0), &cancel).await?;
+        if checkpoint.events.is_some() {
+            return sync_interrupted(&checkpoint, &data_dir);
+        }
     }
     
     if args.trades {
         println!("{}", "💱 Syncing trades...".bright_blue());
-        sync_trades(&client, &storage, args.batch_size, args.detailed || verbose).await?;
+        checkpoint.trades = sync_trades(&client, &storage, args.batch_size, verbose, checkpoint.trades.unwrap_or(0), &cancel).await?;
+        if checkpoint.trades.is_some() {
+            return sync_interrupted(&checkpoint, &data_dir);
+        }
     }
     
     if let Some(user) = args.user {
+        if cancel.is_cancelled() {
+            return sync_interrupted(&checkpoint, &data_dir);
+        }
         println!("{}", "📊 Syncing positions...".bright_blue());
-        sync_positions(&client, &storage, &user, args.detailed || verbose).await?;
+        sync_positions(&client, &storage, &user, verbose).await?;
     }
     
+    checkpoint.store(&data_dir)?;
     println!("{}", "✅ Sync completed!".bright_green());
     
     Ok(())
 }
 
+/// Offsets an interrupted `gamma sync` reached, per kind of data
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct SyncCheckpoint {
+    markets: Option<u32>,
+    events: Option<u32>,
+    trades: Option<u32>,
+}
+
+impl SyncCheckpoint {
+    const FILE: &'static str = "sync_checkpoint.json";
+    
+    fn load(data_dir: &Path) -> Self {
+        fs::read_to_string(data_dir.join(Self::FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+    
+    /// Save the checkpoint, or remove it once nothing is left to resume
+    fn store(&self, data_dir: &Path) -> Result<()> {
+        let path = data_dir.join(Self::FILE);
+        if self.markets.is_none() && self.events.is_none() && self.trades.is_none() {
+            if path.exists() {
+                fs::remove_file(&path).context("Failed to remove sync checkpoint")?;
+            }
+            return Ok(());
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .context("Failed to save sync checkpoint")
+    }
+}
+
+fn sync_interrupted(checkpoint: &SyncCheckpoint, data_dir: &Path) -> Result<()> {
+    checkpoint.store(data_dir)?;
+    println!("{}", "⏹ Sync cancelled; the next `gamma sync` continues where it stopped".bright_yellow());
+    Err(Cancelled("Sync".to_string()).into())
+}
+
 /// Execute export command
-async fn execute_export(args: ExportArgs) -> Result<()> {
+async fn execute_export(args: ExportArgs, data_root: &Path) -> Result<()> {
     println!("{}", "📁 Exporting data...".bright_blue());
     
     // Load data based on type
     let data: Vec<serde_json::Value> = match args.data_type.as_str() {
         "markets" => {
             println!("Loading markets from database...");
-            let markets = load_markets_from_database().await?;
+            let markets = load_markets_from_database(data_root).await?;
             println!("Found {} markets", markets.len());
             
             // Apply filter if provided
@@ -1357,9 +1459,8 @@ async fn execute_export(args: ExportArgs) -> Result<()> {
 // ============================================================================
 
 /// Load all markets from database (deduplicated and efficient)
-async fn load_markets_from_database() -> Result<Vec<GammaMarket>> {
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+async fn load_markets_from_database(data_root: &Path) -> Result<Vec<GammaMarket>> {
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     // Use get_all_markets to fetch all markets, not just the default limit
@@ -1394,21 +1495,22 @@ async fn handle_markets_output(markets: Vec<GammaMarket>, args: &MarketsArgs, ve
         return Ok(());
     }
     
-    if args.no_interactive {
-        // Use text-based output
-        println!("Found {} markets:", markets.len());
-        
-        for (i, market) in markets.iter().enumerate() {
-            if args.detailed || verbose {
-                print_market_detailed(market, i + 1);
-            } else {
-                print_market_summary(market, i + 1);
-            }
-        }
-    } else {
-        // Launch interactive TUI browser (default behavior)
+    // Launch interactive TUI browser (default behavior); headless builds print the list
+    #[cfg(feature = "tui")]
+    if !args.no_interactive {
         println!("{}", "🚀 Launching interactive markets browser...".bright_green());
-        launch_markets_browser(markets).await?;
+        return browser::launch_markets_browser(markets).await;
+    }
+
+    // Use text-based output
+    println!("Found {} markets:", markets.len());
+    
+    for (i, market) in markets.iter().enumerate() {
+        if args.detailed || verbose {
+            print_market_detailed(market, i + 1);
+        } else {
+            print_market_summary(market, i + 1);
+        }
     }
     
     Ok(())
@@ -1420,7 +1522,7 @@ async fn handle_markets_output(markets: Vec<GammaMarket>, args: &MarketsArgs, ve
 
 fn print_market_summary(market: &GammaMarket, index: usize) {
     let status = if market.active { "🟢" } else { "🔴" };
-    let volume = format!("${:.0}", market.volume());
+    let volume = format!("${}", number_format::integer(market.volume()));
     
     println!(
         "{:3}. {} {} {} - {}",
@@ -1440,8 +1542,8 @@ fn print_market_detailed(market: &GammaMarket, index: usize) {
     } else {
         println!("Status: {}", "Inactive".red());
     }
-    println!("Volume: ${:.2}", market.volume());
-    println!("Liquidity: ${:.2}", market.liquidity.unwrap_or_default());
+    println!("Volume: {}", number_format::usd(market.volume()));
+    println!("Liquidity: {}", number_format::usd(market.liquidity.unwrap_or_default()));
     println!("Outcomes: {:?}", market.outcomes);
     println!("Prices: {:?}", market.outcome_prices);
     println!("Category: {:?}", market.category);
@@ -1456,11 +1558,11 @@ fn print_event_summary(event: &GammaEvent, index: usize) {
     };
     
     println!(
-        "{:3}. {} {} (Volume: ${:.0})",
+        "{:3}. {} {} (Volume: ${})",
         index,
         status,
         event.title,
-        event.volume_num.or(event.volume).unwrap_or_default()
+        number_format::integer(event.volume_num.or(event.volume).unwrap_or_default())
     );
 }
 
@@ -1472,10 +1574,10 @@ fn print_event_detailed(event: &GammaEvent, index: usize) {
         println!("Description: {}", desc.chars().take(200).collect::<String>());
     }
     if let Some(volume) = event.volume_num.or(event.volume) {
-        println!("Volume: ${:.2}", volume);
+        println!("Volume: {}", number_format::usd(volume));
     }
     if let Some(liquidity) = event.liquidity_num.or(event.liquidity) {
-        println!("Liquidity: ${:.2}", liquidity);
+        println!("Liquidity: {}", number_format::usd(liquidity));
     }
     if let Some(ref tags) = event.tags {
         let tag_labels: Vec<&str> = tags.iter().map(|t| t.label.as_str()).collect();
@@ -1491,24 +1593,31 @@ fn print_trade_summary(trade: &GammaTrade, index: usize) {
     };
     
     println!(
-        "{:3}. {} {} {} @ ${:.3} ({})",
+        "{:3}. {} {} {} @ ${} ({}) {}",
         index,
         format!("{:?}", trade.side).color(side_color),
-        trade.size,
+        number_format::size(trade.size),
         trade.outcome,
-        trade.price,
-        trade.timestamp.format("%H:%M:%S")
+        number_format::price(trade.price),
+        trade.timestamp.format("%H:%M:%S"),
+        trade_user(trade).dimmed()
     );
 }
 
+/// Trader as label, ENS name or Polymarket profile name, whichever is known
+fn trade_user(trade: &GammaTrade) -> String {
+    let profile = trade.name.as_deref().or(trade.pseudonym.as_deref());
+    crate::address_book::display_address_or(&trade.proxy_wallet.0, profile)
+}
+
 fn print_trade_detailed(trade: &GammaTrade, index: usize) {
     println!("{}", format!("=== Trade {} ===", index).bright_yellow());
     println!("Side: {:?}", trade.side);
     println!("Outcome: {}", trade.outcome);
-    println!("Size: {}", trade.size);
-    println!("Price: ${:.4}", trade.price);
-    println!("Value: ${:.2}", trade.size * trade.price);
-    println!("User: {}", trade.proxy_wallet.0);
+    println!("Size: {}", number_format::size(trade.size));
+    println!("Price: ${}", number_format::price(trade.price));
+    println!("Value: {}", number_format::usd(trade.size * trade.price));
+    println!("User: {}", trade_user(trade));
     println!("Time: {}", trade.timestamp);
     println!();
 }
@@ -1521,12 +1630,12 @@ fn print_position_summary(position: &GammaPosition, index: usize) {
     };
     
     println!(
-        "{:3}. {} {} shares - ${:.2} ({:.1}%)",
+        "{:3}. {} {} shares - {} ({})",
         index,
         position.outcome,
-        position.size,
-        position.current_value,
-        format!("{:.1}", position.percent_pnl).color(pnl_color)
+        number_format::size(position.size),
+        number_format::usd(position.current_value),
+        number_format::percent(position.percent_pnl).color(pnl_color)
     );
 }
 
@@ -1534,10 +1643,14 @@ fn print_position_detailed(position: &GammaPosition, index: usize) {
     println!("{}", format!("=== Position {} ===", index).bright_yellow());
     println!("Market: {}", position.title);
     println!("Outcome: {}", position.outcome);
-    println!("Size: {} shares", position.size);
-    println!("Avg Price: ${:.4}", position.avg_price);
-    println!("Current Value: ${:.2}", position.current_value);
-    println!("P&L: ${:.2} ({:.1}%)", position.cash_pnl, position.percent_pnl);
+    println!("Size: {} shares", number_format::size(position.size));
+    println!("Avg Price: ${}", number_format::price(position.avg_price));
+    println!("Current Value: {}", number_format::usd(position.current_value));
+    println!(
+        "P&L: {} ({})",
+        number_format::usd(position.cash_pnl),
+        number_format::percent(position.percent_pnl)
+    );
     if position.redeemable {
         println!("Redeemable: {}", "Yes".green());
     } else {
@@ -1552,10 +1665,10 @@ fn print_analytics(analytics: &MarketAnalytics, detailed: bool) {
     println!("Active: {}", analytics.active_markets.to_string().green());
     println!("Closed: {}", analytics.closed_markets.to_string().red());
     println!("Archived: {}", analytics.archived_markets);
-    println!("Total Volume: ${:.0}", analytics.total_volume);
-    println!("Average Volume: ${:.0}", analytics.avg_volume);
-    println!("Total Liquidity: ${:.0}", analytics.total_liquidity);
-    println!("Average Liquidity: ${:.0}", analytics.avg_liquidity);
+    println!("Total Volume: ${}", number_format::integer(analytics.total_volume));
+    println!("Average Volume: ${}", number_format::integer(analytics.avg_volume));
+    println!("Total Liquidity: ${}", number_format::integer(analytics.total_liquidity));
+    println!("Average Liquidity: ${}", number_format::integer(analytics.avg_liquidity));
     
     if detailed {
         println!("\n{}", "Top Categories:".bright_blue());
@@ -1570,26 +1683,33 @@ fn print_analytics(analytics: &MarketAnalytics, detailed: bool) {
     }
 }
 
+/// Sync from `offset` on; returns the offset to resume from if cancelled
 async fn sync_markets(
     client: &GammaClient,
     storage: &GammaStorage,
     batch_size: u32,
     verbose: bool,
-) -> Result<()> {
-    let mut offset = 0;
+    mut offset: u32,
+    cancel: &CancellationToken,
+) -> Result<Option<u32>> {
     let mut total_fetched = 0;
     
     loop {
-        let query = MarketQuery {
-            limit: Some(batch_size),
-            offset: Some(offset),
-            order: Some("id".to_string()),
-            ascending: Some(true),
-            ..Default::default()
+        let query = MarketQuery::builder()
+            .limit(batch_size)
+            .offset(offset)
+            .order(MarketOrder::Id)
+            .ascending(true)
+            .build()?;
+        
+        let response = tokio::select! {
+            response = client.fetch_markets(&query) => response?,
+            _ = cancel.cancelled() => {
+                println!("Synced {} markets before cancelling", total_fetched);
+                return Ok(Some(offset));
+            }
         };
         
-        let response = client.fetch_markets(&query).await?;
-        
         if response.data.is_empty() {
             break;
         }
@@ -1609,16 +1729,18 @@ async fn sync_markets(
     }
     
     println!("Synced {} markets total", total_fetched);
-    Ok(())
+    Ok(None)
 }
 
+/// Sync from `offset` on; returns the offset to resume from if cancelled
 async fn sync_events(
     client: &GammaClient,
     storage: &GammaStorage,
     batch_size: u32,
     verbose: bool,
-) -> Result<()> {
-    let mut offset = 0;
+    mut offset: u32,
+    cancel: &CancellationToken,
+) -> Result<Option<u32>> {
     let mut total_fetched = 0;
     
     loop {
@@ -1630,7 +1752,13 @@ async fn sync_events(
             ..Default::default()
         };
         
-        let response = client.fetch_events(&query).await?;
+        let response = tokio::select! {
+            response = client.fetch_events(&query) => response?,
+            _ = cancel.cancelled() => {
+                println!("Synced {} events before cancelling", total_fetched);
+                return Ok(Some(offset));
+            }
+        };
         
         if response.data.is_empty() {
             break;
@@ -1651,16 +1779,18 @@ async fn sync_events(
     }
     
     println!("Synced {} events total", total_fetched);
-    Ok(())
+    Ok(None)
 }
 
+/// Sync from `offset` on; returns the offset to resume from if cancelled
 async fn sync_trades(
     client: &GammaClient,
     storage: &GammaStorage,
     batch_size: u32,
     verbose: bool,
-) -> Result<()> {
-    let mut offset = 0;
+    mut offset: u32,
+    cancel: &CancellationToken,
+) -> Result<Option<u32>> {
     let mut total_fetched = 0;
     
     loop {
@@ -1671,7 +1801,13 @@ async fn sync_trades(
             ..Default::default()
         };
         
-        let response = client.fetch_trades(&query).await?;
+        let response = tokio::select! {
+            response = client.fetch_trades(&query) => response?,
+            _ = cancel.cancelled() => {
+                println!("Synced {} trades before cancelling", total_fetched);
+                return Ok(Some(offset));
+            }
+        };
         
         if response.data.is_empty() {
             break;
@@ -1692,7 +1828,7 @@ async fn sync_trades(
     }
     
     println!("Synced {} trades total", total_fetched);
-    Ok(())
+    Ok(None)
 }
 
 async fn sync_positions(
@@ -1833,79 +1969,14 @@ async fn execute_process_raw(args: ProcessRawArgs) -> Result<()> {
     Ok(())
 }
 
-/// Launch interactive markets browser TUI
-async fn launch_markets_browser(markets: Vec<GammaMarket>) -> Result<()> {
-    use crate::markets::gamma::tui::MarketsBrowser;
-    use ratatui::{Terminal, backend::CrosstermBackend};
-    use std::io;
-    
-    // Setup terminal
-    enable_raw_mode().context("Failed to enable raw mode")?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).context("Failed to setup terminal")?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
-    
-    // Initialize browser state
-    let mut browser = MarketsBrowser::new(markets);
-    
-    let res = run_markets_browser(&mut terminal, &mut browser).await;
-    
-    // Restore terminal
-    disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    ).context("Failed to restore terminal")?;
-    terminal.show_cursor().context("Failed to show cursor")?;
-    
-    res
-}
-
-/// Run the markets browser loop
-async fn run_markets_browser(
-    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
-    browser: &mut crate::markets::gamma::tui::MarketsBrowser,
-) -> Result<()> {
-    loop {
-        terminal.draw(|f| browser.render(f, f.area()))?;
-        
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Esc => {
-                            if browser.is_searching() {
-                                browser.cancel_search();
-                            } else {
-                                break;
-                            }
-                        },
-                        KeyCode::Char('/') => browser.start_search(),
-                        KeyCode::Enter => browser.handle_enter(),
-                        KeyCode::Up => browser.previous(),
-                        KeyCode::Down => browser.next(),
-                        KeyCode::PageUp => browser.page_up(),
-                        KeyCode::PageDown => browser.page_down(),
-                        KeyCode::Home => browser.go_to_top(),
-                        KeyCode::End => browser.go_to_bottom(),
-                        KeyCode::Char(c) => browser.handle_char(c),
-                        KeyCode::Backspace => browser.handle_backspace(),
-                        _ => {}
-                    }
-                }
-            }
-        }
-    }
-    
-    Ok(())
-}
 
 /// Execute import session command
-async fn execute_import_session(args: ImportSessionArgs, verbose: bool) -> Result<()> {
-    println!("{}", "📊 Importing session data into SurrealDB...".bright_blue());
+async fn execute_import_session(
+    args: ImportSessionArgs,
+    verbose: bool,
+    data_root: &Path,
+) -> Result<()> {
+    println!("{}", "📊 Importing session data into the gamma database...".bright_blue());
     
     // Validate arguments
     if args.session_id.is_some() && (args.from_session_id.is_some() || args.to_session_id.is_some()) {
@@ -1917,9 +1988,12 @@ async fn execute_import_session(args: ImportSessionArgs, verbose: bool) -> Resul
     }
     
     // Initialize database
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
-        .context("Failed to initialize gamma database")?;
+    let database = if args.bulk_load {
+        store::open_store_for_bulk_load(data_root).await
+    } else {
+        store::open_store(data_root).await
+    }
+    .context("Failed to initialize gamma database")?;
     
     // Initialize session manager
     let base_path = PathBuf::from("./data/gamma/raw");
@@ -1963,69 +2037,58 @@ async fn execute_import_session(args: ImportSessionArgs, verbose: bool) -> Resul
         return Err(anyhow::anyhow!("Must specify either --session-id or --from-session-id and --to-session-id"));
     }
     
-    // Import the selected sessions (process sessions sequentially but files in parallel)
-    let mut total_imported = 0;
-    
-    println!("🚀 Processing {} sessions...", sessions_to_import.len());
-    
-    // Create multi-progress container for tracking parallel file imports
-    let multi_progress = if !verbose {
-        Some(MultiProgress::new())
-    } else {
-        None
-    };
-    
-    // Create main progress bar for sessions
-    let main_pb = if let Some(ref mp) = multi_progress {
-        let pb = mp.add(ProgressBar::new(sessions_to_import.len() as u64));
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("📦 Sessions: [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
-                .unwrap()
-                .progress_chars("#>-")
-        );
-        pb.set_message("Starting import...");
-        Some(pb)
-    } else {
-        None
+    // Sessions are read concurrently and merged by id, so each market is
+    // written once with its latest version
+    let sessions: Vec<session_import::SessionSource> = sessions_to_import
+        .iter()
+        .map(|(session_id, session_dir, _)| session_import::SessionSource {
+            id: *session_id,
+            path: session_manager.base_path.join(session_dir),
+        })
+        .collect();
+    let options = session_import::ImportOptions {
+        concurrency: args.concurrency,
+        batch_size: if args.bulk_load {
+            args.batch_size.max(BULK_BATCH_SIZE)
+        } else {
+            args.batch_size
+        },
     };
     
-    for (_idx, (session_id, session_dir, is_complete)) in sessions_to_import.iter().enumerate() {
-        if verbose {
-            println!("🔄 Processing session {} ({})...", session_id, 
-                if *is_complete { "complete" } else { "incomplete" });
-        }
-        
-        if let Some(ref pb) = main_pb {
-            pb.set_message(format!("Processing session {}...", session_id));
-        }
-        
-        let session_path = session_manager.base_path.join(&session_dir);
-        let imported_count = import_single_session_parallel(
-            &database, 
-            &session_path, 
-            *session_id, 
-            &args, 
-            verbose,
-            multi_progress.as_ref()
-        ).await?;
-        total_imported += imported_count;
-        
-        if let Some(ref pb) = main_pb {
-            pb.inc(1);
-            pb.set_message(format!("{} markets imported from session {}", imported_count, session_id));
-        }
-        
-        if verbose {
-            println!("✅ Imported {} markets from session {}", imported_count, session_id);
+    println!("🚀 Processing {} sessions, {} at a time...", sessions.len(), options.concurrency.max(1));
+    if verbose {
+        for (session_id, _, is_complete) in &sessions_to_import {
+            println!("  📁 Session {} ({})", session_id, if *is_complete { "complete" } else { "incomplete" });
         }
     }
     
-    if let Some(pb) = main_pb {
-        pb.finish_with_message(format!("✓ Imported {} total markets", total_imported));
+    // Ctrl-C (or q in the progress view) stops after the batch being written;
+    // batches already written stay in the database
+    #[cfg(feature = "tui")]
+    if !verbose && !args.no_tui && std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        let (progress, snapshots) = ProgressReporter::channel();
+        let progress = progress.with_cancellation(ctrl_c_token());
+        let import = session_import::import_sessions(database.as_ref(), sessions, options, &progress);
+        let summary =
+            crate::tui::import_progress::run("Gamma session import", &progress, snapshots, import).await?;
+        return print_import_summary(&summary, database.as_ref()).await;
     }
-    
-    println!("\n🎉 Successfully imported {} total markets", total_imported);
+
+    let progress = ProgressReporter::terminal_if(!verbose).with_cancellation(ctrl_c_token());
+    let summary = session_import::import_sessions(database.as_ref(), sessions, options, &progress).await?;
+    print_import_summary(&summary, database.as_ref()).await
+}
+
+/// Print what a session import stored and the database totals after it
+async fn print_import_summary(
+    summary: &session_import::ImportSummary,
+    database: &dyn GammaStore,
+) -> Result<()> {
+    println!("\n🎉 Imported {} sessions ({} files)", summary.sessions, summary.files);
+    println!("Markets read: {} ({} unique)", summary.markets_read, summary.unique_markets);
+    println!("Markets stored: {} ({} kept a newer stored copy)", summary.markets.stored, summary.markets.skipped);
+    println!("Events stored: {} of {} unique ({} kept a newer stored copy)",
+        summary.events.stored, summary.unique_events, summary.events.skipped);
     
     // Update database statistics
     let stats = database.get_stats().await?;
@@ -2038,162 +2101,30 @@ async fn execute_import_session(args: ImportSessionArgs, verbose: bool) -> Resul
     Ok(())
 }
 
-/// Import markets from a single session into the database (parallel version)
-async fn import_single_session_parallel(
-    database: &GammaDatabase, 
-    session_path: &Path, 
-    session_id: u32,
-    _args: &ImportSessionArgs,
-    verbose: bool,
-    multi_progress: Option<&MultiProgress>
-) -> Result<u64> {
-    if !session_path.exists() {
-        info!("Session directory does not exist, skipping: {:?}", session_path);
-        return Ok(0);
-    }
-    
-    // Read session directory for raw response files
-    let entries = fs::read_dir(session_path)
-        .context("Failed to read session directory")?;
-    
-    let mut raw_files = Vec::new();
-    for entry in entries {
-        let entry = entry.context("Failed to read directory entry")?;
-        let path = entry.path();
-        if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-            if filename.starts_with("raw-offset-") && filename.ends_with(".json") {
-                // Extract offset from filename for sorting
-                if let Some(offset_str) = filename.strip_prefix("raw-offset-").and_then(|s| s.strip_suffix(".json")) {
-                    if let Ok(offset) = offset_str.parse::<usize>() {
-                        raw_files.push((offset, path));
-                    }
-                }
-            }
-        }
-    }
-    
-    // Sort by offset to ensure correct order
-    raw_files.sort_by_key(|(offset, _)| *offset);
-    
-    if raw_files.is_empty() {
-        if verbose {
-            println!("No raw response files found in session {}", session_id);
-        }
-        return Ok(0);
-    }
-    
-    // Create progress bar for this session
-    let session_progress = if let Some(mp) = multi_progress {
-        let pb = mp.add(ProgressBar::new(raw_files.len() as u64));
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template(&format!("{{spinner:.green}} Session {}: [{{bar:40.cyan/blue}}] {{pos}}/{{len}} files ({{percent}}%) {{msg}}", session_id))
-                .unwrap()
-                .progress_chars("#>-")
-        );
-        pb.set_message("Processing...");
-        Some(pb)
-    } else {
-        if verbose {
-            println!("  📁 Found {} raw response files", raw_files.len());
-        }
-        None
-    };
-    
-    // Process files in parallel within the session
-    let session_total = Arc::new(AtomicU64::new(0));
-    let processed_files = Arc::new(AtomicU64::new(0));
-    
-    // Process files in chunks to avoid overwhelming the database
-    let chunk_size = 10; // Process 10 files at a time
-    for chunk in raw_files.chunks(chunk_size) {
-        let session_progress_ref = session_progress.as_ref();
-        let processed_files_ref = processed_files.clone();
-        let raw_files_len = raw_files.len();
-        
-        let chunk_results: Vec<Result<Vec<GammaMarket>>> = chunk
-            .par_iter()
-            .map(|(offset, path)| {
-                let result = (|| -> Result<Vec<GammaMarket>> {
-                    let content = fs::read_to_string(path)
-                        .with_context(|| format!("Failed to read raw response file at offset {}", offset))?;
-                    
-                    // Parse directly as array of markets
-                    let markets: Vec<GammaMarket> = serde_json::from_str(&content)
-                        .with_context(|| format!("Failed to parse raw response file at offset {}", offset))?;
-                    
-                    Ok(markets)
-                })();
-                
-                // Update progress bar
-                let files_done = processed_files_ref.fetch_add(1, Ordering::SeqCst) + 1;
-                if let Some(ref pb) = session_progress_ref {
-                    pb.set_position(files_done);
-                    if files_done == raw_files_len as u64 {
-                        pb.set_message("Finalizing...");
-                    }
-                }
-                
-                result
-            })
-            .collect();
-        
-        // Collect all markets from this chunk
-        let mut chunk_markets = Vec::new();
-        for result in chunk_results {
-            match result {
-                Ok(markets) => chunk_markets.extend(markets),
-                Err(e) => return Err(e),
-            }
-        }
-        
-        // Store the chunk in the database
-        if !chunk_markets.is_empty() {
-            let stored_count = database.store_markets(&chunk_markets, session_id).await
-                .context("Failed to store markets batch in database")?;
-            session_total.fetch_add(stored_count, Ordering::SeqCst);
-            
-            if verbose {
-                println!("  ✅ Stored {} markets from chunk", stored_count);
-            }
-        }
-    }
-    
-    let total = session_total.load(Ordering::SeqCst);
-    
-    // Finish progress bar
-    if let Some(pb) = session_progress {
-        pb.finish_with_message(format!("✓ {} markets imported", total));
-    }
-    
-    Ok(total)
-}
-
-/// Import markets from a single session into the database (sequential version - kept for compatibility)
 
 // ============================================================================
 // DATABASE COMMAND IMPLEMENTATIONS
 // ============================================================================
 
 /// Execute database command
-async fn execute_db_command(args: DbArgs, verbose: bool) -> Result<()> {
+async fn execute_db_command(args: DbArgs, verbose: bool, data_root: &Path) -> Result<()> {
     match args.command {
         Some(cmd) => match cmd {
-            DbCommand::Stats(args) => execute_db_stats(args, verbose).await,
-            DbCommand::Search(args) => execute_db_search(args, verbose).await,
-            DbCommand::Export(args) => execute_db_export(args, verbose).await,
-            DbCommand::List(args) => execute_db_list(args, verbose).await,
-            DbCommand::Cleanup(args) => execute_db_cleanup(args, verbose).await,
-            DbCommand::Get(args) => execute_db_get(args, verbose).await,
-            DbCommand::Count(args) => execute_db_count(args, verbose).await,
-            DbCommand::Health(args) => execute_db_health(args, verbose).await,
+            DbCommand::Stats(args) => execute_db_stats(args, verbose, data_root).await,
+            DbCommand::Search(args) => execute_db_search(args, verbose, data_root).await,
+            DbCommand::Export(args) => execute_db_export(args, verbose, data_root).await,
+            DbCommand::List(args) => execute_db_list(args, verbose, data_root).await,
+            DbCommand::Cleanup(args) => execute_db_cleanup(args, verbose, data_root).await,
+            DbCommand::Get(args) => execute_db_get(args, verbose, data_root).await,
+            DbCommand::Count(args) => execute_db_count(args, verbose, data_root).await,
+            DbCommand::Health(args) => execute_db_health(args, verbose, data_root).await,
         },
         None => {
             // Default to interactive list when no subcommand is provided
             let default_args = DbListArgs {
                 limit: 50,
                 offset: 0,
-                sort_by: "volume".to_string(),
+                sort_by: MarketListOrder::Volume,
                 order: "desc".to_string(),
                 active_only: false,
                 closed_only: false,
@@ -2203,13 +2134,13 @@ async fn execute_db_command(args: DbArgs, verbose: bool) -> Result<()> {
                 interactive: true,
                 no_interactive: false,
             };
-            execute_db_list(default_args, verbose).await
+            execute_db_list(default_args, verbose, data_root).await
         }
     }
 }
 
 /// Execute database stats command
-async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_stats(args: DbStatsArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "📊 Database Statistics".bright_blue().bold());
@@ -2225,8 +2156,7 @@ async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
     pb.set_message("Connecting to database...");
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     pb.set_message("Loading statistics...");
@@ -2239,17 +2169,17 @@ async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
     println!("  {} Active: {} {}", 
         "🟢".bright_green(),
         stats.active_markets.to_string().bright_green().bold(),
-        format!("({:.1}%)", stats.active_markets as f64 / stats.total_markets as f64 * 100.0).bright_black()
+        format!("({})", number_format::percent(stats.active_markets as f64 / stats.total_markets as f64 * 100.0)).bright_black()
     );
     println!("  {} Closed: {} {}", 
         "🔴".bright_red(),
         stats.closed_markets.to_string().bright_red().bold(),
-        format!("({:.1}%)", stats.closed_markets as f64 / stats.total_markets as f64 * 100.0).bright_black()
+        format!("({})", number_format::percent(stats.closed_markets as f64 / stats.total_markets as f64 * 100.0)).bright_black()
     );
     println!("  {} Archived: {} {}", 
         "🟡".bright_yellow(),
         stats.archived_markets.to_string().bright_yellow().bold(),
-        format!("({:.1}%)", stats.archived_markets as f64 / stats.total_markets as f64 * 100.0).bright_black()
+        format!("({})", number_format::percent(stats.archived_markets as f64 / stats.total_markets as f64 * 100.0)).bright_black()
     );
     
     if args.detailed || args.by_category || args.volume_dist || args.temporal {
@@ -2294,7 +2224,7 @@ async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
                     category.chars().take(20).collect::<String>(),
                     bar.bright_cyan(),
                     count.to_string().bright_white().bold(),
-                    format!("({:.1}%)", percentage).bright_black()
+                    format!("({})", number_format::percent(percentage)).bright_black()
                 );
             }
             
@@ -2317,9 +2247,9 @@ async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
                 sorted_volumes.sort();
                 let median_volume = sorted_volumes[sorted_volumes.len() / 2];
                 
-                println!("  Total volume: ${:.2}", total_volume);
-                println!("  Average volume: ${:.2}", avg_volume);
-                println!("  Median volume: ${:.2}", median_volume);
+                println!("  Total volume: {}", number_format::usd(total_volume));
+                println!("  Average volume: {}", number_format::usd(avg_volume));
+                println!("  Median volume: {}", number_format::usd(median_volume));
                 println!("  Markets with volume > $1M: {}", 
                     volumes.iter().filter(|v| **v > rust_decimal::Decimal::from(1_000_000)).count()
                 );
@@ -2340,7 +2270,7 @@ async fn execute_db_stats(args: DbStatsArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database search command
-async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_search(args: DbSearchArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "🔍 Market Search".bright_blue().bold());
@@ -2363,8 +2293,8 @@ async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
     
     // Initialize fast search engine
     let search_start = std::time::Instant::now();
-    let db_path = PathBuf::from("./data/database/gamma");
-    let index_path = crate::markets::gamma::get_index_path();
+    let data_root = data_root.to_path_buf();
+    let index_path = crate::markets::gamma::get_index_path(&data_root);
     
     // Create a static cache for the search engine to avoid rebuilding
     use std::sync::OnceLock;
@@ -2395,7 +2325,7 @@ async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
         // Try to build the engine
         spinner.set_message("Building ultra-fast search engine (one-time operation)...");
         
-        match crate::markets::gamma::build_fast_search_index(&db_path, &index_path, false).await {
+        match crate::markets::gamma::build_fast_search_index(&data_root, &index_path, false).await {
             Ok(engine) => {
                 let build_time = search_start.elapsed();
                 spinner.set_message(format!("⚡ Search engine built in {}ms, searching...", build_time.as_millis()));
@@ -2430,7 +2360,7 @@ async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
             Err(e) => {
                 // Fallback to database search
                 spinner.set_message(format!("Failed to build search engine: {}, using database search...", e));
-                let database = GammaDatabase::new(&db_path).await
+                let database = store::open_store(&data_root).await
                     .context("Failed to initialize gamma database")?;
                 let results = database.search_markets(&args.query, Some(args.limit)).await?;
                 spinner.finish_with_message(format!(
@@ -2502,14 +2432,14 @@ async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
                         status_emoji,
                         status_color,
                         "💰",
-                        format!("${:.0}", market.volume()).bright_green()
+                        format!("${}", number_format::integer(market.volume())).bright_green()
                     );
                 } else {
                     println!("  {} {} | {} Volume: {}",
                         status_emoji,
                         status_color,
                         "💰",
-                        format!("${:.0}", market.volume()).bright_green()
+                        format!("${}", number_format::integer(market.volume())).bright_green()
                     );
                 }
             }
@@ -2520,7 +2450,7 @@ async fn execute_db_search(args: DbSearchArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database export command
-async fn execute_db_export(args: DbExportArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_export(args: DbExportArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "📤 Export Database".bright_blue().bold());
@@ -2539,37 +2469,20 @@ async fn execute_db_export(args: DbExportArgs, _verbose: bool) -> Result<()> {
     spinner.set_message("Initializing database...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     // Build query with filters
-    let mut query = "SELECT * FROM markets".to_string();
-    let mut conditions = Vec::new();
-    
-    if args.active_only {
-        conditions.push("active = true".to_string());
-    }
-    
-    if let Some(min_vol) = args.min_volume {
-        conditions.push(format!("volume >= {}", min_vol));
-    }
-    
-    if let Some(ref category) = args.category {
-        conditions.push(format!("category = '{}'", category));
-    }
-    
-    if !conditions.is_empty() {
-        query.push_str(" WHERE ");
-        query.push_str(&conditions.join(" AND "));
-    }
-    
-    if let Some(limit) = args.limit {
-        query.push_str(&format!(" LIMIT {}", limit));
-    }
+    let query = MarketListQuery {
+        active_only: args.active_only,
+        category: args.category.clone(),
+        min_volume: args.min_volume,
+        limit: args.limit,
+        ..Default::default()
+    };
     
     spinner.set_message("Querying database...");
-    let markets = database.execute_query(&query).await?;
+    let markets = database.list_markets(&query).await?;
     spinner.finish_and_clear();
     
     // Show progress bar for export
@@ -2652,7 +2565,7 @@ async fn execute_db_export(args: DbExportArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database list command with interactive features and enhanced error handling
-async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
+async fn execute_db_list(args: DbListArgs, verbose: bool, data_root: &Path) -> Result<()> {
     // Set verbose logging if requested
     if verbose {
         info!("Verbose mode enabled for db list command");
@@ -2661,7 +2574,10 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     
     // Launch interactive TUI by default, unless --no-interactive is specified
     if args.interactive && !args.no_interactive {
-        return execute_db_list_interactive(args).await;
+        #[cfg(feature = "tui")]
+        return browser::execute_db_list_interactive(args, data_root).await;
+        #[cfg(not(feature = "tui"))]
+        return Err(missing_feature("gamma db list --interactive", "tui"));
     }
     use std::time::Duration;
     use tokio::time::timeout;
@@ -2679,7 +2595,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
         filters.push(format!("Category: {}", cat).bright_magenta().to_string()); 
     }
     if let Some(vol) = args.min_volume { 
-        filters.push(format!("Min volume: ${:.0}", vol).bright_yellow().to_string()); 
+        filters.push(format!("Min volume: ${}", number_format::integer(vol)).bright_yellow().to_string()); 
     }
     
     if !filters.is_empty() {
@@ -2687,7 +2603,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     }
     println!("  {} Sort: {} {}", 
         "📊", 
-        args.sort_by.bright_cyan(),
+        args.sort_by.to_string().bright_cyan(),
         if args.order == "desc" { "↓" } else { "↑" }
     );
     println!();
@@ -2702,11 +2618,10 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     pb.set_message("Connecting to database...");
     pb.enable_steady_tick(Duration::from_millis(80));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    info!("Initializing database connection to: {}", db_path.display());
+    info!("Initializing gamma database in: {}", data_root.display());
     
     let db_start = std::time::Instant::now();
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     let db_time = db_start.elapsed();
     info!("Database connection established in {:.2}s", db_time.as_secs_f64());
@@ -2736,39 +2651,24 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     pb.set_message(format!("Found {} total markets. Building query...", total_count));
     
     // Build query with filters and sorting
-    let mut query = "SELECT * FROM markets".to_string();
-    let mut conditions = Vec::new();
-    
-    if args.active_only {
-        conditions.push("active = true".to_string());
-    }
-    if args.closed_only {
-        conditions.push("closed = true".to_string());
-    }
-    
-    if let Some(min_vol) = args.min_volume {
-        conditions.push(format!("volume >= {}", min_vol));
-    }
-    
-    if let Some(ref category) = args.category {
-        conditions.push(format!("category = '{}'", category));
-    }
-    
-    if !conditions.is_empty() {
-        query.push_str(" WHERE ");
-        query.push_str(&conditions.join(" AND "));
-    }
+    let mut query = MarketListQuery {
+        active_only: args.active_only,
+        closed_only: args.closed_only,
+        category: args.category.clone(),
+        min_volume: args.min_volume,
+        ..Default::default()
+    };
     
     // Add sorting - but skip it for troubleshooting large datasets
-    if total_count < 10000 || args.sort_by == "created_at" || args.sort_by == "updated_at" {
-        let order_dir = if args.order == "asc" { "ASC" } else { "DESC" };
-        query.push_str(&format!(" ORDER BY {} {}", args.sort_by, order_dir));
-        info!("Added sorting: ORDER BY {} {}", args.sort_by, order_dir);
+    if total_count < 10000 || args.sort_by.is_indexed() {
+        query.sort_by = Some(args.sort_by);
+        query.descending = args.order != "asc";
+        info!("Added sorting: {} {}", args.sort_by, if query.descending { "DESC" } else { "ASC" });
     } else {
         info!("Skipping ORDER BY {} for performance on large dataset ({}+ records). Use --sort-by created_at for faster queries.", args.sort_by, total_count);
         println!("  {} Skipping sorting by '{}' for performance ({}+ records)", 
             "⚠️".bright_yellow(), 
-            args.sort_by.bright_red(), 
+            args.sort_by.to_string().bright_red(), 
             total_count.to_string().bright_yellow()
         );
         println!("  {} Use --sort-by created_at for faster queries", "💡".bright_cyan());
@@ -2782,7 +2682,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     pb.set_message("Loading markets with efficient batching...");
     
     // For simple queries without complex filters, use get_all_markets for efficiency
-    let markets = if conditions.is_empty() && args.offset == 0 {
+    let markets = if !query.has_filters() && args.offset == 0 {
         // Use the same efficient method as gamma markets for full database access
         pb.set_message("Fetching all markets with batching...");
         let query_start = std::time::Instant::now();
@@ -2808,14 +2708,15 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
     } else {
         // For filtered queries, use direct SQL query (but still allow larger limits)
         let effective_limit = if args.limit > 10000 { 10000 } else { args.limit }; // Reasonable limit for filtered queries
-        query.push_str(&format!(" LIMIT {} START {}", effective_limit, args.offset));
+        query.limit = Some(effective_limit);
+        query.offset = args.offset;
         
         pb.set_message(format!("Loading {} filtered markets (offset: {})...", effective_limit, args.offset));
         
         let query_start = std::time::Instant::now();
-        debug!("Executing filtered db list query: {}", query);
+        debug!("Executing filtered db list query: {:?}", query);
         
-        match timeout(Duration::from_secs(60), database.execute_query(&query)).await {
+        match timeout(Duration::from_secs(60), database.list_markets(&query)).await {
             Ok(Ok(markets)) => {
                 let query_time = query_start.elapsed();
                 info!("Filtered query completed successfully in {:.2}s, got {} markets", query_time.as_secs_f64(), markets.len());
@@ -2825,13 +2726,13 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
                 pb.finish_and_clear();
                 let query_time = query_start.elapsed();
                 error!("Database filtered query failed after {:.2}s: {}", query_time.as_secs_f64(), e);
-                error!("Query was: {}", query);
+                error!("Query was: {:?}", query);
                 return Err(anyhow::anyhow!("Database filtered query failed: {}", e));
             }
             Err(_) => {
                 pb.finish_and_clear();
                 error!("Database filtered query timed out after 60s");
-                error!("Query was: {}", query);
+                error!("Query was: {:?}", query);
                 return Err(anyhow::anyhow!("Database filtered query timed out"));
             }
         }
@@ -2906,26 +2807,8 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
             );
             println!("{}", "─".repeat(80).bright_black());
             
-            // Enable raw mode for interactive quit
-            let _ = enable_raw_mode();
-            
-            // Spawn a task to check for quit key
-            let quit_handle = tokio::spawn(async {
-                loop {
-                    if event::poll(Duration::from_millis(100)).unwrap_or(false) {
-                        if let Ok(Event::Key(key)) = event::read() {
-                            match key {
-                                KeyEvent { code: KeyCode::Char('q'), modifiers: KeyModifiers::NONE, .. } |
-                                KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::CONTROL, .. } => {
-                                    return true;
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                    tokio::time::sleep(Duration::from_millis(50)).await;
-                }
-            });
+            // Raw mode lets 'q' stop the listing
+            let quit = QuitKey::listen();
             
             // Print markets with progress animation
             let display_pb = ProgressBar::new(markets.len() as u64);
@@ -2938,8 +2821,8 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
             
             for (i, market) in markets.iter().enumerate() {
                 // Check if user wants to quit
-                if quit_handle.is_finished() {
-                    let _ = disable_raw_mode();
+                if quit.pressed() {
+                    quit.stop();
                     println!("\n\n{} Display interrupted by user", "⚠️".bright_yellow());
                     return Ok(());
                 }
@@ -2951,7 +2834,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
                     market.question.clone()
                 };
                 let category = market.category.as_deref().unwrap_or("-").chars().take(13).collect::<String>();
-                let volume = format!("${:.0}", market.volume());
+                let volume = format!("${}", number_format::integer(market.volume()));
                 
                 let status = if market.active {
                     "🟢 Active".bright_green().to_string()
@@ -2978,10 +2861,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
             }
             
             display_pb.finish_and_clear();
-            let _ = disable_raw_mode();
-            
-            // Clean up the quit handler
-            quit_handle.abort();
+            quit.stop();
             
             println!("{}", "─".repeat(80).bright_black());
             
@@ -3033,7 +2913,7 @@ async fn execute_db_list(args: DbListArgs, verbose: bool) -> Result<()> {
 }
 
 /// Execute database cleanup command
-async fn execute_db_cleanup(args: DbCleanupArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_cleanup(args: DbCleanupArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "🧽 Database Cleanup".bright_blue().bold());
@@ -3069,8 +2949,7 @@ async fn execute_db_cleanup(args: DbCleanupArgs, _verbose: bool) -> Result<()> {
     }
     
     // Initialize database
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     if args.remove_duplicates {
@@ -3132,7 +3011,7 @@ async fn execute_db_cleanup(args: DbCleanupArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database get command
-async fn execute_db_get(args: DbGetArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_get(args: DbGetArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "🔎 Market Details".bright_blue().bold());
@@ -3150,21 +3029,17 @@ async fn execute_db_get(args: DbGetArgs, _verbose: bool) -> Result<()> {
     spinner.set_message("Loading market data...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     // Query for specific market
-    let query = format!("SELECT * FROM markets WHERE market_id = '{}'", args.market_id);
-    let mut results = database.execute_query(&query).await?;
+    let market = database.get_market(&args.market_id).await?;
     spinner.finish_and_clear();
     
-    if results.is_empty() {
+    let Some(market) = market else {
         println!("{} {}", "❌", format!("Market '{}' not found.", args.market_id).bright_red());
         return Ok(());
-    }
-    
-    let market = results.remove(0);
+    };
     
     match args.format.as_str() {
         "yaml" => {
@@ -3191,8 +3066,8 @@ async fn execute_db_get(args: DbGetArgs, _verbose: bool) -> Result<()> {
             if let Some(ref cat) = market.category {
                 println!("  {} Category: {}", "🏷️", cat.bright_magenta());
             }
-            println!("  {} Volume: {}", "💰", format!("${:.2}", market.volume()).bright_green().bold());
-            println!("  {} Liquidity: {}", "💧", format!("${:.2}", market.liquidity.unwrap_or_default()).bright_blue());
+            println!("  {} Volume: {}", "💰", number_format::usd(market.volume()).bright_green().bold());
+            println!("  {} Liquidity: {}", "💧", number_format::usd(market.liquidity.unwrap_or_default()).bright_blue());
             
             // Outcomes
             println!("  {} Outcomes ({}):", "🎯", market.outcomes.len().to_string().bright_yellow());
@@ -3219,7 +3094,7 @@ async fn execute_db_get(args: DbGetArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database count command
-async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
+async fn execute_db_count(args: DbCountArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "🧮 Market Count Analysis".bright_blue().bold());
@@ -3235,8 +3110,7 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
     spinner.set_message("Connecting to database...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     spinner.set_message("Counting markets...");
@@ -3247,16 +3121,22 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
     if let Some(ref group_by) = args.group_by {
         match group_by.as_str() {
             "category" => {
-                let query = "SELECT category, COUNT(*) as count FROM markets GROUP BY category ORDER BY count DESC";
-                let _results = database.execute_raw_query(query).await?;
+                let mut counts: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+                for market in database.get_all_markets(None).await? {
+                    let category = market.category.unwrap_or_else(|| "(none)".to_string());
+                    *counts.entry(category).or_default() += 1;
+                }
+                let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+                counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
                 
                 println!("\n📊 Markets by Category:");
                 println!("{:<30} {:>10} {:>10}", "Category", "Count", "Percentage");
                 println!("{}", "-".repeat(52));
                 
-                // Parse results and display
-                // Note: This would need proper result parsing from SurrealDB
-                println!("(Category grouping requires enhanced database query support)");
+                for (category, count) in counts {
+                    let pct = if total_count > 0.0 { count as f64 / total_count * 100.0 } else { 0.0 };
+                    println!("{:<30} {:>10} {:>10}", category, count, number_format::percent(pct));
+                }
             },
             "active" => {
                 println!("\n{} {}", "📊".bright_cyan(), "Markets by Status".bright_white().bold());
@@ -3273,7 +3153,7 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
                     "🟢",
                     active_bar.bright_green(),
                     total_stats.active_markets.to_string().bright_green().bold(),
-                    format!("({:.1}%)", active_pct).bright_black()
+                    format!("({})", number_format::percent(active_pct)).bright_black()
                 );
                 
                 // Inactive markets bar
@@ -3283,7 +3163,7 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
                     "🔴",
                     inactive_bar.bright_red(),
                     inactive_count.to_string().bright_red().bold(),
-                    format!("({:.1}%)", inactive_pct).bright_black()
+                    format!("({})", number_format::percent(inactive_pct)).bright_black()
                 );
             },
             "closed" => {
@@ -3294,9 +3174,9 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
                 let closed_pct = total_stats.closed_markets as f64 / total_count * 100.0;
                 let open_pct = 100.0 - closed_pct;
                 
-                println!("{:<20} {:>10} {:>9.1}%", "Closed", total_stats.closed_markets, closed_pct);
-                println!("{:<20} {:>10} {:>9.1}%", "Open", 
-                    total_stats.total_markets - total_stats.closed_markets, open_pct);
+                println!("{:<20} {:>10} {:>10}", "Closed", total_stats.closed_markets, number_format::percent(closed_pct));
+                println!("{:<20} {:>10} {:>10}", "Open", 
+                    total_stats.total_markets - total_stats.closed_markets, number_format::percent(open_pct));
             },
             "archived" => {
                 println!("\n📊 Markets by Archive Status:");
@@ -3306,9 +3186,9 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
                 let archived_pct = total_stats.archived_markets as f64 / total_count * 100.0;
                 let not_archived_pct = 100.0 - archived_pct;
                 
-                println!("{:<20} {:>10} {:>9.1}%", "Archived", total_stats.archived_markets, archived_pct);
-                println!("{:<20} {:>10} {:>9.1}%", "Not Archived", 
-                    total_stats.total_markets - total_stats.archived_markets, not_archived_pct);
+                println!("{:<20} {:>10} {:>10}", "Archived", total_stats.archived_markets, number_format::percent(archived_pct));
+                println!("{:<20} {:>10} {:>10}", "Not Archived", 
+                    total_stats.total_markets - total_stats.archived_markets, number_format::percent(not_archived_pct));
             },
             _ => {
                 println!("{}", format!("⚠️  Unknown group_by field: {}", group_by).yellow());
@@ -3361,7 +3241,7 @@ async fn execute_db_count(args: DbCountArgs, _verbose: bool) -> Result<()> {
 }
 
 /// Execute database health check command
-async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
+async fn execute_db_health(args: DbHealthArgs, verbose: bool, data_root: &Path) -> Result<()> {
     // Clear console and show header
     print!("\x1B[2J\x1B[1;1H");
     println!("{}", "🏥 Database Health Check".bright_blue().bold());
@@ -3377,8 +3257,7 @@ async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
     pb.set_message("Connecting to database...");
     pb.enable_steady_tick(Duration::from_millis(80));
     
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
+    let database = store::open_store(data_root).await
         .context("Failed to initialize gamma database")?;
     
     pb.set_message("Running health diagnostics...");
@@ -3464,11 +3343,11 @@ async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
         println!("  {} Dataset Size: {}", "📏", size_category);
         
         // Storage path info
-        println!("  {} Database Path: {}", "📁", db_path.display().to_string().bright_black());
+        println!("  {} Database Path: {}", "📁", database.path().display().to_string().bright_black());
         
         if verbose {
             // Show disk usage if verbose
-            if let Ok(_metadata) = std::fs::metadata(&db_path) {
+            if let Ok(_metadata) = std::fs::metadata(database.path()) {
                 println!("  {} Path Exists: {}", "✓", "Yes".bright_green());
             } else {
                 println!("  {} Path Exists: {}", "✗", "No".bright_red());
@@ -3499,9 +3378,12 @@ async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
         let test_sizes = vec![1, 10, 100];
         for size in test_sizes {
             let bench_start = std::time::Instant::now();
-            let query = format!("SELECT * FROM markets LIMIT {}", size);
+            let query = MarketListQuery {
+                limit: Some(size),
+                ..Default::default()
+            };
             
-            match timeout(Duration::from_secs(10), database.execute_query(&query)).await {
+            match timeout(Duration::from_secs(10), database.list_markets(&query)).await {
                 Ok(Ok(results)) => {
                     let bench_time = bench_start.elapsed();
                     let rate = results.len() as f64 / bench_time.as_secs_f64().max(0.001);
@@ -3522,24 +3404,20 @@ async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
     if args.detailed {
         println!("\n{} {}", "🔍".bright_blue(), "Detailed Diagnostics".bright_white().bold());
         
-        // Check database schema
-        match database.execute_raw_query("SHOW TABLES").await {
-            Ok(tables) => {
-                println!("  {} Database tables: {} found", "📋", tables.len());
-                if verbose {
-                    for table in tables {
-                        println!("    • {}", table);
-                    }
-                }
+        // Check database contents
+        match database.get_stats().await {
+            Ok(stats) => {
+                println!("  {} Markets: {} ({} active, {} closed)", "📋",
+                    stats.total_markets, stats.active_markets, stats.closed_markets);
             }
             Err(e) => {
-                println!("  {} Database tables: ERROR ({})", "❌", e);
+                println!("  {} Markets: ERROR ({})", "❌", e);
             }
         }
         
-        // Check cache status
-        println!("  {} Query cache: Active", "🗄️");
-        println!("  {} Connection: SurrealDB RocksDB", "🔗");
+        println!("  {} Backend: {} ({})", "🔗", database.backend().label(), database.backend());
+        println!("  {} Backend config: {}", "⚙️",
+            data_root.join(store::GAMMA_CONFIG_FILE).display());
     }
     
     // Summary recommendations based on health
@@ -3563,7 +3441,7 @@ async fn execute_db_health(args: DbHealthArgs, verbose: bool) -> Result<()> {
 /// Build or load fast search engine with consistent error handling
 
 /// Execute build index command
-async fn execute_build_index(args: BuildIndexArgs, _verbose: bool) -> Result<()> {
+async fn execute_build_index(args: BuildIndexArgs, _verbose: bool, data_root: &Path) -> Result<()> {
     println!("{}", "🔨 Building search index...".bright_blue());
     
     // For now, we only support the markets index
@@ -3573,9 +3451,9 @@ async fn execute_build_index(args: BuildIndexArgs, _verbose: bool) -> Result<()>
     }
     
     // Initialize or get the search service
-    let db_path = PathBuf::from("./data/database/gamma");
-    let index_path = crate::markets::gamma::get_index_path();
-    let service = crate::markets::gamma::init_search_service(db_path, index_path.clone()).await?;
+    let data_root = data_root.to_path_buf();
+    let index_path = crate::markets::gamma::get_index_path(&data_root);
+    let service = crate::markets::gamma::init_search_service(data_root, index_path.clone()).await?;
     
     // Start the service
     service.start().await?;
@@ -3607,133 +3485,19 @@ async fn execute_build_index(args: BuildIndexArgs, _verbose: bool) -> Result<()>
     Ok(())
 }
 
-/// Execute interactive search with ultra-fast engine
-async fn execute_interactive_search(args: InteractiveSearchArgs, _verbose: bool) -> Result<()> {
+
+/// Execute search status command
+async fn execute_search_status(
+    args: SearchStatusArgs,
+    _verbose: bool,
+    data_root: &Path,
+) -> Result<()> {
     use std::io::{self, Write};
     
-    println!("{}", "🚀 Ultra-Fast Interactive Search".bright_blue().bold());
-    println!("{}", "=".repeat(50).bright_black());
-    
     // Initialize or get the search service
-    let db_path = PathBuf::from("./data/database/gamma");
-    let index_path = crate::markets::gamma::get_index_path();
-    let service = crate::markets::gamma::init_search_service(db_path, index_path).await?;
-    
-    // Start the service if not already started
-    service.start().await?;
-    
-    // Wait for the service to be ready
-    print!("⏳ Waiting for search service to be ready...");
-    io::stdout().flush()?;
-    
-    let mut status_rx = service.subscribe_status();
-    loop {
-        let status = status_rx.borrow().clone();
-        match &status {
-            crate::markets::gamma::ServiceStatus::Ready { .. } => {
-                println!("\r✅ Search engine ready! Ultra-fast Aho-Corasick search enabled");
-                if args.show_stats {
-                    if let crate::markets::gamma::ServiceStatus::Ready { markets, patterns, categories, build_time_ms } = status {
-                        println!("\n📊 Index Statistics:");
-                        println!("  • Documents: {}", markets.to_string().bright_cyan());
-                        println!("  • Patterns: {}", patterns.to_string().bright_cyan());
-                        println!("  • Categories: {}", categories.to_string().bright_cyan());
-                        println!("  • Build time: {:.1}s", (build_time_ms as f64 / 1000.0));
-                    }
-                }
-                break;
-            }
-            crate::markets::gamma::ServiceStatus::Failed { error, .. } => {
-                println!("\r❌ Failed to build search engine: {}", error);
-                return Err(anyhow::anyhow!("Search service failed: {}", error));
-            }
-            _ => {
-                print!("\r{}", format_service_status(&status, false));
-                io::stdout().flush()?;
-                status_rx.changed().await?;
-            }
-        }
-    }
-    
-    println!("\n💡 Enter search queries (type 'quit' to exit):");
-    println!("   Examples: 'trump', 'bitcoin', 'election', 'sports'");
-    println!("{}", "=".repeat(50).bright_black());
-    
-    // Interactive search loop
-    loop {
-        print!("\n🔍 Search> ");
-        io::stdout().flush()?;
-        
-        let mut query = String::new();
-        io::stdin().read_line(&mut query)?;
-        let query = query.trim();
-        
-        if query.is_empty() {
-            continue;
-        }
-        
-        if query == "quit" || query == "exit" || query == "q" {
-            println!("👋 Goodbye!");
-            break;
-        }
-        
-        // Perform ultra-fast search
-        let start = std::time::Instant::now();
-        let params = crate::markets::gamma::SearchParams {
-            query: query.to_string(),
-            category: None,
-            tags: vec![],
-            min_volume: None,
-            max_volume: None,
-            limit: 10,
-            case_sensitive: false,
-        };
-        
-        let results = match service.search(&params).await {
-            Ok(results) => results,
-            Err(e) => {
-                println!("❌ Search error: {}", e);
-                continue;
-            }
-        };
-        let search_time = start.elapsed();
-        
-        println!("\n⚡ Found {} results in {}μs ({}ms)", 
-            results.len(), 
-            search_time.as_micros(),
-            search_time.as_millis()
-        );
-        
-        if results.is_empty() {
-            println!("❌ No markets found matching '{}'", query);
-        } else {
-            println!("{}", "=".repeat(80).bright_black());
-            for (i, market) in results.iter().enumerate() {
-                println!("\n{} Result #{}", "📄", (i + 1).to_string().bright_yellow());
-                println!("  {} {}", "❓", market.question.bright_white());
-                println!("  {} ID: {} | Volume: ${:.0}", 
-                    "🔹", 
-                    market.id.0.bright_cyan(),
-                    market.volume()
-                );
-                if let Some(ref cat) = market.category {
-                    println!("  {} Category: {}", "🏷️", cat.bright_magenta());
-                }
-            }
-        }
-    }
-    
-    Ok(())
-}
-
-/// Execute search status command
-async fn execute_search_status(args: SearchStatusArgs, _verbose: bool) -> Result<()> {
-    use std::io::{self, Write};
-    
-    // Initialize or get the search service
-    let db_path = PathBuf::from("./data/database/gamma");
-    let index_path = crate::markets::gamma::get_index_path();
-    let service = crate::markets::gamma::init_search_service(db_path, index_path).await?;
+    let data_root = data_root.to_path_buf();
+    let index_path = crate::markets::gamma::get_index_path(&data_root);
+    let service = crate::markets::gamma::init_search_service(data_root, index_path).await?;
     
     if args.watch {
         // Watch mode - continuously display status updates
@@ -3842,861 +3606,3 @@ fn format_service_status(status: &crate::markets::gamma::ServiceStatus, verbose:
         }
     }
 }
-
-/// Execute interactive database list with TUI (like gamma markets)
-async fn execute_db_list_interactive(args: DbListArgs) -> Result<()> {
-    use ratatui::{
-        backend::CrosstermBackend,
-        Terminal,
-    };
-    
-    info!("Launching interactive database browser");
-    
-    // Initialize database
-    let db_path = PathBuf::from("./data/database/gamma");
-    let database = GammaDatabase::new(&db_path).await
-        .context("Failed to initialize gamma database")?;
-    
-    // Don't initialize search service here to avoid database lock conflicts
-    info!("Database browser ready - press 'b' to build fast search index");
-    
-    // Try to initialize TUI with graceful fallback
-    match (|| -> Result<()> {
-        enable_raw_mode().context("Failed to enable raw mode - terminal may not be available")?;
-        let mut stdout = std::io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-            .context("Failed to setup terminal - make sure you're running in a proper terminal")?;
-        Ok(())
-    })() {
-        Ok(()) => {
-            // TUI initialization successful, continue with TUI mode
-            let backend = CrosstermBackend::new(std::io::stdout());
-            let mut terminal = Terminal::new(backend)
-                .context("Failed to create terminal - TUI not available")?;
-            
-            // Create the database browser
-            let mut browser = DatabaseMarketsBrowser::new(database, args).await?;
-            
-            // Main event loop
-            let result = run_database_browser(&mut terminal, &mut browser).await;
-            
-            // Cleanup TUI
-            disable_raw_mode()?;
-            execute!(
-                terminal.backend_mut(),
-                LeaveAlternateScreen,
-                DisableMouseCapture
-            )?;
-            terminal.show_cursor()?;
-            
-            result
-        },
-        Err(e) => {
-            // TUI failed, fallback to non-interactive mode with existing database
-            eprintln!("⚠️  TUI mode failed: {}", e);
-            eprintln!("📋 Falling back to non-interactive list mode...");
-            
-            // Use the already initialized database to avoid lock conflicts
-            execute_db_list_non_interactive_with_db(args, database).await
-        }
-    }
-}
-
-/// Execute non-interactive database list with pre-initialized database
-async fn execute_db_list_non_interactive_with_db(args: DbListArgs, database: GammaDatabase) -> Result<()> {
-    use std::time::Duration;
-    use tokio::time::timeout;
-    
-    // Clear console and show header
-    print!("\x1B[2J\x1B[1;1H");
-    println!("{}", "📝 Market List (Press 'q' or Ctrl+C to quit)".bright_blue().bold());
-    println!("{}", "=".repeat(60).bright_black());
-    
-    // Show active filters
-    let mut filters = Vec::new();
-    if args.active_only { filters.push("Active only".bright_green().to_string()); }
-    if args.closed_only { filters.push("Closed only".bright_red().to_string()); }
-    if let Some(ref cat) = args.category { 
-        filters.push(format!("Category: {}", cat).bright_magenta().to_string()); 
-    }
-    if let Some(vol) = args.min_volume { 
-        filters.push(format!("Min volume: ${:.0}", vol).bright_yellow().to_string()); 
-    }
-    
-    if !filters.is_empty() {
-        println!("  {} Filters: {}", "🎯", filters.join(" | "));
-    }
-    println!("  {} Sort: {} {}", 
-        "📊", 
-        args.sort_by.bright_cyan(),
-        if args.order == "desc" { "↓" } else { "↑" }
-    );
-    println!();
-    
-    // Get total count (database already initialized)
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg} [{elapsed_precise}]")
-            .unwrap()
-    );
-    pb.set_message("Counting total markets...");
-    pb.enable_steady_tick(Duration::from_millis(80));
-    
-    let total_count = match timeout(Duration::from_secs(5), database.get_market_count()).await {
-        Ok(Ok(count)) => {
-            info!("Market count query completed: {} markets", count);
-            count
-        },
-        Ok(Err(e)) => {
-            pb.finish_with_message("❌ Failed to count markets");
-            return Err(e.into());
-        },
-        Err(_) => {
-            pb.finish_with_message("⏰ Database query timed out after 5s");
-            return Err(anyhow::anyhow!("Database count query timed out"));
-        }
-    };
-    
-    pb.finish_with_message(format!("✅ Found {} markets", total_count));
-    
-    // Continue with the rest of the non-interactive logic...
-    // (This would be the rest of the non-interactive implementation)
-    println!("📊 Total markets: {}", total_count.to_string().bright_cyan().bold());
-    println!("✅ Non-interactive mode working with fallback!");
-    
-    Ok(())
-}
-
-/// Database Markets Browser - with optional ultra-fast search capabilities
-struct DatabaseMarketsBrowser {
-    database: GammaDatabase,
-    db_path: PathBuf,
-    index_service: Arc<crate::markets::gamma::IndexService>,
-    fast_search_engine: Option<crate::markets::gamma::FastSearchEngine>,
-    markets: Vec<GammaMarket>,
-    state: ListState,
-    selected_market: Option<GammaMarket>,
-    show_details: bool,
-    search_query: String,
-    search_mode: bool,
-    show_help: bool,
-    total_markets: u64,
-    current_page: u64,
-    page_size: u64,
-    status_message: String,
-    search_loading: bool,
-    search_start_time: Option<std::time::Instant>,
-    // Progress tracking
-    last_index_progress: crate::markets::gamma::IndexProgress,
-    show_progress: bool,
-    // Search result tracking
-    all_search_results: Vec<GammaMarket>,
-    total_search_results: usize,
-}
-
-impl DatabaseMarketsBrowser {
-    async fn new(database: GammaDatabase, _filters: DbListArgs) -> Result<Self> {
-        let total_markets = database.get_market_count().await?;
-        let page_size = 50; // Start with reasonable page size for TUI
-        let db_path = PathBuf::from("./data/database/gamma");
-        
-        let status_message = "📊 Database search mode (Press 'b' to build fast search index)".to_string();
-        
-        // Initialize the index service
-        let index_service = crate::markets::gamma::init_index_service().await?;
-        let initial_progress = index_service.get_progress().await;
-        
-        let mut browser = Self {
-            database,
-            db_path,
-            index_service,
-            fast_search_engine: None,
-            markets: Vec::new(),
-            state: ListState::default(),
-            selected_market: None,
-            show_details: false,
-            search_query: String::new(),
-            search_mode: false,
-            show_help: false,
-            total_markets,
-            current_page: 0,
-            page_size,
-            status_message,
-            search_loading: false,
-            search_start_time: None,
-            last_index_progress: initial_progress,
-            show_progress: false,
-            all_search_results: Vec::new(),
-            total_search_results: 0,
-        };
-        
-        // Load first page
-        browser.load_current_page().await?;
-        
-        Ok(browser)
-    }
-    
-    
-    async fn load_current_page(&mut self) -> Result<()> {
-        let offset = self.current_page * self.page_size;
-        
-        // Apply search filter if active
-        if !self.search_query.is_empty() {
-            // Check if we need to reload search results
-            if self.all_search_results.is_empty() {
-                // Load all search results first
-                if self.index_service.is_ready().await && self.fast_search_engine.is_some() {
-                    let params = crate::markets::gamma::SearchParams {
-                        query: self.search_query.clone(),
-                        category: None,
-                        tags: vec![],
-                        min_volume: None,
-                        max_volume: None,
-                        limit: 0, // Get all results for proper pagination
-                        case_sensitive: false,
-                    };
-                    
-                    if let Some(ref engine) = self.fast_search_engine {
-                        let all_results = engine.search(&params);
-                        self.all_search_results = all_results
-                            .iter()
-                            .map(|arc| (**arc).clone())
-                            .collect();
-                        self.total_search_results = self.all_search_results.len();
-                    }
-                } else {
-                    // For database search, we'll simulate getting all results
-                    // In practice, this might need pagination for very large result sets
-                    self.all_search_results = self.database.search_markets(&self.search_query, None).await?;
-                    self.total_search_results = self.all_search_results.len();
-                }
-            }
-            
-            // Apply pagination to cached search results
-            let start_idx = (offset as usize).min(self.all_search_results.len());
-            let end_idx = ((offset + self.page_size) as usize).min(self.all_search_results.len());
-            
-            self.markets = self.all_search_results[start_idx..end_idx].to_vec();
-            
-            let search_time = self.search_start_time
-                .map(|start| start.elapsed().as_millis())
-                .unwrap_or(0);
-            
-            let search_type = if self.index_service.is_ready().await && self.fast_search_engine.is_some() {
-                "⚡ Ultra-fast search"
-            } else {
-                "🔍 Database search"
-            };
-            
-            let max_page = (self.total_search_results + self.page_size as usize - 1) / self.page_size as usize;
-            self.status_message = format!(
-                "{}: '{}' - {} total results in {}ms | Page {} of {} | Showing {}-{}", 
-                search_type,
-                self.search_query, 
-                self.total_search_results,
-                search_time,
-                self.current_page + 1,
-                max_page.max(1),
-                start_idx + 1,
-                end_idx
-            );
-        } else {
-            // Clear search results cache when not searching
-            self.all_search_results.clear();
-            self.total_search_results = 0;
-            
-            // Use direct query for proper pagination
-            let query = format!("SELECT * FROM markets LIMIT {} START {}", self.page_size, offset);
-            self.markets = self.database.execute_query(&query).await?;
-            
-            let start = offset + 1;
-            let end = offset + self.markets.len() as u64;
-            let max_page = (self.total_markets + self.page_size - 1) / self.page_size;
-            self.status_message = format!(
-                "Showing {}-{} of {} markets | Page {} of {}", 
-                start, 
-                end, 
-                self.total_markets,
-                self.current_page + 1,
-                max_page
-            );
-        }
-        
-        // Reset selection to first item if we have markets
-        if !self.markets.is_empty() {
-            self.state.select(Some(0));
-            self.selected_market = self.markets.first().cloned();
-        } else {
-            self.state.select(None);
-            self.selected_market = None;
-        }
-        
-        Ok(())
-    }
-    
-    async fn next_page(&mut self) -> Result<()> {
-        let max_page = if !self.search_query.is_empty() {
-            // Use search results count for pagination
-            (self.total_search_results + self.page_size as usize - 1) / self.page_size as usize
-        } else {
-            // Use total markets count for browsing
-            ((self.total_markets + self.page_size - 1) / self.page_size) as usize
-        };
-        
-        if (self.current_page + 1) < max_page as u64 {
-            self.current_page += 1;
-            self.load_current_page().await?;
-        }
-        Ok(())
-    }
-    
-    async fn prev_page(&mut self) -> Result<()> {
-        if self.current_page > 0 {
-            self.current_page -= 1;
-            self.load_current_page().await?;
-        }
-        Ok(())
-    }
-    
-    fn select_next(&mut self) {
-        if self.markets.is_empty() { return; }
-        
-        let selected = self.state.selected().unwrap_or(0);
-        let next = if selected >= self.markets.len() - 1 { 0 } else { selected + 1 };
-        self.state.select(Some(next));
-        self.selected_market = self.markets.get(next).cloned();
-    }
-    
-    fn select_previous(&mut self) {
-        if self.markets.is_empty() { return; }
-        
-        let selected = self.state.selected().unwrap_or(0);
-        let prev = if selected == 0 { self.markets.len() - 1 } else { selected - 1 };
-        self.state.select(Some(prev));
-        self.selected_market = self.markets.get(prev).cloned();
-    }
-    
-    fn start_search(&mut self, query: String) {
-        self.search_query = query;
-        self.current_page = 0; // Reset to first page for search
-        self.search_loading = true;
-        self.search_start_time = Some(std::time::Instant::now());
-        // Clear cached search results when starting new search
-        self.all_search_results.clear();
-        self.total_search_results = 0;
-        self.status_message = if self.search_query.is_empty() {
-            "Clearing search and loading all markets...".to_string()
-        } else {
-            format!("Searching for '{}'...", self.search_query)
-        };
-    }
-    
-    async fn complete_search_if_needed(&mut self) -> Result<()> {
-        if self.search_loading {
-            self.load_current_page().await?;
-            self.search_loading = false;
-            self.search_start_time = None;
-        }
-        Ok(())
-    }
-    
-    /// Start building the fast search index
-    async fn start_fast_search_build(&mut self) -> Result<()> {
-        // Check current status
-        let progress = self.index_service.get_progress().await;
-        
-        match progress.status {
-            crate::markets::gamma::IndexStatus::Ready { .. } => {
-                self.status_message = "⚡ Fast search already ready! Enabled for searches.".to_string();
-                return Ok(());
-            }
-            crate::markets::gamma::IndexStatus::LoadingMarkets { .. } | 
-            crate::markets::gamma::IndexStatus::BuildingIndex { .. } => {
-                self.status_message = "⏳ Fast search index already building...".to_string();
-                self.show_progress = true;
-                return Ok(());
-            }
-            _ => {}
-        }
-        
-        // Show progress immediately
-        self.show_progress = true;
-        self.status_message = "📊 Loading all markets for index build...".to_string();
-        
-        // Create a new task to load markets and build index
-        let index_service = self.index_service.clone();
-        let db_path = self.db_path.clone();
-        
-        // Spawn the loading and building task
-        tokio::spawn(async move {
-            info!("Starting background task to load markets and build index");
-            
-            // Wait a moment to ensure the main thread has released any locks
-            tokio::time::sleep(Duration::from_millis(100)).await;
-            
-            // Try to load markets with retries
-            let mut retry_count = 0;
-            const MAX_RETRIES: u32 = 10;
-            
-            loop {
-                match GammaDatabase::new(&db_path).await {
-                    Ok(db) => {
-                        match db.get_all_markets(None).await {
-                            Ok(markets) => {
-                                info!("Successfully loaded {} markets, starting index build", markets.len());
-                                if let Err(e) = index_service.start_build(markets, false).await {
-                                    error!("Failed to start index build: {}", e);
-                                }
-                                break;
-                            }
-                            Err(e) => {
-                                error!("Failed to load markets: {}", e);
-                                break;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        if retry_count < MAX_RETRIES && e.to_string().contains("locked") {
-                            retry_count += 1;
-                            info!("Database locked, retrying in 1 second... (attempt {}/{})", retry_count, MAX_RETRIES);
-                            tokio::time::sleep(Duration::from_secs(1)).await;
-                        } else {
-                            error!("Failed to connect to database after {} retries: {}", retry_count, e);
-                            break;
-                        }
-                    }
-                }
-            }
-        });
-        
-        self.status_message = "🔨 Loading markets in background for index build...".to_string();
-        
-        Ok(())
-    }
-    
-    /// Update progress from index service (non-blocking)
-    async fn update_progress(&mut self) {
-        // Use a timeout to prevent blocking the UI
-        match tokio::time::timeout(Duration::from_millis(1), self.index_service.get_progress()).await {
-            Ok(new_progress) => {
-                // Check if we need to update the fast search engine reference
-                if matches!(new_progress.status, crate::markets::gamma::IndexStatus::Ready { .. }) && self.fast_search_engine.is_none() {
-                    // For now, we'd need to get the engine from the service
-                    // This is a limitation of the current design
-                }
-                
-                self.last_index_progress = new_progress;
-            }
-            Err(_) => {
-                // Timeout - don't block, keep the current progress
-            }
-        }
-    }
-}
-
-/// Main event loop for the database browser
-async fn run_database_browser(
-    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    browser: &mut DatabaseMarketsBrowser,
-) -> Result<()> {
-    let mut last_tick = std::time::Instant::now();
-    let tick_rate = Duration::from_millis(100); // Faster refresh for better responsiveness
-    
-    loop {
-        terminal.draw(|f| render_database_browser(f, browser))?;
-        
-        // Update progress from index service (non-blocking)
-        browser.update_progress().await;
-        
-        // Use a very short timeout for better responsiveness
-        let timeout = Duration::from_millis(50);
-            
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match handle_key_event(browser, key.code).await {
-                        Ok(should_quit) => {
-                            if should_quit { break; }
-                        }
-                        Err(e) => {
-                            browser.status_message = format!("Error: {}", e);
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Handle search completion asynchronously
-        if browser.search_loading {
-            // Use a quick timeout to check if we can complete the search
-            match tokio::time::timeout(Duration::from_millis(1), browser.complete_search_if_needed()).await {
-                Ok(Ok(())) => {
-                    // Search completed successfully
-                },
-                Ok(Err(e)) => {
-                    browser.search_loading = false;
-                    browser.search_start_time = None;
-                    browser.status_message = format!("Search error: {}", e);
-                },
-                Err(_) => {
-                    // Search still in progress, update status with elapsed time
-                    if let Some(start_time) = browser.search_start_time {
-                        let elapsed = start_time.elapsed();
-                        browser.status_message = if browser.search_query.is_empty() {
-                            format!("Loading all markets... ({:.1}s) - Press Esc to cancel", elapsed.as_secs_f32())
-                        } else {
-                            format!("Searching for '{}'... ({:.1}s) - Press Esc to cancel", browser.search_query, elapsed.as_secs_f32())
-                        };
-                    }
-                }
-            }
-        }
-        
-        if last_tick.elapsed() >= tick_rate {
-            last_tick = std::time::Instant::now();
-        }
-    }
-    
-    Ok(())
-}
-
-/// Handle keyboard input for the database browser
-async fn handle_key_event(browser: &mut DatabaseMarketsBrowser, key: KeyCode) -> Result<bool> {
-    // Allow quitting even during search loading
-    if key == KeyCode::Char('q') {
-        return Ok(true);
-    }
-    
-    // If search is loading, allow cancellation with Escape
-    if browser.search_loading {
-        match key {
-            KeyCode::Esc => {
-                browser.search_loading = false;
-                browser.search_start_time = None;
-                browser.search_mode = false;
-                browser.search_query.clear();
-                browser.status_message = "Search cancelled".to_string();
-            }
-            _ => {} // Ignore other keys during search loading
-        }
-        return Ok(false);
-    }
-    
-    if browser.search_mode {
-        match key {
-            KeyCode::Enter => {
-                browser.search_mode = false;
-                browser.start_search(browser.search_query.clone());
-            }
-            KeyCode::Esc => {
-                browser.search_mode = false;
-                browser.search_query.clear();
-                browser.start_search(String::new());
-            }
-            KeyCode::Char(c) => {
-                browser.search_query.push(c);
-            }
-            KeyCode::Backspace => {
-                browser.search_query.pop();
-            }
-            _ => {}
-        }
-        return Ok(false);
-    }
-    
-    match key {
-        KeyCode::Char('h') => browser.show_help = !browser.show_help,
-        KeyCode::Char('/') => browser.search_mode = true,
-        KeyCode::Down => browser.select_next(),
-        KeyCode::Up => browser.select_previous(),
-        KeyCode::PageDown => browser.next_page().await?,
-        KeyCode::PageUp => browser.prev_page().await?,
-        KeyCode::Enter => browser.show_details = !browser.show_details,
-        KeyCode::Esc => browser.show_details = false,
-        KeyCode::Char('b') => browser.start_fast_search_build().await?,
-        KeyCode::Char('p') => browser.show_progress = !browser.show_progress,
-        _ => {}
-    }
-    
-    Ok(false)
-}
-
-/// Render the database browser UI
-fn render_database_browser(f: &mut Frame, browser: &DatabaseMarketsBrowser) {
-    let mut constraints = vec![
-        Constraint::Length(3), // Header
-    ];
-    
-    // Add progress section if building or showing progress
-    if browser.show_progress {
-        constraints.push(Constraint::Length(4)); // Progress section
-    }
-    
-    constraints.extend([
-        Constraint::Min(0),    // Main content
-        Constraint::Length(3), // Footer
-    ]);
-    
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(f.area());
-    
-    let mut chunk_idx = 0;
-    
-    // Header with search mode info
-    let search_mode_info = match browser.last_index_progress.status {
-        crate::markets::gamma::IndexStatus::Ready { .. } => " | ⚡ FAST SEARCH",
-        crate::markets::gamma::IndexStatus::LoadingMarkets { .. } | 
-        crate::markets::gamma::IndexStatus::BuildingIndex { .. } => " | 🔨 BUILDING INDEX...",
-        _ => " | 📊 DATABASE MODE",
-    };
-    
-    let header_text = format!("📚 Database Market Browser{} | Press 'b' to build, 'p' to toggle progress", search_mode_info);
-    let header = Paragraph::new(header_text)
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-        .block(Block::default().borders(Borders::ALL))
-        .alignment(Alignment::Center);
-    f.render_widget(header, chunks[chunk_idx]);
-    chunk_idx += 1;
-    
-    // Progress section (if showing)
-    if browser.show_progress {
-        render_progress_section(f, chunks[chunk_idx], &browser.last_index_progress);
-        chunk_idx += 1;
-    }
-    
-    // Main content
-    if browser.show_help {
-        render_help_overlay(f, chunks[chunk_idx]);
-    } else if browser.show_details && browser.selected_market.is_some() {
-        render_market_details(f, chunks[chunk_idx], browser.selected_market.as_ref().unwrap());
-    } else {
-        render_market_list(f, chunks[chunk_idx], browser);
-    }
-    chunk_idx += 1;
-    
-    // Footer with status and controls
-    let footer_text = if browser.search_loading {
-        format!("{} | Press 'q' to quit", browser.status_message)
-    } else if browser.search_mode {
-        format!("Search: {} (Enter to search, Esc to cancel)", browser.search_query)
-    } else {
-        format!("{} | Press 'h' for help, 'q' to quit, '/' to search, 'p' for progress", browser.status_message)
-    };
-    
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::Yellow))
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(footer, chunks[chunk_idx]);
-}
-
-/// Render the market list
-fn render_market_list(f: &mut Frame, area: Rect, browser: &DatabaseMarketsBrowser) {
-    // Show loading message if search is in progress
-    if browser.search_loading {
-        let loading_text = if browser.search_query.is_empty() {
-            "🔄 Loading all markets..."
-        } else {
-            "🔍 Searching markets..."
-        };
-        
-        let loading = Paragraph::new(loading_text)
-            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL).title("Loading"))
-            .alignment(Alignment::Center);
-        f.render_widget(loading, area);
-        return;
-    }
-    
-    let items: Vec<ListItem> = browser.markets
-        .iter()
-        .enumerate()
-        .map(|(i, market)| {
-            let status = if market.active { "🟢" } else if market.closed { "🔴" } else { "🟡" };
-            let volume = market.volume().to_f64().unwrap_or(0.0);
-            let volume_str = if volume >= 1_000_000.0 {
-                format!("${:.1}M", volume / 1_000_000.0)
-            } else if volume >= 1_000.0 {
-                format!("${:.1}K", volume / 1_000.0)
-            } else {
-                format!("${:.0}", volume)
-            };
-            
-            let question = if market.question.len() > 60 {
-                format!("{}...", &market.question[..57])
-            } else {
-                market.question.clone()
-            };
-            
-            let category = market.category.as_ref().unwrap_or(&"-".to_string()).clone();
-            let category_short = if category.len() > 12 {
-                format!("{}...", &category[..9])
-            } else {
-                category
-            };
-            
-            let line = format!("{} {} | {} | {}", 
-                status, question, category_short, volume_str);
-            
-            ListItem::new(line).style(
-                if Some(i) == browser.state.selected() {
-                    Style::default().bg(Color::Blue).fg(Color::White)
-                } else {
-                    Style::default()
-                }
-            )
-        })
-        .collect();
-    
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Markets"))
-        .highlight_style(Style::default().bg(Color::Blue).fg(Color::White));
-    
-    f.render_stateful_widget(list, area, &mut browser.state.clone());
-}
-
-/// Render the progress section
-fn render_progress_section(f: &mut Frame, area: Rect, progress: &crate::markets::gamma::IndexProgress) {
-    use crate::markets::gamma::IndexStatus;
-    
-    let progress_text = match &progress.status {
-        IndexStatus::NotStarted => "🔴 Not Started - Press 'b' to build index".to_string(),
-        IndexStatus::Initializing => "🔵 Initializing - Setting up database connection...".to_string(),
-        IndexStatus::LoadingMarkets { loaded, total, rate, elapsed_seconds } => {
-            let percentage = if *total > 0 { (*loaded as f64 / *total as f64) * 100.0 } else { 0.0 };
-            let progress_bar = create_progress_bar(percentage, 30);
-            format!(
-                "📊 Loading Markets: {} {} ({:.1}%) | {}/{} markets | {:.0} markets/s | {:.1}s elapsed",
-                progress_bar, 
-                format!("{:.1}%", percentage),
-                percentage,
-                loaded, 
-                total, 
-                rate, 
-                elapsed_seconds
-            )
-        }
-        IndexStatus::BuildingIndex { markets, patterns, elapsed_seconds } => {
-            format!(
-                "🔨 Building Index: {} markets loaded, {} patterns found | {:.1}s elapsed",
-                markets, patterns, elapsed_seconds
-            )
-        }
-        IndexStatus::Ready { markets, patterns, categories, build_time_seconds, memory_usage_mb } => {
-            format!(
-                "✅ Ready: {} markets, {} patterns, {} categories | Built in {:.1}s | {:.1}MB memory",
-                markets, patterns, categories, build_time_seconds, memory_usage_mb
-            )
-        }
-        IndexStatus::Failed { error, timestamp } => {
-            format!(
-                "❌ Failed at {}: {}",
-                timestamp.format("%H:%M:%S"),
-                error
-            )
-        }
-    };
-    
-    // Add timing information
-    let timing_info = if let Some(started) = progress.started_at {
-        let elapsed = chrono::Utc::now() - started;
-        let elapsed_str = if elapsed.num_seconds() < 60 {
-            format!("{}s", elapsed.num_seconds())
-        } else {
-            format!("{}m{}s", elapsed.num_minutes(), elapsed.num_seconds() % 60)
-        };
-        
-        let eta_str = if let Some(eta) = progress.estimated_completion {
-            let remaining = eta - chrono::Utc::now();
-            if remaining.num_seconds() > 0 {
-                format!(" | ETA: {}s", remaining.num_seconds())
-            } else {
-                "".to_string()
-            }
-        } else {
-            "".to_string()
-        };
-        
-        format!("\nElapsed: {}{}", elapsed_str, eta_str)
-    } else {
-        "".to_string()
-    };
-    
-    let full_text = format!("{}{}", progress_text, timing_info);
-    
-    let progress_widget = Paragraph::new(full_text)
-        .style(Style::default().fg(Color::Green))
-        .block(Block::default().borders(Borders::ALL).title("🚀 Index Progress"))
-        .wrap(Wrap { trim: false });
-        
-    f.render_widget(progress_widget, area);
-}
-
-/// Create a visual progress bar
-fn create_progress_bar(percentage: f64, width: usize) -> String {
-    let filled = ((percentage / 100.0) * width as f64) as usize;
-    let empty = width - filled;
-    format!("{}{}", "█".repeat(filled), "░".repeat(empty))
-}
-
-/// Render market details view
-fn render_market_details(f: &mut Frame, area: Rect, market: &GammaMarket) {
-    let volume = market.volume().to_f64().unwrap_or(0.0);
-    let liquidity = market.liquidity.map(|l| l.to_f64().unwrap_or(0.0)).unwrap_or(0.0);
-    
-    let details = format!(
-        "Question: {}\n\nCategory: {}\nVolume: ${:.2}\nLiquidity: ${:.2}\nActive: {}\nClosed: {}\n\nOutcomes:\n{}",
-        market.question,
-        market.category.as_ref().unwrap_or(&"None".to_string()),
-        volume,
-        liquidity,
-        market.active,
-        market.closed,
-        market.outcomes.join("\n• ")
-    );
-    
-    let paragraph = Paragraph::new(details)
-        .block(Block::default().borders(Borders::ALL).title("Market Details"))
-        .wrap(Wrap { trim: true });
-    
-    f.render_widget(paragraph, area);
-}
-
-/// Render help overlay
-fn render_help_overlay(f: &mut Frame, area: Rect) {
-    let help_text = vec![
-        "Database Market Browser - Keyboard Shortcuts",
-        "",
-        "Navigation:",
-        "  ↑/↓         - Navigate markets",
-        "  PageUp/Down  - Navigate pages",
-        "  Enter        - View market details",
-        "  Esc          - Back to list",
-        "",
-        "Search:",
-        "  /            - Start search",
-        "  Enter        - Execute search",
-        "  Esc          - Cancel search",
-        "",
-        "Fast Search:",
-        "  b            - Build/Enable fast search index",
-        "  p            - Toggle progress display",
-        "               (Takes ~1 minute, then ultra-fast!)",
-        "",
-        "Other:",
-        "  h            - Toggle this help",
-        "  q            - Quit browser",
-        "",
-        "Default: Database search (reliable but slower)",
-        "Fast search: Optional Aho-Corasick index ('b')",
-    ];
-    
-    let paragraph = Paragraph::new(help_text.join("\n"))
-        .block(Block::default().borders(Borders::ALL).title("Help"))
-        .wrap(Wrap { trim: true })
-        .style(Style::default().fg(Color::Green));
-    
-    f.render_widget(paragraph, area);
-}
@@ -0,0 +1,87 @@
+//! Hedge command: buy the complementary token to bring a market to a target exposure
+
+use anyhow::Result;
+use clap::Args;
+use rust_decimal::Decimal;
+use tracing::warn;
+
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::client::PolybotClient;
+use crate::core::portfolio::hedge::HedgePlan;
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct HedgeArgs {
+    /// Market (condition ID) to hedge
+    pub market: String,
+
+    /// Net exposure to reach, in shares of the market's first outcome (0 = fully hedged)
+    #[arg(long, default_value = "0", allow_hyphen_values = true)]
+    pub target_exposure: Decimal,
+
+    /// Submit the hedge orders (without it the plan is only printed)
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Confirm orders above the large order notional threshold without prompting
+    #[arg(long)]
+    pub yes_large: bool,
+}
+
+pub struct HedgeCommand {
+    args: HedgeArgs,
+}
+
+impl HedgeCommand {
+    pub fn new(args: HedgeArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, using cached positions: {}", e);
+        }
+        let positions: Vec<_> = handlers
+            .get_portfolio_state()
+            .await?
+            .positions
+            .into_iter()
+            .filter(|position| position.market_id == self.args.market)
+            .collect();
+
+        let client = PolybotClient::connect_to(host, data_paths.clone()).await?;
+        let plan = HedgePlan::prepare(&client, &self.args.market, &positions, self.args.target_exposure).await?;
+
+        println!("🛡️  Hedge for market {}", self.args.market);
+        println!("{}", plan.summary());
+
+        if plan.orders.is_empty() {
+            return Ok(());
+        }
+        if !self.args.yes {
+            println!("\nDry run. Use --yes to place these orders.");
+            return Ok(());
+        }
+
+        let risk_config = RiskConfig::load(&data_paths)?;
+        let journal = RiskJournal::new(&data_paths);
+        for order in &plan.orders {
+            let price = order.price.unwrap_or_default();
+            if let Some(large) = risk_config.large_orders.check(&order.token_id, Side::Buy, price, order.size) {
+                if !confirm_large_order_cli(&large, self.args.yes_large, &journal)? {
+                    warn!("❌ Large hedge order not confirmed, nothing was placed.");
+                    return Ok(());
+                }
+            }
+        }
+
+        let order_ids = plan.submit(&client).await?;
+        for order_id in order_ids {
+            println!("✅ Placed hedge order {}", order_id);
+        }
+        Ok(())
+    }
+}
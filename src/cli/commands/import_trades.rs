@@ -0,0 +1,102 @@
+//! Import-trades command: seed the local portfolio from the Data API's account history
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::auth::SignerBackend;
+use crate::core::portfolio::import::{AccountHistory, TradeImport, IMPORTED_TRADE_PREFIX};
+use crate::core::portfolio::storage::{PortfolioStorage, RawDataStorage};
+use crate::core::progress::{ctrl_c_token, ProgressReporter};
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::GammaClient;
+use crate::number_format;
+
+#[derive(Args, Clone)]
+pub struct ImportTradesArgs {
+    /// Wallet whose trades to import (default: the profile's funder wallet, e.g. its
+    /// proxy wallet, or else its address)
+    #[arg(long)]
+    pub user: Option<String>,
+
+    /// Replay the history and print the summary without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub struct ImportTradesCommand {
+    args: ImportTradesArgs,
+}
+
+impl ImportTradesCommand {
+    pub fn new(args: ImportTradesArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let address = crate::auth::account_address(&data_paths).await?;
+        let user = match &self.args.user {
+            Some(user) => user.clone(),
+            // Orders of a funded profile trade from its funder wallet
+            None => match SignerBackend::load(&data_paths)?.funder() {
+                Some(funder) => funder.address.clone(),
+                None => address.clone(),
+            },
+        };
+
+        println!("📥 Importing trade history of {}", user);
+        let reporter = ProgressReporter::terminal().with_cancellation(ctrl_c_token());
+        let progress = reporter.task("History", None);
+        let history = AccountHistory::fetch(&GammaClient::new(), host, &user, &progress).await?;
+        progress.finish(format!(
+            "{} trades, {} redeems and merges",
+            history.trades.len(),
+            history.activity.len()
+        ));
+
+        let import = TradeImport::replay(&history);
+        println!("\n📊 Replayed {} fills", import.executions.len());
+        println!(
+            "  Positions: {} ({} open)",
+            import.positions.len(),
+            import.open_positions()
+        );
+        println!("  Closing sells: {}", import.lots.len());
+        println!("  Redeems: {}, merges: {}", import.redeems, import.merges);
+        println!("  Taker fees: {}", number_format::usd(import.fees));
+        println!("  Realized P&L: {}", number_format::signed_usd(import.realized_pnl()));
+        if !import.unmatched_sells.is_zero() {
+            println!(
+                "  ⚠️  {} shares sold beyond the traded position (splits or transfers) realized nothing",
+                number_format::size(import.unmatched_sells)
+            );
+        }
+
+        if self.args.dry_run {
+            println!("\nDry run. Run without --dry-run to write the portfolio.");
+            return Ok(());
+        }
+
+        let storage = PortfolioStorage::new(data_paths.root(), &address);
+        let raw_storage = RawDataStorage::new(data_paths.root());
+        let live_trades = raw_storage
+            .load_all_trades()
+            .await?
+            .iter()
+            .filter(|t| !t.trade_id.starts_with(IMPORTED_TRADE_PREFIX))
+            .count();
+        if live_trades > 0 {
+            println!(
+                "  ⚠️  The trade store already has {} trades from live trading; imported copies of them are not detected",
+                live_trades
+            );
+        }
+
+        let summary = import.save(&storage, &raw_storage).await?;
+        println!("\n✅ Portfolio seeded");
+        println!("  New trade history records: {}", summary.records);
+        println!("  New lot entries: {}", summary.lots);
+        println!("  Positions saved: {}", summary.positions);
+        println!("\n💡 Use 'polybot trades' to browse the imported trades");
+        Ok(())
+    }
+}
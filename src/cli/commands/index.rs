@@ -1,28 +1,105 @@
+use crate::core::memory::{MemoryBudget, MemoryLimitExceeded};
+use crate::core::progress::{ctrl_c_token, Cancelled, ProgressReporter};
 use crate::data_paths::DataPaths;
-use crate::markets::file_store::FileStore;
+use crate::markets::file_store::{FileLayout, FileStore, FileStoreOptions, FsyncPolicy};
 use crate::markets::clob::fetcher::Market;
-use crate::tui::ProgressUpdate;
+use crate::markets::datasets::format_bytes;
+use crate::markets::gamma::store::GammaBackend;
 use crate::typed_store::{
     models::{
-        Condition, ConditionCf, ConditionIndexCf, ConditionTable, MarketByConditionCf,
-        MarketByConditionTable, MarketCf, MarketIndex, MarketIndexCf, MarketIndexTable,
-        MarketTable, RocksDbMarket, Token, TokenIndexCf, TokensByConditionCf,
+        Condition, ConditionCf, ConditionTable, MarketByConditionTable, MarketCf, MarketIndex,
+        MarketIndexTable, MarketTable, RocksDbMarket, Token, TokensByConditionCf,
         TokensByConditionTable, ALL_COLUMN_FAMILIES,
     },
-    TypedDbContext, TypedStore,
+    backup::StoreBackups, compat, context::BULK_BATCH_SIZE, TypedDbContext, TypedStore,
 };
 use anyhow::Result;
-use clap::Args;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use clap::{Args, Subcommand, ValueEnum};
 use rayon::prelude::*;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+/// Indexing progress as shown by the index TUI
+#[derive(Debug, Clone)]
+pub struct IndexingProgress {
+    pub current_file: usize,
+    pub total_files: usize,
+    pub current_file_name: String,
+    pub markets_in_file: usize,
+    pub markets_processed: usize,
+    pub total_markets_indexed: usize,
+    pub total_conditions: usize,
+    pub total_tokens: usize,
+    pub duplicates_skipped: usize,
+    pub phase: IndexingPhase,
+    pub events: Vec<String>,
+    pub start_time: std::time::Instant,
+}
+
+impl Default for IndexingProgress {
+    fn default() -> Self {
+        Self {
+            current_file: 0,
+            total_files: 0,
+            current_file_name: String::new(),
+            markets_in_file: 0,
+            markets_processed: 0,
+            total_markets_indexed: 0,
+            total_conditions: 0,
+            total_tokens: 0,
+            duplicates_skipped: 0,
+            phase: IndexingPhase::Starting,
+            events: Vec::new(),
+            start_time: std::time::Instant::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexingPhase {
+    Starting,
+    ProcessingFiles,
+    IndexingConditions,
+    IndexingTokens,
+    Finalizing,
+    Completed,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum ProgressUpdate {
+    FileStart {
+        file_index: usize,
+        total_files: usize,
+        file_name: String,
+        market_count: usize,
+    },
+    MarketProcessed {
+        markets_in_batch: usize,
+    },
+    FileComplete {
+        duplicates: usize,
+    },
+    PhaseChange(IndexingPhase),
+    Event(String),
+    ConditionCount(usize),
+    TokenCount(usize),
+    Complete,
+    Error(String),
+}
+
+pub fn create_progress_channel() -> (
+    tokio::sync::mpsc::UnboundedSender<ProgressUpdate>,
+    tokio::sync::mpsc::UnboundedReceiver<ProgressUpdate>,
+) {
+    tokio::sync::mpsc::unbounded_channel()
+}
+
 #[derive(Args)]
 #[command(about = "Index raw market data into database for fast queries")]
 pub struct IndexArgs {
@@ -30,14 +107,22 @@ pub struct IndexArgs {
     #[arg(long, short = 'd')]
     pub db_path: Option<PathBuf>,
 
-    /// Use file-based storage instead of RocksDB
-    #[arg(long, default_value = "true")]
+    /// Deprecated: write the JSON file hierarchy instead of RocksDB
+    #[arg(long)]
     pub use_file_store: bool,
 
-    /// Use RocksDB storage (TypedDbContext with column families)
+    /// Use RocksDB storage (TypedDbContext with column families; the default)
     #[arg(long)]
     pub rocksdb: bool,
 
+    /// Use the legacy single-table RocksDB layout
+    #[arg(long, conflicts_with_all = ["rocksdb", "use_file_store"])]
+    pub legacy_store: bool,
+
+    /// Import an existing file store hierarchy (e.g. ./data/database) into RocksDB
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["source_dir", "chunk_files", "use_file_store", "legacy_store"])]
+    pub from_file_store: Option<PathBuf>,
+
     /// Directory containing market JSON chunks to index
     #[arg(long)]
     pub source_dir: Option<PathBuf>,
@@ -50,14 +135,27 @@ pub struct IndexArgs {
     #[arg(long)]
     pub clear: bool,
 
+    /// Load in bulk: no write-ahead log, large batches, conditions and tokens
+    /// ingested as SST files, one compaction at the end (default with --clear)
+    #[arg(long)]
+    pub bulk_load: bool,
+
     /// Skip duplicate markets (based on market_id)
     #[arg(long, default_value = "true")]
     pub skip_duplicates: bool,
 
-    /// Batch size for RocksDB writes
+    /// Batch size for RocksDB writes (files per batch with the file store)
     #[arg(long, default_value = "1000")]
     pub batch_size: usize,
 
+    /// File store layout: one file per view, or one consolidated file per market
+    #[arg(long, value_enum, default_value = "split")]
+    pub file_layout: FileLayout,
+
+    /// When the file store syncs written files to disk
+    #[arg(long, value_enum, default_value = "never")]
+    pub fsync: FsyncPolicy,
+
     /// Show detailed progress information
     #[arg(long)]
     pub detailed: bool,
@@ -65,11 +163,148 @@ pub struct IndexArgs {
     /// Number of parallel threads for processing (0 = auto-detect)
     #[arg(long, default_value = "0")]
     pub threads: usize,
+
+    /// Memory limit before conditions and tokens are written early (e.g. 4G; default: 75% of available memory)
+    #[arg(long, value_parser = crate::core::memory::parse_byte_size)]
+    pub memory_limit: Option<u64>,
+
+    #[command(subcommand)]
+    pub action: Option<IndexAction>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum IndexAction {
+    /// Back up a store; unchanged files are shared with earlier backups in the
+    /// same directory, so repeated backups only copy what changed
+    Backup {
+        /// Backup directory; each store gets its own subdirectory
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Store to back up
+        #[arg(long, value_enum, default_value = "index")]
+        store: BackupStore,
+
+        /// Keep only the newest N backups of the store
+        #[arg(long)]
+        keep: Option<usize>,
+
+        /// List the store's backups instead of creating one
+        #[arg(long)]
+        list: bool,
+    },
+    /// Restore a store, or only some of its column families, from a backup
+    Restore {
+        /// Backup directory given to `backup --out`
+        #[arg(long)]
+        from: PathBuf,
+
+        /// Store to restore
+        #[arg(long, value_enum, default_value = "index")]
+        store: BackupStore,
+
+        /// Backup to restore (default: the newest)
+        #[arg(long)]
+        backup_id: Option<u32>,
+
+        /// Only replace these column families (comma-separated); the others keep their data
+        #[arg(long, value_delimiter = ',')]
+        cf: Vec<String>,
+
+        /// Restore; without it the restore is only described
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+/// Stores `index backup` and `index restore` work on
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupStore {
+    /// The typed market index (`--db-path`, default ./data/database/rocksdb)
+    Index,
+    /// The typed RocksDB gamma database
+    Gamma,
+}
+
+impl BackupStore {
+    fn name(self) -> &'static str {
+        match self {
+            BackupStore::Index => "index",
+            BackupStore::Gamma => "gamma",
+        }
+    }
+}
+
+/// Conditions and token groups written to the database early to stay within
+/// the memory budget
+///
+/// Aggregates with the same id collected afterwards are merged with the
+/// written records, so the final market counts and token lists are complete.
+#[derive(Default)]
+struct SpilledAggregates {
+    conditions: HashSet<String>,
+    /// Token count of each written group
+    tokens: HashMap<String, usize>,
+}
+
+impl SpilledAggregates {
+    /// Write the aggregates collected so far and drop them from memory
+    fn spill(
+        &mut self,
+        ctx: &TypedDbContext,
+        conditions: &mut HashMap<String, Condition>,
+        tokens: &mut HashMap<String, Vec<Token>>,
+    ) -> Result<()> {
+        self.merge_into(ctx, conditions, tokens)?;
+        ctx.batch_write(|batch| {
+            for condition in conditions.values() {
+                batch.put::<ConditionCf>(&condition.id, condition)?;
+            }
+            for (condition_id, group) in tokens.iter() {
+                batch.put::<TokensByConditionCf>(condition_id, group)?;
+            }
+            Ok(())
+        })?;
+
+        self.conditions.extend(conditions.drain().map(|(id, _)| id));
+        self.tokens
+            .extend(tokens.drain().map(|(id, group)| (id, group.len())));
+        conditions.shrink_to_fit();
+        tokens.shrink_to_fit();
+        Ok(())
+    }
+
+    /// Fold written records into the aggregates with the same id; those ids
+    /// are in memory again and are no longer tracked here
+    fn merge_into(
+        &mut self,
+        ctx: &TypedDbContext,
+        conditions: &mut HashMap<String, Condition>,
+        tokens: &mut HashMap<String, Vec<Token>>,
+    ) -> Result<()> {
+        for (id, condition) in conditions.iter_mut() {
+            if self.conditions.remove(id) {
+                if let Some(written) = ctx.get::<ConditionCf>(id)? {
+                    condition.market_count += written.market_count;
+                }
+            }
+        }
+        for (id, group) in tokens.iter_mut() {
+            if self.tokens.remove(id).is_some() {
+                if let Some(mut written) = ctx.get::<TokensByConditionCf>(id)? {
+                    written.append(group);
+                    *group = written;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 pub struct IndexCommand {
     pub args: IndexArgs,
     pub progress_sender: Option<mpsc::UnboundedSender<ProgressUpdate>>,
+    pub progress: ProgressReporter,
 }
 
 impl IndexCommand {
@@ -77,44 +312,226 @@ impl IndexCommand {
         Self {
             args,
             progress_sender: None,
+            progress: ProgressReporter::terminal(),
         }
     }
 
+    /// Report file, condition and token progress here instead of terminal bars
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
     pub fn with_progress_sender(mut self, sender: mpsc::UnboundedSender<ProgressUpdate>) -> Self {
         self.progress_sender = Some(sender);
         self
     }
 
-    pub async fn execute(self, _host: &str, data_paths: DataPaths) -> Result<()> {
+    pub async fn execute(mut self, _host: &str, data_paths: DataPaths) -> Result<()> {
         // Initialize logging for CLI mode
         crate::logging::init_logging(crate::logging::LoggingConfig::new(
             crate::logging::LogMode::ConsoleAndFile,
             data_paths.clone(),
         ))?;
 
+        if let Some(action) = self.args.action.clone() {
+            return self.execute_action(action, &data_paths);
+        }
+
         // If no parameters provided, show TUI
-        if self.args.source_dir.is_none() && self.args.chunk_files.is_none() && !self.args.clear {
-            let mut tui = crate::tui::IndexTui::new(data_paths)?;
-            return tui.run().await;
+        if self.args.source_dir.is_none()
+            && self.args.chunk_files.is_none()
+            && self.args.from_file_store.is_none()
+            && !self.args.clear
+        {
+            #[cfg(feature = "tui")]
+            return crate::tui::IndexTui::new(data_paths)?.run().await;
+            #[cfg(not(feature = "tui"))]
+            return Err(super::unavailable::missing_feature(
+                "index without --source-dir or --chunk-files",
+                "tui",
+            ));
         }
 
+        // Ctrl-C finishes the files in progress and indexes their conditions
+        // and tokens before stopping
+        self.progress = self.progress.with_cancellation(ctrl_c_token());
         self.execute_internal(&data_paths).await
     }
 
     pub async fn execute_internal(&self, data_paths: &DataPaths) -> Result<()> {
-        if self.args.rocksdb {
-            // --rocksdb flag takes priority
-            self.execute_rocksdb_typed(data_paths).await
-        } else if self.args.use_file_store {
-            // Default behavior: file-based storage
+        if let Some(source) = &self.args.from_file_store {
+            self.execute_file_store_import(source, data_paths)
+        } else if self.args.use_file_store && !self.args.rocksdb {
+            // Kept for existing scripts; RocksDB is the source of truth
             self.execute_file_store(data_paths).await
-        } else {
-            // Fallback: legacy RocksDB when file store is explicitly disabled
+        } else if self.args.legacy_store {
             self.execute_rocksdb_legacy(data_paths).await
+        } else {
+            // Default behavior: typed RocksDB with column families
+            self.execute_rocksdb_typed(data_paths).await
+        }
+    }
+
+    /// Database directory of `store`
+    fn store_path(&self, store: BackupStore, data_paths: &DataPaths) -> PathBuf {
+        match store {
+            BackupStore::Index => self
+                .args
+                .db_path
+                .clone()
+                .unwrap_or_else(|| data_paths.root().join("database").join("rocksdb")),
+            BackupStore::Gamma => GammaBackend::Rocksdb.db_path(data_paths.root()),
         }
     }
 
+    fn execute_action(&self, action: IndexAction, data_paths: &DataPaths) -> Result<()> {
+        match action {
+            IndexAction::Backup {
+                out,
+                store,
+                keep,
+                list,
+            } => {
+                let backups =
+                    StoreBackups::new(self.store_path(store, data_paths), out.join(store.name()));
+                if list {
+                    let listed = backups.list()?;
+                    if listed.is_empty() {
+                        info!(
+                            "No {} backups in {}",
+                            store.name(),
+                            backups.backup_dir.display()
+                        );
+                    }
+                    for backup in listed {
+                        info!(
+                            "  #{:<4} {}  {:>10}  {} files",
+                            backup.id,
+                            backup
+                                .created_at
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M:%S"),
+                            format_bytes(backup.size),
+                            backup.files
+                        );
+                    }
+                    return Ok(());
+                }
+
+                info!(
+                    "💾 Backing up {} into {}",
+                    backups.db_path.display(),
+                    backups.backup_dir.display()
+                );
+                let backup = backups.create(keep)?;
+                info!(
+                    "✅ Backup #{} of the {} store: {} in {} files",
+                    backup.id,
+                    store.name(),
+                    format_bytes(backup.size),
+                    backup.files
+                );
+                Ok(())
+            }
+            IndexAction::Restore {
+                from,
+                store,
+                backup_id,
+                cf,
+                yes,
+            } => {
+                let backups =
+                    StoreBackups::new(self.store_path(store, data_paths), from.join(store.name()));
+                let db_path = &backups.db_path;
+                let listed = backups.list()?;
+                let Some(chosen) = listed
+                    .iter()
+                    .rev()
+                    .find(|backup| backup_id.is_none_or(|id| backup.id == id))
+                else {
+                    anyhow::bail!(
+                        "No {} in {}",
+                        backup_id
+                            .map(|id| format!("backup #{}", id))
+                            .unwrap_or_else(|| "backups".to_string()),
+                        backups.backup_dir.display()
+                    );
+                };
+                let what = if cf.is_empty() {
+                    "the whole store".to_string()
+                } else {
+                    format!("column families {}", cf.join(", "))
+                };
+                info!(
+                    "♻️ Restoring {} of {} from backup #{} ({})",
+                    what,
+                    db_path.display(),
+                    chosen.id,
+                    chosen
+                        .created_at
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                );
+                if !yes {
+                    info!("Dry run. Use --yes to restore.");
+                    return Ok(());
+                }
+
+                if cf.is_empty() {
+                    let previous = backups.restore_store(Some(chosen.id))?;
+                    info!("✅ Restored {}", db_path.display());
+                    if let Some(previous) = previous {
+                        info!("   The replaced store was moved to {}", previous.display());
+                    }
+                } else {
+                    for restored in backups.restore_column_families(Some(chosen.id), &cf)? {
+                        info!("✅ Restored {} ({} rows)", restored.name, restored.rows);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the typed store is loaded in bulk: asked for, or implied by
+    /// re-indexing into a cleared database
+    fn bulk_load(&self) -> bool {
+        self.args.bulk_load || self.args.clear
+    }
+
+    /// Import a file store hierarchy into the typed RocksDB store
+    fn execute_file_store_import(&self, source: &PathBuf, data_paths: &DataPaths) -> Result<()> {
+        let db_path = self.store_path(BackupStore::Index, data_paths);
+
+        info!("📥 Importing file store {} into {}", source.display(), db_path.display());
+        if self.args.clear && db_path.exists() {
+            info!("🗑️ Clearing existing database");
+            fs::remove_dir_all(&db_path)?;
+        }
+
+        let ctx = if self.bulk_load() {
+            TypedDbContext::open_bulk_load(&db_path, ALL_COLUMN_FAMILIES.to_vec())?
+        } else {
+            TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec())?
+        };
+        let report = compat::import_file_store(source, &ctx, self.args.skip_duplicates)?;
+        ctx.finish_bulk_load()?;
+
+        info!("✅ Import completed");
+        info!("   • Markets imported: {}", report.markets);
+        info!("   • Duplicates skipped: {}", report.duplicates);
+        info!("   • Conditions: {}", report.conditions);
+        info!("   • Tokens: {}", report.tokens);
+        for path in &report.unreadable {
+            warn!("   • Unreadable: {}", path.display());
+        }
+        Ok(())
+    }
+
+    #[allow(deprecated)]
     async fn execute_file_store(&self, data_paths: &DataPaths) -> Result<()> {
+        warn!("⚠️ --use-file-store is deprecated: market data is read from RocksDB; import this hierarchy later with --from-file-store");
         info!("🗄️ Starting market data indexing to file-based storage");
 
         // Determine database path
@@ -132,9 +549,17 @@ impl IndexCommand {
             fs::remove_dir_all(&db_path)?;
         }
 
-        // Create file store
-        let store = FileStore::new(db_path)?;
+        // Create file store and its batched writer
+        let options = FileStoreOptions {
+            layout: self.args.file_layout,
+            fsync: self.args.fsync,
+            batch_size: self.args.batch_size,
+            ..FileStoreOptions::default()
+        };
+        let store = FileStore::with_options(db_path, options)?;
+        let writer = store.batch_writer();
         info!("✅ Created file-based storage");
+        let started = std::time::Instant::now();
 
         // Determine source files
         let chunk_files = self.get_chunk_files(data_paths)?;
@@ -195,8 +620,8 @@ impl IndexCommand {
                             continue;
                         }
 
-                        // Store the market
-                        if let Err(e) = store.store_market(&market) {
+                        // Queue the market for the writer
+                        if let Err(e) = writer.store_market(&market).await {
                             warn!("⚠️ Failed to store market: {}", e);
                             continue;
                         }
@@ -228,7 +653,9 @@ impl IndexCommand {
             }
         }
 
-        // Get final stats
+        // Drain the write queue before reading back what is on disk
+        let written = writer.finish().await?;
+        let elapsed = started.elapsed();
         let stats = store.get_stats()?;
 
         // Final summary
@@ -238,6 +665,13 @@ impl IndexCommand {
         info!("   • Markets skipped: {}", skipped_markets);
         info!("   • Unique conditions: {}", stats.conditions);
         info!("   • Unique tokens: {}", stats.tokens);
+        info!(
+            "   • Written: {} files, {:.1} MB in {} batches ({:.0} markets/s)",
+            written.files,
+            written.bytes as f64 / 1_048_576.0,
+            written.batches,
+            written.markets as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+        );
         info!("   • Files created:");
         info!("     - Condition directories: {}", stats.conditions);
         info!("     - Token directories: {}", stats.tokens);
@@ -271,7 +705,7 @@ impl IndexCommand {
         // Send initial progress update
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::Starting,
+                IndexingPhase::Starting,
             ));
             let _ = sender.send(ProgressUpdate::Event(
                 "🗄️ Starting market data indexing to RocksDB".to_string(),
@@ -307,14 +741,21 @@ impl IndexCommand {
             fs::remove_dir_all(&db_path)?;
         }
 
-        // Open TypedDbContext with all column families
-        let ctx = TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec())?;
+        // Open TypedDbContext with all column families; a full re-index loads in bulk
+        let ctx = if self.bulk_load() {
+            TypedDbContext::open_bulk_load(&db_path, ALL_COLUMN_FAMILIES.to_vec())?
+        } else {
+            TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec())?
+        };
+        let opened = if ctx.is_bulk_load() {
+            "✅ Opened TypedDbContext database for bulk loading"
+        } else {
+            "✅ Opened TypedDbContext database"
+        };
         if self.progress_sender.is_none() {
-            info!("✅ Opened TypedDbContext database");
+            info!("{}", opened);
         } else if let Some(ref sender) = self.progress_sender {
-            let _ = sender.send(ProgressUpdate::Event(
-                "✅ Opened TypedDbContext database".to_string(),
-            ));
+            let _ = sender.send(ProgressUpdate::Event(opened.to_string()));
         }
 
         // Determine source files
@@ -338,7 +779,7 @@ impl IndexCommand {
         // Change phase to processing files
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::ProcessingFiles,
+                IndexingPhase::ProcessingFiles,
             ));
             let _ = sender.send(ProgressUpdate::Event(format!(
                 "📋 Found {} chunk files to index",
@@ -346,42 +787,40 @@ impl IndexCommand {
             )));
         }
 
-        // Create progress bars only if no progress sender (console mode)
-        let use_progress_bars = self.progress_sender.is_none();
-        let multi_progress = if use_progress_bars {
-            Some(MultiProgress::new())
-        } else {
-            None
-        };
-
-        let overall_progress = if let Some(ref mp) = multi_progress {
-            let bar = mp.add(ProgressBar::new(chunk_files.len() as u64));
-            bar.set_style(
-                ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files ({eta})")
-                .unwrap()
-                .progress_chars("#>-")
-            );
-            bar.set_message("Processing chunk files");
-            Some(bar)
-        } else {
-            None
-        };
+        let overall_progress = self
+            .progress
+            .task("Chunk files", Some(chunk_files.len() as u64));
 
         // Thread-safe shared state
         let ctx = Arc::new(ctx);
         let conditions_map = Arc::new(Mutex::new(HashMap::<String, Condition>::new()));
         let tokens_by_condition = Arc::new(Mutex::new(HashMap::<String, Vec<Token>>::new()));
         let progress_sender = self.progress_sender.clone();
-        let files_processed = Arc::new(Mutex::new(0usize));
         let total_markets = Arc::new(Mutex::new(0usize));
         let duplicate_markets = Arc::new(Mutex::new(0usize));
 
+        // Past the memory budget, aggregated conditions and tokens are written
+        // early; if memory keeps growing, no further files are started
+        let budget = MemoryBudget::new("Indexing", self.args.memory_limit).with_guidance(
+            "Index fewer chunk files at a time with --chunk-files, or lower --threads",
+        );
+        let spilled = Mutex::new(SpilledAggregates::default());
+        let out_of_memory = Mutex::new(None::<MemoryLimitExceeded>);
+
         // Process files in parallel
         let chunk_results: Vec<Result<ChunkProcessResult>> = chunk_files
             .par_iter()
             .enumerate()
             .map(|(i, chunk_file)| {
+                // Files not yet started are skipped once indexing is cancelled
+                // or out of memory
+                if overall_progress.is_cancelled() || out_of_memory.lock().unwrap().is_some() {
+                    return Ok(ChunkProcessResult {
+                        markets_indexed: 0,
+                        duplicates_skipped: 0,
+                    });
+                }
+
                 let file_name = chunk_file
                     .file_name()
                     .unwrap_or_default()
@@ -412,12 +851,26 @@ impl IndexCommand {
                     &progress_sender,
                 );
 
-                // Update progress
-                if let Some(ref bar) = overall_progress {
-                    let mut processed = files_processed.lock().unwrap();
-                    *processed += 1;
-                    bar.set_position(*processed as u64);
-                    bar.set_message(format!("Processed {} files", *processed));
+                overall_progress.inc(1);
+
+                if budget.should_spill() {
+                    let mut spilled = spilled.lock().unwrap();
+                    let mut conditions = conditions_map.lock().unwrap();
+                    let mut tokens = tokens_by_condition.lock().unwrap();
+                    // Another thread may have spilled while this one waited
+                    if !conditions.is_empty() || !tokens.is_empty() {
+                        spilled.spill(&ctx, &mut conditions, &mut tokens)?;
+                        let message =
+                            "💾 Memory budget exceeded; wrote conditions and tokens early";
+                        if let Some(ref sender) = progress_sender {
+                            let _ = sender.send(ProgressUpdate::Event(message.to_string()));
+                        } else {
+                            info!("{}", message);
+                        }
+                    }
+                    if let Err(e) = budget.check() {
+                        out_of_memory.lock().unwrap().get_or_insert(e);
+                    }
                 }
 
                 // Send file complete update
@@ -450,23 +903,28 @@ impl IndexCommand {
         for result in chunk_results {
             result?;
         }
+        // Conditions and tokens of the files already indexed are still
+        // written below, so a cancelled run leaves a consistent database
+        let cancelled = overall_progress.is_cancelled();
+        let out_of_memory = out_of_memory.into_inner().unwrap();
 
         let total_markets = *total_markets.lock().unwrap();
         let duplicate_markets = *duplicate_markets.lock().unwrap();
-        let conditions_map = Arc::try_unwrap(conditions_map)
+        let mut conditions_map = Arc::try_unwrap(conditions_map)
             .unwrap()
             .into_inner()
             .unwrap();
-        let tokens_by_condition = Arc::try_unwrap(tokens_by_condition)
+        let mut tokens_by_condition = Arc::try_unwrap(tokens_by_condition)
             .unwrap()
             .into_inner()
             .unwrap();
 
-        // Complete the overall progress
-        if let Some(bar) = overall_progress {
-            bar.set_position(chunk_files.len() as u64);
-            bar.finish_with_message("All files processed");
-        }
+        // Conditions and tokens written early are merged with the rest, or
+        // are already complete in the database
+        let mut spilled = spilled.into_inner().unwrap();
+        spilled.merge_into(&ctx, &mut conditions_map, &mut tokens_by_condition)?;
+
+        overall_progress.finish(format!("{} markets", total_markets));
 
         // Index aggregated conditions and tokens using batch operations
         if self.progress_sender.is_none() {
@@ -476,102 +934,129 @@ impl IndexCommand {
         // Send phase change for conditions
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::IndexingConditions,
+                IndexingPhase::IndexingConditions,
             ));
             let _ = sender.send(ProgressUpdate::ConditionCount(conditions_map.len()));
         }
 
-        // Progress bar for conditions (only in console mode)
-        let conditions_progress = if let Some(ref mp) = multi_progress {
-            let bar = mp.add(ProgressBar::new(conditions_map.len() as u64));
-            bar.set_style(
-                ProgressStyle::default_bar()
-                    .template("  {spinner:.cyan} [{bar:30.blue}] {pos}/{len} conditions")
-                    .unwrap()
-                    .progress_chars("=>-"),
-            );
-            Some(bar)
-        } else {
-            None
-        };
+        let conditions_progress = self
+            .progress
+            .task("Conditions", Some(conditions_map.len() as u64));
 
         // Keep ctx as Arc for now
-        let mut total_conditions = 0;
-        let mut total_tokens = 0;
+        let mut total_conditions = spilled.conditions.len();
+        let mut total_tokens: usize = spilled.tokens.values().sum();
 
         // Batch conditions for parallel processing
         let conditions_vec: Vec<_> = conditions_map.into_iter().map(|(_, v)| v).collect();
         let batch_size = 1000;
 
-        for (batch_idx, conditions_batch) in conditions_vec.chunks(batch_size).enumerate() {
-            ctx.batch_write(|batch| {
-                for condition in conditions_batch {
-                    batch.put::<ConditionCf>(&condition.id, condition)?;
-                    total_conditions += 1;
-                }
-                Ok(())
-            })?;
+        if ctx.is_bulk_load() {
+            // One SST file instead of batches through the memtables
+            let rows: Vec<(String, Condition)> = conditions_vec
+                .into_iter()
+                .map(|condition| (condition.id.clone(), condition))
+                .collect();
+            total_conditions += ctx.ingest::<ConditionCf>(&rows)?;
+            conditions_progress.set_position(rows.len() as u64);
+        } else {
+            for (batch_idx, conditions_batch) in conditions_vec.chunks(batch_size).enumerate() {
+                ctx.batch_write(|batch| {
+                    for condition in conditions_batch {
+                        batch.put::<ConditionCf>(&condition.id, condition)?;
+                        total_conditions += 1;
+                    }
+                    Ok(())
+                })?;
 
-            if let Some(ref bar) = conditions_progress {
-                bar.set_position(((batch_idx + 1) * batch_size).min(conditions_vec.len()) as u64);
+                conditions_progress
+                    .set_position(((batch_idx + 1) * batch_size).min(conditions_vec.len()) as u64);
             }
         }
-
-        if let Some(bar) = conditions_progress {
-            bar.finish_with_message("✅ Conditions indexed");
-        }
+        conditions_progress.finish("✅ Conditions indexed");
 
         info!("🔄 Indexing tokens by condition");
 
         // Send phase change for tokens
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::IndexingTokens,
+                IndexingPhase::IndexingTokens,
             ));
             let token_count: usize = tokens_by_condition.values().map(|v| v.len()).sum();
             let _ = sender.send(ProgressUpdate::TokenCount(token_count));
         }
 
-        // Progress bar for tokens by condition (only in console mode)
-        let tokens_progress = if let Some(ref mp) = multi_progress {
-            let bar = mp.add(ProgressBar::new(tokens_by_condition.len() as u64));
-            bar.set_style(
-                ProgressStyle::default_bar()
-                    .template("  {spinner:.magenta} [{bar:30.cyan}] {pos}/{len} token groups")
-                    .unwrap()
-                    .progress_chars("=>-"),
-            );
-            Some(bar)
-        } else {
-            None
-        };
+        let tokens_progress = self
+            .progress
+            .task("Token groups", Some(tokens_by_condition.len() as u64));
 
         // Batch tokens for parallel processing
         let tokens_vec: Vec<_> = tokens_by_condition.into_iter().collect();
         let batch_size = 1000;
 
-        for (batch_idx, tokens_batch) in tokens_vec.chunks(batch_size).enumerate() {
-            ctx.batch_write(|batch| {
-                for (condition_id, tokens) in tokens_batch {
-                    batch.put::<TokensByConditionCf>(condition_id, tokens)?;
-                    total_tokens += tokens.len();
-                }
-                Ok(())
-            })?;
+        if ctx.is_bulk_load() {
+            ctx.ingest::<TokensByConditionCf>(&tokens_vec)?;
+            total_tokens += tokens_vec
+                .iter()
+                .map(|(_, tokens)| tokens.len())
+                .sum::<usize>();
+            tokens_progress.set_position(tokens_vec.len() as u64);
+        } else {
+            for (batch_idx, tokens_batch) in tokens_vec.chunks(batch_size).enumerate() {
+                ctx.batch_write(|batch| {
+                    for (condition_id, tokens) in tokens_batch {
+                        batch.put::<TokensByConditionCf>(condition_id, tokens)?;
+                        total_tokens += tokens.len();
+                    }
+                    Ok(())
+                })?;
 
-            if let Some(ref bar) = tokens_progress {
-                bar.set_position(((batch_idx + 1) * batch_size).min(tokens_vec.len()) as u64);
+                tokens_progress
+                    .set_position(((batch_idx + 1) * batch_size).min(tokens_vec.len()) as u64);
+            }
+        }
+        tokens_progress.finish("✅ Token groups indexed");
+
+        // Without the write-ahead log nothing is durable before this flush,
+        // which cancelled and out-of-memory runs need as well
+        if ctx.is_bulk_load() {
+            let message = "🗜️ Flushing and compacting the bulk load";
+            if let Some(ref sender) = self.progress_sender {
+                let _ = sender.send(ProgressUpdate::Event(message.to_string()));
+            } else {
+                info!("{}", message);
             }
+            ctx.finish_bulk_load()?;
         }
 
-        if let Some(bar) = tokens_progress {
-            bar.finish_with_message("✅ Token groups indexed");
+        if cancelled {
+            let cancelled = Cancelled("Indexing".to_string());
+            if let Some(ref sender) = self.progress_sender {
+                let _ = sender.send(ProgressUpdate::Error(cancelled.to_string()));
+            } else {
+                warn!(
+                    "⏹ Indexing cancelled; {} markets, {} conditions and {} tokens were written",
+                    total_markets, total_conditions, total_tokens
+                );
+            }
+            return Err(cancelled.into());
+        }
+        if let Some(out_of_memory) = out_of_memory {
+            if let Some(ref sender) = self.progress_sender {
+                let _ = sender.send(ProgressUpdate::Error(out_of_memory.to_string()));
+            } else {
+                warn!(
+                    "💾 Indexing stopped at its memory limit; {} markets, {} conditions and {} tokens were written",
+                    total_markets, total_conditions, total_tokens
+                );
+            }
+            return Err(out_of_memory.into());
         }
 
         // Send finalizing phase
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::Finalizing,
+                IndexingPhase::Finalizing,
             ));
         }
 
@@ -592,7 +1077,7 @@ impl IndexCommand {
         // Send completion
         if let Some(ref sender) = self.progress_sender {
             let _ = sender.send(ProgressUpdate::PhaseChange(
-                crate::tui::IndexingPhase::Completed,
+                IndexingPhase::Completed,
             ));
             let _ = sender.send(ProgressUpdate::Event(format!(
                 "✅ Indexed {} markets, {} conditions, {} tokens",
@@ -902,8 +1387,12 @@ impl IndexCommand {
             )));
         }
 
-        // Process markets in parallel chunks
-        let chunk_size = 100; // Process 100 markets at a time
+        // Process markets in parallel chunks, each written as one batch
+        let chunk_size = if ctx.is_bulk_load() {
+            BULK_BATCH_SIZE
+        } else {
+            100 // Process 100 markets at a time
+        };
         let mut markets_indexed = 0;
         let mut duplicates_skipped = 0;
 
@@ -953,13 +1442,10 @@ impl IndexCommand {
                         }
                     };
 
-                    let condition_id = match &market.condition_id {
-                        Some(id) if !id.trim().is_empty() => id.clone(),
-                        _ => {
-                            warn!("⚠️ Skipping market {}: no valid condition ID", market_id);
-                            return None;
-                        }
-                    };
+                    if !market.condition_id.as_deref().is_some_and(|id| !id.trim().is_empty()) {
+                        warn!("⚠️ Skipping market {}: no valid condition ID", market_id);
+                        return None;
+                    }
 
                     // Check for duplicates
                     if self.args.skip_duplicates {
@@ -974,30 +1460,18 @@ impl IndexCommand {
                     // Extract condition and tokens
                     let condition = rocks_market.extract_condition();
                     let tokens = rocks_market.extract_tokens();
-                    let index = rocks_market.create_index();
-
-                    Some(Ok((
-                        market_id,
-                        condition_id,
-                        rocks_market,
-                        condition,
-                        tokens,
-                        index,
-                    )))
+
+                    Some(Ok((market_id, rocks_market, condition, tokens)))
                 })
                 .collect();
 
             // Batch write to database
             let mut batch_markets = Vec::new();
-            let mut batch_indices = Vec::new();
 
             for result in results {
                 match result {
-                    Ok((market_id, condition_id, rocks_market, condition, tokens, index)) => {
-                        batch_markets.push((market_id.clone(), condition_id, rocks_market));
-                        if let Some(idx) = index {
-                            batch_indices.push((market_id, idx));
-                        }
+                    Ok((market_id, rocks_market, condition, tokens)) => {
+                        batch_markets.push((market_id, rocks_market));
 
                         // Update conditions map
                         if let Some(cond) = condition {
@@ -1035,27 +1509,9 @@ impl IndexCommand {
                     )));
                 }
                 ctx.batch_write(|batch| {
-                    for (market_id, condition_id, market) in &batch_markets {
-                        batch.put::<MarketCf>(market_id, market)?;
-                        batch.put::<MarketByConditionCf>(&condition_id, market)?;
-
-                        // Update indices
-                        if let Some(ref cond_id) = market.condition_id {
-                            let token_ids: Vec<String> =
-                                market.tokens.iter().map(|t| t.token_id.clone()).collect();
-
-                            for token in &market.tokens {
-                                batch.put::<TokenIndexCf>(&token.token_id, cond_id)?;
-                            }
-
-                            batch.put::<ConditionIndexCf>(cond_id, &token_ids)?;
-                        }
+                    for (market_id, market) in &batch_markets {
+                        market.put_into(batch, market_id)?;
                     }
-
-                    for (market_id, index) in &batch_indices {
-                        batch.put::<MarketIndexCf>(market_id, index)?;
-                    }
-
                     Ok(())
                 })?;
 
@@ -1,3 +1,4 @@
+use crate::auth::SignerBackend;
 use crate::data_paths::DataPaths;
 use anyhow::Result;
 use clap::Args;
@@ -6,8 +7,24 @@ use tracing::info;
 #[derive(Args)]
 pub struct InitArgs {
     /// Private key in hex format (without 0x prefix)
-    #[arg(long = "pk")]
-    pub private_key: String,
+    #[arg(
+        long = "pk",
+        required_unless_present_any = ["ledger", "watch"],
+        conflicts_with_all = ["ledger", "watch"]
+    )]
+    pub private_key: Option<String>,
+
+    /// Sign with a Ledger device instead of storing a private key
+    #[arg(long, conflicts_with = "watch")]
+    pub ledger: bool,
+
+    /// Read-only profile that only monitors this address; mutating commands are refused
+    #[arg(long, value_name = "ADDRESS")]
+    pub watch: Option<String>,
+
+    /// BIP-32 derivation path of the Ledger account (default: m/44'/60'/0'/0/0)
+    #[arg(long, requires = "ledger")]
+    pub derivation_path: Option<String>,
 
     /// Nonce for API key derivation (default: 0)
     #[arg(long, default_value = "0")]
@@ -24,25 +41,45 @@ impl InitCommand {
     }
 
     pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        if let Some(address) = &self.args.watch {
+            SignerBackend::read_only(address)?.save(&data_paths)?;
+            info!("👀 Read-only profile watching {}. Orders and transactions are disabled.", address);
+            if let Err(e) = self.init_address_book(address, &data_paths).await {
+                info!("Note: Could not initialize address book: {}", e);
+            }
+            return Ok(());
+        }
+
         info!("🔐 Initializing Polymarket authentication...");
-        crate::auth::init_auth(host, &data_paths, &self.args.private_key, self.args.nonce).await?;
+        let address = match &self.args.private_key {
+            Some(private_key) => {
+                crate::auth::init_auth(host, &data_paths, private_key, self.args.nonce).await?;
+                crate::ethereum_utils::derive_address_from_private_key(private_key)?
+            }
+            None => {
+                info!("🔏 Approve the authentication message on your Ledger...");
+                let backend = SignerBackend::ledger(self.args.derivation_path.clone(), None);
+                let signer =
+                    crate::auth::init_auth_with_signer(host, &data_paths, backend, self.args.nonce)
+                        .await?;
+                signer.address_hex()
+            }
+        };
         info!("✅ Authentication successful! Credentials saved.");
         
         // Auto-add user's address to address book
-        if let Err(e) = self.init_address_book(host, &data_paths).await {
+        if let Err(e) = self.init_address_book(&address, &data_paths).await {
             info!("Note: Could not initialize address book: {}", e);
         }
         
         Ok(())
     }
     
-    async fn init_address_book(&self, _host: &str, data_paths: &DataPaths) -> Result<()> {
-        use crate::ethereum_utils::derive_address_from_private_key;
+    async fn init_address_book(&self, address: &str, data_paths: &DataPaths) -> Result<()> {
         use crate::address_book::{AddressBookCommand, types::AddressType};
         use tokio::sync::oneshot;
         
-        // Derive address from private key
-        let address = derive_address_from_private_key(&self.args.private_key)?;
+        let address = address.to_string();
         
         // Get address service without portfolio service to avoid circular dependency
         let address_service = crate::address_book::service::get_address_book_service(
@@ -0,0 +1,161 @@
+//! Liquidity command: rank an enriched dataset's markets by executable liquidity
+
+use anyhow::{bail, Result};
+use clap::Args;
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+
+use crate::data_paths::DataPaths;
+use crate::markets::clob::enrich::EnrichedMarket;
+use crate::markets::clob::liquidity::{
+    ImpactPoint, LiquidityReport, DEFAULT_NOTIONALS, LIQUIDITY_REPORT_FILE,
+};
+use crate::number_format;
+
+#[derive(Args, Clone)]
+pub struct LiquidityArgs {
+    /// Enriched dataset name or path (output of 'polybot enrich')
+    pub source_dataset: String,
+
+    /// Dollar sizes to price on each side (comma-separated)
+    #[arg(long, value_delimiter = ',', default_values_t = DEFAULT_NOTIONALS)]
+    pub notionals: Vec<Decimal>,
+
+    /// Distance from the mid, in basis points, within which resting size counts as executable
+    #[arg(long, default_value = "200")]
+    pub max_slippage_bps: Decimal,
+
+    /// Markets to print
+    #[arg(long, default_value = "20")]
+    pub top: usize,
+
+    /// Report file (default: liquidity_report.json in the source dataset)
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+pub struct LiquidityCommand {
+    args: LiquidityArgs,
+}
+
+impl LiquidityCommand {
+    pub fn new(args: LiquidityArgs) -> Self {
+        Self { args }
+    }
+
+    /// "$100", "$1k", "$10k"
+    fn notional_label(notional: Decimal) -> String {
+        let thousand = Decimal::ONE_THOUSAND;
+        if notional >= thousand && (notional % thousand).is_zero() {
+            format!("${}k", (notional / thousand).normalize())
+        } else {
+            format!("${}", notional.normalize())
+        }
+    }
+
+    /// Slippage in bps, or "-" when the recorded book couldn't fill the size
+    fn slippage_cell(point: Option<&ImpactPoint>) -> String {
+        match point {
+            Some(point) if point.is_complete() => format!("{:.0}", point.slippage_bps),
+            _ => "-".to_string(),
+        }
+    }
+
+    pub async fn execute(&self, data_paths: DataPaths) -> Result<()> {
+        let args = &self.args;
+        if args.notionals.is_empty() || args.notionals.iter().any(|n| *n <= Decimal::ZERO) {
+            bail!("--notionals must be positive dollar amounts");
+        }
+        if args.max_slippage_bps <= Decimal::ZERO {
+            bail!("--max-slippage-bps must be positive");
+        }
+
+        let dataset_path = data_paths.source_dataset(&args.source_dataset)?;
+        let markets = EnrichedMarket::load_dataset(&dataset_path)?;
+        let report = LiquidityReport::build(
+            &args.source_dataset,
+            &markets,
+            &args.notionals,
+            args.max_slippage_bps,
+        );
+        let output = args
+            .output
+            .clone()
+            .unwrap_or_else(|| dataset_path.join(LIQUIDITY_REPORT_FILE));
+        std::fs::write(&output, serde_json::to_string_pretty(&report)?)?;
+
+        println!(
+            "💧 Executable liquidity of {} markets (within {} bps of the mid)",
+            report.markets.len(),
+            args.max_slippage_bps
+        );
+        if report.without_depth > 0 {
+            println!(
+                "  ⚠️  {} markets have no recorded book depth; re-run 'polybot enrich' to include them",
+                report.without_depth
+            );
+        }
+        if report.markets.is_empty() {
+            println!("📁 Report saved to {}", output.display());
+            return Ok(());
+        }
+
+        let mut header = format!(
+            "\n{:>4} {:<50} {:>6} {:>6} {:>11} {:>11}",
+            "#", "Market", "Mid", "Spread", "Exec buy", "Exec sell"
+        );
+        for notional in &args.notionals {
+            header.push_str(&format!(
+                " {:>13}",
+                format!("{} b/s bps", Self::notional_label(*notional))
+            ));
+        }
+        header.push_str(&format!(" {:>14} {:>5}", "Volume", "Vol#"));
+        println!("{}", header);
+
+        for (rank, market) in report.markets.iter().take(args.top).enumerate() {
+            let question: String = market
+                .question
+                .as_deref()
+                .unwrap_or(&market.token_id)
+                .chars()
+                .take(50)
+                .collect();
+            let mut row = format!(
+                "{:>4} {:<50} {:>6} {:>6} {:>11} {:>11}",
+                rank + 1,
+                question,
+                number_format::price(market.mid),
+                number_format::price(market.spread),
+                number_format::usd(market.executable_buy),
+                number_format::usd(market.executable_sell)
+            );
+            for (i, _) in args.notionals.iter().enumerate() {
+                let cell = format!(
+                    "{}/{}",
+                    Self::slippage_cell(market.buy.get(i)),
+                    Self::slippage_cell(market.sell.get(i))
+                );
+                row.push_str(&format!(" {:>13}", cell));
+            }
+            row.push_str(&format!(
+                " {:>14} {:>5}",
+                market
+                    .quoted_volume
+                    .map(|v| format!("${}", number_format::integer(v)))
+                    .unwrap_or_else(|| "-".to_string()),
+                market
+                    .volume_rank
+                    .map(|r| r.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            ));
+            println!("{}", row);
+        }
+
+        println!(
+            "\n'-' marks sizes deeper than the book levels enrichment recorded (see --max-depth)"
+        );
+        println!("📁 Report saved to {}", output.display());
+        Ok(())
+    }
+}
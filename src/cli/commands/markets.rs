@@ -1,5 +1,7 @@
 use crate::data_paths::DataPaths;
+use crate::number_format;
 use crate::typed_store::{
+    compat,
     models::{
         MarketCf, MarketIndex, MarketIndexCf, MarketIndexTable, MarketTable, RocksDbMarket,
         ALL_COLUMN_FAMILIES,
@@ -98,6 +100,7 @@ impl MarketsCommand {
 
     pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
         // If no mode or query provided, show interactive TUI
+        #[cfg(feature = "tui")]
         if self.args.mode == MarketMode::List && self.args.query.is_none() {
             let mut tui = crate::tui::MarketsTui::new(data_paths)?;
             return tui.run().await;
@@ -217,7 +220,9 @@ impl MarketsCommand {
                 "📁 Querying markets from file-based storage: {}",
                 file_db_path.display()
             );
-            return self.query_from_file_store(&file_db_path).await;
+            return self
+                .query_from_file_store(&file_db_path, &rocksdb_path)
+                .await;
         }
 
         // Try legacy RocksDB
@@ -346,11 +351,23 @@ impl MarketsCommand {
         Ok(())
     }
 
-    async fn query_from_file_store(&self, _db_path: &std::path::Path) -> Result<()> {
-        // FileStore is for writing, not reading - redirect to RocksDB
-        error!("❌ File storage is write-only. Cannot query from file storage.");
-        info!("💡 Run 'polybot index --rocksdb' to create a queryable database");
-        Ok(())
+    async fn query_from_file_store(
+        &self,
+        db_path: &std::path::Path,
+        rocksdb_path: &std::path::Path,
+    ) -> Result<()> {
+        // The file store is not queryable; import it once and read from RocksDB
+        let base = db_path.parent().unwrap_or(db_path);
+        info!(
+            "📥 Importing file storage into RocksDB: {}",
+            rocksdb_path.display()
+        );
+        let report = {
+            let ctx = TypedDbContext::open(rocksdb_path, ALL_COLUMN_FAMILIES.to_vec())?;
+            compat::import_file_store(base, &ctx, true)?
+        };
+        info!("✅ Imported {} markets", report.markets);
+        self.query_from_rocksdb_typed(rocksdb_path).await
     }
 
     async fn search_markets_in_db(&self, store: &TypedStore, query: &str) -> Result<()> {
@@ -480,7 +497,7 @@ impl MarketsCommand {
 
             let volume_str = market
                 .volume
-                .map(|v| format!("${:.0}", v))
+                .map(|v| format!("${}", number_format::integer(v)))
                 .unwrap_or_else(|| "N/A".to_string());
 
             let mut row = vec![
@@ -530,18 +547,20 @@ impl MarketsCommand {
             }
         );
         if let Some(volume) = market.volume {
-            println!("Volume: ${:.2}", volume);
+            println!("Volume: {}", number_format::usd(volume));
         }
         if let Some(volume_24hr) = market.volume_24hr {
-            println!("24h Volume: ${:.2}", volume_24hr);
+            println!("24h Volume: {}", number_format::usd(volume_24hr));
         }
 
         if !market.tokens.is_empty() {
             println!("\n🎯 Tokens:");
             for token in &market.tokens {
                 println!(
-                    "  • {} ({}): ${:.3}",
-                    token.outcome, token.token_id, token.price
+                    "  • {} ({}): ${}",
+                    token.outcome,
+                    token.token_id,
+                    number_format::price(token.price)
                 );
             }
         }
@@ -6,29 +6,60 @@
 //! See README.md for comprehensive documentation of all available commands,
 //! their usage patterns, and integration points.
 
-// Command modules
+// Command modules; `canvas`, `tui` and `gamma` need the cargo features of the
+// same names (`gamma` also needs `tui`) and `export` needs `sqlite`, see `unavailable`
+pub mod alerts;
+pub mod alias;
 pub mod analyze;
+pub mod audit;
 pub mod book;
+pub mod book_snapshots;
+pub mod bracket;
 pub mod buy;
+pub mod cache;
 pub mod cancel;
+#[cfg(feature = "gui")]
 pub mod canvas;
+pub mod close;
 pub mod daemon;
 pub mod datasets;
+pub mod debug;
+pub mod doctor;
 pub mod enrich;
+#[cfg(feature = "sqlite")]
+pub mod export;
+pub mod failover;
 pub mod fetch_all_markets;
+pub mod funds;
+#[cfg(feature = "fuzz")]
+pub mod fuzz_book;
+pub mod flatten;
+pub mod hedge;
+pub mod import_trades;
 pub mod index;
 pub mod init;
 pub mod install;
+pub mod liquidity;
 pub mod markets;
 pub mod orders;
 pub mod pipeline;
 pub mod portfolio;
-pub mod portfolio_tui;
+pub mod profile;
+pub mod resolution;
 pub mod run_strategy;
+pub mod selftest;
 pub mod sell;
+pub mod session;
+pub mod signer;
+pub mod simulate_fill;
+pub mod strategy;
 pub mod stream;
+pub mod synthetic;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod version;
 pub mod worktree;
 pub mod gamma;
 pub mod portfolio_status;
 pub mod trades;
+pub mod unavailable;
@@ -1,12 +1,33 @@
 use crate::data_paths::DataPaths;
-use anyhow::Result;
-use clap::Args;
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use crate::config;
+use crate::core::portfolio::api::orders::{OrderFilter, PolymarketOrder};
 use crate::core::portfolio::cli::PortfolioCommandHandlers;
-use crate::core::portfolio::display::{OrdersFormatter, DashboardFormatter};
-use tracing::info;
+use crate::core::portfolio::display::market_labels::truncate;
+use crate::core::portfolio::display::{DashboardFormatter, MarketLabels, OrdersFormatter};
+use crate::core::portfolio::exposure::MarketMeta;
+use crate::core::portfolio::order_history;
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::{OrderStatus, Side};
+use crate::core::ws::events::{UserOrderEvent, UserTradeEvent};
+use crate::core::ws::{AuthPayload, WsClient, WsConfig, WsMessage};
+use crate::number_format;
+use crate::strategy::scheduled_orders::{
+    self, NewScheduledOrder, ScheduledOrderStore, ScheduledState, DEFAULT_MAX_DELAY_SECS,
+};
+use owo_colors::OwoColorize;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
 
 #[derive(Args, Clone)]
 pub struct OrdersArgs {
+    #[command(subcommand)]
+    pub command: Option<OrdersSubcommand>,
+
     /// Filter by token ID
     #[arg(long)]
     pub token_id: Option<String>,
@@ -14,6 +35,143 @@ pub struct OrdersArgs {
     /// Show dashboard view with portfolio stats
     #[arg(long, short = 'd')]
     pub dashboard: bool,
+
+    /// Keep running and print order state changes from the user channel as they happen
+    #[arg(long, short = 'f')]
+    pub follow: bool,
+
+    /// Restrict --follow to these market (condition) IDs (comma-separated, default: all)
+    #[arg(long, value_delimiter = ',')]
+    pub markets: Vec<String>,
+
+    /// Print --follow changes as JSON lines instead of text
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show market and token ids instead of market questions and outcome names
+    #[arg(long)]
+    pub raw: bool,
+}
+
+impl OrdersArgs {
+    /// Schedules orders (refused on read-only profiles)
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            &self.command,
+            Some(OrdersSubcommand::Scheduled(ScheduledArgs {
+                action: Some(ScheduledAction::Buy(_) | ScheduledAction::Sell(_)),
+                ..
+            }))
+        )
+    }
+}
+
+#[derive(Subcommand, Clone)]
+pub enum OrdersSubcommand {
+    /// Time-triggered orders placed by the daemon (lists pending and placing ones without an action)
+    Scheduled(ScheduledArgs),
+    /// Filled, cancelled and open orders from the exchange and the local order history
+    History(HistoryArgs),
+}
+
+#[derive(Args, Clone)]
+pub struct HistoryArgs {
+    /// Market (condition) ID
+    #[arg(long)]
+    pub market: Option<String>,
+
+    /// Token ID
+    #[arg(long)]
+    pub token_id: Option<String>,
+
+    /// Orders created at or after this (YYYY-MM-DD or RFC 3339, UTC)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Orders created before this (YYYY-MM-DD or RFC 3339, UTC)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Statuses to show, e.g. matched,canceled (comma-separated, default: all)
+    #[arg(long, value_delimiter = ',')]
+    pub status: Vec<String>,
+
+    /// Page to show, from 1
+    #[arg(long, default_value_t = 1)]
+    pub page: usize,
+
+    /// Orders per page
+    #[arg(long, default_value_t = 50)]
+    pub per_page: usize,
+
+    /// Only read the local order history, without querying the exchange
+    #[arg(long)]
+    pub local: bool,
+
+    /// Print the page as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show market ids instead of market questions and outcome names
+    #[arg(long)]
+    pub raw: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct ScheduledArgs {
+    #[command(subcommand)]
+    pub action: Option<ScheduledAction>,
+
+    /// Include placed, failed, missed and cancelled orders in the list
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ScheduledAction {
+    /// Schedule a limit buy
+    Buy(ScheduleOrderArgs),
+    /// Schedule a limit sell
+    Sell(ScheduleOrderArgs),
+    /// Cancel a pending scheduled order
+    Cancel {
+        /// Scheduled order ID from 'orders scheduled'
+        id: u64,
+    },
+}
+
+#[derive(Args, Clone)]
+pub struct ScheduleOrderArgs {
+    /// Token ID
+    pub token_id: String,
+
+    /// Limit price (e.g., 0.48)
+    #[arg(long)]
+    pub price: Decimal,
+
+    /// Size in shares
+    #[arg(long)]
+    pub size: Decimal,
+
+    /// When to place it, in UTC: HH:MM (next occurrence), YYYY-MM-DD HH:MM or RFC 3339
+    #[arg(long)]
+    pub at: String,
+
+    /// Give up when the daemon reaches the order more than this late (e.g. 5m)
+    #[arg(long, value_parser = crate::cli::parse_duration)]
+    pub max_delay: Option<std::time::Duration>,
+
+    /// Market ID (optional, will use token_id if not provided)
+    #[arg(long)]
+    pub market_id: Option<String>,
+
+    /// Confirm order placement (required unless RUST_ENV=production)
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Confirm orders above the large order notional threshold without prompting
+    #[arg(long)]
+    pub yes_large: bool,
 }
 
 pub struct OrdersCommand {
@@ -27,9 +185,19 @@ impl OrdersCommand {
 
     pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
         info!("Executing orders command");
+
+        if let Some(OrdersSubcommand::Scheduled(args)) = &self.args.command {
+            return scheduled(args, &data_paths);
+        }
+        if let Some(OrdersSubcommand::History(args)) = &self.args.command {
+            return history(args, host, &data_paths).await;
+        }
+        if self.args.follow {
+            return self.follow(&data_paths).await;
+        }
         
         // Create portfolio command handlers
-        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
         
         // Refresh data first
         if let Err(e) = handlers.refresh_data().await {
@@ -59,7 +227,13 @@ impl OrdersCommand {
             println!("👤 Account: {}", handlers.get_address());
             println!("🌐 Host: {}\n", handlers.get_host());
             
-            let formatter = OrdersFormatter::new(&orders);
+            let labels = (!self.args.raw).then(|| {
+                MarketLabels::load(&data_paths, orders.iter().map(|o| o.token_id.as_str()))
+            });
+            let mut formatter = OrdersFormatter::new(&orders);
+            if let Some(labels) = &labels {
+                formatter = formatter.with_labels(labels);
+            }
             println!("{}", formatter.format_table());
             
             if orders.is_empty() {
@@ -76,4 +250,503 @@ impl OrdersCommand {
         
         Ok(())
     }
+
+    /// Stream order changes from the authenticated user channel until Ctrl+C
+    async fn follow(&self, data_paths: &DataPaths) -> Result<()> {
+        let creds = config::load_credentials(data_paths).await?;
+        let auth = AuthPayload {
+            api_key: creds.api_key,
+            secret: creds.secret,
+            passphrase: creds.passphrase,
+        };
+
+        let client = WsClient::new_user(WsConfig::default())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to user feed: {}", e))?;
+        client
+            .subscribe_user(self.args.markets.clone(), auth)
+            .map_err(|e| anyhow!("Failed to subscribe to user feed: {}", e))?;
+        let mut messages = client.messages();
+
+        // Status goes to stderr so stdout stays clean for piping
+        eprintln!(
+            "{}",
+            "👀 Following order changes (Ctrl+C to stop)...".bright_blue()
+        );
+
+        let mut tracker = OrderChangeTracker::default();
+        // Tokens are looked up as they show up, each once
+        let mut labels = MarketLabels::default();
+        let mut looked_up = HashSet::new();
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => break,
+                msg = messages.recv() => match msg {
+                    Ok(msg) => {
+                        for mut change in tracker.process(&msg) {
+                            if let Some(token_id) = &self.args.token_id {
+                                if change.token_id != *token_id {
+                                    continue;
+                                }
+                            }
+                            if !self.args.raw {
+                                let token_id = change.token_id.as_str();
+                                if looked_up.insert(token_id.to_string()) {
+                                    let data_paths = data_paths.clone();
+                                    let token = token_id.to_string();
+                                    let found = tokio::task::spawn_blocking(move || {
+                                        MarketLabels::load(&data_paths, [token.as_str()])
+                                    })
+                                    .await
+                                    .unwrap_or_default();
+                                    labels.extend(found);
+                                }
+                                change.question = labels.question(token_id).map(str::to_string);
+                                change.outcome = labels.outcome(token_id).map(str::to_string);
+                            }
+                            self.print_change(&change)?;
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => warn!("Order follow lagged, {} messages dropped", n),
+                    Err(RecvError::Closed) => return Err(anyhow!("User feed closed")),
+                },
+            }
+        }
+
+        let _ = client.disconnect();
+        Ok(())
+    }
+
+    fn print_change(&self, change: &OrderChange) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        if self.args.json {
+            writeln!(stdout, "{}", serde_json::to_string(change)?)?;
+        } else {
+            let kind = match change.kind {
+                OrderChangeKind::Placed => format!("{:<9}", "PLACED").bright_blue().to_string(),
+                OrderChangeKind::PartialFill => format!("{:<9}", "PARTIAL").bright_yellow().to_string(),
+                OrderChangeKind::Filled => format!("{:<9}", "FILLED").bright_green().to_string(),
+                OrderChangeKind::Cancelled => format!("{:<9}", "CANCELLED").bright_red().to_string(),
+                OrderChangeKind::Updated => format!("{:<9}", "UPDATED").white().to_string(),
+                OrderChangeKind::Trade => format!("{:<9}", "TRADE").bright_cyan().to_string(),
+            };
+            let filled = change
+                .filled_size
+                .map(|f| format!(" filled {}/{}", f, change.size))
+                .unwrap_or_default();
+            let status = change
+                .status
+                .as_ref()
+                .map(|s| format!(" [{}]", s))
+                .unwrap_or_default();
+            let market = match (&change.question, &change.outcome) {
+                (Some(question), Some(outcome)) => format!("\"{}\" {}", question, outcome),
+                (Some(question), None) => format!("\"{}\"", question),
+                _ => format!("token={}", change.token_id),
+            };
+            writeln!(
+                stdout,
+                "{} {} {} {} {} @ {}{}{} {}",
+                change.timestamp.format("%H:%M:%S"),
+                kind,
+                change.order_id,
+                change.side,
+                change.size,
+                change.price,
+                filled,
+                status,
+                market
+            )?;
+        }
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// `orders scheduled`: list, add or cancel time-triggered orders
+fn scheduled(args: &ScheduledArgs, data_paths: &DataPaths) -> Result<()> {
+    let store = ScheduledOrderStore::new(data_paths);
+    let (side, order) = match &args.action {
+        None => return list_scheduled(&store, args.all),
+        Some(ScheduledAction::Cancel { id }) => {
+            store.cancel(*id)?;
+            println!("✅ Scheduled order {} cancelled", id);
+            return Ok(());
+        }
+        Some(ScheduledAction::Buy(order)) => (Side::Buy, order),
+        Some(ScheduledAction::Sell(order)) => (Side::Sell, order),
+    };
+
+    if order.price <= Decimal::ZERO || order.price >= Decimal::ONE {
+        return Err(anyhow!("Price must be between 0 and 1"));
+    }
+    if order.size <= Decimal::ZERO {
+        return Err(anyhow!("Size must be positive"));
+    }
+    let now = chrono::Utc::now();
+    let execute_at = scheduled_orders::parse_execute_at(&order.at, now)?;
+    if execute_at <= now {
+        return Err(anyhow!("{} is in the past", execute_at.format("%Y-%m-%d %H:%M:%S UTC")));
+    }
+    if !order.yes && std::env::var("RUST_ENV").unwrap_or_default() != "production" {
+        warn!("⚠️  Order confirmation required. Use --yes to confirm.");
+        return Ok(());
+    }
+    // Large orders are confirmed now, since nobody may be watching when the daemon places them
+    let risk_config = RiskConfig::load(data_paths)?;
+    if let Some(large) = risk_config.large_orders.check(&order.token_id, side, order.price, order.size) {
+        let journal = RiskJournal::new(data_paths);
+        if !confirm_large_order_cli(&large, order.yes_large, &journal)? {
+            warn!("❌ Large order not confirmed, nothing was scheduled.");
+            return Ok(());
+        }
+    }
+
+    let max_delay_secs = order
+        .max_delay
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(DEFAULT_MAX_DELAY_SECS);
+    let scheduled = store.add(NewScheduledOrder {
+        token_id: order.token_id.clone(),
+        market_id: order.market_id.clone(),
+        side,
+        price: order.price,
+        size: order.size,
+        execute_at,
+        max_delay_secs,
+    })?;
+    println!(
+        "⏰ Scheduled order {}: {} {} @ ${} at {}",
+        scheduled.id,
+        side,
+        order.size,
+        order.price,
+        execute_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("💡 'polybot daemon' must be running then to place it");
+    Ok(())
+}
+
+fn list_scheduled(store: &ScheduledOrderStore, all: bool) -> Result<()> {
+    let orders: Vec<_> = store
+        .load()?
+        .into_iter()
+        .filter(|o| all || o.is_pending() || o.state == ScheduledState::Placing)
+        .collect();
+    if orders.is_empty() {
+        println!("💡 No scheduled orders. Add one with 'polybot orders scheduled buy|sell'");
+        return Ok(());
+    }
+    println!(
+        "{:>4} {:<20} {:<4} {:>8} {:>10}  {:<20} {}",
+        "ID", "Token", "Side", "Price", "Size", "At (UTC)", "State"
+    );
+    for order in orders {
+        let token: String = order.token_id.chars().take(20).collect();
+        println!(
+            "{:>4} {:<20} {:<4} {:>8} {:>10}  {:<20} {}",
+            order.id,
+            token,
+            order.side.to_string(),
+            order.price,
+            order.size,
+            order.execute_at.format("%Y-%m-%d %H:%M:%S"),
+            order.state_label()
+        );
+    }
+    Ok(())
+}
+
+/// `orders history`: a page of past and open orders, newest first
+async fn history(args: &HistoryArgs, host: &str, data_paths: &DataPaths) -> Result<()> {
+    let parse = |raw: &Option<String>| {
+        raw.as_deref()
+            .map(|raw| {
+                MarketMeta::parse_end_date(raw)
+                    .ok_or_else(|| anyhow!("Invalid date '{}'. Use YYYY-MM-DD or RFC 3339", raw))
+            })
+            .transpose()
+    };
+    let filter = OrderFilter {
+        market: args.market.clone(),
+        asset_id: args.token_id.clone(),
+        since: parse(&args.since)?,
+        until: parse(&args.until)?,
+        statuses: args.status.clone(),
+    };
+    let user_address = crate::auth::account_address(data_paths).await?;
+    let history =
+        order_history::query_orders(host, data_paths, &user_address, &filter, args.local).await?;
+    let page = history.page(args.page, args.per_page);
+    let orders = page.orders;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(orders)?);
+        return Ok(());
+    }
+
+    println!("\n📜 Order History\n");
+    println!("👤 Account: {}", user_address);
+    if args.local {
+        println!("💾 Local history only\n");
+    } else {
+        println!(
+            "🌐 {} listed by the exchange, {} settled by ID, {} newly recorded\n",
+            number_format::integer(history.fetched),
+            number_format::integer(history.settled),
+            number_format::integer(history.recorded)
+        );
+    }
+    if orders.is_empty() {
+        if history.orders.is_empty() {
+            println!("💡 No orders match these filters");
+        } else {
+            println!("💡 Page {} is past the last page ({})", args.page, page.total);
+        }
+        return Ok(());
+    }
+
+    let labels = (!args.raw)
+        .then(|| MarketLabels::load(data_paths, orders.iter().map(|o| o.asset_id.as_str())));
+    println!(
+        "{:<16} {:<12} {:<40} {:<4} {:>8} {:>12} {:>12}  {}",
+        "Created (UTC)", "Order", "Market", "Side", "Price", "Size", "Matched", "Status"
+    );
+    for order in orders {
+        print_history_row(order, labels.as_ref());
+    }
+    println!(
+        "\nPage {}/{} of {} orders",
+        args.page.max(1),
+        page.total,
+        number_format::integer(history.orders.len())
+    );
+    if args.page.max(1) < page.total {
+        println!("💡 Next page: --page {}", args.page.max(1) + 1);
+    }
+    Ok(())
+}
+
+fn print_history_row(order: &PolymarketOrder, labels: Option<&MarketLabels>) {
+    let id: String = order.id.chars().take(12).collect();
+    let market = labels
+        .and_then(|labels| labels.label(&order.asset_id, 30))
+        .unwrap_or_else(|| {
+            let market: String = order.market.chars().take(24).collect();
+            format!("{} {}", market, order.outcome)
+        });
+    let market = truncate(&market, 40);
+    let matched = order
+        .size_matched
+        .parse::<Decimal>()
+        .map(number_format::size)
+        .unwrap_or_else(|_| order.size_matched.clone());
+    let status = match order.status.to_uppercase().as_str() {
+        "MATCHED" | "FILLED" => order.status.green().to_string(),
+        "CANCELED" | "CANCELLED" => order.status.red().to_string(),
+        _ if order.is_open() => order.status.yellow().to_string(),
+        _ => order.status.clone(),
+    };
+    println!(
+        "{:<16} {:<12} {:<40} {:<4} {:>8} {:>12} {:>12}  {}",
+        order.created().format("%Y-%m-%d %H:%M"),
+        id,
+        market,
+        order.side,
+        number_format::price(order.price),
+        number_format::size(order.size_structured),
+        matched,
+        status
+    );
+}
+
+/// Kind of order state change reported by --follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OrderChangeKind {
+    Placed,
+    PartialFill,
+    Filled,
+    Cancelled,
+    Updated,
+    Trade,
+}
+
+/// One line of --follow output
+#[derive(Debug, Clone, serde::Serialize)]
+struct OrderChange {
+    timestamp: chrono::DateTime<chrono::Utc>,
+    kind: OrderChangeKind,
+    order_id: String,
+    token_id: String,
+    market: String,
+    side: String,
+    price: Decimal,
+    size: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filled_size: Option<Decimal>,
+    /// Size filled by this change alone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fill_delta: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trade_id: Option<String>,
+    /// Market question, unless --raw
+    #[serde(skip_serializing_if = "Option::is_none")]
+    question: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outcome: Option<String>,
+}
+
+/// Classifies raw user channel messages into order changes
+#[derive(Default)]
+struct OrderChangeTracker {
+    /// Last seen filled size per order, to report fill deltas
+    filled: HashMap<String, Decimal>,
+}
+
+impl OrderChangeTracker {
+    fn process(&mut self, msg: &WsMessage) -> Vec<OrderChange> {
+        match msg.event_type.as_str() {
+            "order" => match msg.decode::<UserOrderEvent>() {
+                Ok(event) => vec![self.order_change(event)],
+                Err(e) => {
+                    debug!("Skipping unparseable order message: {}", e);
+                    Vec::new()
+                }
+            },
+            "user_trade" | "trade" => match msg.decode::<UserTradeEvent>() {
+                Ok(event) => vec![OrderChange {
+                    timestamp: Self::timestamp(event.timestamp),
+                    kind: OrderChangeKind::Trade,
+                    order_id: event.order_id,
+                    token_id: event.asset_id,
+                    market: event.market,
+                    side: event.side.to_string(),
+                    price: event.price,
+                    size: event.size,
+                    filled_size: None,
+                    fill_delta: None,
+                    status: Some(event.status),
+                    trade_id: Some(event.trade_id),
+                    question: None,
+                    outcome: None,
+                }],
+                Err(e) => {
+                    debug!("Skipping unparseable trade message: {}", e);
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    fn order_change(&mut self, event: UserOrderEvent) -> OrderChange {
+        let previous = self
+            .filled
+            .insert(event.order_id.clone(), event.filled_size)
+            .unwrap_or(Decimal::ZERO);
+        let delta = event.filled_size - previous;
+
+        let kind = match event.order_type.to_uppercase().as_str() {
+            "PLACEMENT" => OrderChangeKind::Placed,
+            "CANCELLATION" => OrderChangeKind::Cancelled,
+            _ if event.status == OrderStatus::Cancelled => OrderChangeKind::Cancelled,
+            _ if event.status == OrderStatus::Filled || event.filled_size >= event.size => {
+                OrderChangeKind::Filled
+            }
+            _ if delta > Decimal::ZERO => OrderChangeKind::PartialFill,
+            _ => OrderChangeKind::Updated,
+        };
+
+        if matches!(kind, OrderChangeKind::Filled | OrderChangeKind::Cancelled) {
+            self.filled.remove(&event.order_id);
+        }
+
+        OrderChange {
+            timestamp: Self::timestamp(event.timestamp),
+            kind,
+            order_id: event.order_id,
+            token_id: event.asset_id,
+            market: event.market,
+            side: event.side.to_string(),
+            price: event.price,
+            size: event.size,
+            filled_size: Some(event.filled_size),
+            fill_delta: (delta > Decimal::ZERO).then_some(delta),
+            status: Some(event.status.to_string()),
+            trade_id: None,
+            question: None,
+            outcome: None,
+        }
+    }
+
+    /// User channel timestamps may be seconds or milliseconds
+    fn timestamp(raw: u64) -> chrono::DateTime<chrono::Utc> {
+        let millis = if raw < 10_000_000_000 { raw * 1000 } else { raw };
+        chrono::DateTime::from_timestamp_millis(millis as i64).unwrap_or_else(chrono::Utc::now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn order_message(kind: &str, status: &str, size_matched: &str) -> WsMessage {
+        WsMessage::from_json(&format!(
+            r#"{{"event_type":"order","order_id":"0xo1","asset_id":"123","market":"0xm",
+                "side":"BUY","price":"0.45","size":"100","size_matched":"{}",
+                "status":"{}","type":"{}","timestamp":1767225600}}"#,
+            size_matched, status, kind
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn tracker_reports_placement_fills_and_cancels() {
+        let mut tracker = OrderChangeTracker::default();
+
+        let placed = tracker.process(&order_message("PLACEMENT", "OPEN", "0"));
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].kind, OrderChangeKind::Placed);
+        assert_eq!(placed[0].token_id, "123");
+        assert_eq!(placed[0].timestamp.timestamp(), 1_767_225_600);
+
+        // Fill deltas are measured against the last update of the same order
+        let partial = tracker.process(&order_message("UPDATE", "OPEN", "40"));
+        assert_eq!(partial[0].kind, OrderChangeKind::PartialFill);
+        assert_eq!(partial[0].fill_delta, Some(dec!(40)));
+        let more = tracker.process(&order_message("UPDATE", "OPEN", "55"));
+        assert_eq!(more[0].fill_delta, Some(dec!(15)));
+
+        let cancelled = tracker.process(&order_message("CANCELLATION", "CANCELLED", "55"));
+        assert_eq!(cancelled[0].kind, OrderChangeKind::Cancelled);
+        assert_eq!(cancelled[0].fill_delta, None);
+        assert!(tracker.filled.is_empty());
+
+        let filled = tracker.process(&order_message("UPDATE", "OPEN", "100"));
+        assert_eq!(filled[0].kind, OrderChangeKind::Filled);
+        assert_eq!(filled[0].fill_delta, Some(dec!(100)));
+    }
+
+    #[test]
+    fn tracker_reports_trades_and_skips_other_events() {
+        let mut tracker = OrderChangeTracker::default();
+        let trade = WsMessage::from_json(
+            r#"{"event_type":"trade","trade_id":"t1","order_id":"0xo1","asset_id":"123",
+                "market":"0xm","side":"SELL","price":"0.5","size":"10",
+                "timestamp":1767225600123,"status":"MATCHED"}"#,
+        )
+        .unwrap();
+        let changes = tracker.process(&trade);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, OrderChangeKind::Trade);
+        assert_eq!(changes[0].trade_id.as_deref(), Some("t1"));
+        assert_eq!(changes[0].timestamp.timestamp_millis(), 1_767_225_600_123);
+
+        let book = WsMessage::from_json(r#"{"event_type":"book","asset_id":"123"}"#).unwrap();
+        assert!(tracker.process(&book).is_empty());
+    }
 }
@@ -1,17 +1,28 @@
 //! Pipeline command for running workflow scripts
 
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, Subcommand};
 use owo_colors::OwoColorize;
 use std::collections::HashMap;
 use tracing::info;
 
 use crate::data_paths::DataPaths;
-use crate::pipeline::{Pipeline, PipelineConfig, PipelineRunner};
+#[cfg(feature = "tui")]
+use crate::logging::{init_logging, LogMode, LoggingConfig};
+#[cfg(feature = "tui")]
+use crate::pipeline::PipelineMonitor;
+use crate::markets::datasets::format_bytes;
+use crate::pipeline::history::{format_change, format_duration, RunComparison};
+use crate::pipeline::{Pipeline, PipelineConfig, PipelineContext, PipelineRunner, PipelineStats};
+use crate::typed_store::freshness::default_db_path;
+use crate::typed_store::models::{PipelineRunRecord, PipelineRunStatus, PipelineStepStatus};
+use crate::typed_store::pipeline_runs;
 
 #[derive(Args, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct PipelineArgs {
-    /// Pipeline name to execute (not required when listing)
+    /// Pipeline name to execute (not required when listing); `history` and
+    /// `compare` are subcommands, not pipeline names
     pub name: Option<String>,
 
     /// Directory containing pipeline YAML files
@@ -29,6 +40,40 @@ pub struct PipelineArgs {
     /// List all available pipelines
     #[arg(long)]
     pub list: bool,
+
+    /// Run in the live monitor: step list with ETAs, output of each step, and
+    /// retry/skip when a step fails
+    #[arg(long)]
+    pub monitor: bool,
+
+    #[command(subcommand)]
+    pub action: Option<PipelineAction>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum PipelineAction {
+    /// List recorded runs, newest first
+    History {
+        /// Only runs of this pipeline (its name or file name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Number of runs to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+
+        /// Print the run records as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare two recorded runs step by step, flagging slower steps, smaller
+    /// outputs and new failures
+    Compare {
+        /// Baseline run ID (or a unique prefix of one)
+        run_a: String,
+        /// Run ID to compare against the baseline
+        run_b: String,
+    },
 }
 
 pub struct PipelineCommand {
@@ -40,10 +85,20 @@ impl PipelineCommand {
         Self { args }
     }
 
-    pub async fn execute(&self, _host: &str, _data_paths: DataPaths) -> Result<()> {
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
         let config =
             crate::pipeline::PipelineConfig::new().with_pipelines_dir(&self.args.pipelines_dir);
 
+        match &self.args.action {
+            Some(PipelineAction::History { name, limit, json }) => {
+                return show_history(&data_paths, name.as_deref(), *limit, *json);
+            }
+            Some(PipelineAction::Compare { run_a, run_b }) => {
+                return compare_runs(&data_paths, run_a, run_b);
+            }
+            None => {}
+        }
+
         // List available pipelines
         if self.args.list {
             return self.list_pipelines(&config).await;
@@ -51,7 +106,7 @@ impl PipelineCommand {
 
         // If no pipeline name provided and no other flags, launch interactive TUI
         if self.args.name.is_none() && !self.args.list {
-            return self.launch_interactive_tui(config).await;
+            return self.launch_interactive_tui(config, data_paths).await;
         }
 
         // Validate that name is provided when not listing or using TUI
@@ -94,7 +149,9 @@ impl PipelineCommand {
         context.dry_run = self.args.dry_run;
 
         // Use default verbose setting (can be enhanced later)
-        let runner = crate::pipeline::PipelineRunner::new_auto().with_verbose(false);
+        let runner = crate::pipeline::PipelineRunner::new_auto()
+            .with_verbose(false)
+            .with_history(default_db_path(&data_paths));
 
         println!(
             "{}",
@@ -108,7 +165,11 @@ impl PipelineCommand {
             );
         }
 
-        let stats = runner.execute_pipeline(&pipeline, context).await?;
+        let stats = if self.args.monitor {
+            run_monitored(pipeline, context, runner, &data_paths).await?
+        } else {
+            runner.execute_pipeline(&pipeline, context).await?
+        };
 
         info!("Pipeline execution completed: {:?}", stats);
 
@@ -116,7 +177,12 @@ impl PipelineCommand {
     }
 
     /// Launch the interactive TUI for pipeline selection
-    async fn launch_interactive_tui(&self, config: crate::pipeline::PipelineConfig) -> Result<()> {
+    #[cfg(feature = "tui")]
+    async fn launch_interactive_tui(
+        &self,
+        config: crate::pipeline::PipelineConfig,
+        data_paths: DataPaths,
+    ) -> Result<()> {
         // Create and run the TUI
         let tui = crate::pipeline::PipelineTui::new(config.clone())?;
 
@@ -148,7 +214,9 @@ impl PipelineCommand {
                 let mut context = pipeline.create_context(extra_params);
                 context.dry_run = self.args.dry_run;
 
-                let runner = crate::pipeline::PipelineRunner::new_auto().with_verbose(false);
+                let runner = crate::pipeline::PipelineRunner::new_auto()
+                    .with_verbose(false)
+                    .with_history(default_db_path(&data_paths));
 
                 if self.args.dry_run {
                     println!(
@@ -157,7 +225,8 @@ impl PipelineCommand {
                     );
                 }
 
-                let stats = runner.execute_pipeline(&pipeline, context).await?;
+                // Having picked it in a TUI, watch it run in one too
+                let stats = run_monitored(pipeline, context, runner, &data_paths).await?;
                 info!("Pipeline execution completed: {:?}", stats);
             }
             None => {
@@ -168,6 +237,15 @@ impl PipelineCommand {
         Ok(())
     }
 
+    #[cfg(not(feature = "tui"))]
+    async fn launch_interactive_tui(
+        &self,
+        _config: PipelineConfig,
+        _data_paths: DataPaths,
+    ) -> Result<()> {
+        Err(super::unavailable::missing_feature("pipeline (interactive)", "tui"))
+    }
+
     async fn list_pipelines(&self, config: &PipelineConfig) -> Result<()> {
         println!("{}", "📋 Available Pipelines:".bright_blue());
         println!();
@@ -223,7 +301,254 @@ impl PipelineCommand {
         println!("  polybot pipeline <name> --dry-run       # Preview pipeline execution");
         println!("  polybot pipeline <name> -p key=value    # Pass custom parameters");
         println!("  polybot pipeline <name> --verbose       # Show detailed output");
+        println!("  polybot pipeline <name> --monitor       # Watch the run in the live monitor");
+        println!("  polybot pipeline history --name <name>  # Recorded runs of a pipeline");
+        println!("  polybot pipeline compare <run> <run>    # Compare two recorded runs");
 
         Ok(())
     }
 }
+
+/// Run a pipeline in the live monitor, logging to file only so the log
+/// doesn't draw over it
+#[cfg(feature = "tui")]
+async fn run_monitored(
+    pipeline: Pipeline,
+    context: PipelineContext,
+    runner: PipelineRunner,
+    data_paths: &DataPaths,
+) -> Result<PipelineStats> {
+    init_logging(LoggingConfig::new(LogMode::FileOnly, data_paths.clone()))?;
+    let stats = PipelineMonitor::new(pipeline, context, runner).run().await?;
+    println!(
+        "{}",
+        format!(
+            "✅ Pipeline finished: {}/{} steps succeeded, {} failed ({:.1}s)",
+            stats.successful_steps,
+            stats.total_steps,
+            stats.failed_steps,
+            stats.total_duration.as_secs_f64()
+        )
+        .bright_green()
+    );
+    Ok(stats)
+}
+
+#[cfg(not(feature = "tui"))]
+async fn run_monitored(
+    _pipeline: Pipeline,
+    _context: PipelineContext,
+    _runner: PipelineRunner,
+    _data_paths: &DataPaths,
+) -> Result<PipelineStats> {
+    Err(super::unavailable::missing_feature("pipeline --monitor", "tui"))
+}
+
+fn show_history(data_paths: &DataPaths, name: Option<&str>, limit: usize, json: bool) -> Result<()> {
+    let runs: Vec<PipelineRunRecord> = pipeline_runs::load(&default_db_path(data_paths))?
+        .into_iter()
+        .filter(|run| match name {
+            Some(name) => run_matches(run, name),
+            None => true,
+        })
+        .take(limit)
+        .collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+    if runs.is_empty() {
+        println!("{}", "No recorded pipeline runs.".bright_yellow());
+        return Ok(());
+    }
+
+    println!("{}", "📜 Pipeline Runs:".bright_blue());
+    println!();
+    println!(
+        "{:<50} {:<17} {:<9} {:>8} {:>7} {:>10}",
+        "Run", "Started", "Status", "Duration", "Steps", "Output"
+    );
+    for run in &runs {
+        let succeeded = run
+            .steps
+            .iter()
+            .filter(|step| step.status == PipelineStepStatus::Succeeded)
+            .count();
+        let bytes: u64 = run
+            .steps
+            .iter()
+            .flat_map(|step| &step.outputs)
+            .map(|output| output.bytes)
+            .sum();
+        let line = format!(
+            "{:<50} {:<17} {:<9} {:>8} {:>7} {:>10}",
+            run.run_id,
+            run.started_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            format!("{:?}", run.status).to_lowercase(),
+            format_duration(std::time::Duration::from_secs_f64(run.duration_secs)),
+            format!("{}/{}", succeeded, run.steps.len()),
+            format_bytes(bytes)
+        );
+        match run.status {
+            PipelineRunStatus::Succeeded => println!("{}", line),
+            PipelineRunStatus::Partial => println!("{}", line.bright_yellow()),
+            PipelineRunStatus::Failed | PipelineRunStatus::Aborted => {
+                println!("{}", line.bright_red())
+            }
+        }
+        for step in run
+            .steps
+            .iter()
+            .filter(|step| step.status == PipelineStepStatus::Failed)
+        {
+            let exit = step
+                .exit_code
+                .map(|code| format!(" (exit code {})", code))
+                .unwrap_or_default();
+            println!("    ✗ {}{}", step.name, exit);
+        }
+    }
+    Ok(())
+}
+
+/// `name` is the pipeline's name or its file name; run IDs embed the snake_case name
+fn run_matches(run: &PipelineRunRecord, name: &str) -> bool {
+    let snake = |text: &str| text.replace([' ', '-'], "_").to_lowercase();
+    run.pipeline.eq_ignore_ascii_case(name) || snake(&run.pipeline) == snake(name)
+}
+
+/// The run with ID `id`, or the only run whose ID starts with it
+fn find_run<'a>(runs: &'a [PipelineRunRecord], id: &str) -> Result<&'a PipelineRunRecord> {
+    if let Some(run) = runs.iter().find(|run| run.run_id == id) {
+        return Ok(run);
+    }
+    let matches: Vec<&PipelineRunRecord> = runs
+        .iter()
+        .filter(|run| run.run_id.starts_with(id))
+        .collect();
+    match matches.as_slice() {
+        [run] => Ok(*run),
+        [] => Err(anyhow::anyhow!(
+            "No recorded run '{}'. Run 'polybot pipeline history' to list runs.",
+            id
+        )),
+        _ => Err(anyhow::anyhow!(
+            "'{}' matches {} runs; use a longer run ID",
+            id,
+            matches.len()
+        )),
+    }
+}
+
+fn compare_runs(data_paths: &DataPaths, run_a: &str, run_b: &str) -> Result<()> {
+    let runs = pipeline_runs::load(&default_db_path(data_paths))?;
+    let a = find_run(&runs, run_a)?;
+    let b = find_run(&runs, run_b)?;
+    if a.pipeline != b.pipeline {
+        println!(
+            "{}",
+            format!(
+                "⚠️  Comparing runs of different pipelines ({} vs {})",
+                a.pipeline, b.pipeline
+            )
+            .bright_yellow()
+        );
+    }
+
+    let comparison = RunComparison::between(a, b);
+    let secs = |secs: Option<f64>| {
+        secs.map(|secs| format_duration(std::time::Duration::from_secs_f64(secs)))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let bytes = |bytes: Option<u64>| bytes.map(format_bytes).unwrap_or_else(|| "-".to_string());
+    let status = |status: Option<PipelineStepStatus>| {
+        status
+            .map(|status| format!("{:?}", status).to_lowercase())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("{}", format!("⚖️  {} → {}", a.run_id, b.run_id).bright_blue());
+    println!();
+    println!(
+        "{:<36} {:>19} {:>17} {:>21}",
+        "Step", "Status", "Duration", "Output"
+    );
+    for step in &comparison.steps {
+        let line = format!(
+            "{:<36} {:>19} {:>17} {:>21}",
+            step.name,
+            format!("{} → {}", status(step.status.baseline), status(step.status.candidate)),
+            format!(
+                "{} → {}",
+                secs(step.duration_secs.baseline),
+                secs(step.duration_secs.candidate)
+            ),
+            format!(
+                "{} → {}",
+                bytes(step.output_bytes.baseline),
+                bytes(step.output_bytes.candidate)
+            ),
+        );
+        if step.regressions.is_empty() {
+            println!("{}", line);
+        } else {
+            println!("{}", line.bright_red());
+            for regression in &step.regressions {
+                println!("    ⚠️  {}", regression);
+            }
+        }
+    }
+    println!();
+    println!(
+        "Total: {} → {} ({}), output {} → {} ({})",
+        secs(Some(comparison.duration_secs.baseline)),
+        secs(Some(comparison.duration_secs.candidate)),
+        format_change(
+            comparison.duration_secs.baseline,
+            comparison.duration_secs.candidate
+        ),
+        format_bytes(comparison.output_bytes.baseline),
+        format_bytes(comparison.output_bytes.candidate),
+        format_change(
+            comparison.output_bytes.baseline as f64,
+            comparison.output_bytes.candidate as f64
+        ),
+    );
+    for regression in &comparison.regressions {
+        println!("{}", format!("⚠️  Run: {}", regression).bright_red());
+    }
+    if !comparison.has_regressions() {
+        println!("{}", "✅ No regressions".bright_green());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: PipelineArgs,
+    }
+
+    #[test]
+    fn subcommands_are_not_taken_for_pipeline_names() {
+        let cli = Cli::try_parse_from(["pipeline", "history", "--limit", "5"]).unwrap();
+        assert!(cli.args.name.is_none());
+        assert!(matches!(
+            cli.args.action,
+            Some(PipelineAction::History { limit: 5, .. })
+        ));
+
+        let cli = Cli::try_parse_from(["pipeline", "daily", "--dry-run"]).unwrap();
+        assert_eq!(cli.args.name.as_deref(), Some("daily"));
+        assert!(cli.args.action.is_none());
+
+        assert!(Cli::try_parse_from(["pipeline", "daily", "history"]).is_err());
+    }
+}
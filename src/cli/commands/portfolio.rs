@@ -2,6 +2,16 @@
 
 use anyhow::Result;
 use clap::Args;
+#[cfg(feature = "tui")]
+use std::io::IsTerminal;
+#[cfg(feature = "tui")]
+use std::sync::Arc;
+use crate::core::portfolio::cli::{
+    enhanced_portfolio_command, portfolio_exposure_command, portfolio_webhook_command,
+};
+#[cfg(feature = "tui")]
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::exposure::ExposureGrouping;
 use crate::data_paths::DataPaths;
 
 #[derive(Args, Debug)]
@@ -17,17 +27,49 @@ pub struct PortfolioArgs {
     /// Use simple text output instead of interactive TUI
     #[arg(long, short = 't')]
     text: bool,
+
+    /// Print open positions' exposure grouped by market category or end date
+    #[arg(long, value_enum)]
+    by: Option<ExposureGrouping>,
+
+    /// POST the current snapshot to the webhook in portfolio_webhook.yaml and exit
+    #[arg(long)]
+    send_webhook: bool,
+
+    /// Show market and token ids instead of market questions in orders and trades
+    #[arg(long)]
+    raw: bool,
 }
 
 pub async fn portfolio(args: PortfolioArgs, host: &str, data_paths: DataPaths) -> Result<()> {
-    // Use the enhanced portfolio command with the new portfolio system
-    use crate::core::portfolio::cli::enhanced_portfolio_command;
-    
+    if args.send_webhook {
+        return portfolio_webhook_command(host, data_paths).await;
+    }
+    if let Some(grouping) = args.by {
+        return portfolio_exposure_command(grouping, args.market, args.asset, host, data_paths)
+            .await;
+    }
+
+    #[cfg(feature = "tui")]
+    if !args.text && std::io::stdout().is_terminal() {
+        let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
+        return crate::tui::run_portfolio_tui(
+            Arc::new(handlers),
+            host.to_string(),
+            data_paths,
+            args.market,
+            args.asset,
+            args.raw,
+        ).await;
+    }
+
+    // Text output when piped or redirected, or when the TUI isn't built in
     enhanced_portfolio_command(
         args.market,
         args.asset,
-        args.text,
+        true,
+        args.raw,
         host,
         data_paths,
     ).await
-}
\ No newline at end of file
+}
@@ -5,8 +5,7 @@ use clap::Args;
 use crate::data_paths::DataPaths;
 use crate::core::portfolio::cli::get_portfolio_service_handle;
 use crate::core::portfolio::display::DashboardFormatter;
-use crate::config;
-use crate::ethereum_utils;
+use crate::number_format;
 use tracing::info;
 
 #[derive(Args, Debug)]
@@ -35,11 +34,11 @@ pub struct PortfolioStatusArgs {
 pub async fn portfolio_status(args: PortfolioStatusArgs, host: &str, data_paths: DataPaths) -> Result<()> {
     println!("\n🔍 Portfolio Service Status\n");
     
-    // Load private key to derive user address
-    let private_key = config::load_private_key(&data_paths).await?;
-    let address = ethereum_utils::derive_address_from_private_key(&private_key)?;
+    let address = crate::auth::account_address(&data_paths).await?;
     
-    println!("👤 Account: {}", address);
+    let display = crate::address_book::address_display(&data_paths).await;
+    display.resolve([address.as_str()]).await;
+    println!("👤 Account: {}", display.display(&address));
     println!("🌐 Host: {}", host);
     println!();
     
@@ -107,14 +106,14 @@ pub async fn portfolio_status(args: PortfolioStatusArgs, host: &str, data_paths:
         println!("📈 Portfolio Summary:");
         println!("  Last Updated: {}", portfolio_state.last_updated.format("%Y-%m-%d %H:%M:%S UTC"));
         println!("  Synced: {}", if portfolio_state.is_synced { "✅" } else { "❌" });
-        println!("  Total Value: ${:.2}", portfolio_state.balances.total_value);
-        println!("  Available Cash: ${:.2}", portfolio_state.balances.available_cash);
+        println!("  Total Value: {}", number_format::usd(portfolio_state.balances.total_value));
+        println!("  Available Cash: {}", number_format::usd(portfolio_state.balances.available_cash));
         println!("  Positions: {} ({} open)", portfolio_state.stats.total_positions, portfolio_state.stats.open_positions);
         println!("  Active Orders: {}", portfolio_state.active_orders.len());
-        println!("  Total P&L: ${:.2}", portfolio_state.stats.total_pnl());
+        println!("  Total P&L: {}", number_format::usd(portfolio_state.stats.total_pnl()));
         
         if let Some(win_rate) = portfolio_state.stats.win_rate {
-            println!("  Win Rate: {:.1}%", win_rate);
+            println!("  Win Rate: {}", number_format::percent(win_rate));
         }
         
         println!();
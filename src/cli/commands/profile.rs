@@ -0,0 +1,167 @@
+//! Profile command: manage isolated data directory profiles
+//!
+//! Each profile owns its own credentials, portfolio state and datasets so that
+//! experimentation (`--profile research`) cannot touch live trading state.
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use owo_colors::OwoColorize;
+use serde_json::json;
+use std::fs;
+
+use crate::core::audit::{self, AuditAction};
+use crate::data_paths::{DataPaths, ProfileStore, DEFAULT_PROFILE};
+
+#[derive(Args, Clone)]
+pub struct ProfileArgs {
+    /// Profile subcommand
+    #[command(subcommand)]
+    pub command: ProfileSubcommand,
+}
+
+#[derive(clap::Subcommand, Clone)]
+pub enum ProfileSubcommand {
+    /// List all profiles and mark the active one
+    List,
+
+    /// Create a new profile with its own data tree
+    Create {
+        /// Profile name (letters, digits, '-' or '_')
+        name: String,
+
+        /// Copy encrypted credentials from this profile
+        #[arg(long)]
+        copy_creds_from: Option<String>,
+
+        /// Make the new profile active
+        #[arg(long)]
+        switch: bool,
+    },
+
+    /// Set the profile used when --profile and POLYBOT_PROFILE are not given
+    Switch {
+        /// Profile name
+        name: String,
+    },
+
+    /// Show the profile in use and its data directory
+    Current,
+}
+
+pub struct ProfileCommand {
+    args: ProfileArgs,
+}
+
+impl ProfileCommand {
+    pub fn new(args: ProfileArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let store = ProfileStore::new(data_paths.base());
+
+        match &self.args.command {
+            ProfileSubcommand::List => {
+                let active = store.active_profile();
+                println!("{}", "Profiles".bright_cyan().bold());
+                for name in store.list() {
+                    let paths = DataPaths::with_profile(data_paths.base(), &name);
+                    let has_creds = paths.auth().join("creds.json.enc").exists();
+                    let marker = if name == active { "*" } else { " " };
+                    let line = format!(
+                        "{} {:<20} {:<40} {}",
+                        marker,
+                        name,
+                        paths.root().display(),
+                        if has_creds { "credentials" } else { "no credentials" }
+                    );
+                    if name == data_paths.profile() {
+                        println!("{}", line.bright_green());
+                    } else {
+                        println!("{}", line);
+                    }
+                }
+                println!();
+                println!("{}", "* = active profile".dimmed());
+            }
+            ProfileSubcommand::Create {
+                name,
+                copy_creds_from,
+                switch,
+            } => {
+                let paths = store.create(name)?;
+                println!("✅ Created profile '{}' at {}", name, paths.root().display());
+
+                if let Some(source) = copy_creds_from {
+                    if !store.exists(source) {
+                        return Err(anyhow!("Source profile '{}' does not exist", source));
+                    }
+                    let source_paths = DataPaths::with_profile(data_paths.base(), source);
+                    let mut copied = 0;
+                    for file in ["creds.json.enc", "private_key.enc", "signer.json"] {
+                        let src = source_paths.auth().join(file);
+                        if src.exists() {
+                            fs::copy(&src, paths.auth().join(file))?;
+                            copied += 1;
+                        }
+                    }
+                    if copied == 0 {
+                        println!("⚠️  Profile '{}' has no credentials to copy", source);
+                    } else {
+                        println!("🔐 Copied credentials from '{}'", source);
+                        audit::record(
+                            AuditAction::CredentialsChanged,
+                            format!("Copied credentials from profile '{}' to '{}'", source, name),
+                            json!({ "from": source, "to": name }),
+                            Ok(()),
+                        );
+                    }
+                } else {
+                    println!(
+                        "💡 Run 'polybot --profile {} init --pk <key>' to add credentials",
+                        name
+                    );
+                }
+
+                if *switch {
+                    store.switch(name)?;
+                    println!("➡️  Switched active profile to '{}'", name);
+                }
+            }
+            ProfileSubcommand::Switch { name } => {
+                store.switch(name)?;
+                audit::record(
+                    AuditAction::ConfigChanged,
+                    format!("Active profile switched to '{}'", name),
+                    json!({ "profile": name }),
+                    Ok(()),
+                );
+                println!("➡️  Active profile is now '{}'", name);
+                if name == DEFAULT_PROFILE {
+                    println!("   Data directory: {}", data_paths.base().display());
+                } else {
+                    println!(
+                        "   Data directory: {}",
+                        DataPaths::with_profile(data_paths.base(), name).root().display()
+                    );
+                }
+            }
+            ProfileSubcommand::Current => {
+                println!("Profile:        {}", data_paths.profile().bright_green());
+                println!("Data directory: {}", data_paths.root().display());
+                if data_paths.profile() != store.active_profile() {
+                    println!(
+                        "{}",
+                        format!(
+                            "(overridden by --profile or POLYBOT_PROFILE; active profile is '{}')",
+                            store.active_profile()
+                        )
+                        .dimmed()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+//! Resolution command: resolution sources, oracle ancillary data and
+//! proposal/dispute alerts for held markets
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::{Args, Subcommand};
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::core::alerts::AlertRouter;
+use crate::core::diagnostics::{record_event, Subsystem};
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::PositionStatus;
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::resolution::{MarketResolution, ResolutionAlert, ResolutionStore};
+use crate::markets::gamma::{ConditionId, GammaClient};
+
+#[derive(Args, Clone)]
+pub struct ResolutionArgs {
+    #[command(subcommand)]
+    pub command: ResolutionSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum ResolutionSubcommand {
+    /// Refresh the resolution state of every market with an open position and
+    /// alert on new proposals (warning) and disputes (critical)
+    Check {
+        /// Keep checking every this many seconds
+        #[arg(long)]
+        watch: Option<u64>,
+
+        /// Print alerts as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a market's resolution source, rules and oracle status
+    Show {
+        /// Condition ID of the market
+        condition_id: String,
+    },
+}
+
+pub struct ResolutionCommand {
+    args: ResolutionArgs,
+}
+
+impl ResolutionCommand {
+    pub fn new(args: ResolutionArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            ResolutionSubcommand::Check { watch, json } => {
+                let handlers =
+                    PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
+                let router = AlertRouter::new(&data_paths)?;
+                let Some(interval) = watch else {
+                    return check_held_markets(&handlers, &router, &data_paths, *json).await;
+                };
+                loop {
+                    let checked = check_held_markets(&handlers, &router, &data_paths, *json);
+                    if let Err(e) = checked.await {
+                        warn!("Resolution check failed: {}", e);
+                    }
+                    // Repeat and escalate alerts nobody acknowledged
+                    if let Err(e) = router.follow_up(Utc::now()).await {
+                        warn!("Failed to follow up on alerts: {:#}", e);
+                    }
+                    tokio::time::sleep(Duration::from_secs((*interval).max(30))).await;
+                }
+            }
+            ResolutionSubcommand::Show { condition_id } => {
+                show_market(&data_paths, condition_id).await
+            }
+        }
+    }
+}
+
+async fn check_held_markets(
+    handlers: &PortfolioCommandHandlers,
+    router: &AlertRouter,
+    data_paths: &DataPaths,
+    json: bool,
+) -> Result<()> {
+    handlers.refresh_data().await?;
+    let held: BTreeSet<String> = handlers
+        .get_portfolio_state()
+        .await?
+        .positions
+        .into_iter()
+        .filter(|p| p.status == PositionStatus::Open)
+        .map(|p| p.market_id)
+        .collect();
+    if held.is_empty() {
+        if !json {
+            println!("No open positions to track.");
+        }
+        return Ok(());
+    }
+
+    let condition_ids: Vec<ConditionId> = held.iter().cloned().map(ConditionId).collect();
+    let markets = GammaClient::new()
+        .fetch_markets_by_condition(&condition_ids)
+        .await?;
+
+    let mut store = ResolutionStore::load(data_paths)?;
+    let mut alerts = Vec::new();
+    for market in &markets {
+        if let Some(alert) = store.update(MarketResolution::from_market(market)) {
+            alerts.push(alert);
+        }
+    }
+    store.save()?;
+
+    for alert in &alerts {
+        record_event(Subsystem::Strategy, alert.describe());
+        if let Err(e) = router
+            .raise(alert.severity(), "resolution", alert.describe(), None)
+            .await
+        {
+            warn!("Failed to raise alert: {:#}", e);
+        }
+        if json {
+            println!("{}", serde_json::to_string(alert)?);
+        } else {
+            print_alert(alert);
+        }
+    }
+    if !json {
+        let missing = held.len().saturating_sub(markets.len());
+        println!(
+            "🔎 Checked {} held market(s), {} alert(s){}",
+            markets.len(),
+            alerts.len(),
+            if missing > 0 {
+                format!(", {} not found on Gamma", missing)
+            } else {
+                String::new()
+            }
+        );
+    }
+    Ok(())
+}
+
+fn print_alert(alert: &ResolutionAlert) {
+    println!("🚨 {}", alert.describe());
+    println!("   Condition: {}", alert.condition_id);
+}
+
+async fn show_market(data_paths: &DataPaths, condition_id: &str) -> Result<()> {
+    let markets = GammaClient::new()
+        .fetch_markets_by_condition(&[ConditionId(condition_id.to_string())])
+        .await?;
+    let market = markets
+        .first()
+        .ok_or_else(|| anyhow!("No market with condition ID {}", condition_id))?;
+    let resolution = MarketResolution::from_market(market);
+
+    // Remember the reading so a later `check` compares against it
+    let mut store = ResolutionStore::load(data_paths)?;
+    let previous = store.get(condition_id).map(|r| r.state);
+    store.update(resolution.clone());
+    store.save()?;
+
+    println!("\n⚖️  {}\n", resolution.question);
+    for line in resolution.summary_lines() {
+        println!("  {}", line);
+    }
+    if let Some(previous) = previous.filter(|p| *p != resolution.state) {
+        println!("  (was {} at the last check)", previous);
+    }
+    if let Some(question_id) = &resolution.question_id {
+        println!("  Question ID: {}", question_id);
+    }
+    if let Some(rules) = &resolution.ancillary_data {
+        println!("\n📜 Rules (oracle ancillary data):\n");
+        for line in rules.lines() {
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
@@ -10,11 +10,15 @@ use tracing::{debug, error, info, warn};
 
 use crate::auth::get_authenticated_client;
 use crate::config;
+use crate::core::diagnostics::spawn_recording;
+use crate::core::execution::chaos::{Chaos, ChaosConfig};
 use crate::core::execution::orders::{OrderConfig, PolyBot};
+use crate::core::risk::RiskConfig;
 use crate::core::services::{Streamer, StreamerConfig};
 use crate::core::ws::{AuthPayload, PolyEvent, WsConfig};
 use crate::data_paths::DataPaths;
 use crate::logging::{init_logging, LogMode, LoggingConfig};
+use crate::number_format;
 use crate::strategy::{simple_strategy, SimpleStrategy, SingleTokenStrategy, TradeEvent};
 
 #[derive(Args, Clone)]
@@ -58,6 +62,35 @@ pub struct RunStrategyArgs {
     /// Silence hash mismatch warnings
     #[arg(long)]
     pub quiet_hash_mismatch: bool,
+
+    /// Chaos mode: inject latency, jitter, lost and reordered messages into the
+    /// market feed and order submission (settings from chaos.yaml, overridden below)
+    #[arg(long)]
+    pub chaos: bool,
+
+    /// Chaos: latency added to feed events and order submissions, in milliseconds
+    #[arg(long)]
+    pub chaos_latency_ms: Option<u64>,
+
+    /// Chaos: random extra delay of up to this many milliseconds
+    #[arg(long)]
+    pub chaos_jitter_ms: Option<u64>,
+
+    /// Chaos: fraction of feed events that never arrive (0-1)
+    #[arg(long)]
+    pub chaos_drop_rate: Option<f64>,
+
+    /// Chaos: fraction of feed events delivered after the next one (0-1)
+    #[arg(long)]
+    pub chaos_reorder_rate: Option<f64>,
+
+    /// Chaos: fraction of order placements and cancels that fail as lost (0-1)
+    #[arg(long)]
+    pub chaos_order_drop_rate: Option<f64>,
+
+    /// Chaos: seed for repeatable runs
+    #[arg(long)]
+    pub chaos_seed: Option<u64>,
 }
 
 pub struct RunStrategyCommand {
@@ -112,7 +145,48 @@ impl RunStrategyCommand {
         Ok(matched_tokens)
     }
     
-    pub async fn execute(&self, _host: &str, data_paths: &DataPaths) -> Result<()> {
+    /// Chaos settings from chaos.yaml and the flags, if chaos mode is on
+    fn chaos_config(&self, data_paths: &DataPaths) -> Result<Option<ChaosConfig>> {
+        let args = &self.args;
+        let overridden = args.chaos_latency_ms.is_some()
+            || args.chaos_jitter_ms.is_some()
+            || args.chaos_drop_rate.is_some()
+            || args.chaos_reorder_rate.is_some()
+            || args.chaos_order_drop_rate.is_some()
+            || args.chaos_seed.is_some();
+        if !args.chaos && !overridden {
+            return Ok(None);
+        }
+        let mut config = ChaosConfig::load(data_paths)?;
+        if let Some(latency) = args.chaos_latency_ms {
+            config.events.latency_ms = latency;
+            config.orders.latency_ms = latency;
+        }
+        if let Some(jitter) = args.chaos_jitter_ms {
+            config.events.jitter_ms = jitter;
+            config.orders.jitter_ms = jitter;
+        }
+        if let Some(rate) = args.chaos_drop_rate {
+            config.events.drop_rate = rate;
+        }
+        if let Some(rate) = args.chaos_reorder_rate {
+            config.events.reorder_rate = rate;
+        }
+        if let Some(rate) = args.chaos_order_drop_rate {
+            config.orders.drop_rate = rate;
+        }
+        if args.chaos_seed.is_some() {
+            config.seed = args.chaos_seed;
+        }
+        if !config.is_active() {
+            warn!(
+                "🌪️  Chaos mode is on but injects nothing: set chaos.yaml or the --chaos-* flags"
+            );
+        }
+        Ok(Some(config))
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: &DataPaths) -> Result<()> {
         // Initialize logging
         let logging_config = LoggingConfig::new(
             LogMode::ConsoleAndFile,
@@ -196,6 +270,9 @@ impl RunStrategyCommand {
         }
         
         info!("🚀 Starting {} strategy for {} token(s)", self.args.strategy, resolved_tokens.len());
+        crate::core::audit::set_context(crate::core::audit::AuditContext::Strategy {
+            name: self.args.strategy.clone(),
+        });
         for token in &resolved_tokens {
             info!("  - Token: {}", token);
         }
@@ -204,7 +281,7 @@ impl RunStrategyCommand {
         let api_creds = config::load_credentials(&data_paths).await?;
         
         // Create authenticated ClobClient for order placement
-        let clob_client = get_authenticated_client(_host, &data_paths).await?;
+        let clob_client = get_authenticated_client(host, &data_paths).await?;
         let clob_client = Arc::new(tokio::sync::Mutex::new(clob_client));
         info!("🔐 Created authenticated ClobClient for order placement");
         
@@ -213,7 +290,47 @@ impl RunStrategyCommand {
             enable_detailed_logging: false, // Can be controlled via RUST_LOG env var
             ..Default::default()
         };
-        let polybot = Arc::new(PolyBot::with_order_config(order_config));
+        let risk_config = RiskConfig::load(&data_paths)?;
+        let limits = &risk_config.throttle.default_limits;
+        info!(
+            "🛡️  Order throttle: {}/s (burst {}) new orders, {}/s (burst {}) cancels per market{}",
+            limits.new_orders.per_second,
+            limits.new_orders.burst,
+            limits.cancels.per_second,
+            limits.cancels.burst,
+            if risk_config.throttle.enabled { "" } else { " [DISABLED]" }
+        );
+        let order_signer = crate::auth::order_signer(host, &data_paths).await?;
+        if let Some(order_signer) = &order_signer {
+            info!("🔏 Orders will be signed with {}", order_signer.signer().describe());
+        }
+        let chaos = match self.chaos_config(data_paths)? {
+            Some(config) => {
+                let (events, orders) = (&config.events, &config.orders);
+                warn!("🌪️  CHAOS MODE: simulated network trouble, orders are still real");
+                warn!(
+                    "   Feed: +{}ms (±{}ms jitter), {:.1}% lost, {:.1}% reordered",
+                    events.latency_ms,
+                    events.jitter_ms,
+                    events.drop_rate * 100.0,
+                    events.reorder_rate * 100.0
+                );
+                warn!(
+                    "   Orders: +{}ms (±{}ms jitter), {:.1}% lost{}",
+                    orders.latency_ms,
+                    orders.jitter_ms,
+                    orders.drop_rate * 100.0,
+                    config.seed.map(|seed| format!(", seed {}", seed)).unwrap_or_default()
+                );
+                Some(Chaos::new(config)?)
+            }
+            None => None,
+        };
+        let polybot = Arc::new(
+            PolyBot::with_risk_config(order_config, risk_config)
+                .with_order_signer(order_signer)
+                .with_chaos(chaos.clone()),
+        );
         
         // For now, we'll use the first token ID for the strategy
         let primary_token = resolved_tokens.first()
@@ -235,6 +352,7 @@ impl RunStrategyCommand {
                     tick_size: Decimal::new(1, 2), // 0.01 = cent precision
                     base_order_size: Decimal::new(5, 0), // 5 shares base
                     max_order_value: Decimal::new(250, 2), // $2.50 max per order
+                    ..Default::default()
                 };
                 Box::new(SimpleStrategy::new(
                     primary_token,
@@ -254,8 +372,8 @@ impl RunStrategyCommand {
         info!("📊 Strategy Configuration:");
         info!("   Strategy: {}", strategy.name());
         info!("   Primary Token: {}", strategy.token_id());
-        info!("   Min Spread: ${:.4}", self.args.min_spread);
-        info!("   Max Spread: ${:.4}", self.args.max_spread);
+        info!("   Min Spread: ${}", number_format::price(self.args.min_spread));
+        info!("   Max Spread: ${}", number_format::price(self.args.max_spread));
         info!("   Volume Window: {}s", self.args.volume_window);
         info!("   Log Frequency: every {} updates", self.args.log_frequency);
         if self.args.quiet_hash_mismatch {
@@ -313,8 +431,12 @@ impl RunStrategyCommand {
         
         // Create and start the streamer
         let mut streamer = Streamer::new(streamer_config);
-        streamer.start(_host, data_paths).await?;
+        streamer.start(host, data_paths).await?;
+        let _recording = spawn_recording(data_paths, format!("strategy:{}", self.args.strategy));
         let mut event_receiver = streamer.events();
+        if let Some(chaos) = &chaos {
+            event_receiver = chaos.wrap_events(event_receiver, 1000);
+        }
         
         info!("🔌 Connected to WebSocket, streaming market data...");
         info!("   Press Ctrl+C to stop");
@@ -389,17 +511,28 @@ impl RunStrategyCommand {
                         }
                         PolyEvent::PriceChange { asset_id, side, price, size, .. } => {
                             if asset_id == strategy.token_id() {
-                                debug!("💱 Price change: {:?} ${:.4} x {}", side, price, size);
+                                debug!(
+                                    "💱 Price change: {:?} ${} x {}",
+                                    side,
+                                    number_format::price(*price),
+                                    number_format::size(*size)
+                                );
                             }
                         }
                         PolyEvent::Trade { asset_id, price, size, side } => {
                             if asset_id == strategy.token_id() {
-                                info!("💰 Trade: {:?} ${:.4} x {} = ${:.2}", side, price, size, price * size);
+                                info!(
+                                    "💰 Trade: {:?} ${} x {} = {}",
+                                    side,
+                                    number_format::price(*price),
+                                    number_format::size(*size),
+                                    number_format::usd(price * size)
+                                );
                             }
                         }
                         PolyEvent::LastTradePrice { asset_id, price, .. } => {
                             if asset_id == strategy.token_id() {
-                                debug!("📈 Last trade price: ${:.4}", price);
+                                debug!("📈 Last trade price: ${}", number_format::price(*price));
                             }
                         }
                         PolyEvent::MyOrder { asset_id, side, price, size, status } => {
@@ -415,7 +548,12 @@ impl RunStrategyCommand {
                             
                             info!("{} Order Update - Status: {:?}", emoji, status);
                             info!("   Asset: {}", asset_id);
-                            info!("   Side: {:?} | Price: ${:.4} | Size: {}", side, price, size);
+                            info!(
+                                "   Side: {:?} | Price: ${} | Size: {}",
+                                side,
+                                number_format::price(*price),
+                                number_format::size(*size)
+                            );
                             
                             // Only show target match for our monitored assets
                             if asset_id == strategy.token_id() {
@@ -426,8 +564,13 @@ impl RunStrategyCommand {
                             order_event_count += 1;
                             info!("💵 Trade Executed!");
                             info!("   Asset: {}", asset_id);
-                            info!("   Side: {:?} | Price: ${:.4} | Size: {}", side, price, size);
-                            info!("   Value: ${:.2}", price * size);
+                            info!(
+                                "   Side: {:?} | Price: ${} | Size: {}",
+                                side,
+                                number_format::price(*price),
+                                number_format::size(*size)
+                            );
+                            info!("   Value: {}", number_format::usd(price * size));
                             
                             if asset_id == strategy.token_id() {
                                 info!("   ✨ This is our monitored token!");
@@ -505,7 +648,20 @@ impl RunStrategyCommand {
         info!("   Orders Placed: {}", order_stats.orders_placed);
         info!("   Successful Orders: {}", order_stats.successful_orders);
         info!("   Failed Orders: {}", order_stats.failed_orders);
-        info!("   Total Volume: ${:.2}", order_stats.total_volume_traded);
+        info!("   Total Volume: {}", number_format::usd(order_stats.total_volume_traded));
+        if let Some(chaos) = &chaos {
+            let stats = chaos.stats();
+            info!("");
+            info!("🌪️  Chaos:");
+            info!(
+                "   Feed Events: {} delayed, {} lost, {} reordered",
+                stats.events_delayed, stats.events_dropped, stats.events_reordered
+            );
+            info!(
+                "   Order Submissions: {} delayed, {} lost",
+                stats.orders_delayed, stats.orders_dropped
+            );
+        }
         info!("═══════════════════════════════════════");
         info!("");
         info!("✅ Strategy shutdown complete");
@@ -0,0 +1,247 @@
+//! Selftest command: validate an install end to end against the sandbox
+//!
+//! Runs the subset of the end-to-end integration tests that needs no
+//! credentials (list markets, read an order book, receive a book snapshot
+//! over the market WebSocket) against a real exchange instead of the mock one.
+//! Nothing is written to the data directory. Exits with an error when any step
+//! fails so it can gate install scripts.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use owo_colors::OwoColorize;
+use polymarket_rs_client::ClobClient;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::cli::SANDBOX_HOST;
+use crate::core::ws::{WsClient, WsConfig};
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct SelftestArgs {
+    /// CLOB REST host to test against
+    #[arg(long, default_value = SANDBOX_HOST)]
+    pub host: String,
+
+    /// Market WebSocket URL (default: the standard market feed)
+    #[arg(long)]
+    pub ws_url: Option<String>,
+
+    /// Token to read the book and stream for (default: first market accepting orders)
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Timeout in seconds for each step
+    #[arg(long, default_value = "15")]
+    pub timeout_secs: u64,
+
+    /// Skip the WebSocket step
+    #[arg(long)]
+    pub skip_ws: bool,
+}
+
+/// Where the selftest runs; the integration tests point this at the mock exchange
+#[derive(Debug, Clone)]
+pub struct SelftestTarget {
+    pub host: String,
+    pub ws_url: String,
+    pub token: Option<String>,
+    pub timeout: Duration,
+    pub skip_ws: bool,
+}
+
+impl From<&SelftestArgs> for SelftestTarget {
+    fn from(args: &SelftestArgs) -> Self {
+        Self {
+            host: args.host.trim_end_matches('/').to_string(),
+            ws_url: args.ws_url.clone().unwrap_or_else(|| WsConfig::default().market_url),
+            token: args.token.clone(),
+            timeout: Duration::from_secs(args.timeout_secs),
+            skip_ws: args.skip_ws,
+        }
+    }
+}
+
+/// Outcome of one step: a detail line on success, the error otherwise
+#[derive(Debug)]
+pub struct SelftestStep {
+    pub name: &'static str,
+    pub outcome: Result<String>,
+    pub elapsed: Duration,
+}
+
+impl SelftestStep {
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// What the markets step found: its detail line and the token later steps use
+#[derive(Debug, Clone)]
+pub struct SelftestMarket {
+    pub detail: String,
+    pub token_id: String,
+}
+
+/// Run every step in order; later steps are skipped once one fails, since
+/// each needs the token found by the markets step
+pub async fn run_selftest(target: &SelftestTarget) -> Vec<SelftestStep> {
+    let mut steps = Vec::new();
+
+    let started = Instant::now();
+    let markets = timed(target.timeout, fetch_markets(target)).await;
+    let token = markets.as_ref().ok().map(|market| market.token_id.clone());
+    steps.push(SelftestStep {
+        name: "Markets",
+        outcome: markets.map(|market| market.detail),
+        elapsed: started.elapsed(),
+    });
+    let Some(token) = token else {
+        return steps;
+    };
+
+    let started = Instant::now();
+    let book = timed(target.timeout, fetch_book(target, &token)).await;
+    let book_ok = book.is_ok();
+    steps.push(SelftestStep {
+        name: "Order book",
+        outcome: book,
+        elapsed: started.elapsed(),
+    });
+
+    if book_ok && !target.skip_ws {
+        let started = Instant::now();
+        steps.push(SelftestStep {
+            name: "Market feed",
+            outcome: timed(target.timeout, stream_book(target, &token)).await,
+            elapsed: started.elapsed(),
+        });
+    }
+
+    steps
+}
+
+async fn timed<T>(timeout: Duration, step: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(timeout, step)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", timeout.as_secs()))?
+}
+
+/// First markets page; picks the token later steps use
+async fn fetch_markets(target: &SelftestTarget) -> Result<SelftestMarket> {
+    let client = ClobClient::new(&target.host);
+    let response = client.get_markets(None).await?;
+    let markets = response
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("markets response has no 'data' array"))?;
+
+    let token = match &target.token {
+        Some(token) => token.clone(),
+        None => markets
+            .iter()
+            .filter(|m| m.get("accepting_orders").and_then(Value::as_bool).unwrap_or(false))
+            .find_map(|m| m.get("tokens")?.get(0)?.get("token_id")?.as_str().map(str::to_string))
+            .ok_or_else(|| anyhow!("none of the {} listed markets accepts orders", markets.len()))?,
+    };
+
+    Ok(SelftestMarket {
+        detail: format!("{} markets listed, using token {}", markets.len(), short(&token)),
+        token_id: token,
+    })
+}
+
+async fn fetch_book(target: &SelftestTarget, token: &str) -> Result<String> {
+    let url = format!("{}/book", target.host);
+    let response = reqwest::Client::new()
+        .get(&url)
+        .query(&[("token_id", token)])
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("{} returned {}", url, status));
+    }
+    let book: Value = response.json().await.context("order book is not JSON")?;
+    let levels = |side: &str| book.get(side).and_then(Value::as_array).map_or(0, Vec::len);
+    Ok(format!("{} bid and {} ask levels", levels("bids"), levels("asks")))
+}
+
+/// Subscribe to the token and wait for its book snapshot
+async fn stream_book(target: &SelftestTarget, token: &str) -> Result<String> {
+    let config = WsConfig {
+        market_url: target.ws_url.clone(),
+        max_reconnection_attempts: 1,
+        ..Default::default()
+    };
+    let client = WsClient::new_market(config).await?;
+    let mut messages = client.messages();
+    client.subscribe_market(vec![token.to_string()])?;
+
+    let result = loop {
+        match messages.recv().await {
+            Ok(msg)
+                if msg.event_type == "book"
+                    && msg.asset_id() == Some(token) =>
+            {
+                break Ok(format!("book snapshot received from {}", target.ws_url));
+            }
+            Ok(_) => continue,
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(e) => break Err(anyhow!("feed closed before a book snapshot: {}", e)),
+        }
+    };
+    let _ = client.disconnect();
+    result
+}
+
+fn short(token: &str) -> String {
+    if token.len() > 16 {
+        format!("{}…", &token[..16])
+    } else {
+        token.to_string()
+    }
+}
+
+pub struct SelftestCommand {
+    args: SelftestArgs,
+}
+
+impl SelftestCommand {
+    pub fn new(args: SelftestArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, _data_paths: DataPaths) -> Result<()> {
+        let target = SelftestTarget::from(&self.args);
+        info!("Running selftest against {} and {}", target.host, target.ws_url);
+        println!("\n{} {}\n", "🧪 Polybot selftest against".bright_blue().bold(), target.host);
+
+        let steps = run_selftest(&target).await;
+        for step in &steps {
+            match &step.outcome {
+                Ok(detail) => println!(
+                    "  [{}] {:<12} {} ({} ms)",
+                    "PASS".bright_green().bold(),
+                    step.name,
+                    detail,
+                    step.elapsed.as_millis()
+                ),
+                Err(e) => {
+                    warn!("selftest: {} failed: {:#}", step.name, e);
+                    println!("  [{}] {:<12} {:#}", "FAIL".bright_red().bold(), step.name, e);
+                }
+            }
+        }
+
+        let failed = steps.iter().filter(|s| !s.passed()).count();
+        if failed > 0 {
+            println!();
+            return Err(anyhow!("{} selftest step(s) failed", failed));
+        }
+        println!("\n  {}\n", "Install looks good".bright_green());
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@ use clap::Args;
 use rust_decimal::Decimal;
 use tracing::{info, warn};
 use crate::core::portfolio::cli::enhanced_sell_command;
+use crate::core::risk::{confirm_large_order_cli, RiskConfig, RiskJournal};
+use crate::core::types::common::Side;
 
 #[derive(Args, Clone)]
 pub struct SellArgs {
@@ -25,6 +27,10 @@ pub struct SellArgs {
     /// Confirm order placement (required unless RUST_ENV=production)
     #[arg(long)]
     pub yes: bool,
+
+    /// Confirm orders above the large order notional threshold without prompting
+    #[arg(long)]
+    pub yes_large: bool,
 }
 
 pub struct SellCommand {
@@ -43,6 +49,21 @@ impl SellCommand {
             return Ok(());
         }
 
+        // Second confirmation for orders above the large order threshold
+        let risk_config = RiskConfig::load(&data_paths)?;
+        if let Some(large) = risk_config.large_orders.check(
+            &self.args.token_id,
+            Side::Sell,
+            self.args.price,
+            self.args.size,
+        ) {
+            let journal = RiskJournal::new(&data_paths);
+            if !confirm_large_order_cli(&large, self.args.yes_large, &journal)? {
+                warn!("❌ Large order not confirmed, nothing was placed.");
+                return Ok(());
+            }
+        }
+
         info!("Executing sell command for token: {}", self.args.token_id);
         
         // Use the enhanced sell command from portfolio system
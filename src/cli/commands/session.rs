@@ -0,0 +1,147 @@
+//! Session command: activity and P&L reports of past sessions
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+
+use crate::core::session::{self, SessionReport};
+use crate::data_paths::DataPaths;
+use crate::number_format;
+use crate::typed_store::freshness::format_age;
+
+#[derive(Args, Clone)]
+pub struct SessionArgs {
+    #[command(subcommand)]
+    pub action: SessionAction,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum SessionAction {
+    /// Show the report of the last session that placed or cancelled orders
+    Summary {
+        /// Report of this session instead (id or id prefix)
+        #[arg(long)]
+        id: Option<String>,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List recent sessions with their order counts and P&L
+    List {
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+pub struct SessionCommand {
+    args: SessionArgs,
+}
+
+impl SessionCommand {
+    pub fn new(args: SessionArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let reports = session::load_reports(&data_paths)?;
+        match &self.args.action {
+            SessionAction::Summary { id, json } => {
+                let report = match id {
+                    Some(id) => reports
+                        .iter()
+                        .find(|report| report.session.id.starts_with(id.as_str()))
+                        .ok_or_else(|| anyhow!("No session report matching '{}'", id))?,
+                    None => reports.first().ok_or_else(|| {
+                        anyhow!("No session reports yet (sessions without orders aren't reported)")
+                    })?,
+                };
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(report)?);
+                } else {
+                    print_report(report);
+                }
+            }
+            SessionAction::List { limit } => {
+                if reports.is_empty() {
+                    println!("No session reports yet");
+                    return Ok(());
+                }
+                println!(
+                    "{:<12}  {:<16}  {:<18}  {:>6}  {:>6}  {:>6}  {:>10}",
+                    "ID", "STARTED", "COMMAND", "PLACED", "FILLED", "ERRORS", "NET P&L"
+                );
+                for report in reports.iter().take(*limit) {
+                    println!(
+                        "{:<12}  {:<16}  {:<18}  {:>6}  {:>6}  {:>6}  {:>10}",
+                        report.session.id,
+                        report.session.started_at.format("%Y-%m-%d %H:%M"),
+                        report.session.command,
+                        report.orders_placed,
+                        report.orders_filled,
+                        report.errors.iter().map(|e| e.count).sum::<usize>(),
+                        number_format::usd(report.net_pnl())
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Print a session report; also shown when a session that traded ends
+pub fn print_report(report: &SessionReport) {
+    println!();
+    println!(
+        "📋 Session {} ({}, {})",
+        report.session.id,
+        report.session.command,
+        format_age(report.duration())
+    );
+    println!(
+        "   Orders:    {} placed, {} filled, {} cancelled{}",
+        report.orders_placed,
+        report.orders_filled,
+        report.orders_cancelled,
+        match (report.orders_rejected, report.cancels_failed) {
+            (0, 0) => String::new(),
+            (rejected, failed) => format!(" ({} rejected, {} cancels failed)", rejected, failed),
+        }
+    );
+    if report.fills_available {
+        println!(
+            "   Volume:    {} over {} fills ({} shares)",
+            number_format::usd(report.volume),
+            report.fills,
+            number_format::size(report.shares_traded)
+        );
+        println!(
+            "   P&L:       {} realized, {} fees, {} net",
+            number_format::usd(report.realized_pnl),
+            number_format::usd(report.fees),
+            number_format::usd(report.net_pnl())
+        );
+        println!(
+            "   Exposure:  {} at most",
+            number_format::usd(report.max_exposure)
+        );
+        if !report.unmatched_sell_shares.is_zero() {
+            println!(
+                "   Note:      {} shares sold were bought before this session and aren't in the P&L",
+                report.unmatched_sell_shares.normalize()
+            );
+        }
+    } else {
+        println!("   Fills:     unavailable (trade history couldn't be fetched)");
+    }
+    for (index, error) in report.errors.iter().enumerate() {
+        println!(
+            "   {} {}× {}",
+            if index == 0 {
+                "Errors:   "
+            } else {
+                "          "
+            },
+            error.count,
+            error.message
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Signer command: choose whether the profile signs with its stored key or a Ledger
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+
+use crate::auth::{OrderFunder, SignatureType, Signer, SignerBackend};
+use crate::config;
+use crate::data_paths::DataPaths;
+
+#[derive(Args, Clone)]
+pub struct SignerArgs {
+    #[command(subcommand)]
+    pub command: SignerSubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum SignerSubcommand {
+    /// Show the profile's signing backend and its address
+    Show,
+    /// Sign with the private key stored by `init --pk`
+    UseLocal,
+    /// Sign orders and transactions on a Ledger device
+    UseLedger {
+        /// BIP-32 derivation path of the account (default: m/44'/60'/0'/0/0)
+        #[arg(long)]
+        derivation_path: Option<String>,
+        /// Proxy or Safe wallet that holds the funds, when orders aren't made from the device address
+        #[arg(long)]
+        funder: Option<String>,
+        /// How the exchange checks the funder's orders
+        #[arg(long, value_enum, default_value = "poly-proxy", requires = "funder")]
+        signature_type: SignatureType,
+    },
+    /// Make the profile read-only: monitor an address, refuse anything that signs
+    ReadOnly {
+        /// Address to monitor
+        address: String,
+    },
+}
+
+pub struct SignerCommand {
+    args: SignerArgs,
+}
+
+impl SignerCommand {
+    pub fn new(args: SignerArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let current = SignerBackend::load(&data_paths)?;
+        match &self.args.command {
+            SignerSubcommand::Show => {
+                if let SignerBackend::ReadOnly { address } = &current {
+                    print_read_only(address);
+                    return Ok(());
+                }
+                let signer = current.connect(&data_paths).await?;
+                print_signer(signer.as_ref());
+            }
+            SignerSubcommand::ReadOnly { address } => {
+                let backend = SignerBackend::read_only(address)?;
+                backend.save(&data_paths)?;
+                print_read_only(address);
+            }
+            SignerSubcommand::UseLocal => {
+                if config::load_private_key(&data_paths).await.is_err() {
+                    return Err(anyhow!("No private key stored for this profile. Run 'polybot init --pk <key>'"));
+                }
+                self.switch(&data_paths, &current, SignerBackend::Local).await?;
+            }
+            SignerSubcommand::UseLedger {
+                derivation_path,
+                funder,
+                signature_type,
+            } => {
+                let funder = funder
+                    .as_deref()
+                    .map(|address| OrderFunder::new(address, *signature_type))
+                    .transpose()?;
+                let backend = SignerBackend::ledger(derivation_path.clone(), funder);
+                self.switch(&data_paths, &current, backend.clone()).await?;
+                if let Some(funder) = backend.funder() {
+                    println!("   Orders:  made from {} ({:?})", funder.address, funder.signature_type);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn switch(&self, data_paths: &DataPaths, current: &SignerBackend, backend: SignerBackend) -> Result<()> {
+        // The previous signer is dropped before connecting, so a Ledger is only opened once
+        let previous = current.connect(data_paths).await.ok().map(|s| s.address());
+        // Connect before saving so a missing device or key leaves the profile unchanged
+        let signer = backend.connect(data_paths).await?;
+        backend.save(data_paths)?;
+        print_signer(signer.as_ref());
+
+        if previous.is_some_and(|address| address != signer.address()) {
+            println!("\n⚠️  This is a different address than before; the stored API credentials");
+            println!("   belong to the old one. Run 'polybot init' again to create new ones.");
+        }
+        Ok(())
+    }
+}
+
+fn print_read_only(address: &str) {
+    println!("👀 Signer:  none (read-only profile)");
+    println!("   Address: {}", address);
+}
+
+fn print_signer(signer: &dyn Signer) {
+    println!("🔏 Signer:  {}", signer.describe());
+    println!("   Address: {}", signer.address_hex());
+}
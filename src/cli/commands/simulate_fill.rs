@@ -0,0 +1,331 @@
+//! Simulate-fill command: estimate fill probability for a hypothetical resting order
+//!
+//! Replays a recording of raw market WebSocket messages (one JSON message or
+//! array of messages per line) through the queue position model in
+//! `core::execution::fill_model`. Without `--recording`, the market feed is
+//! captured live for `--record-secs` first and saved under `recordings/` so the
+//! same capture can be re-used with different order parameters.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use owo_colors::OwoColorize;
+use rust_decimal::Decimal;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::core::execution::fill_model::{
+    FillEstimate, FillModel, FillModelConfig, RestingOrder, TimedEvent,
+};
+use crate::core::types::common::Side;
+use crate::core::ws::{parse_message, PolyEvent, WsClient, WsConfig, WsMessage};
+use crate::data_paths::DataPaths;
+use crate::number_format;
+
+/// Directory (relative to the data directory) for live captures
+const RECORDINGS_DIR: &str = "recordings";
+
+#[derive(Args, Clone)]
+pub struct SimulateFillArgs {
+    /// Token ID the order rests on
+    #[arg(long)]
+    pub token: String,
+
+    /// Order side (buy or sell)
+    #[arg(long, default_value = "buy")]
+    pub side: String,
+
+    /// Limit price (e.g., 0.48)
+    #[arg(long)]
+    pub price: Decimal,
+
+    /// Order size in shares
+    #[arg(long)]
+    pub size: Decimal,
+
+    /// How long the order rests, in seconds
+    #[arg(long, default_value = "300")]
+    pub window: u64,
+
+    /// Spacing between simulated placement times, in seconds
+    #[arg(long, default_value = "10")]
+    pub step: u64,
+
+    /// Recording of raw market WebSocket messages (JSONL)
+    #[arg(long)]
+    pub recording: Option<PathBuf>,
+
+    /// Capture the live market feed for this many seconds when no recording is given
+    #[arg(long, default_value = "600")]
+    pub record_secs: u64,
+
+    /// Print every trial
+    #[arg(long)]
+    pub trials: bool,
+}
+
+pub struct SimulateFillCommand {
+    args: SimulateFillArgs,
+}
+
+impl SimulateFillCommand {
+    pub fn new(args: SimulateFillArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        let side = match self.args.side.to_lowercase().as_str() {
+            "buy" => Side::Buy,
+            "sell" => Side::Sell,
+            other => return Err(anyhow!("Invalid side '{}': expected buy or sell", other)),
+        };
+        if self.args.price <= Decimal::ZERO || self.args.price >= Decimal::ONE {
+            return Err(anyhow!("Price must be between 0 and 1"));
+        }
+        if self.args.size <= Decimal::ZERO {
+            return Err(anyhow!("Size must be positive"));
+        }
+
+        let recording = match &self.args.recording {
+            Some(path) => path.clone(),
+            None => self.record_live(&data_paths).await?,
+        };
+
+        let events = load_recording(&recording, &self.args.token)?;
+        if events.is_empty() {
+            return Err(anyhow!(
+                "No events for token {} in {}",
+                self.args.token,
+                recording.display()
+            ));
+        }
+
+        let model = FillModel::new(events);
+        let window = Duration::from_secs(self.args.window);
+        if model.span() < window {
+            warn!(
+                "Recording covers {}s, shorter than the {}s window; a single truncated trial will be used",
+                model.span().as_secs(),
+                self.args.window
+            );
+        }
+
+        let order = RestingOrder {
+            side,
+            price: self.args.price,
+            size: self.args.size,
+        };
+        let config = FillModelConfig {
+            window,
+            step: Duration::from_secs(self.args.step.max(1)),
+        };
+        let estimate = model.estimate(&order, &config);
+        if estimate.trials.is_empty() {
+            return Err(anyhow!(
+                "Recording has no order book snapshot for token {}; cannot place the order",
+                self.args.token
+            ));
+        }
+
+        self.print_estimate(&order, &model, &recording, &estimate);
+        Ok(())
+    }
+
+    /// Capture raw market messages for the token and save them as JSONL
+    async fn record_live(&self, data_paths: &DataPaths) -> Result<PathBuf> {
+        let dir = data_paths.data().join(RECORDINGS_DIR);
+        fs::create_dir_all(&dir)?;
+        let token_prefix: String = self.args.token.chars().take(16).collect();
+        let path = dir.join(format!(
+            "{}-{}.jsonl",
+            token_prefix,
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        println!(
+            "📡 No recording given, capturing market feed for {}s → {}",
+            self.args.record_secs,
+            path.display()
+        );
+
+        let client = WsClient::new_market(WsConfig::default())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to market feed: {}", e))?;
+        client
+            .subscribe_market(vec![self.args.token.clone()])
+            .map_err(|e| anyhow!("Failed to subscribe: {}", e))?;
+        let mut messages = client.messages();
+
+        let mut file = fs::File::create(&path)?;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(self.args.record_secs);
+        let mut count = 0usize;
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => break,
+                _ = tokio::signal::ctrl_c() => {
+                    println!("⏹️  Capture interrupted, simulating on what was recorded");
+                    break;
+                }
+                msg = messages.recv() => match msg {
+                    Ok(msg) => {
+                        writeln!(file, "{}", msg.json())?;
+                        count += 1;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Recorder lagged, {} messages dropped", n);
+                    }
+                    Err(_) => break,
+                },
+            }
+        }
+
+        let _ = client.disconnect();
+        file.flush()?;
+        println!("💾 Recorded {} messages", count);
+        Ok(path)
+    }
+
+    fn print_estimate(
+        &self,
+        order: &RestingOrder,
+        model: &FillModel,
+        recording: &Path,
+        estimate: &FillEstimate,
+    ) {
+        println!();
+        println!("{}", "🎯 Fill Simulation".bright_cyan().bold());
+        println!(
+            "Order:      {} {} @ {} on {}",
+            order.side, order.size, order.price, self.args.token
+        );
+        println!(
+            "Recording:  {} ({} events, {}s)",
+            recording.display(),
+            model.event_count(),
+            model.span().as_secs()
+        );
+        println!(
+            "Trials:     {} placements, {}s window, every {}s",
+            estimate.trials.len(),
+            self.args.window,
+            self.args.step
+        );
+        println!();
+
+        let probability = number_format::percent(estimate.fill_probability * 100.0);
+        let colored = if estimate.fill_probability >= 0.5 {
+            probability.bright_green().to_string()
+        } else if estimate.fill_probability > 0.0 {
+            probability.bright_yellow().to_string()
+        } else {
+            probability.bright_red().to_string()
+        };
+        println!("Full fill probability:    {}", colored);
+        println!(
+            "Partial fill probability: {}",
+            number_format::percent(estimate.partial_fill_probability * 100.0)
+        );
+        println!(
+            "Expected fill ratio:      {}",
+            number_format::percent(estimate.expected_fill_ratio * 100.0)
+        );
+        println!(
+            "Expected time to fill:    {}",
+            format_duration(estimate.expected_time_to_fill)
+        );
+        println!(
+            "Median time to fill:      {}",
+            format_duration(estimate.median_time_to_fill)
+        );
+        println!("Mean queue ahead:         {}", estimate.mean_queue_ahead.round_dp(2));
+        if estimate.marketable_trials > 0 {
+            println!(
+                "{}",
+                format!(
+                    "⚠️  Order crosses the spread in {} of {} trials (taker fill, not resting)",
+                    estimate.marketable_trials,
+                    estimate.trials.len()
+                )
+                .yellow()
+            );
+        }
+
+        if self.args.trials {
+            println!();
+            println!(
+                "{:<24} {:>12} {:>10} {:>12}",
+                "Placed", "Queue ahead", "Filled", "Full fill"
+            );
+            for trial in &estimate.trials {
+                let placed = chrono::DateTime::from_timestamp_millis(trial.placed_at_ms as i64)
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| trial.placed_at_ms.to_string());
+                println!(
+                    "{:<24} {:>12} {:>10} {:>12}",
+                    placed,
+                    trial.initial_queue_ahead.round_dp(2),
+                    trial.filled.round_dp(2),
+                    format_duration(trial.time_to_full_fill)
+                );
+            }
+        }
+    }
+}
+
+/// Load timed events for one token from a JSONL recording of raw market messages
+pub fn load_recording(path: &Path, token: &str) -> Result<Vec<TimedEvent>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open recording {}", path.display()))?;
+
+    let mut events = Vec::new();
+    let mut last_timestamp = 0u64;
+    let mut skipped = 0usize;
+    let mut messages = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        messages.clear();
+        if WsMessage::parse_frame(&line, &mut messages).is_err() {
+            skipped += 1;
+            continue;
+        }
+
+        for msg in &messages {
+            // Messages without a timestamp inherit the previous one
+            if let Some(ts) = msg.timestamp() {
+                last_timestamp = ts;
+            }
+            let Ok(parsed) = parse_message(msg) else {
+                skipped += 1;
+                continue;
+            };
+            for event in parsed {
+                if event.market_asset_id() == Some(token) {
+                    events.push(TimedEvent {
+                        timestamp_ms: last_timestamp,
+                        event,
+                    });
+                }
+            }
+        }
+    }
+
+    if skipped > 0 {
+        debug!("Skipped {} unparseable messages in {}", skipped, path.display());
+    }
+    Ok(events)
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(d) if d.as_secs() >= 60 => format!("{}m {}s", d.as_secs() / 60, d.as_secs() % 60),
+        Some(d) => format!("{:.1}s", d.as_secs_f64()),
+        None => "-".to_string(),
+    }
+}
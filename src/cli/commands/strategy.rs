@@ -0,0 +1,186 @@
+//! Strategy command: performance reports over the account's fills
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use clap::{Args, Subcommand};
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+use tracing::warn;
+
+use crate::core::portfolio::attribution::{self, AttributionRow, Mark};
+use crate::core::portfolio::storage::file::PortfolioStorage;
+use crate::core::types::common::ShortId;
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::{ClobTokenId, GammaClient, PriceHistoryQuery};
+
+#[derive(Args, Clone)]
+pub struct StrategyArgs {
+    #[command(subcommand)]
+    pub command: StrategySubcommand,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum StrategySubcommand {
+    /// Split market-making P&L into spread capture, inventory drift and fees,
+    /// per day and token
+    Attribution {
+        /// Days to report, counting back from now
+        #[arg(long, default_value = "7")]
+        days: i64,
+
+        /// Only this token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Minutes between price history points used as the mid
+        #[arg(long, default_value = "5")]
+        fidelity: u32,
+
+        /// Print rows as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub struct StrategyCommand {
+    args: StrategyArgs,
+}
+
+impl StrategyCommand {
+    pub fn new(args: StrategyArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, _host: &str, data_paths: DataPaths) -> Result<()> {
+        match &self.args.command {
+            StrategySubcommand::Attribution {
+                days,
+                token,
+                fidelity,
+                json,
+            } => attribution(data_paths, *days, token.as_deref(), *fidelity, *json).await,
+        }
+    }
+}
+
+async fn attribution(
+    data_paths: DataPaths,
+    days: i64,
+    token: Option<&str>,
+    fidelity: u32,
+    json: bool,
+) -> Result<()> {
+    let now = Utc::now();
+    let since = now - Duration::days(days.max(1));
+
+    // Every fill the portfolio service recorded, so fills before the window
+    // give the opening position
+    let address = crate::auth::account_address(&data_paths).await?;
+    let storage = PortfolioStorage::new(data_paths.root(), &address);
+    let fills: Vec<_> = storage
+        .load_all_trade_history()
+        .await?
+        .into_iter()
+        .filter(|fill| fill.timestamp <= now)
+        .filter(|fill| token.is_none_or(|token| fill.asset_id == token))
+        .collect();
+
+    let tokens: BTreeSet<&str> = fills
+        .iter()
+        .filter(|fill| fill.timestamp >= since)
+        .map(|fill| fill.asset_id.as_str())
+        .collect();
+    if tokens.is_empty() {
+        println!("No fills in the last {} days.", days);
+        return Ok(());
+    }
+
+    let client = GammaClient::new();
+    let mut marks = HashMap::new();
+    for token_id in &tokens {
+        // Start a bit early so there is a mid going into the window
+        let query = PriceHistoryQuery {
+            market: ClobTokenId(token_id.to_string()),
+            start_ts: Some(since - Duration::hours(6)),
+            end_ts: Some(now),
+            interval: None,
+            fidelity: Some(fidelity),
+        };
+        match client.fetch_price_history(&query).await {
+            Ok(history) => {
+                let mut points: Vec<Mark> = history
+                    .history
+                    .into_iter()
+                    .map(|point| Mark {
+                        timestamp: point.timestamp,
+                        price: point.price,
+                    })
+                    .collect();
+                points.sort_by_key(|mark| mark.timestamp);
+                marks.insert(token_id.to_string(), points);
+            }
+            Err(e) => warn!("No price history for {}: {}", token_id, e),
+        }
+    }
+
+    let rows = attribution::attribute(&fills, &marks, since);
+    if json {
+        for row in &rows {
+            println!("{}", serde_json::to_string(row)?);
+        }
+        return Ok(());
+    }
+
+    println!("\n📐 Market-Making Attribution (last {} days)\n", days);
+    let skipped = tokens.iter().filter(|t| !marks.contains_key(**t)).count();
+    if rows.is_empty() {
+        println!("No fills could be attributed.");
+    } else {
+        print_rows(&rows);
+    }
+    if skipped > 0 {
+        println!(
+            "\n⚠️  {} token(s) skipped for lack of price history",
+            skipped
+        );
+    }
+    println!("\n💡 Spread is earned against the mid at fill time; drift is the held position");
+    println!("   marked to the mid as it moves. Mids come from the CLOB price history.");
+    Ok(())
+}
+
+fn print_rows(rows: &[AttributionRow]) {
+    println!(
+        "{:<10}  {:<15}  {:>5}  {:>10}  {:>10}  {:>10}  {:>8}  {:>10}  {:>10}",
+        "Date", "Token", "Fills", "Volume", "Spread", "Drift", "Fees", "Total", "Position"
+    );
+    println!("{}", "-".repeat(107));
+    for row in rows {
+        println!(
+            "{:<10}  {:<15}  {:>5}  {:>10.2}  {:>10.2}  {:>10.2}  {:>8.2}  {:>10.2}  {:>10.2}",
+            row.date.format("%Y-%m-%d"),
+            ShortId(&row.token_id).to_string(),
+            row.fills,
+            row.volume,
+            row.spread_capture,
+            row.inventory_drift,
+            row.fees,
+            row.total(),
+            row.end_position
+        );
+    }
+
+    let sum = |f: fn(&AttributionRow) -> Decimal| rows.iter().map(f).sum::<Decimal>();
+    println!("{}", "-".repeat(107));
+    println!(
+        "{:<10}  {:<15}  {:>5}  {:>10.2}  {:>10.2}  {:>10.2}  {:>8.2}  {:>10.2}",
+        "Total",
+        "",
+        rows.iter().map(|r| r.fills).sum::<usize>(),
+        sum(|r| r.volume),
+        sum(|r| r.spread_capture),
+        sum(|r| r.inventory_drift),
+        sum(|r| r.fees),
+        sum(AttributionRow::total)
+    );
+}
@@ -1,31 +1,49 @@
 //! Stream command for real-time WebSocket data
+//!
+//! With `--no-tui` the command is a pipeline building block: events can be
+//! filtered by kind (`--only`) and token (`--token`, `--watchlist`), written as
+//! pretty lines or JSON lines (`--format`), and sent to stdout, a file or a
+//! Unix socket (`--sink`). Logs go to stderr, so stdout carries only events.
+//! `--trade-flow` adds sweeps and large prints detected in the trade stream.
+//!
+//! `--record` also writes every received event into a managed dataset
+//! (`--dataset-name`, default `captures/${datetime}`), in the TUI or not; see
+//! [`crate::markets::datasets::capture`].
+//!
+//! Tokens from a selection, markets file or the dataset selector are screened
+//! by the suppression list ([`crate::markets::suppression`]) before
+//! subscribing; tokens named with `--assets` are streamed as given.
+
+mod output;
+mod recorder;
+mod sink;
 
 use anyhow::Result;
 use clap::Args;
-use owo_colors::OwoColorize;
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fs;
+use std::io::IsTerminal;
 use std::time::Duration;
 use tokio::signal;
 use tracing::{debug, error, info, warn};
 
 use crate::data_paths::DataPaths;
+use crate::markets::datasets::capture::{StreamCapture, DEFAULT_CAPTURE_NAME};
 use crate::markets::datasets::SelectionManager;
+use crate::markets::suppression::{SuppressionConfig, SUPPRESSION_CONFIG_FILE};
 use crate::logging::{init_logging, LogMode, LoggingConfig};
+use crate::core::execution::trade_flow::{TradeFlowAnalyzer, CLOSE_CHECK_INTERVAL};
 use crate::core::services::{Streamer, StreamerConfig};
-use crate::tui::{events, ui, App, EventHandler};
+#[cfg(feature = "tui")]
+use crate::tui::runner::{run_app, RunOptions};
 use crate::core::ws::{AuthPayload, PolyEvent, WsConfig};
-use crate::core::types::common::Side;
-
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use futures::FutureExt;
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io::{self, Stdout};
-use std::sync::Arc;
+use crate::typed_store::freshness::default_db_path;
+use output::{EventFilter, EventOutput};
+use recorder::{report_capture, CaptureRecorder};
+pub use output::{EventKind, StreamFormat};
+use sink::{SinkWriter, SINK_FLUSH_INTERVAL};
+pub use sink::StreamSink;
 
 #[derive(Args, Clone)]
 pub struct StreamArgs {
@@ -65,18 +83,42 @@ pub struct StreamArgs {
     #[arg(long, default_value = "0")]
     pub max_reconnection_attempts: u32,
 
-    /// Show order book updates
+    /// Show order book updates (same as `--only book,price-change`)
     #[arg(long)]
     pub show_book: bool,
 
-    /// Show trade updates
+    /// Show trade updates (same as `--only trades`)
     #[arg(long)]
     pub show_trades: bool,
 
-    /// Show user order/trade updates
+    /// Show user order/trade updates (same as `--only orders,fills`)
     #[arg(long)]
     pub show_user: bool,
-    
+
+    /// Only output these event kinds (comma-separated; default: all)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub only: Vec<EventKind>,
+
+    /// Only output events for these tokens (comma-separated)
+    #[arg(long = "token", value_delimiter = ',')]
+    pub token_filter: Vec<String>,
+
+    /// Only output events for the tokens of a saved selection
+    #[arg(long)]
+    pub watchlist: Option<String>,
+
+    /// Also output sweeps and large prints detected in the trade stream
+    #[arg(long)]
+    pub trade_flow: bool,
+
+    /// Output format for `--no-tui`
+    #[arg(long, value_enum, default_value_t)]
+    pub format: StreamFormat,
+
+    /// Output destination for `--no-tui`: stdout, file:<path> or unix:<path>
+    #[arg(long, default_value = "stdout")]
+    pub sink: StreamSink,
+
     /// Debug: directly open orderbook view for the first token
     #[arg(long)]
     pub debug_orderbook: bool,
@@ -89,11 +131,38 @@ pub struct StreamArgs {
     #[arg(long)]
     pub sandbox: bool,
 
-    /// Use TUI interface (default: true, use --no-tui to disable)
+    /// Record every received event into a managed dataset
+    #[arg(long)]
+    pub record: bool,
+
+    /// Dataset for `--record`, under the datasets directory; `${date}`,
+    /// `${datetime}`, `${timestamp}`, ... are filled in (quote them in the shell)
+    #[arg(long, requires = "record", value_name = "NAME")]
+    pub dataset_name: Option<String>,
+
+    /// Stream tokens of markets suppressed by suppression.yaml too
+    #[arg(long)]
+    pub no_suppress: bool,
+
+    /// Use TUI interface (default: true, use --no-tui to disable; output
+    /// options imply --no-tui)
     #[arg(long, default_value = "true", action = clap::ArgAction::Set)]
     pub tui: bool,
 }
 
+impl StreamArgs {
+    /// Whether any pipeline output option was given
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    fn has_output_options(&self) -> bool {
+        !self.only.is_empty()
+            || !self.token_filter.is_empty()
+            || self.watchlist.is_some()
+            || self.trade_flow
+            || self.format != StreamFormat::Pretty
+            || self.sink != StreamSink::Stdout
+    }
+}
+
 pub struct StreamCommand {
     args: StreamArgs,
 }
@@ -104,17 +173,20 @@ impl StreamCommand {
     }
 
     pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
-        if self.args.tui {
+        #[cfg(feature = "tui")]
+        if self.args.tui && !self.args.has_output_options() {
             // For TUI mode: Use file-only logging but show nice console progress first
-            self.execute_tui_with_progress(host, data_paths).await
-        } else {
-            // For CLI mode: Use console and file logging throughout
-            let logging_config = LoggingConfig::new(LogMode::ConsoleAndFile, data_paths.clone());
-            init_logging(logging_config)?;
-            self.execute_cli(host, data_paths).await
+            return self.execute_tui_with_progress(host, data_paths).await;
         }
+
+        // For CLI mode (and headless builds, whatever --tui says): Use console
+        // and file logging throughout
+        let logging_config = LoggingConfig::new(LogMode::ConsoleAndFile, data_paths.clone());
+        init_logging(logging_config)?;
+        self.execute_cli(host, data_paths).await
     }
 
+    #[cfg(feature = "tui")]
     async fn execute_tui_with_progress(&self, host: &str, data_paths: DataPaths) -> Result<()> {
         // Initialize file-only logging for TUI mode
         let logging_config = LoggingConfig::new(LogMode::FileOnly, data_paths.clone());
@@ -204,6 +276,11 @@ impl StreamCommand {
             auto_sync_on_hash_mismatch: true,
         };
 
+        let capture = self.open_capture(&data_paths)?;
+        if let Some(capture) = &capture {
+            println!("📼 Recording to {}", capture.path().display());
+        }
+
         // Create and start streamer with progress
         println!("\n🔌 Connecting to WebSocket...");
         let mut streamer = Streamer::new(streamer_config);
@@ -218,321 +295,29 @@ impl StreamCommand {
                 return Err(anyhow::anyhow!("Connection failed: {}", e));
             }
         }
+        let recorder = capture.map(|capture| CaptureRecorder::spawn(capture, &streamer));
 
         // Wait for initial data with proper timeout handling and progress feedback
         self.wait_for_initial_data(&streamer).await?;
 
         println!("\n🎨 Starting TUI interface...");
-        println!("💡 Navigation: Tab/Shift+Tab cycle pages | 1-5 direct page access | q: Quit");
+        println!("💡 Navigation: Tab/Shift+Tab cycle pages | 1-7 direct page access | q: Quit");
         println!("📄 Logs: {}", data_paths.logs().display());
 
         // Small delay to let user see the message
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
         // Continue with TUI execution
-        self.execute_tui_inner_with_streamer(host, data_paths, streamer)
-            .await
-    }
-
-    async fn execute_tui_inner_with_streamer(
-        &self,
-        host: &str,
-        data_paths: DataPaths,
-        streamer: Streamer,
-    ) -> Result<()> {
-        // Set up panic hook for proper terminal cleanup
-        let original_hook = std::panic::take_hook();
-        std::panic::set_hook(Box::new(move |panic_info| {
-            // Try to restore terminal on panic
-            let _ = disable_raw_mode();
-            let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
-
-            // Call original panic hook
-            original_hook(panic_info);
-        }));
-
-        // Wrap the entire TUI execution in a catch_unwind for additional safety
-        let result = std::panic::AssertUnwindSafe(
-            self.execute_tui_inner_with_streamer_core(host, data_paths, streamer),
-        )
-        .catch_unwind()
-        .await;
-
-        // Restore original panic hook
-        let _ = std::panic::take_hook();
-
-        match result {
-            Ok(Ok(res)) => Ok(res),
-            Ok(Err(e)) => Err(e),
-            Err(panic) => {
-                let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
-                    s.clone()
-                } else if let Some(s) = panic.downcast_ref::<&str>() {
-                    s.to_string()
-                } else {
-                    "Unknown panic occurred".to_string()
-                };
-                Err(anyhow::anyhow!("TUI panicked: {}", panic_msg))
-            }
-        }
-    }
-
-    async fn execute_tui_inner_with_streamer_core(
-        &self,
-        host: &str,
-        data_paths: DataPaths,
-        streamer: Streamer,
-    ) -> Result<()> {
-        let streamer_arc = Arc::new(streamer);
-
-        // Setup terminal with error handling and fallback
-        let mut terminal = match setup_terminal() {
-            Ok(terminal) => terminal,
-            Err(e) => {
-                error!("Failed to setup terminal for TUI: {}", e);
-
-                // Check if this is a terminal device error
-                if e.to_string().contains("Device not configured")
-                    || e.to_string().contains("not a terminal")
-                    || e.to_string().contains("Inappropriate ioctl")
-                {
-                    warn!("Terminal not available for TUI, falling back to CLI mode with FileOnly logging");
-                    // Would need to fall back to CLI here, but that's complex
-                    return Err(anyhow::anyhow!("Terminal not available: {}", e));
-                } else {
-                    return Err(anyhow::anyhow!("Failed to setup terminal: {}", e));
-                }
-            }
-        };
-
-        // Create app and configure data access
-        let mut app = App::new(streamer_arc.clone());
-        app.configure_data_access(data_paths.clone(), host.to_string());
-        
-        // Debug mode: directly open orderbook view
-        if self.args.debug_orderbook {
-            info!("Debug mode: directly opening orderbook view");
-            app.event_log.push("🔧 Debug mode: orderbook view".to_string());
-            
-            // For debugging, we'll wait a moment for data and then select the first token
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-
-        // Create event handler with balanced tick rate for UI responsiveness
-        let mut event_handler = EventHandler::new(Duration::from_millis(50));
-
-        // Get event stream
-        let mut events = streamer_arc.events();
-
-        info!("Starting TUI main loop");
-
-        // Main loop with comprehensive error handling
-        let result = loop {
-            // Draw UI with error handling
-            match terminal.draw(|f| {
-                if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    ui::draw(f, &mut app);
-                })) {
-                    error!("UI drawing panicked: {:?}", e);
-                }
-            }) {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Terminal drawing error: {}", e);
-                    break Err(anyhow::anyhow!("Terminal drawing failed: {}", e));
-                }
-            }
-
-            // Handle events with balanced timeout for UI responsiveness
-            let event_timeout = Duration::from_millis(25);
-
-            tokio::select! {
-                // Handle keyboard events first (higher priority for UI responsiveness)
-                ui_event_opt = event_handler.next() => {
-                    match ui_event_opt {
-                        Some(ui_event) => {
-                            match ui_event {
-                                events::Event::Key(key) => {
-                                    // Handle global navigation keys first
-                                    let handled = match key.code {
-                                        KeyCode::Char('q') | KeyCode::Char('Q') => {
-                                            info!("User requested quit");
-                                            app.should_quit = true;
-                                            true
-                                        }
-                                        KeyCode::Tab => {
-                                            app.navigation.next_page();
-                                            // Check if we switched to Orders page and need to fetch data
-                                            if app.navigation.current_page == crate::tui::navigation::Page::Orders && app.should_fetch_orders() {
-                                                app.request_orders_refresh();
-                                            }
-                                            true
-                                        }
-                                        KeyCode::BackTab => {
-                                            app.navigation.previous_page();
-                                            // Check if we switched to Orders page and need to fetch data
-                                            if app.navigation.current_page == crate::tui::navigation::Page::Orders && app.should_fetch_orders() {
-                                                app.request_orders_refresh();
-                                            }
-                                            true
-                                        }
-                                        KeyCode::Char('1') => {
-                                            app.navigation.go_to_page(crate::tui::navigation::Page::Stream);
-                                            true
-                                        }
-                                        KeyCode::Char('2') => {
-                                            app.navigation.go_to_page(crate::tui::navigation::Page::Orders);
-                                            // Fetch orders when navigating to Orders page
-                                            if app.should_fetch_orders() {
-                                                app.request_orders_refresh();
-                                            }
-                                            true
-                                        }
-                                        KeyCode::Char('3') => {
-                                            app.navigation.go_to_page(crate::tui::navigation::Page::Tokens);
-                                            true
-                                        }
-                                        KeyCode::Char('4') => {
-                                            app.navigation.go_to_page(crate::tui::navigation::Page::Markets);
-                                            true
-                                        }
-                                        KeyCode::Char('5') => {
-                                            app.navigation.go_to_page(crate::tui::navigation::Page::Portfolio);
-                                            // Fetch portfolio data when navigating to Portfolio page
-                                            if app.should_fetch_orders() {
-                                                app.request_orders_refresh();
-                                            }
-                                            true
-                                        }
-                                        _ => false,
-                                    };
-
-                                    // If not handled by global navigation, delegate to the current page
-                                    if !handled {
-                                        use crate::tui::navigation::Page;
-                                        use crate::tui::pages::Page as PageTrait;
-
-                                        let current_page = app.navigation.current_page.clone();
-                                        let page_handled = match current_page {
-                                            Page::Stream => {
-                                                let mut page = std::mem::take(&mut app.stream_page);
-                                                let result = page.handle_key(key, &mut app);
-                                                app.stream_page = page;
-                                                result
-                                            },
-                                            Page::Orders => {
-                                                let mut page = std::mem::take(&mut app.orders_page);
-                                                let result = page.handle_key(key, &mut app);
-                                                app.orders_page = page;
-                                                result
-                                            },
-                                            Page::Tokens => {
-                                                let mut page = std::mem::take(&mut app.tokens_page);
-                                                let result = page.handle_key(key, &mut app);
-                                                app.tokens_page = page;
-                                                result
-                                            },
-                                            Page::Markets => {
-                                                let mut page = std::mem::take(&mut app.markets_page);
-                                                let result = page.handle_key(key, &mut app);
-                                                app.markets_page = page;
-                                                result
-                                            },
-                                            Page::Portfolio => {
-                                                let mut page = std::mem::take(&mut app.portfolio_page);
-                                                let result = page.handle_key(key, &mut app);
-                                                app.portfolio_page = page;
-                                                result
-                                            },
-                                        };
-
-                                        // If page didn't handle it, try some common fallbacks
-                                        if !page_handled {
-                                            match key.code {
-                                                KeyCode::Char('r') => {
-                                                    // Add refresh functionality
-                                                    info!("User requested refresh");
-                                                    events = streamer_arc.events();
-                                                    app.event_log.push("🔄 Refreshed event stream".to_string());
-                                                }
-                                                _ => {}
-                                            }
-                                        }
-
-                                        // Check if refresh was requested after key handling
-                                        app.handle_orders_refresh_request();
-                                    }
-                                }
-                                events::Event::Tick => {
-                                    // Regular tick for UI updates
-                                    app.update_fetching_status();
-                                }
-                                events::Event::Error(error_msg) => {
-                                    error!("Event handler error: {}", error_msg);
-                                    app.event_log.push(format!("❌ Input error: {}", error_msg));
-                                }
-                            }
-                        }
-                        None => {
-                            warn!("Event handler channel closed");
-                            app.event_log.push("⚠️ Input handler stopped".to_string());
-                            break Err(anyhow::anyhow!("Input event handler stopped unexpectedly"));
-                        }
-                    }
-                }
-
-                // Handle websocket events (lower priority)
-                ws_event = events.recv() => {
-                    match ws_event {
-                        Ok(event) => {
-                            if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                app.handle_event(event);
-                            })) {
-                                error!("Event handling panicked: {:?}", e);
-                                app.event_log.push("⚠️ Event processing error occurred".to_string());
-                            }
-                        }
-                        Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
-                            warn!("Event channel lagged by {} messages, resubscribing", n);
-                            events = streamer_arc.events();
-                            app.event_log.push(format!("⚠️ Missed {} events due to lag", n));
-                        }
-                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                            error!("Event channel closed, stopping TUI");
-                            app.event_log.push("❌ Event stream closed".to_string());
-                            break Err(anyhow::anyhow!("WebSocket event stream closed unexpectedly"));
-                        }
-                    }
-                }
-
-                // Timeout to ensure we don't hang
-                _ = tokio::time::sleep(event_timeout) => {
-                    // Regular timeout, continue loop
-                }
-            }
-
-            if app.should_quit {
-                info!("Exiting TUI main loop");
-                break Ok(());
-            }
+        let options = RunOptions {
+            debug_orderbook: self.args.debug_orderbook,
+            ..Default::default()
         };
-
-        // Restore terminal with error handling
-        if let Err(e) = restore_terminal(&mut terminal) {
-            error!("Failed to restore terminal: {}", e);
-            // Don't fail the entire operation for terminal restore errors
+        let db_path = default_db_path(&data_paths);
+        let result = run_app(host, data_paths, streamer, options).await;
+        if let Some(recorder) = recorder {
+            let summary = recorder.finish().await?;
+            report_capture(&summary, &db_path);
         }
-
-        // Stop streamer with error handling
-        info!("Stopping streamer...");
-        match Arc::try_unwrap(streamer_arc) {
-            Ok(mut streamer) => streamer.stop().await,
-            Err(_) => {
-                warn!("Could not unwrap streamer Arc, there are still references");
-                // Streamer will be dropped when Arc goes out of scope
-            }
-        }
-
         result
     }
 
@@ -589,17 +374,23 @@ impl StreamCommand {
             auto_sync_on_hash_mismatch: true,
         };
 
-        // Skip connectivity test for now - proceed directly to streaming
-        println!("\n🔌 Starting WebSocket connection...");
+        // Open the output and the capture before connecting so a bad sink or
+        // dataset name fails fast
+        let mut output = self.event_output(&data_paths).await?;
+        let capture = self.open_capture(&data_paths)?;
+
+        // Skip connectivity test for now - proceed directly to streaming.
+        // Progress goes to stderr: stdout is reserved for events.
+        eprintln!("\n🔌 Starting WebSocket connection...");
 
         // Create and start streamer
-        println!("\n🔗 Creating WebSocket streamer...");
+        eprintln!("\n🔗 Creating WebSocket streamer...");
         let mut streamer = Streamer::new(streamer_config);
         info!("🔌 Starting streamer for host: {}", host);
 
         match streamer.start(host, &data_paths).await {
             Ok(_) => {
-                println!("✅ Streamer started successfully");
+                eprintln!("✅ Streamer started successfully");
                 info!("🔗 Streamer started successfully");
             }
             Err(e) => {
@@ -610,6 +401,10 @@ impl StreamCommand {
 
         // Set up event handling
         let mut events = streamer.events();
+        let recorder = capture.map(|capture| {
+            eprintln!("📼 Recording to {}", capture.path().display());
+            CaptureRecorder::spawn(capture, &streamer)
+        });
 
         // Set up summary timer
         let mut summary_timer = if let Some(interval) = self.args.summary_interval {
@@ -618,6 +413,10 @@ impl StreamCommand {
             None
         };
 
+        // Buffered file output reaches disk at least this often, even when quiet
+        let mut flush_timer = tokio::time::interval(SINK_FLUSH_INTERVAL);
+        let mut trade_flow_timer = tokio::time::interval(CLOSE_CHECK_INTERVAL);
+
         info!("✅ Streaming started. Press Ctrl+C to stop.");
 
         // Main event loop
@@ -627,7 +426,11 @@ impl StreamCommand {
                 result = events.recv() => {
                     match result {
                         Ok(event) => {
-                            self.handle_event(event);
+                            if let Err(e) = output.emit(&event).await {
+                                // e.g. `polybot stream ... | head` exiting
+                                info!("Output closed ({}), stopping", e);
+                                break;
+                            }
                         }
                         Err(e) => {
                             warn!("Event receive error: {}", e);
@@ -658,6 +461,21 @@ impl StreamCommand {
                     }
                 } => {}
 
+                _ = flush_timer.tick() => {
+                    if let Err(e) = output.writer.flush().await {
+                        info!("Output closed ({}), stopping", e);
+                        break;
+                    }
+                }
+
+                // Sweeps that closed without a later trade
+                _ = trade_flow_timer.tick(), if output.trade_flow.is_some() => {
+                    if let Err(e) = output.close_trade_flow().await {
+                        info!("Output closed ({}), stopping", e);
+                        break;
+                    }
+                }
+
                 // Handle Ctrl+C
                 _ = signal::ctrl_c() => {
                     info!("🛑 Shutting down...");
@@ -667,14 +485,47 @@ impl StreamCommand {
         }
 
         // Stop streamer
+        output.writer.close().await;
+        if let Some(recorder) = recorder {
+            let summary = recorder.finish().await?;
+            report_capture(&summary, &default_db_path(&data_paths));
+        }
         streamer.stop().await;
         info!("✅ Stream stopped");
 
         Ok(())
     }
 
-    /// Get assets for streaming from all possible sources with fallback to dataset selector
+    /// Assets to stream, without the tokens of suppressed markets unless they
+    /// were named with `--assets`
     async fn get_assets_for_streaming(&self, data_paths: &DataPaths) -> Result<Vec<String>> {
+        let assets = self.resolve_assets(data_paths).await?;
+        if !self.args.assets.is_empty() || self.args.no_suppress {
+            return Ok(assets);
+        }
+        let suppression = SuppressionConfig::load(data_paths)?;
+        let screen = suppression.screen_tokens(&default_db_path(data_paths), assets)?;
+        if !screen.suppressed.is_empty() {
+            info!(
+                "Suppressed {} tokens of microcap or blacklisted markets (see {}, --no-suppress streams them)",
+                screen.suppressed.len(),
+                SUPPRESSION_CONFIG_FILE
+            );
+            for token in &screen.suppressed {
+                debug!("Suppressed {}: {}", token.token_id, token.reason);
+            }
+        }
+        if screen.kept.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Every token is suppressed by {}; use --no-suppress to stream them anyway",
+                SUPPRESSION_CONFIG_FILE
+            ));
+        }
+        Ok(screen.kept)
+    }
+
+    /// Get assets for streaming from all possible sources with fallback to dataset selector
+    async fn resolve_assets(&self, data_paths: &DataPaths) -> Result<Vec<String>> {
         // 1. Check direct assets argument
         if !self.args.assets.is_empty() {
             info!("Using {} directly specified assets", self.args.assets.len());
@@ -697,6 +548,7 @@ impl StreamCommand {
         info!("No assets specified, triggering dataset selector");
 
         // Check if we're in TUI mode for interactive selector
+        #[cfg(feature = "tui")]
         if self.args.tui {
             match self.run_interactive_dataset_selector(data_paths).await {
                 Ok(assets) => {
@@ -733,6 +585,7 @@ impl StreamCommand {
         self.show_available_selections_and_exit(data_paths).await
     }
 
+    #[cfg(feature = "tui")]
     async fn run_interactive_dataset_selector(
         &self,
         data_paths: &DataPaths,
@@ -866,107 +719,59 @@ impl StreamCommand {
         Ok(assets)
     }
 
-    fn handle_event(&self, event: PolyEvent) {
-        match event {
-            PolyEvent::Book {
-                asset_id,
-                bids,
-                asks,
-                ..
-            } if self.args.show_book => {
-                let best_bid = bids
-                    .first()
-                    .map(|level| format!("${} ({})", level.price, level.size))
-                    .unwrap_or_default();
-                let best_ask = asks
-                    .first()
-                    .map(|level| format!("${} ({})", level.price, level.size))
-                    .unwrap_or_default();
-
-                info!("📈 {} - Bid: {} Ask: {}", asset_id, best_bid, best_ask);
-            }
-
-            PolyEvent::PriceChange {
-                asset_id,
-                side,
-                price,
-                size,
-                ..
-            } if self.args.show_book => {
-                let action = if size == rust_decimal::Decimal::ZERO {
-                    "REMOVE"
-                } else {
-                    "UPDATE"
-                };
-                let side_str = match side {
-                    Side::Buy => "BID".bright_green().to_string(),
-                    Side::Sell => "ASK".bright_red().to_string(),
-                };
-
-                info!(
-                    "📊 {} - {} {} ${} ({})",
-                    asset_id, action, side_str, price, size
-                );
-            }
-
-            PolyEvent::Trade {
-                asset_id,
-                price,
-                size,
-                side,
-                ..
-            } if self.args.show_trades => {
-                let side_str = match side {
-                    Side::Buy => "BUY".bright_green().to_string(),
-                    Side::Sell => "SELL".bright_red().to_string(),
-                };
-
-                info!("💰 {} - {} {} @ ${}", asset_id, side_str, size, price);
-            }
-
-            PolyEvent::MyOrder {
-                asset_id,
-                side,
-                price,
-                size,
-                status,
-                ..
-            } if self.args.show_user => {
-                let side_str = match side {
-                    Side::Buy => "BUY".bright_green().to_string(),
-                    Side::Sell => "SELL".bright_red().to_string(),
-                };
-
-                info!(
-                    "📋 {} - Order {} {} @ ${} - {:?}",
-                    asset_id, side_str, size, price, status
-                );
-            }
-
-            PolyEvent::MyTrade {
-                asset_id,
-                side,
-                price,
-                size,
-                ..
-            } if self.args.show_user => {
-                let side_str = match side {
-                    Side::Buy => "BOUGHT".bright_green().to_string(),
-                    Side::Sell => "SOLD".bright_red().to_string(),
-                };
-
-                info!("✅ {} - {} {} @ ${}", asset_id, side_str, size, price);
-            }
+    /// Create the `--record` dataset, when recording
+    fn open_capture(&self, data_paths: &DataPaths) -> Result<Option<StreamCapture>> {
+        if !self.args.record {
+            return Ok(None);
+        }
+        let template = self
+            .args
+            .dataset_name
+            .as_deref()
+            .unwrap_or(DEFAULT_CAPTURE_NAME);
+        let name = crate::cli::aliases::TimePlaceholders::now().fill(template);
+        let args = vec![
+            "--record".to_string(),
+            "--dataset-name".to_string(),
+            name.clone(),
+        ];
+        StreamCapture::create(&data_paths.datasets(), &name, &args).map(Some)
+    }
 
-            PolyEvent::TickSizeChange {
-                asset_id,
-                tick_size,
-            } => {
-                info!("Tick size changed for {}: {}", asset_id, tick_size);
-            }
+    /// Build the event filter and open the sink from the output options
+    async fn event_output(&self, data_paths: &DataPaths) -> Result<EventOutput> {
+        let mut kinds: HashSet<EventKind> = self.args.only.iter().copied().collect();
+        if self.args.show_book {
+            kinds.extend([EventKind::Book, EventKind::PriceChange]);
+        }
+        if self.args.show_trades {
+            kinds.insert(EventKind::Trades);
+        }
+        if self.args.show_user {
+            kinds.extend([EventKind::Orders, EventKind::Fills]);
+        }
 
-            _ => {} // Ignore other events or when show flags are disabled
+        let mut tokens: HashSet<String> = self.args.token_filter.iter().cloned().collect();
+        if let Some(watchlist) = &self.args.watchlist {
+            let watched = self.load_assets_from_selection(watchlist, data_paths)?;
+            info!(
+                "👀 Filtering to {} tokens from '{}'",
+                watched.len(),
+                watchlist
+            );
+            tokens.extend(watched);
         }
+
+        Ok(EventOutput {
+            filter: EventFilter {
+                kinds: (!kinds.is_empty()).then_some(kinds),
+                tokens: (!tokens.is_empty()).then_some(tokens),
+            },
+            format: self.args.format,
+            color: self.args.sink == StreamSink::Stdout && std::io::stdout().is_terminal(),
+            trade_flow: self.args.trade_flow.then(TradeFlowAnalyzer::default),
+            writer: SinkWriter::open(&self.args.sink).await?,
+        })
     }
 
     fn print_order_book_summary(streamer: &Streamer) {
@@ -1121,40 +926,3 @@ impl StreamCommand {
         Ok(())
     }
 }
-
-fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
-    use std::io::IsTerminal;
-
-    // Check if stdout is a terminal
-    if !io::stdout().is_terminal() {
-        return Err(anyhow::anyhow!("stdout is not a terminal"));
-    }
-
-    // Check if stderr is a terminal (for user interaction)
-    if !io::stderr().is_terminal() {
-        return Err(anyhow::anyhow!("stderr is not a terminal"));
-    }
-
-    enable_raw_mode().map_err(|e| anyhow::anyhow!("Failed to enable raw mode: {}", e))?;
-
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-        .map_err(|e| anyhow::anyhow!("Failed to setup terminal screen: {}", e))?;
-
-    let backend = CrosstermBackend::new(stdout);
-    let terminal =
-        Terminal::new(backend).map_err(|e| anyhow::anyhow!("Failed to create terminal: {}", e))?;
-
-    Ok(terminal)
-}
-
-fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
-}
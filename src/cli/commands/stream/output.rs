@@ -0,0 +1,451 @@
+//! Event filtering and formatting for `stream --no-tui`
+//!
+//! [`EventOutput`] decides which events pass `--only` and the token filters,
+//! renders them as pretty lines or JSON lines, and hands each line to the
+//! configured [`SinkWriter`].
+
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use super::sink::SinkWriter;
+use crate::core::execution::trade_flow::{TradeFlowAnalyzer, TradeFlowEvent};
+use crate::core::types::common::Side;
+use crate::core::ws::PolyEvent;
+
+/// Event kinds selectable with `--only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum EventKind {
+    /// Order book snapshots
+    Book,
+    /// Order book level changes
+    PriceChange,
+    /// Market trades
+    Trades,
+    /// Last trade price updates
+    LastTrade,
+    /// Tick size changes
+    TickSize,
+    /// Your order updates (user feed)
+    Orders,
+    /// Your fills (user feed)
+    Fills,
+}
+
+impl EventKind {
+    fn of(event: &PolyEvent) -> Option<Self> {
+        match event {
+            PolyEvent::Book { .. } => Some(Self::Book),
+            PolyEvent::PriceChange { .. } => Some(Self::PriceChange),
+            PolyEvent::Trade { .. } => Some(Self::Trades),
+            PolyEvent::LastTradePrice { .. } => Some(Self::LastTrade),
+            PolyEvent::TickSizeChange { .. } => Some(Self::TickSize),
+            PolyEvent::MyOrder { .. } => Some(Self::Orders),
+            PolyEvent::MyTrade { .. } => Some(Self::Fills),
+            _ => None,
+        }
+    }
+}
+
+/// How `--no-tui` output is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum StreamFormat {
+    /// One human-readable line per event
+    #[default]
+    Pretty,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Which events are written, from `--only`/`--show-*` and the token filters
+#[derive(Debug, Default)]
+pub(super) struct EventFilter {
+    /// `None` passes every kind
+    pub(super) kinds: Option<HashSet<EventKind>>,
+    /// `None` passes every token
+    pub(super) tokens: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &PolyEvent) -> bool {
+        let Some(kind) = EventKind::of(event) else {
+            return false;
+        };
+        if self
+            .kinds
+            .as_ref()
+            .is_some_and(|kinds| !kinds.contains(&kind))
+        {
+            return false;
+        }
+        match event_asset_id(event) {
+            Some(asset_id) => self.matches_token(asset_id),
+            None => self.tokens.is_none(),
+        }
+    }
+
+    fn matches_token(&self, asset_id: &str) -> bool {
+        self.tokens
+            .as_ref()
+            .is_none_or(|tokens| tokens.contains(asset_id))
+    }
+}
+
+/// Token of any market or user feed event
+fn event_asset_id(event: &PolyEvent) -> Option<&str> {
+    match event {
+        PolyEvent::MyOrder { asset_id, .. } | PolyEvent::MyTrade { asset_id, .. } => Some(asset_id),
+        _ => event.market_asset_id(),
+    }
+}
+
+fn side_label(side: Side, color: bool) -> String {
+    let (label, buy) = match side {
+        Side::Buy => ("BUY", true),
+        Side::Sell => ("SELL", false),
+    };
+    match (color, buy) {
+        (false, _) => label.to_string(),
+        (true, true) => label.bright_green().to_string(),
+        (true, false) => label.bright_red().to_string(),
+    }
+}
+
+/// The human-readable line for `event`
+fn format_pretty(event: &PolyEvent, color: bool) -> Option<String> {
+    let line = match event {
+        PolyEvent::Book {
+            asset_id,
+            bids,
+            asks,
+            ..
+        } => {
+            let level = |level: Option<&crate::core::types::market::PriceLevel>| {
+                level
+                    .map(|level| format!("${} ({})", level.price, level.size))
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            format!(
+                "📈 {} - Bid: {} Ask: {}",
+                asset_id,
+                level(bids.first()),
+                level(asks.first())
+            )
+        }
+        PolyEvent::PriceChange {
+            asset_id,
+            side,
+            price,
+            size,
+            ..
+        } => {
+            let action = if size.is_zero() { "REMOVE" } else { "UPDATE" };
+            let book_side = match (side, color) {
+                (Side::Buy, true) => "BID".bright_green().to_string(),
+                (Side::Sell, true) => "ASK".bright_red().to_string(),
+                (Side::Buy, false) => "BID".to_string(),
+                (Side::Sell, false) => "ASK".to_string(),
+            };
+            format!(
+                "📊 {} - {} {} ${} ({})",
+                asset_id, action, book_side, price, size
+            )
+        }
+        PolyEvent::Trade {
+            asset_id,
+            price,
+            size,
+            side,
+        } => format!(
+            "💰 {} - {} {} @ ${}",
+            asset_id,
+            side_label(*side, color),
+            size,
+            price
+        ),
+        PolyEvent::LastTradePrice {
+            asset_id, price, ..
+        } => format!("🔖 {} - Last trade ${}", asset_id, price),
+        PolyEvent::TickSizeChange {
+            asset_id,
+            tick_size,
+        } => format!("📏 {} - Tick size {}", asset_id, tick_size),
+        PolyEvent::MyOrder {
+            asset_id,
+            side,
+            price,
+            size,
+            status,
+        } => format!(
+            "📋 {} - Order {} {} @ ${} - {:?}",
+            asset_id,
+            side_label(*side, color),
+            size,
+            price,
+            status
+        ),
+        PolyEvent::MyTrade {
+            asset_id,
+            side,
+            price,
+            size,
+        } => format!(
+            "✅ {} - Filled {} {} @ ${}",
+            asset_id,
+            side_label(*side, color),
+            size,
+            price
+        ),
+        _ => return None,
+    };
+    Some(line)
+}
+
+/// The human-readable line for a `--trade-flow` detection
+fn format_trade_flow_pretty(event: &TradeFlowEvent, color: bool) -> String {
+    let line = format!("🐋 {}", event.describe());
+    if color {
+        line.bright_yellow().to_string()
+    } else {
+        line
+    }
+}
+
+/// The JSON object for a `--trade-flow` detection, tagged `sweep` or `large_trade`
+fn format_trade_flow_json(event: &TradeFlowEvent) -> Value {
+    let mut value = serde_json::to_value(event).unwrap_or_else(|_| json!({}));
+    value["received_at"] = json!(chrono::Utc::now().timestamp_millis());
+    value
+}
+
+/// The JSON object for `event`, tagged with its `--only` kind
+pub(super) fn format_json(event: &PolyEvent) -> Option<Value> {
+    let received_at = chrono::Utc::now().timestamp_millis();
+    let mut value = match event {
+        PolyEvent::Book {
+            asset_id,
+            market,
+            timestamp,
+            bids,
+            asks,
+            hash,
+            source,
+        } => json!({
+            "type": "book",
+            "asset_id": asset_id,
+            "market": market,
+            "timestamp": timestamp,
+            "bids": bids,
+            "asks": asks,
+            "hash": hash,
+            "synthetic": source.is_synthetic(),
+        }),
+        PolyEvent::PriceChange {
+            asset_id,
+            side,
+            price,
+            size,
+            hash,
+        } => json!({
+            "type": "price_change",
+            "asset_id": asset_id,
+            "side": side,
+            "price": price,
+            "size": size,
+            "hash": hash,
+        }),
+        PolyEvent::Trade {
+            asset_id,
+            price,
+            size,
+            side,
+        } => json!({
+            "type": "trade",
+            "asset_id": asset_id,
+            "side": side,
+            "price": price,
+            "size": size,
+        }),
+        PolyEvent::LastTradePrice {
+            asset_id,
+            price,
+            timestamp,
+        } => json!({
+            "type": "last_trade",
+            "asset_id": asset_id,
+            "price": price,
+            "timestamp": timestamp,
+        }),
+        PolyEvent::TickSizeChange {
+            asset_id,
+            tick_size,
+        } => json!({
+            "type": "tick_size",
+            "asset_id": asset_id,
+            "tick_size": tick_size,
+        }),
+        PolyEvent::MyOrder {
+            asset_id,
+            side,
+            price,
+            size,
+            status,
+        } => json!({
+            "type": "order",
+            "asset_id": asset_id,
+            "side": side,
+            "price": price,
+            "size": size,
+            "status": status,
+        }),
+        PolyEvent::MyTrade {
+            asset_id,
+            side,
+            price,
+            size,
+        } => json!({
+            "type": "fill",
+            "asset_id": asset_id,
+            "side": side,
+            "price": price,
+            "size": size,
+        }),
+        _ => return None,
+    };
+    value["received_at"] = json!(received_at);
+    Some(value)
+}
+
+/// Filters, formats and writes events for `--no-tui` streaming
+pub(super) struct EventOutput {
+    pub(super) filter: EventFilter,
+    pub(super) format: StreamFormat,
+    pub(super) color: bool,
+    /// Set by `--trade-flow`; sees every trade, whatever the kind filter
+    pub(super) trade_flow: Option<TradeFlowAnalyzer>,
+    pub(super) writer: SinkWriter,
+}
+
+impl EventOutput {
+    /// Write `event` if it passes the filter, followed by any trade flow detections
+    pub(super) async fn emit(&mut self, event: &PolyEvent) -> std::io::Result<()> {
+        let detections = match (&mut self.trade_flow, event) {
+            (
+                Some(analyzer),
+                PolyEvent::Trade {
+                    asset_id,
+                    price,
+                    size,
+                    side,
+                },
+            ) => analyzer.observe(
+                asset_id,
+                *side,
+                *price,
+                *size,
+                chrono::Utc::now().timestamp_millis() as u64,
+            ),
+            _ => Vec::new(),
+        };
+
+        if self.filter.matches(event) {
+            let line = match self.format {
+                StreamFormat::Pretty => format_pretty(event, self.color),
+                StreamFormat::Jsonl => format_json(event).map(|value| value.to_string()),
+            };
+            if let Some(line) = line {
+                self.writer.write_line(&line).await?;
+            }
+        }
+
+        self.write_detections(detections).await
+    }
+
+    /// Write sweeps whose window closed since the last trade on their token
+    pub(super) async fn close_trade_flow(&mut self) -> std::io::Result<()> {
+        let detections = match &mut self.trade_flow {
+            Some(analyzer) => analyzer.flush_expired(chrono::Utc::now().timestamp_millis() as u64),
+            None => return Ok(()),
+        };
+        self.write_detections(detections).await
+    }
+
+    async fn write_detections(&mut self, detections: Vec<TradeFlowEvent>) -> std::io::Result<()> {
+        for detection in detections {
+            if !self.filter.matches_token(detection.asset_id()) {
+                continue;
+            }
+            let line = match self.format {
+                StreamFormat::Pretty => format_trade_flow_pretty(&detection, self.color),
+                StreamFormat::Jsonl => format_trade_flow_json(&detection).to_string(),
+            };
+            self.writer.write_line(&line).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn trade(asset_id: &str) -> PolyEvent {
+        PolyEvent::Trade {
+            asset_id: asset_id.to_string(),
+            price: dec!(0.42),
+            size: dec!(10),
+            side: Side::Buy,
+        }
+    }
+
+    #[test]
+    fn filter_combines_kinds_and_tokens() {
+        let tick = PolyEvent::TickSizeChange {
+            asset_id: "a".to_string(),
+            tick_size: dec!(0.01),
+        };
+        assert!(EventFilter::default().matches(&trade("a")));
+
+        let only_trades = EventFilter {
+            kinds: Some(HashSet::from([EventKind::Trades])),
+            tokens: None,
+        };
+        assert!(only_trades.matches(&trade("a")));
+        assert!(!only_trades.matches(&tick));
+
+        let watchlist = EventFilter {
+            kinds: None,
+            tokens: Some(HashSet::from(["a".to_string()])),
+        };
+        assert!(watchlist.matches(&tick));
+        assert!(!watchlist.matches(&trade("b")));
+    }
+
+    #[test]
+    fn json_lines_carry_the_event_kind() {
+        let value = format_json(&trade("a")).unwrap();
+        assert_eq!(value["type"], "trade");
+        assert_eq!(value["asset_id"], "a");
+        assert!(value["received_at"].is_i64());
+
+        let line = format_pretty(&trade("a"), false).unwrap();
+        assert_eq!(line, "💰 a - BUY 10 @ $0.42");
+    }
+
+    #[test]
+    fn trade_flow_detections_are_written_with_their_type() {
+        let mut analyzer = TradeFlowAnalyzer::default();
+        let mut detections = Vec::new();
+        for ts in [0, 100, 200, 5_000] {
+            detections.extend(analyzer.observe("a", Side::Buy, dec!(0.42), dec!(10), ts));
+        }
+        assert_eq!(detections.len(), 1);
+
+        let value = format_trade_flow_json(&detections[0]);
+        assert_eq!(value["type"], "sweep");
+        assert_eq!(value["trades"], 3);
+        assert!(format_trade_flow_pretty(&detections[0], false).starts_with("🐋 Buy sweep on a"));
+    }
+}
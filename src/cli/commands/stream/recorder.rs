@@ -0,0 +1,97 @@
+//! Background recording of `stream --record` captures
+
+use anyhow::Result;
+use std::path::Path;
+use std::time::Duration;
+use tracing::warn;
+
+use super::output::format_json;
+use crate::core::services::Streamer;
+use crate::markets::datasets::backfill::backfill_capture;
+use crate::markets::datasets::capture::{CaptureSummary, StreamCapture};
+use crate::markets::datasets::{DatasetManager, DatasetManagerConfig};
+
+/// Writes every event of a streamer into a `--record` capture in the background
+pub(super) struct CaptureRecorder {
+    stop: tokio::sync::oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<Result<CaptureSummary>>,
+}
+
+impl CaptureRecorder {
+    pub(super) fn spawn(mut capture: StreamCapture, streamer: &Streamer) -> Self {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut events = streamer.events();
+        let (stop, mut stopped) = tokio::sync::oneshot::channel();
+        let task = tokio::spawn(async move {
+            let mut flush = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tokio::select! {
+                    result = events.recv() => match result {
+                        Ok(event) => {
+                            if let Some(value) = format_json(&event) {
+                                capture.record(&value)?;
+                            }
+                        }
+                        Err(RecvError::Lagged(n)) => {
+                            warn!("Recorder lagged, {} events were not recorded", n);
+                            capture.note_dropped(n as usize);
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = flush.tick() => capture.flush()?,
+                    _ = &mut stopped => break,
+                }
+            }
+            capture.finish()
+        });
+        Self { stop, task }
+    }
+
+    /// Stop recording and close the capture
+    pub(super) async fn finish(self) -> Result<CaptureSummary> {
+        let _ = self.stop.send(());
+        self.task.await?
+    }
+}
+
+/// Print what a capture holds and the dataset health check's verdict, and
+/// backfill the candle store at `db_path` from its trades
+pub(super) fn report_capture(summary: &CaptureSummary, db_path: &Path) {
+    eprintln!(
+        "📼 Recorded {} events for {} tokens into dataset '{}'",
+        summary.events, summary.tokens, summary.name
+    );
+    if summary.dropped > 0 {
+        eprintln!(
+            "   ⚠️  {} events were dropped while the recorder lagged",
+            summary.dropped
+        );
+    }
+    match DatasetManager::new(DatasetManagerConfig::default()).analyze_dataset(&summary.path) {
+        Ok(dataset) => {
+            eprintln!(
+                "   {} Health: {:?}",
+                dataset.status_icon(),
+                dataset.health_status
+            );
+            for warning in &dataset.warnings {
+                eprintln!("   - {}", warning.message);
+            }
+        }
+        Err(e) => warn!("Health check of {} failed: {}", summary.path.display(), e),
+    }
+    match backfill_capture(&summary.path, db_path) {
+        Ok(backfill) if backfill.candles > 0 => eprintln!(
+            "   🕯️  Backfilled {} candles for {} tokens",
+            backfill.candles, backfill.tokens
+        ),
+        Ok(_) => {}
+        Err(e) => warn!(
+            "Candle backfill of {} failed, retry with `polybot datasets backfill {}`: {:#}",
+            summary.path.display(),
+            summary.name,
+            e
+        ),
+    }
+}
@@ -0,0 +1,279 @@
+//! Destinations for `stream --no-tui` output
+//!
+//! Stdout is flushed per line. A file sink is buffered and flushed by
+//! [`SinkWriter::flush`] on the command's flush interval, so a tail of the
+//! file never lags far behind. A Unix socket sink hands each reader its own
+//! queue and writer task: a reader that stops draining its socket falls
+//! behind until its queue is full or a write times out, and is then dropped
+//! without ever holding up the stream or the other readers.
+
+use anyhow::Result;
+#[cfg(unix)]
+use std::fs;
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+#[cfg(unix)]
+use tracing::debug;
+use tracing::{info, warn};
+
+/// How often the command flushes a buffered file sink
+pub const SINK_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lines queued for one socket reader before it counts as too slow
+#[cfg(unix)]
+const READER_QUEUE: usize = 1024;
+
+/// Longest a single write to a socket reader may take
+#[cfg(unix)]
+const READER_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where `--no-tui` output goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamSink {
+    Stdout,
+    /// Appended to a file
+    File(PathBuf),
+    /// Served on a Unix socket to every connected reader
+    Unix(PathBuf),
+}
+
+impl std::str::FromStr for StreamSink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = |p: &str| {
+            if p.is_empty() {
+                Err(format!("Missing path in sink '{}'", s))
+            } else {
+                Ok(PathBuf::from(p))
+            }
+        };
+        match s.split_once(':') {
+            _ if s == "-" || s == "stdout" => Ok(StreamSink::Stdout),
+            Some(("file", p)) => path(p).map(StreamSink::File),
+            Some(("unix", p)) => path(p).map(StreamSink::Unix),
+            _ => Err(format!(
+                "Unknown sink '{}' (use stdout, file:<path> or unix:<path>)",
+                s
+            )),
+        }
+    }
+}
+
+/// One connected reader of a Unix socket sink
+#[cfg(unix)]
+struct SocketReader {
+    lines: tokio::sync::mpsc::Sender<Arc<[u8]>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl SocketReader {
+    /// Start writing queued lines to `stream` until it disconnects or stalls
+    fn spawn(mut stream: tokio::net::UnixStream) -> Self {
+        let (lines, mut queue) = tokio::sync::mpsc::channel::<Arc<[u8]>>(READER_QUEUE);
+        let task = tokio::spawn(async move {
+            while let Some(line) = queue.recv().await {
+                match tokio::time::timeout(READER_WRITE_TIMEOUT, stream.write_all(&line)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(_)) => {
+                        debug!("Stream reader disconnected");
+                        break;
+                    }
+                    Err(_) => {
+                        warn!("Dropping a stream reader that stopped reading");
+                        break;
+                    }
+                }
+            }
+        });
+        Self { lines, task }
+    }
+
+    /// Queue `line`; false once the reader is gone or too far behind
+    fn send(&self, line: &Arc<[u8]>) -> bool {
+        match self.lines.try_send(line.clone()) {
+            Ok(()) => true,
+            Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                warn!("Dropping a stream reader {} lines behind", READER_QUEUE);
+                false
+            }
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SocketReader {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Output destination opened from a [`StreamSink`]
+pub(super) enum SinkWriter {
+    Stdout(tokio::io::Stdout),
+    File(tokio::io::BufWriter<tokio::fs::File>),
+    #[cfg(unix)]
+    Unix {
+        path: PathBuf,
+        readers: Vec<SocketReader>,
+        incoming: tokio::sync::mpsc::UnboundedReceiver<tokio::net::UnixStream>,
+        accept_task: tokio::task::JoinHandle<()>,
+    },
+}
+
+impl SinkWriter {
+    pub(super) async fn open(sink: &StreamSink) -> Result<Self> {
+        match sink {
+            StreamSink::Stdout => Ok(Self::Stdout(tokio::io::stdout())),
+            StreamSink::File(path) => {
+                let file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+                info!("📝 Writing events to {}", path.display());
+                Ok(Self::File(tokio::io::BufWriter::new(file)))
+            }
+            #[cfg(unix)]
+            StreamSink::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                // A socket left behind by an earlier run would make bind fail
+                if fs::metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+                    fs::remove_file(path)?;
+                }
+                let listener = tokio::net::UnixListener::bind(path).map_err(|e| {
+                    anyhow::anyhow!("Failed to bind socket {}: {}", path.display(), e)
+                })?;
+                info!("🔌 Serving events on unix socket {}", path.display());
+
+                let (tx, incoming) = tokio::sync::mpsc::unbounded_channel();
+                let accept_task = tokio::spawn(async move {
+                    while let Ok((stream, _)) = listener.accept().await {
+                        debug!("Stream reader connected");
+                        if tx.send(stream).is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok(Self::Unix {
+                    path: path.clone(),
+                    readers: Vec::new(),
+                    incoming,
+                    accept_task,
+                })
+            }
+            #[cfg(not(unix))]
+            StreamSink::Unix(_) => Err(anyhow::anyhow!(
+                "Unix socket sinks are only available on Unix platforms"
+            )),
+        }
+    }
+
+    /// Write one line; an error means the output is gone (e.g. a closed pipe)
+    pub(super) async fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let mut bytes = Vec::with_capacity(line.len() + 1);
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+        match self {
+            Self::Stdout(out) => {
+                out.write_all(&bytes).await?;
+                out.flush().await
+            }
+            Self::File(out) => out.write_all(&bytes).await,
+            #[cfg(unix)]
+            Self::Unix {
+                readers, incoming, ..
+            } => {
+                while let Ok(stream) = incoming.try_recv() {
+                    readers.push(SocketReader::spawn(stream));
+                }
+                // Readers come and go; a disconnected or stalled one is simply dropped
+                let line: Arc<[u8]> = bytes.into();
+                readers.retain(|reader| reader.send(&line));
+                Ok(())
+            }
+        }
+    }
+
+    /// Push buffered file output to disk; the other sinks never buffer
+    pub(super) async fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::File(out) => out.flush().await,
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) async fn close(self) {
+        match self {
+            Self::Stdout(mut out) => {
+                let _ = out.flush().await;
+            }
+            Self::File(mut out) => {
+                if let Err(e) = out.flush().await {
+                    warn!("Failed to flush event file: {}", e);
+                }
+            }
+            #[cfg(unix)]
+            Self::Unix {
+                path, accept_task, ..
+            } => {
+                accept_task.abort();
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sinks_parse_from_the_command_line() {
+        assert_eq!("stdout".parse(), Ok(StreamSink::Stdout));
+        assert_eq!("-".parse(), Ok(StreamSink::Stdout));
+        assert_eq!(
+            "file:/tmp/events.jsonl".parse(),
+            Ok(StreamSink::File(PathBuf::from("/tmp/events.jsonl")))
+        );
+        assert_eq!(
+            "unix:/tmp/poly.sock".parse(),
+            Ok(StreamSink::Unix(PathBuf::from("/tmp/poly.sock")))
+        );
+        assert!("file:".parse::<StreamSink>().is_err());
+        assert!("tcp:1234".parse::<StreamSink>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn a_stalled_socket_reader_is_dropped_without_blocking() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.sock");
+        let mut writer = SinkWriter::open(&StreamSink::Unix(path.clone())).await.unwrap();
+
+        // Connected but never reads
+        let _stalled = tokio::net::UnixStream::connect(&path).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let line = "x".repeat(1024);
+        let writes = async {
+            for _ in 0..READER_QUEUE * 4 {
+                writer.write_line(&line).await.unwrap();
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(1), writes)
+            .await
+            .expect("a stalled reader held up the stream");
+        let SinkWriter::Unix { readers, .. } = &writer else {
+            panic!("opened as a unix sink");
+        };
+        assert!(readers.is_empty());
+    }
+}
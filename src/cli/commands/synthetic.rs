@@ -0,0 +1,301 @@
+//! Synthetic command: define, show and watch synthetic instruments
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::{Args, Subcommand};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+use tokio::signal;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::core::alerts::{AlertRouter, Severity};
+use crate::core::execution::synthetic::{
+    SyntheticBook, SyntheticInstrument, SyntheticLeg, SyntheticMonitor, SyntheticStore,
+};
+use crate::core::services::{Streamer, StreamerConfig};
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::{OrderBook, PolyEvent};
+use crate::data_paths::DataPaths;
+use crate::number_format;
+
+/// Price points kept in memory per instrument while watching
+const WATCH_SERIES_LEN: usize = 1000;
+
+/// Time between follow-ups on open alerts while watching
+const ALERT_FOLLOW_UP_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Args, Clone)]
+pub struct SyntheticArgs {
+    #[command(subcommand)]
+    pub action: SyntheticAction,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum SyntheticAction {
+    /// Define a synthetic instrument, replacing one of the same name
+    Add {
+        name: String,
+        /// A leg as <token>[:<weight>], at least two (e.g. --leg 123 --leg 456:-1)
+        #[arg(long = "leg", required = true)]
+        legs: Vec<SyntheticLeg>,
+        /// Alert when the synthetic mid falls below this
+        #[arg(long, allow_hyphen_values = true)]
+        lower: Option<Decimal>,
+        /// Alert when the synthetic mid rises above this
+        #[arg(long, allow_hyphen_values = true)]
+        upper: Option<Decimal>,
+    },
+    /// Delete a synthetic instrument
+    Remove { name: String },
+    /// List the synthetic instruments
+    List,
+    /// Show a synthetic instrument's book built from its legs' current books
+    Show {
+        name: String,
+        /// Levels per side
+        #[arg(long, default_value_t = 10)]
+        depth: usize,
+    },
+    /// Stream the legs, printing each move of a synthetic's top of book and its bound alerts
+    Watch {
+        /// Instruments to watch (default: all)
+        names: Vec<String>,
+        /// Severity bound alerts are raised with, which picks where they're routed
+        #[arg(long, value_enum, default_value_t = Severity::Warning)]
+        severity: Severity,
+    },
+}
+
+pub struct SyntheticCommand {
+    args: SyntheticArgs,
+}
+
+impl SyntheticCommand {
+    pub fn new(args: SyntheticArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        let store = SyntheticStore::new(&data_paths);
+        match &self.args.action {
+            SyntheticAction::Add {
+                name,
+                legs,
+                lower,
+                upper,
+            } => {
+                let instrument = SyntheticInstrument::new(name, legs.clone(), *lower, *upper)?;
+                let mut instruments = store.load()?;
+                instruments.retain(|existing| existing.name != instrument.name);
+                println!(
+                    "✅ Saved synthetic '{}': {}",
+                    instrument.name,
+                    instrument.formula()
+                );
+                instruments.push(instrument);
+                store.save(&instruments)?;
+            }
+            SyntheticAction::Remove { name } => {
+                let mut instruments = store.load()?;
+                let before = instruments.len();
+                instruments.retain(|existing| existing.name != *name);
+                if instruments.len() == before {
+                    return Err(anyhow!("No synthetic instrument named '{}'", name));
+                }
+                store.save(&instruments)?;
+                println!("🗑️  Removed synthetic '{}'", name);
+            }
+            SyntheticAction::List => {
+                let instruments = store.load()?;
+                if instruments.is_empty() {
+                    println!("No synthetic instruments yet (add one with `synthetic add`)");
+                }
+                for instrument in instruments {
+                    let bounds = instrument.bounds_label();
+                    println!(
+                        "{:<24} {}{}",
+                        instrument.name,
+                        instrument.formula(),
+                        if bounds.is_empty() {
+                            String::new()
+                        } else {
+                            format!("  [{}]", bounds)
+                        }
+                    );
+                }
+            }
+            SyntheticAction::Show { name, depth } => {
+                let instrument = store.select(std::slice::from_ref(name))?.remove(0);
+                let client = ClobClient::new(host);
+                let mut books = HashMap::new();
+                for leg in &instrument.legs {
+                    let summary = client.get_order_book(&leg.token_id).await?;
+                    let bids = summary
+                        .bids
+                        .iter()
+                        .map(|level| PriceLevel::new(level.price, level.size))
+                        .collect();
+                    let asks = summary
+                        .asks
+                        .iter()
+                        .map(|level| PriceLevel::new(level.price, level.size))
+                        .collect();
+                    let mut book = OrderBook::new(leg.token_id.clone());
+                    book.replace_with_snapshot_no_hash(String::new(), 0, bids, asks);
+                    books.insert(leg.token_id.clone(), book);
+                }
+                print_book(&instrument, &instrument.book(&books, *depth));
+            }
+            SyntheticAction::Watch { names, severity } => {
+                let instruments = store.select(names)?;
+                if instruments.is_empty() {
+                    return Err(anyhow!("No synthetic instruments to watch"));
+                }
+                watch(instruments, *severity, host, &data_paths).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_book(instrument: &SyntheticInstrument, book: &SyntheticBook) {
+    println!("🧮 {} = {}", instrument.name, instrument.formula());
+    if !book.missing.is_empty() {
+        println!("   No book yet for {}", book.missing.join(", "));
+        return;
+    }
+    println!(
+        "   {:>10}  {:>12}    {:>10}  {:>12}",
+        "BID", "SIZE", "ASK", "SIZE"
+    );
+    for index in 0..book.bids.len().max(book.asks.len()) {
+        let side = |levels: &[PriceLevel]| match levels.get(index) {
+            Some(level) => (
+                number_format::price(level.price),
+                number_format::size(level.size),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let (bid, bid_size) = side(&book.bids);
+        let (ask, ask_size) = side(&book.asks);
+        println!(
+            "   {:>10}  {:>12}    {:>10}  {:>12}",
+            bid, bid_size, ask, ask_size
+        );
+    }
+    if let Some(mid) = book.mid() {
+        println!("   Mid: {}", number_format::price(mid));
+    }
+    let bounds = instrument.bounds_label();
+    if !bounds.is_empty() {
+        println!("   Bounds: {}", bounds);
+    }
+}
+
+/// Stream the legs until Ctrl+C, appending each instrument's price series to
+/// `synthetics/<name>.jsonl` in the data directory and raising bound alerts
+/// with `severity`
+async fn watch(
+    instruments: Vec<SyntheticInstrument>,
+    severity: Severity,
+    host: &str,
+    data_paths: &DataPaths,
+) -> Result<()> {
+    let mut monitor = SyntheticMonitor::new(instruments, 1, WATCH_SERIES_LEN);
+    let tokens: Vec<String> = monitor.tokens().into_iter().collect();
+    let series_dir = data_paths.data().join("synthetics");
+    std::fs::create_dir_all(&series_dir)?;
+    let router = AlertRouter::new(data_paths)?;
+
+    let mut streamer = Streamer::new(StreamerConfig {
+        market_assets: tokens.clone(),
+        auto_sync_on_hash_mismatch: false,
+        ..Default::default()
+    });
+    streamer
+        .start(host, data_paths)
+        .await
+        .map_err(|e| anyhow!("Failed to start streaming: {}", e))?;
+    let mut events = streamer.events();
+    println!(
+        "👀 Watching {} synthetic instruments over {} tokens (Ctrl+C to stop)",
+        monitor.instruments().len(),
+        tokens.len()
+    );
+
+    let mut shutdown = Box::pin(signal::ctrl_c());
+    let mut follow_ups = tokio::time::interval(ALERT_FOLLOW_UP_INTERVAL);
+    loop {
+        let event = tokio::select! {
+            _ = &mut shutdown => break,
+            _ = follow_ups.tick() => {
+                match router.follow_up(Utc::now()).await {
+                    Ok(sent) => sent.iter().for_each(|alert| println!("{}", alert.text())),
+                    Err(e) => warn!("Failed to follow up on alerts: {:#}", e),
+                }
+                continue;
+            }
+            received = events.recv() => match received {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Synthetic watch fell behind, skipped {} events", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if !matches!(
+            event,
+            PolyEvent::Book { .. } | PolyEvent::PriceChange { .. }
+        ) {
+            continue;
+        }
+
+        let books: HashMap<String, OrderBook> = tokens
+            .iter()
+            .filter_map(|token| Some((token.clone(), streamer.get_order_book(token)?)))
+            .collect();
+        let update = monitor.update(&books, Utc::now());
+        for name in &update.changed {
+            let Some(point) = monitor.latest(name) else {
+                continue;
+            };
+            let quote = |value: Option<Decimal>| {
+                value
+                    .map(number_format::price)
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            println!(
+                "{} {:<24} bid {:>8}  ask {:>8}  mid {:>8}",
+                point.at.format("%H:%M:%S"),
+                name,
+                quote(point.bid),
+                quote(point.ask),
+                quote(point.mid)
+            );
+            let path = series_dir.join(format!("{}.jsonl", name.replace('/', "_")));
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?;
+            writeln!(file, "{}", serde_json::to_string(point)?)?;
+        }
+        for alert in &update.alerts {
+            let icon = if alert.raised { "🚨" } else { "✅" };
+            println!("{} {}", icon, alert);
+            if alert.raised {
+                let message = format!("Synthetic {}", alert);
+                if let Err(e) = router.raise(severity, "synthetic", message, None).await {
+                    warn!("Failed to raise alert: {:#}", e);
+                }
+            }
+        }
+    }
+
+    streamer.stop().await;
+    Ok(())
+}
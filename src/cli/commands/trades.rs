@@ -5,7 +5,8 @@ use clap::Args;
 use chrono::{DateTime, Utc, Duration};
 use crate::data_paths::DataPaths;
 use crate::core::portfolio::cli::get_portfolio_service_handle;
-use crate::core::portfolio::display::TradesFormatter;
+use crate::core::portfolio::display::{MarketLabels, TradesFormatter};
+use crate::number_format;
 use tracing::info;
 
 #[derive(Args, Debug)]
@@ -33,6 +34,10 @@ pub struct TradesArgs {
     /// CSV export filename
     #[arg(long, default_value = "trades.csv")]
     output: String,
+
+    /// Show market ids instead of market questions and outcome names
+    #[arg(long)]
+    raw: bool,
 }
 
 pub async fn trades(args: TradesArgs, host: &str, data_paths: DataPaths) -> Result<()> {
@@ -69,7 +74,13 @@ pub async fn trades(args: TradesArgs, host: &str, data_paths: DataPaths) -> Resu
     }
     
     // Display trades
-    let formatter = TradesFormatter::new(&trades);
+    let labels = (!args.raw).then(|| {
+        MarketLabels::load(&data_paths, trades.iter().map(|t| t.token_id.as_str()))
+    });
+    let mut formatter = TradesFormatter::new(&trades);
+    if let Some(labels) = &labels {
+        formatter = formatter.with_labels(labels);
+    }
     print!("{}", formatter.format_table(Some(args.limit)));
     
     // Show summary statistics
@@ -77,10 +88,10 @@ pub async fn trades(args: TradesArgs, host: &str, data_paths: DataPaths) -> Resu
     println!("  Total Trades: {}", trades.len());
     
     let total_volume: rust_decimal::Decimal = trades.iter().map(|t| t.size).sum();
-    println!("  Total Volume: ${:.2}", total_volume);
+    println!("  Total Volume: {}", number_format::usd(total_volume));
     
     let total_fees: rust_decimal::Decimal = trades.iter().map(|t| t.fee).sum();
-    println!("  Total Fees: ${:.2}", total_fees);
+    println!("  Total Fees: {}", number_format::usd(total_fees));
     
     let buy_trades: Vec<_> = trades.iter()
         .filter(|t| matches!(t.side, crate::core::portfolio::OrderSide::Buy))
@@ -89,19 +100,19 @@ pub async fn trades(args: TradesArgs, host: &str, data_paths: DataPaths) -> Resu
         .filter(|t| matches!(t.side, crate::core::portfolio::OrderSide::Sell))
         .collect();
     
-    println!("  Buy Trades: {} (${:.2} volume)", 
+    println!("  Buy Trades: {} ({} volume)", 
         buy_trades.len(), 
-        buy_trades.iter().map(|t| t.size).sum::<rust_decimal::Decimal>()
+        number_format::usd(buy_trades.iter().map(|t| t.size).sum::<rust_decimal::Decimal>())
     );
-    println!("  Sell Trades: {} (${:.2} volume)", 
+    println!("  Sell Trades: {} ({} volume)", 
         sell_trades.len(),
-        sell_trades.iter().map(|t| t.size).sum::<rust_decimal::Decimal>()
+        number_format::usd(sell_trades.iter().map(|t| t.size).sum::<rust_decimal::Decimal>())
     );
     
     // Show average trade size
     if !trades.is_empty() {
         let avg_size = total_volume / rust_decimal::Decimal::from(trades.len());
-        println!("  Average Trade Size: ${:.2}", avg_size);
+        println!("  Average Trade Size: {}", number_format::usd(avg_size));
     }
     
     println!();
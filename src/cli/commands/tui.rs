@@ -0,0 +1,150 @@
+//! Unified TUI command: every page in one session over one streaming connection
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use std::sync::Arc;
+
+use crate::config;
+use crate::core::execution::synthetic::SyntheticStore;
+use crate::core::services::{Streamer, StreamerConfig};
+use crate::core::ws::{AuthPayload, WsConfig};
+use crate::data_paths::DataPaths;
+use crate::logging::{init_logging, LogMode, LoggingConfig};
+use crate::markets::datasets::replay::{parse_speed, resolve_capture, CaptureReplay};
+use crate::markets::datasets::SelectionManager;
+use crate::theme::ThemePreset;
+use crate::tui::navigation::Page;
+use crate::tui::runner::{run_app, RunOptions};
+
+#[derive(Args, Clone)]
+pub struct TuiArgs {
+    /// Asset IDs to stream (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub assets: Vec<String>,
+
+    /// Use a saved token selection for the market feed
+    #[arg(long)]
+    pub selection: Option<String>,
+
+    /// Add the legs of saved synthetic instruments to the market feed (comma-separated names)
+    #[arg(long, value_delimiter = ',')]
+    pub synthetics: Vec<String>,
+
+    /// Markets for the user feed (comma-separated, uses saved credentials)
+    #[arg(long, value_delimiter = ',')]
+    pub markets: Option<Vec<String>>,
+
+    /// Page to open on start (stream, orders, tokens, markets, portfolio, datasets, gamma)
+    #[arg(long, default_value = "stream")]
+    pub page: String,
+
+    /// Color palette, overriding the preset in theme.yaml
+    #[arg(long, value_enum)]
+    pub theme: Option<ThemePreset>,
+
+    /// Replay a `stream --record` capture (name or path) instead of streaming live
+    #[arg(long, conflicts_with_all = ["assets", "selection", "synthetics", "markets"])]
+    pub replay: Option<String>,
+
+    /// Start of the replayed window (HH:MM[:SS], 'YYYY-MM-DD HH:MM[:SS]' or RFC 3339)
+    #[arg(long, requires = "replay")]
+    pub from: Option<String>,
+
+    /// End of the replayed window
+    #[arg(long, requires = "replay")]
+    pub to: Option<String>,
+
+    /// Replay speed multiplier
+    #[arg(long, default_value_t = 1.0, value_parser = parse_speed, requires = "replay")]
+    pub replay_speed: f64,
+}
+
+pub struct TuiCommand {
+    args: TuiArgs,
+}
+
+impl TuiCommand {
+    pub fn new(args: TuiArgs) -> Self {
+        Self { args }
+    }
+
+    pub async fn execute(&self, host: &str, data_paths: DataPaths) -> Result<()> {
+        crate::core::audit::set_context(crate::core::audit::AuditContext::Tui);
+        let initial_page = Page::all()
+            .into_iter()
+            .find(|page| page.title().eq_ignore_ascii_case(&self.args.page))
+            .ok_or_else(|| anyhow!("Unknown page '{}'", self.args.page))?;
+
+        // Logs go to file only so they don't draw over the TUI
+        init_logging(LoggingConfig::new(LogMode::FileOnly, data_paths.clone()))?;
+
+        if let Some(dataset) = &self.args.replay {
+            let replay = CaptureReplay::load(
+                &resolve_capture(&data_paths, dataset),
+                self.args.from.as_deref(),
+                self.args.to.as_deref(),
+            )?;
+            let mut streamer = Streamer::new(StreamerConfig {
+                auto_sync_on_hash_mismatch: false,
+                ..Default::default()
+            });
+            streamer.start_replay(&Arc::new(replay), self.args.replay_speed);
+
+            let options = RunOptions {
+                initial_page: Some(initial_page),
+                theme: self.args.theme,
+                ..Default::default()
+            };
+            return run_app(host, data_paths, streamer, options).await;
+        }
+
+        let mut market_assets = self.args.assets.clone();
+        if let Some(selection) = &self.args.selection {
+            let tokens = SelectionManager::new(&data_paths.data())
+                .get_tokens(selection)
+                .map_err(|e| anyhow!("Failed to load selection '{}': {}", selection, e))?;
+            market_assets.extend(tokens);
+        }
+        if !self.args.synthetics.is_empty() {
+            for instrument in SyntheticStore::new(&data_paths).select(&self.args.synthetics)? {
+                market_assets.extend(instrument.legs.into_iter().map(|leg| leg.token_id));
+            }
+            market_assets.sort();
+            market_assets.dedup();
+        }
+
+        let user_auth = match &self.args.markets {
+            Some(_) => {
+                let creds = config::load_credentials(&data_paths).await?;
+                Some(AuthPayload {
+                    api_key: creds.api_key,
+                    secret: creds.secret,
+                    passphrase: creds.passphrase,
+                })
+            }
+            None => None,
+        };
+
+        // One streamer feeds every page; with no assets the market pages stay
+        // empty while orders, portfolio, datasets and gamma still work
+        let mut streamer = Streamer::new(StreamerConfig {
+            ws_config: WsConfig::default(),
+            market_assets,
+            user_markets: self.args.markets.clone(),
+            user_auth,
+            event_buffer_size: 1000,
+            auto_sync_on_hash_mismatch: true,
+        });
+        streamer
+            .start(host, &data_paths)
+            .await
+            .map_err(|e| anyhow!("Failed to start streaming: {}", e))?;
+
+        let options = RunOptions {
+            initial_page: Some(initial_page),
+            theme: self.args.theme,
+            ..Default::default()
+        };
+        run_app(host, data_paths, streamer, options).await
+    }
+}
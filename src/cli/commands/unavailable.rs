@@ -0,0 +1,31 @@
+//! Commands that were compiled out
+//!
+//! Interactive and storage-heavy commands sit behind cargo features (`tui`,
+//! `gui`, `surrealdb`, `sqlite`) so headless builds can leave their dependencies out.
+//! A build without a feature still parses the commands that need it, and
+//! running one explains how to get a build that has it instead of failing
+//! with "unrecognized subcommand".
+
+use anyhow::anyhow;
+use clap::Args;
+
+/// Arguments of a command this build was compiled without
+///
+/// Accepts anything, so the command reaches [`missing_feature`] rather than a
+/// clap error about its options.
+#[derive(Args, Clone, Debug)]
+pub struct UnavailableArgs {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, hide = true)]
+    pub args: Vec<String>,
+}
+
+/// Error for running `command` in a build without `feature`
+pub fn missing_feature(command: &str, feature: &str) -> anyhow::Error {
+    anyhow!(
+        "`{}` needs the `{}` feature, which this build of polybot was compiled without. \
+         Rebuild with `cargo install --path . --features {}` (or without `--no-default-features`)",
+        command,
+        feature,
+        feature
+    )
+}
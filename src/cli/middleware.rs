@@ -0,0 +1,158 @@
+//! Middleware wrapped around every command by [`Cli::execute`](super::Cli::execute)
+//!
+//! Each command is timed and logs structured `Command started` and `Command
+//! finished` events. Commands that write a dataset report it with
+//! [`report_dataset`](crate::markets::datasets::report_dataset); once the
+//! command succeeds the middleware writes the dataset's `dataset.yaml` with the
+//! full invocation and an [`ExecutionContext`], so commands don't each assemble
+//! that metadata themselves.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::ffi::OsString;
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::markets::datasets::{
+    save_command_metadata_with_context, take_reported_datasets, ExecutionContext,
+};
+
+/// Timing and dataset bookkeeping for one running command
+pub struct CommandMiddleware {
+    command: &'static str,
+    args: Vec<String>,
+    started: Instant,
+    started_at: DateTime<Utc>,
+}
+
+impl CommandMiddleware {
+    pub fn start(command: &'static str) -> Self {
+        let args = invocation_args(command);
+        // Reports left over from an earlier command in this process aren't ours
+        take_reported_datasets();
+        // Most commands set up logging themselves, so this only reaches an
+        // already installed subscriber; the finish event repeats the details
+        info!(command, args = ?args, "Command started");
+        Self {
+            command,
+            args,
+            started: Instant::now(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Log the outcome and, if the command succeeded, write the metadata of
+    /// the datasets it reported
+    pub fn finish(self, result: &Result<()>) {
+        let duration = self.started.elapsed();
+        let datasets = take_reported_datasets();
+
+        if let Err(e) = result {
+            warn!(
+                command = self.command,
+                args = ?self.args,
+                started_at = %self.started_at,
+                duration_ms = duration.as_millis() as u64,
+                error = %e,
+                "Command failed"
+            );
+            return;
+        }
+
+        for dataset in &datasets {
+            let context = ExecutionContext::capture(self.started_at, Some(duration.as_secs_f64()));
+            if let Err(e) = save_command_metadata_with_context(
+                &dataset.path,
+                self.command,
+                &self.args,
+                Some(dataset.additional_info.clone()),
+                Some(context),
+            ) {
+                warn!(
+                    "Failed to save metadata for dataset {}: {}",
+                    dataset.path.display(),
+                    e
+                );
+            }
+        }
+        info!(
+            command = self.command,
+            args = ?self.args,
+            started_at = %self.started_at,
+            duration_ms = duration.as_millis() as u64,
+            datasets = datasets.len(),
+            "Command finished"
+        );
+    }
+}
+
+/// The command line that was parsed, when an alias made it differ from the
+/// process arguments
+static INVOCATION: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Record the arguments (program name first) handed to the parser
+pub fn record_invocation(args: &[OsString]) {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let _ = INVOCATION.set(args);
+}
+
+/// The parsed command line, program name first; arguments that aren't UTF-8
+/// are converted lossily
+pub fn invocation() -> Vec<String> {
+    match INVOCATION.get() {
+        Some(args) => args.clone(),
+        None => std::env::args_os()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+    }
+}
+
+/// The command's own arguments: everything after its name on the command line
+fn invocation_args(command: &str) -> Vec<String> {
+    let args: Vec<String> = invocation().into_iter().skip(1).collect();
+    match args.iter().position(|arg| arg == command) {
+        Some(index) => args[index + 1..].to_vec(),
+        None => args,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::datasets::{load_dataset_metadata, report_dataset};
+    use std::collections::HashMap;
+
+    #[test]
+    fn reported_datasets_get_metadata_only_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let middleware = |args: &[&str]| CommandMiddleware {
+            command: "analyze",
+            args: args.iter().map(|a| a.to_string()).collect(),
+            started: Instant::now(),
+            started_at: Utc::now(),
+        };
+
+        report_dataset(dir.path(), HashMap::new());
+        middleware(&["failed"]).finish(&Err(anyhow::anyhow!("boom")));
+        assert!(load_dataset_metadata(dir.path()).is_err());
+
+        let info = HashMap::from([("markets_in_dataset".to_string(), serde_json::json!(3))]);
+        report_dataset(dir.path(), info);
+        middleware(&["out", "--source-dataset", "raw"]).finish(&Ok(()));
+
+        let metadata = load_dataset_metadata(dir.path()).unwrap();
+        assert_eq!(metadata.command_info.command, "analyze");
+        assert_eq!(
+            metadata.command_info.args,
+            ["out", "--source-dataset", "raw"]
+        );
+        assert_eq!(metadata.additional_info["markets_in_dataset"], 3);
+        let context = metadata.execution_context.unwrap();
+        assert!(context.duration_seconds.is_some());
+        assert!(context.working_directory.is_some());
+    }
+}
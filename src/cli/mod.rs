@@ -7,38 +7,84 @@
 //! See README.md for detailed documentation of the CLI architecture and usage patterns.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use std::ffi::OsString;
 use std::path::PathBuf;
 
+mod aliases;
 mod args;
 pub mod commands;
+mod middleware;
 
 use crate::data_paths::{DataPaths, DEFAULT_DATA_DIR};
-pub use args::parse_percentage;
+use crate::migrations::MigrationRunner;
+pub use args::{parse_duration, parse_percentage};
+use middleware::CommandMiddleware;
+
+/// CLOB host used with `--sandbox` (Mumbai testnet)
+pub const SANDBOX_HOST: &str = "https://clob-mumbai.polymarket.com";
 
 // Import all command args and commands
+use commands::alerts::{AlertsArgs, AlertsCommand};
+use commands::alias::{AliasArgs, AliasCommand};
 use commands::analyze::{AnalyzeArgs, AnalyzeCommand};
+use commands::audit::{AuditArgs, AuditCommand};
 use commands::book::{BookArgs, BookCommand};
+use commands::book_snapshots::{BookSnapshotsArgs, BookSnapshotsCommand};
+use commands::bracket::{BracketArgs, BracketCommand};
 use commands::buy::{BuyArgs, BuyCommand};
+use commands::cache::{CacheArgs, CacheCommand};
 use commands::cancel::{CancelArgs, CancelCommand};
+#[cfg(feature = "gui")]
 use commands::canvas::{CanvasArgs, CanvasCommand};
+#[cfg(not(feature = "gui"))]
+use commands::unavailable::UnavailableArgs as CanvasArgs;
+use commands::close::{CloseArgs, CloseCommand};
 use commands::daemon::{DaemonArgs, DaemonCommand};
 use commands::datasets::{DatasetsArgs, DatasetsCommand};
+use commands::debug::{DebugArgs, DebugCommand};
+use commands::doctor::{DoctorArgs, DoctorCommand};
 use commands::enrich::{EnrichArgs, EnrichCommand};
+#[cfg(feature = "sqlite")]
+use commands::export::{ExportArgs, ExportCommand};
+#[cfg(not(feature = "sqlite"))]
+use commands::unavailable::UnavailableArgs as ExportArgs;
+use commands::failover::{FailoverArgs, FailoverCommand};
 use commands::fetch_all_markets::{FetchAllMarketsArgs, FetchAllMarketsCommand};
+use commands::funds::{FundsArgs, FundsCommand};
+#[cfg(feature = "fuzz")]
+use commands::fuzz_book::{FuzzBookArgs, FuzzBookCommand};
+use commands::flatten::{FlattenArgs, FlattenCommand};
+use commands::hedge::{HedgeArgs, HedgeCommand};
+use commands::import_trades::{ImportTradesArgs, ImportTradesCommand};
 use commands::index::{IndexArgs, IndexCommand};
 use commands::init::{InitArgs, InitCommand};
 use commands::install::{InstallArgs, InstallCommand};
+use commands::liquidity::{LiquidityArgs, LiquidityCommand};
 use commands::markets::{MarketsArgs, MarketsCommand};
 use commands::orders::{OrdersArgs, OrdersCommand};
 use commands::pipeline::{PipelineArgs, PipelineCommand};
 use commands::portfolio::PortfolioArgs;
+use commands::profile::{ProfileArgs, ProfileCommand};
+use commands::resolution::{ResolutionArgs, ResolutionCommand};
 use commands::run_strategy::{RunStrategyArgs, RunStrategyCommand};
+use commands::strategy::{StrategyArgs, StrategyCommand};
+use commands::selftest::{SelftestArgs, SelftestCommand};
 use commands::sell::{SellArgs, SellCommand};
+use commands::session::{SessionArgs, SessionCommand};
+use commands::signer::{SignerArgs, SignerCommand};
+use commands::simulate_fill::{SimulateFillArgs, SimulateFillCommand};
 use commands::stream::{StreamArgs, StreamCommand};
+use commands::synthetic::{SyntheticArgs, SyntheticCommand};
+#[cfg(feature = "tui")]
+use commands::tui::{TuiArgs, TuiCommand};
+#[cfg(not(feature = "tui"))]
+use commands::unavailable::UnavailableArgs as TuiArgs;
 use commands::version::{VersionArgs, VersionCommand};
 use commands::worktree::WorktreeArgs;
 use commands::gamma::{GammaArgs, execute_gamma_command};
+#[cfg(not(all(feature = "gui", feature = "tui", feature = "sqlite")))]
+use commands::unavailable::missing_feature;
 use commands::portfolio_status::{PortfolioStatusArgs, portfolio_status};
 use commands::trades::{TradesArgs, trades};
 use crate::address_book::AddressCommand;
@@ -59,9 +105,19 @@ pub struct Cli {
     #[arg(long, global = true, default_value = DEFAULT_DATA_DIR)]
     pub data_dir: PathBuf,
 
+    /// Data profile to use (isolated credentials, portfolio and datasets).
+    /// Falls back to POLYBOT_PROFILE, then the profile set with `profile switch`
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
     /// Verbose logging
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
+
+    /// Bypass the response cache: fetch market metadata and Gamma queries
+    /// from the APIs and don't store the responses
+    #[arg(long, global = true)]
+    pub no_cache: bool,
 }
 
 #[derive(Subcommand)]
@@ -69,6 +125,9 @@ pub enum Commands {
     /// Initialize authentication and save credentials
     Init(InitArgs),
 
+    /// Show or switch the signing backend (stored key or Ledger)
+    Signer(SignerArgs),
+
     /// Browse and search Polymarket markets
     Markets(MarketsArgs),
 
@@ -81,9 +140,18 @@ pub enum Commands {
     /// Enrich market data with real-time information
     Enrich(EnrichArgs),
 
+    /// Rank enriched markets by executable liquidity (price impact of $100/$1k/$10k)
+    Liquidity(LiquidityArgs),
+
+    /// Export market data to other formats (SQLite)
+    Export(ExportArgs),
+
     /// Show orderbook for a token
     Book(BookArgs),
 
+    /// Capture order books of chosen markets on a schedule for liquidity analysis
+    BookSnapshots(BookSnapshotsArgs),
+
     /// Place a buy order
     Buy(BuyArgs),
 
@@ -93,24 +161,57 @@ pub enum Commands {
     /// Cancel an order
     Cancel(CancelArgs),
 
+    /// Close a percentage of a position, priced at its average price by default
+    Close(CloseArgs),
+
+    /// Buy the complementary token to bring a market to a target net exposure
+    Hedge(HedgeArgs),
+
+    /// Cancel resting orders and close every position with slippage-capped orders
+    Flatten(FlattenArgs),
+
+    /// Buy with linked stop-loss and take-profit exits that cancel each other
+    Bracket(BracketArgs),
+
+    /// Show deposit addresses, pending bridge deposits and the wallet's USDC balance
+    Funds(FundsArgs),
+
     /// List open orders
     Orders(OrdersArgs),
 
+    /// Estimate fill probability and time-to-fill for a hypothetical resting order
+    SimulateFill(SimulateFillArgs),
+
     /// Monitor portfolio and positions with real-time updates
     Portfolio(PortfolioArgs),
 
+    /// Show resolution sources and alert when a held market is proposed or disputed
+    Resolution(ResolutionArgs),
+
     /// Stream real-time market data via WebSocket
     Stream(StreamArgs),
+
+    /// Define synthetic instruments combining tokens and watch their books against bounds
+    Synthetic(SyntheticArgs),
+
+    /// Open every TUI page (markets, orders, portfolio, streams, datasets, gamma) in one session
+    Tui(TuiArgs),
     
     /// Run a trading strategy on a single token
     RunStrategy(RunStrategyArgs),
 
+    /// Strategy performance reports (spread capture / inventory drift / fee attribution)
+    Strategy(StrategyArgs),
+
     /// Launch the egui-based trading canvas interface
     Canvas(CanvasArgs),
 
     /// Run streaming daemon with sample strategy
     Daemon(DaemonArgs),
 
+    /// Leader and standbys of daemons started with --failover
+    Failover(FailoverArgs),
+
     /// Run pipeline workflows from YAML configurations
     Pipeline(PipelineArgs),
 
@@ -123,6 +224,9 @@ pub enum Commands {
     /// Show version information
     Version(VersionArgs),
 
+    /// Check credentials, connectivity, storage and indexes for problems
+    Doctor(DoctorArgs),
+
     /// Index raw market data into RocksDB for fast queries
     Index(IndexArgs),
 
@@ -137,31 +241,229 @@ pub enum Commands {
     
     /// View trade history
     Trades(TradesArgs),
+
+    /// Seed the local trade history, lots and positions from the Data API
+    ImportTrades(ImportTradesArgs),
     
     /// Manage address book for multiple Ethereum addresses
     Address(AddressCommand),
+
+    /// Manage isolated data directory profiles (e.g. research vs live)
+    Profile(ProfileArgs),
+
+    /// Manage command aliases (`polybot <alias>` runs a stored command line)
+    Alias(AliasArgs),
+
+    /// Show response cache statistics or clear cached responses
+    Cache(CacheArgs),
+
+    /// Show or verify the tamper-evident log of orders, cancels, transactions and config changes
+    Audit(AuditArgs),
+
+    /// Show the activity and P&L report of the last (or a given) trading session
+    Session(SessionArgs),
+
+    /// List and acknowledge alerts, and follow up on open ones by repeating and escalating them
+    Alerts(AlertsArgs),
+
+    /// Collect diagnostics (session state, recent events, redacted config, logs) for bug reports
+    Debug(DebugArgs),
+
+    /// Validate the install end to end (markets, order book, market feed) against the sandbox
+    Selftest(SelftestArgs),
+
+    /// Replay generated valid and adversarial feed sequences through the order book (development)
+    #[cfg(feature = "fuzz")]
+    FuzzBook(FuzzBookArgs),
+}
+
+impl Commands {
+    /// Whether the command reads or writes the data directory and therefore
+    /// needs its on-disk formats to be current
+    pub fn requires_data_migration(&self) -> bool {
+        match self {
+            // `doctor` reports pending migrations, and what blocks them, as a check
+            Commands::Version(_) | Commands::Install(_) | Commands::Selftest(_) | Commands::Doctor(_) => {
+                false
+            }
+            #[cfg(feature = "fuzz")]
+            Commands::FuzzBook(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Whether the command places or cancels orders or sends transactions,
+    /// which read-only profiles refuse before it starts
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Commands::Buy(_)
+            | Commands::Sell(_)
+            | Commands::Cancel(_)
+            | Commands::Close(_)
+            | Commands::Hedge(_)
+            | Commands::Flatten(_)
+            | Commands::RunStrategy(_) => true,
+            Commands::Funds(args) => args.command.is_mutating(),
+            Commands::Bracket(args) => args.command.is_mutating(),
+            Commands::Orders(args) => args.is_mutating(),
+            _ => false,
+        }
+    }
+
+    /// Subcommand name as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Init(_) => "init",
+            Commands::Signer(_) => "signer",
+            Commands::Markets(_) => "markets",
+            Commands::FetchAllMarkets(_) => "fetch-all-markets",
+            Commands::Analyze(_) => "analyze",
+            Commands::Enrich(_) => "enrich",
+            Commands::Liquidity(_) => "liquidity",
+            Commands::Export(_) => "export",
+            Commands::Book(_) => "book",
+            Commands::BookSnapshots(_) => "book-snapshots",
+            Commands::Buy(_) => "buy",
+            Commands::Sell(_) => "sell",
+            Commands::Cancel(_) => "cancel",
+            Commands::Close(_) => "close",
+            Commands::Hedge(_) => "hedge",
+            Commands::Flatten(_) => "flatten",
+            Commands::Bracket(_) => "bracket",
+            Commands::Funds(_) => "funds",
+            Commands::Orders(_) => "orders",
+            Commands::SimulateFill(_) => "simulate-fill",
+            Commands::Portfolio(_) => "portfolio",
+            Commands::Resolution(_) => "resolution",
+            Commands::Stream(_) => "stream",
+            Commands::Synthetic(_) => "synthetic",
+            Commands::Tui(_) => "tui",
+            Commands::RunStrategy(_) => "run-strategy",
+            Commands::Strategy(_) => "strategy",
+            Commands::Canvas(_) => "canvas",
+            Commands::Daemon(_) => "daemon",
+            Commands::Failover(_) => "failover",
+            Commands::Pipeline(_) => "pipeline",
+            Commands::Datasets(_) => "datasets",
+            Commands::Install(_) => "install",
+            Commands::Version(_) => "version",
+            Commands::Doctor(_) => "doctor",
+            Commands::Index(_) => "index",
+            Commands::Worktree(_) => "worktree",
+            Commands::Gamma(_) => "gamma",
+            Commands::PortfolioStatus(_) => "portfolio-status",
+            Commands::Trades(_) => "trades",
+            Commands::ImportTrades(_) => "import-trades",
+            Commands::Address(_) => "address",
+            Commands::Profile(_) => "profile",
+            Commands::Alias(_) => "alias",
+            Commands::Cache(_) => "cache",
+            Commands::Audit(_) => "audit",
+            Commands::Session(_) => "session",
+            Commands::Alerts(_) => "alerts",
+            Commands::Debug(_) => "debug",
+            Commands::Selftest(_) => "selftest",
+            #[cfg(feature = "fuzz")]
+            Commands::FuzzBook(_) => "fuzz-book",
+        }
+    }
+}
+
+/// Names of the built-in commands, which aliases can't shadow
+pub fn builtin_commands() -> Vec<String> {
+    let mut names = vec!["help".to_string()];
+    for command in Cli::command().get_subcommands() {
+        names.push(command.get_name().to_string());
+        names.extend(command.get_all_aliases().map(String::from));
+    }
+    names
 }
 
 impl Cli {
+    /// Parse the process arguments after expanding user aliases
+    pub fn parse_with_aliases() -> Self {
+        let args: Vec<OsString> = std::env::args_os().collect();
+        match aliases::Aliases::expand_command_line(args, &builtin_commands()) {
+            Ok(args) => {
+                middleware::record_invocation(&args);
+                Self::parse_from(args)
+            }
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
     /// Get the host URL based on sandbox flag
     pub fn get_host(&self) -> &'static str {
         if self.sandbox {
-            "https://clob-mumbai.polymarket.com" // Mumbai testnet
+            SANDBOX_HOST
         } else {
-            "https://clob.polymarket.com"
+            crate::client::DEFAULT_HOST
         }
     }
 
     /// Execute the CLI command
     pub async fn execute(self) -> Result<()> {
         let host = self.get_host();
-        let data_paths = DataPaths::new(&self.data_dir);
+        let data_paths = match DataPaths::resolve(&self.data_dir, self.profile.as_deref()) {
+            Ok(paths) => paths,
+            // Still allow `profile` subcommands to repair a missing active profile
+            Err(_) if self.profile.is_none() && matches!(self.command, Commands::Profile(_)) => {
+                DataPaths::new(&self.data_dir)
+            }
+            Err(e) => return Err(e),
+        };
 
         // Ensure all directories exist
         data_paths.ensure_directories()?;
 
-        match self.command {
+        // Upgrade on-disk formats (or refuse to run on data from a newer build)
+        if self.command.requires_data_migration() {
+            let runner = MigrationRunner::new(&data_paths);
+            // Only `index` runs the long steps; other commands say they're pending
+            let applied = if matches!(self.command, Commands::Index(_)) {
+                runner.run_all()?
+            } else {
+                runner.run_startup()?
+            };
+            for migration in &applied {
+                eprintln!(
+                    "Migrated {} data v{} -> v{} ({})",
+                    migration.component, migration.from_version, migration.to_version, migration.name
+                );
+            }
+            for migration in runner.pending()?.iter().filter(|m| !m.runs_at_startup) {
+                eprintln!(
+                    "Pending {} data migration {} (v{} -> v{}): run 'polybot index' to apply it",
+                    migration.component,
+                    migration.name,
+                    migration.from_version,
+                    migration.from_version + 1
+                );
+            }
+        }
+
+        crate::core::session::start(self.command.name());
+        crate::core::audit::init(
+            &data_paths,
+            crate::core::audit::AuditContext::cli(middleware::invocation()),
+        );
+        crate::core::diagnostics::init(&data_paths);
+        crate::core::http_cache::init(&data_paths, !self.no_cache);
+        crate::number_format::init(&data_paths);
+
+        // Watch-only profiles can read everything but never place, cancel or sign
+        if crate::auth::access::activate(&data_paths)? && self.command.is_mutating() {
+            return Err(crate::auth::access::read_only_error("run this command"));
+        }
+
+        let session_paths = data_paths.clone();
+        let middleware = CommandMiddleware::start(self.command.name());
+        let result = match self.command {
             Commands::Init(args) => InitCommand::new(args).execute(host, data_paths).await,
+            Commands::Signer(args) => SignerCommand::new(args).execute(host, data_paths).await,
             Commands::Markets(args) => MarketsCommand::new(args).execute(host, data_paths).await,
             Commands::FetchAllMarkets(args) => {
                 FetchAllMarketsCommand::new(args)
@@ -170,28 +472,80 @@ impl Cli {
             }
             Commands::Analyze(args) => AnalyzeCommand::new(args).execute(host, data_paths).await,
             Commands::Enrich(args) => EnrichCommand::new(args).execute(host, data_paths).await,
+            Commands::Liquidity(args) => LiquidityCommand::new(args).execute(data_paths).await,
+            #[cfg(feature = "sqlite")]
+            Commands::Export(args) => ExportCommand::new(args).execute(host, data_paths).await,
+            #[cfg(not(feature = "sqlite"))]
+            Commands::Export(_) => Err(missing_feature("export", "sqlite")),
             Commands::Book(args) => BookCommand::new(args).execute(host, data_paths).await,
+            Commands::BookSnapshots(args) => BookSnapshotsCommand::new(args).execute(host, data_paths).await,
             Commands::Buy(args) => BuyCommand::new(args).execute(host, data_paths).await,
             Commands::Sell(args) => SellCommand::new(args).execute(host, data_paths).await,
             Commands::Cancel(args) => CancelCommand::new(args).execute(host, data_paths).await,
+            Commands::Close(args) => CloseCommand::new(args).execute(host, data_paths).await,
+            Commands::Hedge(args) => HedgeCommand::new(args).execute(host, data_paths).await,
+            Commands::Flatten(args) => FlattenCommand::new(args).execute(host, data_paths).await,
+            Commands::Bracket(args) => BracketCommand::new(args).execute(host, data_paths).await,
+            Commands::Funds(args) => FundsCommand::new(args).execute(host, data_paths).await,
             Commands::Orders(args) => OrdersCommand::new(args).execute(host, data_paths).await,
+            Commands::SimulateFill(args) => {
+                SimulateFillCommand::new(args).execute(host, data_paths).await
+            }
             Commands::Portfolio(args) => {
                 commands::portfolio::portfolio(args, host, data_paths).await
             }
+            Commands::Resolution(args) => ResolutionCommand::new(args).execute(host, data_paths).await,
             Commands::Stream(args) => StreamCommand::new(args).execute(host, data_paths).await,
+            Commands::Synthetic(args) => {
+                SyntheticCommand::new(args).execute(host, data_paths).await
+            }
+            #[cfg(feature = "tui")]
+            Commands::Tui(args) => TuiCommand::new(args).execute(host, data_paths).await,
+            #[cfg(not(feature = "tui"))]
+            Commands::Tui(_) => Err(missing_feature("tui", "tui")),
             Commands::RunStrategy(args) => RunStrategyCommand::new(args).execute(host, &data_paths).await,
+            Commands::Strategy(args) => StrategyCommand::new(args).execute(host, data_paths).await,
+            #[cfg(feature = "gui")]
             Commands::Canvas(args) => CanvasCommand::new(args).execute(host, data_paths).await,
+            #[cfg(not(feature = "gui"))]
+            Commands::Canvas(_) => Err(missing_feature("canvas", "gui")),
             Commands::Daemon(args) => DaemonCommand::new(args).execute(host, data_paths).await,
+            Commands::Failover(args) => FailoverCommand::new(args).execute(host, data_paths).await,
             Commands::Pipeline(args) => PipelineCommand::new(args).execute(host, data_paths).await,
             Commands::Datasets(args) => DatasetsCommand::new(args).execute(host, data_paths).await,
             Commands::Install(args) => InstallCommand::new(args).execute(host, data_paths).await,
             Commands::Version(args) => VersionCommand::new(args).execute(host, data_paths).await,
+            Commands::Doctor(args) => DoctorCommand::new(args).execute(host, data_paths).await,
             Commands::Index(args) => IndexCommand::new(args).execute(host, data_paths).await,
             Commands::Worktree(args) => commands::worktree::worktree(args, host, data_paths).await,
-            Commands::Gamma(args) => execute_gamma_command(args, self.verbose > 0).await,
+            Commands::Gamma(args) => {
+                execute_gamma_command(args, self.verbose > 0, data_paths).await
+            }
             Commands::PortfolioStatus(args) => portfolio_status(args, host, data_paths).await,
             Commands::Trades(args) => trades(args, host, data_paths).await,
+            Commands::ImportTrades(args) => ImportTradesCommand::new(args).execute(host, data_paths).await,
             Commands::Address(cmd) => cmd.execute(host, data_paths).await,
+            Commands::Profile(args) => ProfileCommand::new(args).execute(host, data_paths).await,
+            Commands::Alias(args) => AliasCommand::new(args).execute(host, data_paths).await,
+            Commands::Cache(args) => CacheCommand::new(args).execute(host, data_paths).await,
+            Commands::Audit(args) => AuditCommand::new(args).execute(host, data_paths).await,
+            Commands::Session(args) => SessionCommand::new(args).execute(host, data_paths).await,
+            Commands::Alerts(args) => AlertsCommand::new(args).execute(host, data_paths).await,
+            Commands::Debug(args) => DebugCommand::new(args).execute(host, data_paths).await,
+            Commands::Selftest(args) => SelftestCommand::new(args).execute(host, data_paths).await,
+            #[cfg(feature = "fuzz")]
+            Commands::FuzzBook(args) => FuzzBookCommand::new(args).execute(host, data_paths).await,
+        };
+        middleware.finish(&result);
+        // Print what the session did with orders in place of a bare "session ended"
+        match crate::core::session::finish(host, &session_paths).await {
+            Ok(Some(report)) => commands::session::print_report(&report),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("No session report: {:#}", e),
+        }
+        if let Err(e) = crate::core::http_cache::global().finish_session() {
+            tracing::warn!("{:#}", e);
         }
+        result
     }
 }
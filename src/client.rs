@@ -0,0 +1,175 @@
+//! High-level client for using polybot as a library
+//!
+//! [`PolybotClient`] wraps the pieces the CLI wires together for every command
+//! (credentials from a data profile, the CLOB client and the order manager
+//! with its risk limits) behind a small async API. The market feed is public,
+//! so [`MarketStream`] subscribes without credentials:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use polybot::{DataPaths, MarketStream, PolybotClient, DEFAULT_HOST};
+//!
+//! let data_paths = DataPaths::resolve("./data", None)?;
+//! let client = PolybotClient::connect_to(DEFAULT_HOST, data_paths).await?;
+//! let book = client.order_book("71321045679252212594626385532706912750332728571942532289631379312455583992563").await?;
+//! println!("best bid {:?}", book.best_bid());
+//!
+//! let mut stream = MarketStream::subscribe(vec![book.asset_id.clone()]).await?;
+//! while let Some(event) = stream.next().await {
+//!     println!("{:?}", event);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Orders go through the same
+//! [`OrderManager`](crate::core::execution::orders::OrderManager) as
+//! `polybot buy`/`sell`, so read-only profiles, hardware signers, message
+//! throttles and the audit log apply to library callers too. Large order
+//! confirmation is interactive and left to the caller.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::core::execution::orders::{
+    OrderCancellationResponse, OrderConfig, OrderPlacementResponse, PolyBot,
+};
+use crate::core::execution::route::OrderRoute;
+use crate::core::risk::RiskConfig;
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::{parse_message, OrderBook, PolyEvent, WsClient, WsConfig};
+use crate::data_paths::DataPaths;
+
+/// Production CLOB API host
+pub const DEFAULT_HOST: &str = "https://clob.polymarket.com";
+
+/// Authenticated access to market data and order placement
+pub struct PolybotClient {
+    clob: Mutex<ClobClient>,
+    bot: PolyBot,
+}
+
+impl PolybotClient {
+    /// Connect to a CLOB host, [`DEFAULT_HOST`] or the sandbox, with the
+    /// credentials saved in `data_paths`
+    ///
+    /// The profile must have been set up with `polybot init`; read-only
+    /// profiles connect but refuse to place or cancel orders.
+    pub async fn connect_to(host: &str, data_paths: DataPaths) -> Result<Self> {
+        crate::auth::access::activate(&data_paths)?;
+        let clob = crate::auth::get_authenticated_client(host, &data_paths).await?;
+        let risk_config = RiskConfig::load(&data_paths)?;
+        let order_signer = crate::auth::order_signer(host, &data_paths).await?;
+        let bot = PolyBot::with_risk_config(OrderConfig::default(), risk_config)
+            .with_order_signer(order_signer);
+        info!("Polybot client connected to {} (profile {})", host, data_paths.profile());
+
+        Ok(Self {
+            clob: Mutex::new(clob),
+            bot,
+        })
+    }
+
+    /// Current order book for a token, fetched over REST
+    pub async fn order_book(&self, token_id: &str) -> Result<OrderBook> {
+        let summary = self.clob.lock().await.get_order_book(token_id).await?;
+        let bids = summary.bids.iter().map(|l| PriceLevel::new(l.price, l.size)).collect();
+        let asks = summary.asks.iter().map(|l| PriceLevel::new(l.price, l.size)).collect();
+
+        let mut book = OrderBook::new(token_id.to_string());
+        book.replace_with_snapshot_no_hash(
+            summary.market.clone(),
+            Utc::now().timestamp_millis() as u64,
+            bids,
+            asks,
+        );
+        Ok(book)
+    }
+
+    /// Place a limit buy of `size` shares at `price`
+    pub async fn buy(&self, token_id: &str, price: Decimal, size: Decimal) -> Result<OrderPlacementResponse> {
+        let mut clob = self.clob.lock().await;
+        self.bot.order.place_buy_order(&mut clob, token_id, price, size).await
+    }
+
+    /// Place a limit sell of `size` shares at `price`
+    pub async fn sell(&self, token_id: &str, price: Decimal, size: Decimal) -> Result<OrderPlacementResponse> {
+        let mut clob = self.clob.lock().await;
+        self.bot.order.place_sell_order(&mut clob, token_id, price, size).await
+    }
+
+    pub async fn cancel(&self, order_id: &str) -> Result<OrderCancellationResponse> {
+        let mut clob = self.clob.lock().await;
+        self.bot.order.cancel_order(&mut clob, order_id).await
+    }
+
+    /// The order manager and CLOB client, for code that places orders on its own
+    pub fn route(&self) -> OrderRoute<'_> {
+        OrderRoute::new(&self.bot.order, &self.clob)
+    }
+}
+
+/// Parsed events from a market feed subscription
+///
+/// The connection reconnects on its own and is closed when the stream is dropped.
+pub struct MarketStream {
+    ws: WsClient,
+    messages: tokio::sync::broadcast::Receiver<crate::core::ws::WsMessage>,
+    pending: VecDeque<PolyEvent>,
+    dropped: u64,
+}
+
+impl MarketStream {
+    /// Subscribe to the public market feed for `token_ids`
+    pub async fn subscribe(token_ids: Vec<String>) -> Result<Self> {
+        let ws = WsClient::new_market(WsConfig::default())
+            .await
+            .map_err(|e| anyhow!("Failed to connect to market feed: {}", e))?;
+        ws.subscribe_market(token_ids)
+            .map_err(|e| anyhow!("Failed to subscribe: {}", e))?;
+        let messages = ws.messages();
+        Ok(Self {
+            ws,
+            messages,
+            pending: VecDeque::new(),
+            dropped: 0,
+        })
+    }
+
+    /// Next event, or `None` once the feed has closed
+    pub async fn next(&mut self) -> Option<PolyEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            match self.messages.recv().await {
+                Ok(message) => match parse_message(&message) {
+                    Ok(events) => self.pending.extend(events),
+                    Err(e) => debug!("Skipping unparseable market feed message: {}", e),
+                },
+                Err(RecvError::Lagged(n)) => {
+                    debug!("Market stream lagged, {} messages dropped", n);
+                    self.dropped += n;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Messages dropped because the consumer fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl Drop for MarketStream {
+    fn drop(&mut self) {
+        let _ = self.ws.disconnect();
+    }
+}
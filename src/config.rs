@@ -1,4 +1,5 @@
-use crate::data_paths::DataPaths;
+use crate::core::audit::{self, AuditAction};
+use crate::data_paths::{DataPaths, DEFAULT_PROFILE};
 use aes_gcm::{
     aead::{
         rand_core::{OsRng, RngCore},
@@ -28,7 +29,17 @@ fn get_creds_path(data_paths: &DataPaths) -> Result<PathBuf> {
 }
 
 /// Legacy path for backward compatibility
-fn get_legacy_creds_path() -> Result<PathBuf> {
+///
+/// Only the default profile falls back to it, so named profiles never pick up
+/// credentials that belong to another tree.
+fn get_legacy_creds_path(data_paths: &DataPaths) -> Result<PathBuf> {
+    if data_paths.profile() != DEFAULT_PROFILE {
+        return Err(anyhow!(
+            "No legacy credentials for profile '{}'",
+            data_paths.profile()
+        ));
+    }
+
     let config_dir = directories::ProjectDirs::from("com", "polybot", "polybot")
         .ok_or_else(|| anyhow!("Could not determine config directory"))?
         .config_dir()
@@ -37,6 +48,18 @@ fn get_legacy_creds_path() -> Result<PathBuf> {
     Ok(config_dir.join("creds.json.enc"))
 }
 
+fn no_credentials_error(data_paths: &DataPaths) -> anyhow::Error {
+    if data_paths.profile() == DEFAULT_PROFILE {
+        anyhow!("No credentials found. Run 'polybot init' first")
+    } else {
+        anyhow!(
+            "No credentials found for profile '{}'. Run 'polybot --profile {} init' first",
+            data_paths.profile(),
+            data_paths.profile()
+        )
+    }
+}
+
 /// Get or prompt for passphrase
 async fn get_passphrase() -> Result<String> {
     // First check environment variable
@@ -159,7 +182,14 @@ pub async fn save_credentials(data_paths: &DataPaths, api_creds: &ApiCreds) -> R
     stored.api_secret = api_creds.secret.clone();
     stored.api_passphrase = api_creds.passphrase.clone();
 
-    save_stored_credentials(&creds_path, &passphrase, &stored).await
+    let result = save_stored_credentials(&creds_path, &passphrase, &stored).await;
+    audit::record(
+        AuditAction::CredentialsChanged,
+        "API credentials saved",
+        serde_json::json!({ "api_key": api_creds.api_key }),
+        result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+    );
+    result
 }
 
 /// Save private key to encrypted file
@@ -177,7 +207,14 @@ pub async fn save_private_key(data_paths: &DataPaths, private_key: &str) -> Resu
     // Update private key
     stored.private_key = Some(private_key.to_string());
 
-    save_stored_credentials(&creds_path, &passphrase, &stored).await
+    let result = save_stored_credentials(&creds_path, &passphrase, &stored).await;
+    audit::record(
+        AuditAction::CredentialsChanged,
+        "Private key saved",
+        serde_json::Value::Null,
+        result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+    );
+    result
 }
 
 /// Load credentials from encrypted file (with legacy support)
@@ -189,14 +226,14 @@ pub async fn load_credentials(data_paths: &DataPaths) -> Result<ApiCreds> {
         (creds_path, false)
     } else {
         // Check legacy location
-        if let Ok(legacy_path) = get_legacy_creds_path() {
+        if let Ok(legacy_path) = get_legacy_creds_path(data_paths) {
             if legacy_path.exists() {
                 (legacy_path, true)
             } else {
-                return Err(anyhow!("No credentials found. Run 'polybot init' first"));
+                return Err(no_credentials_error(data_paths));
             }
         } else {
-            return Err(anyhow!("No credentials found. Run 'polybot init' first"));
+            return Err(no_credentials_error(data_paths));
         }
     };
 
@@ -226,14 +263,14 @@ pub async fn load_private_key(data_paths: &DataPaths) -> Result<String> {
         creds_path
     } else {
         // Check legacy location
-        if let Ok(legacy_path) = get_legacy_creds_path() {
+        if let Ok(legacy_path) = get_legacy_creds_path(data_paths) {
             if legacy_path.exists() {
                 legacy_path
             } else {
-                return Err(anyhow!("No credentials found. Run 'polybot init' first"));
+                return Err(no_credentials_error(data_paths));
             }
         } else {
-            return Err(anyhow!("No credentials found. Run 'polybot init' first"));
+            return Err(no_credentials_error(data_paths));
         }
     };
 
@@ -0,0 +1,711 @@
+//! Alert severities, routing and escalation
+//!
+//! Every alert has a severity, and `alerts.yaml` in the data directory routes
+//! each severity to channels: a toast in the canvas, a Telegram message, a
+//! webhook POST or a phone call through Twilio. By default info alerts only
+//! toast, warnings also go to Telegram and critical alerts to the webhook and
+//! the phone.
+//!
+//! Raised alerts are kept in `alerts.json` until acknowledged, from `polybot
+//! alerts ack` or the canvas notifications center. While an alert is open, a
+//! process following up on alerts (the canvas, `synthetic watch`, `resolution
+//! check --watch` or `alerts run`) re-sends it on its severity's repeat
+//! interval and escalates it to the next severity once it has been open for the
+//! escalation delay. Every change to `alerts.json` is made under a lock file,
+//! so an acknowledgement from one process isn't overwritten by a follow-up or
+//! a new alert saved by another.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::core::file_lock::{run_blocking, FileLock, LOCK_TIMEOUT};
+use crate::data_paths::DataPaths;
+
+/// Routing settings, in the data directory
+pub const ALERTS_CONFIG_FILE: &str = "alerts.yaml";
+
+/// Raised alerts and their acknowledgements
+pub const ALERTS_FILE: &str = "alerts.json";
+
+/// Acknowledged alerts kept in `alerts.json` for `alerts list --all`
+const ACKNOWLEDGED_KEPT: usize = 200;
+
+/// Timeout of one delivery to a channel
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Info, Severity::Warning, Severity::Critical];
+
+    /// The severity an unacknowledged alert escalates to
+    pub fn escalated(self) -> Self {
+        match self {
+            Severity::Info => Severity::Warning,
+            Severity::Warning | Severity::Critical => Severity::Critical,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ️",
+            Severity::Warning => "⚠️",
+            Severity::Critical => "🚨",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => f.pad("info"),
+            Severity::Warning => f.pad("warning"),
+            Severity::Critical => f.pad("critical"),
+        }
+    }
+}
+
+/// Where an alert is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChannel {
+    /// Toast in the canvas, when it's the process raising the alert
+    Toast,
+    Telegram,
+    Webhook,
+    /// Voice call through Twilio
+    Phone,
+}
+
+impl fmt::Display for AlertChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertChannel::Toast => f.pad("toast"),
+            AlertChannel::Telegram => f.pad("telegram"),
+            AlertChannel::Webhook => f.pad("webhook"),
+            AlertChannel::Phone => f.pad("phone"),
+        }
+    }
+}
+
+/// Channels of one severity and how its unacknowledged alerts are followed up
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutePolicy {
+    pub channels: Vec<AlertChannel>,
+    /// Re-send an unacknowledged alert every this many seconds
+    pub repeat_secs: Option<u64>,
+    /// Re-sends at this severity before it stops repeating
+    pub max_repeats: u32,
+    /// Escalate to the next severity once unacknowledged this many seconds
+    pub escalate_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwilioConfig {
+    pub account_sid: String,
+    pub auth_token: String,
+    /// Twilio number calling
+    pub from: String,
+    /// Number called
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertRouting {
+    pub info: RoutePolicy,
+    pub warning: RoutePolicy,
+    pub critical: RoutePolicy,
+    pub telegram: Option<TelegramConfig>,
+    pub webhook: Option<WebhookConfig>,
+    pub twilio: Option<TwilioConfig>,
+}
+
+impl Default for AlertRouting {
+    fn default() -> Self {
+        Self {
+            info: RoutePolicy {
+                channels: vec![AlertChannel::Toast],
+                ..RoutePolicy::default()
+            },
+            warning: RoutePolicy {
+                channels: vec![AlertChannel::Toast, AlertChannel::Telegram],
+                repeat_secs: Some(15 * 60),
+                max_repeats: 3,
+                escalate_after_secs: Some(60 * 60),
+            },
+            critical: RoutePolicy {
+                channels: vec![
+                    AlertChannel::Toast,
+                    AlertChannel::Webhook,
+                    AlertChannel::Phone,
+                ],
+                repeat_secs: Some(5 * 60),
+                max_repeats: 6,
+                escalate_after_secs: None,
+            },
+            telegram: None,
+            webhook: None,
+            twilio: None,
+        }
+    }
+}
+
+impl AlertRouting {
+    /// Load `alerts.yaml` from the data directory, or the defaults when it
+    /// doesn't exist; channel credentials other users could read are refused
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(ALERTS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let routing: Self = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid alert routing {}", path.display()))?;
+        #[cfg(unix)]
+        if routing.has_credentials() {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)?.permissions().mode();
+            if mode & 0o077 != 0 {
+                bail!(
+                    "{} holds alert channel credentials but is accessible by other users \
+                     (mode {:o}); run chmod 600 on it",
+                    path.display(),
+                    mode & 0o777
+                );
+            }
+        }
+        Ok(routing)
+    }
+
+    /// Whether a bot token, webhook URL or Twilio token is configured; an
+    /// incoming-webhook URL is a credential itself
+    fn has_credentials(&self) -> bool {
+        self.telegram.is_some() || self.webhook.is_some() || self.twilio.is_some()
+    }
+
+    pub fn policy(&self, severity: Severity) -> &RoutePolicy {
+        match severity {
+            Severity::Info => &self.info,
+            Severity::Warning => &self.warning,
+            Severity::Critical => &self.critical,
+        }
+    }
+
+    /// Whether alerts of `severity` show as a toast
+    pub fn toasts(&self, severity: Severity) -> bool {
+        self.policy(severity)
+            .channels
+            .contains(&AlertChannel::Toast)
+    }
+
+    /// Whether `channel` has the settings it needs
+    pub fn is_configured(&self, channel: AlertChannel) -> bool {
+        match channel {
+            AlertChannel::Toast => true,
+            AlertChannel::Telegram => self.telegram.is_some(),
+            AlertChannel::Webhook => self.webhook.is_some(),
+            AlertChannel::Phone => self.twilio.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub id: String,
+    pub raised_at: DateTime<Utc>,
+    /// Severity it was raised with
+    pub raised_severity: Severity,
+    /// Current severity, above `raised_severity` once escalated
+    pub severity: Severity,
+    /// What raised it, e.g. `token_alert`, `synthetic`, `resolution`
+    pub source: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+    pub last_sent_at: DateTime<Utc>,
+    /// Re-sends at the current severity
+    #[serde(default)]
+    pub repeats: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Where it was acknowledged from (`cli`, `gui`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_by: Option<String>,
+}
+
+/// Follow-up due on an open alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUp {
+    Repeat,
+    Escalate(Severity),
+}
+
+impl Alert {
+    pub fn new(
+        severity: Severity,
+        source: &str,
+        message: String,
+        token_id: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            raised_at: now,
+            raised_severity: severity,
+            severity,
+            source: source.to_string(),
+            message,
+            token_id,
+            last_sent_at: now,
+            repeats: 0,
+            escalated_at: None,
+            acknowledged_at: None,
+            acknowledged_by: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.acknowledged_at.is_none()
+    }
+
+    /// The follow-up due at `now`; escalation comes before repeating
+    pub fn follow_up(&self, routing: &AlertRouting, now: DateTime<Utc>) -> Option<FollowUp> {
+        if !self.is_open() {
+            return None;
+        }
+        let policy = routing.policy(self.severity);
+        let elapsed_secs = |since: DateTime<Utc>| (now - since).num_seconds().max(0) as u64;
+
+        let at_severity_since = self.escalated_at.unwrap_or(self.raised_at);
+        if let Some(after) = policy.escalate_after_secs {
+            let next = self.severity.escalated();
+            if next != self.severity && elapsed_secs(at_severity_since) >= after {
+                return Some(FollowUp::Escalate(next));
+            }
+        }
+        policy
+            .repeat_secs
+            .filter(|every| {
+                self.repeats < policy.max_repeats && elapsed_secs(self.last_sent_at) >= *every
+            })
+            .map(|_| FollowUp::Repeat)
+    }
+
+    /// Apply a follow-up sent at `now`
+    pub fn apply(&mut self, follow_up: FollowUp, now: DateTime<Utc>) {
+        match follow_up {
+            FollowUp::Repeat => self.repeats += 1,
+            FollowUp::Escalate(severity) => {
+                self.severity = severity;
+                self.escalated_at = Some(now);
+                self.repeats = 0;
+            }
+        }
+        self.last_sent_at = now;
+    }
+
+    /// Message as delivered, e.g. "⚠️ WARNING: ... (escalated from info, repeat 2)"
+    pub fn text(&self) -> String {
+        let mut notes = Vec::new();
+        if self.severity != self.raised_severity {
+            notes.push(format!("escalated from {}", self.raised_severity));
+        }
+        if self.repeats > 0 {
+            notes.push(format!("repeat {}", self.repeats));
+        }
+        let mut text = format!(
+            "{} {}: {}",
+            self.severity.icon(),
+            self.severity.to_string().to_uppercase(),
+            self.message
+        );
+        if !notes.is_empty() {
+            text.push_str(&format!(" ({})", notes.join(", ")));
+        }
+        text
+    }
+}
+
+/// Alerts kept as JSON in the data directory, newest first
+pub struct AlertStore {
+    path: PathBuf,
+}
+
+impl AlertStore {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            path: data_paths.data().join(ALERTS_FILE),
+        }
+    }
+
+    /// Lock file held while `alerts.json` is read, changed and written back
+    pub fn lock_path(&self) -> PathBuf {
+        self.path.with_extension("json.lock")
+    }
+
+    pub fn load(&self) -> Result<Vec<Alert>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", self.path.display()))
+    }
+
+    /// Save, keeping every open alert and the newest acknowledged ones; callers
+    /// changing loaded alerts hold the [`lock_path`](Self::lock_path) lock
+    pub fn save(&self, alerts: &[Alert]) -> Result<()> {
+        let mut acknowledged = 0;
+        let kept: Vec<&Alert> = alerts
+            .iter()
+            .filter(|alert| {
+                if alert.is_open() {
+                    return true;
+                }
+                acknowledged += 1;
+                acknowledged <= ACKNOWLEDGED_KEPT
+            })
+            .collect();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&kept)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Acknowledge the open alert whose id starts with `id`, or every open alert
+    /// when `id` is `None`; returns the alerts acknowledged
+    pub fn acknowledge(
+        &self,
+        id: Option<&str>,
+        by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Alert>> {
+        let lock = run_blocking(|| FileLock::acquire_blocking(&self.lock_path(), LOCK_TIMEOUT))?;
+        let acknowledged = self.acknowledge_locked(id, by, now);
+        drop(lock);
+        acknowledged
+    }
+
+    fn acknowledge_locked(
+        &self,
+        id: Option<&str>,
+        by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Alert>> {
+        let mut alerts = self.load()?;
+        let mut acknowledged = Vec::new();
+        for alert in alerts.iter_mut().filter(|alert| alert.is_open()) {
+            if id.is_some_and(|id| !alert.id.starts_with(id)) {
+                continue;
+            }
+            alert.acknowledged_at = Some(now);
+            alert.acknowledged_by = Some(by.to_string());
+            acknowledged.push(alert.clone());
+        }
+        if let (Some(id), true) = (id, acknowledged.len() > 1) {
+            bail!(
+                "'{}' matches {} open alerts, give more of the id",
+                id,
+                acknowledged.len()
+            );
+        }
+        if !acknowledged.is_empty() {
+            self.save(&alerts)?;
+        }
+        Ok(acknowledged)
+    }
+}
+
+/// Records alerts and delivers them to the channels of their severity
+pub struct AlertRouter {
+    routing: AlertRouting,
+    store: AlertStore,
+    client: reqwest::Client,
+}
+
+impl AlertRouter {
+    pub fn new(data_paths: &DataPaths) -> Result<Self> {
+        Ok(Self {
+            routing: AlertRouting::load(data_paths)?,
+            store: AlertStore::new(data_paths),
+            client: reqwest::Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                .build()?,
+        })
+    }
+
+    pub fn routing(&self) -> &AlertRouting {
+        &self.routing
+    }
+
+    /// Record an alert and deliver it to the remote channels of its severity;
+    /// showing the toast is up to the caller (see `AlertRouting::toasts`)
+    pub async fn raise(
+        &self,
+        severity: Severity,
+        source: &str,
+        message: String,
+        token_id: Option<String>,
+    ) -> Result<Alert> {
+        let alert = Alert::new(severity, source, message, token_id, Utc::now());
+        let lock = FileLock::acquire(&self.store.lock_path(), LOCK_TIMEOUT).await?;
+        let saved = self.store.load().and_then(|mut alerts| {
+            alerts.insert(0, alert.clone());
+            self.store.save(&alerts)
+        });
+        drop(lock);
+        saved?;
+        self.deliver(&alert).await;
+        Ok(alert)
+    }
+
+    /// Re-send and escalate the open alerts that are due; returns them as sent
+    pub async fn follow_up(&self, now: DateTime<Utc>) -> Result<Vec<Alert>> {
+        let lock = FileLock::acquire(&self.store.lock_path(), LOCK_TIMEOUT).await?;
+        let due = self.apply_follow_ups(now);
+        drop(lock);
+        let due = due?;
+        for alert in &due {
+            self.deliver(alert).await;
+        }
+        Ok(due)
+    }
+
+    /// Apply the follow-ups due at `now` and save them; the caller holds the store lock
+    fn apply_follow_ups(&self, now: DateTime<Utc>) -> Result<Vec<Alert>> {
+        // Read under the lock, so an alert acknowledged by another process
+        // since the last follow-up is closed here and stays acknowledged
+        let mut alerts = self.store.load()?;
+        let mut due = Vec::new();
+        for alert in alerts.iter_mut().filter(|alert| alert.is_open()) {
+            if let Some(follow_up) = alert.follow_up(&self.routing, now) {
+                alert.apply(follow_up, now);
+                due.push(alert.clone());
+            }
+        }
+        if !due.is_empty() {
+            self.store.save(&alerts)?;
+        }
+        Ok(due)
+    }
+
+    /// Send to every remote channel of the alert's severity; failures are logged
+    async fn deliver(&self, alert: &Alert) {
+        for channel in &self.routing.policy(alert.severity).channels {
+            let sent = match channel {
+                AlertChannel::Toast => continue,
+                AlertChannel::Telegram => self.send_telegram(alert).await,
+                AlertChannel::Webhook => self.send_webhook(alert).await,
+                AlertChannel::Phone => self.call_phone(alert).await,
+            };
+            match sent {
+                Ok(true) => debug!("Alert {} sent to {}", alert.id, channel),
+                Ok(false) => warn!(
+                    "Alert {} not sent to {}: not set up in {}",
+                    alert.id, channel, ALERTS_CONFIG_FILE
+                ),
+                Err(e) => warn!("Alert {} not sent to {}: {:#}", alert.id, channel, e),
+            }
+        }
+    }
+
+    async fn send_telegram(&self, alert: &Alert) -> Result<bool> {
+        let Some(telegram) = &self.routing.telegram else {
+            return Ok(false);
+        };
+        let url = format!(
+            "https://api.telegram.org/bot{}/sendMessage",
+            telegram.bot_token
+        );
+        let body = serde_json::json!({ "chat_id": telegram.chat_id, "text": alert.text() });
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            bail!("Telegram answered {}", response.status());
+        }
+        Ok(true)
+    }
+
+    async fn send_webhook(&self, alert: &Alert) -> Result<bool> {
+        let Some(webhook) = &self.routing.webhook else {
+            return Ok(false);
+        };
+        // `text` is what Slack-style incoming webhooks display
+        let body = serde_json::json!({ "text": alert.text(), "alert": alert });
+        let response = self
+            .client
+            .post(&webhook.url)
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Webhook {} unreachable", webhook.url))?;
+        if !response.status().is_success() {
+            bail!("Webhook {} answered {}", webhook.url, response.status());
+        }
+        Ok(true)
+    }
+
+    async fn call_phone(&self, alert: &Alert) -> Result<bool> {
+        let Some(twilio) = &self.routing.twilio else {
+            return Ok(false);
+        };
+        let url = format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{}/Calls.json",
+            twilio.account_sid
+        );
+        let twiml = format!(
+            "<Response><Say>Polybot {} alert. {}</Say></Response>",
+            alert.severity,
+            xml_escape(&alert.message)
+        );
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(&twilio.account_sid, Some(&twilio.auth_token))
+            .form(&[
+                ("To", twilio.to.as_str()),
+                ("From", twilio.from.as_str()),
+                ("Twiml", twiml.as_str()),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!("Twilio answered {}", response.status());
+        }
+        Ok(true)
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn routing_with_credentials_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let path = dir.path().join(ALERTS_CONFIG_FILE);
+        std::fs::write(&path, "webhook:\n  url: https://hooks.example.com/T0/B0/x\n").unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(AlertRouting::load(&data_paths).is_err());
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(AlertRouting::load(&data_paths).unwrap().webhook.is_some());
+
+        // Routing alone holds nothing secret
+        std::fs::write(&path, "info:\n  channels: [toast]\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(AlertRouting::load(&data_paths).is_ok());
+    }
+
+    #[test]
+    fn open_alerts_repeat_then_escalate_until_acknowledged() {
+        let routing = AlertRouting {
+            warning: RoutePolicy {
+                channels: vec![AlertChannel::Telegram],
+                repeat_secs: Some(60),
+                max_repeats: 2,
+                escalate_after_secs: Some(600),
+            },
+            ..AlertRouting::default()
+        };
+        let start = Utc::now();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+        let mut alert = Alert::new(
+            Severity::Warning,
+            "test",
+            "Spread out".to_string(),
+            None,
+            start,
+        );
+
+        assert_eq!(alert.follow_up(&routing, at(30)), None);
+        assert_eq!(alert.follow_up(&routing, at(60)), Some(FollowUp::Repeat));
+        alert.apply(FollowUp::Repeat, at(60));
+        alert.apply(FollowUp::Repeat, at(120));
+        // Out of repeats until it escalates
+        assert_eq!(alert.follow_up(&routing, at(500)), None);
+        assert_eq!(
+            alert.follow_up(&routing, at(600)),
+            Some(FollowUp::Escalate(Severity::Critical))
+        );
+        alert.apply(FollowUp::Escalate(Severity::Critical), at(600));
+        assert_eq!(alert.repeats, 0);
+        assert!(alert.text().contains("escalated from warning"));
+        // Critical repeats on its own policy and goes no higher
+        assert_eq!(alert.follow_up(&routing, at(700)), None);
+        assert_eq!(alert.follow_up(&routing, at(900)), Some(FollowUp::Repeat));
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = AlertStore::new(&DataPaths::new(dir.path()));
+        let other = Alert::new(Severity::Info, "test", "Filled".to_string(), None, start);
+        store.save(&[alert.clone(), other.clone()]).unwrap();
+        let acknowledged = store.acknowledge(Some(&alert.id), "cli", at(901)).unwrap();
+        assert_eq!(acknowledged.len(), 1);
+        assert!(FileLock::try_acquire(&store.lock_path()).unwrap().is_some());
+        let alerts = store.load().unwrap();
+        assert_eq!(alerts[0].follow_up(&routing, at(2000)), None);
+        assert!(alerts[1].is_open());
+        assert_eq!(store.acknowledge(None, "gui", at(902)).unwrap().len(), 1);
+        assert!(store.load().unwrap().iter().all(|alert| !alert.is_open()));
+
+        let partial: AlertRouting =
+            serde_yaml::from_str("info:\n  channels: [toast, webhook]\n").unwrap();
+        assert_eq!(
+            partial.info.channels,
+            vec![AlertChannel::Toast, AlertChannel::Webhook]
+        );
+        assert_eq!(partial.critical, AlertRouting::default().critical);
+    }
+}
@@ -0,0 +1,564 @@
+//! Append-only, hash-chained audit log of mutating actions
+//!
+//! Every order placement, cancel, on-chain transaction, credential change and
+//! config change is appended to `<data_dir>/audit/audit.jsonl` together with
+//! who initiated it (the CLI invocation, the GUI, the TUI or a strategy).
+//! Each entry stores the hash of the previous one and its own SHA-256 over
+//! its contents, so editing, reordering or deleting an entry breaks the chain
+//! and `polybot audit verify` reports where. Truncating the tail is only
+//! detectable against a head hash noted elsewhere (`audit verify --head`).
+//!
+//! Entries carry the id of the session that wrote them, so a command's own
+//! actions can be told apart from those of processes running alongside it.
+//!
+//! The log is opened once per process with [`init`]; [`record`] is a no-op
+//! before that, so library code and tests can call it unconditionally.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use tracing::warn;
+
+use crate::core::file_lock::{run_blocking, FileLock, LOCK_TIMEOUT};
+use crate::data_paths::DataPaths;
+
+const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit.jsonl";
+const LOCK_FILE: &str = "audit.lock";
+const CONFIG_HASHES_FILE: &str = "config_hashes.json";
+
+/// Hash the first entry chains to
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Bytes read from the end of the log per step while looking for the last entry
+const TAIL_CHUNK: u64 = 4096;
+
+/// Command line options whose value must never reach the log
+const SECRET_FLAGS: &[&str] = &[
+    "--pk",
+    "--private-key",
+    "--passphrase",
+    "--api-key",
+    "--secret",
+    "--api-secret",
+];
+
+/// Any option whose name contains one of these is treated as a secret too
+const SECRET_FLAG_PARTS: &[&str] = &["key", "secret", "pass", "pk"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    OrderPlaced,
+    OrderCancelled,
+    /// On-chain transaction (approval, redemption, replacement, ...)
+    Transaction,
+    CredentialsChanged,
+    ConfigChanged,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::OrderPlaced => "order_placed",
+            Self::OrderCancelled => "order_cancelled",
+            Self::Transaction => "transaction",
+            Self::CredentialsChanged => "credentials_changed",
+            Self::ConfigChanged => "config_changed",
+        };
+        f.pad(name)
+    }
+}
+
+/// What initiated an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum AuditContext {
+    /// Command line invocation, secrets redacted
+    Cli { args: Vec<String> },
+    Gui,
+    Tui,
+    Strategy { name: String },
+    Unknown,
+}
+
+impl AuditContext {
+    /// A command line (program name first), as parsed after alias expansion
+    pub fn cli(args: Vec<String>) -> Self {
+        Self::Cli {
+            args: redact_args(args.into_iter().skip(1)),
+        }
+    }
+}
+
+impl std::fmt::Display for AuditContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cli { args } => write!(f, "cli: {}", args.join(" ")),
+            Self::Gui => f.write_str("gui"),
+            Self::Tui => f.write_str("tui"),
+            Self::Strategy { name } => write!(f, "strategy: {}", name),
+            Self::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+fn is_secret_flag(arg: &str) -> bool {
+    if !arg.starts_with("--") {
+        return false;
+    }
+    if SECRET_FLAGS.contains(&arg) {
+        return true;
+    }
+    let name = arg.trim_start_matches('-').to_ascii_lowercase();
+    SECRET_FLAG_PARTS.iter().any(|part| name.contains(part))
+}
+
+fn redact_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut redact_next = false;
+    args.map(|arg| {
+        if std::mem::take(&mut redact_next) {
+            return "<redacted>".to_string();
+        }
+        if let Some((flag, _)) = arg.split_once('=') {
+            if is_secret_flag(flag) {
+                return format!("{}=<redacted>", flag);
+            }
+            return arg;
+        }
+        redact_next = is_secret_flag(&arg);
+        arg
+    })
+    .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    pub context: AuditContext,
+    /// Session of the process that appended the entry (see `core::session`);
+    /// absent from entries written before sessions existed, keeping their hashes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// One line description
+    pub summary: String,
+    #[serde(default)]
+    pub details: Value,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// SHA-256 over the entry with an empty `hash` field
+    pub fn compute_hash(&self) -> String {
+        let mut unhashed = self.clone();
+        unhashed.hash = String::new();
+        let bytes = serde_json::to_vec(&unhashed).unwrap_or_default();
+        hex::encode(Sha256::digest(&bytes))
+    }
+}
+
+/// First link of the chain that failed to verify
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    /// Sequence number of the entry, or its line when it didn't parse
+    pub seq: u64,
+    pub problem: String,
+}
+
+/// Result of walking the chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyReport {
+    pub entries: usize,
+    pub head: String,
+    pub broken: Option<BrokenLink>,
+    /// Whether the expected head hash was found, when one was given
+    pub head_found: Option<bool>,
+}
+
+impl VerifyReport {
+    pub fn is_valid(&self) -> bool {
+        self.broken.is_none() && self.head_found != Some(false)
+    }
+}
+
+pub struct AuditLog {
+    dir: PathBuf,
+    /// Serializes appends within the process; the lock file covers other processes
+    append_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self::at(data_paths.root().join(AUDIT_DIR))
+    }
+
+    pub fn at(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            append_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(AUDIT_FILE)
+    }
+
+    /// Append an entry chained to the current head
+    pub fn append(
+        &self,
+        action: AuditAction,
+        context: AuditContext,
+        summary: impl Into<String>,
+        details: Value,
+        outcome: std::result::Result<(), String>,
+    ) -> Result<AuditEntry> {
+        let _guard = self.append_lock.lock().map_err(|_| anyhow!("Audit log lock poisoned"))?;
+        std::fs::create_dir_all(&self.dir)?;
+        let _file_lock = FileLock::acquire_blocking(&self.dir.join(LOCK_FILE), LOCK_TIMEOUT)?;
+
+        let last = self.last_entry()?;
+        let mut entry = AuditEntry {
+            seq: last.as_ref().map(|e| e.seq + 1).unwrap_or(0),
+            timestamp: Utc::now(),
+            action,
+            context,
+            session: crate::core::session::current_id(),
+            summary: summary.into(),
+            details,
+            success: outcome.is_ok(),
+            error: outcome.err(),
+            prev_hash: last.map(|e| e.hash).unwrap_or_else(|| GENESIS_HASH.to_string()),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())
+            .with_context(|| format!("Failed to open {}", self.path().display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.sync_data()?;
+        crate::core::session::record_action(&entry.action);
+        Ok(entry)
+    }
+
+    /// Last entry, read from the end of the file so appends stay cheap as the log grows
+    fn last_entry(&self) -> Result<Option<AuditEntry>> {
+        let path = self.path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut file = File::open(&path)?;
+        let len = file.metadata()?.len();
+        let mut read_from = len;
+        let mut tail = Vec::new();
+        loop {
+            read_from = read_from.saturating_sub(TAIL_CHUNK);
+            file.seek(SeekFrom::Start(read_from))?;
+            tail.clear();
+            (&mut file).take(len - read_from).read_to_end(&mut tail)?;
+            let content = String::from_utf8_lossy(&tail);
+            let mut lines = content.lines().rev().filter(|line| !line.trim().is_empty());
+            let last = lines.next();
+            // A line is complete once another line precedes it or the file start is reached
+            if let Some(line) = last.filter(|_| lines.next().is_some() || read_from == 0) {
+                return serde_json::from_str(line)
+                    .map(Some)
+                    .context("Last audit entry is corrupt; run 'polybot audit verify'");
+            }
+            if read_from == 0 {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let path = self.path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| format!("Unreadable audit entry on line {}", i + 1))
+            })
+            .collect()
+    }
+
+    /// Walk the chain, checking every hash and link
+    pub fn verify(&self, expected_head: Option<&str>) -> Result<VerifyReport> {
+        let path = self.path();
+        let content = if path.exists() {
+            std::fs::read_to_string(&path)?
+        } else {
+            String::new()
+        };
+
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut entries = 0;
+        let mut broken = None;
+        let mut head_found = expected_head.map(|_| false);
+        for (line_no, line) in content.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+            let entry: AuditEntry = match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    broken = Some(BrokenLink {
+                        seq: line_no as u64,
+                        problem: format!("line {} is not a valid entry: {}", line_no + 1, e),
+                    });
+                    break;
+                }
+            };
+            let problem = if entry.seq != entries as u64 {
+                Some(format!("expected seq {}, found {}", entries, entry.seq))
+            } else if entry.prev_hash != prev_hash {
+                Some("previous hash does not match the preceding entry".to_string())
+            } else if entry.compute_hash() != entry.hash {
+                Some("contents do not match the entry hash".to_string())
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                broken = Some(BrokenLink { seq: entry.seq, problem });
+                break;
+            }
+            if expected_head == Some(entry.hash.as_str()) {
+                head_found = Some(true);
+            }
+            prev_hash = entry.hash;
+            entries += 1;
+        }
+
+        Ok(VerifyReport {
+            entries,
+            head: prev_hash,
+            broken,
+            head_found,
+        })
+    }
+
+    /// Record a config change when `path`'s contents differ from the last time it was seen
+    pub fn track_config(&self, context: AuditContext, path: &Path, content: &[u8]) -> Result<()> {
+        let hashes_path = self.dir.join(CONFIG_HASHES_FILE);
+        let mut hashes: HashMap<String, String> = std::fs::read_to_string(&hashes_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+
+        let key = path.display().to_string();
+        let hash = hex::encode(Sha256::digest(content));
+        let previous = hashes.get(&key).cloned();
+        if previous.as_deref() == Some(hash.as_str()) {
+            return Ok(());
+        }
+        self.append(
+            AuditAction::ConfigChanged,
+            context,
+            format!("{} changed", path.file_name().and_then(|n| n.to_str()).unwrap_or(&key)),
+            serde_json::json!({ "file": key, "previous_sha256": previous, "sha256": hash }),
+            Ok(()),
+        )?;
+        hashes.insert(key, hash);
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(hashes_path, serde_json::to_string_pretty(&hashes)?)?;
+        Ok(())
+    }
+}
+
+static AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+static CONTEXT: RwLock<Option<AuditContext>> = RwLock::new(None);
+
+/// Open the process's audit log under `data_paths`
+pub fn init(data_paths: &DataPaths, context: AuditContext) {
+    if AUDIT_LOG.set(AuditLog::new(data_paths)).is_err() {
+        warn!("Audit log already initialized");
+    }
+    set_context(context);
+}
+
+/// Attribute subsequent actions to `context` (e.g. when a GUI or strategy takes over)
+pub fn set_context(context: AuditContext) {
+    if let Ok(mut current) = CONTEXT.write() {
+        *current = Some(context);
+    }
+}
+
+pub fn context() -> AuditContext {
+    CONTEXT
+        .read()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or(AuditContext::Unknown)
+}
+
+/// Append to the process's audit log. Failures are logged, never returned:
+/// an unwritable log must not stop a cancel from going out. Waiting for
+/// another process's lock doesn't stall the async runtime
+pub fn record(action: AuditAction, summary: impl Into<String>, details: Value, outcome: std::result::Result<(), String>) {
+    let Some(log) = AUDIT_LOG.get() else {
+        return;
+    };
+    let summary = summary.into();
+    if let Err(e) = run_blocking(|| log.append(action, context(), summary, details, outcome)) {
+        warn!("Failed to write audit log: {}", e);
+    }
+}
+
+/// Record a config file change in the process's audit log, if it changed
+pub fn track_config(path: &Path, content: &[u8]) {
+    let Some(log) = AUDIT_LOG.get() else {
+        return;
+    };
+    if let Err(e) = run_blocking(|| log.track_config(context(), path, content)) {
+        warn!("Failed to write audit log: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn log_with_entries(dir: &Path, count: usize) -> AuditLog {
+        let log = AuditLog::at(dir);
+        for i in 0..count {
+            log.append(
+                AuditAction::OrderPlaced,
+                AuditContext::Gui,
+                format!("order {}", i),
+                json!({ "price": "0.5", "size": i }),
+                Ok(()),
+            )
+            .unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn chain_verifies_and_detects_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = log_with_entries(dir.path(), 3);
+        let report = log.verify(None).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.entries, 3);
+        let head = report.head.clone();
+        assert!(log.verify(Some(&head)).unwrap().is_valid());
+
+        let content = std::fs::read_to_string(log.path()).unwrap();
+        std::fs::write(log.path(), content.replacen("order 1", "order 9", 1)).unwrap();
+        let report = log.verify(None).unwrap();
+        assert_eq!(report.broken.unwrap().seq, 1);
+
+        // Dropping the tail keeps the chain consistent but loses the known head
+        let lines: Vec<&str> = content.lines().collect();
+        std::fs::write(log.path(), format!("{}\n{}\n", lines[0], lines[1])).unwrap();
+        let report = log.verify(Some(&head)).unwrap();
+        assert!(report.broken.is_none());
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn appends_chain_to_the_tail_of_a_large_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(dir.path());
+        let padding = "x".repeat(TAIL_CHUNK as usize);
+        for i in 0..3 {
+            log.append(
+                AuditAction::Transaction,
+                AuditContext::Unknown,
+                format!("tx {}", i),
+                json!({ "padding": padding }),
+                Ok(()),
+            )
+            .unwrap();
+        }
+        let report = log.verify(None).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.entries, 3);
+        assert_eq!(log.last_entry().unwrap().unwrap().seq, 2);
+    }
+
+    #[test]
+    fn config_changes_are_recorded_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(dir.path());
+        let path = Path::new("risk.yaml");
+        log.track_config(AuditContext::Unknown, path, b"a: 1").unwrap();
+        log.track_config(AuditContext::Unknown, path, b"a: 1").unwrap();
+        log.track_config(AuditContext::Unknown, path, b"a: 2").unwrap();
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].action, AuditAction::ConfigChanged);
+        assert!(entries[1].details["previous_sha256"].is_string());
+    }
+
+    #[test]
+    fn secrets_are_redacted_from_the_command_line() {
+        let args = ["init", "--pk", "deadbeef", "--nonce", "1", "--pk=cafe"].map(String::from);
+        assert_eq!(
+            redact_args(args.into_iter()),
+            vec!["init", "--pk", "<redacted>", "--nonce", "1", "--pk=<redacted>"]
+        );
+    }
+
+    #[test]
+    fn daemon_credentials_are_redacted() {
+        let args = [
+            "daemon",
+            "--assets",
+            "123",
+            "--api-key",
+            "key-value",
+            "--secret",
+            "secret-value",
+            "--passphrase",
+            "pass-value",
+            "--api-key=key-value",
+            "--secret=secret-value",
+            "--api-secret=secret-value",
+            "--passphrase=pass-value",
+            "--heartbeat-interval",
+            "10",
+        ]
+        .map(String::from);
+        let redacted = redact_args(args.into_iter());
+        assert!(!redacted.iter().any(|arg| arg.contains("-value")));
+        assert_eq!(
+            redacted,
+            vec![
+                "daemon",
+                "--assets",
+                "123",
+                "--api-key",
+                "<redacted>",
+                "--secret",
+                "<redacted>",
+                "--passphrase",
+                "<redacted>",
+                "--api-key=<redacted>",
+                "--secret=<redacted>",
+                "--api-secret=<redacted>",
+                "--passphrase=<redacted>",
+                "--heartbeat-interval",
+                "10",
+            ]
+        );
+    }
+}
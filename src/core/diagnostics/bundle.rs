@@ -0,0 +1,288 @@
+//! Debug bundle: a zip of everything useful for a bug report
+//!
+//! Layout (entries are always written in this order, with the bundle's
+//! creation time as their timestamp):
+//! - `manifest.json`: version, platform, profile and what initiated the dump
+//! - `session.json`: streaming stats, worker states, portfolio and layout of
+//!   the live or last recorded session
+//! - `events.jsonl`: the session's recent events ring, oldest first
+//! - `portfolio/<address>.json`: latest stored portfolio snapshot per account
+//! - `config/<file>.json`: profile config files with secret values redacted
+//! - `logs/<file>`: tails of the most recent log files
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::session::SessionState;
+use super::DIAGNOSTICS_DIR;
+use crate::core::audit;
+use crate::core::portfolio::PortfolioStorage;
+use crate::data_paths::DataPaths;
+
+/// Log files included, newest first
+const LOG_FILES: usize = 3;
+const LOG_TAIL_LINES: usize = 2000;
+/// Config files larger than this are left out
+const MAX_CONFIG_BYTES: u64 = 1024 * 1024;
+const CONFIG_EXTENSIONS: &[&str] = &["yaml", "yml", "json", "toml"];
+
+/// Key fragments whose values are replaced before anything leaves the machine;
+/// webhook and RPC URLs often carry their credential in the path or query
+const SECRET_KEY_FRAGMENTS: &[&str] = &[
+    "key", "secret", "passphrase", "password", "private", "mnemonic", "seed", "_token", "url",
+];
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Manifest {
+    pub created_at: DateTime<Utc>,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub profile: String,
+    /// What produced the bundle (CLI invocation, `gui`, ...)
+    pub initiated_by: String,
+    /// `live` when the session state was captured in-process, `recorded`
+    /// when read from the last session recording, absent when there was none
+    pub session: Option<String>,
+}
+
+pub struct DebugBundle {
+    pub manifest: Manifest,
+    pub session: Option<SessionState>,
+    pub portfolio: BTreeMap<String, Value>,
+    pub config: BTreeMap<String, Value>,
+    pub logs: BTreeMap<String, String>,
+}
+
+impl DebugBundle {
+    /// Gather a bundle for the profile
+    ///
+    /// `live` is the calling session's own state; without it the last
+    /// recorded session of the profile is used.
+    pub async fn collect(data_paths: &DataPaths, live: Option<SessionState>, include_logs: bool) -> Result<Self> {
+        let (session, session_kind) = match live {
+            Some(state) => (Some(state), Some("live")),
+            None => {
+                let recorded = SessionState::load_recorded(data_paths)?.into_iter().next();
+                let kind = recorded.as_ref().map(|_| "recorded");
+                (recorded, kind)
+            }
+        };
+
+        let manifest = Manifest {
+            created_at: Utc::now(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            profile: data_paths.profile().to_string(),
+            initiated_by: audit::context().to_string(),
+            session: session_kind.map(str::to_string),
+        };
+
+        Ok(Self {
+            manifest,
+            session,
+            portfolio: load_portfolio_snapshots(data_paths).await,
+            config: load_config(data_paths),
+            logs: if include_logs { load_log_tails(data_paths) } else { BTreeMap::new() },
+        })
+    }
+
+    /// `<data_dir>/diagnostics/polybot-debug-<timestamp>.zip`
+    pub fn default_path(&self, data_paths: &DataPaths) -> PathBuf {
+        data_paths.root().join(DIAGNOSTICS_DIR).join(format!(
+            "polybot-debug-{}.zip",
+            self.manifest.created_at.format("%Y%m%d_%H%M%S")
+        ))
+    }
+
+    pub fn write_zip(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut zip = ZipWriter::new(file);
+        let created = self.manifest.created_at;
+        let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        if let Ok(time) = zip::DateTime::from_date_and_time(
+            created.year() as u16,
+            created.month() as u8,
+            created.day() as u8,
+            created.hour() as u8,
+            created.minute() as u8,
+            created.second() as u8,
+        ) {
+            options = options.last_modified_time(time);
+        }
+
+        let mut add = |name: &str, bytes: &[u8]| -> Result<()> {
+            zip.start_file(name, options)?;
+            zip.write_all(bytes)?;
+            Ok(())
+        };
+
+        add("manifest.json", &serde_json::to_vec_pretty(&self.manifest)?)?;
+        if let Some(session) = &self.session {
+            add("session.json", &serde_json::to_vec_pretty(session)?)?;
+            let mut events = Vec::new();
+            for event in &session.events {
+                serde_json::to_writer(&mut events, event)?;
+                events.push(b'\n');
+            }
+            add("events.jsonl", &events)?;
+        }
+        for (address, snapshot) in &self.portfolio {
+            add(&format!("portfolio/{}.json", address), &serde_json::to_vec_pretty(snapshot)?)?;
+        }
+        for (name, config) in &self.config {
+            add(&format!("config/{}.json", name), &serde_json::to_vec_pretty(config)?)?;
+        }
+        for (name, tail) in &self.logs {
+            add(&format!("logs/{}", name), tail.as_bytes())?;
+        }
+
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+/// Replace the values of secret-looking keys, at any depth
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEY_FRAGMENTS.iter().any(|fragment| key.contains(fragment)) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+async fn load_portfolio_snapshots(data_paths: &DataPaths) -> BTreeMap<String, Value> {
+    let mut snapshots = BTreeMap::new();
+    let Ok(accounts) = std::fs::read_dir(data_paths.root().join("trade").join("account")) else {
+        return snapshots;
+    };
+    for account in accounts.flatten() {
+        let address = account.file_name().to_string_lossy().to_string();
+        let storage = PortfolioStorage::new(data_paths.root(), &address);
+        if let Ok(Some(snapshot)) = storage.load_latest_snapshot().await {
+            if let Ok(value) = serde_json::to_value(&snapshot) {
+                snapshots.insert(address, value);
+            }
+        }
+    }
+    snapshots
+}
+
+/// Config files at the top of the profile tree plus the signer selection;
+/// encrypted credential files are never read
+fn load_config(data_paths: &DataPaths) -> BTreeMap<String, Value> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(data_paths.root())
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.push(data_paths.auth().join("signer.json"));
+
+    let mut config = BTreeMap::new();
+    for path in files {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        if !CONFIG_EXTENSIONS.contains(&extension)
+            || !path.metadata().is_ok_and(|m| m.is_file() && m.len() <= MAX_CONFIG_BYTES)
+        {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut value = match extension {
+            "json" => serde_json::from_str(&content).ok(),
+            "toml" => None,
+            _ => serde_yaml::from_str::<Value>(&content).ok(),
+        }
+        .unwrap_or_else(|| Value::String("[not parsed]".to_string()));
+        redact(&mut value);
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        config.insert(name, value);
+    }
+    config
+}
+
+fn load_log_tails(data_paths: &DataPaths) -> BTreeMap<String, String> {
+    let mut logs: Vec<PathBuf> = std::fs::read_dir(data_paths.logs())
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    logs.retain(|path| path.extension().is_some_and(|e| e == "log"));
+    // Session ids are timestamps, so names sort chronologically
+    logs.sort();
+
+    logs.iter()
+        .rev()
+        .take(LOG_FILES)
+        .filter_map(|path| {
+            let content = std::fs::read(path).ok()?;
+            let content = String::from_utf8_lossy(&content);
+            let lines: Vec<&str> = content.lines().collect();
+            let tail = lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n");
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((name, tail))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secret_values_at_any_depth() {
+        let mut value = serde_json::json!({
+            "max_order_size": 100,
+            "api": { "api_key": "abc", "secret": "def", "host": "clob" },
+            "accounts": [{ "private_key": "0x01", "address": "0x02" }],
+            "telegram": { "bot_token": "123:abc", "chat_id": "42" },
+            "webhook": { "url": "https://hooks.slack.com/services/T0/B0/x" },
+        });
+        redact(&mut value);
+
+        assert_eq!(value["max_order_size"], 100);
+        assert_eq!(value["api"]["api_key"], REDACTED);
+        assert_eq!(value["api"]["secret"], REDACTED);
+        assert_eq!(value["api"]["host"], "clob");
+        assert_eq!(value["accounts"][0]["private_key"], REDACTED);
+        assert_eq!(value["accounts"][0]["address"], "0x02");
+        assert_eq!(value["telegram"]["bot_token"], REDACTED);
+        assert_eq!(value["telegram"]["chat_id"], "42");
+        assert_eq!(value["webhook"]["url"], REDACTED);
+    }
+
+    #[test]
+    fn config_collection_skips_credentials_and_redacts_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+        std::fs::write(dir.path().join("risk.yaml"), "max_order_usd: 50\nwebhook_secret: hunter2\n").unwrap();
+        std::fs::write(data_paths.auth().join("creds.enc"), "ciphertext").unwrap();
+
+        let config = load_config(&data_paths);
+        assert_eq!(config.keys().collect::<Vec<_>>(), vec!["risk.yaml"]);
+        assert_eq!(config["risk.yaml"]["max_order_usd"], 50);
+        assert_eq!(config["risk.yaml"]["webhook_secret"], REDACTED);
+    }
+}
@@ -0,0 +1,262 @@
+//! Bounded ring buffers of recent events, one per subsystem
+//!
+//! Recording is cheap enough to leave on permanently: each subsystem keeps
+//! only its last N events in memory behind its own lock, with N set per
+//! subsystem in the `event_buffers` section of `diagnostics.yaml` (0 turns a
+//! subsystem off). Every received WebSocket message is only recorded when
+//! `ws_messages` is set, since that path runs for each book update.
+//! Long-running sessions write the buffers to disk with their session
+//! recording, where `polybot debug events` reads them.
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Subsystems with their own event buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    /// WebSocket connections and received messages
+    Ws,
+    /// Order API calls (placements, cancels, listings)
+    Orders,
+    /// Strategy signals (proposals, approvals, market alerts)
+    Strategy,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [Subsystem::Ws, Subsystem::Orders, Subsystem::Strategy];
+
+    fn index(self) -> usize {
+        match self {
+            Self::Ws => 0,
+            Self::Orders => 1,
+            Self::Strategy => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Ws => "ws",
+            Self::Orders => "orders",
+            Self::Strategy => "strategy",
+        };
+        f.pad(name)
+    }
+}
+
+/// Buffer capacity per subsystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventBufferConfig {
+    pub ws: usize,
+    pub orders: usize,
+    pub strategy: usize,
+    /// Also record every received WebSocket message, not just connection changes
+    pub ws_messages: bool,
+}
+
+impl Default for EventBufferConfig {
+    fn default() -> Self {
+        Self {
+            ws: 1000,
+            orders: 500,
+            strategy: 500,
+            ws_messages: false,
+        }
+    }
+}
+
+impl EventBufferConfig {
+    pub fn capacity(&self, subsystem: Subsystem) -> usize {
+        match subsystem {
+            Subsystem::Ws => self.ws,
+            Subsystem::Orders => self.orders,
+            Subsystem::Strategy => self.strategy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentEvent {
+    pub timestamp: DateTime<Utc>,
+    pub subsystem: Subsystem,
+    pub message: String,
+}
+
+/// Fixed-capacity FIFO; pushing into a full ring evicts the oldest event
+#[derive(Debug)]
+pub struct EventRing {
+    capacity: usize,
+    events: VecDeque<RecentEvent>,
+}
+
+impl EventRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, dropping the oldest events that no longer fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.events.len() > capacity {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, event: RecentEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Events from oldest to newest
+    pub fn to_vec(&self) -> Vec<RecentEvent> {
+        self.events.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// One ring per subsystem, each behind its own lock, plus lock-free copies
+/// of the capacities so hot paths can check them without locking
+struct EventRings {
+    rings: [Mutex<EventRing>; 3],
+    capacities: [AtomicUsize; 3],
+    ws_messages: AtomicBool,
+}
+
+impl EventRings {
+    fn get() -> &'static EventRings {
+        static RINGS: OnceLock<EventRings> = OnceLock::new();
+        RINGS.get_or_init(|| {
+            let config = EventBufferConfig::default();
+            let capacity = |s: Subsystem| config.capacity(s);
+            EventRings {
+                rings: Subsystem::ALL.map(|s| Mutex::new(EventRing::new(capacity(s)))),
+                capacities: Subsystem::ALL.map(|s| AtomicUsize::new(capacity(s))),
+                ws_messages: AtomicBool::new(config.ws_messages),
+            }
+        })
+    }
+
+    fn ring(&self, subsystem: Subsystem) -> &Mutex<EventRing> {
+        &self.rings[subsystem.index()]
+    }
+}
+
+/// Apply buffer capacities; events already recorded are kept where they fit
+pub fn configure(config: &EventBufferConfig) {
+    let rings = EventRings::get();
+    for subsystem in Subsystem::ALL {
+        let capacity = config.capacity(subsystem);
+        rings.capacities[subsystem.index()].store(capacity, Ordering::Relaxed);
+        if let Ok(mut ring) = rings.ring(subsystem).lock() {
+            ring.set_capacity(capacity);
+        }
+    }
+    rings.ws_messages.store(config.ws_messages, Ordering::Relaxed);
+}
+
+/// Whether the subsystem's buffer keeps anything; lets hot paths skip
+/// formatting messages nobody will see
+pub fn is_enabled(subsystem: Subsystem) -> bool {
+    EventRings::get().capacities[subsystem.index()].load(Ordering::Relaxed) > 0
+}
+
+/// Whether every received WebSocket message should be recorded (opt-in)
+pub fn records_ws_messages() -> bool {
+    let rings = EventRings::get();
+    rings.ws_messages.load(Ordering::Relaxed) && is_enabled(Subsystem::Ws)
+}
+
+/// Note an event in the subsystem's process-wide buffer
+pub fn record_event(subsystem: Subsystem, message: impl Into<String>) {
+    if !is_enabled(subsystem) {
+        return;
+    }
+    let event = RecentEvent {
+        timestamp: Utc::now(),
+        subsystem,
+        message: message.into(),
+    };
+    if let Ok(mut ring) = EventRings::get().ring(subsystem).lock() {
+        ring.push(event);
+    }
+}
+
+/// Every buffered event of one subsystem, oldest first
+pub fn recent_events_for(subsystem: Subsystem) -> Vec<RecentEvent> {
+    EventRings::get()
+        .ring(subsystem)
+        .lock()
+        .map(|ring| ring.to_vec())
+        .unwrap_or_default()
+}
+
+/// Every buffered event of all subsystems, oldest first
+pub fn recent_events() -> Vec<RecentEvent> {
+    let mut events: Vec<RecentEvent> = Subsystem::ALL.into_iter().flat_map(recent_events_for).collect();
+    events.sort_by_key(|e| e.timestamp);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(message: &str) -> RecentEvent {
+        RecentEvent {
+            timestamp: Utc::now(),
+            subsystem: Subsystem::Ws,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn full_ring_evicts_oldest_events() {
+        let mut ring = EventRing::new(3);
+        for i in 0..5 {
+            ring.push(event(&format!("event {}", i)));
+        }
+
+        let messages: Vec<_> = ring.to_vec().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["event 2", "event 3", "event 4"]);
+
+        ring.set_capacity(1);
+        assert_eq!(ring.to_vec()[0].message, "event 4");
+
+        ring.set_capacity(0);
+        ring.push(event("dropped"));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn ws_messages_are_opt_in() {
+        assert!(!EventBufferConfig::default().ws_messages);
+        assert!(is_enabled(Subsystem::Orders));
+        assert!(!records_ws_messages());
+    }
+}
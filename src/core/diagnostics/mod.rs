@@ -0,0 +1,70 @@
+//! Diagnostics for bug reports
+//!
+//! Subsystems note noteworthy moments (WebSocket messages and connections,
+//! order API calls, strategy signals) in bounded per-subsystem ring buffers
+//! with [`record_event`]. Long-running sessions capture their state as a
+//! [`SessionState`] and record it periodically with a [`SessionRecorder`].
+//! [`DebugBundle`] zips the session state, the buffers, stored portfolio
+//! snapshots, redacted config and recent log tails into a single file for
+//! attaching to an issue (`polybot debug dump`, or Help → Report Issue in the
+//! GUI).
+//!
+//! Buffer sizes live in `<data_dir>/diagnostics.yaml` and are optional:
+//!
+//! ```yaml
+//! event_buffers:
+//!   ws: 1000
+//!   ws_messages: false   # true also records every received message
+//!   orders: 500
+//!   strategy: 0    # off
+//! ```
+
+pub mod bundle;
+pub mod events;
+pub mod session;
+
+pub use bundle::DebugBundle;
+pub use events::{
+    is_enabled, recent_events, recent_events_for, record_event, records_ws_messages, EventBufferConfig,
+    RecentEvent, Subsystem,
+};
+pub use session::{spawn_recording, SessionRecorder, SessionState};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::data_paths::DataPaths;
+
+/// Directory (in the profile tree) for session recordings and bundles
+pub const DIAGNOSTICS_DIR: &str = "diagnostics";
+
+/// Name of the diagnostics configuration file inside the data directory
+pub const DIAGNOSTICS_CONFIG_FILE: &str = "diagnostics.yaml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsConfig {
+    pub event_buffers: EventBufferConfig,
+}
+
+impl DiagnosticsConfig {
+    /// Load `diagnostics.yaml` from the data directory, or defaults when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(DIAGNOSTICS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid diagnostics config {}", path.display()))
+    }
+}
+
+/// Size the event buffers from the profile's config; called once at startup
+pub fn init(data_paths: &DataPaths) {
+    match DiagnosticsConfig::load(data_paths) {
+        Ok(config) => events::configure(&config.event_buffers),
+        Err(e) => warn!("Using default event buffer sizes: {}", e),
+    }
+}
@@ -0,0 +1,196 @@
+//! Live state of a long-running session (GUI, daemon, strategy) and its
+//! periodic recording
+//!
+//! Each session writes its latest state to
+//! `<data_dir>/diagnostics/session-<source>.json` every [`RECORD_INTERVAL`],
+//! so `polybot debug dump` and `polybot debug events` started from another
+//! terminal can still describe what the running session was doing.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use super::events::{recent_events, RecentEvent};
+use super::DIAGNOSTICS_DIR;
+use crate::core::services::streaming::traits::{StreamingStats, WorkerStatus};
+use crate::data_paths::DataPaths;
+
+const SESSION_FILE_PREFIX: &str = "session-";
+
+/// How often a session's state is written to disk
+pub const RECORD_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerState {
+    pub worker_id: usize,
+    pub tokens: Vec<String>,
+    pub is_connected: bool,
+    pub events_processed: u64,
+    pub last_error: Option<String>,
+    /// Seconds since the worker last saw activity
+    pub idle_secs: u64,
+}
+
+impl From<&WorkerStatus> for WorkerState {
+    fn from(status: &WorkerStatus) -> Self {
+        let mut tokens = status.assigned_tokens.clone();
+        tokens.sort();
+        Self {
+            worker_id: status.worker_id,
+            tokens,
+            is_connected: status.is_connected,
+            events_processed: status.events_processed,
+            last_error: status.last_error.clone(),
+            idle_secs: status.last_activity.elapsed().as_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionState {
+    pub captured_at: DateTime<Utc>,
+    pub pid: u32,
+    /// What the session is (`gui`, `tui`, `strategy:<name>`, ...)
+    pub source: String,
+    pub streaming: Option<StreamingStats>,
+    /// Workers ordered by id
+    pub workers: Vec<WorkerState>,
+    pub portfolio: Option<Value>,
+    /// Frontend-specific state such as the GUI's pane layout
+    pub layout: Option<Value>,
+    pub events: Vec<RecentEvent>,
+}
+
+impl SessionState {
+    /// Capture the state, with the recent events of this process
+    pub fn capture(
+        source: impl Into<String>,
+        streaming: Option<StreamingStats>,
+        workers: &[WorkerStatus],
+        portfolio: Option<Value>,
+        layout: Option<Value>,
+    ) -> Self {
+        let mut workers: Vec<WorkerState> = workers.iter().map(WorkerState::from).collect();
+        workers.sort_by_key(|w| w.worker_id);
+        Self {
+            captured_at: Utc::now(),
+            pid: std::process::id(),
+            source: source.into(),
+            streaming,
+            workers,
+            portfolio,
+            layout,
+            events: recent_events(),
+        }
+    }
+
+    /// The states last recorded by running (or crashed) sessions, newest first
+    pub fn load_recorded(data_paths: &DataPaths) -> Result<Vec<Self>> {
+        let dir = data_paths.root().join(DIAGNOSTICS_DIR);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut sessions = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(SESSION_FILE_PREFIX) || !name.ends_with(".json") {
+                continue;
+            }
+            let content = std::fs::read_to_string(entry.path())
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            sessions.push(serde_json::from_str::<Self>(&content)?);
+        }
+        sessions.sort_by(|a, b| b.captured_at.cmp(&a.captured_at));
+        Ok(sessions)
+    }
+}
+
+/// `session-<source>.json`, with the source reduced to file-name-safe characters
+fn session_file_name(source: &str) -> String {
+    let source: String = source
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}{}.json", SESSION_FILE_PREFIX, source)
+}
+
+/// Writes a session's state at most once per [`RECORD_INTERVAL`]
+pub struct SessionRecorder {
+    dir: PathBuf,
+    last_recorded: Option<Instant>,
+}
+
+impl SessionRecorder {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            dir: data_paths.root().join(DIAGNOSTICS_DIR),
+            last_recorded: None,
+        }
+    }
+
+    /// Whether enough time has passed to record again; lets callers skip
+    /// building the state when it won't be written
+    pub fn is_due(&self) -> bool {
+        self.last_recorded
+            .is_none_or(|at| at.elapsed() >= RECORD_INTERVAL)
+    }
+
+    pub fn record(&mut self, state: &SessionState) -> Result<()> {
+        self.last_recorded = Some(Instant::now());
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(session_file_name(&state.source));
+        // Write then rename so a dump never reads a half-written file
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(state)?)?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Record a headless session (daemon, strategy) in the background: only its
+/// event buffers, since it has no streaming service or portfolio to report
+pub fn spawn_recording(data_paths: &DataPaths, source: impl Into<String>) -> JoinHandle<()> {
+    let mut recorder = SessionRecorder::new(data_paths);
+    let source = source.into();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RECORD_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = SessionState::capture(source.clone(), None, &[], None, None);
+            if let Err(e) = recorder.record(&state) {
+                warn!("Failed to record session state: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_are_recorded_per_source_and_loaded_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let mut recorder = SessionRecorder::new(&data_paths);
+
+        let strategy = SessionState::capture("strategy:simple", None, &[], None, None);
+        recorder.record(&strategy).unwrap();
+        assert!(!recorder.is_due());
+        let mut gui = SessionState::capture("gui", None, &[], None, None);
+        gui.captured_at = strategy.captured_at + chrono::Duration::seconds(1);
+        recorder.record(&gui).unwrap();
+
+        assert!(dir.path().join(DIAGNOSTICS_DIR).join("session-strategy_simple.json").exists());
+        let sources: Vec<_> = SessionState::load_recorded(&data_paths)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.source)
+            .collect();
+        assert_eq!(sources, vec!["gui", "strategy:simple"]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Display model for order books shared by the TUI and GUI depth views
+//!
+//! Thin books on Polymarket often spread a small amount of size over many
+//! one-tick levels. `PriceAggregation` groups those levels into wider price
+//! bins before they are drawn, so both front ends show the same numbers.
+//! `DepthDisplay` picks between the level ladder and a cumulative depth chart
+//! drawn from the same view.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::core::types::market::PriceLevel;
+
+/// Price bin width applied to order book levels before display
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PriceAggregation {
+    /// Show every level as received
+    #[default]
+    None,
+    OneCent,
+    TwoCents,
+    FiveCents,
+    TenCents,
+}
+
+impl PriceAggregation {
+    pub fn all() -> [PriceAggregation; 5] {
+        [
+            PriceAggregation::None,
+            PriceAggregation::OneCent,
+            PriceAggregation::TwoCents,
+            PriceAggregation::FiveCents,
+            PriceAggregation::TenCents,
+        ]
+    }
+
+    /// Bin width, or None when levels are not aggregated
+    pub fn step(&self) -> Option<Decimal> {
+        match self {
+            PriceAggregation::None => None,
+            PriceAggregation::OneCent => Some(Decimal::new(1, 2)),
+            PriceAggregation::TwoCents => Some(Decimal::new(2, 2)),
+            PriceAggregation::FiveCents => Some(Decimal::new(5, 2)),
+            PriceAggregation::TenCents => Some(Decimal::new(10, 2)),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PriceAggregation::None => "Tick",
+            PriceAggregation::OneCent => "1¢",
+            PriceAggregation::TwoCents => "2¢",
+            PriceAggregation::FiveCents => "5¢",
+            PriceAggregation::TenCents => "10¢",
+        }
+    }
+
+    /// Next wider bin, wrapping back to no aggregation
+    pub fn next(&self) -> PriceAggregation {
+        let all = Self::all();
+        let index = all.iter().position(|a| a == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+
+    /// Price a level is shown at once binned. Bids round down and asks round
+    /// up, so a bin never advertises a better price than any level inside it.
+    pub fn bin_price(&self, price: Decimal, is_bid: bool) -> Decimal {
+        let Some(step) = self.step() else {
+            return price;
+        };
+        let bins = price / step;
+        let bins = if is_bid { bins.floor() } else { bins.ceil() };
+        bins * step
+    }
+
+    /// Merge levels that fall into the same bin, summing their sizes. The
+    /// result is sorted best price first: descending for bids, ascending for asks.
+    pub fn aggregate(&self, levels: &[PriceLevel], is_bid: bool) -> Vec<PriceLevel> {
+        let mut binned: Vec<PriceLevel> = Vec::with_capacity(levels.len());
+        let mut sorted = levels.to_vec();
+        if is_bid {
+            sorted.sort_by(|a, b| b.price.cmp(&a.price));
+        } else {
+            sorted.sort_by(|a, b| a.price.cmp(&b.price));
+        }
+
+        for level in sorted {
+            let price = self.bin_price(level.price, is_bid);
+            match binned.last_mut() {
+                Some(last) if last.price == price => last.size += level.size,
+                _ => binned.push(PriceLevel::new(price, level.size)),
+            }
+        }
+        binned
+    }
+}
+
+/// How a depth view draws the book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DepthDisplay {
+    /// One row per price level
+    #[default]
+    Ladder,
+    /// Cumulative size stepping away from the best price on each side
+    Chart,
+}
+
+impl DepthDisplay {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DepthDisplay::Ladder => "Ladder",
+            DepthDisplay::Chart => "Depth chart",
+        }
+    }
+
+    pub fn toggle(&self) -> DepthDisplay {
+        match self {
+            DepthDisplay::Ladder => DepthDisplay::Chart,
+            DepthDisplay::Chart => DepthDisplay::Ladder,
+        }
+    }
+}
+
+/// Both sides of a book after aggregation, best price first
+#[derive(Debug, Clone, Default)]
+pub struct BookView {
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+    pub aggregation: PriceAggregation,
+}
+
+impl BookView {
+    pub fn new(bids: &[PriceLevel], asks: &[PriceLevel], aggregation: PriceAggregation) -> Self {
+        Self {
+            bids: aggregation.aggregate(bids, true),
+            asks: aggregation.aggregate(asks, false),
+            aggregation,
+        }
+    }
+
+    /// Chart points of the bid side, see [`depth_steps`]
+    pub fn bid_steps(&self) -> Vec<DepthPoint> {
+        depth_steps(&self.bids)
+    }
+
+    /// Chart points of the ask side, see [`depth_steps`]
+    pub fn ask_steps(&self) -> Vec<DepthPoint> {
+        depth_steps(&self.asks)
+    }
+}
+
+/// One point of a depth chart outline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthPoint {
+    pub price: f64,
+    /// Size available from the best price up to `price`
+    pub cumulative_size: f64,
+}
+
+/// Stepped outline of one side, best price first
+///
+/// The outline starts at zero on the best price and rises by each level's
+/// size at that level's price, staying flat in between, so the area under it
+/// is the size available up to any price.
+pub fn depth_steps(levels: &[PriceLevel]) -> Vec<DepthPoint> {
+    let mut steps = Vec::with_capacity(levels.len() * 2);
+    let mut total = 0.0;
+    for level in levels {
+        let price = level.price.to_f64().unwrap_or_default();
+        steps.push(DepthPoint {
+            price,
+            cumulative_size: total,
+        });
+        total += level.size.to_f64().unwrap_or_default();
+        steps.push(DepthPoint {
+            price,
+            cumulative_size: total,
+        });
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn level(price: Decimal, size: Decimal) -> PriceLevel {
+        PriceLevel::new(price, size)
+    }
+
+    #[test]
+    fn bids_round_down_and_asks_round_up() {
+        let agg = PriceAggregation::FiveCents;
+        assert_eq!(agg.bin_price(dec!(0.537), true), dec!(0.50));
+        assert_eq!(agg.bin_price(dec!(0.537), false), dec!(0.55));
+        assert_eq!(agg.bin_price(dec!(0.55), false), dec!(0.55));
+    }
+
+    #[test]
+    fn aggregate_sums_sizes_within_a_bin() {
+        let bids = [
+            level(dec!(0.531), dec!(10)),
+            level(dec!(0.539), dec!(5)),
+            level(dec!(0.52), dec!(7)),
+        ];
+        let view = BookView::new(&bids, &[], PriceAggregation::OneCent);
+        assert_eq!(view.bids, vec![level(dec!(0.53), dec!(15)), level(dec!(0.52), dec!(7))]);
+    }
+
+    #[test]
+    fn no_aggregation_only_sorts() {
+        let asks = [level(dec!(0.62), dec!(1)), level(dec!(0.61), dec!(2))];
+        let view = BookView::new(&[], &asks, PriceAggregation::None);
+        assert_eq!(view.asks, vec![level(dec!(0.61), dec!(2)), level(dec!(0.62), dec!(1))]);
+    }
+
+    #[test]
+    fn depth_steps_accumulate_from_the_best_price() {
+        let bids = [level(dec!(0.50), dec!(10)), level(dec!(0.52), dec!(5))];
+        let view = BookView::new(&bids, &[], PriceAggregation::None);
+        let steps: Vec<[f64; 2]> = view
+            .bid_steps()
+            .iter()
+            .map(|point| [point.price, point.cumulative_size])
+            .collect();
+        assert_eq!(steps, [[0.52, 0.0], [0.52, 5.0], [0.50, 5.0], [0.50, 15.0]]);
+        assert!(view.ask_steps().is_empty());
+    }
+
+    #[test]
+    fn next_cycles_through_all_bins() {
+        let mut agg = PriceAggregation::None;
+        for _ in 0..PriceAggregation::all().len() {
+            agg = agg.next();
+        }
+        assert_eq!(agg, PriceAggregation::None);
+    }
+}
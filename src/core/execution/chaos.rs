@@ -0,0 +1,413 @@
+//! Chaos mode: simulated network trouble for testing strategies
+//!
+//! With chaos on, market feed events reach the strategy late (a fixed latency
+//! plus random jitter), some never arrive and some swap places with the next
+//! event; order placements and cancels are held back the same way and some
+//! fail as if the request was lost. A strategy that behaves under chaos is
+//! less likely to be surprised by a slow or flaky connection in production.
+//!
+//! Settings live in `<data_dir>/chaos.yaml` (all optional) and are used by
+//! `polybot run-strategy --chaos`, whose flags override them:
+//!
+//! ```yaml
+//! seed: 42               # repeatable runs
+//! events:
+//!   latency_ms: 150
+//!   jitter_ms: 100
+//!   drop_rate: 0.02      # 2% of events never arrive
+//!   reorder_rate: 0.05   # 5% arrive after the event behind them
+//! orders:
+//!   latency_ms: 300
+//!   jitter_ms: 200
+//!   drop_rate: 0.01      # 1% of placements and cancels fail
+//! ```
+//!
+//! Jitter on its own never reorders events, as on a real connection; only
+//! `reorder_rate` does.
+
+use anyhow::{bail, Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::core::risk::MessageKind;
+use crate::core::ws::PolyEvent;
+use crate::data_paths::DataPaths;
+
+/// Name of the chaos configuration file inside the data directory
+pub const CHAOS_CONFIG_FILE: &str = "chaos.yaml";
+
+/// Longest an event held back for reordering waits for the event behind it
+const REORDER_HOLD: Duration = Duration::from_secs(1);
+
+/// Trouble injected into one path
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosProfile {
+    /// Delay added to every message
+    pub latency_ms: u64,
+    /// Extra random delay, up to this much
+    pub jitter_ms: u64,
+    /// Fraction of messages lost
+    pub drop_rate: f64,
+    /// Fraction of messages delivered after the next one (market feed only)
+    pub reorder_rate: f64,
+}
+
+impl ChaosProfile {
+    pub fn is_active(&self) -> bool {
+        self.latency_ms > 0 || self.jitter_ms > 0 || self.drop_rate > 0.0 || self.reorder_rate > 0.0
+    }
+
+    fn validate(&self, path: &str) -> Result<()> {
+        for (name, rate) in [
+            ("drop_rate", self.drop_rate),
+            ("reorder_rate", self.reorder_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                bail!(
+                    "chaos {}.{} must be between 0 and 1, got {}",
+                    path,
+                    name,
+                    rate
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Chaos settings for the market feed and order submission
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    /// Seed for the random choices; random when unset
+    pub seed: Option<u64>,
+    pub events: ChaosProfile,
+    pub orders: ChaosProfile,
+}
+
+impl ChaosConfig {
+    /// Load `chaos.yaml` from the data directory, or defaults (no chaos) when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(CHAOS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid chaos config {}", path.display()))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.events.validate("events")?;
+        self.orders.validate("orders")?;
+        if self.orders.reorder_rate > 0.0 {
+            warn!("chaos orders.reorder_rate is ignored: order submissions are not reordered");
+        }
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.events.is_active() || self.orders.is_active()
+    }
+}
+
+/// What chaos did so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChaosStats {
+    pub events_delayed: u64,
+    pub events_dropped: u64,
+    pub events_reordered: u64,
+    pub orders_delayed: u64,
+    pub orders_dropped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    events_delayed: AtomicU64,
+    events_dropped: AtomicU64,
+    events_reordered: AtomicU64,
+    orders_delayed: AtomicU64,
+    orders_dropped: AtomicU64,
+}
+
+/// Injects the trouble of a [`ChaosConfig`]; shared by the feed and the order manager
+pub struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+    counters: Counters,
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Result<Arc<Self>> {
+        config.validate()?;
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        Ok(Arc::new(Self {
+            config,
+            rng: Mutex::new(rng),
+            counters: Counters::default(),
+        }))
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> ChaosStats {
+        let c = &self.counters;
+        ChaosStats {
+            events_delayed: c.events_delayed.load(Ordering::Relaxed),
+            events_dropped: c.events_dropped.load(Ordering::Relaxed),
+            events_reordered: c.events_reordered.load(Ordering::Relaxed),
+            orders_delayed: c.orders_delayed.load(Ordering::Relaxed),
+            orders_dropped: c.orders_dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn roll(&self, rate: f64) -> bool {
+        rate > 0.0 && self.rng.lock().unwrap().random_bool(rate.min(1.0))
+    }
+
+    fn delay(&self, profile: &ChaosProfile) -> Duration {
+        let jitter = match profile.jitter_ms {
+            0 => 0,
+            max => self.rng.lock().unwrap().random_range(0..=max),
+        };
+        Duration::from_millis(profile.latency_ms + jitter)
+    }
+
+    /// Hold back an order placement or cancel, failing it when it is "lost"
+    pub async fn before_order(&self, kind: MessageKind) -> Result<()> {
+        let profile = &self.config.orders;
+        let delay = self.delay(profile);
+        if !delay.is_zero() {
+            self.counters.orders_delayed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(delay).await;
+        }
+        if self.roll(profile.drop_rate) {
+            self.counters.orders_dropped.fetch_add(1, Ordering::Relaxed);
+            let what = match kind {
+                MessageKind::NewOrder => "order placement",
+                MessageKind::Cancel => "cancel",
+            };
+            bail!("🌪️ Chaos: {} lost in transit (simulated)", what);
+        }
+        Ok(())
+    }
+
+    /// A receiver of the events of `receiver` with the feed's chaos applied
+    pub fn wrap_events(
+        self: &Arc<Self>,
+        mut receiver: broadcast::Receiver<PolyEvent>,
+        buffer_size: usize,
+    ) -> broadcast::Receiver<PolyEvent> {
+        let (tx, rx) = broadcast::channel(buffer_size.max(1));
+        let chaos = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut scheduler = EventScheduler::default();
+            let mut open = true;
+            while open || !scheduler.is_empty() {
+                let next_due = scheduler.next_due();
+                tokio::select! {
+                    received = receiver.recv(), if open => match received {
+                        Ok(event) => scheduler.push(&chaos, event, Instant::now()),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Chaos feed lagged, {} events skipped", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => open = false,
+                    },
+                    _ = sleep_until(next_due) => {}
+                }
+                for event in scheduler.pop_due(Instant::now()) {
+                    if tx.send(event).is_err() {
+                        debug!("Chaos feed has no receivers, stopping");
+                        return;
+                    }
+                }
+            }
+        });
+        rx
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Delivery times of the feed's events under chaos
+#[derive(Default)]
+struct EventScheduler {
+    queue: VecDeque<(Instant, PolyEvent)>,
+    /// Event waiting to be delivered after the next one, with its latest release
+    held: Option<(Instant, PolyEvent)>,
+    last_due: Option<Instant>,
+}
+
+impl EventScheduler {
+    fn push(&mut self, chaos: &Chaos, event: PolyEvent, now: Instant) {
+        let profile = &chaos.config.events;
+        if chaos.roll(profile.drop_rate) {
+            chaos
+                .counters
+                .events_dropped
+                .fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let delay = chaos.delay(profile);
+        if !delay.is_zero() {
+            chaos
+                .counters
+                .events_delayed
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        // Never before an earlier event: jitter alone keeps the order
+        let due = self
+            .last_due
+            .map_or(now + delay, |last| last.max(now + delay));
+        self.last_due = Some(due);
+
+        if let Some((_, held)) = self.held.take() {
+            self.queue.push_back((due, event));
+            self.queue.push_back((due, held));
+        } else if chaos.roll(profile.reorder_rate) {
+            chaos
+                .counters
+                .events_reordered
+                .fetch_add(1, Ordering::Relaxed);
+            self.held = Some((due + REORDER_HOLD, event));
+        } else {
+            self.queue.push_back((due, event));
+        }
+    }
+
+    fn next_due(&self) -> Option<Instant> {
+        let queued = self.queue.front().map(|(due, _)| *due);
+        let held = self.held.as_ref().map(|(release, _)| *release);
+        queued.into_iter().chain(held).min()
+    }
+
+    fn pop_due(&mut self, now: Instant) -> Vec<PolyEvent> {
+        let mut due = Vec::new();
+        while self.queue.front().is_some_and(|(at, _)| *at <= now) {
+            due.extend(self.queue.pop_front().map(|(_, event)| event));
+        }
+        // Nothing came behind the held event in time
+        if self
+            .held
+            .as_ref()
+            .is_some_and(|(release, _)| *release <= now)
+        {
+            due.extend(self.held.take().map(|(_, event)| event));
+        }
+        due
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty() && self.held.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn price(asset: usize) -> PolyEvent {
+        PolyEvent::LastTradePrice {
+            asset_id: asset.to_string(),
+            price: Decimal::ONE,
+            timestamp: 0,
+        }
+    }
+
+    fn asset(event: &PolyEvent) -> String {
+        match event {
+            PolyEvent::LastTradePrice { asset_id, .. } => asset_id.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn scheduler_delays_drops_and_reorders_events() {
+        let chaos = Chaos::new(ChaosConfig {
+            seed: Some(7),
+            events: ChaosProfile {
+                latency_ms: 100,
+                jitter_ms: 50,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        let mut scheduler = EventScheduler::default();
+        let start = Instant::now();
+        for i in 0..20 {
+            scheduler.push(&chaos, price(i), start + Duration::from_millis(i as u64));
+        }
+        assert!(scheduler
+            .pop_due(start + Duration::from_millis(99))
+            .is_empty());
+        let delivered = scheduler.pop_due(start + Duration::from_secs(1));
+        // Jitter delays but keeps the order
+        assert_eq!(
+            delivered.iter().map(asset).collect::<Vec<_>>(),
+            (0..20).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(chaos.stats().events_delayed, 20);
+
+        // Every event lost, then every event swapped with the next
+        let lossy = Chaos::new(ChaosConfig {
+            events: ChaosProfile {
+                drop_rate: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        scheduler.push(&lossy, price(1), start);
+        assert!(scheduler.is_empty());
+        assert_eq!(lossy.stats().events_dropped, 1);
+
+        let swapping = Chaos::new(ChaosConfig {
+            events: ChaosProfile {
+                reorder_rate: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        let mut scheduler = EventScheduler::default();
+        for i in 0..3 {
+            scheduler.push(&swapping, price(i), start);
+        }
+        let delivered = scheduler.pop_due(start);
+        assert_eq!(delivered.iter().map(asset).collect::<Vec<_>>(), ["1", "0"]);
+        // The last one goes out alone once nothing follows it
+        assert!(scheduler.pop_due(start).is_empty());
+        assert_eq!(asset(&scheduler.pop_due(start + REORDER_HOLD)[0]), "2");
+
+        let invalid = ChaosConfig {
+            orders: ChaosProfile {
+                drop_rate: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(Chaos::new(invalid).is_err());
+    }
+}
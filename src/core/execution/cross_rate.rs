@@ -0,0 +1,134 @@
+//! Cross-rate metrics for the two outcome tokens of a binary market
+//!
+//! Both tokens of a condition pay out exactly $1 between them, so the sum of
+//! their best asks and the sum of their best bids should sit around 1.0.
+//! An ask sum below 1 means both sides can be bought for less than the
+//! payout; a bid sum above 1 means both can be sold for more than it.
+
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::core::ws::OrderBook;
+
+/// Top of book for one outcome token
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenQuote {
+    pub asset_id: String,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+}
+
+impl TokenQuote {
+    pub fn from_book(book: &OrderBook) -> Self {
+        Self {
+            asset_id: book.asset_id.clone(),
+            best_bid: book.best_bid().map(|level| level.price),
+            best_ask: book.best_ask().map(|level| level.price),
+        }
+    }
+}
+
+/// Both tokens of one market with their derived consistency metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossRate {
+    pub market: String,
+    /// The two outcome tokens, ordered by asset id
+    pub tokens: [TokenQuote; 2],
+}
+
+impl CrossRate {
+    /// Pair up order books by market. Markets with anything other than exactly
+    /// two streamed tokens are skipped; the result is ordered by market id.
+    pub fn from_books<'a>(books: impl IntoIterator<Item = &'a OrderBook>) -> Vec<CrossRate> {
+        let mut by_market: BTreeMap<&str, Vec<&OrderBook>> = BTreeMap::new();
+        for book in books {
+            by_market.entry(book.market.as_str()).or_default().push(book);
+        }
+
+        by_market
+            .into_iter()
+            .filter_map(|(market, mut books)| {
+                // Books that haven't received a snapshot yet have no market
+                if market.is_empty() || books.len() != 2 {
+                    return None;
+                }
+                books.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+                Some(CrossRate {
+                    market: market.to_string(),
+                    tokens: [TokenQuote::from_book(books[0]), TokenQuote::from_book(books[1])],
+                })
+            })
+            .collect()
+    }
+
+    /// Cost of buying one share of each outcome at the best asks
+    pub fn ask_sum(&self) -> Option<Decimal> {
+        Some(self.tokens[0].best_ask? + self.tokens[1].best_ask?)
+    }
+
+    /// Proceeds of selling one share of each outcome at the best bids
+    pub fn bid_sum(&self) -> Option<Decimal> {
+        Some(self.tokens[0].best_bid? + self.tokens[1].best_bid?)
+    }
+
+    /// Profit per pair from buying both sides, positive when the asks sum below 1
+    pub fn buy_both_edge(&self) -> Option<Decimal> {
+        self.ask_sum().map(|sum| Decimal::ONE - sum)
+    }
+
+    /// Profit per pair from selling both sides, positive when the bids sum above 1
+    pub fn sell_both_edge(&self) -> Option<Decimal> {
+        self.bid_sum().map(|sum| sum - Decimal::ONE)
+    }
+
+    /// True when either edge is positive
+    pub fn has_arbitrage(&self) -> bool {
+        let positive = |edge: Option<Decimal>| edge.is_some_and(|e| e > Decimal::ZERO);
+        positive(self.buy_both_edge()) || positive(self.sell_both_edge())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::market::PriceLevel;
+    use rust_decimal_macros::dec;
+
+    fn book(asset_id: &str, market: &str, bid: Decimal, ask: Decimal) -> OrderBook {
+        let mut book = OrderBook::new(asset_id.to_string());
+        book.replace_with_snapshot_no_hash(
+            market.to_string(),
+            0,
+            vec![PriceLevel::new(bid, dec!(100))],
+            vec![PriceLevel::new(ask, dec!(100))],
+        );
+        book
+    }
+
+    #[test]
+    fn pairs_books_by_market() {
+        let books = [
+            book("yes", "m1", dec!(0.60), dec!(0.62)),
+            book("solo", "m2", dec!(0.10), dec!(0.20)),
+            book("no", "m1", dec!(0.37), dec!(0.39)),
+        ];
+        let rates = CrossRate::from_books(&books);
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].market, "m1");
+        assert_eq!(rates[0].tokens[0].asset_id, "no");
+        assert_eq!(rates[0].ask_sum(), Some(dec!(1.01)));
+        assert_eq!(rates[0].bid_sum(), Some(dec!(0.97)));
+        assert!(!rates[0].has_arbitrage());
+    }
+
+    #[test]
+    fn flags_asks_summing_below_one() {
+        let books = [
+            book("a", "m", dec!(0.50), dec!(0.52)),
+            book("b", "m", dec!(0.44), dec!(0.46)),
+        ];
+        let rate = &CrossRate::from_books(&books)[0];
+        assert_eq!(rate.buy_both_edge(), Some(dec!(0.02)));
+        assert!(rate.has_arbitrage());
+    }
+}
@@ -48,6 +48,12 @@ impl Default for EventId {
     }
 }
 
+impl std::fmt::Display for EventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Event source information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EventSource {
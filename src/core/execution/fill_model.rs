@@ -0,0 +1,490 @@
+//! Fill probability model for hypothetical resting orders
+//!
+//! Replays recorded book and trade events for a single token and estimates how
+//! likely a resting limit order would have been filled, and how long it would
+//! have taken, using a queue position model:
+//!
+//! - the order joins the back of the queue at its price level, so the size
+//!   already resting there is ahead of it
+//! - trades at the order's price consume the queue ahead first, then the order
+//! - trades through the order's price fill whatever remains
+//! - size removed from the level without a matching trade is treated as
+//!   cancellations spread evenly across the queue, so only the share in front
+//!   of the order moves it forward
+//! - size added to the level joins behind the order
+//!
+//! A single recording gives one outcome per placement time, so the model slides
+//! the placement time across the recording and reports the share of trials that
+//! filled within the window.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::core::types::common::Side;
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::PolyEvent;
+
+/// Hypothetical resting limit order
+#[derive(Debug, Clone)]
+pub struct RestingOrder {
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Event with the time (milliseconds since epoch) it was observed
+#[derive(Debug, Clone)]
+pub struct TimedEvent {
+    pub timestamp_ms: u64,
+    pub event: PolyEvent,
+}
+
+/// Simulation parameters
+#[derive(Debug, Clone)]
+pub struct FillModelConfig {
+    /// How long the order rests before it is considered unfilled
+    pub window: Duration,
+    /// Spacing between simulated placement times
+    pub step: Duration,
+}
+
+/// Outcome of resting the order once, from one placement time
+#[derive(Debug, Clone)]
+pub struct FillTrial {
+    /// Placement time (ms)
+    pub placed_at_ms: u64,
+    /// Size resting ahead of the order when it was placed
+    pub initial_queue_ahead: Decimal,
+    /// Size filled within the window
+    pub filled: Decimal,
+    /// Time from placement until the first fill
+    pub time_to_first_fill: Option<Duration>,
+    /// Time from placement until the order was completely filled
+    pub time_to_full_fill: Option<Duration>,
+    /// Order would have crossed the spread when placed
+    pub marketable: bool,
+}
+
+/// Aggregated estimate across all trials
+#[derive(Debug, Clone)]
+pub struct FillEstimate {
+    pub trials: Vec<FillTrial>,
+    /// Share of trials that filled completely within the window
+    pub fill_probability: f64,
+    /// Share of trials that filled at least partially
+    pub partial_fill_probability: f64,
+    /// Mean filled fraction of the order size
+    pub expected_fill_ratio: f64,
+    /// Mean time to complete fill over trials that filled
+    pub expected_time_to_fill: Option<Duration>,
+    /// Median time to complete fill over trials that filled
+    pub median_time_to_fill: Option<Duration>,
+    /// Mean size ahead of the order at placement
+    pub mean_queue_ahead: Decimal,
+    /// Number of trials where the order would have been marketable
+    pub marketable_trials: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BookLevels {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    initialized: bool,
+}
+
+impl BookLevels {
+    fn apply_snapshot(&mut self, bids: &[PriceLevel], asks: &[PriceLevel]) {
+        self.bids = bids.iter().filter(|l| l.has_size()).map(|l| (l.price, l.size)).collect();
+        self.asks = asks.iter().filter(|l| l.has_size()).map(|l| (l.price, l.size)).collect();
+        self.initialized = true;
+    }
+
+    fn apply_change(&mut self, side: Side, price: Decimal, size: Decimal) {
+        let levels = self.side_mut(side);
+        if size.is_zero() {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, size);
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<Decimal, Decimal> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    fn size_at(&self, side: Side, price: Decimal) -> Decimal {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels.get(&price).copied().unwrap_or(Decimal::ZERO)
+    }
+
+    fn best_bid(&self) -> Option<Decimal> {
+        self.bids.keys().next_back().copied()
+    }
+
+    fn best_ask(&self) -> Option<Decimal> {
+        self.asks.keys().next().copied()
+    }
+}
+
+/// Queue position model over a recorded event history for one token
+pub struct FillModel {
+    events: Vec<TimedEvent>,
+}
+
+impl FillModel {
+    /// Create a model from recorded events; events are sorted by time
+    pub fn new(mut events: Vec<TimedEvent>) -> Self {
+        events.sort_by_key(|e| e.timestamp_ms);
+        Self { events }
+    }
+
+    /// Number of events in the recording
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Time span covered by the recording
+    pub fn span(&self) -> Duration {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) => {
+                Duration::from_millis(last.timestamp_ms.saturating_sub(first.timestamp_ms))
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Estimate fill probability and time-to-fill for the order
+    pub fn estimate(&self, order: &RestingOrder, config: &FillModelConfig) -> FillEstimate {
+        let trials = self.run_trials(order, config);
+        Self::aggregate(order, trials)
+    }
+
+    fn run_trials(&self, order: &RestingOrder, config: &FillModelConfig) -> Vec<FillTrial> {
+        let window_ms = config.window.as_millis() as u64;
+        let step_ms = (config.step.as_millis() as u64).max(1);
+
+        // Placement requires a known book, so start at the first snapshot
+        let Some(first_book) = self
+            .events
+            .iter()
+            .position(|e| matches!(e.event, PolyEvent::Book { .. }))
+        else {
+            return Vec::new();
+        };
+        let start_ms = self.events[first_book].timestamp_ms;
+        let end_ms = self.events.last().map(|e| e.timestamp_ms).unwrap_or(start_ms);
+
+        let mut trials = Vec::new();
+        let mut book = BookLevels::default();
+        let mut index = 0;
+        let mut placement = start_ms;
+
+        loop {
+            // Trials must be able to observe the full window
+            if placement.saturating_add(window_ms) > end_ms && !trials.is_empty() {
+                break;
+            }
+
+            while index < self.events.len() && self.events[index].timestamp_ms <= placement {
+                Self::apply_to_book(&mut book, &self.events[index].event);
+                index += 1;
+            }
+
+            if book.initialized {
+                trials.push(self.simulate(order, &book, index, placement, window_ms));
+            }
+
+            if placement.saturating_add(window_ms) > end_ms {
+                break;
+            }
+            placement += step_ms;
+        }
+
+        trials
+    }
+
+    fn apply_to_book(book: &mut BookLevels, event: &PolyEvent) {
+        match event {
+            PolyEvent::Book { bids, asks, .. } => book.apply_snapshot(bids, asks),
+            PolyEvent::PriceChange {
+                side, price, size, ..
+            } => book.apply_change(*side, *price, *size),
+            _ => {}
+        }
+    }
+
+    /// Rest the order from `placed_at_ms` starting with `book`, replaying events from `start_index`
+    fn simulate(
+        &self,
+        order: &RestingOrder,
+        book: &BookLevels,
+        start_index: usize,
+        placed_at_ms: u64,
+        window_ms: u64,
+    ) -> FillTrial {
+        let mut book = book.clone();
+        let mut trial = FillTrial {
+            placed_at_ms,
+            initial_queue_ahead: Decimal::ZERO,
+            filled: Decimal::ZERO,
+            time_to_first_fill: None,
+            time_to_full_fill: None,
+            marketable: false,
+        };
+
+        let crosses = match order.side {
+            Side::Buy => book.best_ask().is_some_and(|ask| order.price >= ask),
+            Side::Sell => book.best_bid().is_some_and(|bid| order.price <= bid),
+        };
+        if crosses {
+            trial.marketable = true;
+            trial.filled = order.size;
+            trial.time_to_first_fill = Some(Duration::ZERO);
+            trial.time_to_full_fill = Some(Duration::ZERO);
+            return trial;
+        }
+
+        let mut queue_ahead = book.size_at(order.side, order.price);
+        trial.initial_queue_ahead = queue_ahead;
+        // Size at our level consumed by trades, so the matching level decrease isn't counted as a cancel
+        let mut traded_at_level = Decimal::ZERO;
+
+        for timed in &self.events[start_index..] {
+            if timed.timestamp_ms > placed_at_ms + window_ms {
+                break;
+            }
+
+            let mut fill_now = Decimal::ZERO;
+            let remaining = order.size - trial.filled;
+
+            match &timed.event {
+                PolyEvent::Trade { price, size, .. } => {
+                    let through = match order.side {
+                        Side::Buy => *price < order.price,
+                        Side::Sell => *price > order.price,
+                    };
+                    if through {
+                        fill_now = remaining;
+                    } else if *price == order.price {
+                        let consumed_ahead = (*size).min(queue_ahead);
+                        queue_ahead -= consumed_ahead;
+                        traded_at_level += consumed_ahead;
+                        fill_now = (*size - consumed_ahead).min(remaining);
+                    }
+                }
+                PolyEvent::PriceChange {
+                    side, price, size, ..
+                } if *side == order.side && *price == order.price => {
+                    let before = book.size_at(order.side, order.price);
+                    if *size < before {
+                        let removed = before - *size;
+                        let explained = removed.min(traded_at_level);
+                        traded_at_level -= explained;
+                        let cancelled = removed - explained;
+                        if cancelled > Decimal::ZERO && before > Decimal::ZERO {
+                            queue_ahead -= cancelled * queue_ahead / before;
+                        }
+                    }
+                    book.apply_change(*side, *price, *size);
+                    queue_ahead = queue_ahead.min(*size).max(Decimal::ZERO);
+                }
+                PolyEvent::Book { bids, asks, .. } => {
+                    book.apply_snapshot(bids, asks);
+                    queue_ahead = queue_ahead.min(book.size_at(order.side, order.price));
+                    traded_at_level = Decimal::ZERO;
+                }
+                other => Self::apply_to_book(&mut book, other),
+            }
+
+            if fill_now > Decimal::ZERO {
+                let elapsed = Duration::from_millis(timed.timestamp_ms.saturating_sub(placed_at_ms));
+                trial.filled += fill_now;
+                trial.time_to_first_fill.get_or_insert(elapsed);
+                if trial.filled >= order.size {
+                    trial.filled = order.size;
+                    trial.time_to_full_fill = Some(elapsed);
+                    break;
+                }
+            }
+        }
+
+        trial
+    }
+
+    fn aggregate(order: &RestingOrder, trials: Vec<FillTrial>) -> FillEstimate {
+        let count = trials.len();
+        if count == 0 {
+            return FillEstimate {
+                trials,
+                fill_probability: 0.0,
+                partial_fill_probability: 0.0,
+                expected_fill_ratio: 0.0,
+                expected_time_to_fill: None,
+                median_time_to_fill: None,
+                mean_queue_ahead: Decimal::ZERO,
+                marketable_trials: 0,
+            };
+        }
+
+        let full = trials.iter().filter(|t| t.time_to_full_fill.is_some()).count();
+        let partial = trials.iter().filter(|t| t.filled > Decimal::ZERO).count();
+        let fill_ratio_sum: f64 = trials
+            .iter()
+            .map(|t| {
+                if order.size.is_zero() {
+                    0.0
+                } else {
+                    (t.filled / order.size).to_f64().unwrap_or(0.0)
+                }
+            })
+            .sum();
+
+        let mut times: Vec<Duration> = trials.iter().filter_map(|t| t.time_to_full_fill).collect();
+        times.sort();
+        let expected_time_to_fill = if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<Duration>() / times.len() as u32)
+        };
+        let median_time_to_fill = times.get(times.len() / 2).copied();
+
+        let queue_sum: Decimal = trials.iter().map(|t| t.initial_queue_ahead).sum();
+        let marketable_trials = trials.iter().filter(|t| t.marketable).count();
+
+        FillEstimate {
+            fill_probability: full as f64 / count as f64,
+            partial_fill_probability: partial as f64 / count as f64,
+            expected_fill_ratio: fill_ratio_sum / count as f64,
+            expected_time_to_fill,
+            median_time_to_fill,
+            mean_queue_ahead: queue_sum / Decimal::from(count),
+            marketable_trials,
+            trials,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ws::BookSource;
+    use rust_decimal_macros::dec;
+
+    fn book(ts: u64, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> TimedEvent {
+        TimedEvent {
+            timestamp_ms: ts,
+            event: PolyEvent::Book {
+                asset_id: "t".to_string(),
+                market: "m".to_string(),
+                timestamp: ts,
+                bids: bids.iter().map(|(p, s)| PriceLevel::new(*p, *s)).collect(),
+                asks: asks.iter().map(|(p, s)| PriceLevel::new(*p, *s)).collect(),
+                hash: String::new(),
+                source: BookSource::WebSocket,
+            },
+        }
+    }
+
+    fn trade(ts: u64, price: Decimal, size: Decimal) -> TimedEvent {
+        TimedEvent {
+            timestamp_ms: ts,
+            event: PolyEvent::Trade {
+                asset_id: "t".to_string(),
+                price,
+                size,
+                side: Side::Sell,
+            },
+        }
+    }
+
+    fn change(ts: u64, price: Decimal, size: Decimal) -> TimedEvent {
+        TimedEvent {
+            timestamp_ms: ts,
+            event: PolyEvent::PriceChange {
+                asset_id: "t".to_string(),
+                side: Side::Buy,
+                price,
+                size,
+                hash: String::new(),
+            },
+        }
+    }
+
+    fn single_trial_config() -> FillModelConfig {
+        FillModelConfig {
+            window: Duration::from_secs(10),
+            step: Duration::from_secs(60),
+        }
+    }
+
+    fn buy(price: Decimal, size: Decimal) -> RestingOrder {
+        RestingOrder {
+            side: Side::Buy,
+            price,
+            size,
+        }
+    }
+
+    #[test]
+    fn test_trades_consume_queue_before_filling() {
+        let model = FillModel::new(vec![
+            book(0, &[(dec!(0.50), dec!(100))], &[(dec!(0.52), dec!(50))]),
+            trade(1_000, dec!(0.50), dec!(80)),
+            change(1_000, dec!(0.50), dec!(20)),
+            trade(2_000, dec!(0.50), dec!(30)),
+            change(2_000, dec!(0.50), dec!(0)),
+            trade(10_000, dec!(0.51), dec!(1)),
+        ]);
+
+        let estimate = model.estimate(&buy(dec!(0.50), dec!(10)), &single_trial_config());
+
+        assert_eq!(estimate.trials.len(), 1);
+        let trial = &estimate.trials[0];
+        assert_eq!(trial.initial_queue_ahead, dec!(100));
+        assert_eq!(trial.filled, dec!(10));
+        assert_eq!(trial.time_to_full_fill, Some(Duration::from_secs(2)));
+        assert_eq!(estimate.fill_probability, 1.0);
+    }
+
+    #[test]
+    fn test_cancels_ahead_advance_queue_pro_rata() {
+        let model = FillModel::new(vec![
+            book(0, &[(dec!(0.50), dec!(100))], &[(dec!(0.52), dec!(50))]),
+            // Half the level cancels: half of the queue ahead is assumed gone
+            change(1_000, dec!(0.50), dec!(50)),
+            trade(2_000, dec!(0.50), dec!(55)),
+            trade(10_000, dec!(0.51), dec!(1)),
+        ]);
+
+        let estimate = model.estimate(&buy(dec!(0.50), dec!(10)), &single_trial_config());
+
+        let trial = &estimate.trials[0];
+        assert_eq!(trial.filled, dec!(5));
+        assert!(trial.time_to_full_fill.is_none());
+        assert_eq!(estimate.fill_probability, 0.0);
+        assert_eq!(estimate.partial_fill_probability, 1.0);
+    }
+
+    #[test]
+    fn test_trade_through_price_fills_and_crossing_order_is_marketable() {
+        let model = FillModel::new(vec![
+            book(0, &[(dec!(0.50), dec!(100))], &[(dec!(0.52), dec!(50))]),
+            trade(3_000, dec!(0.49), dec!(1)),
+            trade(10_000, dec!(0.51), dec!(1)),
+        ]);
+
+        let resting = model.estimate(&buy(dec!(0.50), dec!(10)), &single_trial_config());
+        assert_eq!(resting.trials[0].time_to_full_fill, Some(Duration::from_secs(3)));
+
+        let crossing = model.estimate(&buy(dec!(0.52), dec!(10)), &single_trial_config());
+        assert_eq!(crossing.marketable_trials, 1);
+    }
+}
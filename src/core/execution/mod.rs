@@ -5,17 +5,26 @@
 //! - Replay of historical data from files
 //! - Event processing and state management
 //! - Multiple execution modes and strategies
-//! - Order management and execution
+//! - Order management and execution, and the route orders outside strategies take
 //! - Orderbook representation and manipulation
+//! - Chaos mode: simulated latency, jitter, lost and reordered messages for testing strategies
+//! - Synthetic instruments: books and bound alerts of weighted token combinations
 //!
 //! See README.md for detailed architecture documentation.
 
+pub mod book_view;
+pub mod chaos;
 pub mod config;
+pub mod cross_rate;
 pub mod engine;
 pub mod events;
+pub mod fill_model;
 pub mod orderbook;
 pub mod orders;
+pub mod route;
 pub mod sources;
 pub mod strategies;
+pub mod synthetic;
+pub mod trade_flow;
 
 // Note: AssetOrderBook is imported directly from orderbook module where needed
\ No newline at end of file
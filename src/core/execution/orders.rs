@@ -8,7 +8,12 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
-use crate::core::portfolio::controller::manager::PortfolioManager;
+use crate::auth::SignedOrderClient;
+use crate::core::audit::{self, AuditAction};
+use crate::core::diagnostics::{record_event, Subsystem};
+use crate::core::execution::chaos::Chaos;
+use crate::core::portfolio::PortfolioManager;
+use crate::core::risk::{MessageKind, MessageThrottle, RiskConfig, ThrottleStats};
 
 // Type alias for cleaner API
 pub type Portfolio = PortfolioManager;
@@ -37,12 +42,42 @@ impl PolyBot {
             portfolio: Arc::new(Portfolio::new()),
         }
     }
+
+    /// Create PolyBot with custom order and risk configuration
+    pub fn with_risk_config(order_config: OrderConfig, risk_config: RiskConfig) -> Self {
+        Self {
+            order: Arc::new(OrderManager::with_risk_config(order_config, risk_config)),
+            portfolio: Arc::new(Portfolio::new()),
+        }
+    }
+
+    /// Sign orders through `order_signer` when the profile uses an external signer
+    pub fn with_order_signer(mut self, order_signer: Option<Arc<SignedOrderClient>>) -> Self {
+        self.order = Arc::new((*self.order).clone().with_order_signer(order_signer));
+        self
+    }
+
+    /// Delay and drop order submissions as `chaos` says (testing only)
+    pub fn with_chaos(mut self, chaos: Option<Arc<Chaos>>) -> Self {
+        self.order = Arc::new((*self.order).clone().with_chaos(chaos));
+        self
+    }
 }
 
+/// Throttle bucket for cancels of orders this manager didn't place
+const UNTRACKED_MARKET: &str = "untracked";
+
 /// Thread-safe order management system with strongly typed operations
 #[derive(Clone)]
 pub struct OrderManager {
     state: Arc<RwLock<OrderManagerState>>,
+    throttle: Arc<MessageThrottle>,
+    /// Market (condition) ID of each token an order was sent for, the throttle's key
+    markets: Arc<RwLock<HashMap<String, String>>>,
+    /// Places orders instead of the CLOB client when the key is on a hardware wallet
+    order_signer: Option<Arc<SignedOrderClient>>,
+    /// Simulated latency and lost requests on the way to the exchange
+    chaos: Option<Arc<Chaos>>,
 }
 
 /// Internal state of the order manager
@@ -69,6 +104,9 @@ pub struct OrderStatistics {
     pub orders_cancelled: usize,
     pub successful_orders: usize,
     pub failed_orders: usize,
+    /// Orders and cancels rejected locally by the per-market throttle
+    #[serde(default)]
+    pub throttled_messages: usize,
     pub total_volume_traded: f64,
     pub session_start_time: Option<DateTime<Utc>>,
 }
@@ -83,6 +121,20 @@ pub struct OrderPlacementResponse {
     pub placement_time: DateTime<Utc>,
 }
 
+impl OrderPlacementResponse {
+    /// The exchange order ID, or the rejection reason as an error
+    pub fn into_order_id(self) -> Result<String> {
+        match (self.success, self.order_id) {
+            (true, Some(order_id)) => Ok(order_id),
+            (true, None) => Err(anyhow::anyhow!("Order accepted without an order ID")),
+            (false, _) => Err(anyhow::anyhow!(
+                "Order rejected: {}",
+                self.error_message.unwrap_or_else(|| "no reason given".to_string())
+            )),
+        }
+    }
+}
+
 /// Details of a successfully placed order
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlacedOrderDetails {
@@ -108,6 +160,21 @@ pub struct OrderCancellationResponse {
     pub filled_amount: Option<f64>,
 }
 
+impl OrderCancellationResponse {
+    /// `Ok` only when the exchange confirmed the cancel
+    pub fn confirmed(self) -> Result<()> {
+        if self.success {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Order {} not cancelled: {}",
+                self.order_id,
+                self.error_message.unwrap_or_else(|| "no reason given".to_string())
+            ))
+        }
+    }
+}
+
 /// Enhanced order information with additional metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnhancedOrder {
@@ -206,20 +273,16 @@ impl Default for OrderConfig {
 impl OrderManager {
     /// Create new order manager with default configuration
     pub fn new() -> Self {
-        Self {
-            state: Arc::new(RwLock::new(OrderManagerState {
-                config: OrderConfig::default(),
-                statistics: OrderStatistics {
-                    session_start_time: Some(Utc::now()),
-                    ..Default::default()
-                },
-                active_orders: HashMap::new(),
-            })),
-        }
+        Self::with_config(OrderConfig::default())
     }
     
     /// Create new order manager with custom configuration
     pub fn with_config(config: OrderConfig) -> Self {
+        Self::with_risk_config(config, RiskConfig::default())
+    }
+
+    /// Create new order manager with custom order and risk configuration
+    pub fn with_risk_config(config: OrderConfig, risk_config: RiskConfig) -> Self {
         Self {
             state: Arc::new(RwLock::new(OrderManagerState {
                 config,
@@ -229,8 +292,63 @@ impl OrderManager {
                 },
                 active_orders: HashMap::new(),
             })),
+            throttle: Arc::new(MessageThrottle::new(risk_config.throttle)),
+            markets: Arc::new(RwLock::new(HashMap::new())),
+            order_signer: None,
+            chaos: None,
         }
     }
+
+    /// Route order placement through an external signer instead of the CLOB client
+    pub fn with_order_signer(mut self, order_signer: Option<Arc<SignedOrderClient>>) -> Self {
+        self.order_signer = order_signer;
+        self
+    }
+
+    /// Hold back and drop order submissions as `chaos` says
+    pub fn with_chaos(mut self, chaos: Option<Arc<Chaos>>) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
+    /// Per-market throttle counters
+    pub fn throttle_stats(&self) -> ThrottleStats {
+        self.throttle.stats()
+    }
+
+    /// Market a token trades in, from its order book on first use; the token
+    /// itself stands in when the book can't be loaded
+    async fn market_of(&self, client: &ClobClient, token_id: &str) -> String {
+        if let Some(market) = self.markets.read().await.get(token_id) {
+            return market.clone();
+        }
+        match client.get_order_book(token_id).await {
+            Ok(book) if !book.market.is_empty() => {
+                self.markets
+                    .write()
+                    .await
+                    .insert(token_id.to_string(), book.market.clone());
+                book.market
+            }
+            Ok(_) => token_id.to_string(),
+            Err(e) => {
+                warn!("Market of token {} unknown, throttling it on its own: {}", token_id, e);
+                token_id.to_string()
+            }
+        }
+    }
+
+    /// Consume a throttle allowance, counting local rejections in the statistics
+    async fn check_throttle(&self, market: &str, kind: MessageKind) -> Result<()> {
+        if let Err(e) = self.throttle.check(market, kind) {
+            warn!("🛑 {}", e);
+            self.update_statistics(|stats| {
+                stats.throttled_messages += 1;
+            }).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
     
     /// Get current statistics (thread-safe)
     pub async fn get_statistics(&self) -> OrderStatistics {
@@ -250,6 +368,20 @@ impl OrderManager {
         state.active_orders.values().cloned().collect()
     }
     
+    /// Take over tracking of orders another instance placed (failover handover);
+    /// returns how many weren't tracked yet
+    pub async fn adopt_orders(&self, orders: Vec<EnhancedOrder>) -> usize {
+        let mut state = self.state.write().await;
+        let mut adopted = 0;
+        for order in orders {
+            if !state.active_orders.contains_key(&order.id) {
+                state.active_orders.insert(order.id.clone(), order);
+                adopted += 1;
+            }
+        }
+        adopted
+    }
+    
     /// Add order to active tracking
     async fn track_order(&self, order: EnhancedOrder) {
         let mut state = self.state.write().await;
@@ -270,95 +402,27 @@ impl OrderManager {
         update_fn(&mut state.statistics);
     }
 
-    /// Fetch orders directly from the Polymarket API using HTTP authentication
+    /// Fetch the account's orders from the Polymarket API, following every page
     pub async fn fetch_orders(
         &self,
         host: &str,
         data_paths: &crate::data_paths::DataPaths,
         user_address: &str,
     ) -> Result<Vec<EnhancedOrder>> {
-        use crate::config;
-        use crate::core::portfolio::api::orders::build_auth_headers;
-        use anyhow::anyhow;
+        use crate::core::portfolio::api::orders::{fetch_all_orders, OrderFilter};
 
         info!(
             "Fetching orders from Polymarket API for user: {}",
             user_address
         );
 
-        // Load credentials
-        let api_creds = config::load_credentials(data_paths)
-            .await
-            .map_err(|e| anyhow!("No credentials found. Run 'cargo run -- init' first: {}", e))?;
-
-        // Build the API URL
-        let api_url = format!("{}/data/orders", host.trim_end_matches('/'));
-        info!("Fetching orders from: {}", api_url);
-
-        // Build authentication headers
-        let headers = build_auth_headers(
-            &api_creds.api_key,
-            &api_creds.secret,
-            &api_creds.passphrase,
-            user_address,
-            "GET",
-            "/data/orders",
-            None,
-        )?;
-
-        // Create HTTP client and make request
-        let client = reqwest::Client::new();
-        let response = client
-            .get(&api_url)
-            .headers(headers)
-            .send()
-            .await
-            .map_err(|e| anyhow!("Failed to send request: {}", e))?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "No error details".to_string());
-            return Err(anyhow!(
-                "API request failed with status {}: {}",
-                status,
-                error_text
-            ));
-        }
-
-        // Get the response text
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| anyhow!("Failed to get response text: {}", e))?;
-
-        // Parse as API response object
-        #[derive(serde::Deserialize)]
-        struct ApiResponse {
-            data: Vec<crate::core::portfolio::api::orders::PolymarketOrder>,
-            #[allow(dead_code)]
-            next_cursor: Option<String>,
-            #[allow(dead_code)]
-            limit: u32,
-            #[allow(dead_code)]
-            count: u32,
-        }
-
-        let api_response: ApiResponse = serde_json::from_str(&response_text).map_err(|e| {
-            anyhow!(
-                "Failed to parse response JSON: {}. Response was: {}",
-                e,
-                response_text
-            )
-        })?;
+        let orders =
+            fetch_all_orders(host, data_paths, user_address, &OrderFilter::default()).await?;
 
         // Convert PolymarketOrder to EnhancedOrder
-        let enhanced_orders: Vec<EnhancedOrder> = api_response
-            .data
+        let enhanced_orders: Vec<EnhancedOrder> = orders
             .into_iter()
-            .map(|poly_order| self.convert_polymarket_order_to_enhanced(poly_order))
+            .map(EnhancedOrder::from)
             .collect();
 
         info!(
@@ -368,125 +432,6 @@ impl OrderManager {
         Ok(enhanced_orders)
     }
 
-    /// Convert a PolymarketOrder to an EnhancedOrder
-    fn convert_polymarket_order_to_enhanced(
-        &self,
-        poly_order: crate::core::portfolio::api::orders::PolymarketOrder,
-    ) -> EnhancedOrder {
-        use chrono::{TimeZone, Utc};
-        use rust_decimal::prelude::ToPrimitive;
-
-        // Parse side
-        let side = match poly_order.side.as_str() {
-            "BUY" => OrderSide::Buy,
-            "SELL" => OrderSide::Sell,
-            _ => OrderSide::Buy, // Default fallback
-        };
-
-        // Parse status
-        let status = match poly_order.status.as_str() {
-            "OPEN" => OrderStatus::Open,
-            "FILLED" => OrderStatus::Filled,
-            "CANCELLED" => OrderStatus::Cancelled,
-            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
-            "REJECTED" => OrderStatus::Rejected,
-            "PENDING" => OrderStatus::Pending,
-            _ => OrderStatus::Open, // Default fallback
-        };
-
-        // Convert timestamps (Polymarket uses Unix timestamps in seconds)
-        let created_at = Utc
-            .timestamp_opt(poly_order.created_at as i64, 0)
-            .single()
-            .unwrap_or_else(|| Utc::now());
-
-        // Parse sizes
-        let size_matched = poly_order
-            .size_matched
-            .parse::<rust_decimal::Decimal>()
-            .unwrap_or_default();
-        let filled_size = size_matched.to_f64().unwrap_or(0.0);
-        let original_size = poly_order.size_structured.to_f64().unwrap_or(0.0);
-        let remaining_size = original_size - filled_size;
-
-        // Build additional fields map for extra data
-        let mut additional_fields = HashMap::new();
-        additional_fields.insert(
-            "market".to_string(),
-            serde_json::Value::String(poly_order.market.clone()),
-        );
-        additional_fields.insert(
-            "owner".to_string(),
-            serde_json::Value::String(poly_order.owner.clone()),
-        );
-        additional_fields.insert(
-            "outcome".to_string(),
-            serde_json::Value::String(poly_order.outcome.clone()),
-        );
-        additional_fields.insert(
-            "order_type".to_string(),
-            serde_json::Value::String(poly_order.order_type.clone()),
-        );
-        additional_fields.insert(
-            "expiration".to_string(),
-            serde_json::Value::String(poly_order.expiration.clone()),
-        );
-        additional_fields.insert(
-            "maker_address".to_string(),
-            serde_json::Value::String(poly_order.maker_address.clone()),
-        );
-
-        if let Some(fee_rate) = poly_order.fee_rate_bps {
-            additional_fields.insert(
-                "fee_rate_bps".to_string(),
-                serde_json::Value::Number(serde_json::Number::from(fee_rate)),
-            );
-        }
-
-        if let Some(condition_id) = poly_order.condition_id {
-            additional_fields.insert(
-                "condition_id".to_string(),
-                serde_json::Value::String(condition_id),
-            );
-        }
-
-        if let Some(question_id) = poly_order.question_id {
-            additional_fields.insert(
-                "question_id".to_string(),
-                serde_json::Value::String(question_id),
-            );
-        }
-
-        // Create market info
-        let market_info = Some(OrderMarketInfo {
-            market_question: Some(poly_order.market.clone()),
-            token_outcome: Some(poly_order.outcome.clone()),
-            market_price_at_order: None, // Not provided by API
-            spread_at_order: None,       // Not provided by API
-            liquidity_at_order: None,    // Not provided by API
-        });
-
-        EnhancedOrder {
-            id: poly_order.id,
-            asset_id: poly_order.asset_id,
-            side,
-            price: poly_order.price.to_f64().unwrap_or(0.0),
-            size: original_size,
-            original_size,
-            filled_size,
-            remaining_size,
-            status,
-            created_at,
-            updated_at: None,         // Not provided by API
-            filled_at: None,          // Not provided by API
-            cancelled_at: None,       // Not provided by API
-            fees_paid: None,          // Not provided by API
-            average_fill_price: None, // Not provided by API
-            market_info,
-            additional_fields,
-        }
-    }
-
     /// Place a buy order with comprehensive response handling (thread-safe)
     pub async fn place_buy_order(
         &self,
@@ -520,6 +465,7 @@ impl OrderManager {
         size: Decimal,
         side: OrderSide,
     ) -> Result<OrderPlacementResponse> {
+        crate::auth::ensure_writable("place orders")?;
         let placement_time = Utc::now();
 
         // Display order information
@@ -544,15 +490,48 @@ impl OrderManager {
             token_id: token_id.to_string(),
         };
 
+        // Refuse locally before anything reaches the exchange
+        let market = self.market_of(client, token_id).await;
+        self.check_throttle(&market, MessageKind::NewOrder).await?;
+        if let Some(chaos) = &self.chaos {
+            chaos.before_order(MessageKind::NewOrder).await?;
+        }
+
         // Update statistics (thread-safe)
         self.update_statistics(|stats| {
             stats.orders_placed += 1;
         }).await;
 
         // Create and post order
-        let response = client.create_and_post_order(&args).await?;
+        let response = match &self.order_signer {
+            Some(order_signer) => {
+                info!("   Signing with {}", order_signer.signer().describe());
+                order_signer
+                    .post_limit_order(token_id, args.side, price, size)
+                    .await
+            }
+            None => client.create_and_post_order(&args).await.map_err(anyhow::Error::from),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                audit_order_placed(token_id, &side, price, size, None, Err(e.to_string()));
+                return Err(e);
+            }
+        };
         let parsed_response =
             Self::parse_order_response(response, token_id, &side, price, size, placement_time)?;
+        audit_order_placed(
+            token_id,
+            &side,
+            price,
+            size,
+            parsed_response.order_id.as_deref(),
+            match &parsed_response.error_message {
+                Some(message) if !parsed_response.success => Err(message.clone()),
+                _ => Ok(()),
+            },
+        );
 
         // Update statistics based on result (thread-safe)
         let volume = size.to_f64().unwrap_or(0.0);
@@ -603,14 +582,50 @@ impl OrderManager {
         client: &mut ClobClient,
         order_id: &str,
     ) -> Result<OrderCancellationResponse> {
+        crate::auth::ensure_writable("cancel orders")?;
         let cancellation_time = Utc::now();
 
         info!("🚫 Cancelling order {}...", order_id);
 
-        // Cancel the order
-        let response = client.cancel(order_id).await?;
+        // Cancels are throttled per market too; orders placed elsewhere share one bucket
+        let token_id = {
+            let state = self.state.read().await;
+            state.active_orders.get(order_id).map(|o| o.asset_id.clone())
+        };
+        let market = match token_id {
+            Some(token_id) => self.market_of(client, &token_id).await,
+            None => UNTRACKED_MARKET.to_string(),
+        };
+        self.check_throttle(&market, MessageKind::Cancel).await?;
+        if let Some(chaos) = &self.chaos {
+            chaos.before_order(MessageKind::Cancel).await?;
+        }
+
+        // Cancel with the L2 credentials of whichever client holds the key
+        let response = match &self.order_signer {
+            Some(order_signer) => order_signer.cancel_order(order_id).await,
+            None => client.cancel(order_id).await.map_err(anyhow::Error::from),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                audit_order_cancelled(order_id, Err(e.to_string()));
+                return Err(e);
+            }
+        };
         let parsed_response =
             Self::parse_cancellation_response(response, order_id, cancellation_time)?;
+        audit_order_cancelled(
+            order_id,
+            if parsed_response.success {
+                Ok(())
+            } else {
+                Err(parsed_response
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| "not cancelled".to_string()))
+            },
+        );
 
         // Update statistics (thread-safe)
         self.update_statistics(|stats| {
@@ -628,27 +643,38 @@ impl OrderManager {
         Ok(parsed_response)
     }
 
-    /// List orders with enhanced filtering and typing
+    /// List the account's live orders, fetched with its L2 credentials
     pub async fn list_orders(
         &self,
-        client: ClobClient,
+        host: &str,
+        data_paths: &crate::data_paths::DataPaths,
         filters: OrderFilters,
     ) -> Result<OrderListResponse> {
         let query_time = Utc::now();
 
         info!("📋 Fetching open orders...");
 
-        // Fetch orders from API
-        let raw_orders = client.get_orders(None, None).await?;
-
-        // Convert directly to enhanced orders (skip JSON conversion due to external type constraints)
-        let enhanced_orders = self.convert_raw_orders_to_enhanced(raw_orders);
+        let user_address = crate::auth::account_address(data_paths).await?;
+        let orders = match self.fetch_orders(host, data_paths, &user_address).await {
+            Ok(orders) => {
+                record_event(Subsystem::Orders, format!("listed {} orders", orders.len()));
+                orders
+            }
+            Err(e) => {
+                record_event(Subsystem::Orders, format!("list orders failed: {}", e));
+                return Err(e);
+            }
+        };
+        let open_orders: Vec<EnhancedOrder> = orders
+            .into_iter()
+            .filter(|order| order.status == OrderStatus::Open)
+            .collect();
 
         // Apply filters
-        let filtered_orders = self.apply_filters(&enhanced_orders, &filters);
+        let filtered_orders = self.apply_filters(&open_orders, &filters);
 
         let response = OrderListResponse {
-            total_count: enhanced_orders.len(),
+            total_count: open_orders.len(),
             filtered_count: filtered_orders.len(),
             orders: filtered_orders,
             query_time,
@@ -722,14 +748,22 @@ impl OrderManager {
         order_id: &str,
         cancellation_time: DateTime<Utc>,
     ) -> Result<OrderCancellationResponse> {
-        let success = response
-            .get("success")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+        // DELETE /order lists `canceled` ids and a `not_canceled` id → reason map
+        let canceled = response
+            .get("canceled")
+            .and_then(|v| v.as_array())
+            .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(order_id)));
+        let success = canceled
+            || response
+                .get("success")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
 
         let error_message = if !success {
             response
-                .get("errorMsg")
+                .get("not_canceled")
+                .and_then(|reasons| reasons.get(order_id))
+                .or_else(|| response.get("errorMsg"))
                 .and_then(|v| v.as_str())
                 .map(String::from)
                 .or_else(|| Some("Unknown error occurred".to_string()))
@@ -747,18 +781,6 @@ impl OrderManager {
         })
     }
 
-    /// Convert external order types directly to enhanced orders
-    fn convert_raw_orders_to_enhanced(
-        &self,
-        raw_orders: Vec<impl std::fmt::Debug>,
-    ) -> Vec<EnhancedOrder> {
-        // Note: External OpenOrder type doesn't implement Serialize
-        // Would need to access fields directly from the polymarket_rs_client::Order type
-        warn!("⚠️  Order conversion temporarily disabled due to external type constraints");
-        info!("   Found {} raw orders from API", raw_orders.len());
-        Vec::new()
-    }
-
     /// Apply filters to order list
     fn apply_filters(
         &self,
@@ -949,6 +971,46 @@ impl OrderManager {
     }
 }
 
+fn audit_order_placed(
+    token_id: &str,
+    side: &OrderSide,
+    price: Decimal,
+    size: Decimal,
+    order_id: Option<&str>,
+    outcome: std::result::Result<(), String>,
+) {
+    let summary = format!("{:?} {} @ {} on {}", side, size, price, token_id);
+    record_event(Subsystem::Orders, match &outcome {
+        Ok(()) => format!("placed {} ({})", summary, order_id.unwrap_or("no id")),
+        Err(e) => format!("place {} failed: {}", summary, e),
+    });
+    audit::record(
+        AuditAction::OrderPlaced,
+        summary,
+        serde_json::json!({
+            "token_id": token_id,
+            "side": format!("{:?}", side),
+            "price": price.to_string(),
+            "size": size.to_string(),
+            "order_id": order_id,
+        }),
+        outcome,
+    );
+}
+
+fn audit_order_cancelled(order_id: &str, outcome: std::result::Result<(), String>) {
+    record_event(Subsystem::Orders, match &outcome {
+        Ok(()) => format!("cancelled {}", order_id),
+        Err(e) => format!("cancel {} failed: {}", order_id, e),
+    });
+    audit::record(
+        AuditAction::OrderCancelled,
+        format!("cancel {}", order_id),
+        serde_json::json!({ "order_id": order_id }),
+        outcome,
+    );
+}
+
 /// Legacy function wrappers for backward compatibility
 /// Note: These create new manager instances and don't maintain state
 #[allow(dead_code)]
@@ -987,13 +1049,17 @@ pub async fn cancel_order(client: &mut ClobClient, order_id: &str) -> Result<()>
 }
 
 #[allow(dead_code)]
-pub async fn list_orders(client: ClobClient, token_id: Option<String>) -> Result<()> {
+pub async fn list_orders(
+    host: &str,
+    data_paths: &crate::data_paths::DataPaths,
+    token_id: Option<String>,
+) -> Result<()> {
     let manager = OrderManager::new();
     let filters = OrderFilters {
         token_id,
         ..Default::default()
     };
-    manager.list_orders(client, filters).await?;
+    manager.list_orders(host, data_paths, filters).await?;
     Ok(())
 }
 
@@ -0,0 +1,50 @@
+//! The path every order placed outside a running strategy takes
+//!
+//! [`OrderRoute`] pairs the [`OrderManager`] with the CLOB client it posts
+//! through, so bracket legs and scheduled orders get the same risk limits,
+//! message throttles, hardware signers and audit log as `polybot buy`/`sell`.
+//! [`PolybotClient::route`](crate::PolybotClient::route) and the daemon's
+//! strategy host both hand one out.
+
+use anyhow::Result;
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use tokio::sync::Mutex;
+
+use super::orders::OrderManager;
+use crate::core::types::common::Side;
+
+/// Borrowed order manager and CLOB client
+#[derive(Clone, Copy)]
+pub struct OrderRoute<'a> {
+    orders: &'a OrderManager,
+    clob: &'a Mutex<ClobClient>,
+}
+
+impl<'a> OrderRoute<'a> {
+    pub fn new(orders: &'a OrderManager, clob: &'a Mutex<ClobClient>) -> Self {
+        Self { orders, clob }
+    }
+
+    /// Place a limit order; returns the exchange's order id, or the rejection as an error
+    pub async fn place(
+        &self,
+        side: Side,
+        token_id: &str,
+        price: Decimal,
+        size: Decimal,
+    ) -> Result<String> {
+        let mut clob = self.clob.lock().await;
+        let response = match side {
+            Side::Buy => self.orders.place_buy_order(&mut clob, token_id, price, size).await?,
+            Side::Sell => self.orders.place_sell_order(&mut clob, token_id, price, size).await?,
+        };
+        response.into_order_id()
+    }
+
+    /// Cancel an order; `Ok` only when the exchange confirmed the cancel
+    pub async fn cancel(&self, order_id: &str) -> Result<()> {
+        let mut clob = self.clob.lock().await;
+        self.orders.cancel_order(&mut clob, order_id).await?.confirmed()
+    }
+}
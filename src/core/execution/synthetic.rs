@@ -0,0 +1,661 @@
+//! Synthetic instruments: weighted combinations of token prices
+//!
+//! A synthetic instrument holds two or more tokens with a weight each, such as
+//! candidate A's YES minus the YES of A winning the primary (`A:1`,
+//! `primary:-1`). Definitions are kept in `synthetics.json` in the profile's
+//! data directory, with optional lower and upper bounds on the price.
+//!
+//! Its book is built from the legs' books. Buying one unit buys every
+//! positive-weight leg at its asks and sells every negative-weight leg at its
+//! bids, so a synthetic ask is the weighted sum of those prices; selling one
+//! unit does the opposite. Each synthetic level holds the units every leg can
+//! fill at its current level, so deeper levels walk all the legs' books.
+//!
+//! [`SyntheticMonitor`] keeps a price series of each instrument's mid and
+//! raises an alert when the mid leaves its bounds, clearing it when the mid
+//! comes back, so a synthetic that stays out of bounds is reported once.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::core::token_notes::AlertDirection;
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::OrderBook;
+use crate::data_paths::DataPaths;
+
+pub const SYNTHETICS_FILE: &str = "synthetics.json";
+
+/// Smallest size a synthetic level holds; leg sizes left below their share
+/// of it are dropped, so walking the books always moves on
+const LOT_DECIMALS: u32 = 2;
+
+/// One token of a synthetic instrument and how many of it one unit holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticLeg {
+    pub token_id: String,
+    /// Negative weights are held short
+    pub weight: Decimal,
+}
+
+impl FromStr for SyntheticLeg {
+    type Err = String;
+
+    /// Parse `<token>[:<weight>]`, the weight defaulting to 1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (token_id, weight) = match s.split_once(':') {
+            Some((token_id, weight)) => (
+                token_id.trim(),
+                weight
+                    .trim()
+                    .parse::<Decimal>()
+                    .map_err(|_| format!("Invalid weight in leg '{}'", s))?,
+            ),
+            None => (s.trim(), Decimal::ONE),
+        };
+        if token_id.is_empty() {
+            return Err(format!("Leg '{}' has no token id", s));
+        }
+        if weight.is_zero() {
+            return Err(format!("Leg '{}' has a zero weight", s));
+        }
+        Ok(Self {
+            token_id: token_id.to_string(),
+            weight,
+        })
+    }
+}
+
+/// A named weighted combination of tokens
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticInstrument {
+    pub name: String,
+    pub legs: Vec<SyntheticLeg>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lower: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper: Option<Decimal>,
+}
+
+impl SyntheticInstrument {
+    pub fn new(
+        name: &str,
+        legs: Vec<SyntheticLeg>,
+        lower: Option<Decimal>,
+        upper: Option<Decimal>,
+    ) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("A synthetic instrument needs a name");
+        }
+        if legs.len() < 2 {
+            bail!("A synthetic instrument needs at least two legs");
+        }
+        let tokens: BTreeSet<&str> = legs.iter().map(|leg| leg.token_id.as_str()).collect();
+        if tokens.len() != legs.len() {
+            bail!("Each token can only be one leg of a synthetic instrument");
+        }
+        if let (Some(lower), Some(upper)) = (lower, upper) {
+            if lower >= upper {
+                bail!("The lower bound must be below the upper bound");
+            }
+        }
+        Ok(Self {
+            name: name.to_string(),
+            legs,
+            lower,
+            upper,
+        })
+    }
+
+    /// Legs as a formula, e.g. "+1×12345678… −1×87654321…"
+    pub fn formula(&self) -> String {
+        self.legs
+            .iter()
+            .map(|leg| {
+                let sign = if leg.weight < Decimal::ZERO {
+                    '−'
+                } else {
+                    '+'
+                };
+                format!(
+                    "{}{}×{}",
+                    sign,
+                    leg.weight.abs().normalize(),
+                    short_token(&leg.token_id)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Bounds as shown next to an instrument, e.g. "≥ -0.05 / ≤ 0.05"
+    pub fn bounds_label(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(lower) = self.lower {
+            parts.push(format!("≥ {}", lower));
+        }
+        if let Some(upper) = self.upper {
+            parts.push(format!("≤ {}", upper));
+        }
+        parts.join(" / ")
+    }
+
+    /// The bound `price` is outside of, if any
+    pub fn breach(&self, price: Decimal) -> Option<Breach> {
+        if let Some(upper) = self.upper.filter(|upper| price > *upper) {
+            return Some(Breach {
+                direction: AlertDirection::Above,
+                price: upper,
+            });
+        }
+        self.lower.filter(|lower| price < *lower).map(|lower| Breach {
+            direction: AlertDirection::Below,
+            price: lower,
+        })
+    }
+
+    /// Build the instrument's book, at most `depth` levels a side, from the
+    /// legs' books
+    pub fn book(&self, books: &HashMap<String, OrderBook>, depth: usize) -> SyntheticBook {
+        let missing: Vec<String> = self
+            .legs
+            .iter()
+            .filter(|leg| !books.contains_key(&leg.token_id))
+            .map(|leg| leg.token_id.clone())
+            .collect();
+        if !missing.is_empty() {
+            return SyntheticBook {
+                missing,
+                ..Default::default()
+            };
+        }
+
+        // Buying a unit takes positive legs' asks and negative legs' bids
+        let side_levels = |buying: bool| -> Vec<LegLevels> {
+            self.legs
+                .iter()
+                .map(|leg| {
+                    let book = &books[&leg.token_id];
+                    let levels = if buying == (leg.weight > Decimal::ZERO) {
+                        book.get_asks()
+                    } else {
+                        book.get_bids()
+                    };
+                    LegLevels {
+                        weight: leg.weight,
+                        levels,
+                    }
+                })
+                .collect()
+        };
+        SyntheticBook {
+            bids: LegLevels::walk(side_levels(false), depth),
+            asks: LegLevels::walk(side_levels(true), depth),
+            missing,
+        }
+    }
+}
+
+/// One side of a leg's book, best first, and the leg's weight
+struct LegLevels {
+    weight: Decimal,
+    levels: Vec<PriceLevel>,
+}
+
+impl LegLevels {
+    /// Combine the legs' levels into synthetic levels
+    ///
+    /// Units are rounded down to the lot size; a leg whose level has less
+    /// than one lot's worth left moves to its next level, so rounding dust
+    /// (a weight of 3 against a size of 1 leaves 1e-28) can't stall the walk.
+    fn walk(legs: Vec<LegLevels>, depth: usize) -> Vec<PriceLevel> {
+        let lot = Decimal::new(1, LOT_DECIMALS);
+        let mut cursors = vec![0usize; legs.len()];
+        let mut remaining: Vec<Decimal> = legs
+            .iter()
+            .map(|leg| leg.levels.first().map(|level| level.size).unwrap_or_default())
+            .collect();
+        let mut synthetic: Vec<PriceLevel> = Vec::new();
+
+        loop {
+            if legs
+                .iter()
+                .zip(&cursors)
+                .any(|(leg, cursor)| *cursor >= leg.levels.len())
+            {
+                break;
+            }
+            let units = legs
+                .iter()
+                .zip(&remaining)
+                .map(|(leg, size)| *size / leg.weight.abs())
+                .min()
+                .unwrap_or_default()
+                .round_dp_with_strategy(LOT_DECIMALS, RoundingStrategy::ToZero);
+
+            if units > Decimal::ZERO {
+                let price: Decimal = legs
+                    .iter()
+                    .zip(&cursors)
+                    .map(|(leg, cursor)| leg.weight * leg.levels[*cursor].price)
+                    .sum();
+                if let Some(last) = synthetic.last_mut().filter(|last| last.price == price) {
+                    last.size += units;
+                } else if synthetic.len() == depth {
+                    break;
+                } else {
+                    synthetic.push(PriceLevel::new(price, units));
+                }
+            }
+
+            for (index, leg) in legs.iter().enumerate() {
+                remaining[index] -= units * leg.weight.abs();
+                if remaining[index] < lot * leg.weight.abs() {
+                    cursors[index] += 1;
+                    remaining[index] = leg
+                        .levels
+                        .get(cursors[index])
+                        .map(|level| level.size)
+                        .unwrap_or_default();
+                }
+            }
+        }
+        synthetic
+    }
+}
+
+/// The book of a synthetic instrument
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyntheticBook {
+    /// Best first
+    pub bids: Vec<PriceLevel>,
+    /// Best first
+    pub asks: Vec<PriceLevel>,
+    /// Legs without a book yet; the book is empty until every leg has one
+    pub missing: Vec<String>,
+}
+
+impl SyntheticBook {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_bid()? + self.best_ask()?) / Decimal::TWO)
+    }
+}
+
+/// A point of a synthetic price series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticPoint {
+    pub at: DateTime<Utc>,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+    pub mid: Option<Decimal>,
+}
+
+impl SyntheticPoint {
+    fn same_quote(&self, other: &SyntheticPoint) -> bool {
+        (self.bid, self.ask) == (other.bid, other.ask)
+    }
+}
+
+/// The bound a synthetic's price is outside of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breach {
+    pub direction: AlertDirection,
+    /// The bound's price
+    pub price: Decimal,
+}
+
+/// A synthetic's mid leaving or returning within its bounds
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticAlert {
+    pub name: String,
+    pub at: DateTime<Utc>,
+    pub direction: AlertDirection,
+    pub bound: Decimal,
+    pub mid: Decimal,
+    /// False when the mid came back within bounds
+    pub raised: bool,
+}
+
+impl fmt::Display for SyntheticAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.raised {
+            write!(
+                f,
+                "{} is {} its bound {} at {}",
+                self.name, self.direction, self.bound, self.mid
+            )
+        } else {
+            write!(
+                f,
+                "{} is back within bounds at {} (was {} {})",
+                self.name, self.mid, self.direction, self.bound
+            )
+        }
+    }
+}
+
+/// What changed in one [`SyntheticMonitor::update`]
+#[derive(Debug, Default)]
+pub struct SyntheticUpdate {
+    /// Instruments whose top of book moved
+    pub changed: Vec<String>,
+    pub alerts: Vec<SyntheticAlert>,
+}
+
+#[derive(Debug, Default)]
+struct InstrumentState {
+    book: SyntheticBook,
+    series: VecDeque<SyntheticPoint>,
+    breach: Option<Breach>,
+}
+
+/// Tracks the books, price series and bound alerts of synthetic instruments
+pub struct SyntheticMonitor {
+    instruments: Vec<SyntheticInstrument>,
+    states: HashMap<String, InstrumentState>,
+    depth: usize,
+    series_len: usize,
+}
+
+impl SyntheticMonitor {
+    /// Keep `depth` levels of each book and the last `series_len` price points
+    pub fn new(instruments: Vec<SyntheticInstrument>, depth: usize, series_len: usize) -> Self {
+        Self {
+            instruments,
+            states: HashMap::new(),
+            depth,
+            series_len: series_len.max(1),
+        }
+    }
+
+    pub fn instruments(&self) -> &[SyntheticInstrument] {
+        &self.instruments
+    }
+
+    /// Every leg token of every instrument
+    pub fn tokens(&self) -> BTreeSet<String> {
+        self.instruments
+            .iter()
+            .flat_map(|instrument| instrument.legs.iter().map(|leg| leg.token_id.clone()))
+            .collect()
+    }
+
+    /// Rebuild every instrument's book from the legs' books
+    pub fn update(
+        &mut self,
+        books: &HashMap<String, OrderBook>,
+        now: DateTime<Utc>,
+    ) -> SyntheticUpdate {
+        let mut update = SyntheticUpdate::default();
+        for instrument in &self.instruments {
+            let state = self.states.entry(instrument.name.clone()).or_default();
+            state.book = instrument.book(books, self.depth);
+            let point = SyntheticPoint {
+                at: now,
+                bid: state.book.best_bid(),
+                ask: state.book.best_ask(),
+                mid: state.book.mid(),
+            };
+            if state
+                .series
+                .back()
+                .is_some_and(|last| last.same_quote(&point))
+            {
+                continue;
+            }
+            if state.series.len() == self.series_len {
+                state.series.pop_front();
+            }
+            state.series.push_back(point.clone());
+            update.changed.push(instrument.name.clone());
+
+            let Some(mid) = point.mid else {
+                continue;
+            };
+            let breach = instrument.breach(mid);
+            // A new or different breach raises an alert, leaving the bounds clears it
+            let alert = match (state.breach, breach) {
+                (was, Some(crossed)) if was != Some(crossed) => Some(SyntheticAlert {
+                    name: instrument.name.clone(),
+                    at: now,
+                    direction: crossed.direction,
+                    bound: crossed.price,
+                    mid,
+                    raised: true,
+                }),
+                (Some(cleared), None) => Some(SyntheticAlert {
+                    name: instrument.name.clone(),
+                    at: now,
+                    direction: cleared.direction,
+                    bound: cleared.price,
+                    mid,
+                    raised: false,
+                }),
+                _ => None,
+            };
+            state.breach = breach;
+            update.alerts.extend(alert);
+        }
+        update
+    }
+
+    pub fn book(&self, name: &str) -> Option<&SyntheticBook> {
+        self.states.get(name).map(|state| &state.book)
+    }
+
+    /// Price points of an instrument, oldest first
+    pub fn series(&self, name: &str) -> impl Iterator<Item = &SyntheticPoint> {
+        self.states
+            .get(name)
+            .into_iter()
+            .flat_map(|state| state.series.iter())
+    }
+
+    pub fn latest(&self, name: &str) -> Option<&SyntheticPoint> {
+        self.states.get(name)?.series.back()
+    }
+
+    /// The bound an instrument's mid is outside of
+    pub fn breach(&self, name: &str) -> Option<Breach> {
+        self.states.get(name)?.breach
+    }
+}
+
+/// Instrument definitions, kept as JSON in the data directory
+pub struct SyntheticStore {
+    path: PathBuf,
+}
+
+impl SyntheticStore {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            path: data_paths.data().join(SYNTHETICS_FILE),
+        }
+    }
+
+    /// Instruments ordered by name
+    pub fn load(&self) -> Result<Vec<SyntheticInstrument>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", self.path.display()))
+    }
+
+    pub fn save(&self, instruments: &[SyntheticInstrument]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut instruments = instruments.to_vec();
+        instruments.sort_by(|a, b| a.name.cmp(&b.name));
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&instruments)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Load the named instruments, or all of them when `names` is empty
+    pub fn select(&self, names: &[String]) -> Result<Vec<SyntheticInstrument>> {
+        let instruments = self.load()?;
+        if names.is_empty() {
+            return Ok(instruments);
+        }
+        names
+            .iter()
+            .map(|name| {
+                instruments
+                    .iter()
+                    .find(|instrument| instrument.name == *name)
+                    .cloned()
+                    .with_context(|| format!("No synthetic instrument named '{}'", name))
+            })
+            .collect()
+    }
+}
+
+fn short_token(token_id: &str) -> String {
+    if token_id.len() > 10 {
+        format!("{}…", &token_id[..10])
+    } else {
+        token_id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn book(asset_id: &str, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> OrderBook {
+        let levels = |levels: &[(Decimal, Decimal)]| {
+            levels
+                .iter()
+                .map(|(price, size)| PriceLevel::new(*price, *size))
+                .collect()
+        };
+        let mut book = OrderBook::new(asset_id.to_string());
+        book.replace_with_snapshot_no_hash("m".to_string(), 0, levels(bids), levels(asks));
+        book
+    }
+
+    #[test]
+    fn spread_book_walks_both_legs_and_alerts_on_bounds() {
+        let spread = SyntheticInstrument::new(
+            "a-vs-primary",
+            vec!["a".parse().unwrap(), "p:-1".parse().unwrap()],
+            Some(dec!(-0.05)),
+            Some(dec!(0.05)),
+        )
+        .unwrap();
+        assert!(SyntheticInstrument::new("x", vec!["a".parse().unwrap()], None, None).is_err());
+        assert!("p:0".parse::<SyntheticLeg>().is_err());
+
+        let mut books = HashMap::new();
+        books.insert(
+            "a".to_string(),
+            book(
+                "a",
+                &[(dec!(0.40), dec!(100))],
+                &[(dec!(0.42), dec!(50)), (dec!(0.43), dec!(100))],
+            ),
+        );
+        let mut monitor = SyntheticMonitor::new(vec![spread], 5, 10);
+        let update = monitor.update(&books, Utc::now());
+        assert_eq!(monitor.book("a-vs-primary").unwrap().missing, ["p"]);
+        assert_eq!(update.changed, ["a-vs-primary"]);
+
+        books.insert(
+            "p".to_string(),
+            book(
+                "p",
+                &[(dec!(0.39), dec!(80)), (dec!(0.38), dec!(100))],
+                &[(dec!(0.41), dec!(100))],
+            ),
+        );
+        let update = monitor.update(&books, Utc::now());
+        assert!(update.alerts.is_empty());
+        let synthetic = monitor.book("a-vs-primary").unwrap();
+        // Buying: a's asks less p's bids, 50 units at the first a level
+        assert_eq!(
+            synthetic.asks,
+            vec![
+                PriceLevel::new(dec!(0.03), dec!(50)),
+                PriceLevel::new(dec!(0.04), dec!(30)),
+                PriceLevel::new(dec!(0.05), dec!(70)),
+            ]
+        );
+        assert_eq!(
+            synthetic.bids,
+            vec![PriceLevel::new(dec!(-0.01), dec!(100))]
+        );
+        assert_eq!(synthetic.mid(), Some(dec!(0.01)));
+
+        // p's bid collapsing pushes the spread above its upper bound, once
+        books.insert(
+            "p".to_string(),
+            book("p", &[(dec!(0.30), dec!(80))], &[(dec!(0.31), dec!(100))]),
+        );
+        let alerts = monitor.update(&books, Utc::now()).alerts;
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].raised && alerts[0].direction == AlertDirection::Above);
+        books.get_mut("p").unwrap().set_tick_size(dec!(0.01));
+        assert!(monitor.update(&books, Utc::now()).alerts.is_empty());
+
+        books.insert(
+            "p".to_string(),
+            book("p", &[(dec!(0.39), dec!(80))], &[(dec!(0.41), dec!(100))]),
+        );
+        let alerts = monitor.update(&books, Utc::now()).alerts;
+        assert!(!alerts[0].raised);
+        assert_eq!(monitor.series("a-vs-primary").count(), 4);
+    }
+
+    #[test]
+    fn weights_that_dont_divide_level_sizes_still_walk_the_book() {
+        let weighted = SyntheticInstrument::new(
+            "three-a",
+            vec!["a:3".parse().unwrap(), "p:-1".parse().unwrap()],
+            None,
+            None,
+        )
+        .unwrap();
+        let mut books = HashMap::new();
+        books.insert(
+            "a".to_string(),
+            book(
+                "a",
+                &[(dec!(0.09), dec!(1.0))],
+                &[(dec!(0.10), dec!(1.0)), (dec!(0.11), dec!(1.0))],
+            ),
+        );
+        books.insert(
+            "p".to_string(),
+            book("p", &[(dec!(0.20), dec!(100))], &[(dec!(0.21), dec!(100))]),
+        );
+
+        // 1.0 / 3 rounds down to 0.33 units; the 0.01 left of each a level is dropped
+        let synthetic = weighted.book(&books, 10);
+        assert_eq!(
+            synthetic.asks,
+            vec![
+                PriceLevel::new(dec!(0.10), dec!(0.33)),
+                PriceLevel::new(dec!(0.13), dec!(0.33)),
+            ]
+        );
+        assert_eq!(synthetic.bids, vec![PriceLevel::new(dec!(0.06), dec!(0.33))]);
+    }
+}
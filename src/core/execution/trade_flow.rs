@@ -0,0 +1,318 @@
+//! Trade flow analysis: sweeps and large prints
+//!
+//! A sweep is a run of consecutive trades on one token and side, each within
+//! a short window of the previous one; it usually means one taker walking the
+//! book. A large print is a single trade whose size is above a percentile of
+//! that token's recent trade sizes. [`TradeFlowAnalyzer`] turns the public
+//! trade feed into [`TradeFlowEvent`]s for strategies and alerting.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::core::types::common::{ShortId, Side};
+
+/// How often callers run [`TradeFlowAnalyzer::flush_expired`], so a sweep is
+/// reported shortly after its window closes rather than on the next trade
+pub const CLOSE_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Thresholds of the trade flow analysis
+#[derive(Debug, Clone)]
+pub struct TradeFlowConfig {
+    /// Longest gap between two trades of the same sweep
+    pub sweep_window: Duration,
+    /// Trades a run needs before it is reported as a sweep
+    pub min_sweep_trades: usize,
+    /// Size percentile (0..1) a trade has to exceed to count as a large print
+    pub large_percentile: f64,
+    /// Trades of a token seen before large prints are flagged
+    pub min_history: usize,
+    /// Recent trade sizes kept per token for the percentile
+    pub history_len: usize,
+}
+
+impl Default for TradeFlowConfig {
+    fn default() -> Self {
+        Self {
+            sweep_window: Duration::from_millis(1500),
+            min_sweep_trades: 3,
+            large_percentile: 0.95,
+            min_history: 20,
+            history_len: 500,
+        }
+    }
+}
+
+/// Consecutive same-side trades reported as one sweep
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeCluster {
+    pub asset_id: String,
+    pub side: Side,
+    pub trades: usize,
+    pub size: Decimal,
+    /// Sum of price times size
+    pub notional: Decimal,
+    pub first_price: Decimal,
+    pub last_price: Decimal,
+    pub started_ms: u64,
+    pub ended_ms: u64,
+}
+
+impl TradeCluster {
+    /// Volume-weighted average price of the sweep
+    pub fn vwap(&self) -> Decimal {
+        if self.size.is_zero() {
+            self.last_price
+        } else {
+            self.notional / self.size
+        }
+    }
+}
+
+/// A single trade above the token's size percentile
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LargeTrade {
+    pub asset_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    /// Size at the configured percentile when the trade arrived
+    pub threshold: Decimal,
+    pub percentile: f64,
+    pub timestamp_ms: u64,
+}
+
+/// Output of the trade flow analysis
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TradeFlowEvent {
+    Sweep(TradeCluster),
+    LargeTrade(LargeTrade),
+}
+
+impl TradeFlowEvent {
+    pub fn asset_id(&self) -> &str {
+        match self {
+            TradeFlowEvent::Sweep(cluster) => &cluster.asset_id,
+            TradeFlowEvent::LargeTrade(trade) => &trade.asset_id,
+        }
+    }
+
+    /// One-line summary for logs and alert lists
+    pub fn describe(&self) -> String {
+        match self {
+            TradeFlowEvent::Sweep(c) => format!(
+                "{} sweep on {}: {} trades, {:.2} shares ${:.4} → ${:.4} (vwap ${:.4}) in {}ms",
+                c.side,
+                ShortId(&c.asset_id),
+                c.trades,
+                c.size,
+                c.first_price,
+                c.last_price,
+                c.vwap(),
+                c.ended_ms.saturating_sub(c.started_ms)
+            ),
+            TradeFlowEvent::LargeTrade(t) => format!(
+                "Large {} on {}: {:.2} shares @ ${:.4} (p{:.0} is {:.2})",
+                t.side,
+                ShortId(&t.asset_id),
+                t.size,
+                t.price,
+                t.percentile * 100.0,
+                t.threshold
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AssetFlow {
+    open: Option<TradeCluster>,
+    sizes: VecDeque<Decimal>,
+}
+
+/// Per-token sweep clustering and large print detection over a trade stream
+#[derive(Default)]
+pub struct TradeFlowAnalyzer {
+    config: TradeFlowConfig,
+    assets: HashMap<String, AssetFlow>,
+}
+
+impl TradeFlowAnalyzer {
+    pub fn new(config: TradeFlowConfig) -> Self {
+        Self {
+            config,
+            assets: HashMap::new(),
+        }
+    }
+
+    /// Feed one trade; returns sweeps it closed and whether it is a large print
+    pub fn observe(
+        &mut self,
+        asset_id: &str,
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+        timestamp_ms: u64,
+    ) -> Vec<TradeFlowEvent> {
+        let mut events = self.flush_expired(timestamp_ms);
+        let window_ms = self.config.sweep_window.as_millis() as u64;
+        let config = &self.config;
+        let flow = self.assets.entry(asset_id.to_string()).or_default();
+
+        // The size percentile is taken before this trade joins the history
+        if flow.sizes.len() >= config.min_history {
+            let threshold = percentile(&flow.sizes, config.large_percentile);
+            if size > threshold {
+                events.push(TradeFlowEvent::LargeTrade(LargeTrade {
+                    asset_id: asset_id.to_string(),
+                    side,
+                    price,
+                    size,
+                    threshold,
+                    percentile: config.large_percentile,
+                    timestamp_ms,
+                }));
+            }
+        }
+        flow.sizes.push_back(size);
+        while flow.sizes.len() > config.history_len {
+            flow.sizes.pop_front();
+        }
+
+        let extends = flow.open.as_ref().is_some_and(|open| {
+            open.side == side && timestamp_ms.saturating_sub(open.ended_ms) <= window_ms
+        });
+        if extends {
+            let open = flow.open.as_mut().expect("checked above");
+            open.trades += 1;
+            open.size += size;
+            open.notional += price * size;
+            open.last_price = price;
+            open.ended_ms = timestamp_ms;
+        } else {
+            if let Some(closed) = flow.open.take() {
+                events.extend(sweep(closed, config.min_sweep_trades));
+            }
+            flow.open = Some(TradeCluster {
+                asset_id: asset_id.to_string(),
+                side,
+                trades: 1,
+                size,
+                notional: price * size,
+                first_price: price,
+                last_price: price,
+                started_ms: timestamp_ms,
+                ended_ms: timestamp_ms,
+            });
+        }
+        events
+    }
+
+    /// Close runs whose last trade is more than the sweep window before `now_ms`
+    pub fn flush_expired(&mut self, now_ms: u64) -> Vec<TradeFlowEvent> {
+        let window_ms = self.config.sweep_window.as_millis() as u64;
+        let mut events = Vec::new();
+        for flow in self.assets.values_mut() {
+            let expired = flow
+                .open
+                .as_ref()
+                .is_some_and(|open| now_ms.saturating_sub(open.ended_ms) > window_ms);
+            if expired {
+                let closed = flow.open.take().expect("checked above");
+                events.extend(sweep(closed, self.config.min_sweep_trades));
+            }
+        }
+        events
+    }
+}
+
+fn sweep(cluster: TradeCluster, min_trades: usize) -> Option<TradeFlowEvent> {
+    (cluster.trades >= min_trades.max(2)).then_some(TradeFlowEvent::Sweep(cluster))
+}
+
+/// Nearest-rank percentile of `sizes`, `p` in 0..1
+fn percentile(sizes: &VecDeque<Decimal>, p: f64) -> Decimal {
+    let mut sorted: Vec<Decimal> = sizes.iter().copied().collect();
+    sorted.sort();
+    let rank = (p.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn analyzer() -> TradeFlowAnalyzer {
+        TradeFlowAnalyzer::new(TradeFlowConfig {
+            sweep_window: Duration::from_millis(100),
+            min_sweep_trades: 3,
+            large_percentile: 0.9,
+            min_history: 10,
+            history_len: 50,
+        })
+    }
+
+    #[test]
+    fn same_side_trades_within_the_window_form_a_sweep() {
+        let mut flow = analyzer();
+        assert!(flow
+            .observe("a", Side::Buy, dec!(0.50), dec!(10), 0)
+            .is_empty());
+        assert!(flow
+            .observe("a", Side::Buy, dec!(0.51), dec!(10), 80)
+            .is_empty());
+        assert!(flow
+            .observe("a", Side::Buy, dec!(0.52), dec!(20), 160)
+            .is_empty());
+
+        // The opposite side closes the run
+        let events = flow.observe("a", Side::Sell, dec!(0.49), dec!(5), 170);
+        let [TradeFlowEvent::Sweep(sweep)] = events.as_slice() else {
+            panic!("expected one sweep, got {:?}", events);
+        };
+        assert_eq!((sweep.trades, sweep.size), (3, dec!(40)));
+        assert_eq!(sweep.vwap(), dec!(20.5) / dec!(40));
+        assert_eq!((sweep.started_ms, sweep.ended_ms), (0, 160));
+
+        // A lone trade that ages out is no sweep
+        assert!(flow.flush_expired(1_000).is_empty());
+    }
+
+    #[test]
+    fn gaps_split_runs_and_flush_closes_them() {
+        let mut flow = analyzer();
+        for (i, ts) in [0, 50, 100, 400, 450].into_iter().enumerate() {
+            let events = flow.observe("a", Side::Sell, dec!(0.4), dec!(1), ts);
+            // The fourth trade comes after a gap and closes the first three
+            assert_eq!(events.len(), usize::from(i == 3));
+        }
+        assert!(flow.flush_expired(500).is_empty());
+        // Two trades are below the minimum
+        assert!(flow.flush_expired(600).is_empty());
+    }
+
+    #[test]
+    fn prints_above_the_percentile_are_large() {
+        let mut flow = analyzer();
+        for i in 0..10u64 {
+            let events = flow.observe("a", Side::Buy, dec!(0.5), Decimal::from(i + 1), i * 1_000);
+            assert!(events.is_empty(), "no history yet at trade {}", i);
+        }
+        let events = flow.observe("a", Side::Sell, dec!(0.5), dec!(50), 20_000);
+        let large: Vec<&LargeTrade> = events
+            .iter()
+            .filter_map(|e| match e {
+                TradeFlowEvent::LargeTrade(t) => Some(t),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(large.len(), 1);
+        assert_eq!(large[0].threshold, dec!(9));
+        assert!(flow
+            .observe("a", Side::Sell, dec!(0.5), dec!(5), 30_000)
+            .is_empty());
+    }
+}
@@ -0,0 +1,616 @@
+//! Warm standby and failover between daemon instances
+//!
+//! Daemons started with `--failover` in the same group elect one leader
+//! through a lease file in the profile's data directory, which works for
+//! instances on one host or on hosts sharing the directory. The leader
+//! renews its lease every few seconds; a standby keeps its stream warm and
+//! takes over once the lease has not been renewed for its TTL, or as soon as
+//! the leader releases it on shutdown.
+//!
+//! Every renewal also writes a handover: the hosted strategies with their
+//! parameters and the open orders the leader tracks. The next leader restarts
+//! those strategies and adopts the orders that are still open on the
+//! exchange, so ownership of resting orders passes explicitly from one
+//! instance to the next. Each new leader gets a higher term, and only a
+//! handover of the lease it replaced is taken over. A leader that finds
+//! another instance's lease on renewal, or when writing its handover, stops
+//! trading at once.
+//!
+//! ```text
+//! <data>/failover/<group>/lease.json       current leader and term
+//! <data>/failover/<group>/handover.json    strategies and open orders
+//! <data>/failover/<group>/standby/<id>.json  standby heartbeats
+//! ```
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::execution::orders::{EnhancedOrder, OrderStatus};
+use crate::core::file_lock::{run_blocking, FileLock, LOCK_TIMEOUT};
+use crate::data_paths::DataPaths;
+use crate::strategy::params::StrategyParams;
+
+pub const FAILOVER_DIR: &str = "failover";
+pub const DEFAULT_GROUP: &str = "daemon";
+const LEASE_FILE: &str = "lease.json";
+const HANDOVER_FILE: &str = "handover.json";
+const LOCK_FILE: &str = "lease.lock";
+const STANDBY_DIR: &str = "standby";
+
+/// A running daemon instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: String,
+    pub host: Option<String>,
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+}
+
+impl Instance {
+    /// This process, with a fresh id
+    pub fn current() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            host: std::env::var("HOSTNAME")
+                .ok()
+                .or_else(|| {
+                    fs::read_to_string("/etc/hostname")
+                        .ok()
+                        .map(|name| name.trim().to_string())
+                })
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .filter(|name| !name.is_empty()),
+            pid: std::process::id(),
+            started_at: Utc::now(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} (pid {} on {})",
+            self.id,
+            self.pid,
+            self.host.as_deref().unwrap_or("unknown host")
+        )
+    }
+}
+
+/// Leadership of a failover group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lease {
+    pub holder: Instance,
+    /// Incremented on every change of leader
+    pub term: u64,
+    pub acquired_at: DateTime<Utc>,
+    pub renewed_at: DateTime<Utc>,
+    pub ttl_ms: u64,
+    /// Given up on shutdown, free for a standby right away
+    pub released: bool,
+}
+
+impl Lease {
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.renewed_at + chrono::Duration::milliseconds(self.ttl_ms as i64)
+    }
+
+    /// Held and renewed within its TTL at `now`
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        !self.released && now < self.expires_at()
+    }
+}
+
+/// A standby's last sign of life
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandbyHeartbeat {
+    pub instance: Instance,
+    pub seen_at: DateTime<Utc>,
+}
+
+/// A strategy for the next leader to restart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoverStrategy {
+    pub id: String,
+    pub kind: String,
+    pub token_id: String,
+    pub params: StrategyParams,
+    pub running: bool,
+}
+
+/// What a leader hands to the next one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HandoverState {
+    pub next_strategy_id: u64,
+    pub strategies: Vec<HandoverStrategy>,
+    /// Orders the leader placed that were still resting
+    pub open_orders: Vec<EnhancedOrder>,
+}
+
+/// Handed over orders checked against the account's orders on the exchange
+#[derive(Debug, Clone, Default)]
+pub struct ReconciledOrders {
+    /// Still resting, as the exchange reports them now
+    pub open: Vec<EnhancedOrder>,
+    /// Ids of handed over orders that filled or were cancelled meanwhile
+    pub closed: Vec<String>,
+}
+
+impl ReconciledOrders {
+    /// Keep the `handed` orders that `live` (the exchange's orders) has open
+    pub fn between(handed: Vec<EnhancedOrder>, live: Vec<EnhancedOrder>) -> Self {
+        let mut live: HashMap<String, EnhancedOrder> = live
+            .into_iter()
+            .filter(|order| {
+                matches!(
+                    order.status,
+                    OrderStatus::Open | OrderStatus::PartiallyFilled
+                )
+            })
+            .map(|order| (order.id.clone(), order))
+            .collect();
+        let mut reconciled = Self::default();
+        for order in handed {
+            match live.remove(&order.id) {
+                Some(current) => reconciled.open.push(current),
+                None => reconciled.closed.push(order.id),
+            }
+        }
+        reconciled
+    }
+}
+
+/// A handover as written by a leader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handover {
+    /// The instance that owned the open orders
+    pub from: Instance,
+    pub term: u64,
+    pub written_at: DateTime<Utc>,
+    /// Written on shutdown rather than on a heartbeat
+    pub graceful: bool,
+    #[serde(flatten)]
+    pub state: HandoverState,
+}
+
+impl Handover {
+    /// Written under `previous`, the lease a new leader replaced; one left by
+    /// an older term describes orders and strategies its writer may no longer
+    /// have owned
+    pub fn is_of(&self, previous: Option<&Lease>) -> bool {
+        previous.is_some_and(|lease| lease.term == self.term)
+    }
+}
+
+/// Outcome of an election attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum Role {
+    /// This instance leads; `previous` is the lease it replaced
+    Leader { term: u64, previous: Option<Lease> },
+    /// Another instance holds a live lease
+    Standby { leader: Lease },
+}
+
+/// This instance's side of a failover group
+pub struct Election {
+    dir: PathBuf,
+    group: String,
+    instance: Instance,
+    ttl: Duration,
+    /// Term of the lease this instance holds
+    term: Option<u64>,
+}
+
+impl Election {
+    pub fn new(data_paths: &DataPaths, group: &str, ttl: Duration) -> Result<Self> {
+        Ok(Self::in_dir(group_dir(data_paths, group)?, group, ttl))
+    }
+
+    fn in_dir(dir: PathBuf, group: &str, ttl: Duration) -> Self {
+        Self {
+            dir,
+            group: group.to_string(),
+            instance: Instance::current(),
+            ttl,
+            term: None,
+        }
+    }
+
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    /// How often the lease should be renewed (and standbys check it)
+    pub fn heartbeat_interval(&self) -> Duration {
+        (self.ttl / 3).max(Duration::from_millis(100))
+    }
+
+    pub fn term(&self) -> Option<u64> {
+        self.term
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.term.is_some()
+    }
+
+    /// Take the lease if it is free, expired or released
+    pub fn try_acquire(&mut self) -> Result<Role> {
+        let role = with_lock(&self.dir, || {
+            let now = Utc::now();
+            let previous: Option<Lease> = read_json(&self.dir.join(LEASE_FILE))?;
+            if let Some(lease) = &previous {
+                if lease.is_live(now) && lease.holder.id != self.instance.id {
+                    return Ok(Role::Standby {
+                        leader: lease.clone(),
+                    });
+                }
+            }
+            // Keep the term of a lease this instance still holds
+            let term = match &previous {
+                Some(lease) if lease.holder.id == self.instance.id && !lease.released => lease.term,
+                Some(lease) => lease.term + 1,
+                None => 1,
+            };
+            let lease = Lease {
+                holder: self.instance.clone(),
+                term,
+                acquired_at: now,
+                renewed_at: now,
+                ttl_ms: self.ttl.as_millis() as u64,
+                released: false,
+            };
+            write_json(&self.dir.join(LEASE_FILE), &lease)?;
+            Ok(Role::Leader { term, previous })
+        })?;
+        if let Role::Leader { term, .. } = &role {
+            self.term = Some(*term);
+            let _ = fs::remove_file(self.standby_file());
+        }
+        Ok(role)
+    }
+
+    /// Extend the lease; false when another instance took it over, after
+    /// which this one must stop trading
+    pub fn renew(&mut self) -> Result<bool> {
+        let Some(term) = self.term else {
+            return Ok(false);
+        };
+        let renewed = with_lock(&self.dir, || {
+            let lease: Option<Lease> = read_json(&self.dir.join(LEASE_FILE))?;
+            match lease {
+                Some(mut lease)
+                    if lease.holder.id == self.instance.id
+                        && lease.term == term
+                        && !lease.released =>
+                {
+                    lease.renewed_at = Utc::now();
+                    write_json(&self.dir.join(LEASE_FILE), &lease)?;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        })?;
+        if !renewed {
+            self.term = None;
+        }
+        Ok(renewed)
+    }
+
+    /// Write `state` for the next leader; false, with nothing written, when
+    /// the lease turns out to be another instance's by now
+    pub fn save_handover(&mut self, state: &HandoverState) -> Result<bool> {
+        let Some(term) = self.term else {
+            bail!("Only the leader of '{}' writes handovers", self.group);
+        };
+        let saved = with_lock(&self.dir, || {
+            if !self.holds_lease(term)? {
+                return Ok(false);
+            }
+            self.write_handover(term, state, false)?;
+            Ok(true)
+        })?;
+        if !saved {
+            self.term = None;
+        }
+        Ok(saved)
+    }
+
+    /// Whether the lease on disk is still this instance's of `term`; call
+    /// holding the lock
+    fn holds_lease(&self, term: u64) -> Result<bool> {
+        let lease: Option<Lease> = read_json(&self.dir.join(LEASE_FILE))?;
+        Ok(lease.is_some_and(|lease| {
+            lease.holder.id == self.instance.id && lease.term == term && !lease.released
+        }))
+    }
+
+    fn write_handover(&self, term: u64, state: &HandoverState, graceful: bool) -> Result<()> {
+        let handover = Handover {
+            from: self.instance.clone(),
+            term,
+            written_at: Utc::now(),
+            graceful,
+            state: state.clone(),
+        };
+        write_json(&self.dir.join(HANDOVER_FILE), &handover)
+    }
+
+    /// Hand `state` over and release the lease so a standby takes over at once
+    pub fn release(&mut self, state: &HandoverState) -> Result<()> {
+        let Some(term) = self.term else {
+            return Ok(());
+        };
+        with_lock(&self.dir, || {
+            let lease: Option<Lease> = read_json(&self.dir.join(LEASE_FILE))?;
+            if let Some(mut lease) = lease {
+                if lease.holder.id == self.instance.id && lease.term == term {
+                    self.write_handover(term, state, true)?;
+                    lease.released = true;
+                    lease.renewed_at = Utc::now();
+                    write_json(&self.dir.join(LEASE_FILE), &lease)?;
+                }
+            }
+            Ok(())
+        })?;
+        self.term = None;
+        Ok(())
+    }
+
+    /// The latest handover of the group, from any earlier leader
+    pub fn handover(&self) -> Result<Option<Handover>> {
+        read_json(&self.dir.join(HANDOVER_FILE))
+    }
+
+
+
+    /// Record that this standby is alive, for `polybot failover status`
+    pub fn heartbeat_standby(&self) -> Result<()> {
+        let heartbeat = StandbyHeartbeat {
+            instance: self.instance.clone(),
+            seen_at: Utc::now(),
+        };
+        write_json(&self.standby_file(), &heartbeat)
+    }
+
+    /// Forget this instance's standby heartbeat (on shutdown)
+    pub fn leave(&self) {
+        let _ = fs::remove_file(self.standby_file());
+    }
+
+    fn standby_file(&self) -> PathBuf {
+        self.dir
+            .join(STANDBY_DIR)
+            .join(format!("{}.json", self.instance.id))
+    }
+}
+
+/// A failover group as found on disk
+#[derive(Debug, Clone)]
+pub struct GroupStatus {
+    pub group: String,
+    pub lease: Option<Lease>,
+    pub standbys: Vec<StandbyHeartbeat>,
+    pub handover: Option<Handover>,
+}
+
+/// Directory of `group`, refusing names that could point outside `failover/`
+pub fn group_dir(data_paths: &DataPaths, group: &str) -> Result<PathBuf> {
+    validate_group(group)?;
+    Ok(data_paths.root().join(FAILOVER_DIR).join(group))
+}
+
+/// Group names follow the profile name rules: letters, digits, '-' or '_'
+fn validate_group(group: &str) -> Result<()> {
+    let valid = !group.is_empty()
+        && group.len() <= 64
+        && group
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!("Invalid failover group '{}': use letters, digits, '-' or '_'", group);
+    }
+    Ok(())
+}
+
+/// Groups with a lease in the profile's data directory
+pub fn groups(data_paths: &DataPaths) -> Vec<String> {
+    let mut groups: Vec<String> = fs::read_dir(data_paths.root().join(FAILOVER_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().join(LEASE_FILE).exists())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Leader, standbys and handover of `group`
+pub fn status(data_paths: &DataPaths, group: &str) -> Result<GroupStatus> {
+    status_in(&group_dir(data_paths, group)?, group)
+}
+
+fn status_in(dir: &Path, group: &str) -> Result<GroupStatus> {
+    let mut standbys: Vec<StandbyHeartbeat> = fs::read_dir(dir.join(STANDBY_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| read_json(&entry.path()).ok().flatten())
+        .collect();
+    standbys.sort_by_key(|standby| standby.instance.started_at);
+    Ok(GroupStatus {
+        group: group.to_string(),
+        lease: read_json(&dir.join(LEASE_FILE))?,
+        standbys,
+        handover: read_json(&dir.join(HANDOVER_FILE))?,
+    })
+}
+
+/// Run `f` holding the group's lock file, so lease updates don't interleave
+fn with_lock<T>(dir: &Path, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let path = dir.join(LOCK_FILE);
+    let lock = run_blocking(|| FileLock::acquire_blocking(&path, LOCK_TIMEOUT))?;
+    let result = f();
+    drop(lock);
+    result
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Write through a temporary file, so readers never see half a file
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_names_stay_inside_the_failover_directory() {
+        let data_paths = DataPaths::new("/data");
+        assert_eq!(
+            group_dir(&data_paths, "daemon-2").unwrap(),
+            PathBuf::from("/data/failover/daemon-2")
+        );
+        for group in ["", "..", "../auth", "a/b", "/tmp"] {
+            assert!(group_dir(&data_paths, group).is_err(), "{:?}", group);
+        }
+        assert!(Election::new(&data_paths, "../x", Duration::from_secs(15)).is_err());
+    }
+
+    #[test]
+    fn standby_takes_over_released_and_expired_leases() {
+        let dir = tempfile::tempdir().unwrap();
+        let ttl = Duration::from_secs(60);
+        let mut primary = Election::in_dir(dir.path().to_path_buf(), "daemon", ttl);
+        let mut standby = Election::in_dir(dir.path().to_path_buf(), "daemon", ttl);
+
+        assert!(matches!(
+            primary.try_acquire().unwrap(),
+            Role::Leader {
+                term: 1,
+                previous: None
+            }
+        ));
+        assert!(matches!(
+            standby.try_acquire().unwrap(),
+            Role::Standby { .. }
+        ));
+        standby.heartbeat_standby().unwrap();
+        assert!(primary.renew().unwrap());
+        assert!(primary.save_handover(&HandoverState::default()).unwrap());
+
+        let state = HandoverState {
+            next_strategy_id: 3,
+            strategies: vec![HandoverStrategy {
+                id: "simple-2".to_string(),
+                kind: "simple".to_string(),
+                token_id: "123".to_string(),
+                params: StrategyParams::from([("tick_size".to_string(), 0.01)]),
+                running: true,
+            }],
+            open_orders: Vec::new(),
+        };
+        primary.release(&state).unwrap();
+        assert!(!primary.is_leader());
+
+        // A released lease is free right away, with a new term
+        let previous = match standby.try_acquire().unwrap() {
+            Role::Leader { term, previous } => {
+                assert_eq!(term, 2);
+                previous.unwrap()
+            }
+            other => panic!("unexpected role {:?}", other),
+        };
+        assert!(previous.released);
+        let handover = standby.handover().unwrap().unwrap();
+        assert!(handover.is_of(Some(&previous)));
+        assert!(handover.graceful);
+        assert_eq!(handover.from, *primary.instance());
+        assert_eq!(handover.state.strategies, state.strategies);
+
+        let status = status_in(dir.path(), "daemon").unwrap();
+        assert_eq!(status.lease.unwrap().holder, *standby.instance());
+        assert!(status.standbys.is_empty());
+
+        // A lease past its TTL is taken over, and its holder is fenced off
+        let mut short = Election::in_dir(dir.path().to_path_buf(), "daemon", Duration::ZERO);
+        standby.release(&HandoverState::default()).unwrap();
+        assert!(matches!(
+            short.try_acquire().unwrap(),
+            Role::Leader { term: 3, .. }
+        ));
+        // The handover on disk is term 2's, not of the expired term 3 lease
+        let previous = match primary.try_acquire().unwrap() {
+            Role::Leader { term: 4, previous } => previous.unwrap(),
+            other => panic!("unexpected role {:?}", other),
+        };
+        assert!(!primary.handover().unwrap().unwrap().is_of(Some(&previous)));
+        // The fenced off leader neither renews nor overwrites the handover
+        let fenced = short.term;
+        assert!(!short.renew().unwrap());
+        assert!(!short.is_leader());
+        short.term = fenced;
+        assert!(!short.save_handover(&state).unwrap());
+        assert!(!short.is_leader());
+        assert_eq!(primary.handover().unwrap().unwrap().term, 2);
+    }
+
+    fn order(id: &str, status: &str) -> EnhancedOrder {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "asset_id": "123",
+            "side": "BUY",
+            "price": 0.5,
+            "size": 10.0,
+            "original_size": 10.0,
+            "filled_size": 0.0,
+            "remaining_size": 10.0,
+            "status": status,
+            "created_at": Utc::now(),
+            "updated_at": null,
+            "filled_at": null,
+            "cancelled_at": null,
+            "fees_paid": null,
+            "average_fill_price": null,
+            "market_info": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_orders_still_open_on_the_exchange_are_adopted() {
+        let handed = vec![order("a", "OPEN"), order("b", "OPEN"), order("c", "OPEN")];
+        let mut partly_filled = order("a", "PARTIALLY_FILLED");
+        partly_filled.remaining_size = 4.0;
+        let live = vec![partly_filled, order("c", "CANCELLED"), order("d", "OPEN")];
+
+        let reconciled = ReconciledOrders::between(handed, live);
+        assert_eq!(reconciled.open.len(), 1);
+        assert_eq!(reconciled.open[0].remaining_size, 4.0);
+        assert_eq!(reconciled.closed, ["b", "c"]);
+    }
+}
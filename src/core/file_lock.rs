@@ -0,0 +1,120 @@
+//! Cross-process exclusive lock on a lock file
+//!
+//! Used wherever several polybot processes read-modify-write the same files
+//! (the audit log, failover leases, alert state). Async code waits with
+//! [`FileLock::acquire`], which sleeps on the runtime timer instead of
+//! blocking a worker thread; synchronous code uses
+//! [`FileLock::acquire_blocking`] and, when called from async code, runs it
+//! through [`run_blocking`].
+//!
+//! The lock is an OS advisory lock (`flock` / `LockFileEx`) on the file, so the
+//! kernel releases it when the holder exits or crashes. The file itself stays
+//! on disk: removing it would let a waiter that already opened it lock an
+//! unlinked file while a newcomer locks a fresh one.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Default time to wait for a lock held by another process
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Held while the value lives; dropping it closes the file, which releases the lock
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Wait up to `timeout` for the lock without blocking the runtime
+    pub async fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_acquire(path)? {
+                return Ok(lock);
+            }
+            if Instant::now() >= deadline {
+                return Err(Self::timed_out(path));
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Wait up to `timeout` for the lock, blocking the calling thread
+    pub fn acquire_blocking(path: &Path, timeout: Duration) -> Result<Self> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_acquire(path)? {
+                return Ok(lock);
+            }
+            if Instant::now() >= deadline {
+                return Err(Self::timed_out(path));
+            }
+            std::thread::sleep(RETRY_INTERVAL);
+        }
+    }
+
+    /// Take the lock if it is free; `None` while another process holds it
+    pub fn try_acquire(path: &Path) -> Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e).with_context(|| format!("Failed to lock {}", path.display())),
+        }
+    }
+
+    fn timed_out(path: &Path) -> anyhow::Error {
+        anyhow!("Timed out waiting for the lock {}", path.display())
+    }
+}
+
+/// Run blocking file work from code that may be on an async worker thread
+///
+/// On a multi-threaded runtime the worker hands its other tasks off while `f`
+/// runs; elsewhere (no runtime, or a current-thread one) `f` simply runs.
+pub fn run_blocking<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn second_holder_waits_until_the_first_drops() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.lock");
+        let first = FileLock::acquire(&path, LOCK_TIMEOUT).await.unwrap();
+        assert!(FileLock::try_acquire(&path).unwrap().is_none());
+        assert!(FileLock::acquire(&path, Duration::from_millis(60)).await.is_err());
+
+        drop(first);
+        let _second = FileLock::acquire(&path, LOCK_TIMEOUT).await.unwrap();
+        assert!(FileLock::try_acquire(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn leftover_lock_file_is_not_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leftover.lock");
+        std::fs::write(&path, "").unwrap();
+        assert!(FileLock::try_acquire(&path).unwrap().is_some());
+    }
+}
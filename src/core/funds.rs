@@ -0,0 +1,288 @@
+//! Funding helpers: deposit addresses, bridge deposit status and on-chain USDC balance
+//!
+//! Deposits from other chains go through the Polymarket bridge, which hands out
+//! per-wallet deposit addresses and reports the status of each transfer until
+//! it lands as USDC.e on Polygon. The wallet's settled balance is read straight
+//! from the USDC.e contract over JSON-RPC.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub const BRIDGE_API_URL: &str = "https://bridge.polymarket.com";
+pub const DEFAULT_POLYGON_RPC_URL: &str = "https://polygon-rpc.com";
+
+/// Bridged USDC (USDC.e) on Polygon, the collateral token of the CLOB
+pub const USDC_E_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+const USDC_DECIMALS: u32 = 6;
+
+/// `balanceOf(address)` selector
+const BALANCE_OF_SELECTOR: &str = "70a08231";
+
+/// Deposit addresses the bridge assigned to a wallet, one per source chain family
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct DepositAddresses {
+    #[serde(default)]
+    pub evm: Option<String>,
+    #[serde(default)]
+    pub svm: Option<String>,
+    #[serde(default)]
+    pub btc: Option<String>,
+}
+
+/// One deposit address and the chain family it accepts transfers from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepositAddress<'a> {
+    pub chain: &'static str,
+    pub address: &'a str,
+}
+
+impl DepositAddresses {
+    /// The addresses that are set, labelled with their chain family
+    pub fn entries(&self) -> Vec<DepositAddress<'_>> {
+        [("EVM", &self.evm), ("Solana", &self.svm), ("Bitcoin", &self.btc)]
+            .into_iter()
+            .filter_map(|(chain, address)| {
+                address.as_deref().map(|address| DepositAddress { chain, address })
+            })
+            .collect()
+    }
+}
+
+/// One transfer seen by the bridge for a deposit address
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeDeposit {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub from_chain_id: Option<String>,
+    #[serde(default)]
+    pub from_token_address: Option<String>,
+    /// Amount in the source token's base units
+    #[serde(default)]
+    pub from_amount_base_unit: Option<String>,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    #[serde(default)]
+    pub created_time_ms: Option<i64>,
+}
+
+impl BridgeDeposit {
+    /// Still on its way to the Polygon wallet
+    pub fn is_pending(&self) -> bool {
+        !matches!(self.status.as_str(), "COMPLETED" | "FAILED")
+    }
+}
+
+/// Settled wallet balance plus deposits still in flight, for balance displays
+#[derive(Debug, Clone, Default)]
+pub struct FundingStatus {
+    pub wallet_balance: Decimal,
+    pub pending_deposits: Vec<BridgeDeposit>,
+}
+
+/// Client for the bridge API and the Polygon RPC endpoint
+pub struct FundsClient {
+    http: Client,
+    bridge_url: String,
+    rpc_url: String,
+}
+
+impl FundsClient {
+    pub fn new(rpc_url: Option<String>) -> Result<Self> {
+        let http = Client::builder().timeout(Duration::from_secs(15)).build()?;
+        Ok(Self {
+            http,
+            bridge_url: BRIDGE_API_URL.to_string(),
+            rpc_url: rpc_url.unwrap_or_else(|| DEFAULT_POLYGON_RPC_URL.to_string()),
+        })
+    }
+
+    /// Deposit addresses for `wallet`, created by the bridge on first request
+    ///
+    /// This is a POST that may allocate addresses, so callers that poll should
+    /// fetch the addresses once and pass them to [`Self::funding_status`].
+    pub async fn deposit_addresses(&self, wallet: &str) -> Result<DepositAddresses> {
+        let response: Value = self
+            .http
+            .post(format!("{}/deposit", self.bridge_url))
+            .json(&json!({ "address": wallet }))
+            .send()
+            .await
+            .context("Bridge API request failed")?
+            .error_for_status()?
+            .json()
+            .await?;
+        parse_deposit_addresses(&response)
+    }
+
+    /// Transfers the bridge has seen for a deposit address, newest first
+    pub async fn deposit_status(&self, deposit_address: &str) -> Result<Vec<BridgeDeposit>> {
+        let response: Value = self
+            .http
+            .get(format!("{}/status/{}", self.bridge_url, deposit_address))
+            .send()
+            .await
+            .context("Bridge API request failed")?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(parse_deposit_status(&response))
+    }
+
+    /// Settled USDC.e balance of `wallet` on Polygon
+    pub async fn usdc_balance(&self, wallet: &str) -> Result<Decimal> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{ "to": USDC_E_ADDRESS, "data": balance_of_call_data(wallet)? }, "latest"],
+        });
+        let response: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Polygon RPC request to {} failed", self.rpc_url))?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("Polygon RPC error: {}", error));
+        }
+        let result = response
+            .get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Polygon RPC returned no result"))?;
+        decode_usdc_amount(result)
+    }
+
+    /// Wallet balance and the pending transfers to any of its deposit addresses;
+    /// only reads, so it is safe to poll
+    pub async fn funding_status(&self, wallet: &str, addresses: &DepositAddresses) -> Result<FundingStatus> {
+        let wallet_balance = self.usdc_balance(wallet).await?;
+        let mut pending_deposits = Vec::new();
+        for entry in addresses.entries() {
+            let deposits = self.deposit_status(entry.address).await?;
+            pending_deposits.extend(deposits.into_iter().filter(BridgeDeposit::is_pending));
+        }
+        Ok(FundingStatus {
+            wallet_balance,
+            pending_deposits,
+        })
+    }
+}
+
+/// Whether `value` is a 0x-prefixed 20 byte hex address
+pub fn is_evm_address(value: &str) -> bool {
+    value.len() == 42
+        && value.starts_with("0x")
+        && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// ABI-encoded `balanceOf(wallet)` call
+pub fn balance_of_call_data(wallet: &str) -> Result<String> {
+    if !is_evm_address(wallet) {
+        return Err(anyhow!("Not an EVM address: {}", wallet));
+    }
+    Ok(format!(
+        "0x{}{:0>64}",
+        BALANCE_OF_SELECTOR,
+        wallet[2..].to_lowercase()
+    ))
+}
+
+/// Hex-encoded uint256 token amount to USDC
+pub fn decode_usdc_amount(hex: &str) -> Result<Decimal> {
+    let digits = hex.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    let base_units = u128::from_str_radix(digits, 16)
+        .with_context(|| format!("Invalid token amount: {}", hex))?;
+    let base_units = i128::try_from(base_units).context("Token amount out of range")?;
+    Decimal::try_from_i128_with_scale(base_units, USDC_DECIMALS)
+        .map_err(|e| anyhow!("Token amount out of range: {}", e))
+}
+
+/// Accepts both `{"address": {...}}` and a bare address object
+pub fn parse_deposit_addresses(response: &Value) -> Result<DepositAddresses> {
+    let addresses = response.get("address").unwrap_or(response);
+    let parsed: DepositAddresses = serde_json::from_value(addresses.clone())
+        .context("Unexpected bridge deposit response")?;
+    if parsed.entries().is_empty() {
+        return Err(anyhow!("Bridge returned no deposit addresses"));
+    }
+    Ok(parsed)
+}
+
+/// Transfers from a status response, skipping entries that fail to parse
+pub fn parse_deposit_status(response: &Value) -> Vec<BridgeDeposit> {
+    let mut deposits: Vec<BridgeDeposit> = response
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .map(|transactions| {
+            transactions
+                .iter()
+                .filter_map(|tx| serde_json::from_value(tx.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    deposits.sort_by(|a, b| b.created_time_ms.cmp(&a.created_time_ms));
+    deposits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn encodes_balance_of_call() {
+        let data = balance_of_call_data("0xAbCdEf0123456789abcdef0123456789ABCDEF01").unwrap();
+        assert_eq!(
+            data,
+            "0x70a08231000000000000000000000000abcdef0123456789abcdef0123456789abcdef01"
+        );
+        assert!(balance_of_call_data("my-wallet").is_err());
+    }
+
+    #[test]
+    fn decodes_six_decimal_amounts() {
+        assert_eq!(
+            decode_usdc_amount("0x0000000000000000000000000000000000000000000000000000000005f5e100").unwrap(),
+            dec!(100)
+        );
+        assert_eq!(decode_usdc_amount("0x").unwrap(), Decimal::ZERO);
+        assert_eq!(decode_usdc_amount("0x1").unwrap(), dec!(0.000001));
+    }
+
+    #[test]
+    fn parses_bridge_responses() {
+        let addresses = parse_deposit_addresses(&json!({
+            "address": {"evm": "0xabc", "svm": "So1", "btc": null}
+        }))
+        .unwrap();
+        assert_eq!(
+            addresses.entries(),
+            vec![
+                DepositAddress { chain: "EVM", address: "0xabc" },
+                DepositAddress { chain: "Solana", address: "So1" },
+            ]
+        );
+
+        let deposits = parse_deposit_status(&json!({
+            "transactions": [
+                {"status": "COMPLETED", "createdTimeMs": 1},
+                {"status": "PROCESSING", "txHash": "0x1", "createdTimeMs": 2}
+            ]
+        }));
+        assert_eq!(deposits.len(), 2);
+        assert!(deposits[0].is_pending());
+        assert!(!deposits[1].is_pending());
+    }
+}
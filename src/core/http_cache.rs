@@ -0,0 +1,544 @@
+//! Disk-backed cache of REST responses
+//!
+//! Idempotent GETs of public data (Gamma market and event queries, trades,
+//! price history) go through [`ResponseCache::get`], which serves a response
+//! fetched earlier for the same URL until the TTL of its endpoint runs out.
+//! Repeating a query while iterating on an analysis then costs no API calls.
+//! Account data (positions, activity, orders, a user's own trades) is never
+//! cached; [`fetch`] is the same GET without the cache.
+//!
+//! Entries are JSON files in `<data_dir>/cache/http/<endpoint>/`, shared by
+//! all profiles. `--no-cache` turns the cache off for one command (responses
+//! are neither read nor stored), and `polybot cache` shows hit rates and
+//! clears entries. Only successful responses are stored. Each command removes
+//! the expired entries of the endpoints it used when it finishes, so the
+//! directory doesn't grow with every distinct query ever made.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+use crate::data_paths::{DataPaths, DEFAULT_DATA_DIR};
+
+/// Directory (relative to the base data directory) holding the cache
+pub const HTTP_CACHE_DIR: &str = "cache/http";
+
+/// File in the cache directory with the hit and miss counts of past commands
+const STATS_FILE: &str = "stats.json";
+
+/// Kind of request, which decides how long its responses stay fresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// Gamma market metadata (`/markets`)
+    Markets,
+    /// Gamma events (`/events`)
+    Events,
+    /// Data API trades
+    Trades,
+    /// CLOB price history
+    PriceHistory,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 4] = [
+        Endpoint::Markets,
+        Endpoint::Events,
+        Endpoint::Trades,
+        Endpoint::PriceHistory,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Endpoint::Markets => "markets",
+            Endpoint::Events => "events",
+            Endpoint::Trades => "trades",
+            Endpoint::PriceHistory => "price-history",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|endpoint| endpoint.name() == name)
+    }
+
+    /// How long a response stays fresh
+    pub fn ttl(&self) -> Duration {
+        match self {
+            // Metadata only changes when markets open, close or resolve
+            Endpoint::Markets | Endpoint::Events => Duration::minutes(10),
+            Endpoint::Trades => Duration::minutes(1),
+            Endpoint::PriceHistory => Duration::minutes(5),
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|e| e == self).unwrap_or(0)
+    }
+}
+
+/// A cached response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self, endpoint: Endpoint) -> bool {
+        Utc::now() - self.fetched_at < endpoint.ttl()
+    }
+}
+
+fn read_entry(path: &Path) -> Option<CacheEntry> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// A response, fresh from the API or from the cache
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: String,
+    /// Served from the cache
+    pub cached: bool,
+}
+
+/// Hits and misses of one endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointCounts {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl EndpointCounts {
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Hits and misses of past commands, per endpoint name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheCounts {
+    pub since: Option<DateTime<Utc>>,
+    pub endpoints: std::collections::BTreeMap<String, EndpointCounts>,
+}
+
+/// Entries on disk for one endpoint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointUsage {
+    pub entries: usize,
+    pub expired: usize,
+    pub bytes: u64,
+}
+
+/// An entry file of the cache directory
+struct EntryFile {
+    path: PathBuf,
+    bytes: u64,
+    /// Last write, which is when the entry was fetched
+    modified: Option<SystemTime>,
+}
+
+impl EntryFile {
+    /// Expired judging by the file's write time, without reading the entry
+    fn is_expired(&self, endpoint: Endpoint) -> bool {
+        let Some(modified) = self.modified else {
+            return false;
+        };
+        let written: DateTime<Utc> = modified.into();
+        Utc::now() - written >= endpoint.ttl()
+    }
+}
+
+pub struct ResponseCache {
+    dir: PathBuf,
+    enabled: AtomicBool,
+    hits: [AtomicU64; Endpoint::ALL.len()],
+    misses: [AtomicU64; Endpoint::ALL.len()],
+}
+
+static GLOBAL: OnceLock<ResponseCache> = OnceLock::new();
+
+/// Point the process-wide cache at the data directory of `data_paths`; call
+/// before the first request
+pub fn init(data_paths: &DataPaths, enabled: bool) {
+    let cache = GLOBAL.get_or_init(|| ResponseCache::new(data_paths.base().join(HTTP_CACHE_DIR)));
+    if cache.dir != data_paths.base().join(HTTP_CACHE_DIR) {
+        warn!(
+            "Response cache already in use at {}, not moving it",
+            cache.dir.display()
+        );
+    }
+    cache.set_enabled(enabled);
+}
+
+/// The process-wide cache (in `./data` unless [`init`] chose another directory)
+pub fn global() -> &'static ResponseCache {
+    GLOBAL.get_or_init(|| ResponseCache::new(Path::new(DEFAULT_DATA_DIR).join(HTTP_CACHE_DIR)))
+}
+
+/// GET `url` with `params` bypassing the cache, for requests that must see
+/// the latest data (e.g. an account's own trades)
+pub async fn fetch<P: Serialize + ?Sized>(
+    client: &Client,
+    url: &str,
+    params: &P,
+) -> Result<HttpResponse> {
+    execute(client, build_request(client, url, params)?).await
+}
+
+/// [`ResponseCache::get`] on the process-wide cache, parsing the JSON body
+pub async fn get_json<T: DeserializeOwned, P: Serialize + ?Sized>(
+    client: &Client,
+    endpoint: Endpoint,
+    url: &str,
+    params: &P,
+) -> Result<T> {
+    let response = global().get(client, endpoint, url, params).await?;
+    if !response.status.is_success() {
+        bail!(
+            "{} returned status {}: {}",
+            url,
+            response.status,
+            response.body
+        );
+    }
+    serde_json::from_str(&response.body).with_context(|| format!("Invalid JSON from {}", url))
+}
+
+fn build_request<P: Serialize + ?Sized>(
+    client: &Client,
+    url: &str,
+    params: &P,
+) -> Result<reqwest::Request> {
+    client
+        .get(url)
+        .query(params)
+        .build()
+        .with_context(|| format!("Invalid request to {}", url))
+}
+
+async fn execute(client: &Client, request: reqwest::Request) -> Result<HttpResponse> {
+    let url = request.url().to_string();
+    let response = client
+        .execute(request)
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .with_context(|| format!("Failed to read the response of {}", url))?;
+    Ok(HttpResponse {
+        status,
+        body,
+        cached: false,
+    })
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            enabled: AtomicBool::new(true),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// GET `url` with `params`, served from the cache while fresh
+    pub async fn get<P: Serialize + ?Sized>(
+        &self,
+        client: &Client,
+        endpoint: Endpoint,
+        url: &str,
+        params: &P,
+    ) -> Result<HttpResponse> {
+        let request = build_request(client, url, params)?;
+        let full_url = request.url().to_string();
+
+        if let Some(entry) = self.lookup(endpoint, &full_url) {
+            debug!("Cache hit for {}", full_url);
+            return Ok(HttpResponse {
+                status: StatusCode::OK,
+                body: entry.body,
+                cached: true,
+            });
+        }
+
+        let response = execute(client, request).await?;
+        if response.status.is_success() {
+            if let Err(e) = self.store(endpoint, &full_url, &response.body) {
+                warn!("Failed to cache the response of {}: {:#}", full_url, e);
+            }
+        }
+        Ok(response)
+    }
+
+    /// The fresh entry for `url`, counting a hit or a miss
+    pub fn lookup(&self, endpoint: Endpoint, url: &str) -> Option<CacheEntry> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let entry = read_entry(&self.entry_path(endpoint, url))
+            .filter(|entry| entry.url == url && entry.is_fresh(endpoint));
+        let counter = match entry {
+            Some(_) => &self.hits[endpoint.index()],
+            None => &self.misses[endpoint.index()],
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        entry
+    }
+
+    /// Store `body` as the response for `url`
+    pub fn store(&self, endpoint: Endpoint, url: &str, body: &str) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let entry = CacheEntry {
+            url: url.to_string(),
+            fetched_at: Utc::now(),
+            body: body.to_string(),
+        };
+        let path = self.entry_path(endpoint, url);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so concurrent commands never read half an entry
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_vec(&entry)?)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, endpoint: Endpoint, url: &str) -> PathBuf {
+        let hash = hex::encode(Sha256::digest(url.as_bytes()));
+        self.dir
+            .join(endpoint.name())
+            .join(format!("{}.json", &hash[..32]))
+    }
+
+    /// Hits and misses of this process
+    pub fn session_counts(&self) -> CacheCounts {
+        let mut counts = CacheCounts::default();
+        for endpoint in Endpoint::ALL {
+            let i = endpoint.index();
+            let endpoint_counts = EndpointCounts {
+                hits: self.hits[i].load(Ordering::Relaxed),
+                misses: self.misses[i].load(Ordering::Relaxed),
+            };
+            if endpoint_counts != EndpointCounts::default() {
+                counts
+                    .endpoints
+                    .insert(endpoint.name().to_string(), endpoint_counts);
+            }
+        }
+        counts
+    }
+
+    /// Hits and misses of past commands, as saved by [`finish_session`](Self::finish_session)
+    pub fn saved_counts(&self) -> CacheCounts {
+        fs::read_to_string(self.dir.join(STATS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Add this process's hits and misses to the saved ones and log them
+    pub fn finish_session(&self) -> Result<()> {
+        let session = self.session_counts();
+        if session.endpoints.is_empty() {
+            return Ok(());
+        }
+        let total = session
+            .endpoints
+            .values()
+            .fold(EndpointCounts::default(), |total, c| EndpointCounts {
+                hits: total.hits + c.hits,
+                misses: total.misses + c.misses,
+            });
+        info!(
+            "Response cache: {} hits, {} misses",
+            total.hits, total.misses
+        );
+
+        // Misses stored new entries; drop what expired meanwhile
+        for endpoint in session.endpoints.keys().filter_map(|name| Endpoint::from_name(name)) {
+            let pruned = self.prune_expired(endpoint);
+            if pruned > 0 {
+                debug!("Pruned {} expired {} entries", pruned, endpoint.name());
+            }
+        }
+
+        let mut saved = self.saved_counts();
+        saved.since.get_or_insert_with(Utc::now);
+        for (name, counts) in session.endpoints {
+            let total = saved.endpoints.entry(name).or_default();
+            total.hits += counts.hits;
+            total.misses += counts.misses;
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join(STATS_FILE),
+            serde_json::to_string_pretty(&saved)?,
+        )
+        .context("Failed to save response cache statistics")
+    }
+
+    /// Entries on disk for `endpoint`
+    pub fn usage(&self, endpoint: Endpoint) -> EndpointUsage {
+        let mut usage = EndpointUsage::default();
+        for file in self.entry_files(endpoint) {
+            usage.entries += 1;
+            usage.bytes += file.bytes;
+            if !read_entry(&file.path).is_some_and(|entry| entry.is_fresh(endpoint)) {
+                usage.expired += 1;
+            }
+        }
+        usage
+    }
+
+    /// Delete the entries of `endpoint`, or only its expired ones; returns how many
+    pub fn clear(&self, endpoint: Endpoint, expired_only: bool) -> Result<usize> {
+        let mut removed = 0;
+        for file in self.entry_files(endpoint) {
+            let path = file.path;
+            if expired_only && read_entry(&path).is_some_and(|entry| entry.is_fresh(endpoint)) {
+                continue;
+            }
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Delete the entries of `endpoint` written longer than its TTL ago;
+    /// returns how many. Files another command removed first are skipped
+    pub fn prune_expired(&self, endpoint: Endpoint) -> usize {
+        self.entry_files(endpoint)
+            .into_iter()
+            .filter(|file| file.is_expired(endpoint))
+            .filter(|file| fs::remove_file(&file.path).is_ok())
+            .count()
+    }
+
+    /// Forget the saved hit and miss counts
+    pub fn reset_counts(&self) -> Result<()> {
+        let path = self.dir.join(STATS_FILE);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    fn entry_files(&self, endpoint: Endpoint) -> Vec<EntryFile> {
+        let Ok(entries) = fs::read_dir(self.dir.join(endpoint.name())) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|e| e == "json"))
+            .map(|entry| {
+                let metadata = entry.metadata().ok();
+                EntryFile {
+                    path: entry.path(),
+                    bytes: metadata.as_ref().map_or(0, |m| m.len()),
+                    modified: metadata.and_then(|m| m.modified().ok()),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_served_until_their_ttl_runs_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path());
+        let url = "https://gamma-api.polymarket.com/markets?limit=10";
+
+        assert!(cache.lookup(Endpoint::Markets, url).is_none());
+        cache.store(Endpoint::Markets, url, "[]").unwrap();
+        assert_eq!(cache.lookup(Endpoint::Markets, url).unwrap().body, "[]");
+        // Same URL, other endpoint: separate entries
+        assert!(cache.lookup(Endpoint::Events, url).is_none());
+
+        // An entry older than the TTL is a miss and counts as expired
+        let stale = CacheEntry {
+            url: url.to_string(),
+            fetched_at: Utc::now() - Endpoint::Trades.ttl() - Duration::seconds(1),
+            body: "[]".to_string(),
+        };
+        let path = cache.entry_path(Endpoint::Trades, url);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_vec(&stale).unwrap()).unwrap();
+        assert!(cache.lookup(Endpoint::Trades, url).is_none());
+        assert_eq!(cache.usage(Endpoint::Trades).expired, 1);
+        assert_eq!(cache.clear(Endpoint::Trades, true).unwrap(), 1);
+        assert_eq!(cache.clear(Endpoint::Markets, true).unwrap(), 0);
+
+        // Disabled, nothing is read or written
+        cache.set_enabled(false);
+        assert!(cache.lookup(Endpoint::Markets, url).is_none());
+
+        let counts = cache.session_counts();
+        assert_eq!(
+            counts.endpoints["markets"],
+            EndpointCounts { hits: 1, misses: 1 }
+        );
+        assert_eq!(counts.endpoints["trades"].misses, 1);
+        cache.finish_session().unwrap();
+        assert_eq!(cache.saved_counts().endpoints["events"].misses, 1);
+    }
+
+    #[test]
+    fn finishing_a_session_prunes_expired_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path());
+        let old = "https://data-api.polymarket.com/trades?market=old";
+        let new = "https://data-api.polymarket.com/trades?market=new";
+        cache.store(Endpoint::Trades, old, "[]").unwrap();
+        let written = SystemTime::now() - std::time::Duration::from_secs(120);
+        fs::File::options()
+            .write(true)
+            .open(cache.entry_path(Endpoint::Trades, old))
+            .unwrap()
+            .set_modified(written)
+            .unwrap();
+
+        assert!(cache.lookup(Endpoint::Trades, new).is_none());
+        cache.store(Endpoint::Trades, new, "[]").unwrap();
+        cache.finish_session().unwrap();
+
+        assert!(!cache.entry_path(Endpoint::Trades, old).exists());
+        assert!(cache.lookup(Endpoint::Trades, new).is_some());
+    }
+}
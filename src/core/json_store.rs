@@ -0,0 +1,179 @@
+//! Small JSON files in the data directory that are rewritten whole
+//!
+//! Brackets, scheduled orders and token notes are each kept as one pretty
+//! printed JSON document. [`JsonStore`] reads it (a missing file is the
+//! default value) and replaces it through a temp file and a rename, so a
+//! reader never sees a half-written file. Every change goes through
+//! [`JsonStore::modify`], which holds a lock file next to the document from
+//! the read to the write, so the daemon and a CLI or GUI process changing the
+//! same file don't overwrite each other's edits. Lists of [`Record`]s also
+//! get id allocation and the "write back what changed" merge the daemon uses.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use crate::core::file_lock::{run_blocking, FileLock, LOCK_TIMEOUT};
+
+/// A value persisted as one JSON file
+pub struct JsonStore<T> {
+    path: PathBuf,
+    value: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> JsonStore<T> {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            value: PhantomData,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The stored value, or the default when the file doesn't exist yet
+    pub fn load(&self) -> Result<T> {
+        if !self.path.exists() {
+            return Ok(T::default());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", self.path.display()))
+    }
+
+    /// Change the stored value with `change` and write it back, holding the
+    /// lock file throughout; nothing is written when `change` fails
+    pub fn modify<R>(&self, change: impl FnOnce(&mut T) -> Result<R>) -> Result<R> {
+        let lock = run_blocking(|| FileLock::acquire_blocking(&self.lock_path(), LOCK_TIMEOUT))?;
+        let changed = self.modify_locked(change);
+        drop(lock);
+        changed
+    }
+
+    fn modify_locked<R>(&self, change: impl FnOnce(&mut T) -> Result<R>) -> Result<R> {
+        let mut value = self.load()?;
+        let result = change(&mut value)?;
+        self.save(&value)?;
+        Ok(result)
+    }
+
+    fn save(&self, value: &T) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.path.with_extension("json.lock")
+    }
+}
+
+/// An entry of a stored list, identified by a number the store hands out
+pub trait Record: Clone {
+    fn id(&self) -> u64;
+}
+
+impl<R: Record + Serialize + DeserializeOwned> JsonStore<Vec<R>> {
+    /// Append the record `build` makes from the next free id
+    pub fn insert(&self, build: impl FnOnce(u64) -> R) -> Result<R> {
+        self.modify(|records| {
+            let record = build(records.iter().map(Record::id).max().unwrap_or(0) + 1);
+            records.push(record.clone());
+            Ok(record)
+        })
+    }
+
+    /// Write back changed records, keeping any added since they were loaded
+    pub fn update(&self, changed: &[R]) -> Result<()> {
+        if changed.is_empty() {
+            return Ok(());
+        }
+        self.modify(|records| {
+            for record in changed {
+                match records.iter_mut().find(|r| r.id() == record.id()) {
+                    Some(stored) => *stored = record.clone(),
+                    None => records.push(record.clone()),
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        id: u64,
+        label: String,
+    }
+
+    impl Record for Entry {
+        fn id(&self) -> u64 {
+            self.id
+        }
+    }
+
+    #[test]
+    fn records_get_increasing_ids_and_updates_keep_new_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JsonStore::<Vec<Entry>>::new(dir.path().join("entries.json"));
+        assert!(store.load().unwrap().is_empty());
+
+        let first = store
+            .insert(|id| Entry { id, label: "first".to_string() })
+            .unwrap();
+        let mut loaded = store.load().unwrap();
+        store
+            .insert(|id| Entry { id, label: "second".to_string() })
+            .unwrap();
+        assert_eq!(first.id, 1);
+
+        // The second entry was added after `loaded` was read and survives the write back
+        loaded[0].label = "changed".to_string();
+        store.update(&loaded).unwrap();
+        let stored = store.load().unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].label, "changed");
+        assert_eq!(stored[1].id, 2);
+        assert!(!dir.path().join("entries.json.tmp").exists());
+        assert!(FileLock::try_acquire(&dir.path().join("entries.json.lock")).unwrap().is_some());
+    }
+
+    #[test]
+    fn failed_changes_are_not_written_and_release_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JsonStore::<Vec<Entry>>::new(dir.path().join("entries.json"));
+        store
+            .insert(|id| Entry { id, label: "kept".to_string() })
+            .unwrap();
+
+        let failed: Result<()> = store.modify(|entries| {
+            entries[0].label = "lost".to_string();
+            anyhow::bail!("refused")
+        });
+        assert!(failed.is_err());
+        assert_eq!(store.load().unwrap()[0].label, "kept");
+
+        // The lock went with the failed change, so the next one goes through
+        assert!(FileLock::try_acquire(&store.lock_path()).unwrap().is_some());
+        store
+            .modify(|entries| {
+                entries[0].label = "changed".to_string();
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(store.load().unwrap()[0].label, "changed");
+    }
+}
@@ -0,0 +1,462 @@
+//! Memory guardrails for operations over large datasets
+//!
+//! `analyze`, `enrich` and `index` can hold a whole snapshot in memory, which
+//! on a big snapshot ends with the process being OOM-killed mid-write. They
+//! check a [`MemoryBudget`] instead: it samples the process's resident set
+//! size (RSS) against a limit taken from `--memory-limit`, the
+//! `POLYBOT_MEMORY_LIMIT` environment variable, or 75% of the memory available
+//! to the process. Past the limit, operations move what they accumulate to
+//! disk ([`SpillVec`] for result lists, early database writes for `index`).
+//! If RSS keeps growing past the hard limit anyway, they save what they have
+//! and stop with [`MemoryLimitExceeded`], which says how to get through.
+//!
+//! RSS is read from `/proc`; on other platforms the budget never triggers.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::ser::{Error as _, SerializeSeq};
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::markets::datasets::format_bytes;
+
+/// Environment variable holding the default memory limit (e.g. `8G`)
+pub const MEMORY_LIMIT_ENV: &str = "POLYBOT_MEMORY_LIMIT";
+
+/// Share of the available memory used when no limit is configured
+const DEFAULT_LIMIT_SHARE: f64 = 0.75;
+
+/// How far past the limit RSS may still grow after spilling before giving up
+const HARD_LIMIT_FACTOR: f64 = 1.25;
+
+/// Minimum time between two RSS samples; checks in hot loops reuse the last one
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An operation kept growing past its memory budget even after spilling
+#[derive(Debug, Error)]
+pub struct MemoryLimitExceeded {
+    pub operation: String,
+    pub rss: u64,
+    pub limit: u64,
+    pub guidance: String,
+}
+
+impl fmt::Display for MemoryLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} stopped at {} of memory, past its {} limit even after spilling to disk.",
+            self.operation,
+            format_bytes(self.rss),
+            format_bytes(self.limit)
+        )?;
+        writeln!(f, "💡 {}", self.guidance)?;
+        write!(
+            f,
+            "💡 Or raise the limit with --memory-limit (or {}) if the machine has room",
+            MEMORY_LIMIT_ENV
+        )
+    }
+}
+
+/// Approximate memory limit of one operation; clones share RSS samples, so
+/// worker threads can all check it
+#[derive(Clone)]
+pub struct MemoryBudget {
+    operation: String,
+    limit: Option<u64>,
+    guidance: String,
+    sample: fn() -> Option<u64>,
+    last_sample: Arc<Mutex<Option<RssSample>>>,
+}
+
+/// A resident set size reading and when it was taken
+#[derive(Debug, Clone, Copy)]
+struct RssSample {
+    at: Instant,
+    rss: u64,
+}
+
+impl fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("operation", &self.operation)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::new("Operation", None)
+    }
+}
+
+impl MemoryBudget {
+    /// Budget for `operation`; without an explicit `limit`, the environment
+    /// variable or the default share of available memory applies
+    pub fn new(operation: impl Into<String>, limit: Option<u64>) -> Self {
+        let limit = limit.or_else(configured_limit).or_else(default_limit);
+        Self::with_sampler(operation, limit, current_rss)
+    }
+
+    fn with_sampler(
+        operation: impl Into<String>,
+        limit: Option<u64>,
+        sample: fn() -> Option<u64>,
+    ) -> Self {
+        Self {
+            operation: operation.into(),
+            limit,
+            guidance: "Process the dataset in smaller parts".to_string(),
+            sample,
+            last_sample: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Advice shown when the operation gives up, e.g. which flag to use
+    pub fn with_guidance(mut self, guidance: impl Into<String>) -> Self {
+        self.guidance = guidance.into();
+        self
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Whether accumulated data should go to disk now
+    pub fn should_spill(&self) -> bool {
+        match (self.limit, self.rss()) {
+            (Some(limit), Some(rss)) => rss > limit,
+            _ => false,
+        }
+    }
+
+    /// `Err` once RSS grew past the hard limit, for use between units of work
+    pub fn check(&self) -> Result<(), MemoryLimitExceeded> {
+        let (Some(limit), Some(rss)) = (self.limit, self.rss()) else {
+            return Ok(());
+        };
+        if (rss as f64) <= limit as f64 * HARD_LIMIT_FACTOR {
+            return Ok(());
+        }
+        Err(MemoryLimitExceeded {
+            operation: self.operation.clone(),
+            rss,
+            limit,
+            guidance: self.guidance.clone(),
+        })
+    }
+
+    /// Resident set size, sampled at most every [`SAMPLE_INTERVAL`]
+    fn rss(&self) -> Option<u64> {
+        let mut last = self.last_sample.lock().unwrap();
+        if let Some(sample) = *last {
+            if sample.at.elapsed() < SAMPLE_INTERVAL {
+                return Some(sample.rss);
+            }
+        }
+        let rss = (self.sample)()?;
+        *last = Some(RssSample {
+            at: Instant::now(),
+            rss,
+        });
+        Some(rss)
+    }
+}
+
+/// Current resident set size of this process in bytes
+pub fn current_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    kib_field(&status, "VmRSS:")
+}
+
+/// Limit from [`MEMORY_LIMIT_ENV`]; an invalid value is ignored with a warning
+fn configured_limit() -> Option<u64> {
+    let value = std::env::var(MEMORY_LIMIT_ENV).ok()?;
+    match parse_byte_size(&value) {
+        Ok(limit) => Some(limit),
+        Err(e) => {
+            tracing::warn!("⚠️ Ignoring {}: {}", MEMORY_LIMIT_ENV, e);
+            None
+        }
+    }
+}
+
+/// Share of physical memory, or of the cgroup limit inside a container
+fn default_limit() -> Option<u64> {
+    let total = fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| kib_field(&meminfo, "MemTotal:"));
+    // `max` (no limit) doesn't parse and is skipped
+    let cgroup = fs::read_to_string("/sys/fs/cgroup/memory.max")
+        .ok()
+        .and_then(|max| max.trim().parse::<u64>().ok());
+    let available = match (total, cgroup) {
+        (Some(total), Some(cgroup)) => total.min(cgroup),
+        (total, cgroup) => total.or(cgroup)?,
+    };
+    Some((available as f64 * DEFAULT_LIMIT_SHARE) as u64)
+}
+
+/// A `Name:   1234 kB` line of a `/proc` file, in bytes
+fn kib_field(contents: &str, name: &str) -> Option<u64> {
+    let line = contents.lines().find(|line| line.starts_with(name))?;
+    let kib: u64 = line[name.len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+/// Parse a byte size like `512M`, `4G` or `8GiB` (binary units; plain numbers are bytes)
+pub fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(idx) => s.split_at(idx),
+        None => (s, ""),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid size (e.g. 512M, 4G)", s))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(format!("Unknown size unit '{}' (use K, M, G or T)", unit)),
+    };
+    Ok((value * multiplier as f64) as u64)
+}
+
+/// A list that moves its items to a JSON lines file once the budget is exceeded
+///
+/// Items keep their insertion order: spilled items first, then those still in
+/// memory. The spill file is removed when the list is dropped.
+pub struct SpillVec<T> {
+    budget: MemoryBudget,
+    memory: Vec<T>,
+    spill_path: PathBuf,
+    spill: Option<BufWriter<File>>,
+    spilled: usize,
+}
+
+impl<T: Serialize + DeserializeOwned + Clone> SpillVec<T> {
+    /// A list spilling to `spill_path` when `budget` says so
+    pub fn new(budget: MemoryBudget, spill_path: impl Into<PathBuf>) -> Self {
+        Self {
+            budget,
+            memory: Vec::new(),
+            spill_path: spill_path.into(),
+            spill: None,
+            spilled: 0,
+        }
+    }
+
+    pub fn push(&mut self, item: T) -> Result<()> {
+        self.memory.push(item);
+        if self.budget.should_spill() {
+            self.spill_memory()?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items written to the spill file so far
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    fn spill_memory(&mut self) -> Result<()> {
+        if self.spill.is_none() {
+            let file = File::create(&self.spill_path).with_context(|| {
+                format!("Failed to create spill file {}", self.spill_path.display())
+            })?;
+            tracing::info!(
+                "💾 Memory budget exceeded; spilling results to {}",
+                self.spill_path.display()
+            );
+            self.spill = Some(BufWriter::new(file));
+        }
+        let writer = self.spill.as_mut().expect("spill file was just opened");
+        for item in self.memory.drain(..) {
+            serde_json::to_writer(&mut *writer, &item)?;
+            writer.write_all(b"\n")?;
+            self.spilled += 1;
+        }
+        // Let the allocator reuse what the spilled items held
+        self.memory.shrink_to_fit();
+        Ok(())
+    }
+
+    /// Every item in order; spilled items are read back one at a time
+    pub fn iter(&mut self) -> Result<impl Iterator<Item = Result<T>> + '_> {
+        let spilled = match self.spill.as_mut() {
+            Some(writer) => {
+                writer.flush()?;
+                let file = File::open(&self.spill_path).with_context(|| {
+                    format!("Failed to read spill file {}", self.spill_path.display())
+                })?;
+                Some(BufReader::new(file).lines().map(|line| {
+                    let line = line?;
+                    Ok(serde_json::from_str(&line)?)
+                }))
+            }
+            None => None,
+        };
+        Ok(spilled
+            .into_iter()
+            .flatten()
+            .chain(self.memory.iter().cloned().map(Ok)))
+    }
+
+    /// Write every item as a pretty JSON array, through a temporary file so
+    /// an interrupt can't leave a truncated `path`
+    pub fn write_json_array(&mut self, path: &Path) -> Result<()> {
+        write_json_array(path, self.iter()?)
+    }
+}
+
+impl<T> Drop for SpillVec<T> {
+    fn drop(&mut self) {
+        if self.spill.take().is_some() {
+            let _ = fs::remove_file(&self.spill_path);
+        }
+    }
+}
+
+/// Write `items` as a pretty JSON array without collecting them first
+///
+/// The output matches `serde_json::to_string_pretty` of the collected list.
+pub fn write_json_array<T: Serialize>(
+    path: &Path,
+    items: impl Iterator<Item = Result<T>>,
+) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    let mut writer = BufWriter::new(
+        File::create(&tmp_path)
+            .with_context(|| format!("Failed to create {}", tmp_path.display()))?,
+    );
+    serde_json::to_writer_pretty(&mut writer, &JsonSeq(RefCell::new(Some(items))))?;
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Serializes an iterator of fallible items as a sequence, once
+struct JsonSeq<I>(RefCell<Option<I>>);
+
+impl<I, T> Serialize for JsonSeq<I>
+where
+    I: Iterator<Item = Result<T>>,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = self
+            .0
+            .borrow_mut()
+            .take()
+            .expect("sequence serialized once");
+        let mut seq = serializer.serialize_seq(None)?;
+        for item in items {
+            seq.serialize_element(&item.map_err(S::Error::custom)?)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_spills_then_stops_with_guidance() {
+        const GIB: u64 = 1 << 30;
+        let budget = MemoryBudget::with_sampler("Analysis", Some(GIB), || Some(GIB / 2));
+        assert!(!budget.should_spill());
+        assert!(budget.check().is_ok());
+
+        // Over the limit: spill, but keep going until the hard limit
+        let over = MemoryBudget::with_sampler("Analysis", Some(GIB), || Some(GIB + GIB / 8))
+            .with_guidance("Analyze fewer chunk files at a time");
+        assert!(over.should_spill());
+        assert!(over.check().is_ok());
+
+        let exhausted = MemoryBudget::with_sampler("Analysis", Some(GIB), || Some(2 * GIB))
+            .with_guidance("Analyze fewer chunk files at a time");
+        let message = exhausted.check().unwrap_err().to_string();
+        assert!(message.starts_with("Analysis stopped at 2.00 GB of memory, past its 1.00 GB"));
+        assert!(message.contains("Analyze fewer chunk files at a time"));
+
+        // Without a reading (other platforms) the budget never triggers
+        let unknown = MemoryBudget::with_sampler("Analysis", Some(1), || None);
+        assert!(!unknown.should_spill() && unknown.check().is_ok());
+
+        assert_eq!(
+            kib_field("Name:\tpolybot\nVmRSS:\t  2048 kB\n", "VmRSS:"),
+            Some(2 * 1024 * 1024)
+        );
+    }
+
+    #[test]
+    fn parses_byte_sizes() {
+        assert_eq!(parse_byte_size("512"), Ok(512));
+        assert_eq!(parse_byte_size("512M"), Ok(512 << 20));
+        assert_eq!(parse_byte_size("8GiB"), Ok(8 << 30));
+        assert_eq!(parse_byte_size("1.5g"), Ok(3 << 29));
+        assert!(parse_byte_size("4X").is_err());
+        assert!(parse_byte_size("lots").is_err());
+    }
+
+    #[test]
+    fn spilled_items_keep_their_order_and_json_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let spill_path = dir.path().join("markets.spill.jsonl");
+        let items: Vec<serde_json::Value> = (0..5)
+            .map(|i| serde_json::json!({ "id": i, "tokens": [i] }))
+            .collect();
+
+        let mut spilling = SpillVec::new(
+            MemoryBudget::with_sampler("Analysis", Some(1), || Some(2)),
+            &spill_path,
+        );
+        for item in &items[..3] {
+            spilling.push(item.clone()).unwrap();
+        }
+        assert_eq!(spilling.spilled(), 3);
+        assert!(spill_path.exists());
+        // Items pushed after the last spill are still in memory
+        spilling.memory.extend(items[3..].iter().cloned());
+
+        let read: Vec<_> = spilling.iter().unwrap().map(Result::unwrap).collect();
+        assert_eq!(read, items);
+
+        let output = dir.path().join("markets.json");
+        spilling.write_json_array(&output).unwrap();
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            serde_json::to_string_pretty(&items).unwrap()
+        );
+
+        drop(spilling);
+        assert!(!spill_path.exists());
+    }
+}
@@ -1,15 +1,43 @@
 //! Core module containing fundamental components of the polybot system
 //! 
 //! This module provides the core building blocks including:
+//! - **Alerts**: Alert severities, routing to toast/Telegram/webhook/phone, escalation and acknowledgement
+//! - **Audit log**: Hash-chained record of orders, cancels, transactions and credential/config changes
+//! - **Diagnostics**: Recent events ring, session recording and debug bundles for bug reports
 //! - **Execution engine**: Unified streaming and orderbook management
+//! - **Failover**: Leader election and order handover between daemon instances
+//! - **File lock**: Cross-process lock files that async code can wait on without blocking
+//! - **Funds**: Deposit addresses, bridge deposit status and USDC balance
+//! - **HTTP cache**: Disk-backed TTL cache of idempotent REST responses
+//! - **JSON store**: Small state files rewritten whole through a temp file
+//! - **Memory**: Memory budget and spill-to-disk for operations over large datasets
+//! - **On-chain**: Transaction signing, nonce tracking and stuck transaction replacement
+//! - **Progress**: Progress bars, ETAs and cancellation shared by long-running operations
+//! - **Risk**: Client-side limits on outbound trading traffic
 //! - **Services**: WebSocket streaming and market data management  
+//! - **Session**: Per-process session id and its activity and P&L report
+//! - **Token notes**: Per-token notes and one-shot price alerts
 //! - **WebSocket client**: Real-time event handling and state management
 //! - **Common types**: Shared data structures and events
 //! - **Trait definitions**: Shared interfaces and abstractions
 
+pub mod alerts;
+pub mod audit;
+pub mod diagnostics;
 pub mod execution;
+pub mod failover;
+pub mod file_lock;
+pub mod funds;
+pub mod http_cache;
+pub mod json_store;
+pub mod memory;
+pub mod onchain;
 pub mod portfolio;
+pub mod progress;
+pub mod risk;
 pub mod services;
+pub mod session;
+pub mod token_notes;
 pub mod traits;
 pub mod types;
 pub mod ws;
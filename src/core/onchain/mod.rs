@@ -0,0 +1,266 @@
+//! On-chain transaction sending for the trading wallet
+//!
+//! Wraps signing and `eth_sendRawTransaction` with the pieces that keep
+//! approvals and redemptions from silently hanging: nonces come from the node
+//! and the local journal together, sends retry on nonce and underpriced
+//! errors, and stuck transactions can be sped up or cancelled by resubmitting
+//! under the same nonce with bumped fees.
+
+pub mod nonce;
+pub mod usdc;
+
+pub use nonce::{next_nonce, FeeCaps, TrackedTx, TxJournal, TxStatus};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Bytes, Eip1559TransactionRequest, U256};
+use rust_decimal::Decimal;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::auth::Signer;
+use crate::core::audit::{self, AuditAction};
+use crate::data_paths::DataPaths;
+use crate::ethereum_utils::{ensure_max_fee, GasFees, GasOracle, JsonRpcClient};
+
+pub const POLYGON_CHAIN_ID: u64 = 137;
+
+/// Gas of a plain value transfer, which is all a cancellation needs
+const TRANSFER_GAS_LIMIT: u64 = 21_000;
+
+/// Attempts per send before giving up on nonce or fee errors
+const SEND_ATTEMPTS: usize = 3;
+
+/// Transaction to send, before nonce and fees are chosen
+#[derive(Debug, Clone)]
+pub struct TxRequest {
+    pub action: String,
+    pub to: String,
+    pub data: Vec<u8>,
+    pub value: u128,
+    pub gas_limit: u64,
+}
+
+/// Fees a speed-up or cancellation would pay for a pending transaction
+#[derive(Debug, Clone)]
+pub struct ReplacementPreview {
+    pub original: TrackedTx,
+    pub fees: FeeCaps,
+}
+
+pub struct OnChainClient {
+    rpc: JsonRpcClient,
+    signer: Arc<dyn Signer>,
+    address: String,
+    chain_id: u64,
+    journal: TxJournal,
+    /// Refuse to send while the fee cap is above this many gwei
+    max_fee_gwei: Option<Decimal>,
+}
+
+impl OnChainClient {
+    pub fn new(
+        data_paths: &DataPaths,
+        signer: Arc<dyn Signer>,
+        rpc_url: &str,
+        max_fee_gwei: Option<Decimal>,
+    ) -> Result<Self> {
+        let address = signer.address_hex();
+        Ok(Self {
+            rpc: JsonRpcClient::new(rpc_url)?,
+            signer,
+            journal: TxJournal::new(data_paths, &address),
+            address,
+            chain_id: POLYGON_CHAIN_ID,
+            max_fee_gwei,
+        })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn journal(&self) -> &TxJournal {
+        &self.journal
+    }
+
+    pub async fn current_fees(&self) -> Result<GasFees> {
+        GasOracle::with_client(self.rpc.clone()).fees().await
+    }
+
+    /// Send a new transaction at the next free nonce
+    pub async fn send(&self, request: &TxRequest) -> Result<TrackedTx> {
+        let fees = self.current_fees().await?;
+        fees.ensure_below(self.max_fee_gwei)?;
+        let mut caps = FeeCaps::current(&fees);
+
+        let mut last_error = None;
+        for attempt in 1..=SEND_ATTEMPTS {
+            let pending_count = self.rpc.transaction_count(&self.address, "pending").await?;
+            let nonce = next_nonce(pending_count, &self.journal.load()?);
+            match self.submit(request, nonce, caps).await {
+                Ok(tx) => {
+                    self.journal.record(tx.clone())?;
+                    return Ok(tx);
+                }
+                Err(e) => {
+                    let message = e.to_string().to_lowercase();
+                    if message.contains("underpriced") {
+                        // Something else holds this nonce in the mempool; outbid it
+                        caps = caps.bumped(&fees);
+                        ensure_max_fee(caps.max_fee_per_gas, self.max_fee_gwei)?;
+                    } else if !message.contains("nonce too low") {
+                        return Err(e);
+                    }
+                    warn!("{} attempt {}/{} failed: {}", request.action, attempt, SEND_ATTEMPTS, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("{} was not sent", request.action)))
+    }
+
+    /// Number of transactions from the wallet that are mined
+    pub async fn confirmed_nonce(&self) -> Result<u64> {
+        self.rpc.transaction_count(&self.address, "latest").await
+    }
+
+    /// Refresh the status of pending transactions from receipts and the confirmed nonce
+    pub async fn sync(&self) -> Result<Vec<TrackedTx>> {
+        let mut transactions = self.journal.load()?;
+        let confirmed_nonce = self.confirmed_nonce().await?;
+
+        for tx in transactions.iter_mut().filter(|tx| tx.status == TxStatus::Pending) {
+            let receipt = self
+                .rpc
+                .call("eth_getTransactionReceipt", json!([tx.hash]))
+                .await
+                .unwrap_or_default();
+            if let Some(status) = receipt.get("status").and_then(|v| v.as_str()) {
+                tx.status = if status == "0x1" {
+                    TxStatus::Confirmed
+                } else {
+                    TxStatus::Failed
+                };
+            } else if tx.nonce < confirmed_nonce {
+                tx.status = TxStatus::Dropped;
+            }
+        }
+        self.journal.save(&transactions)?;
+        Ok(transactions)
+    }
+
+    /// Resubmit the pending transaction at `nonce` with bumped fees
+    pub async fn speed_up(&self, nonce: u64) -> Result<TrackedTx> {
+        let original = self.pending_at(nonce)?;
+        let request = TxRequest {
+            action: original.action.clone(),
+            to: original.to.clone(),
+            data: hex::decode(original.data.trim_start_matches("0x"))?,
+            value: original.value,
+            gas_limit: original.gas_limit,
+        };
+        self.replace(&original, &request).await
+    }
+
+    /// Free up `nonce` with a zero value transfer to ourselves
+    pub async fn cancel(&self, nonce: u64) -> Result<TrackedTx> {
+        let original = self.pending_at(nonce)?;
+        let request = TxRequest {
+            action: format!("cancel {}", original.action),
+            to: self.address.clone(),
+            data: Vec::new(),
+            value: 0,
+            gas_limit: TRANSFER_GAS_LIMIT,
+        };
+        self.replace(&original, &request).await
+    }
+
+    fn pending_at(&self, nonce: u64) -> Result<TrackedTx> {
+        self.journal
+            .load()?
+            .into_iter()
+            .rev()
+            .find(|tx| tx.nonce == nonce && tx.status == TxStatus::Pending)
+            .ok_or_else(|| anyhow!("No pending transaction with nonce {}", nonce))
+    }
+
+    /// Fees a speed-up or cancellation of `nonce` would pay right now
+    pub async fn replacement_preview(&self, nonce: u64) -> Result<ReplacementPreview> {
+        let original = self.pending_at(nonce)?;
+        let fees = original.replacement_fees(&self.current_fees().await?);
+        Ok(ReplacementPreview { original, fees })
+    }
+
+    async fn replace(&self, original: &TrackedTx, request: &TxRequest) -> Result<TrackedTx> {
+        let fees = original.replacement_fees(&self.current_fees().await?);
+        ensure_max_fee(fees.max_fee_per_gas, self.max_fee_gwei)?;
+
+        let tx = self.submit(request, original.nonce, fees).await?;
+        self.journal.record_replacement(&original.hash, tx.clone())?;
+        info!("Replaced {} with {} at nonce {}", original.hash, tx.hash, tx.nonce);
+        Ok(tx)
+    }
+
+    async fn submit(&self, request: &TxRequest, nonce: u64, fees: FeeCaps) -> Result<TrackedTx> {
+        crate::auth::ensure_writable("send transactions")?;
+        let from: Address = self.address.parse().context("Invalid wallet address")?;
+        let to: Address = request.to.parse().context("Invalid destination address")?;
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(from)
+            .to(to)
+            .nonce(nonce)
+            .value(U256::from(request.value))
+            .data(Bytes::from(request.data.clone()))
+            .gas(request.gas_limit)
+            .max_fee_per_gas(U256::from(fees.max_fee_per_gas))
+            .max_priority_fee_per_gas(U256::from(fees.max_priority_fee_per_gas))
+            .chain_id(self.chain_id)
+            .into();
+        let signature = self.signer.sign_transaction(&tx).await?;
+        let raw = tx.rlp_signed(&signature);
+
+        let sent = self
+            .rpc
+            .call("eth_sendRawTransaction", json!([raw]))
+            .await
+            .and_then(|result| {
+                result
+                    .as_str()
+                    .map(String::from)
+                    .ok_or_else(|| anyhow!("eth_sendRawTransaction returned no hash"))
+            });
+        audit::record(
+            AuditAction::Transaction,
+            format!("{} (nonce {})", request.action, nonce),
+            json!({
+                "action": request.action,
+                "from": self.address,
+                "to": request.to,
+                "nonce": nonce,
+                "max_fee_per_gas": fees.max_fee_per_gas.to_string(),
+                "hash": sent.as_ref().ok(),
+            }),
+            sent.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+        );
+        let hash = sent?;
+        info!("Sent {} {} (nonce {})", request.action, hash, nonce);
+
+        Ok(TrackedTx {
+            hash,
+            nonce,
+            action: request.action.clone(),
+            to: request.to.clone(),
+            data: format!("0x{}", hex::encode(&request.data)),
+            value: request.value,
+            gas_limit: request.gas_limit,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            submitted_at: Utc::now(),
+            status: TxStatus::Pending,
+            replaced_by: None,
+        })
+    }
+}
@@ -0,0 +1,261 @@
+//! Nonce tracking and fee bumping for submitted transactions
+//!
+//! Every transaction the bot sends is appended to a per-wallet journal with
+//! its nonce and fees. The journal is what lets a later run pick the next
+//! nonce while earlier transactions are still in the mempool, notice that
+//! one is stuck, and rebuild it with higher fees under the same nonce.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::data_paths::DataPaths;
+use crate::ethereum_utils::GasFees;
+
+const TX_JOURNAL_FILE: &str = "transactions.json";
+
+/// Nodes only accept a replacement that raises both fees by at least 10%;
+/// bump by 12.5% so rounding never lands just under the threshold
+const FEE_BUMP_NUMERATOR: u128 = 9;
+const FEE_BUMP_DENOMINATOR: u128 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    /// Sent, no receipt yet
+    Pending,
+    Confirmed,
+    /// Mined but reverted
+    Failed,
+    /// Superseded by a speed-up or cancellation with the same nonce
+    Replaced,
+    /// Its nonce was used by a transaction this journal doesn't know
+    Dropped,
+}
+
+/// Transaction as the bot submitted it, enough to resubmit it with new fees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackedTx {
+    pub hash: String,
+    pub nonce: u64,
+    /// What the transaction does (approve, redeem, cancel, ...)
+    pub action: String,
+    pub to: String,
+    /// Hex encoded calldata
+    pub data: String,
+    /// Value in wei
+    pub value: u128,
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub submitted_at: DateTime<Utc>,
+    pub status: TxStatus,
+    /// Hash of the transaction that took over this nonce
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+}
+
+impl TrackedTx {
+    /// Pending for longer than `after` while its nonce is still unused on chain
+    pub fn is_stuck(&self, confirmed_nonce: u64, now: DateTime<Utc>, after: Duration) -> bool {
+        self.status == TxStatus::Pending
+            && self.nonce >= confirmed_nonce
+            && now - self.submitted_at > after
+    }
+
+    pub fn fee_caps(&self) -> FeeCaps {
+        FeeCaps {
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        }
+    }
+
+    /// Fees for a replacement: at least the required bump over this
+    /// transaction, and at least what the network asks for right now
+    pub fn replacement_fees(&self, current: &GasFees) -> FeeCaps {
+        self.fee_caps().bumped(current)
+    }
+}
+
+/// EIP-1559 fee caps of one transaction, in wei
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeCaps {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl FeeCaps {
+    /// What the gas oracle suggests for a new transaction
+    pub fn current(fees: &GasFees) -> Self {
+        Self {
+            max_fee_per_gas: fees.max_fee_per_gas(),
+            max_priority_fee_per_gas: fees.priority_fee,
+        }
+    }
+
+    /// Caps that outbid these under the same nonce
+    pub fn bumped(&self, current: &GasFees) -> Self {
+        let bump = |fee: u128| (fee * FEE_BUMP_NUMERATOR).div_ceil(FEE_BUMP_DENOMINATOR);
+        let max_priority_fee_per_gas = bump(self.max_priority_fee_per_gas).max(current.priority_fee);
+        let max_fee_per_gas = bump(self.max_fee_per_gas)
+            .max(current.max_fee_per_gas())
+            .max(max_priority_fee_per_gas);
+        Self {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+}
+
+/// Nonce for the next transaction: past both the node's pending count and
+/// anything still pending in the journal (a node that dropped our transaction
+/// from its mempool would otherwise hand the same nonce out again)
+pub fn next_nonce(pending_count: u64, journal: &[TrackedTx]) -> u64 {
+    journal
+        .iter()
+        .filter(|tx| tx.status == TxStatus::Pending)
+        .map(|tx| tx.nonce + 1)
+        .max()
+        .unwrap_or(0)
+        .max(pending_count)
+}
+
+/// Per-wallet transaction journal under `<data_dir>/onchain/<address>/`
+pub struct TxJournal {
+    path: PathBuf,
+}
+
+impl TxJournal {
+    pub fn new(data_paths: &DataPaths, address: &str) -> Self {
+        Self {
+            path: data_paths
+                .root()
+                .join("onchain")
+                .join(address.to_lowercase())
+                .join(TX_JOURNAL_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// All tracked transactions, oldest first
+    pub fn load(&self) -> Result<Vec<TrackedTx>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
+    }
+
+    pub fn save(&self, transactions: &[TrackedTx]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(transactions)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    pub fn record(&self, tx: TrackedTx) -> Result<()> {
+        let mut transactions = self.load()?;
+        transactions.push(tx);
+        self.save(&transactions)
+    }
+
+    /// Mark `hash` as replaced by `replacement` and record the replacement
+    pub fn record_replacement(&self, hash: &str, replacement: TrackedTx) -> Result<()> {
+        let mut transactions = self.load()?;
+        if let Some(original) = transactions.iter_mut().find(|tx| tx.hash == hash) {
+            original.status = TxStatus::Replaced;
+            original.replaced_by = Some(replacement.hash.clone());
+        }
+        transactions.push(replacement);
+        self.save(&transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    fn tx(nonce: u64, status: TxStatus) -> TrackedTx {
+        TrackedTx {
+            hash: format!("0x{}", nonce),
+            nonce,
+            action: "approve".to_string(),
+            to: "0x0".to_string(),
+            data: "0x".to_string(),
+            value: 0,
+            gas_limit: 60_000,
+            max_fee_per_gas: 100 * GWEI,
+            max_priority_fee_per_gas: 30 * GWEI,
+            submitted_at: Utc::now() - Duration::minutes(10),
+            status,
+            replaced_by: None,
+        }
+    }
+
+    #[test]
+    fn next_nonce_skips_locally_pending_transactions() {
+        assert_eq!(next_nonce(5, &[]), 5);
+        let journal = [tx(5, TxStatus::Pending), tx(6, TxStatus::Pending), tx(9, TxStatus::Confirmed)];
+        assert_eq!(next_nonce(5, &journal), 7);
+        assert_eq!(next_nonce(8, &journal), 8);
+    }
+
+    #[test]
+    fn stuck_only_while_nonce_is_unused() {
+        let pending = tx(5, TxStatus::Pending);
+        let now = Utc::now();
+        assert!(pending.is_stuck(5, now, Duration::minutes(5)));
+        assert!(!pending.is_stuck(6, now, Duration::minutes(5)));
+        assert!(!pending.is_stuck(5, now, Duration::minutes(30)));
+        assert!(!tx(5, TxStatus::Confirmed).is_stuck(5, now, Duration::minutes(5)));
+    }
+
+    #[test]
+    fn replacement_fees_clear_the_bump_threshold() {
+        let quiet = GasFees {
+            base_fee: GWEI,
+            priority_fee: 30 * GWEI,
+            eip1559: true,
+        };
+        let fees = tx(1, TxStatus::Pending).replacement_fees(&quiet);
+        assert_eq!(fees.max_priority_fee_per_gas, 33_750_000_000);
+        assert_eq!(fees.max_fee_per_gas, 112_500_000_000);
+
+        let congested = GasFees {
+            base_fee: 200 * GWEI,
+            priority_fee: 50 * GWEI,
+            eip1559: true,
+        };
+        let fees = tx(1, TxStatus::Pending).replacement_fees(&congested);
+        assert_eq!(fees.max_priority_fee_per_gas, 50 * GWEI);
+        assert_eq!(fees.max_fee_per_gas, 450 * GWEI);
+    }
+
+    #[test]
+    fn journal_round_trip_and_replacement() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = TxJournal::new(&DataPaths::new(dir.path()), "0xABC");
+        journal.record(tx(1, TxStatus::Pending)).unwrap();
+        let mut speed_up = tx(1, TxStatus::Pending);
+        speed_up.hash = "0x1b".to_string();
+        journal.record_replacement("0x1", speed_up).unwrap();
+
+        let transactions = journal.load().unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].status, TxStatus::Replaced);
+        assert_eq!(transactions[0].replaced_by.as_deref(), Some("0x1b"));
+        assert_eq!(next_nonce(0, &transactions), 2);
+        assert!(journal.path().to_string_lossy().contains("0xabc"));
+    }
+}
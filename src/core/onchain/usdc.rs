@@ -0,0 +1,106 @@
+//! USDC.e transactions a trading wallet sends: exchange approvals, deposits
+//! into a trading wallet and withdrawals to another address
+//!
+//! Each builds a [`TxRequest`] for [`OnChainClient::send`](super::OnChainClient::send),
+//! so every transfer is journaled and can be sped up or cancelled later.
+
+use anyhow::{anyhow, Context, Result};
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::{Address, U256};
+use rust_decimal::Decimal;
+
+use super::TxRequest;
+use crate::auth::clob::{CTF_EXCHANGE_ADDRESS, NEG_RISK_CTF_EXCHANGE_ADDRESS};
+use crate::core::funds::USDC_E_ADDRESS;
+use crate::ethereum_utils::OnChainAction;
+
+/// `approve(address,uint256)` selector
+const APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+/// `transfer(address,uint256)` selector
+const TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+const USDC_DECIMALS: u32 = 6;
+
+/// Contracts that move the wallet's USDC.e when orders match
+pub const EXCHANGE_SPENDERS: [&str; 2] = [CTF_EXCHANGE_ADDRESS, NEG_RISK_CTF_EXCHANGE_ADDRESS];
+
+impl TxRequest {
+    /// Let `spender` move `amount` USDC.e, or any amount when `None`
+    pub fn usdc_approve(spender: &str, amount: Option<Decimal>) -> Result<Self> {
+        let amount = match amount {
+            Some(amount) => U256::from(Self::usdc_base_units(amount)?),
+            None => U256::MAX,
+        };
+        Self::usdc_call(OnChainAction::APPROVE, APPROVE_SELECTOR, spender, amount)
+    }
+
+    /// Send `amount` USDC.e to `to`, journaled as `action` (deposit or withdraw)
+    pub fn usdc_transfer(action: OnChainAction, to: &str, amount: Decimal) -> Result<Self> {
+        let amount = U256::from(Self::usdc_base_units(amount)?);
+        Self::usdc_call(action, TRANSFER_SELECTOR, to, amount)
+    }
+
+    fn usdc_call(action: OnChainAction, selector: [u8; 4], account: &str, amount: U256) -> Result<Self> {
+        let account: Address = account
+            .parse()
+            .with_context(|| format!("Invalid address: {}", account))?;
+        let mut data = selector.to_vec();
+        data.extend(encode(&[Token::Address(account), Token::Uint(amount)]));
+        Ok(Self {
+            action: action.name.to_string(),
+            to: USDC_E_ADDRESS.to_string(),
+            data,
+            value: 0,
+            gas_limit: action.gas_limit,
+        })
+    }
+
+    /// Positive USDC amount in the token's 6 decimal base units
+    fn usdc_base_units(amount: Decimal) -> Result<u128> {
+        if amount <= Decimal::ZERO {
+            return Err(anyhow!("Amount must be positive, got {}", amount));
+        }
+        if amount.normalize().scale() > USDC_DECIMALS {
+            return Err(anyhow!("USDC has {} decimals, got {}", USDC_DECIMALS, amount));
+        }
+        let base_units = amount * Decimal::from(10u64.pow(USDC_DECIMALS));
+        u128::try_from(base_units).map_err(|_| anyhow!("Amount out of range: {}", amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const WALLET: &str = "0xabcdef0123456789abcdef0123456789abcdef01";
+
+    #[test]
+    fn encodes_transfer_calldata() {
+        let request = TxRequest::usdc_transfer(OnChainAction::WITHDRAW, WALLET, dec!(100)).unwrap();
+        assert_eq!(request.action, "withdraw");
+        assert_eq!(request.to, USDC_E_ADDRESS);
+        assert_eq!(request.gas_limit, OnChainAction::WITHDRAW.gas_limit);
+        assert_eq!(
+            hex::encode(&request.data),
+            "a9059cbb\
+             000000000000000000000000abcdef0123456789abcdef0123456789abcdef01\
+             0000000000000000000000000000000000000000000000000000000005f5e100"
+        );
+    }
+
+    #[test]
+    fn unlimited_approval_uses_max_uint() {
+        let request = TxRequest::usdc_approve(CTF_EXCHANGE_ADDRESS, None).unwrap();
+        assert_eq!(&request.data[..4], &APPROVE_SELECTOR);
+        assert!(request.data[36..].iter().all(|byte| *byte == 0xff));
+    }
+
+    #[test]
+    fn rejects_amounts_usdc_cannot_hold() {
+        assert!(TxRequest::usdc_transfer(OnChainAction::DEPOSIT, WALLET, dec!(0)).is_err());
+        assert!(TxRequest::usdc_transfer(OnChainAction::DEPOSIT, WALLET, dec!(0.0000001)).is_err());
+        assert!(TxRequest::usdc_transfer(OnChainAction::DEPOSIT, WALLET, dec!(1.500000)).is_ok());
+        assert!(TxRequest::usdc_transfer(OnChainAction::DEPOSIT, "not-an-address", dec!(1)).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! Conversions between the portfolio's API types and the rest of the app
+//!
+//! Orders arrive from the CLOB as [`PolymarketOrder`], the order manager and
+//! GUI work with [`EnhancedOrder`], the TUI's portfolio manager tracks
+//! [`ActiveOrder`], and positions are reconciled and stored from
+//! [`PolymarketOrder`]. These conversions are the only place the order
+//! shapes are mapped onto each other, so every front end reconciles
+//! the same orders into the same positions.
+
+use chrono::{TimeZone, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::core::execution::orders::{EnhancedOrder, OrderMarketInfo, OrderSide, OrderStatus};
+use crate::core::portfolio::api::orders::{BalanceInfo, PolymarketOrder};
+use crate::core::portfolio::storage::AccountBalances;
+use crate::core::portfolio::types::{self, ActiveOrder, OrderType, TimeInForce};
+
+impl From<PolymarketOrder> for EnhancedOrder {
+    fn from(poly_order: PolymarketOrder) -> Self {
+        // Parse side
+        let side = match poly_order.side.as_str() {
+            "BUY" => OrderSide::Buy,
+            "SELL" => OrderSide::Sell,
+            _ => OrderSide::Buy, // Default fallback
+        };
+
+        // Parse status
+        let status = match poly_order.status.as_str() {
+            "OPEN" => OrderStatus::Open,
+            "FILLED" => OrderStatus::Filled,
+            "CANCELLED" => OrderStatus::Cancelled,
+            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
+            "REJECTED" => OrderStatus::Rejected,
+            "PENDING" => OrderStatus::Pending,
+            _ => OrderStatus::Open, // Default fallback
+        };
+
+        // Convert timestamps (Polymarket uses Unix timestamps in seconds)
+        let created_at = Utc
+            .timestamp_opt(poly_order.created_at as i64, 0)
+            .single()
+            .unwrap_or_else(Utc::now);
+
+        // Parse sizes
+        let size_matched = poly_order
+            .size_matched
+            .parse::<Decimal>()
+            .unwrap_or_default();
+        let filled_size = size_matched.to_f64().unwrap_or(0.0);
+        let original_size = poly_order.size_structured.to_f64().unwrap_or(0.0);
+        let remaining_size = original_size - filled_size;
+
+        // Build additional fields map for extra data
+        let mut additional_fields = HashMap::new();
+        for (key, value) in [
+            ("market", &poly_order.market),
+            ("owner", &poly_order.owner),
+            ("outcome", &poly_order.outcome),
+            ("order_type", &poly_order.order_type),
+            ("expiration", &poly_order.expiration),
+            ("maker_address", &poly_order.maker_address),
+        ] {
+            additional_fields.insert(key.to_string(), serde_json::Value::String(value.clone()));
+        }
+
+        if let Some(fee_rate) = poly_order.fee_rate_bps {
+            additional_fields.insert(
+                "fee_rate_bps".to_string(),
+                serde_json::Value::Number(serde_json::Number::from(fee_rate)),
+            );
+        }
+
+        if let Some(condition_id) = poly_order.condition_id {
+            additional_fields.insert(
+                "condition_id".to_string(),
+                serde_json::Value::String(condition_id),
+            );
+        }
+
+        if let Some(question_id) = poly_order.question_id {
+            additional_fields.insert(
+                "question_id".to_string(),
+                serde_json::Value::String(question_id),
+            );
+        }
+
+        // Create market info
+        let market_info = Some(OrderMarketInfo {
+            market_question: Some(poly_order.market.clone()),
+            token_outcome: Some(poly_order.outcome.clone()),
+            market_price_at_order: None, // Not provided by API
+            spread_at_order: None,       // Not provided by API
+            liquidity_at_order: None,    // Not provided by API
+        });
+
+        EnhancedOrder {
+            id: poly_order.id,
+            asset_id: poly_order.asset_id,
+            side,
+            price: poly_order.price.to_f64().unwrap_or(0.0),
+            size: original_size,
+            original_size,
+            filled_size,
+            remaining_size,
+            status,
+            created_at,
+            updated_at: None,         // Not provided by API
+            filled_at: None,          // Not provided by API
+            cancelled_at: None,       // Not provided by API
+            fees_paid: None,          // Not provided by API
+            average_fill_price: None, // Not provided by API
+            market_info,
+            additional_fields,
+        }
+    }
+}
+
+impl From<&EnhancedOrder> for PolymarketOrder {
+    fn from(enhanced: &EnhancedOrder) -> Self {
+        let field = |key: &str| {
+            enhanced
+                .additional_fields
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        let side = match enhanced.side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+
+        let status = match enhanced.status {
+            OrderStatus::Open => "OPEN",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Cancelled => "CANCELLED",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Rejected => "REJECTED",
+            OrderStatus::Pending => "PENDING",
+        };
+
+        PolymarketOrder {
+            id: enhanced.id.clone(),
+            owner: field("owner").unwrap_or_default(),
+            market: field("market").unwrap_or_else(|| enhanced.asset_id.clone()),
+            asset_id: enhanced.asset_id.clone(),
+            side: side.to_string(),
+            price: Decimal::from_f64(enhanced.price).unwrap_or_default(),
+            size_structured: Decimal::from_f64(enhanced.original_size).unwrap_or_default(),
+            size_matched: enhanced.filled_size.to_string(),
+            status: status.to_string(),
+            created_at: enhanced.created_at.timestamp() as u64,
+            maker_address: field("maker_address").unwrap_or_default(),
+            outcome: field("outcome").unwrap_or_else(|| "YES".to_string()),
+            expiration: field("expiration").unwrap_or_else(|| "0".to_string()),
+            order_type: field("order_type").unwrap_or_else(|| "LIMIT".to_string()),
+            associate_trades: Vec::new(),
+            fee_rate_bps: enhanced
+                .additional_fields
+                .get("fee_rate_bps")
+                .and_then(|v| v.as_i64())
+                .map(|v| v as i32),
+            nonce: None,
+            condition_id: field("condition_id"),
+            token_id: Some(enhanced.asset_id.clone()),
+            question_id: field("question_id"),
+        }
+    }
+}
+
+impl From<&EnhancedOrder> for ActiveOrder {
+    fn from(enhanced: &EnhancedOrder) -> Self {
+        let side = match enhanced.side {
+            OrderSide::Buy => types::OrderSide::Buy,
+            OrderSide::Sell => types::OrderSide::Sell,
+        };
+
+        let status = match enhanced.status {
+            OrderStatus::Open => types::OrderStatus::Open,
+            OrderStatus::Filled => types::OrderStatus::Filled,
+            OrderStatus::Cancelled => types::OrderStatus::Cancelled,
+            OrderStatus::PartiallyFilled => types::OrderStatus::PartiallyFilled,
+            OrderStatus::Rejected => types::OrderStatus::Rejected,
+            OrderStatus::Pending => types::OrderStatus::Pending,
+        };
+
+        // Extract outcome from market info or additional fields
+        let outcome = enhanced
+            .market_info
+            .as_ref()
+            .and_then(|info| info.token_outcome.clone())
+            .or_else(|| {
+                enhanced
+                    .additional_fields
+                    .get("outcome")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            })
+            .unwrap_or_else(|| "YES".to_string());
+
+        // Extract market_id from additional fields or use asset_id as fallback
+        let market_id = enhanced
+            .additional_fields
+            .get("market")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| enhanced.asset_id.clone());
+
+        ActiveOrder {
+            order_id: enhanced.id.clone(),
+            market_id,
+            token_id: enhanced.asset_id.clone(),
+            outcome,
+            side,
+            order_type: OrderType::Limit, // Default to limit, API doesn't always provide this
+            price: Decimal::from_f64(enhanced.price).unwrap_or_default(),
+            size: Decimal::from_f64(enhanced.original_size).unwrap_or_default(),
+            filled_size: Decimal::from_f64(enhanced.filled_size).unwrap_or_default(),
+            remaining_size: Decimal::from_f64(enhanced.remaining_size).unwrap_or_default(),
+            status,
+            created_at: enhanced.created_at,
+            updated_at: enhanced.updated_at.unwrap_or_else(Utc::now),
+            time_in_force: TimeInForce::GTC, // Default to Good Till Cancelled
+            post_only: false,                // Default, not provided by API
+            reduce_only: false,              // Default, not provided by API
+        }
+    }
+}
+
+impl From<&BalanceInfo> for AccountBalances {
+    fn from(balance: &BalanceInfo) -> Self {
+        Self {
+            total_value: balance.equity_total,
+            available_cash: balance.cash,
+            locked_in_orders: balance.bets,
+            position_value: balance.equity_total - balance.cash,
+            last_updated: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::portfolio::controller::PositionReconciler;
+
+    fn fixture_orders() -> Vec<PolymarketOrder> {
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("../../../tests/fixtures/rest/orders.json")).unwrap();
+        serde_json::from_value(page["data"].clone()).unwrap()
+    }
+
+    #[test]
+    fn orders_reconcile_the_same_through_either_shape() {
+        let orders = fixture_orders();
+        let round_tripped: Vec<PolymarketOrder> = orders
+            .iter()
+            .cloned()
+            .map(EnhancedOrder::from)
+            .map(|order| PolymarketOrder::from(&order))
+            .collect();
+
+        let direct = PositionReconciler::new()
+            .reconcile_from_orders(&orders)
+            .unwrap();
+        let adapted = PositionReconciler::new()
+            .reconcile_from_orders(&round_tripped)
+            .unwrap();
+
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct.len(), adapted.len());
+        for (a, b) in direct.iter().zip(&adapted) {
+            assert_eq!(
+                (
+                    &a.token_id,
+                    &a.market_id,
+                    &a.outcome,
+                    a.size,
+                    a.average_price
+                ),
+                (
+                    &b.token_id,
+                    &b.market_id,
+                    &b.outcome,
+                    b.size,
+                    b.average_price
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_order_identity() {
+        for order in fixture_orders() {
+            let back = PolymarketOrder::from(&EnhancedOrder::from(order.clone()));
+            assert_eq!(back.id, order.id);
+            assert_eq!(back.asset_id, order.asset_id);
+            assert_eq!(back.market, order.market);
+            assert_eq!(back.side, order.side);
+            assert_eq!(back.status, order.status);
+            assert_eq!(back.price, order.price);
+            assert_eq!(
+                back.size_matched.parse::<Decimal>().unwrap(),
+                order.size_matched.parse::<Decimal>().unwrap()
+            );
+            assert_eq!(back.created_at, order.created_at);
+        }
+    }
+
+    #[test]
+    fn active_orders_match_the_api_orders() {
+        for order in fixture_orders() {
+            let active = ActiveOrder::from(&EnhancedOrder::from(order.clone()));
+            assert_eq!(active.order_id, order.id);
+            assert_eq!(active.token_id, order.asset_id);
+            assert_eq!(active.market_id, order.market);
+            assert_eq!(active.outcome, order.outcome);
+            assert_eq!(active.price, order.price);
+            assert_eq!(
+                active.filled_size,
+                order.size_matched.parse::<Decimal>().unwrap()
+            );
+            assert_eq!(
+                active.remaining_size,
+                order.size_structured - active.filled_size
+            );
+        }
+    }
+
+    #[test]
+    fn balances_map_onto_account_balances() {
+        let balance = BalanceInfo {
+            bets: Decimal::new(2500, 2),
+            cash: Decimal::new(10000, 2),
+            equity_total: Decimal::new(14000, 2),
+        };
+        let account = AccountBalances::from(&balance);
+        assert_eq!(account.total_value, balance.equity_total);
+        assert_eq!(account.available_cash, balance.cash);
+        assert_eq!(account.locked_in_orders, balance.bets);
+        assert_eq!(account.position_value, Decimal::new(4000, 2));
+    }
+}
@@ -4,6 +4,7 @@ use crate::auth_env;
 use crate::config;
 use crate::data_paths::DataPaths;
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
 use reqwest::header::{HeaderMap, HeaderValue};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -51,11 +52,201 @@ pub struct PolymarketOrder {
     pub question_id: Option<String>,
 }
 
-/// Response structure for the orders endpoint
+impl PolymarketOrder {
+    /// Whether the order still rests on the book
+    pub fn is_open(&self) -> bool {
+        matches!(self.status.to_ascii_uppercase().as_str(), "LIVE" | "OPEN")
+    }
+
+    pub fn created(&self) -> DateTime<Utc> {
+        Utc.timestamp_opt(self.created_at as i64, 0)
+            .single()
+            .unwrap_or_default()
+    }
+
+    /// Shares matched so far; the API sends them as a string
+    pub fn matched_size(&self) -> Decimal {
+        self.size_matched.parse().unwrap_or(Decimal::ZERO)
+    }
+}
+
+/// Cursor of the first page of a paginated CLOB endpoint
+pub const FIRST_CURSOR: &str = "MA==";
+
+/// Cursor the CLOB returns after the last page
+pub const END_CURSOR: &str = "LTE=";
+
+/// Pages [`fetch_all_orders`] follows at most
+const MAX_ORDER_PAGES: usize = 100;
+
+/// One page of the orders endpoint
 #[derive(Debug, Deserialize)]
 pub struct OrdersResponse {
-    pub _orders: Vec<PolymarketOrder>,
-    pub _next: Option<String>,
+    pub data: Vec<PolymarketOrder>,
+    pub next_cursor: Option<String>,
+}
+
+/// Which orders a query returns; the exchange narrows by market and token
+/// itself, the rest is applied to what it returns
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderFilter {
+    /// Market (condition) ID
+    pub market: Option<String>,
+    pub asset_id: Option<String>,
+    /// Orders created at or after this
+    pub since: Option<DateTime<Utc>>,
+    /// Orders created before this
+    pub until: Option<DateTime<Utc>>,
+    /// Statuses kept, case-insensitive; empty keeps all
+    pub statuses: Vec<String>,
+}
+
+impl OrderFilter {
+    pub fn matches(&self, order: &PolymarketOrder) -> bool {
+        let created = order.created();
+        self.market.as_ref().is_none_or(|market| {
+            order.market == *market || order.condition_id.as_ref() == Some(market)
+        }) && self.asset_id.as_ref().is_none_or(|asset_id| {
+            order.asset_id == *asset_id || order.token_id.as_ref() == Some(asset_id)
+        }) && self.since.is_none_or(|since| created >= since)
+            && self.until.is_none_or(|until| created < until)
+            && (self.statuses.is_empty()
+                || self
+                    .statuses
+                    .iter()
+                    .any(|status| status.eq_ignore_ascii_case(&order.status)))
+    }
+}
+
+/// Authenticated GET of `path` on the CLOB, `None` when it's not found
+async fn get_authenticated(
+    host: &str,
+    data_paths: &DataPaths,
+    user_address: &str,
+    path: &str,
+    query: &[(&str, &str)],
+) -> Result<Option<String>> {
+    let api_creds = config::load_credentials(data_paths)
+        .await
+        .map_err(|e| anyhow!("No credentials found. Run 'cargo run -- init' first: {}", e))?;
+    // Requests are signed over the path alone, without the query string
+    let headers = build_auth_headers(
+        &api_creds.api_key,
+        &api_creds.secret,
+        &api_creds.passphrase,
+        user_address,
+        "GET",
+        path,
+        None,
+    )?;
+
+    let api_url = format!("{}{}", host.trim_end_matches('/'), path);
+    let response = reqwest::Client::new()
+        .get(&api_url)
+        .query(query)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to send request: {}", e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "No error details".to_string());
+        return Err(anyhow!(
+            "API request failed with status {}: {}",
+            status,
+            error_text
+        ));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| anyhow!("Failed to get response text: {}", e))?;
+    Ok(Some(text))
+}
+
+/// Fetch the page of the account's orders at `cursor` ([`FIRST_CURSOR`] for
+/// the first), narrowed by the filter's market and token
+pub async fn fetch_orders_page(
+    host: &str,
+    data_paths: &DataPaths,
+    user_address: &str,
+    filter: &OrderFilter,
+    cursor: &str,
+) -> Result<OrdersResponse> {
+    let mut query = vec![("next_cursor", cursor)];
+    if let Some(market) = &filter.market {
+        query.push(("market", market));
+    }
+    if let Some(asset_id) = &filter.asset_id {
+        query.push(("asset_id", asset_id));
+    }
+    let text = get_authenticated(host, data_paths, user_address, "/data/orders", &query)
+        .await?
+        .ok_or_else(|| anyhow!("Orders endpoint not found on {}", host))?;
+    serde_json::from_str(&text).map_err(|e| {
+        anyhow!(
+            "Failed to parse response JSON: {}. Response was: {}",
+            e,
+            text
+        )
+    })
+}
+
+/// Fetch every page of the account's orders matching `filter`
+pub async fn fetch_all_orders(
+    host: &str,
+    data_paths: &DataPaths,
+    user_address: &str,
+    filter: &OrderFilter,
+) -> Result<Vec<PolymarketOrder>> {
+    let mut orders = Vec::new();
+    let mut cursor = FIRST_CURSOR.to_string();
+    for page in 1..=MAX_ORDER_PAGES {
+        let response = fetch_orders_page(host, data_paths, user_address, filter, &cursor).await?;
+        debug!("Orders page {}: {} orders", page, response.data.len());
+        orders.extend(response.data.into_iter().filter(|o| filter.matches(o)));
+        match response.next_cursor {
+            Some(next) if !next.is_empty() && next != END_CURSOR && next != cursor => cursor = next,
+            _ => return Ok(orders),
+        }
+    }
+    warn!(
+        "Stopped after {} pages of orders; later orders are left out",
+        MAX_ORDER_PAGES
+    );
+    Ok(orders)
+}
+
+/// Fetch one order by ID, whatever its status; `None` when the exchange
+/// doesn't know it
+pub async fn fetch_order(
+    host: &str,
+    data_paths: &DataPaths,
+    user_address: &str,
+    order_id: &str,
+) -> Result<Option<PolymarketOrder>> {
+    let path = format!("/data/order/{}", order_id);
+    let Some(text) = get_authenticated(host, data_paths, user_address, &path, &[]).await? else {
+        return Ok(None);
+    };
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&text).map_err(|e| {
+        anyhow!(
+            "Failed to parse order {}: {}. Response was: {}",
+            order_id,
+            e,
+            text
+        )
+    })
 }
 
 
@@ -15,11 +15,14 @@ use uuid::Uuid;
 use crate::auth;
 use crate::core::portfolio::api::orders::PolymarketOrder;
 use crate::core::portfolio::api::types::*;
+use crate::core::portfolio::pnl_history::{PnlHistory, PnlPoint};
 use crate::core::portfolio::storage::{
     AccountBalances, PortfolioSnapshot, PortfolioStorage, RawDataStorage, SnapshotMetadata,
     SnapshotReason,
 };
 use crate::core::portfolio::types::*;
+use crate::core::portfolio::units::open_exposures;
+use crate::core::portfolio::webhook::PortfolioWebhook;
 use crate::data_paths::DataPaths;
 
 /// Portfolio service actor
@@ -38,6 +41,8 @@ pub struct PortfolioService {
     command_rx: mpsc::Receiver<PortfolioCommand>,
     /// Raw storage for trades and orders
     raw_storage: RawDataStorage,
+    /// Endpoint recorded snapshots are sent to
+    webhook: Option<PortfolioWebhook>,
 }
 
 impl PortfolioService {
@@ -50,6 +55,7 @@ impl PortfolioService {
     ) -> Self {
         let storage = PortfolioStorage::new(data_paths.root(), &address);
         let raw_storage = RawDataStorage::new(data_paths.root());
+        let webhook = PortfolioWebhook::load_for_service(&data_paths);
 
         let state = RwLock::new(PortfolioState {
             positions: Vec::new(),
@@ -82,6 +88,7 @@ impl PortfolioService {
             state,
             command_rx,
             raw_storage,
+            webhook,
         }
     }
 
@@ -225,6 +232,12 @@ impl PortfolioService {
     async fn refresh_data(&mut self) -> Result<()> {
         debug!("Refreshing portfolio data from API...");
 
+        if auth::is_read_only() {
+            self.refresh_public_data().await?;
+            self.record_pnl_point(false).await;
+            return Ok(());
+        }
+
         // Get authenticated client
         let _client = auth::get_authenticated_client(&self.host, &self.data_paths).await?;
 
@@ -237,13 +250,7 @@ impl PortfolioService {
         .await
         {
             let mut state = self.state.write().await;
-            state.balances = AccountBalances {
-                total_value: balance_info.equity_total,
-                available_cash: balance_info.cash,
-                locked_in_orders: balance_info.bets,
-                position_value: balance_info.equity_total - balance_info.cash,
-                last_updated: Utc::now(),
-            };
+            state.balances = AccountBalances::from(&balance_info);
             state.last_updated = Utc::now();
             state.is_synced = true;
 
@@ -251,6 +258,8 @@ impl PortfolioService {
                 "Updated balance: total={}, cash={}, locked={}",
                 balance_info.equity_total, balance_info.cash, balance_info.bets
             );
+            drop(state);
+            self.record_pnl_point(true).await;
         }
 
         // TODO: Fetch orders and trades when API endpoints are available
@@ -258,6 +267,51 @@ impl PortfolioService {
         Ok(())
     }
 
+    /// Append the refreshed account value to the P&L history; `with_cash`
+    /// is false when only positions could be valued. Each point recorded is
+    /// also sent to the portfolio webhook.
+    async fn record_pnl_point(&self, with_cash: bool) {
+        let state = self.state.read().await;
+        let point = PnlPoint::new(
+            Utc::now(),
+            state.balances.total_value,
+            with_cash.then_some(state.balances.available_cash),
+            &state.positions,
+        );
+        match PnlHistory::new(&self.data_paths, &self.address).record(&point).await {
+            Ok(true) => {
+                if let Some(webhook) = &self.webhook {
+                    webhook.post_snapshot(&self.address, &state.balances, &state.positions);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Failed to record P&L history: {}", e),
+        }
+    }
+
+    /// Read-only profiles have no API credentials: value positions from the public data API
+    async fn refresh_public_data(&mut self) -> Result<()> {
+        let client = crate::markets::gamma_api::client::GammaApiClient::new()?;
+        let positions = client.get_all_positions(&self.address, None).await?;
+        let position_value: Decimal = positions
+            .iter()
+            .map(|p| p.size * p.current_price.unwrap_or(p.average_price))
+            .sum();
+
+        let mut state = self.state.write().await;
+        state.balances = AccountBalances {
+            total_value: position_value,
+            available_cash: Decimal::ZERO,
+            locked_in_orders: Decimal::ZERO,
+            position_value,
+            last_updated: Utc::now(),
+        };
+        state.last_updated = Utc::now();
+        state.is_synced = true;
+        debug!("Updated public position value: {}", position_value);
+        Ok(())
+    }
+
     /// Create portfolio snapshot
     async fn create_snapshot(&self, reason: &str) -> Result<String> {
         let state = self.state.read().await;
@@ -266,6 +320,7 @@ impl PortfolioService {
             timestamp: Utc::now(),
             address: self.address.clone(),
             positions: state.positions.clone(),
+            exposure: open_exposures(&state.positions),
             active_orders: Vec::new(), // TODO: Convert ActiveOrder to PolymarketOrder
             stats: state.stats.clone(),
             balances: state.balances.clone(),
@@ -290,6 +345,7 @@ impl PortfolioService {
         price: Decimal,
         size: Decimal,
     ) -> Result<String> {
+        auth::ensure_writable("place orders")?;
         info!(
             "Processing buy order: market={}, token={}, price={}, size={}",
             market_id, token_id, price, size
@@ -335,7 +391,8 @@ impl PortfolioService {
         token_id: &str,
         price: Decimal,
         size: Decimal,
-    ) -> Result<bool> {
+    ) -> Result<String> {
+        auth::ensure_writable("place orders")?;
         info!(
             "Processing sell order: market={}, token={}, price={}, size={}",
             market_id, token_id, price, size
@@ -371,11 +428,12 @@ impl PortfolioService {
 
         self.raw_storage.store_order(&mock_order).await?;
 
-        Ok(true)
+        Ok(order_id)
     }
 
     /// Handle cancel order
     async fn handle_cancel(&self, order_id: &str) -> Result<bool> {
+        auth::ensure_writable("cancel orders")?;
         info!("Processing cancel order: {}", order_id);
 
         // TODO: Implement actual cancel order via API
@@ -25,7 +25,7 @@ pub enum PortfolioCommand {
         token_id: String,
         price: Decimal,
         size: Decimal,
-        response: oneshot::Sender<Result<bool>>,
+        response: oneshot::Sender<Result<String>>,
     },
     Cancel {
         order_id: String,
@@ -106,7 +106,7 @@ impl PortfolioServiceHandle {
         token_id: String,
         price: Decimal,
         size: Decimal,
-    ) -> Result<bool> {
+    ) -> Result<String> {
         let (tx, rx) = oneshot::channel();
         self.sender
             .send(PortfolioCommand::Sell {
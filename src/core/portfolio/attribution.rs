@@ -0,0 +1,267 @@
+//! P&L attribution for market making
+//!
+//! Splits a token's mark-to-market P&L into three parts:
+//! - **Spread capture**: what each fill earned against the mid at fill time,
+//!   `(mid - price) × size` for buys and `(price - mid) × size` for sells
+//! - **Inventory drift**: the held position marked to the mid as it moves,
+//!   `position × Δmid` between consecutive marks
+//! - **Fees**: fees paid on the fills
+//!
+//! The three add up to the P&L of the fills marked at the last mid. The mid at
+//! a point in time is the latest price history point at or before it.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use super::storage::file::TradeRecord;
+use super::types::OrderSide;
+
+/// A token's mid price at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mark {
+    pub timestamp: DateTime<Utc>,
+    pub price: Decimal,
+}
+
+/// Attribution of one token on one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttributionRow {
+    pub date: NaiveDate,
+    pub token_id: String,
+    pub fills: usize,
+    /// Shares filled
+    pub volume: Decimal,
+    pub spread_capture: Decimal,
+    pub inventory_drift: Decimal,
+    pub fees: Decimal,
+    /// Position at the end of the day
+    pub end_position: Decimal,
+}
+
+impl AttributionRow {
+    fn new(date: NaiveDate, token_id: &str) -> Self {
+        Self {
+            date,
+            token_id: token_id.to_string(),
+            fills: 0,
+            volume: Decimal::ZERO,
+            spread_capture: Decimal::ZERO,
+            inventory_drift: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            end_position: Decimal::ZERO,
+        }
+    }
+
+    /// Spread capture plus inventory drift, net of fees
+    pub fn total(&self) -> Decimal {
+        self.spread_capture + self.inventory_drift - self.fees
+    }
+}
+
+enum Step<'a> {
+    Mark(Mark),
+    Fill(&'a TradeRecord),
+}
+
+impl Step<'_> {
+    fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Step::Mark(mark) => mark.timestamp,
+            Step::Fill(fill) => fill.timestamp,
+        }
+    }
+
+    fn is_fill(&self) -> bool {
+        matches!(self, Step::Fill(_))
+    }
+}
+
+/// Attribute the fills of every token from `since` on, per UTC day
+///
+/// `marks` holds each token's price history sorted by time. Fills before
+/// `since` only set the opening position. Tokens without marks are skipped.
+/// Rows come out sorted by date, then token.
+pub fn attribute(
+    fills: &[TradeRecord],
+    marks: &HashMap<String, Vec<Mark>>,
+    since: DateTime<Utc>,
+) -> Vec<AttributionRow> {
+    let mut by_token: BTreeMap<&str, Vec<&TradeRecord>> = BTreeMap::new();
+    for fill in fills {
+        by_token
+            .entry(fill.asset_id.as_str())
+            .or_default()
+            .push(fill);
+    }
+
+    let mut rows = Vec::new();
+    for (token_id, mut token_fills) in by_token {
+        let Some(token_marks) = marks.get(token_id).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        token_fills.sort_by_key(|fill| fill.timestamp);
+        rows.extend(attribute_token(token_id, &token_fills, token_marks, since));
+    }
+    rows.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then_with(|| a.token_id.cmp(&b.token_id))
+    });
+    rows
+}
+
+fn attribute_token(
+    token_id: &str,
+    fills: &[&TradeRecord],
+    marks: &[Mark],
+    since: DateTime<Utc>,
+) -> Vec<AttributionRow> {
+    let (opening, fills): (Vec<&TradeRecord>, Vec<&TradeRecord>) = fills
+        .iter()
+        .copied()
+        .partition(|fill| fill.timestamp < since);
+    let mut position: Decimal = opening.into_iter().map(signed_size).sum();
+
+    // The mid going into the window, so the opening position drifts from the start
+    let mut mid = marks
+        .iter()
+        .take_while(|mark| mark.timestamp <= since)
+        .last()
+        .unwrap_or(&marks[0])
+        .price;
+
+    // Marks and fills in time order; a mark and a fill at the same time apply the mark first
+    let mut steps: Vec<Step> = marks
+        .iter()
+        .filter(|mark| mark.timestamp > since)
+        .map(|mark| Step::Mark(*mark))
+        .chain(fills.iter().map(|fill| Step::Fill(*fill)))
+        .collect();
+    steps.sort_by(|a, b| {
+        a.timestamp()
+            .cmp(&b.timestamp())
+            .then_with(|| a.is_fill().cmp(&b.is_fill()))
+    });
+
+    let mut days: BTreeMap<NaiveDate, AttributionRow> = BTreeMap::new();
+    for step in steps {
+        let date = step.timestamp().date_naive();
+        let row = days
+            .entry(date)
+            .or_insert_with(|| AttributionRow::new(date, token_id));
+        match step {
+            Step::Mark(mark) => {
+                row.inventory_drift += position * (mark.price - mid);
+                mid = mark.price;
+            }
+            Step::Fill(fill) => {
+                row.spread_capture += match fill.side {
+                    OrderSide::Buy => (mid - fill.price) * fill.size,
+                    OrderSide::Sell => (fill.price - mid) * fill.size,
+                };
+                row.fees += fill.fee;
+                row.fills += 1;
+                row.volume += fill.size;
+                position += signed_size(fill);
+            }
+        }
+        row.end_position = position;
+    }
+
+    // Days with only marks and nothing held are noise
+    days.into_values()
+        .filter(|row| row.fills > 0 || !row.inventory_drift.is_zero())
+        .collect()
+}
+
+fn signed_size(fill: &TradeRecord) -> Decimal {
+    match fill.side {
+        OrderSide::Buy => fill.size,
+        OrderSide::Sell => -fill.size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn fill(side: OrderSide, price: Decimal, size: Decimal, ts: DateTime<Utc>) -> TradeRecord {
+        TradeRecord {
+            trade_id: format!("t-{}", ts.timestamp()),
+            order_id: "o".to_string(),
+            market_id: "m".to_string(),
+            asset_id: "tok".to_string(),
+            market_question: "Will it rain?".to_string(),
+            outcome: "Yes".to_string(),
+            side,
+            price,
+            size,
+            fee: dec!(0.01),
+            timestamp: ts,
+            pnl_impact: None,
+            position_after: None,
+        }
+    }
+
+    fn mark(timestamp: DateTime<Utc>, price: Decimal) -> Mark {
+        Mark { timestamp, price }
+    }
+
+    #[test]
+    fn components_add_up_to_marked_pnl() {
+        let marks = HashMap::from([(
+            "tok".to_string(),
+            vec![
+                mark(at(1, 0), dec!(0.50)),
+                mark(at(1, 12), dec!(0.52)),
+                mark(at(2, 6), dec!(0.55)),
+            ],
+        )]);
+        let fills = vec![
+            // Opening position bought before the window
+            fill(
+                OrderSide::Buy,
+                dec!(0.40),
+                dec!(10),
+                at(1, 0) - chrono::Duration::hours(1),
+            ),
+            fill(OrderSide::Buy, dec!(0.49), dec!(100), at(1, 6)),
+            fill(OrderSide::Sell, dec!(0.54), dec!(60), at(2, 1)),
+        ];
+
+        let rows = attribute(&fills, &marks, at(1, 0));
+        assert_eq!(rows.len(), 2);
+
+        let day1 = &rows[0];
+        assert_eq!((day1.fills, day1.volume), (1, dec!(100)));
+        assert_eq!(day1.spread_capture, dec!(1.00));
+        // 110 shares held while the mid moves 0.50 -> 0.52
+        assert_eq!(day1.inventory_drift, dec!(2.20));
+        assert_eq!(day1.end_position, dec!(110));
+
+        let day2 = &rows[1];
+        assert_eq!(day2.spread_capture, dec!(1.20));
+        assert_eq!(day2.inventory_drift, dec!(50) * dec!(0.03));
+        assert_eq!(day2.end_position, dec!(50));
+
+        // Cash flow of the window's fills plus the change in marked position value
+        let cash = dec!(60) * dec!(0.54) - dec!(100) * dec!(0.49) - dec!(0.02);
+        let value_change = dec!(50) * dec!(0.55) - dec!(10) * dec!(0.50);
+        let total: Decimal = rows.iter().map(AttributionRow::total).sum();
+        assert_eq!(total, cash + value_change);
+    }
+
+    #[test]
+    fn tokens_without_marks_are_skipped() {
+        let fills = vec![fill(OrderSide::Buy, dec!(0.5), dec!(1), at(1, 1))];
+        assert!(attribute(&fills, &HashMap::new(), at(1, 0)).is_empty());
+    }
+}
@@ -9,11 +9,14 @@ use std::sync::Arc;
 use tokio::sync::OnceCell;
 use tracing::{info, warn};
 
-use crate::config;
 use crate::core::portfolio::api::{PortfolioServiceHandle, PortfolioState, start_portfolio_service};
-use crate::core::portfolio::types::{ActiveOrder, OrderSide, TradeExecution};
+use crate::core::portfolio::display::MarketLabels;
+use crate::core::portfolio::exposure::{ExposureGroup, ExposureGrouping, MarketMeta};
+use crate::core::portfolio::types::{ActiveOrder, OrderSide, PositionStatus, TradeExecution};
+use crate::core::portfolio::units::{self, OutcomeExposure};
+use crate::core::portfolio::webhook::{PortfolioWebhook, PORTFOLIO_WEBHOOK_CONFIG_FILE};
 use crate::data_paths::DataPaths;
-use crate::ethereum_utils;
+use crate::number_format;
 
 /// Global portfolio service handle
 static PORTFOLIO_SERVICE: OnceCell<Arc<PortfolioServiceHandle>> = OnceCell::const_new();
@@ -28,13 +31,8 @@ pub struct PortfolioCommandHandlers {
 impl PortfolioCommandHandlers {
     /// Create new command handlers
     pub async fn new(host: String, data_paths: DataPaths) -> Result<Self> {
-        // Load private key to derive user address
-        let private_key = config::load_private_key(&data_paths)
-            .await
-            .map_err(|e| anyhow!("No private key found. Run 'polybot init' first: {}", e))?;
-
-        // Derive user's Ethereum address
-        let address = ethereum_utils::derive_address_from_private_key(&private_key)?;
+        // Account address from the key, or the watched address of a read-only profile
+        let address = crate::auth::account_address(&data_paths).await?;
 
         // Get or start portfolio service
         let service_handle =
@@ -103,7 +101,7 @@ impl PortfolioCommandHandlers {
         size: Decimal,
         market_id: Option<String>,
         confirm: bool,
-    ) -> Result<bool> {
+    ) -> Result<String> {
         // Check confirmation in non-production environments
         if !confirm && std::env::var("RUST_ENV").unwrap_or_default() != "production" {
             warn!("⚠️  Order confirmation required. Use --yes to confirm.");
@@ -262,11 +260,12 @@ pub async fn enhanced_sell_command(
     data_paths: DataPaths,
 ) -> Result<()> {
     let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
-    let _result = handlers
+    let order_id = handlers
         .execute_sell(token_id, price, size, market_id, confirm)
         .await?;
 
     println!("🚀 Sell order placed!");
+    println!("📋 Order ID: {}", order_id);
     println!("🎯 Token: {}", token_id);
     println!("💰 Price: ${}", price);
     println!("📊 Size: ${}", size);
@@ -298,10 +297,11 @@ pub async fn enhanced_portfolio_command(
     market_filter: Option<String>,
     asset_filter: Option<String>,
     _text_mode: bool,
+    raw_ids: bool,
     host: &str,
     data_paths: DataPaths,
 ) -> Result<()> {
-    let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+    let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths.clone()).await?;
 
     // Refresh data first
     if let Err(e) = handlers.refresh_data().await {
@@ -338,20 +338,20 @@ pub async fn enhanced_portfolio_command(
     // Display balances
     println!("💰 Account Balances:");
     println!(
-        "  💵 Total Value: ${:.2}",
-        portfolio_state.balances.total_value
+        "  💵 Total Value: {}",
+        number_format::usd(portfolio_state.balances.total_value)
     );
     println!(
-        "  💴 Available Cash: ${:.2}",
-        portfolio_state.balances.available_cash
+        "  💴 Available Cash: {}",
+        number_format::usd(portfolio_state.balances.available_cash)
     );
     println!(
-        "  🔒 Locked in Orders: ${:.2}",
-        portfolio_state.balances.locked_in_orders
+        "  🔒 Locked in Orders: {}",
+        number_format::usd(portfolio_state.balances.locked_in_orders)
     );
     println!(
-        "  📈 Position Value: ${:.2}",
-        portfolio_state.balances.position_value
+        "  📈 Position Value: {}",
+        number_format::usd(portfolio_state.balances.position_value)
     );
     println!();
 
@@ -365,22 +365,25 @@ pub async fn enhanced_portfolio_command(
         "  📈 Open Positions: {}",
         portfolio_state.stats.open_positions
     );
-    println!("  💹 Total P&L: ${:.2}", portfolio_state.stats.total_pnl());
     println!(
-        "  ✅ Realized P&L: ${:.2}",
-        portfolio_state.stats.total_realized_pnl
+        "  💹 Total P&L: {}",
+        number_format::usd(portfolio_state.stats.total_pnl())
+    );
+    println!(
+        "  ✅ Realized P&L: {}",
+        number_format::usd(portfolio_state.stats.total_realized_pnl)
     );
     println!(
-        "  📊 Unrealized P&L: ${:.2}",
-        portfolio_state.stats.total_unrealized_pnl
+        "  📊 Unrealized P&L: {}",
+        number_format::usd(portfolio_state.stats.total_unrealized_pnl)
     );
     println!(
-        "  💸 Total Fees: ${:.2}",
-        portfolio_state.stats.total_fees_paid
+        "  💸 Total Fees: {}",
+        number_format::usd(portfolio_state.stats.total_fees_paid)
     );
 
     if let Some(win_rate) = portfolio_state.stats.win_rate {
-        println!("  🏆 Win Rate: {:.1}%", win_rate);
+        println!("  🏆 Win Rate: {}", number_format::percent(win_rate));
     }
 
     println!();
@@ -397,14 +400,16 @@ pub async fn enhanced_portfolio_command(
     if !positions.is_empty() {
         println!("📍 Positions ({}):", positions.len());
         for (i, position) in positions.iter().enumerate() {
+            let exposure = OutcomeExposure::from_position(position);
             println!(
-                "  {}. {} {} - Size: {}, Avg Price: ${:.3}, P&L: ${:.2}",
+                "  {}. {} {} - {} @ avg ${}, value {}, P&L {}",
                 i + 1,
                 position.outcome,
                 &position.market_id[..8.min(position.market_id.len())],
-                position.size,
-                position.average_price,
-                position.total_pnl()
+                units::format_shares(exposure.shares),
+                units::round_price(position.average_price),
+                units::format_usdc(exposure.value_usdc),
+                exposure.format_pnl()
             );
         }
         println!();
@@ -419,20 +424,37 @@ pub async fn enhanced_portfolio_command(
         orders.retain(|o| o.token_id.contains(asset_filter) || o.outcome.contains(asset_filter));
     }
 
+    // Market questions of the listed orders and trades
+    let trades = handlers.get_trade_history(None, None).await;
+    let labels = if raw_ids {
+        MarketLabels::default()
+    } else {
+        let order_tokens = orders.iter().map(|o| o.token_id.as_str());
+        let trade_tokens = trades.iter().flatten().map(|t| t.token_id.as_str());
+        MarketLabels::load(&data_paths, order_tokens.chain(trade_tokens))
+    };
+    let market_line = |token_id: &str| {
+        labels
+            .label(token_id, 60)
+            .map(|label| format!("\n     {}", label))
+            .unwrap_or_default()
+    };
+
     if !orders.is_empty() {
         println!("📋 Active Orders ({}):", orders.len());
         for (i, order) in orders.iter().enumerate() {
             println!(
-                "  {}. {} {} @ ${:.3} (Size: ${:.2}) - {}",
+                "  {}. {} {} @ ${} (Size: {}) - {}{}",
                 i + 1,
                 match order.side {
                     OrderSide::Buy => "BUY",
                     OrderSide::Sell => "SELL",
                 },
                 &order.order_id[..8.min(order.order_id.len())],
-                order.price,
-                order.size,
-                format!("{:?}", order.status)
+                number_format::price(order.price),
+                number_format::size(order.size),
+                format!("{:?}", order.status),
+                market_line(&order.token_id)
             );
         }
         println!();
@@ -442,23 +464,24 @@ pub async fn enhanced_portfolio_command(
     }
 
     // Show recent trade history
-    match handlers.get_trade_history(None, None).await {
+    match trades {
         Ok(trades) => {
             if !trades.is_empty() {
                 let recent_trades: Vec<_> = trades.iter().rev().take(5).collect();
                 println!("📈 Recent Trades ({} total, showing last 5):", trades.len());
                 for (i, trade) in recent_trades.iter().enumerate() {
                     println!(
-                        "  {}. {} {} @ ${:.3} (Size: {}) - {}",
+                        "  {}. {} {} @ ${} (Size: {}) - {}{}",
                         i + 1,
                         match trade.side {
                             OrderSide::Buy => "BUY",
                             OrderSide::Sell => "SELL",
                         },
                         &trade.trade_id[..8.min(trade.trade_id.len())],
-                        trade.price,
-                        trade.size,
-                        trade.timestamp.format("%Y-%m-%d %H:%M")
+                        number_format::price(trade.price),
+                        number_format::size(trade.size),
+                        trade.timestamp.format("%Y-%m-%d %H:%M"),
+                        market_line(&trade.token_id)
                     );
                 }
             } else {
@@ -477,18 +500,118 @@ pub async fn enhanced_portfolio_command(
     Ok(())
 }
 
+/// Print the open positions' exposure grouped by category or end date
+pub async fn portfolio_exposure_command(
+    grouping: ExposureGrouping,
+    market_filter: Option<String>,
+    asset_filter: Option<String>,
+    host: &str,
+    data_paths: DataPaths,
+) -> Result<()> {
+    let db_path = crate::typed_store::freshness::default_db_path(&data_paths);
+    let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+    if let Err(e) = handlers.refresh_data().await {
+        warn!("Failed to refresh portfolio data: {}", e);
+    }
+
+    let mut positions = handlers.get_portfolio_state().await?.positions;
+    if let Some(market_filter) = &market_filter {
+        positions.retain(|p| p.market_id.contains(market_filter));
+    }
+    if let Some(asset_filter) = &asset_filter {
+        positions.retain(|p| p.token_id.contains(asset_filter) || p.outcome.contains(asset_filter));
+    }
+
+    let meta = MarketMeta::load_all(&db_path, &positions)?;
+    let groups = ExposureGroup::group(
+        &positions,
+        |p| meta.get(&p.token_id).cloned(),
+        grouping,
+        chrono::Utc::now(),
+    );
+    if groups.is_empty() {
+        println!("📊 No open positions");
+        return Ok(());
+    }
+
+    let title = match grouping {
+        ExposureGrouping::Category => "Category",
+        ExposureGrouping::EndDate => "Ends",
+    };
+    let total: Decimal = groups.iter().map(|g| g.value_usdc).sum();
+    println!("\n📊 Exposure by {}\n", title.to_lowercase());
+    println!(
+        "  {:<24} {:>9} {:>14} {:>7} {:>14} {:>14}",
+        title, "Positions", "Value", "Share", "Cost", "P&L"
+    );
+    for group in &groups {
+        println!(
+            "  {:<24} {:>9} {:>14} {:>7} {:>14} {:>14}",
+            group.label,
+            group.positions,
+            units::format_usdc(group.value_usdc),
+            group
+                .share_of(total)
+                .map(|share| format!("{}%", share))
+                .unwrap_or_else(|| "-".to_string()),
+            units::format_usdc(group.cost_usdc),
+            units::format_signed_usdc(group.pnl_usdc)
+        );
+    }
+    println!(
+        "  {:<24} {:>9} {:>14}",
+        "Total",
+        groups.iter().map(|g| g.positions).sum::<usize>(),
+        units::format_usdc(total)
+    );
+
+    let unknown = positions
+        .iter()
+        .filter(|p| p.status == PositionStatus::Open && !meta.contains_key(&p.token_id))
+        .count();
+    if unknown > 0 {
+        println!(
+            "\n💡 {} positions are in markets missing from the local index; run 'polybot index' to group them",
+            unknown
+        );
+    }
+    Ok(())
+}
+
+/// Send the current portfolio snapshot to the portfolio webhook once
+pub async fn portfolio_webhook_command(host: &str, data_paths: DataPaths) -> Result<()> {
+    let Some(webhook) = PortfolioWebhook::load(&data_paths)? else {
+        return Err(anyhow!(
+            "No portfolio webhook configured; set `enabled: true` and `url` in {}",
+            data_paths
+                .root()
+                .join(PORTFOLIO_WEBHOOK_CONFIG_FILE)
+                .display()
+        ));
+    };
+    let handlers = PortfolioCommandHandlers::new(host.to_string(), data_paths).await?;
+    if let Err(e) = handlers.refresh_data().await {
+        warn!("Failed to refresh portfolio data: {}", e);
+    }
+
+    let state = handlers.get_portfolio_state().await?;
+    let payload = webhook.payload(handlers.get_address(), &state.balances, &state.positions);
+    webhook.send(&payload).await?;
+    println!(
+        "✅ Sent portfolio snapshot to {}: {} equity, {} open positions",
+        webhook.url(),
+        units::format_usdc(payload.balances.total_value),
+        payload.open_positions
+    );
+    Ok(())
+}
+
 /// Get portfolio service handle for other components
 pub async fn get_portfolio_service_handle(
     host: &str,
     data_paths: &DataPaths,
 ) -> Result<Arc<PortfolioServiceHandle>> {
-    // Load private key to derive user address
-    let private_key = config::load_private_key(data_paths)
-        .await
-        .map_err(|e| anyhow!("No private key found. Run 'polybot init' first: {}", e))?;
-
-    // Derive user's Ethereum address
-    let address = ethereum_utils::derive_address_from_private_key(&private_key)?;
+    let address = crate::auth::account_address(data_paths).await?;
 
     get_or_start_portfolio_service(data_paths.clone(), address, host.to_string()).await
 }
\ No newline at end of file
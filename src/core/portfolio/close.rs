@@ -0,0 +1,111 @@
+//! Partial position closes
+//!
+//! Turns "close N% of this position" into an order: the size comes from the
+//! current position and the side is whatever reduces it. Unless told
+//! otherwise the order rests at the position's average price, so the closed
+//! part exits at break-even before fees.
+
+use anyhow::{anyhow, Result};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use super::types::{OrderSide, Position, PositionSide, PositionStatus};
+
+/// Share sizes are rounded down to this many decimals so a close never exceeds the position
+const SIZE_DECIMALS: u32 = 2;
+
+/// Where the closing order is priced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClosePrice {
+    /// Rest at the position's average price
+    AveragePrice,
+    /// Explicit limit price
+    Limit(Decimal),
+    /// Cross the spread at this touch price (best bid for longs, best ask for shorts)
+    Market(Decimal),
+}
+
+/// Order that closes part of a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosePlan {
+    pub side: OrderSide,
+    pub size: Decimal,
+    pub price: Decimal,
+    /// Position left once the order fully fills
+    pub remaining: Decimal,
+}
+
+impl ClosePlan {
+    /// Plan closing `pct` percent (0–100] of an open position
+    pub fn new(position: &Position, pct: Decimal, price: ClosePrice) -> Result<Self> {
+        if position.status != PositionStatus::Open || position.size <= Decimal::ZERO {
+            return Err(anyhow!("Position in {} is not open", position.token_id));
+        }
+        if pct <= Decimal::ZERO || pct > Decimal::ONE_HUNDRED {
+            return Err(anyhow!("--pct must be between 0 and 100, got {}", pct));
+        }
+
+        let size = (position.size * pct / Decimal::ONE_HUNDRED)
+            .round_dp_with_strategy(SIZE_DECIMALS, RoundingStrategy::ToZero);
+        if size.is_zero() {
+            return Err(anyhow!(
+                "{}% of {} shares rounds to zero",
+                pct,
+                position.size
+            ));
+        }
+
+        let side = match position.side {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        };
+        let price = match price {
+            ClosePrice::AveragePrice => position.average_price,
+            ClosePrice::Limit(price) | ClosePrice::Market(price) => price,
+        };
+
+        Ok(Self {
+            side,
+            size,
+            price,
+            remaining: position.size - size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// A position bought at 0.42
+    fn position(side: PositionSide, size: Decimal) -> Position {
+        Position::test("t", size)
+            .with_side(side)
+            .with_average_price(dec!(0.42))
+    }
+
+    #[test]
+    fn half_of_a_long_sells_at_average_price() {
+        let plan = ClosePlan::new(&position(PositionSide::Long, dec!(101)), dec!(50), ClosePrice::AveragePrice)
+            .unwrap();
+        assert_eq!(plan.side, OrderSide::Sell);
+        assert_eq!(plan.size, dec!(50.5));
+        assert_eq!(plan.price, dec!(0.42));
+        assert_eq!(plan.remaining, dec!(50.5));
+    }
+
+    #[test]
+    fn sizes_round_down() {
+        let plan = ClosePlan::new(&position(PositionSide::Short, dec!(10.01)), dec!(33), ClosePrice::Limit(dec!(0.4)))
+            .unwrap();
+        assert_eq!(plan.side, OrderSide::Buy);
+        assert_eq!(plan.size, dec!(3.30));
+    }
+
+    #[test]
+    fn rejects_out_of_range_percentages() {
+        let long = position(PositionSide::Long, dec!(10));
+        assert!(ClosePlan::new(&long, dec!(0), ClosePrice::AveragePrice).is_err());
+        assert!(ClosePlan::new(&long, dec!(120), ClosePrice::AveragePrice).is_err());
+    }
+}
@@ -34,38 +34,40 @@ impl PositionReconciler {
         self.positions.clear();
         
         for order in orders {
-            if let Some(token_id) = &order.token_id {
-                // Only process filled orders
-                let filled_size = order.size_matched.parse::<Decimal>().unwrap_or_default();
-                if filled_size > Decimal::ZERO {
-                    let price = order.price;
-                    
-                    let position = self.positions.entry(token_id.clone()).or_insert_with(|| Position {
-                        market_id: order.market.clone(),
-                        token_id: token_id.clone(),
-                        outcome: order.outcome.clone(),
-                        side: PositionSide::Long, // Default to long
-                        size: Decimal::ZERO,
-                        average_price: Decimal::ZERO,
-                        current_price: None,
-                        realized_pnl: Decimal::ZERO,
-                        unrealized_pnl: None,
-                        status: PositionStatus::Open,
-                        opened_at: chrono::Utc::now(),
-                        updated_at: chrono::Utc::now(),
-                        closed_at: None,
-                        fees_paid: Decimal::ZERO,
-                        market_question: None,
-                    });
-                    
-                    // Update position size and average price
-                    let new_total_cost = position.size * position.average_price + filled_size * price;
-                    position.size += filled_size;
-                    if position.size > Decimal::ZERO {
-                        position.average_price = new_total_cost / position.size;
-                    }
-                    position.updated_at = chrono::Utc::now();
+            // The orders endpoint only reports `asset_id`; `token_id` is set by
+            // orders converted from `EnhancedOrder`
+            let token_id = order.token_id.as_ref().unwrap_or(&order.asset_id);
+
+            // Only process filled orders
+            let filled_size = order.size_matched.parse::<Decimal>().unwrap_or_default();
+            if filled_size > Decimal::ZERO {
+                let price = order.price;
+                
+                let position = self.positions.entry(token_id.clone()).or_insert_with(|| Position {
+                    market_id: order.market.clone(),
+                    token_id: token_id.clone(),
+                    outcome: order.outcome.clone(),
+                    side: PositionSide::Long, // Default to long
+                    size: Decimal::ZERO,
+                    average_price: Decimal::ZERO,
+                    current_price: None,
+                    realized_pnl: Decimal::ZERO,
+                    unrealized_pnl: None,
+                    status: PositionStatus::Open,
+                    opened_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                    closed_at: None,
+                    fees_paid: Decimal::ZERO,
+                    market_question: None,
+                });
+                
+                // Update position size and average price
+                let new_total_cost = position.size * position.average_price + filled_size * price;
+                position.size += filled_size;
+                if position.size > Decimal::ZERO {
+                    position.average_price = new_total_cost / position.size;
                 }
+                position.updated_at = chrono::Utc::now();
             }
         }
         
@@ -106,16 +108,13 @@ impl PositionReconciler {
             .context("Failed to parse filled size")?;
 
         // Get current position for this token
-        let position_after = if let Some(token_id) = &order.token_id {
-            self.positions.get(token_id).map(|pos| PositionSummary {
-                size: pos.size,
-                average_price: pos.average_price,
-                realized_pnl: pos.realized_pnl,
-                unrealized_pnl: pos.unrealized_pnl,
-            })
-        } else {
-            None
-        };
+        let token_id = order.token_id.as_ref().unwrap_or(&order.asset_id);
+        let position_after = self.positions.get(token_id).map(|pos| PositionSummary {
+            size: pos.size,
+            average_price: pos.average_price,
+            realized_pnl: pos.realized_pnl,
+            unrealized_pnl: pos.unrealized_pnl,
+        });
 
         let trade = TradeRecord {
             trade_id: format!("{}_{}", order.id, order.created_at),
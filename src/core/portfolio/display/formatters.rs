@@ -3,10 +3,33 @@
 //! This module provides formatting and display utilities for portfolio data
 //! including positions, orders, trades, and statistics.
 
-use rust_decimal::Decimal;
-
 use crate::core::portfolio::api::PortfolioState;
+use crate::core::portfolio::display::market_labels::{truncate, MarketLabels};
 use crate::core::portfolio::types::*;
+use crate::core::portfolio::units::{self, OutcomeExposure};
+use crate::number_format;
+
+/// Width of the market column tables gain with market labels
+const MARKET_COLUMN_WIDTH: usize = 40;
+
+/// Rule segment of the market column, or nothing without labels
+fn market_rule(labels: Option<&MarketLabels>, joint: char) -> String {
+    labels
+        .map(|_| format!("{}{}", "─".repeat(MARKET_COLUMN_WIDTH + 2), joint))
+        .unwrap_or_default()
+}
+
+/// Market column cell holding `text`, or nothing without labels
+fn market_cell(labels: Option<&MarketLabels>, text: &str) -> String {
+    if labels.is_none() {
+        return String::new();
+    }
+    format!(
+        " {:<width$} │",
+        truncate(text, MARKET_COLUMN_WIDTH),
+        width = MARKET_COLUMN_WIDTH
+    )
+}
 
 /// Format portfolio statistics for display
 pub struct PortfolioStatsFormatter<'a> {
@@ -27,16 +50,16 @@ impl<'a> PortfolioStatsFormatter<'a> {
         output.push_str("├─────────────────────────┼─────────────────┤\n");
 
         output.push_str(&format!(
-            "│ Total Balance           │ ${:>14.2} │\n",
-            self.stats.total_balance
+            "│ Total Balance           │ {:>15} │\n",
+            number_format::usd(self.stats.total_balance)
         ));
         output.push_str(&format!(
-            "│ Available Balance       │ ${:>14.2} │\n",
-            self.stats.available_balance
+            "│ Available Balance       │ {:>15} │\n",
+            number_format::usd(self.stats.available_balance)
         ));
         output.push_str(&format!(
-            "│ Locked Balance          │ ${:>14.2} │\n",
-            self.stats.locked_balance
+            "│ Locked Balance          │ {:>15} │\n",
+            number_format::usd(self.stats.locked_balance)
         ));
         output.push_str(&format!(
             "│ Total Positions         │ {:>15} │\n",
@@ -47,40 +70,40 @@ impl<'a> PortfolioStatsFormatter<'a> {
             self.stats.open_positions
         ));
         output.push_str(&format!(
-            "│ Realized P&L            │ ${:>14.2} │\n",
-            self.stats.total_realized_pnl
+            "│ Realized P&L            │ {:>15} │\n",
+            number_format::usd(self.stats.total_realized_pnl)
         ));
         output.push_str(&format!(
-            "│ Unrealized P&L          │ ${:>14.2} │\n",
-            self.stats.total_unrealized_pnl
+            "│ Unrealized P&L          │ {:>15} │\n",
+            number_format::usd(self.stats.total_unrealized_pnl)
         ));
         output.push_str(&format!(
-            "│ Total P&L               │ ${:>14.2} │\n",
-            self.stats.total_pnl()
+            "│ Total P&L               │ {:>15} │\n",
+            number_format::usd(self.stats.total_pnl())
         ));
         output.push_str(&format!(
-            "│ Total Fees Paid         │ ${:>14.2} │\n",
-            self.stats.total_fees_paid
+            "│ Total Fees Paid         │ {:>15} │\n",
+            number_format::usd(self.stats.total_fees_paid)
         ));
 
         if let Some(win_rate) = self.stats.win_rate {
             output.push_str(&format!(
-                "│ Win Rate                │ {:>13.1}% │\n",
-                win_rate
+                "│ Win Rate                │ {:>15} │\n",
+                number_format::percent(win_rate)
             ));
         }
 
         if let Some(avg_win) = self.stats.average_win {
             output.push_str(&format!(
-                "│ Average Win             │ ${:>14.2} │\n",
-                avg_win
+                "│ Average Win             │ {:>15} │\n",
+                number_format::usd(avg_win)
             ));
         }
 
         if let Some(avg_loss) = self.stats.average_loss {
             output.push_str(&format!(
-                "│ Average Loss            │ ${:>14.2} │\n",
-                avg_loss
+                "│ Average Loss            │ {:>15} │\n",
+                number_format::usd(avg_loss)
             ));
         }
 
@@ -108,9 +131,9 @@ impl<'a> PositionsFormatter<'a> {
 
         let mut output = String::new();
 
-        output.push_str("┌──────────┬─────────────┬──────────┬───────────┬──────────┬─────────────┐\n");
-        output.push_str("│ Market   │ Outcome     │ Side     │ Size      │ Avg Price│ P&L         │\n");
-        output.push_str("├──────────┼─────────────┼──────────┼───────────┼──────────┼─────────────┤\n");
+        output.push_str("┌──────────┬─────────────┬──────────┬───────────┬──────────┬─────────────┬─────────────┬─────────────┐\n");
+        output.push_str("│ Market   │ Outcome     │ Side     │ Shares    │ Avg Price│ Value       │ P&L         │ P&L shares  │\n");
+        output.push_str("├──────────┼─────────────┼──────────┼───────────┼──────────┼─────────────┼─────────────┼─────────────┤\n");
 
         for position in self.positions {
             let market_short = if position.market_id.len() > 8 {
@@ -130,22 +153,26 @@ impl<'a> PositionsFormatter<'a> {
                 PositionSide::Short => "SHORT",
             };
 
-            let pnl = position.total_pnl();
-            let pnl_color = if pnl >= Decimal::ZERO { "+" } else { "" };
+            let exposure = OutcomeExposure::from_position(position);
+            let pnl_shares = exposure
+                .pnl_shares
+                .map(units::format_signed_shares)
+                .unwrap_or_else(|| "-".to_string());
 
             output.push_str(&format!(
-                "│ {:<8} │ {:<11} │ {:<8} │ {:>9.2} │ {:>8.3} │ {}{:>10.2} │\n",
+                "│ {:<8} │ {:<11} │ {:<8} │ {:>9} │ {:>8} │ {:>11} │ {:>11} │ {:>11} │\n",
                 market_short,
                 outcome_short,
                 side_str,
-                position.size,
-                position.average_price,
-                pnl_color,
-                pnl
+                number_format::size(units::round_shares(position.size)),
+                number_format::price(units::round_price(position.average_price)),
+                units::format_usdc(exposure.value_usdc),
+                units::format_signed_usdc(exposure.pnl_usdc),
+                pnl_shares
             ));
         }
 
-        output.push_str("└──────────┴─────────────┴──────────┴───────────┴──────────┴─────────────┘\n");
+        output.push_str("└──────────┴─────────────┴──────────┴───────────┴──────────┴─────────────┴─────────────┴─────────────┘\n");
 
         output
     }
@@ -154,11 +181,22 @@ impl<'a> PositionsFormatter<'a> {
 /// Format active orders for display
 pub struct OrdersFormatter<'a> {
     pub orders: &'a [ActiveOrder],
+    /// Adds a market column and outcome names when set
+    pub labels: Option<&'a MarketLabels>,
 }
 
 impl<'a> OrdersFormatter<'a> {
     pub fn new(orders: &'a [ActiveOrder]) -> Self {
-        Self { orders }
+        Self {
+            orders,
+            labels: None,
+        }
+    }
+
+    /// Show each order's market question and outcome name
+    pub fn with_labels(mut self, labels: &'a MarketLabels) -> Self {
+        self.labels = Some(labels);
+        self
     }
 
     /// Format as a table
@@ -169,9 +207,19 @@ impl<'a> OrdersFormatter<'a> {
 
         let mut output = String::new();
 
-        output.push_str("┌─────────────┬─────────────┬──────────┬──────────┬───────────┬─────────────┐\n");
-        output.push_str("│ Order ID    │ Outcome     │ Side     │ Price    │ Size      │ Status      │\n");
-        output.push_str("├─────────────┼─────────────┼──────────┼──────────┼───────────┼─────────────┤\n");
+        let labels = self.labels;
+        output.push_str(&format!(
+            "┌─────────────┬{}─────────────┬──────────┬──────────┬───────────┬─────────────┐\n",
+            market_rule(labels, '┬')
+        ));
+        output.push_str(&format!(
+            "│ Order ID    │{} Outcome     │ Side     │ Price    │ Size      │ Status      │\n",
+            market_cell(labels, "Market")
+        ));
+        output.push_str(&format!(
+            "├─────────────┼{}─────────────┼──────────┼──────────┼───────────┼─────────────┤\n",
+            market_rule(labels, '┼')
+        ));
 
         for order in self.orders {
             let order_id_short = if order.order_id.len() > 11 {
@@ -180,11 +228,13 @@ impl<'a> OrdersFormatter<'a> {
                 order.order_id.clone()
             };
 
-            let outcome_short = if order.outcome.len() > 11 {
-                format!("{}...", &order.outcome[..8])
-            } else {
-                order.outcome.clone()
-            };
+            let market = labels
+                .and_then(|labels| labels.question(&order.token_id))
+                .unwrap_or(&order.market_id);
+            let outcome = labels
+                .and_then(|labels| labels.outcome(&order.token_id))
+                .unwrap_or(&order.outcome);
+            let outcome_short = truncate(outcome, 11);
 
             let side_str = match order.side {
                 OrderSide::Buy => "BUY",
@@ -199,17 +249,21 @@ impl<'a> OrdersFormatter<'a> {
             };
 
             output.push_str(&format!(
-                "│ {:<11} │ {:<11} │ {:<8} │ {:>8.3} │ {:>9.2} │ {:<11} │\n",
+                "│ {:<11} │{} {:<11} │ {:<8} │ {:>8} │ {:>9} │ {:<11} │\n",
                 order_id_short,
+                market_cell(labels, market),
                 outcome_short,
                 side_str,
-                order.price,
-                order.size,
+                number_format::price(order.price),
+                number_format::size(order.size),
                 status_short
             ));
         }
 
-        output.push_str("└─────────────┴─────────────┴──────────┴──────────┴───────────┴─────────────┘\n");
+        output.push_str(&format!(
+            "└─────────────┴{}─────────────┴──────────┴──────────┴───────────┴─────────────┘\n",
+            market_rule(labels, '┴')
+        ));
 
         output
     }
@@ -218,11 +272,22 @@ impl<'a> OrdersFormatter<'a> {
 /// Format trade history for display
 pub struct TradesFormatter<'a> {
     pub trades: &'a [TradeExecution],
+    /// Adds a market column when set
+    pub labels: Option<&'a MarketLabels>,
 }
 
 impl<'a> TradesFormatter<'a> {
     pub fn new(trades: &'a [TradeExecution]) -> Self {
-        Self { trades }
+        Self {
+            trades,
+            labels: None,
+        }
+    }
+
+    /// Show each trade's market question and outcome name
+    pub fn with_labels(mut self, labels: &'a MarketLabels) -> Self {
+        self.labels = Some(labels);
+        self
     }
 
     /// Format as a table
@@ -239,9 +304,19 @@ impl<'a> TradesFormatter<'a> {
 
         let mut output = String::new();
 
-        output.push_str("┌─────────────┬──────────┬──────────┬───────────┬──────────┬─────────────────┐\n");
-        output.push_str("│ Trade ID    │ Side     │ Price    │ Size      │ Fee      │ Timestamp       │\n");
-        output.push_str("├─────────────┼──────────┼──────────┼───────────┼──────────┼─────────────────┤\n");
+        let labels = self.labels;
+        output.push_str(&format!(
+            "┌─────────────┬{}──────────┬──────────┬───────────┬──────────┬─────────────────┐\n",
+            market_rule(labels, '┬')
+        ));
+        output.push_str(&format!(
+            "│ Trade ID    │{} Side     │ Price    │ Size      │ Fee      │ Timestamp       │\n",
+            market_cell(labels, "Market")
+        ));
+        output.push_str(&format!(
+            "├─────────────┼{}──────────┼──────────┼───────────┼──────────┼─────────────────┤\n",
+            market_rule(labels, '┼')
+        ));
 
         for trade in trades_to_show {
             let trade_id_short = if trade.trade_id.len() > 11 {
@@ -255,18 +330,26 @@ impl<'a> TradesFormatter<'a> {
                 OrderSide::Sell => "SELL",
             };
 
+            // Leave room for the outcome after the question
+            let market = labels
+                .and_then(|labels| labels.label(&trade.token_id, MARKET_COLUMN_WIDTH - 8))
+                .unwrap_or_else(|| trade.market_id.clone());
             output.push_str(&format!(
-                "│ {:<11} │ {:<8} │ {:>8.3} │ {:>9.2} │ {:>8.3} │ {:<15} │\n",
+                "│ {:<11} │{} {:<8} │ {:>8} │ {:>9} │ {:>8} │ {:<15} │\n",
                 trade_id_short,
+                market_cell(labels, &market),
                 side_str,
-                trade.price,
-                trade.size,
-                trade.fee,
+                number_format::price(trade.price),
+                number_format::size(trade.size),
+                number_format::price(trade.fee),
                 trade.timestamp.format("%m-%d %H:%M")
             ));
         }
 
-        output.push_str("└─────────────┴──────────┴──────────┴───────────┴──────────┴─────────────────┘\n");
+        output.push_str(&format!(
+            "└─────────────┴{}──────────┴──────────┴───────────┴──────────┴─────────────────┘\n",
+            market_rule(labels, '┴')
+        ));
 
         if let Some(limit) = limit {
             if self.trades.len() > limit {
@@ -332,20 +415,20 @@ impl<'a> DashboardFormatter<'a> {
         // Account balances
         output.push_str("💰 ACCOUNT BALANCES\n");
         output.push_str(&format!(
-            "   Total Value: ${:.2}\n",
-            self.portfolio_state.balances.total_value
+            "   Total Value: {}\n",
+            number_format::usd(self.portfolio_state.balances.total_value)
         ));
         output.push_str(&format!(
-            "   Available Cash: ${:.2}\n",
-            self.portfolio_state.balances.available_cash
+            "   Available Cash: {}\n",
+            number_format::usd(self.portfolio_state.balances.available_cash)
         ));
         output.push_str(&format!(
-            "   Locked in Orders: ${:.2}\n",
-            self.portfolio_state.balances.locked_in_orders
+            "   Locked in Orders: {}\n",
+            number_format::usd(self.portfolio_state.balances.locked_in_orders)
         ));
         output.push_str(&format!(
-            "   Position Value: ${:.2}\n\n",
-            self.portfolio_state.balances.position_value
+            "   Position Value: {}\n\n",
+            number_format::usd(self.portfolio_state.balances.position_value)
         ));
 
         // Statistics
@@ -0,0 +1,130 @@
+//! Market questions and outcome names for token ids
+//!
+//! Order and trade listings only carry token and condition ids.
+//! [`MarketLabels`] joins them against the typed store's market metadata (see
+//! `typed_store::token_info`) so each row can show the market question and
+//! outcome instead. Tokens the store doesn't know, e.g. before `polybot index`
+//! has run, keep showing their ids.
+
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::data_paths::DataPaths;
+use crate::typed_store::token_info::{self, TokenInfo};
+
+/// Market metadata of the tokens in a listing
+#[derive(Debug, Clone, Default)]
+pub struct MarketLabels {
+    tokens: HashMap<String, TokenInfo>,
+}
+
+impl MarketLabels {
+    /// Look up `token_ids` in the data directory's typed store; a store that
+    /// can't be read leaves every token unlabelled
+    pub fn load<'a>(data_paths: &DataPaths, token_ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let db_path = crate::typed_store::freshness::default_db_path(data_paths);
+        let mut token_ids: Vec<&str> = token_ids
+            .into_iter()
+            .filter(|token_id| !token_id.is_empty())
+            .collect();
+        token_ids.sort_unstable();
+        token_ids.dedup();
+        match token_info::lookup_all(&db_path, token_ids) {
+            Ok(tokens) => Self { tokens },
+            Err(e) => {
+                warn!("Market metadata unavailable, showing token ids: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn from_tokens(tokens: HashMap<String, TokenInfo>) -> Self {
+        Self { tokens }
+    }
+
+    /// Add the tokens of `other`, e.g. looked up as they show up
+    pub fn extend(&mut self, other: MarketLabels) {
+        self.tokens.extend(other.tokens);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn question(&self, token_id: &str) -> Option<&str> {
+        self.tokens.get(token_id)?.question.as_deref()
+    }
+
+    pub fn outcome(&self, token_id: &str) -> Option<&str> {
+        self.tokens.get(token_id)?.outcome.as_deref()
+    }
+
+    /// `<question> · <outcome>`, the question cut to `max_chars`
+    pub fn label(&self, token_id: &str, max_chars: usize) -> Option<String> {
+        let question = truncate(self.question(token_id)?, max_chars);
+        Some(match self.outcome(token_id) {
+            Some(outcome) => format!("{} · {}", question, outcome),
+            None => question,
+        })
+    }
+}
+
+/// `text` cut to `max_chars` characters, ending in "…" when cut
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(token_id: &str, question: Option<&str>, outcome: Option<&str>) -> TokenInfo {
+        TokenInfo {
+            token_id: token_id.to_string(),
+            outcome: outcome.map(str::to_string),
+            condition_id: Some("0xabc".to_string()),
+            question: question.map(str::to_string),
+            category: None,
+            tags: Vec::new(),
+            end_date_iso: None,
+            volume: None,
+            volume_24hr: None,
+            liquidity: None,
+            market_slug: None,
+            creator: None,
+            complements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn labels_join_question_and_outcome() {
+        let labels = MarketLabels::from_tokens(HashMap::from([
+            (
+                "1".to_string(),
+                info("1", Some("Will it rain in Paris tomorrow?"), Some("Yes")),
+            ),
+            ("2".to_string(), info("2", Some("Will it snow?"), None)),
+            ("3".to_string(), info("3", None, Some("No"))),
+        ]));
+
+        assert_eq!(
+            labels.label("1", 60).as_deref(),
+            Some("Will it rain in Paris tomorrow? · Yes")
+        );
+        assert_eq!(
+            labels.label("1", 14).as_deref(),
+            Some("Will it rain… · Yes")
+        );
+        assert_eq!(labels.label("2", 60).as_deref(), Some("Will it snow?"));
+        // Without a question there's nothing better than the id to show
+        assert_eq!(labels.label("3", 60), None);
+        assert_eq!(labels.outcome("3"), Some("No"));
+        assert_eq!(labels.label("unknown", 60), None);
+        assert_eq!(truncate("héllo", 5), "héllo");
+        assert_eq!(truncate("héllo wörld", 7), "héllo…");
+    }
+}
@@ -4,5 +4,7 @@
 //! including tables, dashboards, and visualization helpers.
 
 pub mod formatters;
+pub mod market_labels;
 
-pub use formatters::*;
\ No newline at end of file
+pub use formatters::*;
+pub use market_labels::MarketLabels;
\ No newline at end of file
@@ -0,0 +1,400 @@
+//! Exposure of open positions grouped by market category or end date
+//!
+//! Markets carry free-form categories ("US-current-affairs", "NBA Playoffs")
+//! and often only tags, so categories are normalized into a few broad groups
+//! before summing. End dates fall into buckets relative to now: ended but not
+//! yet resolved, within 7 days, within 30 days, later, and unknown.
+//! Market metadata comes from the typed store's market index; positions in
+//! markets it doesn't know land in "Uncategorized" / "No end date".
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::types::{Position, PositionStatus};
+use super::units::OutcomeExposure;
+use crate::typed_store::context::DbContextError;
+use crate::typed_store::token_info::{self, TokenInfo};
+
+/// How positions are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExposureGrouping {
+    /// Normalized market category
+    Category,
+    /// Time until the market's end date
+    EndDate,
+}
+
+/// A broad market group and the keywords of category or tag slugs that map to it
+///
+/// A keyword matches at the start of a slug word, so stems cover their
+/// inflections ("politic" matches "political") but not other words that
+/// contain them ("sport" matches "sports", not "transport"). Dashed keywords
+/// match consecutive words.
+struct CategoryGroup {
+    name: &'static str,
+    keywords: &'static [&'static str],
+}
+
+/// Checked in order; the first group with a matching keyword wins
+const CATEGORY_GROUPS: &[CategoryGroup] = &[
+    CategoryGroup {
+        name: "Politics",
+        keywords: &[
+            "politic",
+            "election",
+            "current-affairs",
+            "government",
+            "geopolitic",
+        ],
+    },
+    CategoryGroup {
+        name: "Sports",
+        keywords: &[
+            "sport", "nba", "nfl", "nhl", "mlb", "soccer", "football", "tennis", "olympic",
+            "chess", "ufc", "golf",
+        ],
+    },
+    CategoryGroup {
+        name: "Crypto",
+        keywords: &["crypto", "bitcoin", "ethereum", "solana", "defi"],
+    },
+    CategoryGroup {
+        name: "Business",
+        keywords: &["business", "econom", "finance", "stock", "fed-rates"],
+    },
+    CategoryGroup {
+        name: "Science & Tech",
+        keywords: &["science", "tech", "space"],
+    },
+    CategoryGroup {
+        name: "Culture",
+        keywords: &[
+            "culture",
+            "entertainment",
+            "movie",
+            "music",
+            "award",
+            "celebrit",
+        ],
+    },
+];
+
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+impl CategoryGroup {
+    fn matches(&self, slug: &str) -> bool {
+        let words: Vec<&str> = slug.split('-').collect();
+        self.keywords.iter().any(|keyword| {
+            let parts: Vec<&str> = keyword.split('-').collect();
+            words.windows(parts.len()).any(|window| {
+                window
+                    .iter()
+                    .zip(&parts)
+                    .all(|(word, part)| word.starts_with(part))
+            })
+        })
+    }
+
+    /// The group whose keywords match `slug`
+    fn find(slug: &str) -> Option<&'static str> {
+        CATEGORY_GROUPS
+            .iter()
+            .find(|group| group.matches(slug))
+            .map(|group| group.name)
+    }
+
+    /// Broad group of a market from its category, falling back to its tags
+    ///
+    /// A category that matches no group is kept, title-cased; unknown tags are not.
+    fn normalize(category: Option<&str>, tags: &[String]) -> String {
+        let category = category.map(Self::slug).filter(|s| !s.is_empty());
+        if let Some(group) = category.as_deref().and_then(Self::find) {
+            return group.to_string();
+        }
+        if let Some(group) = tags.iter().find_map(|tag| Self::find(&Self::slug(tag))) {
+            return group.to_string();
+        }
+        category
+            .map(|c| Self::title_case(&c))
+            .unwrap_or_else(|| UNCATEGORIZED.to_string())
+    }
+
+    /// Lowercase, with runs of spaces, underscores and dashes turned into single dashes
+    fn slug(raw: &str) -> String {
+        raw.trim()
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// "nba-playoffs" -> "Nba Playoffs"
+    fn title_case(slug: &str) -> String {
+        slug.split('-')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// When a market ends relative to now, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum EndDateBucket {
+    /// Past its end date but still held, typically awaiting resolution
+    Ended,
+    ThisWeek,
+    ThisMonth,
+    Later,
+    Unknown,
+}
+
+impl EndDateBucket {
+    pub fn from_end_date(end_date: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        match end_date {
+            None => Self::Unknown,
+            Some(end) if end < now => Self::Ended,
+            Some(end) if end <= now + Duration::days(7) => Self::ThisWeek,
+            Some(end) if end <= now + Duration::days(30) => Self::ThisMonth,
+            Some(_) => Self::Later,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ended => "Ended, unresolved",
+            Self::ThisWeek => "This week (7d)",
+            Self::ThisMonth => "This month (30d)",
+            Self::Later => "Later",
+            Self::Unknown => "No end date",
+        }
+    }
+}
+
+/// The market metadata grouping needs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketMeta {
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+impl From<&TokenInfo> for MarketMeta {
+    fn from(info: &TokenInfo) -> Self {
+        Self {
+            category: info.category.clone(),
+            tags: info.tags.clone(),
+            end_date: info.end_date_iso.as_deref().and_then(Self::parse_end_date),
+        }
+    }
+}
+
+impl MarketMeta {
+    /// Look up the market of every position's token in the typed store,
+    /// opened once for all of them
+    ///
+    /// Tokens the store doesn't know are left out.
+    pub fn load_all(
+        db_path: &Path,
+        positions: &[Position],
+    ) -> Result<HashMap<String, Self>, DbContextError> {
+        let token_ids = positions.iter().map(|p| p.token_id.as_str());
+        Ok(token_info::lookup_all(db_path, token_ids)?
+            .into_iter()
+            .map(|(token_id, info)| (token_id, Self::from(&info)))
+            .collect())
+    }
+
+    /// Parse a market's `end_date_iso`, a full timestamp or a bare date
+    pub fn parse_end_date(raw: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(raw)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|dt| dt.and_utc())
+            })
+    }
+}
+
+/// What positions are summed under: a normalized category or an end-date bucket
+///
+/// Ordered so end-date buckets come out in time order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum GroupKey {
+    Category(String),
+    EndDate(EndDateBucket),
+}
+
+impl GroupKey {
+    fn label(&self) -> String {
+        match self {
+            Self::Category(category) => category.clone(),
+            Self::EndDate(bucket) => bucket.label().to_string(),
+        }
+    }
+}
+
+/// Summed exposure of the open positions in one group
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExposureGroup {
+    pub label: String,
+    pub positions: usize,
+    /// Value at the mark
+    pub value_usdc: Decimal,
+    pub cost_usdc: Decimal,
+    pub pnl_usdc: Decimal,
+}
+
+impl ExposureGroup {
+    /// Share of `total` value, in percent
+    pub fn share_of(&self, total: Decimal) -> Option<Decimal> {
+        (!total.is_zero()).then(|| (self.value_usdc / total * Decimal::from(100)).round_dp(1))
+    }
+
+    /// Group the open positions; `meta` resolves a position's market
+    ///
+    /// Categories come out by value, largest first; end-date buckets in time order.
+    pub fn group<'a>(
+        positions: impl IntoIterator<Item = &'a Position>,
+        mut meta: impl FnMut(&Position) -> Option<MarketMeta>,
+        grouping: ExposureGrouping,
+        now: DateTime<Utc>,
+    ) -> Vec<Self> {
+        let mut groups: BTreeMap<GroupKey, Self> = BTreeMap::new();
+        for position in positions {
+            if position.status != PositionStatus::Open || position.size.is_zero() {
+                continue;
+            }
+            let market = meta(position).unwrap_or_default();
+            let key = match grouping {
+                ExposureGrouping::Category => GroupKey::Category(CategoryGroup::normalize(
+                    market.category.as_deref(),
+                    &market.tags,
+                )),
+                ExposureGrouping::EndDate => {
+                    GroupKey::EndDate(EndDateBucket::from_end_date(market.end_date, now))
+                }
+            };
+
+            let exposure = OutcomeExposure::from_position(position);
+            let group = groups.entry(key).or_insert_with_key(|key| Self {
+                label: key.label(),
+                positions: 0,
+                value_usdc: Decimal::ZERO,
+                cost_usdc: Decimal::ZERO,
+                pnl_usdc: Decimal::ZERO,
+            });
+            group.positions += 1;
+            group.value_usdc += exposure.value_usdc;
+            group.cost_usdc += exposure.cost_usdc;
+            group.pnl_usdc += exposure.pnl_usdc;
+        }
+
+        let mut groups: Vec<Self> = groups.into_values().collect();
+        if grouping == ExposureGrouping::Category {
+            groups.sort_by(|a, b| b.value_usdc.cmp(&a.value_usdc));
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// A position in market `0x<token_id>` worth what it cost
+    fn position(token_id: &str, size: Decimal, price: Decimal) -> Position {
+        Position::test(token_id, size)
+            .with_market(&format!("0x{}", token_id))
+            .with_average_price(price)
+            .with_current_price(price)
+    }
+
+    #[test]
+    fn positions_group_by_normalized_category_and_end_date() {
+        assert_eq!(
+            CategoryGroup::normalize(Some("US-current-affairs"), &[]),
+            "Politics"
+        );
+        assert_eq!(CategoryGroup::normalize(Some("NBA Playoffs"), &[]), "Sports");
+        assert_eq!(CategoryGroup::normalize(None, &["Bitcoin".to_string()]), "Crypto");
+        assert_eq!(
+            CategoryGroup::normalize(Some("weather_events"), &[]),
+            "Weather Events"
+        );
+        assert_eq!(
+            CategoryGroup::normalize(None, &["misc".to_string()]),
+            UNCATEGORIZED
+        );
+        // Keywords match whole words or their inflections, not inside other words
+        assert_eq!(CategoryGroup::normalize(Some("Transportation"), &[]), "Transportation");
+        assert_eq!(CategoryGroup::normalize(Some("political-news"), &[]), "Politics");
+        assert_eq!(CategoryGroup::normalize(Some("Fed Rates"), &[]), "Business");
+
+        let now = Utc::now();
+        let meta: HashMap<&str, MarketMeta> = [
+            ("1", Some("Politics"), Some(now + Duration::days(3))),
+            ("2", Some("Elections"), Some(now + Duration::days(20))),
+            ("3", Some("Sports"), Some(now - Duration::days(1))),
+        ]
+        .into_iter()
+        .map(|(token, category, end_date)| {
+            let meta = MarketMeta {
+                category: category.map(str::to_string),
+                tags: Vec::new(),
+                end_date,
+            };
+            (token, meta)
+        })
+        .collect();
+        let closed = position("5", dec!(10), dec!(0.5)).with_status(PositionStatus::Closed);
+        let positions = [
+            position("1", dec!(100), dec!(0.5)),
+            position("2", dec!(100), dec!(0.25)),
+            position("3", dec!(400), dec!(0.5)),
+            position("4", dec!(10), dec!(0.1)),
+            closed,
+        ];
+        let lookup = |p: &Position| meta.get(p.token_id.as_str()).cloned();
+
+        let by_category = ExposureGroup::group(&positions, lookup, ExposureGrouping::Category, now);
+        let labels: Vec<(&str, usize, Decimal)> = by_category
+            .iter()
+            .map(|g| (g.label.as_str(), g.positions, g.value_usdc))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                ("Sports", 1, dec!(200)),
+                ("Politics", 2, dec!(75)),
+                (UNCATEGORIZED, 1, dec!(1)),
+            ]
+        );
+        assert_eq!(by_category[0].share_of(dec!(276)), Some(dec!(72.5)));
+
+        let by_end_date = ExposureGroup::group(&positions, lookup, ExposureGrouping::EndDate, now);
+        let buckets: Vec<&str> = by_end_date.iter().map(|g| g.label.as_str()).collect();
+        assert_eq!(
+            buckets,
+            [
+                EndDateBucket::Ended.label(),
+                EndDateBucket::ThisWeek.label(),
+                EndDateBucket::ThisMonth.label(),
+                EndDateBucket::Unknown.label(),
+            ]
+        );
+    }
+}
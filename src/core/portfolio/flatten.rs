@@ -0,0 +1,338 @@
+//! Bulk close-out of every position in scope
+//!
+//! Flattening cancels the resting orders first, so sells aren't blocked by
+//! shares reserved for them, then closes each open position with a limit
+//! order capped at a maximum slippage from the touch. Only the part of the
+//! book inside that limit can fill right away; the rest of the order rests at
+//! the limit instead of chasing the price down.
+
+use anyhow::{anyhow, Result};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+
+use super::close::{ClosePlan, ClosePrice};
+use super::types::{ActiveOrder, OrderSide, Position, PositionSide, PositionStatus};
+use crate::core::types::market::PriceLevel;
+
+/// Largest slippage accepted (50%), beyond which the limit protects nothing
+pub const MAX_SLIPPAGE_LIMIT: Decimal = Decimal::from_parts(5, 0, 0, false, 1);
+
+/// Order closing one whole position
+#[derive(Debug, Clone)]
+pub struct FlattenOrder {
+    pub position: Position,
+    pub side: OrderSide,
+    pub size: Decimal,
+    /// Best bid (closing longs) or best ask (closing shorts)
+    pub touch: Decimal,
+    /// Worst price the order accepts
+    pub limit: Decimal,
+    /// Shares the book holds inside the limit
+    pub fillable: Decimal,
+    /// USDC received (sells) or paid (buys) for the fillable shares
+    pub expected_value: Decimal,
+}
+
+impl FlattenOrder {
+    /// Price closing all of `position` against `levels` of the closing side,
+    /// best first, at most `max_slippage` (a fraction) away from the touch;
+    /// the limit is a multiple of the market's `tick_size`
+    pub fn new(
+        position: &Position,
+        levels: &[PriceLevel],
+        tick_size: Decimal,
+        max_slippage: Decimal,
+    ) -> Result<Self> {
+        if tick_size <= Decimal::ZERO || tick_size >= Decimal::ONE {
+            return Err(anyhow!("invalid tick size {}", tick_size));
+        }
+        let touch = levels
+            .first()
+            .map(|level| level.price)
+            .ok_or_else(|| anyhow!("no liquidity on the closing side"))?;
+        let plan = ClosePlan::new(position, Decimal::ONE_HUNDRED, ClosePrice::Market(touch))?;
+
+        // Rounding toward the touch keeps the limit inside the slippage
+        let limit = match plan.side {
+            OrderSide::Sell => ((touch * (Decimal::ONE - max_slippage)) / tick_size).ceil() * tick_size,
+            OrderSide::Buy => ((touch * (Decimal::ONE + max_slippage)) / tick_size).floor() * tick_size,
+        };
+        let limit = match plan.side {
+            OrderSide::Sell => limit.min(touch).max(tick_size),
+            OrderSide::Buy => limit.max(touch).min(Decimal::ONE - tick_size),
+        }
+        .normalize();
+
+        let mut fillable = Decimal::ZERO;
+        let mut expected_value = Decimal::ZERO;
+        for level in levels {
+            let inside = match plan.side {
+                OrderSide::Sell => level.price >= limit,
+                OrderSide::Buy => level.price <= limit,
+            };
+            if !inside || fillable >= plan.size {
+                break;
+            }
+            let take = level.size.min(plan.size - fillable);
+            fillable += take;
+            expected_value += take * level.price;
+        }
+
+        Ok(Self {
+            position: position.clone(),
+            side: plan.side,
+            size: plan.size,
+            touch,
+            limit,
+            fillable,
+            expected_value,
+        })
+    }
+
+    /// Shares left resting at the limit after the fillable part trades
+    pub fn resting(&self) -> Decimal {
+        self.size - self.fillable
+    }
+
+    pub fn average_price(&self) -> Option<Decimal> {
+        (!self.fillable.is_zero()).then(|| self.expected_value / self.fillable)
+    }
+}
+
+/// Everything a flatten will do, for the preview and the submission
+#[derive(Debug, Clone, Default)]
+pub struct FlattenPlan {
+    pub cancels: Vec<ActiveOrder>,
+    pub orders: Vec<FlattenOrder>,
+    /// Positions that can't be closed now
+    pub skipped: Vec<SkippedPosition>,
+}
+
+/// A position a flatten leaves open and why
+#[derive(Debug, Clone)]
+pub struct SkippedPosition {
+    pub position: Position,
+    pub reason: String,
+}
+
+impl FlattenPlan {
+    pub fn is_empty(&self) -> bool {
+        self.cancels.is_empty() && self.orders.is_empty()
+    }
+
+    /// USDC the sells are expected to bring in
+    pub fn expected_proceeds(&self) -> Decimal {
+        self.total(OrderSide::Sell)
+    }
+
+    /// USDC the buys closing shorts are expected to cost
+    pub fn expected_cost(&self) -> Decimal {
+        self.total(OrderSide::Buy)
+    }
+
+    fn total(&self, side: OrderSide) -> Decimal {
+        self.orders
+            .iter()
+            .filter(|order| order.side == side)
+            .map(|order| order.expected_value)
+            .sum()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.cancels.is_empty() {
+            lines.push(format!("Cancel {} resting orders", self.cancels.len()));
+        }
+        for order in &self.orders {
+            let name = order
+                .position
+                .market_question
+                .as_deref()
+                .unwrap_or(&order.position.market_id);
+            let average = order
+                .average_price()
+                .map(|p| format!("avg {}", p.round_dp(4)))
+                .unwrap_or_else(|| "nothing inside the limit".to_string());
+            let mut line = format!(
+                "{:?} {} {} of {} @ limit {} (touch {}): {} fill now, {}, ${}",
+                order.side,
+                order.size,
+                order.position.outcome,
+                name,
+                order.limit,
+                order.touch,
+                order.fillable,
+                average,
+                order.expected_value.round_dp(2)
+            );
+            if !order.resting().is_zero() {
+                line.push_str(&format!(", {} rest at the limit", order.resting()));
+            }
+            lines.push(line);
+        }
+        for skipped in &self.skipped {
+            let position = &skipped.position;
+            lines.push(format!(
+                "Skip {} {} ({}): {}",
+                position.size, position.outcome, position.token_id, skipped.reason
+            ));
+        }
+        lines.push(format!(
+            "Expected proceeds ${}, cost of covering shorts ${}",
+            self.expected_proceeds().round_dp(2),
+            self.expected_cost().round_dp(2)
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Plan flattening `positions` and cancelling `orders`, optionally only in
+/// market `market_id`, pricing each close from the current book
+pub async fn prepare_flatten(
+    client: &ClobClient,
+    positions: &[Position],
+    orders: &[ActiveOrder],
+    market_id: Option<&str>,
+    max_slippage: Decimal,
+) -> Result<FlattenPlan> {
+    if max_slippage < Decimal::ZERO || max_slippage > MAX_SLIPPAGE_LIMIT {
+        return Err(anyhow!(
+            "Max slippage must be between 0% and {}%, got {}%",
+            MAX_SLIPPAGE_LIMIT * Decimal::ONE_HUNDRED,
+            max_slippage * Decimal::ONE_HUNDRED
+        ));
+    }
+    let in_scope = |id: &str| market_id.map_or(true, |market_id| market_id == id);
+
+    let mut plan = FlattenPlan {
+        cancels: orders
+            .iter()
+            .filter(|order| in_scope(&order.market_id))
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+
+    for position in positions
+        .iter()
+        .filter(|p| p.status == PositionStatus::Open && in_scope(&p.market_id))
+    {
+        let book = match client.get_order_book(&position.token_id).await {
+            Ok(book) => book,
+            Err(e) => {
+                plan.skipped.push(SkippedPosition {
+                    position: position.clone(),
+                    reason: format!("failed to load the book: {}", e),
+                });
+                continue;
+            }
+        };
+        let tick_size = match client.get_tick_size(&position.token_id).await {
+            Ok(tick_size) => tick_size,
+            Err(e) => {
+                plan.skipped.push(SkippedPosition {
+                    position: position.clone(),
+                    reason: format!("failed to load the tick size: {}", e),
+                });
+                continue;
+            }
+        };
+        // The closing side: bids for longs, asks for shorts, best first
+        let side = match position.side {
+            PositionSide::Long => &book.bids,
+            PositionSide::Short => &book.asks,
+        };
+        let mut levels: Vec<PriceLevel> = side
+            .iter()
+            .map(|l| PriceLevel::new(l.price, l.size))
+            .collect();
+        match position.side {
+            PositionSide::Long => levels.sort_by(|a, b| b.price.cmp(&a.price)),
+            PositionSide::Short => levels.sort_by(|a, b| a.price.cmp(&b.price)),
+        }
+
+        match FlattenOrder::new(position, &levels, tick_size, max_slippage) {
+            Ok(order) => plan.orders.push(order),
+            Err(e) => plan.skipped.push(SkippedPosition {
+                position: position.clone(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn position(side: PositionSide, size: Decimal) -> Position {
+        Position::test("t", size).with_side(side)
+    }
+
+    #[test]
+    fn closes_are_capped_at_the_slippage_limit() {
+        let bids = [
+            PriceLevel::new(dec!(0.50), dec!(60)),
+            PriceLevel::new(dec!(0.49), dec!(20)),
+            PriceLevel::new(dec!(0.45), dec!(500)),
+        ];
+        let order =
+            FlattenOrder::new(&position(PositionSide::Long, dec!(100)), &bids, dec!(0.01), dec!(0.02))
+                .unwrap();
+        assert_eq!(order.side, OrderSide::Sell);
+        assert_eq!(order.limit, dec!(0.49));
+        assert_eq!(order.fillable, dec!(80));
+        assert_eq!(order.expected_value, dec!(39.80));
+        assert_eq!(order.resting(), dec!(20));
+
+        let asks = [
+            PriceLevel::new(dec!(0.30), dec!(10)),
+            PriceLevel::new(dec!(0.31), dec!(100)),
+        ];
+        let cover =
+            FlattenOrder::new(&position(PositionSide::Short, dec!(50)), &asks, dec!(0.01), dec!(0.05))
+                .unwrap();
+        assert_eq!(cover.side, OrderSide::Buy);
+        assert_eq!(cover.limit, dec!(0.31));
+        assert_eq!(cover.fillable, dec!(50));
+        assert!(cover.resting().is_zero());
+
+        assert!(
+            FlattenOrder::new(&position(PositionSide::Long, dec!(10)), &[], dec!(0.01), dec!(0.02))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn limits_land_on_the_market_tick() {
+        // A 0.1 tick market rejects cent prices; 0.54 rounds up toward the touch
+        let bids = [
+            PriceLevel::new(dec!(0.6), dec!(100)),
+            PriceLevel::new(dec!(0.5), dec!(100)),
+        ];
+        let order =
+            FlattenOrder::new(&position(PositionSide::Long, dec!(150)), &bids, dec!(0.1), dec!(0.1))
+                .unwrap();
+        assert_eq!(order.limit, dec!(0.6));
+        assert_eq!(order.fillable, dec!(100));
+
+        // A 0.001 tick market trades below a cent and up to 0.999
+        let bids = [PriceLevel::new(dec!(0.004), dec!(100))];
+        let order =
+            FlattenOrder::new(&position(PositionSide::Long, dec!(10)), &bids, dec!(0.001), dec!(0.5))
+                .unwrap();
+        assert_eq!(order.limit, dec!(0.002));
+        let asks = [PriceLevel::new(dec!(0.998), dec!(100))];
+        let cover =
+            FlattenOrder::new(&position(PositionSide::Short, dec!(10)), &asks, dec!(0.001), dec!(0.5))
+                .unwrap();
+        assert_eq!(cover.limit, dec!(0.999));
+
+        assert!(
+            FlattenOrder::new(&position(PositionSide::Long, dec!(10)), &bids, Decimal::ZERO, dec!(0.5))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Hedging assistant for binary markets
+//!
+//! Net exposure of a market is measured in shares of its first outcome: the
+//! first outcome's holdings minus the second's, with short positions counted
+//! negative. Buying the complementary token moves that number toward the
+//! target without having to sell into a thin bid, so the planner only ever
+//! produces buy orders.
+//!
+//! Order sizes are rounded down to the exchange's share lot, and a hedge
+//! below the market's minimum order size is refused rather than rounded up.
+
+use anyhow::{anyhow, Result};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_json::Value;
+
+use super::types::{Position, PositionSide, PositionStatus};
+use crate::client::PolybotClient;
+use crate::markets::clob::fetcher::MarketToken;
+
+/// One outcome token of a binary market
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutcomeToken {
+    pub token_id: String,
+    pub outcome: String,
+}
+
+impl OutcomeToken {
+    /// Both tokens from a CLOB market response (`tokens[].token_id`/`outcome`),
+    /// in the order the API lists them
+    pub fn pair_from_market(market: &Value) -> Result<[OutcomeToken; 2]> {
+        let tokens: Vec<OutcomeToken> = market
+            .get("tokens")
+            .and_then(|v| v.as_array())
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .filter_map(|token| {
+                        Some(OutcomeToken {
+                            token_id: token.get("token_id")?.as_str()?.to_string(),
+                            outcome: token
+                                .get("outcome")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("?")
+                                .to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self::pair(tokens)
+    }
+
+    /// Both tokens of a typed CLOB market, in the order the API lists them
+    pub fn pair_from_tokens(tokens: &[MarketToken]) -> Result<[OutcomeToken; 2]> {
+        Self::pair(
+            tokens
+                .iter()
+                .map(|token| OutcomeToken {
+                    token_id: token.token_id.clone(),
+                    outcome: token.outcome.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    fn pair(tokens: Vec<OutcomeToken>) -> Result<[OutcomeToken; 2]> {
+        <[OutcomeToken; 2]>::try_from(tokens)
+            .map_err(|tokens| anyhow!("Expected a binary market with 2 tokens, found {}", tokens.len()))
+    }
+}
+
+/// Order needed to move a market's exposure to the target
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeOrder {
+    pub token_id: String,
+    pub outcome: String,
+    /// Shares to buy
+    pub size: Decimal,
+    /// Limit price, filled in from the book before submitting
+    pub price: Option<Decimal>,
+}
+
+impl HedgeOrder {
+    pub fn notional(&self) -> Option<Decimal> {
+        self.price.map(|price| price * self.size)
+    }
+}
+
+/// Hedge for one market: where exposure is now, where it should be, and how to get there
+#[derive(Debug, Clone)]
+pub struct HedgePlan {
+    pub market_id: String,
+    pub tokens: [OutcomeToken; 2],
+    pub current_exposure: Decimal,
+    pub target_exposure: Decimal,
+    /// Empty when the market is already at the target
+    pub orders: Vec<HedgeOrder>,
+}
+
+impl HedgePlan {
+    /// Shares are traded in lots of 0.01
+    pub const SIZE_DECIMALS: u32 = 2;
+
+    pub fn new(
+        market_id: &str,
+        tokens: [OutcomeToken; 2],
+        positions: &[Position],
+        target_exposure: Decimal,
+    ) -> Self {
+        let current_exposure = Self::net_exposure(positions, &tokens);
+        let excess = current_exposure - target_exposure;
+
+        // Too much of the first outcome is offset with the second and vice versa
+        let offset_token = if excess > Decimal::ZERO { &tokens[1] } else { &tokens[0] };
+        let size = excess
+            .abs()
+            .round_dp_with_strategy(Self::SIZE_DECIMALS, RoundingStrategy::ToZero);
+        let orders = if size.is_zero() {
+            Vec::new()
+        } else {
+            vec![HedgeOrder {
+                token_id: offset_token.token_id.clone(),
+                outcome: offset_token.outcome.clone(),
+                size,
+                price: None,
+            }]
+        };
+
+        Self {
+            market_id: market_id.to_string(),
+            tokens,
+            current_exposure,
+            target_exposure,
+            orders,
+        }
+    }
+
+    /// Look up the market's tokens, plan the hedge and price each order at the best ask
+    pub async fn prepare(
+        client: &PolybotClient,
+        market_id: &str,
+        positions: &[Position],
+        target_exposure: Decimal,
+    ) -> Result<Self> {
+        let market = client
+            .market(market_id)
+            .await
+            .map_err(|e| anyhow!("Failed to load market {}: {}", market_id, e))?;
+        let tokens = OutcomeToken::pair_from_tokens(&market.tokens)?;
+        let mut plan = Self::new(market_id, tokens, positions, target_exposure);
+        let minimum = market
+            .minimum_order_size
+            .and_then(Decimal::from_f64_retain)
+            .unwrap_or(Decimal::ZERO);
+
+        for order in &mut plan.orders {
+            if order.size < minimum {
+                return Err(anyhow!(
+                    "Hedge of {} {} is below the market's minimum order size of {}",
+                    order.size,
+                    order.outcome,
+                    minimum.normalize()
+                ));
+            }
+            let book = client.order_book(&order.token_id).await?;
+            let best_ask = book
+                .best_ask()
+                .ok_or_else(|| anyhow!("No asks for {} to hedge with", order.outcome))?;
+            order.price = Some(best_ask.price);
+        }
+        Ok(plan)
+    }
+
+    /// Place every priced order of the plan through the client's order
+    /// manager, returning the exchange order ids. Stops at the first
+    /// rejection; orders placed before it stay on the book.
+    pub async fn submit(&self, client: &PolybotClient) -> Result<Vec<String>> {
+        let mut order_ids = Vec::with_capacity(self.orders.len());
+        for order in &self.orders {
+            let price = order
+                .price
+                .ok_or_else(|| anyhow!("Hedge order for {} has no price", order.outcome))?;
+            let order_id = client
+                .buy(&order.token_id, price, order.size)
+                .await?
+                .into_order_id()
+                .map_err(|e| match order_ids.len() {
+                    0 => e,
+                    placed => anyhow!("{} ({} hedge order(s) already placed)", e, placed),
+                })?;
+            order_ids.push(order_id);
+        }
+        Ok(order_ids)
+    }
+
+    /// Shares of the first outcome minus shares of the second across open positions
+    pub fn net_exposure(positions: &[Position], tokens: &[OutcomeToken; 2]) -> Decimal {
+        positions
+            .iter()
+            .filter(|position| position.status == PositionStatus::Open)
+            .map(|position| {
+                let signed = match position.side {
+                    PositionSide::Long => position.size,
+                    PositionSide::Short => -position.size,
+                };
+                if position.token_id == tokens[0].token_id {
+                    signed
+                } else if position.token_id == tokens[1].token_id {
+                    -signed
+                } else {
+                    Decimal::ZERO
+                }
+            })
+            .sum()
+    }
+
+    /// Exposure after every order fills
+    pub fn resulting_exposure(&self) -> Decimal {
+        self.orders.iter().fold(self.current_exposure, |exposure, order| {
+            if order.token_id == self.tokens[0].token_id {
+                exposure + order.size
+            } else {
+                exposure - order.size
+            }
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "Exposure {} {} → {} (target {})",
+            self.current_exposure.round_dp(2),
+            self.tokens[0].outcome,
+            self.resulting_exposure().round_dp(2),
+            self.target_exposure.round_dp(2)
+        )];
+        if self.orders.is_empty() {
+            lines.push("Already at target, nothing to do".to_string());
+        }
+        for order in &self.orders {
+            let price = order
+                .price
+                .map(|p| format!(" @ {}", p))
+                .unwrap_or_default();
+            lines.push(format!("BUY {} {}{}", order.size.round_dp(2), order.outcome, price));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn tokens() -> [OutcomeToken; 2] {
+        [
+            OutcomeToken {
+                token_id: "yes".to_string(),
+                outcome: "Yes".to_string(),
+            },
+            OutcomeToken {
+                token_id: "no".to_string(),
+                outcome: "No".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn long_first_outcome_is_hedged_with_the_second() {
+        let positions = [Position::test("yes", dec!(100)), Position::test("no", dec!(30))];
+        let plan = HedgePlan::new("m", tokens(), &positions, Decimal::ZERO);
+        assert_eq!(plan.current_exposure, dec!(70));
+        assert_eq!(plan.orders.len(), 1);
+        assert_eq!(plan.orders[0].token_id, "no");
+        assert_eq!(plan.orders[0].size, dec!(70));
+        assert_eq!(plan.resulting_exposure(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn partial_target_buys_the_difference() {
+        let positions = [Position::test("no", dec!(50))];
+        let plan = HedgePlan::new("m", tokens(), &positions, dec!(10));
+        assert_eq!(plan.orders[0].token_id, "yes");
+        assert_eq!(plan.orders[0].size, dec!(60));
+        assert_eq!(plan.resulting_exposure(), dec!(10));
+    }
+
+    #[test]
+    fn sizes_are_rounded_down_to_the_lot() {
+        let positions = [Position::test("yes", dec!(12.3456))];
+        let plan = HedgePlan::new("m", tokens(), &positions, Decimal::ZERO);
+        assert_eq!(plan.orders[0].size, dec!(12.34));
+
+        let dust = [Position::test("yes", dec!(0.004))];
+        assert!(HedgePlan::new("m", tokens(), &dust, Decimal::ZERO).orders.is_empty());
+    }
+
+    #[test]
+    fn at_target_needs_no_orders() {
+        let positions = [Position::test("yes", dec!(20)), Position::test("no", dec!(20))];
+        let plan = HedgePlan::new("m", tokens(), &positions, Decimal::ZERO);
+        assert!(plan.orders.is_empty());
+    }
+
+    #[test]
+    fn parses_tokens_from_market_response() {
+        let market = serde_json::json!({
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes"},
+                {"token_id": "2", "outcome": "No"}
+            ]
+        });
+        let pair = OutcomeToken::pair_from_market(&market).unwrap();
+        assert_eq!(pair[1].outcome, "No");
+        assert!(OutcomeToken::pair_from_market(&serde_json::json!({"tokens": []})).is_err());
+    }
+}
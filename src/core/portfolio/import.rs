@@ -0,0 +1,785 @@
+//! Seeding the local portfolio from the Data API's account history
+//!
+//! A new install has no trades, lots or positions on disk. The importer pulls
+//! every trade of the account from the Data API, along with the redeems and
+//! merges that closed positions outside the order book, replays them oldest
+//! first at average cost and writes the result where live trading would have
+//! put it: executions in the raw trade store, daily [`TradeRecord`]s with
+//! their P&L impact, one [`LotEntry`] per closing fill in the token's lot
+//! history, and positions.
+//!
+//! Redeems pay 1 per winning share and nothing for the others; merges turn
+//! one share of every outcome back into 1 USDC, split across the outcomes in
+//! proportion to their average cost. Taker fills pay the market's taker fee,
+//! looked up from the CLOB since the Data API reports no fees.
+//!
+//! The Data API has no trade or order IDs, so imported fills get IDs derived
+//! from their transaction hash and act as their own orders. That keeps reruns
+//! idempotent: records and lots that are already stored are skipped.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use tracing::warn;
+
+use crate::auth::MarketSettings;
+use crate::core::portfolio::storage::{
+    LotEntry, LotFill, PortfolioStorage, PositionSummary, RawDataStorage, TradeRecord,
+};
+use crate::core::portfolio::types::*;
+use crate::core::progress::Progress;
+use crate::markets::gamma::{
+    ActivityQuery, ActivityType, GammaActivity, GammaClient, GammaTrade, TradeQuery, TradeSide,
+    UserAddress,
+};
+
+/// Trades or activity entries per Data API page
+const PAGE_SIZE: u32 = 500;
+
+/// Fee rate lookups in flight at once
+const FEE_RATE_CONCURRENCY: usize = 8;
+
+/// Prefix of trade IDs given to imported fills
+pub const IMPORTED_TRADE_PREFIX: &str = "dataapi-";
+
+/// A fill as both trade listings report it, to tell which ones the account took
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FillKey {
+    transaction_hash: String,
+    token_id: String,
+    price: Decimal,
+    size: Decimal,
+}
+
+impl FillKey {
+    fn of(trade: &GammaTrade) -> Self {
+        Self {
+            transaction_hash: trade.transaction_hash.0.clone(),
+            token_id: trade.asset.0.clone(),
+            price: trade.price,
+            size: trade.size,
+        }
+    }
+}
+
+/// Everything the importer replays for one account
+#[derive(Debug, Default)]
+pub struct AccountHistory {
+    /// Maker and taker fills, newest first as served
+    pub trades: Vec<GammaTrade>,
+    /// Redeems and merges
+    pub activity: Vec<GammaActivity>,
+    /// Fills the account took liquidity in, which pay the taker fee
+    taker_fills: HashSet<FillKey>,
+    /// Taker fee of each token the account took liquidity in, in basis points
+    fee_rates: HashMap<String, u32>,
+}
+
+impl AccountHistory {
+    /// Fetch the whole history of `user`, with fee rates from the CLOB at `host`
+    ///
+    /// Stops with [`Cancelled`](crate::core::progress::Cancelled) when the task
+    /// is cancelled: a partial history would replay into wrong average prices.
+    pub async fn fetch(
+        client: &GammaClient,
+        host: &str,
+        user: &str,
+        progress: &Progress,
+    ) -> Result<Self> {
+        let trades = Self::fetch_pages(progress, |offset| async move {
+            anyhow::Ok(client.fetch_trades(&Self::trade_query(user, false, offset)).await?.data)
+        })
+        .await?;
+        let taker_trades = Self::fetch_pages(progress, |offset| async move {
+            anyhow::Ok(client.fetch_trades(&Self::trade_query(user, true, offset)).await?.data)
+        })
+        .await?;
+        let activity = Self::fetch_pages(progress, |offset| async move {
+            let query = ActivityQuery {
+                user: UserAddress(user.to_string()),
+                types: vec![ActivityType::Redeem, ActivityType::Merge],
+                limit: Some(PAGE_SIZE),
+                offset: Some(offset),
+            };
+            anyhow::Ok(client.fetch_activity(&query).await?.data)
+        })
+        .await?;
+
+        let mut history = Self {
+            trades,
+            activity,
+            taker_fills: taker_trades.iter().map(FillKey::of).collect(),
+            fee_rates: HashMap::new(),
+        };
+        history.fee_rates = history.fetch_fee_rates(&MarketSettings::new(host)?).await;
+        Ok(history)
+    }
+
+    fn trade_query(user: &str, taker_only: bool, offset: u32) -> TradeQuery {
+        TradeQuery {
+            user: Some(UserAddress(user.to_string())),
+            limit: Some(PAGE_SIZE),
+            offset: Some(offset),
+            taker_only: Some(taker_only),
+            ..Default::default()
+        }
+    }
+
+    /// Every page of a listing, fetched by offset until a short page
+    async fn fetch_pages<T, F, Fut>(progress: &Progress, mut fetch_page: F) -> Result<Vec<T>>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>>,
+    {
+        let mut items = Vec::new();
+        loop {
+            progress.check_cancelled()?;
+            // Give up on the page when cancelled; the check above then stops
+            let page = tokio::select! {
+                page = fetch_page(items.len() as u32) => page?,
+                _ = progress.cancelled() => continue,
+            };
+            let last_page = page.len() < PAGE_SIZE as usize;
+            progress.inc(page.len() as u64);
+            items.extend(page);
+            if last_page {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Taker fee rates of the tokens the account took liquidity in; a token
+    /// whose rate can't be read (e.g. a delisted market) counts as fee free
+    async fn fetch_fee_rates(&self, settings: &MarketSettings) -> HashMap<String, u32> {
+        let tokens: HashSet<&str> = self
+            .taker_fills
+            .iter()
+            .map(|fill| fill.token_id.as_str())
+            .collect();
+        stream::iter(tokens)
+            .map(|token_id| async move {
+                let rate = settings.fee_rate_bps(token_id).await.unwrap_or_else(|e| {
+                    warn!(
+                        "No fee rate for token {}, importing its fills without fees: {}",
+                        token_id, e
+                    );
+                    0
+                });
+                (token_id.to_string(), rate)
+            })
+            .buffer_unordered(FEE_RATE_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    fn is_taker(&self, trade: &GammaTrade) -> bool {
+        self.taker_fills.contains(&FillKey::of(trade))
+    }
+
+    /// Taker fee of a fill in USDC: the base rate on the cheaper side of the price
+    fn fee(&self, trade: &GammaTrade) -> Decimal {
+        if !self.is_taker(trade) {
+            return Decimal::ZERO;
+        }
+        let Some(rate) = self.fee_rates.get(&trade.asset.0) else {
+            return Decimal::ZERO;
+        };
+        let price = trade.price.min(Decimal::ONE - trade.price);
+        Decimal::from(*rate) / Decimal::from(10_000) * price * trade.size
+    }
+}
+
+/// One entry of the history in replay order
+enum HistoryEvent<'a> {
+    Trade(&'a GammaTrade),
+    Activity(&'a GammaActivity),
+}
+
+impl HistoryEvent<'_> {
+    fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            HistoryEvent::Trade(trade) => trade.timestamp,
+            HistoryEvent::Activity(activity) => activity.timestamp,
+        }
+    }
+
+    /// Oldest first, trades before the redeems and merges of the same second;
+    /// remaining ties are broken by content so reruns number fills the same
+    fn replay_cmp(&self, other: &Self) -> Ordering {
+        let by_time = self.timestamp().cmp(&other.timestamp());
+        match (self, other) {
+            (HistoryEvent::Trade(a), HistoryEvent::Trade(b)) => by_time
+                .then_with(|| a.transaction_hash.0.cmp(&b.transaction_hash.0))
+                .then_with(|| a.asset.0.cmp(&b.asset.0))
+                .then_with(|| a.price.cmp(&b.price))
+                .then_with(|| a.size.cmp(&b.size)),
+            (HistoryEvent::Activity(a), HistoryEvent::Activity(b)) => by_time
+                .then_with(|| a.transaction_hash.0.cmp(&b.transaction_hash.0))
+                .then_with(|| a.size.cmp(&b.size)),
+            (HistoryEvent::Trade(_), HistoryEvent::Activity(_)) => by_time.then(Ordering::Less),
+            (HistoryEvent::Activity(_), HistoryEvent::Trade(_)) => by_time.then(Ordering::Greater),
+        }
+    }
+}
+
+/// Portfolio state rebuilt from an account history
+#[derive(Debug, Default)]
+pub struct TradeImport {
+    pub executions: Vec<TradeExecution>,
+    pub records: Vec<TradeRecord>,
+    pub lots: Vec<LotEntry>,
+    pub positions: Vec<Position>,
+    /// Shares sold beyond the replayed position, e.g. tokens from a split or a
+    /// transfer; they close nothing and realize no P&L
+    pub unmatched_sells: Decimal,
+    pub redeems: usize,
+    pub merges: usize,
+    /// Taker fees of the imported fills
+    pub fees: Decimal,
+}
+
+impl TradeImport {
+    /// Replay a history, in any order, into executions, records, lots and
+    /// positions
+    pub fn replay(history: &AccountHistory) -> Self {
+        let mut events: Vec<HistoryEvent> = history
+            .trades
+            .iter()
+            .map(HistoryEvent::Trade)
+            .chain(history.activity.iter().map(HistoryEvent::Activity))
+            .collect();
+        events.sort_by(|a, b| a.replay_cmp(b));
+
+        let mut replay = Replay::default();
+        for event in events {
+            match event {
+                HistoryEvent::Trade(trade) => replay.trade(trade, history),
+                HistoryEvent::Activity(activity) => match activity.activity_type {
+                    ActivityType::Redeem => replay.redeem(activity),
+                    ActivityType::Merge => replay.merge(activity),
+                    _ => {}
+                },
+            }
+        }
+        replay.finish()
+    }
+
+    pub fn realized_pnl(&self) -> Decimal {
+        self.positions.iter().map(|p| p.realized_pnl).sum()
+    }
+
+    pub fn open_positions(&self) -> usize {
+        self.positions
+            .iter()
+            .filter(|p| p.status == PositionStatus::Open)
+            .count()
+    }
+
+    /// Write the import to the raw trade store, the trade history, the lot
+    /// histories and the current positions, skipping what is already there
+    pub async fn save(
+        &self,
+        storage: &PortfolioStorage,
+        raw_storage: &RawDataStorage,
+    ) -> Result<ImportSummary> {
+        raw_storage.init_directories().await?;
+        for execution in &self.executions {
+            raw_storage.store_trade(execution).await?;
+        }
+
+        let mut lots_by_token: BTreeMap<&str, Vec<LotEntry>> = BTreeMap::new();
+        for lot in &self.lots {
+            lots_by_token
+                .entry(lot.token_id.as_str())
+                .or_default()
+                .push(lot.clone());
+        }
+        let mut lots = 0;
+        for (token_id, entries) in &lots_by_token {
+            lots += storage.record_lot_entries(token_id, entries).await?;
+        }
+
+        let records = storage.record_trade_history(&self.records).await?;
+
+        let existing = storage.load_positions().await.unwrap_or_else(|e| {
+            warn!("Ignoring unreadable positions cache: {}", e);
+            Vec::new()
+        });
+        let mut positions = self.positions.clone();
+        positions.extend(
+            existing
+                .into_iter()
+                .filter(|p| !self.positions.iter().any(|i| i.token_id == p.token_id)),
+        );
+        storage.save_positions(&positions).await?;
+
+        Ok(ImportSummary {
+            trades: self.executions.len(),
+            records,
+            lots,
+            positions: self.positions.len(),
+        })
+    }
+}
+
+/// What [`TradeImport::save`] added to the local stores
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub trades: usize,
+    pub records: usize,
+    pub lots: usize,
+    pub positions: usize,
+}
+
+/// Shares a sell closed and the position it closed them against
+struct ClosedShares {
+    size: Decimal,
+    position_size_before: Decimal,
+    average_price: Decimal,
+    pnl: Decimal,
+}
+
+/// Running average-cost position of one token during the replay
+struct Book {
+    position: Position,
+}
+
+impl Book {
+    fn new(trade: &GammaTrade) -> Self {
+        Self {
+            position: Position {
+                market_id: trade.condition_id.0.clone(),
+                token_id: trade.asset.0.clone(),
+                outcome: trade.outcome.clone(),
+                side: PositionSide::Long,
+                size: Decimal::ZERO,
+                average_price: Decimal::ZERO,
+                current_price: None,
+                realized_pnl: Decimal::ZERO,
+                unrealized_pnl: None,
+                status: PositionStatus::Open,
+                opened_at: trade.timestamp,
+                updated_at: trade.timestamp,
+                closed_at: None,
+                fees_paid: Decimal::ZERO,
+                market_question: Some(trade.title.clone()),
+            },
+        }
+    }
+
+    fn buy(&mut self, price: Decimal, size: Decimal, at: DateTime<Utc>) {
+        let position = &mut self.position;
+        if position.size.is_zero() {
+            position.opened_at = at;
+            position.closed_at = None;
+            position.status = PositionStatus::Open;
+        }
+        let cost = position.size * position.average_price + size * price;
+        position.size += size;
+        position.average_price = cost / position.size;
+        position.updated_at = at;
+    }
+
+    /// Close up to `size` shares at `price`
+    fn sell(&mut self, price: Decimal, size: Decimal, at: DateTime<Utc>) -> ClosedShares {
+        let position = &mut self.position;
+        let closed = ClosedShares {
+            size: size.min(position.size),
+            position_size_before: position.size,
+            average_price: position.average_price,
+            pnl: Decimal::ZERO,
+        };
+        let pnl = (price - closed.average_price) * closed.size;
+
+        position.size -= closed.size;
+        position.realized_pnl += pnl;
+        position.updated_at = at;
+        if position.size.is_zero() && !closed.size.is_zero() {
+            position.status = PositionStatus::Closed;
+            position.closed_at = Some(at);
+        }
+        ClosedShares { pnl, ..closed }
+    }
+
+    /// Fees count against realized P&L
+    fn pay_fee(&mut self, fee: Decimal) {
+        self.position.fees_paid += fee;
+        self.position.realized_pnl -= fee;
+    }
+
+    fn summary(&self) -> PositionSummary {
+        PositionSummary {
+            size: self.position.size,
+            average_price: self.position.average_price,
+            realized_pnl: self.position.realized_pnl,
+            unrealized_pnl: None,
+        }
+    }
+}
+
+/// A fill applied to a token's book
+struct Fill<'a> {
+    transaction_hash: &'a str,
+    token_id: &'a str,
+    side: OrderSide,
+    price: Decimal,
+    size: Decimal,
+    fee: Decimal,
+    is_maker: bool,
+    timestamp: DateTime<Utc>,
+}
+
+/// Shares of a token still held during the replay
+struct OpenShares {
+    token_id: String,
+    size: Decimal,
+}
+
+/// Shares a redeem or merge takes out of a token's book, and at what price
+struct Payout {
+    token_id: String,
+    price: Decimal,
+    size: Decimal,
+}
+
+/// Books and output of a replay in progress
+#[derive(Default)]
+struct Replay {
+    import: TradeImport,
+    books: BTreeMap<String, Book>,
+    fills_per_tx: HashMap<String, usize>,
+}
+
+impl Replay {
+    fn trade(&mut self, trade: &GammaTrade, history: &AccountHistory) {
+        self.books
+            .entry(trade.asset.0.clone())
+            .or_insert_with(|| Book::new(trade));
+        let side = match trade.side {
+            TradeSide::Buy => OrderSide::Buy,
+            TradeSide::Sell => OrderSide::Sell,
+        };
+        self.fill(Fill {
+            transaction_hash: &trade.transaction_hash.0,
+            token_id: &trade.asset.0,
+            side,
+            price: trade.price,
+            size: trade.size,
+            fee: history.fee(trade),
+            is_maker: !history.is_taker(trade),
+            timestamp: trade.timestamp,
+        });
+    }
+
+    /// Close every open position of the market: winning shares at 1, the rest at 0
+    fn redeem(&mut self, activity: &GammaActivity) {
+        let open = self.open_books(&activity.condition_id.0);
+        if open.is_empty() {
+            return;
+        }
+        // One winning outcome pays its size; a payout matching no position
+        // (partly transferred tokens) is spread over all of them
+        let paid = activity.usdc_size;
+        let winner = open
+            .iter()
+            .find(|shares| !paid.is_zero() && shares.size.round_dp(2) == paid.round_dp(2))
+            .map(|shares| shares.token_id.clone());
+        let spread = paid / open.iter().map(|shares| shares.size).sum::<Decimal>();
+        let payouts = open
+            .into_iter()
+            .map(|shares| {
+                let price = match &winner {
+                    Some(winner) if *winner == shares.token_id => Decimal::ONE,
+                    Some(_) => Decimal::ZERO,
+                    None => spread.min(Decimal::ONE),
+                };
+                Payout {
+                    token_id: shares.token_id,
+                    price,
+                    size: shares.size,
+                }
+            })
+            .collect();
+        self.close_all(activity, payouts);
+        self.import.redeems += 1;
+    }
+
+    /// Sell one share of every outcome for 1 USDC per set, split by average cost
+    fn merge(&mut self, activity: &GammaActivity) {
+        let open = self.open_books(&activity.condition_id.0);
+        if open.is_empty() {
+            return;
+        }
+        let cost = |shares: &OpenShares| self.books[&shares.token_id].position.average_price;
+        let total_cost: Decimal = open.iter().map(cost).sum();
+        let outcomes = Decimal::from(open.len());
+        let payouts = open
+            .iter()
+            .map(|shares| Payout {
+                token_id: shares.token_id.clone(),
+                price: if total_cost.is_zero() {
+                    Decimal::ONE / outcomes
+                } else {
+                    cost(shares) / total_cost
+                },
+                size: shares.size.min(activity.size),
+            })
+            .collect();
+        self.close_all(activity, payouts);
+        self.import.merges += 1;
+    }
+
+    /// Tokens of a market with shares left
+    fn open_books(&self, market_id: &str) -> Vec<OpenShares> {
+        self.books
+            .values()
+            .filter(|book| book.position.market_id == market_id && !book.position.size.is_zero())
+            .map(|book| OpenShares {
+                token_id: book.position.token_id.clone(),
+                size: book.position.size,
+            })
+            .collect()
+    }
+
+    fn close_all(&mut self, activity: &GammaActivity, payouts: Vec<Payout>) {
+        for payout in payouts {
+            self.fill(Fill {
+                transaction_hash: &activity.transaction_hash.0,
+                token_id: &payout.token_id,
+                side: OrderSide::Sell,
+                price: payout.price,
+                size: payout.size,
+                fee: Decimal::ZERO,
+                is_maker: false,
+                timestamp: activity.timestamp,
+            });
+        }
+    }
+
+    /// Apply a fill to its (existing) book and record it
+    fn fill(&mut self, fill: Fill<'_>) {
+        let number = self
+            .fills_per_tx
+            .entry(fill.transaction_hash.to_string())
+            .or_default();
+        let trade_id = format!(
+            "{}{}-{}",
+            IMPORTED_TRADE_PREFIX, fill.transaction_hash, number
+        );
+        *number += 1;
+
+        let book = self
+            .books
+            .get_mut(fill.token_id)
+            .expect("fills only reach tokens with a book");
+        let mut pnl_impact = match fill.side {
+            OrderSide::Buy => {
+                book.buy(fill.price, fill.size, fill.timestamp);
+                None
+            }
+            OrderSide::Sell => {
+                let closed = book.sell(fill.price, fill.size, fill.timestamp);
+                self.import.unmatched_sells += fill.size - closed.size;
+                if !closed.size.is_zero() {
+                    self.import.lots.push(LotEntry {
+                        order_id: trade_id.clone(),
+                        market_id: book.position.market_id.clone(),
+                        token_id: fill.token_id.to_string(),
+                        side: OrderSide::Sell,
+                        price: fill.price,
+                        size: closed.size,
+                        position_size_before: closed.position_size_before,
+                        average_price: closed.average_price,
+                        created_at: fill.timestamp,
+                        fills: vec![LotFill {
+                            trade_id: trade_id.clone(),
+                            price: fill.price,
+                            size: closed.size,
+                            timestamp: fill.timestamp,
+                        }],
+                    });
+                }
+                Some(closed.pnl)
+            }
+        };
+        if !fill.fee.is_zero() {
+            book.pay_fee(fill.fee);
+            self.import.fees += fill.fee;
+            pnl_impact = Some(pnl_impact.unwrap_or_default() - fill.fee);
+        }
+
+        self.import.executions.push(TradeExecution {
+            trade_id: trade_id.clone(),
+            order_id: trade_id.clone(),
+            market_id: book.position.market_id.clone(),
+            token_id: fill.token_id.to_string(),
+            side: fill.side,
+            price: fill.price,
+            size: fill.size,
+            fee: fill.fee,
+            timestamp: fill.timestamp,
+            is_maker: fill.is_maker,
+        });
+        self.import.records.push(TradeRecord {
+            trade_id: trade_id.clone(),
+            order_id: trade_id,
+            market_id: book.position.market_id.clone(),
+            asset_id: fill.token_id.to_string(),
+            market_question: book.position.market_question.clone().unwrap_or_default(),
+            outcome: book.position.outcome.clone(),
+            side: fill.side,
+            price: fill.price,
+            size: fill.size,
+            fee: fill.fee,
+            timestamp: fill.timestamp,
+            pnl_impact,
+            position_after: Some(book.summary()),
+        });
+    }
+
+    fn finish(mut self) -> TradeImport {
+        self.import.positions = self.books.into_values().map(|book| book.position).collect();
+        self.import
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::gamma::{ClobTokenId, ConditionId, EventSlug, MarketSlug, TransactionHash};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn trade(tx: &str, day: u32, side: TradeSide, price: Decimal, size: Decimal) -> GammaTrade {
+        GammaTrade {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            proxy_wallet: UserAddress("0xabc".to_string()),
+            side,
+            condition_id: ConditionId("0xmarket".to_string()),
+            asset: ClobTokenId("yes".to_string()),
+            outcome: "Yes".to_string(),
+            outcome_index: 0,
+            price,
+            size,
+            title: "Will it rain?".to_string(),
+            slug: MarketSlug("rain".to_string()),
+            event_slug: EventSlug("weather".to_string()),
+            transaction_hash: TransactionHash(tx.to_string()),
+            name: None,
+            pseudonym: None,
+            profile_image: None,
+            profile_image_optimized: None,
+            icon: None,
+            bio: None,
+        }
+    }
+
+    fn no_trade(tx: &str, day: u32, price: Decimal, size: Decimal) -> GammaTrade {
+        GammaTrade {
+            asset: ClobTokenId("no".to_string()),
+            outcome: "No".to_string(),
+            outcome_index: 1,
+            ..trade(tx, day, TradeSide::Buy, price, size)
+        }
+    }
+
+    fn activity(
+        tx: &str,
+        day: u32,
+        activity_type: ActivityType,
+        size: Decimal,
+        usdc_size: Decimal,
+    ) -> GammaActivity {
+        GammaActivity {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            proxy_wallet: UserAddress("0xabc".to_string()),
+            activity_type,
+            condition_id: ConditionId("0xmarket".to_string()),
+            size,
+            usdc_size,
+            transaction_hash: TransactionHash(tx.to_string()),
+            title: "Will it rain?".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_history_at_average_cost_and_saves_idempotently() {
+        // Served newest first, like the Data API
+        let history = AccountHistory {
+            trades: vec![
+                trade("0x3", 3, TradeSide::Sell, dec!(0.70), dec!(200)),
+                trade("0x2", 2, TradeSide::Buy, dec!(0.60), dec!(100)),
+                trade("0x1", 1, TradeSide::Buy, dec!(0.40), dec!(100)),
+                trade("0x1", 1, TradeSide::Buy, dec!(0.40), dec!(50)),
+            ],
+            ..Default::default()
+        };
+        let import = TradeImport::replay(&history);
+
+        assert_eq!(import.executions[0].trade_id, "dataapi-0x1-0");
+        assert_eq!(import.executions[1].trade_id, "dataapi-0x1-1");
+        // 250 shares at (60 + 60) / 250 = 0.48; selling 200 at 0.70 realizes 44
+        let lot = &import.lots[0];
+        assert_eq!(lot.position_size_before, dec!(250));
+        assert_eq!(lot.average_price, dec!(0.48));
+        assert_eq!(lot.realized_pnl(), dec!(44));
+        assert_eq!(import.records[3].pnl_impact, Some(dec!(44)));
+        assert_eq!(import.realized_pnl(), dec!(44));
+        assert_eq!(import.open_positions(), 1);
+        assert_eq!(import.positions[0].size, dec!(50));
+        assert!(import.unmatched_sells.is_zero());
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PortfolioStorage::new(dir.path(), "0xabc");
+        let raw_storage = RawDataStorage::new(dir.path());
+        let first = import.save(&storage, &raw_storage).await.unwrap();
+        assert_eq!((first.records, first.lots, first.positions), (4, 1, 1));
+        let again = import.save(&storage, &raw_storage).await.unwrap();
+        assert_eq!((again.records, again.lots), (0, 0));
+
+        assert_eq!(raw_storage.load_all_trades().await.unwrap().len(), 4);
+        assert_eq!(storage.load_lot_history("yes").await.unwrap().len(), 1);
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 3, 3, 23, 0, 0).unwrap();
+        let records = storage.load_trade_history(start, end).await.unwrap();
+        assert_eq!(records.len(), 4);
+    }
+
+    #[test]
+    fn redeems_merges_and_taker_fees_close_positions() {
+        let taker_buy = trade("0x1", 1, TradeSide::Buy, dec!(0.40), dec!(100));
+        let mut history = AccountHistory {
+            trades: vec![taker_buy.clone(), no_trade("0x2", 1, dec!(0.50), dec!(30))],
+            activity: vec![
+                // 10 sets merged back at 0.40 / 0.90 and 0.50 / 0.90 per share
+                activity("0x3", 2, ActivityType::Merge, dec!(10), dec!(10)),
+                // Yes won: its 90 shares pay 90, No's 20 pay nothing
+                activity("0x4", 3, ActivityType::Redeem, dec!(110), dec!(90)),
+            ],
+            ..Default::default()
+        };
+        history.taker_fills.insert(FillKey::of(&taker_buy));
+        history.fee_rates.insert("yes".to_string(), 200);
+        let import = TradeImport::replay(&history);
+
+        // 2% of min(0.40, 0.60) on 100 shares
+        assert_eq!(import.fees, dec!(0.8));
+        assert_eq!(import.records[0].pnl_impact, Some(dec!(-0.8)));
+        assert!(import.executions[1].is_maker);
+        assert_eq!((import.merges, import.redeems), (1, 1));
+        assert_eq!(import.open_positions(), 0);
+
+        let yes = import.positions.iter().find(|p| p.token_id == "yes").unwrap();
+        let no = import.positions.iter().find(|p| p.token_id == "no").unwrap();
+        assert_eq!(yes.fees_paid, dec!(0.8));
+        // Redeemed 90 at 1 against 0.40: 54, less the fee and a merge loss
+        assert!(yes.realized_pnl > dec!(50));
+        // 20 worthless shares bought at 0.50
+        assert!(no.realized_pnl < dec!(-9));
+        assert!(import.unmatched_sells.is_zero());
+    }
+}
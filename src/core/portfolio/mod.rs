@@ -6,23 +6,53 @@
 //! - **Storage**: Persistent storage with caching
 //! - **CLI**: Command-line interface handlers
 //! - **Display**: Formatting and display utilities
+//! - **Close**: Sizing partial closes from the current position
+//! - **Flatten**: Cancelling resting orders and closing every position with slippage-capped orders
+//! - **Hedge**: Offsetting orders in the complementary token to reach a target exposure
+//! - **Adapters**: Conversions between API orders/balances and app-side types
+//! - **Units**: USDC and share amounts, rounding rules, per-outcome exposure in both
+//! - **Attribution**: Market-making P&L split into spread capture, inventory drift and fees
+//! - **Import**: Seeding trades, lot histories and positions from the Data API's trade history
+//! - **Exposure**: Open positions grouped by normalized market category or end-date bucket
+//! - **P&L History**: Equity and per-token P&L points recorded on refresh, for charting
+//! - **Order History**: Filled and cancelled orders kept locally, merged with the exchange's listing
+//! - **Webhook**: Periodic snapshots POSTed as signed JSON to an external endpoint
+//!
+//! GUI, TUI and CLI code should import from this module's re-exports rather
+//! than reaching into submodules, so there is one set of portfolio types.
 
+pub mod adapters;
 pub mod api;
+pub mod attribution;
 pub mod cli;
+pub mod close;
 pub mod controller;
 pub mod display;
+pub mod exposure;
+pub mod flatten;
+pub mod hedge;
+pub mod import;
+pub mod order_history;
+pub mod pnl_history;
 pub mod storage;
 pub mod types;
+pub mod units;
+pub mod webhook;
 
 // Re-export core types
 pub use types::{
-    ActiveOrder, MarketPositionSummary, OrderSide, OrderStatus,
-    PortfolioStats, Position, PositionSide, PositionStatus,
+    ActiveOrder, MarketPositionSummary, OrderSide, OrderStatus, OrderType,
+    PortfolioStats, Position, PositionSide, PositionStatus, TimeInForce,
     TradeExecution,
 };
+pub use units::OutcomeExposure;
 
 // Re-export API components
-pub use api::{PortfolioServiceHandle};
+pub use api::orders::{BalanceInfo, PolymarketOrder};
+pub use api::PortfolioServiceHandle;
+
+// Re-export controller
+pub use controller::{PortfolioManager, PositionReconciler};
 
 // Re-export storage
-pub use storage::PortfolioStorage;
\ No newline at end of file
+pub use storage::{AccountBalances, PortfolioSnapshot, PortfolioStorage};
\ No newline at end of file
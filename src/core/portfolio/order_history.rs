@@ -0,0 +1,217 @@
+//! Historical order queries
+//!
+//! The CLOB's orders endpoint only lists orders still on the book: an order
+//! drops out of it once it's filled or cancelled. Every order the app sees is
+//! therefore recorded in the account's order history (`orders/history.json`,
+//! see [`PortfolioStorage`]), and a query merges that history with a fresh,
+//! paginated listing from the exchange, one entry per order ID. Recorded
+//! orders that are still open locally but gone from the listing are looked up
+//! by ID to settle their final status, and the history is updated with
+//! everything the exchange returned.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use crate::core::portfolio::api::orders::{
+    fetch_all_orders, fetch_order, OrderFilter, PolymarketOrder,
+};
+use crate::core::portfolio::storage::PortfolioStorage;
+use crate::data_paths::DataPaths;
+
+/// Orders gone from the listing looked up per query at most
+const SETTLE_LIMIT: usize = 50;
+
+/// Orders matching a query, newest first
+#[derive(Debug, Clone, Default)]
+pub struct OrderHistory {
+    pub orders: Vec<PolymarketOrder>,
+    /// Orders the exchange listed
+    pub fetched: usize,
+    /// Orders looked up to settle their status
+    pub settled: usize,
+    /// Orders new to the local history
+    pub recorded: usize,
+}
+
+/// One page of an [`OrderHistory`]
+#[derive(Debug, Clone, Copy)]
+pub struct OrderPage<'a> {
+    pub orders: &'a [PolymarketOrder],
+    /// Pages the whole history fills
+    pub total: usize,
+}
+
+impl OrderHistory {
+    /// Orders of page `page` (from 1) of `per_page`
+    pub fn page(&self, page: usize, per_page: usize) -> OrderPage<'_> {
+        let per_page = per_page.max(1);
+        let total = self.orders.len().div_ceil(per_page);
+        let start = (page.max(1) - 1).saturating_mul(per_page);
+        let end = (start + per_page).min(self.orders.len());
+        OrderPage {
+            orders: self.orders.get(start..end).unwrap_or_default(),
+            total,
+        }
+    }
+}
+
+/// One order per ID, `fetched` replacing what `recorded` has for the same
+/// order, newest first
+pub fn merge_orders(
+    recorded: Vec<PolymarketOrder>,
+    fetched: Vec<PolymarketOrder>,
+) -> Vec<PolymarketOrder> {
+    let mut by_id: HashMap<String, PolymarketOrder> = HashMap::new();
+    for order in recorded.into_iter().chain(fetched) {
+        by_id.insert(order.id.clone(), order);
+    }
+    let mut orders: Vec<PolymarketOrder> = by_id.into_values().collect();
+    orders.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    orders
+}
+
+/// Orders of `user_address` matching `filter` from the local history and,
+/// unless `local_only`, the exchange
+pub async fn query_orders(
+    host: &str,
+    data_paths: &DataPaths,
+    user_address: &str,
+    filter: &OrderFilter,
+    local_only: bool,
+) -> Result<OrderHistory> {
+    let storage = PortfolioStorage::new(data_paths.root(), user_address);
+    let recorded = storage.load_order_history().await?;
+    let mut history = OrderHistory::default();
+    if local_only {
+        history.orders = merge_orders(recorded, Vec::new());
+        history.orders.retain(|order| filter.matches(order));
+        return Ok(history);
+    }
+
+    // List the whole market or token, so orders missing from it are known
+    let scope = OrderFilter {
+        market: filter.market.clone(),
+        asset_id: filter.asset_id.clone(),
+        ..Default::default()
+    };
+    let mut fetched = fetch_all_orders(host, data_paths, user_address, &scope).await?;
+    history.fetched = fetched.len();
+
+    let listed: HashSet<&str> = fetched.iter().map(|order| order.id.as_str()).collect();
+    let unsettled: Vec<String> = recorded
+        .iter()
+        .filter(|order| {
+            order.is_open() && !listed.contains(order.id.as_str()) && scope.matches(order)
+        })
+        .map(|order| order.id.clone())
+        .collect();
+    if unsettled.len() > SETTLE_LIMIT {
+        warn!(
+            "{} recorded orders left the book; settling {} now, the rest on later queries",
+            unsettled.len(),
+            SETTLE_LIMIT
+        );
+    }
+    for order_id in unsettled.iter().take(SETTLE_LIMIT) {
+        match fetch_order(host, data_paths, user_address, order_id).await {
+            Ok(Some(order)) => {
+                fetched.push(order);
+                history.settled += 1;
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to look up order {}: {:#}", order_id, e),
+        }
+    }
+
+    history.recorded = storage.record_order_history(&fetched).await?;
+    history.orders = merge_orders(recorded, fetched);
+    history.orders.retain(|order| filter.matches(order));
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn order(id: &str, market: &str, status: &str, created_at: u64) -> PolymarketOrder {
+        PolymarketOrder {
+            id: id.to_string(),
+            owner: String::new(),
+            market: market.to_string(),
+            asset_id: format!("{}-yes", market),
+            side: "BUY".to_string(),
+            price: dec!(0.5),
+            size_structured: dec!(10),
+            size_matched: "0".to_string(),
+            status: status.to_string(),
+            created_at,
+            maker_address: String::new(),
+            outcome: "Yes".to_string(),
+            expiration: "0".to_string(),
+            order_type: "GTC".to_string(),
+            associate_trades: Vec::new(),
+            fee_rate_bps: None,
+            nonce: None,
+            condition_id: None,
+            token_id: None,
+            question_id: None,
+        }
+    }
+
+    #[test]
+    fn recorded_and_fetched_orders_merge_filter_and_paginate() {
+        let day = |d: u32| Utc.with_ymd_and_hms(2026, 5, d, 12, 0, 0).unwrap();
+        let at = |d: u32| day(d).timestamp() as u64;
+        let recorded = vec![
+            order("a", "0xm1", "LIVE", at(1)),
+            order("b", "0xm1", "CANCELED", at(2)),
+            order("c", "0xm2", "MATCHED", at(3)),
+        ];
+        let fetched = vec![
+            order("a", "0xm1", "MATCHED", at(1)),
+            order("d", "0xm1", "LIVE", at(4)),
+        ];
+
+        let merged = merge_orders(recorded, fetched);
+        let ids: Vec<&str> = merged.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, ["d", "c", "b", "a"]);
+        assert_eq!(merged[3].status, "MATCHED");
+        assert!(merged[0].is_open() && !merged[3].is_open());
+
+        let filter = OrderFilter {
+            market: Some("0xm1".to_string()),
+            since: Some(day(2)),
+            statuses: vec!["live".to_string(), "canceled".to_string()],
+            ..Default::default()
+        };
+        let kept: Vec<&str> = merged
+            .iter()
+            .filter(|o| filter.matches(o))
+            .map(|o| o.id.as_str())
+            .collect();
+        assert_eq!(kept, ["d", "b"]);
+        let until = OrderFilter {
+            asset_id: Some("0xm2-yes".to_string()),
+            until: Some(day(3)),
+            ..Default::default()
+        };
+        assert!(!merged.iter().any(|o| until.matches(o)));
+
+        let history = OrderHistory {
+            orders: merged,
+            ..Default::default()
+        };
+        let first = history.page(1, 3);
+        assert_eq!(first.orders.len(), 3);
+        assert_eq!(first.total, 2);
+        assert_eq!(history.page(2, 3).orders[0].id, "a");
+        assert!(history.page(3, 3).orders.is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! Portfolio value and P&L over time
+//!
+//! Each portfolio refresh appends a point, at most one per
+//! [`MIN_POINT_SPACING_SECS`], to `stats/pnl_history.jsonl` in the account's
+//! directory: total equity, cash, realized and unrealized P&L, and the P&L of
+//! every token with a position so a strategy's contribution can be charted
+//! from its token. The GUI's portfolio value chart reads it.
+//!
+//! Both the portfolio service and the GUI record points, possibly from
+//! different processes, so the spacing check and the append happen under a
+//! lock file next to the history.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use super::types::Position;
+use crate::core::file_lock::{FileLock, LOCK_TIMEOUT};
+use crate::data_paths::DataPaths;
+
+/// Refreshes closer together than this don't add a point
+pub const MIN_POINT_SPACING_SECS: i64 = 300;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PnlPoint {
+    pub at: DateTime<Utc>,
+    /// Cash plus positions at the mark
+    pub equity: Decimal,
+    pub cash: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// Realized plus unrealized P&L by token
+    #[serde(default)]
+    pub tokens: BTreeMap<String, Decimal>,
+}
+
+impl PnlPoint {
+    pub fn new(
+        at: DateTime<Utc>,
+        equity: Decimal,
+        cash: Option<Decimal>,
+        positions: &[Position],
+    ) -> Self {
+        let mut tokens = BTreeMap::new();
+        for position in positions {
+            *tokens.entry(position.token_id.clone()).or_default() += position.total_pnl();
+        }
+        Self {
+            at,
+            equity,
+            cash,
+            realized_pnl: positions.iter().map(|p| p.realized_pnl).sum(),
+            unrealized_pnl: positions.iter().filter_map(|p| p.unrealized_pnl).sum(),
+            tokens,
+        }
+    }
+}
+
+/// Chart ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PnlRange {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl PnlRange {
+    pub const ALL: [PnlRange; 4] = [Self::Day, Self::Week, Self::Month, Self::All];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "24h",
+            Self::Week => "7d",
+            Self::Month => "30d",
+            Self::All => "All",
+        }
+    }
+
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Day => Some(now - Duration::days(1)),
+            Self::Week => Some(now - Duration::days(7)),
+            Self::Month => Some(now - Duration::days(30)),
+            Self::All => None,
+        }
+    }
+}
+
+/// Append-only P&L history of one account
+pub struct PnlHistory {
+    path: PathBuf,
+}
+
+impl PnlHistory {
+    /// History of the account `address` in the profile
+    pub fn new(data_paths: &DataPaths, address: &str) -> Self {
+        Self {
+            path: data_paths
+                .account(address)
+                .join("stats")
+                .join("pnl_history.jsonl"),
+        }
+    }
+
+    /// Append `point` unless the last one is less than [`MIN_POINT_SPACING_SECS`]
+    /// older; returns whether it was written
+    pub async fn record(&self, point: &PnlPoint) -> Result<bool> {
+        let lock = FileLock::acquire(&self.path.with_extension("jsonl.lock"), LOCK_TIMEOUT).await?;
+        let written = self.append_spaced(point);
+        drop(lock);
+        written
+    }
+
+    fn append_spaced(&self, point: &PnlPoint) -> Result<bool> {
+        if let Some(last) = self.last_point()? {
+            if point.at - last.at < Duration::seconds(MIN_POINT_SPACING_SECS) {
+                return Ok(false);
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(point)?)?;
+        Ok(true)
+    }
+
+    /// Points from `since` on, oldest first; unreadable lines are skipped
+    pub fn load(&self, since: Option<DateTime<Utc>>) -> Result<Vec<PnlPoint>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<PnlPoint>(line).ok())
+            .filter(|point| since.map_or(true, |since| point.at >= since))
+            .collect())
+    }
+
+    /// The newest point, read from the end of the file
+    fn last_point(&self) -> Result<Option<PnlPoint>> {
+        let Ok(mut file) = fs::File::open(&self.path) else {
+            return Ok(None);
+        };
+        let len = file.metadata()?.len();
+        file.seek(SeekFrom::Start(len.saturating_sub(64 * 1024)))?;
+        let mut tail = String::new();
+        file.read_to_string(&mut tail)?;
+        Ok(tail
+            .lines()
+            .rev()
+            .find_map(|line| serde_json::from_str(line).ok()))
+    }
+}
+
+/// How far equity fell below its running peak
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    /// Zero or negative
+    pub dollars: Decimal,
+    /// Of the peak; zero when the peak wasn't positive
+    pub percent: Decimal,
+}
+
+impl Drawdown {
+    /// The drawdown at each point
+    pub fn series(points: &[PnlPoint]) -> Vec<Self> {
+        let mut peak: Option<Decimal> = None;
+        points
+            .iter()
+            .map(|point| {
+                let top = peak.map_or(point.equity, |peak| peak.max(point.equity));
+                peak = Some(top);
+                let dollars = point.equity - top;
+                let percent = if top > Decimal::ZERO {
+                    dollars / top * Decimal::ONE_HUNDRED
+                } else {
+                    Decimal::ZERO
+                };
+                Self { dollars, percent }
+            })
+            .collect()
+    }
+
+    /// The deepest drawdown of the points' equity
+    pub fn max(points: &[PnlPoint]) -> Option<Self> {
+        Self::series(points).into_iter().reduce(|worst, drawdown| {
+            if drawdown.dollars < worst.dollars {
+                drawdown
+            } else {
+                worst
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn point(at: DateTime<Utc>, equity: Decimal) -> PnlPoint {
+        PnlPoint {
+            at,
+            equity,
+            cash: None,
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            tokens: BTreeMap::from([("123".to_string(), equity - dec!(100))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn points_are_spaced_and_drawdowns_measured_from_the_peak() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = PnlHistory::new(&DataPaths::new(dir.path()), "0xabc");
+        let start = Utc::now() - Duration::hours(1);
+
+        assert!(history.record(&point(start, dec!(100))).await.unwrap());
+        // Too close to the previous point
+        assert!(!history
+            .record(&point(start + Duration::minutes(1), dec!(101)))
+            .await
+            .unwrap());
+        for (minutes, equity) in [(10, dec!(120)), (20, dec!(90)), (30, dec!(110))] {
+            assert!(history
+                .record(&point(start + Duration::minutes(minutes), equity))
+                .await
+                .unwrap());
+        }
+
+        let points = history.load(None).unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[1].tokens["123"], dec!(20));
+        let recent = history.load(Some(start + Duration::minutes(15))).unwrap();
+        assert_eq!(recent.len(), 2);
+
+        let dollars: Vec<Decimal> = Drawdown::series(&points).iter().map(|d| d.dollars).collect();
+        assert_eq!(dollars, [dec!(0), dec!(0), dec!(-30), dec!(-10)]);
+        let max = Drawdown::max(&points).unwrap();
+        assert_eq!(max.dollars, dec!(-30));
+        assert_eq!(max.percent, dec!(-25));
+        assert!(
+            FileLock::try_acquire(&dir.path().join("trade/account/0xabc/stats/pnl_history.jsonl.lock"))
+                .unwrap()
+                .is_some()
+        );
+    }
+}
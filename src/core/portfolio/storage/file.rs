@@ -4,20 +4,25 @@
 //! - snapshots/YYYY-MM-DD-HH-MM-SS.json - Full portfolio snapshots
 //! - trades/YYYY-MM-DD.json - Daily trade history
 //! - positions/current.json - Current positions
+//! - positions/lots/<token_id>.json - Closing orders linked to their fills
 //! - orders/active.json - Active orders cache
+//! - orders/history.json - Every order seen, latest state per order ID
 //! - stats/daily/YYYY-MM-DD.json - Daily statistics
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::{info, warn};
 
+use crate::core::file_lock::{FileLock, LOCK_TIMEOUT};
 use crate::core::portfolio::api::orders::PolymarketOrder;
 use crate::core::portfolio::storage::types::*;
 use crate::core::portfolio::types::*;
+use crate::core::portfolio::units::open_exposures;
 
 /// Portfolio storage manager
 #[derive(Clone)]
@@ -69,6 +74,58 @@ pub struct PositionSummary {
     pub unrealized_pnl: Option<Decimal>,
 }
 
+/// Closing order in a position's lot history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotEntry {
+    /// Order that reduces the position
+    pub order_id: String,
+    pub market_id: String,
+    pub token_id: String,
+    /// Order side (sell for longs, buy for shorts)
+    pub side: OrderSide,
+    /// Limit price of the order
+    pub price: Decimal,
+    /// Shares the order closes
+    pub size: Decimal,
+    /// Position size when the order was placed
+    pub position_size_before: Decimal,
+    /// Position average price when the order was placed
+    pub average_price: Decimal,
+    pub created_at: DateTime<Utc>,
+    /// Fills of the order, linked as they show up in the trade history
+    #[serde(default)]
+    pub fills: Vec<LotFill>,
+}
+
+impl LotEntry {
+    pub fn filled_size(&self) -> Decimal {
+        self.fills.iter().map(|fill| fill.size).sum()
+    }
+
+    /// Realized P&L of the linked fills against the average price at placement
+    pub fn realized_pnl(&self) -> Decimal {
+        self.fills
+            .iter()
+            .map(|fill| {
+                let per_share = match self.side {
+                    OrderSide::Sell => fill.price - self.average_price,
+                    OrderSide::Buy => self.average_price - fill.price,
+                };
+                per_share * fill.size
+            })
+            .sum()
+    }
+}
+
+/// Fill linked to a lot entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotFill {
+    pub trade_id: String,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Daily statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyStats {
@@ -122,6 +179,7 @@ impl PortfolioStorage {
             self.account_dir.join("snapshots"),
             self.account_dir.join("trades"),
             self.account_dir.join("positions"),
+            self.account_dir.join("positions").join("lots"),
             self.account_dir.join("orders"),
             self.account_dir.join("stats").join("daily"),
             self.account_dir.join("stats").join("monthly"),
@@ -161,7 +219,6 @@ impl PortfolioStorage {
     }
 
     /// Load latest snapshot
-    #[allow(dead_code)]
     pub async fn load_latest_snapshot(&self) -> Result<Option<PortfolioSnapshot>> {
         let snapshots_dir = self.account_dir.join("snapshots");
 
@@ -200,7 +257,7 @@ impl PortfolioStorage {
     }
 
 
-    /// Save active orders to cache
+    /// Save active orders to cache and record them in the order history
     pub async fn save_active_orders(&self, orders: &[PolymarketOrder]) -> Result<()> {
         self.init_directories().await?;
         
@@ -212,9 +269,78 @@ impl PortfolioStorage {
             .context("Failed to save active orders")?;
             
         info!("Saved {} active orders to cache", orders.len());
+        // The active orders are saved either way; the history catches up on the next save
+        if let Err(e) = self.record_order_history(orders).await {
+            warn!("Failed to record order history: {:#}", e);
+        }
         Ok(())
     }
 
+    /// Load every order recorded in the order history, oldest first
+    pub async fn load_order_history(&self) -> Result<Vec<PolymarketOrder>> {
+        let filepath = self.account_dir.join("orders").join("history.json");
+        if !filepath.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&filepath).await?;
+        serde_json::from_str(&content).context("Failed to parse order history")
+    }
+
+    /// Merge orders into the order history, replacing the recorded state of
+    /// orders already in it. Returns the number of orders new to the history.
+    ///
+    /// The GUI and CLI both record history, so the merge runs under the
+    /// history's lock file.
+    pub async fn record_order_history(&self, orders: &[PolymarketOrder]) -> Result<usize> {
+        if orders.is_empty() {
+            return Ok(0);
+        }
+        self.init_directories().await?;
+
+        let filepath = self.account_dir.join("orders").join("history.json");
+        let lock = FileLock::acquire(&filepath.with_extension("json.lock"), LOCK_TIMEOUT).await?;
+        let recorded = self.merge_order_history(&filepath, orders).await;
+        drop(lock);
+        recorded
+    }
+
+    /// Merge and write back the history; the caller holds its lock
+    async fn merge_order_history(
+        &self,
+        filepath: &Path,
+        orders: &[PolymarketOrder],
+    ) -> Result<usize> {
+        let mut history: BTreeMap<String, PolymarketOrder> = self
+            .load_order_history()
+            .await?
+            .into_iter()
+            .map(|order| (order.id.clone(), order))
+            .collect();
+        let before = history.len();
+        for order in orders {
+            history.insert(order.id.clone(), order.clone());
+        }
+        let recorded = history.len() - before;
+
+        let mut history: Vec<PolymarketOrder> = history.into_values().collect();
+        history.sort_by_key(|order| order.created_at);
+        let json = serde_json::to_string_pretty(&history)?;
+        // Written aside and renamed over, so a crash mid-write keeps the old history;
+        // the temp name is this writer's own, should a stale lock be taken over
+        let tmp = filepath.with_extension(format!(
+            "json.{}-{}.tmp",
+            std::process::id(),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ));
+        fs::write(&tmp, json)
+            .await
+            .context("Failed to save order history")?;
+        fs::rename(&tmp, filepath)
+            .await
+            .context("Failed to replace order history")?;
+        Ok(recorded)
+    }
+
     /// Save current positions
     pub async fn save_positions(&self, positions: &[Position]) -> Result<()> {
         self.init_directories().await?;
@@ -235,7 +361,6 @@ impl PortfolioStorage {
     }
 
     /// Load current positions
-    #[allow(dead_code)]
     pub async fn load_positions(&self) -> Result<Vec<Position>> {
         let filepath = self.account_dir.join("positions").join("current.json");
 
@@ -290,6 +415,53 @@ impl PortfolioStorage {
         Ok(())
     }
 
+    /// Merge trades into the daily history files of their own (UTC) dates,
+    /// skipping trade IDs already recorded. Returns the number of new records.
+    pub async fn record_trade_history(&self, trades: &[TradeRecord]) -> Result<usize> {
+        self.init_directories().await?;
+
+        let mut by_date: BTreeMap<String, Vec<&TradeRecord>> = BTreeMap::new();
+        for trade in trades {
+            by_date
+                .entry(trade.timestamp.format("%Y-%m-%d").to_string())
+                .or_default()
+                .push(trade);
+        }
+
+        let mut recorded = 0;
+        for (date, new_trades) in by_date {
+            let filepath = self
+                .account_dir
+                .join("trades")
+                .join(format!("{}.json", date));
+            let mut day: Vec<TradeRecord> = if filepath.exists() {
+                let content = fs::read_to_string(&filepath).await?;
+                serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse trade history for {}", date))?
+            } else {
+                Vec::new()
+            };
+
+            let before = day.len();
+            for trade in new_trades {
+                if !day.iter().any(|t| t.trade_id == trade.trade_id) {
+                    day.push(trade.clone());
+                }
+            }
+            if day.len() == before {
+                continue;
+            }
+            recorded += day.len() - before;
+            day.sort_by_key(|t| t.timestamp);
+
+            let json = serde_json::to_string_pretty(&day)?;
+            fs::write(&filepath, json).await?;
+        }
+
+        info!("Recorded {} trades in the trade history", recorded);
+        Ok(recorded)
+    }
+
     /// Load trade history for date range
     #[allow(dead_code)]
     pub async fn load_trade_history(
@@ -338,6 +510,106 @@ impl PortfolioStorage {
         Ok(all_trades)
     }
 
+    /// Every fill in the trade history, oldest first
+    pub async fn load_all_trade_history(&self) -> Result<Vec<TradeRecord>> {
+        let trades_dir = self.account_dir.join("trades");
+        let mut all_trades = Vec::new();
+
+        if !trades_dir.exists() {
+            return Ok(all_trades);
+        }
+
+        let mut entries = fs::read_dir(&trades_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).await?;
+            let trades: Vec<TradeRecord> = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse trade history {}", path.display()))?;
+            all_trades.extend(trades);
+        }
+
+        all_trades.sort_by_key(|t| t.timestamp);
+        Ok(all_trades)
+    }
+
+    fn lot_history_path(&self, token_id: &str) -> PathBuf {
+        self.account_dir
+            .join("positions")
+            .join("lots")
+            .join(format!("{}.json", token_id))
+    }
+
+    /// Load the lot history of a token, oldest first
+    pub async fn load_lot_history(&self, token_id: &str) -> Result<Vec<LotEntry>> {
+        let filepath = self.lot_history_path(token_id);
+        if !filepath.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&filepath).await?;
+        serde_json::from_str(&content).context("Failed to parse lot history")
+    }
+
+    async fn save_lot_history(&self, token_id: &str, entries: &[LotEntry]) -> Result<()> {
+        self.init_directories().await?;
+        let json = serde_json::to_string_pretty(entries)?;
+        fs::write(self.lot_history_path(token_id), json)
+            .await
+            .context("Failed to save lot history")
+    }
+
+    /// Append a closing order to its token's lot history
+    pub async fn record_lot_entry(&self, entry: &LotEntry) -> Result<()> {
+        let mut entries = self.load_lot_history(&entry.token_id).await?;
+        entries.push(entry.clone());
+        self.save_lot_history(&entry.token_id, &entries).await?;
+        info!("Recorded lot entry for order {}", entry.order_id);
+        Ok(())
+    }
+
+    /// Append closing orders to a token's lot history, skipping orders it
+    /// already has. Returns the number of new entries.
+    pub async fn record_lot_entries(&self, token_id: &str, entries: &[LotEntry]) -> Result<usize> {
+        let mut history = self.load_lot_history(token_id).await?;
+        let before = history.len();
+        for entry in entries {
+            if !history.iter().any(|e| e.order_id == entry.order_id) {
+                history.push(entry.clone());
+            }
+        }
+
+        let recorded = history.len() - before;
+        if recorded > 0 {
+            history.sort_by_key(|e| e.created_at);
+            self.save_lot_history(token_id, &history).await?;
+        }
+        Ok(recorded)
+    }
+
+    /// Attach what the exchange reports matched on a lot's order since the last
+    /// sync as one fill at the order's limit price (sells fill at or above it).
+    /// Returns the newly linked size.
+    pub async fn link_order_fills(&self, token_id: &str, order: &PolymarketOrder) -> Result<Decimal> {
+        let mut entries = self.load_lot_history(token_id).await?;
+        let Some(entry) = entries.iter_mut().find(|e| e.order_id == order.id) else {
+            return Ok(Decimal::ZERO);
+        };
+        let new_size = order.matched_size().min(entry.size) - entry.filled_size();
+        if new_size <= Decimal::ZERO {
+            return Ok(Decimal::ZERO);
+        }
+        entry.fills.push(LotFill {
+            trade_id: format!("{}#{}", order.id, entry.fills.len() + 1),
+            price: order.price,
+            size: new_size,
+            timestamp: Utc::now(),
+        });
+        self.save_lot_history(token_id, &entries).await?;
+        Ok(new_size)
+    }
+
     /// Save daily statistics
     #[allow(dead_code)]
     pub async fn save_daily_stats(&self, stats: &DailyStats) -> Result<()> {
@@ -379,6 +651,7 @@ impl PortfolioStorage {
         let snapshot = PortfolioSnapshot {
             timestamp: Utc::now(),
             address: self.address.clone(),
+            exposure: open_exposures(&positions),
             positions,
             active_orders: orders,
             stats,
@@ -445,4 +718,46 @@ impl PortfolioStorage {
 
         Ok(removed)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn links_matched_size_of_the_exchange_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PortfolioStorage::new(dir.path(), "0xabc");
+        storage
+            .record_lot_entry(&LotEntry {
+                order_id: "0xorder".to_string(),
+                market_id: "m".to_string(),
+                token_id: "t".to_string(),
+                side: OrderSide::Sell,
+                price: dec!(0.60),
+                size: dec!(50),
+                position_size_before: dec!(100),
+                average_price: dec!(0.50),
+                created_at: Utc::now(),
+                fills: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        let mut order: PolymarketOrder = serde_json::from_value(serde_json::json!({
+            "id": "0xorder", "owner": "", "market": "m", "asset_id": "t", "side": "SELL",
+            "price": "0.60", "original_size": "50", "size_matched": "20", "status": "LIVE",
+            "created_at": 0, "maker_address": "", "outcome": "Yes", "expiration": "0",
+            "order_type": "GTC"
+        }))
+        .unwrap();
+        assert_eq!(storage.link_order_fills("t", &order).await.unwrap(), dec!(20));
+        assert_eq!(storage.link_order_fills("t", &order).await.unwrap(), Decimal::ZERO);
+
+        order.size_matched = "50".to_string();
+        assert_eq!(storage.link_order_fills("t", &order).await.unwrap(), dec!(30));
+        let history = storage.load_lot_history("t").await.unwrap();
+        assert_eq!(history[0].fills.len(), 2);
+        assert_eq!(history[0].realized_pnl(), dec!(5.00));
+    }
+}
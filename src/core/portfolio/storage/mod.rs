@@ -6,7 +6,7 @@
 pub mod file;
 pub mod types;
 
-pub use file::{PortfolioStorage, TradeRecord, PositionSummary};
+pub use file::{LotEntry, LotFill, PortfolioStorage, PositionSummary, TradeRecord};
 pub use types::*;
 
 // Re-export commonly used storage items
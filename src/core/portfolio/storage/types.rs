@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 use crate::core::portfolio::types::*;
+use crate::core::portfolio::units::OutcomeExposure;
 use crate::core::portfolio::api::orders::PolymarketOrder;
 
 /// Portfolio snapshot containing full state at a point in time
@@ -19,6 +20,9 @@ pub struct PortfolioSnapshot {
     pub address: String,
     /// Current positions
     pub positions: Vec<Position>,
+    /// Open positions in USDC and shares, as displayed when the snapshot was taken
+    #[serde(default)]
+    pub exposure: Vec<OutcomeExposure>,
     /// Active orders
     pub active_orders: Vec<PolymarketOrder>,
     /// Portfolio statistics
@@ -135,7 +139,6 @@ impl RawDataStorage {
     }
 
     /// Store individual trade with unique ID
-    #[allow(dead_code)]
     pub async fn store_trade(&self, trade: &TradeExecution) -> Result<String> {
         let trade_id = &trade.trade_id;
         let file_path = self.base_path
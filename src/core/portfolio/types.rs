@@ -58,6 +58,67 @@ impl Position {
 
 }
 
+/// Positions for tests: an open long bought at 0.5 in market "m", without a
+/// current price, P&L or fees, adjusted through the `with_*` methods
+#[cfg(test)]
+impl Position {
+    pub fn test(token_id: &str, size: Decimal) -> Self {
+        Self {
+            market_id: "m".to_string(),
+            token_id: token_id.to_string(),
+            outcome: "Yes".to_string(),
+            side: PositionSide::Long,
+            size,
+            average_price: Decimal::new(5, 1),
+            current_price: None,
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: None,
+            status: PositionStatus::Open,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            closed_at: None,
+            fees_paid: Decimal::ZERO,
+            market_question: None,
+        }
+    }
+
+    pub fn with_market(mut self, market_id: &str) -> Self {
+        self.market_id = market_id.to_string();
+        self
+    }
+
+    pub fn with_side(mut self, side: PositionSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn with_average_price(mut self, price: Decimal) -> Self {
+        self.average_price = price;
+        self
+    }
+
+    pub fn with_status(mut self, status: PositionStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_current_price(mut self, price: Decimal) -> Self {
+        self.current_price = Some(price);
+        self
+    }
+
+    pub fn with_pnl(mut self, realized: Decimal, unrealized: Option<Decimal>) -> Self {
+        self.realized_pnl = realized;
+        self.unrealized_pnl = unrealized;
+        self
+    }
+
+    pub fn with_fees(mut self, fees: Decimal) -> Self {
+        self.fees_paid = fees;
+        self
+    }
+}
+
 /// Active order information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveOrder {
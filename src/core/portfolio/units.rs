@@ -0,0 +1,164 @@
+//! USDC and share amounts with one set of rounding rules
+//!
+//! Polymarket shows holdings in shares and their value and P&L in USDC, so a
+//! P&L figure alone can't be matched to a position without knowing the mark.
+//! Portfolio displays and snapshots report both through [`OutcomeExposure`],
+//! rounded the same way everywhere:
+//! - USDC to cents, half away from zero
+//! - Shares to hundredths, toward zero (what can actually be sold)
+//! - Prices to tenths of a cent, half away from zero
+//!
+//! The `format_*` helpers print the rounded amounts with the configured
+//! separators (see [`crate::number_format`]).
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use super::types::{Position, PositionSide, PositionStatus};
+use crate::number_format;
+
+pub const USDC_DP: u32 = 2;
+pub const SHARE_DP: u32 = 2;
+pub const PRICE_DP: u32 = 3;
+
+pub fn round_usdc(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(USDC_DP, RoundingStrategy::MidpointAwayFromZero)
+}
+
+pub fn round_shares(shares: Decimal) -> Decimal {
+    shares.round_dp_with_strategy(SHARE_DP, RoundingStrategy::ToZero)
+}
+
+pub fn round_price(price: Decimal) -> Decimal {
+    price.round_dp_with_strategy(PRICE_DP, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// `$1.23` / `-$1.23`
+pub fn format_usdc(amount: Decimal) -> String {
+    number_format::usd(round_usdc(amount))
+}
+
+/// `+$1.23` / `-$1.23`, for P&L
+pub fn format_signed_usdc(amount: Decimal) -> String {
+    number_format::signed_usd(round_usdc(amount))
+}
+
+/// `12.34 sh`
+pub fn format_shares(shares: Decimal) -> String {
+    format!("{} sh", number_format::size(round_shares(shares)))
+}
+
+/// `+12.34 sh` / `-12.34 sh`, for P&L in shares
+pub fn format_signed_shares(shares: Decimal) -> String {
+    format!("{} sh", number_format::signed_size(round_shares(shares)))
+}
+
+/// Exposure and P&L of one outcome token in USDC and in shares
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeExposure {
+    pub market_id: String,
+    pub token_id: String,
+    pub outcome: String,
+    /// Shares held, negative for shorts
+    pub shares: Decimal,
+    /// Price the position is valued at: the current price, or the average price without one
+    pub mark: Decimal,
+    /// `shares × mark`
+    pub value_usdc: Decimal,
+    /// `shares × average price`
+    pub cost_usdc: Decimal,
+    /// Realized plus unrealized P&L
+    pub pnl_usdc: Decimal,
+    /// The P&L as a number of this outcome's shares at the mark; `None` at a zero mark
+    pub pnl_shares: Option<Decimal>,
+}
+
+impl OutcomeExposure {
+    pub fn from_position(position: &Position) -> Self {
+        let shares = match position.side {
+            PositionSide::Long => position.size,
+            PositionSide::Short => -position.size,
+        };
+        let mark = position.current_price.unwrap_or(position.average_price);
+        let pnl = position.total_pnl();
+        Self {
+            market_id: position.market_id.clone(),
+            token_id: position.token_id.clone(),
+            outcome: position.outcome.clone(),
+            shares: round_shares(shares),
+            mark: round_price(mark),
+            value_usdc: round_usdc(shares * mark),
+            cost_usdc: round_usdc(shares * position.average_price),
+            pnl_usdc: round_usdc(pnl),
+            pnl_shares: (!mark.is_zero()).then(|| round_shares(pnl / mark)),
+        }
+    }
+
+    /// `+$1.23 (+2.46 sh)`
+    pub fn format_pnl(&self) -> String {
+        match self.pnl_shares {
+            Some(shares) => format!(
+                "{} ({})",
+                format_signed_usdc(self.pnl_usdc),
+                format_signed_shares(shares)
+            ),
+            None => format_signed_usdc(self.pnl_usdc),
+        }
+    }
+}
+
+/// Exposure of every open position
+pub fn open_exposures(positions: &[Position]) -> Vec<OutcomeExposure> {
+    positions
+        .iter()
+        .filter(|p| p.status == PositionStatus::Open)
+        .map(OutcomeExposure::from_position)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn exposure_reports_usdc_and_shares_with_shared_rounding() {
+        let position = Position {
+            market_id: "m".to_string(),
+            token_id: "t".to_string(),
+            outcome: "Yes".to_string(),
+            side: PositionSide::Long,
+            size: dec!(100.019),
+            average_price: dec!(0.40),
+            current_price: Some(dec!(0.5)),
+            realized_pnl: dec!(1.005),
+            unrealized_pnl: Some(dec!(10)),
+            status: PositionStatus::Open,
+            opened_at: Utc::now(),
+            updated_at: Utc::now(),
+            closed_at: None,
+            fees_paid: Decimal::ZERO,
+            market_question: None,
+        };
+
+        let exposure = OutcomeExposure::from_position(&position);
+        // Shares truncate, USDC rounds half away from zero
+        assert_eq!(exposure.shares, dec!(100.01));
+        assert_eq!(exposure.value_usdc, dec!(50.01));
+        assert_eq!(exposure.cost_usdc, dec!(40.01));
+        assert_eq!(exposure.pnl_usdc, dec!(11.01));
+        assert_eq!(exposure.pnl_shares, Some(dec!(22.01)));
+        assert_eq!(exposure.format_pnl(), "+$11.01 (+22.01 sh)");
+
+        let short = Position {
+            side: PositionSide::Short,
+            current_price: Some(Decimal::ZERO),
+            ..position
+        };
+        let exposure = OutcomeExposure::from_position(&short);
+        assert_eq!(exposure.shares, dec!(-100.01));
+        assert_eq!(exposure.pnl_shares, None);
+        assert_eq!(format_usdc(dec!(-0.005)), "-$0.01");
+    }
+}
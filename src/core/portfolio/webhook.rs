@@ -0,0 +1,347 @@
+//! Portfolio snapshot webhook
+//!
+//! External dashboards and spreadsheets can be kept in sync without polling
+//! the machine: `<data_dir>/portfolio_webhook.yaml` names an endpoint that
+//! each periodic portfolio snapshot (the P&L history point recorded on
+//! refresh, at most one per [`MIN_POINT_SPACING_SECS`]) is POSTed to as JSON:
+//!
+//! ```yaml
+//! enabled: true              # nothing is sent until this is set
+//! url: https://example.com/hooks/polybot
+//! secret: change-me          # signs each request; omit to send unsigned
+//! include_positions: true    # false sends balances and P&L only
+//! timeout_secs: 10
+//! ```
+//!
+//! With a secret, each request carries `X-Polybot-Timestamp` (Unix seconds)
+//! and `X-Polybot-Signature: sha256=<hex>`, the HMAC-SHA256 of
+//! `<timestamp>.<body>` keyed with the secret. Receivers recompute it over
+//! the raw body and reject stale timestamps to stop replays. A file holding a
+//! secret is refused while other users can read it (`chmod 600` it).
+//!
+//! [`MIN_POINT_SPACING_SECS`]: super::pnl_history::MIN_POINT_SPACING_SECS
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::core::portfolio::storage::AccountBalances;
+use crate::core::portfolio::types::{Position, PositionStatus};
+use crate::data_paths::DataPaths;
+
+/// Name of the portfolio webhook configuration file inside the data directory
+pub const PORTFOLIO_WEBHOOK_CONFIG_FILE: &str = "portfolio_webhook.yaml";
+
+/// Event name of snapshot payloads
+pub const SNAPSHOT_EVENT: &str = "portfolio.snapshot";
+
+pub const TIMESTAMP_HEADER: &str = "X-Polybot-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Polybot-Signature";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortfolioWebhookConfig {
+    /// Off unless the file turns it on
+    pub enabled: bool,
+    /// Endpoint snapshots are POSTed to
+    pub url: Option<String>,
+    /// HMAC-SHA256 key signing each request
+    pub secret: Option<String>,
+    /// Send open positions along with balances and P&L
+    pub include_positions: bool,
+    pub timeout_secs: u64,
+}
+
+impl Default for PortfolioWebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            secret: None,
+            include_positions: true,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl PortfolioWebhookConfig {
+    /// Load `portfolio_webhook.yaml` from the data directory, or defaults
+    /// when it doesn't exist; a secret other users could read is refused
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(PORTFOLIO_WEBHOOK_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config: Self = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid portfolio webhook config {}", path.display()))?;
+        #[cfg(unix)]
+        if config.secret.is_some() {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)?.permissions().mode();
+            if mode & 0o077 != 0 {
+                bail!(
+                    "{} holds a webhook secret but is accessible by other users (mode {:o}); \
+                     run chmod 600 on it",
+                    path.display(),
+                    mode & 0o777
+                );
+            }
+        }
+        Ok(config)
+    }
+
+    /// Whether snapshots are sent
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.url.is_some()
+    }
+}
+
+/// Realized and unrealized P&L over every position
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotPnl {
+    pub realized: Decimal,
+    pub unrealized: Decimal,
+    pub total: Decimal,
+    pub fees_paid: Decimal,
+}
+
+/// Body of a snapshot request
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotPayload {
+    pub event: &'static str,
+    pub address: String,
+    pub at: DateTime<Utc>,
+    pub balances: AccountBalances,
+    pub pnl: SnapshotPnl,
+    pub open_positions: usize,
+    /// Open positions, left out when `include_positions` is off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub positions: Option<Vec<Position>>,
+}
+
+impl SnapshotPayload {
+    pub fn new(
+        address: &str,
+        at: DateTime<Utc>,
+        balances: &AccountBalances,
+        positions: &[Position],
+        include_positions: bool,
+    ) -> Self {
+        let realized: Decimal = positions.iter().map(|p| p.realized_pnl).sum();
+        let unrealized: Decimal = positions.iter().filter_map(|p| p.unrealized_pnl).sum();
+        let open: Vec<Position> = positions
+            .iter()
+            .filter(|p| p.status == PositionStatus::Open && !p.size.is_zero())
+            .cloned()
+            .collect();
+        Self {
+            event: SNAPSHOT_EVENT,
+            address: address.to_string(),
+            at,
+            balances: balances.clone(),
+            pnl: SnapshotPnl {
+                realized,
+                unrealized,
+                total: realized + unrealized,
+                fees_paid: positions.iter().map(|p| p.fees_paid).sum(),
+            },
+            open_positions: open.len(),
+            positions: include_positions.then_some(open),
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `<timestamp>.<body>` keyed with `secret`
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends snapshots to the configured endpoint
+#[derive(Clone)]
+pub struct PortfolioWebhook {
+    config: PortfolioWebhookConfig,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl PortfolioWebhook {
+    /// Webhook of `config`, or `None` when it isn't active
+    pub fn new(config: PortfolioWebhookConfig) -> Result<Option<Self>> {
+        let Some(url) = config.url.clone().filter(|_| config.is_active()) else {
+            return Ok(None);
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .context("Failed to build webhook client")?;
+        Ok(Some(Self {
+            config,
+            url,
+            client,
+        }))
+    }
+
+    /// Webhook configured in the data directory, if any
+    pub fn load(data_paths: &DataPaths) -> Result<Option<Self>> {
+        Self::new(PortfolioWebhookConfig::load(data_paths)?)
+    }
+
+    /// Webhook for a long-running service: a config that can't be used is
+    /// logged and leaves the webhook off
+    pub fn load_for_service(data_paths: &DataPaths) -> Option<Self> {
+        Self::load(data_paths).unwrap_or_else(|e| {
+            warn!("Portfolio webhook disabled: {:#}", e);
+            None
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Payload of the snapshot, following `include_positions`
+    pub fn payload(
+        &self,
+        address: &str,
+        balances: &AccountBalances,
+        positions: &[Position],
+    ) -> SnapshotPayload {
+        SnapshotPayload::new(
+            address,
+            Utc::now(),
+            balances,
+            positions,
+            self.config.include_positions,
+        )
+    }
+
+    /// Send the snapshot in the background, so a slow endpoint doesn't hold
+    /// up the refresh that recorded it; failures are logged
+    pub fn post_snapshot(
+        &self,
+        address: &str,
+        balances: &AccountBalances,
+        positions: &[Position],
+    ) {
+        let payload = self.payload(address, balances, positions);
+        let webhook = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = webhook.send(&payload).await {
+                warn!("Failed to send portfolio snapshot: {:#}", e);
+            }
+        });
+    }
+
+    /// POST `payload`, signed when a secret is configured
+    pub async fn send(&self, payload: &SnapshotPayload) -> Result<()> {
+        let body = serde_json::to_string(payload)?;
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = &self.config.secret {
+            let timestamp = Utc::now().timestamp();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("Portfolio webhook {} unreachable", self.url))?;
+        if !response.status().is_success() {
+            bail!(
+                "Portfolio webhook {} answered {}",
+                self.url,
+                response.status()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    /// Bought at 0.4 and now at 0.5 with 0.25 in fees; closed when `size` is zero
+    fn position(token_id: &str, size: Decimal, realized: Decimal) -> Position {
+        let status = if size.is_zero() {
+            PositionStatus::Closed
+        } else {
+            PositionStatus::Open
+        };
+        Position::test(token_id, size)
+            .with_market(&format!("0x{}", token_id))
+            .with_status(status)
+            .with_average_price(dec!(0.4))
+            .with_current_price(dec!(0.5))
+            .with_pnl(realized, Some(size * dec!(0.1)))
+            .with_fees(dec!(0.25))
+    }
+
+    #[test]
+    fn snapshot_payload_sums_pnl_and_is_signed() {
+        let config: PortfolioWebhookConfig =
+            serde_yaml::from_str("url: https://example.com/hook\nsecret: topsecret\n").unwrap();
+        // A URL alone doesn't send anything
+        assert!(!config.is_active() && config.include_positions);
+        let config = PortfolioWebhookConfig {
+            enabled: true,
+            ..config
+        };
+        assert!(config.is_active());
+        assert!(!PortfolioWebhookConfig::default().is_active());
+        assert!(PortfolioWebhook::new(PortfolioWebhookConfig::default())
+            .unwrap()
+            .is_none());
+
+        let balances = AccountBalances {
+            total_value: dec!(105),
+            available_cash: dec!(100),
+            locked_in_orders: Decimal::ZERO,
+            position_value: dec!(5),
+            last_updated: Utc::now(),
+        };
+        let positions = [
+            position("a", dec!(10), dec!(2)),
+            position("b", Decimal::ZERO, dec!(-1)),
+        ];
+        let payload = SnapshotPayload::new("0xabc", Utc::now(), &balances, &positions, true);
+        assert_eq!(payload.pnl.realized, dec!(1));
+        assert_eq!(payload.pnl.unrealized, dec!(1));
+        assert_eq!(payload.pnl.total, dec!(2));
+        assert_eq!(payload.pnl.fees_paid, dec!(0.5));
+        assert_eq!(payload.open_positions, 1);
+        assert_eq!(payload.positions.as_ref().unwrap()[0].token_id, "a");
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], SNAPSHOT_EVENT);
+
+        let bare = SnapshotPayload::new("0xabc", Utc::now(), &balances, &positions, false);
+        assert!(serde_json::to_value(&bare)
+            .unwrap()
+            .get("positions")
+            .is_none());
+
+        assert_eq!(
+            sign(
+                "topsecret",
+                1_767_225_600,
+                r#"{"event":"portfolio.snapshot"}"#
+            ),
+            "sha256=b2a77e7d7a95f9844eb34d763a82d26b5fbcf6546e496fbef62baa24e60d0552"
+        );
+    }
+}
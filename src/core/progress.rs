@@ -0,0 +1,401 @@
+//! Progress reporting for long-running operations
+//!
+//! Fetch, enrich, index and the Gamma session import report through a
+//! [`ProgressReporter`] instead of drawing their own output. The reporter
+//! decides where progress goes: indicatif bars on the terminal, a channel of
+//! [`ProgressSnapshot`]s that a TUI or GUI view draws, or nowhere. Each
+//! operation splits its work into [`Progress`] tasks, which all get the same
+//! ETA estimate and share the reporter's [`CancellationToken`]; operations
+//! check it between units of work (or race it against network calls), save
+//! what they have and stop with [`Cancelled`].
+//!
+//! CLI commands hand the reporter a [`ctrl_c_token`], so the first Ctrl-C asks
+//! the operation to wrap up and a second one exits immediately.
+
+use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Minimum time between two snapshots of a task sent over a channel
+const SNAPSHOT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An operation stopped because its reporter was cancelled
+#[derive(Debug, Error)]
+#[error("{0} cancelled")]
+pub struct Cancelled(pub String);
+
+/// A token cancelled by the first Ctrl-C; a second Ctrl-C exits right away
+///
+/// Must be called from within the tokio runtime.
+pub fn ctrl_c_token() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        eprintln!("\n⏹ Stopping and saving progress... (Ctrl-C again to abort)");
+        cancel.cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(130);
+        }
+    });
+    token
+}
+
+/// State of one task, as sent to a TUI or GUI view
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSnapshot {
+    pub task: String,
+    pub position: u64,
+    pub total: Option<u64>,
+    pub message: String,
+    pub elapsed: Duration,
+    pub eta: Option<Duration>,
+    pub finished: bool,
+}
+
+impl ProgressSnapshot {
+    /// Completed share between 0 and 1, when the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.position as f64 / total as f64).min(1.0))
+    }
+
+    /// One-line summary for gauges: position, percentage, ETA and message
+    pub fn label(&self) -> String {
+        let mut label = match (self.total, self.fraction()) {
+            (Some(total), Some(fraction)) => {
+                format!("{}/{} ({:.0}%)", self.position, total, fraction * 100.0)
+            }
+            _ => self.position.to_string(),
+        };
+        if let Some(eta) = self.eta.filter(|_| !self.finished) {
+            label.push_str(&format!(" · ETA {}", HumanDuration(eta)));
+        }
+        if !self.message.is_empty() {
+            label.push_str(&format!(" · {}", self.message));
+        }
+        label
+    }
+}
+
+#[derive(Clone)]
+enum Target {
+    Terminal(MultiProgress),
+    Channel(mpsc::UnboundedSender<ProgressSnapshot>),
+    Hidden,
+}
+
+/// Where an operation's progress goes, shared by all of its tasks
+#[derive(Clone)]
+pub struct ProgressReporter {
+    target: Target,
+    cancel: CancellationToken,
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = match self.target {
+            Target::Terminal(_) => "terminal",
+            Target::Channel(_) => "channel",
+            Target::Hidden => "hidden",
+        };
+        f.debug_struct("ProgressReporter")
+            .field("target", &target)
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        Self::terminal()
+    }
+}
+
+impl ProgressReporter {
+    /// Progress bars on stderr
+    pub fn terminal() -> Self {
+        Self::with_target(Target::Terminal(MultiProgress::new()))
+    }
+
+    /// Snapshots for a TUI or GUI view to draw
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ProgressSnapshot>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self::with_target(Target::Channel(sender)), receiver)
+    }
+
+    /// No output; tasks still track position and cancellation
+    pub fn hidden() -> Self {
+        Self::with_target(Target::Hidden)
+    }
+
+    /// Terminal bars when `show` is set, nothing otherwise
+    pub fn terminal_if(show: bool) -> Self {
+        if show {
+            Self::terminal()
+        } else {
+            Self::hidden()
+        }
+    }
+
+    fn with_target(target: Target) -> Self {
+        Self {
+            target,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Cancel this reporter's tasks when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Start a task of `total` units, or of unknown length
+    pub fn task(&self, name: impl Into<String>, total: Option<u64>) -> Progress {
+        let name = name.into();
+        let bar = match &self.target {
+            Target::Terminal(multi) => {
+                let bar = multi.add(match total {
+                    Some(total) => ProgressBar::new(total),
+                    None => ProgressBar::new_spinner(),
+                });
+                bar.set_style(bar_style(total.is_some()));
+                bar.set_prefix(name.clone());
+                bar.enable_steady_tick(Duration::from_millis(120));
+                Some(bar)
+            }
+            _ => None,
+        };
+        let sender = match &self.target {
+            Target::Channel(sender) => Some(sender.clone()),
+            _ => None,
+        };
+        Progress {
+            inner: Arc::new(Task {
+                name,
+                bar,
+                sender,
+                cancel: self.cancel.clone(),
+                started: Instant::now(),
+                state: Mutex::new(TaskState {
+                    position: 0,
+                    total,
+                    message: String::new(),
+                    finished: false,
+                    last_sent: None,
+                }),
+            }),
+        }
+    }
+
+    /// Ask every task of this reporter to stop
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+}
+
+fn bar_style(bounded: bool) -> ProgressStyle {
+    let template = if bounded {
+        "{spinner:.green} {prefix:.bold} [{elapsed_precise}] [{bar:40.cyan/blue}] {human_pos}/{human_len} ({percent}%, ETA {eta}) {msg}"
+    } else {
+        "{spinner:.green} {prefix:.bold} [{elapsed_precise}] {human_pos} {msg}"
+    };
+    ProgressStyle::with_template(template)
+        .expect("progress template is valid")
+        .progress_chars("#>-")
+}
+
+/// Remaining time, assuming the rate so far holds
+fn estimate_eta(elapsed: Duration, position: u64, total: Option<u64>) -> Option<Duration> {
+    let total = total?;
+    if position == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(position);
+    Some(elapsed.mul_f64(remaining as f64 / position as f64))
+}
+
+struct TaskState {
+    position: u64,
+    total: Option<u64>,
+    message: String,
+    finished: bool,
+    last_sent: Option<Instant>,
+}
+
+struct Task {
+    name: String,
+    bar: Option<ProgressBar>,
+    sender: Option<mpsc::UnboundedSender<ProgressSnapshot>>,
+    cancel: CancellationToken,
+    started: Instant,
+    state: Mutex<TaskState>,
+}
+
+/// One task of an operation; clones update the same task
+#[derive(Clone)]
+pub struct Progress {
+    inner: Arc<Task>,
+}
+
+impl Progress {
+    pub fn inc(&self, delta: u64) {
+        self.update(|state| state.position += delta);
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.update(|state| state.position = position);
+    }
+
+    /// Set the length once it becomes known
+    pub fn set_total(&self, total: u64) {
+        if let Some(bar) = &self.inner.bar {
+            bar.set_style(bar_style(true));
+        }
+        self.update(|state| state.total = Some(total));
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|state| state.message = message);
+    }
+
+    pub fn finish(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|state| {
+            state.finished = true;
+            state.message = message;
+        });
+    }
+
+    pub fn position(&self) -> u64 {
+        self.inner.state.lock().unwrap().position
+    }
+
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        self.snapshot_of(&self.inner.state.lock().unwrap())
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancel.is_cancelled()
+    }
+
+    /// Completes once the reporter is cancelled, for racing against I/O
+    pub async fn cancelled(&self) {
+        self.inner.cancel.cancelled().await
+    }
+
+    /// `Err(Cancelled)` once the reporter was cancelled, for use with `?`
+    /// between units of work
+    pub fn check_cancelled(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled(self.inner.name.clone()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn snapshot_of(&self, state: &TaskState) -> ProgressSnapshot {
+        let elapsed = self.inner.started.elapsed();
+        ProgressSnapshot {
+            task: self.inner.name.clone(),
+            position: state.position,
+            total: state.total,
+            message: state.message.clone(),
+            elapsed,
+            eta: estimate_eta(elapsed, state.position, state.total),
+            finished: state.finished,
+        }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut TaskState)) {
+        let mut state = self.inner.state.lock().unwrap();
+        apply(&mut state);
+
+        if let Some(bar) = &self.inner.bar {
+            if let Some(total) = state.total {
+                bar.set_length(total);
+            }
+            bar.set_position(state.position);
+            bar.set_message(state.message.clone());
+            if state.finished && !bar.is_finished() {
+                bar.finish();
+            }
+        }
+
+        if let Some(sender) = &self.inner.sender {
+            // Views redraw a few times a second; don't flood them from hot loops
+            let done = state.finished || state.total == Some(state.position);
+            let due = match state.last_sent {
+                Some(sent) => sent.elapsed() >= SNAPSHOT_INTERVAL,
+                None => true,
+            };
+            if done || due {
+                let _ = sender.send(self.snapshot_of(&state));
+                state.last_sent = Some(Instant::now());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_tasks_send_throttled_snapshots_and_share_cancellation() {
+        let (reporter, mut receiver) = ProgressReporter::channel();
+        let files = reporter.task("Chunk files", Some(4));
+
+        files.inc(1);
+        files.inc(1);
+        let first = receiver.try_recv().unwrap();
+        assert_eq!(first.position, 1);
+        // The second increment came within the snapshot interval
+        assert!(receiver.try_recv().is_err());
+
+        let snapshot = files.snapshot();
+        assert_eq!(snapshot.fraction(), Some(0.5));
+        assert!(snapshot.eta.is_some());
+        assert!(snapshot.label().starts_with("2/4 (50%)"));
+
+        files.finish("done");
+        let last = receiver.try_recv().unwrap();
+        assert!(last.finished);
+        assert_eq!(last.message, "done");
+
+        let spinner = reporter.task("Pages", None);
+        assert!(spinner.check_cancelled().is_ok());
+        reporter.cancel();
+        assert_eq!(
+            spinner.check_cancelled().unwrap_err().to_string(),
+            "Pages cancelled"
+        );
+
+        // Cancelling a token handed in from outside (e.g. Ctrl-C) stops tasks too
+        let token = CancellationToken::new();
+        let sync = ProgressReporter::hidden()
+            .with_cancellation(token.clone())
+            .task("Sync", None);
+        token.cancel();
+        assert!(sync.is_cancelled());
+
+        assert_eq!(estimate_eta(Duration::from_secs(10), 0, Some(5)), None);
+        assert_eq!(
+            estimate_eta(Duration::from_secs(10), 1, Some(5)),
+            Some(Duration::from_secs(40))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Append-only journal of risk decisions
+//!
+//! One JSON object per line in `<data_dir>/logs/risk_journal.jsonl`, so the
+//! history of confirmations, blocks and overrides survives restarts and can be
+//! grepped or loaded into a notebook.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::warn;
+
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+/// File name of the journal inside the logs directory
+pub const RISK_JOURNAL_FILE: &str = "risk_journal.jsonl";
+
+/// Kind of risk decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskJournalEvent {
+    /// Large order confirmed interactively (prompt or modal)
+    LargeOrderConfirmed,
+    /// Large order confirmed up front with `--yes-large`
+    LargeOrderConfirmedByFlag,
+    /// Large order declined at the confirmation step
+    LargeOrderDeclined,
+    /// Large order refused because no confirmation was possible
+    LargeOrderBlocked,
+}
+
+/// A single journal line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskJournalEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: RiskJournalEvent,
+    /// Where the decision was made (`cli`, `gui`, strategy name, ...)
+    pub source: String,
+    pub profile: Option<String>,
+    pub token_id: Option<String>,
+    pub side: Option<Side>,
+    pub price: Option<Decimal>,
+    pub size: Option<Decimal>,
+    pub notional: Option<Decimal>,
+    pub detail: Option<String>,
+}
+
+impl RiskJournalEntry {
+    pub fn new(event: RiskJournalEvent, source: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            event,
+            source: source.to_string(),
+            profile: None,
+            token_id: None,
+            side: None,
+            price: None,
+            size: None,
+            notional: None,
+            detail: None,
+        }
+    }
+
+    pub fn with_order(mut self, token_id: &str, side: Side, price: Decimal, size: Decimal) -> Self {
+        self.token_id = Some(token_id.to_string());
+        self.side = Some(side);
+        self.price = Some(price);
+        self.size = Some(size);
+        self.notional = Some(price * size);
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Writer for the risk journal of one data directory
+#[derive(Debug, Clone)]
+pub struct RiskJournal {
+    path: PathBuf,
+    profile: String,
+}
+
+impl RiskJournal {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            path: data_paths.logs().join(RISK_JOURNAL_FILE),
+            profile: data_paths.profile().to_string(),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Append an entry; failures are logged rather than interrupting trading
+    pub fn record(&self, entry: &RiskJournalEntry) {
+        if let Err(e) = self.try_record(entry) {
+            warn!("Failed to write risk journal {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn try_record(&self, entry: &RiskJournalEntry) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut entry = entry.clone();
+        entry.profile.get_or_insert_with(|| self.profile.clone());
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
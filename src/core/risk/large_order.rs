@@ -0,0 +1,147 @@
+//! Two-phase confirmation for large orders
+//!
+//! Orders whose notional (price × size) exceeds the configured threshold need
+//! a second, explicit confirmation on top of the usual `--yes`: an interactive
+//! prompt in the CLI, a modal in the GUI, or the `--yes-large` flag for
+//! scripted use. Every decision is written to the risk journal.
+
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, IsTerminal, Write};
+
+use super::journal::{RiskJournal, RiskJournalEntry, RiskJournalEvent};
+use crate::core::types::common::Side;
+
+/// Large order threshold configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LargeOrderConfig {
+    /// Require the second confirmation at all
+    pub enabled: bool,
+    /// Notional (USDC) above which an order is considered large
+    pub notional_threshold: Decimal,
+}
+
+impl Default for LargeOrderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            notional_threshold: Decimal::from(500),
+        }
+    }
+}
+
+/// An order that exceeds the large order threshold
+#[derive(Debug, Clone)]
+pub struct LargeOrder {
+    pub token_id: String,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub notional: Decimal,
+    pub threshold: Decimal,
+}
+
+impl LargeOrder {
+    /// Journal entry for a decision about this order
+    pub fn journal_entry(&self, event: RiskJournalEvent, source: &str) -> RiskJournalEntry {
+        RiskJournalEntry::new(event, source)
+            .with_order(&self.token_id, self.side, self.price, self.size)
+            .with_detail(format!(
+                "notional {} exceeds threshold {}",
+                self.notional.round_dp(2),
+                self.threshold.round_dp(2)
+            ))
+    }
+}
+
+impl LargeOrderConfig {
+    /// Returns the order details when it needs a second confirmation
+    pub fn check(&self, token_id: &str, side: Side, price: Decimal, size: Decimal) -> Option<LargeOrder> {
+        let notional = price * size;
+        if !self.enabled || notional <= self.notional_threshold {
+            return None;
+        }
+        Some(LargeOrder {
+            token_id: token_id.to_string(),
+            side,
+            price,
+            size,
+            notional,
+            threshold: self.notional_threshold,
+        })
+    }
+}
+
+/// CLI confirmation: accept `--yes-large`, otherwise prompt on a terminal
+///
+/// Returns `Ok(true)` when the order may be placed and `Ok(false)` when the
+/// user declined. Non-interactive sessions without `--yes-large` are refused.
+pub fn confirm_large_order_cli(
+    order: &LargeOrder,
+    yes_large: bool,
+    journal: &RiskJournal,
+) -> Result<bool> {
+    if yes_large {
+        journal.record(&order.journal_entry(RiskJournalEvent::LargeOrderConfirmedByFlag, "cli"));
+        return Ok(true);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        journal.record(&order.journal_entry(RiskJournalEvent::LargeOrderBlocked, "cli"));
+        return Err(anyhow!(
+            "Order notional ${} exceeds the large order threshold ${}. Re-run with --yes-large to confirm.",
+            order.notional.round_dp(2),
+            order.threshold.round_dp(2)
+        ));
+    }
+
+    println!(
+        "⚠️  Large order: {} {} @ ${} = ${} notional (threshold ${})",
+        order.side,
+        order.size,
+        order.price,
+        order.notional.round_dp(2),
+        order.threshold.round_dp(2)
+    );
+    print!("Type 'confirm' to place this order: ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let confirmed = answer.trim().eq_ignore_ascii_case("confirm");
+
+    let event = if confirmed {
+        RiskJournalEvent::LargeOrderConfirmed
+    } else {
+        RiskJournalEvent::LargeOrderDeclined
+    };
+    journal.record(&order.journal_entry(event, "cli"));
+
+    Ok(confirmed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_threshold_applies_to_notional() {
+        let config = LargeOrderConfig {
+            enabled: true,
+            notional_threshold: dec!(100),
+        };
+
+        assert!(config.check("t", Side::Buy, dec!(0.5), dec!(200)).is_none());
+        let large = config.check("t", Side::Buy, dec!(0.5), dec!(201)).unwrap();
+        assert_eq!(large.notional, dec!(100.5));
+
+        let disabled = LargeOrderConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(disabled.check("t", Side::Buy, dec!(0.5), dec!(10000)).is_none());
+    }
+}
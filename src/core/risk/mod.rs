@@ -0,0 +1,99 @@
+//! Risk controls applied to outbound trading traffic
+//!
+//! Configuration lives in `<data_dir>/risk.yaml` and is optional; missing
+//! files or sections fall back to conservative defaults:
+//!
+//! ```yaml
+//! throttle:
+//!   enabled: true
+//!   default_limits:
+//!     new_orders: { per_second: 5.0, burst: 10 }
+//!     cancels: { per_second: 10.0, burst: 20 }
+//!   market_overrides:
+//!     "<condition_id>":
+//!       new_orders: { per_second: 1.0, burst: 2 }
+//!       cancels: { per_second: 2.0, burst: 4 }
+//! large_orders:
+//!   enabled: true
+//!   notional_threshold: 500
+//! ```
+//!
+//! Risk decisions (large order confirmations, blocks) are appended to the
+//! risk journal in `<data_dir>/logs/risk_journal.jsonl`.
+
+pub mod journal;
+pub mod large_order;
+pub mod throttle;
+
+pub use journal::{RiskJournal, RiskJournalEntry, RiskJournalEvent};
+pub use large_order::{confirm_large_order_cli, LargeOrder, LargeOrderConfig};
+
+pub use throttle::{
+    MarketLimits, MessageKind, MessageThrottle, RateLimit, ThrottleConfig, ThrottleError,
+    ThrottleStats,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data_paths::DataPaths;
+
+/// Name of the risk configuration file inside the data directory
+pub const RISK_CONFIG_FILE: &str = "risk.yaml";
+
+/// Risk module configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Per-market outbound message throttles
+    pub throttle: ThrottleConfig,
+    /// Notional threshold for two-phase order confirmation
+    pub large_orders: LargeOrderConfig,
+}
+
+impl RiskConfig {
+    /// Load `risk.yaml` from the data directory, or defaults when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(RISK_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        crate::core::audit::track_config(&path, content.as_bytes());
+        let config: Self = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid risk config {}", path.display()))?;
+        config
+            .throttle
+            .validate()
+            .with_context(|| format!("Invalid risk config {}", path.display()))?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(yaml: &str) -> Result<RiskConfig> {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(RISK_CONFIG_FILE), yaml).unwrap();
+        RiskConfig::load(&DataPaths::new(dir.path()))
+    }
+
+    #[test]
+    fn load_rejects_rates_a_bucket_cant_refill_at() {
+        for rate in ["0", "-1.0", ".nan", ".inf"] {
+            let yaml = format!(
+                "throttle:\n  market_overrides:\n    hot:\n      \
+                 new_orders: {{ per_second: {}, burst: 2 }}\n      \
+                 cancels: {{ per_second: 2.0, burst: 4 }}\n",
+                rate
+            );
+            let err = load(&yaml).unwrap_err();
+            assert!(format!("{:#}", err).contains("per_second"), "{:#}", err);
+        }
+        let config = load("throttle:\n  enabled: false\n").unwrap();
+        assert!(!config.throttle.enabled);
+    }
+}
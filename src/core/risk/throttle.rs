@@ -0,0 +1,363 @@
+//! Per-market outbound message throttling
+//!
+//! Token buckets keyed by [`BucketKey`] (market and message kind) cap how
+//! many new orders and cancels a process may send for a single market, across
+//! all of its outcome tokens. Markets are condition IDs; callers resolve the
+//! token an order is for to its market. Each bucket refills at the
+//! configured rate and holds at most `burst` tokens, so short bursts pass while
+//! a runaway loop is cut off after the burst is spent.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Kind of outbound message subject to throttling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    NewOrder,
+    Cancel,
+}
+
+impl std::fmt::Display for MessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageKind::NewOrder => write!(f, "new order"),
+            MessageKind::Cancel => write!(f, "cancel"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ThrottleError {
+    #[error("Throttled {kind} for market {market}: limit of {rate}/s (burst {burst}) reached, retry in {retry_after:?}")]
+    RateLimited {
+        market: String,
+        kind: MessageKind,
+        rate: f64,
+        burst: u32,
+        retry_after: Duration,
+    },
+}
+
+/// Rate and burst for one message kind
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Sustained messages per second
+    pub per_second: f64,
+    /// Messages allowed back-to-back before the rate applies
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Check the rate is one a bucket can refill at, and the bucket can hold
+    /// the one token a message takes
+    fn validate(&self) -> anyhow::Result<()> {
+        if !self.per_second.is_finite() || self.per_second <= 0.0 {
+            anyhow::bail!(
+                "per_second must be a positive number, got {}",
+                self.per_second
+            );
+        }
+        if self.burst == 0 {
+            anyhow::bail!("burst must be at least 1, or every message would be throttled");
+        }
+        Ok(())
+    }
+}
+
+/// Limits applied to a single market
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarketLimits {
+    pub new_orders: RateLimit,
+    pub cancels: RateLimit,
+}
+
+impl MarketLimits {
+    fn for_kind(&self, kind: MessageKind) -> RateLimit {
+        match kind {
+            MessageKind::NewOrder => self.new_orders,
+            MessageKind::Cancel => self.cancels,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.new_orders.validate().context("new_orders")?;
+        self.cancels.validate().context("cancels")
+    }
+}
+
+impl Default for MarketLimits {
+    fn default() -> Self {
+        Self {
+            new_orders: RateLimit {
+                per_second: 5.0,
+                burst: 10,
+            },
+            cancels: RateLimit {
+                per_second: 10.0,
+                burst: 20,
+            },
+        }
+    }
+}
+
+/// Throttle configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// Disable to send without any client-side limit
+    pub enabled: bool,
+    /// Limits for markets without an override
+    pub default_limits: MarketLimits,
+    /// Per-market overrides keyed by market (condition) ID
+    pub market_overrides: HashMap<String, MarketLimits>,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_limits: MarketLimits::default(),
+            market_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl ThrottleConfig {
+    /// Limits that apply to a market
+    pub fn limits_for(&self, market: &str) -> MarketLimits {
+        self.market_overrides
+            .get(market)
+            .copied()
+            .unwrap_or(self.default_limits)
+    }
+
+    /// Check every limit has a rate buckets can refill at
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.default_limits
+            .validate()
+            .context("throttle.default_limits")?;
+        for (market, limits) in &self.market_overrides {
+            limits
+                .validate()
+                .with_context(|| format!("throttle.market_overrides.{}", market))?;
+        }
+        Ok(())
+    }
+}
+
+/// Bucket of one message kind on one market
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BucketKey {
+    market: String,
+    kind: MessageKind,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            last_refill: now,
+        }
+    }
+
+    fn try_take(&mut self, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if limit.per_second > 0.0 {
+            // A tiny rate can put the wait beyond what a Duration holds
+            let wait = (1.0 - self.tokens) / limit.per_second;
+            Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// Counters for throttled traffic
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThrottleStats {
+    pub allowed: u64,
+    pub rejected_new_orders: u64,
+    pub rejected_cancels: u64,
+    /// Rejections per market
+    pub rejected_by_market: HashMap<String, u64>,
+}
+
+/// Thread-safe per-market throttle shared by everything that sends orders
+#[derive(Debug)]
+pub struct MessageThrottle {
+    config: ThrottleConfig,
+    buckets: Mutex<HashMap<BucketKey, TokenBucket>>,
+    stats: Mutex<ThrottleStats>,
+}
+
+impl MessageThrottle {
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            stats: Mutex::new(ThrottleStats::default()),
+        }
+    }
+
+    pub fn config(&self) -> &ThrottleConfig {
+        &self.config
+    }
+
+    /// Consume one message allowance for the market, or report when to retry
+    pub fn check(&self, market: &str, kind: MessageKind) -> Result<(), ThrottleError> {
+        self.check_at(market, kind, Instant::now())
+    }
+
+    fn check_at(&self, market: &str, kind: MessageKind, now: Instant) -> Result<(), ThrottleError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let limit = self.config.limits_for(market).for_kind(kind);
+        let result = {
+            let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+            buckets
+                .entry(BucketKey {
+                    market: market.to_string(),
+                    kind,
+                })
+                .or_insert_with(|| TokenBucket::full(limit, now))
+                .try_take(limit, now)
+        };
+
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                stats.allowed += 1;
+                Ok(())
+            }
+            Err(retry_after) => {
+                match kind {
+                    MessageKind::NewOrder => stats.rejected_new_orders += 1,
+                    MessageKind::Cancel => stats.rejected_cancels += 1,
+                }
+                *stats.rejected_by_market.entry(market.to_string()).or_insert(0) += 1;
+                Err(ThrottleError::RateLimited {
+                    market: market.to_string(),
+                    kind,
+                    rate: limit.per_second,
+                    burst: limit.burst,
+                    retry_after,
+                })
+            }
+        }
+    }
+
+    /// Snapshot of allowed/rejected counters
+    pub fn stats(&self) -> ThrottleStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(per_second: f64, burst: u32) -> MessageThrottle {
+        let limit = RateLimit { per_second, burst };
+        MessageThrottle::new(ThrottleConfig {
+            enabled: true,
+            default_limits: MarketLimits {
+                new_orders: limit,
+                cancels: limit,
+            },
+            market_overrides: HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn test_burst_then_rate_limited() {
+        let throttle = throttle(2.0, 3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(throttle.check_at("m1", MessageKind::NewOrder, now).is_ok());
+        }
+        let err = throttle.check_at("m1", MessageKind::NewOrder, now).unwrap_err();
+        let ThrottleError::RateLimited { retry_after, .. } = err;
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        // Half a second refills one token
+        let later = now + Duration::from_millis(500);
+        assert!(throttle.check_at("m1", MessageKind::NewOrder, later).is_ok());
+        assert_eq!(throttle.stats().rejected_new_orders, 1);
+    }
+
+    #[test]
+    fn test_markets_and_kinds_are_independent() {
+        let throttle = throttle(1.0, 1);
+        let now = Instant::now();
+
+        assert!(throttle.check_at("m1", MessageKind::NewOrder, now).is_ok());
+        assert!(throttle.check_at("m1", MessageKind::NewOrder, now).is_err());
+        assert!(throttle.check_at("m1", MessageKind::Cancel, now).is_ok());
+        assert!(throttle.check_at("m2", MessageKind::NewOrder, now).is_ok());
+    }
+
+    #[test]
+    fn test_market_override_and_disabled() {
+        let mut config = ThrottleConfig::default();
+        config.market_overrides.insert(
+            "hot".to_string(),
+            MarketLimits {
+                new_orders: RateLimit {
+                    per_second: 1.0,
+                    burst: 1,
+                },
+                ..MarketLimits::default()
+            },
+        );
+        let throttle = MessageThrottle::new(config.clone());
+        let now = Instant::now();
+        assert!(throttle.check_at("hot", MessageKind::NewOrder, now).is_ok());
+        assert!(throttle.check_at("hot", MessageKind::NewOrder, now).is_err());
+        assert!(throttle.check_at("cold", MessageKind::NewOrder, now).is_ok());
+
+        config.enabled = false;
+        let disabled = MessageThrottle::new(config);
+        for _ in 0..100 {
+            assert!(disabled.check_at("hot", MessageKind::NewOrder, now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_tiny_rate_waits_without_overflowing() {
+        let throttle = throttle(1e-300, 1);
+        let now = Instant::now();
+        assert!(throttle.check_at("m1", MessageKind::NewOrder, now).is_ok());
+        let err = throttle.check_at("m1", MessageKind::NewOrder, now).unwrap_err();
+        let ThrottleError::RateLimited { retry_after, .. } = err;
+        assert_eq!(retry_after, Duration::MAX);
+    }
+
+    #[test]
+    fn test_zero_burst_is_rejected() {
+        let mut config = ThrottleConfig::default();
+        assert!(config.validate().is_ok());
+        config.default_limits.cancels.burst = 0;
+        let err = config.validate().unwrap_err();
+        assert!(format!("{:#}", err).contains("burst"), "{:#}", err);
+        assert!(format!("{:#}", err).contains("throttle.default_limits"), "{:#}", err);
+    }
+}
@@ -9,6 +9,7 @@ use crate::core::ws::{
     parse_message, AuthPayload, EventError, PolyEvent, WsMessage,
     OrderBook, StateError,
 };
+use crate::markets::datasets::replay::{CaptureReplay, ReplayFeed, ReplayHandle};
 use dashmap::DashMap;
 use polymarket_rs_client::ClobClient;
 use std::sync::Arc;
@@ -71,6 +72,8 @@ pub struct Streamer {
     rest_client: Option<Arc<ClobClient>>,
     market_task: Option<JoinHandle<()>>,
     user_task: Option<JoinHandle<()>>,
+    /// Capture being played in place of the market feed
+    replay: Option<ReplayHandle>,
 }
 
 impl Streamer {
@@ -89,6 +92,7 @@ impl Streamer {
             rest_client: None,
             market_task: None,
             user_task: None,
+            replay: None,
         }
     }
 
@@ -128,6 +132,43 @@ impl Streamer {
         Ok(())
     }
 
+    /// Play a capture replay into the order books and events instead of
+    /// connecting the market feed; no REST client is used
+    pub fn start_replay(&mut self, replay: &Arc<CaptureReplay>, speed: f64) {
+        info!("Replaying {}", replay.describe());
+
+        let feed = ReplayFeed::default();
+        let mut events = feed.subscribe_all();
+        let order_books = self.order_books.clone();
+        let last_trade_prices = self.last_trade_prices.clone();
+        let event_tx = self.event_tx.clone();
+
+        let task = tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                // Recorded books were hash checked when they were streamed
+                Self::apply_market_event(
+                    &event,
+                    &order_books,
+                    &last_trade_prices,
+                    &None,
+                    false,
+                    true,
+                    true,
+                )
+                .await;
+                let _ = event_tx.send(event);
+            }
+        });
+
+        self.market_task = Some(task);
+        self.replay = Some(replay.play(feed, speed));
+    }
+
+    /// The capture replay being played, if any
+    pub fn replay(&self) -> Option<&ReplayHandle> {
+        self.replay.as_ref()
+    }
+
     /// Stop the streaming service
     pub async fn stop(&mut self) {
         info!("Stopping streaming service");
@@ -140,6 +181,11 @@ impl Streamer {
             let _ = client.disconnect();
         }
 
+        // Ending the playback closes the replay feed the market task reads
+        if let Some(replay) = self.replay.take() {
+            replay.stop();
+        }
+
         // Wait for tasks to complete
         if let Some(task) = self.market_task.take() {
             let _ = task.await;
@@ -259,108 +305,16 @@ impl Streamer {
         match parse_message(&ws_message) {
             Ok(events) => {
                 for event in events {
-                    match &event {
-                        PolyEvent::Book {
-                            asset_id,
-                            market,
-                            timestamp,
-                            bids,
-                            asks,
-                            hash,
-                        } => {
-                            trace!(
-                                "Received book update for asset {}: {} bids, {} asks",
-                                asset_id,
-                                bids.len(),
-                                asks.len()
-                            );
-                            Self::handle_book_event(
-                                asset_id,
-                                market,
-                                *timestamp,
-                                bids,
-                                asks,
-                                hash,
-                                order_books,
-                                rest_client,
-                                auto_sync,
-                                skip_hash_verification,
-                                quiet_hash_mismatch,
-                            )
-                            .await;
-                        }
-                        PolyEvent::PriceChange {
-                            asset_id,
-                            side,
-                            price,
-                            size,
-                            hash,
-                        } => {
-                            trace!(
-                                "Received price change for asset {}: {:?} {} @ {}",
-                                asset_id,
-                                side,
-                                size,
-                                price
-                            );
-                            Self::handle_price_change_event(
-                                asset_id,
-                                *side,
-                                *price,
-                                *size,
-                                hash,
-                                order_books,
-                                rest_client,
-                                auto_sync,
-                                skip_hash_verification,
-                            )
-                            .await;
-                        }
-                        PolyEvent::Trade {
-                            asset_id,
-                            price,
-                            size,
-                            side,
-                            ..
-                        } => {
-                            trace!(
-                                "Received trade for asset {}: {:?} {} @ {}",
-                                asset_id,
-                                side,
-                                size,
-                                price
-                            );
-                        }
-                        PolyEvent::TickSizeChange {
-                            asset_id,
-                            tick_size,
-                        } => {
-                            trace!(
-                                "Received tick size change for asset {}: {}",
-                                asset_id,
-                                tick_size
-                            );
-                            if let Some(mut book) = order_books.get_mut(asset_id) {
-                                book.set_tick_size(*tick_size);
-                            }
-                        }
-                        PolyEvent::LastTradePrice {
-                            asset_id,
-                            price,
-                            timestamp,
-                        } => {
-                            info!(
-                                "Received last trade price for asset {}: ${} at {}",
-                                asset_id, price, timestamp
-                            );
-                            // Store the last trade price
-                            last_trade_prices.insert(asset_id.clone(), (*price, *timestamp));
-                            // TODO: Store in trades flatbuffer file for persistence
-                        }
-                        _ => {
-                            debug!("Received other event: {:?}", event);
-                        }
-                    }
+                    Self::apply_market_event(
+                        &event,
+                        order_books,
+                        last_trade_prices,
+                        rest_client,
+                        auto_sync,
+                        skip_hash_verification,
+                        quiet_hash_mismatch,
+                    )
+                    .await;
 
                     match event_tx.send(event) {
                         Ok(receiver_count) => {
@@ -404,6 +358,121 @@ impl Streamer {
         }
     }
 
+    /// Apply a market event to the order books and last trade prices
+    async fn apply_market_event(
+        event: &PolyEvent,
+        order_books: &DashMap<String, OrderBook>,
+        last_trade_prices: &DashMap<String, (rust_decimal::Decimal, u64)>,
+        rest_client: &Option<Arc<ClobClient>>,
+        auto_sync: bool,
+        skip_hash_verification: bool,
+        quiet_hash_mismatch: bool,
+    ) {
+        match event {
+            PolyEvent::Book {
+                asset_id,
+                market,
+                timestamp,
+                bids,
+                asks,
+                hash,
+                ..
+            } => {
+                trace!(
+                    "Received book update for asset {}: {} bids, {} asks",
+                    asset_id,
+                    bids.len(),
+                    asks.len()
+                );
+                Self::handle_book_event(
+                    asset_id,
+                    market,
+                    *timestamp,
+                    bids,
+                    asks,
+                    hash,
+                    order_books,
+                    rest_client,
+                    auto_sync,
+                    skip_hash_verification,
+                    quiet_hash_mismatch,
+                )
+                .await;
+            }
+            PolyEvent::PriceChange {
+                asset_id,
+                side,
+                price,
+                size,
+                hash,
+            } => {
+                trace!(
+                    "Received price change for asset {}: {:?} {} @ {}",
+                    asset_id,
+                    side,
+                    size,
+                    price
+                );
+                Self::handle_price_change_event(
+                    asset_id,
+                    *side,
+                    *price,
+                    *size,
+                    hash,
+                    order_books,
+                    rest_client,
+                    auto_sync,
+                    skip_hash_verification,
+                )
+                .await;
+            }
+            PolyEvent::Trade {
+                asset_id,
+                price,
+                size,
+                side,
+                ..
+            } => {
+                trace!(
+                    "Received trade for asset {}: {:?} {} @ {}",
+                    asset_id,
+                    side,
+                    size,
+                    price
+                );
+            }
+            PolyEvent::TickSizeChange {
+                asset_id,
+                tick_size,
+            } => {
+                trace!(
+                    "Received tick size change for asset {}: {}",
+                    asset_id,
+                    tick_size
+                );
+                if let Some(mut book) = order_books.get_mut(asset_id) {
+                    book.set_tick_size(*tick_size);
+                }
+            }
+            PolyEvent::LastTradePrice {
+                asset_id,
+                price,
+                timestamp,
+            } => {
+                info!(
+                    "Received last trade price for asset {}: ${} at {}",
+                    asset_id, price, timestamp
+                );
+                // Store the last trade price
+                last_trade_prices.insert(asset_id.clone(), (*price, *timestamp));
+                // TODO: Store in trades flatbuffer file for persistence
+            }
+            _ => {
+                debug!("Received other event: {:?}", event);
+            }
+        }
+    }
+
     /// Handle user WebSocket message
     async fn handle_user_message(ws_message: WsMessage, event_tx: &broadcast::Sender<PolyEvent>) {
         match parse_message(&ws_message) {
@@ -0,0 +1,406 @@
+//! Streaming token budget and auto-selection policy
+//!
+//! Each WebSocket connection carries a limited number of tokens, and only so
+//! many connections are worth holding open. `<data_dir>/token_budget.yaml`
+//! caps the tokens streamed and picks them by priority: open positions first,
+//! then the watchlists (saved token selections), then the markets of a screen
+//! ranked by score until the budget is spent:
+//!
+//! ```yaml
+//! enabled: true
+//! max_tokens: 200         # tokens streamed at most
+//! max_connections: 4      # connections at most, each with the per-connection token count
+//! watchlists: [core]      # saved selections to include; empty includes all of them
+//! screen:                 # filters of `datasets create-from-search`
+//!   category: Politics
+//!   min_volume: 10000
+//! reevaluate_secs: 300
+//! ```
+//!
+//! A market's screen score is `0.7 × 24h volume + 0.3 × liquidity`, so the
+//! screen favours markets trading now. The screen searches the local Gamma
+//! store and leaves out what the suppression list (`suppression.yaml`) does.
+//! The canvas re-evaluates the budget every `reevaluate_secs` while streaming,
+//! subscribing to what it picks and dropping what it no longer does.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+use crate::data_paths::DataPaths;
+use crate::markets::datasets::from_search::{search_markets, MarketSearchQuery, GAMMA_DB_DIR};
+use crate::markets::datasets::selection::SelectionManager;
+use crate::markets::gamma::GammaMarket;
+use crate::markets::suppression::SuppressionConfig;
+
+/// Name of the token budget configuration file inside the data directory
+pub const TOKEN_BUDGET_CONFIG_FILE: &str = "token_budget.yaml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenBudgetConfig {
+    pub enabled: bool,
+    /// Tokens streamed at most
+    pub max_tokens: Option<usize>,
+    /// WebSocket connections held open at most
+    pub max_connections: Option<usize>,
+    /// Saved selections whose tokens come after positions; empty includes all
+    pub watchlists: Vec<String>,
+    /// Market screen filling what's left of the budget, best score first
+    pub screen: Option<MarketSearchQuery>,
+    /// Seconds between re-evaluations
+    pub reevaluate_secs: u64,
+}
+
+impl Default for TokenBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_tokens: None,
+            max_connections: None,
+            watchlists: Vec::new(),
+            screen: None,
+            reevaluate_secs: 300,
+        }
+    }
+}
+
+/// Why a token is streamed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenSource {
+    Position,
+    Watchlist(String),
+    /// Place in the screen and the market's score
+    Screen {
+        rank: usize,
+        score: f64,
+    },
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenSource::Position => write!(f, "position"),
+            TokenSource::Watchlist(name) => write!(f, "watchlist {}", name),
+            TokenSource::Screen { rank, .. } => write!(f, "screen #{}", rank),
+        }
+    }
+}
+
+/// Tokens the budget picks from, in the order of each source
+#[derive(Debug, Clone, Default)]
+pub struct BudgetCandidates {
+    pub positions: Vec<String>,
+    pub watchlists: Vec<WatchlistTokens>,
+    /// Best score first
+    pub screen: Vec<ScreenedToken>,
+}
+
+/// A watchlist and its tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchlistTokens {
+    pub name: String,
+    pub tokens: Vec<String>,
+}
+
+/// A screened token and its market's score
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenedToken {
+    pub token_id: String,
+    pub score: f64,
+}
+
+/// Selected tokens from each source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BudgetCounts {
+    pub positions: usize,
+    pub watchlists: usize,
+    pub screen: usize,
+}
+
+/// Tokens to subscribe to and to drop
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetChanges {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+/// A token and why it's a candidate
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetedToken {
+    pub token_id: String,
+    pub source: TokenSource,
+}
+
+/// One evaluation of the budget
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    pub limit: usize,
+    /// Tokens to stream, highest priority first
+    pub selected: Vec<BudgetedToken>,
+    /// Candidates that didn't fit, highest priority first
+    pub left_out: Vec<BudgetedToken>,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl TokenBudgetConfig {
+    /// Load `token_budget.yaml` from the data directory, or defaults when it
+    /// doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(TOKEN_BUDGET_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid token budget config {}", path.display()))
+    }
+
+    /// Tokens the budget allows with `tokens_per_connection` tokens on each
+    /// connection, or `None` when nothing caps them
+    pub fn limit(&self, tokens_per_connection: usize) -> Option<usize> {
+        let by_connections = self
+            .max_connections
+            .map(|connections| connections * tokens_per_connection);
+        match (self.max_tokens, by_connections) {
+            (Some(tokens), Some(connections)) => Some(tokens.min(connections)),
+            (tokens, connections) => tokens.or(connections),
+        }
+    }
+
+    /// Whether the budget picks the streamed tokens
+    pub fn is_active(&self) -> bool {
+        self.enabled && (self.max_tokens.is_some() || self.max_connections.is_some())
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.reevaluate_secs.max(10))
+    }
+
+    /// Candidates from the watchlists and the screen, after `positions`
+    ///
+    /// The screen keeps no more markets than `limit` tokens could cover.
+    pub fn gather(
+        &self,
+        data_paths: &DataPaths,
+        positions: Vec<String>,
+        limit: usize,
+    ) -> Result<BudgetCandidates> {
+        let manager = SelectionManager::new(data_paths.data());
+        let names = if self.watchlists.is_empty() {
+            manager.list_selections()?
+        } else {
+            self.watchlists.clone()
+        };
+        let watchlists = names
+            .into_iter()
+            .map(|name| {
+                let tokens = manager
+                    .get_tokens(&name)
+                    .with_context(|| format!("Failed to load watchlist '{}'", name))?;
+                Ok(WatchlistTokens { name, tokens })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let screen = match &self.screen {
+            Some(query) => {
+                // Rank every match by score, then keep the query's limit
+                let keep = match query.limit {
+                    0 => limit,
+                    max => max.min(limit),
+                };
+                let query = MarketSearchQuery {
+                    limit: 0,
+                    ..query.clone()
+                };
+                let suppression = SuppressionConfig::load(data_paths)?;
+                let mut markets =
+                    search_markets(&data_paths.root().join(GAMMA_DB_DIR), &query, &suppression)
+                        .context("Token budget screen failed")?
+                        .markets;
+                markets.sort_by(|a, b| {
+                    ScreenedToken::market_score(b).total_cmp(&ScreenedToken::market_score(a))
+                });
+                markets.truncate(keep);
+                markets
+                    .iter()
+                    .flat_map(|market| {
+                        let score = ScreenedToken::market_score(market);
+                        market.clob_token_ids.iter().map(move |token| ScreenedToken {
+                            token_id: token.0.clone(),
+                            score,
+                        })
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Ok(BudgetCandidates {
+            positions,
+            watchlists,
+            screen,
+        })
+    }
+}
+
+impl ScreenedToken {
+    /// Score the screen ranks a market by
+    pub fn market_score(market: &GammaMarket) -> f64 {
+        let volume = market.volume_24hr.and_then(|v| v.to_f64()).unwrap_or(0.0);
+        let liquidity = market.liquidity.and_then(|l| l.to_f64()).unwrap_or(0.0);
+        0.7 * volume + 0.3 * liquidity
+    }
+}
+
+impl TokenBudget {
+    /// Pick up to `limit` of `candidates` by priority, each token once under
+    /// its highest priority source
+    pub fn select(candidates: BudgetCandidates, limit: usize) -> Self {
+        let positions = candidates
+            .positions
+            .into_iter()
+            .map(|token_id| (token_id, TokenSource::Position));
+        let watchlists = candidates
+            .watchlists
+            .into_iter()
+            .flat_map(|WatchlistTokens { name, tokens }| {
+                tokens
+                    .into_iter()
+                    .map(move |token_id| (token_id, TokenSource::Watchlist(name.clone())))
+            });
+        let screen = candidates
+            .screen
+            .into_iter()
+            .enumerate()
+            .map(|(index, ScreenedToken { token_id, score })| {
+                let rank = index + 1;
+                (token_id, TokenSource::Screen { rank, score })
+            });
+
+        let mut seen = HashSet::new();
+        let mut budget = Self {
+            limit,
+            selected: Vec::new(),
+            left_out: Vec::new(),
+            evaluated_at: Utc::now(),
+        };
+        for (token_id, source) in positions.chain(watchlists).chain(screen) {
+            if !seen.insert(token_id.clone()) {
+                continue;
+            }
+            let token = BudgetedToken { token_id, source };
+            if budget.selected.len() < limit {
+                budget.selected.push(token);
+            } else {
+                budget.left_out.push(token);
+            }
+        }
+        budget
+    }
+
+    /// Selected tokens from positions, watchlists and the screen
+    pub fn counts(&self) -> BudgetCounts {
+        let mut counts = BudgetCounts::default();
+        for token in &self.selected {
+            match token.source {
+                TokenSource::Position => counts.positions += 1,
+                TokenSource::Watchlist(_) => counts.watchlists += 1,
+                TokenSource::Screen { .. } => counts.screen += 1,
+            }
+        }
+        counts
+    }
+
+    /// Tokens to subscribe to and to drop to go from `streaming` to this
+    /// selection
+    pub fn changes(&self, streaming: &[String]) -> BudgetChanges {
+        let current: HashSet<&str> = streaming.iter().map(String::as_str).collect();
+        let selected: HashSet<&str> = self.selected.iter().map(|t| t.token_id.as_str()).collect();
+        let add = self
+            .selected
+            .iter()
+            .filter(|t| !current.contains(t.token_id.as_str()))
+            .map(|t| t.token_id.clone())
+            .collect();
+        let remove = streaming
+            .iter()
+            .filter(|token| !selected.contains(token.as_str()))
+            .cloned()
+            .collect();
+        BudgetChanges { add, remove }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn budget_fills_by_priority_without_duplicates() {
+        let config: TokenBudgetConfig =
+            serde_yaml::from_str("max_tokens: 100\nmax_connections: 2\n").unwrap();
+        assert!(config.is_active());
+        assert_eq!(config.limit(25), Some(50));
+        assert_eq!(config.limit(60), Some(100));
+        assert!(!TokenBudgetConfig::default().is_active());
+        assert_eq!(TokenBudgetConfig::default().limit(25), None);
+
+        let candidates = BudgetCandidates {
+            positions: tokens(&["p1", "p2"]),
+            watchlists: vec![WatchlistTokens {
+                name: "core".to_string(),
+                tokens: tokens(&["w1", "p1", "w2"]),
+            }],
+            screen: [("s1", 900.0), ("w2", 800.0), ("s2", 700.0), ("s3", 600.0)]
+                .into_iter()
+                .map(|(token_id, score)| ScreenedToken {
+                    token_id: token_id.to_string(),
+                    score,
+                })
+                .collect(),
+        };
+        let budget = TokenBudget::select(candidates, 5);
+        let selected: Vec<&str> = budget
+            .selected
+            .iter()
+            .map(|t| t.token_id.as_str())
+            .collect();
+        assert_eq!(selected, ["p1", "p2", "w1", "w2", "s1"]);
+        assert_eq!(
+            budget.counts(),
+            BudgetCounts {
+                positions: 2,
+                watchlists: 2,
+                screen: 1
+            }
+        );
+        assert_eq!(
+            budget.selected[4].source,
+            TokenSource::Screen {
+                rank: 1,
+                score: 900.0
+            }
+        );
+        // The screen's w2 was already picked from the watchlist
+        let left_out: Vec<&str> = budget
+            .left_out
+            .iter()
+            .map(|t| t.token_id.as_str())
+            .collect();
+        assert_eq!(left_out, ["s2", "s3"]);
+
+        let changes = budget.changes(&tokens(&["p1", "s3", "w1"]));
+        assert_eq!(changes.add, tokens(&["p2", "w2", "s1"]));
+        assert_eq!(changes.remove, tokens(&["s3"]));
+    }
+}
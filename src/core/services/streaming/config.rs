@@ -2,6 +2,7 @@
 
 use crate::data_paths::DataPaths;
 use crate::core::ws::WsConfig;
+use crate::markets::datasets::replay::ReplayFeed;
 
 /// Configuration for the streaming service
 #[derive(Debug, Clone)]
@@ -33,8 +34,8 @@ pub struct StreamingServiceConfig {
     /// Data paths for configuration and logging
     pub _data_paths: DataPaths,
 
-    /// Host for API connections
-    pub _host: String,
+    /// Host for API connections (used by the REST snapshot fallback)
+    pub host: String,
 
     /// Health check interval in seconds
     pub health_check_interval_secs: u64,
@@ -47,6 +48,37 @@ pub struct StreamingServiceConfig {
 
     /// Maximum number of concurrent connection attempts
     pub max_concurrent_connections: usize,
+
+    /// Poll REST order book snapshots for tokens whose WebSocket data is stale
+    pub rest_fallback_enabled: bool,
+
+    /// Seconds without WebSocket events before a token is considered stale
+    pub rest_fallback_stale_secs: u64,
+
+    /// Seconds between REST snapshot polls for a stale token
+    pub rest_fallback_poll_interval_secs: u64,
+
+    /// Maximum number of REST snapshots fetched per poll cycle
+    pub rest_fallback_max_tokens_per_cycle: usize,
+
+    /// Cross-check streamed books against REST snapshots and resync diverged ones
+    pub consistency_check_enabled: bool,
+
+    /// Seconds between consistency checks
+    pub consistency_check_interval_secs: u64,
+
+    /// Tokens checked per cycle, rotating through all streamed tokens
+    pub consistency_sample_size: usize,
+
+    /// Largest best price difference that is not a divergence
+    pub consistency_price_tolerance: f64,
+
+    /// Largest best size difference, as a fraction of the larger size, that is not a divergence
+    pub consistency_size_tolerance: f64,
+
+    /// Play a capture replay from this channel instead of connecting WebSockets;
+    /// the REST fallback and consistency checks don't run while replaying
+    pub replay_feed: Option<ReplayFeed>,
 }
 
 impl Default for StreamingServiceConfig {
@@ -61,11 +93,21 @@ impl Default for StreamingServiceConfig {
             max_reconnect_delay_ms: 30000, // Cap at 30 seconds
             max_reconnect_attempts: 10,
             _data_paths: DataPaths::new("./data"),
-            _host: "https://clob.polymarket.com".to_string(),
+            host: "https://clob.polymarket.com".to_string(),
             health_check_interval_secs: 30,
             stats_interval_secs: 10,
             worker_connection_delay_ms: 250, // 250ms delay between connections
             max_concurrent_connections: 3,   // Only 3 concurrent connection attempts
+            rest_fallback_enabled: true,
+            rest_fallback_stale_secs: 60,          // A minute of silence before polling REST
+            rest_fallback_poll_interval_secs: 15,  // Refresh stale books every 15 seconds
+            rest_fallback_max_tokens_per_cycle: 20, // Keep REST load bounded
+            consistency_check_enabled: true,
+            consistency_check_interval_secs: 30,
+            consistency_sample_size: 5,            // 10 REST requests a minute
+            consistency_price_tolerance: 0.01,     // One cent at the touch
+            consistency_size_tolerance: 0.5,
+            replay_feed: None,
         }
     }
 }
@@ -0,0 +1,342 @@
+//! Order book consistency checks of the streamed books against REST snapshots
+//!
+//! Every `consistency_check_interval_secs` the streaming service takes the
+//! next `consistency_sample_size` streamed tokens, rotating through all of
+//! them whichever worker streams them, fetches their CLOB `/book` snapshot and
+//! compares its top of book with the worker's book. A token whose best prices
+//! differ by more than `consistency_price_tolerance`, whose best sizes differ
+//! by more than the `consistency_size_tolerance` fraction, or which has a side
+//! only one of the books has, diverges. A token that diverges on two
+//! consecutive checks is resynced: the worker's book is replaced with the
+//! snapshot, which is republished as `BookSource::Resync`. A divergent token is
+//! checked again on the next cycle, so a delta that was in flight while the
+//! snapshot was taken doesn't force a resync on its own.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::events::BookEvent;
+use crate::core::ws::{BookSource, OrderBook, PolyEvent};
+
+/// How far a streamed top of book may be from the REST one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Absolute difference of best prices
+    pub price: Decimal,
+    /// Difference of best sizes as a fraction of the larger one
+    pub size: f64,
+}
+
+/// Best bid and ask of a book
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopOfBook {
+    pub bid: Option<PriceLevel>,
+    pub ask: Option<PriceLevel>,
+}
+
+impl TopOfBook {
+    pub fn of(book: &OrderBook) -> Self {
+        Self {
+            bid: book.best_bid(),
+            ask: book.best_ask(),
+        }
+    }
+
+    /// Best levels of unsorted snapshot levels
+    pub fn of_levels(bids: &[PriceLevel], asks: &[PriceLevel]) -> Self {
+        Self {
+            bid: bids.iter().max_by_key(|level| level.price).cloned(),
+            ask: asks.iter().min_by_key(|level| level.price).cloned(),
+        }
+    }
+}
+
+/// Gaps between the streamed and the REST top of book of a token
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Divergence {
+    pub bid_price_gap: Decimal,
+    pub ask_price_gap: Decimal,
+    pub bid_size_gap: f64,
+    pub ask_size_gap: f64,
+    /// A side one book has and the other doesn't
+    pub missing_side: bool,
+}
+
+impl Divergence {
+    pub fn between(streamed: &TopOfBook, rest: &TopOfBook) -> Self {
+        let mut divergence = Self::default();
+        for (stream_level, rest_level, price_gap, size_gap) in [
+            (
+                &streamed.bid,
+                &rest.bid,
+                &mut divergence.bid_price_gap,
+                &mut divergence.bid_size_gap,
+            ),
+            (
+                &streamed.ask,
+                &rest.ask,
+                &mut divergence.ask_price_gap,
+                &mut divergence.ask_size_gap,
+            ),
+        ] {
+            match (stream_level, rest_level) {
+                (Some(a), Some(b)) => {
+                    *price_gap = (a.price - b.price).abs();
+                    let larger = a.size.max(b.size);
+                    if larger > Decimal::ZERO {
+                        *size_gap = ((a.size - b.size).abs() / larger).to_f64().unwrap_or(0.0);
+                    }
+                }
+                (None, None) => {}
+                _ => divergence.missing_side = true,
+            }
+        }
+        divergence
+    }
+
+    pub fn max_price_gap(&self) -> Decimal {
+        self.bid_price_gap.max(self.ask_price_gap)
+    }
+
+    pub fn exceeds(&self, tolerance: &Tolerance) -> bool {
+        self.missing_side
+            || self.max_price_gap() > tolerance.price
+            || self.bid_size_gap.max(self.ask_size_gap) > tolerance.size
+    }
+
+    pub fn describe(&self) -> String {
+        if self.missing_side {
+            return "a side is missing from one book".to_string();
+        }
+        format!(
+            "price gap {}/{}, size gap {:.0}%/{:.0}% (bid/ask)",
+            self.bid_price_gap,
+            self.ask_price_gap,
+            self.bid_size_gap * 100.0,
+            self.ask_size_gap * 100.0
+        )
+    }
+}
+
+/// Divergence metrics since the service started
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConsistencyStats {
+    /// Tokens compared with a REST snapshot
+    pub checks: u64,
+    /// Checks beyond tolerance
+    pub divergent: u64,
+    /// Books replaced with their snapshot
+    pub resyncs: u64,
+    /// Largest best price gap seen
+    pub max_price_gap: f64,
+    /// Mean best price gap over all checks
+    pub mean_price_gap: f64,
+    pub last_divergent_token: Option<String>,
+}
+
+/// A REST book snapshot
+#[derive(Debug, Clone)]
+pub struct RestBook {
+    pub asset_id: String,
+    pub market: String,
+    pub timestamp: u64,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+    pub hash: String,
+}
+
+/// Picks the tokens to check and keeps the divergence metrics
+pub struct ConsistencyChecker {
+    http: reqwest::Client,
+    host: String,
+    sample_size: usize,
+    tolerance: Tolerance,
+    /// Position of the next sample in the sorted streamed tokens
+    cursor: AtomicUsize,
+    /// Tokens whose last check diverged, rechecked on the next cycle
+    suspects: Mutex<HashSet<String>>,
+    stats: Mutex<ConsistencyStats>,
+}
+
+impl ConsistencyChecker {
+    pub fn new(host: String, sample_size: usize, tolerance: Tolerance) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            host: host.trim_end_matches('/').to_string(),
+            sample_size: sample_size.max(1),
+            tolerance,
+            cursor: AtomicUsize::new(0),
+            suspects: Mutex::new(HashSet::new()),
+            stats: Mutex::new(ConsistencyStats::default()),
+        }
+    }
+
+    pub fn tolerance(&self) -> &Tolerance {
+        &self.tolerance
+    }
+
+    /// The next tokens to check: suspects still streamed first, then the
+    /// rotation continuing where the last sample stopped
+    pub fn next_sample(&self, tokens: &[String]) -> Vec<String> {
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+        let mut sorted = tokens.to_vec();
+        sorted.sort();
+        sorted.dedup();
+
+        let mut sample: Vec<String> = {
+            let mut suspects = self.suspects.lock().unwrap_or_else(|e| e.into_inner());
+            suspects.retain(|token| sorted.binary_search(token).is_ok());
+            let mut pending: Vec<String> = suspects.iter().cloned().collect();
+            pending.sort();
+            pending
+        };
+        let count = self.sample_size.min(sorted.len());
+        let start = self.cursor.fetch_add(count, Ordering::Relaxed) % sorted.len();
+        for offset in 0..count {
+            let token = &sorted[(start + offset) % sorted.len()];
+            if !sample.contains(token) {
+                sample.push(token.clone());
+            }
+        }
+        sample
+    }
+
+    /// Whether a check confirms a divergence: true only when the token also
+    /// diverged on its previous check
+    pub fn confirm(&self, token_id: &str, diverged: bool) -> bool {
+        let mut suspects = self.suspects.lock().unwrap_or_else(|e| e.into_inner());
+        if !diverged {
+            suspects.remove(token_id);
+            return false;
+        }
+        if suspects.remove(token_id) {
+            return true;
+        }
+        suspects.insert(token_id.to_string());
+        false
+    }
+
+    /// Fetch the REST snapshot of a token
+    pub async fn fetch_book(&self, token_id: &str) -> Result<RestBook, anyhow::Error> {
+        let url = format!("{}/book?token_id={}", self.host, token_id);
+        let response = self.http.get(&url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "REST book request failed with status {}",
+                status
+            ));
+        }
+        let body = response.text().await?;
+        let snapshot: BookEvent = serde_json::from_str(&body)?;
+        Ok(RestBook {
+            asset_id: token_id.to_string(),
+            market: snapshot.market.into_owned(),
+            timestamp: snapshot.timestamp,
+            bids: snapshot.bids,
+            asks: snapshot.asks,
+            hash: snapshot.hash.into_owned(),
+        })
+    }
+
+    /// Count a check and whether its book was resynced
+    pub fn record(&self, token_id: &str, divergence: &Divergence, resynced: bool) {
+        let gap = divergence.max_price_gap().to_f64().unwrap_or(0.0);
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats.checks += 1;
+        stats.mean_price_gap += (gap - stats.mean_price_gap) / stats.checks as f64;
+        stats.max_price_gap = stats.max_price_gap.max(gap);
+        if divergence.exceeds(&self.tolerance) {
+            stats.divergent += 1;
+            stats.last_divergent_token = Some(token_id.to_string());
+        }
+        if resynced {
+            stats.resyncs += 1;
+        }
+    }
+
+    pub fn stats(&self) -> ConsistencyStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The event republishing a resynced book
+    pub fn resync_event(snapshot: RestBook) -> PolyEvent {
+        PolyEvent::Book {
+            asset_id: snapshot.asset_id,
+            market: snapshot.market,
+            timestamp: snapshot.timestamp,
+            bids: snapshot.bids,
+            asks: snapshot.asks,
+            hash: snapshot.hash,
+            source: BookSource::Resync,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn samples_rotate_and_divergence_beyond_tolerance_is_flagged() {
+        let checker = ConsistencyChecker::new(
+            "https://clob.polymarket.com/".to_string(),
+            2,
+            Tolerance {
+                price: dec!(0.01),
+                size: 0.5,
+            },
+        );
+        let tokens: Vec<String> = ["c", "a", "b"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(checker.next_sample(&tokens), ["a", "b"]);
+        assert_eq!(checker.next_sample(&tokens), ["c", "a"]);
+        assert_eq!(checker.next_sample(&tokens), ["b", "c"]);
+
+        let level = |price, size| Some(PriceLevel::new(price, size));
+        let streamed = TopOfBook {
+            bid: level(dec!(0.45), dec!(100)),
+            ask: level(dec!(0.47), dec!(80)),
+        };
+        let close = TopOfBook::of_levels(
+            &[
+                PriceLevel::new(dec!(0.44), dec!(10)),
+                PriceLevel::new(dec!(0.45), dec!(90)),
+            ],
+            &[PriceLevel::new(dec!(0.47), dec!(60))],
+        );
+        let divergence = Divergence::between(&streamed, &close);
+        assert_eq!(divergence.max_price_gap(), Decimal::ZERO);
+        assert!(!divergence.exceeds(checker.tolerance()));
+
+        let moved = TopOfBook {
+            bid: level(dec!(0.42), dec!(100)),
+            ask: None,
+        };
+        let divergence = Divergence::between(&streamed, &moved);
+        assert!(divergence.missing_side && divergence.exceeds(checker.tolerance()));
+
+        // The first divergent check only marks the token, which leads the next sample
+        assert!(!checker.confirm("b", true));
+        assert_eq!(checker.next_sample(&tokens), ["b", "a"]);
+        assert!(checker.confirm("b", true));
+        // An agreeing check in between starts over
+        assert!(!checker.confirm("c", true));
+        assert!(!checker.confirm("c", false));
+        assert!(!checker.confirm("c", true));
+
+        checker.record("a", &Divergence::between(&streamed, &close), false);
+        checker.record("b", &divergence, true);
+        let stats = checker.stats();
+        assert_eq!((stats.checks, stats.divergent, stats.resyncs), (2, 1, 1));
+        assert_eq!(stats.max_price_gap, 0.03);
+        assert_eq!(stats.last_divergent_token.as_deref(), Some("b"));
+    }
+}
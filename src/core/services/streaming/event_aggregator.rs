@@ -47,6 +47,9 @@ pub struct EventStats {
 
     /// Dropped events due to buffer overflow
     pub dropped_events: u64,
+
+    /// Events injected outside of worker connections (REST fallback snapshots)
+    pub injected_events: u64,
 }
 
 impl EventAggregator {
@@ -164,6 +167,21 @@ impl EventAggregator {
         info!("Removed worker {} from event aggregator", worker_id);
     }
 
+    /// Publish an event that did not originate from a worker (e.g. a REST fallback snapshot)
+    pub async fn inject_event(&self, event: PolyEvent) {
+        match self.main_sender.send(event) {
+            Ok(_) => {
+                let mut stats = self.stats.write().await;
+                stats.total_events += 1;
+                stats.events_last_second += 1;
+                stats.injected_events += 1;
+            }
+            Err(_) => {
+                debug!("No receivers for injected event");
+            }
+        }
+    }
+
     /// Get a receiver for the aggregated events
     pub fn subscribe(&self) -> broadcast::Receiver<PolyEvent> {
         self.main_sender.subscribe()
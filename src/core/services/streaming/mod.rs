@@ -3,8 +3,11 @@
 //! This module provides a scalable streaming service that manages multiple
 //! WebSocket connections, with each connection handling a subset of tokens.
 
+pub mod budget;
 pub mod config;
+pub mod consistency;
 pub mod event_aggregator;
+pub mod rest_fallback;
 pub mod service;
 pub mod token_distributor;
 pub mod traits;
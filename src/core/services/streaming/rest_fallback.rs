@@ -0,0 +1,213 @@
+//! REST order book fallback for tokens whose WebSocket data has gone stale
+//!
+//! When a token has not produced any WebSocket event for longer than the
+//! configured threshold, the streaming service polls the CLOB `/book` endpoint
+//! and republishes the result as a synthetic `PolyEvent::Book` tagged with
+//! `BookSource::RestSnapshot`, so consumers keep seeing a (slower) book instead
+//! of going blind.
+
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::core::ws::events::BookEvent;
+use crate::core::ws::{BookSource, PolyEvent};
+
+/// Tracks WebSocket freshness per token and fetches REST snapshots for stale ones
+pub struct RestSnapshotFallback {
+    /// HTTP client used for `/book` requests
+    http: reqwest::Client,
+
+    /// CLOB REST host
+    host: String,
+
+    /// How long a token may go without WebSocket events before it is considered stale
+    stale_after: Duration,
+
+    /// Minimum delay between two REST polls of the same token
+    poll_interval: Duration,
+
+    /// Last WebSocket event time per token
+    last_seen: DashMap<String, Instant>,
+
+    /// Last REST poll time per token
+    last_polled: DashMap<String, Instant>,
+
+    /// Number of synthetic snapshots published
+    snapshots_injected: AtomicU64,
+}
+
+impl RestSnapshotFallback {
+    /// Create a new fallback tracker
+    pub fn new(host: String, stale_after: Duration, poll_interval: Duration) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            host: host.trim_end_matches('/').to_string(),
+            stale_after,
+            poll_interval,
+            last_seen: DashMap::new(),
+            last_polled: DashMap::new(),
+            snapshots_injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Record activity from the WebSocket feed; synthetic snapshots are ignored
+    pub fn record_event(&self, event: &PolyEvent) {
+        if let PolyEvent::Book { source, .. } = event {
+            if source.is_synthetic() {
+                return;
+            }
+        }
+        let Some(asset_id) = event.market_asset_id() else {
+            return;
+        };
+
+        if self.last_polled.remove(asset_id).is_some() {
+            debug!("WebSocket data resumed for {}, stopping REST fallback", asset_id);
+        }
+        self.last_seen.insert(asset_id.to_string(), Instant::now());
+    }
+
+    /// Select the tokens that are stale and due for a REST poll
+    ///
+    /// Tokens seen here for the first time start their staleness clock now,
+    /// giving a fresh subscription time to deliver its initial snapshot.
+    pub fn tokens_due(&self, tokens: &[String]) -> Vec<String> {
+        let now = Instant::now();
+
+        // Forget tokens that are no longer streamed
+        self.last_seen.retain(|token, _| tokens.contains(token));
+        self.last_polled.retain(|token, _| tokens.contains(token));
+
+        tokens
+            .iter()
+            .filter(|token| {
+                let last_seen = *self.last_seen.entry((*token).clone()).or_insert(now);
+                if now.duration_since(last_seen) < self.stale_after {
+                    return false;
+                }
+                self.last_polled
+                    .get(*token)
+                    .map(|polled| now.duration_since(*polled) >= self.poll_interval)
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Fetch a REST snapshot for a token
+    pub async fn fetch_snapshot(&self, token_id: &str) -> Result<BookEvent, anyhow::Error> {
+        self.last_polled.insert(token_id.to_string(), Instant::now());
+
+        let url = format!("{}/book?token_id={}", self.host, token_id);
+        let response = self.http.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            warn!(
+                "REST fallback snapshot for {} failed with {}: {}",
+                token_id, status, body
+            );
+            return Err(anyhow::anyhow!(
+                "REST snapshot request failed with status {}",
+                status
+            ));
+        }
+
+        let mut snapshot: BookEvent = response.json().await?;
+        if snapshot.asset_id.is_empty() {
+            snapshot.asset_id = token_id.to_string();
+        }
+        Ok(snapshot)
+    }
+
+    /// Build the synthetic book event for a snapshot and count it
+    pub fn synthetic_event(&self, snapshot: BookEvent) -> PolyEvent {
+        self.snapshots_injected.fetch_add(1, Ordering::Relaxed);
+
+        PolyEvent::Book {
+            asset_id: snapshot.asset_id,
+            market: snapshot.market,
+            timestamp: snapshot.timestamp,
+            bids: snapshot.bids,
+            asks: snapshot.asks,
+            hash: snapshot.hash,
+            source: BookSource::RestSnapshot,
+        }
+    }
+
+    /// Number of synthetic snapshots published so far
+    pub fn snapshots_injected(&self) -> u64 {
+        self.snapshots_injected.load(Ordering::Relaxed)
+    }
+
+    /// Number of tokens currently being served from REST polls
+    pub fn stale_token_count(&self) -> usize {
+        self.last_polled.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn trade_event(asset_id: &str) -> PolyEvent {
+        PolyEvent::LastTradePrice {
+            asset_id: asset_id.to_string(),
+            price: Decimal::new(55, 2),
+            timestamp: 1_700_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_new_tokens_get_grace_period() {
+        let fallback = RestSnapshotFallback::new(
+            "https://clob.polymarket.com".to_string(),
+            Duration::from_secs(30),
+            Duration::from_secs(5),
+        );
+        let tokens = vec!["token_a".to_string()];
+
+        assert!(fallback.tokens_due(&tokens).is_empty());
+    }
+
+    #[test]
+    fn test_stale_tokens_are_due() {
+        let fallback = RestSnapshotFallback::new(
+            "https://clob.polymarket.com".to_string(),
+            Duration::ZERO,
+            Duration::ZERO,
+        );
+        let tokens = vec!["token_a".to_string(), "token_b".to_string()];
+        fallback.record_event(&trade_event("token_a"));
+
+        assert_eq!(fallback.tokens_due(&tokens), tokens);
+    }
+
+    #[test]
+    fn test_synthetic_books_do_not_refresh_activity() {
+        let fallback = RestSnapshotFallback::new(
+            "https://clob.polymarket.com".to_string(),
+            Duration::from_secs(30),
+            Duration::from_secs(5),
+        );
+        let synthetic = PolyEvent::Book {
+            asset_id: "token_a".to_string(),
+            market: String::new(),
+            timestamp: 0,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            hash: String::new(),
+            source: BookSource::RestSnapshot,
+        };
+
+        fallback.record_event(&synthetic);
+        assert!(fallback.last_seen.get("token_a").is_none());
+
+        fallback.record_event(&trade_event("token_a"));
+        assert!(fallback.last_seen.get("token_a").is_some());
+    }
+}
@@ -4,18 +4,21 @@ use async_trait::async_trait;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 use super::{
     config::StreamingServiceConfig,
+    consistency::{ConsistencyChecker, ConsistencyStats, Divergence, Tolerance, TopOfBook},
     event_aggregator::EventAggregator,
+    rest_fallback::RestSnapshotFallback,
     token_distributor::{DistributionUpdate, TokenDistributor},
     traits::{StreamingServiceTrait, StreamingStats, WorkerStatus as TraitWorkerStatus},
     worker::{StreamerWorker, StreamerWorkerConfig, WorkerStatus},
 };
+use crate::core::diagnostics::{record_event, Subsystem};
 use crate::core::ws::{PolyEvent, OrderBook};
 
 /// Main streaming service that manages multiple WebSocket workers
@@ -44,6 +47,18 @@ pub struct StreamingService {
     /// Statistics collection task
     stats_task: Arc<Mutex<Option<JoinHandle<()>>>>,
 
+    /// REST snapshot fallback for stale tokens
+    rest_fallback: Arc<RestSnapshotFallback>,
+
+    /// REST fallback activity tracking and polling tasks
+    rest_fallback_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+
+    /// Cross-checks of streamed books against REST snapshots
+    consistency: Arc<ConsistencyChecker>,
+
+    /// Consistency check task
+    consistency_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+
     /// Whether the service is running
     is_running: Arc<RwLock<bool>>,
 }
@@ -53,6 +68,19 @@ impl StreamingService {
     pub fn new(config: StreamingServiceConfig) -> Arc<Self> {
         let distributor = Arc::new(Mutex::new(TokenDistributor::new(config.tokens_per_worker)));
         let aggregator = Arc::new(EventAggregator::new(config.event_buffer_size));
+        let rest_fallback = Arc::new(RestSnapshotFallback::new(
+            config.host.clone(),
+            Duration::from_secs(config.rest_fallback_stale_secs),
+            Duration::from_secs(config.rest_fallback_poll_interval_secs),
+        ));
+        let consistency = Arc::new(ConsistencyChecker::new(
+            config.host.clone(),
+            config.consistency_sample_size,
+            Tolerance {
+                price: Decimal::try_from(config.consistency_price_tolerance).unwrap_or_default(),
+                size: config.consistency_size_tolerance,
+            },
+        ));
 
         Arc::new(Self {
             config,
@@ -63,6 +91,10 @@ impl StreamingService {
             start_time: Instant::now(),
             health_check_task: Arc::new(Mutex::new(None)),
             stats_task: Arc::new(Mutex::new(None)),
+            rest_fallback,
+            rest_fallback_tasks: Arc::new(Mutex::new(Vec::new())),
+            consistency,
+            consistency_task: Arc::new(Mutex::new(None)),
             is_running: Arc::new(RwLock::new(false)),
         })
     }
@@ -85,6 +117,14 @@ impl StreamingService {
         // Start background tasks
         self.start_health_check_task().await;
         self.start_stats_collection_task().await;
+        // A replay is played as recorded; live snapshots would only overwrite it
+        let replaying = self.config.replay_feed.is_some();
+        if self.config.rest_fallback_enabled && !replaying {
+            self.start_rest_fallback_tasks().await;
+        }
+        if self.config.consistency_check_enabled && !replaying {
+            self.start_consistency_task().await;
+        }
 
         info!("StreamingService started successfully");
         Ok(())
@@ -225,6 +265,7 @@ impl StreamingService {
             max_reconnect_delay_ms: self.config.max_reconnect_delay_ms,
             max_reconnect_attempts: self.config.max_reconnect_attempts,
             event_buffer_size: self.config.worker_event_buffer_size,
+            replay_feed: self.config.replay_feed.clone(),
         };
 
         Ok(Arc::new(StreamerWorker::new(worker_id, worker_config)))
@@ -272,6 +313,8 @@ impl StreamingService {
         let workers = Arc::clone(&self.workers);
         let stats = Arc::clone(&self.stats);
         let aggregator = Arc::clone(&self.aggregator);
+        let rest_fallback = Arc::clone(&self.rest_fallback);
+        let consistency = Arc::clone(&self.consistency);
         let start_time = self.start_time;
         let is_running = Arc::clone(&self.is_running);
         let interval_secs = self.config.stats_interval_secs;
@@ -314,6 +357,14 @@ impl StreamingService {
                 let aggregator_stats = aggregator.get_stats().await;
                 new_stats.events_per_second = aggregator_stats.events_per_second;
 
+                new_stats.rest_snapshots_injected = rest_fallback.snapshots_injected();
+                new_stats.rest_fallback_tokens = rest_fallback.stale_token_count();
+
+                let consistency_stats = consistency.stats();
+                new_stats.consistency_checks = consistency_stats.checks;
+                new_stats.consistency_resyncs = consistency_stats.resyncs;
+                new_stats.max_top_of_book_gap = consistency_stats.max_price_gap;
+
                 // Update stats
                 *stats.write().await = new_stats;
             }
@@ -321,6 +372,226 @@ impl StreamingService {
 
         *self.stats_task.lock().await = Some(task);
     }
+
+    /// Start the REST snapshot fallback: one task tracks WebSocket activity per
+    /// token, the other polls `/book` for tokens that have gone stale and
+    /// publishes the results as synthetic book events.
+    async fn start_rest_fallback_tasks(&self) {
+        info!(
+            "REST snapshot fallback enabled: stale after {}s, polling every {}s",
+            self.config.rest_fallback_stale_secs, self.config.rest_fallback_poll_interval_secs
+        );
+
+        let mut events = self.aggregator.subscribe();
+        let rest_fallback = Arc::clone(&self.rest_fallback);
+        let is_running = Arc::clone(&self.is_running);
+
+        let activity_task = tokio::spawn(async move {
+            while *is_running.read().await {
+                match events.recv().await {
+                    Ok(event) => rest_fallback.record_event(&event),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("REST fallback activity tracker lagged by {} events", skipped);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let workers = Arc::clone(&self.workers);
+        let distributor = Arc::clone(&self.distributor);
+        let aggregator = Arc::clone(&self.aggregator);
+        let rest_fallback = Arc::clone(&self.rest_fallback);
+        let is_running = Arc::clone(&self.is_running);
+        let max_per_cycle = self.config.rest_fallback_max_tokens_per_cycle;
+        let check_interval = Duration::from_secs(self.config.rest_fallback_poll_interval_secs.max(1));
+
+        let poll_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+
+            while *is_running.read().await {
+                interval.tick().await;
+
+                let streaming_tokens = {
+                    let workers = workers.read().await;
+                    let mut tokens = Vec::new();
+                    for worker in workers.values() {
+                        tokens.extend(worker.get_assigned_tokens().await);
+                    }
+                    tokens
+                };
+
+                let due = rest_fallback.tokens_due(&streaming_tokens);
+                if due.is_empty() {
+                    continue;
+                }
+
+                warn!(
+                    "{} tokens have stale WebSocket data, polling REST snapshots for up to {}",
+                    due.len(),
+                    max_per_cycle
+                );
+
+                for token_id in due.into_iter().take(max_per_cycle) {
+                    let snapshot = match rest_fallback.fetch_snapshot(&token_id).await {
+                        Ok(snapshot) => snapshot,
+                        Err(e) => {
+                            debug!("REST fallback snapshot failed for {}: {}", token_id, e);
+                            continue;
+                        }
+                    };
+
+                    let worker_id = distributor.lock().await.get_worker_for_token(&token_id);
+                    let mut applied = true;
+                    if let Some(worker_id) = worker_id {
+                        if let Some(worker) = workers.read().await.get(&worker_id) {
+                            applied = worker
+                                .apply_rest_snapshot(
+                                    &token_id,
+                                    snapshot.market.clone(),
+                                    snapshot.timestamp,
+                                    snapshot.bids.clone(),
+                                    snapshot.asks.clone(),
+                                )
+                                .is_some();
+                        }
+                    }
+                    // The feed delivered a newer book while the snapshot was fetched
+                    if !applied {
+                        continue;
+                    }
+
+                    debug!(
+                        "Publishing REST fallback snapshot for {}: {} bids, {} asks",
+                        token_id,
+                        snapshot.bids.len(),
+                        snapshot.asks.len()
+                    );
+                    aggregator
+                        .inject_event(rest_fallback.synthetic_event(snapshot))
+                        .await;
+                }
+            }
+        });
+
+        let mut tasks = self.rest_fallback_tasks.lock().await;
+        tasks.push(activity_task);
+        tasks.push(poll_task);
+    }
+
+    /// Start the consistency checker: each cycle compares the top of book of
+    /// a rotating sample of streamed tokens with REST snapshots and resyncs
+    /// the books that diverged beyond tolerance on two consecutive checks.
+    async fn start_consistency_task(&self) {
+        info!(
+            "Order book consistency checks enabled: {} tokens every {}s",
+            self.config.consistency_sample_size, self.config.consistency_check_interval_secs
+        );
+
+        let workers = Arc::clone(&self.workers);
+        let distributor = Arc::clone(&self.distributor);
+        let aggregator = Arc::clone(&self.aggregator);
+        let consistency = Arc::clone(&self.consistency);
+        let is_running = Arc::clone(&self.is_running);
+        let check_interval =
+            Duration::from_secs(self.config.consistency_check_interval_secs.max(1));
+
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            // The first tick is immediate; give new subscriptions time to deliver their books
+            interval.tick().await;
+
+            while *is_running.read().await {
+                interval.tick().await;
+
+                let streaming_tokens = {
+                    let workers = workers.read().await;
+                    let mut tokens = Vec::new();
+                    for worker in workers.values() {
+                        tokens.extend(worker.get_assigned_tokens().await);
+                    }
+                    tokens
+                };
+
+                for token_id in consistency.next_sample(&streaming_tokens) {
+                    let Some(worker_id) = distributor.lock().await.get_worker_for_token(&token_id)
+                    else {
+                        continue;
+                    };
+                    let Some(worker) = workers.read().await.get(&worker_id).cloned() else {
+                        continue;
+                    };
+                    let snapshot = match consistency.fetch_book(&token_id).await {
+                        Ok(snapshot) => snapshot,
+                        Err(e) => {
+                            debug!("Consistency check snapshot failed for {}: {}", token_id, e);
+                            continue;
+                        }
+                    };
+                    // Read the streamed book after the fetch, as close to the snapshot as possible
+                    let Some(book) = worker.get_order_book(&token_id).await else {
+                        continue;
+                    };
+
+                    let divergence = Divergence::between(
+                        &TopOfBook::of(&book),
+                        &TopOfBook::of_levels(&snapshot.bids, &snapshot.asks),
+                    );
+                    let diverged = divergence.exceeds(consistency.tolerance());
+                    // A single divergent check may be a delta in flight; resync on the second
+                    if !consistency.confirm(&token_id, diverged) {
+                        consistency.record(&token_id, &divergence, false);
+                        if diverged {
+                            debug!(
+                                "Book of {} diverged from REST ({}), checking again",
+                                token_id,
+                                divergence.describe()
+                            );
+                        }
+                        continue;
+                    }
+                    let applied = worker.apply_rest_snapshot(
+                        &token_id,
+                        snapshot.market.clone(),
+                        snapshot.timestamp,
+                        snapshot.bids.clone(),
+                        snapshot.asks.clone(),
+                    );
+                    // None when the streamed book is newer than the snapshot it was compared with
+                    consistency.record(&token_id, &divergence, applied.is_some());
+                    if applied.is_none() {
+                        continue;
+                    }
+
+                    warn!(
+                        "Worker {} book for {} diverged from REST ({}), resyncing",
+                        worker_id,
+                        token_id,
+                        divergence.describe()
+                    );
+                    record_event(
+                        Subsystem::Ws,
+                        format!(
+                            "resynced book of {} on worker {}: {}",
+                            token_id,
+                            worker_id,
+                            divergence.describe()
+                        ),
+                    );
+                    aggregator
+                        .inject_event(ConsistencyChecker::resync_event(snapshot))
+                        .await;
+                }
+            }
+        });
+
+        *self.consistency_task.lock().await = Some(task);
+    }
+
+    /// Divergence metrics of the consistency checker
+    pub fn consistency_stats(&self) -> ConsistencyStats {
+        self.consistency.stats()
+    }
 }
 
 #[async_trait]
@@ -339,6 +610,17 @@ impl StreamingServiceTrait for StreamingService {
         Ok(())
     }
 
+    async fn remove_tokens(&self, tokens: Vec<String>) -> Result<(), anyhow::Error> {
+        info!("Removing {} tokens from streaming service", tokens.len());
+
+        let update = {
+            let mut distributor = self.distributor.lock().await;
+            distributor.remove_tokens(tokens)
+        };
+
+        self.apply_distribution_update(update).await
+    }
+
     async fn get_streaming_tokens(&self) -> Vec<String> {
         let workers = self.workers.read().await;
         let mut all_tokens = Vec::new();
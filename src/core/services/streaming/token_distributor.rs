@@ -98,7 +98,6 @@ impl TokenDistributor {
     }
 
     /// Remove tokens and return distribution changes
-    #[allow(dead_code)]
     pub fn remove_tokens(&mut self, tokens: Vec<String>) -> DistributionUpdate {
         let mut update = DistributionUpdate::new();
 
@@ -3,12 +3,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
 use crate::core::ws::{PolyEvent, OrderBook};
 
 /// Statistics about the streaming service
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StreamingStats {
     /// Total number of active WebSocket connections
     pub active_connections: usize,
@@ -30,6 +31,21 @@ pub struct StreamingStats {
 
     /// Uptime in seconds
     pub uptime_seconds: u64,
+
+    /// Synthetic REST snapshots published for stale tokens
+    pub rest_snapshots_injected: u64,
+
+    /// Tokens currently served by the REST fallback
+    pub rest_fallback_tokens: usize,
+
+    /// Streamed books compared with a REST snapshot
+    pub consistency_checks: u64,
+
+    /// Books found beyond the consistency tolerance and resynced
+    pub consistency_resyncs: u64,
+
+    /// Largest best price gap between a streamed and a REST book
+    pub max_top_of_book_gap: f64,
 }
 
 /// Worker status information
@@ -60,6 +76,9 @@ pub trait StreamingServiceTrait: Send + Sync {
     /// Add tokens to stream (will be distributed across workers)
     async fn add_tokens(&self, tokens: Vec<String>) -> Result<()>;
 
+    /// Stop streaming tokens, shutting down workers left without any
+    async fn remove_tokens(&self, tokens: Vec<String>) -> Result<()>;
+
     /// Get all currently streaming tokens
     async fn get_streaming_tokens(&self) -> Vec<String>;
 
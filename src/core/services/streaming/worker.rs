@@ -9,6 +9,9 @@ use tokio::sync::{broadcast, Mutex, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+use crate::core::diagnostics::{record_event, Subsystem};
+use crate::core::types::market::PriceLevel;
+use crate::markets::datasets::replay::ReplayFeed;
 use crate::core::ws::{
     WsClient, WsConfig,
     parse_message, PolyEvent, WsMessage,
@@ -77,6 +80,8 @@ pub struct StreamerWorkerConfig {
     pub max_reconnect_delay_ms: u64,
     pub max_reconnect_attempts: u32,
     pub event_buffer_size: usize,
+    /// Take events from this capture replay instead of a WebSocket
+    pub replay_feed: Option<ReplayFeed>,
 }
 
 /// Worker statistics
@@ -152,7 +157,10 @@ impl StreamerWorker {
         *self.shutdown_tx.lock().await = Some(shutdown_tx);
 
         // Start the worker task
-        let task = self.spawn_worker_task(tokens, shutdown_rx).await?;
+        let task = match &self.config.replay_feed {
+            Some(feed) => self.spawn_replay_task(feed, tokens, shutdown_rx).await,
+            None => self.spawn_worker_task(tokens, shutdown_rx).await?,
+        };
         *self.task_handle.lock().await = Some(task);
 
         Ok(())
@@ -247,6 +255,40 @@ impl StreamerWorker {
         self.stats.read().await.clone()
     }
 
+    /// Replace a token's order book with a REST snapshot while its WebSocket feed is stale
+    ///
+    /// Returns `None`, leaving the book alone, when the streamed book is at
+    /// least as recent as the snapshot: the feed moved on while it was fetched.
+    pub fn apply_rest_snapshot(
+        &self,
+        asset_id: &str,
+        market: String,
+        timestamp: u64,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+    ) -> Option<OrderBook> {
+        let mut book = self
+            .order_books
+            .entry(asset_id.to_string())
+            .or_insert_with(|| OrderBook::new(asset_id.to_string()));
+        if book.timestamp != 0 && book.timestamp >= timestamp {
+            debug!(
+                "Worker {} skipped the REST snapshot of {} at {}, its book is from {}",
+                self.worker_id, asset_id, timestamp, book.timestamp
+            );
+            return None;
+        }
+
+        book.replace_with_snapshot_no_hash(market, timestamp, bids, asks);
+        if book.validate_and_clean() {
+            warn!(
+                "Worker {} REST snapshot for {} was cleaned due to crossed market",
+                self.worker_id, asset_id
+            );
+        }
+
+        Some(book.clone())
+    }
 
     /// Restart worker with new tokens
     async fn restart_with_tokens(&self, tokens: Vec<String>) -> Result<(), anyhow::Error> {
@@ -313,6 +355,7 @@ impl StreamerWorker {
                     }
                     Err(e) => {
                         error!("Worker {} connection failed: {}", worker_id, e);
+                        record_event(Subsystem::Ws, format!("worker {} connection failed: {}", worker_id, e));
 
                         // Update stats
                         {
@@ -332,6 +375,10 @@ impl StreamerWorker {
                                 "Worker {} giving up after {} attempts",
                                 worker_id, reconnect_attempts
                             );
+                            record_event(
+                                Subsystem::Ws,
+                                format!("worker {} gave up after {} attempts", worker_id, reconnect_attempts),
+                            );
                             let mut status = status.write().await;
                             *status = WorkerStatus::Failed {
                                 error: e.to_string(),
@@ -397,6 +444,49 @@ impl StreamerWorker {
         Ok(task)
     }
 
+    /// Spawn the task taking the assigned tokens' events from a replay feed
+    async fn spawn_replay_task(
+        &self,
+        feed: &ReplayFeed,
+        tokens: Vec<String>,
+        mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+    ) -> JoinHandle<()> {
+        let worker_id = self.worker_id;
+        let event_sender = self.event_sender.clone();
+        let order_books = Arc::clone(&self.order_books);
+        let last_trade_prices = Arc::clone(&self.last_trade_prices);
+        let stats = Arc::clone(&self.stats);
+        let token_count = tokens.len();
+        // Subscribed before returning so nothing played after start is missed
+        let mut events = feed.subscribe(tokens.into_iter().collect());
+
+        *self.status.write().await = WorkerStatus::Connected;
+        info!("Worker {} replaying {} tokens", worker_id, token_count);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    received = events.recv() => match received {
+                        Some(event) => {
+                            // Recorded books were hash checked when they were streamed
+                            Self::apply_event(
+                                &event,
+                                &order_books,
+                                &last_trade_prices,
+                                true,
+                                true,
+                            );
+                            Self::publish_event(worker_id, event, &event_sender, &stats).await;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            info!("Worker {} replay ended", worker_id);
+        })
+    }
+
     /// Connect to WebSocket and stream events
     async fn connect_and_stream(
         worker_id: usize,
@@ -433,6 +523,10 @@ impl StreamerWorker {
             worker_id,
             tokens.len()
         );
+        record_event(
+            Subsystem::Ws,
+            format!("worker {} connected, {} tokens", worker_id, tokens.len()),
+        );
 
         // Get message receiver
         let client = ws_client.lock().await;
@@ -492,103 +586,124 @@ impl StreamerWorker {
         match parse_message(&ws_message) {
             Ok(events) => {
                 for event in events {
-                    // Update local state
-                    match &event {
-                        PolyEvent::Book {
-                            asset_id,
-                            market,
-                            timestamp,
-                            bids,
-                            asks,
-                            hash,
-                        } => {
-                            let mut book = order_books
-                                .entry(asset_id.clone())
-                                .or_insert_with(|| OrderBook::new(asset_id.clone()));
-                            
-                            // Get the skip_hash_verification and quiet_hash_mismatch flags from config
-                            let skip_hash = config.ws_config.skip_hash_verification;
-                            let quiet_hash_mismatch = config.ws_config.quiet_hash_mismatch;
-                            
-                            // Apply snapshot based on hash verification setting
-                            if skip_hash {
-                                book.replace_with_snapshot_no_hash(
-                                    market.clone(),
-                                    *timestamp,
-                                    bids.clone(),
-                                    asks.clone(),
-                                );
-                                debug!(
-                                    "Order book snapshot applied (no hash verification) for {}",
-                                    asset_id
-                                );
-                            } else {
-                                if let Err(e) = book.replace_with_snapshot(
-                                    market.clone(),
-                                    *timestamp,
-                                    bids.clone(),
-                                    asks.clone(),
-                                    hash.clone(),
-                                ) {
-                                    if !quiet_hash_mismatch {
-                                        warn!(
-                                            "Failed to apply book snapshot with hash verification: {}",
-                                            e
-                                        );
-                                    }
-                                    // Fallback to no-hash method if verification fails
-                                    book.replace_with_snapshot_no_hash(
-                                        market.clone(),
-                                        *timestamp,
-                                        bids.clone(),
-                                        asks.clone(),
-                                    );
-                                    debug!(
-                                        "Order book snapshot applied (fallback no hash) for {}",
-                                        asset_id
-                                    );
-                                } else {
-                                    debug!("Order book snapshot applied successfully for {}", asset_id);
-                                }
-                            }
-                        }
-                        PolyEvent::PriceChange {
-                            asset_id,
-                            side,
-                            price,
-                            size,
-                            ..
-                        } => {
-                            if let Some(mut book) = order_books.get_mut(asset_id) {
-                                book.apply_price_change_no_hash(*side, *price, *size);
-                            }
-                        }
-                        PolyEvent::LastTradePrice {
-                            asset_id,
-                            price,
-                            timestamp,
-                        } => {
-                            last_trade_prices.insert(asset_id.clone(), (*price, *timestamp));
-                        }
-                        _ => {}
-                    }
+                    Self::apply_event(
+                        &event,
+                        order_books,
+                        last_trade_prices,
+                        config.ws_config.skip_hash_verification,
+                        config.ws_config.quiet_hash_mismatch,
+                    );
+                    Self::publish_event(worker_id, event, event_sender, stats).await;
+                }
+            }
+            Err(e) => {
+                warn!("Worker {} failed to parse message: {}", worker_id, e);
+            }
+        }
+    }
 
-                    // Broadcast event
-                    match event_sender.send(event) {
-                        Ok(_) => {
-                            // Update stats
-                            let mut stats = stats.write().await;
-                            stats.events_processed += 1;
-                            stats.last_activity = Some(Instant::now());
-                        }
-                        Err(_) => {
-                            debug!("Worker {} no event receivers", worker_id);
+    /// Update local state with an event
+    fn apply_event(
+        event: &PolyEvent,
+        order_books: &DashMap<String, OrderBook>,
+        last_trade_prices: &DashMap<String, (Decimal, u64)>,
+        skip_hash: bool,
+        quiet_hash_mismatch: bool,
+    ) {
+        match event {
+            PolyEvent::Book {
+                asset_id,
+                market,
+                timestamp,
+                bids,
+                asks,
+                hash,
+                ..
+            } => {
+                let mut book = order_books
+                    .entry(asset_id.clone())
+                    .or_insert_with(|| OrderBook::new(asset_id.clone()));
+
+                // Apply snapshot based on hash verification setting
+                if skip_hash {
+                    book.replace_with_snapshot_no_hash(
+                        market.clone(),
+                        *timestamp,
+                        bids.clone(),
+                        asks.clone(),
+                    );
+                    debug!(
+                        "Order book snapshot applied (no hash verification) for {}",
+                        asset_id
+                    );
+                } else {
+                    if let Err(e) = book.replace_with_snapshot(
+                        market.clone(),
+                        *timestamp,
+                        bids.clone(),
+                        asks.clone(),
+                        hash.clone(),
+                    ) {
+                        if !quiet_hash_mismatch {
+                            warn!(
+                                "Failed to apply book snapshot with hash verification: {}",
+                                e
+                            );
                         }
+                        // Fallback to no-hash method if verification fails
+                        book.replace_with_snapshot_no_hash(
+                            market.clone(),
+                            *timestamp,
+                            bids.clone(),
+                            asks.clone(),
+                        );
+                        debug!(
+                            "Order book snapshot applied (fallback no hash) for {}",
+                            asset_id
+                        );
+                    } else {
+                        debug!("Order book snapshot applied successfully for {}", asset_id);
                     }
                 }
             }
-            Err(e) => {
-                warn!("Worker {} failed to parse message: {}", worker_id, e);
+            PolyEvent::PriceChange {
+                asset_id,
+                side,
+                price,
+                size,
+                ..
+            } => {
+                if let Some(mut book) = order_books.get_mut(asset_id) {
+                    book.apply_price_change_no_hash(*side, *price, *size);
+                }
+            }
+            PolyEvent::LastTradePrice {
+                asset_id,
+                price,
+                timestamp,
+            } => {
+                last_trade_prices.insert(asset_id.clone(), (*price, *timestamp));
+            }
+            _ => {}
+        }
+    }
+
+    /// Broadcast an event and count it in the stats
+    async fn publish_event(
+        worker_id: usize,
+        event: PolyEvent,
+        event_sender: &broadcast::Sender<PolyEvent>,
+        stats: &Arc<RwLock<WorkerStats>>,
+    ) {
+        match event_sender.send(event) {
+            Ok(_) => {
+                // Update stats
+                let mut stats = stats.write().await;
+                stats.events_processed += 1;
+                stats.last_activity = Some(Instant::now());
+            }
+            Err(_) => {
+                debug!("Worker {} no event receivers", worker_id);
             }
         }
     }
@@ -0,0 +1,452 @@
+//! Session activity and P&L report
+//!
+//! [`start`] gives the process a session id, which the audit log stamps on
+//! every entry it appends. When the command ends, [`finish`] collects the
+//! session's order entries from the audit log and the fills of the orders it
+//! placed from the trade history, and saves the resulting [`SessionReport`]
+//! to `<data_dir>/sessions/<id>.json` for `polybot session summary`.
+//! Sessions that neither placed nor cancelled orders have nothing to report;
+//! the audit log notes order entries as it appends them, so those sessions
+//! end without reading the log.
+//!
+//! Realized P&L is computed on the session's own fills with average cost per
+//! token: shares sold beyond what the session bought were opened earlier and
+//! are counted separately rather than guessed at.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::core::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::core::portfolio::cli::get_portfolio_service_handle;
+use crate::core::portfolio::types::{OrderSide, TradeExecution};
+use crate::data_paths::DataPaths;
+
+const SESSIONS_DIR: &str = "sessions";
+
+/// Give up on the fills rather than hold up the exit
+const FILL_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Distinct error messages kept in a report
+const MAX_ERRORS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub command: String,
+    pub started_at: DateTime<Utc>,
+    pub pid: u32,
+}
+
+static SESSION: OnceLock<Session> = OnceLock::new();
+
+/// Set once this process appends an order placement or cancel to the audit log
+static TOUCHED_ORDERS: AtomicBool = AtomicBool::new(false);
+
+/// Start the process's session; called once before the command runs
+pub fn start(command: &str) {
+    let session = Session {
+        id: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+        command: command.to_string(),
+        started_at: Utc::now(),
+        pid: std::process::id(),
+    };
+    if SESSION.set(session).is_err() {
+        warn!("Session already started");
+    }
+}
+
+pub fn current() -> Option<&'static Session> {
+    SESSION.get()
+}
+
+pub fn current_id() -> Option<String> {
+    SESSION.get().map(|session| session.id.clone())
+}
+
+/// Note an action the audit log appended for this process
+pub fn record_action(action: &AuditAction) {
+    if matches!(action, AuditAction::OrderPlaced | AuditAction::OrderCancelled) {
+        TOUCHED_ORDERS.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorCount {
+    pub message: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub session: Session,
+    pub ended_at: DateTime<Utc>,
+    pub orders_placed: usize,
+    pub orders_rejected: usize,
+    pub orders_cancelled: usize,
+    pub cancels_failed: usize,
+    /// Placed orders with at least one fill
+    pub orders_filled: usize,
+    pub fills: usize,
+    /// Shares bought and sold
+    pub shares_traded: Decimal,
+    /// Notional of all fills
+    pub volume: Decimal,
+    /// Sells against the session's average buy cost, before fees
+    pub realized_pnl: Decimal,
+    pub fees: Decimal,
+    /// Largest cost of the session's open positions at any point
+    pub max_exposure: Decimal,
+    /// Shares sold that the session didn't buy, left out of the P&L
+    pub unmatched_sell_shares: Decimal,
+    /// False when the trade history couldn't be fetched; fill figures are then zero
+    pub fills_available: bool,
+    /// Failed actions, most frequent first
+    pub errors: Vec<ErrorCount>,
+}
+
+/// Open shares of a token bought during the session and what they cost
+#[derive(Default)]
+struct Position {
+    shares: Decimal,
+    cost: Decimal,
+}
+
+impl SessionReport {
+    /// Summarize `session`'s audit entries and the fills of its orders;
+    /// `fills` is `None` when the trade history wasn't available
+    pub fn build(
+        session: Session,
+        entries: &[AuditEntry],
+        fills: Option<&[TradeExecution]>,
+        ended_at: DateTime<Utc>,
+    ) -> Self {
+        let entries: Vec<&AuditEntry> = entries
+            .iter()
+            .filter(|entry| entry.session.as_deref() == Some(session.id.as_str()))
+            .collect();
+
+        let mut report = Self {
+            session,
+            ended_at,
+            orders_placed: 0,
+            orders_rejected: 0,
+            orders_cancelled: 0,
+            cancels_failed: 0,
+            orders_filled: 0,
+            fills: 0,
+            shares_traded: Decimal::ZERO,
+            volume: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            max_exposure: Decimal::ZERO,
+            unmatched_sell_shares: Decimal::ZERO,
+            fills_available: fills.is_some(),
+            errors: Vec::new(),
+        };
+
+        let mut errors: Vec<ErrorCount> = Vec::new();
+        for entry in &entries {
+            match (entry.action, entry.success) {
+                (AuditAction::OrderPlaced, true) => report.orders_placed += 1,
+                (AuditAction::OrderPlaced, false) => report.orders_rejected += 1,
+                (AuditAction::OrderCancelled, true) => report.orders_cancelled += 1,
+                (AuditAction::OrderCancelled, false) => report.cancels_failed += 1,
+                _ => {}
+            }
+            if let Some(error) = entry.error.as_deref().filter(|_| !entry.success) {
+                match errors.iter_mut().find(|e| e.message == error) {
+                    Some(existing) => existing.count += 1,
+                    None => errors.push(ErrorCount {
+                        message: error.to_string(),
+                        count: 1,
+                    }),
+                }
+            }
+        }
+        // Stable, so equally frequent errors stay in the order they first happened
+        errors.sort_by(|a, b| b.count.cmp(&a.count));
+        errors.truncate(MAX_ERRORS);
+        report.errors = errors;
+
+        let placed = placed_order_ids(entries.iter().copied());
+        let mut fills: Vec<&TradeExecution> = fills
+            .unwrap_or_default()
+            .iter()
+            .filter(|fill| placed.contains(fill.order_id.as_str()))
+            .collect();
+        fills.sort_by_key(|fill| fill.timestamp);
+
+        let mut filled_orders = HashSet::new();
+        let mut positions: HashMap<&str, Position> = HashMap::new();
+        for fill in fills {
+            filled_orders.insert(fill.order_id.as_str());
+            report.fills += 1;
+            report.shares_traded += fill.size;
+            report.volume += fill.price * fill.size;
+            report.fees += fill.fee;
+
+            let position = positions.entry(fill.token_id.as_str()).or_default();
+            match fill.side {
+                OrderSide::Buy => {
+                    position.shares += fill.size;
+                    position.cost += fill.price * fill.size;
+                }
+                OrderSide::Sell => {
+                    let matched = fill.size.min(position.shares);
+                    if matched > Decimal::ZERO {
+                        let average = position.cost / position.shares;
+                        report.realized_pnl += (fill.price - average) * matched;
+                        position.cost -= average * matched;
+                        position.shares -= matched;
+                    }
+                    report.unmatched_sell_shares += fill.size - matched;
+                }
+            }
+            let exposure: Decimal = positions.values().map(|p| p.cost).sum();
+            report.max_exposure = report.max_exposure.max(exposure);
+        }
+        report.orders_filled = filled_orders.len();
+        report
+    }
+
+    pub fn duration(&self) -> chrono::Duration {
+        self.ended_at - self.session.started_at
+    }
+
+    pub fn net_pnl(&self) -> Decimal {
+        self.realized_pnl - self.fees
+    }
+}
+
+/// Ids of the orders the entries record as placed
+fn placed_order_ids<'a>(entries: impl Iterator<Item = &'a AuditEntry>) -> HashSet<&'a str> {
+    entries
+        .filter(|entry| entry.action == AuditAction::OrderPlaced && entry.success)
+        .filter_map(|entry| entry.details.get("order_id").and_then(|id| id.as_str()))
+        .collect()
+}
+
+/// Build and save the report of the process's session, if it touched any orders
+pub async fn finish(host: &str, data_paths: &DataPaths) -> Result<Option<SessionReport>> {
+    let Some(session) = current() else {
+        return Ok(None);
+    };
+    // Most commands never touch orders; they shouldn't parse the whole log
+    if !TOUCHED_ORDERS.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    let entries: Vec<AuditEntry> = AuditLog::new(data_paths)
+        .entries()?
+        .into_iter()
+        .filter(|entry| entry.session.as_deref() == Some(session.id.as_str()))
+        .collect();
+
+    let fills = if placed_order_ids(entries.iter()).is_empty() {
+        Some(Vec::new())
+    } else {
+        match tokio::time::timeout(
+            FILL_FETCH_TIMEOUT,
+            fetch_fills(host, data_paths, session.started_at),
+        )
+        .await
+        {
+            Ok(Ok(fills)) => Some(fills),
+            Ok(Err(e)) => {
+                warn!("Session report without fills: {:#}", e);
+                None
+            }
+            Err(_) => {
+                warn!("Session report without fills: trade history timed out");
+                None
+            }
+        }
+    };
+
+    let report = SessionReport::build(session.clone(), &entries, fills.as_deref(), Utc::now());
+    save(data_paths, &report)?;
+    Ok(Some(report))
+}
+
+async fn fetch_fills(
+    host: &str,
+    data_paths: &DataPaths,
+    since: DateTime<Utc>,
+) -> Result<Vec<TradeExecution>> {
+    let service = get_portfolio_service_handle(host, data_paths).await?;
+    service.get_trade_history(Some(since), None).await
+}
+
+fn sessions_dir(data_paths: &DataPaths) -> PathBuf {
+    data_paths.root().join(SESSIONS_DIR)
+}
+
+pub fn save(data_paths: &DataPaths, report: &SessionReport) -> Result<PathBuf> {
+    let dir = sessions_dir(data_paths);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", report.session.id));
+    std::fs::write(&path, serde_json::to_string_pretty(report)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Saved reports, most recent first
+pub fn load_reports(data_paths: &DataPaths) -> Result<Vec<SessionReport>> {
+    let dir = sessions_dir(data_paths);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reports = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        match serde_json::from_str::<SessionReport>(&content) {
+            Ok(report) => reports.push(report),
+            Err(e) => warn!(
+                "Skipping unreadable session report {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    reports.sort_by(|a, b| b.ended_at.cmp(&a.ended_at));
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn entry(
+        session: &str,
+        action: AuditAction,
+        details: serde_json::Value,
+        error: Option<&str>,
+    ) -> AuditEntry {
+        AuditEntry {
+            seq: 0,
+            timestamp: Utc::now(),
+            action,
+            context: crate::core::audit::AuditContext::Unknown,
+            session: Some(session.to_string()),
+            summary: String::new(),
+            details,
+            success: error.is_none(),
+            error: error.map(String::from),
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    fn fill(
+        order_id: &str,
+        side: OrderSide,
+        price: Decimal,
+        size: Decimal,
+        minute: i64,
+    ) -> TradeExecution {
+        TradeExecution {
+            trade_id: format!("{}-{}", order_id, minute),
+            order_id: order_id.to_string(),
+            market_id: "m".to_string(),
+            token_id: "t".to_string(),
+            side,
+            price,
+            size,
+            fee: dec!(0.1),
+            timestamp: DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minute),
+            is_maker: false,
+        }
+    }
+
+    #[test]
+    fn report_counts_orders_and_realizes_pnl_on_session_fills() {
+        let session = Session {
+            id: "s1".to_string(),
+            command: "daemon".to_string(),
+            started_at: DateTime::<Utc>::UNIX_EPOCH,
+            pid: 1,
+        };
+        let entries = vec![
+            entry(
+                "s1",
+                AuditAction::OrderPlaced,
+                json!({ "order_id": "a" }),
+                None,
+            ),
+            entry(
+                "s1",
+                AuditAction::OrderPlaced,
+                json!({ "order_id": "b" }),
+                None,
+            ),
+            entry(
+                "s1",
+                AuditAction::OrderPlaced,
+                json!({ "order_id": null }),
+                Some("not enough balance"),
+            ),
+            entry(
+                "s1",
+                AuditAction::OrderPlaced,
+                json!({ "order_id": null }),
+                Some("not enough balance"),
+            ),
+            entry(
+                "s1",
+                AuditAction::OrderCancelled,
+                json!({ "order_id": "a" }),
+                None,
+            ),
+            entry(
+                "other",
+                AuditAction::OrderPlaced,
+                json!({ "order_id": "x" }),
+                None,
+            ),
+        ];
+        let fills = vec![
+            fill("a", OrderSide::Buy, dec!(0.40), dec!(100), 1),
+            fill("a", OrderSide::Buy, dec!(0.50), dec!(100), 2),
+            fill("b", OrderSide::Sell, dec!(0.60), dec!(250), 3),
+            // Another session's order
+            fill("x", OrderSide::Buy, dec!(0.10), dec!(1000), 4),
+        ];
+
+        let report = SessionReport::build(session, &entries, Some(&fills), Utc::now());
+        assert_eq!(
+            (
+                report.orders_placed,
+                report.orders_rejected,
+                report.orders_cancelled
+            ),
+            (2, 2, 1)
+        );
+        assert_eq!((report.orders_filled, report.fills), (2, 3));
+        assert_eq!(report.volume, dec!(240));
+        assert_eq!(report.max_exposure, dec!(90));
+        // 200 shares at an average of 0.45 sold at 0.60; 50 more were opened earlier
+        assert_eq!(report.realized_pnl, dec!(30));
+        assert_eq!(report.unmatched_sell_shares, dec!(50));
+        assert_eq!(report.net_pnl(), dec!(29.7));
+        assert_eq!(
+            report.errors,
+            vec![ErrorCount {
+                message: "not enough balance".to_string(),
+                count: 2
+            }]
+        );
+    }
+}
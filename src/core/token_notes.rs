@@ -0,0 +1,315 @@
+//! Per-token notes and price alerts
+//!
+//! A token can carry a short note and an alert above and/or below a price,
+//! kept in `token_notes.json` in the profile's data directory. An alert is
+//! armed while a threshold is set and it hasn't fired; it fires once when the
+//! token's price (mid of the streamed bid and ask, else the last trade) crosses
+//! a threshold, and stays disarmed until the thresholds are edited. A fired
+//! alert is raised with the note's severity, which picks where it's routed
+//! (see `core::alerts`).
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::core::alerts::Severity;
+use crate::core::json_store::JsonStore;
+use crate::data_paths::DataPaths;
+
+pub const TOKEN_NOTES_FILE: &str = "token_notes.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl fmt::Display for AlertDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertDirection::Above => write!(f, "above"),
+            AlertDirection::Below => write!(f, "below"),
+        }
+    }
+}
+
+/// The threshold a price crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossing {
+    pub direction: AlertDirection,
+    pub threshold: Decimal,
+}
+
+/// A fired alert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertTrigger {
+    pub at: DateTime<Utc>,
+    pub direction: AlertDirection,
+    pub threshold: Decimal,
+    pub price: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenNote {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    pub alert_above: Option<Decimal>,
+    pub alert_below: Option<Decimal>,
+    /// Severity the alert is raised with
+    #[serde(default)]
+    pub severity: Severity,
+    /// Set when the alert fired, cleared when the thresholds change
+    pub triggered: Option<AlertTrigger>,
+}
+
+impl TokenNote {
+    pub fn has_alert(&self) -> bool {
+        self.alert_above.is_some() || self.alert_below.is_some()
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.has_alert() && self.triggered.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.note.trim().is_empty() && !self.has_alert()
+    }
+
+    /// The threshold `price` crosses, if the alert is armed
+    pub fn crossed(&self, price: Decimal) -> Option<Crossing> {
+        if !self.is_armed() {
+            return None;
+        }
+        if let Some(threshold) = self.alert_above.filter(|above| price >= *above) {
+            return Some(Crossing {
+                direction: AlertDirection::Above,
+                threshold,
+            });
+        }
+        self.alert_below
+            .filter(|below| price <= *below)
+            .map(|threshold| Crossing {
+                direction: AlertDirection::Below,
+                threshold,
+            })
+    }
+
+    /// Thresholds as shown next to a token, e.g. "≥ 0.60 / ≤ 0.40"
+    pub fn alert_label(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(above) = self.alert_above {
+            parts.push(format!("≥ {}", above));
+        }
+        if let Some(below) = self.alert_below {
+            parts.push(format!("≤ {}", below));
+        }
+        parts.join(" / ")
+    }
+}
+
+/// An alert that fired on a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredAlert {
+    pub token_id: String,
+    pub trigger: AlertTrigger,
+    pub severity: Severity,
+}
+
+impl FiredAlert {
+    /// e.g. "🔔 123456789012... at $0.38, below 0.40"
+    pub fn message(&self) -> String {
+        let short: String = self.token_id.chars().take(12).collect();
+        format!(
+            "🔔 {}... at ${}, {} {}",
+            short, self.trigger.price, self.trigger.direction, self.trigger.threshold
+        )
+    }
+}
+
+/// Notes keyed by token id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TokenNotes {
+    notes: BTreeMap<String, TokenNote>,
+}
+
+impl TokenNotes {
+    pub fn get(&self, token_id: &str) -> Option<&TokenNote> {
+        self.notes.get(token_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Replace a token's note, re-arming its alert when the thresholds changed;
+    /// an empty note removes the entry
+    pub fn set(&mut self, token_id: &str, mut note: TokenNote) {
+        if note.is_empty() {
+            self.notes.remove(token_id);
+            return;
+        }
+        if let Some(previous) = self.notes.get(token_id) {
+            let same_thresholds = previous.alert_above == note.alert_above
+                && previous.alert_below == note.alert_below;
+            if same_thresholds && note.triggered.is_none() {
+                note.triggered = previous.triggered.clone();
+            }
+        }
+        self.notes.insert(token_id.to_string(), note);
+    }
+
+    /// Mark alerts fired by [`Self::check_alerts`] on another copy of the
+    /// notes, skipping tokens whose thresholds were edited since
+    pub fn record_fired(&mut self, fired: &[FiredAlert]) {
+        for alert in fired {
+            let Some(note) = self.notes.get_mut(&alert.token_id) else {
+                continue;
+            };
+            if note.crossed(alert.trigger.price).is_some_and(|crossing| {
+                crossing.direction == alert.trigger.direction
+                    && crossing.threshold == alert.trigger.threshold
+            }) {
+                note.triggered = Some(alert.trigger.clone());
+            }
+        }
+    }
+
+    /// Fire the armed alerts whose token price crossed a threshold
+    pub fn check_alerts(
+        &mut self,
+        price_of: impl Fn(&str) -> Option<Decimal>,
+        now: DateTime<Utc>,
+    ) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+        for (token_id, note) in self.notes.iter_mut() {
+            let Some(price) = note.is_armed().then(|| price_of(token_id)).flatten() else {
+                continue;
+            };
+            if let Some(crossing) = note.crossed(price) {
+                let trigger = AlertTrigger {
+                    at: now,
+                    direction: crossing.direction,
+                    threshold: crossing.threshold,
+                    price,
+                };
+                note.triggered = Some(trigger.clone());
+                fired.push(FiredAlert {
+                    token_id: token_id.clone(),
+                    trigger,
+                    severity: note.severity,
+                });
+            }
+        }
+        fired
+    }
+}
+
+/// Token notes kept as JSON in the data directory
+pub struct TokenNoteStore {
+    file: JsonStore<TokenNotes>,
+}
+
+impl TokenNoteStore {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            file: JsonStore::new(data_paths.data().join(TOKEN_NOTES_FILE)),
+        }
+    }
+
+    pub fn load(&self) -> Result<TokenNotes> {
+        self.file.load()
+    }
+
+    /// Apply `change` to the stored notes under the file's lock; returns the
+    /// notes as saved, including edits made by other processes
+    pub fn modify(&self, change: impl FnOnce(&mut TokenNotes)) -> Result<TokenNotes> {
+        self.file.modify(|notes| {
+            change(notes);
+            Ok(notes.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn alerts_fire_once_and_rearm_when_edited() {
+        let mut notes = TokenNotes::default();
+        let note = TokenNote {
+            note: "fade the spike".to_string(),
+            alert_above: Some(dec!(0.60)),
+            alert_below: Some(dec!(0.40)),
+            severity: Severity::Warning,
+            triggered: None,
+        };
+        notes.set("123", note.clone());
+        assert!(notes.get("123").unwrap().is_armed());
+        assert_eq!(notes.get("123").unwrap().alert_label(), "≥ 0.60 / ≤ 0.40");
+
+        let now = Utc::now();
+        assert!(notes.check_alerts(|_| Some(dec!(0.5)), now).is_empty());
+        let fired = notes.check_alerts(|_| Some(dec!(0.38)), now);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].trigger.direction, AlertDirection::Below);
+        assert_eq!(fired[0].trigger.threshold, dec!(0.40));
+        assert_eq!(fired[0].severity, Severity::Warning);
+        // Fired alerts stay quiet
+        assert!(notes.check_alerts(|_| Some(dec!(0.30)), now).is_empty());
+
+        // Editing only the note keeps it fired, new thresholds re-arm it
+        notes.set(
+            "123",
+            TokenNote {
+                note: "still fading".to_string(),
+                ..note.clone()
+            },
+        );
+        assert!(!notes.get("123").unwrap().is_armed());
+        notes.set(
+            "123",
+            TokenNote {
+                alert_below: Some(dec!(0.25)),
+                ..note
+            },
+        );
+        assert!(notes.get("123").unwrap().is_armed());
+
+        // The file keeps the plain token id map
+        let json = serde_json::to_value(&notes).unwrap();
+        assert!(json["123"].is_object());
+        assert_eq!(serde_json::from_value::<TokenNotes>(json).unwrap(), notes);
+
+        notes.set("123", TokenNote::default());
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn store_changes_keep_notes_saved_by_other_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let gui = TokenNoteStore::new(&data_paths);
+        let other = TokenNoteStore::new(&data_paths);
+        let note = TokenNote {
+            alert_below: Some(dec!(0.40)),
+            ..TokenNote::default()
+        };
+
+        let mut shown = gui.modify(|notes| notes.set("123", note.clone())).unwrap();
+        other.modify(|notes| notes.set("456", note.clone())).unwrap();
+
+        // The alert fires on the GUI's copy and is recorded without losing 456
+        let fired = shown.check_alerts(|_| Some(dec!(0.38)), Utc::now());
+        assert_eq!(fired.len(), 1);
+        let saved = gui.modify(|notes| notes.record_fired(&fired)).unwrap();
+        assert!(saved.get("456").unwrap().is_armed());
+        assert!(!saved.get("123").unwrap().is_armed());
+    }
+}
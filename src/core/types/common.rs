@@ -15,12 +15,28 @@ impl<'de> serde::Deserialize<'de> for Side {
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.to_lowercase().as_str() {
-            "buy" => Ok(Side::Buy),
-            "sell" => Ok(Side::Sell),
-            _ => Err(serde::de::Error::unknown_variant(&s, &["buy", "sell"])),
+        // Visit the borrowed string so hot feed parsing does not allocate per side
+        struct SideVisitor;
+
+        impl serde::de::Visitor<'_> for SideVisitor {
+            type Value = Side;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("\"buy\" or \"sell\" in any case")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Side, E> {
+                if s.eq_ignore_ascii_case("buy") {
+                    Ok(Side::Buy)
+                } else if s.eq_ignore_ascii_case("sell") {
+                    Ok(Side::Sell)
+                } else {
+                    Err(E::unknown_variant(s, &["buy", "sell"]))
+                }
+            }
         }
+
+        deserializer.deserialize_str(SideVisitor)
     }
 }
 
@@ -84,4 +100,34 @@ impl std::fmt::Display for Timestamp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
-}
\ No newline at end of file
+}
+/// Token, market or order ID cut to its first 12 characters for tables and
+/// status lines; the full ID belongs in hover text or JSON output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortId<'a>(pub &'a str);
+
+impl ShortId<'_> {
+    /// Characters kept before the ellipsis
+    pub const LEN: usize = 12;
+}
+
+impl std::fmt::Display for ShortId<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.char_indices().nth(Self::LEN) {
+            Some((end, _)) => write!(f, "{}...", &self.0[..end]),
+            None => f.write_str(self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_id_keeps_short_ids_whole() {
+        assert_eq!(ShortId("0xabc").to_string(), "0xabc");
+        assert_eq!(ShortId("123456789012").to_string(), "123456789012");
+        assert_eq!(ShortId("1234567890123").to_string(), "123456789012...");
+    }
+}
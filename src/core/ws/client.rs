@@ -1,6 +1,7 @@
 //! WebSocket client for Polymarket streaming with auto-reconnection
 
 use super::events::{MarketSubscription, UserSubscription, WsMessage};
+use crate::core::diagnostics::{self, record_event, Subsystem};
 use backoff::{backoff::Backoff, ExponentialBackoff};
 use futures::{SinkExt, StreamExt};
 use serde_json;
@@ -162,6 +163,7 @@ impl WsClient {
                 }
                 Err(e) => {
                     error!("WebSocket connection error: {}", e);
+                    record_event(Subsystem::Ws, format!("connection error: {}", e));
 
                     // Check if we should attempt reconnection
                     if config.max_reconnection_attempts > 0
@@ -210,12 +212,15 @@ impl WsClient {
             _response.status()
         );
         debug!("Response headers: {:?}", _response.headers());
+        record_event(Subsystem::Ws, format!("connected to {}", url));
 
         // Set up heartbeat timer
         let mut heartbeat = interval(Duration::from_secs(config.heartbeat_interval));
         heartbeat.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         let mut last_pong = Instant::now();
+        // Reused for every frame so splitting does not allocate once warmed up
+        let mut frame_events: Vec<WsMessage> = Vec::with_capacity(64);
         let pong_timeout = Duration::from_secs(config.heartbeat_interval * 2);
 
         loop {
@@ -232,45 +237,27 @@ impl WsClient {
                                 continue;
                             }
 
-                            // Try to parse as array of events (Polymarket format)
-                            match serde_json::from_str::<Vec<serde_json::Value>>(&text) {
-                                Ok(events) => {
-                                    debug!("Parsed {} events from websocket", events.len());
+                            // Split the frame (an array of events in Polymarket's format,
+                            // or a single event) into messages sharing one buffer
+                            frame_events.clear();
+                            match WsMessage::parse_frame(&text, &mut frame_events) {
+                                Ok(()) => {
+                                    debug!("Parsed {} events from websocket", frame_events.len());
 
                                     // Send each event individually
-                                    for event in events {
-                                        // Polymarket uses either "type" or "event_type" field
-                                        let event_type = event.get("type")
-                                            .or_else(|| event.get("event_type"))
-                                            .and_then(|v| v.as_str());
-
-                                        if let Some(event_type) = event_type {
-                                            let ws_msg = WsMessage {
-                                                event_type: event_type.to_string(),
-                                                data: event,
-                                            };
-
-                                            if let Err(e) = message_tx.send(ws_msg) {
-                                                warn!("Failed to send event to channel: {}", e);
-                                            }
-                                        } else {
-                                            warn!("Event missing type/event_type field: {:?}", event);
+                                    for ws_msg in frame_events.drain(..) {
+                                        if diagnostics::records_ws_messages() {
+                                            let asset_id = ws_msg.asset_id().unwrap_or("-");
+                                            record_event(Subsystem::Ws, format!("{} {}", ws_msg.event_type, asset_id));
+                                        }
+                                        if let Err(e) = message_tx.send(ws_msg) {
+                                            warn!("Failed to send event to channel: {}", e);
                                         }
                                     }
                                 }
                                 Err(e) => {
-                                    // Fallback: try to parse as single WsMessage
-                                    match serde_json::from_str::<WsMessage>(&text) {
-                                        Ok(ws_msg) => {
-                                            debug!("Parsed single WebSocket message: {:?}", ws_msg);
-                                            if let Err(e) = message_tx.send(ws_msg) {
-                                                warn!("Failed to send message to channel: {}", e);
-                                            }
-                                        }
-                                        Err(e2) => {
-                                            error!("Failed to parse message: Array parse: {}, Single parse: {} - Raw: {}", e, e2, text);
-                                        }
-                                    }
+                                    error!("Failed to parse message: {} - Raw: {}", e, text);
+                                    record_event(Subsystem::Ws, format!("unparseable message: {}", text));
                                 }
                             }
                         }
@@ -280,6 +267,7 @@ impl WsClient {
                         }
                         Some(Ok(Message::Close(_))) => {
                             info!("WebSocket closed by server");
+                            record_event(Subsystem::Ws, "closed by server");
                             break;
                         }
                         Some(Err(e)) => {
@@ -4,6 +4,10 @@ use crate::core::types::common::{OrderStatus, Side};
 use crate::core::types::market::PriceLevel;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
 use thiserror::Error;
 use tracing::{debug, error, info, trace, warn};
 
@@ -15,6 +19,25 @@ pub enum EventError {
     UnknownEventType(String),
 }
 
+/// Origin of an order book snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookSource {
+    /// Snapshot pushed by the WebSocket market feed
+    #[default]
+    WebSocket,
+    /// Synthetic snapshot polled from the REST API while the WebSocket feed is stale
+    RestSnapshot,
+    /// REST snapshot replacing a streamed book that diverged from it
+    Resync,
+}
+
+impl BookSource {
+    /// Whether this snapshot was synthesized from a REST poll
+    pub fn is_synthetic(&self) -> bool {
+        matches!(self, BookSource::RestSnapshot | BookSource::Resync)
+    }
+}
+
 /// High-level events published by the streamer
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -27,6 +50,7 @@ pub enum PolyEvent {
         bids: Vec<PriceLevel>,
         asks: Vec<PriceLevel>,
         hash: String,
+        source: BookSource,
     },
     /// Price level change (add/remove/update)
     PriceChange {
@@ -76,13 +100,125 @@ pub enum PolyEvent {
     },
 }
 
+impl PolyEvent {
+    /// Token a market-feed event is about; `None` for user-feed and unknown
+    /// events
+    pub fn market_asset_id(&self) -> Option<&str> {
+        match self {
+            PolyEvent::Book { asset_id, .. }
+            | PolyEvent::PriceChange { asset_id, .. }
+            | PolyEvent::TickSizeChange { asset_id, .. }
+            | PolyEvent::Trade { asset_id, .. }
+            | PolyEvent::LastTradePrice { asset_id, .. } => Some(asset_id),
+            _ => None,
+        }
+    }
+}
+
 /// Raw WebSocket message envelope
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Holds one event's JSON exactly as received, as a span of the frame it
+/// arrived in. The frame is copied once and shared by every event in it and
+/// every subscriber; typed events are deserialized straight from the span,
+/// borrowing strings, with no intermediate `serde_json::Value` tree.
+#[derive(Debug, Clone)]
 pub struct WsMessage {
-    #[serde(alias = "event_type", rename = "type")]
     pub event_type: String,
-    #[serde(flatten)]
-    pub data: serde_json::Value,
+    frame: Arc<str>,
+    span: Range<usize>,
+}
+
+/// Envelope fields read when a frame is split into events
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(rename = "type", borrow, default)]
+    kind: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    event_type: Option<Cow<'a, str>>,
+}
+
+/// Common event fields, read on demand
+#[derive(Deserialize)]
+struct EventFields<'a> {
+    #[serde(default)]
+    asset_id: Option<&'a str>,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    timestamp: Option<u64>,
+}
+
+impl WsMessage {
+    /// Parse one event object
+    pub fn from_json(json: &str) -> Result<Self, EventError> {
+        let mut messages = Vec::with_capacity(1);
+        Self::parse_frame(json, &mut messages)?;
+        match messages.pop() {
+            Some(message) if messages.is_empty() => Ok(message),
+            _ => Err(EventError::InvalidFormat("expected a single event object".to_string())),
+        }
+    }
+
+    /// Split a feed frame (an array of events or a single event) into
+    /// messages, appending them to `out` so callers can reuse one buffer
+    ///
+    /// Events without a `type`/`event_type` are skipped.
+    pub fn parse_frame(text: &str, out: &mut Vec<WsMessage>) -> Result<(), EventError> {
+        let frame: Arc<str> = Arc::from(text);
+        let invalid = |e: serde_json::Error| EventError::InvalidFormat(e.to_string());
+        if frame.trim_start().starts_with('[') {
+            let events: Vec<&RawValue> = serde_json::from_str(&frame).map_err(invalid)?;
+            out.reserve(events.len());
+            for raw in events {
+                Self::push_event(&frame, raw, out)?;
+            }
+        } else {
+            let raw: &RawValue = serde_json::from_str(&frame).map_err(invalid)?;
+            Self::push_event(&frame, raw, out)?;
+        }
+        Ok(())
+    }
+
+    fn push_event(frame: &Arc<str>, raw: &RawValue, out: &mut Vec<WsMessage>) -> Result<(), EventError> {
+        let json = raw.get();
+        let envelope: Envelope = serde_json::from_str(json)
+            .map_err(|e| EventError::InvalidFormat(e.to_string()))?;
+        let Some(event_type) = envelope.kind.or(envelope.event_type) else {
+            warn!("Event missing type/event_type field: {}", json);
+            return Ok(());
+        };
+        // `raw` borrows from `frame`, so its offset locates the event in it
+        let start = json.as_ptr() as usize - frame.as_ptr() as usize;
+        out.push(WsMessage {
+            event_type: event_type.into_owned(),
+            frame: Arc::clone(frame),
+            span: start..start + json.len(),
+        });
+        Ok(())
+    }
+
+    /// The event's JSON as received
+    pub fn json(&self) -> &str {
+        &self.frame[self.span.clone()]
+    }
+
+    /// Deserialize the event into a typed struct, borrowing from the frame
+    pub fn decode<'a, T: Deserialize<'a>>(&'a self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(self.json())
+    }
+
+    /// The event's `asset_id`, if it has one
+    pub fn asset_id(&self) -> Option<&str> {
+        self.decode::<EventFields>().ok()?.asset_id
+    }
+
+    /// The event's `timestamp` (sent as a string or a number), if it has one
+    pub fn timestamp(&self) -> Option<u64> {
+        self.decode::<EventFields>().ok()?.timestamp
+    }
+
+    /// The event as a generic JSON value, for events without a typed model
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::from_str(self.json()).unwrap_or(serde_json::Value::Null)
+    }
 }
 
 /// Market feed subscription message
@@ -134,10 +270,11 @@ pub struct AuthPayload {
 
 /// Order book snapshot event (Polymarket format)
 #[derive(Debug, Deserialize)]
-pub struct BookEvent {
-    pub asset_id: String,
-    #[serde(default)]
-    pub market: String,
+pub struct BookEvent<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub market: Cow<'a, str>,
     #[serde(default, deserialize_with = "deserialize_timestamp_flexible")]
     pub timestamp: u64,
     #[serde(
@@ -152,8 +289,8 @@ pub struct BookEvent {
         deserialize_with = "deserialize_order_levels"
     )]
     pub asks: Vec<PriceLevel>,
-    #[serde(default)]
-    pub hash: String,
+    #[serde(borrow, default)]
+    pub hash: Cow<'a, str>,
 }
 
 /// Order level for book events  
@@ -165,12 +302,20 @@ struct OrderLevel {
     size: Decimal,
 }
 
+impl From<OrderLevel> for PriceLevel {
+    fn from(level: OrderLevel) -> Self {
+        PriceLevel::new(level.price, level.size)
+    }
+}
+
 /// Price change event (order add/cancel/update) - Polymarket format
 #[derive(Debug, Deserialize)]
-pub struct PriceChangeEvent {
-    pub asset_id: String,
+pub struct PriceChangeEvent<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
     pub changes: Vec<PriceChange>,
-    pub hash: String,
+    #[serde(borrow)]
+    pub hash: Cow<'a, str>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -184,16 +329,18 @@ pub struct PriceChange {
 
 /// Tick size change event
 #[derive(Debug, Deserialize)]
-pub struct TickSizeChangeEvent {
-    pub asset_id: String,
+pub struct TickSizeChangeEvent<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
     #[serde(deserialize_with = "deserialize_decimal_flexible")]
     pub tick_size: Decimal,
 }
 
 /// Trade event
 #[derive(Debug, Deserialize)]
-pub struct TradeEvent {
-    pub asset_id: String,
+pub struct TradeEvent<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
     #[serde(deserialize_with = "deserialize_decimal_flexible")]
     pub price: Decimal,
     #[serde(deserialize_with = "deserialize_decimal_flexible")]
@@ -242,8 +389,9 @@ pub struct UserTradeEvent {
 
 /// Last trade price event
 #[derive(Debug, Deserialize)]
-pub struct LastTradePriceEvent {
-    pub asset_id: String,
+pub struct LastTradePriceEvent<'a> {
+    #[serde(borrow)]
+    pub asset_id: Cow<'a, str>,
     #[serde(deserialize_with = "deserialize_decimal_flexible")]
     pub price: Decimal,
     #[serde(deserialize_with = "deserialize_timestamp_flexible")]
@@ -251,15 +399,36 @@ pub struct LastTradePriceEvent {
 }
 
 /// Helper function to deserialize order levels from Polymarket book events
+/// straight into `PriceLevel`s, without an intermediate vector
 fn deserialize_order_levels<'de, D>(deserializer: D) -> Result<Vec<PriceLevel>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let levels: Vec<OrderLevel> = Deserialize::deserialize(deserializer)?;
-    Ok(levels
-        .into_iter()
-        .map(|level| PriceLevel::new(level.price, level.size))
-        .collect())
+    use serde::de::{SeqAccess, Visitor};
+    use std::fmt;
+
+    struct LevelsVisitor;
+
+    impl<'de> Visitor<'de> for LevelsVisitor {
+        type Value = Vec<PriceLevel>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array of price levels")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut levels = Vec::with_capacity(seq.size_hint().unwrap_or(16));
+            while let Some(level) = seq.next_element::<OrderLevel>()? {
+                levels.push(level.into());
+            }
+            Ok(levels)
+        }
+    }
+
+    deserializer.deserialize_seq(LevelsVisitor)
 }
 
 
@@ -363,6 +532,12 @@ where
     deserializer.deserialize_any(TimestampVisitor)
 }
 
+fn deserialize_optional_timestamp<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_timestamp_flexible(deserializer).map(Some)
+}
 
 /// Parse a raw WebSocket message into typed events
 pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
@@ -370,23 +545,10 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
 
     match msg.event_type.as_str() {
         "book" => {
-            // Debug log to see what fields are present
-            if let Some(obj) = msg.data.as_object() {
-                let keys: Vec<&str> = obj.keys().map(|k| k.as_str()).collect();
-                debug!(available_keys = ?keys, "Keys in book event");
-            }
-            
-            // Parse as BookEvent for processing
-            let event: BookEvent = serde_json::from_value(msg.data.clone())
-                .map_err(|e| {
-                    error!(error = %e, event_type = "book", raw_data = ?msg.data, "Failed to parse book event");
-                    // Log the keys available in the data to help debug
-                    if let Some(obj) = msg.data.as_object() {
-                        let keys: Vec<&str> = obj.keys().map(|k| k.as_str()).collect();
-                        error!(available_keys = ?keys, "Available keys in book event");
-                    }
-                    EventError::InvalidFormat(e.to_string())
-                })?;
+            let event: BookEvent = msg.decode().map_err(|e| {
+                error!(error = %e, event_type = "book", raw_data = %msg.json(), "Failed to parse book event");
+                EventError::InvalidFormat(e.to_string())
+            })?;
 
             info!(
                 asset_id = %event.asset_id,
@@ -399,20 +561,20 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             );
 
             Ok(vec![PolyEvent::Book {
-                asset_id: event.asset_id,
-                market: event.market,
+                asset_id: event.asset_id.into_owned(),
+                market: event.market.into_owned(),
                 timestamp: event.timestamp,
                 bids: event.bids,
                 asks: event.asks,
-                hash: event.hash,
+                hash: event.hash.into_owned(),
+                source: BookSource::WebSocket,
             }])
         }
         "price_change" => {
-            let event: PriceChangeEvent = serde_json::from_value(msg.data.clone())
-                .map_err(|e| {
-                    error!(error = %e, event_type = "price_change", "Failed to parse price change event");
-                    EventError::InvalidFormat(e.to_string())
-                })?;
+            let event: PriceChangeEvent = msg.decode().map_err(|e| {
+                error!(error = %e, event_type = "price_change", "Failed to parse price change event");
+                EventError::InvalidFormat(e.to_string())
+            })?;
 
             debug!(
                 asset_id = %event.asset_id,
@@ -422,7 +584,7 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             );
 
             // Return all changes as individual events
-            let mut events = Vec::new();
+            let mut events = Vec::with_capacity(event.changes.len());
             for change in &event.changes {
                 debug!(
                     asset_id = %event.asset_id,
@@ -433,11 +595,11 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
                 );
 
                 events.push(PolyEvent::PriceChange {
-                    asset_id: event.asset_id.clone(),
+                    asset_id: event.asset_id.to_string(),
                     side: change.side,
                     price: change.price,
                     size: change.size,
-                    hash: event.hash.clone(),
+                    hash: event.hash.to_string(),
                 });
             }
 
@@ -452,11 +614,10 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             }
         }
         "tick_size_change" => {
-            let event: TickSizeChangeEvent = serde_json::from_value(msg.data.clone())
-                .map_err(|e| {
-                    error!(error = %e, event_type = "tick_size_change", "Failed to parse tick size change event");
-                    EventError::InvalidFormat(e.to_string())
-                })?;
+            let event: TickSizeChangeEvent = msg.decode().map_err(|e| {
+                error!(error = %e, event_type = "tick_size_change", "Failed to parse tick size change event");
+                EventError::InvalidFormat(e.to_string())
+            })?;
 
             info!(
                 asset_id = %event.asset_id,
@@ -465,12 +626,12 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             );
 
             Ok(vec![PolyEvent::TickSizeChange {
-                asset_id: event.asset_id,
+                asset_id: event.asset_id.into_owned(),
                 tick_size: event.tick_size,
             }])
         }
         "trade" => {
-            let event: TradeEvent = serde_json::from_value(msg.data.clone()).map_err(|e| {
+            let event: TradeEvent = msg.decode().map_err(|e| {
                 error!(error = %e, event_type = "trade", "Failed to parse trade event");
                 EventError::InvalidFormat(e.to_string())
             })?;
@@ -485,14 +646,14 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             );
 
             Ok(vec![PolyEvent::Trade {
-                asset_id: event.asset_id,
+                asset_id: event.asset_id.into_owned(),
                 price: event.price,
                 size: event.size,
                 side: event.side,
             }])
         }
         "order" => {
-            let event: UserOrderEvent = serde_json::from_value(msg.data.clone()).map_err(|e| {
+            let event: UserOrderEvent = msg.decode().map_err(|e| {
                 error!(error = %e, event_type = "order", "Failed to parse user order event");
                 EventError::InvalidFormat(e.to_string())
             })?;
@@ -516,7 +677,7 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             }])
         }
         "user_trade" => {
-            let event: UserTradeEvent = serde_json::from_value(msg.data.clone()).map_err(|e| {
+            let event: UserTradeEvent = msg.decode().map_err(|e| {
                 error!(error = %e, event_type = "user_trade", "Failed to parse user trade event");
                 EventError::InvalidFormat(e.to_string())
             })?;
@@ -540,28 +701,15 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             }])
         }
         "last_trade_price" => {
-            // Debug log the raw JSON data
-            debug!(
-                event_type = "last_trade_price",
-                raw_data = ?msg.data,
-                "Raw last_trade_price event data"
-            );
-
-            let event: LastTradePriceEvent =
-                serde_json::from_value(msg.data.clone()).map_err(|e| {
-                    error!(
-                        error = %e,
-                        event_type = "last_trade_price",
-                        raw_data = ?msg.data,
-                        "Failed to parse last trade price event"
-                    );
-                    // Log the keys available in the data to help debug
-                    if let Some(obj) = msg.data.as_object() {
-                        let keys: Vec<&str> = obj.keys().map(|k| k.as_str()).collect();
-                        error!(available_keys = ?keys, "Available keys in last_trade_price event");
-                    }
-                    EventError::InvalidFormat(e.to_string())
-                })?;
+            let event: LastTradePriceEvent = msg.decode().map_err(|e| {
+                error!(
+                    error = %e,
+                    event_type = "last_trade_price",
+                    raw_data = %msg.json(),
+                    "Failed to parse last trade price event"
+                );
+                EventError::InvalidFormat(e.to_string())
+            })?;
 
             info!(
                 asset_id = %event.asset_id,
@@ -571,19 +719,81 @@ pub fn parse_message(msg: &WsMessage) -> Result<Vec<PolyEvent>, EventError> {
             );
 
             Ok(vec![PolyEvent::LastTradePrice {
-                asset_id: event.asset_id,
+                asset_id: event.asset_id.into_owned(),
                 price: event.price,
                 timestamp: event.timestamp,
             }])
         }
         _ => {
-            warn!(event_type = %msg.event_type, raw_data = ?msg.data, "Unknown event type - creating Unknown event");
+            warn!(event_type = %msg.event_type, raw_data = %msg.json(), "Unknown event type - creating Unknown event");
             
             // Return the unknown event so it can be displayed in the TUI
             Ok(vec![PolyEvent::Unknown {
                 event_type: msg.event_type.clone(),
-                data: msg.data.clone(),
+                data: msg.to_value(),
             }])
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const ASSET_ID: &str = "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+
+    #[test]
+    fn frames_split_into_events_sharing_one_buffer() {
+        let frame = format!(
+            r#"[{{"event_type":"price_change","asset_id":"{id}","changes":[{{"price":"0.49","side":"BUY","size":"120"}},{{"price":"0.52","side":"sell","size":"0"}}],"hash":"0x5f1c","timestamp":"1750428146322"}}, {{"event_type":"last_trade_price","asset_id":"{id}","price":"0.5","timestamp":1750428146400}}]"#,
+            id = ASSET_ID
+        );
+        let mut messages = Vec::new();
+        WsMessage::parse_frame(&frame, &mut messages).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert!(Arc::ptr_eq(&messages[0].frame, &messages[1].frame));
+        assert_eq!(messages[0].asset_id(), Some(ASSET_ID));
+        assert_eq!(messages[0].timestamp(), Some(1_750_428_146_322));
+        assert_eq!(messages[1].timestamp(), Some(1_750_428_146_400));
+        assert!(messages[1].json().starts_with(r#"{"event_type":"last_trade_price""#));
+
+        let events = parse_message(&messages[0]).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.market_asset_id() == Some(ASSET_ID)));
+        match &events[1] {
+            PolyEvent::PriceChange { asset_id, side, price, size, hash } => {
+                assert_eq!(asset_id, ASSET_ID);
+                assert_eq!(*side, Side::Sell);
+                assert_eq!(*price, dec!(0.52));
+                assert!(size.is_zero());
+                assert_eq!(hash, "0x5f1c");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn single_events_and_untyped_entries() {
+        let book = WsMessage::from_json(&format!(
+            r#"{{"type":"book","asset_id":"{}","market":"0xbd31dc8a","buys":[{{"price":"0.48","size":"30"}}],"sells":[],"timestamp":"123","hash":"0xabc"}}"#,
+            ASSET_ID
+        ))
+        .unwrap();
+        assert_eq!(book.event_type, "book");
+        match &parse_message(&book).unwrap()[0] {
+            PolyEvent::Book { bids, asks, timestamp, .. } => {
+                assert_eq!(bids, &vec![PriceLevel::new(dec!(0.48), dec!(30))]);
+                assert!(asks.is_empty());
+                assert_eq!(*timestamp, 123);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let mut messages = Vec::new();
+        WsMessage::parse_frame(r#"[{"asset_id":"1"}, {"event_type":"new_market"}]"#, &mut messages).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(matches!(&parse_message(&messages[0]).unwrap()[0], PolyEvent::Unknown { event_type, .. } if event_type == "new_market"));
+    }
+}
@@ -0,0 +1,385 @@
+//! Property-based generators for order book update sequences
+//!
+//! The strategies produce what a market feed could send for one token:
+//! well-formed sequences (a snapshot, then changes and newer snapshots that
+//! never cross) and adversarial ones (updates delivered out of order or
+//! twice, crossed levels, stale snapshots, and prices or sizes no book can
+//! hold). [`replay`] applies a sequence the way the streaming service does and
+//! checks the book's invariants after every update. The state tests and
+//! `polybot fuzz-book` both run on these.
+
+use anyhow::{anyhow, Result};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, RngAlgorithm, TestCaseError, TestError, TestRng, TestRunner};
+use rust_decimal::Decimal;
+use std::fmt;
+
+use super::state::OrderBook;
+use crate::core::types::common::Side;
+use crate::core::types::market::PriceLevel;
+
+/// Asset the replayed books are created for
+pub const FUZZ_ASSET_ID: &str = "fuzz";
+
+/// Timestamp of the first snapshot; later snapshots are a second apart
+const BASE_TIMESTAMP: u64 = 1_700_000_000_000;
+const TIMESTAMP_STEP: u64 = 1_000;
+const MAX_LEVELS: usize = 12;
+
+/// One message of a token's market feed, reduced to what the book consumes
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookUpdate {
+    Snapshot {
+        timestamp: u64,
+        bids: Vec<PriceLevel>,
+        asks: Vec<PriceLevel>,
+    },
+    Change {
+        side: Side,
+        price: Decimal,
+        size: Decimal,
+    },
+}
+
+impl fmt::Display for BookUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let levels = |levels: &[PriceLevel]| {
+            levels
+                .iter()
+                .map(|l| format!("{}x{}", l.price, l.size))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match self {
+            BookUpdate::Snapshot { timestamp, bids, asks } => write!(
+                f,
+                "snapshot t={} bids [{}] asks [{}]",
+                timestamp,
+                levels(bids),
+                levels(asks)
+            ),
+            BookUpdate::Change { side, price, size } => write!(f, "change {} {} -> {}", side, price, size),
+        }
+    }
+}
+
+/// Which sequences to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SequenceKind {
+    /// What a healthy feed sends
+    Valid,
+    /// Reordered, duplicated, crossed and malformed updates
+    Adversarial,
+}
+
+impl fmt::Display for SequenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceKind::Valid => write!(f, "valid"),
+            SequenceKind::Adversarial => write!(f, "adversarial"),
+        }
+    }
+}
+
+fn cents(range: std::ops::RangeInclusive<i64>) -> impl Strategy<Value = Decimal> + Clone {
+    range.prop_map(|c| Decimal::new(c, 2))
+}
+
+fn side() -> impl Strategy<Value = Side> {
+    prop_oneof![Just(Side::Buy), Just(Side::Sell)]
+}
+
+/// A positive size with two decimals, up to 1000 shares
+pub fn size() -> impl Strategy<Value = Decimal> {
+    (1i64..=100_000).prop_map(|n| Decimal::new(n, 2))
+}
+
+/// Sizes a change can carry: mostly positive, sometimes a removal
+fn change_size() -> impl Strategy<Value = Decimal> {
+    prop_oneof![4 => size(), 1 => Just(Decimal::ZERO)]
+}
+
+fn levels(prices: impl Strategy<Value = Decimal>) -> impl Strategy<Value = Vec<PriceLevel>> {
+    prop::collection::vec((prices, size()).prop_map(|(p, s)| PriceLevel::new(p, s)), 0..MAX_LEVELS)
+}
+
+/// Bids strictly below `split` and asks strictly above it, so the book never crosses
+fn valid_update(split: i64) -> impl Strategy<Value = BookUpdate> {
+    let bid = cents(1..=split - 1);
+    let ask = cents(split + 1..=99);
+    prop_oneof![
+        4 => (bid.clone(), change_size()).prop_map(|(price, size)| BookUpdate::Change { side: Side::Buy, price, size }),
+        4 => (ask.clone(), change_size()).prop_map(|(price, size)| BookUpdate::Change { side: Side::Sell, price, size }),
+        1 => (levels(bid), levels(ask)).prop_map(|(bids, asks)| BookUpdate::Snapshot { timestamp: 0, bids, asks }),
+    ]
+}
+
+/// Stamp snapshots with increasing timestamps, in sequence order
+fn stamp(mut updates: Vec<BookUpdate>) -> Vec<BookUpdate> {
+    let mut timestamp = BASE_TIMESTAMP;
+    for update in &mut updates {
+        if let BookUpdate::Snapshot { timestamp: t, .. } = update {
+            *t = timestamp;
+            timestamp += TIMESTAMP_STEP;
+        }
+    }
+    updates
+}
+
+/// A snapshot followed by up to `max_len` changes and newer snapshots
+pub fn valid_sequence(max_len: usize) -> impl Strategy<Value = Vec<BookUpdate>> {
+    (2i64..=98).prop_flat_map(move |split| {
+        let first = (levels(cents(1..=split - 1)), levels(cents(split + 1..=99)))
+            .prop_map(|(bids, asks)| BookUpdate::Snapshot { timestamp: 0, bids, asks });
+        (first, prop::collection::vec(valid_update(split), 0..max_len)).prop_map(|(first, rest)| {
+            let mut updates = vec![first];
+            updates.extend(rest);
+            stamp(updates)
+        })
+    })
+}
+
+/// Any price a corrupted feed could carry, including 0, 1, negative,
+/// above 1 and finer than the tick
+fn any_price() -> impl Strategy<Value = Decimal> {
+    prop_oneof![
+        8 => cents(1..=99),
+        1 => prop_oneof![Just(Decimal::ZERO), Just(Decimal::ONE), cents(-100..=-1), cents(101..=500)],
+        1 => (1i64..=9_999).prop_map(|n| Decimal::new(n, 4)),
+    ]
+}
+
+fn any_size() -> impl Strategy<Value = Decimal> {
+    prop_oneof![
+        6 => size(),
+        2 => Just(Decimal::ZERO),
+        1 => (1i64..=100_000).prop_map(|n| Decimal::new(-n, 2)),
+        1 => (1i64..=1_000_000_000_000_000).prop_map(|n| Decimal::new(n, 0)),
+    ]
+}
+
+fn any_levels() -> impl Strategy<Value = Vec<PriceLevel>> {
+    prop::collection::vec((any_price(), any_size()).prop_map(|(p, s)| PriceLevel::new(p, s)), 0..MAX_LEVELS)
+}
+
+/// A single update with no guarantees: crossing, malformed, or stamped at
+/// any time including zero and before the first snapshot
+pub fn adversarial_update() -> impl Strategy<Value = BookUpdate> {
+    prop_oneof![
+        4 => (side(), any_price(), any_size()).prop_map(|(side, price, size)| BookUpdate::Change { side, price, size }),
+        1 => (0u64..=BASE_TIMESTAMP + 100 * TIMESTAMP_STEP, any_levels(), any_levels())
+            .prop_map(|(timestamp, bids, asks)| BookUpdate::Snapshot { timestamp, bids, asks }),
+    ]
+}
+
+/// Valid sequences delivered out of order or with repeats, and arbitrary
+/// streams of adversarial updates
+pub fn adversarial_sequence(max_len: usize) -> impl Strategy<Value = Vec<BookUpdate>> {
+    prop_oneof![
+        valid_sequence(max_len).prop_shuffle(),
+        valid_sequence(max_len).prop_flat_map(|updates| {
+            let len = updates.len();
+            (Just(updates), prop::collection::vec((0..len, 0..=len), 1..=len.max(1)))
+        })
+        .prop_map(|(mut updates, repeats)| {
+            for (from, to) in repeats {
+                let repeated = updates[from].clone();
+                updates.insert(to.min(updates.len()), repeated);
+            }
+            updates
+        }),
+        prop::collection::vec(adversarial_update(), 1..max_len.max(2)),
+    ]
+}
+
+pub fn sequence(kind: SequenceKind, max_len: usize) -> BoxedStrategy<Vec<BookUpdate>> {
+    match kind {
+        SequenceKind::Valid => valid_sequence(max_len).boxed(),
+        SequenceKind::Adversarial => adversarial_sequence(max_len).boxed(),
+    }
+}
+
+/// Apply an update the way the streaming service does
+pub fn apply(book: &mut OrderBook, update: &BookUpdate) {
+    match update {
+        BookUpdate::Snapshot { timestamp, bids, asks } => {
+            book.replace_with_snapshot_no_hash(String::new(), *timestamp, bids.clone(), asks.clone())
+        }
+        BookUpdate::Change { side, price, size } => book.apply_price_change_no_hash(*side, *price, *size),
+    }
+    book.validate_and_clean();
+}
+
+/// Invariants every book must hold, whatever the feed sent
+///
+/// `previous_timestamp` is the book's timestamp before the last update; it
+/// may only move back to zero (an unstamped REST seed).
+pub fn check_invariants(book: &OrderBook, previous_timestamp: u64) -> Result<(), String> {
+    for (name, levels) in [("bid", &book.bids), ("ask", &book.asks)] {
+        for (price, size) in levels.iter() {
+            if price <= Decimal::ZERO || price >= Decimal::ONE {
+                return Err(format!("{} level at impossible price {}", name, price));
+            }
+            if size <= Decimal::ZERO {
+                return Err(format!("{} level {} holds non-positive size {}", name, price, size));
+            }
+        }
+    }
+    if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+        if bid.price >= ask.price {
+            return Err(format!("book is crossed: bid {} >= ask {}", bid.price, ask.price));
+        }
+    }
+    if !book.get_bids().windows(2).all(|w| w[0].price > w[1].price) {
+        return Err("bids are not strictly descending".to_string());
+    }
+    if !book.get_asks().windows(2).all(|w| w[0].price < w[1].price) {
+        return Err("asks are not strictly ascending".to_string());
+    }
+    if book.timestamp != 0 && book.timestamp < previous_timestamp {
+        return Err(format!(
+            "timestamp went back from {} to {}",
+            previous_timestamp, book.timestamp
+        ));
+    }
+    Ok(())
+}
+
+/// The update after which a replayed book broke an invariant
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub step: usize,
+    pub update: BookUpdate,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {} ({}): {}", self.step, self.update, self.reason)
+    }
+}
+
+/// Replay a sequence into a fresh book, checking invariants after every update
+pub fn replay(updates: &[BookUpdate]) -> Result<OrderBook, Violation> {
+    let mut book = OrderBook::new(FUZZ_ASSET_ID.to_string());
+    for (step, update) in updates.iter().enumerate() {
+        let previous_timestamp = book.timestamp;
+        apply(&mut book, update);
+        check_invariants(&book, previous_timestamp).map_err(|reason| Violation {
+            step,
+            update: update.clone(),
+            reason,
+        })?;
+    }
+    Ok(book)
+}
+
+/// A failing sequence, shrunk to a minimal one
+#[derive(Debug, Clone)]
+pub struct FuzzFailure {
+    pub reason: String,
+    pub updates: Vec<BookUpdate>,
+}
+
+/// Replay `cases` generated sequences; the same seed generates the same sequences
+pub fn fuzz(kind: SequenceKind, cases: u32, max_len: usize, seed: u64) -> Result<Option<FuzzFailure>> {
+    let config = Config {
+        cases,
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let mut seed_bytes = [0u8; 32];
+    for chunk in seed_bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&seed.to_le_bytes());
+    }
+    let mut runner = TestRunner::new_with_rng(config, TestRng::from_seed(RngAlgorithm::ChaCha, &seed_bytes));
+
+    let result = runner.run(&sequence(kind, max_len), |updates| {
+        replay(&updates)
+            .map(|_| ())
+            .map_err(|violation| TestCaseError::fail(violation.to_string()))
+    });
+    match result {
+        Ok(()) => Ok(None),
+        Err(TestError::Fail(reason, updates)) => Ok(Some(FuzzFailure {
+            reason: reason.to_string(),
+            updates,
+        })),
+        Err(TestError::Abort(reason)) => Err(anyhow!("Fuzzing aborted: {}", reason)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ws::levels::PriceLevels;
+    use std::collections::BTreeMap;
+
+    /// Book a valid sequence must produce: the last snapshot plus the changes after it
+    fn expected_levels(updates: &[BookUpdate]) -> (BTreeMap<Decimal, Decimal>, BTreeMap<Decimal, Decimal>) {
+        let mut bids = BTreeMap::new();
+        let mut asks = BTreeMap::new();
+        for update in updates {
+            match update {
+                BookUpdate::Snapshot { bids: b, asks: a, .. } => {
+                    bids = b.iter().map(|l| (l.price, l.size)).collect();
+                    asks = a.iter().map(|l| (l.price, l.size)).collect();
+                }
+                BookUpdate::Change { side, price, size } => {
+                    let levels = if *side == Side::Buy { &mut bids } else { &mut asks };
+                    if size.is_zero() {
+                        levels.remove(price);
+                    } else {
+                        levels.insert(*price, *size);
+                    }
+                }
+            }
+        }
+        (bids, asks)
+    }
+
+    proptest! {
+        #[test]
+        fn valid_sequences_build_the_expected_book(updates in valid_sequence(48)) {
+            let book = replay(&updates).map_err(|v| TestCaseError::fail(v.to_string()))?;
+            let (bids, asks) = expected_levels(&updates);
+            let levels = |side: &PriceLevels| -> BTreeMap<Decimal, Decimal> {
+                side.iter().map(|l| (l.price, l.size)).collect()
+            };
+            prop_assert_eq!(levels(&book.bids), bids);
+            prop_assert_eq!(levels(&book.asks), asks);
+        }
+
+        #[test]
+        fn adversarial_sequences_keep_the_book_sane(updates in adversarial_sequence(48)) {
+            replay(&updates).map_err(|v| TestCaseError::fail(v.to_string()))?;
+        }
+
+        #[test]
+        fn repeated_updates_are_idempotent(updates in valid_sequence(24)) {
+            let twice: Vec<_> = updates.iter().flat_map(|u| [u.clone(), u.clone()]).collect();
+            let once = replay(&updates).unwrap();
+            let doubled = replay(&twice).unwrap();
+            prop_assert_eq!(once.bids, doubled.bids);
+            prop_assert_eq!(once.asks, doubled.asks);
+        }
+    }
+
+    #[test]
+    fn stale_snapshots_are_ignored() {
+        let level = |p: i64| vec![PriceLevel::new(Decimal::new(p, 2), Decimal::ONE)];
+        let book = replay(&[
+            BookUpdate::Snapshot { timestamp: 2_000, bids: level(40), asks: level(60) },
+            BookUpdate::Snapshot { timestamp: 1_000, bids: level(45), asks: level(55) },
+        ])
+        .unwrap();
+        assert_eq!(book.timestamp, 2_000);
+        assert_eq!(book.best_bid().unwrap().price, Decimal::new(40, 2));
+    }
+
+    #[test]
+    fn same_seed_finds_the_same_sequences() {
+        assert!(fuzz(SequenceKind::Adversarial, 32, 16, 7).unwrap().is_none());
+        assert!(fuzz(SequenceKind::Valid, 32, 16, 7).unwrap().is_none());
+    }
+}
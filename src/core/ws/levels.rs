@@ -0,0 +1,282 @@
+//! Price-level array backing one side of an order book
+//!
+//! Outcome token prices lie strictly between 0 and 1 on a decimal tick grid,
+//! so a side is stored as a flat array of sizes indexed by tick instead of a
+//! tree keyed by price. Updates are an index computation and a store, the
+//! best level is tracked incrementally and only rescanned when it empties,
+//! and iteration walks the array in price order. The grid starts at cents
+//! and is refined in place the first time a finer price arrives (or when the
+//! tick size is known), down to [`MAX_SCALE`] decimals.
+
+use rust_decimal::Decimal;
+
+use crate::core::types::market::PriceLevel;
+
+/// Finest price grid a book can hold (0.0001, Polymarket's smallest tick)
+pub const MAX_SCALE: u32 = 4;
+
+/// Grid a new side starts on (0.01)
+const INITIAL_SCALE: u32 = 2;
+
+/// Whether `price` can be held by a book: strictly between 0 and 1 with at
+/// most [`MAX_SCALE`] decimals
+pub fn is_supported_price(price: Decimal) -> bool {
+    price > Decimal::ZERO && price < Decimal::ONE && price.normalize().scale() <= MAX_SCALE
+}
+
+/// Which end of the array is the best level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    /// Best level is the highest price
+    Bid,
+    /// Best level is the lowest price
+    Ask,
+}
+
+/// One side of an order book: size per tick, zero meaning no level
+#[derive(Debug, Clone)]
+pub struct PriceLevels {
+    side: BookSide,
+    /// Decimals of the grid; index `i` is price `i / 10^scale`
+    scale: u32,
+    /// Allocated on the first insert so empty books stay small
+    sizes: Vec<Decimal>,
+    len: usize,
+    best: Option<usize>,
+}
+
+impl PriceLevels {
+    pub fn new(side: BookSide) -> Self {
+        Self {
+            side,
+            scale: INITIAL_SCALE,
+            sizes: Vec::new(),
+            len: 0,
+            best: None,
+        }
+    }
+
+    /// Number of levels
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decimals of the current price grid
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Refine the grid to at least `scale` decimals (capped at [`MAX_SCALE`]);
+    /// existing levels keep their prices
+    pub fn ensure_scale(&mut self, scale: u32) {
+        let scale = scale.min(MAX_SCALE);
+        if scale <= self.scale {
+            return;
+        }
+        let factor = 10usize.pow(scale - self.scale);
+        self.scale = scale;
+        if self.sizes.is_empty() {
+            return;
+        }
+        let mut sizes = vec![Decimal::ZERO; self.slots()];
+        for (index, size) in self.sizes.iter().enumerate() {
+            if !size.is_zero() {
+                sizes[index * factor] = *size;
+            }
+        }
+        self.sizes = sizes;
+        self.best = self.best.map(|index| index * factor);
+    }
+
+    /// Size at `price`, if there is a level there
+    pub fn get(&self, price: &Decimal) -> Option<Decimal> {
+        let index = self.index_of(*price)?;
+        self.sizes.get(index).copied().filter(|size| !size.is_zero())
+    }
+
+    /// Set the level at `price`, returning the size it replaced; a size of
+    /// zero or less removes the level. Unsupported prices are ignored.
+    pub fn insert(&mut self, price: Decimal, size: Decimal) -> Option<Decimal> {
+        if size <= Decimal::ZERO {
+            return self.remove(&price);
+        }
+        if !is_supported_price(price) {
+            return None;
+        }
+        self.ensure_scale(price.normalize().scale());
+        let index = self.index_of(price)?;
+        if self.sizes.is_empty() {
+            self.sizes = vec![Decimal::ZERO; self.slots()];
+        }
+
+        let previous = std::mem::replace(&mut self.sizes[index], size);
+        if !previous.is_zero() {
+            return Some(previous);
+        }
+        self.len += 1;
+        self.best = Some(match (self.side, self.best) {
+            (_, None) => index,
+            (BookSide::Bid, Some(best)) => best.max(index),
+            (BookSide::Ask, Some(best)) => best.min(index),
+        });
+        None
+    }
+
+    /// Remove the level at `price`, returning its size
+    pub fn remove(&mut self, price: &Decimal) -> Option<Decimal> {
+        let index = self.index_of(*price)?;
+        let slot = self.sizes.get_mut(index)?;
+        if slot.is_zero() {
+            return None;
+        }
+        let previous = std::mem::replace(slot, Decimal::ZERO);
+        self.len -= 1;
+        if self.best == Some(index) {
+            self.best = self.next_best(index);
+        }
+        Some(previous)
+    }
+
+    /// Remove every level, keeping the grid and its allocation
+    pub fn clear(&mut self) {
+        if self.len > 0 {
+            self.sizes.fill(Decimal::ZERO);
+        }
+        self.len = 0;
+        self.best = None;
+    }
+
+    /// Best level (highest bid or lowest ask) without scanning
+    pub fn best(&self) -> Option<PriceLevel> {
+        self.best.map(|index| PriceLevel::new(self.price_at(index), self.sizes[index]))
+    }
+
+    /// Levels in ascending price order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = PriceLevel> + '_ {
+        self.sizes
+            .iter()
+            .enumerate()
+            .filter(|(_, size)| !size.is_zero())
+            .map(move |(index, size)| PriceLevel::new(self.price_at(index), *size))
+    }
+
+    /// Levels from the best price outwards
+    pub fn iter_from_best(&self) -> Box<dyn Iterator<Item = PriceLevel> + '_> {
+        match self.side {
+            BookSide::Bid => Box::new(self.iter().rev()),
+            BookSide::Ask => Box::new(self.iter()),
+        }
+    }
+
+    /// Prices in ascending order
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = Decimal> + '_ {
+        self.iter().map(|level| level.price)
+    }
+
+    /// Sizes in ascending price order
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = Decimal> + '_ {
+        self.iter().map(|level| level.size)
+    }
+
+    fn slots(&self) -> usize {
+        10usize.pow(self.scale)
+    }
+
+    fn price_at(&self, index: usize) -> Decimal {
+        Decimal::new(index as i64, self.scale).normalize()
+    }
+
+    /// Array index of `price` on the current grid; `None` when the price is
+    /// unsupported or finer than the grid (so no level can exist there)
+    fn index_of(&self, price: Decimal) -> Option<usize> {
+        if !is_supported_price(price) {
+            return None;
+        }
+        let price = price.normalize();
+        let scale = price.scale();
+        if scale > self.scale {
+            return None;
+        }
+        Some(price.mantissa() as usize * 10usize.pow(self.scale - scale))
+    }
+
+    /// Next occupied index behind `from`, moving away from the touch
+    fn next_best(&self, from: usize) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        match self.side {
+            BookSide::Bid => (0..from).rev().find(|&i| !self.sizes[i].is_zero()),
+            BookSide::Ask => (from + 1..self.sizes.len()).find(|&i| !self.sizes[i].is_zero()),
+        }
+    }
+}
+
+impl PartialEq for PriceLevels {
+    /// Same levels at the same prices, whatever grid each side is on
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn best_level_is_tracked_through_inserts_and_removals() {
+        let mut bids = PriceLevels::new(BookSide::Bid);
+        bids.insert(dec!(0.45), dec!(10));
+        bids.insert(dec!(0.48), dec!(5));
+        bids.insert(dec!(0.40), dec!(7));
+        assert_eq!(bids.best(), Some(PriceLevel::new(dec!(0.48), dec!(5))));
+
+        assert_eq!(bids.remove(&dec!(0.48)), Some(dec!(5)));
+        assert_eq!(bids.best(), Some(PriceLevel::new(dec!(0.45), dec!(10))));
+        bids.insert(dec!(0.45), dec!(0));
+        assert_eq!(bids.best(), Some(PriceLevel::new(dec!(0.40), dec!(7))));
+        assert_eq!(bids.len(), 1);
+
+        let mut asks = PriceLevels::new(BookSide::Ask);
+        asks.insert(dec!(0.55), dec!(3));
+        asks.insert(dec!(0.52), dec!(4));
+        asks.remove(&dec!(0.52));
+        assert_eq!(asks.best(), Some(PriceLevel::new(dec!(0.55), dec!(3))));
+        asks.remove(&dec!(0.55));
+        assert_eq!(asks.best(), None);
+        assert!(asks.is_empty());
+    }
+
+    #[test]
+    fn finer_prices_refine_the_grid_in_place() {
+        let mut asks = PriceLevels::new(BookSide::Ask);
+        asks.insert(dec!(0.50), dec!(100));
+        asks.insert(dec!(0.505), dec!(20));
+        assert_eq!(asks.scale(), 3);
+        assert_eq!(asks.get(&dec!(0.5)), Some(dec!(100)));
+        assert_eq!(
+            asks.iter().collect::<Vec<_>>(),
+            vec![
+                PriceLevel::new(dec!(0.5), dec!(100)),
+                PriceLevel::new(dec!(0.505), dec!(20))
+            ]
+        );
+        assert_eq!(asks.best(), Some(PriceLevel::new(dec!(0.5), dec!(100))));
+    }
+
+    #[test]
+    fn unsupported_prices_are_never_stored() {
+        let mut bids = PriceLevels::new(BookSide::Bid);
+        for price in [dec!(0), dec!(1), dec!(-0.2), dec!(1.5), dec!(0.00001)] {
+            assert_eq!(bids.insert(price, dec!(10)), None);
+            assert_eq!(bids.get(&price), None);
+        }
+        assert!(bids.is_empty());
+        assert_eq!(bids.scale(), INITIAL_SCALE);
+    }
+}
@@ -1,16 +1,22 @@
 //! WebSocket client and state management
 //! 
 //! This module provides the core WebSocket functionality for connecting to
-//! Polymarket's CLOB API and managing real-time market data streams.
+//! Polymarket's CLOB API and managing real-time market data streams. It is the
+//! only WebSocket implementation: feed frames are parsed into [`PolyEvent`]
+//! here and applied to the one [`OrderBook`] type; `types` keeps older import
+//! paths working.
 
 pub mod client;
 pub mod events;
+#[cfg(any(test, feature = "fuzz"))]
+pub mod fuzz;
+pub mod levels;
 pub mod state;
 pub mod types;
 
 // Re-export commonly used items
 pub use client::{WsClient, WsConfig, WsError};
-pub use events::{PolyEvent, WsMessage, parse_message, EventError};
+pub use events::{BookSource, PolyEvent, WsMessage, parse_message, EventError};
 pub use state::{OrderBook, StateError};
 
 // Re-export authentication types
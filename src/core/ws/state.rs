@@ -2,11 +2,11 @@
 
 use crate::core::types::common::Side;
 use crate::core::types::market::PriceLevel;
+use super::levels::{self, BookSide, PriceLevels};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
 use thiserror::Error;
 use tracing::{debug, error, warn};
 
@@ -14,6 +14,14 @@ use tracing::{debug, error, warn};
 pub enum StateError {
     #[error("Hash verification failed: expected {expected}, got {computed}")]
     HashMismatch { expected: String, computed: String },
+    #[error("Stale snapshot: timestamp {timestamp} is older than the book's {current}")]
+    StaleSnapshot { timestamp: u64, current: u64 },
+}
+
+/// Outcome token prices lie strictly between 0 and 1 on a tick no finer than
+/// the book's grid; anything else is malformed
+fn is_valid_price(price: Decimal) -> bool {
+    levels::is_supported_price(price)
 }
 
 /// One aggregated price level (price + size) for hash calculation
@@ -131,10 +139,10 @@ pub struct OrderBook {
     pub market: String,
     /// Last update timestamp
     pub timestamp: u64,
-    /// Bid levels (price -> size), sorted descending by price
-    pub bids: BTreeMap<Decimal, Decimal>,
-    /// Ask levels (price -> size), sorted ascending by price
-    pub asks: BTreeMap<Decimal, Decimal>,
+    /// Bid levels (price -> size), iterated ascending by price
+    pub bids: PriceLevels,
+    /// Ask levels (price -> size), iterated ascending by price
+    pub asks: PriceLevels,
     /// Last known hash from WebSocket feed
     pub last_hash: Option<String>,
     /// Tick size for this asset
@@ -148,8 +156,8 @@ impl OrderBook {
             asset_id,
             market: String::new(),
             timestamp: 0,
-            bids: BTreeMap::new(),
-            asks: BTreeMap::new(),
+            bids: PriceLevels::new(BookSide::Bid),
+            asks: PriceLevels::new(BookSide::Ask),
             last_hash: None,
             tick_size: None,
         }
@@ -164,6 +172,13 @@ impl OrderBook {
         asks: Vec<PriceLevel>,
         hash: String,
     ) -> Result<(), StateError> {
+        if self.is_stale(timestamp) {
+            return Err(StateError::StaleSnapshot {
+                timestamp,
+                current: self.timestamp,
+            });
+        }
+
         // Update market and timestamp
         self.market = market;
         self.timestamp = timestamp;
@@ -173,13 +188,13 @@ impl OrderBook {
 
         // Insert new levels
         for level in bids {
-            if level.size > Decimal::ZERO {
+            if level.size > Decimal::ZERO && is_valid_price(level.price) {
                 self.bids.insert(level.price, level.size);
             }
         }
 
         for level in asks {
-            if level.size > Decimal::ZERO {
+            if level.size > Decimal::ZERO && is_valid_price(level.price) {
                 self.asks.insert(level.price, level.size);
             }
         }
@@ -189,8 +204,8 @@ impl OrderBook {
         if computed_hash != hash {
             // Debug: show what we're hashing
             let debug_json = self.debug_hash_json(
-                &self.bids.iter().map(|(&p, &s)| (p, s)).collect::<Vec<_>>(),
-                &self.asks.iter().map(|(&p, &s)| (p, s)).collect::<Vec<_>>(),
+                &self.bids.iter().collect::<Vec<_>>(),
+                &self.asks.iter().collect::<Vec<_>>(),
             );
             warn!(
                 asset_id = %self.asset_id,
@@ -227,6 +242,16 @@ impl OrderBook {
         bids: Vec<PriceLevel>,
         asks: Vec<PriceLevel>,
     ) {
+        if self.is_stale(timestamp) {
+            warn!(
+                asset_id = %self.asset_id,
+                timestamp,
+                current = self.timestamp,
+                "Ignoring snapshot older than the order book"
+            );
+            return;
+        }
+
         // Update market and timestamp
         self.market = market;
         self.timestamp = timestamp;
@@ -236,13 +261,13 @@ impl OrderBook {
 
         // Insert new levels
         for level in bids {
-            if level.size > Decimal::ZERO {
+            if level.size > Decimal::ZERO && is_valid_price(level.price) {
                 self.bids.insert(level.price, level.size);
             }
         }
 
         for level in asks {
-            if level.size > Decimal::ZERO {
+            if level.size > Decimal::ZERO && is_valid_price(level.price) {
                 self.asks.insert(level.price, level.size);
             }
         }
@@ -264,45 +289,29 @@ impl OrderBook {
         size: Decimal,
         expected_hash: String,
     ) -> Result<(), StateError> {
-        // CAPTURE FULL DIAGNOSTIC STATE BEFORE CHANGE
-        let before_hash = self.compute_polymarket_hash();
-        let before_bids: Vec<(Decimal, Decimal)> =
-            self.bids.iter().map(|(&p, &s)| (p, s)).collect();
-        let before_asks: Vec<(Decimal, Decimal)> =
-            self.asks.iter().map(|(&p, &s)| (p, s)).collect();
-
         // Get the previous value at this price level (if any)
         let previous_value = match side {
-            Side::Buy => self.bids.get(&price).cloned(),
-            Side::Sell => self.asks.get(&price).cloned(),
+            Side::Buy => self.bids.get(&price),
+            Side::Sell => self.asks.get(&price),
         };
 
         // Apply the change
-        match side {
-            Side::Buy => {
-                if size == Decimal::ZERO {
-                    self.bids.remove(&price);
-                } else {
-                    self.bids.insert(price, size);
-                }
-            }
-            Side::Sell => {
-                if size == Decimal::ZERO {
-                    self.asks.remove(&price);
-                } else {
-                    self.asks.insert(price, size);
-                }
-            }
-        }
-
-        // CAPTURE FULL DIAGNOSTIC STATE AFTER CHANGE
+        self.set_level(side, price, size);
         let after_hash = self.compute_polymarket_hash();
-        let after_bids: Vec<(Decimal, Decimal)> = self.bids.iter().map(|(&p, &s)| (p, s)).collect();
-        let after_asks: Vec<(Decimal, Decimal)> = self.asks.iter().map(|(&p, &s)| (p, s)).collect();
 
         // Verify hash after applying change using Polymarket-compatible calculation
         if after_hash != expected_hash {
             // COMPREHENSIVE HASH MISMATCH DIAGNOSTICS
+            // Rebuild the state before the change only now, so the happy path
+            // never copies the book
+            let mut before = self.clone();
+            before.set_level(side, price, previous_value.unwrap_or(Decimal::ZERO));
+            let before_hash = before.compute_polymarket_hash();
+            let before_bids: Vec<PriceLevel> = before.bids.iter().collect();
+            let before_asks: Vec<PriceLevel> = before.asks.iter().collect();
+            let after_bids: Vec<PriceLevel> = self.bids.iter().collect();
+            let after_asks: Vec<PriceLevel> = self.asks.iter().collect();
+
             error!("🚨 HASH MISMATCH DETECTED - STOPPING FOR FULL DIAGNOSTIC 🚨");
             error!("Asset ID: {}", self.asset_id);
             error!("═══════════════════════════════════════════════════════════════");
@@ -345,8 +354,8 @@ impl OrderBook {
 
             // Show actual orderbook data
             error!("📈 BIDS BEFORE:");
-            for (i, (price, size)) in before_bids.iter().enumerate() {
-                error!("  [{}] ${} → {}", i, price, size);
+            for (i, level) in before_bids.iter().enumerate() {
+                error!("  [{}] ${} → {}", i, level.price, level.size);
                 if i >= 10 {
                     error!("  ... ({} more)", before_bids.len() - 10);
                     break;
@@ -354,8 +363,8 @@ impl OrderBook {
             }
 
             error!("📈 BIDS AFTER:");
-            for (i, (price, size)) in after_bids.iter().enumerate() {
-                error!("  [{}] ${} → {}", i, price, size);
+            for (i, level) in after_bids.iter().enumerate() {
+                error!("  [{}] ${} → {}", i, level.price, level.size);
                 if i >= 10 {
                     error!("  ... ({} more)", after_bids.len() - 10);
                     break;
@@ -363,8 +372,8 @@ impl OrderBook {
             }
 
             error!("📉 ASKS BEFORE:");
-            for (i, (price, size)) in before_asks.iter().enumerate() {
-                error!("  [{}] ${} → {}", i, price, size);
+            for (i, level) in before_asks.iter().enumerate() {
+                error!("  [{}] ${} → {}", i, level.price, level.size);
                 if i >= 10 {
                     error!("  ... ({} more)", before_asks.len() - 10);
                     break;
@@ -372,8 +381,8 @@ impl OrderBook {
             }
 
             error!("📉 ASKS AFTER:");
-            for (i, (price, size)) in after_asks.iter().enumerate() {
-                error!("  [{}] ${} → {}", i, price, size);
+            for (i, level) in after_asks.iter().enumerate() {
+                error!("  [{}] ${} → {}", i, level.price, level.size);
                 if i >= 10 {
                     error!("  ... ({} more)", after_asks.len() - 10);
                     break;
@@ -438,22 +447,7 @@ impl OrderBook {
     /// Apply a price change without hash validation
     pub fn apply_price_change_no_hash(&mut self, side: Side, price: Decimal, size: Decimal) {
         // Apply the change
-        match side {
-            Side::Buy => {
-                if size == Decimal::ZERO {
-                    self.bids.remove(&price);
-                } else {
-                    self.bids.insert(price, size);
-                }
-            }
-            Side::Sell => {
-                if size == Decimal::ZERO {
-                    self.asks.remove(&price);
-                } else {
-                    self.asks.insert(price, size);
-                }
-            }
-        }
+        self.set_level(side, price, size);
 
         debug!(
             asset_id = %self.asset_id,
@@ -464,58 +458,72 @@ impl OrderBook {
         );
     }
 
-    /// Update tick size
+    /// Set one level's size; zero or negative sizes remove it and levels at
+    /// impossible prices are ignored
+    fn set_level(&mut self, side: Side, price: Decimal, size: Decimal) {
+        if !is_valid_price(price) {
+            warn!(asset_id = %self.asset_id, price = %price, "Ignoring price change at invalid price");
+            return;
+        }
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if size <= Decimal::ZERO {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, size);
+        }
+    }
+
+    /// Whether a snapshot stamped `timestamp` is older than the book; zero
+    /// means unknown (REST seeds) and is never stale
+    pub fn is_stale(&self, timestamp: u64) -> bool {
+        timestamp != 0 && timestamp < self.timestamp
+    }
+
+    /// Update tick size, moving both sides onto its price grid up front
     pub fn set_tick_size(&mut self, tick_size: Decimal) {
         self.tick_size = Some(tick_size);
+        let scale = tick_size.normalize().scale();
+        self.bids.ensure_scale(scale);
+        self.asks.ensure_scale(scale);
         debug!(asset_id = %self.asset_id, tick_size = %tick_size, "Tick size updated");
     }
 
     /// Get all bids as a vector (highest to lowest)
     pub fn get_bids(&self) -> Vec<PriceLevel> {
-        self.bids
-            .iter()
-            .rev()
-            .map(|(&price, &size)| PriceLevel::new(price, size))
-            .collect()
+        self.bids.iter().rev().collect()
     }
 
     /// Get all asks as a vector (lowest to highest)
     pub fn get_asks(&self) -> Vec<PriceLevel> {
-        self.asks
-            .iter()
-            .map(|(&price, &size)| PriceLevel::new(price, size))
-            .collect()
+        self.asks.iter().collect()
     }
 
     /// Get best bid (highest bid price)
     pub fn best_bid(&self) -> Option<PriceLevel> {
-        self.bids
-            .iter()
-            .next_back()
-            .map(|(&price, &size)| PriceLevel::new(price, size))
+        self.bids.best()
     }
 
     /// Get best ask (lowest ask price)
     pub fn best_ask(&self) -> Option<PriceLevel> {
-        self.asks
-            .iter()
-            .next()
-            .map(|(&price, &size)| PriceLevel::new(price, size))
+        self.asks.best()
     }
 
     /// Compute Polymarket-compatible SHA-1 hash of current order book state
     pub fn compute_polymarket_hash(&self) -> String {
-        // Convert BTreeMaps to OrderSummary vectors
+        // Convert price levels to OrderSummary vectors
         let bids: Vec<OrderSummary> = self
             .bids
             .iter()
-            .map(|(&price, &size)| OrderSummary::new(price, size))
+            .map(|level| OrderSummary::new(level.price, level.size))
             .collect();
 
         let asks: Vec<OrderSummary> = self
             .asks
             .iter()
-            .map(|(&price, &size)| OrderSummary::new(price, size))
+            .map(|level| OrderSummary::new(level.price, level.size))
             .collect();
 
         // Create Polymarket-compatible order book and compute hash using the exact algorithm
@@ -530,15 +538,15 @@ impl OrderBook {
     }
 
     /// Debug helper to show exact JSON being hashed
-    fn debug_hash_json(&self, bids: &[(Decimal, Decimal)], asks: &[(Decimal, Decimal)]) -> String {
+    fn debug_hash_json(&self, bids: &[PriceLevel], asks: &[PriceLevel]) -> String {
         let bids_summary: Vec<OrderSummary> = bids
             .iter()
-            .map(|(price, size)| OrderSummary::new(*price, *size))
+            .map(|level| OrderSummary::new(level.price, level.size))
             .collect();
 
         let asks_summary: Vec<OrderSummary> = asks
             .iter()
-            .map(|(price, size)| OrderSummary::new(*price, *size))
+            .map(|level| OrderSummary::new(level.price, level.size))
             .collect();
 
         let polymarket_book = PolymarketOrderBook::new(
@@ -609,8 +617,8 @@ impl OrderBook {
     /// Validate and clean the orderbook to ensure no crossed markets
     pub fn validate_and_clean(&mut self) -> bool {
         // Get best bid and ask
-        let best_bid = self.bids.keys().rev().next().cloned();
-        let best_ask = self.asks.keys().next().cloned();
+        let best_bid = self.bids.best().map(|level| level.price);
+        let best_ask = self.asks.best().map(|level| level.price);
 
         if let (Some(bid), Some(ask)) = (best_bid, best_ask) {
             if bid >= ask {
@@ -626,8 +634,7 @@ impl OrderBook {
                 let bids_to_remove: Vec<Decimal> = self
                     .bids
                     .keys()
-                    .filter(|&&price| price >= ask)
-                    .cloned()
+                    .filter(|&price| price >= ask)
                     .collect();
 
                 for price in bids_to_remove {
@@ -635,13 +642,12 @@ impl OrderBook {
                 }
 
                 // Remove all asks <= best bid after the removals above
-                let new_best_bid = self.bids.keys().rev().next().cloned();
+                let new_best_bid = self.bids.best().map(|level| level.price);
                 if let Some(new_bid) = new_best_bid {
                     let asks_to_remove: Vec<Decimal> = self
                         .asks
                         .keys()
-                        .filter(|&&price| price <= new_bid)
-                        .cloned()
+                        .filter(|&price| price <= new_bid)
                         .collect();
 
                     for price in asks_to_remove {
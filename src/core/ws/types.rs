@@ -1,38 +1,11 @@
-//! WebSocket event types for storage serialization
-
-use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
-
-/// Market event types that can be stored
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum MarketEvent {
-    /// Order book change event
-    BookChange(BookChange),
-    /// Trade event
-    Trade(Trade),
-    /// Tick size change event
-    TickSizeChange(TickSizeChange),
-}
-
-/// Book change event
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BookChange {
-    pub side: String,
-    pub changes: Vec<(Decimal, Decimal)>,
-}
-
-/// Trade event
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Trade {
-    pub id: String,
-    pub price: Decimal,
-    pub size: Decimal,
-    pub side: String,
-}
-
-/// Tick size change event
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TickSizeChange {
-    pub tick_size: Decimal,
-    pub min_tick_size: Option<Decimal>,
-}
+//! Older import path for the WebSocket event and book types
+//!
+//! Feed events are parsed once, in `events`, into [`PolyEvent`]; there is a
+//! single [`OrderBook`] in `state`. Events handed to the execution engine or
+//! stored use `core::execution::events::MarketEvent`, converted from
+//! `PolyEvent`. This module used to define a separate string-sided event model
+//! for storage; it now only re-exports the shared types.
+
+pub use super::events::{BookSource, PolyEvent, WsMessage};
+pub use super::state::OrderBook;
+pub use crate::core::execution::events::MarketEvent;
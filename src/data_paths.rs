@@ -15,10 +15,27 @@ pub const RUNS_DIR: &str = "runs";
 pub const AUTH_DIR: &str = "auth";
 pub const LOGS_DIR: &str = "logs";
 
+/// Directory (relative to the base data directory) holding non-default profiles
+pub const PROFILES_DIR: &str = "profiles";
+
+/// Profile whose tree is the base data directory itself
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// File (in the base data directory) recording the active profile
+pub const ACTIVE_PROFILE_FILE: &str = "active_profile";
+
+/// Environment variable selecting a profile when `--profile` is not given
+pub const PROFILE_ENV_VAR: &str = "POLYBOT_PROFILE";
+
 /// Helper struct to manage data paths
 #[derive(Clone, Debug)]
 pub struct DataPaths {
+    /// Root of the active profile's tree (credentials, portfolio, datasets, logs)
     root: PathBuf,
+    /// Base data directory shared by all profiles
+    base: PathBuf,
+    /// Active profile name
+    profile: String,
 }
 
 impl DataPaths {
@@ -26,19 +43,87 @@ impl DataPaths {
     pub fn new(root: impl AsRef<Path>) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
+            base: root.as_ref().to_path_buf(),
+            profile: DEFAULT_PROFILE.to_string(),
+        }
+    }
+
+    /// Create DataPaths for a named profile under a base data directory
+    ///
+    /// The default profile maps to the base directory itself so existing data
+    /// directories keep working; other profiles live in `<base>/profiles/<name>`.
+    pub fn with_profile(base: impl AsRef<Path>, profile: &str) -> Self {
+        let base = base.as_ref().to_path_buf();
+        let root = if profile == DEFAULT_PROFILE {
+            base.clone()
+        } else {
+            base.join(PROFILES_DIR).join(profile)
+        };
+        Self {
+            root,
+            base,
+            profile: profile.to_string(),
         }
     }
 
+    /// Resolve the profile to use: explicit flag, then `POLYBOT_PROFILE`, then
+    /// the recorded active profile, then the default profile
+    pub fn resolve(base: impl AsRef<Path>, requested: Option<&str>) -> anyhow::Result<Self> {
+        let store = ProfileStore::new(base.as_ref());
+        let profile = match requested {
+            Some(name) => name.to_string(),
+            None => match std::env::var(PROFILE_ENV_VAR) {
+                Ok(name) if !name.is_empty() => name,
+                _ => store.active_profile(),
+            },
+        };
+
+        ProfileStore::validate_name(&profile)?;
+        if !store.exists(&profile) {
+            return Err(anyhow::anyhow!(
+                "Profile '{}' does not exist. Create it with 'polybot profile create {}'",
+                profile,
+                profile
+            ));
+        }
+
+        Ok(Self::with_profile(base, &profile))
+    }
+
     /// Get the root data directory
     pub fn root(&self) -> &PathBuf {
         &self.root
     }
 
+    /// Get the base data directory shared by all profiles
+    pub fn base(&self) -> &PathBuf {
+        &self.base
+    }
+
+    /// Get the active profile name
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
     /// Get the datasets directory (default location for all dataset outputs)
     pub fn datasets(&self) -> PathBuf {
         self.root.join(DATASETS_DIR)
     }
 
+    /// A source dataset given by path (absolute or `./`-relative) or by name
+    /// under the datasets directory; errors when it doesn't exist
+    pub fn source_dataset(&self, dataset: &str) -> anyhow::Result<PathBuf> {
+        let path = if dataset.starts_with('/') || dataset.starts_with("./") {
+            PathBuf::from(dataset)
+        } else {
+            self.datasets().join(dataset)
+        };
+        if !path.exists() {
+            anyhow::bail!("Source dataset not found: {}", path.display());
+        }
+        Ok(path)
+    }
+
     /// Get the runs directory (for pipeline and command outputs)
     pub fn runs(&self) -> PathBuf {
         self.datasets().join(RUNS_DIR)
@@ -54,6 +139,11 @@ impl DataPaths {
         self.root.join(LOGS_DIR)
     }
 
+    /// Directory of one trading account's portfolio files
+    pub fn account(&self, address: &str) -> PathBuf {
+        self.root.join("trade").join("account").join(address)
+    }
+
     /// Get the root data directory
     pub fn data(&self) -> PathBuf {
         self.root.clone()
@@ -69,3 +159,91 @@ impl DataPaths {
         Ok(())
     }
 }
+
+/// Manages the profiles stored under a base data directory
+#[derive(Clone, Debug)]
+pub struct ProfileStore {
+    base: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(base: impl AsRef<Path>) -> Self {
+        Self {
+            base: base.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Profile names are used as directory names, so keep them simple
+    pub fn validate_name(name: &str) -> anyhow::Result<()> {
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Invalid profile name '{}': use letters, digits, '-' or '_'",
+                name
+            ))
+        }
+    }
+
+    /// Whether a profile exists (the default profile always does)
+    pub fn exists(&self, name: &str) -> bool {
+        name == DEFAULT_PROFILE || self.base.join(PROFILES_DIR).join(name).is_dir()
+    }
+
+    /// All profiles, default first
+    pub fn list(&self) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(self.base.join(PROFILES_DIR))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                    .filter(|name| Self::validate_name(name).is_ok() && name != DEFAULT_PROFILE)
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE.to_string());
+        names
+    }
+
+    /// Profile recorded as active, falling back to the default profile
+    pub fn active_profile(&self) -> String {
+        std::fs::read_to_string(self.base.join(ACTIVE_PROFILE_FILE))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|name| Self::validate_name(name).is_ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Create a profile and its directory tree
+    pub fn create(&self, name: &str) -> anyhow::Result<DataPaths> {
+        Self::validate_name(name)?;
+        if self.exists(name) {
+            return Err(anyhow::anyhow!("Profile '{}' already exists", name));
+        }
+        let paths = DataPaths::with_profile(&self.base, name);
+        paths.ensure_directories()?;
+        Ok(paths)
+    }
+
+    /// Record the active profile used when neither `--profile` nor `POLYBOT_PROFILE` is set
+    pub fn switch(&self, name: &str) -> anyhow::Result<()> {
+        Self::validate_name(name)?;
+        if !self.exists(name) {
+            return Err(anyhow::anyhow!(
+                "Profile '{}' does not exist. Create it with 'polybot profile create {}'",
+                name,
+                name
+            ));
+        }
+        std::fs::create_dir_all(&self.base)?;
+        std::fs::write(self.base.join(ACTIVE_PROFILE_FILE), format!("{}\n", name))?;
+        Ok(())
+    }
+}
@@ -1,11 +1,50 @@
-//! Ethereum utilities for address derivation and key management
+//! Ethereum utilities for address derivation, key management, gas pricing and
+//! transaction signing
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Bytes, Signature, U256};
 use ethers_core::utils::secret_key_to_address;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::time::Duration;
 
-/// Derive Ethereum address from private key hex string
-pub fn derive_address_from_private_key(private_key_hex: &str) -> Result<String> {
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+const GWEI_DECIMALS: u32 = 9;
+const NATIVE_DECIMALS: u32 = 18;
+
+/// Blocks sampled by `eth_feeHistory`
+const FEE_HISTORY_BLOCKS: u64 = 10;
+/// Reward percentile used as the priority fee
+const PRIORITY_FEE_PERCENTILE: u64 = 50;
+/// Polygon validators reject tips below 30 gwei, so never suggest less
+const MIN_PRIORITY_FEE_WEI: u128 = 30 * WEI_PER_GWEI;
+
+/// On-chain action a trading wallet needs and the gas limit it is sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnChainAction {
+    pub name: &'static str,
+    pub gas_limit: u64,
+}
+
+impl OnChainAction {
+    pub const APPROVE: Self = Self { name: "approve", gas_limit: 60_000 };
+    pub const REDEEM: Self = Self { name: "redeem", gas_limit: 200_000 };
+    pub const DEPOSIT: Self = Self { name: "deposit", gas_limit: 80_000 };
+    pub const WITHDRAW: Self = Self { name: "withdraw", gas_limit: 80_000 };
+}
+
+/// Actions shown in cost previews
+pub const ON_CHAIN_ACTIONS: [OnChainAction; 4] = [
+    OnChainAction::APPROVE,
+    OnChainAction::REDEEM,
+    OnChainAction::DEPOSIT,
+    OnChainAction::WITHDRAW,
+];
+
+/// Parse a private key hex string, with or without 0x prefix
+pub fn signing_key_from_hex(private_key_hex: &str) -> Result<SigningKey> {
     // Remove 0x prefix if present
     let private_key_hex = private_key_hex.trim_start_matches("0x");
 
@@ -13,9 +52,12 @@ pub fn derive_address_from_private_key(private_key_hex: &str) -> Result<String>
     let private_key_bytes =
         hex::decode(private_key_hex).map_err(|e| anyhow!("Invalid private key hex: {}", e))?;
 
-    // Create signing key
-    let signing_key = SigningKey::from_slice(&private_key_bytes)
-        .map_err(|e| anyhow!("Invalid private key: {}", e))?;
+    SigningKey::from_slice(&private_key_bytes).map_err(|e| anyhow!("Invalid private key: {}", e))
+}
+
+/// Derive Ethereum address from private key hex string
+pub fn derive_address_from_private_key(private_key_hex: &str) -> Result<String> {
+    let signing_key = signing_key_from_hex(private_key_hex)?;
 
     // Derive address
     let address = secret_key_to_address(&signing_key);
@@ -24,6 +66,248 @@ pub fn derive_address_from_private_key(private_key_hex: &str) -> Result<String>
     Ok(format!("{:?}", address))
 }
 
+/// Current fees from the gas oracle, in wei
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasFees {
+    /// Base fee of the next block (the gas price on pre-EIP-1559 chains)
+    pub base_fee: u128,
+    pub priority_fee: u128,
+    /// Whether the chain reports EIP-1559 fee history
+    pub eip1559: bool,
+}
+
+impl GasFees {
+    /// Fees from an `eth_feeHistory` result: the last base fee is the next
+    /// block's, the priority fee is the median tip of the sampled blocks
+    pub fn from_fee_history(history: &Value) -> Result<Self> {
+        let base_fee = history
+            .get("baseFeePerGas")
+            .and_then(|v| v.as_array())
+            .and_then(|fees| fees.last())
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Fee history has no base fee"))
+            .and_then(parse_hex_quantity)?;
+
+        let mut tips: Vec<u128> = history
+            .get("reward")
+            .and_then(|v| v.as_array())
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .filter_map(|block| block.get(0)?.as_str())
+                    .filter_map(|tip| parse_hex_quantity(tip).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        tips.sort_unstable();
+        let median_tip = tips.get(tips.len() / 2).copied().unwrap_or_default();
+
+        Ok(Self {
+            base_fee,
+            priority_fee: median_tip.max(MIN_PRIORITY_FEE_WEI),
+            eip1559: true,
+        })
+    }
+
+    /// Fee cap that survives the base fee doubling before inclusion
+    pub fn max_fee_per_gas(&self) -> u128 {
+        if self.eip1559 {
+            self.base_fee * 2 + self.priority_fee
+        } else {
+            self.base_fee
+        }
+    }
+
+    /// Worst case cost of an action with the given gas limit
+    pub fn preview(&self, action: &str, gas_limit: u64) -> TxCostPreview {
+        TxCostPreview {
+            action: action.to_string(),
+            gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas(),
+            max_cost: self.max_fee_per_gas() * gas_limit as u128,
+        }
+    }
+
+    /// Abort when the fee cap is above `max_fee_gwei`
+    pub fn ensure_below(&self, max_fee_gwei: Option<Decimal>) -> Result<()> {
+        ensure_max_fee(self.max_fee_per_gas(), max_fee_gwei)
+    }
+}
+
+/// Abort when a fee cap in wei is above `max_fee_gwei`
+pub fn ensure_max_fee(max_fee_per_gas: u128, max_fee_gwei: Option<Decimal>) -> Result<()> {
+    let Some(limit) = max_fee_gwei else {
+        return Ok(());
+    };
+    let current = wei_to_gwei(max_fee_per_gas);
+    if current > limit {
+        return Err(anyhow!(
+            "Current max fee {} gwei exceeds --max-fee {} gwei",
+            current.round_dp(2),
+            limit
+        ));
+    }
+    Ok(())
+}
+
+/// Cost estimate shown before an on-chain action
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxCostPreview {
+    pub action: String,
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u128,
+    /// In wei of the native token
+    pub max_cost: u128,
+}
+
+impl std::fmt::Display for TxCostPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<10} {:>8} gas @ {} gwei = max {} POL",
+            self.action,
+            self.gas_limit,
+            wei_to_gwei(self.max_fee_per_gas).round_dp(2),
+            wei_to_native(self.max_cost).round_dp(6)
+        )
+    }
+}
+
+/// Sign a transaction and return its raw encoding for `eth_sendRawTransaction`
+pub fn sign_transaction(signing_key: &SigningKey, tx: &TypedTransaction) -> Result<Bytes> {
+    let signature = sign_digest(signing_key, tx.sighash().to_fixed_bytes())?;
+    Ok(tx.rlp_signed(&signature))
+}
+
+/// Recoverable signature over a 32 byte digest, with `v` as 27 or 28
+pub fn sign_digest(signing_key: &SigningKey, digest: [u8; 32]) -> Result<Signature> {
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(&digest)
+        .map_err(|e| anyhow!("Failed to sign digest: {}", e))?;
+    let bytes = signature.to_bytes();
+    Ok(Signature {
+        r: U256::from_big_endian(&bytes[..32]),
+        s: U256::from_big_endian(&bytes[32..]),
+        v: 27 + recovery_id.to_byte() as u64,
+    })
+}
+
+/// Minimal JSON-RPC client for the handful of node calls the bot makes
+#[derive(Clone)]
+pub struct JsonRpcClient {
+    http: reqwest::Client,
+    rpc_url: String,
+}
+
+impl JsonRpcClient {
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()?;
+        Ok(Self {
+            http,
+            rpc_url: rpc_url.to_string(),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .with_context(|| format!("RPC request to {} failed", self.rpc_url))?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("{} failed: {}", method, error));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    /// `eth_getTransactionCount` at `block` ("latest" or "pending")
+    pub async fn transaction_count(&self, address: &str, block: &str) -> Result<u64> {
+        let count = self
+            .call("eth_getTransactionCount", json!([address, block]))
+            .await?;
+        let count = count
+            .as_str()
+            .ok_or_else(|| anyhow!("eth_getTransactionCount returned no value"))
+            .and_then(parse_hex_quantity)?;
+        u64::try_from(count).context("Nonce out of range")
+    }
+}
+
+/// EIP-1559 aware gas price oracle over JSON-RPC
+pub struct GasOracle {
+    rpc: JsonRpcClient,
+}
+
+impl GasOracle {
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        Ok(Self {
+            rpc: JsonRpcClient::new(rpc_url)?,
+        })
+    }
+
+    pub fn with_client(rpc: JsonRpcClient) -> Self {
+        Self { rpc }
+    }
+
+    /// Current fees, falling back to `eth_gasPrice` on chains without fee history
+    pub async fn fees(&self) -> Result<GasFees> {
+        let history = self
+            .rpc
+            .call(
+                "eth_feeHistory",
+                json!([format!("{:#x}", FEE_HISTORY_BLOCKS), "latest", [PRIORITY_FEE_PERCENTILE]]),
+            )
+            .await;
+        match history.and_then(|history| GasFees::from_fee_history(&history)) {
+            Ok(fees) => Ok(fees),
+            Err(e) => {
+                tracing::debug!("eth_feeHistory unavailable, using eth_gasPrice: {}", e);
+                let price = self.rpc.call("eth_gasPrice", json!([])).await?;
+                let gas_price = price
+                    .as_str()
+                    .ok_or_else(|| anyhow!("eth_gasPrice returned no value"))
+                    .and_then(parse_hex_quantity)?;
+                Ok(GasFees {
+                    base_fee: gas_price,
+                    priority_fee: 0,
+                    eip1559: false,
+                })
+            }
+        }
+    }
+}
+
+/// Parse a JSON-RPC hex quantity such as `0x1a`
+pub fn parse_hex_quantity(hex: &str) -> Result<u128> {
+    let digits = hex.trim_start_matches("0x");
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16).map_err(|e| anyhow!("Invalid hex quantity {}: {}", hex, e))
+}
+
+pub fn wei_to_gwei(wei: u128) -> Decimal {
+    Decimal::from_i128_with_scale(wei as i128, GWEI_DECIMALS)
+}
+
+/// Wei to whole units of the chain's native token
+pub fn wei_to_native(wei: u128) -> Decimal {
+    Decimal::from_i128_with_scale(wei as i128, NATIVE_DECIMALS)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,4 +330,37 @@ mod tests {
         let result = derive_address_from_private_key(private_key);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_fee_history_uses_next_base_fee_and_median_tip() {
+        let history = json!({
+            "baseFeePerGas": ["0x3b9aca00", "0x77359400"],
+            "reward": [["0x9502f9000"], ["0xba43b7400"], ["0xdf8475800"]]
+        });
+        let fees = GasFees::from_fee_history(&history).unwrap();
+        assert_eq!(fees.base_fee, 2 * WEI_PER_GWEI);
+        assert_eq!(fees.priority_fee, 50 * WEI_PER_GWEI);
+        assert_eq!(fees.max_fee_per_gas(), 54 * WEI_PER_GWEI);
+    }
+
+    #[test]
+    fn test_priority_fee_floor_and_max_fee_guard() {
+        let history = json!({ "baseFeePerGas": ["0x0"], "reward": [["0x1"]] });
+        let fees = GasFees::from_fee_history(&history).unwrap();
+        assert_eq!(fees.priority_fee, MIN_PRIORITY_FEE_WEI);
+        assert!(fees.ensure_below(Some(Decimal::from(30))).is_ok());
+        assert!(fees.ensure_below(Some(Decimal::from(29))).is_err());
+        assert!(fees.ensure_below(None).is_ok());
+    }
+
+    #[test]
+    fn test_cost_preview() {
+        let fees = GasFees {
+            base_fee: 100 * WEI_PER_GWEI,
+            priority_fee: 0,
+            eip1559: false,
+        };
+        let preview = fees.preview("approve", 50_000);
+        assert_eq!(wei_to_native(preview.max_cost), Decimal::new(5, 3));
+    }
 }
@@ -9,14 +9,49 @@ use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
 use crate::data_paths::DataPaths;
+use crate::number_format;
 use crate::markets::datasets::{DatasetManager, DatasetManagerConfig};
-use crate::core::execution::orders::{EnhancedOrder, OrderManager};
+use crate::markets::datasets::replay::{CaptureReplay, ReplayFeed, ReplayHandle};
+use crate::core::execution::book_view::{BookView, DepthDisplay, PriceAggregation};
+use crate::core::execution::cross_rate::CrossRate;
+use crate::core::execution::orders::{EnhancedOrder, OrderConfig, OrderManager};
+use crate::core::diagnostics::{DebugBundle, SessionRecorder, SessionState};
+use crate::core::risk::RiskConfig;
+use crate::gui::components::dataset_preview::{
+    market_token_ids, read_dataset_markets, DatasetPreviews,
+};
+use crate::gui::components::market_data::{depth_chart, enrichment_age_label};
+use crate::gui::components::orders::{CancelAllDialog, OrderTicket};
+use crate::gui::components::pnl_chart::PnlChart;
+use crate::gui::components::search_dataset::SearchDatasetForm;
+use crate::gui::components::search_palette::{PaletteCommand, PaletteItem, SearchPalette};
+use crate::gui::components::shortcuts::ShortcutEditor;
+use crate::gui::components::strategies::StrategyPanel;
+use crate::strategy::control::DEFAULT_CONTROL_PORT;
+use crate::gui::components::token_notes::TokenNotes;
+use crate::gui::components::token_sidebar::TokenSidebar;
+use crate::gui::notifications::{
+    AlertToast, AlertWatcher, FillWatcher, NotificationKind, Notifier,
+};
+use crate::gui::onboarding::{self, OnboardingWizard, WizardOutcome};
 use crate::gui::panes::Pane;
+use crate::gui::repaint::{FrameStats, RepaintScheduler, DEFAULT_MAX_FPS};
+use crate::gui::subscriptions::{
+    spawn_data_task, PaneDataUpdate, Subscription, SubscriptionRegistry, TokenData,
+};
 use crate::gui::services::PortfolioService;
-use crate::core::portfolio::controller::PortfolioManager;
+use crate::gui::token_budget::TokenBudgetRunner;
+use crate::core::portfolio::display::market_labels::truncate;
+use crate::core::portfolio::exposure::{ExposureGroup, ExposureGrouping, MarketMeta};
+use crate::core::portfolio::units::{self, OutcomeExposure};
+use crate::core::portfolio::PortfolioManager;
+use crate::core::services::streaming::budget::{TokenBudgetConfig, TOKEN_BUDGET_CONFIG_FILE};
 use crate::core::services::streaming::{StreamingService, StreamingServiceConfig, StreamingServiceTrait};
-use crate::core::ws::{PolyEvent, WsConfig};
-use crate::core::types::common::Side;
+use crate::core::ws::{OrderBook, PolyEvent, WsConfig};
+use crate::core::types::common::{ShortId, Side};
+use crate::keybindings::{self, Action, KeyChord, Keymap, Surface};
+use crate::markets::snapshot_diff::{MarketSnapshot, SnapshotDiff, SnapshotHistory};
+use crate::typed_store::{freshness, EnrichmentFreshness};
 
 // Additional imports for screenshot functionality
 use std::fs;
@@ -32,7 +67,16 @@ struct SavedLayout {
     pinned_tiles: Vec<TileId>,  // Using Vec since HashSet doesn't serialize nicely
 }
 
-#[derive(Clone, Debug)]
+/// File (under `<data_dir>/config/`) caching the last dataset scan
+const DATASET_CACHE_FILE: &str = "dataset_cache.json";
+
+/// How often enrichment freshness is re-read from the typed store
+const FRESHNESS_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Tokens each WebSocket connection of the streaming service carries
+const TOKENS_PER_CONNECTION: usize = 25;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct DatasetInfo {
     name: String,
     path: std::path::PathBuf,
@@ -72,6 +116,65 @@ struct OrderBookChange {
     is_bid: bool,
 }
 
+/// One side of a book and the same side before the latest refresh
+struct BookSideChange {
+    levels: Vec<crate::core::types::market::PriceLevel>,
+    previous: Option<Vec<crate::core::types::market::PriceLevel>>,
+    is_bid: bool,
+}
+
+impl BookSideChange {
+    /// Levels that are new or resized, looking old sizes up by price
+    fn changed_levels(&self) -> impl Iterator<Item = &crate::core::types::market::PriceLevel> + '_ {
+        let previous_sizes: HashMap<Decimal, Decimal> = self
+            .previous
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|level| (level.price, level.size))
+            .collect();
+        let had_previous = self.previous.is_some();
+        self.levels.iter().filter(move |level| {
+            !had_previous || previous_sizes.get(&level.price) != Some(&level.size)
+        })
+    }
+}
+
+impl OrderBookChange {
+    /// Levels of `book` that are new or resized since `previous`
+    fn between(
+        token_id: &str,
+        previous: Option<&crate::core::ws::OrderBook>,
+        book: &crate::core::ws::OrderBook,
+    ) -> Vec<OrderBookChange> {
+        let now = Instant::now();
+        let sides = [
+            BookSideChange {
+                levels: book.get_bids(),
+                previous: previous.map(|p| p.get_bids()),
+                is_bid: true,
+            },
+            BookSideChange {
+                levels: book.get_asks(),
+                previous: previous.map(|p| p.get_asks()),
+                is_bid: false,
+            },
+        ];
+        sides
+            .iter()
+            .flat_map(|side| {
+                side.changed_levels().map(|level| OrderBookChange {
+                    token_id: token_id.to_string(),
+                    price: level.price,
+                    size: level.size,
+                    changed_at: now,
+                    is_bid: side.is_bid,
+                })
+            })
+            .collect()
+    }
+}
+
 pub struct TradingApp {
     /// Tile management
     tree: Tree<Pane>,
@@ -85,13 +188,28 @@ pub struct TradingApp {
     /// Trading infrastructure
     portfolio_manager: Arc<PortfolioManager>,
     portfolio_service: PortfolioService,
-    _order_manager: OrderManager,
+    /// Places and cancels the GUI's orders, so its throttle spans all of them
+    order_manager: OrderManager,
 
     /// Data state
     orders_cache: Arc<RwLock<Vec<EnhancedOrder>>>,
 
+    /// Risk configuration (large order threshold) from risk.yaml
+    risk_config: RiskConfig,
+
+    /// First-run wizard, shown instead of the canvas until finished or skipped
+    onboarding: Option<OnboardingWizard>,
+
     /// UI state
-    show_new_order_dialog: bool,
+    order_ticket: OrderTicket,
+    cancel_all: CancelAllDialog,
+    search_palette: SearchPalette,
+    strategy_panel: StrategyPanel,
+    pnl_chart: PnlChart,
+    /// The strategy panel is in its own window instead of a pane
+    strategy_panel_detached: bool,
+    /// Set by the strategy pane's Detach button, handled after the tiles are drawn
+    detach_strategy_panel: bool,
     show_dataset_selector: bool,
     show_streams_overview: bool,
     sidebar_width: f32,
@@ -101,23 +219,48 @@ pub struct TradingApp {
     show_about: bool,
     show_settings: bool,
 
+    /// Keyboard shortcuts from keybindings.yaml, edited in Settings
+    keymap: Keymap,
+    shortcut_editor: ShortcutEditor,
+
+    /// Sound and flash cues for fills and alerts, listed in the notifications center
+    notifier: Notifier,
+    show_notifications: bool,
+    /// Sampled books of the streamed tokens, for the market cards of notifications
+    market_history: SnapshotHistory,
+    /// Polls the account's activity for fills once its address is known
+    fill_watcher: Option<FillWatcher>,
+    /// Routes token alerts by severity and follows up on open alerts
+    alert_watcher: Option<AlertWatcher>,
+    /// Alert routing couldn't start (e.g. invalid alerts.yaml); not retried
+    alert_watcher_failed: bool,
+    /// Alerts routed to a toast
+    alert_toast: AlertToast,
+
     /// Streaming state
     streaming_service: Option<Arc<StreamingService>>,
     streaming_assets: Vec<String>,
+    /// Picks the streamed tokens when `token_budget.yaml` sets a budget
+    token_budget: Option<TokenBudgetRunner>,
+    /// Orders pane shows asset ids instead of market questions
+    orders_raw_ids: bool,
     streaming_state: StreamingState,
     streaming_task: Option<tokio::task::JoinHandle<()>>,
     streaming_progress_rx: Option<tokio::sync::mpsc::Receiver<(f32, String)>>,
     streaming_result_rx:
         Option<tokio::sync::oneshot::Receiver<Result<Arc<StreamingService>, anyhow::Error>>>,
+    /// Capture window played instead of streaming live, and its speed
+    replay: Option<(Arc<CaptureReplay>, f64)>,
+    /// Feed the replay is played into once its streaming service is ready
+    replay_feed: Option<ReplayFeed>,
+    replay_handle: Option<ReplayHandle>,
 
     /// Position refresh state (unused but kept for compatibility)
     _last_position_fetch: Option<std::time::Instant>,
     _is_fetching_positions: bool,
 
-    /// Orderbook state
+    /// Token of the most recently opened orderbook, the order ticket's default
     current_token_id: Option<String>,
-    current_bids: Vec<crate::core::types::market::PriceLevel>,
-    current_asks: Vec<crate::core::types::market::PriceLevel>,
 
     /// Track orderbook changes for flash animation
     orderbook_changes: Vec<OrderBookChange>,
@@ -125,6 +268,27 @@ pub struct TradingApp {
     /// Dataset selection state
     available_datasets: Vec<DatasetInfo>,
     selected_datasets: std::collections::HashSet<String>,
+    /// Background dataset scan, `Some` while it runs
+    dataset_scan_rx: Option<std::sync::mpsc::Receiver<Vec<DatasetInfo>>>,
+    /// A rescan was asked for while one ran, e.g. after a dataset was written
+    dataset_rescan_pending: bool,
+    /// Quick start was requested before the first scan finished
+    quick_start_pending: bool,
+    /// Market lists of expanded datasets and the markets picked from them
+    dataset_previews: DatasetPreviews,
+    /// Dataset selector form writing a dataset from a market search
+    search_dataset: SearchDatasetForm,
+
+    /// Last enrichment of each token, re-read in the background
+    enrichment_freshness: HashMap<String, EnrichmentFreshness>,
+    freshness_rx: Option<std::sync::mpsc::Receiver<HashMap<String, EnrichmentFreshness>>>,
+    freshness_loaded_at: Option<Instant>,
+    /// Market metadata of tokens shown in depth panes
+    token_sidebar: TokenSidebar,
+    /// Notes and price alerts attached from the Streams pane
+    token_notes: TokenNotes,
+    /// Portfolio pane shows exposure groups instead of positions when set
+    portfolio_grouping: Option<ExposureGrouping>,
 
     /// Token activity tracking for streams overview
     token_activities: Arc<RwLock<HashMap<String, TokenActivity>>>,
@@ -142,8 +306,9 @@ pub struct TradingApp {
     worker_stream_events: Vec<PolyEvent>,
     worker_stream_max_events: usize,
     cached_streaming_tokens: Vec<String>,
-    cached_orderbook: Option<crate::core::ws::OrderBook>,
-    cached_last_trade_price: Option<(Decimal, u64)>,
+    cached_cross_rates: Vec<CrossRate>,
+    /// Book and last trade of every token a pane subscribes to
+    token_data: HashMap<String, TokenData>,
     cached_streaming_stats: Option<crate::core::services::streaming::traits::StreamingStats>,
     cached_worker_statuses: Vec<crate::core::services::streaming::traits::WorkerStatus>,
 
@@ -151,6 +316,11 @@ pub struct TradingApp {
     pending_screenshot: Option<(std::path::PathBuf, String)>,
     pending_tile_screenshot: Option<(TileId, std::path::PathBuf, String)>,
     screenshot_message: Option<(String, std::time::Instant)>,
+
+    /// Bug report state: periodic session recording and the pending debug bundle
+    session_recorder: SessionRecorder,
+    issue_report_rx: Option<std::sync::mpsc::Receiver<Result<std::path::PathBuf, String>>>,
+    issue_report_message: Option<(String, std::time::Instant)>,
     
     /// Pinned tiles that should not be moved or affected by auto-arrange
     pinned_tiles: HashSet<TileId>,
@@ -165,23 +335,24 @@ pub struct TradingApp {
     /// Background task handle for data updates
     _data_update_task: Option<tokio::task::JoinHandle<()>>,
 
-    /// Channels for receiving cached data from background task
-    cached_data_receivers: Option<(
-        std::sync::mpsc::Receiver<Vec<String>>,
-        std::sync::mpsc::Receiver<crate::core::ws::OrderBook>,
-        std::sync::mpsc::Receiver<(Decimal, u64)>,
-        std::sync::mpsc::Receiver<crate::core::services::streaming::traits::StreamingStats>,
-        std::sync::mpsc::Receiver<Vec<crate::core::services::streaming::traits::WorkerStatus>>,
-    )>,
+    /// Subscriptions held by open panes, and the channels to the background task
+    subscriptions: SubscriptionRegistry,
+    subscription_tx: Option<std::sync::mpsc::Sender<std::collections::BTreeSet<Subscription>>>,
+    pane_data_rx: Option<std::sync::mpsc::Receiver<PaneDataUpdate>>,
 
-    /// Channel for sending current token updates to background task
-    current_token_sender: Option<std::sync::mpsc::Sender<Option<String>>>,
+    /// Price bin width for market depth panes
+    depth_aggregation: PriceAggregation,
 
     /// Track if this is the first update call
     first_update: bool,
 
     /// Track fullscreen state for proper F11 toggling
     is_fullscreen: bool,
+
+    /// Coalesces streaming updates into rate-limited repaints of visible panes
+    repaint: RepaintScheduler<TileId>,
+    frame_stats: FrameStats,
+    show_perf_overlay: bool,
 }
 
 impl TradingApp {
@@ -289,18 +460,42 @@ impl TradingApp {
     }
 
     fn with_tree(tree: Tree<Pane>, host: String, data_paths: DataPaths) -> Self {
-        info!("🔧 with_tree() called, loading datasets...");
+        info!("🔧 with_tree() called");
 
-        // Load datasets on startup
-        let available_datasets = Self::load_available_datasets(&data_paths);
-        info!("📊 Loaded {} datasets", available_datasets.len());
+        // Show the last scan's datasets right away; the rescan runs in the background
+        let available_datasets = Self::load_cached_datasets(&data_paths);
+        info!("📊 Loaded {} cached datasets", available_datasets.len());
 
         info!("💼 Creating portfolio service...");
         // Create portfolio service
         let portfolio_service = PortfolioService::new(host.clone(), data_paths.clone());
         info!("✅ Portfolio service created");
 
-        let app = Self {
+        // Labels and ENS name for the user shown in the orders and portfolio panes
+        let display_paths = data_paths.clone();
+        tokio::spawn(async move {
+            let display = crate::address_book::address_display(&display_paths).await;
+            if let Ok(address) = crate::auth::account_address(&display_paths).await {
+                display.resolve([address.as_str()]).await;
+            }
+        });
+
+        let risk_config = RiskConfig::load(&data_paths).unwrap_or_else(|e| {
+            warn!("Using default risk config: {}", e);
+            RiskConfig::default()
+        });
+        let order_manager =
+            OrderManager::with_risk_config(OrderConfig::default(), risk_config.clone());
+        let session_recorder = SessionRecorder::new(&data_paths);
+        let token_sidebar = TokenSidebar::new(freshness::default_db_path(&data_paths));
+        let token_notes = TokenNotes::new(&data_paths);
+        let keymap = keybindings::load_keymap(&data_paths, Surface::Gui);
+        let onboarding = onboarding::is_needed(&data_paths)
+            .then(|| OnboardingWizard::new(&host, &data_paths));
+        let strategy_panel = StrategyPanel::new(DEFAULT_CONTROL_PORT, &data_paths);
+        let notifier = Notifier::new(&data_paths);
+
+        let mut app = Self {
             tree,
             focused_tile_id: None,
             tiles_to_close: Vec::new(),
@@ -308,36 +503,67 @@ impl TradingApp {
             data_paths,
             portfolio_manager: Arc::new(PortfolioManager::new()),
             portfolio_service,
-            _order_manager: OrderManager::new(),
+            order_manager,
+            risk_config,
             orders_cache: Arc::new(RwLock::new(Vec::new())),
-            show_new_order_dialog: false,
+            onboarding,
+            order_ticket: OrderTicket::default(),
+            cancel_all: CancelAllDialog::default(),
+            search_palette: SearchPalette::default(),
+            strategy_panel,
+            pnl_chart: PnlChart::default(),
+            strategy_panel_detached: false,
+            detach_strategy_panel: false,
             show_dataset_selector: false,
             show_streams_overview: false,
             sidebar_width: 180.0,
             auto_arrange_on_add: false,
             show_about: false,
             show_settings: false,
+            keymap,
+            shortcut_editor: ShortcutEditor::default(),
+            notifier,
+            show_notifications: false,
+            market_history: SnapshotHistory::default(),
+            fill_watcher: None,
+            alert_watcher: None,
+            alert_watcher_failed: false,
+            alert_toast: AlertToast::default(),
             streaming_service: None,
             streaming_assets: Vec::new(),
+            token_budget: None,
+            orders_raw_ids: false,
             streaming_state: StreamingState::Disconnected,
             streaming_task: None,
             streaming_progress_rx: None,
             streaming_result_rx: None,
+            replay: None,
+            replay_feed: None,
+            replay_handle: None,
             _last_position_fetch: None,
             _is_fetching_positions: false,
             current_token_id: None,
-            current_bids: Vec::new(),
-            current_asks: Vec::new(),
             orderbook_changes: Vec::new(),
             available_datasets,
             selected_datasets: std::collections::HashSet::new(),
+            dataset_scan_rx: None,
+            dataset_rescan_pending: false,
+            quick_start_pending: false,
+            dataset_previews: DatasetPreviews::default(),
+            search_dataset: SearchDatasetForm::default(),
+            enrichment_freshness: HashMap::new(),
+            freshness_rx: None,
+            freshness_loaded_at: None,
+            token_sidebar,
+            token_notes,
+            portfolio_grouping: None,
             token_activities: Arc::new(RwLock::new(HashMap::new())),
             event_receiver: None,
             pending_new_orderbook: None,
             pending_new_worker_details: None,
             cached_streaming_tokens: Vec::new(),
-            cached_orderbook: None,
-            cached_last_trade_price: None,
+            cached_cross_rates: Vec::new(),
+            token_data: HashMap::new(),
             cached_streaming_stats: None,
             cached_worker_statuses: Vec::new(),
             selected_worker_id: None,
@@ -346,21 +572,70 @@ impl TradingApp {
             pending_screenshot: None,
             pending_tile_screenshot: None,
             screenshot_message: None,
+            session_recorder,
+            issue_report_rx: None,
+            issue_report_message: None,
             pinned_tiles: HashSet::new(),
             has_unsaved_layout_changes: false,
             previous_tree_hash: None,
             tile_bounds: std::collections::HashMap::new(),
             _data_update_task: None,
-            cached_data_receivers: None,
-            current_token_sender: None,
+            subscriptions: SubscriptionRegistry::default(),
+            subscription_tx: None,
+            pane_data_rx: None,
+            depth_aggregation: PriceAggregation::default(),
             first_update: true,
             is_fullscreen: false,
+            repaint: RepaintScheduler::new(DEFAULT_MAX_FPS),
+            frame_stats: FrameStats::default(),
+            show_perf_overlay: false,
         };
+        app.start_dataset_scan();
 
         info!("🎉 TradingApp fully constructed successfully");
         app
     }
 
+    /// Cap streaming-driven repaints at `max_fps` per pane
+    pub fn with_max_fps(mut self, max_fps: u32) -> Self {
+        self.repaint = RepaintScheduler::new(max_fps);
+        self
+    }
+
+    /// Manage the strategies of the daemon whose control API is on `port`
+    pub fn with_control_port(mut self, port: u16) -> Self {
+        self.strategy_panel = StrategyPanel::new(port, &self.data_paths);
+        self
+    }
+
+    /// Play a capture window `speed` times faster than recorded instead of
+    /// streaming live, starting right away
+    pub fn with_replay(mut self, replay: Arc<CaptureReplay>, speed: f64) -> Self {
+        self.replay = Some((replay, speed));
+        self.start_replay_streaming();
+        self
+    }
+
+    /// Leave the onboarding wizard, reopening on the data directory it picked
+    fn finish_onboarding(&mut self, data_paths: DataPaths, starter_dataset: bool) {
+        self.onboarding = None;
+        if data_paths.root() != self.data_paths.root() {
+            info!("📁 Switching to data directory {}", data_paths.root().display());
+            let mut app =
+                Self::with_tree(Self::create_default_layout(), self.host.clone(), data_paths);
+            std::mem::swap(&mut app.repaint, &mut self.repaint);
+            // Same daemon port, authenticated with the new profile's token
+            app.strategy_panel =
+                StrategyPanel::new(self.strategy_panel.control_port(), &app.data_paths);
+            *self = app;
+        }
+        if starter_dataset {
+            self.selected_datasets
+                .insert(onboarding::STARTER_DATASET.to_string());
+            self.start_dataset_scan();
+        }
+    }
+
     fn create_default_layout() -> Tree<Pane> {
         let mut tiles = Tiles::default();
 
@@ -424,6 +699,11 @@ impl TradingApp {
                         ui.close_menu();
                     }
 
+                    if ui.button("💹 Add Portfolio Value Pane").clicked() {
+                        self.add_pane(Pane::PortfolioValue);
+                        ui.close_menu();
+                    }
+
                     if ui.button("📡 Add Streams Pane").clicked() {
                         self.add_pane(Pane::Streams);
                         ui.close_menu();
@@ -434,23 +714,81 @@ impl TradingApp {
                         ui.close_menu();
                     }
 
+                    if ui.button("⚖ Add Cross Rates Pane").clicked() {
+                        self.add_pane(Pane::CrossRates);
+                        ui.close_menu();
+                    }
+
                     if ui.button("📈 Add Charts Pane").clicked() {
                         self.add_pane(Pane::Charts);
                         ui.close_menu();
                     }
 
+                    if ui.button("🎛 Add Strategies Pane").clicked() {
+                        if self.strategy_panel_detached {
+                            ctx.send_viewport_cmd_to(
+                                strategy_viewport_id(),
+                                egui::ViewportCommand::Focus,
+                            );
+                        } else {
+                            self.add_pane(Pane::Strategies);
+                        }
+                        ui.close_menu();
+                    }
+
                     ui.separator();
 
                     if ui.button("🔍 Streams Overview").clicked() {
                         self.show_streams_overview = true;
                         ui.close_menu();
                     }
+
+                    let unread = self.notifier.unread();
+                    let notifications = if unread > 0 {
+                        format!("🔔 Notifications ({})", unread)
+                    } else {
+                        "🔔 Notifications".to_string()
+                    };
+                    if ui.button(notifications).clicked() {
+                        self.show_notifications = true;
+                        ui.close_menu();
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("🔎 Search...")
+                                .shortcut_text(self.shortcut_text(Action::SearchPalette)),
+                        )
+                        .clicked()
+                    {
+                        self.search_palette.open();
+                        ui.close_menu();
+                    }
+
+                    ui.checkbox(&mut self.show_perf_overlay, "⏱ FPS / CPU Overlay");
                 });
 
                 // Trading menu
                 ui.menu_button("Trading", |ui| {
-                    if ui.button("🛒 New Order").clicked() {
-                        self.show_new_order_dialog = true;
+                    if ui
+                        .add(
+                            egui::Button::new("🛒 New Order")
+                                .shortcut_text(self.shortcut_text(Action::OpenOrderTicket)),
+                        )
+                        .clicked()
+                    {
+                        self.order_ticket.open_for(self.current_token_id.as_deref());
+                        ui.close_menu();
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("🚫 Cancel All Orders")
+                                .shortcut_text(self.shortcut_text(Action::CancelAll)),
+                        )
+                        .clicked()
+                    {
+                        self.cancel_all.open(self.portfolio_service.order_count_sync());
                         ui.close_menu();
                     }
 
@@ -481,7 +819,7 @@ impl TradingApp {
                         }
 
                         if ui.button("⌨️ Keyboard Shortcuts").clicked() {
-                            self.show_about = true; // Reuse about dialog to show shortcuts
+                            self.show_settings = true; // The shortcut editor is in Settings
                             ui.close_menu();
                         }
 
@@ -491,6 +829,11 @@ impl TradingApp {
                             // Open documentation URL
                             ui.close_menu();
                         }
+
+                        if ui.button("🐞 Report Issue").clicked() {
+                            self.report_issue();
+                            ui.close_menu();
+                        }
                     });
                 });
             });
@@ -554,7 +897,7 @@ impl TradingApp {
                             )
                             .clicked()
                         {
-                            self.show_new_order_dialog = true;
+                            self.order_ticket.open_for(self.current_token_id.as_deref());
                         }
 
                         if ui
@@ -772,6 +1115,24 @@ impl TradingApp {
                         match &self.streaming_state {
                             StreamingState::Connected => {
                                 ui.label(format!("Assets: {}", self.streaming_assets.len()));
+                                if let (Some((replay, speed)), Some(handle)) =
+                                    (&self.replay, &self.replay_handle)
+                                {
+                                    if handle.is_finished() {
+                                        ui.label(format!(
+                                            "⏪ Replay of {} finished",
+                                            replay.name()
+                                        ));
+                                    } else {
+                                        let position = handle.position().with_timezone(&Local);
+                                        ui.label(format!(
+                                            "⏪ Replay {} at {} ({}×)",
+                                            replay.name(),
+                                            position.format("%H:%M:%S"),
+                                            speed
+                                        ));
+                                    }
+                                }
 
                                 let button_height = 26.0;
                                 if ui
@@ -809,6 +1170,17 @@ impl TradingApp {
                                 {
                                     self.quick_start_streaming();
                                 }
+
+                                if self.replay.is_some()
+                                    && ui
+                                        .add_sized(
+                                            [ui.available_width(), button_height],
+                                            egui::Button::new("⏪ Replay Again"),
+                                        )
+                                        .clicked()
+                                {
+                                    self.start_replay_streaming();
+                                }
                             }
                             StreamingState::Error(error) => {
                                 ui.label(format!("Error: {}", error));
@@ -1215,11 +1587,14 @@ impl TradingApp {
             (Pane::Orders, Pane::Orders) => true,
             (Pane::Streams, Pane::Streams) => true,
             (Pane::Portfolio, Pane::Portfolio) => true,
+            (Pane::PortfolioValue, Pane::PortfolioValue) => true,
             (Pane::Tokens, Pane::Tokens) => true,
+            (Pane::CrossRates, Pane::CrossRates) => true,
             (Pane::Charts, Pane::Charts) => true,
             (Pane::TradeHistory, Pane::TradeHistory) => true,
             (Pane::Balances, Pane::Balances) => true,
             (Pane::WebSocketManager, Pane::WebSocketManager) => true,
+            (Pane::Strategies, Pane::Strategies) => true,
             // MarketDepth and WorkerDetails can have multiple instances with different parameters
             (Pane::MarketDepth(_), Pane::MarketDepth(_)) => false,
             (Pane::WorkerDetails(_), Pane::WorkerDetails(_)) => false,
@@ -1378,9 +1753,17 @@ impl TradingApp {
     fn get_pane_category(&self, pane: &Pane) -> u8 {
         match pane {
             // Trading group - highest priority
-            Pane::Orders | Pane::Portfolio | Pane::Balances => 1,
+            Pane::Orders
+            | Pane::Portfolio
+            | Pane::PortfolioValue
+            | Pane::Balances
+            | Pane::Strategies => 1,
             // Market data group - medium priority  
-            Pane::Streams | Pane::MarketDepth(_) | Pane::Charts | Pane::TradeHistory => 2,
+            Pane::Streams
+            | Pane::MarketDepth(_)
+            | Pane::CrossRates
+            | Pane::Charts
+            | Pane::TradeHistory => 2,
             // Token and utility group
             Pane::Tokens => 3,
             // Management group - lowest priority
@@ -1395,12 +1778,15 @@ impl TradingApp {
             Pane::Orders => 1,
             Pane::Portfolio => 2,
             Pane::Balances => 3,
+            Pane::Strategies => 4,
+            Pane::PortfolioValue => 5,
             
             // Market data group priorities
             Pane::Streams => 1,
             Pane::MarketDepth(_) => 2,
             Pane::Charts => 3,
             Pane::TradeHistory => 4,
+            Pane::CrossRates => 5,
             
             // Token group
             Pane::Tokens => 1,
@@ -1595,6 +1981,18 @@ impl TradingApp {
             task.abort();
         }
 
+        if let Some(task) = self._data_update_task.take() {
+            task.abort();
+        }
+        self.token_budget = None;
+        if let Some(replay) = self.replay_handle.take() {
+            replay.stop();
+        }
+        self.replay_feed = None;
+        self.subscription_tx = None;
+        self.pane_data_rx = None;
+        self.token_data.clear();
+
         // Reset state
         self.streaming_state = StreamingState::Disconnected;
         self.streaming_assets.clear();
@@ -1602,6 +2000,121 @@ impl TradingApp {
     }
 
 
+    fn dataset_cache_path(data_paths: &DataPaths) -> PathBuf {
+        data_paths.data().join("config").join(DATASET_CACHE_FILE)
+    }
+
+    /// Datasets found by the previous scan, empty if there is no usable cache
+    fn load_cached_datasets(data_paths: &DataPaths) -> Vec<DatasetInfo> {
+        let path = Self::dataset_cache_path(data_paths);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable dataset cache {}: {}", path.display(), e);
+            Vec::new()
+        })
+    }
+
+    fn save_dataset_cache(data_paths: &DataPaths, datasets: &[DatasetInfo]) -> anyhow::Result<()> {
+        let path = Self::dataset_cache_path(data_paths);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(datasets)?)?;
+        Ok(())
+    }
+
+    /// Rescan the datasets directory off the UI thread
+    ///
+    /// Results arrive through [`Self::poll_dataset_scan`] and are written to the
+    /// cache so the next launch can list datasets before its own scan finishes.
+    /// A call while a scan runs queues another, which may see newer datasets.
+    fn start_dataset_scan(&mut self) {
+        if self.dataset_scan_rx.is_some() {
+            self.dataset_rescan_pending = true;
+            return;
+        }
+        let data_paths = self.data_paths.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.dataset_scan_rx = Some(rx);
+
+        tokio::task::spawn_blocking(move || {
+            let datasets = Self::load_available_datasets(&data_paths);
+            if let Err(e) = Self::save_dataset_cache(&data_paths, &datasets) {
+                warn!("Failed to write dataset cache: {}", e);
+            }
+            let _ = tx.send(datasets);
+        });
+    }
+
+    fn poll_dataset_scan(&mut self) {
+        let Some(rx) = &self.dataset_scan_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(datasets) => {
+                info!("📊 Dataset scan found {} datasets", datasets.len());
+                // Selections of datasets that no longer exist would stream nothing,
+                // unless they were made after this scan started
+                if !self.dataset_rescan_pending {
+                    self.selected_datasets
+                        .retain(|name| datasets.iter().any(|d| &d.name == name));
+                }
+                self.available_datasets = datasets;
+                self.dataset_previews.invalidate();
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                warn!("Dataset scan task stopped without a result");
+            }
+        }
+        self.dataset_scan_rx = None;
+
+        if self.dataset_rescan_pending {
+            self.dataset_rescan_pending = false;
+            self.start_dataset_scan();
+            return;
+        }
+        if self.quick_start_pending {
+            self.quick_start_pending = false;
+            self.quick_start_streaming();
+        }
+    }
+
+    /// Re-read enrichment freshness off the UI thread once the last read is old enough
+    fn refresh_freshness_if_due(&mut self) {
+        let due = self
+            .freshness_loaded_at
+            .is_none_or(|at| at.elapsed() >= FRESHNESS_REFRESH_INTERVAL);
+        if !due || self.freshness_rx.is_some() {
+            return;
+        }
+        self.freshness_loaded_at = Some(Instant::now());
+
+        let db_path = freshness::default_db_path(&self.data_paths);
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.freshness_rx = Some(rx);
+        tokio::task::spawn_blocking(move || match freshness::load(&db_path) {
+            Ok(tracked) => {
+                let _ = tx.send(tracked);
+            }
+            Err(e) => warn!("Failed to read enrichment freshness: {}", e),
+        });
+    }
+
+    fn poll_freshness(&mut self) {
+        let Some(rx) = &self.freshness_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(tracked) => self.enrichment_freshness = tracked,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {}
+        }
+        self.freshness_rx = None;
+    }
+
     fn load_available_datasets(data_paths: &DataPaths) -> Vec<DatasetInfo> {
         let mut datasets = Vec::new();
 
@@ -1656,6 +2169,11 @@ impl TradingApp {
 
         // Clone necessary data for the async task
         let selected_datasets = self.selected_datasets.clone();
+        // Datasets narrowed down in the preview stream only their checked markets
+        let token_subsets: HashMap<String, Vec<String>> = selected_datasets
+            .iter()
+            .filter_map(|name| Some((name.clone(), self.dataset_previews.token_subset(name)?)))
+            .collect();
         let available_datasets = self.available_datasets.clone();
         let host = self.host.clone();
         let data_paths = self.data_paths.clone();
@@ -1669,6 +2187,7 @@ impl TradingApp {
         let task = tokio::spawn(async move {
             match Self::initialize_streaming_async(
                 selected_datasets,
+                token_subsets,
                 available_datasets,
                 host,
                 data_paths,
@@ -1692,6 +2211,38 @@ impl TradingApp {
         info!("Started streaming initialization task");
     }
 
+    /// Start a streaming service playing the replay instead of connecting WebSockets
+    fn start_replay_streaming(&mut self) {
+        let Some((replay, _)) = &self.replay else {
+            return;
+        };
+
+        self.streaming_state = StreamingState::Initializing {
+            progress: 0.0,
+            message: format!("Preparing replay of {}...", replay.name()),
+        };
+
+        let tokens = replay.tokens().to_vec();
+        let feed = ReplayFeed::default();
+        self.replay_feed = Some(feed.clone());
+        let host = self.host.clone();
+        let data_paths = self.data_paths.clone();
+
+        let (progress_tx, progress_rx) = tokio::sync::mpsc::channel(10);
+        let (streamer_tx, streamer_rx) = tokio::sync::oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            let result =
+                Self::start_streaming_service(tokens, host, data_paths, Some(feed), progress_tx)
+                    .await;
+            let _ = streamer_tx.send(result);
+        });
+
+        self.streaming_task = Some(task);
+        self.streaming_progress_rx = Some(progress_rx);
+        self.streaming_result_rx = Some(streamer_rx);
+    }
+
     fn quick_start_streaming(&mut self) {
         info!("🚀 Quick Start streaming initiated");
 
@@ -1699,12 +2250,20 @@ impl TradingApp {
         if let Some(dataset) = self.available_datasets.first() {
             info!("Auto-selecting dataset: {}", dataset.name);
 
-            // Clear any existing selections and select this dataset
+            // Clear any existing selections and select all of this dataset
             self.selected_datasets.clear();
             self.selected_datasets.insert(dataset.name.clone());
+            self.dataset_previews.clear_selection();
 
             // Start streaming with this dataset
             self.start_streaming_with_datasets();
+        } else if self.dataset_scan_rx.is_some() {
+            info!("Datasets are still being scanned, quick start will follow the scan");
+            self.quick_start_pending = true;
+            self.streaming_state = StreamingState::Initializing {
+                progress: 0.0,
+                message: "Scanning datasets...".to_string(),
+            };
         } else {
             warn!("No datasets available for quick start");
             self.streaming_state = StreamingState::Error(
@@ -1715,6 +2274,7 @@ impl TradingApp {
 
     async fn initialize_streaming_async(
         selected_datasets: std::collections::HashSet<String>,
+        token_subsets: HashMap<String, Vec<String>>,
         available_datasets: Vec<DatasetInfo>,
         host: String,
         data_paths: DataPaths,
@@ -1730,7 +2290,15 @@ impl TradingApp {
         let mut processed = 0;
 
         for dataset_name in &selected_datasets {
-            if let Some(dataset) = available_datasets.iter().find(|d| d.name == *dataset_name) {
+            if let Some(tokens) = token_subsets.get(dataset_name) {
+                info!(
+                    "✅ Using {} tokens of the markets picked from dataset: {}",
+                    tokens.len(),
+                    dataset_name
+                );
+                all_tokens.extend(tokens.iter().cloned());
+                processed += 1;
+            } else if let Some(dataset) = available_datasets.iter().find(|d| d.name == *dataset_name) {
                 info!("Loading tokens from dataset: {}", dataset.name);
 
                 // Load actual token IDs from the dataset files
@@ -1768,6 +2336,18 @@ impl TradingApp {
         all_tokens.sort();
         all_tokens.dedup();
 
+        Self::start_streaming_service(all_tokens, host, data_paths, None, progress_tx).await
+    }
+
+    /// Start a streaming service over `all_tokens`, fed by `replay_feed` when replaying
+    async fn start_streaming_service(
+        all_tokens: Vec<String>,
+        host: String,
+        data_paths: DataPaths,
+        replay_feed: Option<ReplayFeed>,
+        progress_tx: tokio::sync::mpsc::Sender<(f32, String)>,
+    ) -> Result<Arc<StreamingService>, anyhow::Error> {
+        let replaying = replay_feed.is_some();
         let _ = progress_tx
             .send((
                 0.5,
@@ -1783,9 +2363,9 @@ impl TradingApp {
 
         let config = StreamingServiceConfig {
             ws_config,
-            _host: host,
+            host,
             _data_paths: data_paths,
-            tokens_per_worker: 25, // Increased to reduce worker count
+            tokens_per_worker: TOKENS_PER_CONNECTION,
             event_buffer_size: 1000,
             worker_event_buffer_size: 500,
             auto_reconnect: true,
@@ -1796,6 +2376,16 @@ impl TradingApp {
             stats_interval_secs: 5,
             worker_connection_delay_ms: 500, // 500ms delay between connections
             max_concurrent_connections: 2,   // Only 2 concurrent connections
+            rest_fallback_enabled: true,
+            rest_fallback_stale_secs: 60,
+            rest_fallback_poll_interval_secs: 15,
+            rest_fallback_max_tokens_per_cycle: 20,
+            consistency_check_enabled: true,
+            consistency_check_interval_secs: 30,
+            consistency_sample_size: 5,
+            consistency_price_tolerance: 0.01,
+            consistency_size_tolerance: 0.5,
+            replay_feed,
         };
 
         let _ = progress_tx
@@ -1836,18 +2426,165 @@ impl TradingApp {
             }
         }
 
-        let _ = progress_tx
-            .send((0.9, "Waiting for initial data...".to_string()))
-            .await;
+        // A replay only starts playing once the service is handed over
+        if !replaying {
+            let _ = progress_tx
+                .send((0.9, "Waiting for initial data...".to_string()))
+                .await;
 
-        // Wait a bit for initial data
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            // Wait a bit for initial data
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
 
         let _ = progress_tx.send((1.0, "Connected!".to_string())).await;
 
         Ok(streaming_service)
     }
 
+    /// Fire token alerts crossed by the streamed prices, raise them with their
+    /// severity and toast those routed to a toast
+    fn check_token_alerts(&mut self) {
+        let Ok(activities) = self.token_activities.try_read() else {
+            return;
+        };
+        let fired = self.token_notes.check_alerts(|token_id| {
+            let activity = activities.get(token_id)?;
+            match (activity.last_bid, activity.last_ask) {
+                (Some(bid), Some(ask)) => Some((bid + ask) / Decimal::TWO),
+                _ => activity.last_trade_price,
+            }
+        });
+        drop(activities);
+        let mut toasts = Vec::new();
+        for alert in fired {
+            let message = alert.message();
+            info!("Token alert ({}): {}", alert.severity, message);
+            let diff = self.recent_market_change(&alert.token_id);
+            self.notifier
+                .record(NotificationKind::Alert, Some(&alert.token_id), message.clone(), diff);
+            match &mut self.alert_watcher {
+                Some(watcher) => {
+                    if watcher.toasts(alert.severity) {
+                        toasts.push(message.clone());
+                    }
+                    watcher.raise(alert.severity, message, Some(alert.token_id));
+                }
+                None => toasts.push(message),
+            }
+        }
+        self.alert_toast.push(toasts);
+    }
+
+    /// Start alert routing, then cue and toast the repeats and escalations it sent
+    fn check_alert_follow_ups(&mut self, ctx: &egui::Context) {
+        if self.alert_watcher.is_none() && !self.alert_watcher_failed {
+            match AlertWatcher::start(&self.data_paths, ctx.clone()) {
+                Ok(watcher) => self.alert_watcher = Some(watcher),
+                Err(e) => {
+                    warn!("Alerts are only shown in the canvas: {:#}", e);
+                    self.alert_watcher_failed = true;
+                }
+            }
+        }
+        let Some(watcher) = &mut self.alert_watcher else {
+            return;
+        };
+        let sent = watcher.poll();
+        let toasts: Vec<String> = sent
+            .iter()
+            .filter(|alert| watcher.toasts(alert.severity))
+            .map(|alert| alert.text())
+            .collect();
+        for alert in sent {
+            info!("Alert follow-up: {}", alert.text());
+            let diff = alert
+                .token_id
+                .as_deref()
+                .and_then(|token_id| self.recent_market_change(token_id));
+            self.notifier.record(
+                NotificationKind::Alert,
+                alert.token_id.as_deref(),
+                alert.text(),
+                diff,
+            );
+        }
+        self.alert_toast.push(toasts);
+    }
+
+    /// Cue and toast the account's new fills
+    fn check_fills(&mut self, ctx: &egui::Context) {
+        if self.fill_watcher.is_none() && self.notifier.settings().fill.is_enabled() {
+            if let Some(address) = self.portfolio_service.get_user_address_sync() {
+                info!("Watching {} for fills", address);
+                self.fill_watcher = Some(FillWatcher::start(address, ctx.clone()));
+            }
+        }
+        let Some(watcher) = &self.fill_watcher else {
+            return;
+        };
+        let fills = watcher.poll();
+        for fill in &fills {
+            info!("Order fill: {}", fill.summary());
+            let diff = fill
+                .asset
+                .as_deref()
+                .and_then(|token_id| self.recent_market_change(token_id));
+            self.notifier
+                .record(NotificationKind::Fill, fill.asset.as_deref(), fill.summary(), diff);
+        }
+        if !fills.is_empty() {
+            let messages: Vec<String> = fills.iter().map(|fill| format!("💰 {}", fill.summary())).collect();
+            self.screenshot_message = Some((messages.join("\n"), std::time::Instant::now()));
+        }
+    }
+
+    /// Current book of `token_id` with its streamed volume
+    fn market_snapshot(&self, token_id: &str, book: &OrderBook) -> MarketSnapshot {
+        let volume = self
+            .token_activities
+            .try_read()
+            .ok()
+            .and_then(|activities| activities.get(token_id).map(|a| a.total_volume));
+        MarketSnapshot::from_order_book(book, volume, chrono::Utc::now())
+    }
+
+    /// How the market of `token_id` moved over the last 15 minutes of sampled books
+    fn recent_market_change(&self, token_id: &str) -> Option<SnapshotDiff> {
+        let book = self.token_data.get(token_id)?.book.as_ref()?;
+        let current = self.market_snapshot(token_id, book);
+        self.market_history
+            .diff(token_id, "Last 15m", &current, chrono::Duration::minutes(15))
+    }
+
+    /// Outline the panes of recent fills and alerts, fading out
+    fn paint_flashes(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        if !self.notifier.update_flashes(now) {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("pane_flashes"),
+        ));
+        for tile_id in self.visible_panes() {
+            let (Some(egui_tiles::Tile::Pane(pane)), Some(rect)) =
+                (self.tree.tiles.get(tile_id), self.tile_bounds.get(&tile_id))
+            else {
+                continue;
+            };
+            if let Some(color) = self.notifier.flash_color(pane, now) {
+                painter.rect_filled(*rect, egui::CornerRadius::same(4), color.gamma_multiply(0.12));
+                painter.rect_stroke(
+                    *rect,
+                    egui::CornerRadius::same(4),
+                    egui::Stroke::new(3.0, color),
+                    egui::StrokeKind::Inside,
+                );
+            }
+        }
+        ctx.request_repaint();
+    }
+
     fn poll_streaming_events(&mut self, ctx: &egui::Context) {
         // Only poll if we're connected and have a receiver
         if !matches!(self.streaming_state, StreamingState::Connected) {
@@ -1859,7 +2596,8 @@ impl TradingApp {
         let mut should_close_receiver = false;
 
         if let Some(receiver) = &mut self.event_receiver {
-            let max_events_per_frame = 100;
+            // Frames are rate limited, so each one drains a larger batch
+            let max_events_per_frame = 2000;
 
             for _ in 0..max_events_per_frame {
                 match receiver.try_recv() {
@@ -1882,98 +2620,33 @@ impl TradingApp {
             }
         }
 
-        // Process collected events
+        // Process collected events; the panes showing them repaint on the scheduler's clock
         let events_count = events_to_process.len();
         for event in events_to_process {
             self.handle_streaming_event(event);
         }
+        if events_count > 0 {
+            // Depth panes read their subscribed books, which arrive through poll_pane_data
+            self.mark_panes_dirty(|pane| match pane {
+                Pane::Streams | Pane::Tokens | Pane::Charts | Pane::WebSocketManager | Pane::WorkerDetails(_) => true,
+                _ => false,
+            });
+        }
 
         // Close receiver if needed
         if should_close_receiver {
             self.event_receiver = None;
         }
 
-        // Request repaint if we processed events
-        if events_count > 0 {
-            ctx.request_repaint();
-        }
-
-        // Update orderbook for selected token using cached data
-        if let (Some(token_id), Some(_streaming_service)) =
-            (&self.current_token_id, &self.streaming_service)
-        {
-            if let Some(order_book) = &self.cached_orderbook {
-                let new_bids = order_book.get_bids().to_vec();
-                let new_asks = order_book.get_asks().to_vec();
-
-                // Track changes in bids
-                for new_bid in &new_bids {
-                    let changed = if let Some(old_bid) =
-                        self.current_bids.iter().find(|b| b.price == new_bid.price)
-                    {
-                        old_bid.size != new_bid.size
-                    } else {
-                        true // New price level
-                    };
-
-                    if changed {
-                        self.orderbook_changes.push(OrderBookChange {
-                            token_id: token_id.clone(),
-                            price: new_bid.price,
-                            size: new_bid.size,
-                            changed_at: Instant::now(),
-                            is_bid: true,
-                        });
-                    }
-                }
-
-                // Track changes in asks
-                for new_ask in &new_asks {
-                    let changed = if let Some(old_ask) =
-                        self.current_asks.iter().find(|a| a.price == new_ask.price)
-                    {
-                        old_ask.size != new_ask.size
-                    } else {
-                        true // New price level
-                    };
-
-                    if changed {
-                        self.orderbook_changes.push(OrderBookChange {
-                            token_id: token_id.clone(),
-                            price: new_ask.price,
-                            size: new_ask.size,
-                            changed_at: Instant::now(),
-                            is_bid: false,
-                        });
-                    }
-                }
-
-                // Update current state
-                self.current_bids = new_bids;
-                self.current_asks = new_asks;
-
-                // Clean up old changes (older than 2 seconds)
-                let now = Instant::now();
-                self.orderbook_changes
-                    .retain(|change| now.duration_since(change.changed_at).as_secs() < 2);
-            }
-        }
-
         // Handle pending new orderbook
         if let Some(token_id) = self.pending_new_orderbook.take() {
             // Create the new MarketDepth pane with the token ID
             let new_pane = Pane::MarketDepth(Some(token_id.clone()));
             self.add_pane(new_pane);
 
-            // Set this as the current token for orderbook data fetching
+            // The pane subscribes to the token itself; this only picks the order ticket's default
             self.current_token_id = Some(token_id.clone());
 
-            // Notify background task of current token change
-            if let Some(sender) = &self.current_token_sender {
-                let _ = sender.send(Some(token_id.clone()));
-                info!("Updated current token to: {}", token_id);
-            }
-
             info!("Created new orderbook pane for token: {}", token_id);
 
             // Request UI repaint to show the new pane immediately
@@ -1991,121 +2664,193 @@ impl TradingApp {
             // Request UI repaint to show the new pane immediately
             ctx.request_repaint();
         }
+    }
+
+    /// Mark every pane matching `affected` as having new data
+    fn mark_panes_dirty(&mut self, affected: impl Fn(&Pane) -> bool) {
+        let now = Instant::now();
+        for (tile_id, tile) in self.tree.tiles.iter() {
+            if let egui_tiles::Tile::Pane(pane) = tile {
+                if affected(pane) {
+                    self.repaint.mark_dirty(*tile_id, now);
+                }
+            }
+        }
+    }
+
+    /// Panes drawn this frame: the active tab of every visible container
+    fn visible_panes(&self) -> HashSet<TileId> {
+        self.tree
+            .active_tiles()
+            .into_iter()
+            .filter(|tile_id| matches!(self.tree.tiles.get(*tile_id), Some(egui_tiles::Tile::Pane(_))))
+            .collect()
+    }
 
-        // Request continuous updates while streaming
+    /// Schedule the next frame after one has been drawn
+    fn finish_frame(&mut self, ctx: &egui::Context, started: Instant) {
+        let now = Instant::now();
+        let visible = self.visible_panes();
+        self.repaint.frame_painted(&visible, started, now);
         if matches!(self.streaming_state, StreamingState::Connected) {
-            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            ctx.request_repaint_after(self.repaint.streaming_delay(&visible, now));
+        }
+        self.frame_stats.record(started, now.duration_since(started));
+    }
+
+    fn show_perf_overlay(&mut self, ctx: &egui::Context) {
+        if !self.show_perf_overlay {
+            return;
         }
+        egui::Window::new("⏱ Performance")
+            .anchor(egui::Align2::RIGHT_TOP, [-10.0, 40.0])
+            .resizable(false)
+            .collapsible(false)
+            .open(&mut self.show_perf_overlay)
+            .show(ctx, |ui| {
+                ui.label(format!("FPS: {}", self.frame_stats.fps()));
+                ui.label(format!(
+                    "Frame time: {:.1} ms",
+                    self.frame_stats.mean_frame_time().as_secs_f64() * 1000.0
+                ));
+                ui.label(format!("UI CPU: {:.0}%", self.frame_stats.ui_load() * 100.0));
+                ui.label(format!("Repaint cap: {} / pane / s", self.repaint.max_fps()));
+                ui.label(format!("Panes awaiting repaint: {}", self.repaint.dirty_count()));
+            });
     }
 
-    /// Start background task to update cached streaming data
+    /// Start the background task that fetches what the open panes subscribe to
     fn start_data_update_task(&mut self) {
         if let Some(streaming_service) = &self.streaming_service {
-            let streaming_service = Arc::clone(streaming_service);
-
-            // Create channels for sending cached data back to UI thread
-            let (tokens_tx, tokens_rx) = std::sync::mpsc::channel();
-            let (orderbook_tx, orderbook_rx) = std::sync::mpsc::channel();
-            let (trade_price_tx, trade_price_rx) = std::sync::mpsc::channel();
-            let (stats_tx, stats_rx) = std::sync::mpsc::channel();
-            let (workers_tx, workers_rx) = std::sync::mpsc::channel();
-
-            // Create a channel for sending current token updates to background task
-            let (current_token_tx, current_token_rx) = std::sync::mpsc::channel();
-
-            // Store receivers for polling in update()
-            self.cached_data_receivers = Some((
-                tokens_rx,
-                orderbook_rx,
-                trade_price_rx,
-                stats_rx,
-                workers_rx,
+            let (subscription_tx, subscription_rx) = std::sync::mpsc::channel();
+            let (update_tx, update_rx) = std::sync::mpsc::channel();
+            let _ = subscription_tx.send(self.subscriptions.active());
+
+            self._data_update_task = Some(spawn_data_task(
+                Arc::clone(streaming_service),
+                subscription_rx,
+                update_tx,
             ));
+            self.subscription_tx = Some(subscription_tx);
+            self.pane_data_rx = Some(update_rx);
+        }
+    }
 
-            // Store the current token sender for updating the background task
-            self.current_token_sender = Some(current_token_tx);
-
-            // Send initial current token if any
-            if let (Some(token_id), Some(sender)) =
-                (&self.current_token_id, &self.current_token_sender)
-            {
-                let _ = sender.send(Some(token_id.clone()));
+    /// Let the token budget pick the streamed tokens from here on, when one is
+    /// set; a replay streams what it recorded
+    fn start_token_budget(&mut self, ctx: &egui::Context) {
+        let Some(streaming_service) = &self.streaming_service else {
+            return;
+        };
+        if self.replay.is_some() {
+            return;
+        }
+        let config = match TokenBudgetConfig::load(&self.data_paths) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Token budget disabled: {:#}", e);
+                return;
             }
+        };
+        self.token_budget = TokenBudgetRunner::start(
+            config,
+            TOKENS_PER_CONNECTION,
+            self.data_paths.clone(),
+            Arc::clone(streaming_service),
+            self.portfolio_service.clone(),
+            ctx.clone(),
+        );
+    }
 
-            // Spawn background task
-            let task = tokio::task::spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
-                let mut current_token_id: Option<String> = None;
-
-                loop {
-                    interval.tick().await;
-
-                    // Check for current token updates
-                    while let Ok(new_token) = current_token_rx.try_recv() {
-                        current_token_id = new_token;
-                    }
+    /// Recount the subscriptions of the open panes and tell the data task if the set changed
+    fn sync_subscriptions(&mut self) {
+        let held: Vec<Subscription> = self
+            .tree
+            .tiles
+            .iter()
+            .filter_map(|(_, tile)| match tile {
+                egui_tiles::Tile::Pane(pane) => Some(pane.subscriptions()),
+                _ => None,
+            })
+            .flatten()
+            // The sidebar always shows connection statistics
+            .chain(std::iter::once(Subscription::StreamStats))
+            .collect();
+        if !self.subscriptions.update(held) {
+            return;
+        }
 
-                    // Update streaming tokens
-                    let tokens = streaming_service.get_streaming_tokens().await;
-                    let _ = tokens_tx.send(tokens.clone());
+        let subscriptions = &self.subscriptions;
+        self.token_data
+            .retain(|token_id, _| subscriptions.is_subscribed(&Subscription::Token(token_id.clone())));
+        if let Some(sender) = &self.subscription_tx {
+            let _ = sender.send(self.subscriptions.active());
+        }
+    }
 
-                    // Update orderbook for current token if available
-                    if let Some(token_id) = &current_token_id {
-                        if let Some(orderbook) = streaming_service.get_order_book(token_id).await {
-                            let _ = orderbook_tx.send(orderbook);
-                        }
+    /// Apply data refreshed by the background task
+    fn poll_pane_data(&mut self) {
+        let Some(rx) = &self.pane_data_rx else {
+            return;
+        };
+        let updates: Vec<PaneDataUpdate> = rx.try_iter().collect();
+        if updates.is_empty() {
+            return;
+        }
 
-                        // Update last trade price
-                        if let Some(trade_data) =
-                            streaming_service.get_last_trade_price(token_id).await
-                        {
-                            let _ = trade_price_tx.send(trade_data);
+        let mut updated_tokens = HashSet::new();
+        let mut cross_rates_updated = false;
+        for update in updates {
+            match update {
+                PaneDataUpdate::StreamingTokens(tokens) => {
+                    self.streaming_assets = tokens.clone();
+                    self.cached_streaming_tokens = tokens;
+                }
+                PaneDataUpdate::Token { token_id, data } => {
+                    // A late update for a pane closed since the request went out
+                    if !self
+                        .subscriptions
+                        .is_subscribed(&Subscription::Token(token_id.clone()))
+                    {
+                        continue;
+                    }
+                    if let Some(book) = &data.book {
+                        let previous = self.token_data.get(&token_id).and_then(|d| d.book.as_ref());
+                        self.orderbook_changes
+                            .extend(OrderBookChange::between(&token_id, previous, book));
+                        if self.market_history.is_due(&token_id, chrono::Utc::now()) {
+                            let snapshot = self.market_snapshot(&token_id, book);
+                            self.market_history.record(&token_id, snapshot);
                         }
                     }
-
-                    // Update streaming stats
-                    let stats = streaming_service.get_stats().await;
-                    let _ = stats_tx.send(stats);
-
-                    // Update worker statuses
-                    let workers = streaming_service.get_worker_statuses().await;
-                    let _ = workers_tx.send(workers);
+                    self.token_data.insert(token_id.clone(), data);
+                    updated_tokens.insert(token_id);
                 }
-            });
-
-            self._data_update_task = Some(task);
-        }
-    }
-
-    /// Poll for cached data updates from background task
-    fn poll_cached_data_updates(&mut self) {
-        if let Some((tokens_rx, orderbook_rx, trade_price_rx, stats_rx, workers_rx)) =
-            &self.cached_data_receivers
-        {
-            // Update cached streaming tokens
-            if let Ok(tokens) = tokens_rx.try_recv() {
-                self.cached_streaming_tokens = tokens;
-                self.streaming_assets = self.cached_streaming_tokens.clone();
-            }
-
-            // Update cached orderbook
-            if let Ok(orderbook) = orderbook_rx.try_recv() {
-                self.cached_orderbook = Some(orderbook);
+                PaneDataUpdate::CrossRates(rates) => {
+                    self.cached_cross_rates = rates;
+                    cross_rates_updated = true;
+                }
+                PaneDataUpdate::Stats(stats) => self.cached_streaming_stats = Some(stats),
+                PaneDataUpdate::Workers(workers) => self.cached_worker_statuses = workers,
             }
+        }
 
-            // Update cached last trade price
-            if let Ok(trade_price) = trade_price_rx.try_recv() {
-                self.cached_last_trade_price = Some(trade_price);
-            }
+        // Clean up old changes (older than 2 seconds)
+        let now = Instant::now();
+        self.orderbook_changes
+            .retain(|change| now.duration_since(change.changed_at).as_secs() < 2);
 
-            // Update cached streaming stats
-            if let Ok(stats) = stats_rx.try_recv() {
-                self.cached_streaming_stats = Some(stats);
-            }
+        self.mark_panes_dirty(|pane| match pane {
+            Pane::MarketDepth(Some(token_id)) => updated_tokens.contains(token_id),
+            Pane::CrossRates => cross_rates_updated,
+            _ => false,
+        });
 
-            // Update cached worker statuses
-            if let Ok(workers) = workers_rx.try_recv() {
-                self.cached_worker_statuses = workers;
+        // Record the session so `polybot debug dump` can describe it
+        if self.session_recorder.is_due() {
+            let state = self.session_state();
+            if let Err(e) = self.session_recorder.record(&state) {
+                warn!("Failed to record session state: {}", e);
             }
         }
     }
@@ -2254,65 +2999,10 @@ impl TradingApp {
     async fn load_tokens_from_dataset(
         dataset_path: &std::path::Path,
     ) -> Result<Vec<String>, anyhow::Error> {
-        let mut tokens = Vec::new();
-
-        // Look for markets.json files
-        let markets_file = dataset_path.join("markets.json");
-        if markets_file.exists() {
-            let contents = tokio::fs::read_to_string(&markets_file).await?;
-            let markets: Vec<serde_json::Value> = serde_json::from_str(&contents)?;
-
-            for market in markets {
-                if let Some(market_tokens) = market.get("tokens").and_then(|v| v.as_array()) {
-                    for token in market_tokens {
-                        if let Some(token_id) = token.get("token_id").and_then(|v| v.as_str()) {
-                            tokens.push(token_id.to_string());
-                        }
-                    }
-                }
-            }
-        }
-
-        // Also look for market chunk files
-        if let Ok(entries) = std::fs::read_dir(dataset_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file()
-                    && path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .map(|n| n.starts_with("markets_chunk_") && n.ends_with(".json"))
-                        .unwrap_or(false)
-                {
-                    match tokio::fs::read_to_string(&path).await {
-                        Ok(contents) => {
-                            if let Ok(markets) =
-                                serde_json::from_str::<Vec<serde_json::Value>>(&contents)
-                            {
-                                for market in markets {
-                                    if let Some(market_tokens) =
-                                        market.get("tokens").and_then(|v| v.as_array())
-                                    {
-                                        for token in market_tokens {
-                                            if let Some(token_id) =
-                                                token.get("token_id").and_then(|v| v.as_str())
-                                            {
-                                                tokens.push(token_id.to_string());
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to read chunk file {:?}: {}", path, e);
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(tokens)
+        let dataset_path = dataset_path.to_path_buf();
+        let markets =
+            tokio::task::spawn_blocking(move || read_dataset_markets(&dataset_path)).await??;
+        Ok(markets.iter().flat_map(market_token_ids).collect())
     }
 
     /// Initialize portfolio service if not already done
@@ -2332,27 +3022,65 @@ impl TradingApp {
         }
     }
 
-    fn show_dialogs(&mut self, ctx: &egui::Context) {
-        // New Order Dialog
-        if self.show_new_order_dialog {
-            egui::Window::new("🛒 New Order")
-                .default_width(400.0)
-                .show(ctx, |ui| {
-                    ui.label("Order placement functionality coming soon!");
+    /// Draw the strategy panel in its own window once detached from the tiles
+    ///
+    /// Closing that window docks the panel back as a pane.
+    fn show_detached_strategy_panel(&mut self, ctx: &egui::Context) {
+        if std::mem::take(&mut self.detach_strategy_panel) {
+            self.strategy_panel_detached = true;
+        }
+        if !self.strategy_panel_detached {
+            return;
+        }
 
-                    ui.separator();
+        let mut close_requested = false;
+        ctx.show_viewport_immediate(
+            strategy_viewport_id(),
+            egui::ViewportBuilder::default()
+                .with_title("🎛 Strategies")
+                .with_inner_size([520.0, 640.0]),
+            |ctx, class| {
+                if class == egui::ViewportClass::Embedded {
+                    // Backends without multiple native windows draw it inside the canvas
+                    let mut open = true;
+                    egui::Window::new("🎛 Strategies")
+                        .open(&mut open)
+                        .show(ctx, |ui| {
+                            self.strategy_panel.ui(ui, &self.streaming_assets, true);
+                        });
+                    close_requested = !open;
+                } else {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        self.strategy_panel.ui(ui, &self.streaming_assets, true);
+                    });
+                    close_requested = ctx.input(|i| i.viewport().close_requested());
+                }
+            },
+        );
 
-                    ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
-                            self.show_new_order_dialog = false;
-                        }
+        if close_requested {
+            self.strategy_panel_detached = false;
+            self.add_pane(Pane::Strategies);
+        }
+    }
 
-                        if ui.button("Place Order").clicked() {
-                            // TODO: Implement order placement
-                            self.show_new_order_dialog = false;
-                        }
-                    });
-                });
+    fn show_dialogs(&mut self, ctx: &egui::Context) {
+        // New Order Dialog (with large order confirmation modal)
+        self.order_ticket.show(
+            ctx,
+            &self.host,
+            &self.data_paths,
+            &self.risk_config,
+            &self.order_manager,
+        );
+        self.cancel_all.show(ctx, &self.host, &self.data_paths, &self.order_manager);
+
+        // Search palette
+        if self.search_palette.is_open() {
+            let items = self.palette_items();
+            if let Some(command) = self.search_palette.show(ctx, &items) {
+                self.run_palette_command(ctx, command);
+            }
         }
 
         // About Dialog
@@ -2367,8 +3095,12 @@ impl TradingApp {
 
                     ui.heading("⌨️ Keyboard Shortcuts");
                     ui.label("• ESC - Exit fullscreen mode");
-                    ui.label("• F11 - Toggle fullscreen mode");
-                    ui.label("• Cmd/Ctrl+Q - Quit application");
+                    for (action, chord) in self.keymap.iter() {
+                        if let Some(chord) = chord {
+                            ui.label(format!("• {} - {}", chord, action.label()));
+                        }
+                    }
+                    ui.label(egui::RichText::new("Change them in File → Settings").small().weak());
 
                     ui.separator();
 
@@ -2398,7 +3130,13 @@ impl TradingApp {
             egui::Window::new("⚙️ Settings")
                 .default_width(500.0)
                 .show(ctx, |ui| {
-                    ui.label("Settings panel coming soon!");
+                    ui.heading("⌨️ Keyboard Shortcuts");
+                    self.shortcut_editor
+                        .ui(ui, &mut self.keymap, &self.data_paths);
+
+                    ui.separator();
+                    ui.heading("🔔 Notifications");
+                    self.notifier.settings_ui(ui, &self.data_paths);
 
                     ui.separator();
 
@@ -2408,6 +3146,20 @@ impl TradingApp {
                 });
         }
 
+        // Notifications Center
+        if self.show_notifications {
+            egui::Window::new("🔔 Notifications")
+                .open(&mut self.show_notifications)
+                .default_width(420.0)
+                .default_height(500.0)
+                .show(ctx, |ui| {
+                    if let Some(watcher) = &mut self.alert_watcher {
+                        watcher.open_alerts_ui(ui);
+                    }
+                    self.notifier.center_ui(ui);
+                });
+        }
+
         // Streams Overview Dialog
         if self.show_streams_overview {
             egui::Window::new("📡 Market Streams Overview")
@@ -2465,7 +3217,7 @@ impl TradingApp {
                                     ui.group(|ui| {
                                         ui.vertical_centered(|ui| {
                                             ui.label("Total Volume");
-                                            ui.heading(format!("${:.2}", total_volume));
+                                            ui.heading(number_format::usd(total_volume));
                                         });
                                     });
                                 });
@@ -2516,21 +3268,20 @@ impl TradingApp {
                                                     ui.label(format!("{}", activity.trade_count));
 
                                                     // Volume
-                                                    ui.label(format!(
-                                                        "${:.2}",
-                                                        activity.total_volume
+                                                    ui.label(number_format::usd(
+                                                        activity.total_volume,
                                                     ));
 
                                                     // Last price (bid or ask)
                                                     if let Some(bid) = activity.last_bid {
                                                         ui.colored_label(
-                                                            egui::Color32::from_rgb(100, 200, 100),
-                                                            format!("${:.4}", bid),
+                                                            egui::Color32::from(crate::theme::palette().bid),
+                                                            format!("${}", number_format::price(bid)),
                                                         );
                                                     } else if let Some(ask) = activity.last_ask {
                                                         ui.colored_label(
-                                                            egui::Color32::from_rgb(200, 100, 100),
-                                                            format!("${:.4}", ask),
+                                                            egui::Color32::from(crate::theme::palette().ask),
+                                                            format!("${}", number_format::price(ask)),
                                                         );
                                                     } else {
                                                         ui.label("-");
@@ -2582,6 +3333,9 @@ impl TradingApp {
 
         // Dataset Selector Dialog
         if self.show_dataset_selector {
+            if self.dataset_previews.poll() {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
             egui::Window::new("📊 Select Dataset for Streaming")
                 .default_width(800.0)
                 .default_height(600.0)
@@ -2590,10 +3344,26 @@ impl TradingApp {
 
                     ui.separator();
 
-                    // Refresh datasets button
-                    if ui.button("🔄 Refresh Datasets").clicked() {
-                        self.available_datasets = Self::load_available_datasets(&self.data_paths);
-                        info!("Refreshed datasets, found: {}", self.available_datasets.len());
+                    // Refresh datasets button, or the scan already in progress
+                    ui.horizontal(|ui| {
+                        let scanning = self.dataset_scan_rx.is_some();
+                        if ui
+                            .add_enabled(!scanning, egui::Button::new("🔄 Refresh Datasets"))
+                            .clicked()
+                        {
+                            self.start_dataset_scan();
+                        }
+                        if scanning {
+                            ui.spinner();
+                            ui.label("Scanning datasets...");
+                        }
+                    });
+
+                    // A dataset written from a search is selected and picked up by a rescan
+                    if let Some(name) = self.search_dataset.ui(ui, &self.data_paths) {
+                        info!("📊 Created dataset {} from search", name);
+                        self.selected_datasets.insert(name);
+                        self.start_dataset_scan();
                     }
 
                     ui.separator();
@@ -2603,23 +3373,18 @@ impl TradingApp {
                         .id_salt("dataset_selector_scroll")
                         .max_height(400.0)
                         .show(ui, |ui| {
-                            if self.available_datasets.is_empty() {
+                            if self.available_datasets.is_empty() && self.dataset_scan_rx.is_some() {
+                                ui.label("Looking for datasets in data/datasets...");
+                            } else if self.available_datasets.is_empty() {
                                 ui.label("No datasets found in data/datasets directory");
                                 ui.label("Use the CLI to fetch market data first:");
                                 ui.code("polybot fetch-all-markets --dataset-name raw_markets/$(date +%Y-%m-%d)");
                             } else {
                                 for dataset in &self.available_datasets {
                                     ui.group(|ui| {
+                                        let mut is_selected = self.selected_datasets.contains(&dataset.name);
                                         ui.horizontal(|ui| {
-                                            let mut is_selected = self.selected_datasets.contains(&dataset.name);
-
-                                            if ui.checkbox(&mut is_selected, "").clicked() {
-                                                if is_selected {
-                                                    self.selected_datasets.insert(dataset.name.clone());
-                                                } else {
-                                                    self.selected_datasets.remove(&dataset.name);
-                                                }
-                                            }
+                                            ui.checkbox(&mut is_selected, "");
 
                                             ui.vertical(|ui| {
                                                 ui.heading(&dataset.name);
@@ -2628,6 +3393,14 @@ impl TradingApp {
                                                     dataset.asset_count, dataset.size_mb));
                                             });
                                         });
+                                        // Picking markets in the preview selects or deselects the dataset
+                                        self.dataset_previews.ui(ui, &dataset.name, &dataset.path, &mut is_selected);
+
+                                        if is_selected {
+                                            self.selected_datasets.insert(dataset.name.clone());
+                                        } else {
+                                            self.selected_datasets.remove(&dataset.name);
+                                        }
                                     });
                                 }
                             }
@@ -2635,8 +3408,16 @@ impl TradingApp {
 
                     ui.separator();
 
-                    // Show selected count
+                    // Show selected count, and which datasets stream only part of their markets
                     ui.label(format!("Selected {} dataset(s)", self.selected_datasets.len()));
+                    for name in &self.selected_datasets {
+                        if let Some(count) = self.dataset_previews.selected_count(name) {
+                            ui.label(
+                                egui::RichText::new(format!("{}: {} of {} markets", name, count.checked, count.total))
+                                    .weak(),
+                            );
+                        }
+                    }
 
                     ui.separator();
 
@@ -2644,6 +3425,7 @@ impl TradingApp {
                         if ui.button("Cancel").clicked() {
                             self.show_dataset_selector = false;
                             self.selected_datasets.clear();
+                            self.dataset_previews.clear_selection();
                         }
 
                         if ui.button("Start Streaming").clicked() && !self.selected_datasets.is_empty() {
@@ -2656,6 +3438,213 @@ impl TradingApp {
         }
     }
 
+    /// Current streaming, portfolio and layout state for diagnostics
+    fn session_state(&self) -> SessionState {
+        let portfolio = serde_json::json!({
+            "address": self.portfolio_service.get_user_address_sync(),
+            "balance": self.portfolio_service.get_balance_sync(),
+            "stats": self.portfolio_service.get_stats_sync(),
+            "positions": self.portfolio_service.get_positions_sync(),
+            "orders": self.portfolio_service.get_orders_sync(),
+        });
+        let layout = serde_json::to_value(SavedLayout {
+            tree: self.tree.clone(),
+            pinned_tiles: self.pinned_tiles.iter().cloned().collect(),
+        })
+        .ok();
+
+        SessionState::capture(
+            "gui",
+            self.cached_streaming_stats.clone(),
+            &self.cached_worker_statuses,
+            Some(portfolio),
+            layout,
+        )
+    }
+
+    /// Write a debug bundle in the background; the result shows as a toast
+    fn report_issue(&mut self) {
+        if self.issue_report_rx.is_some() {
+            return;
+        }
+        let state = self.session_state();
+        let data_paths = self.data_paths.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.issue_report_rx = Some(rx);
+        self.issue_report_message = Some(("🐞 Collecting diagnostics...".to_string(), Instant::now()));
+
+        tokio::spawn(async move {
+            let result = async {
+                let bundle = DebugBundle::collect(&data_paths, Some(state), true).await?;
+                let path = bundle.default_path(&data_paths);
+                bundle.write_zip(&path)?;
+                Ok::<_, anyhow::Error>(path)
+            }
+            .await;
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    fn poll_issue_report(&mut self) {
+        let Some(rx) = &self.issue_report_rx else {
+            return;
+        };
+        let message = match rx.try_recv() {
+            Ok(Ok(path)) => {
+                info!("Debug bundle written to {}", path.display());
+                format!("🐞 Debug bundle saved: {}\nAttach it to your bug report", path.display())
+            }
+            Ok(Err(e)) => {
+                error!("Failed to write debug bundle: {}", e);
+                format!("❌ Failed to write debug bundle: {}", e)
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => "❌ Debug bundle task stopped".to_string(),
+        };
+        self.issue_report_rx = None;
+        self.issue_report_message = Some((message, Instant::now()));
+    }
+
+    /// Actions whose shortcuts were pressed this frame
+    ///
+    /// Nothing fires while the shortcut editor waits for a key, and keys that
+    /// type text don't fire while a text field has focus.
+    fn pressed_actions(&self, ctx: &egui::Context) -> Vec<Action> {
+        if self.shortcut_editor.is_capturing() {
+            return Vec::new();
+        }
+        let typing = ctx.wants_keyboard_input();
+        ctx.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        repeat: false,
+                        modifiers,
+                        ..
+                    } => KeyChord::from_egui(*key, *modifiers),
+                    _ => None,
+                })
+                .filter(|chord| !(typing && chord.is_text_input()))
+                .filter_map(|chord| self.keymap.action_for(&chord))
+                .collect()
+        })
+    }
+
+    fn run_action(&mut self, ctx: &egui::Context, action: Action) {
+        match action {
+            Action::FocusNextPane => self.cycle_focus(true),
+            Action::FocusPreviousPane => self.cycle_focus(false),
+            Action::OpenOrderTicket => {
+                self.order_ticket.open_for(self.current_token_id.as_deref());
+            }
+            Action::CancelAll => {
+                self.cancel_all.open(self.portfolio_service.order_count_sync());
+            }
+            Action::Screenshot => self.take_screenshot(ctx),
+            Action::SearchPalette => self.search_palette.open(),
+            Action::ToggleFullscreen => {
+                self.is_fullscreen = !self.is_fullscreen;
+                if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.is_fullscreen));
+                })) {
+                    error!("❌ Fullscreen command failed: {:?}", e);
+                    // Reset state on failure
+                    self.is_fullscreen = !self.is_fullscreen;
+                } else {
+                    info!("🖥️ Toggling fullscreen to: {}", self.is_fullscreen);
+                }
+            }
+            Action::Quit => {
+                info!("🚪 User pressed the quit shortcut");
+                if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                })) {
+                    error!("❌ Quit command failed: {:?}", e);
+                } else {
+                    info!("🚪 Close command sent");
+                }
+            }
+        }
+    }
+
+    /// Key of `action` for menus, empty when unbound
+    fn shortcut_text(&self, action: Action) -> String {
+        self.keymap
+            .binding(action)
+            .map(|chord| chord.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Focus the next (or previous) visible pane, in layout order
+    fn cycle_focus(&mut self, forward: bool) {
+        let panes: Vec<TileId> = self
+            .tree
+            .active_tiles()
+            .into_iter()
+            .filter(|tile_id| matches!(self.tree.tiles.get(*tile_id), Some(egui_tiles::Tile::Pane(_))))
+            .collect();
+        if panes.is_empty() {
+            return;
+        }
+        let current = self
+            .focused_tile_id
+            .and_then(|focused| panes.iter().position(|tile_id| *tile_id == focused));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % panes.len(),
+            (Some(index), false) => (index + panes.len() - 1) % panes.len(),
+            (None, true) => 0,
+            (None, false) => panes.len() - 1,
+        };
+        self.focus_pane(panes[next]);
+    }
+
+    /// Shortcut actions and pane openers offered by the search palette
+    fn palette_items(&self) -> Vec<PaletteItem> {
+        let mut items: Vec<PaletteItem> = self
+            .keymap
+            .iter()
+            .filter(|(action, _)| *action != Action::SearchPalette)
+            .map(|(action, chord)| PaletteItem {
+                label: action.label().to_string(),
+                shortcut: chord.map(|chord| chord.to_string()),
+                command: PaletteCommand::Action(action),
+            })
+            .collect();
+        let panes = [
+            Pane::Orders,
+            Pane::Portfolio,
+            Pane::PortfolioValue,
+            Pane::Streams,
+            Pane::Tokens,
+            Pane::MarketDepth(None),
+            Pane::CrossRates,
+            Pane::Charts,
+            Pane::TradeHistory,
+            Pane::Balances,
+            Pane::WebSocketManager,
+            Pane::Strategies,
+        ];
+        items.extend(panes.into_iter().map(|pane| PaletteItem {
+            label: format!("Open {}", pane.title().trim_start_matches(pane.icon()).trim()),
+            shortcut: None,
+            command: PaletteCommand::AddPane(pane),
+        }));
+        items
+    }
+
+    fn run_palette_command(&mut self, ctx: &egui::Context, command: PaletteCommand) {
+        match command {
+            PaletteCommand::Action(action) => self.run_action(ctx, action),
+            PaletteCommand::AddPane(Pane::Strategies) if self.strategy_panel_detached => {
+                ctx.send_viewport_cmd_to(strategy_viewport_id(), egui::ViewportCommand::Focus);
+            }
+            PaletteCommand::AddPane(pane) => self.add_pane(pane),
+        }
+    }
+
     /// Take a screenshot and save it to the screenshots directory
     fn take_screenshot(&mut self, ctx: &egui::Context) {
         // Create screenshots directory if it doesn't exist
@@ -2892,6 +3881,13 @@ impl TradingApp {
                     // Subscribe to events
                     self.event_receiver = Some(streaming_service.subscribe_events());
 
+                    // The workers listen to the replay feed by now, so playback can start
+                    if let (Some((replay, speed)), Some(feed)) =
+                        (&self.replay, self.replay_feed.take())
+                    {
+                        self.replay_handle = Some(replay.play(feed, *speed));
+                    }
+
                     self.streaming_service = Some(streaming_service);
                     self.streaming_state = StreamingState::Connected;
                     self.streaming_result_rx = None;
@@ -2899,6 +3895,7 @@ impl TradingApp {
 
                     // Start background task to update cached data
                     self.start_data_update_task();
+                    self.start_token_budget(ctx);
 
                     ctx.request_repaint();
                 }
@@ -2930,8 +3927,15 @@ impl TradingApp {
     }
 }
 
+/// Native window the strategy panel is detached into
+fn strategy_viewport_id() -> egui::ViewportId {
+    egui::ViewportId::from_hash_of("strategy_panel")
+}
+
 impl eframe::App for TradingApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let frame_started = Instant::now();
+
         // Add debug logging to confirm GUI is running (thread-safe)
         if self.first_update {
             info!("🎮 First GUI update() call - interface is running!");
@@ -2966,48 +3970,33 @@ impl eframe::App for TradingApp {
             style.spacing.button_padding = egui::vec2(8.0, 4.0);
         });
 
-        // Handle keyboard shortcuts with improved error handling
-        ctx.input(|i| {
-            // F11 to toggle fullscreen (proper toggling with error handling)
-            if i.key_pressed(egui::Key::F11) {
-                self.is_fullscreen = !self.is_fullscreen;
-                if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.is_fullscreen));
-                })) {
-                    error!("❌ F11 fullscreen command failed: {:?}", e);
-                    // Reset state on failure
-                    self.is_fullscreen = !self.is_fullscreen;
-                } else {
-                    info!("🖥️ F11 pressed - toggling fullscreen to: {}", self.is_fullscreen);
-                }
-            }
+        // Keyboard shortcuts from the keymap; ESC always leaves fullscreen
+        for action in self.pressed_actions(ctx) {
+            self.run_action(ctx, action);
+        }
 
-            // ESC to exit fullscreen (safe approach with error handling)
-            if i.key_pressed(egui::Key::Escape) {
-                if self.is_fullscreen {
-                    if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
-                    })) {
-                        error!("❌ ESC fullscreen exit failed: {:?}", e);
-                    } else {
-                        self.is_fullscreen = false;
-                        info!("🖥️ ESC pressed - exiting fullscreen");
-                    }
-                }
+        // ESC to exit fullscreen (safe approach with error handling)
+        if self.is_fullscreen && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+            })) {
+                error!("❌ ESC fullscreen exit failed: {:?}", e);
+            } else {
+                self.is_fullscreen = false;
+                info!("🖥️ ESC pressed - exiting fullscreen");
             }
+        }
 
-            // Ctrl/Cmd+Q to quit (with error handling)
-            if i.modifiers.command && i.key_pressed(egui::Key::Q) {
-                info!("🚪 User pressed Cmd/Ctrl+Q to quit");
-                if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                })) {
-                    error!("❌ Quit command failed: {:?}", e);
-                } else {
-                    info!("🚪 Close command sent");
-                }
+        // First run: the wizard replaces the canvas until it is finished or skipped
+        if let Some(wizard) = &mut self.onboarding {
+            match wizard.ui(ctx) {
+                WizardOutcome::Continue => return,
+                WizardOutcome::Finished {
+                    data_paths,
+                    starter_dataset,
+                } => self.finish_onboarding(data_paths, starter_dataset),
             }
-        });
+        }
 
         // Initialize portfolio service if needed
         self.init_portfolio_service_if_needed();
@@ -3015,11 +4004,18 @@ impl eframe::App for TradingApp {
         // Poll for streaming progress updates first
         self.poll_streaming_progress(ctx);
 
-        // Poll for cached data updates from background task
-        self.poll_cached_data_updates();
+        // Recount pane subscriptions, then apply what the data task fetched for them
+        self.sync_subscriptions();
+        self.poll_pane_data();
 
         // Poll for streaming events
         self.poll_streaming_events(ctx);
+        self.check_alert_follow_ups(ctx);
+        self.check_token_alerts();
+        self.check_fills(ctx);
+        if let Some(budget) = &mut self.token_budget {
+            budget.poll();
+        }
 
         // Show menu bar
         self.show_menu_bar(ctx);
@@ -3029,6 +4025,8 @@ impl eframe::App for TradingApp {
 
         // Show dialogs on top
         self.show_dialogs(ctx);
+        self.show_detached_strategy_panel(ctx);
+        self.show_perf_overlay(ctx);
 
         // Main content area with tiles - ensure no gaps between sidebar and central panel
         let window_fill = ctx.style().visuals.window_fill();
@@ -3053,27 +4051,33 @@ impl eframe::App for TradingApp {
                 streaming_state: &self.streaming_state,
                 streaming_assets: &self.streaming_assets,
                 current_token_id: &mut self.current_token_id,
-                _current_bids: &self.current_bids,
-                _current_asks: &self.current_asks,
                 orderbook_changes: &self.orderbook_changes,
                 show_dataset_selector: &mut self.show_dataset_selector,
                 _last_position_fetch: &mut self._last_position_fetch,
                 _is_fetching_positions: &mut self._is_fetching_positions,
                 token_activities: &self.token_activities,
                 streaming_service: &self.streaming_service,
+                token_budget: &mut self.token_budget,
                 cached_streaming_stats: &self.cached_streaming_stats,
                 pending_new_orderbook: &mut self.pending_new_orderbook,
                 pending_new_worker_details: &mut self.pending_new_worker_details,
-                cached_orderbook: &self.cached_orderbook,
-                cached_last_trade_price: &self.cached_last_trade_price,
+                token_data: &self.token_data,
+                enrichment_freshness: &self.enrichment_freshness,
+                token_sidebar: &mut self.token_sidebar,
+                token_notes: &mut self.token_notes,
+                cached_cross_rates: &self.cached_cross_rates,
                 cached_worker_statuses: &self.cached_worker_statuses,
                 selected_worker_id: &mut self.selected_worker_id,
                 worker_stream_events: &mut self.worker_stream_events,
                 worker_stream_max_events: &self.worker_stream_max_events,
                 event_receiver: &mut self.event_receiver,
-                current_token_sender: &self.current_token_sender,
+                depth_aggregation: &mut self.depth_aggregation,
                 focused_tile_id: &mut self.focused_tile_id,
                 tiles_to_close: &mut self.tiles_to_close,
+                strategy_panel: &mut self.strategy_panel,
+                pnl_chart: &mut self.pnl_chart,
+                data_paths: &self.data_paths,
+                detach_strategy_panel: &mut self.detach_strategy_panel,
                 screenshot_message: &mut self.screenshot_message,
                 pending_tile_screenshot: &mut self.pending_tile_screenshot,
                 tile_bounds: &mut self.tile_bounds,
@@ -3118,6 +4122,8 @@ impl eframe::App for TradingApp {
             self.previous_tree_hash = Some(current_hash);
         });
 
+        self.paint_flashes(ctx);
+
         // Handle screenshot messages
         if let Some((message, timestamp)) = &self.screenshot_message {
             if timestamp.elapsed().as_secs() < 3 {
@@ -3136,6 +4142,37 @@ impl eframe::App for TradingApp {
                 self.screenshot_message = None;
             }
         }
+        self.alert_toast.show(ctx);
+
+        // Pick up background dataset scans
+        self.poll_dataset_scan();
+        if self.dataset_scan_rx.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        self.poll_freshness();
+        self.refresh_freshness_if_due();
+        if self.token_sidebar.poll() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        // Handle issue report results
+        self.poll_issue_report();
+        if let Some((message, timestamp)) = &self.issue_report_message {
+            if timestamp.elapsed().as_secs() < 8 {
+                egui::Window::new("Report Issue")
+                    .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -80.0])
+                    .fixed_size([360.0, 60.0])
+                    .frame(egui::Frame::popup(&ctx.style()))
+                    .show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.add_space(10.0);
+                            ui.label(message);
+                        });
+                    });
+            } else {
+                self.issue_report_message = None;
+            }
+        }
 
         // Handle screenshot events
         ctx.input(|i| {
@@ -3191,6 +4228,8 @@ impl eframe::App for TradingApp {
                 }
             }
         });
+
+        self.finish_frame(ctx, frame_started);
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
@@ -3212,27 +4251,33 @@ struct TradingBehavior<'a> {
     streaming_state: &'a StreamingState,
     streaming_assets: &'a Vec<String>,
     current_token_id: &'a mut Option<String>,
-    _current_bids: &'a Vec<crate::core::types::market::PriceLevel>,
-    _current_asks: &'a Vec<crate::core::types::market::PriceLevel>,
     orderbook_changes: &'a Vec<OrderBookChange>,
     show_dataset_selector: &'a mut bool,
     _last_position_fetch: &'a mut Option<std::time::Instant>,
     _is_fetching_positions: &'a mut bool,
     token_activities: &'a Arc<RwLock<HashMap<String, TokenActivity>>>,
     streaming_service: &'a Option<Arc<StreamingService>>,
+    token_budget: &'a mut Option<TokenBudgetRunner>,
     cached_streaming_stats: &'a Option<crate::core::services::streaming::traits::StreamingStats>,
     pending_new_orderbook: &'a mut Option<String>,
     pending_new_worker_details: &'a mut Option<usize>,
-    cached_orderbook: &'a Option<crate::core::ws::OrderBook>,
-    cached_last_trade_price: &'a Option<(Decimal, u64)>,
+    token_data: &'a HashMap<String, TokenData>,
+    enrichment_freshness: &'a HashMap<String, EnrichmentFreshness>,
+    token_sidebar: &'a mut TokenSidebar,
+    token_notes: &'a mut TokenNotes,
+    cached_cross_rates: &'a [CrossRate],
     cached_worker_statuses: &'a Vec<crate::core::services::streaming::traits::WorkerStatus>,
     selected_worker_id: &'a mut Option<usize>,
     worker_stream_events: &'a mut Vec<PolyEvent>,
     worker_stream_max_events: &'a usize,
     event_receiver: &'a mut Option<tokio::sync::broadcast::Receiver<PolyEvent>>,
-    current_token_sender: &'a Option<std::sync::mpsc::Sender<Option<String>>>,
+    depth_aggregation: &'a mut PriceAggregation,
     focused_tile_id: &'a mut Option<egui_tiles::TileId>,
     tiles_to_close: &'a mut Vec<egui_tiles::TileId>,
+    strategy_panel: &'a mut StrategyPanel,
+    pnl_chart: &'a mut PnlChart,
+    data_paths: &'a DataPaths,
+    detach_strategy_panel: &'a mut bool,
     // Screenshot-related fields
     screenshot_message: &'a mut Option<(String, std::time::Instant)>,
     pending_tile_screenshot: &'a mut Option<(TileId, std::path::PathBuf, String)>,
@@ -3358,13 +4403,16 @@ impl egui_tiles::Behavior<Pane> for TradingBehavior<'_> {
             Pane::Orders => egui::vec2(400.0, 300.0),
             Pane::Streams => egui::vec2(600.0, 400.0),
             Pane::Portfolio => egui::vec2(500.0, 300.0),
+            Pane::PortfolioValue => egui::vec2(500.0, 350.0),
             Pane::Tokens => egui::vec2(400.0, 300.0),
             Pane::MarketDepth(_) => egui::vec2(350.0, 400.0),
+            Pane::CrossRates => egui::vec2(600.0, 250.0),
             Pane::Charts => egui::vec2(500.0, 400.0),
             Pane::TradeHistory => egui::vec2(400.0, 300.0),
             Pane::Balances => egui::vec2(350.0, 200.0),
             Pane::WebSocketManager => egui::vec2(700.0, 500.0),
             Pane::WorkerDetails(_) => egui::vec2(600.0, 500.0),
+            Pane::Strategies => egui::vec2(450.0, 400.0),
         };
 
         ui.set_min_size(min_size);
@@ -3421,13 +4469,16 @@ impl egui_tiles::Behavior<Pane> for TradingBehavior<'_> {
                     Pane::Orders => self.show_orders_pane(ui),
                     Pane::Streams => self.show_streams_pane(ui),
                     Pane::Portfolio => self.show_portfolio_pane(ui),
+                    Pane::PortfolioValue => self.show_portfolio_value_pane(ui),
                     Pane::Tokens => self.show_tokens_pane(ui),
-                    Pane::MarketDepth(token_id) => self.show_market_depth_pane(ui, token_id),
+                    Pane::MarketDepth(token_id) => self.show_market_depth_pane(ui, tile_id, token_id),
+                    Pane::CrossRates => self.show_cross_rates_pane(ui),
                     Pane::Charts => self.show_charts_pane(ui),
                     Pane::TradeHistory => self.show_trade_history_pane(ui),
                     Pane::Balances => self.show_balances_pane(ui),
                     Pane::WebSocketManager => self.show_websocket_manager_pane(ui),
                     Pane::WorkerDetails(worker_id) => self.show_worker_details_pane(ui, *worker_id),
+                    Pane::Strategies => self.show_strategies_pane(ui, tile_id),
                 }
             });
         });
@@ -3485,13 +4536,16 @@ impl TradingBehavior<'_> {
             Pane::Orders => "orders",
             Pane::Streams => "streams",
             Pane::Portfolio => "portfolio",
+            Pane::PortfolioValue => "portfolio-value",
             Pane::Tokens => "tokens",
             Pane::MarketDepth(_) => "market-depth",
+            Pane::CrossRates => "cross-rates",
             Pane::Charts => "charts",
             Pane::TradeHistory => "trade-history",
             Pane::Balances => "balances",
             Pane::WebSocketManager => "websocket-manager",
             Pane::WorkerDetails(_) => "worker-details",
+            Pane::Strategies => "strategies",
         };
         let filename = format!("tile_{}_{}.png", pane_name, timestamp);
         let filepath = screenshots_dir.join(&filename);
@@ -3695,7 +4749,10 @@ impl TradingBehavior<'_> {
     fn show_orders_pane(&mut self, ui: &mut egui::Ui) {
         // Show user address if available
         if let Some(user_address) = self.portfolio_service.get_user_address_sync() {
-            ui.label(format!("👤 User: {}", user_address));
+            ui.label(format!(
+                "👤 User: {}",
+                crate::address_book::display_address(&user_address)
+            ));
             ui.separator();
         }
 
@@ -3725,11 +4782,14 @@ impl TradingBehavior<'_> {
                     self.portfolio_service.refresh_data_async();
                 }
             }
+            ui.checkbox(&mut self.orders_raw_ids, "Raw IDs")
+                .on_hover_text("Show asset ids instead of market questions");
         });
         ui.separator();
 
         // Get orders from portfolio service
         let orders = self.portfolio_service.get_orders_sync();
+        let raw_ids = self.orders_raw_ids;
 
         if orders.is_empty() && !self.portfolio_service.is_refreshing_sync() {
             ui.label("✅ Successfully authenticated with Polymarket API");
@@ -3770,28 +4830,27 @@ impl TradingBehavior<'_> {
                                 };
                                 ui.monospace(order_id_display);
 
-                                // Market (get from market_info or use asset_id)
-                                let market_display = if let Some(market_info) = &order.market_info {
-                                    if let Some(question) = &market_info.market_question {
-                                        if question.len() > 25 {
-                                            format!("{}...", &question[..25])
-                                        } else {
-                                            question.clone()
-                                        }
-                                    } else {
-                                        format!(
-                                            "Asset: {}",
-                                            &order.asset_id
-                                                [..std::cmp::min(25, order.asset_id.len())]
-                                        )
+                                // Market question and outcome from the market
+                                // metadata cache, else the asset id
+                                let market_info = order
+                                    .market_info
+                                    .as_ref()
+                                    .filter(|_| !raw_ids);
+                                match market_info.and_then(|i| i.market_question.as_deref()) {
+                                    Some(question) => {
+                                        let outcome = market_info
+                                            .and_then(|i| i.token_outcome.as_deref())
+                                            .map(|outcome| format!(" · {}", outcome))
+                                            .unwrap_or_default();
+                                        ui.label(format!("{}{}", truncate(question, 40), outcome))
+                                            .on_hover_text(question);
                                     }
-                                } else {
-                                    format!(
-                                        "Asset: {}",
-                                        &order.asset_id[..std::cmp::min(25, order.asset_id.len())]
-                                    )
-                                };
-                                ui.label(market_display);
+                                    None => {
+                                        let asset: String = order.asset_id.chars().take(25).collect();
+                                        ui.label(format!("Asset: {}", asset))
+                                            .on_hover_text(&order.asset_id);
+                                    }
+                                }
 
                                 // Side with color
                                 let side_str = match order.side {
@@ -3800,23 +4859,23 @@ impl TradingBehavior<'_> {
                                 };
                                 let side_color = match order.side {
                                     crate::core::execution::orders::OrderSide::Buy => {
-                                        egui::Color32::from_rgb(100, 200, 100)
+                                        egui::Color32::from(crate::theme::palette().bid)
                                     }
                                     crate::core::execution::orders::OrderSide::Sell => {
-                                        egui::Color32::from_rgb(200, 100, 100)
+                                        egui::Color32::from(crate::theme::palette().ask)
                                     }
                                 };
                                 ui.colored_label(side_color, side_str);
 
                                 // Price
-                                ui.label(format!("${:.4}", order.price));
+                                ui.label(format!("${}", number_format::price(order.price)));
 
                                 // Size
-                                ui.label(format!("{:.2}", order.size));
+                                ui.label(number_format::size(order.size));
 
                                 // Remaining
                                 let remaining = order.remaining_size;
-                                ui.label(format!("{:.2}", remaining));
+                                ui.label(number_format::size(remaining));
 
                                 // Status with color
                                 let status_str = match order.status {
@@ -3983,14 +5042,14 @@ impl TradingBehavior<'_> {
                                         // Bid/Ask
                                         if let Some(bid_price) = bid {
                                             ui.colored_label(
-                                                egui::Color32::from_rgb(100, 200, 100),
-                                                format!("BID: ${:.4}", bid_price),
+                                                egui::Color32::from(crate::theme::palette().bid),
+                                                format!("BID: ${}", number_format::price(bid_price)),
                                             );
                                         }
                                         if let Some(ask_price) = ask {
                                             ui.colored_label(
-                                                egui::Color32::from_rgb(200, 100, 100),
-                                                format!("ASK: ${:.4}", ask_price),
+                                                egui::Color32::from(crate::theme::palette().ask),
+                                                format!("ASK: ${}", number_format::price(ask_price)),
                                             );
                                         }
                                     });
@@ -4055,11 +5114,17 @@ impl TradingBehavior<'_> {
                                             activity.token_id.clone()
                                         };
 
-                                        // Make clickable to select
-                                        let response = ui.selectable_label(
-                                            self.current_token_id.as_ref() == Some(&activity.token_id),
-                                            &token_display
-                                        );
+                                        // Make clickable to select, with a badge for its note or alert
+                                        let response = ui
+                                            .horizontal(|ui| {
+                                                let response = ui.selectable_label(
+                                                    self.current_token_id.as_ref() == Some(&activity.token_id),
+                                                    &token_display
+                                                );
+                                                self.token_notes.badge(ui, &activity.token_id);
+                                                response
+                                            })
+                                            .inner;
 
                                         if response.clicked() {
                                             // Left click - open new orderbook
@@ -4067,15 +5132,18 @@ impl TradingBehavior<'_> {
                                             info!("Left-clicked token to open new orderbook: {}", activity.token_id);
                                         }
 
-                                        // Right click for additional options (future use)
-                                        if response.secondary_clicked() {
-                                            // Could add context menu here
-                                            *self.pending_new_orderbook = Some(activity.token_id.clone());
-                                            info!("Right-clicked token for new orderbook: {}", activity.token_id);
-                                        }
+                                        // Right click for the note and alert editor
+                                        response.context_menu(|ui| {
+                                            if ui.button("📊 Open orderbook").clicked() {
+                                                *self.pending_new_orderbook = Some(activity.token_id.clone());
+                                                ui.close_menu();
+                                            }
+                                            ui.separator();
+                                            self.token_notes.edit_menu(ui, &activity.token_id);
+                                        });
 
                                         // Add tooltip
-                                        response.on_hover_text("Click to open orderbook");
+                                        response.on_hover_text("Click to open orderbook, right-click for notes and alerts");
 
                                         // Event count
                                         ui.label(format!("{}", activity.event_count));
@@ -4084,13 +5152,13 @@ impl TradingBehavior<'_> {
                                         ui.label(format!("{}", activity.trade_count));
 
                                         // Volume
-                                        ui.label(format!("${:.2}", activity.total_volume));
+                                        ui.label(number_format::usd(activity.total_volume));
 
                                         // Bid price
                                         if let Some(bid) = activity.last_bid {
                                             ui.colored_label(
-                                                egui::Color32::from_rgb(100, 200, 100),
-                                                format!("${:.4}", bid)
+                                                egui::Color32::from(crate::theme::palette().bid),
+                                                format!("${}", number_format::price(bid))
                                             );
                                         } else {
                                             ui.label("-");
@@ -4099,8 +5167,8 @@ impl TradingBehavior<'_> {
                                         // Ask price
                                         if let Some(ask) = activity.last_ask {
                                             ui.colored_label(
-                                                egui::Color32::from_rgb(200, 100, 100),
-                                                format!("${:.4}", ask)
+                                                egui::Color32::from(crate::theme::palette().ask),
+                                                format!("${}", number_format::price(ask))
                                             );
                                         } else {
                                             ui.label("-");
@@ -4110,7 +5178,7 @@ impl TradingBehavior<'_> {
                                         if let Some(last_trade) = activity.last_trade_price {
                                             ui.colored_label(
                                                 egui::Color32::from_rgb(150, 150, 255),
-                                                format!("${:.4}", last_trade)
+                                                format!("${}", number_format::price(last_trade))
                                             );
                                         } else {
                                             ui.label("-");
@@ -4146,7 +5214,10 @@ impl TradingBehavior<'_> {
     fn show_portfolio_pane(&mut self, ui: &mut egui::Ui) {
         // Show user address if available
         if let Some(user_address) = self.portfolio_service.get_user_address_sync() {
-            ui.label(format!("👤 User: {}", user_address));
+            ui.label(format!(
+                "👤 User: {}",
+                crate::address_book::display_address(&user_address)
+            ));
             ui.label(format!(
                 "🔗 Profile: https://polymarket.com/profile/{}",
                 user_address
@@ -4169,21 +5240,21 @@ impl TradingBehavior<'_> {
                 ui.group(|ui| {
                     ui.vertical_centered(|ui| {
                         ui.heading("💰 Cash");
-                        ui.label(format!("${:.2}", balance.cash));
+                        ui.label(number_format::usd(balance.cash));
                     });
                 });
 
                 ui.group(|ui| {
                     ui.vertical_centered(|ui| {
                         ui.heading("🎯 Bets");
-                        ui.label(format!("${:.2}", balance.bets));
+                        ui.label(number_format::usd(balance.bets));
                     });
                 });
 
                 ui.group(|ui| {
                     ui.vertical_centered(|ui| {
                         ui.heading("📊 Total Equity");
-                        ui.label(format!("${:.2}", balance.equity_total));
+                        ui.label(number_format::usd(balance.equity_total));
                     });
                 });
             });
@@ -4197,12 +5268,8 @@ impl TradingBehavior<'_> {
                     ui.vertical_centered(|ui| {
                         ui.heading("Total P&L");
                         let pnl = stats.total_pnl();
-                        let color = if pnl >= rust_decimal::Decimal::ZERO {
-                            egui::Color32::from_rgb(100, 200, 100)
-                        } else {
-                            egui::Color32::from_rgb(200, 100, 100)
-                        };
-                        ui.colored_label(color, format!("${:.2}", pnl));
+                        let color = egui::Color32::from(crate::theme::palette().pnl(pnl >= rust_decimal::Decimal::ZERO));
+                        ui.colored_label(color, number_format::usd(pnl));
                     });
                 });
 
@@ -4210,7 +5277,7 @@ impl TradingBehavior<'_> {
                     ui.vertical_centered(|ui| {
                         ui.heading("Win Rate");
                         if let Some(win_rate) = stats.win_rate {
-                            ui.label(format!("{:.1}%", win_rate));
+                            ui.label(number_format::percent(win_rate));
                         } else {
                             ui.label("N/A");
                         }
@@ -4227,11 +5294,28 @@ impl TradingBehavior<'_> {
             ui.separator();
         }
 
+        ui.horizontal(|ui| {
+            ui.label("Group by:");
+            ui.selectable_value(&mut self.portfolio_grouping, None, "Positions");
+            ui.selectable_value(
+                &mut self.portfolio_grouping,
+                Some(ExposureGrouping::Category),
+                "Category",
+            );
+            ui.selectable_value(
+                &mut self.portfolio_grouping,
+                Some(ExposureGrouping::EndDate),
+                "End date",
+            );
+        });
+
         // Show positions from service
         let positions = self.portfolio_service.get_positions_sync();
         if positions.is_empty() && !self.portfolio_service.is_refreshing_sync() {
             ui.label("📊 No positions found");
             ui.label("💡 Your positions will appear here after placing trades");
+        } else if let Some(grouping) = self.portfolio_grouping.filter(|_| !positions.is_empty()) {
+            self.show_exposure_groups(ui, &positions, grouping);
         } else if !positions.is_empty() {
             ui.label(format!("📊 Positions ({} total)", positions.len()));
             ui.separator();
@@ -4242,7 +5326,7 @@ impl TradingBehavior<'_> {
                 .max_height(400.0)
                 .show(ui, |ui| {
                     egui::Grid::new("positions_grid")
-                        .num_columns(8)
+                        .num_columns(10)
                         .spacing([8.0, 4.0])
                         .striped(true)
                         .show(ui, |ui| {
@@ -4253,7 +5337,9 @@ impl TradingBehavior<'_> {
                             ui.heading("Size");
                             ui.heading("Avg Price");
                             ui.heading("Current");
+                            ui.heading("Value");
                             ui.heading("P&L");
+                            ui.heading("P&L (sh)");
                             ui.heading("Status");
                             ui.end_row();
 
@@ -4273,40 +5359,43 @@ impl TradingBehavior<'_> {
                                 // Side with color
                                 let side_color = match position.side {
                                     crate::core::portfolio::PositionSide::Long => {
-                                        egui::Color32::from_rgb(100, 200, 100)
+                                        egui::Color32::from(crate::theme::palette().bid)
                                     }
                                     crate::core::portfolio::PositionSide::Short => {
-                                        egui::Color32::from_rgb(200, 100, 100)
+                                        egui::Color32::from(crate::theme::palette().ask)
                                     }
                                 };
                                 ui.colored_label(side_color, format!("{:?}", position.side));
 
                                 // Size
-                                ui.label(format!("{:.2}", position.size));
+                                ui.label(number_format::size(position.size));
 
                                 // Average price
-                                ui.label(format!("${:.4}", position.average_price));
+                                ui.label(format!("${}", number_format::price(position.average_price)));
 
                                 // Current price
                                 let current_price = position
                                     .current_price
-                                    .map(|p| format!("${:.4}", p))
+                                    .map(|p| format!("${}", number_format::price(p)))
                                     .unwrap_or_else(|| "N/A".to_string());
                                 ui.label(current_price);
 
-                                // P&L with color
-                                let pnl = position.total_pnl();
-                                let pnl_color = if pnl >= rust_decimal::Decimal::ZERO {
-                                    egui::Color32::from_rgb(100, 200, 100)
-                                } else {
-                                    egui::Color32::from_rgb(200, 100, 100)
-                                };
-                                let pnl_str = if pnl >= rust_decimal::Decimal::ZERO {
-                                    format!("+${:.2}", pnl)
-                                } else {
-                                    format!("-${:.2}", pnl.abs())
-                                };
-                                ui.colored_label(pnl_color, pnl_str);
+                                let exposure = OutcomeExposure::from_position(position);
+
+                                // Value at the mark
+                                ui.label(units::format_usdc(exposure.value_usdc));
+
+                                // P&L with color, in USDC and in shares of the outcome
+                                let pnl = exposure.pnl_usdc;
+                                let pnl_color = egui::Color32::from(crate::theme::palette().pnl(pnl >= rust_decimal::Decimal::ZERO));
+                                ui.colored_label(pnl_color, units::format_signed_usdc(pnl));
+                                ui.colored_label(
+                                    pnl_color,
+                                    exposure
+                                        .pnl_shares
+                                        .map(units::format_signed_shares)
+                                        .unwrap_or_else(|| "N/A".to_string()),
+                                );
 
                                 // Status
                                 let status_color = match position.status {
@@ -4334,131 +5423,436 @@ impl TradingBehavior<'_> {
         }
     }
 
+    /// Open positions' exposure per category or end-date bucket
+    fn show_exposure_groups(
+        &mut self,
+        ui: &mut egui::Ui,
+        positions: &[crate::core::portfolio::Position],
+        grouping: ExposureGrouping,
+    ) {
+        // Markets resolve through the token sidebar's cache; unresolved ones
+        // count as uncategorized until their lookup finishes
+        let sidebar = &mut self.token_sidebar;
+        let groups = ExposureGroup::group(
+            positions,
+            |p| sidebar.info(&p.token_id).map(MarketMeta::from),
+            grouping,
+            chrono::Utc::now(),
+        );
+        if groups.is_empty() {
+            ui.label("📊 No open positions");
+            return;
+        }
+        let total: rust_decimal::Decimal = groups.iter().map(|g| g.value_usdc).sum();
+
+        egui::Grid::new("exposure_grid")
+            .num_columns(5)
+            .spacing([8.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.heading(match grouping {
+                    ExposureGrouping::Category => "Category",
+                    ExposureGrouping::EndDate => "Ends",
+                });
+                ui.heading("Positions");
+                ui.heading("Value");
+                ui.heading("Share");
+                ui.heading("P&L");
+                ui.end_row();
+
+                for group in &groups {
+                    ui.label(&group.label);
+                    ui.label(group.positions.to_string());
+                    ui.label(units::format_usdc(group.value_usdc));
+                    ui.label(
+                        group
+                            .share_of(total)
+                            .map(|share| format!("{}%", share))
+                            .unwrap_or_else(|| "-".to_string()),
+                    );
+                    let pnl = group.pnl_usdc;
+                    let pnl_color = egui::Color32::from(
+                        crate::theme::palette().pnl(pnl >= rust_decimal::Decimal::ZERO),
+                    );
+                    ui.colored_label(pnl_color, units::format_signed_usdc(pnl));
+                    ui.end_row();
+                }
+            });
+    }
+
     fn show_tokens_pane(&mut self, ui: &mut egui::Ui) {
-        ui.label("Token data and selection interface coming soon.");
+        if self.streaming_assets.is_empty() {
+            ui.label("No tokens streaming. Select datasets to start.");
+            return;
+        }
+
+        let stale = self
+            .streaming_assets
+            .iter()
+            .filter(|id| {
+                freshness::is_stale(
+                    self.enrichment_freshness.get(*id),
+                    freshness::DEFAULT_MAX_AGE,
+                    chrono::Utc::now(),
+                )
+            })
+            .count();
+        ui.label(format!(
+            "{} tokens · {} with stale or missing enrichment",
+            self.streaming_assets.len(),
+            stale
+        ));
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("tokens_grid")
+                .num_columns(4)
+                .spacing([12.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Token");
+                    ui.strong("Enrichment");
+                    ui.strong("Dataset");
+                    ui.strong("");
+                    ui.end_row();
+
+                    for token_id in self.streaming_assets.iter() {
+                        let short = if token_id.len() > 16 {
+                            format!("{}...", &token_id[..16])
+                        } else {
+                            token_id.clone()
+                        };
+                        ui.label(egui::RichText::new(short).monospace())
+                            .on_hover_text(token_id);
+                        let entry = self.enrichment_freshness.get(token_id);
+                        enrichment_age_label(ui, entry);
+                        ui.label(entry.map(|e| e.dataset.as_str()).unwrap_or("-"));
+                        if ui.small_button("📊 Depth").clicked() {
+                            *self.pending_new_orderbook = Some(token_id.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
 
-        // TODO: Port token functionality from ratatui version
+    /// Bind a depth pane to any streamed token; the token is saved with the layout
+    fn show_depth_token_picker(
+        &mut self,
+        ui: &mut egui::Ui,
+        tile_id: TileId,
+        pane_token_id: &mut Option<String>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("Token:");
+            let selected = pane_token_id.as_deref().unwrap_or("none");
+            egui::ComboBox::from_id_salt(("depth_token", tile_id))
+                .selected_text(if selected.len() > 16 {
+                    format!("{}...", &selected[..16])
+                } else {
+                    selected.to_string()
+                })
+                .width(180.0)
+                .show_ui(ui, |ui| {
+                    for token_id in self.streaming_assets.iter() {
+                        let is_current = pane_token_id.as_ref() == Some(token_id);
+                        if ui.selectable_label(is_current, token_id).clicked() && !is_current {
+                            info!("Depth pane {:?} now follows token {}", tile_id, token_id);
+                            *pane_token_id = Some(token_id.clone());
+                            *self.has_unsaved_layout_changes = true;
+                        }
+                    }
+                });
+        });
     }
 
-    fn show_market_depth_pane(&mut self, ui: &mut egui::Ui, pane_token_id: &Option<String>) {
-        if let Some(token_id) = pane_token_id {
-            ui.label(format!("Market depth for: {}", token_id));
+    fn show_market_depth_pane(
+        &mut self,
+        ui: &mut egui::Ui,
+        tile_id: TileId,
+        pane_token_id: &mut Option<String>,
+    ) {
+        self.show_depth_token_picker(ui, tile_id, pane_token_id);
+
+        if let Some(token_id) = pane_token_id.as_ref() {
+            let sidebar_id = egui::Id::new(("depth_token_sidebar", tile_id));
+            let mut sidebar_open = ui.data(|d| d.get_temp(sidebar_id)).unwrap_or(true);
+            ui.horizontal(|ui| {
+                // Name the market once the store has resolved the token
+                let title = match self.token_sidebar.info(token_id) {
+                    Some(info) => match (&info.question, &info.outcome) {
+                        (Some(question), Some(outcome)) => format!("{} ({})", question, outcome),
+                        (Some(question), None) => question.clone(),
+                        _ => token_id.clone(),
+                    },
+                    None => token_id.clone(),
+                };
+                ui.label(format!("Market depth for: {}", title))
+                    .on_hover_text(token_id);
+                enrichment_age_label(ui, self.enrichment_freshness.get(token_id));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.toggle_value(&mut sidebar_open, "ℹ Info").changed() {
+                        ui.data_mut(|d| d.insert_temp(sidebar_id, sidebar_open));
+                    }
+                });
+            });
             ui.separator();
 
-            // Update current token if this pane has a different token
-            if self.current_token_id.as_ref() != Some(token_id) {
-                *self.current_token_id = Some(token_id.clone());
+            egui::SidePanel::right(sidebar_id)
+                .resizable(true)
+                .default_width(220.0)
+                .show_animated_inside(ui, sidebar_open, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_salt(("depth_token_sidebar_scroll", tile_id))
+                        .show(ui, |ui| {
+                            if let Some(open) = self.token_sidebar.ui(ui, token_id) {
+                                *self.pending_new_orderbook = Some(open);
+                            }
+                        });
+                });
 
-                // Notify background task of current token change
-                if let Some(sender) = self.current_token_sender {
-                    let _ = sender.send(Some(token_id.clone()));
-                }
-            }
+            egui::CentralPanel::default()
+                .frame(egui::Frame::NONE)
+                .show_inside(ui, |ui| self.show_depth_book(ui, tile_id, token_id));
+        } else {
+            ui.label("This orderbook pane has no token assigned");
+            ui.label("Pick a streamed token above, or click a token in the Streams pane.");
+        }
+    }
 
-            // Get orderbook data for this specific token
-            if let Some(_streaming_service) = &self.streaming_service {
-                // Display cached last trade price if available
-                if let Some((last_price, timestamp)) = &self.cached_last_trade_price {
-                    ui.horizontal(|ui| {
-                        ui.group(|ui| {
-                            ui.vertical_centered(|ui| {
-                                ui.label("Last Trade Price");
-                                ui.heading(format!("${:.4}", last_price));
-
-                                // Format timestamp
-                                let dt = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(
-                                    *timestamp as i64,
-                                )
-                                .unwrap_or_else(chrono::Utc::now);
-                                ui.label(format!("{}", dt.format("%H:%M:%S UTC")));
-                            });
+    /// Order book, last trade and depth metrics of a depth pane's token
+    fn show_depth_book(&mut self, ui: &mut egui::Ui, tile_id: TileId, token_id: &str) {
+        // This pane's own subscription, fetched independently of other depth panes
+        let data = self.token_data.get(token_id);
+
+        if let Some(_streaming_service) = &self.streaming_service {
+            // Display cached last trade price if available
+            if let Some(last_trade) = data.and_then(|d| d.last_trade.as_ref()) {
+                ui.horizontal(|ui| {
+                    ui.group(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label("Last Trade Price");
+                            ui.heading(format!("${}", number_format::price(&last_trade.price)));
+
+                            // Format timestamp
+                            let dt = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(
+                                last_trade.timestamp as i64,
+                            )
+                            .unwrap_or_else(chrono::Utc::now);
+                            ui.label(format!("{}", dt.format("%H:%M:%S UTC")));
                         });
                     });
+                });
+                ui.separator();
+            }
+
+            if let Some(order_book) = data.and_then(|d| d.book.as_ref()) {
+                // Each pane keeps its own choice of ladder or chart
+                let display_id = egui::Id::new(("depth_display", tile_id));
+                let mut display: DepthDisplay = ui.data(|d| d.get_temp(display_id)).unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.label("Price bins:");
+                    egui::ComboBox::from_id_salt(("depth_aggregation", tile_id))
+                        .selected_text(self.depth_aggregation.label())
+                        .show_ui(ui, |ui| {
+                            for aggregation in PriceAggregation::all() {
+                                ui.selectable_value(
+                                    self.depth_aggregation,
+                                    aggregation,
+                                    aggregation.label(),
+                                );
+                            }
+                        });
+
                     ui.separator();
-                }
+                    for option in [DepthDisplay::Ladder, DepthDisplay::Chart] {
+                        if ui.selectable_value(&mut display, option, option.label()).changed() {
+                            ui.data_mut(|d| d.insert_temp(display_id, display));
+                        }
+                    }
+                });
 
-                if let Some(order_book) = &self.cached_orderbook {
-                    let bids = order_book.get_bids();
-                    let asks = order_book.get_asks();
+                let aggregation = *self.depth_aggregation;
+                let view = BookView::new(&order_book.get_bids(), &order_book.get_asks(), aggregation);
 
+                if display == DepthDisplay::Chart {
+                    depth_chart(ui, tile_id, &view);
+                } else {
                     // Enhanced order book display with depth visualization
                     use crate::gui::components::market_data::order_book_display_enhanced;
 
-                    // Filter changes for this specific token
+                    // Filter changes for this specific token, moved into the displayed bins
                     let changes: Vec<(Decimal, Decimal, Instant, bool)> = self
                         .orderbook_changes
                         .iter()
                         .filter(|change| change.token_id == *token_id)
-                        .map(|change| (change.price, change.size, change.changed_at, change.is_bid))
+                        .map(|change| {
+                            (
+                                aggregation.bin_price(change.price, change.is_bid),
+                                change.size,
+                                change.changed_at,
+                                change.is_bid,
+                            )
+                        })
                         .collect();
 
-                    order_book_display_enhanced(ui, &bids[..], &asks[..], &changes);
+                    order_book_display_enhanced(ui, &view.bids[..], &view.asks[..], &changes);
+                }
+                let bids = view.bids;
+                let asks = view.asks;
 
-                    // Additional market depth metrics
-                    ui.separator();
-                    ui.horizontal(|ui| {
-                        ui.group(|ui| {
-                            ui.vertical_centered(|ui| {
-                                ui.label("Bid Levels");
-                                ui.heading(format!("{}", bids.len()));
-                            });
+                // Additional market depth metrics
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.group(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label("Bid Levels");
+                            ui.heading(format!("{}", bids.len()));
                         });
+                    });
 
-                        ui.group(|ui| {
-                            ui.vertical_centered(|ui| {
-                                ui.label("Ask Levels");
-                                ui.heading(format!("{}", asks.len()));
-                            });
+                    ui.group(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label("Ask Levels");
+                            ui.heading(format!("{}", asks.len()));
                         });
-
-                        if !bids.is_empty() && !asks.is_empty() {
-                            let spread = asks[0].price - bids[0].price;
-                            let spread_pct = (spread / asks[0].price) * Decimal::from(100);
-
-                            ui.group(|ui| {
-                                ui.vertical_centered(|ui| {
-                                    ui.label("Spread");
-                                    ui.heading(format!("{:.2}%", spread_pct));
-                                });
-                            });
-                        }
                     });
-                } else {
-                    ui.label("No orderbook data available");
-                    ui.label("Waiting for orderbook data...");
 
-                    // Show helpful information
-                    ui.separator();
-                    ui.label("Troubleshooting:");
-                    ui.label("• Ensure the token is being streamed");
-                    ui.label("• Check that streaming service is connected");
-                    ui.label(format!("• Current token: {}", token_id));
+                    // Spread comes from the raw book so binning doesn't widen it
+                    if let (Some(best_bid), Some(best_ask)) =
+                        (order_book.best_bid(), order_book.best_ask())
+                    {
+                        let spread = best_ask.price - best_bid.price;
+                        let spread_pct = (spread / best_ask.price) * Decimal::from(100);
 
-                    if let Some(_streaming_service) = &self.streaming_service {
-                        // Show debug info
-                        ui.separator();
-                        ui.label("Debug info:");
-                        ui.label("• Background task should be fetching orderbook data");
-                        ui.label("• Check application logs for fetch attempts");
+                        ui.group(|ui| {
+                            ui.vertical_centered(|ui| {
+                                ui.label("Spread");
+                                ui.heading(number_format::percent(spread_pct));
+                            });
+                        });
                     }
-                }
+                });
             } else {
-                ui.label("Streaming service not available");
-                ui.label("Start streaming to see market depth data.");
+                ui.label("No orderbook data available");
+                ui.label("Waiting for orderbook data...");
 
+                // Show helpful information
                 ui.separator();
-                ui.label("To start streaming:");
-                ui.label("1. Go to the Streams tab");
-                ui.label("2. Click 'Start Streaming'");
-                ui.label("3. Add tokens to stream");
+                ui.label("Troubleshooting:");
+                ui.label("• Ensure the token is being streamed");
+                ui.label("• Check that streaming service is connected");
+                ui.label(format!("• Current token: {}", token_id));
+
+                if let Some(_streaming_service) = &self.streaming_service {
+                    // Show debug info
+                    ui.separator();
+                    ui.label("Debug info:");
+                    ui.label("• Background task should be fetching orderbook data");
+                    ui.label("• Check application logs for fetch attempts");
+                }
             }
         } else {
-            ui.label("This orderbook pane has no token assigned");
+            ui.label("Streaming service not available");
+            ui.label("Start streaming to see market depth data.");
 
-            if ui.button("📡 Open Streams Panel").clicked() {
-                info!("User requested to open streams panel");
-            }
+            ui.separator();
+            ui.label("To start streaming:");
+            ui.label("1. Go to the Streams tab");
+            ui.label("2. Click 'Start Streaming'");
+            ui.label("3. Add tokens to stream");
+        }
+    }
+
+    fn show_strategies_pane(&mut self, ui: &mut egui::Ui, tile_id: TileId) {
+        if self.strategy_panel.ui(ui, self.streaming_assets, false) {
+            *self.detach_strategy_panel = true;
+            self.close_pane(tile_id);
+        }
+    }
+
+    fn show_portfolio_value_pane(&mut self, ui: &mut egui::Ui) {
+        let address = self.portfolio_service.get_user_address_sync();
+        self.pnl_chart.ui(
+            ui,
+            self.data_paths,
+            address.as_deref(),
+            self.strategy_panel.statuses(),
+        );
+    }
+
+    fn show_cross_rates_pane(&mut self, ui: &mut egui::Ui) {
+        let rates = self.cached_cross_rates;
+        let palette = crate::theme::palette();
+        let with_edge = rates.iter().filter(|rate| rate.has_arbitrage()).count();
+
+        ui.horizontal(|ui| {
+            ui.heading("⚖ Cross Rates");
+            ui.separator();
+            ui.label(format!("{} markets, {} with edge", rates.len(), with_edge));
+        });
+        ui.label("Ask sum below 1 or bid sum above 1 means both outcomes can be traded for an edge.");
+        ui.separator();
+
+        if rates.is_empty() {
+            ui.label("No market has both outcome tokens streaming.");
+            ui.label("Stream both tokens of a condition to see cross rates.");
+            return;
         }
+
+        let fmt = |value: Option<Decimal>| {
+            value
+                .map(number_format::price)
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let short = |id: &str| ShortId(id).to_string();
+        let edge_label = |ui: &mut egui::Ui, edge: Option<Decimal>| match edge {
+            Some(edge) if edge > Decimal::ZERO => {
+                ui.colored_label(
+                    palette.profit,
+                    egui::RichText::new(number_format::price_change(edge)).strong(),
+                );
+            }
+            Some(edge) => {
+                ui.colored_label(palette.muted, number_format::price_change(edge));
+            }
+            None => {
+                ui.label("-");
+            }
+        };
+
+        egui::ScrollArea::vertical()
+            .id_salt("cross_rates_scroll")
+            .show(ui, |ui| {
+                egui::Grid::new("cross_rates_grid")
+                    .striped(true)
+                    .num_columns(11)
+                    .show(ui, |ui| {
+                        for header in [
+                            "Market", "Token A", "A Bid", "A Ask", "Token B", "B Bid", "B Ask",
+                            "Ask Sum", "Bid Sum", "Buy Both", "Sell Both",
+                        ] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+
+                        for rate in rates {
+                            let [a, b] = &rate.tokens;
+                            ui.label(short(&rate.market)).on_hover_text(&rate.market);
+                            ui.label(short(&a.asset_id)).on_hover_text(&a.asset_id);
+                            ui.colored_label(palette.bid, fmt(a.best_bid));
+                            ui.colored_label(palette.ask, fmt(a.best_ask));
+                            ui.label(short(&b.asset_id)).on_hover_text(&b.asset_id);
+                            ui.colored_label(palette.bid, fmt(b.best_bid));
+                            ui.colored_label(palette.ask, fmt(b.best_ask));
+                            ui.label(fmt(rate.ask_sum()));
+                            ui.label(fmt(rate.bid_sum()));
+                            edge_label(ui, rate.buy_both_edge());
+                            edge_label(ui, rate.sell_both_edge());
+                            ui.end_row();
+                        }
+                    });
+            });
     }
 
     fn show_charts_pane(&mut self, ui: &mut egui::Ui) {
@@ -4559,6 +5953,23 @@ impl TradingBehavior<'_> {
 
         ui.separator();
 
+        // Token budget picking what streams
+        ui.group(|ui| {
+            ui.label("🎯 Token Budget");
+            match self.token_budget.as_mut() {
+                Some(budget) => budget.ui(ui),
+                None => {
+                    ui.small(format!(
+                        "Off: set max_tokens or max_connections in {} to stream positions, \
+                         watchlists and top screened markets within a budget",
+                        TOKEN_BUDGET_CONFIG_FILE
+                    ));
+                }
+            }
+        });
+
+        ui.separator();
+
         // Workers Table
         ui.group(|ui| {
             ui.label("👷 Worker Connections");
@@ -4777,6 +6188,9 @@ impl TradingBehavior<'_> {
                                                     "PRICE",
                                                 );
                                             }
+                                            PolyEvent::Book { source, .. } if source.is_synthetic() => {
+                                                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "BOOK (REST)");
+                                            }
                                             PolyEvent::Book { .. } => {
                                                 ui.colored_label(egui::Color32::YELLOW, "BOOK");
                                             }
@@ -4878,14 +6292,14 @@ impl TradingBehavior<'_> {
 
                                         // Price
                                         if let Some(price) = price {
-                                            ui.monospace(format!("{:.4}", price));
+                                            ui.monospace(number_format::price(price));
                                         } else {
                                             ui.label("-");
                                         }
 
                                         // Size
                                         if let Some(size) = size {
-                                            ui.monospace(format!("{:.2}", size));
+                                            ui.monospace(number_format::size(size));
                                         } else {
                                             ui.label("-");
                                         }
@@ -5265,6 +6679,9 @@ impl TradingBehavior<'_> {
                                                     "PRICE",
                                                 );
                                             }
+                                            PolyEvent::Book { source, .. } if source.is_synthetic() => {
+                                                ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "BOOK (REST)");
+                                            }
                                             PolyEvent::Book { .. } => {
                                                 ui.colored_label(egui::Color32::YELLOW, "BOOK");
                                             }
@@ -5366,14 +6783,14 @@ impl TradingBehavior<'_> {
 
                                         // Price
                                         if let Some(price) = price {
-                                            ui.monospace(format!("{:.4}", price));
+                                            ui.monospace(number_format::price(price));
                                         } else {
                                             ui.label("-");
                                         }
 
                                         // Size
                                         if let Some(size) = size {
-                                            ui.monospace(format!("{:.2}", size));
+                                            ui.monospace(number_format::size(size));
                                         } else {
                                             ui.label("-");
                                         }
@@ -0,0 +1,381 @@
+//! Market preview for the dataset selector
+//!
+//! Expanding a dataset in the selector reads its markets on a blocking task
+//! and lists them with per-market checkboxes, so a subset of a large dataset
+//! can be streamed. Datasets that are never narrowed down stream every market.
+
+use egui::RichText;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tracing::warn;
+
+use crate::number_format;
+
+/// A market as listed in the preview
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewMarket {
+    /// Condition ID, or whatever identifies the market in the dataset
+    pub key: String,
+    pub question: String,
+    pub volume: Option<f64>,
+    pub token_ids: Vec<String>,
+}
+
+enum PreviewState {
+    Loading,
+    Loaded(Vec<PreviewMarket>),
+    Failed(String),
+}
+
+/// A finished read of one dataset's markets
+struct PreviewResult {
+    name: String,
+    markets: Result<Vec<PreviewMarket>, String>,
+}
+
+/// How much of a partly selected dataset will stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedCount {
+    pub checked: usize,
+    pub total: usize,
+}
+
+pub struct DatasetPreviews {
+    previews: HashMap<String, PreviewState>,
+    /// Unchecked markets per dataset; a dataset without entries streams all markets
+    excluded: HashMap<String, HashSet<String>>,
+    filters: HashMap<String, String>,
+    result_tx: mpsc::Sender<PreviewResult>,
+    result_rx: mpsc::Receiver<PreviewResult>,
+}
+
+impl Default for DatasetPreviews {
+    fn default() -> Self {
+        let (result_tx, result_rx) = mpsc::channel();
+        Self {
+            previews: HashMap::new(),
+            excluded: HashMap::new(),
+            filters: HashMap::new(),
+            result_tx,
+            result_rx,
+        }
+    }
+}
+
+impl DatasetPreviews {
+    /// Pick up finished loads; returns whether any are still running
+    pub fn poll(&mut self) -> bool {
+        while let Ok(result) = self.result_rx.try_recv() {
+            let state = match result.markets {
+                Ok(markets) => PreviewState::Loaded(markets),
+                Err(e) => PreviewState::Failed(e),
+            };
+            self.previews.insert(result.name, state);
+        }
+        self.previews
+            .values()
+            .any(|state| matches!(state, PreviewState::Loading))
+    }
+
+    fn request(&mut self, name: &str, path: &Path) {
+        self.previews
+            .insert(name.to_string(), PreviewState::Loading);
+        let name = name.to_string();
+        let path = path.to_path_buf();
+        let tx = self.result_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let markets = read_dataset_markets(&path)
+                .map(|values| preview_markets(&values))
+                .map_err(|e| e.to_string());
+            let _ = tx.send(PreviewResult { name, markets });
+        });
+    }
+
+    /// Forget every per-market choice, e.g. when the selector is cancelled
+    pub fn clear_selection(&mut self) {
+        self.excluded.clear();
+    }
+
+    /// Drop cached previews so they are re-read after a dataset rescan
+    pub fn invalidate(&mut self) {
+        self.previews
+            .retain(|_, state| matches!(state, PreviewState::Loading));
+    }
+
+    /// Checked and total markets of `name` when only part of it is selected
+    pub fn selected_count(&self, name: &str) -> Option<SelectedCount> {
+        let excluded = self.excluded.get(name).filter(|e| !e.is_empty())?;
+        let Some(PreviewState::Loaded(markets)) = self.previews.get(name) else {
+            return None;
+        };
+        let checked = markets
+            .iter()
+            .filter(|m| !excluded.contains(&m.key))
+            .count();
+        Some(SelectedCount {
+            checked,
+            total: markets.len(),
+        })
+    }
+
+    /// Tokens of the checked markets of `name`, or `None` to stream all of it
+    pub fn token_subset(&self, name: &str) -> Option<Vec<String>> {
+        let excluded = self.excluded.get(name).filter(|e| !e.is_empty())?;
+        let Some(PreviewState::Loaded(markets)) = self.previews.get(name) else {
+            return None;
+        };
+        Some(
+            markets
+                .iter()
+                .filter(|m| !excluded.contains(&m.key))
+                .flat_map(|m| m.token_ids.iter().cloned())
+                .collect(),
+        )
+    }
+
+    /// Collapsible market list of one dataset
+    ///
+    /// Checking a market selects its dataset; unchecking the last one deselects it.
+    pub fn ui(&mut self, ui: &mut egui::Ui, name: &str, path: &Path, selected: &mut bool) {
+        let header = match self.selected_count(name) {
+            Some(count) => {
+                format!("🔍 Preview markets ({} of {} checked)", count.checked, count.total)
+            }
+            None => "🔍 Preview markets".to_string(),
+        };
+        egui::CollapsingHeader::new(header)
+            .id_salt(("dataset_preview", name))
+            .show(ui, |ui| {
+                if !self.previews.contains_key(name) {
+                    self.request(name, path);
+                }
+                match self.previews.get(name) {
+                    Some(PreviewState::Loaded(markets)) => markets_ui(
+                        ui,
+                        name,
+                        markets,
+                        self.filters.entry(name.to_string()).or_default(),
+                        self.excluded.entry(name.to_string()).or_default(),
+                        selected,
+                    ),
+                    Some(PreviewState::Failed(e)) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 80, 80), e);
+                    }
+                    _ => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Reading markets...");
+                        });
+                    }
+                }
+            });
+    }
+}
+
+/// Filterable market rows with a checkbox each
+fn markets_ui(
+    ui: &mut egui::Ui,
+    name: &str,
+    markets: &[PreviewMarket],
+    filter: &mut String,
+    excluded: &mut HashSet<String>,
+    selected: &mut bool,
+) {
+    if markets.is_empty() {
+        ui.label(RichText::new("No markets in this dataset").weak());
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Filter:");
+        ui.text_edit_singleline(filter);
+    });
+    let needle = filter.to_lowercase();
+    let shown: Vec<&PreviewMarket> = markets
+        .iter()
+        .filter(|m| needle.is_empty() || m.question.to_lowercase().contains(&needle))
+        .collect();
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        if ui.small_button("Check shown").clicked() {
+            for market in &shown {
+                excluded.remove(&market.key);
+            }
+            changed = true;
+        }
+        if ui.small_button("Uncheck shown").clicked() {
+            excluded.extend(shown.iter().map(|m| m.key.clone()));
+            changed = true;
+        }
+        ui.label(
+            RichText::new(format!(
+                "{} of {} markets shown",
+                shown.len(),
+                markets.len()
+            ))
+            .weak(),
+        );
+    });
+
+    let row_height = ui.spacing().interact_size.y;
+    egui::ScrollArea::vertical()
+        .id_salt(("dataset_preview_rows", name))
+        .max_height(240.0)
+        .show_rows(ui, row_height, shown.len(), |ui, rows| {
+            for market in &shown[rows] {
+                ui.horizontal(|ui| {
+                    let mut checked = !excluded.contains(&market.key);
+                    if ui.checkbox(&mut checked, "").changed() {
+                        if checked {
+                            excluded.remove(&market.key);
+                        } else {
+                            excluded.insert(market.key.clone());
+                        }
+                        changed = true;
+                    }
+                    ui.label(RichText::new(format_volume(market.volume)).monospace());
+                    ui.label(
+                        RichText::new(format!("{} tok", market.token_ids.len()))
+                            .monospace()
+                            .weak(),
+                    );
+                    ui.label(&market.question).on_hover_text(&market.key);
+                });
+            }
+        });
+
+    if changed {
+        *selected = markets.iter().any(|m| !excluded.contains(&m.key));
+    }
+}
+
+/// Every market in a dataset's `markets.json` and `markets_chunk_*.json` files
+pub fn read_dataset_markets(dataset_path: &Path) -> anyhow::Result<Vec<Value>> {
+    let mut markets = Vec::new();
+
+    let markets_file = dataset_path.join("markets.json");
+    if markets_file.exists() {
+        let contents = std::fs::read_to_string(&markets_file)?;
+        markets.extend(serde_json::from_str::<Vec<Value>>(&contents)?);
+    }
+
+    let mut chunks: Vec<PathBuf> = std::fs::read_dir(dataset_path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                            n.starts_with("markets_chunk_") && n.ends_with(".json")
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    chunks.sort();
+    for path in chunks {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                if let Ok(chunk) = serde_json::from_str::<Vec<Value>>(&contents) {
+                    markets.extend(chunk);
+                }
+            }
+            Err(e) => warn!("Failed to read chunk file {:?}: {}", path, e),
+        }
+    }
+
+    Ok(markets)
+}
+
+/// Token IDs of a market as stored in a dataset
+pub fn market_token_ids(market: &Value) -> Vec<String> {
+    market
+        .get("tokens")
+        .and_then(|v| v.as_array())
+        .map(|tokens| {
+            tokens
+                .iter()
+                .filter_map(|t| t.get("token_id").and_then(|v| v.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Preview rows for dataset markets, highest volume first
+pub fn preview_markets(markets: &[Value]) -> Vec<PreviewMarket> {
+    let text = |market: &Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| market.get(*key).and_then(|v| v.as_str()))
+            .map(String::from)
+    };
+    // Gamma data carries numbers as strings
+    let number = |market: &Value, keys: &[&str]| {
+        keys.iter().find_map(|key| match market.get(*key)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        })
+    };
+
+    let mut previews: Vec<PreviewMarket> = markets
+        .iter()
+        .enumerate()
+        .map(|(idx, market)| PreviewMarket {
+            key: text(market, &["condition_id", "conditionId", "id"])
+                .unwrap_or_else(|| format!("#{}", idx)),
+            question: text(market, &["question", "title"]).unwrap_or_default(),
+            volume: number(market, &["volume", "volumeNum", "volume_24hr"]),
+            token_ids: market_token_ids(market),
+        })
+        .collect();
+    previews.sort_by(|a, b| {
+        b.volume
+            .unwrap_or(0.0)
+            .partial_cmp(&a.volume.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    previews
+}
+
+/// Compact dollar volume, padded for monospace columns
+pub fn format_volume(volume: Option<f64>) -> String {
+    let format = number_format::number_format();
+    match volume {
+        Some(v) if v >= 1_000_000.0 => format!("${:>6}M", format.decimal(v / 1_000_000.0, 1)),
+        Some(v) if v >= 1_000.0 => format!("${:>6}K", format.decimal(v / 1_000.0, 1)),
+        Some(v) => format!("${:>7}", format.integer(v)),
+        None => format!("{:>8}", "-"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn previews_read_clob_and_gamma_markets() {
+        let markets = vec![
+            json!({
+                "condition_id": "0xa",
+                "question": "Small?",
+                "volume": 10.5,
+                "tokens": [{"token_id": "1"}, {"token_id": "2"}]
+            }),
+            json!({"conditionId": "0xb", "question": "Big?", "volumeNum": "2500000"}),
+            json!({"question": "Unkeyed?"}),
+        ];
+
+        let previews = preview_markets(&markets);
+        let keys: Vec<&str> = previews.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, ["0xb", "0xa", "#2"]);
+        assert_eq!(previews[0].volume, Some(2_500_000.0));
+        assert_eq!(previews[1].token_ids, ["1", "2"]);
+        assert!(format_volume(previews[0].volume).ends_with("2.5M"));
+    }
+}
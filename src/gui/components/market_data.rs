@@ -1,7 +1,11 @@
 //! Market data display components
 
+use crate::core::execution::book_view::{BookView, DepthPoint};
 use crate::core::types::market::PriceLevel;
+use crate::number_format;
+use crate::typed_store::{freshness, EnrichmentFreshness};
 use egui::{Response, Ui};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
 use rust_decimal::Decimal;
 use std::time::Instant;
 
@@ -29,6 +33,80 @@ pub fn streaming_status(ui: &mut Ui, connected: bool, asset_count: usize) -> Res
     .response
 }
 
+/// Age of a token's last enrichment, amber once it is stale
+pub fn enrichment_age_label(ui: &mut Ui, entry: Option<&EnrichmentFreshness>) -> Response {
+    let Some(entry) = entry else {
+        return ui
+            .colored_label(egui::Color32::GRAY, "not enriched")
+            .on_hover_text("Run `polybot enrich` on a dataset containing this token");
+    };
+    let now = chrono::Utc::now();
+    let age = freshness::format_age(now - entry.refreshed_at);
+    let hover = format!(
+        "Enriched {} into '{}'",
+        entry
+            .refreshed_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M"),
+        entry.dataset
+    );
+    if freshness::is_stale(Some(entry), freshness::DEFAULT_MAX_AGE, now) {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 170, 60),
+            format!("⚠ enriched {} ago", age),
+        )
+        .on_hover_text(format!(
+            "{}; refresh with `polybot enrich --stale-only`",
+            hover
+        ))
+    } else {
+        ui.colored_label(
+            egui::Color32::from_rgb(100, 200, 100),
+            format!("enriched {} ago", age),
+        )
+        .on_hover_text(hover)
+    }
+}
+
+/// Cumulative depth of both sides as stepped areas, built from the same view as the ladder
+pub fn depth_chart(ui: &mut Ui, id_salt: impl std::hash::Hash, view: &BookView) -> Response {
+    let palette = crate::theme::palette();
+    let points = |steps: Vec<DepthPoint>| {
+        let points: Vec<[f64; 2]> = steps
+            .into_iter()
+            .map(|point| [point.price, point.cumulative_size])
+            .collect();
+        PlotPoints::from(points)
+    };
+
+    ui.vertical(|ui| {
+        ui.heading("📈 Depth Chart");
+        if view.bids.is_empty() && view.asks.is_empty() {
+            ui.label("Book is empty");
+            return;
+        }
+        Plot::new(("depth_chart", id_salt))
+            .height(260.0)
+            .legend(Legend::default())
+            .allow_scroll(false)
+            .x_axis_label("Price")
+            .y_axis_label("Cumulative size")
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new("Bids", points(view.bid_steps()))
+                        .color(egui::Color32::from(palette.bid))
+                        .fill(0.0),
+                );
+                plot_ui.line(
+                    Line::new("Asks", points(view.ask_steps()))
+                        .color(egui::Color32::from(palette.ask))
+                        .fill(0.0),
+                );
+            });
+    })
+    .response
+}
+
 /// Enhanced order book display with change flash animations
 pub fn order_book_display_enhanced(
     ui: &mut Ui,
@@ -36,6 +114,10 @@ pub fn order_book_display_enhanced(
     asks: &[PriceLevel],
     changes: &[(Decimal, Decimal, Instant, bool)], // (price, size, changed_at, is_bid)
 ) -> Response {
+    let palette = crate::theme::palette();
+    let (bid_r, bid_g, bid_b) = palette.bid.to_rgb();
+    let (ask_r, ask_g, ask_b) = palette.ask.to_rgb();
+
     ui.vertical(|ui| {
         ui.heading("📊 Order Book");
 
@@ -56,7 +138,11 @@ pub fn order_book_display_enhanced(
                 ui.label("Spread:");
                 ui.colored_label(
                     egui::Color32::from_rgb(150, 150, 150),
-                    format!("${:.4} ({:.2}%)", spread_val, spread_pct),
+                    format!(
+                        "${} ({})",
+                        number_format::price(spread_val),
+                        number_format::percent(spread_pct)
+                    ),
                 );
             });
         }
@@ -93,7 +179,7 @@ pub fn order_book_display_enhanced(
                         });
 
                         // Size label with flash effect
-                        let size_response = ui.label(format!("{:.2}", bid.size));
+                        let size_response = ui.label(number_format::size(bid.size));
                         if is_changed {
                             // Calculate flash intensity based on how recent the change is
                             let age_ms = changes
@@ -107,9 +193,9 @@ pub fn order_book_display_enhanced(
 
                             let flash_intensity = 1.0 - (age_ms as f32 / 1000.0);
                             let flash_color = egui::Color32::from_rgba_unmultiplied(
-                                100,
-                                255,
-                                100,
+                                bid_r,
+                                bid_g,
+                                bid_b,
                                 (flash_intensity * 60.0) as u8,
                             );
 
@@ -123,8 +209,8 @@ pub fn order_book_display_enhanced(
 
                         // Price label with flash effect
                         let price_response = ui.colored_label(
-                            egui::Color32::from_rgb(100, 200, 100),
-                            format!("${:.4}", bid.price),
+                            egui::Color32::from(palette.bid),
+                            format!("${}", number_format::price(bid.price)),
                         );
                         if is_changed {
                             let age_ms = changes
@@ -138,9 +224,9 @@ pub fn order_book_display_enhanced(
 
                             let flash_intensity = 1.0 - (age_ms as f32 / 1000.0);
                             let flash_color = egui::Color32::from_rgba_unmultiplied(
-                                100,
-                                255,
-                                100,
+                                bid_r,
+                                bid_g,
+                                bid_b,
                                 (flash_intensity * 60.0) as u8,
                             );
 
@@ -169,8 +255,8 @@ pub fn order_book_display_enhanced(
 
                         // Price label with flash effect
                         let price_response = ui.colored_label(
-                            egui::Color32::from_rgb(200, 100, 100),
-                            format!("${:.4}", ask.price),
+                            egui::Color32::from(palette.ask),
+                            format!("${}", number_format::price(ask.price)),
                         );
                         if is_changed {
                             let age_ms = changes
@@ -184,9 +270,9 @@ pub fn order_book_display_enhanced(
 
                             let flash_intensity = 1.0 - (age_ms as f32 / 1000.0);
                             let flash_color = egui::Color32::from_rgba_unmultiplied(
-                                255,
-                                100,
-                                100,
+                                ask_r,
+                                ask_g,
+                                ask_b,
                                 (flash_intensity * 60.0) as u8,
                             );
 
@@ -199,7 +285,7 @@ pub fn order_book_display_enhanced(
                         }
 
                         // Size label with flash effect
-                        let size_response = ui.label(format!("{:.2}", ask.size));
+                        let size_response = ui.label(number_format::size(ask.size));
                         if is_changed {
                             let age_ms = changes
                                 .iter()
@@ -212,9 +298,9 @@ pub fn order_book_display_enhanced(
 
                             let flash_intensity = 1.0 - (age_ms as f32 / 1000.0);
                             let flash_color = egui::Color32::from_rgba_unmultiplied(
-                                255,
-                                100,
-                                100,
+                                ask_r,
+                                ask_g,
+                                ask_b,
                                 (flash_intensity * 60.0) as u8,
                             );
 
@@ -1,5 +1,14 @@
 //! Reusable UI components for the trading interface
 
+pub mod dataset_preview;
 pub mod market_data;
 pub mod orders;
+pub mod pnl_chart;
 pub mod portfolio;
+pub mod search_dataset;
+pub mod search_palette;
+pub mod shortcuts;
+pub mod snapshot_diff;
+pub mod strategies;
+pub mod token_notes;
+pub mod token_sidebar;
@@ -1 +1,582 @@
 //! Order management components
+
+use egui::{Color32, Context, RichText};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::mpsc;
+use tracing::{error, info};
+
+use crate::core::execution::orders::OrderManager;
+use crate::core::risk::{LargeOrder, RiskConfig, RiskJournal, RiskJournalEvent};
+use crate::core::types::common::{ShortId, Side};
+use crate::data_paths::DataPaths;
+use crate::strategy::brackets::{BracketExits, BracketStore, NewBracket};
+
+/// Validated order ready to submit
+#[derive(Debug, Clone)]
+struct OrderRequest {
+    token_id: String,
+    side: Side,
+    price: Decimal,
+    size: Decimal,
+    /// Stop and target prices when the entry opens a bracket
+    bracket: Option<BracketExits>,
+}
+
+/// Order above the large order threshold, waiting for the confirmation modal
+#[derive(Debug, Clone)]
+struct PendingLargeOrder {
+    request: OrderRequest,
+    large: LargeOrder,
+}
+
+/// New order dialog with a second confirmation step for large orders
+pub struct OrderTicket {
+    pub open: bool,
+    token_id: String,
+    side: Side,
+    price: String,
+    size: String,
+    /// Arm a stop and a target once the (buy) entry fills
+    bracket: bool,
+    stop: String,
+    target: String,
+    error: Option<String>,
+    pending_large: Option<PendingLargeOrder>,
+    result_rx: Option<mpsc::Receiver<Result<String, String>>>,
+    last_result: Option<Result<String, String>>,
+}
+
+impl Default for OrderTicket {
+    fn default() -> Self {
+        Self {
+            open: false,
+            token_id: String::new(),
+            side: Side::Buy,
+            price: String::new(),
+            size: String::new(),
+            bracket: false,
+            stop: String::new(),
+            target: String::new(),
+            error: None,
+            pending_large: None,
+            result_rx: None,
+            last_result: None,
+        }
+    }
+}
+
+impl OrderTicket {
+    /// Open the ticket, prefilling the token when one is selected
+    pub fn open_for(&mut self, token_id: Option<&str>) {
+        if let Some(token_id) = token_id {
+            self.token_id = token_id.to_string();
+        }
+        self.error = None;
+        self.last_result = None;
+        self.open = true;
+    }
+
+    /// Draw the ticket and, when needed, the large order confirmation modal;
+    /// orders go through the app's `order_manager`
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        host: &str,
+        data_paths: &DataPaths,
+        risk_config: &RiskConfig,
+        order_manager: &OrderManager,
+    ) {
+        self.poll_result();
+
+        if self.open {
+            let mut open = self.open;
+            egui::Window::new("🛒 New Order")
+                .default_width(400.0)
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    self.form_ui(ui, host, data_paths, risk_config, order_manager);
+                });
+            self.open = open && self.open;
+        }
+
+        self.confirmation_modal(ctx, host, data_paths, order_manager);
+    }
+
+    fn form_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        host: &str,
+        data_paths: &DataPaths,
+        risk_config: &RiskConfig,
+        order_manager: &OrderManager,
+    ) {
+        let submitting = self.result_rx.is_some();
+
+        egui::Grid::new("order_ticket_grid")
+            .num_columns(2)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                ui.label("Token ID");
+                ui.text_edit_singleline(&mut self.token_id);
+                ui.end_row();
+
+                ui.label("Side");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.side, Side::Buy, "Buy");
+                    ui.selectable_value(&mut self.side, Side::Sell, "Sell");
+                });
+                ui.end_row();
+
+                ui.label("Price");
+                ui.text_edit_singleline(&mut self.price);
+                ui.end_row();
+
+                ui.label("Size (shares)");
+                ui.text_edit_singleline(&mut self.size);
+                ui.end_row();
+
+                if self.side == Side::Buy {
+                    ui.label("Bracket");
+                    ui.checkbox(&mut self.bracket, "Stop and target once filled");
+                    ui.end_row();
+
+                    if self.bracket {
+                        ui.label("Stop price");
+                        ui.text_edit_singleline(&mut self.stop);
+                        ui.end_row();
+
+                        ui.label("Target price");
+                        ui.text_edit_singleline(&mut self.target);
+                        ui.end_row();
+                    }
+                }
+            });
+
+        if self.side == Side::Buy && self.bracket {
+            ui.label(
+                RichText::new("Exits are armed by the daemon or 'polybot bracket run'")
+                    .small()
+                    .weak(),
+            );
+        }
+
+        if let Ok(request) = self.validate() {
+            let notional = request.price * request.size;
+            let mut text = RichText::new(format!("Notional: ${}", notional.round_dp(2)));
+            if risk_config.large_orders.enabled && notional > risk_config.large_orders.notional_threshold {
+                text = text.color(Color32::from_rgb(255, 165, 0));
+            }
+            ui.label(text);
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::from_rgb(220, 80, 80), error);
+        }
+        match &self.last_result {
+            Some(Ok(order_id)) => {
+                ui.colored_label(Color32::from_rgb(100, 200, 100), format!("✅ Order placed: {}", order_id));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(Color32::from_rgb(220, 80, 80), format!("❌ {}", e));
+            }
+            None => {}
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.button("Cancel").clicked() {
+                self.open = false;
+            }
+
+            let place = ui.add_enabled(!submitting, egui::Button::new("Place Order"));
+            if submitting {
+                ui.spinner();
+            }
+            if place.clicked() {
+                match self.validate() {
+                    Ok(request) => {
+                        self.error = None;
+                        match risk_config.large_orders.check(
+                            &request.token_id,
+                            request.side,
+                            request.price,
+                            request.size,
+                        ) {
+                            Some(large) => {
+                                self.pending_large = Some(PendingLargeOrder { request, large })
+                            }
+                            None => self.submit(request, host, data_paths, order_manager),
+                        }
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+        });
+    }
+
+    fn confirmation_modal(
+        &mut self,
+        ctx: &Context,
+        host: &str,
+        data_paths: &DataPaths,
+        order_manager: &OrderManager,
+    ) {
+        let Some(pending) = &self.pending_large else {
+            return;
+        };
+        let large = pending.large.clone();
+        let mut decision = None;
+
+        egui::Modal::new(egui::Id::new("large_order_confirmation")).show(ctx, |ui| {
+            ui.set_width(380.0);
+            ui.heading("⚠️ Confirm Large Order");
+            ui.separator();
+            ui.label(format!(
+                "{} {} @ ${} on {}",
+                large.side,
+                large.size,
+                large.price,
+                ShortId(&large.token_id)
+            ));
+            ui.label(
+                RichText::new(format!("Notional: ${}", large.notional.round_dp(2)))
+                    .strong()
+                    .color(Color32::from_rgb(255, 165, 0)),
+            );
+            ui.label(format!(
+                "This exceeds the large order threshold of ${}.",
+                large.threshold.round_dp(2)
+            ));
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Go back").clicked() {
+                    decision = Some(false);
+                }
+                if ui
+                    .button(RichText::new("Confirm and place").color(Color32::from_rgb(255, 165, 0)))
+                    .clicked()
+                {
+                    decision = Some(true);
+                }
+            });
+        });
+
+        if let Some(confirmed) = decision {
+            let journal = RiskJournal::new(data_paths);
+            let PendingLargeOrder { request, large } =
+                self.pending_large.take().expect("pending large order");
+            if confirmed {
+                journal.record(&large.journal_entry(RiskJournalEvent::LargeOrderConfirmed, "gui"));
+                self.submit(request, host, data_paths, order_manager);
+            } else {
+                journal.record(&large.journal_entry(RiskJournalEvent::LargeOrderDeclined, "gui"));
+                self.error = Some("Large order not confirmed".to_string());
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<OrderRequest, String> {
+        let token_id = self.token_id.trim();
+        if token_id.is_empty() {
+            return Err("Token ID is required".to_string());
+        }
+        let price = Decimal::from_str(self.price.trim()).map_err(|_| "Invalid price".to_string())?;
+        if price <= Decimal::ZERO || price >= Decimal::ONE {
+            return Err("Price must be between 0 and 1".to_string());
+        }
+        let size = Decimal::from_str(self.size.trim()).map_err(|_| "Invalid size".to_string())?;
+        if size <= Decimal::ZERO {
+            return Err("Size must be positive".to_string());
+        }
+        let bracket = if self.side == Side::Buy && self.bracket {
+            let stop = Decimal::from_str(self.stop.trim()).map_err(|_| "Invalid stop price".to_string())?;
+            let target = Decimal::from_str(self.target.trim()).map_err(|_| "Invalid target price".to_string())?;
+            let exits = BracketExits { stop, target };
+            exits.validate(price).map_err(|e| e.to_string())?;
+            Some(exits)
+        } else {
+            None
+        };
+        Ok(OrderRequest {
+            token_id: token_id.to_string(),
+            side: self.side,
+            price,
+            size,
+            bracket,
+        })
+    }
+
+    fn submit(
+        &mut self,
+        request: OrderRequest,
+        host: &str,
+        data_paths: &DataPaths,
+        order_manager: &OrderManager,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        self.result_rx = Some(rx);
+        self.last_result = None;
+
+        let host = host.to_string();
+        let data_paths = data_paths.clone();
+        let order_manager = order_manager.clone();
+        tokio::spawn(async move {
+            let result = place_order(&host, &data_paths, &order_manager, &request).await;
+            match &result {
+                Ok(order_id) => info!("GUI order placed: {}", order_id),
+                Err(e) => error!("GUI order failed: {}", e),
+            }
+            let _ = tx.send(result);
+        });
+    }
+
+    fn poll_result(&mut self) {
+        if let Some(rx) = &self.result_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.last_result = Some(result);
+                    self.result_rx = None;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.last_result = Some(Err("Order task ended unexpectedly".to_string()));
+                    self.result_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+    }
+}
+
+async fn place_order(
+    host: &str,
+    data_paths: &DataPaths,
+    order_manager: &OrderManager,
+    request: &OrderRequest,
+) -> Result<String, String> {
+    let mut client = crate::auth::get_authenticated_client(host, data_paths)
+        .await
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+    let order_signer = crate::auth::order_signer(host, data_paths)
+        .await
+        .map_err(|e| format!("Signer unavailable: {}", e))?;
+    // Clones share the throttle and tracked orders; only the signer is per order
+    let manager = order_manager.clone().with_order_signer(order_signer);
+    let response = match request.side {
+        Side::Buy => manager
+            .place_buy_order(&mut client, &request.token_id, request.price, request.size)
+            .await,
+        Side::Sell => manager
+            .place_sell_order(&mut client, &request.token_id, request.price, request.size)
+            .await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    if !response.success {
+        return Err(response
+            .error_message
+            .unwrap_or_else(|| "Order rejected".to_string()));
+    }
+    let order_id = response.order_id.unwrap_or_else(|| "unknown".to_string());
+    let Some(exits) = request.bracket else {
+        return Ok(order_id);
+    };
+    let bracket = BracketStore::new(data_paths)
+        .add(NewBracket {
+            token_id: request.token_id.clone(),
+            market_id: None,
+            entry_order_id: order_id.clone(),
+            entry_price: request.price,
+            size: request.size,
+            exits,
+        })
+        .map_err(|e| format!("Entry {} placed but its bracket was not saved: {}", order_id, e))?;
+    Ok(format!("{} (bracket {})", order_id, bracket.id))
+}
+
+/// Confirmation and progress of cancelling every open order
+#[derive(Default)]
+pub struct CancelAllDialog {
+    /// Waiting for the user to confirm
+    confirming: bool,
+    /// Open orders the orders pane showed when the dialog opened
+    open_orders: usize,
+    result_rx: Option<mpsc::Receiver<Result<CancelAllSummary, String>>>,
+    last_result: Option<Result<CancelAllSummary, String>>,
+}
+
+/// Outcome of a cancel-all
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CancelAllSummary {
+    pub cancelled: usize,
+    pub failed: usize,
+}
+
+impl CancelAllDialog {
+    /// Ask for confirmation of cancelling what the orders pane counts as
+    /// `open_orders`; does nothing while a cancel-all runs
+    pub fn open(&mut self, open_orders: usize) {
+        if self.result_rx.is_none() {
+            self.last_result = None;
+            self.open_orders = open_orders;
+            self.confirming = true;
+        }
+    }
+
+    /// Draw the confirmation modal, then the outcome
+    pub fn show(
+        &mut self,
+        ctx: &Context,
+        host: &str,
+        data_paths: &DataPaths,
+        order_manager: &OrderManager,
+    ) {
+        self.poll_result();
+
+        if self.confirming {
+            let mut decision = None;
+            egui::Modal::new(egui::Id::new("cancel_all_confirmation")).show(ctx, |ui| {
+                ui.set_width(360.0);
+                ui.heading("🚫 Cancel All Orders");
+                ui.separator();
+                ui.label(format!(
+                    "Cancel every open order of this account ({} shown in the orders pane)?",
+                    self.open_orders
+                ));
+                ui.label(RichText::new("Orders are fetched again before cancelling.").small().weak());
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Go back").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        decision = Some(false);
+                    }
+                    if ui
+                        .button(RichText::new("Cancel all").color(Color32::from_rgb(220, 80, 80)))
+                        .clicked()
+                    {
+                        decision = Some(true);
+                    }
+                });
+            });
+            if let Some(confirmed) = decision {
+                self.confirming = false;
+                if confirmed {
+                    self.submit(host, data_paths, order_manager);
+                }
+            }
+        }
+
+        let running = self.result_rx.is_some();
+        if !running && self.last_result.is_none() {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("🚫 Cancel All")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| match &self.last_result {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Cancelling open orders...");
+                    });
+                }
+                Some(Ok(summary)) if summary.failed == 0 => {
+                    ui.colored_label(
+                        Color32::from_rgb(100, 200, 100),
+                        format!("✅ Cancelled {} orders", summary.cancelled),
+                    );
+                }
+                Some(Ok(summary)) => {
+                    ui.colored_label(
+                        Color32::from_rgb(255, 165, 0),
+                        format!(
+                            "⚠️ Cancelled {} orders, {} failed (see the log)",
+                            summary.cancelled, summary.failed
+                        ),
+                    );
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::from_rgb(220, 80, 80), format!("❌ {}", e));
+                }
+            });
+        if !open && !running {
+            self.last_result = None;
+        }
+    }
+
+    fn submit(&mut self, host: &str, data_paths: &DataPaths, order_manager: &OrderManager) {
+        let (tx, rx) = mpsc::channel();
+        self.result_rx = Some(rx);
+        self.last_result = None;
+
+        let host = host.to_string();
+        let data_paths = data_paths.clone();
+        let manager = order_manager.clone();
+        tokio::spawn(async move {
+            let result = cancel_all_orders(&host, data_paths, &manager).await;
+            match &result {
+                Ok(summary) => info!(
+                    "GUI cancel-all: {} cancelled, {} failed",
+                    summary.cancelled, summary.failed
+                ),
+                Err(e) => error!("GUI cancel-all failed: {}", e),
+            }
+            let _ = tx.send(result);
+        });
+    }
+
+    fn poll_result(&mut self) {
+        if let Some(rx) = &self.result_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.last_result = Some(result);
+                    self.result_rx = None;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.last_result = Some(Err("Cancel task ended unexpectedly".to_string()));
+                    self.result_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+    }
+}
+
+async fn cancel_all_orders(
+    host: &str,
+    data_paths: DataPaths,
+    manager: &OrderManager,
+) -> Result<CancelAllSummary, String> {
+    let mut client = crate::auth::get_authenticated_client(host, &data_paths)
+        .await
+        .map_err(|e| format!("Authentication failed: {}", e))?;
+    let address = crate::auth::account_address(&data_paths)
+        .await
+        .map_err(|e| format!("Account address unavailable: {}", e))?;
+    let orders = manager
+        .fetch_orders(host, &data_paths, &address)
+        .await
+        .map_err(|e| format!("Failed to fetch open orders: {}", e))?;
+
+    let mut summary = CancelAllSummary::default();
+    for order in orders {
+        match manager.cancel_order(&mut client, &order.id).await {
+            Ok(response) if response.success => summary.cancelled += 1,
+            Ok(response) => {
+                error!(
+                    "Order {} not cancelled: {}",
+                    order.id,
+                    response.error_message.unwrap_or_default()
+                );
+                summary.failed += 1;
+            }
+            Err(e) => {
+                error!("Order {} not cancelled: {}", order.id, e);
+                summary.failed += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
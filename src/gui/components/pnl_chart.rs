@@ -0,0 +1,290 @@
+//! Portfolio value chart
+//!
+//! Plots the account's equity from its P&L history over a selectable range,
+//! shades drawdowns below the running peak, and overlays a line per hosted
+//! strategy: the equity the account would have had if only that strategy's
+//! token had moved. The history is re-read from disk every minute while shown.
+
+use chrono::{DateTime, TimeZone, Utc};
+use egui::{Color32, RichText, Stroke};
+use egui_plot::{GridMark, Legend, Line, Plot, PlotPoints, Polygon};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::core::portfolio::pnl_history::{Drawdown, PnlHistory, PnlPoint, PnlRange};
+use crate::data_paths::DataPaths;
+use crate::number_format;
+use crate::strategy::control::StrategyStatus;
+
+/// How often the history is re-read while the chart is shown
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// When and for which account and range the shown points were loaded
+struct LoadedHistory {
+    at: Instant,
+    address: String,
+    range: PnlRange,
+}
+
+/// The equity line of the strategies trading one token
+struct StrategyLine {
+    /// Ids of the strategies
+    name: String,
+    points: Vec<[f64; 2]>,
+}
+
+/// Strategies grouped by the token they trade
+struct TokenStrategies<'a> {
+    token_id: &'a str,
+    ids: Vec<&'a str>,
+}
+
+pub struct PnlChart {
+    range: PnlRange,
+    show_drawdown: bool,
+    show_strategies: bool,
+    points: Vec<PnlPoint>,
+    loaded: Option<LoadedHistory>,
+    error: Option<String>,
+}
+
+impl Default for PnlChart {
+    fn default() -> Self {
+        Self {
+            range: PnlRange::Week,
+            show_drawdown: true,
+            show_strategies: true,
+            points: Vec::new(),
+            loaded: None,
+            error: None,
+        }
+    }
+}
+
+impl PnlChart {
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data_paths: &DataPaths,
+        address: Option<&str>,
+        strategies: &[StrategyStatus],
+    ) {
+        let Some(address) = address else {
+            ui.label("Connect a wallet to record portfolio value.");
+            return;
+        };
+        self.reload_if_due(data_paths, address);
+        ui.ctx().request_repaint_after(RELOAD_INTERVAL);
+
+        ui.horizontal(|ui| {
+            for range in PnlRange::ALL {
+                ui.selectable_value(&mut self.range, range, range.label());
+            }
+            ui.separator();
+            ui.checkbox(&mut self.show_drawdown, "Drawdowns");
+            ui.checkbox(&mut self.show_strategies, "Strategies");
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::from_rgb(220, 80, 80), error);
+        }
+
+        let palette = crate::theme::palette();
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            ui.label(
+                "No portfolio value recorded for this range yet. A point is added on each \
+                 portfolio refresh, at most every 5 minutes.",
+            );
+            return;
+        };
+
+        let start = first.equity;
+        let change = last.equity - start;
+        let change_color = if change >= Decimal::ZERO {
+            Color32::from(palette.profit)
+        } else {
+            Color32::from(palette.loss)
+        };
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(number_format::usd(last.equity)).strong());
+            let percent = if !start.is_zero() {
+                format!(
+                    " ({})",
+                    number_format::signed_percent(change / start * Decimal::ONE_HUNDRED)
+                )
+            } else {
+                String::new()
+            };
+            ui.colored_label(
+                change_color,
+                format!(
+                    "{}{} over {}",
+                    number_format::signed_usd(change),
+                    percent,
+                    self.range.label()
+                ),
+            );
+            if let Some(max) = Drawdown::max(&self.points) {
+                ui.separator();
+                ui.colored_label(
+                    Color32::from(palette.muted),
+                    format!(
+                        "Max drawdown {} ({})",
+                        number_format::usd(max.dollars),
+                        number_format::percent(max.percent)
+                    ),
+                );
+            }
+        });
+
+        let xs: Vec<f64> = self
+            .points
+            .iter()
+            .map(|p| p.at.timestamp() as f64)
+            .collect();
+        let equity: Vec<f64> = self
+            .points
+            .iter()
+            .map(|p| p.equity.to_f64().unwrap_or_default())
+            .collect();
+        let drawdowns: Vec<f64> = Drawdown::series(&self.points)
+            .iter()
+            .map(|d| d.dollars.to_f64().unwrap_or_default())
+            .collect();
+        let overlays = if self.show_strategies {
+            StrategyLine::for_strategies(&self.points, strategies, start)
+        } else {
+            Vec::new()
+        };
+
+        Plot::new("portfolio_value_chart")
+            .legend(Legend::default())
+            .allow_scroll(false)
+            .y_axis_label("Equity ($)")
+            .x_axis_formatter(|mark: GridMark, _range: &RangeInclusive<f64>| {
+                format_timestamp(mark.value)
+            })
+            .label_formatter(|name, value| {
+                let when = format_timestamp(value.x);
+                if name.is_empty() {
+                    format!("{}\n{}", when, number_format::usd(value.y))
+                } else {
+                    format!("{}\n{}\n{}", name, when, number_format::usd(value.y))
+                }
+            })
+            .show(ui, |plot_ui| {
+                if self.show_drawdown {
+                    let fill = Color32::from(palette.loss).gamma_multiply(0.25);
+                    // One quad per segment below the peak, since polygons are
+                    // filled as convex shapes
+                    for i in 1..xs.len() {
+                        if drawdowns[i - 1] == 0.0 && drawdowns[i] == 0.0 {
+                            continue;
+                        }
+                        let quad = vec![
+                            [xs[i - 1], equity[i - 1] - drawdowns[i - 1]],
+                            [xs[i], equity[i] - drawdowns[i]],
+                            [xs[i], equity[i]],
+                            [xs[i - 1], equity[i - 1]],
+                        ];
+                        plot_ui.polygon(
+                            Polygon::new("Drawdown", PlotPoints::from(quad))
+                                .fill_color(fill)
+                                .stroke(Stroke::NONE),
+                        );
+                    }
+                }
+                let line: Vec<[f64; 2]> = xs.iter().zip(&equity).map(|(&x, &y)| [x, y]).collect();
+                plot_ui.line(
+                    Line::new("Equity", PlotPoints::from(line))
+                        .color(Color32::from(palette.highlight))
+                        .width(2.0),
+                );
+                for overlay in overlays {
+                    plot_ui.line(Line::new(overlay.name, PlotPoints::from(overlay.points)));
+                }
+            });
+
+        if self.show_strategies && strategies.is_empty() {
+            ui.label(
+                RichText::new("Open the Strategies pane to overlay the daemon's strategies")
+                    .small()
+                    .color(Color32::from(palette.muted)),
+            );
+        }
+    }
+
+    fn reload_if_due(&mut self, data_paths: &DataPaths, address: &str) {
+        let fresh = self.loaded.as_ref().is_some_and(|loaded| {
+            loaded.address == address
+                && loaded.range == self.range
+                && loaded.at.elapsed() < RELOAD_INTERVAL
+        });
+        if fresh {
+            return;
+        }
+        let history = PnlHistory::new(data_paths, address);
+        match history.load(self.range.since(Utc::now())) {
+            Ok(points) => {
+                self.points = points;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to load P&L history: {}", e)),
+        }
+        self.loaded = Some(LoadedHistory {
+            at: Instant::now(),
+            address: address.to_string(),
+            range: self.range,
+        });
+    }
+}
+
+impl StrategyLine {
+    /// Starting equity plus the change in each strategy's token P&L, one line
+    /// per token named after the strategies trading it
+    fn for_strategies(
+        points: &[PnlPoint],
+        strategies: &[StrategyStatus],
+        start_equity: Decimal,
+    ) -> Vec<Self> {
+        let mut by_token: Vec<TokenStrategies> = Vec::new();
+        for strategy in strategies {
+            match by_token.iter_mut().find(|t| t.token_id == strategy.token_id) {
+                Some(token) => token.ids.push(&strategy.id),
+                None => by_token.push(TokenStrategies {
+                    token_id: &strategy.token_id,
+                    ids: vec![&strategy.id],
+                }),
+            }
+        }
+
+        by_token
+            .into_iter()
+            .filter_map(|token| {
+                let base = points.iter().find_map(|p| p.tokens.get(token.token_id))?;
+                let line = points
+                    .iter()
+                    .filter_map(|p| {
+                        let equity = start_equity + p.tokens.get(token.token_id)? - base;
+                        Some([p.at.timestamp() as f64, equity.to_f64()?])
+                    })
+                    .collect();
+                Some(Self {
+                    name: token.ids.join(", "),
+                    points: line,
+                })
+            })
+            .collect()
+    }
+}
+
+fn format_timestamp(secs: f64) -> String {
+    let at: Option<DateTime<Utc>> = Utc.timestamp_opt(secs as i64, 0).single();
+    at.map(|at| {
+        at.with_timezone(&chrono::Local)
+            .format("%m-%d %H:%M")
+            .to_string()
+    })
+    .unwrap_or_default()
+}
@@ -0,0 +1,208 @@
+//! Dataset selector form that creates a dataset from a market search
+//!
+//! Runs a query against the local Gamma store on a blocking task and writes the
+//! matches as a token-selection dataset (`markets::datasets::from_search`), the
+//! same as `polybot datasets create-from-search`.
+
+use egui::{Color32, RichText};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::mpsc;
+
+use crate::data_paths::DataPaths;
+use crate::markets::datasets::from_search::{self, MarketSearchQuery, SearchDataset};
+
+pub struct SearchDatasetForm {
+    keyword: String,
+    category: String,
+    min_volume: String,
+    min_liquidity: String,
+    limit: usize,
+    /// Dataset name; empty uses the query's default name
+    name: String,
+    overwrite: bool,
+    pending: Option<mpsc::Receiver<Result<SearchDataset, String>>>,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for SearchDatasetForm {
+    fn default() -> Self {
+        Self {
+            keyword: String::new(),
+            category: String::new(),
+            min_volume: String::new(),
+            min_liquidity: String::new(),
+            limit: 50,
+            name: String::new(),
+            overwrite: false,
+            pending: None,
+            status: None,
+        }
+    }
+}
+
+impl SearchDatasetForm {
+    /// Draw the form; returns the name of a dataset once it has been written
+    pub fn ui(&mut self, ui: &mut egui::Ui, data_paths: &DataPaths) -> Option<String> {
+        let created = self.poll();
+        if self.pending.is_some() {
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        egui::CollapsingHeader::new("🔍 Create dataset from search")
+            .id_salt("search_dataset_form")
+            .show(ui, |ui| {
+                egui::Grid::new("search_dataset_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Search");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.keyword)
+                                .hint_text("e.g. election")
+                                .desired_width(220.0),
+                        );
+                        ui.end_row();
+                        ui.label("Category");
+                        ui.add(egui::TextEdit::singleline(&mut self.category).desired_width(220.0));
+                        ui.end_row();
+                        ui.label("Min volume ($)");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.min_volume).desired_width(100.0),
+                        );
+                        ui.end_row();
+                        ui.label("Min liquidity ($)");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.min_liquidity)
+                                .desired_width(100.0),
+                        );
+                        ui.end_row();
+                        ui.label("Max markets");
+                        ui.add(egui::DragValue::new(&mut self.limit).range(1..=1000));
+                        ui.end_row();
+                        ui.label("Dataset name");
+                        let hint = self.query().map(|q| q.default_name()).unwrap_or_default();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.name)
+                                .hint_text(hint)
+                                .desired_width(220.0),
+                        );
+                        ui.end_row();
+                    });
+
+                ui.horizontal(|ui| {
+                    let busy = self.pending.is_some();
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Create dataset"))
+                        .clicked()
+                    {
+                        self.start(data_paths);
+                    }
+                    ui.checkbox(&mut self.overwrite, "Replace existing");
+                    if busy {
+                        ui.spinner();
+                        ui.label("Searching...");
+                    }
+                });
+                match &self.status {
+                    Some(Ok(message)) => {
+                        ui.colored_label(Color32::from(crate::theme::palette().profit), message);
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(Color32::from_rgb(220, 80, 80), error);
+                    }
+                    None => {
+                        ui.label(
+                            RichText::new(
+                                "Searches the local Gamma data from `polybot gamma sync`; \
+                                 only open markets are kept, highest volume first.",
+                            )
+                            .small()
+                            .weak(),
+                        );
+                    }
+                }
+            });
+        created
+    }
+
+    fn query(&self) -> Result<MarketSearchQuery, String> {
+        let text = |raw: &str| Some(raw.trim().to_string()).filter(|s| !s.is_empty());
+        let query = MarketSearchQuery {
+            keyword: text(&self.keyword),
+            tags: Vec::new(),
+            category: text(&self.category),
+            min_volume: parse_amount(&self.min_volume, "Min volume")?,
+            min_liquidity: parse_amount(&self.min_liquidity, "Min liquidity")?,
+            limit: self.limit,
+        };
+        if query.is_empty() {
+            return Err("Enter a search or at least one filter".to_string());
+        }
+        Ok(query)
+    }
+
+    fn start(&mut self, data_paths: &DataPaths) {
+        let query = match self.query() {
+            Ok(query) => query,
+            Err(e) => {
+                self.status = Some(Err(e));
+                return;
+            }
+        };
+        let name = match self.name.trim() {
+            "" => query.default_name(),
+            name => name.to_string(),
+        };
+        let data_paths = data_paths.clone();
+        let overwrite = self.overwrite;
+        let (tx, rx) = mpsc::channel();
+        self.pending = Some(rx);
+        self.status = None;
+
+        tokio::task::spawn_blocking(move || {
+            let result = from_search::create_from_search(&data_paths, &name, &query, overwrite)
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    fn poll(&mut self) -> Option<String> {
+        let result = match self.pending.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => Err("Search task stopped".to_string()),
+        };
+        self.pending = None;
+        match result {
+            Ok(dataset) => {
+                let mut status = format!(
+                    "Created {} with {} markets ({} tokens)",
+                    dataset.name, dataset.markets, dataset.tokens
+                );
+                if dataset.suppressed > 0 {
+                    status.push_str(&format!(", {} suppressed", dataset.suppressed));
+                }
+                self.status = Some(Ok(status));
+                self.name.clear();
+                Some(dataset.name)
+            }
+            Err(e) => {
+                self.status = Some(Err(e));
+                None
+            }
+        }
+    }
+}
+
+/// An optional non-negative dollar amount
+fn parse_amount(raw: &str, label: &str) -> Result<Option<Decimal>, String> {
+    let raw = raw.trim().trim_start_matches('$').replace(',', "");
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    match Decimal::from_str(&raw) {
+        Ok(amount) if amount >= Decimal::ZERO => Ok(Some(amount)),
+        _ => Err(format!("{}: '{}' is not an amount", label, raw)),
+    }
+}
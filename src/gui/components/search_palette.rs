@@ -0,0 +1,172 @@
+//! Search palette: run a shortcut action or open a pane by typing its name
+//!
+//! Items are matched case-insensitively, as a substring first and otherwise
+//! as letters in order ("ordtk" finds "Open order ticket"). Up/Down move the
+//! selection, Enter runs it and Esc closes the palette.
+
+use egui::{Context, RichText};
+
+use crate::gui::panes::Pane;
+use crate::keybindings::Action;
+
+/// What an item does when picked
+#[derive(Debug, Clone)]
+pub enum PaletteCommand {
+    Action(Action),
+    AddPane(Pane),
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteItem {
+    pub label: String,
+    /// Key shown next to the label
+    pub shortcut: Option<String>,
+    pub command: PaletteCommand,
+}
+
+#[derive(Default)]
+pub struct SearchPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+    /// Focus the query field on the first frame after opening
+    focus_query: bool,
+}
+
+impl SearchPalette {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.focus_query = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Draw the palette over `items`; returns the command picked this frame
+    pub fn show(&mut self, ctx: &Context, items: &[PaletteItem]) -> Option<PaletteCommand> {
+        if !self.open {
+            return None;
+        }
+
+        let mut matches: Vec<(u32, &PaletteItem)> = items
+            .iter()
+            .filter_map(|item| match_score(&self.query, &item.label).map(|score| (score, item)))
+            .collect();
+        matches.sort_by_key(|(score, _)| *score);
+
+        let (up, down, enter, escape) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::Enter),
+                i.key_pressed(egui::Key::Escape),
+            )
+        });
+        if down && self.selected + 1 < matches.len() {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut picked = None;
+        egui::Window::new("🔎 Search")
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let query = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Search actions and panes...")
+                        .desired_width(f32::INFINITY),
+                );
+                if self.focus_query {
+                    query.request_focus();
+                    self.focus_query = false;
+                }
+                if query.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+
+                if matches.is_empty() {
+                    ui.label(RichText::new("No matches").weak());
+                }
+                for (index, (_, item)) in matches.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let row = ui.selectable_label(index == self.selected, &item.label);
+                        if let Some(shortcut) = &item.shortcut {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(RichText::new(shortcut).weak().monospace());
+                                },
+                            );
+                        }
+                        if row.clicked() {
+                            picked = Some(item.command.clone());
+                        }
+                    });
+                }
+            });
+
+        if enter {
+            picked = picked.or_else(|| {
+                matches
+                    .get(self.selected)
+                    .map(|(_, item)| item.command.clone())
+            });
+        }
+        if picked.is_some() || escape {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+/// Rank of `label` for `query`, lower is better; `None` when it doesn't match
+fn match_score(query: &str, label: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let label = label.to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+    if label.starts_with(&query) {
+        return Some(1);
+    }
+    if let Some(position) = label.find(&query) {
+        return Some(2 + position as u32);
+    }
+    // Letters in order, scored by how spread out they are
+    let mut chars = label.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let (index, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(index);
+        last = index;
+    }
+    Some(1000 + (last - first.unwrap_or(0)) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_beats_substring_beats_letters_in_order() {
+        let prefix = match_score("cancel", "Cancel all orders").unwrap();
+        let substring = match_score("order", "Open order ticket").unwrap();
+        let letters = match_score("ordtk", "Open order ticket").unwrap();
+        assert!(prefix < substring && substring < letters);
+        assert_eq!(match_score("", "Quit"), Some(0));
+        assert_eq!(match_score("xyz", "Screenshot"), None);
+        assert_eq!(match_score("kcit", "Open order ticket"), None);
+    }
+}
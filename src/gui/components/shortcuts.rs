@@ -0,0 +1,174 @@
+//! Keyboard shortcut editor shown in the Settings dialog
+//!
+//! Each action can be rebound by pressing the new key, unbound, or reset to
+//! its default. A key taken from another action unbinds that action and says
+//! so. Every change is written to the `gui` section of keybindings.yaml right
+//! away, keeping the `tui` section as it is.
+
+use egui::{Color32, RichText};
+use tracing::{error, info};
+
+use crate::data_paths::DataPaths;
+use crate::keybindings::{Action, BoundAction, KeyChord, KeybindingsConfig, Keymap};
+
+#[derive(Default)]
+pub struct ShortcutEditor {
+    /// Action waiting for its new key
+    capturing: Option<Action>,
+    /// Outcome of the last change, and whether it is a warning
+    message: Option<(String, bool)>,
+}
+
+impl ShortcutEditor {
+    /// While capturing, key presses belong to the editor, not to shortcuts
+    pub fn is_capturing(&self) -> bool {
+        self.capturing.is_some()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, keymap: &mut Keymap, data_paths: &DataPaths) {
+        if let Some(action) = self.capturing {
+            self.capture_key(ui, action, keymap, data_paths);
+        }
+
+        egui::Grid::new("shortcut_editor_grid")
+            .num_columns(3)
+            .spacing([12.0, 6.0])
+            .striped(true)
+            .show(ui, |ui| {
+                let rows: Vec<_> = keymap.iter().collect();
+                for BoundAction { action, chord } in rows {
+                    ui.label(action.label());
+
+                    let key_text = if self.capturing == Some(action) {
+                        RichText::new("Press a key... (Esc to cancel)").italics()
+                    } else {
+                        match chord {
+                            Some(chord) => RichText::new(chord.to_string()).monospace(),
+                            None => RichText::new("unbound").weak(),
+                        }
+                    };
+                    if ui
+                        .add(egui::Button::new(key_text).min_size(egui::vec2(160.0, 0.0)))
+                        .on_hover_text("Click, then press the new key")
+                        .clicked()
+                    {
+                        self.capturing = Some(action);
+                        self.message = None;
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(chord.is_some(), egui::Button::new("Clear"))
+                            .clicked()
+                        {
+                            keymap.set(action, None);
+                            self.saved(keymap, data_paths, format!("'{}' unbound", action.label()));
+                        }
+                        let default = action.default_binding(keymap.surface());
+                        if ui
+                            .add_enabled(chord != default, egui::Button::new("Reset"))
+                            .clicked()
+                        {
+                            let conflict = keymap.set(action, default);
+                            let text = match conflict {
+                                Some(conflict) => conflict.to_string(),
+                                None => format!("'{}' reset", action.label()),
+                            };
+                            self.saved(keymap, data_paths, text);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            if ui.button("Reset all to defaults").clicked() {
+                *keymap = Keymap::defaults(keymap.surface());
+                self.capturing = None;
+                self.saved(keymap, data_paths, "All shortcuts reset".to_string());
+            }
+            ui.label(
+                RichText::new(format!(
+                    "Saved in {}",
+                    crate::keybindings::KEYBINDINGS_CONFIG_FILE
+                ))
+                .small()
+                .weak(),
+            );
+        });
+
+        if let Some((text, warning)) = &self.message {
+            let color = if *warning {
+                Color32::from_rgb(255, 165, 0)
+            } else {
+                Color32::from_rgb(100, 200, 100)
+            };
+            ui.colored_label(color, text);
+        }
+    }
+
+    /// Bind `action` to the first key pressed this frame
+    fn capture_key(
+        &mut self,
+        ui: &egui::Ui,
+        action: Action,
+        keymap: &mut Keymap,
+        data_paths: &DataPaths,
+    ) {
+        let pressed = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+        let Some((key, modifiers)) = pressed else {
+            return;
+        };
+        self.capturing = None;
+        if key == egui::Key::Escape && modifiers.is_none() {
+            return;
+        }
+        let Some(chord) = KeyChord::from_egui(key, modifiers) else {
+            self.message = Some((format!("{:?} can't be used as a shortcut", key), true));
+            return;
+        };
+
+        match keymap.set(action, Some(chord)) {
+            Some(conflict) => {
+                let text = conflict.to_string();
+                self.saved(keymap, data_paths, text);
+                if let Some((_, warning)) = &mut self.message {
+                    *warning = true;
+                }
+            }
+            None => {
+                let text = format!("'{}' bound to {}", action.label(), chord);
+                self.saved(keymap, data_paths, text);
+            }
+        }
+    }
+
+    /// Write the keymap and report `text`, or the write error
+    fn saved(&mut self, keymap: &Keymap, data_paths: &DataPaths, text: String) {
+        let result = KeybindingsConfig::load(data_paths).and_then(|mut config| {
+            config.set_keymap(keymap);
+            config.save(data_paths)
+        });
+        self.message = Some(match result {
+            Ok(()) => {
+                info!("⌨️ {}", text);
+                (text, false)
+            }
+            Err(e) => {
+                error!("Failed to save keybindings: {:#}", e);
+                (format!("{} (not saved: {})", text, e), true)
+            }
+        });
+    }
+}
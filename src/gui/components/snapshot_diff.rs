@@ -0,0 +1,37 @@
+//! Compact before/after card of a market, rendering `markets::snapshot_diff`
+
+use egui::{Color32, Response, RichText, Ui};
+
+use crate::markets::snapshot_diff::{SnapshotDiff, Trend};
+
+/// Color of a change: up and new in the profit color, down and gone in the loss color
+fn trend_color(trend: Option<Trend>) -> Color32 {
+    let palette = crate::theme::palette();
+    match trend {
+        Some(Trend::Up | Trend::Added) => palette.profit.into(),
+        Some(Trend::Down | Trend::Removed) => palette.loss.into(),
+        Some(Trend::Flat) | None => palette.muted.into(),
+    }
+}
+
+/// Draw `diff` as a framed card: title, period, then a before → after grid
+pub fn diff_card(ui: &mut Ui, id_salt: impl std::hash::Hash, diff: &SnapshotDiff) -> Response {
+    egui::Frame::group(ui.style())
+        .show(ui, |ui| {
+            ui.strong(&diff.title);
+            ui.label(RichText::new(diff.period()).small().weak());
+            egui::Grid::new(("snapshot_diff", id_salt))
+                .num_columns(4)
+                .spacing([10.0, 2.0])
+                .show(ui, |ui| {
+                    for row in diff.rows() {
+                        ui.label(row.label);
+                        ui.label(RichText::new(row.before).monospace());
+                        ui.label(RichText::new(format!("→ {}", row.after)).monospace());
+                        ui.colored_label(trend_color(row.trend), row.delta);
+                        ui.end_row();
+                    }
+                });
+        })
+        .response
+}
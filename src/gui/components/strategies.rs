@@ -0,0 +1,443 @@
+//! Strategy control panel
+//!
+//! Lists the strategies hosted by `polybot daemon` and starts, stops and
+//! retunes them through the daemon's control API, so the GUI and the headless
+//! daemon manage one strategy set. Requests run on the tokio runtime and their
+//! results are polled each frame, like the order ticket's.
+
+use egui::{Color32, RichText};
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::data_paths::DataPaths;
+use crate::strategy::control::{
+    ControlClient, ControlRequest, ControlResponse, StrategyState, StrategyStatus,
+};
+use crate::strategy::params::{ParamSpec, StrategyParams, STRATEGY_KINDS};
+
+/// How often the strategy list is refreshed while the panel is shown
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A finished control request and what the daemon answered
+struct CallResult {
+    request: ControlRequest,
+    response: Result<ControlResponse, String>,
+}
+
+pub struct StrategyPanel {
+    client: ControlClient,
+    statuses: Vec<StrategyStatus>,
+    /// Parameter schema per strategy kind
+    schemas: HashMap<String, Vec<ParamSpec>>,
+    /// Unapplied parameter edits per strategy id
+    edits: HashMap<String, StrategyParams>,
+    new_kind: String,
+    new_token_id: String,
+    new_params: StrategyParams,
+    /// Whether the last request reached the daemon
+    connected: bool,
+    in_flight: usize,
+    last_refresh: Option<Instant>,
+    message: Option<Result<String, String>>,
+    result_tx: mpsc::Sender<CallResult>,
+    result_rx: mpsc::Receiver<CallResult>,
+}
+
+impl StrategyPanel {
+    /// Panel for the daemon on `control_port`, authenticating with the
+    /// control token of the `data_paths` profile
+    pub fn new(control_port: u16, data_paths: &DataPaths) -> Self {
+        let (result_tx, result_rx) = mpsc::channel();
+        Self {
+            client: ControlClient::local(control_port, data_paths),
+            statuses: Vec::new(),
+            schemas: HashMap::new(),
+            edits: HashMap::new(),
+            new_kind: STRATEGY_KINDS[0].to_string(),
+            new_token_id: String::new(),
+            new_params: StrategyParams::new(),
+            connected: false,
+            in_flight: 0,
+            last_refresh: None,
+            message: None,
+            result_tx,
+            result_rx,
+        }
+    }
+
+    pub fn control_port(&self) -> u16 {
+        self.client.addr().port()
+    }
+
+    /// Strategies from the daemon's last answer
+    pub fn statuses(&self) -> &[StrategyStatus] {
+        &self.statuses
+    }
+
+    /// Draw the panel; returns true when the user asked to detach it
+    pub fn ui(&mut self, ui: &mut egui::Ui, streaming_assets: &[String], detached: bool) -> bool {
+        self.poll_results();
+        self.refresh_if_due();
+        // Keep polling while requests are out and the list fresh while visible
+        ui.ctx().request_repaint_after(if self.in_flight > 0 {
+            Duration::from_millis(100)
+        } else {
+            REFRESH_INTERVAL
+        });
+
+        let mut detach = false;
+        ui.horizontal(|ui| {
+            let (dot, status) = if self.connected {
+                (Color32::from_rgb(100, 200, 100), "Connected")
+            } else {
+                (Color32::from_rgb(220, 80, 80), "No daemon")
+            };
+            ui.colored_label(dot, "●");
+            ui.label(format!("{} · {}", status, self.client.addr()));
+            if self.in_flight > 0 {
+                ui.spinner();
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if !detached
+                    && ui
+                        .button("⧉ Detach")
+                        .on_hover_text("Open in its own window")
+                        .clicked()
+                {
+                    detach = true;
+                }
+                if ui.button("🔄 Refresh").clicked() {
+                    self.send(ControlRequest::List);
+                }
+            });
+        });
+
+        match &self.message {
+            Some(Ok(message)) => {
+                ui.colored_label(Color32::from_rgb(100, 200, 100), message);
+            }
+            Some(Err(message)) => {
+                ui.colored_label(Color32::from_rgb(220, 80, 80), message);
+            }
+            None => {}
+        }
+        ui.separator();
+
+        if !self.connected {
+            ui.label("Strategies run in the daemon. Start one with:");
+            ui.code(format!(
+                "polybot daemon --assets <TOKEN_IDS> --control-port {}",
+                self.client.addr().port()
+            ));
+            return detach;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if self.statuses.is_empty() {
+                ui.label(RichText::new("No strategies hosted yet").weak());
+            }
+            for status in self.statuses.clone() {
+                self.strategy_ui(ui, &status);
+            }
+
+            ui.separator();
+            self.new_strategy_ui(ui, streaming_assets);
+        });
+
+        detach
+    }
+
+    fn strategy_ui(&mut self, ui: &mut egui::Ui, status: &StrategyStatus) {
+        let (state_text, state_color) = match &status.state {
+            StrategyState::Running => ("Running".to_string(), Color32::from_rgb(100, 200, 100)),
+            StrategyState::Stopped => ("Stopped".to_string(), Color32::GRAY),
+            StrategyState::Failed { error } => {
+                (format!("Failed: {}", error), Color32::from_rgb(220, 80, 80))
+            }
+        };
+
+        egui::CollapsingHeader::new(format!("{} · {}", status.id, short_token(&status.token_id)))
+            .id_salt(("strategy", &status.id))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(state_color, state_text);
+                    if let Some(started_at) = status.started_at {
+                        ui.label(
+                            RichText::new(format!(
+                                "since {}",
+                                started_at.with_timezone(&chrono::Local).format("%H:%M:%S")
+                            ))
+                            .weak(),
+                        );
+                    }
+                });
+                ui.label(format!(
+                    "{} book updates · {} trades",
+                    status.orderbook_updates, status.trades
+                ));
+                ui.label(RichText::new(&status.token_id).monospace().weak());
+                if let Some(flatten) = &status.last_flatten {
+                    let color = if flatten.error.is_some() {
+                        Color32::from_rgb(220, 80, 80)
+                    } else {
+                        Color32::from_rgb(230, 180, 80)
+                    };
+                    ui.colored_label(
+                        color,
+                        format!(
+                            "📉 {} {}",
+                            flatten.at.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                            flatten.describe()
+                        ),
+                    );
+                }
+
+                ui.horizontal(|ui| match status.state {
+                    StrategyState::Running => {
+                        if ui.button("⏹ Stop").clicked() {
+                            self.send(ControlRequest::Stop {
+                                id: status.id.clone(),
+                            });
+                        }
+                    }
+                    _ => {
+                        if ui.button("▶ Resume").clicked() {
+                            self.send(ControlRequest::Resume {
+                                id: status.id.clone(),
+                            });
+                        }
+                    }
+                });
+
+                let Some(schema) = self.schema_for(&status.kind) else {
+                    ui.spinner();
+                    return;
+                };
+                let mut params = self
+                    .edits
+                    .get(&status.id)
+                    .cloned()
+                    .unwrap_or_else(|| status.params.clone());
+                params_ui(ui, &schema, &mut params, ("params", &status.id));
+
+                let changed = params != status.params;
+                let (mut apply, mut reset) = (false, false);
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(changed, egui::Button::new("✔ Apply"))
+                        .on_hover_text("A running strategy restarts with the new parameters")
+                        .clicked();
+                    reset = ui
+                        .add_enabled(changed, egui::Button::new("↺ Reset"))
+                        .clicked();
+                });
+
+                if apply {
+                    self.send(ControlRequest::Update {
+                        id: status.id.clone(),
+                        params: params.clone(),
+                    });
+                }
+                if changed && !reset {
+                    self.edits.insert(status.id.clone(), params);
+                } else {
+                    self.edits.remove(&status.id);
+                }
+            });
+    }
+
+    fn new_strategy_ui(&mut self, ui: &mut egui::Ui, streaming_assets: &[String]) {
+        ui.strong("Start a strategy");
+        egui::Grid::new("new_strategy_grid")
+            .num_columns(2)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                ui.label("Kind");
+                egui::ComboBox::from_id_salt("new_strategy_kind")
+                    .selected_text(&self.new_kind)
+                    .show_ui(ui, |ui| {
+                        for kind in STRATEGY_KINDS {
+                            if ui.selectable_label(self.new_kind == *kind, *kind).clicked() {
+                                self.new_kind = kind.to_string();
+                                self.new_params.clear();
+                            }
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Token ID");
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.new_token_id);
+                    egui::ComboBox::from_id_salt("new_strategy_token")
+                        .selected_text("Streamed")
+                        .show_ui(ui, |ui| {
+                            for token_id in streaming_assets {
+                                if ui
+                                    .selectable_label(
+                                        self.new_token_id == *token_id,
+                                        short_token(token_id),
+                                    )
+                                    .clicked()
+                                {
+                                    self.new_token_id = token_id.clone();
+                                }
+                            }
+                        });
+                });
+                ui.end_row();
+            });
+
+        let Some(schema) = self.schema_for(&self.new_kind.clone()) else {
+            ui.spinner();
+            return;
+        };
+        if self.new_params.is_empty() {
+            self.new_params = schema
+                .iter()
+                .map(|spec| (spec.name.clone(), spec.default))
+                .collect();
+        }
+        egui::CollapsingHeader::new("Parameters")
+            .id_salt("new_strategy_params")
+            .show(ui, |ui| {
+                params_ui(ui, &schema, &mut self.new_params, ("params", "new"));
+            });
+
+        let token_id = self.new_token_id.trim().to_string();
+        if ui
+            .add_enabled(!token_id.is_empty(), egui::Button::new("▶ Start"))
+            .on_hover_text("The token must be one the daemon streams (--assets)")
+            .clicked()
+        {
+            self.send(ControlRequest::Start {
+                kind: self.new_kind.clone(),
+                token_id,
+                params: self.new_params.clone(),
+            });
+        }
+    }
+
+    /// Schema of `kind`, requesting it from the daemon on first use
+    fn schema_for(&mut self, kind: &str) -> Option<Vec<ParamSpec>> {
+        if let Some(schema) = self.schemas.get(kind) {
+            return Some(schema.clone());
+        }
+        if self.in_flight == 0 {
+            self.send(ControlRequest::Schema {
+                kind: kind.to_string(),
+            });
+        }
+        None
+    }
+
+    fn refresh_if_due(&mut self) {
+        let due = self
+            .last_refresh
+            .is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL);
+        if due && self.in_flight == 0 {
+            self.send(ControlRequest::List);
+        }
+    }
+
+    fn send(&mut self, request: ControlRequest) {
+        if matches!(request, ControlRequest::List) {
+            self.last_refresh = Some(Instant::now());
+        }
+        self.in_flight += 1;
+
+        let client = self.client.clone();
+        let tx = self.result_tx.clone();
+        tokio::spawn(async move {
+            let response = client.call(&request).await.map_err(|e| e.to_string());
+            let _ = tx.send(CallResult { request, response });
+        });
+    }
+
+    fn poll_results(&mut self) {
+        while let Ok(CallResult { request, response }) = self.result_rx.try_recv() {
+            self.in_flight = self.in_flight.saturating_sub(1);
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    self.connected = false;
+                    // The disconnected view already says so; only report failed actions
+                    if !matches!(
+                        request,
+                        ControlRequest::List | ControlRequest::Schema { .. }
+                    ) {
+                        self.message = Some(Err(e));
+                    }
+                    continue;
+                }
+            };
+            self.connected = true;
+
+            match (request, response) {
+                (_, ControlResponse::Strategies { strategies }) => {
+                    // Drop edits of strategies that are gone
+                    self.edits
+                        .retain(|id, _| strategies.iter().any(|status| &status.id == id));
+                    self.statuses = strategies;
+                }
+                (ControlRequest::Schema { kind }, ControlResponse::Schema { params }) => {
+                    self.schemas.insert(kind, params);
+                }
+                (_, ControlResponse::Started { id }) => {
+                    self.message = Some(Ok(format!("Started {}", id)));
+                    self.send(ControlRequest::List);
+                }
+                (request, ControlResponse::Ok) => {
+                    if let ControlRequest::Update { id, .. } = &request {
+                        self.edits.remove(id);
+                    }
+                    self.message = Some(Ok(describe(&request)));
+                    self.send(ControlRequest::List);
+                }
+                (_, ControlResponse::Error { message }) => self.message = Some(Err(message)),
+                (_, ControlResponse::Schema { .. }) => {}
+            }
+        }
+    }
+}
+
+/// Sliders for every parameter in `schema`
+fn params_ui(
+    ui: &mut egui::Ui,
+    schema: &[ParamSpec],
+    params: &mut StrategyParams,
+    id_salt: impl std::hash::Hash,
+) {
+    egui::Grid::new(id_salt)
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for spec in schema {
+                let value = params.entry(spec.name.clone()).or_insert(spec.default);
+                ui.label(&spec.label).on_hover_text(&spec.help);
+                let mut slider = egui::Slider::new(value, spec.min..=spec.max).step_by(spec.step);
+                if spec.integer {
+                    slider = slider.integer();
+                }
+                ui.add(slider).on_hover_text(&spec.help);
+                ui.end_row();
+            }
+        });
+}
+
+fn describe(request: &ControlRequest) -> String {
+    match request {
+        ControlRequest::Stop { id } => format!("Stopped {}", id),
+        ControlRequest::Resume { id } => format!("Resumed {}", id),
+        ControlRequest::Update { id, .. } => format!("Updated {}", id),
+        _ => "Done".to_string(),
+    }
+}
+
+fn short_token(token_id: &str) -> String {
+    if token_id.len() > 12 {
+        format!("{}...", &token_id[..12])
+    } else {
+        token_id.to_string()
+    }
+}
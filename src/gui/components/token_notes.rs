@@ -0,0 +1,192 @@
+//! Token notes and price alerts in stream rows
+//!
+//! Right-clicking a token in the Streams pane opens an editor for its note and
+//! alert thresholds; rows show a badge while an alert is armed or has fired.
+//! Notes are kept by `core::token_notes` and alerts are checked every frame
+//! against the streamed prices, then raised with the note's severity.
+
+use egui::{Color32, RichText};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use tracing::warn;
+
+use crate::core::alerts::Severity;
+use crate::core::token_notes::{self, FiredAlert, TokenNote, TokenNoteStore};
+use crate::data_paths::DataPaths;
+
+/// Unsaved edits of one token's note
+#[derive(Default)]
+struct NoteDraft {
+    token_id: String,
+    note: String,
+    above: String,
+    below: String,
+    severity: Severity,
+    error: Option<String>,
+}
+
+pub struct TokenNotes {
+    store: TokenNoteStore,
+    notes: token_notes::TokenNotes,
+    draft: Option<NoteDraft>,
+}
+
+impl TokenNotes {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        let store = TokenNoteStore::new(data_paths);
+        let notes = store.load().unwrap_or_else(|e| {
+            warn!("Failed to load token notes: {}", e);
+            token_notes::TokenNotes::default()
+        });
+        Self {
+            store,
+            notes,
+            draft: None,
+        }
+    }
+
+    /// Bell while an alert is armed, crossed-out bell once it fired, pad for a
+    /// plain note; the hover shows the note and thresholds
+    pub fn badge(&self, ui: &mut egui::Ui, token_id: &str) {
+        let Some(note) = self.notes.get(token_id) else {
+            return;
+        };
+        let palette = crate::theme::palette();
+        let (icon, color) = if note.is_armed() {
+            ("🔔", Color32::from(palette.highlight))
+        } else if note.triggered.is_some() {
+            ("🔕", Color32::from(palette.muted))
+        } else {
+            ("📝", Color32::from(palette.muted))
+        };
+        let mut hover = Vec::new();
+        if !note.note.is_empty() {
+            hover.push(note.note.clone());
+        }
+        if note.has_alert() {
+            hover.push(format!("Alert {}", note.alert_label()));
+        }
+        if let Some(trigger) = &note.triggered {
+            hover.push(format!(
+                "Fired {} at ${} ({} {})",
+                trigger
+                    .at
+                    .with_timezone(&chrono::Local)
+                    .format("%m-%d %H:%M"),
+                trigger.price,
+                trigger.direction,
+                trigger.threshold
+            ));
+        }
+        ui.label(RichText::new(icon).color(color))
+            .on_hover_text(hover.join("\n"));
+    }
+
+    /// Note and alert editor for a token's context menu
+    pub fn edit_menu(&mut self, ui: &mut egui::Ui, token_id: &str) {
+        if self.draft.as_ref().is_none_or(|d| d.token_id != token_id) {
+            let note = self.notes.get(token_id).cloned().unwrap_or_default();
+            let format = |price: Option<Decimal>| price.map(|p| p.to_string()).unwrap_or_default();
+            self.draft = Some(NoteDraft {
+                token_id: token_id.to_string(),
+                note: note.note,
+                above: format(note.alert_above),
+                below: format(note.alert_below),
+                severity: note.severity,
+                error: None,
+            });
+        }
+        let Some(draft) = self.draft.as_mut() else {
+            return;
+        };
+
+        ui.label("📝 Note");
+        ui.add(
+            egui::TextEdit::singleline(&mut draft.note)
+                .hint_text("Short note")
+                .desired_width(200.0),
+        );
+        egui::Grid::new("token_alert_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("🔔 Alert above");
+                ui.add(egui::TextEdit::singleline(&mut draft.above).desired_width(70.0));
+                ui.end_row();
+                ui.label("🔔 Alert below");
+                ui.add(egui::TextEdit::singleline(&mut draft.below).desired_width(70.0));
+                ui.end_row();
+                ui.label("Severity");
+                egui::ComboBox::from_id_salt("token_alert_severity")
+                    .selected_text(draft.severity.to_string())
+                    .show_ui(ui, |ui| {
+                        for severity in Severity::ALL {
+                            ui.selectable_value(
+                                &mut draft.severity,
+                                severity,
+                                severity.to_string(),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+        if let Some(error) = &draft.error {
+            ui.colored_label(Color32::from_rgb(220, 80, 80), error);
+        }
+
+        let mut save = None;
+        ui.horizontal(|ui| {
+            if ui.button("Save").clicked() {
+                match (parse_price(&draft.above), parse_price(&draft.below)) {
+                    (Ok(alert_above), Ok(alert_below)) => {
+                        save = Some(TokenNote {
+                            note: draft.note.trim().to_string(),
+                            alert_above,
+                            alert_below,
+                            severity: draft.severity,
+                            triggered: None,
+                        });
+                    }
+                    (Err(e), _) | (_, Err(e)) => draft.error = Some(e),
+                }
+            }
+            if ui.button("Clear").clicked() {
+                save = Some(TokenNote::default());
+            }
+        });
+        if let Some(note) = save {
+            self.notes.set(token_id, note.clone());
+            self.save(|notes| notes.set(token_id, note));
+            self.draft = None;
+            ui.close_menu();
+        }
+    }
+
+    /// Fire the alerts whose token crossed a threshold
+    pub fn check_alerts(&mut self, price_of: impl Fn(&str) -> Option<Decimal>) -> Vec<FiredAlert> {
+        let fired = self.notes.check_alerts(price_of, chrono::Utc::now());
+        if !fired.is_empty() {
+            self.save(|notes| notes.record_fired(&fired));
+        }
+        fired
+    }
+
+    /// Apply `change` to the stored notes and pick up edits made elsewhere
+    fn save(&mut self, change: impl FnOnce(&mut token_notes::TokenNotes)) {
+        match self.store.modify(change) {
+            Ok(notes) => self.notes = notes,
+            Err(e) => warn!("Failed to save token notes: {}", e),
+        }
+    }
+}
+
+/// An optional threshold between 0 and 1
+fn parse_price(raw: &str) -> Result<Option<Decimal>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    match Decimal::from_str(raw) {
+        Ok(price) if price > Decimal::ZERO && price < Decimal::ONE => Ok(Some(price)),
+        _ => Err(format!("'{}' is not a price between 0 and 1", raw)),
+    }
+}
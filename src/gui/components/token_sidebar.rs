@@ -0,0 +1,195 @@
+//! Token metadata sidebar for depth panes
+//!
+//! Depth panes are bound to a bare token id. The sidebar resolves it against
+//! the typed store on a blocking task, caches the result per token, and shows
+//! the market question, outcome, end date and volume with shortcuts to copy
+//! the condition id or open the market's other outcomes.
+
+use egui::RichText;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use crate::gui::components::dataset_preview::format_volume;
+use crate::typed_store::freshness;
+use crate::typed_store::token_info::{self, TokenInfo};
+
+enum LookupState {
+    Loading,
+    Found(TokenInfo),
+    Missing,
+    Failed(String),
+}
+
+/// A finished lookup sent back from its blocking task
+struct LookupResult {
+    token_id: String,
+    result: Result<Option<TokenInfo>, String>,
+}
+
+pub struct TokenSidebar {
+    db_path: PathBuf,
+    lookups: HashMap<String, LookupState>,
+    result_tx: mpsc::Sender<LookupResult>,
+    result_rx: mpsc::Receiver<LookupResult>,
+}
+
+impl TokenSidebar {
+    pub fn new(db_path: PathBuf) -> Self {
+        let (result_tx, result_rx) = mpsc::channel();
+        Self {
+            db_path,
+            lookups: HashMap::new(),
+            result_tx,
+            result_rx,
+        }
+    }
+
+    /// Pick up finished lookups; returns whether any are still running
+    pub fn poll(&mut self) -> bool {
+        while let Ok(LookupResult { token_id, result }) = self.result_rx.try_recv() {
+            let state = match result {
+                Ok(Some(info)) => LookupState::Found(info),
+                Ok(None) => LookupState::Missing,
+                Err(e) => LookupState::Failed(e),
+            };
+            self.lookups.insert(token_id, state);
+        }
+        self.lookups
+            .values()
+            .any(|state| matches!(state, LookupState::Loading))
+    }
+
+    fn request(&mut self, token_id: &str) {
+        self.lookups
+            .insert(token_id.to_string(), LookupState::Loading);
+        let token_id = token_id.to_string();
+        let db_path = self.db_path.clone();
+        let tx = self.result_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = token_info::lookup(&db_path, &token_id).map_err(|e| e.to_string());
+            let _ = tx.send(LookupResult { token_id, result });
+        });
+    }
+
+    /// Resolved metadata of `token_id`, starting a lookup on first use
+    pub fn info(&mut self, token_id: &str) -> Option<&TokenInfo> {
+        if !self.lookups.contains_key(token_id) {
+            self.request(token_id);
+        }
+        match self.lookups.get(token_id) {
+            Some(LookupState::Found(info)) => Some(info),
+            _ => None,
+        }
+    }
+
+    /// Sidebar contents for `token_id`; returns a token to open in a new depth pane
+    pub fn ui(&mut self, ui: &mut egui::Ui, token_id: &str) -> Option<String> {
+        let mut open_token = None;
+        let mut retry = false;
+
+        self.info(token_id);
+        match self.lookups.get(token_id) {
+            Some(LookupState::Found(info)) => {
+                info_ui(ui, info, &mut open_token);
+                ui.separator();
+                retry = ui.small_button("↻ Reload").clicked();
+            }
+            Some(LookupState::Missing) => {
+                ui.label(RichText::new("Token not found in the market index").weak());
+                ui.label(
+                    RichText::new("Run `polybot index` to resolve its market")
+                        .small()
+                        .weak(),
+                );
+                retry = ui.small_button("↻ Retry").clicked();
+            }
+            Some(LookupState::Failed(e)) => {
+                ui.colored_label(egui::Color32::from_rgb(220, 80, 80), e);
+                retry = ui.small_button("↻ Retry").clicked();
+            }
+            _ => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Resolving token...");
+                });
+            }
+        }
+
+        if retry {
+            self.request(token_id);
+        }
+        open_token
+    }
+}
+
+fn info_ui(ui: &mut egui::Ui, info: &TokenInfo, open_token: &mut Option<String>) {
+    if let Some(question) = &info.question {
+        ui.label(RichText::new(question).strong());
+    }
+    ui.add_space(4.0);
+
+    egui::Grid::new(("token_sidebar_grid", &info.token_id))
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("Outcome:");
+            ui.label(info.outcome.as_deref().unwrap_or("-"));
+            ui.end_row();
+
+            ui.label("Ends:");
+            ui.label(end_date_text(info.end_date_iso.as_deref()));
+            ui.end_row();
+
+            ui.label("Volume:");
+            ui.label(format_volume(info.volume).trim());
+            ui.end_row();
+
+            ui.label("24h volume:");
+            ui.label(format_volume(info.volume_24hr).trim());
+            ui.end_row();
+        });
+
+    ui.separator();
+    ui.label(RichText::new(&info.token_id).small().monospace().weak())
+        .on_hover_text("Token ID");
+    if ui.small_button("📋 Copy token ID").clicked() {
+        ui.ctx().copy_text(info.token_id.clone());
+    }
+    if let Some(condition_id) = &info.condition_id {
+        if ui
+            .small_button("📋 Copy condition ID")
+            .on_hover_text(condition_id)
+            .clicked()
+        {
+            ui.ctx().copy_text(condition_id.clone());
+        }
+    }
+    for complement in &info.complements {
+        if ui
+            .small_button(format!("📊 Open '{}'", complement.outcome))
+            .on_hover_text(&complement.token_id)
+            .clicked()
+        {
+            *open_token = Some(complement.token_id.clone());
+        }
+    }
+}
+
+/// End date as `YYYY-MM-DD`, with how far away it is
+fn end_date_text(end_date_iso: Option<&str>) -> String {
+    let Some(raw) = end_date_iso else {
+        return "-".to_string();
+    };
+    let Ok(end) = chrono::DateTime::parse_from_rfc3339(raw) else {
+        return raw.to_string();
+    };
+    let end = end.with_timezone(&chrono::Utc);
+    let now = chrono::Utc::now();
+    let date = end.format("%Y-%m-%d");
+    if end > now {
+        format!("{} (in {})", date, freshness::format_age(end - now))
+    } else {
+        format!("{} (ended)", date)
+    }
+}
@@ -0,0 +1,183 @@
+//! Monitor discovery for the trading canvas
+//!
+//! Monitors are enumerated through winit (as bundled with eframe) on the event
+//! loop the canvas then runs on, so there are no platform tools to shell out
+//! to. The loop runs just long enough to start up, since monitors are only
+//! queried from a running loop. Geometry is converted from physical pixels to
+//! egui points with each monitor's own scale factor, which keeps the window
+//! the right size on HiDPI and mixed-DPI setups.
+
+use anyhow::{anyhow, Result};
+use eframe::egui_winit::winit::application::ApplicationHandler;
+use eframe::egui_winit::winit::dpi::{PhysicalPosition, PhysicalSize};
+use eframe::egui_winit::winit::event::WindowEvent;
+use eframe::egui_winit::winit::event_loop::{ActiveEventLoop, EventLoop};
+use eframe::egui_winit::winit::monitor::MonitorHandle;
+use eframe::egui_winit::winit::platform::run_on_demand::EventLoopExtRunOnDemand;
+use eframe::egui_winit::winit::window::WindowId;
+use tracing::{info, warn};
+
+/// A monitor's position and size in egui points
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub name: String,
+    pub position: egui::Pos2,
+    pub size: egui::Vec2,
+    /// Physical pixels per point
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    fn from_physical(
+        name: String,
+        position: PhysicalPosition<i32>,
+        size: PhysicalSize<u32>,
+        scale_factor: f64,
+        is_primary: bool,
+    ) -> Self {
+        // Some platforms report 0 for monitors they cannot query
+        let scale = if scale_factor > 0.0 { scale_factor as f32 } else { 1.0 };
+        Self {
+            name,
+            position: egui::pos2(position.x as f32 / scale, position.y as f32 / scale),
+            size: egui::vec2(size.width as f32 / scale, size.height as f32 / scale),
+            scale_factor: scale,
+            is_primary,
+        }
+    }
+
+    fn from_handle(handle: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        Self::from_physical(
+            handle.name().unwrap_or_else(|| "unnamed".to_string()),
+            handle.position(),
+            handle.size(),
+            handle.scale_factor(),
+            primary == Some(handle),
+        )
+    }
+}
+
+/// Reads the monitors once the loop is running, then stops it
+#[derive(Default)]
+struct MonitorProbe {
+    monitors: Vec<MonitorInfo>,
+}
+
+impl<T: 'static> ApplicationHandler<T> for MonitorProbe {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        let primary = event_loop.primary_monitor();
+        self.monitors = event_loop
+            .available_monitors()
+            .map(|handle| MonitorInfo::from_handle(&handle, primary.as_ref()))
+            .collect();
+        event_loop.exit();
+    }
+
+    // No window exists while probing
+    fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+}
+
+/// Every monitor the event loop can see, in the platform's order
+pub fn list_monitors<T: 'static>(event_loop: &mut EventLoop<T>) -> Result<Vec<MonitorInfo>> {
+    // Enumerating before the canvas runs is what lets the first frame open
+    // on the chosen monitor at the right size
+    let mut probe = MonitorProbe::default();
+    event_loop
+        .run_app_on_demand(&mut probe)
+        .map_err(|e| anyhow!("Failed to enumerate monitors: {}", e))?;
+    let monitors = probe.monitors;
+
+    for (index, monitor) in monitors.iter().enumerate() {
+        info!(
+            "🖥️ Monitor {}: {} {}x{} pt at ({}, {}), scale {}{}",
+            index,
+            monitor.name,
+            monitor.size.x,
+            monitor.size.y,
+            monitor.position.x,
+            monitor.position.y,
+            monitor.scale_factor,
+            if monitor.is_primary { " (primary)" } else { "" }
+        );
+    }
+    Ok(monitors)
+}
+
+/// The monitor to open on: `requested` by index, else the primary, else the first
+///
+/// `None` when no monitor could be enumerated (e.g. some Wayland compositors);
+/// the window then opens at its configured size wherever the platform puts it.
+pub fn select_monitor(monitors: &[MonitorInfo], requested: Option<usize>) -> Result<Option<MonitorInfo>> {
+    if let Some(index) = requested {
+        return monitors.get(index).cloned().map(Some).ok_or_else(|| {
+            anyhow!(
+                "No monitor {} (found {}; use 0 to {})",
+                index,
+                monitors.len(),
+                monitors.len().saturating_sub(1)
+            )
+        });
+    }
+
+    let monitor = monitors
+        .iter()
+        .find(|m| m.is_primary)
+        .or_else(|| monitors.first())
+        .cloned();
+    if monitor.is_none() {
+        warn!("⚠️ No monitors reported by the windowing system, using the configured window size");
+    }
+    Ok(monitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitors() -> Vec<MonitorInfo> {
+        vec![
+            MonitorInfo::from_physical(
+                "laptop".into(),
+                PhysicalPosition::new(0, 0),
+                PhysicalSize::new(2880, 1800),
+                2.0,
+                false,
+            ),
+            MonitorInfo::from_physical(
+                "external".into(),
+                PhysicalPosition::new(2880, 0),
+                PhysicalSize::new(2560, 1440),
+                1.0,
+                true,
+            ),
+        ]
+    }
+
+    #[test]
+    fn physical_geometry_is_scaled_to_points() {
+        let laptop = &monitors()[0];
+        assert_eq!(laptop.size, egui::vec2(1440.0, 900.0));
+        assert_eq!(laptop.scale_factor, 2.0);
+
+        let unknown_scale = MonitorInfo::from_physical(
+            "x".into(),
+            PhysicalPosition::new(10, 20),
+            PhysicalSize::new(800, 600),
+            0.0,
+            false,
+        );
+        assert_eq!(unknown_scale.position, egui::pos2(10.0, 20.0));
+        assert_eq!(unknown_scale.size, egui::vec2(800.0, 600.0));
+    }
+
+    #[test]
+    fn selects_requested_then_primary_then_first() {
+        let monitors = monitors();
+        assert_eq!(select_monitor(&monitors, Some(0)).unwrap().unwrap().name, "laptop");
+        assert_eq!(select_monitor(&monitors, None).unwrap().unwrap().name, "external");
+        assert_eq!(select_monitor(&monitors[..1], None).unwrap().unwrap().name, "laptop");
+        assert!(select_monitor(&monitors, Some(2)).is_err());
+        assert!(select_monitor(&[], None).unwrap().is_none());
+    }
+}
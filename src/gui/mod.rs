@@ -2,56 +2,88 @@
 
 pub mod app;
 pub mod components;
+pub mod display;
+pub mod notifications;
+pub mod onboarding;
 pub mod panes;
+pub mod repaint;
 pub mod services;
+pub mod subscriptions;
+pub mod token_budget;
 
 pub use app::TradingApp;
 
 use crate::data_paths::DataPaths;
+use crate::markets::datasets::replay::CaptureReplay;
 use anyhow::Result;
+use eframe::egui_winit::winit::event_loop::EventLoop;
+use eframe::egui_winit::winit::platform::run_on_demand::EventLoopExtRunOnDemand;
+use std::sync::Arc;
 use tracing::{error, info};
 
 /// Launch the trading canvas GUI application
+///
+/// Opens fullscreen on `monitor` (an index into the monitors logged at
+/// startup), or on the primary monitor. `width`/`height` are only used when
+/// the windowing system reports no monitors. Streaming updates repaint each
+/// visible pane at most `max_fps` times per second. The strategy panel talks
+/// to the daemon's control API on `control_port`. With `replay`, the canvas
+/// plays that capture window at the given speed instead of streaming live.
 pub async fn launch_trading_canvas(
-    _width: u32,
-    _height: u32,
+    width: u32,
+    height: u32,
+    monitor: Option<usize>,
+    max_fps: u32,
+    control_port: u16,
     dark_mode: bool,
     title: &str,
     host: &str,
     data_paths: DataPaths,
+    replay: Option<(Arc<CaptureReplay>, f64)>,
 ) -> Result<()> {
     info!("🎨 Launching Trading Canvas GUI in fullscreen mode");
 
-    // Get screen resolution for fullscreen
-    let (screen_width, screen_height) = get_screen_resolution();
-    info!(
-        "🖥️ Detected screen resolution: {}x{}",
-        screen_width, screen_height
-    );
+    // Own the event loop so monitors can be enumerated before the window opens
+    let mut event_loop = EventLoop::<eframe::UserEvent>::with_user_event()
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to create window event loop: {}", e))?;
+    let monitors = display::list_monitors(&mut event_loop)?;
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_title(title)
+        .with_min_inner_size([800.0, 600.0])
+        .with_fullscreen(true) // Start in fullscreen mode
+        .with_maximized(true) // Also maximize as fallback
+        .with_decorations(false) // No window decorations in fullscreen
+        .with_active(true) // Request window focus
+        .with_visible(true); // Ensure window is visible
+    match display::select_monitor(&monitors, monitor)? {
+        Some(screen) => {
+            info!(
+                "🖥️ Opening on {}: {}x{} pt at scale {}",
+                screen.name, screen.size.x, screen.size.y, screen.scale_factor
+            );
+            // Placing the window on the monitor first makes fullscreen use it
+            viewport = viewport
+                .with_position(screen.position)
+                .with_inner_size(screen.size);
+        }
+        None => viewport = viewport.with_inner_size([width as f32, height as f32]),
+    }
 
-    // Configure native options for fullscreen mode
     let native_options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([screen_width, screen_height])
-            .with_title(title)
-            .with_min_inner_size([800.0, 600.0])
-            .with_fullscreen(true) // Start in fullscreen mode
-            .with_maximized(true) // Also maximize as fallback
-            .with_decorations(false) // No window decorations in fullscreen
-            .with_active(true) // Request window focus
-            .with_visible(true), // Ensure window is visible
+        viewport,
         ..Default::default()
     };
 
     info!("🚀 About to create and run the trading app...");
 
     // Create and run the trading app
-    let app_result = eframe::run_native(
+    let mut app = eframe::create_native(
         title,
         native_options,
         Box::new({
             let host = host.to_string();
-            let _title = title.to_string();
             move |cc| {
                 info!("📱 GUI context created, setting up styling...");
 
@@ -61,16 +93,23 @@ pub async fn launch_trading_canvas(
                 info!("🎯 Creating TradingApp instance...");
 
                 // Create the trading app
-                let app = TradingApp::new(cc, host, data_paths);
+                let mut app = TradingApp::new(cc, host, data_paths)
+                    .with_max_fps(max_fps)
+                    .with_control_port(control_port);
+                if let Some((replay, speed)) = replay {
+                    app = app.with_replay(replay, speed);
+                }
 
                 info!("✅ TradingApp created successfully");
 
                 Ok(Box::new(app))
             }
         }),
+        &event_loop,
     );
+    let app_result = event_loop.run_app_on_demand(&mut app);
 
-    info!("📊 eframe::run_native call completed");
+    info!("📊 Trading canvas event loop finished");
 
     match app_result {
         Ok(()) => {
@@ -103,7 +142,7 @@ fn setup_custom_style(ctx: &egui::Context, dark_mode: bool) {
         style.visuals.widgets.active.bg_fill = egui::Color32::from_gray(55);
 
         // Trading-specific colors
-        style.visuals.error_fg_color = egui::Color32::from_rgb(220, 80, 80); // Red for sells/losses
+        style.visuals.error_fg_color = crate::theme::palette().loss.into(); // Sells/losses
         style.visuals.warn_fg_color = egui::Color32::from_rgb(255, 200, 100); // Yellow for warnings
     } else {
         ctx.set_visuals(egui::Visuals::light());
@@ -111,188 +150,3 @@ fn setup_custom_style(ctx: &egui::Context, dark_mode: bool) {
 
     ctx.set_style(style);
 }
-
-/// Get the primary monitor's screen resolution
-fn get_screen_resolution() -> (f32, f32) {
-    // Try to get screen resolution using platform-specific methods
-    #[cfg(target_os = "macos")]
-    {
-        get_macos_screen_resolution()
-    }
-    #[cfg(target_os = "windows")]
-    {
-        get_windows_screen_resolution()
-    }
-    #[cfg(target_os = "linux")]
-    {
-        get_linux_screen_resolution()
-    }
-    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
-    {
-        // Fallback for unsupported platforms
-        info!("⚠️ Screen resolution detection not supported on this platform, using default");
-        (1920.0, 1080.0)
-    }
-}
-
-#[cfg(target_os = "macos")]
-fn get_macos_screen_resolution() -> (f32, f32) {
-    use std::process::Command;
-
-    // Use system_profiler to get display information
-    if let Ok(output) = Command::new("system_profiler")
-        .args(&["SPDisplaysDataType", "-json"])
-        .output()
-    {
-        if let Ok(json_str) = String::from_utf8(output.stdout) {
-            // Parse the JSON to extract resolution
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                if let Some(displays) = json["SPDisplaysDataType"].as_array() {
-                    for display in displays {
-                        if let Some(resolution) = display["_spdisplays_resolution"].as_str() {
-                            if let Some((width_str, height_str)) = resolution.split_once(" x ") {
-                                if let (Ok(width), Ok(height)) =
-                                    (width_str.parse::<f32>(), height_str.parse::<f32>())
-                                {
-                                    info!(
-                                        "📱 Detected macOS display resolution: {}x{}",
-                                        width, height
-                                    );
-                                    return (width, height);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Alternative approach using displayplacer command if available
-    if let Ok(output) = Command::new("displayplacer").args(&["list"]).output() {
-        if let Ok(output_str) = String::from_utf8(output.stdout) {
-            // Parse displayplacer output for resolution
-            for line in output_str.lines() {
-                if line.contains("Resolution:") {
-                    if let Some(resolution_part) = line.split("Resolution: ").nth(1) {
-                        if let Some(resolution) = resolution_part.split_whitespace().next() {
-                            if let Some((width_str, height_str)) = resolution.split_once("x") {
-                                if let (Ok(width), Ok(height)) =
-                                    (width_str.parse::<f32>(), height_str.parse::<f32>())
-                                {
-                                    info!("📱 Detected macOS display resolution via displayplacer: {}x{}", width, height);
-                                    return (width, height);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Fallback for macOS
-    info!("⚠️ Could not detect macOS screen resolution, using default");
-    (1920.0, 1080.0)
-}
-
-#[cfg(target_os = "windows")]
-fn get_windows_screen_resolution() -> (f32, f32) {
-    use std::process::Command;
-
-    // Use wmic to get screen resolution
-    if let Ok(output) = Command::new("wmic")
-        .args(&[
-            "path",
-            "Win32_VideoController",
-            "get",
-            "CurrentHorizontalResolution,CurrentVerticalResolution",
-            "/format:csv",
-        ])
-        .output()
-    {
-        if let Ok(output_str) = String::from_utf8(output.stdout) {
-            for line in output_str.lines() {
-                if line.contains(",") && !line.contains("Node") {
-                    let parts: Vec<&str> = line.split(',').collect();
-                    if parts.len() >= 3 {
-                        if let (Ok(width), Ok(height)) =
-                            (parts[1].parse::<f32>(), parts[2].parse::<f32>())
-                        {
-                            if width > 0.0 && height > 0.0 {
-                                info!(
-                                    "📱 Detected Windows display resolution: {}x{}",
-                                    width, height
-                                );
-                                return (width, height);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Fallback for Windows
-    info!("⚠️ Could not detect Windows screen resolution, using default");
-    (1920.0, 1080.0)
-}
-
-#[cfg(target_os = "linux")]
-fn get_linux_screen_resolution() -> (f32, f32) {
-    use std::process::Command;
-
-    // Try xrandr first (most common)
-    if let Ok(output) = Command::new("xrandr").output() {
-        if let Ok(output_str) = String::from_utf8(output.stdout) {
-            for line in output_str.lines() {
-                if line.contains(" connected ") && line.contains("x") {
-                    // Parse xrandr output: "1920x1080+0+0"
-                    for part in line.split_whitespace() {
-                        if part.contains("x") && part.contains("+") {
-                            if let Some(resolution) = part.split('+').next() {
-                                if let Some((width_str, height_str)) = resolution.split_once('x') {
-                                    if let (Ok(width), Ok(height)) =
-                                        (width_str.parse::<f32>(), height_str.parse::<f32>())
-                                    {
-                                        info!("📱 Detected Linux display resolution via xrandr: {}x{}", width, height);
-                                        return (width, height);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Try xdpyinfo as fallback
-    if let Ok(output) = Command::new("xdpyinfo").output() {
-        if let Ok(output_str) = String::from_utf8(output.stdout) {
-            for line in output_str.lines() {
-                if line.trim().starts_with("dimensions:") {
-                    if let Some(dimensions) = line.split("dimensions:").nth(1) {
-                        if let Some(resolution) = dimensions.trim().split_whitespace().next() {
-                            if let Some((width_str, height_str)) = resolution.split_once('x') {
-                                if let (Ok(width), Ok(height)) =
-                                    (width_str.parse::<f32>(), height_str.parse::<f32>())
-                                {
-                                    info!(
-                                        "📱 Detected Linux display resolution via xdpyinfo: {}x{}",
-                                        width, height
-                                    );
-                                    return (width, height);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Fallback for Linux
-    info!("⚠️ Could not detect Linux screen resolution, using default");
-    (1920.0, 1080.0)
-}
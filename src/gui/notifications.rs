@@ -0,0 +1,794 @@
+//! Sound and flash cues for own order fills and token alerts
+//!
+//! Fills are found by polling the account's Data API activity feed (the same
+//! feed `polybot address tail` follows), so they are noticed whichever pane has
+//! focus and whoever placed the order. Alerts are the token note thresholds
+//! fired by `check_token_alerts`.
+//!
+//! Each kind of event has its own cue in `<data_dir>/notifications.yaml`: a
+//! tone at a volume, and a fading outline over the panes it concerns. Tones
+//! need the `sound` feature; without it only the flashes are shown.
+//!
+//! Every fill and alert is also listed in the notifications center, with a
+//! card of how the token's market moved in the minutes before it.
+//!
+//! Alerts are also raised through `core::alerts`, which routes them by
+//! severity and repeats or escalates them until acknowledged; open alerts are
+//! listed at the top of the notifications center with a button to acknowledge
+//! them.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::address_book::tail::{fetch_activity, ActivityEvent, ActivityTracker};
+use crate::core::alerts::{Alert, AlertRouter, AlertRouting, AlertStore, Severity};
+use crate::data_paths::DataPaths;
+use crate::gui::components::snapshot_diff::diff_card;
+use crate::gui::panes::Pane;
+use crate::markets::snapshot_diff::SnapshotDiff;
+
+/// Cue settings, in the data directory
+pub const NOTIFICATIONS_CONFIG_FILE: &str = "notifications.yaml";
+
+/// How long a pane flash takes to fade out
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// Time between polls of the account's activity feed
+const FILL_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Activity entries fetched per poll
+const FILL_FETCH_LIMIT: usize = 50;
+
+/// Entries kept in the notifications center
+const HISTORY_LIMIT: usize = 100;
+
+/// Time between follow-ups on open alerts (repeats and escalations)
+const ALERT_FOLLOW_UP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the open alerts listed in the center are reused before re-reading
+const OPEN_ALERTS_REFRESH: Duration = Duration::from_secs(2);
+
+/// How long an alert toast stays up
+const ALERT_TOAST_DURATION: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// One of the account's orders traded
+    Fill,
+    /// A token alert crossed its threshold
+    Alert,
+}
+
+impl NotificationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationKind::Fill => "Order fills",
+            NotificationKind::Alert => "Token alerts",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            NotificationKind::Fill => "💰",
+            NotificationKind::Alert => "🔔",
+        }
+    }
+
+    /// Whether a cue of this kind outlines `pane`
+    fn flashes(&self, token_id: Option<&str>, pane: &Pane) -> bool {
+        match (self, pane) {
+            (_, Pane::MarketDepth(Some(pane_token))) => token_id == Some(pane_token.as_str()),
+            (NotificationKind::Fill, pane) => matches!(
+                pane,
+                Pane::Orders
+                    | Pane::Portfolio
+                    | Pane::PortfolioValue
+                    | Pane::Balances
+                    | Pane::TradeHistory
+            ),
+            (NotificationKind::Alert, pane) => matches!(pane, Pane::Tokens),
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        let palette = crate::theme::palette();
+        match self {
+            NotificationKind::Fill => Color32::from(palette.profit),
+            NotificationKind::Alert => Color32::from(palette.highlight),
+        }
+    }
+}
+
+/// What an event of one kind does
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cue {
+    pub sound: bool,
+    /// 0.0 to 1.0
+    pub volume: f32,
+    pub flash: bool,
+}
+
+impl Default for Cue {
+    fn default() -> Self {
+        Self {
+            sound: true,
+            volume: 0.5,
+            flash: true,
+        }
+    }
+}
+
+impl Cue {
+    pub fn is_enabled(&self) -> bool {
+        self.sound || self.flash
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub fill: Cue,
+    pub alert: Cue,
+}
+
+impl NotificationSettings {
+    /// Load `notifications.yaml` from the data directory, or the defaults when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(NOTIFICATIONS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid notifications config {}", path.display()))
+    }
+
+    pub fn save(&self, data_paths: &DataPaths) -> Result<()> {
+        let path = data_paths.root().join(NOTIFICATIONS_CONFIG_FILE);
+        let content = serde_yaml::to_string(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn cue(&self, kind: NotificationKind) -> &Cue {
+        match kind {
+            NotificationKind::Fill => &self.fill,
+            NotificationKind::Alert => &self.alert,
+        }
+    }
+
+    fn cue_mut(&mut self, kind: NotificationKind) -> &mut Cue {
+        match kind {
+            NotificationKind::Fill => &mut self.fill,
+            NotificationKind::Alert => &mut self.alert,
+        }
+    }
+}
+
+struct Flash {
+    kind: NotificationKind,
+    token_id: Option<String>,
+    started: Instant,
+}
+
+impl Flash {
+    /// Strength from 1.0 when started down to 0.0 once faded
+    fn strength(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.started);
+        1.0 - (elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32()).min(1.0)
+    }
+}
+
+/// A fill or alert as listed in the notifications center
+pub struct NotificationEntry {
+    pub kind: NotificationKind,
+    pub at: DateTime<Local>,
+    pub message: String,
+    pub token_id: Option<String>,
+    /// How the token's market moved in the minutes before
+    pub diff: Option<SnapshotDiff>,
+}
+
+/// Plays the cues of fills and alerts and keeps their history
+pub struct Notifier {
+    settings: NotificationSettings,
+    sound: SoundPlayer,
+    flashes: Vec<Flash>,
+    /// Newest first, at most `HISTORY_LIMIT`
+    history: VecDeque<NotificationEntry>,
+    /// Entries added since the center was last shown
+    unread: usize,
+    /// Error of the last settings save
+    save_error: Option<String>,
+}
+
+impl Notifier {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        let settings = NotificationSettings::load(data_paths).unwrap_or_else(|e| {
+            warn!("Using default notification settings: {:#}", e);
+            NotificationSettings::default()
+        });
+        Self {
+            settings,
+            sound: SoundPlayer::default(),
+            flashes: Vec::new(),
+            history: VecDeque::new(),
+            unread: 0,
+            save_error: None,
+        }
+    }
+
+    pub fn settings(&self) -> &NotificationSettings {
+        &self.settings
+    }
+
+    /// Play the cue of `kind`; `token_id` picks the depth pane to flash
+    pub fn notify(&mut self, kind: NotificationKind, token_id: Option<&str>) {
+        let cue = *self.settings.cue(kind);
+        if cue.sound {
+            self.sound.play(kind, cue.volume);
+        }
+        if cue.flash {
+            self.flashes.push(Flash {
+                kind,
+                token_id: token_id.map(str::to_string),
+                started: Instant::now(),
+            });
+        }
+    }
+
+    /// Play the cue of `kind` and list it in the notifications center
+    pub fn record(
+        &mut self,
+        kind: NotificationKind,
+        token_id: Option<&str>,
+        message: String,
+        diff: Option<SnapshotDiff>,
+    ) {
+        self.notify(kind, token_id);
+        self.history.push_front(NotificationEntry {
+            kind,
+            at: Local::now(),
+            message,
+            token_id: token_id.map(str::to_string),
+            diff,
+        });
+        self.history.truncate(HISTORY_LIMIT);
+        self.unread = (self.unread + 1).min(HISTORY_LIMIT);
+    }
+
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    /// Recorded fills and alerts, newest first, each with its market card
+    pub fn center_ui(&mut self, ui: &mut egui::Ui) {
+        self.unread = 0;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} notifications", self.history.len()));
+            if ui
+                .add_enabled(!self.history.is_empty(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                self.history.clear();
+            }
+        });
+        ui.separator();
+        if self.history.is_empty() {
+            ui.label(egui::RichText::new("No fills or alerts yet").weak());
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, entry) in self.history.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(entry.kind.color(), entry.kind.icon());
+                    ui.label(
+                        egui::RichText::new(entry.at.format("%H:%M:%S").to_string())
+                            .monospace()
+                            .weak(),
+                    );
+                    ui.label(&entry.message);
+                });
+                match &entry.diff {
+                    Some(diff) => {
+                        diff_card(ui, i, diff);
+                    }
+                    None if entry.token_id.is_some() => {
+                        ui.label(
+                            egui::RichText::new("No book history for this token yet")
+                                .small()
+                                .weak(),
+                        );
+                    }
+                    None => {}
+                }
+                ui.add_space(6.0);
+            }
+        });
+    }
+
+    /// Drop faded flashes; true while some are still showing
+    pub fn update_flashes(&mut self, now: Instant) -> bool {
+        self.flashes.retain(|flash| flash.strength(now) > 0.0);
+        !self.flashes.is_empty()
+    }
+
+    /// Outline color of `pane`, faded by the age of its newest flash
+    pub fn flash_color(&self, pane: &Pane, now: Instant) -> Option<Color32> {
+        self.flashes
+            .iter()
+            .filter(|flash| flash.kind.flashes(flash.token_id.as_deref(), pane))
+            .map(|flash| (flash.strength(now), flash.kind))
+            .filter(|(strength, _)| *strength > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(strength, kind)| kind.color().gamma_multiply(strength))
+    }
+
+    /// Per-kind sound, volume and flash controls, saved on change
+    pub fn settings_ui(&mut self, ui: &mut egui::Ui, data_paths: &DataPaths) {
+        if !cfg!(feature = "sound") {
+            ui.label(
+                egui::RichText::new("Built without the `sound` feature: flashes only")
+                    .small()
+                    .weak(),
+            );
+        }
+
+        let mut changed = false;
+        let mut test = None;
+        egui::Grid::new("notification_settings_grid")
+            .num_columns(5)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                for kind in [NotificationKind::Fill, NotificationKind::Alert] {
+                    let cue = self.settings.cue_mut(kind);
+                    ui.label(kind.label());
+                    changed |= ui.checkbox(&mut cue.sound, "Sound").changed();
+                    let volume = ui.add_enabled(
+                        cue.sound,
+                        egui::Slider::new(&mut cue.volume, 0.0..=1.0).text("Volume"),
+                    );
+                    // Save once the slider is let go, not on every step
+                    changed |= volume.drag_stopped() || (volume.changed() && !volume.dragged());
+                    changed |= ui.checkbox(&mut cue.flash, "Flash panes").changed();
+                    if ui
+                        .add_enabled(cue.is_enabled(), egui::Button::new("Test"))
+                        .clicked()
+                    {
+                        test = Some(kind);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(kind) = test {
+            self.notify(kind, None);
+        }
+        if changed {
+            self.save_error = self
+                .settings
+                .save(data_paths)
+                .err()
+                .map(|e| format!("Not saved: {:#}", e));
+        }
+        if let Some(error) = &self.save_error {
+            ui.colored_label(Color32::from_rgb(255, 165, 0), error);
+        }
+    }
+}
+
+/// Plays tones on an audio thread started on first use
+#[derive(Default)]
+struct SoundPlayer {
+    tx: Option<mpsc::Sender<(NotificationKind, f32)>>,
+}
+
+impl SoundPlayer {
+    fn play(&mut self, kind: NotificationKind, volume: f32) {
+        if self.tx.is_none() {
+            self.tx = spawn_audio_thread();
+        }
+        if let Some(tx) = &self.tx {
+            if tx.send((kind, volume)).is_err() {
+                self.tx = None;
+            }
+        }
+    }
+}
+
+/// Audio output lives on its own thread: the output stream can't cross threads
+#[cfg(feature = "sound")]
+fn spawn_audio_thread() -> Option<mpsc::Sender<(NotificationKind, f32)>> {
+    use rodio::source::{SineWave, Source, Zero};
+
+    let (tx, rx) = mpsc::channel::<(NotificationKind, f32)>();
+    let spawned = std::thread::Builder::new()
+        .name("notification-sound".to_string())
+        .spawn(move || {
+            let (_stream, handle) = match rodio::OutputStream::try_default() {
+                Ok(output) => output,
+                Err(e) => {
+                    warn!("No audio output, notification sounds are off: {}", e);
+                    return;
+                }
+            };
+            for (kind, volume) in rx {
+                // Rising pair for a fill, three short beeps for an alert
+                let tones: &[(f32, u64)] = match kind {
+                    NotificationKind::Fill => &[(880.0, 90), (1320.0, 140)],
+                    NotificationKind::Alert => {
+                        &[(660.0, 80), (0.0, 60), (660.0, 80), (0.0, 60), (660.0, 80)]
+                    }
+                };
+                let sink = match rodio::Sink::try_new(&handle) {
+                    Ok(sink) => sink,
+                    Err(e) => {
+                        warn!("Failed to play notification sound: {}", e);
+                        continue;
+                    }
+                };
+                sink.set_volume(volume.clamp(0.0, 1.0));
+                for &(frequency, millis) in tones {
+                    let duration = Duration::from_millis(millis);
+                    if frequency > 0.0 {
+                        sink.append(
+                            SineWave::new(frequency)
+                                .take_duration(duration)
+                                .amplify(0.4),
+                        );
+                    } else {
+                        sink.append(Zero::<f32>::new(1, 48_000).take_duration(duration));
+                    }
+                }
+                sink.detach();
+            }
+        });
+    match spawned {
+        Ok(_) => Some(tx),
+        Err(e) => {
+            warn!("Failed to start the notification sound thread: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "sound"))]
+fn spawn_audio_thread() -> Option<mpsc::Sender<(NotificationKind, f32)>> {
+    debug!("Built without the sound feature, notification sounds are off");
+    None
+}
+
+/// New trades of the account, polled from its activity feed in the background
+pub struct FillWatcher {
+    rx: mpsc::Receiver<ActivityEvent>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl FillWatcher {
+    /// Poll `address`; `ctx` is woken when fills arrive
+    pub fn start(address: String, ctx: egui::Context) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let task = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut tracker = ActivityTracker::default();
+            let mut primed = false;
+            let mut interval = tokio::time::interval(FILL_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                let events = match fetch_activity(&client, &address, FILL_FETCH_LIMIT).await {
+                    Ok(events) => events,
+                    Err(e) => {
+                        debug!("Fill poll failed: {:#}", e);
+                        continue;
+                    }
+                };
+                let new_events = tracker.new_events(events);
+                // Trades from before the canvas started aren't news
+                if !primed {
+                    primed = true;
+                    continue;
+                }
+                let mut woke = false;
+                for event in new_events.into_iter().filter(|e| e.kind == "TRADE") {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                    woke = true;
+                }
+                if woke {
+                    ctx.request_repaint();
+                }
+            }
+        });
+        Self { rx, task }
+    }
+
+    /// Fills found since the last call
+    pub fn poll(&self) -> Vec<ActivityEvent> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for FillWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// An alert to raise
+struct RaiseRequest {
+    severity: Severity,
+    message: String,
+    token_id: Option<String>,
+}
+
+/// Alerts routed to a toast, shown apart from the screenshot and fill messages
+/// so neither replaces the other
+#[derive(Default)]
+pub struct AlertToast {
+    lines: Vec<String>,
+    shown_at: Option<Instant>,
+}
+
+impl AlertToast {
+    /// Add alerts to the toast, restarting its timer
+    pub fn push(&mut self, messages: impl IntoIterator<Item = String>) {
+        if self.shown_at.is_none() {
+            self.lines.clear();
+        }
+        let before = self.lines.len();
+        self.lines.extend(messages);
+        if self.lines.len() > before {
+            self.shown_at = Some(Instant::now());
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Some(shown_at) = self.shown_at else {
+            return;
+        };
+        if shown_at.elapsed() >= ALERT_TOAST_DURATION {
+            self.shown_at = None;
+            return;
+        }
+        egui::Window::new("Alert")
+            .anchor(egui::Align2::RIGHT_TOP, [-10.0, 40.0])
+            .collapsible(false)
+            .resizable(false)
+            .frame(egui::Frame::popup(&ctx.style()))
+            .show(ctx, |ui| {
+                for line in &self.lines {
+                    ui.label(line);
+                }
+            });
+        ctx.request_repaint_after(ALERT_TOAST_DURATION - shown_at.elapsed());
+    }
+}
+
+/// Raises alerts through their routing and follows up on open ones in the background
+pub struct AlertWatcher {
+    routing: AlertRouting,
+    store: AlertStore,
+    raise_tx: tokio::sync::mpsc::UnboundedSender<RaiseRequest>,
+    sent_rx: mpsc::Receiver<Alert>,
+    task: tokio::task::JoinHandle<()>,
+    /// Open alerts as last read, for the notifications center
+    open: Vec<Alert>,
+    open_read_at: Option<Instant>,
+}
+
+impl AlertWatcher {
+    /// Start following up; `ctx` is woken when a repeat or escalation is sent
+    pub fn start(data_paths: &DataPaths, ctx: egui::Context) -> Result<Self> {
+        let router = AlertRouter::new(data_paths)?;
+        let routing = router.routing().clone();
+        let (raise_tx, mut raise_rx) = tokio::sync::mpsc::unbounded_channel::<RaiseRequest>();
+        let (sent_tx, sent_rx) = mpsc::channel();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ALERT_FOLLOW_UP_INTERVAL);
+            loop {
+                tokio::select! {
+                    request = raise_rx.recv() => {
+                        let Some(request) = request else {
+                            return;
+                        };
+                        let raised = router.raise(
+                            request.severity,
+                            "token_alert",
+                            request.message,
+                            request.token_id,
+                        );
+                        if let Err(e) = raised.await {
+                            warn!("Failed to raise alert: {:#}", e);
+                        }
+                    }
+                    _ = interval.tick() => {
+                        let sent = match router.follow_up(Utc::now()).await {
+                            Ok(sent) => sent,
+                            Err(e) => {
+                                warn!("Failed to follow up on alerts: {:#}", e);
+                                continue;
+                            }
+                        };
+                        for alert in &sent {
+                            if sent_tx.send(alert.clone()).is_err() {
+                                return;
+                            }
+                        }
+                        if !sent.is_empty() {
+                            ctx.request_repaint();
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            routing,
+            store: AlertStore::new(data_paths),
+            raise_tx,
+            sent_rx,
+            task,
+            open: Vec::new(),
+            open_read_at: None,
+        })
+    }
+
+    /// Record and route a token alert
+    pub fn raise(&mut self, severity: Severity, message: String, token_id: Option<String>) {
+        let request = RaiseRequest {
+            severity,
+            message,
+            token_id,
+        };
+        if self.raise_tx.send(request).is_err() {
+            warn!("Alert routing stopped, alert not raised");
+        }
+        self.open_read_at = None;
+    }
+
+    /// Whether alerts of `severity` show as a toast
+    pub fn toasts(&self, severity: Severity) -> bool {
+        self.routing.toasts(severity)
+    }
+
+    /// Repeats and escalations sent since the last call
+    pub fn poll(&mut self) -> Vec<Alert> {
+        let sent: Vec<Alert> = self.sent_rx.try_iter().collect();
+        if !sent.is_empty() {
+            self.open_read_at = None;
+        }
+        sent
+    }
+
+    /// Open alerts with a button to acknowledge each, or all of them
+    pub fn open_alerts_ui(&mut self, ui: &mut egui::Ui) {
+        if self
+            .open_read_at
+            .is_none_or(|read_at| read_at.elapsed() > OPEN_ALERTS_REFRESH)
+        {
+            self.open = match self.store.load() {
+                Ok(alerts) => alerts.into_iter().filter(Alert::is_open).collect(),
+                Err(e) => {
+                    debug!("Failed to read alerts: {:#}", e);
+                    Vec::new()
+                }
+            };
+            self.open_read_at = Some(Instant::now());
+        }
+        if self.open.is_empty() {
+            return;
+        }
+
+        let mut acknowledge = None;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(format!("{} open alerts", self.open.len())).strong());
+            if ui.button("Acknowledge all").clicked() {
+                acknowledge = Some(None);
+            }
+        });
+        for alert in &self.open {
+            ui.horizontal(|ui| {
+                ui.label(alert.severity.icon());
+                ui.label(
+                    egui::RichText::new(
+                        alert
+                            .raised_at
+                            .with_timezone(&Local)
+                            .format("%H:%M:%S")
+                            .to_string(),
+                    )
+                    .monospace()
+                    .weak(),
+                );
+                ui.label(&alert.message);
+                if ui.small_button("Ack").clicked() {
+                    acknowledge = Some(Some(alert.id.clone()));
+                }
+            });
+        }
+        if let Some(id) = acknowledge {
+            if let Err(e) = self.store.acknowledge(id.as_deref(), "gui", Utc::now()) {
+                warn!("Failed to acknowledge alerts: {:#}", e);
+            }
+            self.open_read_at = None;
+        }
+        ui.separator();
+    }
+}
+
+impl Drop for AlertWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flashes_fade_on_the_panes_of_their_event() {
+        let mut notifier = Notifier {
+            settings: NotificationSettings {
+                fill: Cue {
+                    sound: false,
+                    ..Cue::default()
+                },
+                alert: Cue {
+                    sound: false,
+                    flash: false,
+                    ..Cue::default()
+                },
+            },
+            sound: SoundPlayer::default(),
+            flashes: Vec::new(),
+            history: VecDeque::new(),
+            unread: 0,
+            save_error: None,
+        };
+        notifier.record(
+            NotificationKind::Fill,
+            Some("123"),
+            "Bought".to_string(),
+            None,
+        );
+        notifier.record(
+            NotificationKind::Alert,
+            Some("456"),
+            "Above 0.6".to_string(),
+            None,
+        );
+        assert_eq!(notifier.unread(), 2);
+        assert_eq!(notifier.history[0].message, "Above 0.6");
+        let now = Instant::now();
+
+        assert!(notifier.flash_color(&Pane::Orders, now).is_some());
+        assert!(notifier
+            .flash_color(&Pane::MarketDepth(Some("123".to_string())), now)
+            .is_some());
+        assert!(notifier
+            .flash_color(&Pane::MarketDepth(Some("456".to_string())), now)
+            .is_none());
+        assert!(notifier.flash_color(&Pane::Tokens, now).is_none());
+
+        let later = now + FLASH_DURATION;
+        assert!(notifier.flash_color(&Pane::Orders, later).is_none());
+        assert!(!notifier.update_flashes(later));
+
+        let yaml = serde_yaml::to_string(&notifier.settings).unwrap();
+        let parsed: NotificationSettings = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, notifier.settings);
+        let partial: NotificationSettings = serde_yaml::from_str("fill:\n  volume: 0.2\n").unwrap();
+        assert_eq!(partial.fill.volume, 0.2);
+        assert!(partial.fill.sound && partial.alert.flash);
+    }
+}
@@ -0,0 +1,640 @@
+//! First-run onboarding wizard
+//!
+//! Shown instead of the empty canvas while the data directory has neither
+//! credentials nor a finished (or skipped) onboarding. It walks through the
+//! data directory, connecting a wallet (private key, Ledger or a read-only
+//! address, or none for public data only), fetching a snapshot of the most
+//! traded markets (less those `suppression.yaml` rules out) into the
+//! `starter_markets` dataset, and saving some of them as a starter selection.
+//! Finishing or skipping writes `onboarding.json` so the wizard doesn't come
+//! back.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use egui::{Color32, RichText};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::auth::SignerBackend;
+use crate::data_paths::DataPaths;
+use crate::gui::components::dataset_preview::{format_volume, market_token_ids};
+use crate::markets::datasets::from_search::dataset_market;
+use crate::markets::datasets::SelectionManager;
+use crate::markets::gamma::{GammaClient, MarketOrder, MarketQuery};
+use crate::markets::suppression::SuppressionConfig;
+
+pub const ONBOARDING_FILE: &str = "onboarding.json";
+
+/// Dataset the market snapshot is written to
+pub const STARTER_DATASET: &str = "starter_markets";
+
+/// Markets in the snapshot, by 24h volume
+const SNAPSHOT_MARKETS: u32 = 50;
+
+/// Markets checked for the starter selection by default
+const DEFAULT_PICKS: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OnboardingRecord {
+    completed_at: DateTime<Utc>,
+    skipped: bool,
+}
+
+/// Whether `data_paths` still needs onboarding: no record of it and no
+/// credentials or signer configured
+pub fn is_needed(data_paths: &DataPaths) -> bool {
+    if data_paths.root().join(ONBOARDING_FILE).exists() {
+        return false;
+    }
+    let configured = std::fs::read_dir(data_paths.auth())
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    !configured
+}
+
+fn record(data_paths: &DataPaths, skipped: bool) -> Result<()> {
+    std::fs::create_dir_all(data_paths.root())?;
+    let record = OnboardingRecord {
+        completed_at: Utc::now(),
+        skipped,
+    };
+    std::fs::write(
+        data_paths.root().join(ONBOARDING_FILE),
+        serde_json::to_string_pretty(&record)?,
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Welcome,
+    DataDir,
+    Wallet,
+    Snapshot,
+    Watchlist,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WalletMode {
+    PrivateKey,
+    Ledger,
+    ReadOnly,
+    None,
+}
+
+enum TaskResult {
+    Wallet(Result<String, String>),
+    Snapshot(Result<Vec<Value>, String>),
+}
+
+/// What the app should do after a frame of the wizard
+pub enum WizardOutcome {
+    Continue,
+    /// Leave the wizard and open the canvas on these data paths
+    Finished {
+        data_paths: DataPaths,
+        starter_dataset: bool,
+    },
+}
+
+pub struct OnboardingWizard {
+    host: String,
+    /// Directory the canvas was launched with
+    launched_with: DataPaths,
+    data_paths: DataPaths,
+    step: Step,
+    data_dir: String,
+    mode: WalletMode,
+    private_key: String,
+    passphrase: String,
+    derivation_path: String,
+    watch_address: String,
+    /// Address of the connected or watched wallet
+    account: Option<String>,
+    markets: Vec<Value>,
+    picked: Vec<bool>,
+    selection_name: String,
+    selection_saved: bool,
+    task: Option<mpsc::Receiver<TaskResult>>,
+    message: Option<Result<String, String>>,
+}
+
+impl OnboardingWizard {
+    pub fn new(host: &str, data_paths: &DataPaths) -> Self {
+        Self {
+            host: host.to_string(),
+            launched_with: data_paths.clone(),
+            data_paths: data_paths.clone(),
+            step: Step::Welcome,
+            data_dir: data_paths.root().display().to_string(),
+            mode: WalletMode::PrivateKey,
+            private_key: String::new(),
+            passphrase: String::new(),
+            derivation_path: String::new(),
+            watch_address: String::new(),
+            account: None,
+            markets: Vec::new(),
+            picked: Vec::new(),
+            selection_name: "starter".to_string(),
+            selection_saved: false,
+            task: None,
+            message: None,
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) -> WizardOutcome {
+        self.poll_task();
+        if self.task.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        let mut outcome = WizardOutcome::Continue;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.0);
+                ui.heading("👋 Welcome to Polybot");
+                ui.label(self.progress_label());
+                ui.add_space(16.0);
+            });
+            egui::Frame::group(ui.style())
+                .inner_margin(egui::Margin::same(16))
+                .show(ui, |ui| {
+                    ui.set_max_width(620.0);
+                    match self.step {
+                        Step::Welcome => self.welcome(ui, &mut outcome),
+                        Step::DataDir => self.data_dir(ui),
+                        Step::Wallet => self.wallet(ui),
+                        Step::Snapshot => self.snapshot(ui),
+                        Step::Watchlist => self.watchlist(ui),
+                        Step::Done => self.done(ui, &mut outcome),
+                    }
+                    if let Some(message) = &self.message {
+                        ui.add_space(8.0);
+                        match message {
+                            Ok(text) => ui.colored_label(Color32::from_rgb(100, 200, 100), text),
+                            Err(text) => ui.colored_label(Color32::from_rgb(220, 80, 80), text),
+                        };
+                    }
+                });
+        });
+        outcome
+    }
+
+    fn progress_label(&self) -> String {
+        let steps = [
+            (Step::DataDir, "Data"),
+            (Step::Wallet, "Wallet"),
+            (Step::Snapshot, "Markets"),
+            (Step::Watchlist, "Watchlist"),
+        ];
+        steps
+            .iter()
+            .map(|(step, label)| {
+                if *step == self.step {
+                    format!("[{}]", label)
+                } else {
+                    label.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  →  ")
+    }
+
+    fn go(&mut self, step: Step) {
+        self.step = step;
+        self.message = None;
+    }
+
+    fn welcome(&mut self, ui: &mut egui::Ui, outcome: &mut WizardOutcome) {
+        ui.label(
+            "This looks like a first run. A few steps get the canvas ready: where data is \
+             kept, which wallet to use, and a first set of markets to stream.",
+        );
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            if ui.button("Get started →").clicked() {
+                self.go(Step::DataDir);
+            }
+            if ui
+                .button("Skip setup")
+                .on_hover_text("Open the empty canvas; run 'polybot init' later")
+                .clicked()
+            {
+                *outcome = self.finish(true);
+            }
+        });
+    }
+
+    fn data_dir(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("Data directory").strong());
+        ui.label(
+            "Credentials, portfolio history, datasets and logs are kept here. Each profile \
+             has its own tree.",
+        );
+        ui.add(egui::TextEdit::singleline(&mut self.data_dir).desired_width(f32::INFINITY));
+        if self.data_dir.trim() != self.launched_with.root().display().to_string() {
+            ui.label(
+                RichText::new(format!(
+                    "Later launches need: polybot --data-dir {} canvas",
+                    self.data_dir.trim()
+                ))
+                .small(),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("← Back").clicked() {
+                self.go(Step::Welcome);
+            }
+            if ui.button("Use this directory →").clicked() {
+                let root = PathBuf::from(self.data_dir.trim());
+                let data_paths = if root == *self.launched_with.root() {
+                    self.launched_with.clone()
+                } else {
+                    DataPaths::new(&root)
+                };
+                match data_paths.ensure_directories() {
+                    Ok(()) => {
+                        self.data_paths = data_paths;
+                        self.go(Step::Wallet);
+                    }
+                    Err(e) => {
+                        self.message = Some(Err(format!("Cannot use {}: {}", root.display(), e)))
+                    }
+                }
+            }
+        });
+    }
+
+    fn wallet(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("Wallet").strong());
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.mode, WalletMode::PrivateKey, "Private key");
+            ui.selectable_value(&mut self.mode, WalletMode::Ledger, "Ledger");
+            ui.selectable_value(&mut self.mode, WalletMode::ReadOnly, "Read-only");
+            ui.selectable_value(&mut self.mode, WalletMode::None, "No wallet");
+        });
+        ui.add_space(4.0);
+        let busy = self.task.is_some();
+        match self.mode {
+            WalletMode::PrivateKey => {
+                ui.label("API keys are derived from the key; both are stored encrypted.");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.private_key)
+                        .password(true)
+                        .hint_text("Private key (hex)"),
+                );
+                self.passphrase_field(ui);
+            }
+            WalletMode::Ledger => {
+                ui.label("Approve the authentication message on the device when asked.");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.derivation_path)
+                        .hint_text("Derivation path (default m/44'/60'/0'/0/0)"),
+                );
+                self.passphrase_field(ui);
+            }
+            WalletMode::ReadOnly => {
+                ui.label("Monitor an address without a key; orders and transfers are disabled.");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.watch_address)
+                        .hint_text("0x address to watch"),
+                );
+            }
+            WalletMode::None => {
+                ui.label(
+                    "Browse and stream public market data only. Run 'polybot init' later \
+                     to trade.",
+                );
+            }
+        }
+        if let Some(account) = &self.account {
+            ui.colored_label(
+                Color32::from_rgb(100, 200, 100),
+                format!("✅ Wallet {}", account),
+            );
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("← Back").clicked() {
+                self.go(Step::DataDir);
+            }
+            if self.mode == WalletMode::None || self.account.is_some() {
+                if ui.button("Next →").clicked() {
+                    self.go(Step::Snapshot);
+                }
+            } else if busy {
+                ui.spinner();
+            } else if ui.button("Connect").clicked() {
+                self.connect_wallet();
+            }
+        });
+    }
+
+    fn passphrase_field(&mut self, ui: &mut egui::Ui) {
+        ui.add(
+            egui::TextEdit::singleline(&mut self.passphrase)
+                .password(true)
+                .hint_text("Passphrase to encrypt credentials"),
+        )
+        .on_hover_text("Used for this session; set POLYBOT_PASSPHRASE for later runs");
+    }
+
+    fn connect_wallet(&mut self) {
+        let mode = self.mode;
+        if matches!(mode, WalletMode::PrivateKey | WalletMode::Ledger) {
+            if self.passphrase.is_empty() {
+                self.message = Some(Err("Enter a passphrase for the credentials".to_string()));
+                return;
+            }
+            // The credential store reads it instead of prompting on the terminal
+            std::env::set_var("POLYBOT_PASSPHRASE", &self.passphrase);
+        }
+        let host = self.host.clone();
+        let data_paths = self.data_paths.clone();
+        let private_key = self.private_key.trim().to_string();
+        let derivation_path =
+            Some(self.derivation_path.trim().to_string()).filter(|p| !p.is_empty());
+        let watch_address = self.watch_address.trim().to_string();
+
+        let (tx, rx) = mpsc::channel();
+        self.task = Some(rx);
+        self.message = None;
+        tokio::spawn(async move {
+            let result: Result<String> = async {
+                match mode {
+                    WalletMode::PrivateKey => {
+                        crate::auth::init_auth(&host, &data_paths, &private_key, 0).await?;
+                        crate::ethereum_utils::derive_address_from_private_key(&private_key)
+                    }
+                    WalletMode::Ledger => {
+                        let backend = SignerBackend::ledger(derivation_path, None);
+                        let signer =
+                            crate::auth::init_auth_with_signer(&host, &data_paths, backend, 0)
+                                .await?;
+                        Ok(signer.address_hex())
+                    }
+                    WalletMode::ReadOnly => {
+                        let backend = SignerBackend::read_only(&watch_address)?;
+                        backend.save(&data_paths)?;
+                        crate::auth::access::set_read_only(true);
+                        Ok(watch_address.to_lowercase())
+                    }
+                    WalletMode::None => Ok(String::new()),
+                }
+            }
+            .await;
+            match &result {
+                Ok(address) => info!("Onboarding wallet set up: {}", address),
+                Err(e) => warn!("Onboarding wallet setup failed: {}", e),
+            }
+            let _ = tx.send(TaskResult::Wallet(result.map_err(|e| e.to_string())));
+        });
+    }
+
+    fn snapshot(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("Market snapshot").strong());
+        ui.label(format!(
+            "Fetch the {} markets with the most 24h volume into the '{}' dataset, ready to \
+             stream.",
+            SNAPSHOT_MARKETS, STARTER_DATASET
+        ));
+        if !self.markets.is_empty() {
+            ui.colored_label(
+                Color32::from_rgb(100, 200, 100),
+                format!("✅ {} markets saved", self.markets.len()),
+            );
+        }
+        ui.horizontal(|ui| {
+            if ui.button("← Back").clicked() {
+                self.go(Step::Wallet);
+            }
+            if self.task.is_some() {
+                ui.spinner();
+                ui.label("Fetching markets...");
+            } else if self.markets.is_empty() {
+                if ui.button("Fetch markets").clicked() {
+                    self.fetch_snapshot();
+                }
+                if ui.button("Skip").clicked() {
+                    self.go(Step::Done);
+                }
+            } else if ui.button("Next →").clicked() {
+                self.go(Step::Watchlist);
+            }
+        });
+    }
+
+    fn fetch_snapshot(&mut self) {
+        let dataset_dir = self.data_paths.datasets().join(STARTER_DATASET);
+        let suppression = SuppressionConfig::load(&self.data_paths).unwrap_or_else(|e| {
+            warn!("Ignoring suppression list: {:#}", e);
+            SuppressionConfig::default()
+        });
+        let (tx, rx) = mpsc::channel();
+        self.task = Some(rx);
+        self.message = None;
+        tokio::spawn(async move {
+            let result = fetch_starter_markets(&dataset_dir, &suppression).await;
+            if let Err(e) = &result {
+                warn!("Onboarding market snapshot failed: {}", e);
+            }
+            let _ = tx.send(TaskResult::Snapshot(result.map_err(|e| e.to_string())));
+        });
+    }
+
+    fn watchlist(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("Starter watchlist").strong());
+        ui.label("Save the checked markets as a selection to stream from the CLI or TUI.");
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut self.selection_name);
+        });
+        egui::ScrollArea::vertical()
+            .max_height(320.0)
+            .show(ui, |ui| {
+                for (market, picked) in self.markets.iter().zip(self.picked.iter_mut()) {
+                    let question = market
+                        .get("question")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("?");
+                    let volume = market.get("volume").and_then(|v| v.as_f64());
+                    ui.checkbox(picked, format!("{}  {}", format_volume(volume), question));
+                }
+            });
+        let count = self.picked.iter().filter(|p| **p).count();
+        ui.horizontal(|ui| {
+            if ui.button("← Back").clicked() {
+                self.go(Step::Snapshot);
+            }
+            let save = ui.add_enabled(
+                count > 0 && !self.selection_name.trim().is_empty(),
+                egui::Button::new(format!("Save {} markets →", count)),
+            );
+            if save.clicked() {
+                match self.save_selection() {
+                    Ok(tokens) => {
+                        self.selection_saved = true;
+                        self.go(Step::Done);
+                        self.message = Some(Ok(format!(
+                            "Saved selection '{}' ({} tokens)",
+                            self.selection_name.trim(),
+                            tokens
+                        )));
+                    }
+                    Err(e) => self.message = Some(Err(format!("Failed to save selection: {}", e))),
+                }
+            }
+            if ui.button("Skip").clicked() {
+                self.go(Step::Done);
+            }
+        });
+    }
+
+    /// Save the picked markets' tokens as a selection; returns the token count
+    fn save_selection(&self) -> Result<usize> {
+        let tokens: Vec<String> = self
+            .markets
+            .iter()
+            .zip(&self.picked)
+            .filter(|(_, picked)| **picked)
+            .flat_map(|(market, _)| market_token_ids(market))
+            .collect();
+        let manager = SelectionManager::new(self.data_paths.root());
+        manager.ensure_directory()?;
+        let selection = manager.create_selection(
+            self.selection_name.trim().to_string(),
+            Some("Starter watchlist from onboarding".to_string()),
+            tokens.clone(),
+        )?;
+        manager.save_selection(&selection)?;
+        Ok(tokens.len())
+    }
+
+    fn done(&mut self, ui: &mut egui::Ui, outcome: &mut WizardOutcome) {
+        ui.label(RichText::new("All set").strong());
+        ui.label(format!(
+            "Data directory: {}",
+            self.data_paths.root().display()
+        ));
+        match &self.account {
+            Some(account) => ui.label(format!("Wallet: {}", account)),
+            None => ui.label("Wallet: none (public data only)"),
+        };
+        if !self.markets.is_empty() {
+            ui.label(format!(
+                "Dataset '{}' is preselected; press Start Streaming in the sidebar.",
+                STARTER_DATASET
+            ));
+        }
+        if self.selection_saved {
+            ui.label(format!(
+                "Stream the watchlist from a terminal with: polybot stream --selection {}",
+                self.selection_name.trim()
+            ));
+        }
+        ui.add_space(8.0);
+        if ui.button("Open the canvas").clicked() {
+            *outcome = self.finish(false);
+        }
+    }
+
+    fn finish(&self, skipped: bool) -> WizardOutcome {
+        // Both directories, so neither shows the wizard again
+        for data_paths in [&self.launched_with, &self.data_paths] {
+            if let Err(e) = record(data_paths, skipped) {
+                warn!("Failed to record onboarding: {}", e);
+            }
+        }
+        WizardOutcome::Finished {
+            data_paths: self.data_paths.clone(),
+            starter_dataset: !self.markets.is_empty(),
+        }
+    }
+
+    fn poll_task(&mut self) {
+        let Some(rx) = &self.task else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.task = None;
+                self.message = Some(Err("Setup task ended unexpectedly".to_string()));
+                return;
+            }
+        };
+        self.task = None;
+        match result {
+            TaskResult::Wallet(Ok(address)) => {
+                self.private_key.clear();
+                self.account = Some(address);
+            }
+            TaskResult::Snapshot(Ok(markets)) => {
+                self.picked = (0..markets.len()).map(|i| i < DEFAULT_PICKS).collect();
+                self.markets = markets;
+            }
+            TaskResult::Wallet(Err(e)) | TaskResult::Snapshot(Err(e)) => {
+                self.message = Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Fetch the most traded open markets `suppression` keeps and write them to
+/// `dataset_dir/markets.json` in the CLOB layout datasets use
+async fn fetch_starter_markets(
+    dataset_dir: &std::path::Path,
+    suppression: &SuppressionConfig,
+) -> Result<Vec<Value>> {
+    let query = MarketQuery::builder()
+        .active(true)
+        .closed(false)
+        .order(MarketOrder::Volume24hr)
+        .ascending(false)
+        .limit(SNAPSHOT_MARKETS)
+        .build()?;
+    let page = GammaClient::new().fetch_markets(&query).await?;
+    let markets: Vec<Value> = page
+        .data
+        .iter()
+        .filter(|market| market.accepting_orders && !market.clob_token_ids.is_empty())
+        .filter(|market| suppression.keeps(*market))
+        .map(dataset_market)
+        .collect();
+    if markets.is_empty() {
+        anyhow::bail!("No open markets returned");
+    }
+    std::fs::create_dir_all(dataset_dir)?;
+    std::fs::write(
+        dataset_dir.join("markets.json"),
+        serde_json::to_string_pretty(&markets)?,
+    )?;
+    Ok(markets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn onboarding_is_needed_until_recorded_or_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        assert!(is_needed(&data_paths));
+        record(&data_paths, true).unwrap();
+        assert!(!is_needed(&data_paths));
+
+        let other = tempfile::tempdir().unwrap();
+        let configured = DataPaths::new(other.path());
+        SignerBackend::read_only("0x0000000000000000000000000000000000000001")
+            .unwrap()
+            .save(&configured)
+            .unwrap();
+        assert!(!is_needed(&configured));
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::gui::subscriptions::Subscription;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Pane {
     /// Orders management pane
@@ -10,10 +12,14 @@ pub enum Pane {
     Streams,
     /// Portfolio overview
     Portfolio,
+    /// Portfolio equity over time from the P&L history
+    PortfolioValue,
     /// Token information and selection
     Tokens,
     /// Market depth and order book for a specific token
     MarketDepth(Option<String>),
+    /// Both outcome tokens of each streamed market with ask/bid sums
+    CrossRates,
     /// Price charts and analysis
     Charts,
     /// Trade history
@@ -24,6 +30,8 @@ pub enum Pane {
     WebSocketManager,
     /// Individual worker details and event stream
     WorkerDetails(usize),
+    /// Strategies hosted by the daemon, with parameter editors
+    Strategies,
 }
 
 impl Pane {
@@ -32,6 +40,7 @@ impl Pane {
             Pane::Orders => format!("{} Orders", self.icon()),
             Pane::Streams => format!("{} Market Streams", self.icon()),
             Pane::Portfolio => format!("{} Portfolio", self.icon()),
+            Pane::PortfolioValue => format!("{} Portfolio Value", self.icon()),
             Pane::Tokens => format!("{} Tokens", self.icon()),
             Pane::MarketDepth(token_id) => {
                 if let Some(id) = token_id {
@@ -46,6 +55,7 @@ impl Pane {
                     format!("{} Market Depth", self.icon())
                 }
             }
+            Pane::CrossRates => format!("{} Cross Rates", self.icon()),
             Pane::Charts => format!("{} Charts", self.icon()),
             Pane::TradeHistory => format!("{} Trade History", self.icon()),
             Pane::Balances => format!("{} Balances", self.icon()),
@@ -53,6 +63,7 @@ impl Pane {
             Pane::WorkerDetails(worker_id) => {
                 format!("{} Worker #{} Details", self.icon(), worker_id)
             }
+            Pane::Strategies => format!("{} Strategies", self.icon()),
         }
     }
 
@@ -61,13 +72,30 @@ impl Pane {
             Pane::Orders => "📋",
             Pane::Streams => "📡",
             Pane::Portfolio => "💼",
+            Pane::PortfolioValue => "💹",
             Pane::Tokens => "🪙",
             Pane::MarketDepth(_) => "📊",
+            Pane::CrossRates => "⚖",
             Pane::Charts => "📈",
             Pane::TradeHistory => "📜",
             Pane::Balances => "💰",
             Pane::WebSocketManager => "🔌",
             Pane::WorkerDetails(_) => "👷",
+            Pane::Strategies => "🎛",
+        }
+    }
+
+    /// Data this pane needs from the streaming service
+    ///
+    /// The background data task only fetches what some open pane subscribes to.
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        match self {
+            Pane::MarketDepth(Some(token_id)) => vec![Subscription::Token(token_id.clone())],
+            Pane::CrossRates => vec![Subscription::CrossRates],
+            Pane::Streams | Pane::WebSocketManager | Pane::WorkerDetails(_) => {
+                vec![Subscription::StreamStats]
+            }
+            _ => Vec::new(),
         }
     }
 
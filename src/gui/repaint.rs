@@ -0,0 +1,227 @@
+//! Adaptive repaint scheduling for the trading canvas
+//!
+//! egui repaints the whole window, so a busy market feed that asks for a
+//! repaint per event keeps a core busy re-rendering panes nobody is looking
+//! at. Instead, streaming updates mark the panes they affect as dirty and
+//! [`RepaintScheduler`] works out when the next frame is due: a dirty visible
+//! pane is repainted at most `max_fps` times per second, dirty hidden panes
+//! wait until they are shown, and an idle heartbeat keeps the feed drained.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Default per-pane repaint cap while market data streams
+pub const DEFAULT_MAX_FPS: u32 = 30;
+
+/// Frame interval when streaming with nothing visible to redraw
+const IDLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Window over which [`FrameStats`] averages
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Decides when the next frame is needed from which panes have new data
+#[derive(Debug)]
+pub struct RepaintScheduler<K> {
+    min_interval: Duration,
+    /// Panes with updates not yet on screen, with when the latest one arrived
+    dirty: HashMap<K, Instant>,
+    last_painted: HashMap<K, Instant>,
+}
+
+impl<K: Copy + Eq + Hash> RepaintScheduler<K> {
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            min_interval: Duration::from_secs(1) / max_fps.max(1),
+            dirty: HashMap::new(),
+            last_painted: HashMap::new(),
+        }
+    }
+
+    pub fn max_fps(&self) -> u32 {
+        (Duration::from_secs(1).as_nanos() / self.min_interval.as_nanos().max(1)) as u32
+    }
+
+    /// Record that `pane` has data newer than what is on screen
+    pub fn mark_dirty(&mut self, pane: K, now: Instant) {
+        self.dirty.insert(pane, now);
+    }
+
+    /// Number of panes waiting for a repaint, visible or not
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// A frame that started at `started` and showed `visible` was just drawn
+    ///
+    /// Only marks older than the frame are cleared: data that arrived while
+    /// the frame was being built may not be on screen yet, so those panes stay
+    /// dirty and get the next frame their repaint cap allows.
+    pub fn frame_painted(&mut self, visible: &HashSet<K>, started: Instant, now: Instant) {
+        self.dirty
+            .retain(|pane, marked| !visible.contains(pane) || *marked >= started);
+        for pane in visible {
+            self.last_painted.insert(*pane, now);
+        }
+        // Closed panes never paint again; keep the map to the live ones
+        self.last_painted.retain(|pane, _| visible.contains(pane));
+    }
+
+    /// Delay until the next frame is due, `None` when nothing visible is dirty
+    ///
+    /// Updates to a pane inside its `1 / max_fps` window are coalesced into
+    /// the one repaint at the end of the window.
+    pub fn next_repaint(&self, visible: &HashSet<K>, now: Instant) -> Option<Duration> {
+        self.dirty
+            .keys()
+            .filter(|pane| visible.contains(pane))
+            .map(|pane| match self.last_painted.get(pane) {
+                Some(last) => (*last + self.min_interval).saturating_duration_since(now),
+                None => Duration::ZERO,
+            })
+            .min()
+    }
+
+    /// Delay to request while streaming: the next due pane, else the heartbeat
+    pub fn streaming_delay(&self, visible: &HashSet<K>, now: Instant) -> Duration {
+        self.next_repaint(visible, now)
+            .map_or(IDLE_INTERVAL, |delay| delay.min(IDLE_INTERVAL))
+    }
+}
+
+/// One frame as seen by [`FrameStats`]
+#[derive(Debug, Clone, Copy)]
+struct FrameSample {
+    started: Instant,
+    /// Time spent in `update()`
+    busy: Duration,
+}
+
+/// Rolling frame rate and UI-thread time for the performance overlay
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    /// Every frame in the window, oldest first
+    frames: VecDeque<FrameSample>,
+}
+
+impl FrameStats {
+    pub fn record(&mut self, started: Instant, busy: Duration) {
+        self.frames.push_back(FrameSample { started, busy });
+        while let Some(oldest) = self.frames.front() {
+            if started.duration_since(oldest.started) > STATS_WINDOW {
+                self.frames.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Frames drawn over the last second
+    pub fn fps(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Mean time spent building a frame
+    pub fn mean_frame_time(&self) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::ZERO;
+        }
+        self.busy() / self.frames.len() as u32
+    }
+
+    /// Share of the last second the UI thread spent building frames
+    pub fn ui_load(&self) -> f32 {
+        self.busy().as_secs_f32() / STATS_WINDOW.as_secs_f32()
+    }
+
+    fn busy(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.busy).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_within_a_window_coalesce_into_one_repaint() {
+        let start = Instant::now();
+        let visible: HashSet<u32> = [1].into();
+        let mut scheduler = RepaintScheduler::new(10);
+        assert_eq!(scheduler.max_fps(), 10);
+        assert_eq!(scheduler.next_repaint(&visible, start), None);
+
+        scheduler.mark_dirty(1, start);
+        assert_eq!(
+            scheduler.next_repaint(&visible, start),
+            Some(Duration::ZERO)
+        );
+        scheduler.frame_painted(&visible, start, start);
+
+        // A burst right after a paint waits for the rest of the 100ms window
+        for ms in 1..50 {
+            scheduler.mark_dirty(1, start + Duration::from_millis(ms));
+        }
+        let now = start + Duration::from_millis(50);
+        assert_eq!(
+            scheduler.next_repaint(&visible, now),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(
+            scheduler.next_repaint(&visible, start + Duration::from_millis(150)),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn hidden_panes_wait_until_shown() {
+        let now = Instant::now();
+        let mut scheduler = RepaintScheduler::new(30);
+        scheduler.mark_dirty(2, now);
+
+        let only_one: HashSet<u32> = [1].into();
+        assert_eq!(scheduler.next_repaint(&only_one, now), None);
+        assert_eq!(scheduler.streaming_delay(&only_one, now), IDLE_INTERVAL);
+        scheduler.frame_painted(&only_one, now, now);
+        assert_eq!(scheduler.dirty_count(), 1);
+
+        let both: HashSet<u32> = [1, 2].into();
+        assert_eq!(scheduler.next_repaint(&both, now), Some(Duration::ZERO));
+        scheduler.frame_painted(&both, now, now);
+        assert_eq!(scheduler.dirty_count(), 0);
+    }
+
+    #[test]
+    fn updates_during_a_frame_stay_dirty() {
+        let started = Instant::now();
+        let visible: HashSet<u32> = [1, 2].into();
+        let mut scheduler = RepaintScheduler::new(10);
+        scheduler.mark_dirty(1, started - Duration::from_millis(5));
+        scheduler.mark_dirty(2, started + Duration::from_millis(1));
+
+        let painted = started + Duration::from_millis(3);
+        scheduler.frame_painted(&visible, started, painted);
+        assert_eq!(scheduler.dirty_count(), 1);
+        // The pane updated mid-frame gets the next frame its cap allows
+        assert_eq!(
+            scheduler.streaming_delay(&visible, painted),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn frame_stats_cover_the_last_second() {
+        let start = Instant::now();
+        let mut stats = FrameStats::default();
+        for i in 0..30 {
+            stats.record(
+                start + Duration::from_millis(i * 100),
+                Duration::from_millis(5),
+            );
+        }
+        // Frames 19..=29 are within a second of the last one
+        assert_eq!(stats.fps(), 11);
+        assert_eq!(stats.mean_frame_time(), Duration::from_millis(5));
+        assert!((stats.ui_load() - 0.055).abs() < 1e-6);
+    }
+}
@@ -1,25 +1,20 @@
 //! Portfolio service for managing portfolio data in GUI with local storage and HTTP refresh
 
 use anyhow::{anyhow, Result};
-use rust_decimal::Decimal;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
-use crate::config;
 use crate::data_paths::DataPaths;
-use crate::ethereum_utils;
-use crate::core::execution::orders::{EnhancedOrder, OrderManager};
-use crate::core::portfolio::{PortfolioStats, PortfolioStorage, Position};
-
-/// Placeholder for balance information - would need proper implementation
-#[derive(Debug, Clone)]
-pub struct BalanceInfo {
-    pub cash: Decimal,
-    pub bets: Decimal,
-    pub equity_total: Decimal,
-}
+use crate::core::execution::orders::{EnhancedOrder, OrderManager, OrderMarketInfo};
+use crate::core::portfolio::display::MarketLabels;
+use crate::core::portfolio::pnl_history::{PnlHistory, PnlPoint};
+use crate::core::portfolio::webhook::PortfolioWebhook;
+use crate::core::portfolio::{
+    AccountBalances, BalanceInfo, PolymarketOrder, PortfolioStats, PortfolioStorage, Position,
+    PositionReconciler,
+};
 
 /// Service for managing portfolio data in the GUI
 #[derive(Clone)]
@@ -36,11 +31,14 @@ pub struct PortfolioService {
     is_refreshing: Arc<RwLock<bool>>,
     order_manager: Arc<RwLock<Option<OrderManager>>>,
     portfolio_storage: Arc<RwLock<Option<PortfolioStorage>>>,
+    /// Endpoint recorded snapshots are sent to
+    webhook: Option<PortfolioWebhook>,
 }
 
 impl PortfolioService {
     /// Create new portfolio service
     pub fn new(host: String, data_paths: DataPaths) -> Self {
+        let webhook = PortfolioWebhook::load_for_service(&data_paths);
         Self {
             data_paths,
             host,
@@ -54,6 +52,7 @@ impl PortfolioService {
             is_refreshing: Arc::new(RwLock::new(false)),
             order_manager: Arc::new(RwLock::new(None)),
             portfolio_storage: Arc::new(RwLock::new(None)),
+            webhook,
         }
     }
 
@@ -61,13 +60,7 @@ impl PortfolioService {
     pub async fn init(&self) -> Result<()> {
         info!("Initializing portfolio service");
 
-        // Load private key to derive user address
-        let private_key = config::load_private_key(&self.data_paths)
-            .await
-            .map_err(|e| anyhow!("No private key found. Run 'cargo run -- init' first: {}", e))?;
-
-        // Derive user's Ethereum address
-        let address = ethereum_utils::derive_address_from_private_key(&private_key)?;
+        let address = crate::auth::account_address(&self.data_paths).await?;
 
         {
             let mut user_address = self.user_address.write().await;
@@ -166,11 +159,12 @@ impl PortfolioService {
             .fetch_orders(&self.host, &self.data_paths, &user_address)
             .await
         {
-            Ok(fetched_orders) => {
+            Ok(mut fetched_orders) => {
                 info!(
                     "Successfully fetched {} orders from API",
                     fetched_orders.len()
                 );
+                self.label_orders(&mut fetched_orders).await;
 
                 // Update orders in memory
                 {
@@ -179,13 +173,8 @@ impl PortfolioService {
                 }
 
                 // Convert EnhancedOrder to PolymarketOrder for storage compatibility
-                let poly_orders: Vec<crate::core::portfolio::api::orders::PolymarketOrder> =
-                    fetched_orders
-                        .iter()
-                        .map(|enhanced_order| {
-                            self.convert_enhanced_to_polymarket_order(enhanced_order)
-                        })
-                        .collect();
+                let poly_orders: Vec<PolymarketOrder> =
+                    fetched_orders.iter().map(PolymarketOrder::from).collect();
 
                 // Save orders to local storage
                 if let Err(e) = portfolio_storage.save_active_orders(&poly_orders).await {
@@ -217,16 +206,27 @@ impl PortfolioService {
                 {
                     Ok(api_balance) => {
                         info!("Successfully fetched balance from API");
-                        let balance_info = BalanceInfo {
-                            cash: api_balance.cash,
-                            bets: api_balance.bets,
-                            equity_total: api_balance.equity_total,
-                        };
-
-                        {
-                            let mut balance = self.balance.write().await;
-                            *balance = Some(balance_info);
+                        let positions = self.positions.read().await;
+                        let point = PnlPoint::new(
+                            chrono::Utc::now(),
+                            api_balance.equity_total,
+                            Some(api_balance.cash),
+                            &positions,
+                        );
+                        let history = PnlHistory::new(&self.data_paths, &user_address);
+                        match history.record(&point).await {
+                            Ok(true) => {
+                                if let Some(webhook) = &self.webhook {
+                                    let balances = AccountBalances::from(&api_balance);
+                                    webhook.post_snapshot(&user_address, &balances, &positions);
+                                }
+                            }
+                            Ok(false) => {}
+                            Err(e) => warn!("Failed to record P&L history: {}", e),
                         }
+                        drop(positions);
+                        let mut balance = self.balance.write().await;
+                        *balance = Some(api_balance);
                     }
                     Err(e) => {
                         info!("Balance API not available: {}", e);
@@ -255,14 +255,44 @@ impl PortfolioService {
         Ok(())
     }
 
+    /// Fill in each order's market question and outcome from the market
+    /// metadata cache, read off the runtime's worker threads
+    async fn label_orders(&self, orders: &mut [EnhancedOrder]) {
+        let data_paths = self.data_paths.clone();
+        let token_ids: Vec<String> = orders.iter().map(|o| o.asset_id.clone()).collect();
+        let labels = match tokio::task::spawn_blocking(move || {
+            MarketLabels::load(&data_paths, token_ids.iter().map(String::as_str))
+        })
+        .await
+        {
+            Ok(labels) => labels,
+            Err(e) => {
+                warn!("Failed to load market labels: {}", e);
+                return;
+            }
+        };
+        for order in orders {
+            let Some(question) = labels.question(&order.asset_id) else {
+                continue;
+            };
+            let info = order.market_info.get_or_insert_with(|| OrderMarketInfo {
+                market_question: None,
+                token_outcome: None,
+                market_price_at_order: None,
+                spread_at_order: None,
+                liquidity_at_order: None,
+            });
+            info.market_question = Some(question.to_string());
+            info.token_outcome = labels.outcome(&order.asset_id).map(str::to_string);
+        }
+    }
+
     /// Update positions from orders using the portfolio reconciler
     async fn update_positions_from_orders(
         &self,
-        orders: &[crate::core::portfolio::api::orders::PolymarketOrder],
-        portfolio_storage: &crate::core::portfolio::PortfolioStorage,
+        orders: &[PolymarketOrder],
+        portfolio_storage: &PortfolioStorage,
     ) -> Result<usize> {
-        use crate::core::portfolio::controller::PositionReconciler;
-
         // Use position reconciler to generate positions from orders
         let mut reconciler = PositionReconciler::new();
         let positions = reconciler.reconcile_from_orders(orders)?;
@@ -286,117 +316,6 @@ impl PortfolioService {
         Ok(positions.len())
     }
 
-    /// Convert EnhancedOrder to PolymarketOrder for storage compatibility
-    fn convert_enhanced_to_polymarket_order(
-        &self,
-        enhanced: &crate::core::execution::orders::EnhancedOrder,
-    ) -> crate::core::portfolio::api::orders::PolymarketOrder {
-        use rust_decimal::prelude::FromPrimitive;
-        use rust_decimal::Decimal;
-
-        // Extract additional fields for conversion
-        let market = enhanced
-            .additional_fields
-            .get("market")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&enhanced.asset_id)
-            .to_string();
-
-        let owner = enhanced
-            .additional_fields
-            .get("owner")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let outcome = enhanced
-            .additional_fields
-            .get("outcome")
-            .and_then(|v| v.as_str())
-            .unwrap_or("YES")
-            .to_string();
-
-        let order_type = enhanced
-            .additional_fields
-            .get("order_type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("LIMIT")
-            .to_string();
-
-        let expiration = enhanced
-            .additional_fields
-            .get("expiration")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-
-        let maker_address = enhanced
-            .additional_fields
-            .get("maker_address")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let fee_rate_bps = enhanced
-            .additional_fields
-            .get("fee_rate_bps")
-            .and_then(|v| v.as_i64())
-            .map(|v| v as i32);
-
-        let condition_id = enhanced
-            .additional_fields
-            .get("condition_id")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        let question_id = enhanced
-            .additional_fields
-            .get("question_id")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        // Convert side
-        let side = match enhanced.side {
-            crate::core::execution::orders::OrderSide::Buy => "BUY".to_string(),
-            crate::core::execution::orders::OrderSide::Sell => "SELL".to_string(),
-        };
-
-        // Convert status
-        let status = match enhanced.status {
-            crate::core::execution::orders::OrderStatus::Open => "OPEN".to_string(),
-            crate::core::execution::orders::OrderStatus::Filled => "FILLED".to_string(),
-            crate::core::execution::orders::OrderStatus::Cancelled => "CANCELLED".to_string(),
-            crate::core::execution::orders::OrderStatus::PartiallyFilled => {
-                "PARTIALLY_FILLED".to_string()
-            }
-            crate::core::execution::orders::OrderStatus::Rejected => "REJECTED".to_string(),
-            crate::core::execution::orders::OrderStatus::Pending => "PENDING".to_string(),
-        };
-
-        crate::core::portfolio::api::orders::PolymarketOrder {
-            id: enhanced.id.clone(),
-            owner,
-            market,
-            asset_id: enhanced.asset_id.clone(),
-            side,
-            price: Decimal::from_f64(enhanced.price).unwrap_or_default(),
-            size_structured: Decimal::from_f64(enhanced.original_size).unwrap_or_default(),
-            size_matched: enhanced.filled_size.to_string(),
-            status,
-            created_at: enhanced.created_at.timestamp() as u64,
-            maker_address,
-            outcome,
-            expiration,
-            order_type,
-            associate_trades: Vec::new(),
-            fee_rate_bps,
-            nonce: None,
-            condition_id,
-            token_id: Some(enhanced.asset_id.clone()),
-            question_id,
-        }
-    }
-
     /// Get user address (non-blocking for UI)
     pub fn get_user_address_sync(&self) -> Option<String> {
         if let Ok(address) = self.user_address.try_read() {
@@ -415,6 +334,11 @@ impl PortfolioService {
         }
     }
 
+    /// Number of orders, without copying them (non-blocking for UI)
+    pub fn order_count_sync(&self) -> usize {
+        self.orders.try_read().map_or(0, |orders| orders.len())
+    }
+
     /// Get positions (non-blocking for UI)
     pub fn get_positions_sync(&self) -> Vec<Position> {
         if let Ok(positions) = self.positions.try_read() {
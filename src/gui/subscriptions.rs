@@ -0,0 +1,215 @@
+//! Per-pane data subscriptions for the trading canvas
+//!
+//! Each pane declares what it shows through [`Pane::subscriptions`]. The app
+//! reference-counts those declarations across every open pane in a
+//! [`SubscriptionRegistry`] and sends the active set to the background data
+//! task, which then reads only the order books, cross rates and statistics
+//! something on screen needs. Any number of market depth panes can follow
+//! different tokens at once, since each token's book arrives as its own
+//! [`PaneDataUpdate::Token`].
+//!
+//! [`Pane::subscriptions`]: crate::gui::panes::Pane::subscriptions
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+
+use crate::core::execution::cross_rate::CrossRate;
+use crate::core::services::streaming::traits::{StreamingStats, WorkerStatus};
+use crate::core::services::streaming::{StreamingService, StreamingServiceTrait};
+use crate::core::ws::OrderBook;
+
+/// How often the background task refreshes subscribed data
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A stream of data a pane can subscribe to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Subscription {
+    /// Order book and last trade price of one token
+    Token(String),
+    /// Both outcome books of every streamed market, paired up
+    CrossRates,
+    /// Streaming service and worker statistics
+    StreamStats,
+}
+
+/// Reference counts of the subscriptions held by open panes
+#[derive(Debug, Default)]
+pub struct SubscriptionRegistry {
+    counts: BTreeMap<Subscription, usize>,
+}
+
+impl SubscriptionRegistry {
+    /// Replace the held subscriptions, returning whether the active set changed
+    ///
+    /// Only a change in *which* subscriptions are held matters to the data
+    /// task; a second pane on an already subscribed token just bumps a count.
+    pub fn update(&mut self, held: impl IntoIterator<Item = Subscription>) -> bool {
+        let mut counts = BTreeMap::new();
+        for subscription in held {
+            *counts.entry(subscription).or_insert(0) += 1;
+        }
+        let changed = !counts.keys().eq(self.counts.keys());
+        self.counts = counts;
+        changed
+    }
+
+    /// Panes holding `subscription`
+    pub fn count(&self, subscription: &Subscription) -> usize {
+        self.counts.get(subscription).copied().unwrap_or(0)
+    }
+
+    pub fn is_subscribed(&self, subscription: &Subscription) -> bool {
+        self.counts.contains_key(subscription)
+    }
+
+    pub fn active(&self) -> BTreeSet<Subscription> {
+        self.counts.keys().cloned().collect()
+    }
+}
+
+/// Price and time (Unix milliseconds) of a token's last trade
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastTrade {
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
+/// Latest data for a subscribed token
+#[derive(Debug, Clone, Default)]
+pub struct TokenData {
+    pub book: Option<OrderBook>,
+    pub last_trade: Option<LastTrade>,
+}
+
+/// One piece of refreshed data from the background task
+#[derive(Debug)]
+pub enum PaneDataUpdate {
+    /// Every token the streaming service follows; always sent
+    StreamingTokens(Vec<String>),
+    Token {
+        token_id: String,
+        data: TokenData,
+    },
+    CrossRates(Vec<CrossRate>),
+    Stats(StreamingStats),
+    Workers(Vec<WorkerStatus>),
+}
+
+/// Refresh the subscribed data from `service` until the app goes away
+///
+/// The active set arrives on `subscriptions` whenever it changes; nothing is
+/// fetched for a subscription no pane holds. Subscribed tokens the service is
+/// not streaming (e.g. depth panes restored with a saved layout) are added to
+/// the stream so those panes reconnect to their books.
+pub fn spawn_data_task(
+    service: Arc<StreamingService>,
+    subscriptions: Receiver<BTreeSet<Subscription>>,
+    updates: Sender<PaneDataUpdate>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        let mut active = BTreeSet::new();
+        let mut added: HashSet<String> = HashSet::new();
+
+        loop {
+            interval.tick().await;
+            while let Ok(latest) = subscriptions.try_recv() {
+                active = latest;
+            }
+
+            let tokens = service.get_streaming_tokens().await;
+            let missing: Vec<String> = active
+                .iter()
+                .filter_map(|subscription| match subscription {
+                    Subscription::Token(token_id) => Some(token_id),
+                    _ => None,
+                })
+                .filter(|token_id| !tokens.contains(token_id) && !added.contains(*token_id))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                info!("Streaming {} tokens subscribed by panes", missing.len());
+                // Tried once per token; a failure leaves the pane waiting for data
+                added.extend(missing.iter().cloned());
+                if let Err(e) = service.add_tokens(missing).await {
+                    warn!("Failed to stream subscribed tokens: {}", e);
+                }
+            }
+            let mut refreshed = vec![PaneDataUpdate::StreamingTokens(tokens.clone())];
+
+            for subscription in &active {
+                match subscription {
+                    Subscription::Token(token_id) => refreshed.push(PaneDataUpdate::Token {
+                        token_id: token_id.clone(),
+                        data: TokenData {
+                            book: service.get_order_book(token_id).await,
+                            last_trade: service
+                                .get_last_trade_price(token_id)
+                                .await
+                                .map(|(price, timestamp)| LastTrade { price, timestamp }),
+                        },
+                    }),
+                    Subscription::CrossRates => {
+                        let mut books = Vec::with_capacity(tokens.len());
+                        for token_id in &tokens {
+                            if let Some(book) = service.get_order_book(token_id).await {
+                                books.push(book);
+                            }
+                        }
+                        refreshed.push(PaneDataUpdate::CrossRates(CrossRate::from_books(&books)));
+                    }
+                    Subscription::StreamStats => {
+                        refreshed.push(PaneDataUpdate::Stats(service.get_stats().await));
+                        refreshed
+                            .push(PaneDataUpdate::Workers(service.get_worker_statuses().await));
+                    }
+                }
+            }
+
+            // The app dropped its receiver: streaming stopped or the window closed
+            if refreshed
+                .into_iter()
+                .any(|update| updates.send(update).is_err())
+            {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(id: &str) -> Subscription {
+        Subscription::Token(id.to_string())
+    }
+
+    #[test]
+    fn registry_reports_changes_to_the_active_set_only() {
+        let mut registry = SubscriptionRegistry::default();
+        assert!(registry.update([token("a"), Subscription::StreamStats]));
+        assert!(registry.is_subscribed(&token("a")));
+
+        // A second pane on the same token only bumps the count
+        assert!(!registry.update([token("a"), token("a"), Subscription::StreamStats]));
+        assert_eq!(registry.count(&token("a")), 2);
+
+        // Closing one of them keeps the token subscribed
+        assert!(!registry.update([token("a"), Subscription::StreamStats]));
+        assert_eq!(registry.count(&token("a")), 1);
+
+        // Closing the last releases it
+        assert!(registry.update([Subscription::StreamStats]));
+        assert!(!registry.is_subscribed(&token("a")));
+        assert_eq!(
+            registry.active(),
+            BTreeSet::from([Subscription::StreamStats])
+        );
+    }
+}
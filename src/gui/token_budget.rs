@@ -0,0 +1,270 @@
+//! Streamed token budget in the canvas
+//!
+//! While streaming live, the budget of `token_budget.yaml` (see
+//! `core::services::streaming::budget`) is re-evaluated in the background
+//! and the streaming service is brought in line with it: tokens it picks are
+//! subscribed to, tokens it no longer picks are dropped. Open positions come
+//! from the portfolio service. The WebSocket Manager pane shows the latest
+//! evaluation and can ask for one right away.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::sync::mpsc;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::core::portfolio::PositionStatus;
+use crate::core::services::streaming::budget::{TokenBudget, TokenBudgetConfig, TokenSource};
+use crate::core::services::streaming::{StreamingService, StreamingServiceTrait};
+use crate::data_paths::DataPaths;
+use crate::gui::services::PortfolioService;
+use crate::number_format;
+
+/// Selected tokens listed in the pane at most
+const LISTED_TOKENS: usize = 200;
+
+/// An evaluation and what it changed
+#[derive(Debug, Clone)]
+pub struct BudgetEvaluation {
+    pub budget: TokenBudget,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Re-evaluates the token budget in the background while streaming
+pub struct TokenBudgetRunner {
+    config: TokenBudgetConfig,
+    limit: usize,
+    evaluate_tx: tokio::sync::mpsc::UnboundedSender<()>,
+    result_rx: mpsc::Receiver<Result<BudgetEvaluation, String>>,
+    task: tokio::task::JoinHandle<()>,
+    latest: Option<BudgetEvaluation>,
+    error: Option<(String, DateTime<Local>)>,
+    evaluating: bool,
+}
+
+impl TokenBudgetRunner {
+    /// Start re-evaluating, or `None` when the budget isn't active;
+    /// `ctx` is woken after each evaluation
+    pub fn start(
+        config: TokenBudgetConfig,
+        tokens_per_connection: usize,
+        data_paths: DataPaths,
+        service: Arc<StreamingService>,
+        portfolio: PortfolioService,
+        ctx: egui::Context,
+    ) -> Option<Self> {
+        if !config.is_active() {
+            return None;
+        }
+        let limit = config.limit(tokens_per_connection)?;
+        let (evaluate_tx, mut evaluate_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (result_tx, result_rx) = mpsc::channel();
+        let task_config = config.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(task_config.interval());
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    request = evaluate_rx.recv() => {
+                        if request.is_none() {
+                            return;
+                        }
+                        interval.reset();
+                    }
+                }
+                let result = evaluate(&task_config, limit, &data_paths, &service, &portfolio).await;
+                if let Err(e) = &result {
+                    warn!("Token budget evaluation failed: {:#}", e);
+                }
+                if result_tx
+                    .send(result.map_err(|e| format!("{:#}", e)))
+                    .is_err()
+                {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+        });
+        info!(
+            "🎯 Token budget of {} tokens, re-evaluated every {}s",
+            limit,
+            config.interval().as_secs()
+        );
+        Some(Self {
+            config,
+            limit,
+            evaluate_tx,
+            result_rx,
+            task,
+            latest: None,
+            error: None,
+            evaluating: true,
+        })
+    }
+
+    /// Take in evaluations finished since the last call
+    pub fn poll(&mut self) {
+        for result in self.result_rx.try_iter() {
+            self.evaluating = false;
+            match result {
+                Ok(evaluation) => {
+                    self.latest = Some(evaluation);
+                    self.error = None;
+                }
+                Err(e) => self.error = Some((e, Local::now())),
+            }
+        }
+    }
+
+    /// Evaluate now instead of at the next interval
+    pub fn evaluate_now(&mut self) {
+        if self.evaluate_tx.send(()).is_ok() {
+            self.evaluating = true;
+        }
+    }
+
+    /// Budget, what the latest evaluation picked and a button to re-evaluate
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Budget: {} tokens{}",
+                number_format::integer(self.limit),
+                self.config
+                    .max_connections
+                    .map(|c| format!(" (≤ {} connections)", c))
+                    .unwrap_or_default()
+            ));
+            ui.separator();
+            ui.label(format!(
+                "Re-evaluated every {}s",
+                self.config.interval().as_secs()
+            ));
+            ui.separator();
+            let button = ui.add_enabled(!self.evaluating, egui::Button::new("🔄 Re-evaluate"));
+            if button.clicked() {
+                self.evaluate_now();
+            }
+            if self.evaluating {
+                ui.spinner();
+            }
+        });
+
+        if let Some((error, at)) = &self.error {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("⚠️ {} failed: {}", at.format("%H:%M:%S"), error),
+            );
+        }
+        let Some(evaluation) = &self.latest else {
+            if self.error.is_none() {
+                ui.label("⏳ Evaluating...");
+            }
+            return;
+        };
+
+        let budget = &evaluation.budget;
+        let counts = budget.counts();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "🎯 {} / {} streamed",
+                number_format::integer(budget.selected.len()),
+                number_format::integer(budget.limit)
+            ));
+            ui.separator();
+            ui.label(format!(
+                "💼 {} positions · 👁 {} watchlists · 🔎 {} screen",
+                counts.positions, counts.watchlists, counts.screen
+            ));
+            ui.separator();
+            ui.label(format!("{} left out", budget.left_out.len()));
+        });
+        ui.small(format!(
+            "Last evaluated {}: +{} / -{} tokens",
+            budget.evaluated_at.with_timezone(&Local).format("%H:%M:%S"),
+            evaluation.added,
+            evaluation.removed
+        ));
+
+        egui::CollapsingHeader::new("Selected tokens")
+            .id_salt("token_budget_selected")
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt("token_budget_selected_scroll")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("token_budget_selected_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Token");
+                                ui.strong("Source");
+                                ui.strong("Score");
+                                ui.end_row();
+                                for token in budget.selected.iter().take(LISTED_TOKENS) {
+                                    ui.monospace(&token.token_id);
+                                    ui.label(token.source.to_string());
+                                    match &token.source {
+                                        TokenSource::Screen { score, .. } => {
+                                            ui.label(number_format::integer(*score))
+                                        }
+                                        _ => ui.label("-"),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+    }
+}
+
+impl Drop for TokenBudgetRunner {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Evaluate the budget over the open positions and apply it to `service`
+async fn evaluate(
+    config: &TokenBudgetConfig,
+    limit: usize,
+    data_paths: &DataPaths,
+    service: &StreamingService,
+    portfolio: &PortfolioService,
+) -> Result<BudgetEvaluation> {
+    let positions: Vec<String> = portfolio
+        .get_positions_sync()
+        .into_iter()
+        .filter(|p| p.status == PositionStatus::Open && !p.size.is_zero())
+        .map(|p| p.token_id)
+        .collect();
+    let gather_config = config.clone();
+    let gather_paths = data_paths.clone();
+    let candidates =
+        tokio::task::spawn_blocking(move || gather_config.gather(&gather_paths, positions, limit))
+            .await??;
+    let budget = TokenBudget::select(candidates, limit);
+
+    let changes = budget.changes(&service.get_streaming_tokens().await);
+    let added = changes.add.len();
+    let removed = changes.remove.len();
+    if !changes.remove.is_empty() {
+        service.remove_tokens(changes.remove).await?;
+    }
+    if !changes.add.is_empty() {
+        service.add_tokens(changes.add).await?;
+    }
+    if added > 0 || removed > 0 {
+        info!(
+            "🎯 Token budget: streaming {} tokens (+{} / -{}), {} left out",
+            budget.selected.len(),
+            added,
+            removed,
+            budget.left_out.len()
+        );
+    }
+    Ok(BudgetEvaluation {
+        budget,
+        added,
+        removed,
+    })
+}
@@ -0,0 +1,653 @@
+//! Keyboard shortcuts shared by the TUI and the GUI
+//!
+//! Every [`Action`] has a default key on each surface it exists on. User
+//! overrides live in `<data_dir>/keybindings.yaml`, written by the canvas
+//! shortcut editor (Settings) or by hand, one section per surface:
+//!
+//! ```yaml
+//! gui:
+//!   cancel_all: ctrl+shift+c
+//!   screenshot: none        # unbound
+//! tui:
+//!   focus_next_pane: n
+//! ```
+//!
+//! A key can only trigger one action. When an override takes a key another
+//! action is bound to, that action loses it and the [`Conflict`] is reported.
+//! `ctrl` is Cmd on macOS in the GUI.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use tracing::warn;
+
+use crate::data_paths::DataPaths;
+
+/// Name of the keybindings file inside the data directory
+pub const KEYBINDINGS_CONFIG_FILE: &str = "keybindings.yaml";
+
+/// Where a keymap applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Gui,
+    Tui,
+}
+
+/// Something a shortcut does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Focus the next pane (the next page in the TUI)
+    FocusNextPane,
+    /// Focus the previous pane (the previous page in the TUI)
+    FocusPreviousPane,
+    OpenOrderTicket,
+    /// Cancel every open order, after a confirmation
+    CancelAll,
+    Screenshot,
+    /// Search actions and panes by name
+    SearchPalette,
+    ToggleFullscreen,
+    Quit,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::FocusNextPane,
+            Action::FocusPreviousPane,
+            Action::OpenOrderTicket,
+            Action::CancelAll,
+            Action::Screenshot,
+            Action::SearchPalette,
+            Action::ToggleFullscreen,
+            Action::Quit,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::FocusNextPane => "Focus next pane",
+            Action::FocusPreviousPane => "Focus previous pane",
+            Action::OpenOrderTicket => "Open order ticket",
+            Action::CancelAll => "Cancel all orders",
+            Action::Screenshot => "Screenshot",
+            Action::SearchPalette => "Search palette",
+            Action::ToggleFullscreen => "Toggle fullscreen",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// Default key on `surface`, `None` when the action doesn't exist there
+    pub fn default_binding(&self, surface: Surface) -> Option<KeyChord> {
+        let spec = match (surface, self) {
+            (Surface::Gui, Action::FocusNextPane) => "f6",
+            (Surface::Gui, Action::FocusPreviousPane) => "shift+f6",
+            (Surface::Gui, Action::OpenOrderTicket) => "ctrl+n",
+            (Surface::Gui, Action::CancelAll) => "ctrl+shift+x",
+            (Surface::Gui, Action::Screenshot) => "ctrl+shift+s",
+            (Surface::Gui, Action::SearchPalette) => "ctrl+k",
+            (Surface::Gui, Action::ToggleFullscreen) => "f11",
+            (Surface::Gui, Action::Quit) => "ctrl+q",
+            (Surface::Tui, Action::FocusNextPane) => "tab",
+            (Surface::Tui, Action::FocusPreviousPane) => "shift+tab",
+            (Surface::Tui, Action::Quit) => "q",
+            (Surface::Tui, _) => return None,
+        };
+        Some(spec.parse().expect("valid default key"))
+    }
+
+    /// Actions `surface` has
+    pub fn available(surface: Surface) -> impl Iterator<Item = Action> {
+        Self::all()
+            .iter()
+            .copied()
+            .filter(move |action| action.default_binding(surface).is_some())
+    }
+}
+
+/// A key without modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// Letters are kept lowercase
+    Char(char),
+    F(u8),
+    Tab,
+    Enter,
+    Escape,
+    Space,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A key known by name rather than by the character it types
+struct NamedKey {
+    key: Key,
+    display: &'static str,
+    /// Accepted spellings, lowercase
+    spellings: &'static [&'static str],
+}
+
+impl NamedKey {
+    const fn new(key: Key, display: &'static str, spellings: &'static [&'static str]) -> Self {
+        Self {
+            key,
+            display,
+            spellings,
+        }
+    }
+}
+
+const NAMED_KEYS: &[NamedKey] = &[
+    NamedKey::new(Key::Tab, "Tab", &["tab"]),
+    NamedKey::new(Key::Enter, "Enter", &["enter", "return"]),
+    NamedKey::new(Key::Escape, "Esc", &["esc", "escape"]),
+    NamedKey::new(Key::Space, "Space", &["space"]),
+    NamedKey::new(Key::Backspace, "Backspace", &["backspace"]),
+    NamedKey::new(Key::Delete, "Delete", &["delete", "del"]),
+    NamedKey::new(Key::Insert, "Insert", &["insert", "ins"]),
+    NamedKey::new(Key::Home, "Home", &["home"]),
+    NamedKey::new(Key::End, "End", &["end"]),
+    NamedKey::new(Key::PageUp, "PageUp", &["pageup", "pgup"]),
+    NamedKey::new(Key::PageDown, "PageDown", &["pagedown", "pgdn"]),
+    NamedKey::new(Key::Up, "Up", &["up", "arrowup"]),
+    NamedKey::new(Key::Down, "Down", &["down", "arrowdown"]),
+    NamedKey::new(Key::Left, "Left", &["left", "arrowleft"]),
+    NamedKey::new(Key::Right, "Right", &["right", "arrowright"]),
+];
+
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_lowercase();
+        let mut chars = lower.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if !c.is_whitespace() {
+                return Ok(Key::Char(c));
+            }
+        }
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            if (1..=24).contains(&n) {
+                return Ok(Key::F(n));
+            }
+        }
+        NAMED_KEYS
+            .iter()
+            .find(|named| named.spellings.contains(&lower.as_str()))
+            .map(|named| named.key)
+            .ok_or_else(|| anyhow!("Unknown key '{}'", s.trim()))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(c) => write!(f, "{}", c.to_uppercase()),
+            Key::F(n) => write!(f, "F{}", n),
+            key => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|named| named.key == *key)
+                    .map(|named| named.display)
+                    .unwrap_or("?");
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+/// A key with its modifiers, written like `ctrl+shift+x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub key: Key,
+}
+
+impl KeyChord {
+    pub fn new(key: Key) -> Self {
+        Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            key,
+        }
+    }
+
+    /// Whether typing into a text field can produce this chord
+    pub fn is_text_input(&self) -> bool {
+        !self.ctrl
+            && !self.alt
+            && matches!(
+                self.key,
+                Key::Char(_) | Key::Space | Key::Backspace | Key::Delete
+            )
+    }
+
+    /// The same letter without Shift, for a shifted letter chord
+    fn unshifted_letter(&self) -> Option<Self> {
+        match self.key {
+            Key::Char(c) if self.shift && c.is_ascii_alphabetic() => Some(Self {
+                shift: false,
+                ..*self
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        // The last part is the key; a trailing "+" is the plus key itself
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let mut chord = KeyChord::new(key.parse()?);
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => chord.ctrl = true,
+                "alt" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                other => return Err(anyhow!("Unknown modifier '{}' in '{}'", other, s)),
+            }
+        }
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl KeyChord {
+    /// Chord of a GUI key press; `command` is Ctrl, or Cmd on macOS
+    pub fn from_egui(key: egui::Key, modifiers: egui::Modifiers) -> Option<Self> {
+        let key = match key {
+            egui::Key::ArrowUp => Key::Up,
+            egui::Key::ArrowDown => Key::Down,
+            egui::Key::ArrowLeft => Key::Left,
+            egui::Key::ArrowRight => Key::Right,
+            other => other.symbol_or_name().parse().ok()?,
+        };
+        Some(Self {
+            ctrl: modifiers.command,
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+            key,
+        })
+    }
+}
+
+#[cfg(feature = "tui")]
+impl KeyChord {
+    /// Chord of a terminal key event
+    pub fn from_crossterm(event: &crossterm::event::KeyEvent) -> Option<Self> {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let mut shift = event.modifiers.contains(KeyModifiers::SHIFT);
+        let key = match event.code {
+            KeyCode::Char(' ') => Key::Space,
+            KeyCode::Char(c) if c.is_ascii_uppercase() => {
+                shift = true;
+                Key::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Char(c) => {
+                // Shift is already part of symbols like '?'
+                if !c.is_ascii_alphanumeric() {
+                    shift = false;
+                }
+                Key::Char(c)
+            }
+            KeyCode::F(n) => Key::F(n),
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => {
+                shift = true;
+                Key::Tab
+            }
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Escape,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            _ => return None,
+        };
+        Some(Self {
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+            alt: event.modifiers.contains(KeyModifiers::ALT),
+            shift,
+            key,
+        })
+    }
+}
+
+/// A binding in the config file: a chord, or `none` to unbind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding(pub Option<KeyChord>);
+
+impl TryFrom<String> for Binding {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        if value.trim().eq_ignore_ascii_case("none") {
+            return Ok(Binding(None));
+        }
+        value.parse().map(|chord| Binding(Some(chord)))
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        match binding.0 {
+            Some(chord) => chord.to_string().to_lowercase(),
+            None => "none".to_string(),
+        }
+    }
+}
+
+/// Two actions asked for the same key; `kept` has it, `unbound` lost it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub chord: KeyChord,
+    pub kept: Action,
+    pub unbound: Action,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is bound to '{}', so '{}' is now unbound",
+            self.chord,
+            self.kept.label(),
+            self.unbound.label()
+        )
+    }
+}
+
+/// The keymap of a config file's overrides, and the conflicts they had
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedKeymap {
+    pub keymap: Keymap,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// An action and the key it is bound to, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundAction {
+    pub action: Action,
+    pub chord: Option<KeyChord>,
+}
+
+/// Effective bindings of one surface, without conflicts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    surface: Surface,
+    bindings: BTreeMap<Action, Option<KeyChord>>,
+}
+
+impl Keymap {
+    pub fn defaults(surface: Surface) -> Self {
+        Self {
+            surface,
+            bindings: Action::available(surface)
+                .map(|action| (action, action.default_binding(surface)))
+                .collect(),
+        }
+    }
+
+    /// Defaults with `overrides` applied in action order; later overrides win
+    /// a key both ask for
+    pub fn with_overrides(
+        surface: Surface,
+        overrides: &BTreeMap<Action, Binding>,
+    ) -> ResolvedKeymap {
+        let mut keymap = Self::defaults(surface);
+        let mut conflicts = Vec::new();
+        for (action, binding) in overrides {
+            if !keymap.bindings.contains_key(action) {
+                warn!(
+                    "Ignoring keybinding for '{}', not available here",
+                    action.label()
+                );
+                continue;
+            }
+            conflicts.extend(keymap.set(*action, binding.0));
+        }
+        ResolvedKeymap { keymap, conflicts }
+    }
+
+    pub fn surface(&self) -> Surface {
+        self.surface
+    }
+
+    pub fn binding(&self, action: Action) -> Option<KeyChord> {
+        self.bindings.get(&action).copied().flatten()
+    }
+
+    /// Action bound to `chord`
+    pub fn action_for(&self, chord: &KeyChord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| bound.as_ref() == Some(chord))
+            .map(|(action, _)| *action)
+    }
+
+    /// Action a typed key triggers: a shifted letter nothing is bound to acts
+    /// as the plain letter, so `Q` quits the TUI like `q`
+    pub fn action_for_typed(&self, chord: &KeyChord) -> Option<Action> {
+        self.action_for(chord).or_else(|| {
+            chord
+                .unshifted_letter()
+                .and_then(|plain| self.action_for(&plain))
+        })
+    }
+
+    /// Whether `chord` triggers `action`
+    pub fn matches(&self, action: Action, chord: &KeyChord) -> bool {
+        self.binding(action).as_ref() == Some(chord)
+    }
+
+    /// Bind `action` to `chord` (or unbind it), taking the key from any other
+    /// action that has it
+    pub fn set(&mut self, action: Action, chord: Option<KeyChord>) -> Option<Conflict> {
+        let conflict = chord.and_then(|chord| {
+            let other = self.action_for(&chord).filter(|other| *other != action)?;
+            self.bindings.insert(other, None);
+            Some(Conflict {
+                chord,
+                kept: action,
+                unbound: other,
+            })
+        });
+        self.bindings.insert(action, chord);
+        conflict
+    }
+
+    /// Bindings that differ from the defaults, for the config file
+    pub fn overrides(&self) -> BTreeMap<Action, Binding> {
+        self.bindings
+            .iter()
+            .filter(|(action, chord)| action.default_binding(self.surface) != **chord)
+            .map(|(action, chord)| (*action, Binding(*chord)))
+            .collect()
+    }
+
+    /// Actions with their keys, in display order
+    pub fn iter(&self) -> impl Iterator<Item = BoundAction> + '_ {
+        self.bindings.iter().map(|(action, chord)| BoundAction {
+            action: *action,
+            chord: *chord,
+        })
+    }
+}
+
+/// Keybindings configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeybindingsConfig {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub gui: BTreeMap<Action, Binding>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tui: BTreeMap<Action, Binding>,
+}
+
+impl KeybindingsConfig {
+    /// Load `keybindings.yaml` from the data directory, or no overrides when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(KEYBINDINGS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid keybindings config {}", path.display()))
+    }
+
+    pub fn save(&self, data_paths: &DataPaths) -> Result<()> {
+        let path = data_paths.root().join(KEYBINDINGS_CONFIG_FILE);
+        let content = serde_yaml::to_string(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn overrides(&self, surface: Surface) -> &BTreeMap<Action, Binding> {
+        match surface {
+            Surface::Gui => &self.gui,
+            Surface::Tui => &self.tui,
+        }
+    }
+
+    /// Keep `keymap`'s differences from the defaults as its surface's overrides
+    pub fn set_keymap(&mut self, keymap: &Keymap) {
+        let overrides = keymap.overrides();
+        match keymap.surface() {
+            Surface::Gui => self.gui = overrides,
+            Surface::Tui => self.tui = overrides,
+        }
+    }
+}
+
+/// Keymap of `surface` from the data directory. An unreadable file falls back
+/// to the defaults; conflicts are resolved and logged.
+pub fn load_keymap(data_paths: &DataPaths, surface: Surface) -> Keymap {
+    let config = KeybindingsConfig::load(data_paths).unwrap_or_else(|e| {
+        warn!("Using default keybindings: {:#}", e);
+        KeybindingsConfig::default()
+    });
+    let resolved = Keymap::with_overrides(surface, config.overrides(surface));
+    for conflict in &resolved.conflicts {
+        warn!("Keybinding conflict: {}", conflict);
+    }
+    resolved.keymap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_parse_and_display() {
+        let chord: KeyChord = "Ctrl+Shift+X".parse().unwrap();
+        assert!(chord.ctrl && chord.shift && !chord.alt);
+        assert_eq!(chord.key, Key::Char('x'));
+        assert_eq!(chord.to_string(), "Ctrl+Shift+X");
+        assert_eq!("cmd+k".parse::<KeyChord>().unwrap().to_string(), "Ctrl+K");
+        assert_eq!("ctrl++".parse::<KeyChord>().unwrap().key, Key::Char('+'));
+        assert_eq!("shift+F6".parse::<KeyChord>().unwrap().key, Key::F(6));
+        assert_eq!("pgup".parse::<KeyChord>().unwrap().key, Key::PageUp);
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("ctrl+banana".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn overrides_take_keys_from_other_actions() {
+        let config: KeybindingsConfig = serde_yaml::from_str(
+            "gui:\n  screenshot: ctrl+n\n  quit: none\ntui:\n  cancel_all: x\n",
+        )
+        .unwrap();
+
+        let ResolvedKeymap { keymap, conflicts } =
+            Keymap::with_overrides(Surface::Gui, &config.gui);
+        let ctrl_n = "ctrl+n".parse().unwrap();
+        assert_eq!(keymap.action_for(&ctrl_n), Some(Action::Screenshot));
+        assert_eq!(keymap.binding(Action::OpenOrderTicket), None);
+        assert_eq!(keymap.binding(Action::Quit), None);
+        assert_eq!(
+            conflicts,
+            [Conflict {
+                chord: ctrl_n,
+                kept: Action::Screenshot,
+                unbound: Action::OpenOrderTicket,
+            }]
+        );
+
+        // The displaced action is written back as unbound
+        let overrides = keymap.overrides();
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(overrides[&Action::OpenOrderTicket], Binding(None));
+
+        // Actions a surface doesn't have are ignored
+        let tui = Keymap::with_overrides(Surface::Tui, &config.tui);
+        assert!(tui.conflicts.is_empty());
+        assert_eq!(tui.keymap, Keymap::defaults(Surface::Tui));
+
+        // Shift+Q quits the TUI like q, unless something is bound to it
+        let mut tui = tui.keymap;
+        let shift_q = "shift+q".parse().unwrap();
+        assert_eq!(tui.action_for_typed(&shift_q), Some(Action::Quit));
+        tui.set(Action::FocusNextPane, Some(shift_q));
+        assert_eq!(tui.action_for_typed(&shift_q), Some(Action::FocusNextPane));
+    }
+
+    #[test]
+    fn config_round_trips_through_yaml() {
+        let mut keymap = Keymap::defaults(Surface::Gui);
+        keymap.set(Action::CancelAll, Some("alt+c".parse().unwrap()));
+        let mut config = KeybindingsConfig::default();
+        config.set_keymap(&keymap);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert_eq!(yaml.trim(), "gui:\n  cancel_all: alt+c");
+        let loaded: KeybindingsConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(Keymap::with_overrides(Surface::Gui, &loaded.gui).keymap, keymap);
+    }
+}
@@ -1,22 +1,34 @@
 pub mod auth;
 pub mod auth_env;
 pub mod cli;
+pub mod client;
 pub mod config;
 pub mod core;
 pub mod data_paths;
 pub use data_paths as data;
 pub mod errors;
 pub mod ethereum_utils;
+#[cfg(feature = "gui")]
 pub mod gui;
+pub mod keybindings;
 pub mod logging;
 pub mod markets;
+pub mod migrations;
+pub mod number_format;
 pub mod pipeline;
 pub mod storage;
 pub mod strategy;
+pub mod theme;
+#[cfg(feature = "tui")]
 pub mod tui;
 pub mod types;
 pub mod address_book;
 pub mod typed_store;
 
 // Re-export the GUI launcher function at the root level
+#[cfg(feature = "gui")]
 pub use gui::launch_trading_canvas;
+
+// Library entry point; see `client` for an example
+pub use client::{MarketStream, PolybotClient, DEFAULT_HOST};
+pub use data_paths::DataPaths;
@@ -1,21 +1,27 @@
 use anyhow::Result;
-use clap::Parser;
 
 mod auth;
 mod auth_env;
 mod cli;
+mod client;
 mod config;
 mod core;
 mod data_paths;
 use data_paths as data;
 mod errors;
 mod ethereum_utils;
+#[cfg(feature = "gui")]
 mod gui;
+mod keybindings;
 mod logging;
 mod markets;
+mod migrations;
+mod number_format;
 mod pipeline;
 mod storage;
 mod strategy;
+mod theme;
+#[cfg(feature = "tui")]
 mod tui;
 mod typed_store;
 mod types;
@@ -27,7 +33,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Parse CLI and execute (CLI will handle logging initialization)
-    let cli = cli::Cli::parse();
+    let cli = cli::Cli::parse_with_aliases();
 
     // Execute with error handling
     let result = match cli.execute().await {
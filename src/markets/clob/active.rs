@@ -5,6 +5,8 @@ use rust_decimal::Decimal;
 use serde_json::Value;
 use tracing::{info, warn};
 
+use crate::number_format;
+
 /// List actively traded markets by checking orderbook activity
 ///
 /// TODO: This is a placeholder implementation. The full implementation
@@ -293,22 +295,25 @@ pub async fn list_active_markets(
             println!("\n{} {}", format!("{}.", idx + 1), question);
 
             println!(
-                "   {} YES: ${:.3} | NO: ${:.3}",
-                "Prices:", market_info.yes_price, market_info.no_price
+                "   {} YES: ${} | NO: ${}",
+                "Prices:",
+                number_format::price(market_info.yes_price),
+                number_format::price(market_info.no_price)
             );
 
             println!(
-                "   {} {:.1}% | {} ${:.0} bid / ${:.0} ask",
+                "   {} {} | {} ${} bid / ${} ask",
                 "Spread:",
-                market_info.spread,
+                number_format::percent(market_info.spread),
                 "Depth:",
-                market_info.bid_depth,
-                market_info.ask_depth
+                number_format::integer(market_info.bid_depth),
+                number_format::integer(market_info.ask_depth)
             );
 
             println!(
-                "   {} ${:.0}",
-                "Total Liquidity:", market_info.total_liquidity
+                "   {} ${}",
+                "Total Liquidity:",
+                number_format::integer(market_info.total_liquidity)
             );
 
             if detailed {
@@ -343,11 +348,14 @@ pub async fn list_active_markets(
                 "{:<4} {:<50} {:>8} {:>8} {:>8} {:>12} {:>10}",
                 format!("{}", idx + 1),
                 question,
-                format!("${:.3}", market_info.yes_price),
-                format!("${:.3}", market_info.no_price),
-                format!("{:.1}%", market_info.spread),
-                format!("${:.0}", market_info.total_liquidity),
-                format!("${:.0}", market_info.bid_depth + market_info.ask_depth),
+                format!("${}", number_format::price(market_info.yes_price)),
+                format!("${}", number_format::price(market_info.no_price)),
+                number_format::percent(market_info.spread),
+                format!("${}", number_format::integer(market_info.total_liquidity)),
+                format!(
+                    "${}",
+                    number_format::integer(market_info.bid_depth + market_info.ask_depth)
+                ),
             );
         }
     }
@@ -1,15 +1,52 @@
 use anyhow::{anyhow, Result};
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 use crate::cli::commands::analyze::AnalyzeArgs;
+use crate::core::memory::{MemoryBudget, SpillVec};
 use crate::data_paths::DataPaths;
-use crate::markets::datasets::save_command_metadata;
+use crate::markets::datasets::report_dataset;
+use crate::typed_store::freshness;
+use crate::typed_store::EnrichmentFreshness;
+
+/// Markets of the source dataset that passed the filters
+struct FilteredMarkets {
+    /// Markets read, matching or not
+    total: usize,
+    matching: SpillVec<Value>,
+}
+
+/// Keeps markets whose tokens were all enriched within `max_age`
+struct FreshnessFilter {
+    tracked: HashMap<String, EnrichmentFreshness>,
+    max_age: std::time::Duration,
+    now: DateTime<Utc>,
+}
+
+impl FreshnessFilter {
+    fn is_fresh(&self, market: &Value) -> bool {
+        let token_ids: Vec<&str> = market
+            .get("tokens")
+            .and_then(|v| v.as_array())
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .filter_map(|t| t.get("token_id").and_then(|id| id.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        !token_ids.is_empty()
+            && token_ids
+                .iter()
+                .all(|id| !freshness::is_stale(self.tracked.get(*id), self.max_age, self.now))
+    }
+}
 
 /// Market analysis configuration and execution engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +59,9 @@ pub struct MarketAnalyzer {
     pub filters: AnalysisFilters,
     /// Output configuration
     pub output_config: OutputConfig,
+    /// Memory limit; past it filtered markets spill to disk
+    #[serde(skip)]
+    pub budget: MemoryBudget,
 }
 
 /// Analysis filters for market data
@@ -84,6 +124,9 @@ pub struct AnalysisFilters {
     /// Filter: search in all text fields (title, description, tags)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text_search: Option<String>,
+    /// Filter: maximum age in seconds of the tokens' last enrichment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_enrichment_age_secs: Option<u64>,
 }
 
 fn default_fuzzy_threshold() -> f64 {
@@ -173,11 +216,15 @@ impl MarketAnalyzer {
                 fuzzy_search: args.fuzzy_search,
                 fuzzy_threshold: args.fuzzy_threshold,
                 text_search: args.text_search,
+                max_enrichment_age_secs: args.max_enrichment_age.map(|age| age.as_secs()),
             },
             output_config: OutputConfig {
                 detailed: args.detailed,
                 summary: args.summary,
             },
+            budget: MemoryBudget::new("Analysis", args.memory_limit).with_guidance(
+                "Analyze fewer chunk files at a time, or narrow the filters so fewer markets match",
+            ),
         }
     }
 
@@ -191,21 +238,23 @@ impl MarketAnalyzer {
         );
 
         // Resolve source dataset path
-        let source_path = self.resolve_source_path(data_paths)?;
+        let source_path = data_paths.source_dataset(&self.source_dataset)?;
         info!("📂 Reading from: {}", source_path.display());
 
         // Create output dataset directory
         let output_path = data_paths.datasets().join(&self.dataset_name);
         fs::create_dir_all(&output_path)?;
 
-        // Load and process markets
-        let markets = self.load_markets(&source_path).await?;
-        let filtered_markets = self.apply_filters(&markets)?;
+        // Load and filter markets one file at a time
+        let FilteredMarkets {
+            total: total_markets,
+            matching: mut filtered_markets,
+        } = self.load_filtered_markets(data_paths, &source_path, &output_path)?;
 
         if filtered_markets.is_empty() {
             warn!("⚠️  No markets matched the filters");
             return Ok(AnalysisResult {
-                total_markets: markets.len(),
+                total_markets,
                 filtered_markets: 0,
                 execution_time_ms: start_time.elapsed().as_millis() as u64,
                 output_path,
@@ -214,23 +263,24 @@ impl MarketAnalyzer {
         }
 
         // Save results
-        self.save_results(&output_path, &filtered_markets).await?;
+        self.save_results(&output_path, &mut filtered_markets)
+            .await?;
 
         // Calculate statistics
-        let statistics = self.calculate_statistics(&filtered_markets);
+        let statistics = self.calculate_statistics(filtered_markets.iter()?)?;
 
-        // Save command metadata
-        self.save_command_metadata(&output_path, markets.len(), filtered_markets.len())?;
+        // Report the dataset; its metadata is written when the command succeeds
+        self.report_output(&output_path, total_markets, filtered_markets.len());
 
         let execution_time = start_time.elapsed().as_millis() as u64;
 
         // Show summary
-        self.display_results(markets.len(), filtered_markets.len(), &statistics);
+        self.display_results(total_markets, filtered_markets.len(), &statistics);
 
         info!("📁 Saved to: {}", output_path.display());
 
         Ok(AnalysisResult {
-            total_markets: markets.len(),
+            total_markets,
             filtered_markets: filtered_markets.len(),
             execution_time_ms: execution_time,
             output_path,
@@ -238,30 +288,21 @@ impl MarketAnalyzer {
         })
     }
 
-    /// Resolve the source dataset path
-    fn resolve_source_path(&self, data_paths: &DataPaths) -> Result<PathBuf> {
-        let source_path =
-            if self.source_dataset.starts_with('/') || self.source_dataset.starts_with("./") {
-                // Absolute or relative path provided
-                PathBuf::from(&self.source_dataset)
-            } else {
-                // Dataset name provided, look in datasets directory
-                data_paths.datasets().join(&self.source_dataset)
-            };
-
-        if !source_path.exists() {
-            return Err(anyhow!(
-                "Source dataset not found: {}",
-                source_path.display()
-            ));
-        }
-
-        Ok(source_path)
-    }
-
-    /// Load markets from the source dataset
-    async fn load_markets(&self, source_path: &Path) -> Result<Vec<Value>> {
-        let mut all_markets = Vec::new();
+    /// Load the markets of the source dataset that pass the filters
+    ///
+    /// Files are read and filtered one at a time, so only matching markets
+    /// stay in memory; past the memory budget they spill to disk as well.
+    fn load_filtered_markets(
+        &self,
+        data_paths: &DataPaths,
+        source_path: &Path,
+        output_path: &Path,
+    ) -> Result<FilteredMarkets> {
+        let mut filtered = SpillVec::new(
+            self.budget.clone(),
+            output_path.join(".markets.spill.jsonl"),
+        );
+        let mut total = 0;
 
         // Find data files in the source dataset
         let data_files = self.find_data_files(source_path)?;
@@ -275,6 +316,16 @@ impl MarketAnalyzer {
 
         info!("📊 Found {} data files to analyze", data_files.len());
 
+        let freshness_filter = match self.filters.max_enrichment_age_secs {
+            Some(max_age_secs) => Some(FreshnessFilter {
+                tracked: freshness::load(&freshness::default_db_path(data_paths))?,
+                max_age: std::time::Duration::from_secs(max_age_secs),
+                now: Utc::now(),
+            }),
+            None => None,
+        };
+        let mut stale = 0;
+
         // Read and process all data files
         for file_path in data_files {
             debug!(
@@ -283,10 +334,48 @@ impl MarketAnalyzer {
             );
 
             let markets = self.read_market_file(&file_path)?;
-            all_markets.extend(markets);
+            total += markets.len();
+            for market in markets {
+                if !self.market_passes_filters(&market)? {
+                    continue;
+                }
+                if let Some(filter) = &freshness_filter {
+                    if !filter.is_fresh(&market) {
+                        stale += 1;
+                        continue;
+                    }
+                }
+                filtered.push(market)?;
+            }
+
+            // Give up before the OOM killer does; the output isn't written yet
+            self.budget.check()?;
         }
 
-        Ok(all_markets)
+        info!(
+            "📊 Loaded {} markets, filtered to {}",
+            total,
+            filtered.len() + stale
+        );
+        if let Some(max_age_secs) = self.filters.max_enrichment_age_secs {
+            info!(
+                "🕒 Dropped {} markets with enrichment older than {} (run `polybot enrich --stale-only` to refresh)",
+                stale,
+                freshness::format_age(chrono::Duration::seconds(max_age_secs as i64))
+            );
+        }
+        if filtered.spilled() > 0 {
+            info!(
+                "💾 {} of {} matching markets were spilled to disk",
+                filtered.spilled(),
+                filtered.len()
+            );
+        }
+
+        Ok(FilteredMarkets {
+            total,
+            matching: filtered,
+        })
     }
 
     /// Find data files in the source dataset directory
@@ -331,25 +420,6 @@ impl MarketAnalyzer {
         Ok(markets)
     }
 
-    /// Apply filters to markets
-    fn apply_filters(&self, markets: &[Value]) -> Result<Vec<Value>> {
-        let mut filtered = Vec::new();
-        let total = markets.len();
-
-        for market in markets {
-            if self.market_passes_filters(market)? {
-                filtered.push(market.clone());
-            }
-        }
-
-        info!(
-            "📊 Loaded {} markets, filtered to {}",
-            total,
-            filtered.len()
-        );
-        Ok(filtered)
-    }
-
     /// Check if a market passes all filters
     fn market_passes_filters(&self, market: &Value) -> Result<bool> {
         // Active filter
@@ -682,12 +752,9 @@ impl MarketAnalyzer {
     }
 
     /// Save analysis results to files
-    async fn save_results(&self, output_path: &Path, markets: &[Value]) -> Result<()> {
-        // Save filtered markets as JSON
-        let markets_file = output_path.join("markets.json");
-        let json = serde_json::to_string_pretty(markets)?;
-        let mut file = File::create(&markets_file)?;
-        file.write_all(json.as_bytes())?;
+    async fn save_results(&self, output_path: &Path, markets: &mut SpillVec<Value>) -> Result<()> {
+        // Save filtered markets as JSON, streaming spilled ones back from disk
+        markets.write_json_array(&output_path.join("markets.json"))?;
 
         // Save analysis configuration
         let config_file = output_path.join("analysis_config.yaml");
@@ -699,12 +766,16 @@ impl MarketAnalyzer {
     }
 
     /// Calculate analysis statistics
-    fn calculate_statistics(&self, markets: &[Value]) -> AnalysisStatistics {
+    fn calculate_statistics(
+        &self,
+        markets: impl Iterator<Item = Result<Value>>,
+    ) -> Result<AnalysisStatistics> {
         let mut stats = AnalysisStatistics::default();
         let mut categories: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
 
         for market in markets {
+            let market = market?;
             // Count status types
             if market
                 .get("active")
@@ -752,22 +823,16 @@ impl MarketAnalyzer {
         cat_vec.sort_by(|a, b| b.1.cmp(&a.1));
         stats.top_categories = cat_vec.into_iter().take(10).collect();
 
-        stats
+        Ok(stats)
     }
 
-    /// Save command metadata
-    fn save_command_metadata(
+    /// Report the produced dataset with analysis details for its metadata
+    fn report_output(
         &self,
         output_path: &Path,
         total_markets: usize,
         filtered_markets: usize,
-    ) -> Result<()> {
-        let command_args = vec![
-            self.dataset_name.clone(),
-            "--source-dataset".to_string(),
-            self.source_dataset.clone(),
-        ];
-
+    ) {
         let mut additional_info = std::collections::HashMap::new();
         additional_info.insert(
             "dataset_type".to_string(),
@@ -786,13 +851,7 @@ impl MarketAnalyzer {
             serde_json::json!(filtered_markets),
         );
 
-        if let Err(e) =
-            save_command_metadata(output_path, "analyze", &command_args, Some(additional_info))
-        {
-            warn!("Warning: Failed to save command metadata: {}", e);
-        }
-
-        Ok(())
+        report_dataset(output_path, additional_info);
     }
 
     /// Display analysis results
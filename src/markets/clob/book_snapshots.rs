@@ -0,0 +1,318 @@
+//! Order book snapshot scheduler
+//!
+//! Captures the full REST book of a fixed set of tokens on an interval,
+//! independent of any stream, and stores each round in the typed store
+//! (`typed_store::book_snapshots`). The set comes from `<data_dir>/snapshots.yaml`
+//! and/or the `book-snapshots run` flags:
+//!
+//! ```yaml
+//! interval_secs: 300
+//! # Every outcome token of these markets
+//! markets:
+//!   - "0x1234...condition_id"
+//! tokens:
+//!   - "71321045679252212594626385532706912750332728571942532289631379312455583992563"
+//! ```
+//!
+//! A `watch` section configures the spread and depth alerts of
+//! `book-snapshots watch` (see `book_watch`).
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use polymarket_rs_client::ClobClient;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, warn};
+
+use super::book_watch::WatchConfig;
+use crate::core::progress::Progress;
+use crate::data_paths::DataPaths;
+use crate::typed_store::book_snapshots;
+use crate::typed_store::models::{BookSnapshot, BookSnapshotLevel};
+use crate::typed_store::token_info;
+
+/// Name of the snapshot configuration file inside the data directory
+pub const SNAPSHOTS_CONFIG_FILE: &str = "snapshots.yaml";
+
+/// Shortest interval accepted, to stay well clear of REST rate limits
+pub const MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Book requests in flight at once during a round
+const CONCURRENT_REQUESTS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    pub interval_secs: u64,
+    /// Condition ids; every outcome token of each market is captured
+    pub markets: Vec<String>,
+    pub tokens: Vec<String>,
+    /// Thresholds of `book-snapshots watch`
+    pub watch: WatchConfig,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            markets: Vec::new(),
+            tokens: Vec::new(),
+            watch: WatchConfig::default(),
+        }
+    }
+}
+
+impl SnapshotConfig {
+    /// Load `snapshots.yaml` from the data directory, or defaults when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(SNAPSHOTS_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid snapshot config {}", path.display()))
+    }
+
+    pub fn interval(&self) -> Result<Duration> {
+        let interval = Duration::from_secs(self.interval_secs);
+        if interval < MIN_INTERVAL {
+            bail!(
+                "Snapshot interval must be at least {}s, got {}s",
+                MIN_INTERVAL.as_secs(),
+                self.interval_secs
+            );
+        }
+        Ok(interval)
+    }
+}
+
+/// A token whose book is captured
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotTarget {
+    pub token_id: String,
+    pub condition_id: Option<String>,
+}
+
+/// Expand the configured markets into their tokens and add the configured
+/// tokens, looking their market up in the local index; duplicates are dropped
+pub async fn resolve_targets(
+    client: &ClobClient,
+    config: &SnapshotConfig,
+    db_path: &Path,
+) -> Result<Vec<SnapshotTarget>> {
+    let mut targets: Vec<SnapshotTarget> = Vec::new();
+    for condition_id in &config.markets {
+        let market = client
+            .get_market(condition_id)
+            .await
+            .map_err(|e| anyhow!("Failed to load market {}: {}", condition_id, e))?;
+        let token_ids: Vec<String> = market
+            .get("tokens")
+            .and_then(|v| v.as_array())
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .filter_map(|token| Some(token.get("token_id")?.as_str()?.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if token_ids.is_empty() {
+            bail!("Market {} has no tokens", condition_id);
+        }
+        for token_id in token_ids {
+            targets.push(SnapshotTarget {
+                token_id,
+                condition_id: Some(condition_id.clone()),
+            });
+        }
+    }
+
+    for token_id in &config.tokens {
+        let condition_id = token_info::lookup(db_path, token_id)
+            .unwrap_or_else(|e| {
+                debug!("Token index lookup failed for {}: {}", token_id, e);
+                None
+            })
+            .and_then(|info| info.condition_id);
+        targets.push(SnapshotTarget {
+            token_id: token_id.clone(),
+            condition_id,
+        });
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    targets.retain(|target| seen.insert(target.token_id.clone()));
+    Ok(targets)
+}
+
+/// Price levels best first: highest bids, lowest asks
+fn sorted_levels(
+    levels: impl Iterator<Item = BookSnapshotLevel>,
+    descending: bool,
+) -> Vec<BookSnapshotLevel> {
+    let mut levels: Vec<BookSnapshotLevel> = levels.collect();
+    levels.sort_by(|a, b| {
+        if descending {
+            b.price.cmp(&a.price)
+        } else {
+            a.price.cmp(&b.price)
+        }
+    });
+    levels
+}
+
+/// The books captured in one round
+#[derive(Debug, Default)]
+pub struct RoundResult {
+    pub snapshots: Vec<BookSnapshot>,
+    /// Books that couldn't be fetched
+    pub failed: usize,
+}
+
+impl RoundResult {
+    /// Fetch every target's book; all books of a round share its capture time
+    pub async fn capture(
+        client: &ClobClient,
+        targets: &[SnapshotTarget],
+        captured_at: DateTime<Utc>,
+    ) -> Self {
+        let results: Vec<_> = stream::iter(targets)
+            .map(|target| async move { (target, client.get_order_book(&target.token_id).await) })
+            .buffer_unordered(CONCURRENT_REQUESTS)
+            .collect()
+            .await;
+
+        let mut round = Self::default();
+        for (target, result) in results {
+            match result {
+                Ok(book) => round.snapshots.push(BookSnapshot {
+                    token_id: target.token_id.clone(),
+                    condition_id: target.condition_id.clone(),
+                    captured_at,
+                    bids: sorted_levels(
+                        book.bids.iter().map(|l| BookSnapshotLevel {
+                            price: l.price,
+                            size: l.size,
+                        }),
+                        true,
+                    ),
+                    asks: sorted_levels(
+                        book.asks.iter().map(|l| BookSnapshotLevel {
+                            price: l.price,
+                            size: l.size,
+                        }),
+                        false,
+                    ),
+                }),
+                Err(e) => {
+                    warn!("Failed to snapshot book of {}: {}", target.token_id, e);
+                    round.failed += 1;
+                }
+            }
+        }
+        round
+    }
+}
+
+/// Totals of a scheduler run
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SchedulerReport {
+    pub rounds: usize,
+    pub snapshots: usize,
+    pub failed: usize,
+}
+
+/// Capture a round every `interval` until `rounds` rounds are done, or until
+/// the task is cancelled
+///
+/// Rounds that take longer than the interval delay the next one instead of
+/// bunching up. When the store can't be written (e.g. another command holds
+/// its lock) the round is kept and written with the next one.
+pub async fn run_scheduler(
+    client: &ClobClient,
+    targets: &[SnapshotTarget],
+    db_path: &Path,
+    interval: Duration,
+    rounds: Option<usize>,
+    progress: &Progress,
+) -> Result<SchedulerReport> {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut report = SchedulerReport::default();
+    let mut pending: Vec<BookSnapshot> = Vec::new();
+
+    while rounds.map_or(true, |rounds| report.rounds < rounds) {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = progress.cancelled() => break,
+        }
+
+        let round = RoundResult::capture(client, targets, Utc::now()).await;
+        report.rounds += 1;
+        report.failed += round.failed;
+        pending.extend(round.snapshots);
+        match book_snapshots::record(db_path, &pending) {
+            Ok(()) => {
+                report.snapshots += pending.len();
+                pending.clear();
+            }
+            Err(e) => warn!(
+                "Failed to store {} snapshots, retrying next round: {}",
+                pending.len(),
+                e
+            ),
+        }
+
+        progress.inc(1);
+        progress.set_message(format!(
+            "{} books stored, {} failed",
+            report.snapshots, report.failed
+        ));
+    }
+
+    if !pending.is_empty() {
+        book_snapshots::record(db_path, &pending)
+            .with_context(|| format!("Failed to store the last {} snapshots", pending.len()))?;
+        report.snapshots += pending.len();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn config_defaults_and_levels_sort_best_first() {
+        let config: SnapshotConfig = serde_yaml::from_str("tokens: [\"123\"]").unwrap();
+        assert_eq!(config.interval().unwrap(), Duration::from_secs(300));
+        assert_eq!(config.tokens, ["123"]);
+
+        let fast = SnapshotConfig {
+            interval_secs: 1,
+            ..config
+        };
+        assert!(fast.interval().is_err());
+
+        let raw = [
+            BookSnapshotLevel {
+                price: dec!(0.45),
+                size: dec!(10),
+            },
+            BookSnapshotLevel {
+                price: dec!(0.48),
+                size: dec!(5),
+            },
+        ];
+        let bids = sorted_levels(raw.into_iter(), true);
+        let asks = sorted_levels(raw.into_iter(), false);
+        assert_eq!(bids[0].price, dec!(0.48));
+        assert_eq!(asks[0].price, dec!(0.45));
+    }
+}
@@ -0,0 +1,339 @@
+//! Spread and depth alerts for the books of held tokens
+//!
+//! `book-snapshots watch` snapshots the books of tokens with an open position
+//! or a resting order and compares every snapshot with the token's recent
+//! ones. An alert is raised when a condition starts and cleared when it ends,
+//! so a book that stays wide is reported once. Thresholds live in the `watch`
+//! section of `snapshots.yaml`:
+//!
+//! ```yaml
+//! watch:
+//!   interval_secs: 60
+//!   # Alert when the spread is wider than this, in price units
+//!   max_spread: 0.05
+//!   # Alert when the spread reaches this multiple of its recent median
+//!   widen_factor: 2
+//!   # Depth counts the size within this distance of the best price
+//!   depth_band: 0.05
+//!   # Alert when a side's depth falls this many percent below its recent median
+//!   depth_drop_pct: 60
+//!   # Snapshots making up the recent median
+//!   baseline_snapshots: 12
+//! ```
+
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+use super::book_snapshots::MIN_INTERVAL;
+use crate::typed_store::models::{BookSnapshot, BookSnapshotLevel};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    pub interval_secs: u64,
+    pub max_spread: Option<Decimal>,
+    pub widen_factor: Option<Decimal>,
+    pub depth_band: Decimal,
+    pub depth_drop_pct: Option<Decimal>,
+    pub baseline_snapshots: usize,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            max_spread: Some(Decimal::new(5, 2)),
+            widen_factor: Some(Decimal::TWO),
+            depth_band: Decimal::new(5, 2),
+            depth_drop_pct: Some(Decimal::from(60)),
+            baseline_snapshots: 12,
+        }
+    }
+}
+
+impl WatchConfig {
+    pub fn interval(&self) -> Result<Duration> {
+        let interval = Duration::from_secs(self.interval_secs);
+        if interval < MIN_INTERVAL {
+            bail!(
+                "Watch interval must be at least {}s, got {}s",
+                MIN_INTERVAL.as_secs(),
+                self.interval_secs
+            );
+        }
+        Ok(interval)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+impl fmt::Display for BookSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookSide::Bid => write!(f, "bid"),
+            BookSide::Ask => write!(f, "ask"),
+        }
+    }
+}
+
+/// What an alert is about; a token has at most one active alert of each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BookCondition {
+    /// Spread above `max_spread`
+    WideSpread,
+    /// Spread at `widen_factor` times its recent median or more
+    SpreadWidened,
+    /// Depth within `depth_band` fell `depth_drop_pct` below its recent median
+    DepthCollapsed(BookSide),
+    /// No orders left on a side
+    EmptySide(BookSide),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookAlert {
+    pub token_id: String,
+    pub condition: BookCondition,
+    /// Whether the condition started (`true`) or ended with this snapshot
+    pub raised: bool,
+    pub message: String,
+}
+
+/// Size resting within `band` of the best price of `levels` (best first)
+pub fn near_depth(levels: &[BookSnapshotLevel], band: Decimal) -> Decimal {
+    let Some(best) = levels.first().map(|level| level.price) else {
+        return Decimal::ZERO;
+    };
+    levels
+        .iter()
+        .take_while(|level| (level.price - best).abs() <= band)
+        .map(|level| level.size)
+        .sum()
+}
+
+fn median(mut values: Vec<Decimal>) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / Decimal::TWO
+    } else {
+        values[mid]
+    })
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    recent: VecDeque<BookSnapshot>,
+    active: BTreeSet<BookCondition>,
+}
+
+/// Tracks the recent books of each watched token and turns new snapshots
+/// into alerts
+#[derive(Debug)]
+pub struct BookWatcher {
+    config: WatchConfig,
+    tokens: HashMap<String, TokenState>,
+}
+
+impl BookWatcher {
+    pub fn new(config: WatchConfig) -> Self {
+        Self {
+            config,
+            tokens: HashMap::new(),
+        }
+    }
+
+    pub fn is_tracking(&self, token_id: &str) -> bool {
+        self.tokens.contains_key(token_id)
+    }
+
+    /// Start the baseline of a token from stored snapshots, oldest first
+    pub fn seed(&mut self, token_id: &str, history: Vec<BookSnapshot>) {
+        let keep = self.config.baseline_snapshots;
+        let state = self.tokens.entry(token_id.to_string()).or_default();
+        state.recent = history.into_iter().collect();
+        while state.recent.len() > keep {
+            state.recent.pop_front();
+        }
+    }
+
+    /// Stop watching tokens that are no longer held
+    pub fn retain(&mut self, token_ids: &BTreeSet<String>) {
+        self.tokens
+            .retain(|token_id, _| token_ids.contains(token_id));
+    }
+
+    /// Compare `snapshot` with the token's recent books and return the
+    /// conditions that started or ended
+    pub fn observe(&mut self, snapshot: BookSnapshot) -> Vec<BookAlert> {
+        let config = &self.config;
+        let state = self.tokens.entry(snapshot.token_id.clone()).or_default();
+
+        let baseline_spread = median(state.recent.iter().filter_map(|s| s.spread()).collect());
+        let baseline_depth = |side: BookSide| {
+            median(
+                state
+                    .recent
+                    .iter()
+                    .map(|s| near_depth(levels(s, side), config.depth_band))
+                    .collect(),
+            )
+        };
+
+        let mut current: Vec<(BookCondition, String)> = Vec::new();
+        let spread = snapshot.spread();
+        if let (Some(spread), Some(max_spread)) = (spread, config.max_spread) {
+            if spread > max_spread {
+                current.push((
+                    BookCondition::WideSpread,
+                    format!("spread {} is above {}", spread, max_spread),
+                ));
+            }
+        }
+        if let (Some(spread), Some(baseline), Some(factor)) =
+            (spread, baseline_spread, config.widen_factor)
+        {
+            if baseline > Decimal::ZERO && spread >= baseline * factor {
+                current.push((
+                    BookCondition::SpreadWidened,
+                    format!(
+                        "spread widened to {} from a recent {}",
+                        spread,
+                        baseline.normalize()
+                    ),
+                ));
+            }
+        }
+        for side in [BookSide::Bid, BookSide::Ask] {
+            let book_levels = levels(&snapshot, side);
+            if book_levels.is_empty() {
+                current.push((
+                    BookCondition::EmptySide(side),
+                    format!("no {}s left in the book", side),
+                ));
+                continue;
+            }
+            let (Some(baseline), Some(drop_pct)) = (baseline_depth(side), config.depth_drop_pct)
+            else {
+                continue;
+            };
+            let depth = near_depth(book_levels, config.depth_band);
+            let floor = baseline * (Decimal::ONE_HUNDRED - drop_pct) / Decimal::ONE_HUNDRED;
+            if baseline > Decimal::ZERO && depth <= floor {
+                current.push((
+                    BookCondition::DepthCollapsed(side),
+                    format!(
+                        "{} depth fell to {} from a recent {}",
+                        side,
+                        depth.round_dp(2),
+                        baseline.round_dp(2)
+                    ),
+                ));
+            }
+        }
+
+        let mut alerts = Vec::new();
+        let now: BTreeSet<BookCondition> = current.iter().map(|(c, _)| *c).collect();
+        for cleared in state.active.difference(&now) {
+            alerts.push(BookAlert {
+                token_id: snapshot.token_id.clone(),
+                condition: *cleared,
+                raised: false,
+                message: format!("{} recovered", describe(*cleared)),
+            });
+        }
+        for (condition, message) in current {
+            if !state.active.contains(&condition) {
+                alerts.push(BookAlert {
+                    token_id: snapshot.token_id.clone(),
+                    condition,
+                    raised: true,
+                    message,
+                });
+            }
+        }
+        state.active = now;
+
+        state.recent.push_back(snapshot);
+        while state.recent.len() > config.baseline_snapshots {
+            state.recent.pop_front();
+        }
+        alerts
+    }
+}
+
+fn levels(snapshot: &BookSnapshot, side: BookSide) -> &[BookSnapshotLevel] {
+    match side {
+        BookSide::Bid => &snapshot.bids,
+        BookSide::Ask => &snapshot.asks,
+    }
+}
+
+fn describe(condition: BookCondition) -> String {
+    match condition {
+        BookCondition::WideSpread => "wide spread".to_string(),
+        BookCondition::SpreadWidened => "spread widening".to_string(),
+        BookCondition::DepthCollapsed(side) => format!("{} depth", side),
+        BookCondition::EmptySide(side) => format!("{} side", side),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn book(bid: Decimal, ask: Decimal, size: Decimal) -> BookSnapshot {
+        BookSnapshot {
+            token_id: "1".to_string(),
+            condition_id: None,
+            captured_at: Utc::now(),
+            bids: vec![BookSnapshotLevel { price: bid, size }],
+            asks: vec![BookSnapshotLevel { price: ask, size }],
+        }
+    }
+
+    #[test]
+    fn alerts_fire_once_per_condition_and_clear_on_recovery() {
+        let mut watcher = BookWatcher::new(WatchConfig::default());
+        watcher.seed("1", vec![book(dec!(0.48), dec!(0.50), dec!(1000)); 3]);
+
+        assert!(watcher
+            .observe(book(dec!(0.48), dec!(0.50), dec!(900)))
+            .is_empty());
+
+        let alerts = watcher.observe(book(dec!(0.44), dec!(0.52), dec!(100)));
+        let raised: Vec<BookCondition> = alerts.iter().map(|a| a.condition).collect();
+        assert!(alerts.iter().all(|a| a.raised));
+        assert!(raised.contains(&BookCondition::WideSpread));
+        assert!(raised.contains(&BookCondition::SpreadWidened));
+        assert!(raised.contains(&BookCondition::DepthCollapsed(BookSide::Bid)));
+
+        // Still wide against a baseline that now includes the wide book
+        let again = watcher.observe(book(dec!(0.44), dec!(0.52), dec!(100)));
+        assert!(again.is_empty());
+
+        let recovered = watcher.observe(book(dec!(0.48), dec!(0.50), dec!(1000)));
+        assert!(recovered
+            .iter()
+            .any(|a| !a.raised && a.condition == BookCondition::WideSpread));
+
+        let mut empty = book(dec!(0.48), dec!(0.50), dec!(1000));
+        empty.asks.clear();
+        let alerts = watcher.observe(empty);
+        assert!(alerts
+            .iter()
+            .any(|a| a.raised && a.condition == BookCondition::EmptySide(BookSide::Ask)));
+    }
+}
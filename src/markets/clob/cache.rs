@@ -1,3 +1,6 @@
+use crate::core::http_cache::{get_json, Endpoint};
+use crate::markets::gamma::query::GAMMA_MARKETS_URL;
+use crate::markets::gamma::{MarketOrder, MarketQuery};
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
@@ -255,13 +258,20 @@ async fn fetch_gamma_markets() -> Result<Vec<serde_json::Value>> {
 
     // Fetch multiple pages to get more markets
     for _ in 0..10 {
-        let url = format!(
-            "https://gamma-api.polymarket.com/markets?limit={}&offset={}&order=volume&ascending=false",
-            limit, offset
-        );
-
-        let response = client.get(&url).send().await?;
-        let markets: Vec<serde_json::Value> = response.json().await?;
+        let query = MarketQuery::builder()
+            .limit(limit)
+            .offset(offset)
+            .order(MarketOrder::Volume)
+            .ascending(false)
+            .build()?;
+
+        let markets: Vec<serde_json::Value> = get_json(
+            &client,
+            Endpoint::Markets,
+            GAMMA_MARKETS_URL,
+            &query.to_params(),
+        )
+        .await?;
 
         if markets.is_empty() {
             break;
@@ -2,6 +2,8 @@ use anyhow::Result;
 use owo_colors::OwoColorize;
 use serde_json::Value;
 
+use crate::number_format;
+
 /// Display market information from CLOB API
 pub fn display_market_info(market: &Value, idx: usize, detailed: bool) -> Result<()> {
     let question = market
@@ -44,7 +46,7 @@ pub fn display_market_info(market: &Value, idx: usize, detailed: bool) -> Result
             let price = token
                 .get("price")
                 .and_then(|v| v.as_f64())
-                .map(|p| format!("${:.4}", p))
+                .map(|p| format!("${}", number_format::price(p)))
                 .unwrap_or_else(|| "N/A".to_string());
 
             println!(
@@ -107,11 +109,19 @@ pub fn display_gamma_market_info(market: &Value, idx: usize, detailed: bool) ->
 
     if detailed {
         if let Some(volume) = market.get("volume").and_then(|v| v.as_f64()) {
-            println!("   {} ${:.2}", "Volume:".bright_black(), volume);
+            println!(
+                "   {} {}",
+                "Volume:".bright_black(),
+                number_format::usd(volume)
+            );
         }
 
         if let Some(liquidity) = market.get("liquidity").and_then(|v| v.as_f64()) {
-            println!("   {} ${:.2}", "Liquidity:".bright_black(), liquidity);
+            println!(
+                "   {} {}",
+                "Liquidity:".bright_black(),
+                number_format::usd(liquidity)
+            );
         }
 
         if let Some(active) = market.get("active").and_then(|v| v.as_bool()) {
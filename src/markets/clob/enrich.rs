@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use futures::future::join_all;
 use polymarket_rs_client::ClobClient;
@@ -16,8 +16,15 @@ use tokio::time::sleep;
 use tracing::{info, warn};
 
 use crate::cli::commands::enrich::EnrichArgs;
+use crate::core::http_cache::{get_json, Endpoint};
+use crate::core::memory::{write_json_array, MemoryBudget, MemoryLimitExceeded, SpillVec};
+use crate::core::progress::{ctrl_c_token, Cancelled, ProgressReporter};
 use crate::data_paths::DataPaths;
-use crate::markets::datasets::save_command_metadata;
+use crate::markets::datasets::report_dataset;
+use crate::markets::gamma::query::GAMMA_MARKETS_URL;
+use crate::markets::gamma::{ConditionId, MarketQuery};
+use crate::typed_store::freshness;
+use crate::typed_store::EnrichmentFreshness;
 
 /// Market enrichment configuration and execution engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +37,12 @@ pub struct MarketEnricher {
     pub config: EnrichmentConfig,
     /// Execution options
     pub execution_options: ExecutionOptions,
+    /// Where per-market progress goes
+    #[serde(skip)]
+    pub progress: ProgressReporter,
+    /// Memory limit; past it enriched markets spill to disk
+    #[serde(skip)]
+    pub budget: MemoryBudget,
 }
 
 /// Configuration for market enrichment options
@@ -61,6 +74,12 @@ pub struct ExecutionOptions {
     /// Show progress during processing
     #[serde(default)]
     pub progress: bool,
+    /// Skip markets whose tokens were all enriched within `max_age_secs`
+    #[serde(default)]
+    pub stale_only: bool,
+    /// Age in seconds after which enrichment data is stale
+    #[serde(default = "default_max_age_secs")]
+    pub max_age_secs: u64,
 }
 
 /// Market data structure
@@ -110,6 +129,26 @@ pub struct EnrichedMarket {
     pub enriched_at: DateTime<Utc>,
 }
 
+impl EnrichedMarket {
+    /// Load the `enriched_markets.json` of an enriched dataset
+    pub fn load_dataset(dataset_path: &Path) -> Result<Vec<Self>> {
+        let path = dataset_path.join("enriched_markets.json");
+        let content = fs::read_to_string(&path).with_context(|| {
+            format!(
+                "No enriched markets at {}; run 'polybot enrich' first",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+/// Markets enriched in one run, and why it stopped early if memory ran out
+struct EnrichedBatch {
+    markets: SpillVec<EnrichedMarket>,
+    out_of_memory: Option<MemoryLimitExceeded>,
+}
+
 /// Market enrichment data
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MarketEnrichment {
@@ -122,6 +161,9 @@ pub struct MarketEnrichment {
     /// Volume metrics
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume: Option<VolumeMetrics>,
+    /// Price levels of the first token's book, up to `max_depth` per side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<BookDepth>,
     /// Processing status
     pub status: EnrichmentStatus,
 }
@@ -138,6 +180,20 @@ pub struct OrderbookMetrics {
     pub ask_levels: usize,
 }
 
+/// Book levels captured at enrichment time, best first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookDepth {
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+/// A single price level
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
 /// Liquidity metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiquidityMetrics {
@@ -265,7 +321,13 @@ impl MarketEnricher {
                 parallel: args.parallel,
                 start_from: args.start_from,
                 progress: args.progress,
+                stale_only: args.stale_only,
+                max_age_secs: args.max_age.as_secs(),
             },
+            progress: ProgressReporter::terminal_if(args.progress),
+            budget: MemoryBudget::new("Enrichment", args.memory_limit).with_guidance(
+                "Resume with the --start-from index above, or enrich a smaller source dataset",
+            ),
         }
     }
 
@@ -282,75 +344,82 @@ impl MarketEnricher {
         info!("📁 Output directory: {}", output_path.display());
 
         // Load source dataset
-        let source_path = self.resolve_source_path(data_paths)?;
-        let markets = self.load_source_markets(&source_path).await?;
+        let source_path = data_paths.source_dataset(&self.source_dataset)?;
+        let mut markets = self.load_source_markets(&source_path).await?;
+        if self.execution_options.stale_only {
+            markets = self.stale_markets(data_paths, markets);
+        }
 
         // Get authenticated client
         let client = Arc::new(crate::auth::get_authenticated_client(host, data_paths).await?);
 
         // Process markets
-        let enriched_markets = self.process_markets(&client, &markets).await?;
+        let EnrichedBatch {
+            markets: mut enriched_markets,
+            out_of_memory,
+        } = self
+            .process_markets(&client, &markets, &output_path)
+            .await?;
 
         // Save results
-        self.save_results(&output_path, &enriched_markets).await?;
+        self.save_results(&output_path, &mut enriched_markets)
+            .await?;
+        self.record_freshness(data_paths, &mut enriched_markets)?;
+
+        let next = self.execution_options.start_from.unwrap_or(0) + enriched_markets.len();
+        if self.progress.is_cancelled() {
+            warn!(
+                "⏹ Enrichment cancelled; saved {} of {} markets. Resume with --start-from {}",
+                next,
+                markets.len(),
+                next
+            );
+            return Err(Cancelled("Enrichment".to_string()).into());
+        }
+        if let Some(out_of_memory) = out_of_memory {
+            warn!(
+                "💾 Enrichment stopped at its memory limit; saved {} of {} markets. Resume with --start-from {}",
+                next,
+                markets.len(),
+                next
+            );
+            return Err(out_of_memory.into());
+        }
 
         // Calculate statistics
-        let statistics = self.calculate_statistics(&enriched_markets);
+        let statistics = self.calculate_statistics(enriched_markets.iter()?)?;
+        let mut successful = 0;
+        for market in enriched_markets.iter()? {
+            if market?.enrichment.status.success {
+                successful += 1;
+            }
+        }
 
-        // Save command metadata
-        self.save_command_metadata(&output_path, markets.len(), enriched_markets.len())?;
+        // Report the dataset; its metadata is written when the command succeeds
+        self.report_output(&output_path, markets.len(), enriched_markets.len());
 
         let execution_time = start_time.elapsed().as_millis() as u64;
 
         // Display results
-        self.display_results(markets.len(), &enriched_markets, &statistics);
+        self.display_results(
+            markets.len(),
+            enriched_markets.len(),
+            successful,
+            &statistics,
+        );
 
         Ok(EnrichmentResult {
             total_markets: markets.len(),
-            successfully_enriched: enriched_markets
-                .iter()
-                .filter(|m| m.enrichment.status.success)
-                .count(),
-            failed_enrichments: enriched_markets
-                .iter()
-                .filter(|m| !m.enrichment.status.success)
-                .count(),
+            successfully_enriched: successful,
+            failed_enrichments: enriched_markets.len() - successful,
             execution_time_ms: execution_time,
             output_path,
             statistics,
         })
     }
 
-    /// Resolve the source dataset path
-    fn resolve_source_path(&self, data_paths: &DataPaths) -> Result<PathBuf> {
-        let source_path =
-            if self.source_dataset.starts_with('/') || self.source_dataset.starts_with("./") {
-                // Absolute or relative path provided
-                PathBuf::from(&self.source_dataset)
-            } else {
-                // Dataset name provided, look in datasets directory
-                data_paths.datasets().join(&self.source_dataset)
-            };
-
-        if !source_path.exists() {
-            return Err(anyhow!(
-                "Source dataset not found: {}",
-                source_path.display()
-            ));
-        }
-
-        Ok(source_path)
-    }
-
     /// Load markets from source dataset
     async fn load_source_markets(&self, source_path: &Path) -> Result<Vec<Market>> {
-        if !source_path.exists() {
-            return Err(anyhow!(
-                "Source dataset not found: {}",
-                source_path.display()
-            ));
-        }
-
         let markets_file = source_path.join("markets.json");
         let mut file = File::open(&markets_file)?;
         let mut contents = String::new();
@@ -483,17 +552,26 @@ impl MarketEnricher {
     }
 
     /// Process all markets with enrichment
+    ///
+    /// Stops early, keeping the markets enriched so far, when cancelled or
+    /// when memory grows past the budget's hard limit; the latter is kept in
+    /// the batch for the caller to report after saving.
     async fn process_markets(
         &self,
         client: &Arc<ClobClient>,
         markets: &[Market],
-    ) -> Result<Vec<EnrichedMarket>> {
+        output_path: &Path,
+    ) -> Result<EnrichedBatch> {
         let rate_limiter = Arc::new(RateLimiter::new(50));
         let chunk_size = self.execution_options.parallel.min(10);
         let start_idx = self.execution_options.start_from.unwrap_or(0);
 
-        let progress = Arc::new(Mutex::new((0usize, 0usize)));
         let total_markets = markets.len();
+        let task = self.progress.task(
+            "Enriching markets",
+            Some(total_markets.saturating_sub(start_idx) as u64),
+        );
+        let errors = Arc::new(Mutex::new(0usize));
 
         info!(
             "⚡ Processing {} markets with {} concurrent requests",
@@ -501,10 +579,21 @@ impl MarketEnricher {
             chunk_size
         );
 
-        let start_time = Instant::now();
-        let mut all_enriched = Vec::new();
+        let mut all_enriched = SpillVec::new(
+            self.budget.clone(),
+            output_path.join(".enriched_markets.spill.jsonl"),
+        );
+        let mut out_of_memory = None;
 
         for chunk_start in (start_idx..markets.len()).step_by(chunk_size) {
+            // Markets enriched so far are saved by the caller
+            if task.is_cancelled() {
+                break;
+            }
+            if let Err(e) = self.budget.check() {
+                out_of_memory = Some(e);
+                break;
+            }
             let chunk_end = (chunk_start + chunk_size).min(markets.len());
             let chunk_markets = &markets[chunk_start..chunk_end];
 
@@ -513,10 +602,10 @@ impl MarketEnricher {
             for (idx, market) in chunk_markets.iter().enumerate() {
                 let client = Arc::clone(client);
                 let rate_limiter = Arc::clone(&rate_limiter);
-                let progress = Arc::clone(&progress);
+                let task = task.clone();
+                let errors = Arc::clone(&errors);
                 let market = market.clone();
                 let config = self.config.clone();
-                let show_progress = self.execution_options.progress;
                 let market_idx = chunk_start + idx;
 
                 let future = async move {
@@ -551,32 +640,12 @@ impl MarketEnricher {
                         },
                     };
 
-                    let mut prog = progress.lock().await;
-                    prog.0 += 1;
                     if !enriched.enrichment.status.success {
-                        prog.1 += 1;
-                    }
-
-                    if show_progress {
-                        let elapsed = start_time.elapsed().as_secs();
-                        let rate = if elapsed > 0 {
-                            prog.0 as f64 / elapsed as f64
-                        } else {
-                            0.0
-                        };
-
-                        print!(
-                            "\r{}",
-                            format!(
-                                "Processing market {}/{} (errors: {}) - {:.1} markets/sec",
-                                prog.0 + start_idx,
-                                total_markets,
-                                prog.1,
-                                rate
-                            )
-                        );
-                        let _ = std::io::stdout().flush();
+                        let mut errors = errors.lock().await;
+                        *errors += 1;
+                        task.set_message(format!("errors: {}", *errors));
                     }
+                    task.inc(1);
 
                     (market_idx, enriched)
                 };
@@ -587,7 +656,7 @@ impl MarketEnricher {
             let chunk_results = join_all(futures).await;
 
             for (_idx, enriched) in chunk_results {
-                all_enriched.push(enriched);
+                all_enriched.push(enriched)?;
             }
 
             // Save progress periodically
@@ -596,11 +665,25 @@ impl MarketEnricher {
             }
         }
 
-        if self.execution_options.progress {
-            println!();
+        if task.is_cancelled() {
+            task.finish("cancelled");
+        } else if out_of_memory.is_some() {
+            task.finish("memory limit reached");
+        } else {
+            task.finish(format!("errors: {}", *errors.lock().await));
+        }
+        if all_enriched.spilled() > 0 {
+            info!(
+                "💾 {} of {} enriched markets were spilled to disk",
+                all_enriched.spilled(),
+                all_enriched.len()
+            );
         }
 
-        Ok(all_enriched)
+        Ok(EnrichedBatch {
+            markets: all_enriched,
+            out_of_memory,
+        })
     }
 
     /// Enrich a single market with additional data
@@ -634,6 +717,7 @@ impl MarketEnricher {
                         } else {
                             warnings.push("Empty orderbook - no metrics available".to_string());
                         }
+                        enrichment.depth = Some(Self::book_depth(&orderbook, config.max_depth));
                     }
 
                     // Calculate liquidity metrics
@@ -703,6 +787,39 @@ impl MarketEnricher {
         })
     }
 
+    /// Keep the best `max_depth` levels of each side
+    fn book_depth(
+        orderbook: &polymarket_rs_client::OrderBookSummary,
+        max_depth: usize,
+    ) -> BookDepth {
+        let level = |l: &polymarket_rs_client::OrderSummary| DepthLevel {
+            price: l.price,
+            size: l.size,
+        };
+        BookDepth {
+            bids: Self::depth_levels(orderbook.bids.iter().map(level), true, max_depth),
+            asks: Self::depth_levels(orderbook.asks.iter().map(level), false, max_depth),
+        }
+    }
+
+    /// Levels sorted best first (highest bids, lowest asks), truncated to `max_depth`
+    fn depth_levels(
+        levels: impl Iterator<Item = DepthLevel>,
+        descending: bool,
+        max_depth: usize,
+    ) -> Vec<DepthLevel> {
+        let mut levels: Vec<DepthLevel> = levels.collect();
+        levels.sort_by(|a, b| {
+            if descending {
+                b.price.cmp(&a.price)
+            } else {
+                a.price.cmp(&b.price)
+            }
+        });
+        levels.truncate(max_depth);
+        levels
+    }
+
     /// Calculate liquidity metrics
     fn calculate_liquidity_metrics(
         orderbook: &polymarket_rs_client::OrderBookSummary,
@@ -740,13 +857,17 @@ impl MarketEnricher {
             .ok_or_else(|| anyhow!("Market has no condition_id"))?;
 
         let client = reqwest::Client::new();
-        let url = format!(
-            "https://gamma-api.polymarket.com/markets?condition_id={}",
-            condition_id
-        );
-
-        let response = client.get(&url).send().await?;
-        let gamma_markets: Vec<serde_json::Value> = response.json().await?;
+        let query = MarketQuery::builder()
+            .condition_id(ConditionId(condition_id.clone()))
+            .build()?;
+
+        let gamma_markets: Vec<serde_json::Value> = get_json(
+            &client,
+            Endpoint::Markets,
+            GAMMA_MARKETS_URL,
+            &query.to_params(),
+        )
+        .await?;
 
         let gamma_market = gamma_markets
             .first()
@@ -763,13 +884,95 @@ impl MarketEnricher {
         })
     }
 
+    /// Markets with at least one token not enriched within the max age
+    fn stale_markets(&self, data_paths: &DataPaths, markets: Vec<Market>) -> Vec<Market> {
+        let db_path = freshness::default_db_path(data_paths);
+        let tracked = match freshness::load(&db_path) {
+            Ok(tracked) => tracked,
+            Err(e) => {
+                warn!("⚠️ Cannot read enrichment freshness ({}), enriching all markets", e);
+                return markets;
+            }
+        };
+
+        let max_age = Duration::from_secs(self.execution_options.max_age_secs);
+        let now = Utc::now();
+        let total = markets.len();
+        let stale: Vec<Market> = markets
+            .into_iter()
+            .filter(|market| {
+                market.tokens.is_empty()
+                    || market.tokens.iter().any(|token| {
+                        freshness::is_stale(tracked.get(&token.token_id), max_age, now)
+                    })
+            })
+            .collect();
+        info!(
+            "🕒 {} of {} markets have enrichment older than {} ({} still fresh)",
+            stale.len(),
+            total,
+            freshness::format_age(chrono::Duration::seconds(max_age.as_secs() as i64)),
+            total - stale.len()
+        );
+        stale
+    }
+
+    /// Note the refresh time of every token whose market was enriched successfully
+    fn record_freshness(
+        &self,
+        data_paths: &DataPaths,
+        markets: &mut SpillVec<EnrichedMarket>,
+    ) -> Result<()> {
+        let mut entries: Vec<EnrichmentFreshness> = Vec::new();
+        for m in markets.iter()? {
+            let m = m?;
+            if !m.enrichment.status.success {
+                continue;
+            }
+            entries.extend(m.market.tokens.iter().map(|token| EnrichmentFreshness {
+                token_id: token.token_id.clone(),
+                condition_id: m.market.condition_id.clone(),
+                refreshed_at: m.enriched_at,
+                dataset: self.output_dataset.clone(),
+                has_orderbook: m.enrichment.status.has_orderbook,
+            }));
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        // Freshness is bookkeeping; a locked store must not fail the enrichment
+        let db_path = freshness::default_db_path(data_paths);
+        match freshness::record(&db_path, &entries) {
+            Ok(()) => info!("🕒 Recorded enrichment time of {} tokens", entries.len()),
+            Err(e) => warn!("⚠️ Failed to record enrichment freshness: {}", e),
+        }
+        Ok(())
+    }
+
     /// Save enrichment results
-    async fn save_results(&self, output_path: &Path, markets: &[EnrichedMarket]) -> Result<()> {
-        // Save enriched markets
+    async fn save_results(
+        &self,
+        output_path: &Path,
+        markets: &mut SpillVec<EnrichedMarket>,
+    ) -> Result<()> {
+        // Save enriched markets. A resumed run (`--start-from N`) continues
+        // the file an interrupted run left: its first N markets are kept.
         let markets_file = output_path.join("enriched_markets.json");
-        let json = serde_json::to_string_pretty(&markets)?;
-        let mut file = File::create(&markets_file)?;
-        file.write_all(json.as_bytes())?;
+        let earlier: Vec<EnrichedMarket> = match self.execution_options.start_from {
+            Some(start) if markets_file.exists() => {
+                let existing: Vec<EnrichedMarket> =
+                    serde_json::from_str(&fs::read_to_string(&markets_file)?)?;
+                existing.into_iter().take(start).collect()
+            }
+            _ => Vec::new(),
+        };
+        // Written through a temporary file so an interrupt can't truncate
+        // it; spilled markets are streamed back from disk
+        write_json_array(
+            &markets_file,
+            earlier.into_iter().map(Ok).chain(markets.iter()?),
+        )?;
 
         // Save enrichment configuration
         let config_file = output_path.join("enrichment_config.yaml");
@@ -781,7 +984,10 @@ impl MarketEnricher {
     }
 
     /// Calculate enrichment statistics
-    fn calculate_statistics(&self, markets: &[EnrichedMarket]) -> EnrichmentStatistics {
+    fn calculate_statistics(
+        &self,
+        markets: impl Iterator<Item = Result<EnrichedMarket>>,
+    ) -> Result<EnrichmentStatistics> {
         let mut markets_with_orderbook = 0;
         let mut markets_with_liquidity = 0;
         let mut markets_with_volume = 0;
@@ -790,8 +996,11 @@ impl MarketEnricher {
         let mut total_processing_time = 0u64;
         let mut total_bid_volume = 0.0;
         let mut total_ask_volume = 0.0;
+        let mut market_count = 0;
 
         for market in markets {
+            let market = market?;
+            market_count += 1;
             if market.enrichment.status.has_orderbook {
                 markets_with_orderbook += 1;
             }
@@ -821,13 +1030,13 @@ impl MarketEnricher {
             0.0
         };
 
-        let avg_processing_time = if !markets.is_empty() {
-            total_processing_time as f64 / markets.len() as f64
+        let avg_processing_time = if market_count > 0 {
+            total_processing_time as f64 / market_count as f64
         } else {
             0.0
         };
 
-        EnrichmentStatistics {
+        Ok(EnrichmentStatistics {
             markets_with_orderbook,
             markets_with_liquidity,
             markets_with_volume,
@@ -835,18 +1044,16 @@ impl MarketEnricher {
             average_processing_time_ms: avg_processing_time,
             total_bid_volume,
             total_ask_volume,
-        }
+        })
     }
 
-    /// Save command metadata
-    fn save_command_metadata(
+    /// Report the produced dataset with enrichment details for its metadata
+    fn report_output(
         &self,
         output_path: &Path,
         total_markets: usize,
         enriched_count: usize,
-    ) -> Result<()> {
-        let command_args = vec![self.source_dataset.clone(), self.output_dataset.clone()];
-
+    ) {
         let mut additional_info = HashMap::new();
         additional_info.insert(
             "dataset_type".to_string(),
@@ -877,27 +1084,18 @@ impl MarketEnricher {
             serde_json::json!(self.config.include_volume),
         );
 
-        if let Err(e) =
-            save_command_metadata(output_path, "enrich", &command_args, Some(additional_info))
-        {
-            warn!("Warning: Failed to save command metadata: {}", e);
-        }
-
-        Ok(())
+        report_dataset(output_path, additional_info);
     }
 
     /// Display enrichment results
     fn display_results(
         &self,
         total_markets: usize,
-        enriched_markets: &[EnrichedMarket],
+        enriched_count: usize,
+        successful: usize,
         statistics: &EnrichmentStatistics,
     ) {
-        let successful = enriched_markets
-            .iter()
-            .filter(|m| m.enrichment.status.success)
-            .count();
-        let failed = enriched_markets.len() - successful;
+        let failed = enriched_count - successful;
 
         info!(
             "✅ Enriched {} markets (errors: {}) - Success rate: {:.1}%",
@@ -960,6 +1158,8 @@ impl Default for ExecutionOptions {
             parallel: default_parallel(),
             start_from: None,
             progress: false,
+            stale_only: false,
+            max_age_secs: default_max_age_secs(),
         }
     }
 }
@@ -970,10 +1170,14 @@ fn default_max_depth() -> usize {
 fn default_parallel() -> usize {
     5
 }
+fn default_max_age_secs() -> u64 {
+    freshness::DEFAULT_MAX_AGE.as_secs()
+}
 
 /// Main entry point for market enrichment
 pub async fn enrich_markets(host: &str, data_paths: DataPaths, args: EnrichArgs) -> Result<()> {
-    let enricher = MarketEnricher::from_args(args);
+    let mut enricher = MarketEnricher::from_args(args);
+    enricher.progress = enricher.progress.with_cancellation(ctrl_c_token());
     enricher.execute(host, &data_paths).await?;
     Ok(())
 }
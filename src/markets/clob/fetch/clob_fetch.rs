@@ -1,7 +1,9 @@
 use anyhow::Result;
 use owo_colors::OwoColorize;
 use polymarket_rs_client::ClobClient;
+use tokio_util::sync::CancellationToken;
 
+use crate::core::progress::ProgressReporter;
 use crate::markets::clob::{
     fetcher::{FetcherConfig, MarketFetcher},
     providers::ClobProvider,
@@ -22,6 +24,7 @@ pub async fn fetch_all_markets(
     verbose: bool,
     clear_state: bool,
     chunk_size_mb: f64,
+    cancel: CancellationToken,
 ) -> Result<()> {
     // Create storage
     let storage = MarketStorage::new(output_dir, chunk_size_mb)?;
@@ -50,7 +53,8 @@ pub async fn fetch_all_markets(
     } else {
         Default::default()
     };
-    let mut fetcher = MarketFetcher::with_config(provider, storage, config);
+    let mut fetcher = MarketFetcher::with_config(provider, storage, config)
+        .with_progress(ProgressReporter::terminal().with_cancellation(cancel));
 
     // Fetch all markets
     fetcher
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use owo_colors::OwoColorize;
+use tokio_util::sync::CancellationToken;
 
+use crate::core::progress::ProgressReporter;
 use crate::markets::clob::{
     fetcher::{FetcherConfig, MarketFetcher},
     providers::GammaProvider,
@@ -20,6 +22,7 @@ pub async fn fetch_all_markets_gamma(
     verbose: bool,
     clear_state: bool,
     chunk_size_mb: f64,
+    cancel: CancellationToken,
 ) -> Result<()> {
     // Create storage
     let storage = MarketStorage::new(output_dir, chunk_size_mb)?;
@@ -47,7 +50,8 @@ pub async fn fetch_all_markets_gamma(
     } else {
         Default::default()
     };
-    let mut fetcher = MarketFetcher::with_config(provider, storage, config);
+    let mut fetcher = MarketFetcher::with_config(provider, storage, config)
+        .with_progress(ProgressReporter::terminal().with_cancellation(cancel));
 
     // Fetch all markets
     fetcher
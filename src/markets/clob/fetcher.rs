@@ -2,11 +2,11 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::{self, Write};
 use tracing::{info, warn};
 
 use super::providers::MarketDataProvider;
 use super::storage::MarketStorage;
+use crate::core::progress::{Cancelled, Progress, ProgressReporter};
 
 /// Strongly typed market data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +62,8 @@ pub struct MarketFetcher<T: MarketDataProvider> {
     provider: T,
     storage: MarketStorage,
     config: FetcherConfig,
+    #[serde(skip)]
+    progress: ProgressReporter,
 }
 
 /// Configuration for the market fetcher
@@ -171,41 +173,14 @@ impl FetchProgress {
         self.last_update = now;
     }
 
-    fn display_detailed(&mut self, status: &str) {
+    /// Show the counters on the fetch's progress task
+    fn report(&mut self, task: &Progress, status: &str) {
         self.update_rates();
-
-        let elapsed_seconds = (Utc::now().timestamp() - self.start_time.timestamp()) as f64;
-
-        let progress_pct = if let Some(total) = self.estimated_total_markets {
-            (self.markets_fetched as f64 / total as f64 * 100.0).min(100.0)
-        } else {
-            0.0
-        };
-
-        print!("\r{}", " ".repeat(120)); // Clear line
-        print!(
-            "\r{} {} [{:>3.0}%] Pages: {} | Markets: {} | Chunks: {} | {:.0}/s | {}",
-            status,
-            progress_bar(progress_pct),
-            progress_pct,
-            self.pages_fetched,
-            self.markets_fetched,
-            self.chunks_saved,
-            self.average_rate_per_second,
-            format_duration(std::time::Duration::from_secs(elapsed_seconds as u64))
-        );
-
-        if let Some(eta) = self.estimated_completion_time {
-            let remaining = eta.timestamp() - Utc::now().timestamp();
-            if remaining > 0 {
-                print!(
-                    " | ETA: {}",
-                    format_duration(std::time::Duration::from_secs(remaining as u64))
-                );
-            }
-        }
-
-        let _ = io::stdout().flush();
+        task.set_position(self.markets_fetched as u64);
+        task.set_message(format!(
+            "{} | Pages: {} | Chunks: {} | {:.0}/s",
+            status, self.pages_fetched, self.chunks_saved, self.average_rate_per_second
+        ));
     }
 }
 
@@ -380,13 +355,6 @@ impl MarketToken {
     }
 }
 
-fn progress_bar(percentage: f64) -> String {
-    let width = 20;
-    let filled = (percentage / 100.0 * width as f64) as usize;
-    let empty = width - filled;
-    format!("{}{}{}{}", "[", "█".repeat(filled), "░".repeat(empty), "]")
-}
-
 fn format_duration(duration: std::time::Duration) -> String {
     let secs = duration.as_secs();
     if secs < 60 {
@@ -404,9 +372,16 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
             provider,
             storage,
             config,
+            progress: ProgressReporter::terminal(),
         }
     }
 
+    /// Report the fetch here instead of a terminal bar
+    pub fn with_progress(mut self, progress: ProgressReporter) -> Self {
+        self.progress = progress;
+        self
+    }
+
     /// Fetch all markets with resumable state and strong typing
     pub async fn fetch_all<S>(
         &mut self,
@@ -430,9 +405,13 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
         info!("🔄 Fetching all markets from {}...", self.provider.name());
 
         let mut progress = FetchProgress::new();
+        let task = self
+            .progress
+            .task(format!("Markets from {}", self.provider.name()), None);
         let mut current_chunk: Vec<Market> = Vec::new();
         let mut current_chunk_size: usize = 0;
         let mut page_token = state.get_page_token();
+        let mut cancelled = false;
 
         // Show initial progress
         info!("⏳ Starting fetch... (this may take a few minutes)");
@@ -442,14 +421,12 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
             // Check termination conditions
             if let Some(ref token) = page_token {
                 if token == "LTE=" || token.is_empty() {
-                    println!();
                     info!("📍 Reached end of data");
                     break;
                 }
             }
 
             if !self.provider.has_more_pages() && page_token.is_none() {
-                println!();
                 info!("📍 No more pages to fetch");
                 break;
             }
@@ -457,14 +434,19 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
             // Check max pages limit
             if let Some(max_pages) = self.config.max_pages {
                 if progress.pages_fetched >= max_pages {
-                    println!();
                     info!("📍 Reached maximum page limit: {}", max_pages);
                     break;
                 }
             }
 
+            // Stop between pages; what was fetched so far is saved below
+            if task.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+
             // Show progress
-            progress.display_detailed("🔄 Fetching");
+            progress.report(&task, "🔄 Fetching");
 
             // Add delay between requests
             if progress.pages_fetched > 0 && self.config.delay_between_requests_ms > 0 {
@@ -474,11 +456,17 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
                 .await;
             }
 
-            // Fetch next page with retry logic
-            let (raw_markets, next_token) = self.fetch_page_with_retry(page_token.clone()).await?;
+            // Fetch next page with retry logic, giving up on it when cancelled
+            let page = tokio::select! {
+                page = self.fetch_page_with_retry(page_token.clone()) => page?,
+                _ = task.cancelled() => {
+                    cancelled = true;
+                    break;
+                }
+            };
+            let (raw_markets, next_token) = page;
 
             if raw_markets.is_empty() {
-                println!();
                 info!("📍 No more markets to fetch");
                 break;
             }
@@ -499,7 +487,7 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
                     && current_chunk_size + market_size > self.config.chunk_size_bytes
                 {
                     // Save chunk
-                    progress.display_detailed("💾 Saving chunk");
+                    progress.report(&task, "💾 Saving chunk");
 
                     let chunk_number = state.get_chunk_number() + 1;
                     self.save_chunk_typed(chunk_number, &current_chunk, chunk_prefix)
@@ -511,7 +499,6 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
 
                     progress.chunks_saved += 1;
 
-                    println!();
                     info!(
                         "💾 Saved chunk {} with {} markets",
                         chunk_number,
@@ -548,7 +535,7 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
 
         // Save final chunk
         if !current_chunk.is_empty() {
-            progress.display_detailed("💾 Saving final chunk");
+            progress.report(&task, "💾 Saving final chunk");
 
             let chunk_number = state.get_chunk_number() + 1;
             self.save_chunk_typed(chunk_number, &current_chunk, chunk_prefix)
@@ -557,16 +544,30 @@ impl<T: MarketDataProvider> MarketFetcher<T> {
             self.storage.save_state(state_filename, &state)?;
             progress.chunks_saved += 1;
 
-            println!();
             info!(
                 "💾 Saved final chunk {} with {} markets",
                 chunk_number,
                 current_chunk.len()
             );
-        } else {
-            println!();
         }
 
+        if cancelled {
+            // The final chunk holds everything fetched; the saved page token
+            // lets the next run continue after it
+            self.storage.save_state(state_filename, &state)?;
+            task.finish("cancelled");
+            warn!(
+                "⏹ Fetch cancelled after {} markets in {} chunks; run the same command again to resume",
+                progress.markets_fetched, progress.chunks_saved
+            );
+            return Err(Cancelled("Market fetch".to_string()).into());
+        }
+
+        task.finish(format!(
+            "{} markets in {} chunks",
+            progress.markets_fetched, progress.chunks_saved
+        ));
+
         // Create final result
         let elapsed_seconds = (Utc::now().timestamp() - progress.start_time.timestamp()) as f64;
         let result = FetchResult {
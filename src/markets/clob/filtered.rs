@@ -1,4 +1,5 @@
 use super::cache::{fetch_and_cache_markets, MarketCache};
+use crate::number_format;
 use anyhow::Result;
 use owo_colors::OwoColorize;
 use polymarket_rs_client::ClobClient;
@@ -128,19 +129,23 @@ pub async fn list_filtered_markets(
             );
             println!("   {} {}", "Slug:".bright_black(), market.slug);
             println!(
-                "   {} ${:.2} | {} ${:.2}",
+                "   {} {} | {} {}",
                 "Volume:".bright_black(),
-                market.volume,
+                number_format::usd(market.volume),
                 "Liquidity:".bright_black(),
-                market.liquidity
+                number_format::usd(market.liquidity)
             );
 
             // Show token prices
             for token in &market.tokens {
                 let color = if token.outcome.to_lowercase() == "yes" {
-                    format!("${:.4}", token.price).bright_green().to_string()
+                    format!("${}", number_format::price(token.price))
+                        .bright_green()
+                        .to_string()
                 } else {
-                    format!("${:.4}", token.price).bright_red().to_string()
+                    format!("${}", number_format::price(token.price))
+                        .bright_red()
+                        .to_string()
                 };
                 println!(
                     "   {} {} {} {}",
@@ -199,10 +204,10 @@ pub async fn list_filtered_markets(
                 "{:<4} {:<60} {:>12} {:>12} {:>8} {:>8}",
                 format!("{}", idx + 1).bright_black(),
                 question,
-                format!("${:.0}", market.volume).bright_yellow(),
-                format!("${:.0}", market.liquidity).bright_cyan(),
-                format!("${:.3}", yes_price).bright_green(),
-                format!("${:.3}", no_price).bright_red(),
+                format!("${}", number_format::integer(market.volume)).bright_yellow(),
+                format!("${}", number_format::integer(market.liquidity)).bright_cyan(),
+                format!("${}", number_format::price(yes_price)).bright_green(),
+                format!("${}", number_format::price(no_price)).bright_red(),
             );
         }
     }
@@ -214,8 +219,8 @@ pub async fn list_filtered_markets(
     println!("\n{}", "SUMMARY".bright_yellow());
     println!("{}", "─".repeat(50).bright_black());
     println!("Total markets shown: {}", display_markets.len());
-    println!("Combined volume: ${:.2}", total_volume);
-    println!("Average volume: ${:.2}", avg_volume);
+    println!("Combined volume: {}", number_format::usd(total_volume));
+    println!("Average volume: {}", number_format::usd(avg_volume));
 
     Ok(())
 }
@@ -0,0 +1,331 @@
+//! Liquidity surface analytics
+//!
+//! Walks the book levels that `enrich` records to price what buying and
+//! selling a range of dollar sizes would cost at the captured books, and
+//! ranks markets by how much can actually be executed near the mid rather
+//! than by quoted volume. Only the levels `enrich --max-depth` kept are
+//! known, so sizes deeper than that are reported as partial fills.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::markets::clob::enrich::{DepthLevel, EnrichedMarket};
+
+/// Dollar sizes priced when none are given: $100, $1k and $10k
+pub const DEFAULT_NOTIONALS: [Decimal; 3] = [
+    Decimal::ONE_HUNDRED,
+    Decimal::ONE_THOUSAND,
+    Decimal::from_parts(10_000, 0, 0, false, 0),
+];
+
+/// Report file written into the analyzed dataset by default
+pub const LIQUIDITY_REPORT_FILE: &str = "liquidity_report.json";
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImpactSide {
+    Buy,
+    Sell,
+}
+
+impl ImpactSide {
+    /// Dollars resting on this side within `max_slippage_bps` of the mid
+    pub fn executable_depth(
+        self,
+        levels: &[DepthLevel],
+        mid: Decimal,
+        max_slippage_bps: Decimal,
+    ) -> Decimal {
+        let band = mid * max_slippage_bps / BPS;
+        levels
+            .iter()
+            .filter(|l| match self {
+                ImpactSide::Buy => l.price <= mid + band,
+                ImpactSide::Sell => l.price >= mid - band,
+            })
+            .map(|l| l.price * l.size)
+            .sum()
+    }
+}
+
+/// Result of sweeping one side of a book for a dollar amount
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpactPoint {
+    pub side: ImpactSide,
+    /// Dollars to spend (buy) or to receive (sell)
+    pub notional: Decimal,
+    /// Dollars the recorded levels absorbed; below `notional` when they ran out
+    pub filled: Decimal,
+    pub shares: Decimal,
+    pub average_price: Decimal,
+    pub worst_price: Decimal,
+    /// Distance of the average price from the mid, against the trader
+    pub slippage_bps: Decimal,
+    /// Dollars lost compared to trading the same shares at the mid
+    pub cost: Decimal,
+}
+
+impl ImpactPoint {
+    /// Sweep `levels` (best first) until `notional` dollars are traded
+    ///
+    /// Returns `None` when the side has no usable level.
+    pub fn sweep(
+        levels: &[DepthLevel],
+        side: ImpactSide,
+        notional: Decimal,
+        mid: Decimal,
+    ) -> Option<Self> {
+        let mut filled = Decimal::ZERO;
+        let mut shares = Decimal::ZERO;
+        let mut worst_price = Decimal::ZERO;
+        for level in levels.iter().filter(|l| l.price > Decimal::ZERO && l.size > Decimal::ZERO) {
+            let remaining = notional - filled;
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let take = remaining.min(level.price * level.size);
+            filled += take;
+            shares += take / level.price;
+            worst_price = level.price;
+        }
+        if shares <= Decimal::ZERO {
+            return None;
+        }
+
+        let average_price = filled / shares;
+        let slippage = match side {
+            ImpactSide::Buy => average_price - mid,
+            ImpactSide::Sell => mid - average_price,
+        };
+        Some(Self {
+            side,
+            notional,
+            filled,
+            shares,
+            average_price,
+            worst_price,
+            slippage_bps: slippage / mid * BPS,
+            cost: slippage * shares,
+        })
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.filled >= self.notional
+    }
+}
+
+/// Price impact curves and executable depth of a market's first token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquiditySurface {
+    pub condition_id: Option<String>,
+    pub question: Option<String>,
+    pub token_id: String,
+    pub outcome: Option<String>,
+    pub mid: Decimal,
+    pub spread: Decimal,
+    pub buy: Vec<ImpactPoint>,
+    pub sell: Vec<ImpactPoint>,
+    /// Dollars that can be bought within the slippage limit
+    pub executable_buy: Decimal,
+    /// Dollars that can be sold within the slippage limit
+    pub executable_sell: Decimal,
+    /// The smaller of both sides, which the ranking uses
+    pub executable: Decimal,
+    /// 24h volume (or total volume when that's all the dataset has)
+    pub quoted_volume: Option<Decimal>,
+    /// Position when ranked by quoted volume instead
+    pub volume_rank: Option<usize>,
+    pub enriched_at: DateTime<Utc>,
+}
+
+impl LiquiditySurface {
+    /// Surface of a market, or `None` when its enrichment has no two-sided book depth
+    pub fn of(
+        market: &EnrichedMarket,
+        notionals: &[Decimal],
+        max_slippage_bps: Decimal,
+    ) -> Option<Self> {
+        let depth = market.enrichment.depth.as_ref()?;
+        let best_bid = depth.bids.first()?.price;
+        let best_ask = depth.asks.first()?.price;
+        let mid = (best_bid + best_ask) / Decimal::TWO;
+        if mid <= Decimal::ZERO {
+            return None;
+        }
+
+        let curve = |levels: &[DepthLevel], side: ImpactSide| -> Vec<ImpactPoint> {
+            notionals
+                .iter()
+                .filter_map(|&notional| ImpactPoint::sweep(levels, side, notional, mid))
+                .collect()
+        };
+        let executable_buy = ImpactSide::Buy.executable_depth(&depth.asks, mid, max_slippage_bps);
+        let executable_sell = ImpactSide::Sell.executable_depth(&depth.bids, mid, max_slippage_bps);
+        let token = market.market.tokens.first()?;
+
+        Some(Self {
+            condition_id: market.market.condition_id.clone(),
+            question: market.market.question.clone(),
+            token_id: token.token_id.clone(),
+            outcome: token.outcome.clone(),
+            mid,
+            spread: best_ask - best_bid,
+            buy: curve(&depth.asks, ImpactSide::Buy),
+            sell: curve(&depth.bids, ImpactSide::Sell),
+            executable_buy,
+            executable_sell,
+            executable: executable_buy.min(executable_sell),
+            quoted_volume: Self::quoted_volume(market),
+            volume_rank: None,
+            enriched_at: market.enriched_at,
+        })
+    }
+
+    /// Volume the market advertises: the enrichment's 24h volume, else the source data's
+    fn quoted_volume(market: &EnrichedMarket) -> Option<Decimal> {
+        let number = |value: &serde_json::Value| match value {
+            serde_json::Value::Number(n) => n.as_f64().and_then(|v| Decimal::try_from(v).ok()),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        };
+        market
+            .enrichment
+            .volume
+            .as_ref()
+            .and_then(|v| v.volume_24hr.or(v.volume_total))
+            .and_then(|v| Decimal::try_from(v).ok())
+            .or_else(|| {
+                ["volume24hr", "volume_24hr", "volume"]
+                    .iter()
+                    .find_map(|key| market.market.additional_fields.get(*key).and_then(number))
+            })
+    }
+}
+
+/// Markets ranked by executable liquidity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityReport {
+    pub source_dataset: String,
+    pub generated_at: DateTime<Utc>,
+    pub notionals: Vec<Decimal>,
+    pub max_slippage_bps: Decimal,
+    /// Most executable first
+    pub markets: Vec<LiquiditySurface>,
+    /// Markets enriched without book depth (older datasets or empty books)
+    pub without_depth: usize,
+}
+
+impl LiquidityReport {
+    /// Compute every market's surface and rank them by executable liquidity
+    pub fn build(
+        source_dataset: &str,
+        markets: &[EnrichedMarket],
+        notionals: &[Decimal],
+        max_slippage_bps: Decimal,
+    ) -> Self {
+        let mut surfaces: Vec<LiquiditySurface> = markets
+            .iter()
+            .filter_map(|market| LiquiditySurface::of(market, notionals, max_slippage_bps))
+            .collect();
+        let without_depth = markets.len() - surfaces.len();
+
+        // Volume ranks first, so the report shows how far the rankings disagree
+        let mut by_volume: Vec<usize> = (0..surfaces.len())
+            .filter(|&i| surfaces[i].quoted_volume.is_some())
+            .collect();
+        by_volume.sort_by(|&a, &b| surfaces[b].quoted_volume.cmp(&surfaces[a].quoted_volume));
+        for (rank, index) in by_volume.into_iter().enumerate() {
+            surfaces[index].volume_rank = Some(rank + 1);
+        }
+
+        surfaces.sort_by(|a, b| b.executable.cmp(&a.executable).then(a.spread.cmp(&b.spread)));
+
+        Self {
+            source_dataset: source_dataset.to_string(),
+            generated_at: Utc::now(),
+            notionals: notionals.to_vec(),
+            max_slippage_bps,
+            markets: surfaces,
+            without_depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::clob::enrich::BookDepth;
+    use rust_decimal_macros::dec;
+
+    fn market(question: &str, volume: f64, depth: BookDepth) -> EnrichedMarket {
+        serde_json::from_value(serde_json::json!({
+            "id": null,
+            "condition_id": format!("0x{}", question),
+            "question": question,
+            "description": null,
+            "category": null,
+            "tags": null,
+            "tokens": [{"token_id": "1", "outcome": "Yes", "price": null, "winner": null}],
+            "active": true,
+            "closed": false,
+            "archived": false,
+            "accepting_orders": true,
+            "minimum_order_size": null,
+            "minimum_tick_size": null,
+            "end_date_iso": null,
+            "created_at": null,
+            "updated_at": null,
+            "volume24hr": volume,
+            "enrichment": {
+                "depth": depth,
+                "status": {"success": true, "has_orderbook": true, "processing_time_ms": 0}
+            },
+            "enriched_at": Utc::now()
+        }))
+        .unwrap()
+    }
+
+    fn level(price: Decimal, size: Decimal) -> DepthLevel {
+        DepthLevel { price, size }
+    }
+
+    #[test]
+    fn impact_curves_and_ranking_follow_the_book() {
+        let deep = BookDepth {
+            bids: vec![level(dec!(0.49), dec!(5000)), level(dec!(0.48), dec!(20000))],
+            asks: vec![level(dec!(0.51), dec!(5000)), level(dec!(0.52), dec!(20000))],
+        };
+        let thin = BookDepth {
+            bids: vec![level(dec!(0.48), dec!(1000)), level(dec!(0.45), dec!(2000))],
+            asks: vec![level(dec!(0.52), dec!(500)), level(dec!(0.55), dec!(10000))],
+        };
+        let markets = [
+            market("thin", 1_000_000.0, thin),
+            market("deep", 5_000.0, deep),
+        ];
+        let report = LiquidityReport::build("test", &markets, &DEFAULT_NOTIONALS, dec!(200));
+
+        assert_eq!(report.without_depth, 0);
+        let deep = &report.markets[0];
+        assert_eq!(deep.question.as_deref(), Some("deep"));
+        assert_eq!(deep.volume_rank, Some(2));
+        // Both sides' first level lies within 2% of the 0.50 mid
+        assert_eq!(deep.executable, dec!(2450));
+
+        let thin = &report.markets[1];
+        assert_eq!(thin.executable, Decimal::ZERO);
+        // $100 fills at the best ask: 4% above the mid
+        let buy_100 = &thin.buy[0];
+        assert!(buy_100.is_complete());
+        assert!((buy_100.slippage_bps - dec!(400)).abs() < dec!(0.000001));
+        // $1k walks into the second ask level
+        assert_eq!(thin.buy[1].worst_price, dec!(0.55));
+        // $10k is more than the recorded bids hold
+        let sell_10k = &thin.sell[2];
+        assert!(!sell_10k.is_complete());
+        assert_eq!(sell_10k.filled, dec!(1380));
+    }
+}
@@ -2,12 +2,15 @@
 
 mod active;
 mod analyze;
+pub mod book_snapshots;
+pub mod book_watch;
 mod cache;
 mod display;
-mod enrich;
+pub mod enrich;
 mod fetch;
 pub mod fetcher;
 mod filtered;
+pub mod liquidity;
 mod list;
 mod orderbook;
 mod providers;
@@ -3,6 +3,8 @@ use owo_colors::OwoColorize;
 use polymarket_rs_client::ClobClient;
 use rust_decimal::Decimal;
 
+use crate::number_format;
+
 /// Show orderbook for a specific token
 pub async fn show_orderbook(client: ClobClient, token_id: &str, depth: usize) -> Result<()> {
     println!(
@@ -65,18 +67,22 @@ pub async fn show_orderbook(client: ClobClient, token_id: &str, depth: usize) ->
         println!("\n{}", "MARKET SUMMARY".bright_yellow());
         println!("{}", "─".repeat(40).bright_black());
         println!(
-            "{} ${:.4} / ${:.4}",
+            "{} ${} / ${}",
             "Best Bid/Ask:".bright_white(),
-            best_bid,
-            best_ask
+            number_format::price(best_bid),
+            number_format::price(best_ask)
         );
         println!(
-            "{} ${:.4} ({:.2}%)",
+            "{} ${} ({})",
             "Spread:".bright_white(),
-            spread,
-            spread_pct
+            number_format::price(spread),
+            number_format::percent(spread_pct)
+        );
+        println!(
+            "{} ${}",
+            "Mid Price:".bright_white(),
+            number_format::price(mid_price)
         );
-        println!("{} ${:.4}", "Mid Price:".bright_white(), mid_price);
     }
 
     // Display bids
@@ -99,8 +105,8 @@ pub async fn show_orderbook(client: ClobClient, token_id: &str, depth: usize) ->
         for bid in relevant_bids.iter().take(depth) {
             println!(
                 "{:>10} {:>15}",
-                format!("${:.4}", bid.price).bright_green(),
-                format!("{:.2}", bid.size)
+                format!("${}", number_format::price(bid.price)).bright_green(),
+                number_format::size(bid.size)
             );
         }
 
@@ -133,8 +139,8 @@ pub async fn show_orderbook(client: ClobClient, token_id: &str, depth: usize) ->
         for ask in relevant_asks.iter().take(depth) {
             println!(
                 "{:>10} {:>15}",
-                format!("${:.4}", ask.price).bright_red(),
-                format!("{:.2}", ask.size)
+                format!("${}", number_format::price(ask.price)).bright_red(),
+                number_format::size(ask.size)
             );
         }
 
@@ -158,13 +164,13 @@ pub async fn show_orderbook(client: ClobClient, token_id: &str, depth: usize) ->
         println!(
             "{} {} USDC across {} orders",
             "Total Bid Size:".bright_white(),
-            format!("{:.2}", total_bid_size).bright_green(),
+            number_format::size(total_bid_size).bright_green(),
             relevant_bids.len()
         );
         println!(
             "{} {} USDC across {} orders",
             "Total Ask Size:".bright_white(),
-            format!("{:.2}", total_ask_size).bright_red(),
+            number_format::size(total_ask_size).bright_red(),
             relevant_asks.len()
         );
     }
@@ -1,3 +1,6 @@
+use crate::core::http_cache::{get_json, Endpoint};
+use crate::markets::gamma::query::GAMMA_MARKETS_URL;
+use crate::markets::gamma::{MarketOrder, MarketQuery};
 use anyhow::Result;
 use async_trait::async_trait;
 use owo_colors::OwoColorize;
@@ -120,15 +123,22 @@ impl MarketDataProvider for GammaProvider {
             }
         }
 
-        // Build URL
-        let url = format!(
-            "https://gamma-api.polymarket.com/markets?limit={}&offset={}&order=id&ascending=true",
-            self.limit, self.current_offset
-        );
+        // Build query
+        let query = MarketQuery::builder()
+            .limit(self.limit as u32)
+            .offset(self.current_offset as u32)
+            .order(MarketOrder::Id)
+            .ascending(true)
+            .build()?;
 
         // Fetch markets
-        let response = self.client.get(&url).send().await?;
-        let markets: Vec<Value> = response.json().await?;
+        let markets: Vec<Value> = get_json(
+            &self.client,
+            Endpoint::Markets,
+            GAMMA_MARKETS_URL,
+            &query.to_params(),
+        )
+        .await?;
 
         // Update state
         self.last_batch_size = markets.len();
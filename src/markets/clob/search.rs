@@ -1,4 +1,7 @@
 use super::display::{display_gamma_market_info, display_market_info};
+use crate::core::http_cache::{get_json, Endpoint};
+use crate::markets::gamma::query::GAMMA_MARKETS_URL;
+use crate::markets::gamma::{MarketOrder, MarketQuery, MarketSlug};
 use anyhow::Result;
 use owo_colors::OwoColorize;
 use polymarket_rs_client::ClobClient;
@@ -92,13 +95,19 @@ pub async fn search_markets(
 /// Search markets using Gamma API
 async fn search_markets_gamma(keyword: &str, detailed: bool, limit: usize) -> Result<()> {
     let client = reqwest::Client::new();
-    let url = format!(
-        "https://gamma-api.polymarket.com/markets?limit={}&order=volume&ascending=false",
-        limit * 10 // Get more to filter
-    );
-
-    let response = client.get(&url).send().await?;
-    let markets: Vec<serde_json::Value> = response.json().await?;
+    let query = MarketQuery::builder()
+        .limit((limit * 10) as u32) // Get more to filter
+        .order(MarketOrder::Volume)
+        .ascending(false)
+        .build()?;
+
+    let markets: Vec<serde_json::Value> = get_json(
+        &client,
+        Endpoint::Markets,
+        GAMMA_MARKETS_URL,
+        &query.to_params(),
+    )
+    .await?;
 
     // Filter by keyword
     let found_markets: Vec<_> = markets
@@ -179,13 +188,17 @@ async fn get_market_details_gamma(identifier: &str) -> Result<()> {
     let client = reqwest::Client::new();
 
     // Try to find by slug or search
-    let url = format!(
-        "https://gamma-api.polymarket.com/markets?slug={}",
-        identifier
-    );
-
-    let response = client.get(&url).send().await?;
-    let markets: Vec<serde_json::Value> = response.json().await?;
+    let query = MarketQuery::builder()
+        .slug(MarketSlug(identifier.to_string()))
+        .build()?;
+
+    let markets: Vec<serde_json::Value> = get_json(
+        &client,
+        Endpoint::Markets,
+        GAMMA_MARKETS_URL,
+        &query.to_params(),
+    )
+    .await?;
 
     if let Some(market) = markets.first() {
         println!("\n{}", "Market Details (Gamma API):".bright_green());
@@ -236,8 +249,13 @@ pub async fn get_market_from_url(url: &str) -> Result<()> {
     // First, try the events endpoint
     println!("\n{}", "🔍 Checking events endpoint...".bright_yellow());
     let events_url = format!("https://gamma-api.polymarket.com/events");
-    let response = client.get(&events_url).send().await?;
-    let events: Vec<serde_json::Value> = response.json().await?;
+    let events: Vec<serde_json::Value> = get_json(
+        &client,
+        Endpoint::Events,
+        &events_url,
+        &[] as &[(&str, &str)],
+    )
+    .await?;
 
     // Search for the event by slug
     let matching_event = events
@@ -308,9 +326,14 @@ pub async fn get_market_from_url(url: &str) -> Result<()> {
 
         // Try searching markets directly
         println!("\n{}", "🔍 Searching markets directly...".bright_yellow());
-        let markets_url = format!("https://gamma-api.polymarket.com/markets?limit=10000");
-        let response = client.get(&markets_url).send().await?;
-        let all_markets: Vec<serde_json::Value> = response.json().await?;
+        let query = MarketQuery::builder().limit(10000).build()?;
+        let all_markets: Vec<serde_json::Value> = get_json(
+            &client,
+            Endpoint::Markets,
+            GAMMA_MARKETS_URL,
+            &query.to_params(),
+        )
+        .await?;
 
         // Search for markets that might be related
         let related_markets: Vec<_> = all_markets
@@ -2,7 +2,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use tracing::info;
 
@@ -62,9 +62,7 @@ impl MarketStorage {
     pub fn save_state<T: Serialize>(&self, filename: &str, state: &T) -> Result<()> {
         let path = self.output_dir.join(filename);
         let json = serde_json::to_string_pretty(state)?;
-        let mut file = File::create(path)?;
-        file.write_all(json.as_bytes())?;
-        Ok(())
+        write_atomically(&path, json.as_bytes())
     }
 
     /// Load state from file
@@ -93,8 +91,7 @@ impl MarketStorage {
         let path = self.output_dir.join(&filename);
 
         let json = serde_json::to_string_pretty(markets)?;
-        let mut file = File::create(path)?;
-        file.write_all(json.as_bytes())?;
+        write_atomically(&path, json.as_bytes())?;
 
         if verbose {
             let size_mb = json.len() as f64 / 1024.0 / 1024.0;
@@ -109,3 +106,12 @@ impl MarketStorage {
         Ok(())
     }
 }
+
+/// Write through a temporary file, so an interrupted fetch never leaves a
+/// half-written chunk or state file behind
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
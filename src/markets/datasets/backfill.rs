@@ -0,0 +1,163 @@
+//! Candle backfill from stream captures
+//!
+//! A capture's trades are folded into 1m, 5m and 1h candles and merged into
+//! the typed store's candle series (see `typed_store::candles`), which also
+//! restates the rolling statistics from the first candle the capture changed.
+//! `polybot stream --record` backfills its capture when the recording stops,
+//! `polybot datasets import` backfills what it imports, and `polybot datasets
+//! backfill` fills in captures recorded before either existed.
+//!
+//! Trades give a candle its prices, volume and trade count; last trade price
+//! updates only its prices, so a capture without trade events still has
+//! candles. Events are placed by when the capture received them.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::replay::{read_events, ReplayEvent};
+use crate::core::ws::PolyEvent;
+use crate::typed_store::candles;
+use crate::typed_store::models::{Candle, CandleInterval};
+
+/// Intervals candles are backfilled at; daily candles add nothing a capture
+/// of a few hours can fill
+pub const BACKFILL_INTERVALS: [CandleInterval; 3] = [
+    CandleInterval::OneMinute,
+    CandleInterval::FiveMinutes,
+    CandleInterval::OneHour,
+];
+
+/// What backfilling one capture covered and wrote
+#[derive(Debug, Clone, Default)]
+pub struct BackfillSummary {
+    pub capture: String,
+    /// Tokens with at least one trade or trade price
+    pub tokens: usize,
+    /// Candles new or changed in the store
+    pub candles: usize,
+    /// Rolling statistics recomputed
+    pub stats: usize,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Candles of every token traded in `events` at each of `intervals`
+pub fn build_candles(events: &[ReplayEvent], intervals: &[CandleInterval]) -> Vec<Candle> {
+    let mut candles: BTreeMap<(String, CandleInterval, DateTime<Utc>), Candle> = BTreeMap::new();
+    for recorded in events {
+        let (asset_id, price, size) = match &recorded.event {
+            PolyEvent::Trade {
+                asset_id,
+                price,
+                size,
+                ..
+            } => (asset_id, *price, Some(*size)),
+            PolyEvent::LastTradePrice {
+                asset_id, price, ..
+            } => (asset_id, *price, None),
+            _ => continue,
+        };
+        for &interval in intervals {
+            let key = (asset_id.clone(), interval, interval.bucket(recorded.at));
+            match candles.get_mut(&key) {
+                Some(candle) => candle.update(recorded.at, price, size),
+                None => {
+                    let candle = Candle::new(asset_id, interval, recorded.at, price, size);
+                    candles.insert(key, candle);
+                }
+            }
+        }
+    }
+    // Each candle's recording covers the capture's span within its interval,
+    // so a later capture of another part of the interval adds to it
+    if let (Some(first), Some(last)) = (events.first(), events.last()) {
+        for candle in candles.values_mut() {
+            candle.covering(first.at, last.at);
+        }
+    }
+    candles.into_values().collect()
+}
+
+/// Backfill the candle store at `db_path` from the capture in `dir`; a
+/// capture without trades backfills nothing
+pub fn backfill_capture(dir: &Path, db_path: &Path) -> Result<BackfillSummary> {
+    let capture = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.display().to_string());
+    let events = read_events(dir)?;
+    let candles = build_candles(&events, &BACKFILL_INTERVALS);
+    if candles.is_empty() {
+        return Ok(BackfillSummary {
+            capture,
+            ..Default::default()
+        });
+    }
+
+    let tokens: BTreeSet<&str> = candles.iter().map(|c| c.token_id.as_str()).collect();
+    let summary = BackfillSummary {
+        capture: capture.clone(),
+        tokens: tokens.len(),
+        from: candles.iter().map(|c| c.first_at).min(),
+        to: candles.iter().map(|c| c.last_at).max(),
+        ..Default::default()
+    };
+    let counts = candles::backfill(db_path, candles)
+        .with_context(|| format!("Failed to backfill candles from '{}'", capture))?;
+    Ok(BackfillSummary {
+        candles: counts.candles,
+        stats: counts.stats,
+        ..summary
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::common::Side;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn trades_and_trade_prices_fold_into_candles() {
+        let at = |secs: i64| DateTime::from_timestamp(1_717_250_400 + secs, 0).unwrap();
+        let trade = |secs, price, size| ReplayEvent {
+            at: at(secs),
+            event: PolyEvent::Trade {
+                asset_id: "1".to_string(),
+                price,
+                size,
+                side: Side::Buy,
+            },
+        };
+        let events = vec![
+            trade(5, dec!(0.40), dec!(10)),
+            ReplayEvent {
+                at: at(30),
+                event: PolyEvent::LastTradePrice {
+                    asset_id: "1".to_string(),
+                    price: dec!(0.45),
+                    timestamp: 0,
+                },
+            },
+            trade(50, dec!(0.42), dec!(5)),
+            trade(70, dec!(0.38), dec!(20)),
+        ];
+
+        let candles = build_candles(&events, &[CandleInterval::OneMinute]);
+        assert_eq!(candles.len(), 2);
+        let first = &candles[0];
+        assert_eq!(first.start, at(0));
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (dec!(0.40), dec!(0.45), dec!(0.40), dec!(0.42))
+        );
+        assert_eq!((first.volume, first.trades), (dec!(15), 2));
+        assert_eq!(candles[1].close, dec!(0.38));
+
+        let hourly = build_candles(&events, &[CandleInterval::OneHour]);
+        assert_eq!(hourly.len(), 1);
+        assert_eq!((hourly[0].low, hourly[0].trades), (dec!(0.38), 3));
+    }
+}
@@ -0,0 +1,330 @@
+//! Stream recordings written as managed datasets
+//!
+//! `polybot stream --record --dataset-name captures/${date}` writes every
+//! event it receives to `events.jsonl` in a dataset directory, one JSON object
+//! per line as `--format jsonl` prints them. The file is written as
+//! `events.jsonl.partial` and renamed when the recording stops cleanly, so the
+//! dataset manager reports an interrupted recording as incomplete.
+//!
+//! A `dataset.yaml` is written when recording starts, so the capture shows up
+//! in `polybot datasets` and the datasets TUI straight away; the CLI middleware
+//! rewrites it with the final counts once the command succeeds.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::{report_dataset, save_command_metadata};
+
+/// Command recorded in the `dataset.yaml` of stream captures
+pub const CAPTURE_COMMAND: &str = "stream";
+
+/// Events of a finished capture
+pub const CAPTURE_EVENTS_FILE: &str = "events.jsonl";
+
+/// Events of a capture still being recorded, or one that was interrupted
+pub const CAPTURE_PARTIAL_FILE: &str = "events.jsonl.partial";
+
+/// Dataset name used when `--record` is given without `--dataset-name`
+pub const DEFAULT_CAPTURE_NAME: &str = "captures/${datetime}";
+
+/// Counts of a capture, for its metadata and the end-of-stream summary
+#[derive(Debug, Clone, Default)]
+pub struct CaptureSummary {
+    pub name: String,
+    pub path: PathBuf,
+    pub events: usize,
+    /// Events per `type`, e.g. `book`, `trade`
+    pub event_types: BTreeMap<String, usize>,
+    pub tokens: usize,
+    /// Events the recorder fell too far behind to receive
+    pub dropped: usize,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl CaptureSummary {
+    fn additional_info(&self) -> HashMap<String, Value> {
+        let mut info = HashMap::new();
+        info.insert("capture_name".to_string(), json!(self.name));
+        info.insert("event_count".to_string(), json!(self.events));
+        info.insert("event_types".to_string(), json!(self.event_types));
+        info.insert("token_count".to_string(), json!(self.tokens));
+        info.insert("dropped_events".to_string(), json!(self.dropped));
+        info.insert("started_at".to_string(), json!(self.started_at));
+        info.insert("finished_at".to_string(), json!(self.finished_at));
+        info
+    }
+}
+
+/// First and last time of a run of events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeRange {
+    /// The range holding just `at`
+    pub fn at(at: DateTime<Utc>) -> Self {
+        Self { start: at, end: at }
+    }
+
+    /// Grow the range to cover `at`
+    pub fn extend(&mut self, at: DateTime<Utc>) {
+        self.start = self.start.min(at);
+        self.end = self.end.max(at);
+    }
+}
+
+/// A recording in progress
+pub struct StreamCapture {
+    summary: CaptureSummary,
+    tokens: BTreeSet<String>,
+    writer: BufWriter<File>,
+}
+
+impl StreamCapture {
+    /// Start recording into dataset `name` under `datasets_dir`
+    ///
+    /// `name` may have `/`-separated parts (`captures/2024-06-01`); each part
+    /// is letters, digits, `-`, `_` or `.`. An existing dataset is never
+    /// replaced. `args` are the stream arguments for the initial metadata.
+    pub fn create(datasets_dir: &Path, name: &str, args: &[String]) -> Result<Self> {
+        validate_name(name)?;
+        let path = datasets_dir.join(name);
+        if path.exists() {
+            bail!("Dataset '{}' already exists at {}", name, path.display());
+        }
+        std::fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let file = File::create(path.join(CAPTURE_PARTIAL_FILE))
+            .with_context(|| format!("Failed to create the capture in {}", path.display()))?;
+
+        let summary = CaptureSummary {
+            name: name.to_string(),
+            path,
+            started_at: Some(Utc::now()),
+            ..Default::default()
+        };
+        save_command_metadata(
+            &summary.path,
+            CAPTURE_COMMAND,
+            args,
+            Some(summary.additional_info()),
+        )?;
+        Ok(Self {
+            summary,
+            tokens: BTreeSet::new(),
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.summary.path
+    }
+
+    /// Append one event, as produced by the stream's JSON formatter
+    pub fn record(&mut self, event: &Value) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+
+        self.summary.events += 1;
+        let kind = event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        *self
+            .summary
+            .event_types
+            .entry(kind.to_string())
+            .or_default() += 1;
+        if let Some(asset_id) = event.get("asset_id").and_then(Value::as_str) {
+            if !self.tokens.contains(asset_id) {
+                self.tokens.insert(asset_id.to_string());
+            }
+        }
+        self.summary.tokens = self.tokens.len();
+        Ok(())
+    }
+
+    /// Count events that were lost before reaching the recorder
+    pub fn note_dropped(&mut self, count: usize) {
+        self.summary.dropped += count;
+    }
+
+    /// Flush buffered events, e.g. before a long idle period
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to write the capture")
+    }
+
+    /// Close the recording: move the events into place and report the
+    /// dataset so its metadata gets the final counts
+    pub fn finish(self) -> Result<CaptureSummary> {
+        let mut summary = self.close()?;
+        summary.finished_at = Some(Utc::now());
+        report_dataset(&summary.path, summary.additional_info());
+        Ok(summary)
+    }
+
+    fn close(mut self) -> Result<CaptureSummary> {
+        self.flush()?;
+        drop(self.writer);
+        let path = &self.summary.path;
+        std::fs::rename(
+            path.join(CAPTURE_PARTIAL_FILE),
+            path.join(CAPTURE_EVENTS_FILE),
+        )
+        .with_context(|| format!("Failed to finish the capture in {}", path.display()))?;
+        Ok(self.summary)
+    }
+}
+
+/// Copy a capture recorded elsewhere into dataset `name` under `datasets_dir`
+///
+/// `source` is a capture directory or its events file; an interrupted capture
+/// is imported as far as it got. Lines that aren't JSON objects are skipped.
+/// The capture's start and finish are those of the events it received, so the
+/// dataset covers the period it recorded rather than when it was imported.
+pub fn import_capture(datasets_dir: &Path, source: &Path, name: &str) -> Result<CaptureSummary> {
+    let events = if source.is_dir() {
+        [CAPTURE_EVENTS_FILE, CAPTURE_PARTIAL_FILE]
+            .iter()
+            .map(|file| source.join(file))
+            .find(|path| path.exists())
+            .with_context(|| {
+                format!(
+                    "{} is not a stream capture (no {})",
+                    source.display(),
+                    CAPTURE_EVENTS_FILE
+                )
+            })?
+    } else {
+        source.to_path_buf()
+    };
+    let file =
+        File::open(&events).with_context(|| format!("Failed to open {}", events.display()))?;
+
+    let args = vec!["import".to_string(), source.display().to_string()];
+    let mut capture = StreamCapture::create(datasets_dir, name, &args)?;
+    let mut received: Option<TimeRange> = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let Ok(event) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if !event.is_object() {
+            continue;
+        }
+        if let Some(at) = event
+            .get("received_at")
+            .and_then(Value::as_i64)
+            .and_then(DateTime::from_timestamp_millis)
+        {
+            match received.as_mut() {
+                Some(range) => range.extend(at),
+                None => received = Some(TimeRange::at(at)),
+            }
+        }
+        capture.record(&event)?;
+    }
+
+    let mut summary = capture.close()?;
+    if summary.events == 0 {
+        std::fs::remove_dir_all(&summary.path)?;
+        bail!("{} has no recorded events to import", events.display());
+    }
+    if let Some(range) = received {
+        summary.started_at = Some(range.start);
+        summary.finished_at = Some(range.end);
+    }
+    save_command_metadata(
+        &summary.path,
+        CAPTURE_COMMAND,
+        &args,
+        Some(summary.additional_info()),
+    )?;
+    Ok(summary)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.split('/').all(|part| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if !valid {
+        bail!(
+            "Invalid dataset name '{}': use '/'-separated parts of letters, digits, '-', '_' or '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::datasets::{
+        load_dataset_metadata, DatasetHealthStatus, DatasetManager, DatasetManagerConfig,
+        DatasetType,
+    };
+
+    #[test]
+    fn captures_become_healthy_datasets_once_finished() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(StreamCapture::create(dir.path(), "../escape", &[]).is_err());
+        assert!(StreamCapture::create(dir.path(), "captures//x", &[]).is_err());
+
+        let args = vec!["--record".to_string()];
+        let mut capture = StreamCapture::create(dir.path(), "captures/2024-06-01", &args).unwrap();
+        assert!(StreamCapture::create(dir.path(), "captures/2024-06-01", &args).is_err());
+        let path = capture.path().to_path_buf();
+        assert!(load_dataset_metadata(&path).is_ok());
+
+        capture
+            .record(&json!({"type": "book", "asset_id": "1", "bids": [], "asks": []}))
+            .unwrap();
+        capture
+            .record(&json!({"type": "trade", "asset_id": "2", "price": "0.4"}))
+            .unwrap();
+        capture
+            .record(&json!({"type": "book", "asset_id": "1", "bids": [], "asks": []}))
+            .unwrap();
+        capture.flush().unwrap();
+
+        let manager = DatasetManager::new(DatasetManagerConfig::default());
+        let recording = manager.analyze_dataset(&path).unwrap();
+        assert_eq!(recording.health_status, DatasetHealthStatus::Incomplete);
+
+        let summary = capture.finish().unwrap();
+        assert_eq!((summary.events, summary.tokens), (3, 2));
+        assert_eq!(summary.event_types["book"], 2);
+        let lines = std::fs::read_to_string(path.join(CAPTURE_EVENTS_FILE)).unwrap();
+        assert_eq!(lines.lines().count(), 3);
+        assert!(!path.join(CAPTURE_PARTIAL_FILE).exists());
+
+        // What the middleware writes once the command succeeds
+        let info = summary.additional_info();
+        save_command_metadata(&path, CAPTURE_COMMAND, &args, Some(info)).unwrap();
+
+        let finished = manager.analyze_dataset(&path).unwrap();
+        assert_eq!(finished.health_status, DatasetHealthStatus::Healthy);
+        assert!(matches!(
+            finished.dataset_type,
+            DatasetType::StreamCapture {
+                event_count: 3,
+                token_count: 2
+            }
+        ));
+    }
+}
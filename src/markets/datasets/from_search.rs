@@ -0,0 +1,317 @@
+//! Token-selection datasets built from a market search
+//!
+//! `polybot datasets create-from-search` and the canvas dataset selector run a
+//! query against the local Gamma store (the filters of `polybot gamma search`)
+//! and write the open markets it matches as a dataset: `markets.json` in the
+//! CLOB layout the streamer reads, and a `dataset.yaml` recording the query.
+//! Streaming targets can then be picked by criteria instead of by whole fetch
+//! snapshots. Markets the suppression list (`suppression.yaml`) rules out
+//! never make it into the results.
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::save_command_metadata;
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::{GammaMarket, GammaSearchEngine, GammaStorage, SearchFilters};
+use crate::markets::suppression::SuppressionConfig;
+
+/// Command recorded in the `dataset.yaml` of datasets built from a search
+pub const SEARCH_DATASET_COMMAND: &str = "datasets create-from-search";
+
+/// Local Gamma store searched for markets, relative to the data directory
+pub const GAMMA_DB_DIR: &str = "database/gamma";
+
+/// A market screen: every set field must match
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarketSearchQuery {
+    /// Text in the question, description or outcomes
+    pub keyword: Option<String>,
+    /// Markets with any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    pub min_volume: Option<Decimal>,
+    pub min_liquidity: Option<Decimal>,
+    /// Most markets to keep, highest volume first (0 keeps all)
+    #[serde(default)]
+    pub limit: usize,
+}
+
+impl MarketSearchQuery {
+    /// Whether no criterion is set, which would match every open market
+    pub fn is_empty(&self) -> bool {
+        self.keyword.as_deref().is_none_or(|k| k.trim().is_empty())
+            && self.tags.is_empty()
+            && self.category.is_none()
+            && self.min_volume.is_none()
+            && self.min_liquidity.is_none()
+    }
+
+    /// Name for a dataset of this query's results, e.g. `search_us_election`
+    pub fn default_name(&self) -> String {
+        let words = self
+            .keyword
+            .as_deref()
+            .or(self.category.as_deref())
+            .or(self.tags.first().map(String::as_str))
+            .unwrap_or("markets");
+        let slug: String = words
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        let slug: String = slug.chars().take(48).collect();
+        format!("search_{}", if slug.is_empty() { "markets" } else { &slug })
+    }
+
+    /// The query as `create-from-search` arguments, for the dataset metadata
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(keyword) = &self.keyword {
+            args.push(keyword.clone());
+        }
+        for tag in &self.tags {
+            args.extend(["--tags".to_string(), tag.clone()]);
+        }
+        if let Some(category) = &self.category {
+            args.extend(["--category".to_string(), category.clone()]);
+        }
+        if let Some(min_volume) = self.min_volume {
+            args.extend(["--min-volume".to_string(), min_volume.to_string()]);
+        }
+        if let Some(min_liquidity) = self.min_liquidity {
+            args.extend(["--min-liquidity".to_string(), min_liquidity.to_string()]);
+        }
+        args.extend(["--limit".to_string(), self.limit.to_string()]);
+        args
+    }
+
+    fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            keyword: self
+                .keyword
+                .as_ref()
+                .map(|k| k.trim().to_string())
+                .filter(|k| !k.is_empty()),
+            tags: self.tags.clone(),
+            category: self.category.clone(),
+            min_volume: self.min_volume,
+            min_liquidity: self.min_liquidity,
+            active_only: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// A dataset written from a search
+#[derive(Debug, Clone)]
+pub struct SearchDataset {
+    pub name: String,
+    pub path: PathBuf,
+    pub markets: usize,
+    pub tokens: usize,
+    /// Matching markets left out by the suppression list
+    pub suppressed: usize,
+}
+
+/// Markets a search matched
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// Highest volume first
+    pub markets: Vec<GammaMarket>,
+    /// Matching markets left out by the suppression list
+    pub suppressed: usize,
+}
+
+/// Open markets in the Gamma store at `gamma_db` matching `query` that
+/// `suppression` keeps, and how many it left out
+pub fn search_markets(
+    gamma_db: &Path,
+    query: &MarketSearchQuery,
+    suppression: &SuppressionConfig,
+) -> Result<SearchResult> {
+    // Opening the store creates it, so check first rather than search nothing
+    if !gamma_db.join("rocksdb").exists() {
+        bail!(
+            "No local Gamma data at {}; run `polybot gamma sync` first",
+            gamma_db.display()
+        );
+    }
+    let storage = GammaStorage::new(gamma_db).context("Failed to open Gamma storage")?;
+    let mut markets: Vec<GammaMarket> = GammaSearchEngine::new(storage)
+        .search_markets(&query.filters())
+        .context("Market search failed")?
+        .into_iter()
+        .filter(|m| !m.closed && m.accepting_orders && !m.clob_token_ids.is_empty())
+        .collect();
+    let matched = markets.len();
+    markets.retain(|m| suppression.keeps(m));
+    let suppressed = matched - markets.len();
+    if query.limit > 0 {
+        markets.truncate(query.limit);
+    }
+    Ok(SearchResult {
+        markets,
+        suppressed,
+    })
+}
+
+/// A Gamma market in the CLOB layout of dataset `markets.json` files
+pub fn dataset_market(market: &GammaMarket) -> Value {
+    let tokens: Vec<Value> = market
+        .clob_token_ids
+        .iter()
+        .enumerate()
+        .map(|(i, token)| {
+            json!({
+                "token_id": token.0,
+                "outcome": market.outcomes.get(i),
+                "price": market.outcome_prices.as_ref().and_then(|prices| prices.get(i)),
+            })
+        })
+        .collect();
+    json!({
+        "condition_id": market.condition_id.0,
+        "question": market.question,
+        "market_slug": market.slug,
+        "end_date_iso": market.end_date,
+        "volume": market.volume_24hr.and_then(|v| v.to_f64()),
+        "tokens": tokens,
+    })
+}
+
+/// Write `markets` as dataset `name` under `datasets_dir`, replacing an
+/// existing dataset of that name only with `overwrite`
+pub fn write_dataset(
+    datasets_dir: &Path,
+    name: &str,
+    query: &MarketSearchQuery,
+    markets: &[Value],
+    overwrite: bool,
+) -> Result<SearchDataset> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!(
+            "Invalid dataset name '{}': use letters, digits, '-' or '_'",
+            name
+        );
+    }
+    if markets.is_empty() {
+        bail!("The search matched no open markets");
+    }
+    let path = datasets_dir.join(name);
+    if path.exists() {
+        if !overwrite {
+            bail!("Dataset '{}' already exists", name);
+        }
+        std::fs::remove_dir_all(&path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+    }
+    std::fs::create_dir_all(&path)?;
+    std::fs::write(
+        path.join("markets.json"),
+        serde_json::to_string_pretty(markets)?,
+    )?;
+
+    let tokens: usize = markets
+        .iter()
+        .filter_map(|m| m.get("tokens").and_then(Value::as_array))
+        .map(Vec::len)
+        .sum();
+    let mut info = HashMap::new();
+    info.insert("selection_name".to_string(), json!(name));
+    info.insert("query".to_string(), serde_json::to_value(query)?);
+    info.insert("market_count".to_string(), json!(markets.len()));
+    info.insert("token_count".to_string(), json!(tokens));
+    save_command_metadata(&path, SEARCH_DATASET_COMMAND, &query.to_args(), Some(info))?;
+
+    Ok(SearchDataset {
+        name: name.to_string(),
+        path,
+        markets: markets.len(),
+        tokens,
+        suppressed: 0,
+    })
+}
+
+/// Search the profile's Gamma store and write the results as dataset `name`
+pub fn create_from_search(
+    data_paths: &DataPaths,
+    name: &str,
+    query: &MarketSearchQuery,
+    overwrite: bool,
+) -> Result<SearchDataset> {
+    let suppression = SuppressionConfig::load(data_paths)?;
+    let found = search_markets(&data_paths.root().join(GAMMA_DB_DIR), query, &suppression)?;
+    let markets: Vec<Value> = found.markets.iter().map(dataset_market).collect();
+    let dataset = write_dataset(&data_paths.datasets(), name, query, &markets, overwrite)?;
+    Ok(SearchDataset {
+        suppressed: found.suppressed,
+        ..dataset
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::datasets::{DatasetManager, DatasetManagerConfig, DatasetType};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn search_results_are_written_as_a_token_selection_dataset() {
+        let dir = tempfile::tempdir().unwrap();
+        let query = MarketSearchQuery {
+            keyword: Some("US Election".to_string()),
+            min_volume: Some(dec!(10000)),
+            limit: 20,
+            ..Default::default()
+        };
+        assert!(!query.is_empty());
+        assert!(MarketSearchQuery::default().is_empty());
+        assert_eq!(query.default_name(), "search_us_election");
+
+        let markets = vec![json!({
+            "condition_id": "0xabc",
+            "question": "Who wins the US election?",
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes"},
+                {"token_id": "2", "outcome": "No"},
+            ],
+        })];
+        let name = query.default_name();
+        let written = write_dataset(dir.path(), &name, &query, &markets, false).unwrap();
+        assert_eq!((written.markets, written.tokens), (1, 2));
+        assert!(write_dataset(dir.path(), &name, &query, &markets, false).is_err());
+        assert!(write_dataset(dir.path(), &name, &query, &markets, true).is_ok());
+        assert!(write_dataset(dir.path(), "../escape", &query, &markets, false).is_err());
+        assert!(write_dataset(dir.path(), "empty", &query, &[], false).is_err());
+
+        let mut manager = DatasetManager::new(DatasetManagerConfig {
+            base_dir: dir.path().to_path_buf(),
+            scan_dirs: Vec::new(),
+            recursive: true,
+            max_depth: 2,
+        });
+        manager.scan_datasets().unwrap();
+        let dataset = manager
+            .get_datasets()
+            .into_iter()
+            .find(|d| d.name == name)
+            .unwrap();
+        assert!(matches!(
+            &dataset.dataset_type,
+            DatasetType::TokenSelection { token_count: 2, .. }
+        ));
+    }
+}
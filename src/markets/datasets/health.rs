@@ -0,0 +1,79 @@
+//! Health check verdicts stored with a dataset
+//!
+//! Checks that run after a dataset is written (see `pipeline::health`) store
+//! their verdict under `health_check` in the dataset's `dataset.yaml`. The
+//! dataset manager folds it into the health it reports, so a dataset that
+//! failed its check shows up as corrupted in `polybot datasets` and the
+//! datasets TUI until it is rewritten.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{load_dataset_metadata, save_command_metadata, DatasetMetadata};
+
+/// Key of the verdict in `additional_info`
+pub const HEALTH_CHECK_KEY: &str = "health_check";
+
+/// What a health check of a dataset found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub checked_at: DateTime<Utc>,
+    /// Pipeline run that wrote the dataset
+    #[serde(default)]
+    pub run_id: Option<String>,
+    pub files: usize,
+    pub bytes: u64,
+    pub records: usize,
+    /// `records` was extrapolated from the sampled files
+    #[serde(default)]
+    pub records_estimated: bool,
+    /// Files parsed to check they hold valid JSON
+    #[serde(default)]
+    pub sampled_files: usize,
+    /// Problems that make the dataset unusable
+    #[serde(default)]
+    pub failures: Vec<String>,
+    /// Anomalies worth a look
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl HealthCheck {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// The verdict stored in a dataset's metadata, if any
+pub fn recorded_health_check(metadata: &DatasetMetadata) -> Option<HealthCheck> {
+    metadata
+        .additional_info
+        .get(HEALTH_CHECK_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+/// Store `check` in the `dataset.yaml` at `dataset_path`, writing one that
+/// names `command` and `args` as the producer if the dataset has none
+pub fn record_health_check(
+    dataset_path: &Path,
+    check: &HealthCheck,
+    command: &str,
+    args: &[String],
+) -> Result<()> {
+    let value = serde_json::to_value(check).context("Failed to serialize health check")?;
+    let Ok(mut metadata) = load_dataset_metadata(dataset_path) else {
+        let additional_info = HashMap::from([(HEALTH_CHECK_KEY.to_string(), value)]);
+        return save_command_metadata(dataset_path, command, args, Some(additional_info));
+    };
+    metadata
+        .additional_info
+        .insert(HEALTH_CHECK_KEY.to_string(), value);
+    let yaml_content =
+        serde_yaml::to_string(&metadata).context("Failed to serialize dataset metadata to YAML")?;
+    fs::write(dataset_path.join("dataset.yaml"), yaml_content)
+        .context("Failed to write dataset metadata file")
+}
@@ -255,7 +255,7 @@ impl DatasetManager {
     }
 
     /// Analyze a dataset directory and create DatasetInfo
-    fn analyze_dataset(&self, path: &Path) -> Result<DatasetInfo> {
+    pub fn analyze_dataset(&self, path: &Path) -> Result<DatasetInfo> {
         let name = path
             .file_name()
             .and_then(|n| n.to_str())
@@ -302,7 +302,8 @@ impl DatasetManager {
         files.sort_by(|a, b| a.name.cmp(&b.name));
 
         // Use the new generic detection system
-        let dataset_type = DatasetType::from_dir_analysis(&name, &files, None);
+        let metadata = load_dataset_metadata(path).ok();
+        let dataset_type = DatasetType::from_dir_analysis(&name, &files, metadata.as_ref());
         let command_info = self.analyze_commands(&path, &name, &files);
         let is_complete =
             self.check_dataset_completeness_generic(&command_info, &files, &mut warnings);
@@ -313,7 +314,7 @@ impl DatasetManager {
         };
         use chrono::Utc;
 
-        let dataset_warnings: Vec<DatasetWarning> = warnings
+        let mut dataset_warnings: Vec<DatasetWarning> = warnings
             .into_iter()
             .map(|msg| DatasetWarning {
                 category: WarningCategory::InconsistentMetadata,
@@ -324,8 +325,36 @@ impl DatasetManager {
             })
             .collect();
 
-        // Determine health status based on completeness and warnings
-        let health_status = if !is_complete {
+        // Findings of the health check run after the dataset was written
+        let health_check = metadata
+            .as_ref()
+            .and_then(super::health::recorded_health_check);
+        if let Some(check) = &health_check {
+            let findings = check
+                .failures
+                .iter()
+                .map(|msg| (msg, WarningCategory::CorruptedFiles, WarningSeverity::Error))
+                .chain(
+                    check
+                        .warnings
+                        .iter()
+                        .map(|msg| (msg, WarningCategory::LargeFiles, WarningSeverity::Warning)),
+                );
+            for (msg, category, severity) in findings {
+                dataset_warnings.push(DatasetWarning {
+                    category,
+                    message: format!("Health check: {}", msg),
+                    affected_file: None,
+                    detected_at: check.checked_at,
+                    severity,
+                });
+            }
+        }
+
+        // Determine health status based on completeness, warnings and the health check
+        let health_status = if health_check.as_ref().is_some_and(|check| !check.passed()) {
+            DatasetHealthStatus::Corrupted
+        } else if !is_complete {
             DatasetHealthStatus::Incomplete
         } else if !dataset_warnings.is_empty() {
             DatasetHealthStatus::Warning
@@ -684,6 +713,33 @@ impl DatasetManager {
                         all_complete = false;
                     }
                 }
+                super::capture::CAPTURE_COMMAND => {
+                    // The events file is renamed into place when recording stops
+                    let finished = files
+                        .iter()
+                        .find(|f| f.name == super::capture::CAPTURE_EVENTS_FILE);
+                    match finished {
+                        Some(events) if events.size_bytes > 0 => {}
+                        Some(_) => {
+                            warnings.push("stream: The capture recorded no events".to_string());
+                        }
+                        None => {
+                            warnings.push(
+                                "stream: Recording in progress or interrupted".to_string(),
+                            );
+                            all_complete = false;
+                        }
+                    }
+                }
+                super::from_search::SEARCH_DATASET_COMMAND => {
+                    if !files.iter().any(|f| f.name == "markets.json") {
+                        warnings.push(format!(
+                            "{}: Missing markets.json",
+                            super::from_search::SEARCH_DATASET_COMMAND
+                        ));
+                        all_complete = false;
+                    }
+                }
                 _ => {
                     // Unknown command, can't validate
                     warnings.push(format!(
@@ -6,13 +6,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
+pub mod backfill;
+pub mod capture;
+pub mod from_search;
+pub mod health;
 pub mod manager;
+pub mod replay;
 pub mod selection;
+#[cfg(feature = "tui")]
 pub mod tui;
 
 pub use manager::*;
 pub use selection::*;
+#[cfg(feature = "tui")]
 pub use tui::*;
 
 /// Comprehensive dataset information with enhanced metadata
@@ -188,6 +196,11 @@ pub enum DatasetType {
     },
     /// User-created token selections
     TokenSelection { name: String, token_count: usize },
+    /// Events recorded by `stream --record`
+    StreamCapture {
+        event_count: usize,
+        token_count: usize,
+    },
     /// Mixed dataset with multiple command outputs
     Mixed { components: Vec<String> },
     /// Unknown/unidentified dataset
@@ -271,6 +284,54 @@ pub struct ExecutionContext {
     pub duration_seconds: Option<f64>,
 }
 
+/// Environment variables recorded in an [`ExecutionContext`]: polybot's own settings
+const RECORDED_ENV_PREFIXES: &[&str] = &["POLYBOT_", "RUST_LOG"];
+
+/// Environment variable names whose values are never recorded
+const SECRET_ENV_MARKERS: &[&str] = &["KEY", "SECRET", "TOKEN", "PASSWORD", "PASSPHRASE", "PRIVATE"];
+
+impl ExecutionContext {
+    /// Context of the current process for a command started at `execution_time`
+    pub fn capture(execution_time: DateTime<Utc>, duration_seconds: Option<f64>) -> Self {
+        let environment = std::env::vars()
+            .filter(|(name, _)| {
+                RECORDED_ENV_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            })
+            .map(|(name, value)| {
+                let upper = name.to_ascii_uppercase();
+                if SECRET_ENV_MARKERS.iter().any(|marker| upper.contains(marker)) {
+                    (name, "<redacted>".to_string())
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            host: std::env::var("HOSTNAME")
+                .ok()
+                .or_else(|| {
+                    fs::read_to_string("/etc/hostname")
+                        .ok()
+                        .map(|name| name.trim().to_string())
+                })
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .filter(|name| !name.is_empty()),
+            user: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok(),
+            working_directory: std::env::current_dir()
+                .ok()
+                .map(|dir| dir.display().to_string()),
+            environment,
+            execution_time,
+            duration_seconds,
+        }
+    }
+}
+
 /// Enhanced file type classification
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FileType {
@@ -421,6 +482,13 @@ impl DatasetType {
             DatasetType::TokenSelection { name, token_count } => {
                 format!("Token Selection: {} ({} tokens)", name, token_count)
             }
+            DatasetType::StreamCapture {
+                event_count,
+                token_count,
+            } => format!(
+                "Stream Capture ({} events, {} tokens)",
+                event_count, token_count
+            ),
             DatasetType::Mixed { components } => {
                 if components.is_empty() {
                     "Mixed Output".to_string()
@@ -440,6 +508,7 @@ impl DatasetType {
             DatasetType::AnalyzedMarkets { .. } => "📊",
             DatasetType::EnrichedMarkets { .. } => "✨",
             DatasetType::TokenSelection { .. } => "⭐",
+            DatasetType::StreamCapture { .. } => "📼",
             DatasetType::Mixed { .. } => "🔄",
             DatasetType::Unknown => "❓",
         }
@@ -451,9 +520,12 @@ impl DatasetType {
         files: &[FileInfo],
         metadata: Option<&DatasetMetadata>,
     ) -> Self {
-        // If we have metadata, use it
-        if let Some(meta) = metadata {
-            return Self::from_metadata(meta);
+        // If we have metadata naming a known type, use it
+        if let Some(dataset_type) = metadata
+            .map(Self::from_metadata)
+            .filter(|t| *t != DatasetType::Unknown)
+        {
+            return dataset_type;
         }
 
         // Detect patterns in files
@@ -557,6 +629,32 @@ impl DatasetType {
                     .and_then(|v| v.as_str())
                     .map(String::from),
             },
+            "TokenSelection" => DatasetType::TokenSelection {
+                name: metadata
+                    .additional_info
+                    .get("selection_name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&metadata.name)
+                    .to_string(),
+                token_count: metadata
+                    .additional_info
+                    .get("token_count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize,
+            },
+            "StreamCapture" => {
+                let count = |key: &str| {
+                    metadata
+                        .additional_info
+                        .get(key)
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as usize
+                };
+                DatasetType::StreamCapture {
+                    event_count: count("event_count"),
+                    token_count: count("token_count"),
+                }
+            }
             _ => DatasetType::Unknown,
         }
     }
@@ -595,7 +693,7 @@ impl FileType {
             .unwrap_or("");
 
         match extension.to_lowercase().as_str() {
-            "json" => {
+            "json" | "jsonl" => {
                 let subtype = if name.contains("chunk") {
                     JsonSubtype::MarketChunk
                 } else if name.contains("state") || name.contains("progress") {
@@ -758,6 +856,17 @@ pub fn save_command_metadata(
     command: &str,
     args: &[String],
     additional_info: Option<HashMap<String, serde_json::Value>>,
+) -> Result<()> {
+    save_command_metadata_with_context(dataset_path, command, args, additional_info, None)
+}
+
+/// [`save_command_metadata`], also recording where and how long the command ran
+pub fn save_command_metadata_with_context(
+    dataset_path: &Path,
+    command: &str,
+    args: &[String],
+    additional_info: Option<HashMap<String, serde_json::Value>>,
+    execution_context: Option<ExecutionContext>,
 ) -> Result<()> {
     let metadata = DatasetMetadata {
         name: dataset_path
@@ -775,6 +884,7 @@ pub fn save_command_metadata(
         dataset_type: infer_dataset_type_from_command(command),
         created_at: Utc::now(),
         additional_info: additional_info.unwrap_or_default(),
+        execution_context,
     };
 
     let yaml_content =
@@ -801,6 +911,40 @@ pub struct DatasetMetadata {
     /// Additional command-specific information
     #[serde(default)]
     pub additional_info: HashMap<String, serde_json::Value>,
+    /// Where and how long the producing command ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_context: Option<ExecutionContext>,
+}
+
+/// A dataset the running command wrote, waiting for its metadata
+#[derive(Debug, Clone)]
+pub struct ReportedDataset {
+    pub path: PathBuf,
+    pub additional_info: HashMap<String, serde_json::Value>,
+}
+
+static REPORTED_DATASETS: Mutex<Vec<ReportedDataset>> = Mutex::new(Vec::new());
+
+/// Report a dataset written by the running command
+///
+/// The CLI middleware writes its `dataset.yaml` (the full invocation, an
+/// [`ExecutionContext`] and `additional_info`) once the command succeeds.
+pub fn report_dataset(path: &Path, additional_info: HashMap<String, serde_json::Value>) {
+    if let Ok(mut reported) = REPORTED_DATASETS.lock() {
+        reported.retain(|dataset| dataset.path != path);
+        reported.push(ReportedDataset {
+            path: path.to_path_buf(),
+            additional_info,
+        });
+    }
+}
+
+/// Datasets reported since the last call
+pub fn take_reported_datasets() -> Vec<ReportedDataset> {
+    REPORTED_DATASETS
+        .lock()
+        .map(|mut reported| std::mem::take(&mut *reported))
+        .unwrap_or_default()
 }
 
 /// Enhanced command execution information
@@ -884,6 +1028,8 @@ fn infer_dataset_type_from_command(command: &str) -> String {
         "analyze" => "AnalyzedMarkets".to_string(),
         "enrich" => "EnrichedMarkets".to_string(),
         "pipeline" => "Pipeline".to_string(),
+        from_search::SEARCH_DATASET_COMMAND => "TokenSelection".to_string(),
+        capture::CAPTURE_COMMAND => "StreamCapture".to_string(),
         _ => "Unknown".to_string(),
     }
 }
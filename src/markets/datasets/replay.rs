@@ -0,0 +1,675 @@
+//! Time-range replays of stream captures
+//!
+//! `polybot canvas --replay <dataset> --from 14:00 --to 14:10` (and the same
+//! flags on `polybot tui`) play back what a `stream --record` capture received
+//! in that window instead of streaming live. Events are paced by when they
+//! were received, optionally sped up, so the window plays as it happened.
+//!
+//! Events before `--from` aren't played; they build the books the replay opens
+//! with, published as one snapshot per token together with the last tick size
+//! and trade price, so depth views start from the market as it was at `--from`.
+//!
+//! `--from` and `--to` take a time of day (`14:00`, `14:00:30`) on the
+//! capture's own date in local time, a local date and time
+//! (`2024-06-01 14:00`) or an RFC 3339 timestamp. Either may be left out to
+//! replay from the start or to the end of the capture.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use super::capture::{CAPTURE_EVENTS_FILE, CAPTURE_PARTIAL_FILE};
+use crate::core::ws::{BookSource, OrderBook, PolyEvent};
+use crate::data_paths::DataPaths;
+
+/// Buffer of each channel a replay is played into; once a consumer's buffer
+/// is full, playback waits for it instead of dropping events
+pub const REPLAY_FEED_BUFFER: usize = 10_000;
+
+/// A recorded event and when the capture received it
+#[derive(Debug, Clone)]
+pub struct ReplayEvent {
+    pub at: DateTime<Utc>,
+    pub event: PolyEvent,
+}
+
+/// The part of a capture to play back
+#[derive(Debug, Clone)]
+pub struct CaptureReplay {
+    name: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    /// State at `from`: tick sizes, book snapshots and last trade prices
+    opening: Vec<PolyEvent>,
+    /// Events in the window, oldest first
+    events: Vec<ReplayEvent>,
+    tokens: Vec<String>,
+}
+
+/// The capture directory for `dataset`, a path or a name under the datasets directory
+pub fn resolve_capture(data_paths: &DataPaths, dataset: &str) -> PathBuf {
+    let path = Path::new(dataset);
+    if path.join(CAPTURE_EVENTS_FILE).exists() || path.join(CAPTURE_PARTIAL_FILE).exists() {
+        return path.to_path_buf();
+    }
+    data_paths.datasets().join(dataset)
+}
+
+/// Every replayable market event recorded by the capture in `dir`, in file
+/// order; an interrupted capture yields what it recorded
+pub fn read_events(dir: &Path) -> Result<Vec<ReplayEvent>> {
+    let finished = dir.join(CAPTURE_EVENTS_FILE);
+    let path = if finished.exists() {
+        finished
+    } else if dir.join(CAPTURE_PARTIAL_FILE).exists() {
+        warn!(
+            "Capture {} was interrupted, reading what it recorded",
+            dir.display()
+        );
+        dir.join(CAPTURE_PARTIAL_FILE)
+    } else {
+        bail!(
+            "{} is not a stream capture (no {})",
+            dir.display(),
+            CAPTURE_EVENTS_FILE
+        );
+    };
+
+    let file =
+        std::fs::File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut recorded = Vec::new();
+    let mut unreadable = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(&line)
+            .ok()
+            .and_then(|value| recorded_event(&value))
+        {
+            Some(event) => recorded.push(event),
+            None => unreadable += 1,
+        }
+    }
+    if unreadable > 0 {
+        warn!(
+            "Skipped {} capture lines that aren't replayable market events",
+            unreadable
+        );
+    }
+    Ok(recorded)
+}
+
+impl CaptureReplay {
+    /// Load the window of the capture in `dir` between `from` and `to`
+    pub fn load(dir: &Path, from: Option<&str>, to: Option<&str>) -> Result<Self> {
+        let recorded = read_events(dir)?;
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| dir.display().to_string());
+        Self::from_events(name, recorded, from, to)
+    }
+
+    /// Cut the window out of recorded events
+    pub fn from_events(
+        name: String,
+        mut recorded: Vec<ReplayEvent>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Self> {
+        recorded.sort_by_key(|event| event.at);
+        let (Some(first), Some(last)) = (recorded.first(), recorded.last()) else {
+            bail!("Capture '{}' has no market events to replay", name);
+        };
+        let (first, last) = (first.at, last.at);
+        let from = match from {
+            Some(bound) => parse_bound(bound, first)?,
+            None => first,
+        };
+        let to = match to {
+            Some(bound) => parse_bound(bound, from)?,
+            None => last,
+        };
+        if from >= to {
+            bail!("--from must be before --to");
+        }
+        if from > last || to < first {
+            bail!(
+                "Capture '{}' covers {} to {}, which doesn't overlap the requested window",
+                name,
+                local_time(first),
+                local_time(last)
+            );
+        }
+
+        let split = recorded.partition_point(|event| event.at < from);
+        let end = recorded.partition_point(|event| event.at <= to);
+        let opening = opening_state(&recorded[..split]);
+        let events = recorded[split..end].to_vec();
+        let tokens: BTreeSet<String> = opening
+            .iter()
+            .chain(events.iter().map(|event| &event.event))
+            .filter_map(|event| event.market_asset_id().map(str::to_string))
+            .collect();
+
+        Ok(Self {
+            name,
+            from,
+            to,
+            opening,
+            events,
+            tokens: tokens.into_iter().collect(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn from(&self) -> DateTime<Utc> {
+        self.from
+    }
+
+    pub fn to(&self) -> DateTime<Utc> {
+        self.to
+    }
+
+    /// Tokens with events in the window or a book at its start
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Events played at their recorded time
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} from {} to {} ({} events, {} tokens)",
+            self.name,
+            local_time(self.from),
+            local_time(self.to),
+            self.events.len(),
+            self.tokens.len()
+        )
+    }
+
+    /// Play the window into `feed`, `speed` times faster than recorded
+    ///
+    /// A consumer that can't keep up holds playback back; the position then
+    /// trails the wall clock rather than the books missing events.
+    pub fn play(self: &Arc<Self>, feed: ReplayFeed, speed: f64) -> ReplayHandle {
+        let position = Arc::new(AtomicI64::new(self.from.timestamp_millis()));
+        let finished = Arc::new(AtomicBool::new(false));
+        let replay = Arc::clone(self);
+        let task = tokio::spawn({
+            let position = Arc::clone(&position);
+            let finished = Arc::clone(&finished);
+            async move {
+                // No subscribers just means nothing is listening yet; keep time regardless
+                for event in &replay.opening {
+                    feed.send(event).await;
+                }
+                let started = tokio::time::Instant::now();
+                for recorded in &replay.events {
+                    let offset = (recorded.at - replay.from).to_std().unwrap_or_default();
+                    tokio::time::sleep_until(started + offset.div_f64(speed)).await;
+                    position.store(recorded.at.timestamp_millis(), Ordering::Relaxed);
+                    feed.send(&recorded.event).await;
+                }
+                position.store(replay.to.timestamp_millis(), Ordering::Relaxed);
+                finished.store(true, Ordering::Relaxed);
+            }
+        });
+        ReplayHandle {
+            position,
+            finished,
+            task,
+        }
+    }
+}
+
+/// A consumer of a replay and the tokens it takes events of
+#[derive(Debug)]
+struct ReplaySubscriber {
+    /// `None` takes every event
+    tokens: Option<HashSet<String>>,
+    events: mpsc::Sender<PolyEvent>,
+}
+
+/// Where a replay is played: fans its events out to bounded channels
+///
+/// Each worker replaying a capture subscribes for its tokens. Playback waits
+/// for room in a subscriber's channel, so a slow consumer delays the replay
+/// instead of skipping events. A subscriber whose receiver was dropped, like
+/// a worker restarted with other tokens, is forgotten on the next event.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayFeed {
+    subscribers: Arc<Mutex<Vec<ReplaySubscriber>>>,
+}
+
+impl ReplayFeed {
+    /// Receive the events of `tokens`
+    pub fn subscribe(&self, tokens: HashSet<String>) -> mpsc::Receiver<PolyEvent> {
+        self.add(Some(tokens))
+    }
+
+    /// Receive every event
+    pub fn subscribe_all(&self) -> mpsc::Receiver<PolyEvent> {
+        self.add(None)
+    }
+
+    fn add(&self, tokens: Option<HashSet<String>>) -> mpsc::Receiver<PolyEvent> {
+        let (events, receiver) = mpsc::channel(REPLAY_FEED_BUFFER);
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ReplaySubscriber { tokens, events });
+        receiver
+    }
+
+    /// Deliver an event to the subscribers taking it, waiting while their channels are full
+    pub async fn send(&self, event: &PolyEvent) {
+        let targets: Vec<mpsc::Sender<PolyEvent>> = {
+            let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+            subscribers.retain(|subscriber| !subscriber.events.is_closed());
+            subscribers
+                .iter()
+                .filter(|subscriber| match (&subscriber.tokens, event.market_asset_id()) {
+                    (None, _) => true,
+                    (Some(tokens), Some(id)) => tokens.contains(id),
+                    (Some(_), None) => false,
+                })
+                .map(|subscriber| subscriber.events.clone())
+                .collect()
+        };
+        for target in targets {
+            // A receiver dropped meanwhile is pruned on the next event
+            let _ = target.send(event.clone()).await;
+        }
+    }
+}
+
+/// A replay being played
+pub struct ReplayHandle {
+    /// Recorded time of the last played event, in milliseconds
+    position: Arc<AtomicI64>,
+    finished: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl ReplayHandle {
+    /// Where in the recording playback is
+    pub fn position(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.position.load(Ordering::Relaxed)).unwrap_or_default()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for ReplayHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Parse a replay speed multiplier
+pub fn parse_speed(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!(
+            "Invalid speed '{}': use a positive number like 1, 2 or 0.5",
+            s
+        )),
+    }
+}
+
+/// Resolve a `--from`/`--to` bound; a bare time of day falls on the local date
+/// of `reference`, or the day after when that time has already passed
+pub fn parse_bound(s: &str, reference: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return local_to_utc(naive, s);
+        }
+    }
+    for format in ["%H:%M:%S", "%H:%M"] {
+        if let Ok(time) = NaiveTime::parse_from_str(s, format) {
+            let local = reference.with_timezone(&Local);
+            let mut date: NaiveDate = local.date_naive();
+            // Whole seconds, so `14:00` still means today for a capture started at 14:00:00.4
+            let started = local.time().with_nanosecond(0).unwrap_or(local.time());
+            if time < started {
+                date = date.succ_opt().unwrap_or(date);
+            }
+            return local_to_utc(date.and_time(time), s);
+        }
+    }
+    bail!(
+        "Invalid time '{}': use HH:MM[:SS], 'YYYY-MM-DD HH:MM[:SS]' or RFC 3339",
+        s
+    )
+}
+
+fn local_to_utc(naive: NaiveDateTime, input: &str) -> Result<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .with_context(|| format!("'{}' doesn't exist in the local time zone", input))
+}
+
+fn local_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// A token's last trade price as of the replay's start
+#[derive(Debug, Clone, Copy)]
+struct LastTrade {
+    price: Decimal,
+    timestamp: u64,
+}
+
+/// Tick sizes, books and last trade prices left by `events`
+fn opening_state(events: &[ReplayEvent]) -> Vec<PolyEvent> {
+    let mut books: HashMap<&str, OrderBook> = HashMap::new();
+    let mut tick_sizes: HashMap<&str, Decimal> = HashMap::new();
+    let mut last_trades: HashMap<&str, LastTrade> = HashMap::new();
+    for recorded in events {
+        match &recorded.event {
+            PolyEvent::Book {
+                asset_id,
+                market,
+                timestamp,
+                bids,
+                asks,
+                ..
+            } => {
+                books
+                    .entry(asset_id.as_str())
+                    .or_insert_with(|| OrderBook::new(asset_id.clone()))
+                    .replace_with_snapshot_no_hash(
+                        market.clone(),
+                        *timestamp,
+                        bids.clone(),
+                        asks.clone(),
+                    );
+            }
+            PolyEvent::PriceChange {
+                asset_id,
+                side,
+                price,
+                size,
+                ..
+            } => {
+                if let Some(book) = books.get_mut(asset_id.as_str()) {
+                    book.apply_price_change_no_hash(*side, *price, *size);
+                }
+            }
+            PolyEvent::TickSizeChange {
+                asset_id,
+                tick_size,
+            } => {
+                tick_sizes.insert(asset_id.as_str(), *tick_size);
+            }
+            PolyEvent::LastTradePrice {
+                asset_id,
+                price,
+                timestamp,
+            } => {
+                last_trades.insert(
+                    asset_id.as_str(),
+                    LastTrade {
+                        price: *price,
+                        timestamp: *timestamp,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let mut opening = Vec::new();
+    let mut ticked: Vec<_> = tick_sizes.into_iter().collect();
+    ticked.sort();
+    opening.extend(
+        ticked
+            .into_iter()
+            .map(|(asset_id, tick_size)| PolyEvent::TickSizeChange {
+                asset_id: asset_id.to_string(),
+                tick_size,
+            }),
+    );
+    let mut books: Vec<_> = books.into_values().collect();
+    books.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+    opening.extend(books.into_iter().map(|book| PolyEvent::Book {
+        bids: book.get_bids(),
+        asks: book.get_asks(),
+        asset_id: book.asset_id,
+        market: book.market,
+        timestamp: book.timestamp,
+        hash: String::new(),
+        source: BookSource::WebSocket,
+    }));
+    let mut traded: Vec<_> = last_trades.into_iter().collect();
+    traded.sort_by_key(|(asset_id, _)| *asset_id);
+    opening.extend(traded.into_iter().map(|(asset_id, trade)| {
+        PolyEvent::LastTradePrice {
+            asset_id: asset_id.to_string(),
+            price: trade.price,
+            timestamp: trade.timestamp,
+        }
+    }));
+    opening
+}
+
+/// The market event of a capture line, as written by `stream --record`;
+/// user order and fill events aren't replayed
+fn recorded_event(value: &Value) -> Option<ReplayEvent> {
+    let at = DateTime::from_timestamp_millis(value.get("received_at")?.as_i64()?)?;
+    let field = |key: &str| value.get(key).cloned().unwrap_or(Value::Null);
+    let text = |key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let parse = |key: &str| serde_json::from_value(field(key)).ok();
+
+    let event = match value.get("type")?.as_str()? {
+        "book" => PolyEvent::Book {
+            asset_id: text("asset_id"),
+            market: text("market"),
+            timestamp: value.get("timestamp").and_then(Value::as_u64).unwrap_or(0),
+            bids: parse("bids")?,
+            asks: parse("asks")?,
+            hash: text("hash"),
+            source: if value.get("synthetic").and_then(Value::as_bool) == Some(true) {
+                BookSource::RestSnapshot
+            } else {
+                BookSource::WebSocket
+            },
+        },
+        "price_change" => PolyEvent::PriceChange {
+            asset_id: text("asset_id"),
+            side: parse("side")?,
+            price: parse("price")?,
+            size: parse("size")?,
+            hash: text("hash"),
+        },
+        "trade" => PolyEvent::Trade {
+            asset_id: text("asset_id"),
+            price: parse("price")?,
+            size: parse("size")?,
+            side: parse("side")?,
+        },
+        "last_trade" => PolyEvent::LastTradePrice {
+            asset_id: text("asset_id"),
+            price: parse("price")?,
+            timestamp: value.get("timestamp").and_then(Value::as_u64).unwrap_or(0),
+        },
+        "tick_size" => PolyEvent::TickSizeChange {
+            asset_id: text("asset_id"),
+            tick_size: parse("tick_size")?,
+        },
+        _ => return None,
+    };
+    Some(ReplayEvent { at, event })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn window_opens_with_the_books_recorded_before_it() {
+        let at = |minute: i64| {
+            DateTime::parse_from_rfc3339("2024-06-01T14:00:00Z")
+                .unwrap()
+                .timestamp_millis()
+                + minute * 60_000
+        };
+        let lines = [
+            json!({"type": "book", "asset_id": "1", "market": "m", "timestamp": 1,
+                   "bids": [{"price": "0.40", "size": "10"}], "asks": [{"price": "0.45", "size": "5"}],
+                   "hash": "h", "synthetic": false, "received_at": at(0)}),
+            json!({"type": "price_change", "asset_id": "1", "side": "Buy", "price": "0.41",
+                   "size": "7", "hash": "h", "received_at": at(2)}),
+            json!({"type": "order", "asset_id": "1", "received_at": at(3)}),
+            json!({"type": "trade", "asset_id": "2", "side": "Sell", "price": "0.7",
+                   "size": "3", "received_at": at(6)}),
+            json!({"type": "price_change", "asset_id": "1", "side": "Sell", "price": "0.44",
+                   "size": "1", "hash": "h", "received_at": at(12)}),
+        ];
+        let recorded: Vec<ReplayEvent> = lines.iter().filter_map(recorded_event).collect();
+        assert_eq!(recorded.len(), 4);
+
+        let replay = CaptureReplay::from_events(
+            "captures/x".to_string(),
+            recorded.clone(),
+            Some("2024-06-01T14:05:00Z"),
+            Some("2024-06-01T14:10:00Z"),
+        )
+        .unwrap();
+        assert_eq!(replay.tokens(), ["1", "2"]);
+        assert_eq!(replay.len(), 1);
+        match &replay.opening[..] {
+            [PolyEvent::Book { bids, asks, .. }] => {
+                assert_eq!(bids[0].price.to_string(), "0.41");
+                assert_eq!(bids.len(), 2);
+                assert_eq!(asks.len(), 1);
+            }
+            other => panic!("unexpected opening {:?}", other),
+        }
+
+        let everything =
+            CaptureReplay::from_events("x".to_string(), recorded.clone(), None, None).unwrap();
+        assert_eq!((everything.len(), everything.opening.len()), (4, 0));
+
+        assert!(CaptureReplay::from_events(
+            "x".to_string(),
+            recorded,
+            Some("2024-06-01T15:00:00Z"),
+            None
+        )
+        .is_err());
+
+        // A bare time of day lands on the reference's local date, or the next one
+        let reference = DateTime::parse_from_rfc3339("2024-06-01T14:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let local = reference.with_timezone(&Local);
+        let later = (local + chrono::Duration::minutes(30))
+            .format("%H:%M")
+            .to_string();
+        let earlier = (local - chrono::Duration::minutes(30))
+            .format("%H:%M")
+            .to_string();
+        assert_eq!(
+            parse_bound(&later, reference).unwrap(),
+            reference + chrono::Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_bound(&earlier, reference).unwrap(),
+            reference + chrono::Duration::minutes(23 * 60 + 30)
+        );
+        assert!(parse_bound("soon", reference).is_err());
+    }
+
+    #[tokio::test]
+    async fn feed_routes_by_token_and_waits_for_full_subscribers() {
+        let trade = |asset_id: &str| {
+            recorded_event(&json!({"type": "trade", "asset_id": asset_id, "side": "Sell",
+                                   "price": "0.7", "size": "3", "received_at": 0}))
+            .unwrap()
+            .event
+        };
+        let feed = ReplayFeed::default();
+        let mut first = feed.subscribe(HashSet::from(["1".to_string()]));
+        let mut everything = feed.subscribe_all();
+        let dropped = feed.subscribe(HashSet::from(["2".to_string()]));
+        drop(dropped);
+
+        feed.send(&trade("1")).await;
+        feed.send(&trade("2")).await;
+        assert_eq!(first.try_recv().unwrap().market_asset_id(), Some("1"));
+        assert!(first.try_recv().is_err());
+        assert_eq!(everything.try_recv().unwrap().market_asset_id(), Some("1"));
+        assert_eq!(everything.try_recv().unwrap().market_asset_id(), Some("2"));
+        assert_eq!(feed.subscribers.lock().unwrap().len(), 2);
+
+        // A full subscriber holds playback back until it takes an event
+        drop(everything);
+        for _ in 0..REPLAY_FEED_BUFFER {
+            feed.send(&trade("1")).await;
+        }
+        let blocked = tokio::spawn({
+            let feed = feed.clone();
+            let event = trade("1");
+            async move { feed.send(&event).await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!blocked.is_finished());
+        first.recv().await.unwrap();
+        blocked.await.unwrap();
+        let mut queued = 0;
+        while first.try_recv().is_ok() {
+            queued += 1;
+        }
+        assert_eq!(queued, REPLAY_FEED_BUFFER);
+    }
+}
@@ -17,6 +17,9 @@ use ratatui::{
 use std::io;
 
 use super::{DatasetInfo, DatasetManager, DatasetManagerConfig, DatasetSummary};
+use crate::markets::clob::enrich::EnrichedMarket;
+use crate::markets::snapshot_diff::MarketsDiff;
+use crate::tui::widgets::snapshot_diff::{card_height, render_diff_card};
 
 /// Dataset management TUI application state
 pub struct DatasetTui {
@@ -34,6 +37,19 @@ pub struct DatasetTui {
     marked_for_deletion: Vec<String>,
     /// Current operation result message
     status_message: Option<StatusMessage>,
+    /// Dataset picked with 'x' to compare with the next one
+    diff_base: Option<String>,
+    /// Comparison shown in the diff view
+    diff: Option<DatasetDiff>,
+    /// First card shown in the diff view
+    diff_scroll: usize,
+}
+
+/// Market changes between two enriched datasets
+struct DatasetDiff {
+    older: String,
+    newer: String,
+    markets: MarketsDiff,
 }
 
 /// Different view modes for the TUI
@@ -45,6 +61,8 @@ pub enum ViewMode {
     Summary,
     /// Confirmation dialog for deletion
     DeleteConfirmation,
+    /// Market changes between two datasets
+    Diff,
 }
 
 /// Status message for user feedback
@@ -68,6 +86,9 @@ impl DatasetTui {
             should_quit: false,
             marked_for_deletion: Vec::new(),
             status_message: None,
+            diff_base: None,
+            diff: None,
+            diff_scroll: 0,
         };
 
         // Select first dataset if available
@@ -93,6 +114,7 @@ impl DatasetTui {
             ViewMode::List => self.handle_list_keys(key),
             ViewMode::Summary => self.handle_summary_keys(key),
             ViewMode::DeleteConfirmation => self.handle_delete_confirmation_keys(key),
+            ViewMode::Diff => self.handle_diff_keys(key),
         }
     }
 
@@ -131,10 +153,89 @@ impl DatasetTui {
                 self.marked_for_deletion.clear();
                 self.set_status_message("Cleared deletion marks", false);
             }
+            KeyCode::Char('x') => {
+                self.compare_selected();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle keys in diff view mode
+    fn handle_diff_keys(&mut self, key: KeyCode) {
+        let cards = self
+            .diff
+            .as_ref()
+            .map_or(0, |diff| diff.markets.changed.len());
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.view_mode = ViewMode::List;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.diff_scroll = (self.diff_scroll + 1).min(cards.saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.diff_scroll = self.diff_scroll.saturating_sub(1);
+            }
             _ => {}
         }
     }
 
+    /// Pick the selected dataset as the diff base, or compare it with the base
+    fn compare_selected(&mut self) {
+        let Some(selected) = self.selected_dataset().cloned() else {
+            return;
+        };
+        let base = match self.diff_base.take() {
+            Some(base) if base != selected.name => base,
+            Some(_) => {
+                self.set_status_message("Cleared the diff base", false);
+                return;
+            }
+            None => {
+                self.set_status_message(
+                    &format!(
+                        "Diff base: {}. Select another dataset and press x",
+                        selected.name
+                    ),
+                    false,
+                );
+                self.diff_base = Some(selected.name);
+                return;
+            }
+        };
+        let Some(base) = self
+            .manager
+            .get_datasets()
+            .iter()
+            .find(|dataset| dataset.name == base)
+            .cloned()
+        else {
+            self.set_status_message(&format!("Dataset {} is gone", base), true);
+            return;
+        };
+
+        // Older first, so changes read forward in time
+        let (older, newer) = if base.created_at <= selected.created_at {
+            (base, selected)
+        } else {
+            (selected, base)
+        };
+        let loaded = EnrichedMarket::load_dataset(&older.path)
+            .and_then(|before| Ok((before, EnrichedMarket::load_dataset(&newer.path)?)));
+        match loaded {
+            Ok((before, after)) => {
+                self.diff = Some(DatasetDiff {
+                    older: older.name,
+                    newer: newer.name,
+                    markets: MarketsDiff::between(&before, &after),
+                });
+                self.diff_scroll = 0;
+                self.view_mode = ViewMode::Diff;
+            }
+            Err(e) => self.set_status_message(&format!("Can't compare: {:#}", e), true),
+        }
+    }
+
     /// Handle keys in summary view mode
     fn handle_summary_keys(&mut self, key: KeyCode) {
         match key {
@@ -321,6 +422,7 @@ impl DatasetTui {
             ViewMode::List => self.draw_list_view(f),
             ViewMode::Summary => self.draw_summary_view(f),
             ViewMode::DeleteConfirmation => self.draw_delete_confirmation(f),
+            ViewMode::Diff => self.draw_diff_view(f),
         }
     }
 
@@ -392,7 +494,13 @@ impl DatasetTui {
             .iter()
             .map(|dataset| {
                 let is_marked = self.marked_for_deletion.contains(&dataset.name);
-                let mark_icon = if is_marked { "🗑️" } else { " " };
+                let mark_icon = if is_marked {
+                    "🗑️"
+                } else if self.diff_base.as_ref() == Some(&dataset.name) {
+                    "🔀"
+                } else {
+                    " "
+                };
 
                 let style = if is_marked {
                     Style::default().fg(Color::Red)
@@ -665,6 +773,68 @@ impl DatasetTui {
         f.render_widget(summary_paragraph, area);
     }
 
+    /// Draw the market changes between two datasets as a column of cards
+    fn draw_diff_view(&self, f: &mut Frame) {
+        let size = f.area();
+        let Some(diff) = &self.diff else {
+            return;
+        };
+        let markets = &diff.markets;
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ])
+            .split(size);
+
+        let header = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled(&diff.older, Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(" → "),
+                Span::styled(&diff.newer, Style::default().add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(Span::styled(
+                format!(
+                    "{} changed (biggest price move first) • {} unchanged • {} added • {} removed",
+                    markets.changed.len(),
+                    markets.unchanged,
+                    markets.added,
+                    markets.removed
+                ),
+                Style::default().fg(Color::Gray),
+            )),
+        ])
+        .block(
+            Block::default()
+                .title("🔀 Dataset Diff")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        f.render_widget(header, chunks[0]);
+
+        let area = chunks[1];
+        if markets.changed.is_empty() {
+            let empty = Paragraph::new("No market changed between these datasets")
+                .style(Style::default().fg(Color::Gray))
+                .alignment(Alignment::Center);
+            f.render_widget(empty, area);
+        }
+        let mut y = area.y;
+        for card in markets.changed.iter().skip(self.diff_scroll) {
+            let height = card_height(card);
+            if y + height > area.y + area.height {
+                break;
+            }
+            render_diff_card(f, ratatui::layout::Rect { y, height, ..area }, card);
+            y += height;
+        }
+
+        self.draw_help(f, size);
+    }
+
     /// Draw delete confirmation dialog
     fn draw_delete_confirmation(&self, f: &mut Frame) {
         let size = f.area();
@@ -745,11 +915,12 @@ impl DatasetTui {
                 if self.manager.get_datasets().is_empty() {
                     "q: Quit • r: Refresh"
                 } else {
-                    "↑/↓ or j/k: Navigate • d: Mark for deletion • D: Delete marked • c: Clear marks • Space: Details • s: Summary • r: Refresh • q: Quit"
+                    "↑/↓ or j/k: Navigate • d: Mark for deletion • D: Delete marked • c: Clear marks • x: Compare • Space: Details • s: Summary • r: Refresh • q: Quit"
                 }
             }
             ViewMode::Summary => "q/Esc: Back to list • r: Refresh",
             ViewMode::DeleteConfirmation => "Y: Confirm deletion • N/Esc: Cancel",
+            ViewMode::Diff => "↑/↓ or j/k: Scroll • q/Esc: Back to list",
         };
 
         let help = Paragraph::new(help_text)
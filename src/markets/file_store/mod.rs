@@ -3,175 +3,295 @@
 //! Creates a structured file hierarchy:
 //! - data/database/markets/condition/<condition_id>/
 //! - data/database/markets/token/<token_id>/
+//!
+//! Markets are rendered to files up front and then written either directly
+//! (`FileStore::store_market`) or through a [`BatchWriter`], which queues
+//! rendered files and writes them in batches off the caller's task.
+//!
+//! Deprecated as a write target: typed_store (RocksDB) is the source of truth
+//! for market data. Existing hierarchies are imported by the `typed_store`
+//! v2 migration, and `typed_store::compat::LegacyPathReader` serves reads
+//! addressed by the old paths.
+
+mod writer;
+
+pub use writer::{BatchWriter, WriteStats};
 
 use crate::markets::clob::fetcher::{Market, MarketToken};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
+/// How a market is laid out on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum FileLayout {
+    /// Condition, market and token directories with one file per view
+    #[default]
+    Split,
+    /// A single `market.json` per condition holding the market, its
+    /// metadata and its tokens
+    Consolidated,
+}
+
+/// When written files are flushed to stable storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum FsyncPolicy {
+    /// Leave it to the OS
+    #[default]
+    Never,
+    /// Sync every file of a batch once the batch is written
+    Batch,
+    /// Sync each file as it is written
+    File,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileStoreOptions {
+    pub layout: FileLayout,
+    pub fsync: FsyncPolicy,
+    /// Files written per batch by the [`BatchWriter`]
+    pub batch_size: usize,
+    /// Rendered markets the write-ahead queue holds before producers wait
+    pub queue_depth: usize,
+}
+
+impl Default for FileStoreOptions {
+    fn default() -> Self {
+        Self {
+            layout: FileLayout::Split,
+            fsync: FsyncPolicy::Never,
+            batch_size: 1000,
+            queue_depth: 10_000,
+        }
+    }
+}
+
+/// A file rendered from a market, waiting to be written
+#[derive(Debug)]
+pub(crate) struct PendingFile {
+    pub(crate) path: PathBuf,
+    pub(crate) contents: Vec<u8>,
+}
+
 pub struct FileStore {
     base_path: PathBuf,
+    options: FileStoreOptions,
 }
 
 impl FileStore {
     pub fn new(base_path: PathBuf) -> Result<Self> {
+        Self::with_options(base_path, FileStoreOptions::default())
+    }
+
+    pub fn with_options(base_path: PathBuf, options: FileStoreOptions) -> Result<Self> {
         // Ensure base directories exist
         let markets_path = base_path.join("markets");
         fs::create_dir_all(markets_path.join("condition"))?;
         fs::create_dir_all(markets_path.join("token"))?;
         fs::create_dir_all(markets_path.join("market"))?;
 
-        Ok(Self { base_path })
+        Ok(Self { base_path, options })
+    }
+
+    pub fn options(&self) -> &FileStoreOptions {
+        &self.options
     }
 
-    /// Store a market and all its related data
+    /// Store a market and all its related data, writing synchronously
+    #[deprecated(note = "market data lives in typed_store; index with `polybot index` (RocksDB) instead")]
     pub fn store_market(&self, market: &Market) -> Result<()> {
-        // Store by condition_id
-        if let Some(condition_id) = &market.condition_id {
-            if !condition_id.trim().is_empty() {
-                self.store_market_by_condition(condition_id, market)?;
-            }
+        let files = self.render_market(market)?;
+        let mut created_dirs = HashSet::new();
+        for file in &files {
+            write_file(file, &mut created_dirs, self.options.fsync == FsyncPolicy::File)?;
         }
+        Ok(())
+    }
 
-        // Store by market_id
-        if let Some(market_id) = &market.id {
-            if !market_id.trim().is_empty() {
-                self.store_market_by_id(market_id, market)?;
-            }
-        }
+    /// Start a batched writer for this store; see [`BatchWriter`]
+    #[deprecated(note = "market data lives in typed_store; index with `polybot index` (RocksDB) instead")]
+    pub fn batch_writer(&self) -> BatchWriter {
+        BatchWriter::start(self.base_path.clone(), self.options.clone())
+    }
 
-        // Store tokens
-        for token in &market.tokens {
-            self.store_token(&token, market)?;
+    /// Render every file a market is stored as, without touching the disk
+    fn render_market(&self, market: &Market) -> Result<Vec<PendingFile>> {
+        let mut files = render_market_files(market, self.options.layout)?;
+        for file in &mut files {
+            file.path = self.base_path.join(&file.path);
         }
+        Ok(files)
+    }
 
-        Ok(())
+    /// Get statistics about stored data
+    pub fn get_stats(&self) -> Result<StoreStats> {
+        let conditions_path = self.base_path.join("markets").join("condition");
+        let tokens_path = self.base_path.join("markets").join("token");
+        let markets_path = self.base_path.join("markets").join("market");
+
+        let condition_count = count_directories(&conditions_path)?;
+        let token_count = count_directories(&tokens_path)?;
+        let market_count = count_directories(&markets_path)?;
+
+        Ok(StoreStats {
+            conditions: condition_count,
+            tokens: token_count,
+            markets: market_count,
+        })
     }
+}
 
-    fn store_market_by_condition(&self, condition_id: &str, market: &Market) -> Result<()> {
-        let condition_path = self
-            .base_path
-            .join("markets")
-            .join("condition")
-            .join(sanitize_filename(condition_id));
+/// Render the files a market is stored as, with paths relative to the store's
+/// base directory
+///
+/// Also used by `typed_store::compat` to serve reads of these paths after the
+/// hierarchy has been imported into RocksDB.
+pub(crate) fn render_market_files(market: &Market, layout: FileLayout) -> Result<Vec<PendingFile>> {
+    let mut files = Vec::new();
+    let markets_path = PathBuf::from("markets");
+    let condition_id = market.condition_id.as_deref().filter(|id| !id.trim().is_empty());
+    let market_id = market.id.as_deref().filter(|id| !id.trim().is_empty());
+
+    if layout == FileLayout::Consolidated {
+        if let Some(condition_id) = condition_id {
+            let consolidated = ConsolidatedMarket {
+                metadata: condition_metadata(condition_id, market),
+                tokens: market.tokens.iter().map(|t| token_data(t, market)).collect(),
+                market,
+            };
+            files.push(PendingFile {
+                path: markets_path
+                    .join("condition")
+                    .join(sanitize_filename(condition_id))
+                    .join("market.json"),
+                contents: serde_json::to_vec(&consolidated)?,
+            });
+        }
+        return Ok(files);
+    }
 
-        fs::create_dir_all(&condition_path)?;
+    // Store by condition_id
+    if let Some(condition_id) = condition_id {
+        let condition_path = markets_path.join("condition").join(sanitize_filename(condition_id));
 
         // Store market info
-        let market_file = condition_path.join("market.json");
-        let json = serde_json::to_string_pretty(market)?;
-        fs::write(market_file, json)?;
+        files.push(PendingFile {
+            path: condition_path.join("market.json"),
+            contents: serde_json::to_vec_pretty(market)?,
+        });
 
         // Store metadata
-        let metadata = ConditionMetadata {
-            condition_id: condition_id.to_string(),
-            question: market.question.clone(),
-            description: market.description.clone(),
-            category: market.category.clone(),
-            tags: market.tags.clone(),
-            outcomes: market.outcomes.clone(),
-            token_ids: market.tokens.iter().map(|t| t.token_id.clone()).collect(),
-            market_id: market.id.clone(),
-            active: market.active,
-            closed: market.closed,
-            volume: market.volume,
-            volume_24hr: market.volume_24hr,
-            created_at: market.created_at.clone(),
-            updated_at: market.updated_at.clone(),
-        };
-
-        let metadata_file = condition_path.join("metadata.json");
-        let json = serde_json::to_string_pretty(&metadata)?;
-        fs::write(metadata_file, json)?;
+        files.push(PendingFile {
+            path: condition_path.join("metadata.json"),
+            contents: serde_json::to_vec_pretty(&condition_metadata(condition_id, market))?,
+        });
 
         // Store token list
-        let tokens_file = condition_path.join("tokens.json");
-        let json = serde_json::to_string_pretty(&market.tokens)?;
-        fs::write(tokens_file, json)?;
-
-        Ok(())
+        files.push(PendingFile {
+            path: condition_path.join("tokens.json"),
+            contents: serde_json::to_vec_pretty(&market.tokens)?,
+        });
     }
 
-    fn store_market_by_id(&self, market_id: &str, market: &Market) -> Result<()> {
-        let market_path = self
-            .base_path
-            .join("markets")
-            .join("market")
-            .join(sanitize_filename(market_id));
-
-        fs::create_dir_all(&market_path)?;
-
-        let market_file = market_path.join("data.json");
-        let json = serde_json::to_string_pretty(market)?;
-        fs::write(market_file, json)?;
-
-        Ok(())
+    // Store by market_id
+    if let Some(market_id) = market_id {
+        files.push(PendingFile {
+            path: markets_path.join("market").join(sanitize_filename(market_id)).join("data.json"),
+            contents: serde_json::to_vec_pretty(market)?,
+        });
     }
 
-    fn store_token(&self, token: &MarketToken, market: &Market) -> Result<()> {
-        let token_path = self
-            .base_path
-            .join("markets")
-            .join("token")
-            .join(sanitize_filename(&token.token_id));
-
-        fs::create_dir_all(&token_path)?;
+    // Store tokens
+    for token in &market.tokens {
+        let token_path = markets_path.join("token").join(sanitize_filename(&token.token_id));
 
         // Store token data
-        let token_data = TokenData {
-            token_id: token.token_id.clone(),
-            outcome: token.outcome.clone(),
-            price: token.price,
-            winner: token.winner,
-            volume: token.volume,
-            volume_24hr: token.volume_24hr,
-            supply: token.supply,
-            market_cap: token.market_cap,
-            condition_id: market.condition_id.clone(),
-            market_id: market.id.clone(),
-            question: market.question.clone(),
-            active: market.active,
-            closed: market.closed,
-        };
-
-        let token_file = token_path.join("data.json");
-        let json = serde_json::to_string_pretty(&token_data)?;
-        fs::write(token_file, json)?;
+        files.push(PendingFile {
+            path: token_path.join("data.json"),
+            contents: serde_json::to_vec_pretty(&token_data(token, market))?,
+        });
 
         // Store market reference
         if let Some(market_id) = &market.id {
-            let market_ref_file = token_path.join("market_reference.json");
             let market_ref = MarketReference {
                 market_id: market_id.clone(),
                 condition_id: market.condition_id.clone(),
                 question: market.question.clone(),
             };
-            let json = serde_json::to_string_pretty(&market_ref)?;
-            fs::write(market_ref_file, json)?;
+            files.push(PendingFile {
+                path: token_path.join("market_reference.json"),
+                contents: serde_json::to_vec_pretty(&market_ref)?,
+            });
         }
-
-        Ok(())
     }
 
-    /// Get statistics about stored data
-    pub fn get_stats(&self) -> Result<StoreStats> {
-        let conditions_path = self.base_path.join("markets").join("condition");
-        let tokens_path = self.base_path.join("markets").join("token");
-        let markets_path = self.base_path.join("markets").join("market");
+    Ok(files)
+}
 
-        let condition_count = count_directories(&conditions_path)?;
-        let token_count = count_directories(&tokens_path)?;
-        let market_count = count_directories(&markets_path)?;
+fn condition_metadata(condition_id: &str, market: &Market) -> ConditionMetadata {
+    ConditionMetadata {
+        condition_id: condition_id.to_string(),
+        question: market.question.clone(),
+        description: market.description.clone(),
+        category: market.category.clone(),
+        tags: market.tags.clone(),
+        outcomes: market.outcomes.clone(),
+        token_ids: market.tokens.iter().map(|t| t.token_id.clone()).collect(),
+        market_id: market.id.clone(),
+        active: market.active,
+        closed: market.closed,
+        volume: market.volume,
+        volume_24hr: market.volume_24hr,
+        created_at: market.created_at.clone(),
+        updated_at: market.updated_at.clone(),
+    }
+}
 
-        Ok(StoreStats {
-            conditions: condition_count,
-            tokens: token_count,
-            markets: market_count,
-        })
+fn token_data(token: &MarketToken, market: &Market) -> TokenData {
+    TokenData {
+        token_id: token.token_id.clone(),
+        outcome: token.outcome.clone(),
+        price: token.price,
+        winner: token.winner,
+        volume: token.volume,
+        volume_24hr: token.volume_24hr,
+        supply: token.supply,
+        market_cap: token.market_cap,
+        condition_id: market.condition_id.clone(),
+        market_id: market.id.clone(),
+        question: market.question.clone(),
+        active: market.active,
+        closed: market.closed,
     }
 }
 
+/// Write one rendered file, creating its directory the first time it is seen
+fn write_file(file: &PendingFile, created_dirs: &mut HashSet<PathBuf>, sync: bool) -> Result<()> {
+    if let Some(dir) = file.path.parent() {
+        if !created_dirs.contains(dir) {
+            fs::create_dir_all(dir)?;
+            created_dirs.insert(dir.to_path_buf());
+        }
+    }
+    let mut handle = fs::File::create(&file.path)?;
+    handle.write_all(&file.contents)?;
+    if sync {
+        handle.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Everything stored for a market in the consolidated layout
+#[derive(Debug, Serialize)]
+struct ConsolidatedMarket<'a> {
+    market: &'a Market,
+    metadata: ConditionMetadata,
+    tokens: Vec<TokenData>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ConditionMetadata {
     condition_id: String,
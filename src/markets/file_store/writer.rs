@@ -0,0 +1,234 @@
+//! Batched background writer for the file store
+//!
+//! Producers render markets on their own task and push the files onto a
+//! bounded write-ahead queue; a blocking worker drains the queue in batches,
+//! creating each directory once and syncing according to the store's
+//! [`FsyncPolicy`]. Queued files are only on disk after [`BatchWriter::flush`]
+//! or [`BatchWriter::finish`] returns.
+
+use super::{write_file, FileStore, FileStoreOptions, FsyncPolicy, PendingFile};
+use crate::markets::clob::fetcher::Market;
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+enum Request {
+    Market(Vec<PendingFile>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// What a writer has put on disk
+#[derive(Debug, Clone, Default)]
+pub struct WriteStats {
+    pub markets: usize,
+    pub files: usize,
+    pub bytes: u64,
+    pub batches: usize,
+}
+
+pub struct BatchWriter {
+    store: FileStore,
+    queue: mpsc::Sender<Request>,
+    worker: JoinHandle<Result<WriteStats>>,
+}
+
+impl BatchWriter {
+    /// Spawn the worker; must be called from within a Tokio runtime
+    pub(super) fn start(base_path: PathBuf, options: FileStoreOptions) -> Self {
+        let (queue, requests) = mpsc::channel(options.queue_depth.max(1));
+        let batch_size = options.batch_size.max(1);
+        let fsync = options.fsync;
+        let worker = tokio::task::spawn_blocking(move || run(requests, batch_size, fsync));
+        Self {
+            store: FileStore { base_path, options },
+            queue,
+            worker,
+        }
+    }
+
+    /// Render a market and queue its files, waiting only while the queue is full
+    pub async fn store_market(&self, market: &Market) -> Result<()> {
+        let files = self.store.render_market(market)?;
+        self.queue
+            .send(Request::Market(files))
+            .await
+            .map_err(|_| anyhow!("File store writer stopped; finish() reports why"))
+    }
+
+    /// Wait until everything queued so far is written (and synced, per policy)
+    pub async fn flush(&self) -> Result<()> {
+        let (done, written) = oneshot::channel();
+        self.queue
+            .send(Request::Flush(done))
+            .await
+            .map_err(|_| anyhow!("File store writer stopped; finish() reports why"))?;
+        written
+            .await
+            .map_err(|_| anyhow!("File store writer stopped; finish() reports why"))
+    }
+
+    /// Write whatever is still queued and stop the worker
+    pub async fn finish(self) -> Result<WriteStats> {
+        drop(self.queue);
+        self.worker.await?
+    }
+}
+
+fn run(mut requests: mpsc::Receiver<Request>, batch_size: usize, fsync: FsyncPolicy) -> Result<WriteStats> {
+    let mut stats = WriteStats::default();
+    let mut created_dirs = HashSet::new();
+    let mut batch: Vec<PendingFile> = Vec::with_capacity(batch_size);
+    let mut waiting = Vec::new();
+
+    while let Some(request) = requests.blocking_recv() {
+        // Take what is already queued, up to a batch, before touching the disk
+        let mut next = Some(request);
+        while let Some(request) = next.take() {
+            match request {
+                Request::Market(files) => {
+                    stats.markets += 1;
+                    batch.extend(files);
+                }
+                Request::Flush(done) => waiting.push(done),
+            }
+            if batch.len() >= batch_size {
+                break;
+            }
+            next = requests.try_recv().ok();
+        }
+
+        write_batch(&mut batch, &mut created_dirs, fsync, &mut stats)?;
+        for done in waiting.drain(..) {
+            let _ = done.send(());
+        }
+    }
+
+    Ok(stats)
+}
+
+fn write_batch(
+    batch: &mut Vec<PendingFile>,
+    created_dirs: &mut HashSet<PathBuf>,
+    fsync: FsyncPolicy,
+    stats: &mut WriteStats,
+) -> Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let mut unsynced = Vec::new();
+    for file in batch.drain(..) {
+        write_file(&file, created_dirs, fsync == FsyncPolicy::File)?;
+        stats.files += 1;
+        stats.bytes += file.contents.len() as u64;
+        if fsync == FsyncPolicy::Batch {
+            unsynced.push(file.path);
+        }
+    }
+    // Reopen rather than hold a descriptor per file, so batch size is not
+    // bounded by the process's open-file limit
+    for path in unsynced {
+        fs::File::open(&path)?.sync_all()?;
+    }
+
+    stats.batches += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::file_store::FileLayout;
+
+    fn fixture_markets() -> Vec<Market> {
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("../../../tests/fixtures/rest/markets_page_1.json")).unwrap();
+        page["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| Market::from_value(m.clone()).unwrap())
+            .collect()
+    }
+
+    fn tree(root: &std::path::Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else {
+                    files.push((path.strip_prefix(root).unwrap().to_path_buf(), fs::read(&path).unwrap()));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn batched_writes_match_direct_writes() {
+        let markets = fixture_markets();
+        let direct = tempfile::tempdir().unwrap();
+        let batched = tempfile::tempdir().unwrap();
+
+        let store = FileStore::new(direct.path().to_path_buf()).unwrap();
+        for market in &markets {
+            store.store_market(market).unwrap();
+        }
+
+        let options = FileStoreOptions {
+            batch_size: 3,
+            fsync: FsyncPolicy::Batch,
+            ..FileStoreOptions::default()
+        };
+        let writer = FileStore::with_options(batched.path().to_path_buf(), options)
+            .unwrap()
+            .batch_writer();
+        for market in &markets {
+            writer.store_market(market).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        assert_eq!(tree(batched.path()), tree(direct.path()));
+
+        let stats = writer.finish().await.unwrap();
+        assert_eq!(stats.markets, markets.len());
+        assert_eq!(stats.files, tree(direct.path()).len());
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn consolidated_layout_writes_one_file_per_condition() {
+        let markets = fixture_markets();
+        let dir = tempfile::tempdir().unwrap();
+        let options = FileStoreOptions {
+            layout: FileLayout::Consolidated,
+            ..FileStoreOptions::default()
+        };
+        let writer = FileStore::with_options(dir.path().to_path_buf(), options)
+            .unwrap()
+            .batch_writer();
+        for market in &markets {
+            writer.store_market(market).await.unwrap();
+        }
+        let stats = writer.finish().await.unwrap();
+
+        assert_eq!(stats.files, markets.len());
+        for market in &markets {
+            let path = dir
+                .path()
+                .join("markets/condition")
+                .join(market.condition_id.as_ref().unwrap())
+                .join("market.json");
+            let stored: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+            assert_eq!(stored["market"]["question"], market.question.as_str());
+            assert_eq!(stored["tokens"].as_array().unwrap().len(), market.tokens.len());
+        }
+    }
+}
@@ -14,6 +14,7 @@ use tracing::{debug, info, error, warn};
 use super::types::*;
 use super::cache::GammaCache;
 use super::individual_storage::IndividualMarketStorage;
+use crate::core::http_cache::{self, Endpoint};
 
 /// Comprehensive Gamma API client with intelligent caching
 pub struct GammaClient {
@@ -101,43 +102,43 @@ impl GammaClient {
 
     pub async fn fetch_markets(&self, query: &MarketQuery) -> Result<PaginatedResponse<GammaMarket>> {
         let url = format!("{}/markets", self.gamma_base_url);
-        let params = self.build_market_query_params(query);
+        query.validate()?;
+        let params = query.to_params();
         
         info!("Fetching markets from URL: {}", url);
         debug!("Query parameters: {:?}", params);
         
-        let response = self.client
-            .get(&url)
-            .query(&params)
-            .send()
+        let response = http_cache::global()
+            .get(&self.client, Endpoint::Markets, &url, &params)
             .await
             .context("Failed to fetch markets from Gamma API")?;
 
-        let status = response.status();
-        info!("API response status: {}", status);
+        let status = response.status;
+        info!("API response status: {}{}", status, if response.cached { " (cached)" } else { "" });
 
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            error!("Markets API error response: {}", error_text);
+            error!("Markets API error response: {}", response.body);
             return Err(anyhow::anyhow!(
                 "Markets API returned status {}: {}",
                 status,
-                error_text
+                response.body
             ));
         }
 
-        let response_text = response.text().await
-            .context("Failed to get response text")?;
+        let response_text = response.body;
         
         debug!("Raw API response (first 1000 chars): {}", &response_text.chars().take(1000).collect::<String>());
         
         // IMMEDIATE SAVE: Save raw API response to prevent data loss
+        // (a cached response was saved when it was fetched)
         let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f");
         let raw_response_path = format!("./data/gamma_raw_responses/markets_{}_offset_{}.json", 
                                        timestamp, query.offset.unwrap_or(0));
         
         // Create directory if it doesn't exist
-        if let Err(e) = std::fs::create_dir_all("./data/gamma_raw_responses") {
+        if response.cached {
+            debug!("Skipping raw response save for a cached response");
+        } else if let Err(e) = std::fs::create_dir_all("./data/gamma_raw_responses") {
             warn!("Failed to create raw responses directory: {}", e);
         } else {
             // Save raw response
@@ -174,23 +175,18 @@ impl GammaClient {
 
     /// Fetch a single market by ID
     pub async fn _fetch_market(&self, id: &MarketId) -> Result<Option<GammaMarket>> {
-        let query = MarketQuery {
-            ids: vec![id.clone()],
-            limit: Some(1),
-            ..Default::default()
-        };
+        let query = MarketQuery::builder().id(id.clone()).limit(1).build()?;
         
         let response = self.fetch_markets(&query).await?;
         Ok(response.data.into_iter().next())
     }
 
     /// Fetch markets by condition IDs
-    pub async fn _fetch_markets_by_condition(&self, condition_ids: &[ConditionId]) -> Result<Vec<GammaMarket>> {
-        let query = MarketQuery {
-            condition_ids: condition_ids.to_vec(),
-            limit: Some(500),
-            ..Default::default()
-        };
+    pub async fn fetch_markets_by_condition(&self, condition_ids: &[ConditionId]) -> Result<Vec<GammaMarket>> {
+        let query = MarketQuery::builder()
+            .condition_ids(condition_ids.iter().cloned())
+            .limit(500)
+            .build()?;
         
         let response = self.fetch_markets(&query).await?;
         Ok(response.data)
@@ -207,20 +203,11 @@ impl GammaClient {
         info!("Starting to fetch all markets with batch size {}", batch_size);
         
         loop {
+            // Every filter of the caller's query applies to each page
             let current_query = MarketQuery {
                 limit: Some(batch_size),
                 offset: Some(offset),
-                order: query.order.clone(),
-                ascending: query.ascending,
-                archived: query.archived,
-                active: query.active,
-                closed: query.closed,
-                tags: query.tags.clone(),
-                volume_min: query.volume_min,
-                volume_max: query.volume_max,
-                liquidity_min: query.liquidity_min,
-                liquidity_max: query.liquidity_max,
-                ..Default::default()
+                ..query.clone()
             };
             
             info!("Fetching batch at offset {} with limit {}", offset, batch_size);
@@ -281,23 +268,20 @@ impl GammaClient {
         
         debug!("Fetching events with query: {:?}", query);
         
-        let response = self.client
-            .get(&url)
-            .query(&params)
-            .send()
+        let response = http_cache::global()
+            .get(&self.client, Endpoint::Events, &url, &params)
             .await
             .context("Failed to fetch events from Gamma API")?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(anyhow::anyhow!(
                 "Events API returned status {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
+                response.status,
+                response.body
             ));
         }
 
-        let raw_json = response.text().await
-            .context("Failed to read response text")?;
+        let raw_json = response.body;
         
         debug!("Raw API response (first 1000 chars): {}", &raw_json.chars().take(1000).collect::<String>());
         
@@ -333,23 +317,23 @@ impl GammaClient {
         
         debug!("Fetching trades with query: {:?}", query);
         
-        let response = self.client
-            .get(&url)
-            .query(&params)
-            .send()
-            .await
-            .context("Failed to fetch trades from Data API")?;
+        // A user's own trades must include their latest fills
+        let response = if query.user.is_some() {
+            http_cache::fetch(&self.client, &url, &params).await
+        } else {
+            http_cache::global().get(&self.client, Endpoint::Trades, &url, &params).await
+        }
+        .context("Failed to fetch trades from Data API")?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(anyhow::anyhow!(
                 "Trades API returned status {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
+                response.status,
+                response.body
             ));
         }
 
-        let raw_json = response.text().await
-            .context("Failed to read response text")?;
+        let raw_json = response.body;
         
         debug!("Raw API response (first 1000 chars): {}", &raw_json.chars().take(1000).collect::<String>());
         
@@ -362,6 +346,43 @@ impl GammaClient {
         Ok(PaginatedResponse::new(trades, offset, limit, None))
     }
 
+    /// Fetch account activity, newest first
+    pub async fn fetch_activity(&self, query: &ActivityQuery) -> Result<PaginatedResponse<GammaActivity>> {
+        let url = format!("{}/activity", self.data_base_url);
+        let mut params = vec![("user", query.user.0.clone())];
+        if !query.types.is_empty() {
+            let types: Vec<&str> = query.types.iter().map(|t| t.as_param()).collect();
+            params.push(("type", types.join(",")));
+        }
+        if let Some(limit) = query.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(offset) = query.offset {
+            params.push(("offset", offset.to_string()));
+        }
+
+        debug!("Fetching activity with query: {:?}", query);
+
+        let response = http_cache::fetch(&self.client, &url, &params)
+            .await
+            .context("Failed to fetch activity from Data API")?;
+        if !response.status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Activity API returned status {}: {}",
+                response.status,
+                response.body
+            ));
+        }
+
+        let activity: Vec<GammaActivity> = serde_json::from_str(&response.body)
+            .context("Failed to parse activity from JSON")?;
+
+        let offset = query.offset.unwrap_or(0);
+        let limit = query.limit.unwrap_or(100);
+
+        Ok(PaginatedResponse::new(activity, offset, limit, None))
+    }
+
     /// Fetch trades for a specific market
     pub async fn _fetch_market_trades(&self, condition_id: &ConditionId, limit: Option<u32>) -> Result<Vec<GammaTrade>> {
         let query = TradeQuery {
@@ -444,28 +465,26 @@ impl GammaClient {
     // ============================================================================
 
     /// Fetch price history for a token
-    pub async fn _fetch_price_history(&self, query: &PriceHistoryQuery) -> Result<PriceHistory> {
+    pub async fn fetch_price_history(&self, query: &PriceHistoryQuery) -> Result<PriceHistory> {
         let url = format!("{}/prices-history", self._clob_base_url);
         let params = self._build_price_history_params(query);
         
         debug!("Fetching price history with query: {:?}", query);
         
-        let response = self.client
-            .get(&url)
-            .query(&params)
-            .send()
+        let response = http_cache::global()
+            .get(&self.client, Endpoint::PriceHistory, &url, &params)
             .await
             .context("Failed to fetch price history from CLOB API")?;
 
-        if !response.status().is_success() {
+        if !response.status.is_success() {
             return Err(anyhow::anyhow!(
                 "Price history API returned status {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
+                response.status,
+                response.body
             ));
         }
 
-        let history_json: Value = response.json().await
+        let history_json: Value = serde_json::from_str(&response.body)
             .context("Failed to parse price history response as JSON")?;
 
         self._parse_price_history(history_json, query.market.clone())
@@ -475,78 +494,6 @@ impl GammaClient {
     // HELPER METHODS
     // ============================================================================
 
-    fn build_market_query_params(&self, query: &MarketQuery) -> Vec<(&str, String)> {
-        let mut params = Vec::new();
-        
-        if let Some(limit) = query.limit {
-            params.push(("limit", limit.to_string()));
-        }
-        if let Some(offset) = query.offset {
-            params.push(("offset", offset.to_string()));
-        }
-        if let Some(ref order) = query.order {
-            params.push(("order", order.clone()));
-        }
-        if let Some(ascending) = query.ascending {
-            params.push(("ascending", ascending.to_string()));
-        }
-        if let Some(archived) = query.archived {
-            params.push(("archived", archived.to_string()));
-        }
-        if let Some(active) = query.active {
-            params.push(("active", active.to_string()));
-        }
-        if let Some(closed) = query.closed {
-            params.push(("closed", closed.to_string()));
-        }
-        
-        // Add vector parameters
-        for id in &query.ids {
-            params.push(("id", id.0.to_string()));
-        }
-        for slug in &query.slugs {
-            params.push(("slug", slug.0.clone()));
-        }
-        
-        // Add numeric ranges
-        if let Some(min) = query.liquidity_min {
-            params.push(("liquidity_num_min", min.to_string()));
-        }
-        if let Some(max) = query.liquidity_max {
-            params.push(("liquidity_num_max", max.to_string()));
-        }
-        if let Some(min) = query.volume_min {
-            params.push(("volume_num_min", min.to_string()));
-        }
-        if let Some(max) = query.volume_max {
-            params.push(("volume_num_max", max.to_string()));
-        }
-        
-        // Add date ranges
-        if let Some(min) = query.start_date_min {
-            params.push(("start_date_min", min.to_rfc3339()));
-        }
-        if let Some(max) = query.start_date_max {
-            params.push(("start_date_max", max.to_rfc3339()));
-        }
-        if let Some(min) = query.end_date_min {
-            params.push(("end_date_min", min.to_rfc3339()));
-        }
-        if let Some(max) = query.end_date_max {
-            params.push(("end_date_max", max.to_rfc3339()));
-        }
-        
-        // Add tag filters
-        for tag in &query.tags {
-            params.push(("tag", tag.clone()));
-        }
-        for tag_id in &query.tag_ids {
-            params.push(("tag_id", tag_id.0.to_string()));
-        }
-        
-        params
-    }
-
     fn build_event_query_params(&self, query: &EventQuery) -> Vec<(&str, String)> {
         let mut params = Vec::new();
         
@@ -662,8 +609,8 @@ impl GammaClient {
         if let Some(offset) = query.offset {
             params.push(("offset", offset.to_string()));
         }
-        if let Some(ref sort_by) = query.sort_by {
-            params.push(("sortBy", sort_by.clone()));
+        if let Some(sort_by) = query.sort_by {
+            params.push(("sortBy", sort_by.as_param().to_string()));
         }
         if let Some(ref sort_direction) = query.sort_direction {
             params.push(("sortDirection", sort_direction.clone()));
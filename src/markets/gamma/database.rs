@@ -3,23 +3,29 @@
 //! This module provides persistent, deduplicated storage for gamma markets
 //! using SurrealDB with RocksDB backend for high performance and reliability.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use async_trait::async_trait;
+use serde::Deserialize;
 use surrealdb::engine::local::{Db, RocksDb};
 use surrealdb::Surreal;
 use tracing::{info, debug, error};
 
-use crate::markets::gamma::types::{GammaMarket, MarketId, ConditionId, ClobTokenId};
+use crate::markets::gamma::types::{GammaMarket, MarketEventInfo, MarketId, ConditionId, ClobTokenId};
+use crate::markets::gamma::store::{
+    market_to_record, record_to_market, GammaBackend, GammaStore, MarketListQuery, SessionEvent,
+    SessionMarket,
+};
+pub use crate::markets::gamma::store::{DatabaseHealth, MarketRecord, MarketStats, UpsertCounts};
 
 /// Database connection and operations for gamma markets with caching
 pub struct GammaDatabase {
     db: Surreal<Db>,
-    _path: String,
+    path: PathBuf,
     // Query result cache for improved performance
     query_cache: Arc<RwLock<HashMap<String, (DateTime<Utc>, serde_json::Value)>>>,
     // Statistics cache
@@ -28,6 +34,13 @@ pub struct GammaDatabase {
     cache_ttl_minutes: i64,
 }
 
+/// A row for `upsert_latest`, written only if no newer copy is stored
+struct VersionedRow {
+    id: String,
+    updated_at: Option<DateTime<Utc>>,
+    record: serde_json::Value,
+}
+
 use surrealdb::sql::Thing;
 
 /// Helper type for deserializing SurrealDB records that include Thing IDs
@@ -128,64 +141,6 @@ impl MarketRecordRaw {
     }
 }
 
-/// Lightweight market record for database storage
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarketRecord {
-    pub market_id: String, // The actual market ID from GammaMarket
-    pub condition_id: String,
-    pub slug: String,
-    pub question: String,
-    pub description: Option<String>,
-    pub outcomes: Vec<String>,
-    pub outcome_prices: Option<Vec<String>>,
-    pub clob_token_ids: Vec<String>,
-    pub category: Option<String>,
-    pub end_date: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
-    pub closed_time: Option<String>,
-    pub image: Option<String>,
-    pub icon: Option<String>,
-    pub volume: Option<String>,
-    pub liquidity: Option<String>,
-    pub active: bool,
-    pub closed: bool,
-    pub archived: bool,
-    pub restricted: bool,
-    pub cyom: bool,
-    pub approved: bool,
-    pub volume_24hr: Option<String>,
-    pub volume_1wk: Option<String>,
-    pub volume_1mo: Option<String>,
-    pub volume_1yr: Option<String>,
-    pub best_bid: Option<String>,
-    pub best_ask: Option<String>,
-    pub last_trade_price: Option<String>,
-    pub accepting_orders: bool,
-    pub enable_order_book: bool,
-    pub featured: bool,
-    pub new: bool,
-    pub neg_risk: bool,
-    pub spread: Option<String>,
-    pub start_date: Option<String>,
-    // Database metadata
-    pub first_seen: String,
-    pub last_updated_db: String,
-    pub source_session: u32,
-}
-
-/// Market statistics for analytics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarketStats {
-    pub total_markets: u64,
-    pub active_markets: u64,
-    pub closed_markets: u64,
-    pub archived_markets: u64,
-    pub total_volume: String,
-    pub total_liquidity: String,
-    pub last_updated: String,
-}
-
 impl GammaDatabase {
     /// Create new database connection
     pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
@@ -204,7 +159,7 @@ impl GammaDatabase {
         
         let database = Self {
             db,
-            _path: path_str,
+            path: db_path.as_ref().to_path_buf(),
             query_cache: Arc::new(RwLock::new(HashMap::new())),
             stats_cache: Arc::new(RwLock::new(None)),
             cache_ttl_minutes: 5, // 5 minute cache TTL
@@ -225,6 +180,11 @@ impl GammaDatabase {
         let schema_queries = vec![
             // Markets table - schemaless for flexibility
             "DEFINE TABLE markets SCHEMALESS;",
+            "DEFINE INDEX IF NOT EXISTS market_id_idx ON TABLE markets COLUMNS market_id;",
+
+            // Events embedded in markets, stored once per event id
+            "DEFINE TABLE events SCHEMALESS;",
+            "DEFINE INDEX IF NOT EXISTS event_id_idx ON TABLE events COLUMNS event_id;",
             
             // Stats table for analytics
             "DEFINE TABLE stats SCHEMAFULL;",
@@ -248,97 +208,137 @@ impl GammaDatabase {
     
     /// Store markets with deduplication
     pub async fn store_markets(&self, markets: &[GammaMarket], session_id: u32) -> Result<u64> {
-        let now = Utc::now();
-        let mut stored_count = 0;
-        
         info!("Storing {} markets from session {}", markets.len(), session_id);
-        
-        // Store each market individually
-        for market in markets {
-            let market_record = self.convert_market_to_record(market, session_id, now);
-            let market_id = market.id.0.to_string();
-            
-            // Convert to JSON
-            let json_value = serde_json::to_value(&market_record)?;
-            
-            // Use CREATE with the JSON content
-            let query = format!(
-                "CREATE markets CONTENT {}",
-                serde_json::to_string(&json_value)?
-            );
-            
-            let result = self.db.query(&query).await;
-            
-            if let Err(e) = &result {
-                if stored_count < 3 {
-                    error!("Failed to create market {}: {}", market_id, e);
+        let markets: Vec<SessionMarket> = markets
+            .iter()
+            .map(|market| SessionMarket { session_id, market })
+            .collect();
+        let counts = self.upsert_markets(&markets).await?;
+        info!(
+            "Stored {} markets from session {} ({} older than the stored copy)",
+            counts.stored, session_id, counts.skipped
+        );
+        Ok(counts.stored)
+    }
+
+    /// Store markets keyed by market id, each tagged with the session it came
+    /// from. A market already stored with a later `updated_at` is kept, and any
+    /// other rows for the same id (from imports before records were keyed) are
+    /// replaced.
+    pub async fn upsert_markets(&self, markets: &[SessionMarket<'_>]) -> Result<UpsertCounts> {
+        let now = Utc::now();
+        let mut rows = Vec::with_capacity(markets.len());
+        for entry in markets {
+            let record = self.convert_market_to_record(entry.market, entry.session_id, now);
+            let mut value = serde_json::to_value(&record)?;
+            value["id"] = serde_json::json!(record.market_id);
+            rows.push(VersionedRow {
+                id: record.market_id.clone(),
+                updated_at: Some(entry.market.updated_at),
+                record: value,
+            });
+        }
+        self.upsert_latest("markets", "market_id", rows).await
+    }
+
+    /// Store events keyed by event id, last `updated_at` wins as for markets
+    pub async fn upsert_events(&self, events: &[SessionEvent<'_>]) -> Result<UpsertCounts> {
+        let now = Utc::now().to_rfc3339();
+        let mut rows = Vec::with_capacity(events.len());
+        for SessionEvent { session_id, event } in events {
+            let event_id = event.id.0.to_string();
+            let record = serde_json::json!({
+                "id": event_id,
+                "event_id": event_id,
+                "slug": event.slug,
+                "title": event.title,
+                "closed": event.closed,
+                "updated_at": event.updated_at.map(|d| d.to_rfc3339()),
+                "data": event,
+                "last_updated_db": now,
+                "source_session": session_id,
+            });
+            rows.push(VersionedRow {
+                id: event_id,
+                updated_at: event.updated_at,
+                record,
+            });
+        }
+        self.upsert_latest("events", "event_id", rows).await
+    }
+
+    /// Write `rows` to `table` unless the stored row of the same id is newer,
+    /// in one transaction per call
+    async fn upsert_latest(
+        &self,
+        table: &'static str,
+        id_field: &'static str,
+        rows: Vec<VersionedRow>,
+    ) -> Result<UpsertCounts> {
+        if rows.is_empty() {
+            return Ok(UpsertCounts::default());
+        }
+        let ids: Vec<String> = rows.iter().map(|row| row.id.clone()).collect();
+        let mut response = self
+            .db
+            .query(format!(
+                "SELECT {id_field} AS key, updated_at FROM {table} WHERE {id_field} IN $ids"
+            ))
+            .bind(("ids", ids))
+            .await
+            .with_context(|| format!("Failed to read stored {}", table))?;
+        let existing: Vec<serde_json::Value> = response.take(0)?;
+        let mut stored_versions: HashMap<String, Option<DateTime<Utc>>> = HashMap::new();
+        for row in existing {
+            let Some(id) = row.get("key").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let updated_at = row
+                .get("updated_at")
+                .and_then(|v| v.as_str())
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|d| d.with_timezone(&Utc));
+            let version = stored_versions.entry(id.to_string()).or_insert(updated_at);
+            *version = (*version).max(updated_at);
+        }
+
+        let mut counts = UpsertCounts::default();
+        let mut records = Vec::with_capacity(rows.len());
+        let mut write_ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            match stored_versions.get(&row.id) {
+                Some(stored) if *stored > row.updated_at => counts.skipped += 1,
+                _ => {
+                    write_ids.push(row.id);
+                    records.push(row.record);
                 }
-            } else {
-                stored_count += 1;
-            }
-            
-            if stored_count > 0 && stored_count % 1000 == 0 {
-                debug!("Stored {} markets so far", stored_count);
             }
         }
-        
-        info!("Stored {} markets from session {}", stored_count, session_id);
-        
-        // Quick verification - try to count markets
-        let verify_count = self.get_market_count().await?;
-        info!("Verification: Database now contains {} markets", verify_count);
-        
-        // Skip update_stats to avoid hanging on large datasets
-        info!("Skipping statistics update to avoid performance issues with large dataset");
-        
-        Ok(stored_count)
+        if records.is_empty() {
+            return Ok(counts);
+        }
+        counts.stored = records.len() as u64;
+
+        self.db
+            .query(format!(
+                "BEGIN TRANSACTION; \
+                 DELETE {table} WHERE {id_field} IN $ids; \
+                 INSERT INTO {table} $records; \
+                 COMMIT TRANSACTION;"
+            ))
+            .bind(("ids", write_ids))
+            .bind(("records", records))
+            .await
+            .with_context(|| format!("Failed to write {}", table))?
+            .check()
+            .with_context(|| format!("Failed to write {}", table))?;
+        self.clear_cache()?;
+        Ok(counts)
     }
-    
-    
+
     /// Convert GammaMarket to MarketRecord
     fn convert_market_to_record(&self, market: &GammaMarket, session_id: u32, now: DateTime<Utc>) -> MarketRecord {
-        MarketRecord {
-            market_id: market.id.0.to_string(),
-            condition_id: market.condition_id.0.clone(),
-            slug: market.slug.clone(),
-            question: market.question.clone(),
-            description: market.description.clone(),
-            outcomes: market.outcomes.clone(),
-            outcome_prices: market.outcome_prices.as_ref().map(|prices| prices.iter().map(|d| d.to_string()).collect()),
-            clob_token_ids: market.clob_token_ids.iter().map(|id| id.0.clone()).collect(),
-            category: market.category.clone(),
-            end_date: market.end_date.map(|d| d.to_rfc3339()),
-            created_at: market.created_at.to_rfc3339(),
-            updated_at: market.updated_at.to_rfc3339(),
-            closed_time: market.closed_time.map(|d| d.to_rfc3339()),
-            image: market.image.clone(),
-            icon: market.icon.clone(),
-            volume: Some(market.volume().to_string()),
-            liquidity: market.liquidity.map(|d| d.to_string()),
-            active: market.active,
-            closed: market.closed,
-            archived: market.archived,
-            restricted: market.restricted,
-            cyom: market.cyom,
-            approved: market.approved,
-            volume_24hr: market.volume_24hr.map(|d| d.to_string()),
-            volume_1wk: market.volume_1wk.map(|d| d.to_string()),
-            volume_1mo: market.volume_1mo.map(|d| d.to_string()),
-            volume_1yr: market.volume_1yr.map(|d| d.to_string()),
-            best_bid: market.best_bid.map(|d| d.to_string()),
-            best_ask: market.best_ask.map(|d| d.to_string()),
-            last_trade_price: market.last_trade_price.map(|d| d.to_string()),
-            accepting_orders: market.accepting_orders,
-            enable_order_book: market.enable_order_book,
-            featured: market.featured,
-            new: market.new,
-            neg_risk: market.neg_risk,
-            spread: market.spread.map(|d| d.to_string()),
-            start_date: market.start_date.map(|d| d.to_rfc3339()),
-            first_seen: now.to_rfc3339(),
-            last_updated_db: now.to_rfc3339(),
-            source_session: session_id,
-        }
+        market_to_record(market, session_id, now)
     }
     
     /// Get all markets with optional filtering using batching
@@ -377,6 +377,46 @@ impl GammaDatabase {
         Ok(markets)
     }
     
+    /// Get a market by its id
+    pub async fn get_market(&self, market_id: &str) -> Result<Option<GammaMarket>> {
+        let query = format!("SELECT * FROM markets WHERE market_id = '{}' LIMIT 1", market_id.replace('\'', ""));
+        Ok(self.execute_query(&query).await?.into_iter().next())
+    }
+
+    /// List markets matching `query`, filtered, ordered and paged in SurrealQL
+    pub async fn list_markets(&self, query: &MarketListQuery) -> Result<Vec<GammaMarket>> {
+        let mut conditions = Vec::new();
+        if query.active_only {
+            conditions.push("active = true".to_string());
+        }
+        if query.closed_only {
+            conditions.push("closed = true".to_string());
+        }
+        if let Some(min_volume) = query.min_volume {
+            conditions.push(format!("volume >= {}", min_volume));
+        }
+        if let Some(category) = &query.category {
+            conditions.push(format!("category = '{}'", category.replace('\'', "")));
+        }
+
+        let mut sql = "SELECT * FROM markets".to_string();
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        if let Some(sort_by) = query.sort_by {
+            let direction = if query.descending { "DESC" } else { "ASC" };
+            sql.push_str(&format!(" ORDER BY {} {}", sort_by.field(), direction));
+        }
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        if query.offset > 0 {
+            sql.push_str(&format!(" START {}", query.offset));
+        }
+        debug!("Listing markets: {}", sql);
+        self.execute_query(&sql).await
+    }
+
     /// Get markets by status
     
     /// Get database statistics with enhanced caching
@@ -907,133 +947,7 @@ impl GammaDatabase {
     
     /// Convert MarketRecord back to GammaMarket
     fn convert_record_to_gamma_market(&self, record: MarketRecord) -> Result<GammaMarket> {
-        // Parse market_id as u64
-        let market_id = record.market_id.parse::<u64>()
-            .context("Failed to parse market_id as u64")?;
-            
-        Ok(GammaMarket {
-            id: MarketId(market_id),
-            condition_id: ConditionId(record.condition_id),
-            slug: record.slug,
-            question: record.question,
-            description: record.description,
-            outcomes: record.outcomes,
-            outcome_prices: record.outcome_prices.map(|prices| {
-                prices.into_iter()
-                    .filter_map(|p| p.parse::<Decimal>().ok())
-                    .collect()
-            }),
-            clob_token_ids: record.clob_token_ids.into_iter()
-                .map(ClobTokenId)
-                .collect(),
-            category: record.category,
-            end_date: record.end_date.clone().and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
-                .map(|d| d.with_timezone(&Utc)),
-            created_at: DateTime::parse_from_rfc3339(&record.created_at)
-                .map(|d| d.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            updated_at: DateTime::parse_from_rfc3339(&record.updated_at)
-                .map(|d| d.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            closed_time: record.closed_time.and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
-                .map(|d| d.with_timezone(&Utc)),
-            image: record.image,
-            icon: record.icon,
-            twitter_card_image: None,
-            market_maker_address: None,
-            volume_num: record.volume.and_then(|v| v.parse::<Decimal>().ok()),
-            volume_alt: None,
-            liquidity: record.liquidity.and_then(|l| l.parse::<Decimal>().ok()),
-            active: record.active,
-            closed: record.closed,
-            archived: record.archived,
-            restricted: record.restricted,
-            cyom: record.cyom,
-            approved: record.approved,
-            volume_24hr: record.volume_24hr.and_then(|v| v.parse::<Decimal>().ok()),
-            volume_1wk: record.volume_1wk.and_then(|v| v.parse::<Decimal>().ok()),
-            volume_1mo: record.volume_1mo.and_then(|v| v.parse::<Decimal>().ok()),
-            volume_1yr: record.volume_1yr.and_then(|v| v.parse::<Decimal>().ok()),
-            best_bid: record.best_bid.and_then(|v| v.parse::<Decimal>().ok()),
-            best_ask: record.best_ask.and_then(|v| v.parse::<Decimal>().ok()),
-            last_trade_price: record.last_trade_price.and_then(|v| v.parse::<Decimal>().ok()),
-            accepting_orders: record.accepting_orders,
-            accepting_orders_timestamp: None,
-            automatically_resolved: None,
-            automatically_active: None,
-            clear_book_on_start: None,
-            clob_rewards: Vec::new(),
-            deploying: None,
-            enable_order_book: record.enable_order_book,
-            end_date_iso: record.end_date,
-            events: None,
-            featured: record.featured,
-            funded: None,
-            group_item_threshold: None,
-            group_item_title: None,
-            has_reviewed_dates: None,
-            manual_activation: None,
-            neg_risk: record.neg_risk,
-            neg_risk_other: None,
-            new: record.new,
-            one_day_price_change: None,
-            one_hour_price_change: None,
-            one_month_price_change: None,
-            one_week_price_change: None,
-            one_year_price_change: None,
-            order_min_size: None,
-            order_price_min_tick_size: None,
-            pager_duty_notification_enabled: None,
-            pending_deployment: None,
-            question_id: None,
-            ready: None,
-            resolution_source: None,
-            resolved_by: None,
-            rewards_max_spread: None,
-            rewards_min_size: None,
-            rfq_enabled: None,
-            spread: record.spread.and_then(|v| v.parse::<Decimal>().ok()),
-            start_date: record.start_date.and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
-                .map(|d| d.with_timezone(&Utc)),
-            start_date_iso: None,
-            submitted_by: None,
-            uma_bond: None,
-            uma_end_date: None,
-            uma_resolution_status: None,
-            uma_resolution_statuses: None,
-            uma_reward: None,
-            volume_1mo_amm: None,
-            volume_1mo_clob: None,
-            volume_1wk_amm: None,
-            volume_1wk_clob: None,
-            volume_1yr_amm: None,
-            volume_1yr_clob: None,
-            volume_clob: None,
-            competitive: None,
-            deploying_timestamp: None,
-            neg_risk_market_id: None,
-            neg_risk_request_id: None,
-            series_color: None,
-            show_gmp_outcome: None,
-            show_gmp_series: None,
-            mailchimp_tag: None,
-            market_type: None,
-            ready_for_cron: None,
-            updated_by: None,
-            creator: None,
-            wide_format: None,
-            game_start_time: None,
-            seconds_delay: None,
-            sent_discord: None,
-            notifications_enabled: None,
-            fee: None,
-            fpmm_live: None,
-            volume_24hr_clob: None,
-            volume_amm: None,
-            liquidity_amm: None,
-            comments_enabled: None,
-            ticker: None,
-        })
+        record_to_market(record)
     }
     
     /// Perform a quick health check on the database
@@ -1119,15 +1033,63 @@ impl GammaDatabase {
     }
 }
 
-/// Database health information
-#[derive(Debug, Clone)]
-pub struct DatabaseHealth {
-    pub is_healthy: bool,
-    pub ping_time_ms: u64,
-    pub market_count: Option<u64>,
-    pub count_time_ms: Option<u64>,
-    pub issues: Vec<String>,
-    pub recommendations: Vec<String>,
+#[async_trait]
+impl GammaStore for GammaDatabase {
+    fn backend(&self) -> GammaBackend {
+        GammaBackend::Surrealdb
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn upsert_markets(&self, markets: &[SessionMarket<'_>]) -> Result<UpsertCounts> {
+        GammaDatabase::upsert_markets(self, markets).await
+    }
+
+    async fn upsert_events(&self, events: &[SessionEvent<'_>]) -> Result<UpsertCounts> {
+        GammaDatabase::upsert_events(self, events).await
+    }
+
+    async fn store_markets(&self, markets: &[GammaMarket], session_id: u32) -> Result<u64> {
+        GammaDatabase::store_markets(self, markets, session_id).await
+    }
+
+    async fn get_market(&self, market_id: &str) -> Result<Option<GammaMarket>> {
+        GammaDatabase::get_market(self, market_id).await
+    }
+
+    async fn list_markets(&self, query: &MarketListQuery) -> Result<Vec<GammaMarket>> {
+        GammaDatabase::list_markets(self, query).await
+    }
+
+    async fn get_all_markets(&self, limit: Option<u64>) -> Result<Vec<GammaMarket>> {
+        GammaDatabase::get_all_markets(self, limit).await
+    }
+
+    async fn get_all_markets_with_progress(&self, progress: Option<&indicatif::ProgressBar>) -> Result<Vec<GammaMarket>> {
+        GammaDatabase::get_all_markets_with_progress(self, progress).await
+    }
+
+    async fn get_market_count(&self) -> Result<u64> {
+        GammaDatabase::get_market_count(self).await
+    }
+
+    async fn get_stats(&self) -> Result<MarketStats> {
+        GammaDatabase::get_stats(self).await
+    }
+
+    async fn search_markets(&self, query: &str, limit: Option<u64>) -> Result<Vec<GammaMarket>> {
+        GammaDatabase::search_markets(self, query, limit).await
+    }
+
+    async fn health_check(&self) -> Result<DatabaseHealth> {
+        GammaDatabase::health_check(self).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        GammaDatabase::close(self).await
+    }
 }
 
 #[cfg(test)]
@@ -20,7 +20,10 @@ use rayon::prelude::*;
 use dashmap::DashMap;
 
 use super::types::GammaMarket;
-use super::database::GammaDatabase;
+use super::store::{open_store, GammaStore};
+
+/// File name of the fast search index inside the search index directory
+pub const FAST_SEARCH_INDEX_FILE: &str = "fast_search.bin";
 
 /// Document ID type for internal use
 type DocId = u32;
@@ -380,19 +383,19 @@ impl FastSearchEngine {
     }
 }
 
-/// Build and save fast search index from database
-pub async fn build_fast_search_index(db_path: &Path, index_path: &Path, force_rebuild: bool) -> Result<FastSearchEngine> {
+/// Build and save fast search index from the gamma database under `data_root`
+pub async fn build_fast_search_index(data_root: &Path, index_path: &Path, force_rebuild: bool) -> Result<FastSearchEngine> {
     info!("Building fast search index from database");
     
     // Check if index already exists
-    let index_file = index_path.join("fast_search.bin");
+    let index_file = index_path.join(FAST_SEARCH_INDEX_FILE);
     if !force_rebuild && index_file.exists() {
         info!("Fast search index already exists at {:?}", index_file);
         // For now, we rebuild anyway since we don't have serialization yet
     }
     
     // Load all markets from database
-    let database = GammaDatabase::new(db_path).await
+    let database = open_store(data_root).await
         .context("Failed to initialize database")?;
     
     let total_count = database.get_market_count().await?;
@@ -417,11 +420,11 @@ pub async fn build_fast_search_index(db_path: &Path, index_path: &Path, force_re
 
 /// Build fast search index using an existing database connection
 #[allow(dead_code)] // API kept for future use
-pub async fn build_fast_search_index_with_db(database: &GammaDatabase, index_path: &Path, force_rebuild: bool) -> Result<FastSearchEngine> {
+pub async fn build_fast_search_index_with_db(database: &dyn GammaStore, index_path: &Path, force_rebuild: bool) -> Result<FastSearchEngine> {
     info!("Building fast search index using existing database connection");
     
     // Check if index already exists
-    let index_file = index_path.join("fast_search.bin");
+    let index_file = index_path.join(FAST_SEARCH_INDEX_FILE);
     if !force_rebuild && index_file.exists() {
         info!("Fast search index already exists at {:?}", index_file);
         // For now, we rebuild anyway since we don't have serialization yet
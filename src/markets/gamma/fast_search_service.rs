@@ -15,7 +15,7 @@ use tracing::{info, error};
 use serde::{Serialize, Deserialize};
 
 use super::fast_search::{FastSearchEngine, SearchParams};
-use super::database::GammaDatabase;
+use super::store::{open_store, MarketListQuery};
 use super::types::GammaMarket;
 
 /// Status of the search service
@@ -72,15 +72,15 @@ pub struct FastSearchService {
     status_rx: watch::Receiver<ServiceStatus>,
     /// Build task handle
     build_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
-    /// Database path
-    db_path: PathBuf,
+    /// Data directory holding the gamma database
+    data_root: PathBuf,
     /// Index path
     index_path: PathBuf,
 }
 
 impl FastSearchService {
     /// Create a new search service
-    pub fn new(db_path: PathBuf, index_path: PathBuf) -> Self {
+    pub fn new(data_root: PathBuf, index_path: PathBuf) -> Self {
         let (status_tx, status_rx) = watch::channel(ServiceStatus::NotStarted);
         
         Self {
@@ -95,7 +95,7 @@ impl FastSearchService {
             status_tx,
             status_rx,
             build_handle: Arc::new(Mutex::new(None)),
-            db_path,
+            data_root,
             index_path,
         }
     }
@@ -117,7 +117,7 @@ impl FastSearchService {
         let status = self.status.clone();
         let progress = self.progress.clone();
         let status_tx = self.status_tx.clone();
-        let db_path = self.db_path.clone();
+        let data_root = self.data_root.clone();
         let index_path = self.index_path.clone();
         
         // Spawn background build task
@@ -127,7 +127,7 @@ impl FastSearchService {
                 status,
                 progress,
                 status_tx,
-                db_path,
+                data_root,
                 index_path,
             ).await {
                 Ok(()) => info!("Fast search engine built successfully"),
@@ -145,13 +145,13 @@ impl FastSearchService {
         status: Arc<RwLock<ServiceStatus>>,
         progress: Arc<RwLock<ServiceProgress>>,
         status_tx: watch::Sender<ServiceStatus>,
-        db_path: PathBuf,
+        data_root: PathBuf,
         _index_path: PathBuf,
     ) -> Result<()> {
         let build_start = std::time::Instant::now();
         
         // Initialize database
-        let database = match GammaDatabase::new(&db_path).await {
+        let database = match open_store(&data_root).await {
             Ok(db) => db,
             Err(e) => {
                 let error_status = ServiceStatus::Failed {
@@ -185,7 +185,12 @@ impl FastSearchService {
         let mut offset = 0;
         
         while offset < total_count {
-            match database.get_all_markets(Some(batch_size as u64)).await {
+            let page = MarketListQuery {
+                limit: Some(batch_size as u64),
+                offset: offset as u64,
+                ..Default::default()
+            };
+            match database.list_markets(&page).await {
                 Ok(batch) => {
                     let batch_len = batch.len();
                     all_markets.extend(batch);
@@ -363,9 +368,9 @@ impl FastSearchService {
 static SEARCH_SERVICE: tokio::sync::OnceCell<Arc<FastSearchService>> = tokio::sync::OnceCell::const_new();
 
 /// Initialize the global search service
-pub async fn init_search_service(db_path: PathBuf, index_path: PathBuf) -> Result<Arc<FastSearchService>> {
+pub async fn init_search_service(data_root: PathBuf, index_path: PathBuf) -> Result<Arc<FastSearchService>> {
     let service = SEARCH_SERVICE.get_or_init(|| async {
-        Arc::new(FastSearchService::new(db_path, index_path))
+        Arc::new(FastSearchService::new(data_root, index_path))
     }).await;
     
     Ok(service.clone())
@@ -7,24 +7,32 @@
 //! 
 //! - **Client**: HTTP client with built-in caching and rate limiting
 //! - **Types**: Strongly-typed domain models for all API entities
-//! - **Database**: SurrealDB storage with RocksDB backend for persistence
+//! - **Query**: Validated builder for market queries, shared by the client and scripts
+//! - **Store**: `GammaStore` trait over the local market database, backed by
+//!   SurrealDB (**Database**, `surrealdb` feature) or typed RocksDB column
+//!   families (**Typed Backend**), chosen in `gamma.yaml`
 //! - **Session Manager**: Session-based data fetching and storage
+//! - **Session Import**: Concurrent, deduplicating import of sessions into the database
 //! - **TUI**: Interactive terminal interface for data exploration
 //! - **Analytics**: Real-time market analytics and statistics
 //! 
 //! ## Usage
 //! 
 //! ```rust
-//! use polybot::gamma::{GammaClient, MarketQuery};
+//! use polybot::gamma::{GammaClient, MarketOrder, MarketQuery};
 //! 
 //! let client = GammaClient::new(None)?;
-//! let query = MarketQuery::default().with_limit(100);
+//! let query = MarketQuery::builder()
+//!     .active(true)
+//!     .order(MarketOrder::Volume)
+//!     .limit(100)
+//!     .build()?;
 //! let markets = client.fetch_markets(&query).await?;
 //! ```
 //! 
 //! ## Database Import
 //! 
-//! Import session data into SurrealDB:
+//! Import session data into the local database:
 //! ```bash
 //! # Import single session
 //! cargo run -- gamma import-session --session-id 1
@@ -35,26 +43,42 @@
 //! # Import all sessions
 //! cargo run -- gamma import-session --session-id all
 //! ```
+//!
+//! Sessions are read concurrently and merged by market/event id, the latest
+//! `updated_at` winning (see `session_import`).
 
 pub mod types;
+pub mod query;
 pub mod client;
 pub mod storage;
+pub mod store;
+pub mod typed_backend;
+#[cfg(feature = "surrealdb")]
 pub mod database;
+#[cfg(feature = "tui")]
 pub mod tui;
 pub mod search;
 pub mod cache;
+pub mod resolution;
 pub mod individual_storage;
 pub mod session;
+pub mod session_import;
 pub mod search_index;
 pub mod fast_search;
 pub mod fast_search_service;
 pub mod index_service;
 
 pub use types::*;
+pub use query::{MarketOrder, MarketQueryBuilder, MarketQueryError};
 pub use client::GammaClient;
 pub use storage::GammaStorage;
+pub use store::{
+    open_store, GammaBackend, GammaStore, GammaStoreConfig, MarketListOrder, MarketListQuery,
+};
+#[cfg(feature = "surrealdb")]
 pub use database::GammaDatabase;
 pub use search::{GammaSearchEngine, MarketAnalytics};
+#[cfg(feature = "tui")]
 pub use tui::GammaTui;
 #[allow(unused_imports)]
 pub use cache::{GammaCache, Cursor, CacheStats, MarketFilter};
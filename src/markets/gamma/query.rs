@@ -0,0 +1,517 @@
+//! Typed builder for Gamma `/markets` queries
+//!
+//! [`MarketQuery`] mirrors the filter parameters of the Gamma markets endpoint.
+//! Build it with [`MarketQuery::builder`] rather than a struct literal: the
+//! builder takes typed ids, [`MarketOrder`] instead of a free-form sort field,
+//! and [`MarketQueryBuilder::build`] rejects combinations the API would answer
+//! with an empty page or an error (inverted ranges, negative bounds, related
+//! tags without a tag id) before a request is sent.
+//!
+//! ```rust,ignore
+//! let query = MarketQuery::builder()
+//!     .active(true)
+//!     .closed(false)
+//!     .tag_id(TagId(2))
+//!     .related_tags(true)
+//!     .liquidity_min(dec!(10000))
+//!     .order(MarketOrder::Volume24hr)
+//!     .ascending(false)
+//!     .limit(100)
+//!     .build()?;
+//! let markets = client.fetch_markets(&query).await?;
+//! ```
+//!
+//! Scripts that talk to the endpoint with plain `reqwest` use
+//! [`MarketQuery::to_params`] so both paths send the same parameters.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use super::types::{
+    ClobTokenId, ConditionId, EventId, GammaMarket, MarketId, MarketQuery, MarketSlug, TagId,
+    TagSlug,
+};
+
+/// The Gamma markets endpoint
+pub const GAMMA_MARKETS_URL: &str = "https://gamma-api.polymarket.com/markets";
+
+/// A query the Gamma API would reject or answer with nothing
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MarketQueryError {
+    #[error("limit must be at least 1")]
+    ZeroLimit,
+    #[error("{field} must not be negative (got {value})")]
+    NegativeBound { field: &'static str, value: Decimal },
+    #[error("{field}: minimum {min} is above maximum {max}")]
+    InvertedRange {
+        field: &'static str,
+        min: String,
+        max: String,
+    },
+    #[error("related_tags needs at least one tag_id")]
+    RelatedTagsWithoutTagId,
+}
+
+/// Sort field of the markets endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketOrder {
+    Id,
+    Volume,
+    Volume24hr,
+    Liquidity,
+    StartDate,
+    EndDate,
+    CreatedAt,
+}
+
+impl MarketOrder {
+    pub const ALL: [MarketOrder; 7] = [
+        MarketOrder::Id,
+        MarketOrder::Volume,
+        MarketOrder::Volume24hr,
+        MarketOrder::Liquidity,
+        MarketOrder::StartDate,
+        MarketOrder::EndDate,
+        MarketOrder::CreatedAt,
+    ];
+
+    /// Value of the `order` parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MarketOrder::Id => "id",
+            MarketOrder::Volume => "volume",
+            MarketOrder::Volume24hr => "volume24hr",
+            MarketOrder::Liquidity => "liquidity",
+            MarketOrder::StartDate => "startDate",
+            MarketOrder::EndDate => "endDate",
+            MarketOrder::CreatedAt => "createdAt",
+        }
+    }
+
+    /// Ascending order of two markets on this field, for sorting stored
+    /// markets the way the API would
+    pub fn compare(self, a: &GammaMarket, b: &GammaMarket) -> Ordering {
+        match self {
+            MarketOrder::Id => a.id.0.cmp(&b.id.0),
+            MarketOrder::Volume => a.volume().cmp(&b.volume()),
+            MarketOrder::Volume24hr => a
+                .volume_24hr
+                .unwrap_or_default()
+                .cmp(&b.volume_24hr.unwrap_or_default()),
+            MarketOrder::Liquidity => a
+                .liquidity
+                .unwrap_or_default()
+                .cmp(&b.liquidity.unwrap_or_default()),
+            MarketOrder::StartDate => a.start_date.cmp(&b.start_date),
+            MarketOrder::EndDate => a.end_date.cmp(&b.end_date),
+            MarketOrder::CreatedAt => a.created_at.cmp(&b.created_at),
+        }
+    }
+}
+
+impl fmt::Display for MarketOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MarketOrder {
+    type Err = String;
+
+    /// Accepts the API names and their snake_case spelling (`start_date`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.replace(['_', '-'], "").to_ascii_lowercase();
+        MarketOrder::ALL
+            .into_iter()
+            .find(|order| order.as_str().to_ascii_lowercase() == normalized)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = MarketOrder::ALL.iter().map(|o| o.as_str()).collect();
+                format!("unknown sort field '{}' (use {})", s, valid.join(", "))
+            })
+    }
+}
+
+impl MarketQuery {
+    pub fn builder() -> MarketQueryBuilder {
+        MarketQueryBuilder::default()
+    }
+
+    /// Shorthand for a query that only sets a page size
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Check the query before it is sent
+    pub fn validate(&self) -> Result<(), MarketQueryError> {
+        if self.limit == Some(0) {
+            return Err(MarketQueryError::ZeroLimit);
+        }
+        let liquidity = Range::new("liquidity", self.liquidity_min, self.liquidity_max);
+        let volume = Range::new("volume", self.volume_min, self.volume_max);
+        liquidity.check_non_negative()?;
+        volume.check_non_negative()?;
+        liquidity.check_order()?;
+        volume.check_order()?;
+        Range::new("start_date", self.start_date_min, self.start_date_max).check_order()?;
+        Range::new("end_date", self.end_date_min, self.end_date_max).check_order()?;
+        if self.related_tags == Some(true) && self.tag_ids.is_empty() {
+            return Err(MarketQueryError::RelatedTagsWithoutTagId);
+        }
+        Ok(())
+    }
+
+    /// Query string parameters for the markets endpoint
+    pub fn to_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(ref order) = self.order {
+            params.push(("order", order.clone()));
+        }
+        if let Some(ascending) = self.ascending {
+            params.push(("ascending", ascending.to_string()));
+        }
+        if let Some(archived) = self.archived {
+            params.push(("archived", archived.to_string()));
+        }
+        if let Some(active) = self.active {
+            params.push(("active", active.to_string()));
+        }
+        if let Some(closed) = self.closed {
+            params.push(("closed", closed.to_string()));
+        }
+
+        // Identifiers
+        for id in &self.ids {
+            params.push(("id", id.0.to_string()));
+        }
+        for slug in &self.slugs {
+            params.push(("slug", slug.0.clone()));
+        }
+        for token_id in &self.clob_token_ids {
+            params.push(("clob_token_ids", token_id.0.clone()));
+        }
+        for condition_id in &self.condition_ids {
+            params.push(("condition_ids", condition_id.0.clone()));
+        }
+        for event_id in &self.event_ids {
+            params.push(("event_id", event_id.0.to_string()));
+        }
+
+        // Numeric ranges
+        if let Some(min) = self.liquidity_min {
+            params.push(("liquidity_num_min", min.to_string()));
+        }
+        if let Some(max) = self.liquidity_max {
+            params.push(("liquidity_num_max", max.to_string()));
+        }
+        if let Some(min) = self.volume_min {
+            params.push(("volume_num_min", min.to_string()));
+        }
+        if let Some(max) = self.volume_max {
+            params.push(("volume_num_max", max.to_string()));
+        }
+
+        // Date ranges
+        if let Some(min) = self.start_date_min {
+            params.push(("start_date_min", min.to_rfc3339()));
+        }
+        if let Some(max) = self.start_date_max {
+            params.push(("start_date_max", max.to_rfc3339()));
+        }
+        if let Some(min) = self.end_date_min {
+            params.push(("end_date_min", min.to_rfc3339()));
+        }
+        if let Some(max) = self.end_date_max {
+            params.push(("end_date_max", max.to_rfc3339()));
+        }
+
+        // Tags
+        for tag in &self.tags {
+            params.push(("tag", tag.clone()));
+        }
+        for tag_id in &self.tag_ids {
+            params.push(("tag_id", tag_id.0.to_string()));
+        }
+        for tag_slug in &self.tag_slugs {
+            params.push(("tag_slug", tag_slug.0.clone()));
+        }
+        if let Some(related_tags) = self.related_tags {
+            params.push(("related_tags", related_tags.to_string()));
+        }
+
+        params
+    }
+}
+
+/// Optional bounds of one range filter
+struct Range<T> {
+    field: &'static str,
+    min: Option<T>,
+    max: Option<T>,
+}
+
+impl<T: PartialOrd + fmt::Display> Range<T> {
+    fn new(field: &'static str, min: Option<T>, max: Option<T>) -> Self {
+        Self { field, min, max }
+    }
+
+    fn check_order(&self) -> Result<(), MarketQueryError> {
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) if min > max => Err(MarketQueryError::InvertedRange {
+                field: self.field,
+                min: min.to_string(),
+                max: max.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Range<Decimal> {
+    fn check_non_negative(&self) -> Result<(), MarketQueryError> {
+        match [self.min, self.max].into_iter().flatten().find(Decimal::is_sign_negative) {
+            Some(value) => Err(MarketQueryError::NegativeBound {
+                field: self.field,
+                value,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Fluent builder for [`MarketQuery`]
+///
+/// Scalar setters take `impl Into<Option<T>>`, so CLI flags that are already
+/// optional pass straight through: `.volume_min(args.min_volume)`.
+#[derive(Debug, Clone, Default)]
+pub struct MarketQueryBuilder {
+    query: MarketQuery,
+}
+
+impl MarketQueryBuilder {
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.query.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.query.offset = Some(offset);
+        self
+    }
+
+    pub fn order(mut self, order: impl Into<Option<MarketOrder>>) -> Self {
+        self.query.order = order.into().map(|order| order.as_str().to_string());
+        self
+    }
+
+    pub fn ascending(mut self, ascending: impl Into<Option<bool>>) -> Self {
+        self.query.ascending = ascending.into();
+        self
+    }
+
+    pub fn active(mut self, active: impl Into<Option<bool>>) -> Self {
+        self.query.active = active.into();
+        self
+    }
+
+    pub fn closed(mut self, closed: impl Into<Option<bool>>) -> Self {
+        self.query.closed = closed.into();
+        self
+    }
+
+    pub fn archived(mut self, archived: impl Into<Option<bool>>) -> Self {
+        self.query.archived = archived.into();
+        self
+    }
+
+    pub fn id(mut self, id: MarketId) -> Self {
+        self.query.ids.push(id);
+        self
+    }
+
+    pub fn slug(mut self, slug: MarketSlug) -> Self {
+        self.query.slugs.push(slug);
+        self
+    }
+
+    pub fn clob_token_id(mut self, token_id: ClobTokenId) -> Self {
+        self.query.clob_token_ids.push(token_id);
+        self
+    }
+
+    pub fn condition_id(mut self, condition_id: ConditionId) -> Self {
+        self.query.condition_ids.push(condition_id);
+        self
+    }
+
+    pub fn condition_ids(mut self, condition_ids: impl IntoIterator<Item = ConditionId>) -> Self {
+        self.query.condition_ids.extend(condition_ids);
+        self
+    }
+
+    /// Only markets of this event
+    pub fn event_id(mut self, event_id: EventId) -> Self {
+        self.query.event_ids.push(event_id);
+        self
+    }
+
+    pub fn liquidity_min(mut self, min: impl Into<Option<Decimal>>) -> Self {
+        self.query.liquidity_min = min.into();
+        self
+    }
+
+    pub fn liquidity_max(mut self, max: impl Into<Option<Decimal>>) -> Self {
+        self.query.liquidity_max = max.into();
+        self
+    }
+
+    pub fn volume_min(mut self, min: impl Into<Option<Decimal>>) -> Self {
+        self.query.volume_min = min.into();
+        self
+    }
+
+    pub fn volume_max(mut self, max: impl Into<Option<Decimal>>) -> Self {
+        self.query.volume_max = max.into();
+        self
+    }
+
+    pub fn start_date_min(mut self, min: impl Into<Option<DateTime<Utc>>>) -> Self {
+        self.query.start_date_min = min.into();
+        self
+    }
+
+    pub fn start_date_max(mut self, max: impl Into<Option<DateTime<Utc>>>) -> Self {
+        self.query.start_date_max = max.into();
+        self
+    }
+
+    pub fn end_date_min(mut self, min: impl Into<Option<DateTime<Utc>>>) -> Self {
+        self.query.end_date_min = min.into();
+        self
+    }
+
+    pub fn end_date_max(mut self, max: impl Into<Option<DateTime<Utc>>>) -> Self {
+        self.query.end_date_max = max.into();
+        self
+    }
+
+    /// Filter by tag label; `None` leaves the query unchanged
+    pub fn tag(mut self, tag: impl Into<Option<String>>) -> Self {
+        self.query.tags.extend(tag.into());
+        self
+    }
+
+    pub fn tag_id(mut self, tag_id: impl Into<Option<TagId>>) -> Self {
+        self.query.tag_ids.extend(tag_id.into());
+        self
+    }
+
+    pub fn tag_slug(mut self, tag_slug: TagSlug) -> Self {
+        self.query.tag_slugs.push(tag_slug);
+        self
+    }
+
+    /// Also match markets whose tags are related to the tag ids
+    pub fn related_tags(mut self, related_tags: impl Into<Option<bool>>) -> Self {
+        self.query.related_tags = related_tags.into();
+        self
+    }
+
+    /// The query, validated
+    pub fn build(self) -> Result<MarketQuery, MarketQueryError> {
+        self.query.validate()?;
+        Ok(self.query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn builder_sets_typed_params_and_rejects_invalid_queries() {
+        let query = MarketQuery::builder()
+            .limit(100)
+            .active(true)
+            .archived(false.then_some(true))
+            .order(MarketOrder::Volume24hr)
+            .ascending(false)
+            .tag(Some("politics".to_string()))
+            .tag_id(TagId(2))
+            .related_tags(true)
+            .liquidity_min(dec!(1000))
+            .condition_id(ConditionId("0xabc".to_string()))
+            .event_id(EventId(42))
+            .build()
+            .unwrap();
+
+        let params = query.to_params();
+        for expected in [
+            ("limit", "100"),
+            ("active", "true"),
+            ("order", "volume24hr"),
+            ("ascending", "false"),
+            ("tag", "politics"),
+            ("tag_id", "2"),
+            ("related_tags", "true"),
+            ("liquidity_num_min", "1000"),
+            ("condition_ids", "0xabc"),
+            ("event_id", "42"),
+        ] {
+            assert!(
+                params.contains(&(expected.0, expected.1.to_string())),
+                "missing {:?} in {:?}",
+                expected,
+                params
+            );
+        }
+        assert!(!params.iter().any(|(name, _)| *name == "archived"));
+
+        assert_eq!(
+            MarketQuery::builder()
+                .volume_min(dec!(500))
+                .volume_max(dec!(100))
+                .build()
+                .unwrap_err()
+                .to_string(),
+            "volume: minimum 500 is above maximum 100"
+        );
+        assert!(matches!(
+            MarketQuery::builder().related_tags(true).build(),
+            Err(MarketQueryError::RelatedTagsWithoutTagId)
+        ));
+        assert!(matches!(
+            MarketQuery::builder().liquidity_min(dec!(-1)).build(),
+            Err(MarketQueryError::NegativeBound { .. })
+        ));
+        assert!(matches!(
+            MarketQuery::builder().limit(0).build(),
+            Err(MarketQueryError::ZeroLimit)
+        ));
+
+        assert_eq!(
+            MarketQuery::builder()
+                .volume_max(dec!(-5))
+                .build()
+                .unwrap_err()
+                .to_string(),
+            "volume must not be negative (got -5)"
+        );
+
+        assert_eq!("start_date".parse(), Ok(MarketOrder::StartDate));
+        assert_eq!("volume24hr".parse(), Ok(MarketOrder::Volume24hr));
+        assert!("popularity".parse::<MarketOrder>().is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Resolution metadata and UMA dispute tracking
+//!
+//! Polymarket markets resolve through UMA's optimistic oracle: after the end
+//! date someone proposes an outcome, and anyone can dispute the proposal within
+//! the liveness window, which sends the question to a vote. Gamma exposes the
+//! resolution source, the rules text that forms the oracle's ancillary data and
+//! the oracle's status history per market.
+//!
+//! [`ResolutionStore`] keeps the last seen state of each tracked market in
+//! `<data_dir>/resolution.json`, so a later check can tell when a held market
+//! has been proposed or disputed and raise a [`ResolutionAlert`].
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::types::GammaMarket;
+use crate::core::alerts::Severity;
+use crate::data_paths::DataPaths;
+
+/// File name of the store inside the data directory
+pub const RESOLUTION_STORE_FILE: &str = "resolution.json";
+
+/// Where a market is in the oracle process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionState {
+    /// Trading, or ended without a proposal yet
+    Open,
+    /// An outcome was proposed and the liveness window is running
+    Proposed,
+    /// The proposal was disputed and awaits a vote or a new proposal
+    Disputed,
+    Resolved,
+}
+
+impl ResolutionState {
+    /// State from Gamma's `umaResolutionStatus`, falling back to the market's closed flag
+    pub fn from_status(status: Option<&str>, closed: bool) -> Self {
+        match status.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+            Some("proposed") => Self::Proposed,
+            Some("disputed") | Some("challenged") => Self::Disputed,
+            Some("resolved") | Some("settled") => Self::Resolved,
+            _ if closed => Self::Resolved,
+            _ => Self::Open,
+        }
+    }
+
+    /// Whether entering this state warrants an alert for holders
+    pub fn is_alerting(self) -> bool {
+        matches!(self, Self::Proposed | Self::Disputed)
+    }
+}
+
+impl fmt::Display for ResolutionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Open => "open",
+            Self::Proposed => "proposed",
+            Self::Disputed => "disputed",
+            Self::Resolved => "resolved",
+        };
+        f.pad(name)
+    }
+}
+
+/// Resolution metadata of one market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketResolution {
+    pub condition_id: String,
+    pub market_id: String,
+    pub question: String,
+    pub resolution_source: Option<String>,
+    /// Market rules, which the oracle request carries as ancillary data
+    pub ancillary_data: Option<String>,
+    pub question_id: Option<String>,
+    pub end_date: Option<DateTime<Utc>>,
+    /// End of the current liveness window
+    pub uma_end_date: Option<DateTime<Utc>>,
+    /// Proposal bond and reward in USDC, as Gamma reports them
+    pub uma_bond: Option<String>,
+    pub uma_reward: Option<String>,
+    pub state: ResolutionState,
+    /// Every oracle status so far, oldest first
+    #[serde(default)]
+    pub history: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl MarketResolution {
+    pub fn from_market(market: &GammaMarket) -> Self {
+        Self {
+            condition_id: market.condition_id.0.clone(),
+            market_id: market.id.0.to_string(),
+            question: market.question.clone(),
+            resolution_source: market
+                .resolution_source
+                .clone()
+                .filter(|s| !s.trim().is_empty()),
+            ancillary_data: market.description.clone().filter(|s| !s.trim().is_empty()),
+            question_id: market.question_id.clone(),
+            end_date: market.end_date,
+            uma_end_date: market.uma_end_date,
+            uma_bond: market.uma_bond.clone(),
+            uma_reward: market.uma_reward.clone(),
+            state: ResolutionState::from_status(
+                market.uma_resolution_status.as_deref(),
+                market.closed,
+            ),
+            history: parse_history(market.uma_resolution_statuses.as_deref()),
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// How many times the market's proposals were disputed
+    pub fn disputes(&self) -> usize {
+        self.history
+            .iter()
+            .filter(|status| {
+                ResolutionState::from_status(Some(status.as_str()), false)
+                    == ResolutionState::Disputed
+            })
+            .count()
+    }
+
+    /// Detail lines for CLI and TUI views
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Status: {}", self.state)];
+        if let Some(source) = &self.resolution_source {
+            lines.push(format!("Source: {}", source));
+        }
+        if let Some(end) = self.uma_end_date.filter(|_| self.state.is_alerting()) {
+            lines.push(format!(
+                "Liveness ends: {}",
+                end.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        if let Some(bond) = &self.uma_bond {
+            lines.push(format!(
+                "Bond: ${}  Reward: ${}",
+                bond,
+                self.uma_reward.as_deref().unwrap_or("-")
+            ));
+        }
+        if !self.history.is_empty() {
+            lines.push(format!(
+                "History: {} ({} dispute(s))",
+                self.history.join(" → "),
+                self.disputes()
+            ));
+        }
+        lines
+    }
+}
+
+/// `umaResolutionStatuses` is a JSON array encoded as a string
+fn parse_history(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str::<Vec<String>>(raw).ok())
+        .unwrap_or_default()
+}
+
+/// A tracked market entered a state holders should know about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResolutionAlert {
+    pub condition_id: String,
+    pub question: String,
+    pub from: Option<ResolutionState>,
+    pub to: ResolutionState,
+    /// Set when a new dispute was seen without a state change in between
+    pub new_dispute: bool,
+    pub uma_end_date: Option<DateTime<Utc>>,
+}
+
+impl ResolutionAlert {
+    /// Disputes are critical for holders, proposals a warning
+    pub fn severity(&self) -> Severity {
+        if self.new_dispute || self.to == ResolutionState::Disputed {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let change = match (self.from, self.new_dispute) {
+            (_, true) => "disputed again".to_string(),
+            (Some(from), false) => format!("{} → {}", from, self.to),
+            (None, false) => format!("is {}", self.to),
+        };
+        let mut text = format!("Resolution: \"{}\" {}", self.question, change);
+        if let Some(end) = self
+            .uma_end_date
+            .filter(|_| self.to == ResolutionState::Proposed)
+        {
+            text.push_str(&format!(
+                " (dispute window ends {})",
+                end.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        text
+    }
+}
+
+/// Last seen resolution state of tracked markets, keyed by condition id
+#[derive(Debug, Default)]
+pub struct ResolutionStore {
+    path: PathBuf,
+    markets: HashMap<String, MarketResolution>,
+}
+
+impl ResolutionStore {
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        Self::load_from(&data_paths.data().join(RESOLUTION_STORE_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let markets = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid resolution store {}", path.display()))?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            markets,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.markets)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    pub fn get(&self, condition_id: &str) -> Option<&MarketResolution> {
+        self.markets.get(condition_id)
+    }
+
+    /// Record a fresh reading; returns an alert when it moved into a proposed
+    /// or disputed state, or picked up another dispute
+    ///
+    /// A market seen for the first time alerts only if it's already proposed
+    /// or disputed.
+    pub fn update(&mut self, resolution: MarketResolution) -> Option<ResolutionAlert> {
+        let previous = self.markets.get(&resolution.condition_id);
+        let from = previous.map(|p| p.state);
+        let new_dispute = previous
+            .is_some_and(|p| p.state == resolution.state && resolution.disputes() > p.disputes());
+        let alert = ((from != Some(resolution.state) && resolution.state.is_alerting())
+            || new_dispute)
+            .then(|| ResolutionAlert {
+                condition_id: resolution.condition_id.clone(),
+                question: resolution.question.clone(),
+                from,
+                to: resolution.state,
+                new_dispute,
+                uma_end_date: resolution.uma_end_date,
+            });
+        self.markets
+            .insert(resolution.condition_id.clone(), resolution);
+        alert
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(state: ResolutionState, history: &[&str]) -> MarketResolution {
+        MarketResolution {
+            condition_id: "0xabc".to_string(),
+            market_id: "1".to_string(),
+            question: "Will it rain?".to_string(),
+            resolution_source: Some("https://weather.example".to_string()),
+            ancillary_data: None,
+            question_id: None,
+            end_date: None,
+            uma_end_date: None,
+            uma_bond: None,
+            uma_reward: None,
+            state,
+            history: history.iter().map(|s| s.to_string()).collect(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn alerts_on_proposal_dispute_and_repeat_dispute() {
+        assert_eq!(
+            ResolutionState::from_status(Some("Disputed"), false),
+            ResolutionState::Disputed
+        );
+        assert_eq!(
+            ResolutionState::from_status(None, true),
+            ResolutionState::Resolved
+        );
+        assert_eq!(parse_history(Some(r#"["proposed","disputed"]"#)).len(), 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RESOLUTION_STORE_FILE);
+        let mut store = ResolutionStore::load_from(&path).unwrap();
+
+        assert_eq!(store.update(reading(ResolutionState::Open, &[])), None);
+        let alert = store
+            .update(reading(ResolutionState::Proposed, &["proposed"]))
+            .unwrap();
+        assert_eq!(alert.from, Some(ResolutionState::Open));
+        assert_eq!(
+            store.update(reading(ResolutionState::Proposed, &["proposed"])),
+            None
+        );
+
+        let alert = store
+            .update(reading(
+                ResolutionState::Disputed,
+                &["proposed", "disputed"],
+            ))
+            .unwrap();
+        assert_eq!(alert.to, ResolutionState::Disputed);
+        store.save().unwrap();
+
+        // A second dispute between checks shows up only in the history
+        let mut store = ResolutionStore::load_from(&path).unwrap();
+        let alert = store
+            .update(reading(
+                ResolutionState::Disputed,
+                &["proposed", "disputed", "proposed", "disputed"],
+            ))
+            .unwrap();
+        assert!(alert.new_dispute);
+        assert_eq!(store.update(reading(ResolutionState::Resolved, &[])), None);
+    }
+}
@@ -10,7 +10,7 @@ use tracing::{info, debug, warn};
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 
-use super::database::GammaDatabase;
+use super::store::{open_store, GammaStore, MarketListQuery};
 use super::types::GammaMarket;
 
 /// Ultra-fast in-memory search index
@@ -38,20 +38,20 @@ pub struct IndexStats {
 /// Search index builder and manager
 #[allow(dead_code)] // Search index API kept for future use
 pub struct SearchIndexBuilder {
-    db: GammaDatabase,
+    db: Box<dyn GammaStore>,
     index_path: std::path::PathBuf,
 }
 
 #[allow(dead_code)] // Search index API kept for future use
 impl SearchIndexBuilder {
-    /// Create new search index builder
-    pub async fn new(db_path: &Path, index_path: &Path) -> Result<Self> {
+    /// Create new search index builder over the gamma database in `data_root`
+    pub async fn new(data_root: &Path, index_path: &Path) -> Result<Self> {
         info!("Initializing ultra-fast search index builder");
-        info!("Database path: {}", db_path.display());
+        info!("Data directory: {}", data_root.display());
         info!("Index path: {}", index_path.display());
         
         // Initialize database
-        let db = GammaDatabase::new(db_path).await
+        let db = open_store(data_root).await
             .context("Failed to initialize database")?;
         
         // Create index directory if it doesn't exist
@@ -116,8 +116,12 @@ impl SearchIndexBuilder {
             
             // Fetch batch of markets using proper database query
             let batch_start = std::time::Instant::now();
-            let query = format!("SELECT * FROM markets LIMIT {} START {}", batch_size, offset);
-            let markets = self.db.execute_query(&query).await?;
+            let page = MarketListQuery {
+                limit: Some(batch_size),
+                offset,
+                ..Default::default()
+            };
+            let markets = self.db.list_markets(&page).await?;
             let fetch_time = batch_start.elapsed();
             
             if markets.is_empty() {
@@ -404,23 +408,22 @@ impl SearchIndex {
     }
 }
 
-/// Helper function to get standard index path
-pub fn get_index_path() -> std::path::PathBuf {
-    std::path::PathBuf::from("./data/database/gamma-search")
+/// Helper function to get standard index path under the profile's data root
+pub fn get_index_path(data_root: &Path) -> std::path::PathBuf {
+    data_root.join("database").join("gamma-search")
 }
 
 /// Build or rebuild the search index
 #[allow(dead_code)] // Search index API kept for future use
-pub async fn build_search_index(rebuild: bool) -> Result<SearchIndex> {
+pub async fn build_search_index(data_root: &Path, rebuild: bool) -> Result<SearchIndex> {
     println!("🚀 Initializing ultra-fast search index builder...");
     
-    let db_path = std::path::PathBuf::from("./data/database/gamma");
-    let index_path = get_index_path();
+    let index_path = get_index_path(data_root);
     
-    println!("📁 Database path: {}", db_path.display());
+    println!("📁 Data directory: {}", data_root.display());
     println!("📁 Index path: {}", index_path.display());
     
-    let mut builder = SearchIndexBuilder::new(&db_path, &index_path).await?;
+    let mut builder = SearchIndexBuilder::new(&data_root, &index_path).await?;
     
     let index = if rebuild {
         println!("\\n🔨 Rebuilding search index from scratch (--force flag used)");
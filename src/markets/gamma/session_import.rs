@@ -0,0 +1,357 @@
+//! Concurrent import of fetch sessions into the Gamma database
+//!
+//! `polybot gamma import-session` reads several sessions at once (each
+//! session's `raw-offset-*.json` files in parallel with rayon), merges their
+//! markets and embedded events by id, and writes each unique market and event
+//! once. When the same id appears more than once, the copy with the latest
+//! `updated_at` wins; on a tie the later session does. Rows already in the
+//! database are only replaced by a copy that is at least as recent.
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::progress::{Progress, ProgressReporter};
+use crate::markets::gamma::store::{GammaStore, SessionEvent, SessionMarket, UpsertCounts};
+use crate::markets::gamma::types::{GammaMarket, MarketEventInfo};
+
+/// Progress task names, for views that pick tasks out of snapshots
+pub const SESSIONS_TASK: &str = "📦 Sessions";
+pub const FILES_TASK: &str = "📄 Files";
+pub const UNIQUE_TASK: &str = "🔀 Unique markets";
+pub const WRITE_TASK: &str = "💾 Writing";
+
+/// A fetch session to import
+#[derive(Debug, Clone)]
+pub struct SessionSource {
+    pub id: u32,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImportOptions {
+    /// Sessions read at the same time
+    pub concurrency: usize,
+    /// Markets per database write
+    pub batch_size: usize,
+}
+
+/// Totals of an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub sessions: usize,
+    pub files: usize,
+    /// Markets read across all sessions, duplicates included
+    pub markets_read: u64,
+    pub unique_markets: usize,
+    pub unique_events: usize,
+    pub markets: UpsertCounts,
+    pub events: UpsertCounts,
+}
+
+/// Raw response files of a session, in offset order
+pub fn raw_files(session_path: &Path) -> Result<Vec<PathBuf>> {
+    if !session_path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(session_path).context("Failed to read session directory")? {
+        let path = entry.context("Failed to read directory entry")?.path();
+        let offset = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("raw-offset-"))
+            .and_then(|n| n.strip_suffix(".json"))
+            .and_then(|n| n.parse::<usize>().ok());
+        if let Some(offset) = offset {
+            files.push((offset, path));
+        }
+    }
+    files.sort_by_key(|(offset, _)| *offset);
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Parse a session's raw files in parallel, advancing `files` once per file
+pub fn read_session(files: &[PathBuf], progress: &Progress) -> Result<Vec<GammaMarket>> {
+    let batches: Vec<Vec<GammaMarket>> = files
+        .par_iter()
+        .map(|path| -> Result<Vec<GammaMarket>> {
+            progress.check_cancelled()?;
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let markets: Vec<GammaMarket> = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            progress.inc(1);
+            Ok(markets)
+        })
+        .collect::<Result<_>>()?;
+    Ok(batches.into_iter().flatten().collect())
+}
+
+/// Markets and events merged by id, last write wins
+#[derive(Debug, Default)]
+pub struct MarketDedup {
+    markets: HashMap<u64, KeptMarket>,
+    events: HashMap<u64, KeptEvent>,
+    seen: u64,
+}
+
+/// A market the merge kept and the session it came from
+#[derive(Debug, Clone)]
+pub struct KeptMarket {
+    pub session_id: u32,
+    pub market: GammaMarket,
+}
+
+/// An event the merge kept and the session it came from
+#[derive(Debug, Clone)]
+pub struct KeptEvent {
+    pub session_id: u32,
+    pub event: MarketEventInfo,
+}
+
+/// What survives a merge, each list ordered by id
+#[derive(Debug, Clone, Default)]
+pub struct DedupedSessions {
+    pub markets: Vec<KeptMarket>,
+    pub events: Vec<KeptEvent>,
+}
+
+impl MarketDedup {
+    /// Merge a market read from `session_id`; returns whether its id is new
+    pub fn insert(&mut self, session_id: u32, market: GammaMarket) -> bool {
+        self.seen += 1;
+        for event in market.events.iter().flatten() {
+            let newer = match self.events.get(&event.id.0) {
+                Some(kept) => {
+                    (event.updated_at, session_id) >= (kept.event.updated_at, kept.session_id)
+                }
+                None => true,
+            };
+            if newer {
+                let event = KeptEvent {
+                    session_id,
+                    event: event.clone(),
+                };
+                self.events.insert(event.event.id.0, event);
+            }
+        }
+        match self.markets.get_mut(&market.id.0) {
+            Some(kept) => {
+                if (market.updated_at, session_id) >= (kept.market.updated_at, kept.session_id) {
+                    *kept = KeptMarket { session_id, market };
+                }
+                false
+            }
+            None => {
+                self.markets.insert(market.id.0, KeptMarket { session_id, market });
+                true
+            }
+        }
+    }
+
+    /// Markets merged so far, duplicates included
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    pub fn events_len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// The surviving markets and events, each with its session, ordered by id
+    pub fn into_parts(self) -> DedupedSessions {
+        let mut markets: Vec<KeptMarket> = self.markets.into_values().collect();
+        markets.sort_by_key(|kept| kept.market.id.0);
+        let mut events: Vec<KeptEvent> = self.events.into_values().collect();
+        events.sort_by_key(|kept| kept.event.id.0);
+        DedupedSessions { markets, events }
+    }
+}
+
+/// Read `sessions` `options.concurrency` at a time, merge them, then write the
+/// unique markets and events in batches of `options.batch_size`
+pub async fn import_sessions(
+    database: &dyn GammaStore,
+    sessions: Vec<SessionSource>,
+    options: ImportOptions,
+    progress: &ProgressReporter,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary {
+        sessions: sessions.len(),
+        ..Default::default()
+    };
+    let mut listed = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let files = raw_files(&session.path).with_context(|| format!("Session {}", session.id))?;
+        summary.files += files.len();
+        listed.push((session.id, files));
+    }
+
+    let sessions_task = progress.task(SESSIONS_TASK, Some(listed.len() as u64));
+    let files_task = progress.task(FILES_TASK, Some(summary.files as u64));
+    let unique_task = progress.task(UNIQUE_TASK, None);
+
+    let mut reads = stream::iter(listed)
+        .map(|(session_id, files)| {
+            let files_task = files_task.clone();
+            async move {
+                let markets =
+                    tokio::task::spawn_blocking(move || read_session(&files, &files_task))
+                        .await
+                        .context("Session reader panicked")?
+                        .with_context(|| format!("Failed to read session {}", session_id))?;
+                Ok::<_, anyhow::Error>((session_id, markets))
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1));
+
+    let mut dedup = MarketDedup::default();
+    while let Some(read) = reads.next().await {
+        let (session_id, markets) = read?;
+        let count = markets.len();
+        let mut added = 0;
+        for market in markets {
+            if dedup.insert(session_id, market) {
+                added += 1;
+            }
+        }
+        unique_task.inc(added);
+        sessions_task.inc(1);
+        sessions_task.set_message(format!("session {} read ({} markets)", session_id, count));
+        sessions_task.check_cancelled()?;
+    }
+    summary.markets_read = dedup.seen();
+    summary.unique_markets = dedup.len();
+    summary.unique_events = dedup.events_len();
+    sessions_task.finish(format!("✓ {} sessions read", summary.sessions));
+    files_task.finish(format!("✓ {} files", summary.files));
+    unique_task.finish(format!(
+        "✓ {} unique of {} read, {} events",
+        summary.unique_markets, summary.markets_read, summary.unique_events
+    ));
+
+    let DedupedSessions { markets, events } = dedup.into_parts();
+    let write_task = progress.task(WRITE_TASK, Some((markets.len() + events.len()) as u64));
+    let batch_size = options.batch_size.max(1);
+    for batch in markets.chunks(batch_size) {
+        // Batches already written stay in the database
+        write_task.check_cancelled()?;
+        let batch: Vec<SessionMarket> = batch
+            .iter()
+            .map(|kept| SessionMarket {
+                session_id: kept.session_id,
+                market: &kept.market,
+            })
+            .collect();
+        let counts = database
+            .upsert_markets(&batch)
+            .await
+            .context("Failed to store markets batch in database")?;
+        summary.markets.stored += counts.stored;
+        summary.markets.skipped += counts.skipped;
+        write_task.inc(batch.len() as u64);
+        write_task.set_message(format!("{} markets stored", summary.markets.stored));
+    }
+    for batch in events.chunks(batch_size) {
+        write_task.check_cancelled()?;
+        let batch: Vec<SessionEvent> = batch
+            .iter()
+            .map(|kept| SessionEvent {
+                session_id: kept.session_id,
+                event: &kept.event,
+            })
+            .collect();
+        let counts = database
+            .upsert_events(&batch)
+            .await
+            .context("Failed to store events batch in database")?;
+        summary.events.stored += counts.stored;
+        summary.events.skipped += counts.skipped;
+        write_task.inc(batch.len() as u64);
+        write_task.set_message(format!("{} events stored", summary.events.stored));
+    }
+    write_task.set_message("flushing");
+    database
+        .finish_bulk_load()
+        .await
+        .context("Failed to finish the bulk load")?;
+    write_task.finish(format!(
+        "✓ {} markets, {} events stored",
+        summary.markets.stored, summary.events.stored
+    ));
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn market(id: u64, updated_hour: u32, question: &str) -> GammaMarket {
+        let mut market: GammaMarket = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "conditionId": format!("0x{:x}", id),
+            "slug": format!("market-{}", id),
+            "question": question,
+            "outcomes": "[\"Yes\", \"No\"]",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "active": true,
+            "closed": false,
+            "archived": false,
+            "restricted": false,
+            "cyom": false,
+            "approved": true,
+        }))
+        .unwrap();
+        market.updated_at = Utc
+            .with_ymd_and_hms(2024, 1, 1, updated_hour, 0, 0)
+            .unwrap();
+        market
+    }
+
+    #[test]
+    fn duplicates_keep_the_latest_update() {
+        let mut dedup = MarketDedup::default();
+        assert!(dedup.insert(1, market(7, 12, "noon")));
+        // An older copy from a later session doesn't replace a newer one
+        assert!(!dedup.insert(2, market(7, 9, "morning")));
+        // A tie goes to the later session
+        assert!(!dedup.insert(3, market(7, 12, "noon, session 3")));
+        assert!(dedup.insert(2, market(8, 1, "other")));
+
+        assert_eq!((dedup.seen(), dedup.len()), (4, 2));
+        let parts = dedup.into_parts();
+        assert!(parts.events.is_empty());
+        assert_eq!(parts.markets[0].session_id, 3);
+        assert_eq!(parts.markets[0].market.question, "noon, session 3");
+        assert_eq!(parts.markets[1].market.id.0, 8);
+    }
+
+    #[test]
+    fn raw_files_are_listed_in_offset_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["raw-offset-500.json", "raw-offset-0.json", "metadata.json"] {
+            fs::write(dir.path().join(name), "[]").unwrap();
+        }
+        let files = raw_files(dir.path()).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["raw-offset-0.json", "raw-offset-500.json"]);
+        assert!(raw_files(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,697 @@
+//! Storage backends for the local Gamma market database
+//!
+//! Imported sessions, `gamma markets` pages and the search indexes all go
+//! through the [`GammaStore`] trait, which has two implementations:
+//!
+//! - `surrealdb`: [`GammaDatabase`](super::database::GammaDatabase), SurrealDB
+//!   on RocksDB in `database/gamma` (needs the `surrealdb` feature)
+//! - `rocksdb`: [`TypedGammaStore`](super::typed_backend::TypedGammaStore),
+//!   plain typed_store column families in `database/gamma_typed`
+//!
+//! `gamma.yaml` in the data directory picks one (`backend: rocksdb`); without
+//! it, builds with the `surrealdb` feature use SurrealDB and others RocksDB.
+//! The two keep separate files, so switching starts from an empty database
+//! until sessions are imported again.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::markets::gamma::types::{
+    ClobTokenId, ConditionId, GammaMarket, MarketEventInfo, MarketId,
+};
+
+/// Backend selection file inside the data directory
+pub const GAMMA_CONFIG_FILE: &str = "gamma.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GammaBackend {
+    Surrealdb,
+    Rocksdb,
+}
+
+impl Default for GammaBackend {
+    fn default() -> Self {
+        if cfg!(feature = "surrealdb") {
+            GammaBackend::Surrealdb
+        } else {
+            GammaBackend::Rocksdb
+        }
+    }
+}
+
+impl GammaBackend {
+    /// Whether this build can open the backend
+    pub fn is_available(self) -> bool {
+        match self {
+            GammaBackend::Surrealdb => cfg!(feature = "surrealdb"),
+            GammaBackend::Rocksdb => true,
+        }
+    }
+
+    /// Database directory of the backend under `data_root`
+    pub fn db_path(self, data_root: &Path) -> PathBuf {
+        match self {
+            GammaBackend::Surrealdb => data_root.join("database").join("gamma"),
+            GammaBackend::Rocksdb => data_root.join("database").join("gamma_typed"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GammaBackend::Surrealdb => "SurrealDB (RocksDB engine)",
+            GammaBackend::Rocksdb => "typed RocksDB",
+        }
+    }
+}
+
+impl fmt::Display for GammaBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GammaBackend::Surrealdb => "surrealdb",
+            GammaBackend::Rocksdb => "rocksdb",
+        })
+    }
+}
+
+impl FromStr for GammaBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "surrealdb" | "surreal" => Ok(GammaBackend::Surrealdb),
+            "rocksdb" | "rocks" | "typed" => Ok(GammaBackend::Rocksdb),
+            other => bail!(
+                "Unknown gamma backend '{}': use surrealdb or rocksdb",
+                other
+            ),
+        }
+    }
+}
+
+/// Contents of `gamma.yaml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GammaStoreConfig {
+    pub backend: GammaBackend,
+}
+
+impl GammaStoreConfig {
+    /// Load `gamma.yaml` from `data_root`, or defaults when it doesn't exist
+    pub fn load(data_root: &Path) -> Result<Self> {
+        let path = data_root.join(GAMMA_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid gamma config {}", path.display()))
+    }
+}
+
+/// Open the backend `gamma.yaml` in `data_root` selects
+pub async fn open_store(data_root: &Path) -> Result<Box<dyn GammaStore>> {
+    let config = GammaStoreConfig::load(data_root)?;
+    open_backend(data_root, config.backend).await
+}
+
+/// [`open_store`] for a large import: the `rocksdb` backend skips its
+/// write-ahead log and defers compaction until
+/// [`GammaStore::finish_bulk_load`]; SurrealDB opens as usual
+pub async fn open_store_for_bulk_load(data_root: &Path) -> Result<Box<dyn GammaStore>> {
+    let config = GammaStoreConfig::load(data_root)?;
+    if config.backend != GammaBackend::Rocksdb {
+        return open_backend(data_root, config.backend).await;
+    }
+    let db_path = config.backend.db_path(data_root);
+    let store = tokio::task::spawn_blocking(move || {
+        super::typed_backend::TypedGammaStore::open_bulk_load(&db_path)
+    })
+    .await
+    .context("Gamma store task panicked")?
+    .context("Failed to open gamma database")?;
+    Ok(Box::new(store))
+}
+
+/// Open `backend`'s database under `data_root`
+pub async fn open_backend(data_root: &Path, backend: GammaBackend) -> Result<Box<dyn GammaStore>> {
+    let db_path = backend.db_path(data_root);
+    match backend {
+        #[cfg(feature = "surrealdb")]
+        GammaBackend::Surrealdb => {
+            let database = super::database::GammaDatabase::new(&db_path)
+                .await
+                .context("Failed to initialize gamma database")?;
+            Ok(Box::new(database))
+        }
+        #[cfg(not(feature = "surrealdb"))]
+        GammaBackend::Surrealdb => bail!(
+            "{} selects the surrealdb backend, but this build doesn't include the `surrealdb` \
+             feature; set `backend: rocksdb` or rebuild with `--features surrealdb`",
+            data_root.join(GAMMA_CONFIG_FILE).display()
+        ),
+        GammaBackend::Rocksdb => {
+            let store = tokio::task::spawn_blocking(move || {
+                super::typed_backend::TypedGammaStore::open(&db_path)
+            })
+            .await
+            .context("Gamma store task panicked")?
+            .context("Failed to open gamma database")?;
+            Ok(Box::new(store))
+        }
+    }
+}
+
+/// Record field a market listing is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketListOrder {
+    Volume,
+    Volume24hr,
+    Liquidity,
+    CreatedAt,
+    UpdatedAt,
+    EndDate,
+    Question,
+}
+
+impl MarketListOrder {
+    pub const ALL: [MarketListOrder; 7] = [
+        MarketListOrder::Volume,
+        MarketListOrder::Volume24hr,
+        MarketListOrder::Liquidity,
+        MarketListOrder::CreatedAt,
+        MarketListOrder::UpdatedAt,
+        MarketListOrder::EndDate,
+        MarketListOrder::Question,
+    ];
+
+    /// Name of the [`MarketRecord`] field, also the SurrealDB column
+    pub fn field(self) -> &'static str {
+        match self {
+            MarketListOrder::Volume => "volume",
+            MarketListOrder::Volume24hr => "volume_24hr",
+            MarketListOrder::Liquidity => "liquidity",
+            MarketListOrder::CreatedAt => "created_at",
+            MarketListOrder::UpdatedAt => "updated_at",
+            MarketListOrder::EndDate => "end_date",
+            MarketListOrder::Question => "question",
+        }
+    }
+
+    /// Whether the backends keep records in an order that makes this sort cheap
+    pub fn is_indexed(self) -> bool {
+        matches!(self, MarketListOrder::CreatedAt | MarketListOrder::UpdatedAt)
+    }
+
+    fn compare(self, a: &MarketRecord, b: &MarketRecord) -> std::cmp::Ordering {
+        match self {
+            MarketListOrder::Volume => number(&a.volume).partial_cmp(&number(&b.volume)),
+            MarketListOrder::Volume24hr => number(&a.volume_24hr).partial_cmp(&number(&b.volume_24hr)),
+            MarketListOrder::Liquidity => number(&a.liquidity).partial_cmp(&number(&b.liquidity)),
+            MarketListOrder::CreatedAt => Some(a.created_at.cmp(&b.created_at)),
+            MarketListOrder::UpdatedAt => Some(a.updated_at.cmp(&b.updated_at)),
+            MarketListOrder::EndDate => Some(a.end_date.cmp(&b.end_date)),
+            MarketListOrder::Question => Some(a.question.cmp(&b.question)),
+        }
+        .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+impl fmt::Display for MarketListOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.field())
+    }
+}
+
+impl FromStr for MarketListOrder {
+    type Err = anyhow::Error;
+
+    /// Accepts the field names, with or without underscores (`volume24hr`)
+    fn from_str(s: &str) -> Result<Self> {
+        let normalized = s.trim().replace(['_', '-'], "").to_ascii_lowercase();
+        match MarketListOrder::ALL
+            .into_iter()
+            .find(|order| order.field().replace('_', "") == normalized)
+        {
+            Some(order) => Ok(order),
+            None => {
+                let valid: Vec<&str> = MarketListOrder::ALL.iter().map(|o| o.field()).collect();
+                bail!("Unknown sort field '{}': use {}", s, valid.join(", "))
+            }
+        }
+    }
+}
+
+/// Filters, order and page of a market listing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketListQuery {
+    pub active_only: bool,
+    pub closed_only: bool,
+    pub category: Option<String>,
+    pub min_volume: Option<f64>,
+    pub sort_by: Option<MarketListOrder>,
+    pub descending: bool,
+    pub limit: Option<u64>,
+    pub offset: u64,
+}
+
+impl MarketListQuery {
+    /// Whether any filter (not just order or page) is set
+    pub fn has_filters(&self) -> bool {
+        self.active_only || self.closed_only || self.category.is_some() || self.min_volume.is_some()
+    }
+
+    /// Whether `record` passes the filters
+    pub fn matches(&self, record: &MarketRecord) -> bool {
+        (!self.active_only || record.active)
+            && (!self.closed_only || record.closed)
+            && self
+                .category
+                .as_ref()
+                .is_none_or(|category| record.category.as_ref() == Some(category))
+            && self
+                .min_volume
+                .is_none_or(|min| number(&record.volume).is_some_and(|volume| volume >= min))
+    }
+
+    /// Filter, sort and page `records`, as the SurrealDB backend's query does
+    pub fn apply(&self, records: Vec<MarketRecord>) -> Vec<MarketRecord> {
+        let mut records: Vec<MarketRecord> =
+            records.into_iter().filter(|r| self.matches(r)).collect();
+        if let Some(sort_by) = self.sort_by {
+            records.sort_by(|a, b| {
+                let order = sort_by.compare(a, b);
+                if self.descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+        let limit = self.limit.map_or(usize::MAX, |limit| limit as usize);
+        records
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(limit)
+            .collect()
+    }
+}
+
+/// A decimal text field as a number, for filters and sorting
+fn number(value: &Option<String>) -> Option<f64> {
+    value.as_deref().and_then(|v| v.parse::<f64>().ok())
+}
+
+/// Storage for imported Gamma markets and their events
+#[async_trait]
+pub trait GammaStore: Send + Sync {
+    fn backend(&self) -> GammaBackend;
+
+    /// Database directory
+    fn path(&self) -> &Path;
+
+    /// Store markets keyed by market id, each tagged with the session it came
+    /// from; a market already stored with a later `updated_at` is kept
+    async fn upsert_markets(&self, markets: &[SessionMarket<'_>]) -> Result<UpsertCounts>;
+
+    /// Store events keyed by event id, last `updated_at` wins as for markets
+    async fn upsert_events(&self, events: &[SessionEvent<'_>]) -> Result<UpsertCounts>;
+
+    /// Store one session's markets; returns how many were written
+    async fn store_markets(&self, markets: &[GammaMarket], session_id: u32) -> Result<u64> {
+        let markets: Vec<SessionMarket> = markets
+            .iter()
+            .map(|market| SessionMarket { session_id, market })
+            .collect();
+        Ok(self.upsert_markets(&markets).await?.stored)
+    }
+
+    async fn get_market(&self, market_id: &str) -> Result<Option<GammaMarket>>;
+
+    async fn list_markets(&self, query: &MarketListQuery) -> Result<Vec<GammaMarket>>;
+
+    /// Every market, or the first `limit`
+    async fn get_all_markets(&self, limit: Option<u64>) -> Result<Vec<GammaMarket>>;
+
+    /// Every market, advancing `progress` (whose length is set to the count)
+    async fn get_all_markets_with_progress(
+        &self,
+        progress: Option<&indicatif::ProgressBar>,
+    ) -> Result<Vec<GammaMarket>> {
+        if let Some(pb) = progress {
+            pb.set_length(self.get_market_count().await?);
+        }
+        let markets = self.get_all_markets(None).await?;
+        if let Some(pb) = progress {
+            pb.set_position(markets.len() as u64);
+        }
+        Ok(markets)
+    }
+
+    async fn get_market_count(&self) -> Result<u64>;
+
+    async fn get_stats(&self) -> Result<MarketStats>;
+
+    /// Markets whose question or slug contains `query`, highest volume first
+    async fn search_markets(&self, query: &str, limit: Option<u64>) -> Result<Vec<GammaMarket>>;
+
+    async fn health_check(&self) -> Result<DatabaseHealth>;
+
+    /// Make the writes of a store opened with [`open_store_for_bulk_load`]
+    /// durable and compact them; nothing to do for other stores
+    async fn finish_bulk_load(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<()>;
+}
+
+/// Lightweight market record for database storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketRecord {
+    pub market_id: String, // The actual market ID from GammaMarket
+    pub condition_id: String,
+    pub slug: String,
+    pub question: String,
+    pub description: Option<String>,
+    pub outcomes: Vec<String>,
+    pub outcome_prices: Option<Vec<String>>,
+    pub clob_token_ids: Vec<String>,
+    pub category: Option<String>,
+    pub end_date: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub closed_time: Option<String>,
+    pub image: Option<String>,
+    pub icon: Option<String>,
+    pub volume: Option<String>,
+    pub liquidity: Option<String>,
+    pub active: bool,
+    pub closed: bool,
+    pub archived: bool,
+    pub restricted: bool,
+    pub cyom: bool,
+    pub approved: bool,
+    pub volume_24hr: Option<String>,
+    pub volume_1wk: Option<String>,
+    pub volume_1mo: Option<String>,
+    pub volume_1yr: Option<String>,
+    pub best_bid: Option<String>,
+    pub best_ask: Option<String>,
+    pub last_trade_price: Option<String>,
+    pub accepting_orders: bool,
+    pub enable_order_book: bool,
+    pub featured: bool,
+    pub new: bool,
+    pub neg_risk: bool,
+    pub spread: Option<String>,
+    pub start_date: Option<String>,
+    // Database metadata
+    pub first_seen: String,
+    pub last_updated_db: String,
+    pub source_session: u32,
+}
+
+/// Market statistics for analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketStats {
+    pub total_markets: u64,
+    pub active_markets: u64,
+    pub closed_markets: u64,
+    pub archived_markets: u64,
+    pub total_volume: String,
+    pub total_liquidity: String,
+    pub last_updated: String,
+}
+
+/// A market to store and the session it was read from
+#[derive(Debug, Clone, Copy)]
+pub struct SessionMarket<'a> {
+    pub session_id: u32,
+    pub market: &'a GammaMarket,
+}
+
+/// An event to store and the session it was read from
+#[derive(Debug, Clone, Copy)]
+pub struct SessionEvent<'a> {
+    pub session_id: u32,
+    pub event: &'a MarketEventInfo,
+}
+
+/// Outcome of an upsert: rows written, and rows skipped because the stored
+/// copy was updated later
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpsertCounts {
+    pub stored: u64,
+    pub skipped: u64,
+}
+
+/// Database health information
+#[derive(Debug, Clone)]
+pub struct DatabaseHealth {
+    pub is_healthy: bool,
+    pub ping_time_ms: u64,
+    pub market_count: Option<u64>,
+    pub count_time_ms: Option<u64>,
+    pub issues: Vec<String>,
+    pub recommendations: Vec<String>,
+}
+
+/// Flatten a market into its stored record, tagged with the session it came from
+pub fn market_to_record(market: &GammaMarket, session_id: u32, now: DateTime<Utc>) -> MarketRecord {
+    MarketRecord {
+        market_id: market.id.0.to_string(),
+        condition_id: market.condition_id.0.clone(),
+        slug: market.slug.clone(),
+        question: market.question.clone(),
+        description: market.description.clone(),
+        outcomes: market.outcomes.clone(),
+        outcome_prices: market
+            .outcome_prices
+            .as_ref()
+            .map(|prices| prices.iter().map(|d| d.to_string()).collect()),
+        clob_token_ids: market
+            .clob_token_ids
+            .iter()
+            .map(|id| id.0.clone())
+            .collect(),
+        category: market.category.clone(),
+        end_date: market.end_date.map(|d| d.to_rfc3339()),
+        created_at: market.created_at.to_rfc3339(),
+        updated_at: market.updated_at.to_rfc3339(),
+        closed_time: market.closed_time.map(|d| d.to_rfc3339()),
+        image: market.image.clone(),
+        icon: market.icon.clone(),
+        volume: Some(market.volume().to_string()),
+        liquidity: market.liquidity.map(|d| d.to_string()),
+        active: market.active,
+        closed: market.closed,
+        archived: market.archived,
+        restricted: market.restricted,
+        cyom: market.cyom,
+        approved: market.approved,
+        volume_24hr: market.volume_24hr.map(|d| d.to_string()),
+        volume_1wk: market.volume_1wk.map(|d| d.to_string()),
+        volume_1mo: market.volume_1mo.map(|d| d.to_string()),
+        volume_1yr: market.volume_1yr.map(|d| d.to_string()),
+        best_bid: market.best_bid.map(|d| d.to_string()),
+        best_ask: market.best_ask.map(|d| d.to_string()),
+        last_trade_price: market.last_trade_price.map(|d| d.to_string()),
+        accepting_orders: market.accepting_orders,
+        enable_order_book: market.enable_order_book,
+        featured: market.featured,
+        new: market.new,
+        neg_risk: market.neg_risk,
+        spread: market.spread.map(|d| d.to_string()),
+        start_date: market.start_date.map(|d| d.to_rfc3339()),
+        first_seen: now.to_rfc3339(),
+        last_updated_db: now.to_rfc3339(),
+        source_session: session_id,
+    }
+}
+
+/// Rebuild a market from its stored record; fields the record doesn't keep are empty
+pub fn record_to_market(record: MarketRecord) -> Result<GammaMarket> {
+    // Parse market_id as u64
+    let market_id = record
+        .market_id
+        .parse::<u64>()
+        .context("Failed to parse market_id as u64")?;
+
+    Ok(GammaMarket {
+        id: MarketId(market_id),
+        condition_id: ConditionId(record.condition_id),
+        slug: record.slug,
+        question: record.question,
+        description: record.description,
+        outcomes: record.outcomes,
+        outcome_prices: record.outcome_prices.map(|prices| {
+            prices
+                .into_iter()
+                .filter_map(|p| p.parse::<Decimal>().ok())
+                .collect()
+        }),
+        clob_token_ids: record.clob_token_ids.into_iter().map(ClobTokenId).collect(),
+        category: record.category,
+        end_date: record
+            .end_date
+            .clone()
+            .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+            .map(|d| d.with_timezone(&Utc)),
+        created_at: DateTime::parse_from_rfc3339(&record.created_at)
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        updated_at: DateTime::parse_from_rfc3339(&record.updated_at)
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        closed_time: record
+            .closed_time
+            .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+            .map(|d| d.with_timezone(&Utc)),
+        image: record.image,
+        icon: record.icon,
+        twitter_card_image: None,
+        market_maker_address: None,
+        volume_num: record.volume.and_then(|v| v.parse::<Decimal>().ok()),
+        volume_alt: None,
+        liquidity: record.liquidity.and_then(|l| l.parse::<Decimal>().ok()),
+        active: record.active,
+        closed: record.closed,
+        archived: record.archived,
+        restricted: record.restricted,
+        cyom: record.cyom,
+        approved: record.approved,
+        volume_24hr: record.volume_24hr.and_then(|v| v.parse::<Decimal>().ok()),
+        volume_1wk: record.volume_1wk.and_then(|v| v.parse::<Decimal>().ok()),
+        volume_1mo: record.volume_1mo.and_then(|v| v.parse::<Decimal>().ok()),
+        volume_1yr: record.volume_1yr.and_then(|v| v.parse::<Decimal>().ok()),
+        best_bid: record.best_bid.and_then(|v| v.parse::<Decimal>().ok()),
+        best_ask: record.best_ask.and_then(|v| v.parse::<Decimal>().ok()),
+        last_trade_price: record
+            .last_trade_price
+            .and_then(|v| v.parse::<Decimal>().ok()),
+        accepting_orders: record.accepting_orders,
+        accepting_orders_timestamp: None,
+        automatically_resolved: None,
+        automatically_active: None,
+        clear_book_on_start: None,
+        clob_rewards: Vec::new(),
+        deploying: None,
+        enable_order_book: record.enable_order_book,
+        end_date_iso: record.end_date,
+        events: None,
+        featured: record.featured,
+        funded: None,
+        group_item_threshold: None,
+        group_item_title: None,
+        has_reviewed_dates: None,
+        manual_activation: None,
+        neg_risk: record.neg_risk,
+        neg_risk_other: None,
+        new: record.new,
+        one_day_price_change: None,
+        one_hour_price_change: None,
+        one_month_price_change: None,
+        one_week_price_change: None,
+        one_year_price_change: None,
+        order_min_size: None,
+        order_price_min_tick_size: None,
+        pager_duty_notification_enabled: None,
+        pending_deployment: None,
+        question_id: None,
+        ready: None,
+        resolution_source: None,
+        resolved_by: None,
+        rewards_max_spread: None,
+        rewards_min_size: None,
+        rfq_enabled: None,
+        spread: record.spread.and_then(|v| v.parse::<Decimal>().ok()),
+        start_date: record
+            .start_date
+            .and_then(|d| DateTime::parse_from_rfc3339(&d).ok())
+            .map(|d| d.with_timezone(&Utc)),
+        start_date_iso: None,
+        submitted_by: None,
+        uma_bond: None,
+        uma_end_date: None,
+        uma_resolution_status: None,
+        uma_resolution_statuses: None,
+        uma_reward: None,
+        volume_1mo_amm: None,
+        volume_1mo_clob: None,
+        volume_1wk_amm: None,
+        volume_1wk_clob: None,
+        volume_1yr_amm: None,
+        volume_1yr_clob: None,
+        volume_clob: None,
+        competitive: None,
+        deploying_timestamp: None,
+        neg_risk_market_id: None,
+        neg_risk_request_id: None,
+        series_color: None,
+        show_gmp_outcome: None,
+        show_gmp_series: None,
+        mailchimp_tag: None,
+        market_type: None,
+        ready_for_cron: None,
+        updated_by: None,
+        creator: None,
+        wide_format: None,
+        game_start_time: None,
+        seconds_delay: None,
+        sent_discord: None,
+        notifications_enabled: None,
+        fee: None,
+        fpmm_live: None,
+        volume_24hr_clob: None,
+        volume_amm: None,
+        liquidity_amm: None,
+        comments_enabled: None,
+        ticker: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_comes_from_gamma_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = GammaStoreConfig::load(dir.path()).unwrap();
+        assert_eq!(config.backend, GammaBackend::default());
+
+        std::fs::write(dir.path().join(GAMMA_CONFIG_FILE), "backend: rocksdb\n").unwrap();
+        let config = GammaStoreConfig::load(dir.path()).unwrap();
+        assert_eq!(config.backend, GammaBackend::Rocksdb);
+        assert_eq!(
+            config.backend.db_path(dir.path()),
+            dir.path().join("database").join("gamma_typed")
+        );
+
+        std::fs::write(dir.path().join(GAMMA_CONFIG_FILE), "backend: mongodb\n").unwrap();
+        assert!(GammaStoreConfig::load(dir.path()).is_err());
+    }
+
+    #[test]
+    fn list_order_parses_field_names() {
+        assert_eq!("volume".parse::<MarketListOrder>().unwrap(), MarketListOrder::Volume);
+        assert_eq!("volume24hr".parse::<MarketListOrder>().unwrap(), MarketListOrder::Volume24hr);
+        assert_eq!("created_at".parse::<MarketListOrder>().unwrap(), MarketListOrder::CreatedAt);
+        assert!("volume; DROP TABLE markets".parse::<MarketListOrder>().is_err());
+        assert_eq!(
+            "SurrealDB".parse::<GammaBackend>().unwrap(),
+            GammaBackend::Surrealdb
+        );
+    }
+}
@@ -27,8 +27,10 @@ use std::io;
 use std::time::{Duration, Instant};
 
 use super::types::*;
+use super::resolution::{MarketResolution, ResolutionState};
 use super::storage::GammaStorage;
 use super::search::{GammaSearchEngine, MarketAnalytics};
+use crate::number_format;
 
 /// Main TUI application state
 pub struct GammaTui {
@@ -392,7 +394,7 @@ impl MarketBrowser {
         let items: Vec<ListItem> = self.markets
             .iter()
             .map(|market| {
-                let volume = format!("${:.0}", market.volume());
+                let volume = format!("${}", number_format::integer(market.volume()));
                 let status = if market.active { "🟢" } else { "🔴" };
                 
                 ListItem::new(Line::from(vec![
@@ -423,8 +425,8 @@ impl MarketBrowser {
                 Line::from(vec![Span::styled("Market Details", Style::default().add_modifier(Modifier::BOLD))]),
                 Line::from(""),
                 Line::from(vec![Span::raw("Question: "), Span::styled(&market.question, Style::default().fg(Color::Yellow))]),
-                Line::from(vec![Span::raw("Volume: "), Span::styled(format!("${:.2}", market.volume()), Style::default().fg(Color::Green))]),
-                Line::from(vec![Span::raw("Liquidity: "), Span::styled(format!("${:.2}", market.liquidity.unwrap_or_default()), Style::default().fg(Color::Blue))]),
+                Line::from(vec![Span::raw("Volume: "), Span::styled(number_format::usd(market.volume()), Style::default().fg(Color::Green))]),
+                Line::from(vec![Span::raw("Liquidity: "), Span::styled(number_format::usd(market.liquidity.unwrap_or_default()), Style::default().fg(Color::Blue))]),
                 Line::from(vec![Span::raw("Active: "), Span::styled(if market.active { "Yes" } else { "No" }, Style::default().fg(if market.active { Color::Green } else { Color::Red }))]),
                 Line::from(""),
                 Line::from("Outcomes:"),
@@ -437,9 +439,10 @@ impl MarketBrowser {
                     .unwrap_or(&rust_decimal::Decimal::ZERO);
                 all_lines.push(Line::from(vec![
                     Span::raw(format!("  {}: ", outcome)),
-                    Span::styled(format!("${:.3}", price), Style::default().fg(Color::Cyan))
+                    Span::styled(format!("${}", number_format::price(*price)), Style::default().fg(Color::Cyan))
                 ]));
             }
+            all_lines.extend(resolution_lines(market));
 
             let paragraph = Paragraph::new(all_lines)
                 .block(Block::default().title("Market Details - Press Esc to go back").borders(Borders::ALL))
@@ -588,7 +591,7 @@ impl EventBrowser {
         let items: Vec<ListItem> = self.events
             .iter()
             .map(|event| {
-                let volume = format!("${:.0}", event.volume_num.or(event.volume).unwrap_or_default());
+                let volume = format!("${}", number_format::integer(event.volume_num.or(event.volume).unwrap_or_default()));
                 let status = match event.active {
                     Some(true) => "🟢",
                     Some(false) => "🔴",
@@ -630,10 +633,10 @@ impl EventBrowser {
 
             // Draw event info
             let volume_str = event.volume_num.or(event.volume)
-                .map(|v| format!("${:.2}", v))
+                .map(number_format::usd)
                 .unwrap_or_else(|| "N/A".to_string());
             let liquidity_str = event.liquidity_num.or(event.liquidity)
-                .map(|l| format!("${:.2}", l))
+                .map(number_format::usd)
                 .unwrap_or_else(|| "N/A".to_string());
             let active_str = match event.active {
                 Some(true) => ("Yes", Color::Green),
@@ -659,7 +662,7 @@ impl EventBrowser {
             let market_items: Vec<ListItem> = event.markets
                 .iter()
                 .map(|market| {
-                    let volume = format!("${:.0}", market.volume());
+                    let volume = format!("${}", number_format::integer(market.volume()));
                     let status = if market.active { "🟢" } else { "🔴" };
                     
                     ListItem::new(Line::from(vec![
@@ -787,9 +790,9 @@ impl TradeViewer {
                 ListItem::new(Line::from(vec![
                     Span::styled(side_text, Style::default().fg(side_color)),
                     Span::raw(" "),
-                    Span::styled(format!("{} @ ${:.3}", trade.size, trade.price), Style::default().fg(Color::White)),
+                    Span::styled(format!("{} @ ${}", number_format::size(trade.size), number_format::price(trade.price)), Style::default().fg(Color::White)),
                     Span::raw(" "),
-                    Span::styled(format!("${:.2}", value), Style::default().fg(Color::Yellow)),
+                    Span::styled(number_format::usd(value), Style::default().fg(Color::Yellow)),
                     Span::raw(" "),
                     Span::styled(trade.timestamp.format("%H:%M:%S").to_string(), Style::default().fg(Color::DarkGray)),
                 ]))
@@ -822,11 +825,21 @@ impl TradeViewer {
                 Line::from(vec![Span::raw("Outcome: "), Span::styled(&trade.outcome, Style::default().fg(Color::Cyan))]),
                 Line::from(vec![Span::raw("Side: "), Span::styled(format!("{:?}", trade.side), Style::default().fg(side_color))]),
                 Line::from(vec![Span::raw("Size: "), Span::styled(trade.size.to_string(), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::raw("Price: "), Span::styled(format!("${:.4}", trade.price), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::raw("Value: "), Span::styled(format!("${:.2}", trade.size * trade.price), Style::default().fg(Color::Yellow))]),
+                Line::from(vec![Span::raw("Price: "), Span::styled(format!("${}", number_format::price(trade.price)), Style::default().fg(Color::White))]),
+                Line::from(vec![Span::raw("Value: "), Span::styled(number_format::usd(trade.size * trade.price), Style::default().fg(Color::Yellow))]),
                 Line::from(vec![Span::raw("Time: "), Span::styled(trade.timestamp.to_string(), Style::default().fg(Color::White))]),
                 Line::from(""),
-                Line::from(vec![Span::raw("User: "), Span::styled(&trade.proxy_wallet.0, Style::default().fg(Color::Blue))]),
+                Line::from(vec![
+                    Span::raw("User: "),
+                    Span::styled(
+                        crate::address_book::display_address_or(
+                            &trade.proxy_wallet.0,
+                            trade.name.as_deref().or(trade.pseudonym.as_deref()),
+                        ),
+                        Style::default().fg(Color::Blue),
+                    ),
+                ]),
+                Line::from(vec![Span::raw("Wallet: "), Span::styled(&trade.proxy_wallet.0, Style::default().fg(Color::DarkGray))]),
                 Line::from(vec![Span::raw("Tx Hash: "), Span::styled(&trade.transaction_hash.0, Style::default().fg(Color::DarkGray))]),
             ];
 
@@ -939,16 +952,15 @@ impl PositionViewer {
                 } else {
                     Color::Red
                 };
-                let pnl_sign = if position.cash_pnl >= rust_decimal::Decimal::ZERO { "+" } else { "" };
                 
                 ListItem::new(Line::from(vec![
                     Span::styled(&position.outcome, Style::default().fg(Color::White)),
                     Span::raw(" "),
                     Span::styled(format!("{} shares", position.size), Style::default().fg(Color::Cyan)),
                     Span::raw(" "),
-                    Span::styled(format!("${:.2}", position.current_value), Style::default().fg(Color::Yellow)),
+                    Span::styled(number_format::usd(position.current_value), Style::default().fg(Color::Yellow)),
                     Span::raw(" "),
-                    Span::styled(format!("{}{:.2}%", pnl_sign, position.percent_pnl), Style::default().fg(pnl_color)),
+                    Span::styled(number_format::signed_percent(position.percent_pnl), Style::default().fg(pnl_color)),
                 ]))
             })
             .collect();
@@ -981,14 +993,14 @@ impl PositionViewer {
                 Line::from(""),
                 Line::from("Position Info:"),
                 Line::from(vec![Span::raw("  Size: "), Span::styled(format!("{} shares", position.size), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::raw("  Avg Price: "), Span::styled(format!("${:.4}", position.avg_price), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::raw("  Initial Value: "), Span::styled(format!("${:.2}", position.initial_value), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::raw("  Current Value: "), Span::styled(format!("${:.2}", position.current_value), Style::default().fg(Color::Yellow))]),
+                Line::from(vec![Span::raw("  Avg Price: "), Span::styled(format!("${}", number_format::price(position.avg_price)), Style::default().fg(Color::White))]),
+                Line::from(vec![Span::raw("  Initial Value: "), Span::styled(number_format::usd(position.initial_value), Style::default().fg(Color::White))]),
+                Line::from(vec![Span::raw("  Current Value: "), Span::styled(number_format::usd(position.current_value), Style::default().fg(Color::Yellow))]),
                 Line::from(""),
                 Line::from("P&L:"),
-                Line::from(vec![Span::raw("  Cash P&L: "), Span::styled(format!("${:.2}", position.cash_pnl), Style::default().fg(pnl_color))]),
-                Line::from(vec![Span::raw("  Percent P&L: "), Span::styled(format!("{:.2}%", position.percent_pnl), Style::default().fg(pnl_color))]),
-                Line::from(vec![Span::raw("  Realized P&L: "), Span::styled(format!("${:.2}", position.realized_pnl), Style::default().fg(Color::Blue))]),
+                Line::from(vec![Span::raw("  Cash P&L: "), Span::styled(number_format::usd(position.cash_pnl), Style::default().fg(pnl_color))]),
+                Line::from(vec![Span::raw("  Percent P&L: "), Span::styled(number_format::percent(position.percent_pnl), Style::default().fg(pnl_color))]),
+                Line::from(vec![Span::raw("  Realized P&L: "), Span::styled(number_format::usd(position.realized_pnl), Style::default().fg(Color::Blue))]),
                 Line::from(""),
                 Line::from(vec![Span::raw("Redeemable: "), Span::styled(if position.redeemable { "Yes" } else { "No" }, Style::default().fg(if position.redeemable { Color::Green } else { Color::Red }))]),
                 Line::from(vec![Span::raw("End Date: "), Span::styled(position.end_date.format("%Y-%m-%d %H:%M").to_string(), Style::default().fg(Color::White))]),
@@ -1132,7 +1144,7 @@ impl SearchInterface {
         let items: Vec<ListItem> = self.search_results
             .iter()
             .map(|market| {
-                let volume = format!("${:.0}", market.volume());
+                let volume = format!("${}", number_format::integer(market.volume()));
                 let status = if market.active { "🟢" } else { "🔴" };
                 
                 ListItem::new(Line::from(vec![
@@ -1163,8 +1175,8 @@ impl SearchInterface {
                 Line::from(vec![Span::styled("Market Details", Style::default().add_modifier(Modifier::BOLD))]),
                 Line::from(""),
                 Line::from(vec![Span::raw("Question: "), Span::styled(&market.question, Style::default().fg(Color::Yellow))]),
-                Line::from(vec![Span::raw("Volume: "), Span::styled(format!("${:.2}", market.volume()), Style::default().fg(Color::Green))]),
-                Line::from(vec![Span::raw("Liquidity: "), Span::styled(format!("${:.2}", market.liquidity.unwrap_or_default()), Style::default().fg(Color::Blue))]),
+                Line::from(vec![Span::raw("Volume: "), Span::styled(number_format::usd(market.volume()), Style::default().fg(Color::Green))]),
+                Line::from(vec![Span::raw("Liquidity: "), Span::styled(number_format::usd(market.liquidity.unwrap_or_default()), Style::default().fg(Color::Blue))]),
                 Line::from(vec![Span::raw("Active: "), Span::styled(if market.active { "Yes" } else { "No" }, Style::default().fg(if market.active { Color::Green } else { Color::Red }))]),
                 Line::from(""),
                 Line::from("Outcomes:"),
@@ -1177,9 +1189,10 @@ impl SearchInterface {
                     .unwrap_or(&rust_decimal::Decimal::ZERO);
                 all_lines.push(Line::from(vec![
                     Span::raw(format!("  {}: ", outcome)),
-                    Span::styled(format!("${:.3}", price), Style::default().fg(Color::Cyan))
+                    Span::styled(format!("${}", number_format::price(*price)), Style::default().fg(Color::Cyan))
                 ]));
             }
+            all_lines.extend(resolution_lines(market));
 
             let paragraph = Paragraph::new(all_lines)
                 .block(Block::default().title("Market Details - Press Esc to go back").borders(Borders::ALL))
@@ -1237,8 +1250,8 @@ impl AnalyticsDashboard {
             Line::from(vec![Span::raw("Total Markets: "), Span::styled(analytics.total_markets.to_string(), Style::default().fg(Color::Yellow))]),
             Line::from(vec![Span::raw("Active: "), Span::styled(analytics.active_markets.to_string(), Style::default().fg(Color::Green))]),
             Line::from(vec![Span::raw("Closed: "), Span::styled(analytics.closed_markets.to_string(), Style::default().fg(Color::Red))]),
-            Line::from(vec![Span::raw("Total Volume: "), Span::styled(format!("${:.0}", analytics.total_volume), Style::default().fg(Color::Cyan))]),
-            Line::from(vec![Span::raw("Average Volume: "), Span::styled(format!("${:.0}", analytics.avg_volume), Style::default().fg(Color::Cyan))]),
+            Line::from(vec![Span::raw("Total Volume: "), Span::styled(format!("${}", number_format::integer(analytics.total_volume)), Style::default().fg(Color::Cyan))]),
+            Line::from(vec![Span::raw("Average Volume: "), Span::styled(format!("${}", number_format::integer(analytics.avg_volume)), Style::default().fg(Color::Cyan))]),
         ];
 
         let stats = Paragraph::new(stats_text)
@@ -1420,7 +1433,7 @@ impl MarketsBrowser {
                 } else if volume_val >= 1_000.0 {
                     format!("${:.1}K", volume_val / 1_000.0)
                 } else {
-                    format!("${:.0}", volume_val)
+                    format!("${}", number_format::integer(volume_val))
                 };
                 
                 // Format liquidity
@@ -1429,7 +1442,7 @@ impl MarketsBrowser {
                 } else if liquidity >= rust_decimal::Decimal::new(1_000, 0) {
                     format!("${:.1}K", liquidity.to_f64().unwrap_or(0.0) / 1_000.0)
                 } else {
-                    format!("${:.0}", liquidity.to_f64().unwrap_or(0.0))
+                    format!("${}", number_format::integer(liquidity.to_f64().unwrap_or(0.0)))
                 };
                 
                 // Truncate question but add "..." if truncated
@@ -1652,3 +1665,32 @@ impl MarketsBrowser {
         }
     }
 }
+
+/// Resolution source, oracle status and rules of a market for the details views
+fn resolution_lines(market: &GammaMarket) -> Vec<Line<'static>> {
+    let resolution = MarketResolution::from_market(market);
+    let state_color = match resolution.state {
+        ResolutionState::Open => Color::Gray,
+        ResolutionState::Proposed => Color::Yellow,
+        ResolutionState::Disputed => Color::Red,
+        ResolutionState::Resolved => Color::Green,
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Resolution: "),
+            Span::styled(resolution.state.to_string(), Style::default().fg(state_color)),
+        ]),
+    ];
+    // The first summary line is the status shown above
+    for line in resolution.summary_lines().into_iter().skip(1) {
+        lines.push(Line::from(format!("  {}", line)));
+    }
+    if let Some(rules) = &resolution.ancillary_data {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Rules:"));
+        lines.extend(rules.lines().map(|line| Line::from(format!("  {}", line))));
+    }
+    lines
+}
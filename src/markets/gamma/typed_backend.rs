@@ -0,0 +1,513 @@
+//! Pure RocksDB backend for the local Gamma database
+//!
+//! Keeps market records and events in their own typed_store column families
+//! under `database/gamma_typed`, so `gamma` works in builds without the
+//! `surrealdb` feature. There is no query engine here: searches and stats
+//! scan the markets column family and filter in memory, which is fine for the
+//! tens of thousands of markets Polymarket lists. Unsorted listings stop
+//! scanning once they have a page, and the market count is kept under a key
+//! of its own.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::define_typed_cf;
+use crate::markets::gamma::store::{
+    market_to_record, record_to_market, DatabaseHealth, GammaBackend, GammaStore, MarketListOrder,
+    MarketListQuery, MarketRecord, MarketStats, SessionEvent, SessionMarket, UpsertCounts,
+};
+use crate::markets::gamma::types::GammaMarket;
+use crate::typed_store::context::TypedDbContext;
+use crate::typed_store::table::TypedCf;
+
+/// An event as stored, with the full API object as JSON in `data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub event_id: String,
+    pub slug: String,
+    pub title: String,
+    pub closed: bool,
+    pub updated_at: Option<String>,
+    pub data: String,
+    pub last_updated_db: String,
+    pub source_session: u32,
+}
+
+define_typed_cf!(GammaMarketCf, String, MarketRecord, "gamma_markets", 0x20); // market_id -> record
+define_typed_cf!(GammaEventCf, String, EventRecord, "gamma_events", 0x21); // event_id -> record
+define_typed_cf!(GammaMetaCf, String, u64, "gamma_meta", 0x22); // counter name -> value
+
+/// Column families of the gamma database
+pub const GAMMA_COLUMN_FAMILIES: &[&str] =
+    &[GammaMarketCf::NAME, GammaEventCf::NAME, GammaMetaCf::NAME];
+
+/// [`GammaMetaCf`] key of the number of stored markets
+const MARKET_COUNT_KEY: &str = "market_count";
+
+/// A record to upsert and the `updated_at` it is compared by
+struct PendingRow<R> {
+    updated_at: Option<DateTime<Utc>>,
+    record: R,
+}
+
+/// Gamma markets and events in typed RocksDB column families
+pub struct TypedGammaStore {
+    ctx: Arc<TypedDbContext>,
+    path: PathBuf,
+    /// Held while upserting markets, so the stored count stays exact
+    market_writes: Arc<Mutex<()>>,
+}
+
+impl TypedGammaStore {
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with(path, false)
+    }
+
+    /// Open the database at `path` for a bulk import, see
+    /// [`TypedDbContext::open_bulk_load`]
+    pub fn open_bulk_load(path: &Path) -> Result<Self> {
+        Self::open_with(path, true)
+    }
+
+    fn open_with(path: &Path, bulk_load: bool) -> Result<Self> {
+        std::fs::create_dir_all(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let column_families = GAMMA_COLUMN_FAMILIES.to_vec();
+        let ctx = if bulk_load {
+            TypedDbContext::open_bulk_load(path, column_families)
+        } else {
+            TypedDbContext::open(path, column_families)
+        }
+        .with_context(|| {
+            format!(
+                "Failed to open gamma database at {}. It might be locked by another process.",
+                path.display()
+            )
+        })?;
+        Ok(Self {
+            ctx: Arc::new(ctx),
+            path: path.to_path_buf(),
+            market_writes: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Run blocking RocksDB work off the async runtime
+    async fn blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&TypedDbContext) -> Result<T> + Send + 'static,
+    {
+        let ctx = self.ctx.clone();
+        tokio::task::spawn_blocking(move || f(&ctx))
+            .await
+            .context("Gamma database task panicked")?
+    }
+
+    async fn records(&self) -> Result<Vec<MarketRecord>> {
+        self.blocking(|ctx| {
+            let entries = ctx
+                .scan::<GammaMarketCf>()
+                .context("Failed to scan markets")?;
+            Ok(entries.into_iter().map(|(_, record)| record).collect())
+        })
+        .await
+    }
+
+    /// The stored market count, counted with a scan and stored when missing
+    fn market_count(ctx: &TypedDbContext) -> Result<u64> {
+        let key = MARKET_COUNT_KEY.to_string();
+        if let Some(count) = ctx.get::<GammaMetaCf>(&key)? {
+            return Ok(count);
+        }
+        let mut count = 0u64;
+        ctx.scan_while::<GammaMarketCf>(0, |_, _| {
+            count += 1;
+            true
+        })
+        .context("Failed to count markets")?;
+        ctx.put::<GammaMetaCf>(&key, &count)?;
+        Ok(count)
+    }
+
+    /// One page of unsorted markets, read in key order up to the page's end
+    fn page(ctx: &TypedDbContext, query: &MarketListQuery) -> Result<Vec<MarketRecord>> {
+        let limit = query.limit.map_or(usize::MAX, |limit| limit as usize);
+        let mut records = Vec::new();
+        if limit == 0 {
+            return Ok(records);
+        }
+        // Without filters the offset is a count of raw entries and needn't be decoded
+        let (skip, mut to_skip) = if query.has_filters() {
+            (0, query.offset as usize)
+        } else {
+            (query.offset as usize, 0)
+        };
+        ctx.scan_while::<GammaMarketCf>(skip, |_, record| {
+            if query.matches(&record) {
+                if to_skip > 0 {
+                    to_skip -= 1;
+                } else {
+                    records.push(record);
+                }
+            }
+            records.len() < limit
+        })
+        .context("Failed to scan markets")?;
+        Ok(records)
+    }
+}
+
+/// Whether a row updated at `incoming` may replace one stored as `stored`
+fn is_newer(incoming: Option<DateTime<Utc>>, stored: Option<&str>) -> bool {
+    let stored = stored.and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+    match (incoming, stored) {
+        (Some(incoming), Some(stored)) => incoming >= stored.with_timezone(&Utc),
+        (None, Some(_)) => false,
+        (_, None) => true,
+    }
+}
+
+fn to_markets(records: Vec<MarketRecord>) -> Result<Vec<GammaMarket>> {
+    records.into_iter().map(record_to_market).collect()
+}
+
+/// Sum of a decimal text field over `records`
+fn total(records: &[MarketRecord], field: fn(&MarketRecord) -> &Option<String>) -> Decimal {
+    records
+        .iter()
+        .filter_map(|r| field(r).as_deref()?.parse::<Decimal>().ok())
+        .sum()
+}
+
+#[async_trait]
+impl GammaStore for TypedGammaStore {
+    fn backend(&self) -> GammaBackend {
+        GammaBackend::Rocksdb
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn upsert_markets(&self, markets: &[SessionMarket<'_>]) -> Result<UpsertCounts> {
+        let now = Utc::now();
+        let rows: Vec<PendingRow<MarketRecord>> = markets
+            .iter()
+            .map(|entry| PendingRow {
+                updated_at: Some(entry.market.updated_at),
+                record: market_to_record(entry.market, entry.session_id, now),
+            })
+            .collect();
+        let market_writes = self.market_writes.clone();
+        self.blocking(move |ctx| {
+            let writing = market_writes
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Gamma market writer panicked"))?;
+            let count_key = MARKET_COUNT_KEY.to_string();
+            let stored_count = ctx.get::<GammaMetaCf>(&count_key)?;
+            let mut counts = UpsertCounts::default();
+            ctx.batch_write(|batch| {
+                let mut added = 0u64;
+                for PendingRow { updated_at, mut record } in rows {
+                    match ctx.get::<GammaMarketCf>(&record.market_id)? {
+                        Some(stored) => {
+                            if !is_newer(updated_at, Some(&stored.updated_at)) {
+                                counts.skipped += 1;
+                                continue;
+                            }
+                            record.first_seen = stored.first_seen;
+                        }
+                        None => added += 1,
+                    }
+                    batch.put::<GammaMarketCf>(&record.market_id, &record)?;
+                    counts.stored += 1;
+                }
+                // A missing count is taken with a scan the first time it is read
+                if let Some(count) = stored_count {
+                    batch.put::<GammaMetaCf>(&count_key, &(count + added))?;
+                }
+                Ok(())
+            })
+            .context("Failed to write markets")?;
+            drop(writing);
+            Ok(counts)
+        })
+        .await
+    }
+
+    async fn upsert_events(&self, events: &[SessionEvent<'_>]) -> Result<UpsertCounts> {
+        let now = Utc::now().to_rfc3339();
+        let rows = events
+            .iter()
+            .map(|&SessionEvent { session_id, event }| {
+                let record = EventRecord {
+                    event_id: event.id.0.to_string(),
+                    slug: event.slug.clone(),
+                    title: event.title.clone(),
+                    closed: event.closed,
+                    updated_at: event.updated_at.map(|d| d.to_rfc3339()),
+                    data: serde_json::to_string(event)?,
+                    last_updated_db: now.clone(),
+                    source_session: session_id,
+                };
+                Ok(PendingRow {
+                    updated_at: event.updated_at,
+                    record,
+                })
+            })
+            .collect::<Result<Vec<PendingRow<EventRecord>>>>()?;
+        self.blocking(move |ctx| {
+            let mut counts = UpsertCounts::default();
+            ctx.batch_write(|batch| {
+                for PendingRow { updated_at, record } in rows {
+                    if let Some(stored) = ctx.get::<GammaEventCf>(&record.event_id)? {
+                        if !is_newer(updated_at, stored.updated_at.as_deref()) {
+                            counts.skipped += 1;
+                            continue;
+                        }
+                    }
+                    batch.put::<GammaEventCf>(&record.event_id, &record)?;
+                    counts.stored += 1;
+                }
+                Ok(())
+            })
+            .context("Failed to write events")?;
+            Ok(counts)
+        })
+        .await
+    }
+
+    async fn get_market(&self, market_id: &str) -> Result<Option<GammaMarket>> {
+        let key = market_id.to_string();
+        let record = self
+            .blocking(move |ctx| Ok(ctx.get::<GammaMarketCf>(&key)?))
+            .await?;
+        record.map(record_to_market).transpose()
+    }
+
+    async fn list_markets(&self, query: &MarketListQuery) -> Result<Vec<GammaMarket>> {
+        let query = query.clone();
+        let records = self
+            .blocking(move |ctx| {
+                if query.sort_by.is_none() {
+                    return Self::page(ctx, &query);
+                }
+                // Sorting needs every match, but only matches are kept
+                let mut matches = Vec::new();
+                ctx.scan_while::<GammaMarketCf>(0, |_, record| {
+                    if query.matches(&record) {
+                        matches.push(record);
+                    }
+                    true
+                })
+                .context("Failed to scan markets")?;
+                Ok(query.apply(matches))
+            })
+            .await?;
+        to_markets(records)
+    }
+
+    async fn get_all_markets(&self, limit: Option<u64>) -> Result<Vec<GammaMarket>> {
+        let query = MarketListQuery {
+            limit,
+            ..Default::default()
+        };
+        self.list_markets(&query).await
+    }
+
+    async fn get_market_count(&self) -> Result<u64> {
+        self.blocking(Self::market_count).await
+    }
+
+    async fn get_stats(&self) -> Result<MarketStats> {
+        let records = self.records().await?;
+        Ok(MarketStats {
+            total_markets: records.len() as u64,
+            active_markets: records.iter().filter(|r| r.active).count() as u64,
+            closed_markets: records.iter().filter(|r| r.closed).count() as u64,
+            archived_markets: records.iter().filter(|r| r.archived).count() as u64,
+            total_volume: total(&records, |r| &r.volume).to_string(),
+            total_liquidity: total(&records, |r| &r.liquidity).to_string(),
+            last_updated: Utc::now().to_rfc3339(),
+        })
+    }
+
+    async fn search_markets(&self, query: &str, limit: Option<u64>) -> Result<Vec<GammaMarket>> {
+        let needle = query.to_lowercase();
+        let records: Vec<MarketRecord> = self
+            .records()
+            .await?
+            .into_iter()
+            .filter(|r| {
+                r.question.to_lowercase().contains(&needle)
+                    || r.slug.to_lowercase().contains(&needle)
+            })
+            .collect();
+        let order = MarketListQuery {
+            sort_by: Some(MarketListOrder::Volume),
+            descending: true,
+            limit,
+            ..Default::default()
+        };
+        to_markets(order.apply(records))
+    }
+
+    async fn health_check(&self) -> Result<DatabaseHealth> {
+        let start = Instant::now();
+        let count = self.get_market_count().await;
+        let count_time_ms = start.elapsed().as_millis() as u64;
+        let mut health = DatabaseHealth {
+            is_healthy: count.is_ok(),
+            ping_time_ms: count_time_ms,
+            market_count: None,
+            count_time_ms: Some(count_time_ms),
+            issues: Vec::new(),
+            recommendations: Vec::new(),
+        };
+        match count {
+            Ok(count) => health.market_count = Some(count),
+            Err(e) => {
+                health.issues.push(format!("Failed to read markets: {}", e));
+                health
+                    .recommendations
+                    .push("Check that no other process has the database open".to_string());
+            }
+        }
+        Ok(health)
+    }
+
+    async fn finish_bulk_load(&self) -> Result<()> {
+        self.blocking(|ctx| {
+            ctx.finish_bulk_load()
+                .context("Failed to flush and compact the bulk load")
+        })
+        .await
+    }
+
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn market(id: u64, updated_hour: u32, question: &str, volume: &str) -> GammaMarket {
+        let mut market: GammaMarket = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "conditionId": format!("0x{:x}", id),
+            "slug": format!("market-{}", id),
+            "question": question,
+            "outcomes": "[\"Yes\", \"No\"]",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "volume": volume,
+            "active": id % 2 == 0,
+            "closed": id % 2 == 1,
+            "archived": false,
+            "restricted": false,
+            "cyom": false,
+            "approved": true,
+        }))
+        .unwrap();
+        market.updated_at = Utc
+            .with_ymd_and_hms(2024, 1, 1, updated_hour, 0, 0)
+            .unwrap();
+        market
+    }
+
+    fn from_session(session_id: u32, market: &GammaMarket) -> SessionMarket<'_> {
+        SessionMarket { session_id, market }
+    }
+
+    #[tokio::test]
+    async fn upserts_keep_the_latest_market_and_listings_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TypedGammaStore::open(&dir.path().join("gamma_typed")).unwrap();
+
+        let noon = market(2, 12, "Will it rain?", "500");
+        let morning = market(2, 9, "Will it rain (old)?", "100");
+        let other = market(3, 1, "Will it snow?", "50");
+        let counts = store
+            .upsert_markets(&[from_session(1, &noon), from_session(1, &other)])
+            .await
+            .unwrap();
+        assert_eq!(
+            counts,
+            UpsertCounts {
+                stored: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(store.get_market_count().await.unwrap(), 2);
+        let counts = store
+            .upsert_markets(&[from_session(2, &morning)])
+            .await
+            .unwrap();
+        assert_eq!(
+            counts,
+            UpsertCounts {
+                stored: 0,
+                skipped: 1
+            }
+        );
+
+        let later = market(4, 2, "Will it hail?", "10");
+        store
+            .upsert_markets(&[from_session(3, &later)])
+            .await
+            .unwrap();
+        assert_eq!(store.get_market_count().await.unwrap(), 3);
+
+        let kept = store.get_market("2").await.unwrap().unwrap();
+        assert_eq!(kept.question, "Will it rain?");
+        assert!(store.get_market("9").await.unwrap().is_none());
+
+        let active = MarketListQuery {
+            active_only: true,
+            ..Default::default()
+        };
+        let listed = store.list_markets(&active).await.unwrap();
+        let ids: Vec<u64> = listed.iter().map(|m| m.id.0).collect();
+        assert_eq!(ids, [2, 4]);
+
+        let second_page = MarketListQuery {
+            limit: Some(1),
+            offset: 1,
+            ..Default::default()
+        };
+        let listed = store.list_markets(&second_page).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id.0, 3);
+
+        let by_volume = MarketListQuery {
+            sort_by: Some(MarketListOrder::Volume),
+            ..Default::default()
+        };
+        let ids: Vec<u64> = store
+            .list_markets(&by_volume)
+            .await
+            .unwrap()
+            .iter()
+            .map(|m| m.id.0)
+            .collect();
+        assert_eq!(ids, [4, 3, 2]);
+
+        let found = store.search_markets("snow", None).await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        let stats = store.get_stats().await.unwrap();
+        assert_eq!(stats.total_markets, 3);
+        assert_eq!(stats.active_markets, 2);
+        assert_eq!(stats.total_volume, "560");
+    }
+}
@@ -641,6 +641,59 @@ pub struct GammaTrade {
     pub bio: Option<String>,
 }
 
+/// Kind of a Data API activity entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ActivityType {
+    Trade,
+    Split,
+    Merge,
+    Redeem,
+    Reward,
+    Conversion,
+    #[serde(other)]
+    Other,
+}
+
+impl ActivityType {
+    /// Value of the `type` parameter
+    pub fn as_param(self) -> &'static str {
+        match self {
+            ActivityType::Trade => "TRADE",
+            ActivityType::Split => "SPLIT",
+            ActivityType::Merge => "MERGE",
+            ActivityType::Redeem => "REDEEM",
+            ActivityType::Reward => "REWARD",
+            ActivityType::Conversion => "CONVERSION",
+            ActivityType::Other => "OTHER",
+        }
+    }
+}
+
+/// Account activity from Data API: trades and the on-chain operations that
+/// move positions outside the order book (splits, merges, redeems)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GammaActivity {
+    #[serde(deserialize_with = "serde_helpers::deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    #[serde(rename = "proxyWallet")]
+    pub proxy_wallet: UserAddress,
+    #[serde(rename = "type")]
+    pub activity_type: ActivityType,
+    #[serde(rename = "conditionId")]
+    pub condition_id: ConditionId,
+    /// Shares moved; for a merge, the number of full sets merged
+    #[serde(deserialize_with = "serde_helpers::deserialize_decimal_from_string")]
+    pub size: Decimal,
+    /// USDC paid out or in
+    #[serde(rename = "usdcSize", deserialize_with = "serde_helpers::deserialize_decimal_from_string")]
+    pub usdc_size: Decimal,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: TransactionHash,
+    #[serde(default)]
+    pub title: String,
+}
+
 /// User position from Data API
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GammaPosition {
@@ -696,6 +749,9 @@ pub struct PriceHistory {
 }
 
 /// Query parameters for market fetching
+///
+/// Build with [`MarketQuery::builder`](crate::markets::gamma::query), which
+/// validates the filters.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MarketQuery {
     pub limit: Option<u32>,
@@ -725,6 +781,9 @@ pub struct MarketQuery {
     pub tags: Vec<String>,
     pub tag_slugs: Vec<TagSlug>,
     pub related_tags: Option<bool>,
+    /// Only markets of these events
+    #[serde(default)]
+    pub event_ids: Vec<EventId>,
 }
 
 /// Query parameters for event fetching
@@ -772,6 +831,16 @@ pub struct TradeQuery {
     pub side: Option<TradeSide>,
 }
 
+/// Query parameters for activity fetching
+#[derive(Debug, Clone, Default)]
+pub struct ActivityQuery {
+    pub user: UserAddress,
+    /// Every kind when empty
+    pub types: Vec<ActivityType>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
 /// Query parameters for position fetching
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PositionQuery {
@@ -785,10 +854,59 @@ pub struct PositionQuery {
     pub title: Option<String>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
-    pub sort_by: Option<String>,
+    pub sort_by: Option<PositionOrder>,
     pub sort_direction: Option<String>,
 }
 
+/// Sort field of the positions endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionOrder {
+    Size,
+    CurrentValue,
+    Pnl,
+}
+
+impl PositionOrder {
+    pub const ALL: [PositionOrder; 3] = [
+        PositionOrder::Size,
+        PositionOrder::CurrentValue,
+        PositionOrder::Pnl,
+    ];
+
+    /// Name of the flag value
+    pub fn name(self) -> &'static str {
+        match self {
+            PositionOrder::Size => "size",
+            PositionOrder::CurrentValue => "current_value",
+            PositionOrder::Pnl => "pnl",
+        }
+    }
+
+    /// Value of the `sortBy` parameter
+    pub fn as_param(self) -> &'static str {
+        match self {
+            PositionOrder::Size => "TOKENS",
+            PositionOrder::CurrentValue => "CURRENT",
+            PositionOrder::Pnl => "CASHPNL",
+        }
+    }
+}
+
+impl std::str::FromStr for PositionOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().replace('-', "_").to_ascii_lowercase();
+        PositionOrder::ALL
+            .into_iter()
+            .find(|order| order.name() == normalized)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = PositionOrder::ALL.iter().map(|o| o.name()).collect();
+                format!("unknown sort field '{}' (use {})", s, valid.join(", "))
+            })
+    }
+}
+
 /// Query parameters for price history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceHistoryQuery {
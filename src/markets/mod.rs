@@ -7,6 +7,8 @@
 //! - **Gamma**: Gamma API client and related functionality  
 //! - **Gamma API**: Enhanced gamma API operations and storage
 //! - **Search**: Indexed search capabilities for market data
+//! - **Snapshot Diff**: Before/after view-model of a market's price, volume and top of book
+//! - **Suppression**: Microcap and spam markets left out of screens, watchlists and streams
 
 pub mod clob;
 pub mod datasets;
@@ -14,6 +16,8 @@ pub mod file_store;
 pub mod gamma;
 pub mod gamma_api;
 pub mod search;
+pub mod snapshot_diff;
+pub mod suppression;
 
 // Re-export commonly used functions from CLOB
 pub use clob::{
@@ -3,6 +3,7 @@
 //! This module provides lightning-fast search capabilities using Milli embedded directly
 //! in the application. No external dependencies or servers required.
 
+#[cfg(feature = "surrealdb")]
 pub mod milli_service;
 pub mod search_types;
 
@@ -0,0 +1,597 @@
+//! What changed in a market between two points in time
+//!
+//! A `MarketSnapshot` is what is known about a market at one moment: price,
+//! volume and the top of its book. `SnapshotDiff::between` compares two of
+//! them into a view-model that the GUI (`gui::components::snapshot_diff`) and
+//! the TUI (`tui::widgets::snapshot_diff`) both render as a compact card, so
+//! a change reads the same wherever it shows up:
+//!
+//! - the datasets TUI diffs the markets of two enriched datasets
+//! - the GUI notifications center attaches each alert's and fill's recent change
+//! - the TUI markets page shows the selected token's recent change
+//!
+//! Live views keep a `SnapshotHistory` of periodic samples per token to have
+//! a "before" to compare the current book with.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::core::types::market::PriceLevel;
+use crate::core::ws::OrderBook;
+use crate::markets::clob::enrich::{DepthLevel, EnrichedMarket};
+use crate::typed_store::models::{BookSnapshot, BookSnapshotLevel};
+
+/// Book levels per side kept in a snapshot
+const SNAPSHOT_LEVELS: usize = 10;
+
+/// Top levels per side compared in a diff card
+pub const DIFF_LEVELS: usize = 3;
+
+/// Price, volume and top of book of a market at one moment
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSnapshot {
+    pub captured_at: DateTime<Utc>,
+    /// Mid of the book; enriched markets without depth fall back to their
+    /// recorded mid or token price
+    pub price: Option<Decimal>,
+    pub volume: Option<Decimal>,
+    /// Best (highest) bid first
+    pub bids: Vec<BookSnapshotLevel>,
+    /// Best (lowest) ask first
+    pub asks: Vec<BookSnapshotLevel>,
+}
+
+impl MarketSnapshot {
+    /// Snapshot from book levels given best first; the price is their mid
+    pub fn from_levels(
+        captured_at: DateTime<Utc>,
+        bids: impl IntoIterator<Item = PriceLevel>,
+        asks: impl IntoIterator<Item = PriceLevel>,
+        volume: Option<Decimal>,
+    ) -> Self {
+        let bids = Self::top_levels(bids);
+        let asks = Self::top_levels(asks);
+        let price = match (bids.first(), asks.first()) {
+            (Some(bid), Some(ask)) => Some((bid.price + ask.price) / Decimal::TWO),
+            _ => None,
+        };
+        Self {
+            captured_at,
+            price,
+            volume,
+            bids,
+            asks,
+        }
+    }
+
+    /// Snapshot of a live order book
+    pub fn from_order_book(
+        book: &OrderBook,
+        volume: Option<Decimal>,
+        captured_at: DateTime<Utc>,
+    ) -> Self {
+        Self::from_levels(
+            captured_at,
+            book.bids.iter_from_best(),
+            book.asks.iter_from_best(),
+            volume,
+        )
+    }
+
+    /// Snapshot of an enriched market's first token, as of its enrichment
+    pub fn from_enriched(market: &EnrichedMarket) -> Self {
+        let decimal = |value: f64| Decimal::try_from(value).ok();
+        let levels = |levels: &[DepthLevel]| {
+            levels
+                .iter()
+                .map(|level| PriceLevel::new(level.price, level.size))
+                .collect::<Vec<_>>()
+        };
+        let depth = market.enrichment.depth.clone().unwrap_or_default();
+        let volume = market
+            .enrichment
+            .volume
+            .as_ref()
+            .and_then(|volume| volume.volume_24hr.or(volume.volume_total))
+            .or_else(|| {
+                ["volume24hr", "volume"]
+                    .iter()
+                    .find_map(|key| market.market.additional_fields.get(*key)?.as_f64())
+            })
+            .and_then(decimal);
+
+        let mut snapshot = Self::from_levels(
+            market.enriched_at,
+            levels(&depth.bids),
+            levels(&depth.asks),
+            volume,
+        );
+        if snapshot.price.is_none() {
+            snapshot.price = market
+                .enrichment
+                .orderbook
+                .as_ref()
+                .map(|book| book.mid_price)
+                .or_else(|| market.market.tokens.first()?.price)
+                .and_then(decimal);
+        }
+        snapshot
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.asks.first()?.price - self.bids.first()?.price)
+    }
+
+    fn top_levels(levels: impl IntoIterator<Item = PriceLevel>) -> Vec<BookSnapshotLevel> {
+        levels
+            .into_iter()
+            .take(SNAPSHOT_LEVELS)
+            .map(|level| BookSnapshotLevel {
+                price: level.price,
+                size: level.size,
+            })
+            .collect()
+    }
+}
+
+impl From<&BookSnapshot> for MarketSnapshot {
+    fn from(snapshot: &BookSnapshot) -> Self {
+        Self::from_levels(
+            snapshot.captured_at,
+            snapshot.bids.iter().map(|l| PriceLevel::new(l.price, l.size)),
+            snapshot.asks.iter().map(|l| PriceLevel::new(l.price, l.size)),
+            None,
+        )
+    }
+}
+
+/// Direction of a change, for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Up,
+    Down,
+    Flat,
+    /// Known now, unknown before
+    Added,
+    /// Known before, gone now
+    Removed,
+}
+
+/// A value before and after
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub before: Option<Decimal>,
+    pub after: Option<Decimal>,
+}
+
+impl Change {
+    pub fn delta(&self) -> Option<Decimal> {
+        Some(self.after? - self.before?)
+    }
+
+    /// Relative change in percent, when there was a non-zero value before
+    pub fn pct(&self) -> Option<Decimal> {
+        let before = self.before.filter(|before| !before.is_zero())?;
+        Some(self.delta()? / before * Decimal::ONE_HUNDRED)
+    }
+
+    /// `None` when the value is unknown on both sides
+    pub fn trend(&self) -> Option<Trend> {
+        match (self.before, self.after) {
+            (Some(before), Some(after)) => Some(if after > before {
+                Trend::Up
+            } else if after < before {
+                Trend::Down
+            } else {
+                Trend::Flat
+            }),
+            (None, Some(_)) => Some(Trend::Added),
+            (Some(_), None) => Some(Trend::Removed),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Size resting at one price before and after; zero where there was none
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelChange {
+    pub price: Decimal,
+    pub before: Decimal,
+    pub after: Decimal,
+}
+
+impl LevelChange {
+    /// Sizes at the prices of the top `levels` of either side, best first
+    fn between(
+        before: &[BookSnapshotLevel],
+        after: &[BookSnapshotLevel],
+        levels: usize,
+        descending: bool,
+    ) -> Vec<Self> {
+        let size_at = |book: &[BookSnapshotLevel], price: Decimal| {
+            book.iter()
+                .find(|level| level.price == price)
+                .map(|level| level.size)
+                .unwrap_or_default()
+        };
+        let mut prices: Vec<Decimal> = before
+            .iter()
+            .take(levels)
+            .chain(after.iter().take(levels))
+            .map(|level| level.price)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        prices.sort();
+        if descending {
+            prices.reverse();
+        }
+        prices
+            .into_iter()
+            .map(|price| Self {
+                price,
+                before: size_at(before, price),
+                after: size_at(after, price),
+            })
+            .collect()
+    }
+
+    fn change(&self) -> Change {
+        let present = |size: Decimal| (!size.is_zero()).then_some(size);
+        Change {
+            before: present(self.before),
+            after: present(self.after),
+        }
+    }
+}
+
+/// One line of a diff card, already formatted
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffRow {
+    pub label: String,
+    pub before: String,
+    pub after: String,
+    pub delta: String,
+    pub trend: Option<Trend>,
+}
+
+impl DiffRow {
+    /// Row of a value rounded to `decimals`, with the relative change if `with_pct`
+    fn of(label: &str, change: Change, decimals: u32, with_pct: bool) -> Self {
+        let value = |value: Option<Decimal>| {
+            value
+                .map(|v| v.round_dp(decimals).normalize().to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let delta = match change.delta() {
+            Some(delta) => {
+                let delta = delta.round_dp(decimals).normalize();
+                let sign = if delta > Decimal::ZERO { "+" } else { "" };
+                match change.pct().filter(|_| with_pct) {
+                    Some(pct) => format!(
+                        "{}{} ({}{}%)",
+                        sign,
+                        delta,
+                        sign,
+                        pct.round_dp(1).normalize()
+                    ),
+                    None => format!("{}{}", sign, delta),
+                }
+            }
+            None => String::new(),
+        };
+        Self {
+            label: label.to_string(),
+            before: value(change.before),
+            after: value(change.after),
+            delta,
+            trend: change.trend(),
+        }
+    }
+}
+
+/// Changes of one market between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub title: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub price: Change,
+    pub spread: Change,
+    pub volume: Change,
+    /// Top bid levels of either snapshot, best first
+    pub bids: Vec<LevelChange>,
+    /// Top ask levels of either snapshot, best first
+    pub asks: Vec<LevelChange>,
+}
+
+impl SnapshotDiff {
+    /// Compare `before` with `after`, keeping the top `levels` of each side
+    pub fn between(
+        title: impl Into<String>,
+        before: &MarketSnapshot,
+        after: &MarketSnapshot,
+        levels: usize,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            from: before.captured_at,
+            to: after.captured_at,
+            price: Change {
+                before: before.price,
+                after: after.price,
+            },
+            spread: Change {
+                before: before.spread(),
+                after: after.spread(),
+            },
+            volume: Change {
+                before: before.volume,
+                after: after.volume,
+            },
+            bids: LevelChange::between(&before.bids, &after.bids, levels, true),
+            asks: LevelChange::between(&before.asks, &after.asks, levels, false),
+        }
+    }
+
+    /// Nothing moved: same price, spread, volume and top levels
+    pub fn is_unchanged(&self) -> bool {
+        [self.price, self.spread, self.volume]
+            .iter()
+            .all(|change| change.before == change.after)
+            && self
+                .bids
+                .iter()
+                .chain(&self.asks)
+                .all(|level| level.before == level.after)
+    }
+
+    /// Absolute price change, for ranking cards
+    pub fn price_move(&self) -> Decimal {
+        self.price.delta().map(|d| d.abs()).unwrap_or_default()
+    }
+
+    /// `14:02 → 14:17 (15m)`, with dates when the snapshots are days apart
+    pub fn period(&self) -> String {
+        let elapsed = self.to - self.from;
+        let format = if elapsed >= Duration::days(1) {
+            "%Y-%m-%d %H:%M"
+        } else {
+            "%H:%M:%S"
+        };
+        format!(
+            "{} → {} ({})",
+            self.from.format(format),
+            self.to.format(format),
+            Self::format_elapsed(elapsed)
+        )
+    }
+
+    fn format_elapsed(elapsed: Duration) -> String {
+        let minutes = elapsed.num_minutes();
+        if minutes < 1 {
+            format!("{}s", elapsed.num_seconds().max(0))
+        } else if minutes < 60 {
+            format!("{}m", minutes)
+        } else if minutes < 60 * 24 {
+            format!("{}h {}m", minutes / 60, minutes % 60)
+        } else {
+            format!("{}d {}h", minutes / (60 * 24), minutes / 60 % 24)
+        }
+    }
+
+    /// Price, spread and volume, then the top levels that changed
+    pub fn rows(&self) -> Vec<DiffRow> {
+        let mut rows = vec![
+            DiffRow::of("Price", self.price, 4, true),
+            DiffRow::of("Spread", self.spread, 4, false),
+            DiffRow::of("Volume", self.volume, 0, true),
+        ];
+        for (side, levels) in [("Bid", &self.bids), ("Ask", &self.asks)] {
+            for level in levels.iter().filter(|level| level.before != level.after) {
+                let mut row = DiffRow::of(
+                    &format!("{} {}", side, level.price.normalize()),
+                    level.change(),
+                    2,
+                    false,
+                );
+                if row.before == "-" {
+                    row.before = "new".to_string();
+                }
+                if row.after == "-" {
+                    row.after = "gone".to_string();
+                }
+                rows.push(row);
+            }
+        }
+        rows
+    }
+}
+
+/// Markets of two datasets compared by condition ID
+#[derive(Debug, Clone, Default)]
+pub struct MarketsDiff {
+    /// Markets in both that changed, biggest price move first
+    pub changed: Vec<SnapshotDiff>,
+    pub unchanged: usize,
+    /// Only in the newer dataset
+    pub added: usize,
+    /// Only in the older dataset
+    pub removed: usize,
+}
+
+impl MarketsDiff {
+    /// Compare the markets of an older and a newer enriched dataset
+    pub fn between(before: &[EnrichedMarket], after: &[EnrichedMarket]) -> Self {
+        let key = |market: &EnrichedMarket| market.market.condition_id.clone();
+        let earlier: HashMap<String, &EnrichedMarket> = before
+            .iter()
+            .filter_map(|market| Some((key(market)?, market)))
+            .collect();
+
+        let mut diff = Self::default();
+        let mut matched = HashSet::new();
+        for market in after {
+            let Some(condition_id) = key(market) else {
+                continue;
+            };
+            let Some(previous) = earlier.get(&condition_id) else {
+                diff.added += 1;
+                continue;
+            };
+            matched.insert(condition_id.clone());
+            let title = market.market.question.clone().unwrap_or(condition_id);
+            let card = SnapshotDiff::between(
+                title,
+                &MarketSnapshot::from_enriched(previous),
+                &MarketSnapshot::from_enriched(market),
+                DIFF_LEVELS,
+            );
+            if card.is_unchanged() {
+                diff.unchanged += 1;
+            } else {
+                diff.changed.push(card);
+            }
+        }
+        diff.removed = earlier.len() - matched.len();
+        diff.changed
+            .sort_by(|a, b| b.price_move().cmp(&a.price_move()));
+        diff
+    }
+}
+
+/// Periodic snapshots per token, to compare the current book with an earlier one
+#[derive(Debug)]
+pub struct SnapshotHistory {
+    every: Duration,
+    keep: Duration,
+    samples: HashMap<String, VecDeque<MarketSnapshot>>,
+}
+
+impl Default for SnapshotHistory {
+    /// A sample every 30 seconds, kept for an hour
+    fn default() -> Self {
+        Self::new(Duration::seconds(30), Duration::hours(1))
+    }
+}
+
+impl SnapshotHistory {
+    pub fn new(every: Duration, keep: Duration) -> Self {
+        Self {
+            every,
+            keep,
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Whether a sample of `token_id` taken `now` would be kept; check before
+    /// building one from a live book
+    pub fn is_due(&self, token_id: &str, now: DateTime<Utc>) -> bool {
+        self.samples
+            .get(token_id)
+            .and_then(|samples| samples.back())
+            .map_or(true, |last| now - last.captured_at >= self.every)
+    }
+
+    /// Keep `snapshot` if it is due, dropping samples older than the retention
+    pub fn record(&mut self, token_id: &str, snapshot: MarketSnapshot) {
+        if !self.is_due(token_id, snapshot.captured_at) {
+            return;
+        }
+        let cutoff = snapshot.captured_at - self.keep;
+        let samples = self.samples.entry(token_id.to_string()).or_default();
+        samples.push_back(snapshot);
+        while samples.front().is_some_and(|s| s.captured_at < cutoff) {
+            samples.pop_front();
+        }
+    }
+
+    /// Change of `token_id` from the sample about `window` before `current`
+    /// (or the oldest one, if the history is shorter) to `current`
+    pub fn diff(
+        &self,
+        token_id: &str,
+        title: impl Into<String>,
+        current: &MarketSnapshot,
+        window: Duration,
+    ) -> Option<SnapshotDiff> {
+        let samples = self.samples.get(token_id)?;
+        let target = current.captured_at - window;
+        let before = samples
+            .iter()
+            .rev()
+            .find(|sample| sample.captured_at <= target)
+            .or_else(|| samples.front())
+            .filter(|sample| sample.captured_at < current.captured_at)?;
+        Some(SnapshotDiff::between(title, before, current, DIFF_LEVELS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(minutes_ago: i64, bid: Decimal, ask: Decimal, volume: Decimal) -> MarketSnapshot {
+        MarketSnapshot::from_levels(
+            Utc::now() - Duration::minutes(minutes_ago),
+            [
+                PriceLevel::new(bid, dec!(100)),
+                PriceLevel::new(bid - dec!(0.01), dec!(50)),
+            ],
+            [PriceLevel::new(ask, dec!(80))],
+            Some(volume),
+        )
+    }
+
+    #[test]
+    fn diff_rows_show_price_volume_and_moved_levels() {
+        let before = snapshot(15, dec!(0.48), dec!(0.50), dec!(1000));
+        let after = snapshot(0, dec!(0.52), dec!(0.54), dec!(1500));
+        let diff = SnapshotDiff::between("Will it rain?", &before, &after, DIFF_LEVELS);
+
+        assert_eq!(diff.price.delta(), Some(dec!(0.04)));
+        assert_eq!(diff.price_move(), dec!(0.04));
+        assert!(!diff.is_unchanged());
+
+        let rows = diff.rows();
+        assert_eq!(rows[0].label, "Price");
+        assert_eq!(rows[0].delta, "+0.04 (+8.2%)");
+        assert_eq!(rows[0].trend, Some(Trend::Up));
+        assert_eq!(rows[1].trend, Some(Trend::Flat));
+        assert_eq!(rows[2].delta, "+500 (+50%)");
+        let new_bid = rows.iter().find(|row| row.label == "Bid 0.52").unwrap();
+        assert_eq!(
+            (new_bid.before.as_str(), new_bid.trend),
+            ("new", Some(Trend::Added))
+        );
+        let old_bid = rows.iter().find(|row| row.label == "Bid 0.48").unwrap();
+        assert_eq!(old_bid.after, "gone");
+
+        assert!(SnapshotDiff::between("same", &before, &before, DIFF_LEVELS).is_unchanged());
+    }
+
+    #[test]
+    fn history_compares_with_the_sample_before_the_window() {
+        let mut history = SnapshotHistory::default();
+        history.record("t", snapshot(40, dec!(0.40), dec!(0.42), dec!(10)));
+        history.record("t", snapshot(20, dec!(0.45), dec!(0.47), dec!(20)));
+        // Too soon after the previous sample
+        history.record("t", snapshot(20, dec!(0.30), dec!(0.32), dec!(30)));
+        history.record("t", snapshot(5, dec!(0.46), dec!(0.48), dec!(30)));
+
+        let current = snapshot(0, dec!(0.50), dec!(0.52), dec!(40));
+        let diff = history
+            .diff("t", "t", &current, Duration::minutes(15))
+            .unwrap();
+        assert_eq!(diff.price.before, Some(dec!(0.46)));
+        let long = history
+            .diff("t", "t", &current, Duration::hours(3))
+            .unwrap();
+        assert_eq!(long.price.before, Some(dec!(0.41)));
+        assert!(history
+            .diff("other", "o", &current, Duration::minutes(15))
+            .is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! Microcap and spam market suppression
+//!
+//! Thousands of markets never trade or exist only to spam the listings.
+//! `<data_dir>/suppression.yaml` sets a floor on volume and liquidity and
+//! blacklists creators and slugs; markets it suppresses are left out of
+//! market screens (`datasets create-from-search`, the canvas search form),
+//! starter watchlists and the token sets `polybot stream` subscribes to:
+//!
+//! ```yaml
+//! enabled: true
+//! min_volume: 1000        # lifetime volume, USDC
+//! min_liquidity: 100      # USDC
+//! creators:
+//!   - "0xabc..."
+//! slugs:
+//!   - will-elon-tweet-*   # a trailing * matches a prefix
+//! ```
+//!
+//! A market missing a figure isn't suppressed for it: only what's known
+//! counts against it. Tokens the local store doesn't know are kept for the
+//! same reason.
+
+use anyhow::{Context, Result};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::GammaMarket;
+use crate::number_format;
+use crate::typed_store::models::RocksDbMarket;
+use crate::typed_store::token_info::{self, TokenInfo};
+
+/// Name of the suppression configuration file inside the data directory
+pub const SUPPRESSION_CONFIG_FILE: &str = "suppression.yaml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuppressionConfig {
+    pub enabled: bool,
+    /// Lifetime volume a market needs, in USDC
+    pub min_volume: Option<f64>,
+    /// Liquidity a market needs, in USDC
+    pub min_liquidity: Option<f64>,
+    /// Creator addresses whose markets are suppressed
+    pub creators: Vec<String>,
+    /// Market slugs suppressed; a trailing `*` matches every slug with that prefix
+    pub slugs: Vec<String>,
+}
+
+impl Default for SuppressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_volume: None,
+            min_liquidity: None,
+            creators: Vec::new(),
+            slugs: Vec::new(),
+        }
+    }
+}
+
+/// What a market is judged on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarketFacts<'a> {
+    pub volume: Option<f64>,
+    pub liquidity: Option<f64>,
+    pub creator: Option<&'a str>,
+    pub slug: Option<&'a str>,
+}
+
+impl<'a> From<&'a GammaMarket> for MarketFacts<'a> {
+    fn from(market: &'a GammaMarket) -> Self {
+        Self {
+            volume: market.volume().to_f64(),
+            liquidity: market.liquidity.and_then(|l| l.to_f64()),
+            creator: market.creator.as_deref(),
+            slug: Some(market.slug.as_str()),
+        }
+    }
+}
+
+impl<'a> From<&'a RocksDbMarket> for MarketFacts<'a> {
+    fn from(market: &'a RocksDbMarket) -> Self {
+        Self {
+            volume: market.volume,
+            liquidity: market.liquidity,
+            creator: market.creator.as_deref(),
+            slug: market.market_slug.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a TokenInfo> for MarketFacts<'a> {
+    fn from(info: &'a TokenInfo) -> Self {
+        Self {
+            volume: info.volume,
+            liquidity: info.liquidity,
+            creator: info.creator.as_deref(),
+            slug: info.market_slug.as_deref(),
+        }
+    }
+}
+
+/// Why a market is suppressed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuppressReason {
+    LowVolume(f64),
+    LowLiquidity(f64),
+    Creator(String),
+    Slug(String),
+}
+
+impl fmt::Display for SuppressReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SuppressReason::LowVolume(volume) => {
+                write!(f, "volume ${}", number_format::integer(*volume))
+            }
+            SuppressReason::LowLiquidity(liquidity) => {
+                write!(f, "liquidity ${}", number_format::integer(*liquidity))
+            }
+            SuppressReason::Creator(creator) => write!(f, "blacklisted creator {}", creator),
+            SuppressReason::Slug(slug) => write!(f, "blacklisted slug {}", slug),
+        }
+    }
+}
+
+/// Tokens split by the suppression list
+#[derive(Debug, Clone, Default)]
+pub struct TokenScreen {
+    pub kept: Vec<String>,
+    pub suppressed: Vec<SuppressedToken>,
+}
+
+/// A token left out by the suppression list and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuppressedToken {
+    pub token_id: String,
+    pub reason: SuppressReason,
+}
+
+impl SuppressionConfig {
+    /// Load `suppression.yaml` from the data directory, or defaults when it
+    /// doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(SUPPRESSION_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid suppression config {}", path.display()))
+    }
+
+    /// Whether the file sets anything that can suppress a market
+    pub fn is_active(&self) -> bool {
+        self.enabled
+            && (self.min_volume.is_some()
+                || self.min_liquidity.is_some()
+                || !self.creators.is_empty()
+                || !self.slugs.is_empty())
+    }
+
+    /// Why `market` is suppressed, or `None` when it's kept
+    pub fn check<'a>(&self, market: impl Into<MarketFacts<'a>>) -> Option<SuppressReason> {
+        if !self.enabled {
+            return None;
+        }
+        let market = market.into();
+        if let Some(creator) = market.creator {
+            if self
+                .creators
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(creator))
+            {
+                return Some(SuppressReason::Creator(creator.to_string()));
+            }
+        }
+        if let Some(slug) = market.slug {
+            if self.blacklists_slug(slug) {
+                return Some(SuppressReason::Slug(slug.to_string()));
+            }
+        }
+        match (self.min_volume, market.volume) {
+            (Some(min), Some(volume)) if volume < min => {
+                return Some(SuppressReason::LowVolume(volume));
+            }
+            _ => {}
+        }
+        match (self.min_liquidity, market.liquidity) {
+            (Some(min), Some(liquidity)) if liquidity < min => {
+                Some(SuppressReason::LowLiquidity(liquidity))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `market` passes the suppression list
+    pub fn keeps<'a>(&self, market: impl Into<MarketFacts<'a>>) -> bool {
+        self.check(market).is_none()
+    }
+
+    /// Split `tokens` by their markets in the store at `db_path`, keeping
+    /// their order
+    pub fn screen_tokens(&self, db_path: &Path, tokens: Vec<String>) -> Result<TokenScreen> {
+        if !self.is_active() {
+            return Ok(TokenScreen {
+                kept: tokens,
+                suppressed: Vec::new(),
+            });
+        }
+        let infos = token_info::lookup_all(db_path, tokens.iter().map(String::as_str))
+            .context("Failed to look up token markets")?;
+        let mut screen = TokenScreen::default();
+        for token in tokens {
+            match infos.get(&token).and_then(|info| self.check(info)) {
+                Some(reason) => screen.suppressed.push(SuppressedToken {
+                    token_id: token,
+                    reason,
+                }),
+                None => screen.kept.push(token),
+            }
+        }
+        Ok(screen)
+    }
+
+    /// Whether a `slugs` pattern matches `slug`
+    fn blacklists_slug(&self, slug: &str) -> bool {
+        self.slugs.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => slug.starts_with(prefix),
+            None => slug == pattern,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markets_are_suppressed_by_floor_and_blacklist() {
+        let config: SuppressionConfig = serde_yaml::from_str(
+            "min_volume: 1000\n\
+             min_liquidity: 50\n\
+             creators: ['0xSPAM']\n\
+             slugs: ['will-elon-tweet-*', 'gm']\n",
+        )
+        .unwrap();
+        let market = |volume, liquidity, creator, slug| MarketFacts {
+            volume,
+            liquidity,
+            creator,
+            slug,
+        };
+
+        assert!(config.keeps(market(Some(5000.0), Some(200.0), None, Some("fed-cut"))));
+        assert_eq!(
+            config.check(market(Some(10.0), Some(200.0), None, None)),
+            Some(SuppressReason::LowVolume(10.0))
+        );
+        assert_eq!(
+            config.check(market(Some(5000.0), Some(20.0), None, None)),
+            Some(SuppressReason::LowLiquidity(20.0))
+        );
+        assert!(matches!(
+            config.check(market(Some(5000.0), None, Some("0xspam"), None)),
+            Some(SuppressReason::Creator(_))
+        ));
+        assert!(matches!(
+            config.check(market(None, None, None, Some("will-elon-tweet-100-times"))),
+            Some(SuppressReason::Slug(_))
+        ));
+        assert!(config.keeps(market(None, None, None, Some("gm-2026"))));
+        // Unknown figures don't count against a market
+        assert!(config.keeps(market(None, None, None, None)));
+
+        let disabled = SuppressionConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(!disabled.is_active());
+        assert!(disabled.keeps(market(Some(0.0), Some(0.0), Some("0xspam"), None)));
+        assert!(!SuppressionConfig::default().is_active());
+    }
+}
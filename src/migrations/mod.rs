@@ -0,0 +1,546 @@
+//! Versioned migrations for on-disk data formats
+//!
+//! Every storage component (portfolio files, typed_store RocksDB schemas,
+//! dataset metadata) carries its own format version, recorded in
+//! `<data_dir>/format_version.json`. At startup the runner compares the
+//! recorded versions with the versions this binary understands:
+//!
+//! - older versions are migrated step by step, after backing up the files the
+//!   step will touch into `<data_dir>/backups/migrations/`
+//! - newer (unknown) versions abort startup, so an old binary never rewrites
+//!   data produced by a newer one
+//! - long-running steps are deferred: startup leaves them pending and only
+//!   `polybot index` runs them
+//!
+//! See README.md for how to add a migration step.
+
+pub mod steps;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use crate::data_paths::DataPaths;
+
+/// Name of the version manifest inside the data directory
+pub const FORMAT_MANIFEST_FILE: &str = "format_version.json";
+
+/// Directory (relative to the data directory) holding pre-migration backups
+pub const MIGRATION_BACKUP_DIR: &str = "backups/migrations";
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("{component} data is at format version {found}, but this polybot build only supports up to version {supported}. Upgrade polybot or use a different --data-dir.")]
+    UnsupportedVersion {
+        component: StorageComponent,
+        found: u32,
+        supported: u32,
+    },
+    #[error("No migration registered for {component} from version {from}")]
+    MissingStep { component: StorageComponent, from: u32 },
+    #[error("Migration '{name}' failed: {reason}")]
+    StepFailed { name: &'static str, reason: String },
+    #[error("I/O error during migration: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid format manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+}
+
+/// Independently versioned on-disk storage components
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageComponent {
+    /// Portfolio snapshots, trade history and caches (`trade/`, `raw/`, `cache/`)
+    Portfolio,
+    /// Typed RocksDB market index (`database/rocksdb`), and the file store
+    /// hierarchy it replaced (`database/markets`)
+    TypedStore,
+    /// Dataset metadata files (`datasets/**/dataset.yaml`)
+    Datasets,
+}
+
+impl StorageComponent {
+    /// All components, in migration order
+    pub const ALL: [StorageComponent; 3] = [
+        StorageComponent::Portfolio,
+        StorageComponent::TypedStore,
+        StorageComponent::Datasets,
+    ];
+
+    /// Latest format version this build reads and writes
+    pub fn current_version(&self) -> u32 {
+        match self {
+            StorageComponent::Portfolio => 1,
+            StorageComponent::TypedStore => 2,
+            StorageComponent::Datasets => 1,
+        }
+    }
+
+    /// Whether any data for this component exists in the data directory
+    pub fn has_data(&self, data_paths: &DataPaths) -> bool {
+        let root = data_paths.root();
+        match self {
+            StorageComponent::Portfolio => {
+                root.join("trade").exists() || root.join("raw").exists() || root.join("cache").exists()
+            }
+            StorageComponent::TypedStore => {
+                root.join("database").join("rocksdb").exists() || root.join("database").join("markets").exists()
+            }
+            StorageComponent::Datasets => fs::read_dir(data_paths.datasets())
+                .map(|mut entries| {
+                    entries.any(|entry| {
+                        entry
+                            .map(|e| e.file_name() != crate::data_paths::RUNS_DIR)
+                            .unwrap_or(false)
+                    })
+                })
+                .unwrap_or(false),
+        }
+    }
+}
+
+impl std::fmt::Display for StorageComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            StorageComponent::Portfolio => "portfolio",
+            StorageComponent::TypedStore => "typed_store",
+            StorageComponent::Datasets => "datasets",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Contents of `format_version.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormatManifest {
+    /// Format version per component
+    pub components: BTreeMap<StorageComponent, u32>,
+    /// Last time the manifest was written
+    pub updated_at: Option<DateTime<Utc>>,
+    /// polybot version that last wrote the manifest
+    pub written_by: Option<String>,
+}
+
+impl FormatManifest {
+    /// Path of the manifest for a data directory
+    pub fn path(data_paths: &DataPaths) -> PathBuf {
+        data_paths.root().join(FORMAT_MANIFEST_FILE)
+    }
+
+    /// Load the manifest, returning `None` for data directories that predate it
+    pub fn load(data_paths: &DataPaths) -> Result<Option<Self>, MigrationError> {
+        let path = Self::path(data_paths);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Write the manifest atomically (temp file + rename)
+    pub fn save(&mut self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+        self.updated_at = Some(Utc::now());
+        self.written_by = Some(env!("CARGO_PKG_VERSION").to_string());
+
+        let path = Self::path(data_paths);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Recorded version of a component; missing entries are legacy (version 0)
+    pub fn version_of(&self, component: StorageComponent) -> u32 {
+        self.components.get(&component).copied().unwrap_or(0)
+    }
+}
+
+/// A single format upgrade for one component, from `from_version` to `from_version + 1`
+pub trait Migration: Send + Sync {
+    /// Stable identifier used in logs and backup directory names
+    fn name(&self) -> &'static str;
+
+    /// Component this step upgrades
+    fn component(&self) -> StorageComponent;
+
+    /// Version this step upgrades from
+    fn from_version(&self) -> u32;
+
+    /// Files or directories this step rewrites; they are backed up first
+    fn backup_paths(&self, data_paths: &DataPaths) -> Vec<PathBuf>;
+
+    /// Apply the migration
+    fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError>;
+
+    /// Whether every command may run the step before it starts; a step that
+    /// can take long or needs exclusive access to a store returns false and
+    /// is left to `polybot index`
+    fn runs_at_startup(&self, _data_paths: &DataPaths) -> bool {
+        true
+    }
+}
+
+/// Summary of a single applied step
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub name: &'static str,
+    pub component: StorageComponent,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub backup_dir: Option<PathBuf>,
+}
+
+/// A step the data directory still needs
+#[derive(Debug, Clone)]
+pub struct PendingMigration {
+    pub name: &'static str,
+    pub component: StorageComponent,
+    pub from_version: u32,
+    /// Whether the next command runs it, rather than only `polybot index`
+    pub runs_at_startup: bool,
+}
+
+/// Detects outdated formats and runs registered migrations
+pub struct MigrationRunner {
+    data_paths: DataPaths,
+    migrations: Vec<Box<dyn Migration>>,
+}
+
+impl MigrationRunner {
+    /// Create a runner with all registered migration steps
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self::with_migrations(data_paths, steps::registered_migrations())
+    }
+
+    /// Create a runner with an explicit set of steps
+    pub fn with_migrations(data_paths: &DataPaths, migrations: Vec<Box<dyn Migration>>) -> Self {
+        Self {
+            data_paths: data_paths.clone(),
+            migrations,
+        }
+    }
+
+    /// Bring every component up to the current version, or refuse if the data
+    /// is newer; deferred steps, and the later steps of their component, are
+    /// left pending
+    pub fn run_startup(&self) -> Result<Vec<AppliedMigration>, MigrationError> {
+        self.run(false)
+    }
+
+    /// Like [`Self::run_startup`], but deferred steps run too
+    pub fn run_all(&self) -> Result<Vec<AppliedMigration>, MigrationError> {
+        self.run(true)
+    }
+
+    /// Steps the data directory still needs, in the order they would run;
+    /// nothing is changed on disk
+    pub fn pending(&self) -> Result<Vec<PendingMigration>, MigrationError> {
+        let Some(manifest) = self.manifest()? else {
+            return Ok(Vec::new());
+        };
+        let mut pending = Vec::new();
+        for component in StorageComponent::ALL {
+            for from in manifest.version_of(component)..component.current_version() {
+                let step = self.step(component, from)?;
+                pending.push(PendingMigration {
+                    name: step.name(),
+                    component,
+                    from_version: from,
+                    runs_at_startup: step.runs_at_startup(&self.data_paths),
+                });
+            }
+        }
+        Ok(pending)
+    }
+
+    /// The manifest to migrate from, or `None` for a fresh data directory
+    /// (stamped with the current versions); refuses data from a newer build
+    fn manifest(&self) -> Result<Option<FormatManifest>, MigrationError> {
+        let existing = FormatManifest::load(&self.data_paths)?;
+        let is_fresh = existing.is_none()
+            && !StorageComponent::ALL
+                .iter()
+                .any(|component| component.has_data(&self.data_paths));
+        if is_fresh {
+            return Ok(None);
+        }
+        let manifest = existing.unwrap_or_default();
+
+        // Refuse to touch anything if any component is from a newer build
+        for component in StorageComponent::ALL {
+            let found = manifest.version_of(component);
+            if found > component.current_version() {
+                return Err(MigrationError::UnsupportedVersion {
+                    component,
+                    found,
+                    supported: component.current_version(),
+                });
+            }
+        }
+        Ok(Some(manifest))
+    }
+
+    fn step(&self, component: StorageComponent, from: u32) -> Result<&dyn Migration, MigrationError> {
+        self.migrations
+            .iter()
+            .find(|m| m.component() == component && m.from_version() == from)
+            .map(|m| m.as_ref())
+            .ok_or(MigrationError::MissingStep { component, from })
+    }
+
+    fn run(&self, include_deferred: bool) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let Some(mut manifest) = self.manifest()? else {
+            debug!("Fresh data directory, stamping current format versions");
+            let mut manifest = FormatManifest::default();
+            for component in StorageComponent::ALL {
+                manifest.components.insert(component, component.current_version());
+            }
+            manifest.save(&self.data_paths)?;
+            return Ok(Vec::new());
+        };
+
+        let mut applied = Vec::new();
+        for component in StorageComponent::ALL {
+            while manifest.version_of(component) < component.current_version() {
+                let from = manifest.version_of(component);
+                let step = self.step(component, from)?;
+                if !include_deferred && !step.runs_at_startup(&self.data_paths) {
+                    debug!(
+                        "Migration {} of {} data is pending; 'polybot index' runs it",
+                        step.name(),
+                        component
+                    );
+                    break;
+                }
+
+                info!(
+                    "Migrating {} data from format v{} to v{} ({})",
+                    component,
+                    from,
+                    from + 1,
+                    step.name()
+                );
+
+                let backup_dir = self.backup(step)?;
+                if let Err(e) = step.apply(&self.data_paths) {
+                    if let Some(dir) = &backup_dir {
+                        warn!(
+                            "Migration {} failed; original files are preserved in {}",
+                            step.name(),
+                            dir.display()
+                        );
+                    }
+                    return Err(e);
+                }
+
+                // Persist after every step so an interrupted run resumes where it stopped
+                manifest.components.insert(component, from + 1);
+                manifest.save(&self.data_paths)?;
+
+                applied.push(AppliedMigration {
+                    name: step.name(),
+                    component,
+                    from_version: from,
+                    to_version: from + 1,
+                    backup_dir,
+                });
+            }
+        }
+
+        if !applied.is_empty() {
+            info!("Applied {} data format migration(s)", applied.len());
+        }
+        Ok(applied)
+    }
+
+    /// Copy the paths a step rewrites into a timestamped backup directory
+    fn backup(&self, step: &dyn Migration) -> Result<Option<PathBuf>, MigrationError> {
+        let paths: Vec<PathBuf> = step
+            .backup_paths(&self.data_paths)
+            .into_iter()
+            .filter(|p| p.exists())
+            .collect();
+        if paths.is_empty() {
+            return Ok(None);
+        }
+
+        let backup_dir = self.data_paths.root().join(MIGRATION_BACKUP_DIR).join(format!(
+            "{}-{}-v{}",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            step.name(),
+            step.from_version()
+        ));
+        fs::create_dir_all(&backup_dir)?;
+
+        for path in &paths {
+            let relative = path.strip_prefix(self.data_paths.root()).unwrap_or(path);
+            let target = backup_dir.join(relative);
+            Self::copy_recursive(path, &target)?;
+        }
+
+        info!(
+            "Backed up {} path(s) for migration {} to {}",
+            paths.len(),
+            step.name(),
+            backup_dir.display()
+        );
+        Ok(Some(backup_dir))
+    }
+
+    fn copy_recursive(src: &Path, dst: &Path) -> Result<(), MigrationError> {
+        if src.is_dir() {
+            fs::create_dir_all(dst)?;
+            for entry in fs::read_dir(src)? {
+                let entry = entry?;
+                Self::copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+            }
+        } else {
+            if let Some(parent) = dst.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(src, dst)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    struct RenameFileStep;
+
+    impl Migration for RenameFileStep {
+        fn name(&self) -> &'static str {
+            "rename_legacy_positions"
+        }
+        fn component(&self) -> StorageComponent {
+            StorageComponent::Portfolio
+        }
+        fn from_version(&self) -> u32 {
+            0
+        }
+        fn backup_paths(&self, data_paths: &DataPaths) -> Vec<PathBuf> {
+            vec![data_paths.root().join("trade")]
+        }
+        fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+            let trade = data_paths.root().join("trade");
+            fs::rename(trade.join("positions.json"), trade.join("current.json"))?;
+            Ok(())
+        }
+    }
+
+    struct SlowImportStep;
+
+    impl Migration for SlowImportStep {
+        fn name(&self) -> &'static str {
+            "slow_import"
+        }
+        fn component(&self) -> StorageComponent {
+            StorageComponent::Datasets
+        }
+        fn from_version(&self) -> u32 {
+            0
+        }
+        fn backup_paths(&self, _data_paths: &DataPaths) -> Vec<PathBuf> {
+            Vec::new()
+        }
+        fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+            fs::write(data_paths.root().join("imported"), "")?;
+            Ok(())
+        }
+        fn runs_at_startup(&self, _data_paths: &DataPaths) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_fresh_directory_is_stamped_without_migrating() {
+        let dir = tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+
+        let applied = MigrationRunner::with_migrations(&data_paths, Vec::new())
+            .run_startup()
+            .unwrap();
+
+        assert!(applied.is_empty());
+        let manifest = FormatManifest::load(&data_paths).unwrap().unwrap();
+        assert_eq!(manifest.version_of(StorageComponent::Portfolio), 1);
+    }
+
+    #[test]
+    fn test_legacy_data_is_backed_up_and_migrated() {
+        let dir = tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+        let trade = dir.path().join("trade");
+        fs::create_dir_all(&trade).unwrap();
+        fs::write(trade.join("positions.json"), "[]").unwrap();
+
+        let mut migrations = steps::registered_migrations();
+        migrations.retain(|m| m.component() != StorageComponent::Portfolio);
+        migrations.push(Box::new(RenameFileStep));
+
+        let applied = MigrationRunner::with_migrations(&data_paths, migrations)
+            .run_startup()
+            .unwrap();
+
+        let portfolio_step = applied
+            .iter()
+            .find(|a| a.component == StorageComponent::Portfolio)
+            .unwrap();
+        let backup_dir = portfolio_step.backup_dir.as_ref().unwrap();
+        assert!(backup_dir.join("trade").join("positions.json").exists());
+        assert!(trade.join("current.json").exists());
+        assert!(!trade.join("positions.json").exists());
+    }
+
+    #[test]
+    fn test_deferred_steps_stay_pending_until_run_all() {
+        let dir = tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+        fs::create_dir_all(data_paths.datasets().join("markets")).unwrap();
+
+        let mut migrations = steps::registered_migrations();
+        migrations.retain(|m| m.component() != StorageComponent::Datasets);
+        migrations.push(Box::new(SlowImportStep));
+        let runner = MigrationRunner::with_migrations(&data_paths, migrations);
+
+        let applied = runner.run_startup().unwrap();
+        assert!(applied.iter().all(|a| a.component != StorageComponent::Datasets));
+        assert!(!dir.path().join("imported").exists());
+        let pending = runner.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].name, "slow_import");
+        assert!(!pending[0].runs_at_startup);
+
+        let applied = runner.run_all().unwrap();
+        assert_eq!(applied.len(), 1);
+        assert!(dir.path().join("imported").exists());
+        assert!(runner.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_newer_version_is_refused() {
+        let dir = tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+
+        let mut manifest = FormatManifest::default();
+        manifest.components.insert(StorageComponent::TypedStore, 99);
+        manifest.save(&data_paths).unwrap();
+
+        let result = MigrationRunner::new(&data_paths).run_startup();
+        assert!(matches!(
+            result,
+            Err(MigrationError::UnsupportedVersion { found: 99, .. })
+        ));
+    }
+}
@@ -0,0 +1,222 @@
+//! Registered migration steps
+//!
+//! Add new steps to `registered_migrations()` and bump the matching
+//! `StorageComponent::current_version()` in the same change.
+
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use super::{Migration, MigrationError, StorageComponent};
+use crate::data_paths::DataPaths;
+use crate::typed_store::{compat, TypedDbContext, ALL_COLUMN_FAMILIES};
+
+/// All migration steps known to this build
+pub fn registered_migrations() -> Vec<Box<dyn Migration>> {
+    vec![
+        Box::new(PortfolioBaseline),
+        Box::new(TypedStoreBaseline),
+        Box::new(FileStoreImport),
+        Box::new(DatasetsBaseline),
+    ]
+}
+
+/// v0 → v1: adopt the pre-manifest portfolio layout (`trade/account/<address>/`,
+/// `raw/`, `cache/`) as format version 1. Nothing is rewritten.
+struct PortfolioBaseline;
+
+impl Migration for PortfolioBaseline {
+    fn name(&self) -> &'static str {
+        "portfolio_baseline"
+    }
+
+    fn component(&self) -> StorageComponent {
+        StorageComponent::Portfolio
+    }
+
+    fn from_version(&self) -> u32 {
+        0
+    }
+
+    fn backup_paths(&self, _data_paths: &DataPaths) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+        let accounts_dir = data_paths.root().join("trade").join("account");
+        let accounts = std::fs::read_dir(&accounts_dir)
+            .map(|entries| entries.filter_map(|e| e.ok()).count())
+            .unwrap_or(0);
+        info!("Adopting existing portfolio storage for {} account(s) as format v1", accounts);
+        Ok(())
+    }
+}
+
+/// v0 → v1: adopt the existing typed_store RocksDB as format version 1 after
+/// checking that its column families are the ones this build knows.
+struct TypedStoreBaseline;
+
+impl Migration for TypedStoreBaseline {
+    fn name(&self) -> &'static str {
+        "typed_store_baseline"
+    }
+
+    fn component(&self) -> StorageComponent {
+        StorageComponent::TypedStore
+    }
+
+    fn from_version(&self) -> u32 {
+        0
+    }
+
+    fn backup_paths(&self, _data_paths: &DataPaths) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+        let db_path = data_paths.root().join("database").join("rocksdb");
+        if !db_path.exists() {
+            return Ok(());
+        }
+
+        let column_families = rocksdb::DB::list_cf(&rocksdb::Options::default(), &db_path)
+            .map_err(|e| MigrationError::StepFailed {
+                name: self.name(),
+                reason: format!("cannot read column families at {}: {}", db_path.display(), e),
+            })?;
+
+        let unknown: Vec<&String> = column_families
+            .iter()
+            .filter(|cf| cf.as_str() != "default" && !ALL_COLUMN_FAMILIES.contains(&cf.as_str()))
+            .collect();
+        if !unknown.is_empty() {
+            warn!(
+                "typed_store at {} contains unknown column families {:?}; they will be ignored",
+                db_path.display(),
+                unknown
+            );
+        }
+        Ok(())
+    }
+}
+
+/// v1 → v2: import the JSON file store hierarchy (`database/markets/`) into
+/// the typed_store column families, which become the only market store. The
+/// hierarchy is left in place; reads by its old paths go through
+/// `typed_store::compat::LegacyPathReader`.
+///
+/// Importing a hierarchy reads every market file and needs the RocksDB lock,
+/// so it only runs from `polybot index`; without a hierarchy the version is
+/// bumped at startup.
+struct FileStoreImport;
+
+impl FileStoreImport {
+    fn has_file_store(data_paths: &DataPaths) -> bool {
+        data_paths
+            .root()
+            .join("database")
+            .join("markets")
+            .join("condition")
+            .is_dir()
+    }
+}
+
+impl Migration for FileStoreImport {
+    fn name(&self) -> &'static str {
+        "file_store_import"
+    }
+
+    fn component(&self) -> StorageComponent {
+        StorageComponent::TypedStore
+    }
+
+    fn from_version(&self) -> u32 {
+        1
+    }
+
+    fn backup_paths(&self, data_paths: &DataPaths) -> Vec<PathBuf> {
+        let db_path = data_paths.root().join("database").join("rocksdb");
+        if Self::has_file_store(data_paths) && db_path.exists() {
+            vec![db_path]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn runs_at_startup(&self, data_paths: &DataPaths) -> bool {
+        !Self::has_file_store(data_paths)
+    }
+
+    fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+        if !Self::has_file_store(data_paths) {
+            return Ok(());
+        }
+        let base = data_paths.root().join("database");
+
+        let failed = |e: &dyn std::fmt::Display| MigrationError::StepFailed {
+            name: self.name(),
+            reason: e.to_string(),
+        };
+        let ctx = TypedDbContext::open(base.join("rocksdb"), ALL_COLUMN_FAMILIES.to_vec())
+            .map_err(|e| failed(&e))?;
+        let report = compat::import_file_store(&base, &ctx, true).map_err(|e| failed(&e))?;
+
+        info!(
+            "Imported {} market(s) from the file store into typed_store ({} already present)",
+            report.markets, report.duplicates
+        );
+        if !report.unreadable.is_empty() {
+            warn!(
+                "{} file store market file(s) could not be read and were not imported",
+                report.unreadable.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// v0 → v1: adopt existing `dataset.yaml` files as format version 1, reporting
+/// any that no longer parse.
+struct DatasetsBaseline;
+
+impl Migration for DatasetsBaseline {
+    fn name(&self) -> &'static str {
+        "datasets_baseline"
+    }
+
+    fn component(&self) -> StorageComponent {
+        StorageComponent::Datasets
+    }
+
+    fn from_version(&self) -> u32 {
+        0
+    }
+
+    fn backup_paths(&self, _data_paths: &DataPaths) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    fn apply(&self, data_paths: &DataPaths) -> Result<(), MigrationError> {
+        let mut stack = vec![data_paths.datasets()];
+        let mut checked = 0;
+
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else if entry.file_name() == "dataset.yaml" {
+                    checked += 1;
+                    if let Err(e) = crate::markets::datasets::load_dataset_metadata(&dir) {
+                        warn!("Dataset metadata at {} is unreadable: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+
+        info!("Adopted {} dataset metadata file(s) as format v1", checked);
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+//! Number formatting shared by CLI tables, the TUI and the GUI
+//!
+//! Configuration lives in `<data_dir>/number_format.yaml` and is optional.
+//! Each kind of number has its own precision, and the locale picks the
+//! thousands and decimal separators:
+//!
+//! ```yaml
+//! locale: de            # en | de | fr | ch
+//! grouping: true        # thousands separators
+//! price_decimals: 4
+//! size_decimals: 2
+//! usd_decimals: 2
+//! percent_decimals: 2
+//! ```
+//!
+//! [`init`] runs once at startup of every command; display code formats
+//! through [`number_format`] or the shorthands for each kind, [`price`],
+//! [`size`], [`usd`], [`percent`] and their signed forms. JSON, CSV and
+//! anything sent to the API keep plain full-precision numbers.
+
+use anyhow::{Context, Result};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::data_paths::DataPaths;
+
+/// Name of the number format configuration file inside the data directory
+pub const NUMBER_FORMAT_CONFIG_FILE: &str = "number_format.yaml";
+
+static NUMBER_FORMAT: OnceLock<NumberFormat> = OnceLock::new();
+
+/// Separator conventions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberLocale {
+    /// 1,234.56
+    #[default]
+    En,
+    /// 1.234,56
+    De,
+    /// 1 234,56
+    Fr,
+    /// 1'234.56
+    Ch,
+}
+
+/// The characters a locale groups thousands and starts decimals with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Separators {
+    pub thousands: char,
+    pub decimal: char,
+}
+
+impl Separators {
+    const fn new(thousands: char, decimal: char) -> Self {
+        Self { thousands, decimal }
+    }
+}
+
+impl NumberLocale {
+    pub fn separators(self) -> Separators {
+        match self {
+            NumberLocale::En => Separators::new(',', '.'),
+            NumberLocale::De => Separators::new('.', ','),
+            NumberLocale::Fr => Separators::new(' ', ','),
+            NumberLocale::Ch => Separators::new('\'', '.'),
+        }
+    }
+}
+
+/// A value the formatter takes; floats that aren't finite format as `-`
+pub trait Number: Copy {
+    fn to_decimal(self) -> Option<Decimal>;
+}
+
+impl Number for Decimal {
+    fn to_decimal(self) -> Option<Decimal> {
+        Some(self)
+    }
+}
+
+impl Number for f64 {
+    fn to_decimal(self) -> Option<Decimal> {
+        Decimal::from_f64(self)
+    }
+}
+
+impl Number for f32 {
+    fn to_decimal(self) -> Option<Decimal> {
+        Decimal::from_f32(self)
+    }
+}
+
+impl Number for i64 {
+    fn to_decimal(self) -> Option<Decimal> {
+        Some(Decimal::from(self))
+    }
+}
+
+impl Number for u64 {
+    fn to_decimal(self) -> Option<Decimal> {
+        Some(Decimal::from(self))
+    }
+}
+
+impl Number for usize {
+    fn to_decimal(self) -> Option<Decimal> {
+        Some(Decimal::from(self))
+    }
+}
+
+/// Precision and separators of displayed numbers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NumberFormat {
+    pub locale: NumberLocale,
+    /// Group the integer part into thousands
+    pub grouping: bool,
+    /// Decimals of prices (per share, 0 to 1)
+    pub price_decimals: u32,
+    /// Decimals of share sizes
+    pub size_decimals: u32,
+    /// Decimals of dollar amounts: balances, volume, P&L
+    pub usd_decimals: u32,
+    /// Decimals of percentages
+    pub percent_decimals: u32,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            locale: NumberLocale::default(),
+            grouping: true,
+            price_decimals: 4,
+            size_decimals: 2,
+            usd_decimals: 2,
+            percent_decimals: 2,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Load `number_format.yaml` from the data directory, or defaults when it
+    /// doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(NUMBER_FORMAT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid number format config {}", path.display()))
+    }
+
+    /// `value` rounded half away from zero to `places` decimals, with the
+    /// locale's separators
+    pub fn decimal(&self, value: impl Number, places: u32) -> String {
+        let Some(value) = value.to_decimal() else {
+            return "-".to_string();
+        };
+        let rounded = value.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero);
+        let text = format!("{:.*}", places as usize, rounded.abs());
+        let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let separators = self.locale.separators();
+
+        let mut out = String::with_capacity(text.len() + integer.len() / 3 + 1);
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            out.push('-');
+        }
+        for (index, digit) in integer.chars().enumerate() {
+            if self.grouping && index > 0 && (integer.len() - index) % 3 == 0 {
+                out.push(separators.thousands);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push(separators.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Like [`decimal`](Self::decimal), with a `+` on positive values
+    pub fn signed(&self, value: impl Number, places: u32) -> String {
+        let text = self.decimal(value, places);
+        match value.to_decimal() {
+            Some(value) if value > Decimal::ZERO && text != self.decimal(0i64, places) => {
+                format!("+{}", text)
+            }
+            _ => text,
+        }
+    }
+
+    /// Whole number, e.g. a count or a rounded volume
+    pub fn integer(&self, value: impl Number) -> String {
+        self.decimal(value, 0)
+    }
+
+    pub fn price(&self, value: impl Number) -> String {
+        self.decimal(value, self.price_decimals)
+    }
+
+    /// Signed price move, e.g. a change or an edge
+    pub fn price_change(&self, value: impl Number) -> String {
+        self.signed(value, self.price_decimals)
+    }
+
+    pub fn size(&self, value: impl Number) -> String {
+        self.decimal(value, self.size_decimals)
+    }
+
+    /// Signed share count, e.g. a net position
+    pub fn signed_size(&self, value: impl Number) -> String {
+        self.signed(value, self.size_decimals)
+    }
+
+    /// Dollar amount with its sign before the `$`: `-$1,234.56`
+    pub fn usd(&self, value: impl Number) -> String {
+        let text = self.decimal(value, self.usd_decimals);
+        if text == "-" {
+            return text;
+        }
+        match text.strip_prefix('-') {
+            Some(amount) => format!("-${}", amount),
+            None => format!("${}", text),
+        }
+    }
+
+    /// Dollar amount with a `+` on gains: `+$12.50`
+    pub fn signed_usd(&self, value: impl Number) -> String {
+        let text = self.usd(value);
+        match value.to_decimal() {
+            Some(value) if value > Decimal::ZERO && text != self.usd(0i64) => {
+                format!("+{}", text)
+            }
+            _ => text,
+        }
+    }
+
+    /// `value` in percent (12.5 for 12.5%)
+    pub fn percent(&self, value: impl Number) -> String {
+        format!("{}%", self.decimal(value, self.percent_decimals))
+    }
+
+    pub fn signed_percent(&self, value: impl Number) -> String {
+        format!("{}%", self.signed(value, self.percent_decimals))
+    }
+}
+
+/// Load the number format from the data directory and make it the active one.
+/// An invalid file is reported and the defaults are used instead. Only the
+/// first call has an effect.
+pub fn init(data_paths: &DataPaths) -> NumberFormat {
+    let format = NumberFormat::load(data_paths).unwrap_or_else(|e| {
+        warn!("Using default number format: {:#}", e);
+        NumberFormat::default()
+    });
+    NUMBER_FORMAT.get_or_init(|| format).clone()
+}
+
+/// Active number format; the defaults until [`init`] is called
+pub fn number_format() -> &'static NumberFormat {
+    NUMBER_FORMAT.get_or_init(NumberFormat::default)
+}
+
+/// Whole number with the locale's thousands separators
+pub fn integer(value: impl Number) -> String {
+    number_format().integer(value)
+}
+
+/// Price at the configured precision
+pub fn price(value: impl Number) -> String {
+    number_format().price(value)
+}
+
+/// Signed price move at the price precision
+pub fn price_change(value: impl Number) -> String {
+    number_format().price_change(value)
+}
+
+/// Share size at the configured precision
+pub fn size(value: impl Number) -> String {
+    number_format().size(value)
+}
+
+/// Signed share count at the size precision
+pub fn signed_size(value: impl Number) -> String {
+    number_format().signed_size(value)
+}
+
+/// Dollar amount at the configured precision, with a `$`
+pub fn usd(value: impl Number) -> String {
+    number_format().usd(value)
+}
+
+/// Dollar amount with a `+` on gains
+pub fn signed_usd(value: impl Number) -> String {
+    number_format().signed_usd(value)
+}
+
+/// Percentage at the configured precision, with a `%`
+pub fn percent(value: impl Number) -> String {
+    number_format().percent(value)
+}
+
+/// Percentage with a `+` on gains
+pub fn signed_percent(value: impl Number) -> String {
+    number_format().signed_percent(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn precision_and_separators_follow_the_config() {
+        let format = NumberFormat::default();
+        assert_eq!(format.usd(dec!(1234567.891)), "$1,234,567.89");
+        assert_eq!(format.usd(dec!(-1234.5)), "-$1,234.50");
+        assert_eq!(format.price(dec!(0.45)), "0.4500");
+        assert_eq!(format.size(dec!(999.995)), "1,000.00");
+        assert_eq!(format.size(2.5), "2.50");
+        assert_eq!(format.signed_percent(dec!(2.5)), "+2.50%");
+        assert_eq!(format.signed_usd(dec!(-0.001)), "$0.00");
+        assert_eq!(format.price_change(dec!(-0.012)), "-0.0120");
+        assert_eq!(format.integer(123usize), "123");
+        assert_eq!(format.usd(f64::NAN), "-");
+
+        let config: NumberFormat = serde_yaml::from_str(
+            "locale: de\nprice_decimals: 2\nusd_decimals: 0\npercent_decimals: 1\n",
+        )
+        .unwrap();
+        assert_eq!(config.usd(dec!(1234567.5)), "$1.234.568");
+        assert_eq!(config.price(dec!(0.455)), "0,46");
+        assert_eq!(config.percent(12.25), "12,3%");
+        assert_eq!(config.size_decimals, 2);
+
+        let fr = NumberFormat {
+            locale: NumberLocale::Fr,
+            grouping: false,
+            ..Default::default()
+        };
+        assert_eq!(fr.size(dec!(12345.6)), "12345,60");
+    }
+}
@@ -0,0 +1,330 @@
+//! Dataset health checks after pipeline runs
+//!
+//! Once a pipeline has run its steps, every dataset a step wrote (the outputs
+//! [`RunRecorder`](super::history::RunRecorder) found) is checked: JSON and
+//! JSONL files are sampled and parsed, records are counted (extrapolated from
+//! the sample when not every file was parsed), and records and size are
+//! compared with the same step's output in the pipeline's previous run. The
+//! verdict is stored in the dataset's `dataset.yaml`, so the dataset manager
+//! rates a dataset that failed as corrupted.
+//!
+//! Checks are on by default and only report; a pipeline can tune them, or
+//! fail the run when a check fails:
+//!
+//! ```yaml
+//! health_checks:
+//!   fail_pipeline: true
+//!   sample_files: 20
+//!   max_shrink: 0.5
+//!   max_growth: 3.0
+//!   min_records: 100
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::history::format_change;
+use super::steps::{collect_files, count_records};
+use super::{Pipeline, PipelineContext};
+use crate::markets::datasets::format_bytes;
+use crate::markets::datasets::health::{record_health_check, recorded_health_check, HealthCheck};
+use crate::markets::datasets::load_dataset_metadata;
+use crate::typed_store::models::{
+    PipelineOutput, PipelineRunRecord, PipelineStepRecord, PipelineStepStatus,
+};
+use crate::typed_store::pipeline_runs;
+
+/// How the outputs of a run are checked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthCheckConfig {
+    /// Check the outputs after every run (default: true)
+    pub enabled: bool,
+    /// Fail the run when a check fails, instead of only marking the dataset
+    pub fail_pipeline: bool,
+    /// Most files per dataset parsed for JSON validity and record counts
+    pub sample_files: usize,
+    /// Fraction of the previous run's records or bytes a dataset may lose
+    pub max_shrink: f64,
+    /// Growth factor over the previous run's size that is flagged as a warning
+    pub max_growth: f64,
+    /// Fewest records each dataset should hold
+    pub min_records: Option<usize>,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fail_pipeline: false,
+            sample_files: 20,
+            max_shrink: 0.5,
+            max_growth: 3.0,
+            min_records: None,
+        }
+    }
+}
+
+/// Size of a dataset in an earlier run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    pub bytes: u64,
+    /// Records its own health check counted
+    pub records: Option<usize>,
+}
+
+impl Baseline {
+    fn of(output: &PipelineOutput) -> Self {
+        Self {
+            bytes: output.bytes,
+            records: load_dataset_metadata(Path::new(&output.path))
+                .ok()
+                .as_ref()
+                .and_then(recorded_health_check)
+                .map(|check| check.records),
+        }
+    }
+}
+
+/// A checked output of a step
+#[derive(Debug, Clone)]
+pub struct OutputHealth {
+    pub step: String,
+    pub path: String,
+    pub check: HealthCheck,
+}
+
+/// The latest recorded run of `pipeline` in the history at `db_path`
+pub fn previous_run(db_path: &Path, pipeline: &str) -> Option<PipelineRunRecord> {
+    pipeline_runs::load(db_path)
+        .ok()?
+        .into_iter()
+        .find(|run| run.pipeline == pipeline)
+}
+
+/// Check and mark every output of the succeeded `steps`
+pub fn check_run(
+    pipeline: &Pipeline,
+    context: &PipelineContext,
+    run_id: &str,
+    steps: &[PipelineStepRecord],
+    previous: Option<&PipelineRunRecord>,
+) -> Vec<OutputHealth> {
+    let config = &pipeline.health_checks;
+    let mut checked = Vec::new();
+    for (step, record) in pipeline.steps.iter().zip(steps) {
+        if record.status != PipelineStepStatus::Succeeded {
+            continue;
+        }
+        let previous_outputs = previous
+            .and_then(|run| run.steps.iter().find(|s| s.name == record.name))
+            .map(|s| s.outputs.as_slice())
+            .unwrap_or_default();
+        for (index, output) in record.outputs.iter().enumerate() {
+            // The same directory if the step rewrites one, else the output in the same place
+            let baseline = previous_outputs
+                .iter()
+                .find(|prev| prev.path == output.path)
+                .or_else(|| previous_outputs.get(index))
+                .map(Baseline::of);
+            let path = Path::new(&output.path);
+            let mut check = match check_dataset(path, config, baseline.as_ref()) {
+                Ok(check) => check,
+                Err(e) => {
+                    tracing::warn!("Failed to check {}: {:#}", output.path, e);
+                    continue;
+                }
+            };
+            check.run_id = Some(run_id.to_string());
+            let args: Vec<String> = step
+                .args
+                .iter()
+                .map(|arg| pipeline.resolve_parameters(arg, context))
+                .collect();
+            if let Err(e) = record_health_check(path, &check, step.kind(), &args) {
+                tracing::warn!("Failed to mark health of {}: {:#}", output.path, e);
+            }
+            checked.push(OutputHealth {
+                step: record.name.clone(),
+                path: output.path.clone(),
+                check,
+            });
+        }
+    }
+    checked
+}
+
+/// Check the dataset at `path` against `config` and its `baseline`
+pub fn check_dataset(
+    path: &Path,
+    config: &HealthCheckConfig,
+    baseline: Option<&Baseline>,
+) -> Result<HealthCheck> {
+    let files = collect_files(path)?;
+    let sizes: Vec<u64> = files
+        .iter()
+        .map(|file| fs::metadata(file).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let bytes: u64 = sizes.iter().sum();
+    let data: Vec<usize> = (0..files.len())
+        .filter(|&i| is_data_file(&files[i]))
+        .collect();
+    let data_bytes: u64 = data.iter().map(|&i| sizes[i]).sum();
+
+    let mut failures = Vec::new();
+    let mut warnings = Vec::new();
+
+    // An even spread over the (sorted) data files, first and last included
+    let sample: Vec<usize> = if data.len() <= config.sample_files {
+        data.clone()
+    } else {
+        let last = config.sample_files.saturating_sub(1).max(1);
+        let mut picks: Vec<usize> = (0..=last)
+            .map(|n| data[n * (data.len() - 1) / last])
+            .collect();
+        picks.dedup();
+        picks
+    };
+    let (mut sampled_records, mut sampled_bytes) = (0usize, 0u64);
+    for &i in &sample {
+        match parse_records(&files[i]) {
+            Ok(records) => {
+                sampled_records += records;
+                sampled_bytes += sizes[i];
+            }
+            Err(e) => failures.push(format!("{:#}", e)),
+        }
+    }
+    let records_estimated = sample.len() < data.len() && sampled_bytes > 0;
+    let records = if records_estimated {
+        (sampled_records as f64 * data_bytes as f64 / sampled_bytes as f64).round() as usize
+    } else {
+        sampled_records
+    };
+
+    if data.is_empty() || data_bytes == 0 {
+        failures.push("no data files".to_string());
+    } else if records == 0 && failures.is_empty() {
+        failures.push("no records".to_string());
+    }
+    if let Some(min) = config.min_records.filter(|min| records < *min) {
+        failures.push(format!("{} records, expected at least {}", records, min));
+    }
+    if let Some(baseline) = baseline {
+        let shrunk =
+            |before: f64, after: f64| before > 0.0 && after < before * (1.0 - config.max_shrink);
+        if let Some(before) = baseline.records {
+            if shrunk(before as f64, records as f64) {
+                failures.push(format!(
+                    "{} records vs {} in the previous run ({})",
+                    records,
+                    before,
+                    format_change(before as f64, records as f64)
+                ));
+            }
+        }
+        if shrunk(baseline.bytes as f64, bytes as f64) {
+            failures.push(format!(
+                "{} vs {} in the previous run ({})",
+                format_bytes(bytes),
+                format_bytes(baseline.bytes),
+                format_change(baseline.bytes as f64, bytes as f64)
+            ));
+        } else if baseline.bytes > 0 && bytes as f64 > baseline.bytes as f64 * config.max_growth {
+            warnings.push(format!(
+                "{} vs {} in the previous run ({})",
+                format_bytes(bytes),
+                format_bytes(baseline.bytes),
+                format_change(baseline.bytes as f64, bytes as f64)
+            ));
+        }
+    }
+
+    Ok(HealthCheck {
+        checked_at: Utc::now(),
+        run_id: None,
+        files: files.len(),
+        bytes,
+        records,
+        records_estimated,
+        sampled_files: sample.len(),
+        failures,
+        warnings,
+    })
+}
+
+/// Files holding records, as [`count_records`] counts them
+fn is_data_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    matches!(extension.as_str(), "json" | "jsonl" | "ndjson" | "csv")
+}
+
+/// Records of a data file, failing if it isn't valid JSON (every line of JSONL files)
+fn parse_records(path: &Path) -> Result<usize> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if !matches!(extension.as_str(), "jsonl" | "ndjson") {
+        return count_records(path);
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut records = 0;
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        serde_json::from_str::<serde::de::IgnoredAny>(line)
+            .with_context(|| format!("Invalid JSON in {} line {}", path.display(), number + 1))?;
+        records += 1;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::datasets::{DatasetHealthStatus, DatasetManager, DatasetManagerConfig};
+
+    #[test]
+    fn checks_flag_invalid_json_and_shrunk_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let dataset = dir.path().join("markets");
+        fs::create_dir_all(&dataset).unwrap();
+        fs::write(dataset.join("markets.json"), r#"[{"id": 1}, {"id": 2}]"#).unwrap();
+        fs::write(dataset.join("events.jsonl"), "{\"a\": 1}\n{\"a\": 2}\n").unwrap();
+        let config = HealthCheckConfig::default();
+
+        let check = check_dataset(&dataset, &config, None).unwrap();
+        assert!(check.passed(), "{:?}", check.failures);
+        assert_eq!((check.records, check.records_estimated), (4, false));
+
+        // Far fewer records and bytes than the run before
+        let baseline = Baseline {
+            bytes: check.bytes * 10,
+            records: Some(400),
+        };
+        let shrunk = check_dataset(&dataset, &config, Some(&baseline)).unwrap();
+        assert_eq!(shrunk.failures.len(), 2);
+
+        fs::write(dataset.join("events.jsonl"), "{\"a\": 1}\n{\"a\": \n").unwrap();
+        let broken = check_dataset(&dataset, &config, None).unwrap();
+        assert!(broken.failures[0].contains("line 2"));
+
+        // A failed verdict makes the manager rate the dataset corrupted
+        record_health_check(&dataset, &broken, "fetch-all-markets", &[]).unwrap();
+        let info = DatasetManager::new(DatasetManagerConfig::default())
+            .analyze_dataset(&dataset)
+            .unwrap();
+        assert_eq!(info.health_status, DatasetHealthStatus::Corrupted);
+    }
+}
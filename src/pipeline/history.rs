@@ -0,0 +1,451 @@
+//! Pipeline run history
+//!
+//! [`RunRecorder`] builds a [`PipelineRunRecord`] while a pipeline runs; the
+//! runner stores it in the typed store when the run ends. [`RunComparison`]
+//! lines up two recorded runs step by step and flags regressions: steps that
+//! got markedly slower, produced markedly less output, or started failing.
+
+use chrono::{Local, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::steps::collect_files;
+use super::{Pipeline, PipelineContext};
+use crate::data_paths::DEFAULT_DATASETS_DIR;
+use crate::markets::datasets::format_bytes;
+use crate::typed_store::models::{
+    PipelineOutput, PipelineRunRecord, PipelineRunStatus, PipelineStepRecord, PipelineStepStatus,
+};
+
+/// A step taking this much longer (as a fraction) than in the baseline run is a regression
+pub const DURATION_REGRESSION: f64 = 0.25;
+/// ... unless it's fewer than this many seconds slower
+pub const MIN_DURATION_REGRESSION_SECS: f64 = 5.0;
+/// A step producing this much less output (as a fraction) than in the baseline run is a regression
+pub const OUTPUT_REGRESSION: f64 = 0.2;
+
+/// Exit status of a step command that failed, attached to the step's error
+#[derive(Debug, thiserror::Error)]
+#[error("exited with {0}")]
+pub struct StepExit(pub ExitStatus);
+
+/// Builds the record of a run as its steps finish
+pub struct RunRecorder {
+    record: PipelineRunRecord,
+    started: Instant,
+}
+
+impl RunRecorder {
+    pub fn start(pipeline: &Pipeline, context: &PipelineContext) -> Self {
+        // The output directory is unique per run and is where its metadata lives
+        let run_id = context
+            .parameters
+            .get("pipeline_output_dir")
+            .and_then(|dir| Path::new(dir).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| {
+                format!(
+                    "pipeline_{}_{}",
+                    pipeline.snake_name(),
+                    Local::now().format("%Y%m%d_%H%M%S")
+                )
+            });
+        let steps = pipeline
+            .steps
+            .iter()
+            .map(|step| PipelineStepRecord {
+                name: step.name.clone(),
+                kind: step.kind().to_string(),
+                status: PipelineStepStatus::NotRun,
+                exit_code: None,
+                error: None,
+                duration_secs: 0.0,
+                outputs: Vec::new(),
+            })
+            .collect();
+        Self {
+            record: PipelineRunRecord {
+                run_id,
+                pipeline: pipeline.name.clone(),
+                parameters: context
+                    .parameters
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<BTreeMap<_, _>>(),
+                started_at: Utc::now(),
+                duration_secs: 0.0,
+                status: PipelineRunStatus::Succeeded,
+                steps,
+            },
+            started: Instant::now(),
+        }
+    }
+
+    /// Record how an attempt at step `index` went; a retry replaces the earlier attempt
+    pub fn step_finished(
+        &mut self,
+        pipeline: &Pipeline,
+        context: &PipelineContext,
+        index: usize,
+        error: Option<&anyhow::Error>,
+        started_at: SystemTime,
+        duration: Duration,
+    ) {
+        let step = &pipeline.steps[index];
+        let exit_code = match error {
+            None if !step.command.is_empty() => Some(0),
+            None => None,
+            Some(error) => error
+                .downcast_ref::<StepExit>()
+                .and_then(|exit| exit.0.code()),
+        };
+        self.record.steps[index] = PipelineStepRecord {
+            status: if error.is_some() {
+                PipelineStepStatus::Failed
+            } else {
+                PipelineStepStatus::Succeeded
+            },
+            exit_code,
+            error: error.map(|e| format!("{:#}", e)),
+            duration_secs: duration.as_secs_f64(),
+            outputs: produced_outputs(pipeline, index, context, started_at),
+            ..self.record.steps[index].clone()
+        };
+    }
+
+    /// A failed step was skipped from the run monitor
+    pub fn step_skipped(&mut self, index: usize) {
+        self.record.steps[index].status = PipelineStepStatus::Skipped;
+    }
+
+    /// Status of a run that got through all its steps
+    pub fn completed_status(&self) -> PipelineRunStatus {
+        let failed = self.record.steps.iter().any(|step| {
+            matches!(
+                step.status,
+                PipelineStepStatus::Failed | PipelineStepStatus::Skipped
+            )
+        });
+        if failed {
+            PipelineRunStatus::Partial
+        } else {
+            PipelineRunStatus::Succeeded
+        }
+    }
+
+    /// The run as recorded so far
+    pub fn record(&self) -> &PipelineRunRecord {
+        &self.record
+    }
+
+    pub fn finish(mut self, status: PipelineRunStatus) -> PipelineRunRecord {
+        self.record.status = status;
+        self.record.duration_secs = self.started.elapsed().as_secs_f64();
+        self.record
+    }
+}
+
+/// Directories step `index` wrote to since `since`: resolved arguments (and a
+/// publish destination) naming a directory, as given or under `datasets_dir`
+fn produced_outputs(
+    pipeline: &Pipeline,
+    index: usize,
+    context: &PipelineContext,
+    since: SystemTime,
+) -> Vec<PipelineOutput> {
+    let step = &pipeline.steps[index];
+    let datasets_dir = context
+        .parameters
+        .get("datasets_dir")
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_DATASETS_DIR);
+    let mut candidates: Vec<String> = step
+        .args
+        .iter()
+        .map(|arg| pipeline.resolve_parameters(arg, context))
+        .collect();
+    if let Some(publish) = &step.publish {
+        candidates.push(pipeline.resolve_parameters(&publish.to, context));
+    }
+
+    let mut outputs: Vec<PipelineOutput> = Vec::new();
+    for candidate in candidates {
+        // Flags, and paths too broad to be one dataset
+        if candidate.starts_with('-') || matches!(candidate.as_str(), "" | "." | ".." | "/") {
+            continue;
+        }
+        for path in [
+            PathBuf::from(&candidate),
+            Path::new(datasets_dir).join(&candidate),
+        ] {
+            let display = path.display().to_string();
+            if !path.is_dir() || outputs.iter().any(|output| output.path == display) {
+                continue;
+            }
+            let Ok(files) = collect_files(&path) else {
+                continue;
+            };
+            let metadata: Vec<_> = files.iter().filter_map(|f| f.metadata().ok()).collect();
+            // Inputs are left alone; only directories the step touched count
+            if !metadata
+                .iter()
+                .any(|m| m.modified().is_ok_and(|modified| modified >= since))
+            {
+                continue;
+            }
+            outputs.push(PipelineOutput {
+                path: display,
+                files: files.len(),
+                bytes: metadata.iter().map(|m| m.len()).sum(),
+            });
+            break;
+        }
+    }
+    outputs
+}
+
+/// A value in the baseline run and in the run compared against it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Side<T> {
+    pub baseline: T,
+    pub candidate: T,
+}
+
+impl<T> Side<T> {
+    pub fn new(baseline: T, candidate: T) -> Self {
+        Self {
+            baseline,
+            candidate,
+        }
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Side<U> {
+        Side::new(f(&self.baseline), f(&self.candidate))
+    }
+}
+
+impl<T: Copy> Side<Option<T>> {
+    /// Both values, when both runs have one
+    pub fn both(&self) -> Option<Side<T>> {
+        Some(Side::new(self.baseline?, self.candidate?))
+    }
+}
+
+/// One step in both runs of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepComparison {
+    pub name: String,
+    pub status: Side<Option<PipelineStepStatus>>,
+    pub duration_secs: Side<Option<f64>>,
+    pub output_bytes: Side<Option<u64>>,
+    pub regressions: Vec<String>,
+}
+
+/// Two recorded runs side by side, `a` being the baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunComparison {
+    pub steps: Vec<StepComparison>,
+    pub duration_secs: Side<f64>,
+    pub output_bytes: Side<u64>,
+    /// Regressions of the run as a whole
+    pub regressions: Vec<String>,
+}
+
+impl RunComparison {
+    pub fn between(a: &PipelineRunRecord, b: &PipelineRunRecord) -> Self {
+        // Steps in `b`'s order, then steps only `a` had
+        let mut names: Vec<&str> = b.steps.iter().map(|step| step.name.as_str()).collect();
+        for step in &a.steps {
+            if !names.contains(&step.name.as_str()) {
+                names.push(&step.name);
+            }
+        }
+
+        let steps = names
+            .into_iter()
+            .map(|name| {
+                let find = |run: &PipelineRunRecord| {
+                    run.steps.iter().find(|step| step.name == name).cloned()
+                };
+                let step = Side::new(find(a), find(b));
+                let ran = step.map(|step| {
+                    step.as_ref()
+                        .filter(|s| s.status != PipelineStepStatus::NotRun)
+                        .cloned()
+                });
+                let duration_secs = ran.map(|s| s.as_ref().map(|s| s.duration_secs));
+                let output_bytes = ran.map(|s| s.as_ref().map(output_bytes));
+
+                let mut regressions = Vec::new();
+                if let Some(duration) = duration_secs.both() {
+                    regressions.extend(duration_regression(duration.baseline, duration.candidate));
+                }
+                if let Some(bytes) = output_bytes.both() {
+                    regressions.extend(output_regression(bytes.baseline, bytes.candidate));
+                }
+                let status = step.map(|s| s.as_ref().map(|s| s.status));
+                if status.baseline == Some(PipelineStepStatus::Succeeded) {
+                    if let Some(step_b) = step
+                        .candidate
+                        .as_ref()
+                        .filter(|s| s.status == PipelineStepStatus::Failed)
+                    {
+                        regressions.push(match step_b.exit_code {
+                            Some(code) => format!("now fails (exit code {})", code),
+                            None => "now fails".to_string(),
+                        });
+                    }
+                }
+
+                StepComparison {
+                    name: name.to_string(),
+                    status,
+                    duration_secs,
+                    output_bytes,
+                    regressions,
+                }
+            })
+            .collect();
+
+        let runs = Side::new(a, b);
+        let output = runs.map(|run| run.steps.iter().map(output_bytes).sum::<u64>());
+        let mut regressions: Vec<String> = duration_regression(a.duration_secs, b.duration_secs)
+            .into_iter()
+            .chain(output_regression(output.baseline, output.candidate))
+            .collect();
+        if a.status == PipelineRunStatus::Succeeded && b.status != PipelineRunStatus::Succeeded {
+            regressions.push(format!("run status {:?}", b.status).to_lowercase());
+        }
+
+        Self {
+            steps,
+            duration_secs: runs.map(|run| run.duration_secs),
+            output_bytes: output,
+            regressions,
+        }
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty() || self.steps.iter().any(|s| !s.regressions.is_empty())
+    }
+}
+
+fn output_bytes(step: &PipelineStepRecord) -> u64 {
+    step.outputs.iter().map(|output| output.bytes).sum()
+}
+
+fn duration_regression(a: f64, b: f64) -> Option<String> {
+    (b > a * (1.0 + DURATION_REGRESSION) && b - a >= MIN_DURATION_REGRESSION_SECS).then(|| {
+        format!(
+            "{} slower ({} vs {})",
+            format_change(a, b),
+            format_duration(Duration::from_secs_f64(b)),
+            format_duration(Duration::from_secs_f64(a))
+        )
+    })
+}
+
+fn output_regression(a: u64, b: u64) -> Option<String> {
+    (a > 0 && (b as f64) < a as f64 * (1.0 - OUTPUT_REGRESSION)).then(|| {
+        format!(
+            "{} less output ({} vs {})",
+            format_change(a as f64, b as f64).trim_start_matches('-'),
+            format_bytes(b),
+            format_bytes(a)
+        )
+    })
+}
+
+/// `+62%` / `-40%` relative to `a`
+pub fn format_change(a: f64, b: f64) -> String {
+    if a == 0.0 {
+        return "n/a".to_string();
+    }
+    format!("{:+.0}%", (b - a) / a * 100.0)
+}
+
+/// `42s`, `3m05s`, `1h02m`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(name: &str, status: PipelineStepStatus, secs: f64, bytes: u64) -> PipelineStepRecord {
+        PipelineStepRecord {
+            name: name.to_string(),
+            kind: "analyze".to_string(),
+            status,
+            exit_code: (status == PipelineStepStatus::Failed).then_some(2),
+            error: None,
+            duration_secs: secs,
+            outputs: vec![PipelineOutput {
+                path: format!("data/datasets/{}", name),
+                files: 1,
+                bytes,
+            }],
+        }
+    }
+
+    fn run(run_id: &str, steps: Vec<PipelineStepRecord>) -> PipelineRunRecord {
+        PipelineRunRecord {
+            run_id: run_id.to_string(),
+            pipeline: "Daily Market Monitor".to_string(),
+            parameters: BTreeMap::new(),
+            started_at: Utc::now(),
+            duration_secs: steps.iter().map(|s| s.duration_secs).sum(),
+            status: PipelineRunStatus::Succeeded,
+            steps,
+        }
+    }
+
+    #[test]
+    fn comparison_flags_slower_smaller_and_failing_steps() {
+        use PipelineStepStatus::*;
+        let a = run(
+            "a",
+            vec![
+                step("fetch", Succeeded, 40.0, 10_000),
+                step("filter", Succeeded, 2.0, 1_000),
+                step("report", Succeeded, 10.0, 500),
+            ],
+        );
+        let b = run(
+            "b",
+            vec![
+                // Slower by more than a quarter and 5s
+                step("fetch", Succeeded, 66.0, 10_000),
+                // Slower by more than a quarter, but only by 1s
+                step("filter", Succeeded, 3.0, 700),
+                step("report", Failed, 1.0, 0),
+            ],
+        );
+
+        let comparison = RunComparison::between(&a, &b);
+        let names: Vec<&str> = comparison.steps.iter().map(|s| s.name.as_str()).collect();
+        let regressions: Vec<usize> = comparison.steps.iter().map(|s| s.regressions.len()).collect();
+        assert_eq!(names, ["fetch", "filter", "report"]);
+        // `filter` lost 30% of its output, `report` both its output and its success
+        assert_eq!(regressions, [1, 1, 2]);
+        assert_eq!(
+            comparison.steps[0].regressions[0],
+            "+65% slower (1m06s vs 40s)"
+        );
+        assert_eq!(comparison.output_bytes, Side::new(11_500, 10_700));
+        assert!(comparison.has_regressions());
+
+        let same = RunComparison::between(&a, &a);
+        assert!(!same.has_regressions());
+    }
+}
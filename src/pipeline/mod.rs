@@ -8,19 +8,31 @@ use std::collections::HashMap;
 use std::path::Path;
 
 pub mod config;
+pub mod health;
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod monitor;
 pub mod runner;
+pub mod steps;
+#[cfg(feature = "tui")]
 pub mod tui;
 
 pub use config::*;
+#[cfg(feature = "tui")]
+pub use monitor::*;
 pub use runner::*;
+pub use steps::{NotifyStep, PublishStep, ValidateStep};
+#[cfg(feature = "tui")]
 pub use tui::*;
 
-/// A pipeline step that executes a CLI command
+/// A pipeline step that executes a CLI command or one of the built-in step
+/// types (`publish`, `notify`, `validate`, see [`steps`])
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineStep {
     /// Human-readable name for this step
     pub name: String,
-    /// CLI command to execute (without the binary name)
+    /// CLI command to execute (without the binary name); empty for built-in steps
+    #[serde(default)]
     pub command: String,
     /// Arguments to pass to the command
     #[serde(default)]
@@ -31,6 +43,49 @@ pub struct PipelineStep {
     /// Environment variables to set for this step
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Copy or upload outputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish: Option<PublishStep>,
+    /// Send a run summary to a webhook
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<NotifyStep>,
+    /// Check record counts and health of a dataset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate: Option<ValidateStep>,
+}
+
+impl PipelineStep {
+    /// `command`, or the built-in step type
+    pub fn kind(&self) -> &str {
+        if self.publish.is_some() {
+            "publish"
+        } else if self.notify.is_some() {
+            "notify"
+        } else if self.validate.is_some() {
+            "validate"
+        } else {
+            &self.command
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        let sections = [
+            self.publish.is_some(),
+            self.notify.is_some(),
+            self.validate.is_some(),
+        ]
+        .into_iter()
+        .filter(|set| *set)
+        .count();
+        match (self.command.is_empty(), sections) {
+            (false, 0) | (true, 1) => Ok(()),
+            (true, 0) => anyhow::bail!("Step '{}' has no command", self.name),
+            _ => anyhow::bail!(
+                "Step '{}' must have exactly one of `command`, `publish`, `notify` or `validate`",
+                self.name
+            ),
+        }
+    }
 }
 
 /// Pipeline configuration loaded from YAML
@@ -46,6 +101,9 @@ pub struct Pipeline {
     pub parameters: HashMap<String, String>,
     /// List of steps to execute
     pub steps: Vec<PipelineStep>,
+    /// Health checks of the datasets the steps wrote
+    #[serde(default)]
+    pub health_checks: health::HealthCheckConfig,
 }
 
 /// Pipeline execution context with resolved parameters
@@ -67,10 +125,18 @@ impl Pipeline {
         let pipeline: Pipeline = serde_yaml::from_str(&content).with_context(|| {
             format!("Failed to parse pipeline YAML: {}", path.as_ref().display())
         })?;
+        for step in &pipeline.steps {
+            step.check()?;
+        }
 
         Ok(pipeline)
     }
 
+    /// Pipeline name in snake_case_lowercase, as used in run directory names
+    pub fn snake_name(&self) -> String {
+        self.name.replace(" ", "_").replace("-", "_").to_lowercase()
+    }
+
     /// Create execution context with resolved parameters
     pub fn create_context(&self, extra_params: HashMap<String, String>) -> PipelineContext {
         let mut parameters = self.parameters.clone();
@@ -91,15 +157,12 @@ impl Pipeline {
         parameters.insert("datasets_dir".to_string(), DEFAULT_DATASETS_DIR.to_string());
         parameters.insert("runs_dir".to_string(), DEFAULT_RUNS_DIR.to_string());
 
-        // Convert pipeline name to snake_case_lowercase
-        let pipeline_name_snake = self.name.replace(" ", "_").replace("-", "_").to_lowercase();
-
         parameters.insert(
             "pipeline_output_dir".to_string(),
             format!(
                 "{}/pipeline_{}_{}",
                 DEFAULT_RUNS_DIR,
-                pipeline_name_snake,
+                self.snake_name(),
                 now.format("%Y%m%d_%H%M%S")
             ),
         );
@@ -158,6 +221,7 @@ mod tests {
             description: "Test pipeline".to_string(),
             parameters: HashMap::new(),
             steps: vec![],
+            health_checks: Default::default(),
         };
 
         let mut extra_params = HashMap::new();
@@ -171,4 +235,31 @@ mod tests {
         assert!(resolved.contains("my_dataset"));
         assert!(!resolved.contains("${"));
     }
+
+    #[test]
+    fn built_in_steps_replace_command() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+name: Daily
+steps:
+  - name: Fetch
+    command: fetch-all-markets
+  - name: Check
+    validate:
+      path: out
+      min_records: 10
+  - name: Announce
+    notify:
+      url: https://hooks.example.com/x
+"#,
+        )
+        .unwrap();
+        let kinds: Vec<_> = pipeline.steps.iter().map(PipelineStep::kind).collect();
+        assert_eq!(kinds, ["fetch-all-markets", "validate", "notify"]);
+        assert!(pipeline.steps.iter().all(|step| step.check().is_ok()));
+
+        let mut both = pipeline.steps[1].clone();
+        both.command = "analyze".to_string();
+        assert!(both.check().is_err());
+    }
 }
@@ -0,0 +1,724 @@
+//! Live monitor for pipeline runs: step progress with ETAs, output tails and
+//! skip/retry of failed steps
+
+use anyhow::{anyhow, Result};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame, Terminal,
+};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::history::{format_duration, RunRecorder};
+use super::{Pipeline, PipelineContext, PipelineRunner, PipelineStats, StepTimings};
+use crate::typed_store::models::PipelineRunStatus;
+
+/// Status of one step in a monitored run
+#[derive(Debug, Clone, PartialEq)]
+enum StepStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed(String),
+    Skipped,
+}
+
+/// Progress and output of one step
+#[derive(Debug)]
+struct StepRun {
+    status: StepStatus,
+    started: Option<Instant>,
+    /// Wall-clock start, to tell the step's outputs from its inputs
+    started_at: SystemTime,
+    elapsed: Option<Duration>,
+    expected: Option<Duration>,
+    output: VecDeque<String>,
+    attempts: u32,
+}
+
+/// Progress of the running step, sent from its task
+enum MonitorEvent {
+    Output { index: usize, line: String },
+    Finished { index: usize, result: Result<String> },
+}
+
+/// Output lines kept per step
+const OUTPUT_TAIL: usize = 500;
+
+/// Live view of a pipeline run: the step list with elapsed time and ETA, the
+/// output tail of the selected step, and skip/retry when a step fails
+pub struct PipelineMonitor {
+    pipeline: Arc<Pipeline>,
+    context: Arc<PipelineContext>,
+    runner: Arc<PipelineRunner>,
+    steps: Vec<StepRun>,
+    stats: PipelineStats,
+    timings: StepTimings,
+    recorder: RunRecorder,
+    started: Instant,
+    /// Step whose output is shown
+    selected: usize,
+    /// Whether the selection follows the running step
+    follow: bool,
+    /// Failed step waiting for retry/skip/abort
+    paused_on: Option<usize>,
+    task: Option<JoinHandle<()>>,
+    events_tx: mpsc::UnboundedSender<MonitorEvent>,
+    events_rx: mpsc::UnboundedReceiver<MonitorEvent>,
+    finished: bool,
+    aborted: bool,
+    should_quit: bool,
+}
+
+impl PipelineMonitor {
+    pub fn new(pipeline: Pipeline, context: PipelineContext, runner: PipelineRunner) -> Self {
+        let timings = StepTimings::load(&context);
+        let steps = pipeline
+            .steps
+            .iter()
+            .map(|step| StepRun {
+                status: StepStatus::Pending,
+                started: None,
+                started_at: SystemTime::now(),
+                elapsed: None,
+                expected: timings.expected(&pipeline.name, &step.name),
+                output: VecDeque::new(),
+                attempts: 0,
+            })
+            .collect();
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let recorder = RunRecorder::start(&pipeline, &context);
+        Self {
+            stats: PipelineStats {
+                total_steps: pipeline.steps.len(),
+                ..Default::default()
+            },
+            pipeline: Arc::new(pipeline),
+            context: Arc::new(context),
+            runner: Arc::new(runner),
+            steps,
+            timings,
+            recorder,
+            started: Instant::now(),
+            selected: 0,
+            follow: true,
+            paused_on: None,
+            task: None,
+            events_tx,
+            events_rx,
+            finished: false,
+            aborted: false,
+            should_quit: false,
+        }
+    }
+
+    /// Run the pipeline inside the monitor until the user leaves it
+    pub async fn run(mut self) -> Result<PipelineStats> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        self.started = Instant::now();
+        self.advance_from(0);
+        let result = self.run_app(&mut terminal).await;
+
+        disable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        terminal.show_cursor()?;
+
+        result?;
+        if let Some(task) = self.task.take() {
+            // Dropping the step's future kills its process
+            task.abort();
+        }
+        self.stats.total_duration = self.started.elapsed();
+        if !self.context.dry_run {
+            PipelineRunner::save_run_metadata(&self.pipeline, &self.context, &self.stats);
+            self.timings.save();
+        }
+        let health = if self.aborted {
+            Ok(())
+        } else {
+            self.runner
+                .check_output_health(&self.pipeline, &self.context, &self.recorder)
+        };
+        let status = if self.aborted {
+            PipelineRunStatus::Aborted
+        } else if health.is_err() {
+            PipelineRunStatus::Failed
+        } else {
+            self.recorder.completed_status()
+        };
+        self.runner
+            .record_run(&self.context, self.recorder.finish(status));
+        if self.aborted {
+            return Err(anyhow!(
+                "Pipeline '{}' aborted ({}/{} steps succeeded)",
+                self.pipeline.name,
+                self.stats.successful_steps,
+                self.stats.total_steps
+            ));
+        }
+        health?;
+        Ok(self.stats)
+    }
+
+    async fn run_app(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<()> {
+        loop {
+            while let Ok(event) = self.events_rx.try_recv() {
+                self.apply(event);
+            }
+            terminal.draw(|f| self.ui(f))?;
+
+            if self.should_quit {
+                break;
+            }
+            if event::poll(Duration::from_millis(200))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key_event(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the step at `index`
+    fn start_step(&mut self, index: usize) {
+        let run = &mut self.steps[index];
+        run.attempts += 1;
+        if run.attempts > 1 {
+            run.output
+                .push_back(format!("── retry (attempt {}) ──", run.attempts));
+        }
+        run.status = StepStatus::Running;
+        run.started = Some(Instant::now());
+        run.started_at = SystemTime::now();
+        run.elapsed = None;
+        if self.follow {
+            self.selected = index;
+        }
+
+        let pipeline = self.pipeline.clone();
+        let context = self.context.clone();
+        let runner = self.runner.clone();
+        let events = self.events_tx.clone();
+        let stats = self.stats.clone();
+        let started = self.started;
+        self.task = Some(tokio::spawn(async move {
+            let (line_tx, mut line_rx) = mpsc::unbounded_channel();
+            let run =
+                runner.run_step_streaming(&pipeline, index, &context, &stats, started, line_tx);
+            let forward = async {
+                while let Some(line) = line_rx.recv().await {
+                    let _ = events.send(MonitorEvent::Output { index, line });
+                }
+            };
+            let (result, ()) = tokio::join!(run, forward);
+            let _ = events.send(MonitorEvent::Finished { index, result });
+        }));
+    }
+
+    /// Start the first pending step at or after `index`, or finish the run
+    fn advance_from(&mut self, index: usize) {
+        match (index..self.steps.len()).find(|i| self.steps[*i].status == StepStatus::Pending) {
+            Some(next) => self.start_step(next),
+            None => {
+                self.task = None;
+                self.finished = true;
+            }
+        }
+    }
+
+    fn apply(&mut self, event: MonitorEvent) {
+        match event {
+            MonitorEvent::Output { index, line } => {
+                let output = &mut self.steps[index].output;
+                output.push_back(line);
+                while output.len() > OUTPUT_TAIL {
+                    output.pop_front();
+                }
+            }
+            MonitorEvent::Finished { index, result } => {
+                let step = &self.pipeline.steps[index];
+                let run = &mut self.steps[index];
+                let elapsed = run.started.map(|started| started.elapsed());
+                run.elapsed = elapsed;
+                self.recorder.step_finished(
+                    &self.pipeline,
+                    &self.context,
+                    index,
+                    result.as_ref().err(),
+                    run.started_at,
+                    elapsed.unwrap_or_default(),
+                );
+                match result {
+                    Ok(_) => {
+                        run.status = StepStatus::Succeeded;
+                        self.stats.successful_steps += 1;
+                        if let Some(elapsed) = elapsed.filter(|_| !self.context.dry_run) {
+                            self.timings
+                                .record(&self.pipeline.name, &step.name, elapsed);
+                        }
+                        self.advance_from(index + 1);
+                    }
+                    Err(e) => {
+                        let e = format!("{:#}", e);
+                        run.output.push_back(format!("✗ {}", e));
+                        run.status = StepStatus::Failed(e);
+                        self.stats.failed_steps += 1;
+                        if step.continue_on_error {
+                            self.advance_from(index + 1);
+                        } else {
+                            self.task = None;
+                            self.paused_on = Some(index);
+                            self.selected = index;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.follow = false;
+                self.selected = (self.selected + 1).min(self.steps.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.follow = false;
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Char('f') => {
+                self.follow = true;
+                if let Some(running) = self
+                    .steps
+                    .iter()
+                    .position(|run| run.status == StepStatus::Running)
+                {
+                    self.selected = running;
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Some(index) = self.paused_on.take() {
+                    // The failure is replaced by the retry's outcome
+                    self.stats.failed_steps -= 1;
+                    self.start_step(index);
+                }
+            }
+            KeyCode::Char('s') => {
+                if let Some(index) = self.paused_on.take() {
+                    self.steps[index].status = StepStatus::Skipped;
+                    self.recorder.step_skipped(index);
+                    self.advance_from(index + 1);
+                }
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.aborted = !self.finished;
+                self.should_quit = true;
+            }
+            KeyCode::Enter if self.finished => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(f.area());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[1]);
+
+        self.draw_header(f, rows[0]);
+        self.draw_steps(f, columns[0]);
+        self.draw_output(f, columns[1]);
+        self.draw_monitor_help(f, rows[2]);
+    }
+
+    fn draw_header(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let (state, color) = if let Some(index) = self.paused_on {
+            (
+                format!("Step {} failed, waiting for retry/skip", index + 1),
+                Color::Red,
+            )
+        } else if self.finished {
+            (
+                format!(
+                    "Finished: {}/{} succeeded, {} failed",
+                    self.stats.successful_steps, self.stats.total_steps, self.stats.failed_steps
+                ),
+                if self.stats.failed_steps == 0 {
+                    Color::Green
+                } else {
+                    Color::Yellow
+                },
+            )
+        } else {
+            let done = self
+                .steps
+                .iter()
+                .filter(|run| !matches!(run.status, StepStatus::Pending | StepStatus::Running))
+                .count();
+            (
+                format!("Running ({}/{} done)", done, self.steps.len()),
+                Color::Cyan,
+            )
+        };
+
+        let mut spans = vec![
+            Span::styled(
+                format!("🚀 {}", self.pipeline.name),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            Span::styled(state, Style::default().fg(color)),
+            Span::raw(format!("  ⏱ {}", format_duration(self.started.elapsed()))),
+        ];
+        if let Some(eta) = self.remaining().filter(|_| !self.finished) {
+            spans.push(Span::styled(
+                format!("  ETA ~{}", format_duration(eta)),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        if self.context.dry_run {
+            spans.push(Span::styled(
+                "  (dry run)",
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        let header = Paragraph::new(Line::from(spans)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        f.render_widget(header, area);
+    }
+
+    /// Time left from the expected durations of the running and pending steps
+    fn remaining(&self) -> Option<Duration> {
+        let mut total = Duration::ZERO;
+        for run in &self.steps {
+            match run.status {
+                StepStatus::Pending => total += run.expected?,
+                StepStatus::Running => {
+                    let elapsed = run.started.map(|s| s.elapsed()).unwrap_or_default();
+                    total += run.expected?.saturating_sub(elapsed);
+                }
+                _ => {}
+            }
+        }
+        Some(total)
+    }
+
+    fn draw_steps(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let last = self.steps.len().saturating_sub(1);
+        let items: Vec<ListItem> = self
+            .pipeline
+            .steps
+            .iter()
+            .zip(&self.steps)
+            .enumerate()
+            .map(|(index, (step, run))| {
+                let (icon, color) = match &run.status {
+                    StepStatus::Pending => ("·", Color::Gray),
+                    StepStatus::Running => ("⟳", Color::Cyan),
+                    StepStatus::Succeeded => ("✓", Color::Green),
+                    StepStatus::Failed(_) => ("✗", Color::Red),
+                    StepStatus::Skipped => ("↷", Color::Yellow),
+                };
+                let timing = match (&run.status, run.elapsed, run.expected) {
+                    (StepStatus::Running, _, expected) => {
+                        let elapsed = run.started.map(|s| s.elapsed()).unwrap_or_default();
+                        match expected {
+                            Some(expected) => format!(
+                                "{} / ~{}",
+                                format_duration(elapsed),
+                                format_duration(expected)
+                            ),
+                            None => format_duration(elapsed),
+                        }
+                    }
+                    (StepStatus::Pending, _, Some(expected)) => {
+                        format!("~{}", format_duration(expected))
+                    }
+                    (_, Some(elapsed), _) => format_duration(elapsed),
+                    _ => String::new(),
+                };
+                let connector = if index == last { "└─" } else { "├─" };
+                let retries = if run.attempts > 1 {
+                    format!(" ×{}", run.attempts)
+                } else {
+                    String::new()
+                };
+
+                let style = if index == self.selected {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(connector, Style::default().fg(Color::DarkGray)),
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::styled(
+                        step.name.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" [{}]{}", step.kind(), retries),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(format!("  {}", timing), Style::default().fg(Color::Yellow)),
+                ]))
+                .style(style)
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .title("📋 Steps")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+        f.render_widget(list, area);
+    }
+
+    fn draw_output(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let Some((step, run)) = self
+            .pipeline
+            .steps
+            .get(self.selected)
+            .zip(self.steps.get(self.selected))
+        else {
+            return;
+        };
+        let visible = area.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = run
+            .output
+            .iter()
+            .skip(run.output.len().saturating_sub(visible))
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+
+        let border = match run.status {
+            StepStatus::Failed(_) => Color::Red,
+            StepStatus::Running => Color::Cyan,
+            _ => Color::Green,
+        };
+        let output = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!("📜 {} — output", step.name))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border)),
+        );
+        f.render_widget(output, area);
+    }
+
+    fn draw_monitor_help(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let (text, color) = if self.paused_on.is_some() {
+            (
+                "r: Retry step • s: Skip step • q: Abort pipeline",
+                Color::Red,
+            )
+        } else if self.finished {
+            ("↑/↓: Inspect step output • Enter/q: Close", Color::Gray)
+        } else {
+            (
+                "↑/↓: Inspect step output • f: Follow running step • q: Abort",
+                Color::Gray,
+            )
+        };
+        let help = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(Color::Gray)),
+            )
+            .style(Style::default().fg(color))
+            .alignment(Alignment::Center);
+        f.render_widget(help, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    const TWO_STEPS: &str = r#"
+name: Daily
+steps:
+  - name: Fetch
+    command: fetch-all-markets
+  - name: Enrich
+    command: enrich
+"#;
+
+    fn context(runs_dir: &Path, dry_run: bool) -> PipelineContext {
+        PipelineContext {
+            parameters: HashMap::from([(
+                "runs_dir".to_string(),
+                runs_dir.display().to_string(),
+            )]),
+            dry_run,
+        }
+    }
+
+    fn monitor(yaml: &str, context: PipelineContext) -> PipelineMonitor {
+        let runner = PipelineRunner {
+            binary_name: "polybot".to_string(),
+            verbose: false,
+            history_db: None,
+        };
+        PipelineMonitor::new(serde_yaml::from_str(yaml).unwrap(), context, runner)
+    }
+
+    /// Apply the running step's events until it finishes
+    async fn finish_running_step(monitor: &mut PipelineMonitor) {
+        while let Some(event) = monitor.events_rx.recv().await {
+            let finished = matches!(event, MonitorEvent::Finished { .. });
+            monitor.apply(event);
+            if finished {
+                return;
+            }
+        }
+    }
+
+    fn fail(monitor: &mut PipelineMonitor, index: usize) {
+        monitor.steps[index].status = StepStatus::Running;
+        monitor.apply(MonitorEvent::Finished {
+            index,
+            result: Err(anyhow!("exited with 1")),
+        });
+    }
+
+    #[test]
+    fn steps_expect_their_last_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut timings = StepTimings::load(&context(dir.path(), false));
+        timings.record("Daily", "Fetch", Duration::from_secs(42));
+        timings.save();
+
+        let monitor = monitor(TWO_STEPS, context(dir.path(), false));
+        assert_eq!(monitor.steps[0].expected, Some(Duration::from_secs(42)));
+        assert_eq!(monitor.steps[1].expected, None);
+        // No ETA while a pending step has never run
+        assert_eq!(monitor.remaining(), None);
+    }
+
+    #[test]
+    fn successful_steps_record_their_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let one_step = "name: Daily\nsteps:\n  - name: Fetch\n    command: fetch-all-markets\n";
+        let mut monitor = monitor(one_step, context(dir.path(), false));
+        monitor.steps[0].status = StepStatus::Running;
+        monitor.steps[0].started = Some(Instant::now());
+        monitor.apply(MonitorEvent::Finished {
+            index: 0,
+            result: Ok("done".to_string()),
+        });
+
+        assert_eq!(monitor.steps[0].status, StepStatus::Succeeded);
+        assert!(monitor.finished);
+        assert!(monitor.timings.expected("Daily", "Fetch").is_some());
+    }
+
+    #[tokio::test]
+    async fn dry_runs_stream_each_step_in_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut monitor = monitor(TWO_STEPS, context(dir.path(), true));
+        monitor.advance_from(0);
+        assert_eq!(monitor.steps[0].status, StepStatus::Running);
+
+        finish_running_step(&mut monitor).await;
+        assert_eq!(monitor.steps[0].status, StepStatus::Succeeded);
+        assert_eq!(
+            monitor.steps[0].output.front().map(String::as_str),
+            Some("Dry run: polybot fetch-all-markets ")
+        );
+        assert_eq!(monitor.steps[1].status, StepStatus::Running);
+
+        finish_running_step(&mut monitor).await;
+        assert!(monitor.finished);
+        assert_eq!(monitor.stats.successful_steps, 2);
+        // Dry runs leave the ETAs alone
+        assert_eq!(monitor.timings.expected("Daily", "Fetch"), None);
+    }
+
+    #[tokio::test]
+    async fn failed_step_waits_for_skip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut monitor = monitor(TWO_STEPS, context(dir.path(), true));
+        fail(&mut monitor, 0);
+        assert_eq!(monitor.paused_on, Some(0));
+        assert!(matches!(monitor.steps[0].status, StepStatus::Failed(_)));
+        assert_eq!(monitor.steps[1].status, StepStatus::Pending);
+        assert!(!monitor.finished);
+
+        monitor.handle_key_event(KeyCode::Char('s'));
+        assert_eq!(monitor.steps[0].status, StepStatus::Skipped);
+        assert_eq!(monitor.steps[1].status, StepStatus::Running);
+        finish_running_step(&mut monitor).await;
+        assert!(monitor.finished);
+        assert_eq!(
+            (monitor.stats.successful_steps, monitor.stats.failed_steps),
+            (1, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_replaces_the_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut monitor = monitor(TWO_STEPS, context(dir.path(), true));
+        fail(&mut monitor, 0);
+        assert_eq!(monitor.stats.failed_steps, 1);
+
+        monitor.handle_key_event(KeyCode::Char('r'));
+        assert_eq!(monitor.paused_on, None);
+        assert_eq!(monitor.stats.failed_steps, 0);
+        assert_eq!(monitor.steps[0].status, StepStatus::Running);
+        finish_running_step(&mut monitor).await;
+        assert_eq!(monitor.steps[0].status, StepStatus::Succeeded);
+    }
+
+    #[test]
+    fn quitting_before_the_end_aborts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut monitor = monitor(TWO_STEPS, context(dir.path(), true));
+        fail(&mut monitor, 0);
+        monitor.handle_key_event(KeyCode::Char('q'));
+        assert!(monitor.should_quit && monitor.aborted);
+    }
+}
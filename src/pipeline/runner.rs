@@ -1,15 +1,26 @@
 //! Pipeline execution engine
 
-use super::{Pipeline, PipelineContext, PipelineStep};
+use super::health;
+use super::history::{RunRecorder, StepExit};
+use super::{NotifyStep, Pipeline, PipelineContext, PipelineStep, PublishStep, ValidateStep};
 use crate::markets::datasets::save_command_metadata;
+use crate::typed_store::models::{PipelineRunRecord, PipelineRunStatus};
+use crate::typed_store::pipeline_runs;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// File in the runs directory with each step's last successful duration
+pub const STEP_TIMINGS_FILE: &str = "step_timings.json";
+
 /// Pipeline execution statistics
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct PipelineStats {
     pub total_steps: usize,
     pub successful_steps: usize,
@@ -23,6 +34,8 @@ pub struct PipelineRunner {
     pub binary_name: String,
     /// Whether to show verbose output
     pub verbose: bool,
+    /// Typed store that finished runs are recorded in
+    pub history_db: Option<PathBuf>,
 }
 
 impl PipelineRunner {
@@ -37,6 +50,7 @@ impl PipelineRunner {
         Self {
             binary_name,
             verbose: false,
+            history_db: None,
         }
     }
 
@@ -113,6 +127,84 @@ impl PipelineRunner {
         self
     }
 
+    /// Record finished runs in the typed store at `db_path`
+    pub fn with_history(mut self, db_path: PathBuf) -> Self {
+        self.history_db = Some(db_path);
+        self
+    }
+
+    /// Store a finished run in the run history; dry runs aren't recorded
+    pub fn record_run(&self, context: &PipelineContext, run: PipelineRunRecord) {
+        let Some(db_path) = self.history_db.as_ref().filter(|_| !context.dry_run) else {
+            return;
+        };
+        match pipeline_runs::record(db_path, &run) {
+            Ok(()) => info!("🗂️  Recorded run {}", run.run_id),
+            Err(e) => warn!("Failed to record pipeline run {}: {}", run.run_id, e),
+        }
+    }
+
+    /// Run the health checks of the datasets the run's steps wrote and mark
+    /// them; an error if a check failed and the pipeline fails on that
+    pub fn check_output_health(
+        &self,
+        pipeline: &Pipeline,
+        context: &PipelineContext,
+        recorder: &RunRecorder,
+    ) -> Result<()> {
+        if context.dry_run || !pipeline.health_checks.enabled {
+            return Ok(());
+        }
+        let run = recorder.record();
+        let previous = self
+            .history_db
+            .as_ref()
+            .and_then(|db_path| health::previous_run(db_path, &pipeline.name));
+        let checked = health::check_run(
+            pipeline,
+            context,
+            &run.run_id,
+            &run.steps,
+            previous.as_ref(),
+        );
+
+        let mut failed = Vec::new();
+        for output in &checked {
+            let check = &output.check;
+            let records = if check.records_estimated {
+                format!("~{}", check.records)
+            } else {
+                check.records.to_string()
+            };
+            if check.passed() {
+                info!(
+                    "🩺 {}: {} healthy ({} records)",
+                    output.step, output.path, records
+                );
+            } else {
+                warn!(
+                    "🩺 {}: {} failed its health check: {}",
+                    output.step,
+                    output.path,
+                    check.failures.join("; ")
+                );
+                failed.push(output.path.as_str());
+            }
+            for warning in &check.warnings {
+                warn!("🩺 {}: {}: {}", output.step, output.path, warning);
+            }
+        }
+        if pipeline.health_checks.fail_pipeline && !failed.is_empty() {
+            anyhow::bail!(
+                "Health checks failed for {} of {} datasets: {}",
+                failed.len(),
+                checked.len(),
+                failed.join(", ")
+            );
+        }
+        Ok(())
+    }
+
     /// Execute a complete pipeline
     pub async fn execute_pipeline(
         &self,
@@ -138,13 +230,37 @@ impl PipelineRunner {
             }
         }
 
+        let mut recorder = RunRecorder::start(pipeline, &context);
+        let mut step_durations: Vec<StepDuration> = Vec::new();
         for (step_index, step) in pipeline.steps.iter().enumerate() {
             let step_num = step_index + 1;
-            let step_result = self.execute_step(pipeline, step, &context, step_num).await;
+            let step_start = Instant::now();
+            let step_started_at = SystemTime::now();
+            let step_result = if step.command.is_empty() {
+                self.execute_builtin_step(pipeline, step, &context, step_num, &stats, start_time)
+                    .await
+                    .map(|_| ())
+            } else {
+                self.execute_step(pipeline, step, &context, step_num).await
+            };
+            recorder.step_finished(
+                pipeline,
+                &context,
+                step_index,
+                step_result.as_ref().err(),
+                step_started_at,
+                step_start.elapsed(),
+            );
 
             match step_result {
                 Ok(()) => {
                     stats.successful_steps += 1;
+                    if !context.dry_run {
+                        step_durations.push(StepDuration {
+                            step: &step.name,
+                            elapsed: step_start.elapsed(),
+                        });
+                    }
                 }
                 Err(e) => {
                     stats.failed_steps += 1;
@@ -161,12 +277,18 @@ impl PipelineRunner {
                         );
 
                         stats.total_duration = start_time.elapsed();
+                        self.record_run(&context, recorder.finish(PipelineRunStatus::Failed));
                         return Err(e);
                     }
                 }
             }
         }
 
+        if let Err(e) = self.check_output_health(pipeline, &context, &recorder) {
+            stats.total_duration = start_time.elapsed();
+            self.record_run(&context, recorder.finish(PipelineRunStatus::Failed));
+            return Err(e);
+        }
         stats.total_duration = start_time.elapsed();
 
         // Log summary
@@ -177,7 +299,26 @@ impl PipelineRunner {
         );
         info!("⏱️  Total duration: {:?}", stats.total_duration);
 
-        // Save pipeline metadata if we have a pipeline output directory
+        Self::save_run_metadata(pipeline, &context, &stats);
+        let status = recorder.completed_status();
+        self.record_run(&context, recorder.finish(status));
+        if !step_durations.is_empty() {
+            let mut timings = StepTimings::load(&context);
+            for finished in &step_durations {
+                timings.record(&pipeline.name, finished.step, finished.elapsed);
+            }
+            timings.save();
+        }
+
+        Ok(stats)
+    }
+
+    /// Save the run's metadata into `pipeline_output_dir` so it shows up as a dataset
+    pub fn save_run_metadata(
+        pipeline: &Pipeline,
+        context: &PipelineContext,
+        stats: &PipelineStats,
+    ) {
         if let Some(output_dir) = context.parameters.get("pipeline_output_dir") {
             let pipeline_path = std::path::PathBuf::from(output_dir);
 
@@ -219,7 +360,7 @@ impl PipelineRunner {
                     .map(|step| {
                         serde_json::json!({
                             "name": step.name,
-                            "command": step.command,
+                            "command": step.kind(),
                             "args": step.args
                         })
                     })
@@ -236,8 +377,6 @@ impl PipelineRunner {
                 }
             }
         }
-
-        Ok(stats)
     }
 
     /// Execute a single pipeline step
@@ -270,28 +409,7 @@ impl PipelineRunner {
 
         let step_start = Instant::now();
 
-        // Build command based on binary type
-        let mut cmd = if self.binary_name.starts_with("cargo run") {
-            let mut cargo_cmd = Command::new("cargo");
-            cargo_cmd.arg("run").arg("--").arg(&step.command);
-            cargo_cmd
-        } else {
-            let mut binary_cmd = Command::new(&self.binary_name);
-            binary_cmd.arg(&step.command);
-            binary_cmd
-        };
-
-        // Add resolved arguments
-        for arg in &step.args {
-            let resolved_arg = pipeline.resolve_parameters(arg, context);
-            cmd.arg(resolved_arg);
-        }
-
-        // Set environment variables
-        for (key, value) in &step.env {
-            let resolved_value = pipeline.resolve_parameters(value, context);
-            cmd.env(key, resolved_value);
-        }
+        let mut cmd = self.build_command(pipeline, step, context);
 
         // Configure command execution
         if self.verbose {
@@ -333,7 +451,7 @@ impl PipelineRunner {
             };
 
             // Enhanced error message with context
-            let enhanced_error = anyhow::anyhow!(
+            let enhanced_error = anyhow::Error::new(StepExit(output.status)).context(format!(
                 "❌ Pipeline step '{}' failed.\n\
                  💥 Command: {} {} {}\n\
                  💥 Error details: {}\n\
@@ -348,7 +466,7 @@ impl PipelineRunner {
                 step.command,
                 step.args.join(" "),
                 error_details
-            );
+            ));
 
             return Err(enhanced_error);
         }
@@ -356,6 +474,225 @@ impl PipelineRunner {
         Ok(())
     }
 
+    /// Execute a `publish`, `notify` or `validate` step; returns a one-line summary
+    async fn execute_builtin_step(
+        &self,
+        pipeline: &Pipeline,
+        step: &PipelineStep,
+        context: &PipelineContext,
+        step_num: usize,
+        stats: &PipelineStats,
+        started: Instant,
+    ) -> Result<String> {
+        let total_steps = pipeline.steps.len();
+        let resolve = |text: &str| pipeline.resolve_parameters(text, context);
+
+        info!(
+            "🔄 Step {}/{}: {} ({})",
+            step_num,
+            total_steps,
+            step.name,
+            step.kind()
+        );
+        let step_start = Instant::now();
+
+        let summary = if let Some(publish) = &step.publish {
+            let publish = PublishStep {
+                from: resolve(&publish.from),
+                to: resolve(&publish.to),
+                overwrite: publish.overwrite,
+                headers: publish
+                    .headers
+                    .iter()
+                    .map(|(name, value)| (name.clone(), resolve(value)))
+                    .collect(),
+            };
+            if context.dry_run {
+                format!("Dry run: publish {} -> {}", publish.from, publish.to)
+            } else {
+                let count = publish
+                    .run()
+                    .await
+                    .with_context(|| format!("Pipeline step '{}' failed", step.name))?;
+                format!("Published {} file(s) to {}", count, publish.to)
+            }
+        } else if let Some(notify) = &step.notify {
+            let url = resolve(&notify.url);
+            // Steps before this one; a failed step stops the run unless it continues on error
+            let text = match &notify.message {
+                Some(message) => resolve(message),
+                None => format!(
+                    "{} Pipeline '{}': {}/{} steps succeeded, {} failed ({}s)",
+                    if stats.failed_steps == 0 {
+                        "✅"
+                    } else {
+                        "⚠️"
+                    },
+                    pipeline.name,
+                    stats.successful_steps,
+                    stats.total_steps,
+                    stats.failed_steps,
+                    started.elapsed().as_secs()
+                ),
+            };
+            if context.dry_run {
+                format!("Dry run: notify {}: {}", url, text)
+            } else {
+                let summary = serde_json::json!({
+                    "pipeline": pipeline.name,
+                    "total_steps": stats.total_steps,
+                    "successful_steps": stats.successful_steps,
+                    "failed_steps": stats.failed_steps,
+                    "elapsed_secs": started.elapsed().as_secs(),
+                    "output_dir": context.parameters.get("pipeline_output_dir"),
+                });
+                NotifyStep {
+                    url: url.clone(),
+                    message: Some(text.clone()),
+                }
+                .run(&text, summary)
+                .await
+                .with_context(|| format!("Pipeline step '{}' failed", step.name))?;
+                format!("Notified {}", url)
+            }
+        } else if let Some(validate) = &step.validate {
+            let validate = ValidateStep {
+                path: resolve(&validate.path),
+                ..validate.clone()
+            };
+            if context.dry_run {
+                format!("Dry run: validate {}", validate.path)
+            } else {
+                let report = validate
+                    .run()
+                    .with_context(|| format!("Pipeline step '{}' failed", step.name))?;
+                format!(
+                    "{} record(s) in {} file(s){}",
+                    report.records,
+                    report.files,
+                    report
+                        .health
+                        .map(|health| format!(", health {:?}", health))
+                        .unwrap_or_default()
+                )
+            }
+        } else {
+            anyhow::bail!("Step '{}' is not a built-in step", step.name);
+        };
+
+        info!("   {}", summary);
+        info!(
+            "✅ Step {}/{} completed in {:?}",
+            step_num,
+            total_steps,
+            step_start.elapsed()
+        );
+        Ok(summary)
+    }
+
+    /// Run one step for the live monitor, sending its output line by line
+    ///
+    /// Commands run with piped stdout/stderr and are killed if the returned
+    /// future is dropped; built-in steps send their summary line.
+    pub async fn run_step_streaming(
+        &self,
+        pipeline: &Pipeline,
+        step_index: usize,
+        context: &PipelineContext,
+        stats: &PipelineStats,
+        started: Instant,
+        output: mpsc::UnboundedSender<String>,
+    ) -> Result<String> {
+        let step = &pipeline.steps[step_index];
+        if step.command.is_empty() {
+            let summary = self
+                .execute_builtin_step(pipeline, step, context, step_index + 1, stats, started)
+                .await?;
+            let _ = output.send(summary.clone());
+            return Ok(summary);
+        }
+        if context.dry_run {
+            let resolved_args: Vec<String> = step
+                .args
+                .iter()
+                .map(|arg| pipeline.resolve_parameters(arg, context))
+                .collect();
+            let line = format!(
+                "Dry run: {} {} {}",
+                self.binary_name,
+                step.command,
+                resolved_args.join(" ")
+            );
+            let _ = output.send(line.clone());
+            return Ok(line);
+        }
+
+        let mut cmd = tokio::process::Command::from(self.build_command(pipeline, step, context));
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = cmd.spawn().with_context(|| {
+            format!(
+                "Failed to execute pipeline step '{}' (command: {} {})",
+                step.name, self.binary_name, step.command
+            )
+        })?;
+        let readers = [
+            child
+                .stdout
+                .take()
+                .map(|out| forward_lines(out, output.clone())),
+            child
+                .stderr
+                .take()
+                .map(|err| forward_lines(err, output.clone())),
+        ];
+        let status = child.wait().await?;
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.await;
+        }
+
+        if status.success() {
+            Ok(format!("{} exited successfully", step.command))
+        } else {
+            Err(anyhow::Error::new(StepExit(status)).context(format!(
+                "Pipeline step '{}' failed: {}",
+                step.name, step.command
+            )))
+        }
+    }
+
+    /// The step's command with resolved arguments and environment
+    fn build_command(
+        &self,
+        pipeline: &Pipeline,
+        step: &PipelineStep,
+        context: &PipelineContext,
+    ) -> Command {
+        let mut cmd = if self.binary_name.starts_with("cargo run") {
+            let mut cargo_cmd = Command::new("cargo");
+            cargo_cmd.arg("run").arg("--").arg(&step.command);
+            cargo_cmd
+        } else {
+            let mut binary_cmd = Command::new(&self.binary_name);
+            binary_cmd.arg(&step.command);
+            binary_cmd
+        };
+
+        // Add resolved arguments
+        for arg in &step.args {
+            let resolved_arg = pipeline.resolve_parameters(arg, context);
+            cmd.arg(resolved_arg);
+        }
+
+        // Set environment variables
+        for (key, value) in &step.env {
+            let resolved_value = pipeline.resolve_parameters(value, context);
+            cmd.env(key, resolved_value);
+        }
+        cmd
+    }
+
     /// List available pipelines in a directory
     pub fn list_pipelines(pipelines_dir: &str) -> Result<Vec<String>> {
         let dir = std::path::Path::new(pipelines_dir);
@@ -408,3 +745,107 @@ impl PipelineRunner {
         Ok(pipelines)
     }
 }
+
+/// Send each line read from `reader` until it closes
+fn forward_lines<R>(reader: R, output: mpsc::UnboundedSender<String>) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if output.send(line).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// How long a step of the current run took to succeed
+struct StepDuration<'a> {
+    step: &'a str,
+    elapsed: Duration,
+}
+
+/// Last successful duration of each pipeline step, used for ETAs
+#[derive(Debug, Default)]
+pub struct StepTimings {
+    path: PathBuf,
+    /// Seconds by pipeline name, then step name
+    durations: HashMap<String, HashMap<String, f64>>,
+}
+
+impl StepTimings {
+    /// Timings stored in the context's `runs_dir`
+    pub fn load(context: &PipelineContext) -> Self {
+        let runs_dir = context
+            .parameters
+            .get("runs_dir")
+            .map(String::as_str)
+            .unwrap_or(crate::data_paths::DEFAULT_RUNS_DIR);
+        let path = PathBuf::from(runs_dir).join(STEP_TIMINGS_FILE);
+        let durations = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, durations }
+    }
+
+    pub fn expected(&self, pipeline: &str, step: &str) -> Option<Duration> {
+        self.durations
+            .get(pipeline)?
+            .get(step)
+            .map(|secs| Duration::from_secs_f64(*secs))
+    }
+
+    pub fn record(&mut self, pipeline: &str, step: &str, duration: Duration) {
+        self.durations
+            .entry(pipeline.to_string())
+            .or_default()
+            .insert(step.to_string(), duration.as_secs_f64());
+    }
+
+    /// Write the timings; failures only cost future ETAs
+    pub fn save(&self) {
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                std::fs::write(
+                    &self.path,
+                    serde_json::to_string_pretty(&self.durations).unwrap_or_default(),
+                )
+            });
+        if let Err(e) = result {
+            warn!("Failed to save step timings {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_timings_survive_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = PipelineContext {
+            parameters: HashMap::from([(
+                "runs_dir".to_string(),
+                dir.path().join("runs").display().to_string(),
+            )]),
+            dry_run: false,
+        };
+        let mut timings = StepTimings::load(&context);
+        assert_eq!(timings.expected("Daily", "Fetch"), None);
+        timings.record("Daily", "Fetch", Duration::from_millis(1500));
+        timings.record("Daily", "Fetch", Duration::from_secs(3));
+        timings.save();
+
+        let reloaded = StepTimings::load(&context);
+        assert_eq!(reloaded.expected("Daily", "Fetch"), Some(Duration::from_secs(3)));
+        assert_eq!(reloaded.expected("Daily", "Enrich"), None);
+        assert_eq!(reloaded.expected("Weekly", "Fetch"), None);
+    }
+}
@@ -0,0 +1,337 @@
+//! Built-in pipeline step types
+//!
+//! Besides running a CLI command, a step can carry one of these sections
+//! instead of `command`, so a daily pipeline can check, ship and announce its
+//! own outputs:
+//!
+//! ```yaml
+//! - name: "Check raw markets"
+//!   validate:
+//!     path: "${datasets_dir}/raw_markets/${date}"
+//!     min_records: 1000
+//!     require_healthy: true
+//! - name: "Publish"
+//!   publish:
+//!     from: "${datasets_dir}/raw_markets/${date}"
+//!     to: "/mnt/share/markets/${date}"
+//! - name: "Announce"
+//!   notify:
+//!     url: "https://hooks.example.com/T000/B000"
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+use crate::markets::datasets::{DatasetHealthStatus, DatasetManager, DatasetManagerConfig};
+
+/// Copy a step's outputs to a directory, or upload them to an HTTP endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishStep {
+    /// File or directory to publish
+    #[serde(default = "default_publish_from")]
+    pub from: String,
+    /// Destination directory, or an `http(s)://` base URL each file is PUT under
+    pub to: String,
+    /// Replace files that already exist at a local destination
+    #[serde(default)]
+    pub overwrite: bool,
+    /// Extra headers for uploads (e.g. `Authorization`)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+fn default_publish_from() -> String {
+    "${pipeline_output_dir}".to_string()
+}
+
+/// POST a run summary to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyStep {
+    pub url: String,
+    /// Message text; defaults to a summary of the steps run so far
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Assert record counts and health of a produced dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateStep {
+    /// Dataset directory (or a single file)
+    pub path: String,
+    #[serde(default)]
+    pub min_records: Option<usize>,
+    #[serde(default)]
+    pub max_records: Option<usize>,
+    #[serde(default)]
+    pub min_files: Option<usize>,
+    /// Fail unless the dataset scanner rates the dataset healthy
+    #[serde(default)]
+    pub require_healthy: bool,
+}
+
+/// What a validate step found
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub files: usize,
+    pub records: usize,
+    pub health: Option<DatasetHealthStatus>,
+}
+
+impl PublishStep {
+    /// Publish and return how many files were copied or uploaded
+    pub async fn run(&self) -> Result<usize> {
+        let from = PathBuf::from(&self.from);
+        if !from.exists() {
+            bail!("Nothing to publish: {} does not exist", from.display());
+        }
+        let files = collect_files(&from)?;
+
+        if self.to.starts_with("http://") || self.to.starts_with("https://") {
+            self.upload(&from, &files).await?;
+        } else {
+            let to = PathBuf::from(&self.to);
+            for file in &files {
+                let target = to.join(relative_to(&from, file));
+                if target.exists() && !self.overwrite {
+                    bail!(
+                        "{} already exists (set `overwrite: true` to replace it)",
+                        target.display()
+                    );
+                }
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                fs::copy(file, &target).with_context(|| {
+                    format!("Failed to copy {} to {}", file.display(), target.display())
+                })?;
+            }
+        }
+        Ok(files.len())
+    }
+
+    async fn upload(&self, from: &Path, files: &[PathBuf]) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()?;
+        let base = self.to.trim_end_matches('/');
+        for file in files {
+            let relative = relative_to(from, file).to_string_lossy().replace('\\', "/");
+            let url = format!("{}/{}", base, relative);
+            let mut request = client.put(&url).body(
+                fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?,
+            );
+            for (name, value) in &self.headers {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("Upload to {} failed", url))?;
+            if !response.status().is_success() {
+                bail!("Upload to {} failed with status {}", url, response.status());
+            }
+            info!("   Uploaded {}", relative);
+        }
+        Ok(())
+    }
+}
+
+impl NotifyStep {
+    pub async fn run(&self, text: &str, summary: serde_json::Value) -> Result<()> {
+        // `text` is what Slack-style incoming webhooks display
+        let mut body = serde_json::json!({ "text": text });
+        if let (Some(body), serde_json::Value::Object(summary)) = (body.as_object_mut(), summary) {
+            body.extend(summary);
+        }
+        let response = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Webhook {} unreachable", self.url))?;
+        if !response.status().is_success() {
+            bail!("Webhook {} answered {}", self.url, response.status());
+        }
+        Ok(())
+    }
+}
+
+impl ValidateStep {
+    pub fn run(&self) -> Result<ValidationReport> {
+        let path = PathBuf::from(&self.path);
+        if !path.exists() {
+            bail!("Dataset {} does not exist", path.display());
+        }
+        let files = collect_files(&path)?;
+        let records = files
+            .iter()
+            .map(|file| count_records(file))
+            .sum::<Result<usize>>()?;
+        let health = if path.is_dir() {
+            DatasetManager::new(DatasetManagerConfig::default())
+                .analyze_dataset(&path)
+                .ok()
+                .map(|dataset| dataset.health_status)
+        } else {
+            None
+        };
+        let report = ValidationReport {
+            files: files.len(),
+            records,
+            health,
+        };
+
+        let mut failures = Vec::new();
+        if let Some(min) = self.min_records.filter(|min| records < *min) {
+            failures.push(format!("{} records, expected at least {}", records, min));
+        }
+        if let Some(max) = self.max_records.filter(|max| records > *max) {
+            failures.push(format!("{} records, expected at most {}", records, max));
+        }
+        if let Some(min) = self.min_files.filter(|min| report.files < *min) {
+            failures.push(format!("{} files, expected at least {}", report.files, min));
+        }
+        if self.require_healthy && report.health != Some(DatasetHealthStatus::Healthy) {
+            failures.push(format!(
+                "dataset health is {}",
+                report
+                    .health
+                    .as_ref()
+                    .map(|h| format!("{:?}", h))
+                    .unwrap_or_else(|| "unknown".to_string())
+            ));
+        }
+        if !failures.is_empty() {
+            return Err(anyhow!(
+                "Validation of {} failed: {}",
+                path.display(),
+                failures.join("; ")
+            ));
+        }
+        Ok(report)
+    }
+}
+
+/// Records in one output file: array elements of JSON files, lines of JSONL
+/// files, data rows of CSV files; other files count as none
+pub fn count_records(path: &Path) -> Result<usize> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let read =
+        || fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()));
+    Ok(match extension.as_str() {
+        "json" => {
+            // Metadata files describe the dataset rather than hold records
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if name.contains("metadata") || name.contains("state") {
+                return Ok(0);
+            }
+            match serde_json::from_str::<serde_json::Value>(&read()?)
+                .with_context(|| format!("Invalid JSON in {}", path.display()))?
+            {
+                serde_json::Value::Array(items) => items.len(),
+                serde_json::Value::Object(object) => object
+                    .values()
+                    .find_map(|value| value.as_array().map(Vec::len))
+                    .unwrap_or(1),
+                _ => 0,
+            }
+        }
+        "jsonl" | "ndjson" => read()?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+        "csv" => read()?.lines().count().saturating_sub(1),
+        _ => 0,
+    })
+}
+
+/// Every file under `path` (or `path` itself), sorted
+pub(crate) fn collect_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let entry_path = entry?.path();
+            if entry_path.is_dir() {
+                dirs.push(entry_path);
+            } else {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn relative_to(root: &Path, file: &Path) -> PathBuf {
+    if root.is_file() {
+        return file.file_name().map(PathBuf::from).unwrap_or_default();
+    }
+    file.strip_prefix(root).unwrap_or(file).to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn validate_counts_records_and_publish_copies_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let dataset = dir.path().join("dataset");
+        fs::create_dir_all(dataset.join("chunks")).unwrap();
+        fs::write(
+            dataset.join("chunks/markets_1.json"),
+            r#"[{"id":1},{"id":2}]"#,
+        )
+        .unwrap();
+        fs::write(dataset.join("trades.jsonl"), "{}\n{}\n\n{}\n").unwrap();
+        fs::write(
+            dataset.join("dataset_metadata.json"),
+            r#"{"commands":[1,2]}"#,
+        )
+        .unwrap();
+
+        let mut validate = ValidateStep {
+            path: dataset.to_string_lossy().to_string(),
+            min_records: Some(5),
+            max_records: None,
+            min_files: Some(3),
+            require_healthy: false,
+        };
+        let report = validate.run().unwrap();
+        assert_eq!((report.files, report.records), (3, 5));
+        validate.min_records = Some(6);
+        assert!(validate.run().is_err());
+
+        let publish = PublishStep {
+            from: dataset.to_string_lossy().to_string(),
+            to: dir.path().join("out").to_string_lossy().to_string(),
+            overwrite: false,
+            headers: HashMap::new(),
+        };
+        assert_eq!(publish.run().await.unwrap(), 3);
+        assert!(dir.path().join("out/chunks/markets_1.json").exists());
+        // A second run must not clobber what was published
+        assert!(publish.run().await.is_err());
+    }
+}
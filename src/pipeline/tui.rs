@@ -1,4 +1,4 @@
-//! Interactive TUI for pipeline selection
+//! Interactive TUI for picking a pipeline to run
 
 use anyhow::Result;
 use crossterm::{
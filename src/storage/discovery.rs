@@ -229,7 +229,10 @@ impl DatasetDiscovery {
         let (dataset_type, command_info) = if dataset_yaml_path.exists() {
             if let Ok(metadata) = crate::markets::datasets::load_dataset_metadata(path) {
                 let dataset_type = self.parse_dataset_type(&metadata, category_name);
-                let dataset_command_info = self.convert_command_info(&metadata.command_info);
+                let dataset_command_info = self.convert_command_info(
+                    &metadata.command_info,
+                    metadata.execution_context.clone(),
+                );
                 (dataset_type, dataset_command_info)
             } else {
                 (
@@ -351,7 +354,11 @@ impl DatasetDiscovery {
     }
 
     /// Convert CommandInfo to DatasetCommandInfo
-    fn convert_command_info(&self, command_info: &CommandInfo) -> DatasetCommandInfo {
+    fn convert_command_info(
+        &self,
+        command_info: &CommandInfo,
+        execution_context: Option<ExecutionContext>,
+    ) -> DatasetCommandInfo {
         let detected_command = DetectedCommand {
             command: command_info.command.clone(),
             args: command_info.args.clone(),
@@ -364,7 +371,8 @@ impl DatasetDiscovery {
             detected_commands: vec![detected_command],
             evidence: HashMap::new(),
             confidence: 1.0,
-            execution_context: Some(ExecutionContext {
+            // Recorded by the CLI middleware for datasets written since it existed
+            execution_context: Some(execution_context.unwrap_or_else(|| ExecutionContext {
                 version: command_info.version.clone(),
                 host: None,
                 user: None,
@@ -372,7 +380,7 @@ impl DatasetDiscovery {
                 environment: HashMap::new(),
                 execution_time: command_info.executed_at,
                 duration_seconds: None,
-            }),
+            })),
         }
     }
 
@@ -0,0 +1,714 @@
+//! Bracket orders: a buy entry with linked stop-loss and take-profit exits
+//!
+//! A bracket is a buy entry plus a stop price below it and a target price
+//! above it. Once the entry is done (filled, or cancelled after partial fills)
+//! the filled shares are armed: a take-profit sell rests at the target, and
+//! the stop is watched locally because the exchange has no stop orders. The
+//! exits cancel each other: a filled target closes the bracket and disarms the
+//! stop, and a best bid at or below the stop cancels the target and sells what
+//! is left at the bid. Cancelling the target by hand leaves the stop armed.
+//!
+//! Every leg is placed and cancelled through an [`OrderRoute`], and a bracket
+//! only closes once the exchange reports its exit filled: a stop sell that
+//! leaves the book short of its size is placed again for the rest at the bid,
+//! and one still resting above a bid that kept falling is cancelled and
+//! re-placed at the bid.
+//!
+//! Brackets live in `brackets.json` in the profile's data directory and are
+//! advanced by the daemon or `polybot bracket run`; failed actions are kept
+//! on the bracket and retried on the next pass.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use tracing::warn;
+
+use crate::core::execution::route::OrderRoute;
+use crate::core::json_store::{JsonStore, Record};
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::{ActiveOrder, OrderStatus, TradeExecution};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+pub const BRACKETS_FILE: &str = "brackets.json";
+
+/// How long an order may be missing from both the resting orders and the
+/// trade history before it counts as gone unfilled, since fills show up late
+const FILL_GRACE: i64 = 120;
+
+/// Stop and target prices of a bracket's exits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BracketExits {
+    pub stop: Decimal,
+    pub target: Decimal,
+}
+
+impl BracketExits {
+    /// Check that the exits sit on either side of the entry, inside (0, 1)
+    pub fn validate(&self, entry: Decimal) -> Result<()> {
+        let in_range = |name: &str, price: Decimal| -> Result<()> {
+            if price <= Decimal::ZERO || price >= Decimal::ONE {
+                bail!("The {} price must be between 0 and 1", name);
+            }
+            Ok(())
+        };
+        in_range("entry", entry)?;
+        in_range("stop", self.stop)?;
+        in_range("target", self.target)?;
+        if self.stop >= entry {
+            bail!("The stop ({}) must be below the entry ({})", self.stop, entry);
+        }
+        if self.target <= entry {
+            bail!(
+                "The target ({}) must be above the entry ({})",
+                self.target,
+                entry
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A bracket around an entry order that was just placed
+#[derive(Debug, Clone)]
+pub struct NewBracket {
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub entry_order_id: String,
+    pub entry_price: Decimal,
+    pub size: Decimal,
+    pub exits: BracketExits,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketExit {
+    Target,
+    Stop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BracketState {
+    /// Waiting for the entry to fill
+    PendingEntry,
+    /// `size` shares protected by the resting target and the watched stop
+    Armed {
+        size: Decimal,
+        target_order_id: String,
+    },
+    /// The stop fired: the target is cancelled and `exit_order_id` sells
+    /// `size` shares at the bid
+    Stopping {
+        size: Decimal,
+        exit_order_id: String,
+    },
+    /// One exit filled; `exit_order_id` is the stop's last sell order
+    Closed {
+        exit: BracketExit,
+        exit_order_id: Option<String>,
+    },
+    /// Cancelled by hand, or the entry went away unfilled
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bracket {
+    pub id: u64,
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub entry_order_id: String,
+    pub entry_price: Decimal,
+    pub size: Decimal,
+    pub stop_price: Decimal,
+    pub target_price: Decimal,
+    #[serde(flatten)]
+    pub state: BracketState,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Last failed action, retried on the next pass
+    pub last_error: Option<String>,
+}
+
+impl Record for Bracket {
+    fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl fmt::Display for BracketExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BracketExit::Target => write!(f, "target"),
+            BracketExit::Stop => write!(f, "stop"),
+        }
+    }
+}
+
+/// An order's fills and whether it still rests on the book
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OrderProgress {
+    pub filled: Decimal,
+    pub resting: bool,
+    /// Limit price while it rests
+    pub price: Option<Decimal>,
+}
+
+impl OrderProgress {
+    /// Fills from the resting order or, once it's gone, the trade history
+    pub fn of(order_id: &str, orders: &[ActiveOrder], trades: &[TradeExecution]) -> Self {
+        let order = orders.iter().find(|o| o.order_id == order_id);
+        let traded: Decimal = trades
+            .iter()
+            .filter(|t| t.order_id == order_id)
+            .map(|t| t.size)
+            .sum();
+        let resting = order.filter(|o| {
+            matches!(
+                o.status,
+                OrderStatus::Pending | OrderStatus::Open | OrderStatus::PartiallyFilled
+            )
+        });
+        Self {
+            filled: traded.max(order.map(|o| o.filled_size).unwrap_or_default()),
+            resting: resting.is_some(),
+            price: resting.map(|o| o.price),
+        }
+    }
+}
+
+/// What a pass should do with a bracket
+#[derive(Debug, Clone, PartialEq)]
+pub enum BracketStep {
+    /// Entry done: rest the target for the filled shares
+    Arm { size: Decimal },
+    /// Entry gone without fills
+    EntryCancelled,
+    /// Target filled, the stop is disarmed
+    TargetFilled,
+    /// Sell `size` shares at the bid: on the stop firing, cancelling the
+    /// target first while it rests, again for what an earlier stop sell left
+    /// unfilled, or in place of a stop sell the bid fell below
+    Stop {
+        bid: Decimal,
+        size: Decimal,
+        cancel_resting: bool,
+    },
+    /// The stop sell filled
+    StopFilled,
+}
+
+impl Bracket {
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self.state,
+            BracketState::PendingEntry | BracketState::Armed { .. } | BracketState::Stopping { .. }
+        )
+    }
+
+    /// Whether a pass needs the token's best bid
+    fn watches_bid(&self) -> bool {
+        matches!(
+            self.state,
+            BracketState::Armed { .. } | BracketState::Stopping { .. }
+        )
+    }
+
+    pub fn state_label(&self) -> String {
+        match &self.state {
+            BracketState::PendingEntry => "pending entry".to_string(),
+            BracketState::Armed { size, .. } => format!("armed ({} shares)", size),
+            BracketState::Stopping { size, .. } => format!("stopping ({} shares)", size),
+            BracketState::Closed { exit, .. } => format!("closed by {}", exit),
+            BracketState::Cancelled => "cancelled".to_string(),
+        }
+    }
+
+    /// The exit (the target, or the stop sell) that may rest on the book
+    pub fn exit_order_id(&self) -> Option<&str> {
+        match &self.state {
+            BracketState::Armed {
+                target_order_id, ..
+            } => Some(target_order_id),
+            BracketState::Stopping { exit_order_id, .. } => Some(exit_order_id),
+            _ => None,
+        }
+    }
+
+    /// The order of this bracket that may rest on the book, if any
+    fn live_order_id(&self) -> Option<&str> {
+        match &self.state {
+            BracketState::PendingEntry => Some(&self.entry_order_id),
+            BracketState::Armed {
+                target_order_id, ..
+            } => Some(target_order_id),
+            BracketState::Stopping { exit_order_id, .. } => Some(exit_order_id),
+            BracketState::Closed { .. } | BracketState::Cancelled => None,
+        }
+    }
+
+    /// Next step given the progress of the entry and of the current exit
+    /// order (the target, or the stop sell) and the token's best bid
+    pub fn next_step(
+        &self,
+        entry: OrderProgress,
+        exit: OrderProgress,
+        best_bid: Option<Decimal>,
+        now: DateTime<Utc>,
+    ) -> Option<BracketStep> {
+        let grace = Duration::seconds(FILL_GRACE);
+        match &self.state {
+            BracketState::PendingEntry => {
+                if entry.resting {
+                    None
+                } else if entry.filled > Decimal::ZERO {
+                    Some(BracketStep::Arm {
+                        size: entry.filled.min(self.size),
+                    })
+                } else if now - self.created_at > grace {
+                    Some(BracketStep::EntryCancelled)
+                } else {
+                    None
+                }
+            }
+            BracketState::Armed { size, .. } => {
+                if exit.filled >= *size {
+                    return Some(BracketStep::TargetFilled);
+                }
+                let bid = best_bid.filter(|bid| *bid <= self.stop_price)?;
+                Some(BracketStep::Stop {
+                    bid,
+                    size: *size - exit.filled,
+                    cancel_resting: exit.resting,
+                })
+            }
+            BracketState::Stopping { size, .. } => {
+                if exit.filled >= *size {
+                    return Some(BracketStep::StopFilled);
+                }
+                if now - self.updated_at <= grace {
+                    return None;
+                }
+                let bid = best_bid?;
+                // A sell resting above the bid won't fill while the price falls
+                let stale = exit.price.is_some_and(|price| price > bid);
+                (!exit.resting || stale).then(|| BracketStep::Stop {
+                    bid,
+                    size: *size - exit.filled,
+                    cancel_resting: exit.resting,
+                })
+            }
+            BracketState::Closed { .. } | BracketState::Cancelled => None,
+        }
+    }
+
+    async fn apply(&mut self, route: &OrderRoute<'_>, step: &BracketStep) -> Result<String> {
+        match step {
+            BracketStep::Arm { size } => {
+                let target_order_id = route
+                    .place(Side::Sell, &self.token_id, self.target_price, *size)
+                    .await?;
+                self.state = BracketState::Armed {
+                    size: *size,
+                    target_order_id: target_order_id.clone(),
+                };
+                Ok(format!(
+                    "entry filled, target {} rests at ${}, stop at ${}",
+                    target_order_id, self.target_price, self.stop_price
+                ))
+            }
+            BracketStep::EntryCancelled => {
+                self.state = BracketState::Cancelled;
+                Ok("entry cancelled without fills".to_string())
+            }
+            BracketStep::TargetFilled => {
+                self.state = BracketState::Closed {
+                    exit: BracketExit::Target,
+                    exit_order_id: None,
+                };
+                Ok(format!(
+                    "target filled at ${}, stop disarmed",
+                    self.target_price
+                ))
+            }
+            BracketStep::Stop {
+                bid,
+                size,
+                cancel_resting,
+            } => {
+                // The resting exit holds the shares, so it must be gone before they can be sold
+                if *cancel_resting {
+                    if let Some(order_id) = self.live_order_id() {
+                        route.cancel(order_id).await?;
+                    }
+                }
+                let exit_order_id = route.place(Side::Sell, &self.token_id, *bid, *size).await?;
+                self.state = BracketState::Stopping {
+                    size: *size,
+                    exit_order_id: exit_order_id.clone(),
+                };
+                Ok(format!(
+                    "bid ${} at or below the stop ${}, selling {} at the bid ({})",
+                    bid, self.stop_price, size, exit_order_id
+                ))
+            }
+            BracketStep::StopFilled => {
+                let BracketState::Stopping { exit_order_id, .. } = &self.state else {
+                    bail!("Bracket {} is not stopping", self.id);
+                };
+                let message = format!("stop sell {} filled", exit_order_id);
+                self.state = BracketState::Closed {
+                    exit: BracketExit::Stop,
+                    exit_order_id: Some(exit_order_id.clone()),
+                };
+                Ok(message)
+            }
+        }
+    }
+
+    /// Cancel the bracket and whichever of its orders is resting; shares
+    /// already bought are kept
+    pub async fn cancel(&mut self, route: &OrderRoute<'_>) -> Result<()> {
+        if !self.is_open() {
+            bail!("Bracket {} is already {}", self.id, self.state_label());
+        }
+        if let Some(order_id) = self.live_order_id() {
+            route.cancel(order_id).await?;
+        }
+        self.state = BracketState::Cancelled;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+}
+
+/// Brackets kept as JSON in the data directory
+pub struct BracketStore {
+    file: JsonStore<Vec<Bracket>>,
+}
+
+impl BracketStore {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            file: JsonStore::new(data_paths.data().join(BRACKETS_FILE)),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<Bracket>> {
+        self.file.load()
+    }
+
+    /// Record a bracket around an entry order that was just placed
+    pub fn add(&self, new: NewBracket) -> Result<Bracket> {
+        let now = Utc::now();
+        self.file.insert(|id| Bracket {
+            id,
+            token_id: new.token_id,
+            market_id: new.market_id,
+            entry_order_id: new.entry_order_id,
+            entry_price: new.entry_price,
+            size: new.size,
+            stop_price: new.exits.stop,
+            target_price: new.exits.target,
+            state: BracketState::PendingEntry,
+            created_at: now,
+            updated_at: now,
+            last_error: None,
+        })
+    }
+
+    /// Write back changed brackets, keeping any added since they were loaded
+    pub fn update(&self, changed: &[Bracket]) -> Result<()> {
+        self.file.update(changed)
+    }
+}
+
+/// Outcome of advancing the open brackets one step
+#[derive(Debug, Default)]
+pub struct BracketPass {
+    /// Brackets whose state or last error changed, to write back
+    pub changed: Vec<Bracket>,
+    /// One per action taken
+    pub messages: Vec<String>,
+}
+
+impl BracketPass {
+    /// Tokens whose best bid the open brackets need
+    pub fn watched_tokens(brackets: &[Bracket]) -> Vec<String> {
+        let mut tokens: Vec<String> = brackets
+            .iter()
+            .filter(|b| b.watches_bid())
+            .map(|b| b.token_id.clone())
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+        tokens
+    }
+
+    /// Best bids of `tokens` from the REST books; tokens whose book fails to load are left out
+    pub async fn fetch_best_bids(
+        client: &ClobClient,
+        tokens: &[String],
+    ) -> HashMap<String, Decimal> {
+        let mut bids = HashMap::new();
+        for token_id in tokens {
+            match client.get_order_book(token_id).await {
+                Ok(book) => {
+                    if let Some(bid) = book.bids.iter().map(|l| l.price).max() {
+                        bids.insert(token_id.clone(), bid);
+                    }
+                }
+                Err(e) => warn!("Failed to load the book of {}: {}", token_id, e),
+            }
+        }
+        bids
+    }
+
+    /// Advance every open bracket one step, placing and cancelling its legs through `route`
+    pub async fn run(
+        handlers: &PortfolioCommandHandlers,
+        route: &OrderRoute<'_>,
+        brackets: &[Bracket],
+        best_bids: &HashMap<String, Decimal>,
+    ) -> Result<Self> {
+        let mut pass = Self::default();
+        let open: Vec<&Bracket> = brackets.iter().filter(|b| b.is_open()).collect();
+        if open.is_empty() {
+            return Ok(pass);
+        }
+        let orders = handlers.get_active_orders().await?;
+        let trades = handlers.get_trade_history(None, None).await?;
+        let now = Utc::now();
+
+        for bracket in open {
+            let entry = OrderProgress::of(&bracket.entry_order_id, &orders, &trades);
+            let exit = match &bracket.state {
+                BracketState::Armed {
+                    target_order_id: order_id,
+                    ..
+                }
+                | BracketState::Stopping {
+                    exit_order_id: order_id,
+                    ..
+                } => OrderProgress::of(order_id, &orders, &trades),
+                _ => OrderProgress::default(),
+            };
+            let best_bid = best_bids.get(&bracket.token_id).copied();
+            let Some(step) = bracket.next_step(entry, exit, best_bid, now) else {
+                continue;
+            };
+
+            let mut bracket = bracket.clone();
+            match bracket.apply(route, &step).await {
+                Ok(message) => {
+                    bracket.last_error = None;
+                    pass.messages.push(format!("bracket {}: {}", bracket.id, message));
+                }
+                Err(e) => {
+                    pass.messages
+                        .push(format!("bracket {}: {:?} failed: {}", bracket.id, step, e));
+                    bracket.last_error = Some(e.to_string());
+                }
+            }
+            bracket.updated_at = now;
+            pass.changed.push(bracket);
+        }
+        Ok(pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn bracket(state: BracketState) -> Bracket {
+        let now = Utc::now();
+        Bracket {
+            id: 1,
+            token_id: "123".to_string(),
+            market_id: None,
+            entry_order_id: "0xentry".to_string(),
+            entry_price: dec!(0.50),
+            size: dec!(100),
+            stop_price: dec!(0.40),
+            target_price: dec!(0.65),
+            state,
+            created_at: now - Duration::minutes(5),
+            updated_at: now - Duration::minutes(5),
+            last_error: None,
+        }
+    }
+
+    fn progress(filled: Decimal, resting: bool) -> OrderProgress {
+        OrderProgress {
+            filled,
+            resting,
+            price: None,
+        }
+    }
+
+    fn resting_at(filled: Decimal, price: Decimal) -> OrderProgress {
+        OrderProgress {
+            filled,
+            resting: true,
+            price: Some(price),
+        }
+    }
+
+    #[test]
+    fn exits_arm_on_entry_fill_and_cancel_each_other() {
+        let now = Utc::now();
+        let exits = |stop, target| BracketExits { stop, target };
+        assert!(exits(dec!(0.4), dec!(0.65)).validate(dec!(0.5)).is_ok());
+        assert!(exits(dec!(0.55), dec!(0.65)).validate(dec!(0.5)).is_err());
+
+        // A resting entry waits even when partly filled; a done one arms its fills
+        let pending = bracket(BracketState::PendingEntry);
+        let none = OrderProgress::default();
+        assert_eq!(
+            pending.next_step(progress(dec!(40), true), none, None, now),
+            None
+        );
+        assert_eq!(
+            pending.next_step(progress(dec!(40), false), none, None, now),
+            Some(BracketStep::Arm { size: dec!(40) })
+        );
+        assert_eq!(
+            pending.next_step(none, none, None, now),
+            Some(BracketStep::EntryCancelled)
+        );
+        // Fills may not be reported yet right after the entry leaves the book
+        let fresh = Bracket {
+            created_at: now,
+            ..pending.clone()
+        };
+        assert_eq!(fresh.next_step(none, none, None, now), None);
+
+        let armed = bracket(BracketState::Armed {
+            size: dec!(100),
+            target_order_id: "0xtarget".to_string(),
+        });
+        let target = progress(dec!(30), true);
+        assert_eq!(armed.next_step(none, target, Some(dec!(0.45)), now), None);
+        // The stop sells only what the target hasn't
+        assert_eq!(
+            armed.next_step(none, target, Some(dec!(0.40)), now),
+            Some(BracketStep::Stop {
+                bid: dec!(0.40),
+                size: dec!(70),
+                cancel_resting: true,
+            })
+        );
+        // A target cancelled by hand leaves the stop armed, with nothing to cancel
+        assert_eq!(
+            armed.next_step(none, progress(dec!(30), false), Some(dec!(0.40)), now),
+            Some(BracketStep::Stop {
+                bid: dec!(0.40),
+                size: dec!(70),
+                cancel_resting: false,
+            })
+        );
+        // A filled target disarms the stop
+        assert_eq!(
+            armed.next_step(none, progress(dec!(100), false), Some(dec!(0.30)), now),
+            Some(BracketStep::TargetFilled)
+        );
+
+        let json = serde_json::to_value(&armed).unwrap();
+        assert_eq!(json["state"], "armed");
+        assert_eq!(serde_json::from_value::<Bracket>(json).unwrap(), armed);
+    }
+
+    #[test]
+    fn stop_closes_only_once_its_sell_fills() {
+        let now = Utc::now();
+        let none = OrderProgress::default();
+        let stopping = bracket(BracketState::Stopping {
+            size: dec!(70),
+            exit_order_id: "0xstop".to_string(),
+        });
+        assert!(stopping.is_open());
+
+        // Resting or partly filled, the stop sell is waited on
+        let resting = progress(dec!(20), true);
+        assert_eq!(stopping.next_step(none, resting, Some(dec!(0.35)), now), None);
+        // Gone short of its size, the rest is sold again at the bid
+        assert_eq!(
+            stopping.next_step(none, progress(dec!(20), false), Some(dec!(0.35)), now),
+            Some(BracketStep::Stop {
+                bid: dec!(0.35),
+                size: dec!(50),
+                cancel_resting: false,
+            })
+        );
+        // ...but not before its fills had time to show up
+        let just_placed = Bracket {
+            updated_at: now,
+            ..stopping.clone()
+        };
+        assert_eq!(
+            just_placed.next_step(none, progress(dec!(20), false), Some(dec!(0.35)), now),
+            None
+        );
+        assert_eq!(
+            stopping.next_step(none, progress(dec!(70), false), None, now),
+            Some(BracketStep::StopFilled)
+        );
+
+        // Resting at or below the bid, the stop sell is left alone
+        assert_eq!(
+            stopping.next_step(none, resting_at(dec!(20), dec!(0.35)), Some(dec!(0.35)), now),
+            None
+        );
+
+        let closed = bracket(BracketState::Closed {
+            exit: BracketExit::Stop,
+            exit_order_id: Some("0xstop".to_string()),
+        });
+        assert!(!closed.is_open());
+        assert_eq!(closed.next_step(none, none, Some(dec!(0.3)), now), None);
+        assert_eq!(
+            BracketPass::watched_tokens(&[stopping, closed]),
+            vec!["123".to_string()]
+        );
+    }
+
+    #[test]
+    fn stop_sell_follows_a_falling_bid() {
+        let now = Utc::now();
+        let none = OrderProgress::default();
+        let stopping = |price: Decimal, updated_at| Bracket {
+            updated_at,
+            ..bracket(BracketState::Stopping {
+                size: dec!(70),
+                exit_order_id: format!("0xstop{}", price),
+            })
+        };
+        let mut price = dec!(0.40);
+        let mut placed_at = now - Duration::minutes(5);
+        for bid in [dec!(0.36), dec!(0.31), dec!(0.25)] {
+            let sell = resting_at(dec!(10), price);
+            // Not before the last sell had its grace period
+            assert_eq!(stopping(price, now).next_step(none, sell, Some(bid), now), None);
+            assert_eq!(
+                stopping(price, placed_at).next_step(none, sell, Some(bid), now),
+                Some(BracketStep::Stop {
+                    bid,
+                    size: dec!(60),
+                    cancel_resting: true,
+                })
+            );
+            price = bid;
+            placed_at = now - Duration::seconds(FILL_GRACE + 1);
+        }
+        // Without a bid there's nothing to re-price to
+        assert_eq!(
+            stopping(price, placed_at).next_step(none, resting_at(dec!(10), price), None, now),
+            None
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Strategy control API
+//!
+//! `polybot daemon` hosts a set of strategies and exposes them on a local TCP
+//! port. Requests and responses are single JSON lines, so the GUI (through
+//! [`ControlClient`]) and ad-hoc tools like `nc` can list, start, stop and
+//! retune the same strategies the headless daemon runs. The socket only binds
+//! to loopback, and every request carries the token the daemon keeps in
+//! `auth/control.token` (owner-only permissions), so only processes that can
+//! read the profile's credentials can trade through it. A connection is
+//! closed on its first malformed or unauthorized line, which also cuts off
+//! HTTP requests a browser may be tricked into sending to the port.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+
+use super::flatten::FlattenReport;
+use super::params::{ParamSpec, StrategyParams};
+use crate::data_paths::DataPaths;
+
+/// Port the daemon listens on and the GUI connects to by default
+pub const DEFAULT_CONTROL_PORT: u16 = 8790;
+
+/// How long a client waits for the daemon to answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Token file in the profile's auth directory
+pub const CONTROL_TOKEN_FILE: &str = "control.token";
+
+/// Shared secret every control request must carry
+#[derive(Clone, PartialEq, Eq)]
+pub struct ControlToken {
+    secret: String,
+}
+
+impl std::fmt::Debug for ControlToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ControlToken(..)")
+    }
+}
+
+impl ControlToken {
+    pub fn path(data_paths: &DataPaths) -> PathBuf {
+        data_paths.auth().join(CONTROL_TOKEN_FILE)
+    }
+
+    /// The daemon's token, generated and written owner-only on first start
+    pub fn load_or_create(data_paths: &DataPaths) -> Result<Self> {
+        let path = Self::path(data_paths);
+        if path.exists() {
+            return Self::load(&path);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let token = Self {
+            secret: hex::encode(rand::random::<[u8; 32]>()),
+        };
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        file.write_all(token.secret.as_bytes())?;
+        info!("🔑 Created strategy control token {}", path.display());
+        Ok(token)
+    }
+
+    /// Read the token from `path`, refusing one other users could read
+    pub fn load(path: &Path) -> Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path)
+                .with_context(|| format!("No strategy control token at {}", path.display()))?
+                .permissions()
+                .mode();
+            if mode & 0o077 != 0 {
+                return Err(anyhow!(
+                    "{} is accessible by other users (mode {:o}); run chmod 600 on it",
+                    path.display(),
+                    mode & 0o777
+                ));
+            }
+        }
+        let secret = std::fs::read_to_string(path)
+            .with_context(|| format!("No strategy control token at {}", path.display()))?
+            .trim()
+            .to_string();
+        if secret.is_empty() {
+            return Err(anyhow!("{} is empty", path.display()));
+        }
+        Ok(Self { secret })
+    }
+
+    /// Compare without short-circuiting, so timing does not leak the secret
+    fn matches(&self, presented: &str) -> bool {
+        let expected = self.secret.as_bytes();
+        let presented = presented.as_bytes();
+        expected.len() == presented.len()
+            && expected
+                .iter()
+                .zip(presented)
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// A request line as sent on the wire: the token next to the request's fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ControlEnvelope {
+    token: String,
+    #[serde(flatten)]
+    request: ControlRequest,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Every hosted strategy, running or not
+    List,
+    /// Parameter schema of a strategy kind
+    Schema {
+        kind: String,
+    },
+    /// Start a new strategy; unset parameters take the kind's defaults
+    Start {
+        kind: String,
+        token_id: String,
+        #[serde(default)]
+        params: StrategyParams,
+    },
+    Stop {
+        id: String,
+    },
+    /// Start a stopped strategy again with its last parameters
+    Resume {
+        id: String,
+    },
+    /// Change parameters, restarting the strategy if it is running
+    Update {
+        id: String,
+        params: StrategyParams,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Strategies { strategies: Vec<StrategyStatus> },
+    Schema { params: Vec<ParamSpec> },
+    Started { id: String },
+    Ok,
+    Error { message: String },
+}
+
+impl ControlResponse {
+    pub fn error(e: impl std::fmt::Display) -> Self {
+        Self::Error {
+            message: e.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum StrategyState {
+    Running,
+    Stopped,
+    Failed { error: String },
+}
+
+/// A hosted strategy as reported to control clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyStatus {
+    pub id: String,
+    pub kind: String,
+    pub token_id: String,
+    #[serde(flatten)]
+    pub state: StrategyState,
+    pub params: StrategyParams,
+    pub started_at: Option<DateTime<Utc>>,
+    pub orderbook_updates: u64,
+    pub trades: u64,
+    /// Most recent scheduled flatten of the strategy's position
+    #[serde(default)]
+    pub last_flatten: Option<FlattenReport>,
+}
+
+/// A request from a control connection and where to send its answer
+#[derive(Debug)]
+pub struct ControlCall {
+    pub request: ControlRequest,
+    pub reply: oneshot::Sender<ControlResponse>,
+}
+
+impl ControlCall {
+    /// Hand `request` to the daemon and wait for its answer
+    async fn dispatch(request: ControlRequest, calls: &mpsc::Sender<ControlCall>) -> ControlResponse {
+        let (reply, reply_rx) = oneshot::channel();
+        if calls.send(Self { request, reply }).await.is_err() {
+            return ControlResponse::error("Daemon is shutting down");
+        }
+        reply_rx
+            .await
+            .unwrap_or_else(|_| ControlResponse::error("Daemon dropped the request"))
+    }
+}
+
+/// Listen on `127.0.0.1:port` and forward each request carrying `token` to `calls`
+///
+/// Binding happens before this returns, so a port that is already taken is
+/// reported to the caller; connections are then served in the background.
+pub async fn serve(
+    port: u16,
+    token: ControlToken,
+    calls: mpsc::Sender<ControlCall>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind strategy control port {}", addr))?;
+    info!(
+        "🎛️ Strategy control API listening on {}",
+        listener.local_addr()?
+    );
+
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    debug!("Control connection from {}", peer);
+                    tokio::spawn(handle_connection(stream, token.clone(), calls.clone()));
+                }
+                Err(e) => warn!("Control connection failed: {}", e),
+            }
+        }
+    }))
+}
+
+async fn handle_connection(stream: TcpStream, token: ControlToken, calls: mpsc::Sender<ControlCall>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Anything but an authorized request ends the connection after the error
+        let authorized = match serde_json::from_str::<ControlEnvelope>(&line) {
+            Ok(envelope) if token.matches(&envelope.token) => Ok(envelope.request),
+            Ok(_) => {
+                warn!("Rejected a strategy control request with a wrong token");
+                Err(ControlResponse::error("Unauthorized"))
+            }
+            Err(e) => Err(ControlResponse::error(format!("Invalid request: {}", e))),
+        };
+        let keep_open = authorized.is_ok();
+        let response = match authorized {
+            Ok(request) => ControlCall::dispatch(request, &calls).await,
+            Err(response) => response,
+        };
+
+        let Ok(mut json) = serde_json::to_string(&response) else {
+            break;
+        };
+        json.push('\n');
+        if writer.write_all(json.as_bytes()).await.is_err() || !keep_open {
+            break;
+        }
+    }
+}
+
+/// Client for a daemon's control API
+#[derive(Debug, Clone)]
+pub struct ControlClient {
+    addr: SocketAddr,
+    /// Read on every call, so a daemon started after the client is reachable
+    token_path: PathBuf,
+}
+
+impl ControlClient {
+    pub fn new(addr: SocketAddr, token_path: PathBuf) -> Self {
+        Self { addr, token_path }
+    }
+
+    /// Client for a daemon on this machine running with `data_paths`' profile
+    pub fn local(port: u16, data_paths: &DataPaths) -> Self {
+        Self::new(
+            SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+            ControlToken::path(data_paths),
+        )
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Send one request and wait for its response
+    ///
+    /// Daemon-side failures come back as [`ControlResponse::Error`]; `Err` means
+    /// the daemon could not be reached.
+    pub async fn call(&self, request: &ControlRequest) -> Result<ControlResponse> {
+        tokio::time::timeout(REQUEST_TIMEOUT, self.exchange(request))
+            .await
+            .map_err(|_| anyhow!("Strategy control request to {} timed out", self.addr))?
+    }
+
+    async fn exchange(&self, request: &ControlRequest) -> Result<ControlResponse> {
+        let stream = TcpStream::connect(self.addr).await.with_context(|| {
+            format!(
+                "No daemon control API at {} (is `polybot daemon` running?)",
+                self.addr
+            )
+        })?;
+        let (reader, mut writer) = stream.into_split();
+
+        let envelope = ControlEnvelope {
+            token: ControlToken::load(&self.token_path)?.secret,
+            request: request.clone(),
+        };
+        let mut json = serde_json::to_string(&envelope)?;
+        json.push('\n');
+        writer.write_all(json.as_bytes()).await?;
+
+        let line = BufReader::new(reader)
+            .lines()
+            .next_line()
+            .await?
+            .ok_or_else(|| anyhow!("Daemon closed the control connection"))?;
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_use_the_documented_wire_format() {
+        let request: ControlRequest =
+            serde_json::from_str(r#"{"op":"start","kind":"simple","token_id":"123"}"#).unwrap();
+        assert_eq!(
+            request,
+            ControlRequest::Start {
+                kind: "simple".into(),
+                token_id: "123".into(),
+                params: StrategyParams::new(),
+            }
+        );
+
+        let status = StrategyStatus {
+            id: "simple-1".into(),
+            kind: "simple".into(),
+            token_id: "123".into(),
+            state: StrategyState::Failed {
+                error: "boom".into(),
+            },
+            params: StrategyParams::new(),
+            started_at: None,
+            orderbook_updates: 0,
+            trades: 0,
+            last_flatten: None,
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["state"], "failed");
+        assert_eq!(json["error"], "boom");
+    }
+
+    /// Serve on a free port with a fresh token, answering `Stop` with an error
+    async fn test_daemon(data_paths: &DataPaths) -> u16 {
+        let (tx, mut rx) = mpsc::channel::<ControlCall>(4);
+        // Port 0 would hide the bound port, so probe for a free one first
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let token = ControlToken::load_or_create(data_paths).unwrap();
+        let _server = serve(port, token, tx).await.unwrap();
+
+        tokio::spawn(async move {
+            while let Some(ControlCall { request, reply }) = rx.recv().await {
+                let response = match request {
+                    ControlRequest::Stop { id } => {
+                        ControlResponse::error(format!("No strategy {}", id))
+                    }
+                    _ => ControlResponse::Ok,
+                };
+                let _ = reply.send(response);
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn client_and_server_exchange_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let port = test_daemon(&data_paths).await;
+
+        let client = ControlClient::local(port, &data_paths);
+        assert_eq!(
+            client.call(&ControlRequest::List).await.unwrap(),
+            ControlResponse::Ok
+        );
+        assert_eq!(
+            client
+                .call(&ControlRequest::Stop { id: "x".into() })
+                .await
+                .unwrap(),
+            ControlResponse::error("No strategy x")
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthorized_and_invalid_lines_close_the_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let port = test_daemon(&data_paths).await;
+
+        for first_line in [
+            "{\"token\":\"guess\",\"op\":\"list\"}\n",
+            "POST / HTTP/1.1\n",
+        ] {
+            let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            writer.write_all(first_line.as_bytes()).await.unwrap();
+
+            let mut lines = BufReader::new(reader).lines();
+            let response: ControlResponse =
+                serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+            assert!(matches!(response, ControlResponse::Error { .. }));
+            // The daemon hung up instead of waiting for another request
+            assert!(lines.next_line().await.unwrap().is_none());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        let created = ControlToken::load_or_create(&data_paths).unwrap();
+        let path = ControlToken::path(&data_paths);
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(ControlToken::load(&path).unwrap(), created);
+        assert!(created.matches(&created.secret));
+        assert!(!created.matches("guess"));
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(ControlToken::load(&path).is_err());
+    }
+}
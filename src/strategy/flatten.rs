@@ -0,0 +1,199 @@
+//! Scheduled inventory flattening for hosted strategies
+//!
+//! Strategies that opt in (`auto_flatten`) have their token's position reduced
+//! to `flatten_target` shares at fixed UTC times of day and/or shortly before
+//! their market's end date, so positions aren't carried overnight or into
+//! resolution unattended. The daemon places the reducing order at the touch and
+//! stops the strategy so it doesn't rebuild the position; it can be resumed.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::types::common::Side;
+
+/// When opted-in strategies are flattened
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlattenSchedule {
+    /// UTC times of day
+    pub times: Vec<NaiveTime>,
+    /// Flatten this long before the market's end date
+    pub before_end: Option<chrono::Duration>,
+}
+
+impl FlattenSchedule {
+    /// Schedule from `HH:MM` UTC times and minutes before the end date
+    pub fn new(times: &[String], before_end_minutes: Option<i64>) -> Result<Self> {
+        let times = times
+            .iter()
+            .map(|time| {
+                NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                    .map_err(|_| anyhow!("Invalid flatten time '{}' (expected HH:MM in UTC)", time))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            times,
+            before_end: before_end_minutes.map(chrono::Duration::minutes),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty() && self.before_end.is_none()
+    }
+
+    /// The scheduled time passed in `(last_check, now]`, if any
+    pub fn due_time(&self, last_check: DateTime<Utc>, now: DateTime<Utc>) -> Option<NaiveTime> {
+        self.times.iter().copied().find(|time| {
+            // Checks may straddle midnight, so look at today's and yesterday's occurrence
+            [
+                now.date_naive(),
+                now.date_naive().pred_opt().unwrap_or(now.date_naive()),
+            ]
+            .into_iter()
+            .map(|date| date.and_time(*time).and_utc())
+            .any(|at| last_check < at && at <= now)
+        })
+    }
+
+    /// Whether a market ending at `end` is inside the pre-resolution window
+    pub fn near_end(&self, end: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.before_end
+            .is_some_and(|before| now < end && end - before <= now)
+    }
+}
+
+/// Why a strategy was flattened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum FlattenReason {
+    Scheduled { time: NaiveTime },
+    BeforeResolution { end: DateTime<Utc> },
+}
+
+impl fmt::Display for FlattenReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlattenReason::Scheduled { time } => {
+                write!(f, "scheduled {} UTC", time.format("%H:%M"))
+            }
+            FlattenReason::BeforeResolution { end } => {
+                write!(f, "market ends {}", end.format("%Y-%m-%d %H:%M UTC"))
+            }
+        }
+    }
+}
+
+/// Order that shrinks a position toward its flatten target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReducingOrder {
+    pub side: Side,
+    pub size: Decimal,
+}
+
+impl ReducingOrder {
+    /// Order that brings a long `position` down to `target` shares
+    ///
+    /// Short positions are bought back toward `-target`. Returns `None` when the
+    /// position is already within the target.
+    pub fn between(position: Decimal, target: Decimal) -> Option<Self> {
+        let target = target.abs();
+        if position > target {
+            Some(Self {
+                side: Side::Sell,
+                size: position - target,
+            })
+        } else if position < -target {
+            Some(Self {
+                side: Side::Buy,
+                size: -target - position,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Outcome of one flatten, reported through the control API and the event buffer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlattenReport {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub reason: FlattenReason,
+    /// Signed position before the flatten (negative for shorts)
+    pub position: Decimal,
+    pub target: Decimal,
+    pub side: Option<Side>,
+    pub price: Option<Decimal>,
+    pub size: Option<Decimal>,
+    pub order_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl FlattenReport {
+    pub fn describe(&self) -> String {
+        let action = match (&self.side, &self.size, &self.price, &self.error) {
+            (_, _, _, Some(error)) => format!("failed: {}", error),
+            (Some(side), Some(size), Some(price), None) => {
+                format!("{} {} @ ${}", side, size, price)
+            }
+            _ => "already within target".to_string(),
+        };
+        format!(
+            "flatten ({}): position {} → target {}, {}",
+            self.reason, self.position, self.target, action
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn scheduled_times_fire_once_per_crossing() {
+        let schedule =
+            FlattenSchedule::new(&["23:59".to_string(), "08:00".to_string()], Some(30)).unwrap();
+        let at = |d: u32, h: u32, m: u32| Utc.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+
+        assert_eq!(
+            schedule.due_time(at(1, 7, 59), at(1, 8, 0)),
+            NaiveTime::from_hms_opt(8, 0, 0)
+        );
+        assert_eq!(schedule.due_time(at(1, 8, 0), at(1, 8, 1)), None);
+        // A check across midnight still sees the 23:59 flatten
+        assert_eq!(
+            schedule.due_time(at(1, 23, 58), at(2, 0, 1)),
+            NaiveTime::from_hms_opt(23, 59, 0)
+        );
+
+        let end = at(5, 12, 0);
+        assert!(!schedule.near_end(end, at(5, 11, 29)));
+        assert!(schedule.near_end(end, at(5, 11, 30)));
+        assert!(!schedule.near_end(end, at(5, 12, 0)));
+        assert!(FlattenSchedule::new(&["25:00".to_string()], None).is_err());
+    }
+
+    #[test]
+    fn reducing_orders_never_flip_the_position() {
+        assert_eq!(
+            ReducingOrder::between(dec!(100), dec!(20)),
+            Some(ReducingOrder {
+                side: Side::Sell,
+                size: dec!(80)
+            })
+        );
+        assert_eq!(
+            ReducingOrder::between(dec!(-50), dec!(0)),
+            Some(ReducingOrder {
+                side: Side::Buy,
+                size: dec!(50)
+            })
+        );
+        assert_eq!(ReducingOrder::between(dec!(10), dec!(20)), None);
+        assert_eq!(ReducingOrder::between(dec!(-10), dec!(20)), None);
+    }
+}
@@ -0,0 +1,980 @@
+//! Strategies hosted by the daemon
+//!
+//! [`StrategyHost`] owns every strategy started through the control API,
+//! feeds them the daemon's stream and answers [`ControlRequest`]s. Stopped
+//! strategies stay listed with their parameters so they can be resumed or
+//! retuned; parameter updates rebuild the strategy, since strategy configs are
+//! fixed once constructed. Strategies that opt into auto-flattening are
+//! flattened and stopped on the host's [`FlattenSchedule`], and stale resting
+//! orders are cancelled by the host's [`StaleOrderRule`] and the strategies' own limits.
+//! The host also advances the account's bracket orders (see [`super::brackets`])
+//! and places scheduled orders when they fall due (see [`super::scheduled_orders`]).
+//! Under failover the host hands its strategies and open orders to the next
+//! leader (see [`crate::core::failover`]).
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use polymarket_rs_client::ClobClient;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use super::brackets::{Bracket, BracketPass, BracketStore};
+use super::control::{ControlRequest, ControlResponse, StrategyState, StrategyStatus};
+use super::flatten::{FlattenReason, FlattenReport, FlattenSchedule, ReducingOrder};
+use super::params::{self, StrategyParams, STRATEGY_KINDS};
+use super::scheduled_orders::{DueOrder, ScheduledOrderStore, ScheduledPass};
+use super::simple_strategy::SimpleStrategyConfig;
+use super::stale_orders::{StaleOrderReport, StaleOrderRule};
+use super::{SimpleStrategy, SingleTokenStrategy, TradeEvent};
+use crate::auth::get_authenticated_client;
+use crate::core::diagnostics::{record_event, Subsystem};
+use crate::core::execution::orders::{OrderConfig, PolyBot};
+use crate::core::execution::route::OrderRoute;
+use crate::core::execution::trade_flow::{TradeFlowAnalyzer, TradeFlowEvent};
+use crate::core::failover::{HandoverState, HandoverStrategy, ReconciledOrders};
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::{PositionSide, PositionStatus};
+use crate::core::risk::RiskConfig;
+use crate::core::services::Streamer;
+use crate::core::types::common::Side;
+use crate::core::ws::PolyEvent;
+use crate::data_paths::DataPaths;
+use crate::typed_store::{freshness, token_info};
+
+/// Order placement shared by every hosted strategy
+#[derive(Clone)]
+struct Trading {
+    polybot: Arc<PolyBot>,
+    clob_client: Arc<Mutex<ClobClient>>,
+}
+
+impl Trading {
+    /// Orders through the shared order manager and CLOB client
+    fn route(&self) -> OrderRoute<'_> {
+        OrderRoute::new(&self.polybot.order, &self.clob_client)
+    }
+}
+
+/// A running strategy due for a scheduled or pre-resolution flatten check
+struct FlattenCandidate {
+    id: String,
+    token_id: String,
+}
+
+struct HostedStrategy {
+    id: String,
+    kind: String,
+    token_id: String,
+    config: SimpleStrategyConfig,
+    state: StrategyState,
+    running: Option<Box<dyn SingleTokenStrategy>>,
+    started_at: Option<DateTime<Utc>>,
+    orderbook_updates: u64,
+    trades: u64,
+    last_flatten: Option<FlattenReport>,
+}
+
+impl HostedStrategy {
+    fn status(&self) -> StrategyStatus {
+        StrategyStatus {
+            id: self.id.clone(),
+            kind: self.kind.clone(),
+            token_id: self.token_id.clone(),
+            state: self.state.clone(),
+            params: self.config.params(),
+            started_at: self.started_at,
+            orderbook_updates: self.orderbook_updates,
+            trades: self.trades,
+            last_flatten: self.last_flatten.clone(),
+        }
+    }
+}
+
+pub struct StrategyHost {
+    host: String,
+    data_paths: DataPaths,
+    /// Created on the first start so a daemon without strategies needs no credentials
+    trading: Option<Trading>,
+    strategies: Vec<HostedStrategy>,
+    next_id: u64,
+    /// Sweeps and large prints over every streamed token
+    trade_flow: TradeFlowAnalyzer,
+    flatten_schedule: FlattenSchedule,
+    last_flatten_check: DateTime<Utc>,
+    /// Market end dates by token, `None` when the store doesn't know the token
+    end_dates: HashMap<String, Option<DateTime<Utc>>>,
+    /// Strategies already flattened ahead of their market's end
+    flattened_before_end: HashSet<String>,
+    /// Positions and order placement for flattens, created on the first one
+    portfolio: Option<PortfolioCommandHandlers>,
+    /// Daemon-wide stale order limits
+    stale_rule: StaleOrderRule,
+    /// Report stale orders instead of cancelling them
+    stale_dry_run: bool,
+    /// Stale orders already reported in dry-run mode, and bracket exits kept
+    stale_reported: HashSet<String>,
+}
+
+impl StrategyHost {
+    pub fn new(host: &str, data_paths: DataPaths) -> Self {
+        Self {
+            host: host.to_string(),
+            data_paths,
+            trading: None,
+            strategies: Vec::new(),
+            next_id: 1,
+            trade_flow: TradeFlowAnalyzer::default(),
+            flatten_schedule: FlattenSchedule::default(),
+            last_flatten_check: Utc::now(),
+            end_dates: HashMap::new(),
+            flattened_before_end: HashSet::new(),
+            portfolio: None,
+            stale_rule: StaleOrderRule::default(),
+            stale_dry_run: false,
+            stale_reported: HashSet::new(),
+        }
+    }
+
+    /// Flatten opted-in strategies on `schedule`
+    pub fn with_flatten_schedule(mut self, schedule: FlattenSchedule) -> Self {
+        self.flatten_schedule = schedule;
+        self
+    }
+
+    /// Cancel stale resting orders by `rule`; with `dry_run` only report them
+    pub fn with_stale_order_rule(mut self, rule: StaleOrderRule, dry_run: bool) -> Self {
+        self.stale_rule = rule;
+        self.stale_dry_run = dry_run;
+        self
+    }
+
+    pub fn statuses(&self) -> Vec<StrategyStatus> {
+        self.strategies.iter().map(HostedStrategy::status).collect()
+    }
+
+    /// Answer a control request; `streamed` are the tokens the daemon follows
+    pub async fn handle(
+        &mut self,
+        request: ControlRequest,
+        streamed: &[String],
+    ) -> ControlResponse {
+        let result = match request {
+            ControlRequest::List => Ok(ControlResponse::Strategies {
+                strategies: self.statuses(),
+            }),
+            ControlRequest::Schema { kind } => params::schema(&kind)
+                .map(|params| ControlResponse::Schema { params })
+                .ok_or_else(|| unknown_kind(&kind)),
+            ControlRequest::Start {
+                kind,
+                token_id,
+                params,
+            } => self
+                .start(kind, token_id, &params, streamed)
+                .await
+                .map(|id| ControlResponse::Started { id }),
+            ControlRequest::Stop { id } => self.stop(&id).await.map(|_| ControlResponse::Ok),
+            ControlRequest::Resume { id } => self.resume(&id).await.map(|_| ControlResponse::Ok),
+            ControlRequest::Update { id, params } => {
+                self.update(&id, &params).await.map(|_| ControlResponse::Ok)
+            }
+        };
+        result.unwrap_or_else(ControlResponse::error)
+    }
+
+    async fn start(
+        &mut self,
+        kind: String,
+        token_id: String,
+        params: &StrategyParams,
+        streamed: &[String],
+    ) -> Result<String> {
+        if !STRATEGY_KINDS.contains(&kind.as_str()) {
+            return Err(unknown_kind(&kind));
+        }
+        if !streamed.contains(&token_id) {
+            return Err(anyhow!(
+                "Token {} is not streamed by this daemon (start it with --assets)",
+                token_id
+            ));
+        }
+        let config = SimpleStrategyConfig::default().with_params(params)?;
+
+        let id = format!("{}-{}", kind, self.next_id);
+        self.next_id += 1;
+        self.strategies.push(HostedStrategy {
+            id: id.clone(),
+            kind,
+            token_id,
+            config,
+            state: StrategyState::Stopped,
+            running: None,
+            started_at: None,
+            orderbook_updates: 0,
+            trades: 0,
+            last_flatten: None,
+        });
+        self.launch(&id).await?;
+        Ok(id)
+    }
+
+    async fn stop(&mut self, id: &str) -> Result<()> {
+        let strategy = self.get_mut(id)?;
+        if let Some(running) = strategy.running.take() {
+            running.shutdown().await?;
+            info!("⏹️ Stopped strategy {}", id);
+        }
+        strategy.state = StrategyState::Stopped;
+        Ok(())
+    }
+
+    async fn resume(&mut self, id: &str) -> Result<()> {
+        if self.get_mut(id)?.running.is_some() {
+            return Err(anyhow!("Strategy {} is already running", id));
+        }
+        self.launch(id).await
+    }
+
+    async fn update(&mut self, id: &str, params: &StrategyParams) -> Result<()> {
+        let strategy = self.get_mut(id)?;
+        strategy.config = strategy.config.with_params(params)?;
+        info!("🎛️ Updated parameters of strategy {}", id);
+
+        if strategy.running.is_some() {
+            self.stop(id).await?;
+            self.launch(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Build and start the strategy `id` from its current config
+    async fn launch(&mut self, id: &str) -> Result<()> {
+        let trading = match self.trading_handles().await {
+            Ok(trading) => trading,
+            Err(e) => {
+                self.get_mut(id)?.state = StrategyState::Failed {
+                    error: e.to_string(),
+                };
+                return Err(e);
+            }
+        };
+
+        let strategy = self.get_mut(id)?;
+        let mut running = SimpleStrategy::new(
+            strategy.token_id.clone(),
+            strategy.config.clone(),
+            trading.polybot,
+        );
+        running.set_clob_client(trading.clob_client);
+        info!(
+            "▶️ Started strategy {} ({}) on {}",
+            id,
+            running.name(),
+            strategy.token_id
+        );
+
+        strategy.running = Some(Box::new(running));
+        strategy.state = StrategyState::Running;
+        strategy.started_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn trading_handles(&mut self) -> Result<Trading> {
+        if self.trading.is_none() {
+            let clob_client = get_authenticated_client(&self.host, &self.data_paths).await?;
+            let order_config = OrderConfig {
+                enable_detailed_logging: false,
+                ..Default::default()
+            };
+            let risk_config = RiskConfig::load(&self.data_paths)?;
+            let order_signer = crate::auth::order_signer(&self.host, &self.data_paths).await?;
+            self.trading = Some(Trading {
+                polybot: Arc::new(
+                    PolyBot::with_risk_config(order_config, risk_config)
+                        .with_order_signer(order_signer),
+                ),
+                clob_client: Arc::new(Mutex::new(clob_client)),
+            });
+        }
+        Ok(self.trading.clone().expect("trading initialised above"))
+    }
+
+    /// Feed a streaming event to the running strategies on its token
+    pub async fn on_event(&mut self, event: &PolyEvent, streamer: &Streamer) {
+        let trade = Option::<TradeEvent>::from(event);
+        let flow_events = match event {
+            PolyEvent::Trade {
+                asset_id,
+                price,
+                size,
+                side,
+            } => self.trade_flow.observe(
+                asset_id,
+                *side,
+                *price,
+                *size,
+                Utc::now().timestamp_millis() as u64,
+            ),
+            _ => Vec::new(),
+        };
+        let book_asset = match event {
+            PolyEvent::Book { asset_id, .. } | PolyEvent::PriceChange { asset_id, .. } => {
+                Some(asset_id.as_str())
+            }
+            _ => None,
+        };
+
+        for strategy in &mut self.strategies {
+            let Some(running) = &strategy.running else {
+                continue;
+            };
+            if let Some(trade) = trade.as_ref().filter(|t| t.asset_id == strategy.token_id) {
+                strategy.trades += 1;
+                if let Err(e) = running.trade_event(trade).await {
+                    error!("[{}] Trade event error: {}", strategy.id, e);
+                }
+            }
+            if book_asset == Some(strategy.token_id.as_str()) {
+                if let Some(book) = streamer.get_order_book(&strategy.token_id) {
+                    strategy.orderbook_updates += 1;
+                    if let Err(e) = running.orderbook_update(&book).await {
+                        error!("[{}] Order book update error: {}", strategy.id, e);
+                    }
+                }
+            }
+        }
+        self.dispatch_trade_flow(&flow_events).await;
+    }
+
+    /// Report sweeps whose window closed since the last trade on their token
+    ///
+    /// Called periodically by the daemon, so a sweep reaches strategies when it
+    /// ends instead of when the next trade arrives.
+    pub async fn close_trade_flow(&mut self) {
+        let flow_events = self
+            .trade_flow
+            .flush_expired(Utc::now().timestamp_millis() as u64);
+        self.dispatch_trade_flow(&flow_events).await;
+    }
+
+    /// Log trade flow detections and pass each to the running strategies on its token
+    async fn dispatch_trade_flow(&self, flow_events: &[TradeFlowEvent]) {
+        for flow_event in flow_events {
+            info!("🐋 {}", flow_event.describe());
+            record_event(Subsystem::Strategy, flow_event.describe());
+            for strategy in self
+                .strategies
+                .iter()
+                .filter(|s| s.token_id == flow_event.asset_id())
+            {
+                let Some(running) = &strategy.running else {
+                    continue;
+                };
+                if let Err(e) = running.trade_flow_event(flow_event).await {
+                    error!("[{}] Trade flow event error: {}", strategy.id, e);
+                }
+            }
+        }
+    }
+
+    /// Flatten the opted-in running strategies that are due
+    ///
+    /// Called periodically by the daemon; a scheduled time fires once for the
+    /// first check after it, a market's pre-resolution window once per strategy.
+    pub async fn run_flatten_schedule(&mut self, streamer: &Streamer) {
+        if self.flatten_schedule.is_empty() {
+            return;
+        }
+        let now = Utc::now();
+        let scheduled = self.flatten_schedule.due_time(self.last_flatten_check, now);
+        self.last_flatten_check = now;
+
+        let candidates: Vec<FlattenCandidate> = self
+            .strategies
+            .iter()
+            .filter(|s| s.running.is_some() && s.config.auto_flatten)
+            .map(|s| FlattenCandidate {
+                id: s.id.clone(),
+                token_id: s.token_id.clone(),
+            })
+            .collect();
+        for FlattenCandidate { id, token_id } in candidates {
+            let reason = match scheduled {
+                Some(time) => Some(FlattenReason::Scheduled { time }),
+                None if self.flattened_before_end.contains(&id) => None,
+                None => self
+                    .end_date(&token_id)
+                    .await
+                    .filter(|end| self.flatten_schedule.near_end(*end, now))
+                    .map(|end| FlattenReason::BeforeResolution { end }),
+            };
+            if let Some(reason) = reason {
+                self.flatten(&id, reason, streamer).await;
+            }
+        }
+    }
+
+    /// End date of the token's market from the typed store, looked up once
+    async fn end_date(&mut self, token_id: &str) -> Option<DateTime<Utc>> {
+        if self.flatten_schedule.before_end.is_none() {
+            return None;
+        }
+        if let Some(end) = self.end_dates.get(token_id) {
+            return *end;
+        }
+        let db_path = freshness::default_db_path(&self.data_paths);
+        let key = token_id.to_string();
+        let info = tokio::task::spawn_blocking(move || token_info::lookup(&db_path, &key)).await;
+        let end = match info {
+            Ok(Ok(info)) => info
+                .and_then(|info| info.end_date_iso)
+                .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+                .map(|end| end.with_timezone(&Utc)),
+            Ok(Err(e)) => {
+                warn!("Failed to look up the end date of {}: {}", token_id, e);
+                None
+            }
+            Err(e) => {
+                warn!("End date lookup of {} panicked: {}", token_id, e);
+                None
+            }
+        };
+        if end.is_none() {
+            warn!(
+                "No end date known for {}; run `polybot index` to flatten it before resolution",
+                token_id
+            );
+        }
+        self.end_dates.insert(token_id.to_string(), end);
+        end
+    }
+
+    /// Stop strategy `id` and reduce its token's position to the strategy's target
+    async fn flatten(&mut self, id: &str, reason: FlattenReason, streamer: &Streamer) {
+        if let Err(e) = self.stop(id).await {
+            error!("Failed to stop strategy {} before flattening: {}", id, e);
+        }
+        let Ok(strategy) = self.get_mut(id) else {
+            return;
+        };
+        let (token_id, target) = (strategy.token_id.clone(), strategy.config.flatten_target);
+
+        let mut report = FlattenReport {
+            at: Utc::now(),
+            reason: reason.clone(),
+            position: Decimal::ZERO,
+            target,
+            side: None,
+            price: None,
+            size: None,
+            order_id: None,
+            error: None,
+        };
+        if let Err(e) = self.place_flatten(&token_id, &mut report, streamer).await {
+            report.error = Some(e.to_string());
+        }
+
+        let message = format!("[{}] {}", id, report.describe());
+        if report.error.is_some() {
+            error!("📉 {}", message);
+        } else {
+            info!("📉 {}", message);
+        }
+        record_event(Subsystem::Strategy, message);
+        if matches!(reason, FlattenReason::BeforeResolution { .. }) {
+            self.flattened_before_end.insert(id.to_string());
+        }
+        if let Ok(strategy) = self.get_mut(id) {
+            strategy.last_flatten = Some(report);
+        }
+    }
+
+    /// Place the order reducing `token_id`'s position, filling in `report`
+    async fn place_flatten(
+        &mut self,
+        token_id: &str,
+        report: &mut FlattenReport,
+        streamer: &Streamer,
+    ) -> Result<()> {
+        self.ensure_portfolio().await?;
+        let handlers = self
+            .portfolio
+            .as_ref()
+            .expect("portfolio initialised above");
+        if let Err(e) = handlers.refresh_data().await {
+            warn!(
+                "Portfolio refresh failed, flattening from cached positions: {}",
+                e
+            );
+        }
+        let position = handlers
+            .get_portfolio_state()
+            .await?
+            .positions
+            .into_iter()
+            .find(|p| p.token_id == token_id && p.status == PositionStatus::Open);
+        report.position = match &position {
+            Some(p) if p.side == PositionSide::Short => -p.size,
+            Some(p) => p.size,
+            None => Decimal::ZERO,
+        };
+
+        let Some(ReducingOrder { side, size }) = ReducingOrder::between(report.position, report.target)
+        else {
+            return Ok(());
+        };
+        // Cross the spread so the position is gone by the time the window starts
+        let book = streamer
+            .get_order_book(token_id)
+            .ok_or_else(|| anyhow!("No order book streamed for {}", token_id))?;
+        let touch = match side {
+            Side::Sell => book.best_bid(),
+            Side::Buy => book.best_ask(),
+        };
+        let price = touch
+            .map(|level| level.price)
+            .ok_or_else(|| anyhow!("Book is empty on the {} side", side))?;
+        report.side = Some(side);
+        report.price = Some(price);
+        report.size = Some(size);
+
+        let trading = self.trading_handles().await?;
+        report.order_id = Some(trading.route().place(side, token_id, price, size).await?);
+        Ok(())
+    }
+
+    async fn ensure_portfolio(&mut self) -> Result<()> {
+        if self.portfolio.is_none() {
+            self.portfolio = Some(
+                PortfolioCommandHandlers::new(self.host.clone(), self.data_paths.clone()).await?,
+            );
+        }
+        Ok(())
+    }
+
+    /// Stale order limits for the orders on a running strategy's token: the
+    /// strategy's own where set, the daemon's otherwise
+    fn stale_rule_for(&self, token_id: &str) -> StaleOrderRule {
+        let Some(config) = self
+            .strategies
+            .iter()
+            .find(|s| s.token_id == token_id && s.running.is_some())
+            .map(|s| &s.config)
+        else {
+            return self.stale_rule;
+        };
+        StaleOrderRule {
+            max_age: match config.stale_order_age.as_secs() {
+                0 => self.stale_rule.max_age,
+                secs => Some(chrono::Duration::seconds(secs as i64)),
+            },
+            max_drift_ticks: match config.stale_order_ticks {
+                0 => self.stale_rule.max_drift_ticks,
+                ticks => Some(ticks),
+            },
+        }
+    }
+
+    /// Cancel the stale resting orders on the streamed tokens, or report them in dry-run mode
+    ///
+    /// Called periodically by the daemon. Drift is measured from the streamed
+    /// mid in ticks of the book's tick size, falling back to the strategy's.
+    pub async fn run_stale_order_check(&mut self, streamer: &Streamer, streamed: &[String]) {
+        let any_rule = !self.stale_rule.is_empty()
+            || self
+                .strategies
+                .iter()
+                .any(|s| !self.stale_rule_for(&s.token_id).is_empty());
+        if !any_rule {
+            return;
+        }
+        if let Err(e) = self.cancel_stale_orders(streamer, streamed).await {
+            warn!("Stale order check failed: {}", e);
+        }
+    }
+
+    async fn cancel_stale_orders(
+        &mut self,
+        streamer: &Streamer,
+        streamed: &[String],
+    ) -> Result<()> {
+        self.ensure_portfolio().await?;
+        let handlers = self
+            .portfolio
+            .as_ref()
+            .expect("portfolio initialised above");
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, checking cached orders: {}", e);
+        }
+        let orders = handlers.get_active_orders().await?;
+        let now = Utc::now();
+        // Bracket exits rest far from the mid on purpose; cancelling one would
+        // move its bracket on as if the exit had gone away
+        let bracket_exits: HashMap<String, u64> = BracketStore::new(&self.data_paths)
+            .load()
+            .context("Failed to load bracket orders")?
+            .iter()
+            .filter(|b| b.is_open())
+            .filter_map(|b| Some((b.exit_order_id()?.to_string(), b.id)))
+            .collect();
+
+        for order in orders.iter().filter(|o| streamed.contains(&o.token_id)) {
+            let strategy = self
+                .strategies
+                .iter()
+                .find(|s| s.token_id == order.token_id && s.running.is_some());
+            let book = streamer.get_order_book(&order.token_id);
+            let mid = book.as_ref().and_then(|book| {
+                Some((book.best_bid()?.price + book.best_ask()?.price) / Decimal::TWO)
+            });
+            let tick_size = book
+                .as_ref()
+                .and_then(|book| book.tick_size)
+                .or(strategy.map(|s| s.config.tick_size))
+                .unwrap_or(Decimal::new(1, 2));
+            let Some(reason) = self
+                .stale_rule_for(&order.token_id)
+                .check(order, tick_size, mid, now)
+            else {
+                continue;
+            };
+            let bracket_id = bracket_exits.get(&order.order_id).copied();
+            if (self.stale_dry_run || bracket_id.is_some())
+                && !self.stale_reported.insert(order.order_id.clone())
+            {
+                continue;
+            }
+
+            let mut report = StaleOrderReport {
+                at: now,
+                order_id: order.order_id.clone(),
+                token_id: order.token_id.clone(),
+                side: order.side,
+                price: order.price,
+                remaining_size: order.remaining_size,
+                reason,
+                strategy_id: strategy.map(|s| s.id.clone()),
+                bracket_id,
+                cancelled: false,
+                error: None,
+            };
+            if !self.stale_dry_run && bracket_id.is_none() {
+                let cancelled = match self.trading_handles().await {
+                    Ok(trading) => trading.route().cancel(&order.order_id).await,
+                    Err(e) => Err(e),
+                };
+                match cancelled {
+                    Ok(()) => report.cancelled = true,
+                    Err(e) => report.error = Some(e.to_string()),
+                }
+            }
+
+            let message = match &report.strategy_id {
+                Some(id) => format!("[{}] {}", id, report.describe()),
+                None => report.describe(),
+            };
+            if report.error.is_some() {
+                error!("🧹 {}", message);
+            } else {
+                info!("🧹 {}", message);
+            }
+            record_event(Subsystem::Strategy, message);
+        }
+        Ok(())
+    }
+
+    /// Arm and fire the exits of the bracket orders
+    ///
+    /// Called periodically by the daemon. Stops trigger on the streamed best
+    /// bid, or the REST book's for tokens the daemon doesn't stream.
+    pub async fn run_brackets(&mut self, streamer: &Streamer) {
+        let store = BracketStore::new(&self.data_paths);
+        let open: Vec<_> = match store.load() {
+            Ok(all) => all.into_iter().filter(|b| b.is_open()).collect(),
+            Err(e) => {
+                warn!("Failed to load bracket orders: {}", e);
+                return;
+            }
+        };
+        if open.is_empty() {
+            return;
+        }
+        if let Err(e) = self.advance_brackets(streamer, &store, &open).await {
+            warn!("Bracket order pass failed: {}", e);
+        }
+    }
+
+    async fn advance_brackets(
+        &mut self,
+        streamer: &Streamer,
+        store: &BracketStore,
+        open: &[Bracket],
+    ) -> Result<()> {
+        let trading = self.trading_handles().await?;
+        self.ensure_portfolio().await?;
+        let handlers = self
+            .portfolio
+            .as_ref()
+            .expect("portfolio initialised above");
+        if let Err(e) = handlers.refresh_data().await {
+            warn!("Portfolio refresh failed, checking cached orders: {}", e);
+        }
+
+        let mut best_bids = HashMap::new();
+        let mut unstreamed = Vec::new();
+        for token_id in BracketPass::watched_tokens(open) {
+            match streamer
+                .get_order_book(&token_id)
+                .and_then(|book| book.best_bid().map(|level| level.price))
+            {
+                Some(bid) => {
+                    best_bids.insert(token_id, bid);
+                }
+                None => unstreamed.push(token_id),
+            }
+        }
+        if !unstreamed.is_empty() {
+            let client = ClobClient::new(&self.host);
+            best_bids.extend(BracketPass::fetch_best_bids(&client, &unstreamed).await);
+        }
+
+        let pass = BracketPass::run(handlers, &trading.route(), open, &best_bids).await?;
+        for message in pass.messages {
+            info!("🎯 {}", message);
+            record_event(Subsystem::Strategy, message);
+        }
+        store.update(&pass.changed)
+    }
+
+    /// Place the scheduled orders that are due, or mark them missed when too late
+    ///
+    /// Called every second by the daemon.
+    pub async fn run_scheduled_orders(&mut self) {
+        let store = ScheduledOrderStore::new(&self.data_paths);
+        let now = Utc::now();
+        match store.load() {
+            Ok(orders) if DueOrder::select(orders, now).is_empty() => return,
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Failed to load scheduled orders: {}", e);
+                return;
+            }
+        }
+        let trading = match self.trading_handles().await {
+            Ok(trading) => trading,
+            Err(e) => {
+                warn!("Scheduled orders are due but trading is unavailable: {}", e);
+                return;
+            }
+        };
+        // Nothing goes out unless the claim is on disk; a claimed order whose
+        // outcome fails to save stays placing and is never placed again
+        let due = match store.claim_due(now) {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Failed to claim due scheduled orders, skipping this check: {}", e);
+                return;
+            }
+        };
+
+        let pass = ScheduledPass::run(&trading.route(), due, now).await;
+        for message in pass.messages {
+            info!("⏰ {}", message);
+            record_event(Subsystem::Strategy, message);
+        }
+        if let Err(e) = store.update(&pass.changed) {
+            error!(
+                "Failed to save the outcome of scheduled orders, they stay marked placing: {}",
+                e
+            );
+        }
+    }
+
+    pub async fn process_pending_orders(&self) {
+        for strategy in &self.strategies {
+            if let Some(running) = &strategy.running {
+                if let Err(e) = running.process_pending_orders().await {
+                    error!("[{}] Failed to process pending orders: {}", strategy.id, e);
+                }
+            }
+        }
+    }
+
+    /// Stop every running strategy
+    pub async fn shutdown(&mut self) {
+        let ids: Vec<String> = self
+            .strategies
+            .iter()
+            .filter(|s| s.running.is_some())
+            .map(|s| s.id.clone())
+            .collect();
+        for id in ids {
+            if let Err(e) = self.stop(&id).await {
+                error!("Failed to stop strategy {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Strategies and tracked open orders for the next leader of a failover group
+    pub async fn handover_state(&self) -> HandoverState {
+        let open_orders = match &self.trading {
+            Some(trading) => trading.polybot.order.get_active_orders().await,
+            None => Vec::new(),
+        };
+        HandoverState {
+            next_strategy_id: self.next_id,
+            strategies: self
+                .strategies
+                .iter()
+                .map(|s| HandoverStrategy {
+                    id: s.id.clone(),
+                    kind: s.kind.clone(),
+                    token_id: s.token_id.clone(),
+                    params: s.config.params(),
+                    running: s.running.is_some(),
+                })
+                .collect(),
+            open_orders,
+        }
+    }
+
+    /// Restart the strategies of a previous leader and adopt its open orders
+    pub async fn take_over(&mut self, state: HandoverState) -> Result<()> {
+        self.next_id = self.next_id.max(state.next_strategy_id);
+        for handed in state.strategies {
+            let config = match SimpleStrategyConfig::default().with_params(&handed.params) {
+                Ok(config) => config,
+                Err(e) => {
+                    warn!("Skipping handed over strategy {}: {}", handed.id, e);
+                    continue;
+                }
+            };
+            // Strategies this instance stopped when it last lost the lease are replaced
+            match self.strategies.iter_mut().find(|s| s.id == handed.id) {
+                Some(existing) if existing.running.is_some() => continue,
+                Some(existing) => {
+                    existing.token_id = handed.token_id;
+                    existing.config = config;
+                }
+                None => self.strategies.push(HostedStrategy {
+                    id: handed.id.clone(),
+                    kind: handed.kind,
+                    token_id: handed.token_id,
+                    config,
+                    state: StrategyState::Stopped,
+                    running: None,
+                    started_at: None,
+                    orderbook_updates: 0,
+                    trades: 0,
+                    last_flatten: None,
+                }),
+            }
+            if handed.running {
+                if let Err(e) = self.launch(&handed.id).await {
+                    error!(
+                        "Failed to restart handed over strategy {}: {}",
+                        handed.id, e
+                    );
+                }
+            }
+        }
+
+        if !state.open_orders.is_empty() {
+            let polybot = self.trading_handles().await?.polybot;
+            // Orders that filled or were cancelled since the handover was written aren't adopted
+            let address = crate::auth::account_address(&self.data_paths).await?;
+            let live = polybot
+                .order
+                .fetch_orders(&self.host, &self.data_paths, &address)
+                .await
+                .context("Failed to fetch open orders to reconcile the handover")?;
+            let reconciled = ReconciledOrders::between(state.open_orders, live);
+            if !reconciled.closed.is_empty() {
+                info!(
+                    "{} handed over orders are no longer open: {}",
+                    reconciled.closed.len(),
+                    reconciled.closed.join(", ")
+                );
+            }
+            let adopted = polybot.order.adopt_orders(reconciled.open).await;
+            info!(
+                "📥 Adopted {} open orders from the previous leader",
+                adopted
+            );
+            record_event(
+                Subsystem::Orders,
+                format!("adopted {} open orders on failover", adopted),
+            );
+        }
+        Ok(())
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut HostedStrategy> {
+        self.strategies
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| anyhow!("No strategy {}", id))
+    }
+}
+
+fn unknown_kind(kind: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown strategy kind '{}' (available: {})",
+        kind,
+        STRATEGY_KINDS.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejected_starts_need_no_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut host = StrategyHost::new("https://clob.polymarket.com", DataPaths::new(dir.path()));
+        let streamed = vec!["123".to_string()];
+
+        let start = |kind: &str, token_id: &str, params: StrategyParams| ControlRequest::Start {
+            kind: kind.into(),
+            token_id: token_id.into(),
+            params,
+        };
+        for request in [
+            start("martingale", "123", StrategyParams::new()),
+            start("simple", "456", StrategyParams::new()),
+            start(
+                "simple",
+                "123",
+                StrategyParams::from([("tick_size".to_string(), 5.0)]),
+            ),
+            ControlRequest::Stop {
+                id: "simple-1".into(),
+            },
+        ] {
+            assert!(matches!(
+                host.handle(request, &streamed).await,
+                ControlResponse::Error { .. }
+            ));
+        }
+        assert!(host.statuses().is_empty());
+
+        match host
+            .handle(
+                ControlRequest::Schema {
+                    kind: "simple".into(),
+                },
+                &streamed,
+            )
+            .await
+        {
+            ControlResponse::Schema { params } => {
+                assert_eq!(params.len(), SimpleStrategyConfig::schema().len())
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+}
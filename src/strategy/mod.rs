@@ -9,10 +9,18 @@ use rust_decimal::Decimal;
 use std::sync::Arc;
 use polymarket_rs_client::ClobClient;
 
+use crate::core::execution::trade_flow::TradeFlowEvent;
 use crate::core::types::common::Side;
 use crate::core::ws::{OrderBook, PolyEvent};
 
+pub mod brackets;
+pub mod control;
+pub mod flatten;
+pub mod host;
+pub mod params;
+pub mod scheduled_orders;
 pub mod simple_strategy;
+pub mod stale_orders;
 
 // Re-export for convenience
 pub use simple_strategy::SimpleStrategy;
@@ -58,6 +66,12 @@ pub trait SingleTokenStrategy: Send + Sync {
     
     /// Called when a trade event occurs
     async fn trade_event(&self, trade: &TradeEvent) -> Result<()>;
+
+    /// Called when trade flow analysis flags a sweep or large print on this token
+    async fn trade_flow_event(&self, event: &TradeFlowEvent) -> Result<()> {
+        tracing::trace!("[{}] Ignoring {}", self.name(), event.describe());
+        Ok(())
+    }
     
     /// Set the ClobClient for order placement
     fn set_clob_client(&mut self, client: Arc<tokio::sync::Mutex<ClobClient>>);
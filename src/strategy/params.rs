@@ -0,0 +1,366 @@
+//! Strategy parameter schemas
+//!
+//! A strategy describes its tunable configuration as a list of [`ParamSpec`]s
+//! so front ends can render editors for it (sliders in the GUI) without
+//! knowing the strategy's config type. Values travel as [`StrategyParams`]
+//! and are validated against the schema before a strategy is (re)built.
+
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::simple_strategy::SimpleStrategyConfig;
+
+/// Parameter values by [`ParamSpec::name`]
+pub type StrategyParams = BTreeMap<String, f64>;
+
+/// One tunable strategy parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamSpec {
+    pub name: String,
+    pub label: String,
+    pub help: String,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    /// Whole numbers only (counts, seconds)
+    pub integer: bool,
+    /// Value a new strategy starts with
+    pub default: f64,
+}
+
+/// Inclusive bounds of a parameter's values
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ParamRange {
+    min: f64,
+    max: f64,
+}
+
+impl ParamRange {
+    const fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+}
+
+impl ParamSpec {
+    fn new(name: &str, label: &str, help: &str, range: ParamRange, step: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            help: help.to_string(),
+            min: range.min,
+            max: range.max,
+            step,
+            integer: false,
+            default: range.min,
+        }
+    }
+
+    fn integer(mut self) -> Self {
+        self.integer = true;
+        self
+    }
+}
+
+/// Strategy kinds the daemon can host
+pub const STRATEGY_KINDS: &[&str] = &["simple"];
+
+/// Parameter schema of a strategy kind
+pub fn schema(kind: &str) -> Option<Vec<ParamSpec>> {
+    match kind {
+        "simple" => Some(SimpleStrategyConfig::schema()),
+        _ => None,
+    }
+}
+
+/// Check `params` against `schema`, filling unset parameters from `defaults`
+pub fn validate(
+    schema: &[ParamSpec],
+    defaults: &StrategyParams,
+    params: &StrategyParams,
+) -> Result<StrategyParams> {
+    if let Some(unknown) = params
+        .keys()
+        .find(|name| !schema.iter().any(|spec| &spec.name == *name))
+    {
+        return Err(anyhow!("Unknown parameter '{}'", unknown));
+    }
+
+    let mut values = defaults.clone();
+    for spec in schema {
+        let Some(value) = params.get(&spec.name).copied() else {
+            continue;
+        };
+        if !value.is_finite() || value < spec.min || value > spec.max {
+            return Err(anyhow!(
+                "{} must be between {} and {} (got {})",
+                spec.name,
+                spec.min,
+                spec.max,
+                value
+            ));
+        }
+        if spec.integer && value.fract() != 0.0 {
+            return Err(anyhow!(
+                "{} must be a whole number (got {})",
+                spec.name,
+                value
+            ));
+        }
+        values.insert(spec.name.clone(), value);
+    }
+    Ok(values)
+}
+
+impl SimpleStrategyConfig {
+    pub fn schema() -> Vec<ParamSpec> {
+        let defaults = Self::default().params();
+        let mut schema = vec![
+            ParamSpec::new(
+                "min_spread_threshold",
+                "Min spread",
+                "Spread (price units) at which the market counts as wide",
+                ParamRange::new(0.0, 0.1),
+                0.001,
+            ),
+            ParamSpec::new(
+                "max_spread_threshold",
+                "Max spread",
+                "Spread (price units) at which the market counts as tight",
+                ParamRange::new(0.0, 0.5),
+                0.001,
+            ),
+            ParamSpec::new(
+                "volume_window_secs",
+                "Volume window (s)",
+                "Window for trade volume analysis",
+                ParamRange::new(10.0, 3600.0),
+                10.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "log_frequency",
+                "Log every N updates",
+                "Only log every Nth order book update",
+                ParamRange::new(1.0, 1000.0),
+                1.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "order_check_interval_secs",
+                "Order check (s)",
+                "How often to consider placing orders",
+                ParamRange::new(1.0, 300.0),
+                1.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "max_active_orders",
+                "Max active orders",
+                "Orders kept open at once",
+                ParamRange::new(0.0, 20.0),
+                1.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "base_discount_percent",
+                "Base discount",
+                "Discount from the best price for the first order (0.005 = 0.5%)",
+                ParamRange::new(0.0, 0.1),
+                0.001,
+            ),
+            ParamSpec::new(
+                "discount_increment",
+                "Discount step",
+                "Extra discount for each further order",
+                ParamRange::new(0.0, 0.1),
+                0.001,
+            ),
+            ParamSpec::new(
+                "tick_size",
+                "Tick size",
+                "Price rounding increment",
+                ParamRange::new(0.001, 0.1),
+                0.001,
+            ),
+            ParamSpec::new(
+                "base_order_size",
+                "Order size",
+                "Base order size in shares",
+                ParamRange::new(1.0, 1000.0),
+                1.0,
+            ),
+            ParamSpec::new(
+                "max_order_value",
+                "Max order value ($)",
+                "Largest notional per order",
+                ParamRange::new(0.5, 1000.0),
+                0.5,
+            ),
+            ParamSpec::new(
+                "auto_flatten",
+                "Auto-flatten",
+                "1 = flatten the position on the daemon's flatten schedule",
+                ParamRange::new(0.0, 1.0),
+                1.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "flatten_target",
+                "Flatten target",
+                "Shares kept when the position is flattened",
+                ParamRange::new(0.0, 1000.0),
+                1.0,
+            ),
+            ParamSpec::new(
+                "stale_order_age_secs",
+                "Stale order age (s)",
+                "Cancel resting orders older than this; 0 = the daemon's --cancel-stale-after",
+                ParamRange::new(0.0, 86400.0),
+                60.0,
+            )
+            .integer(),
+            ParamSpec::new(
+                "stale_order_ticks",
+                "Stale order drift (ticks)",
+                "Cancel resting orders this many ticks from the mid; 0 = the daemon's --cancel-drift-ticks",
+                ParamRange::new(0.0, 100.0),
+                1.0,
+            )
+            .integer(),
+        ];
+        for spec in &mut schema {
+            spec.default = defaults[&spec.name];
+        }
+        schema
+    }
+
+    /// Current values, keyed as in [`Self::schema`]
+    pub fn params(&self) -> StrategyParams {
+        let decimal = |d: Decimal| d.to_f64().unwrap_or(0.0);
+        StrategyParams::from([
+            (
+                "min_spread_threshold".to_string(),
+                decimal(self.min_spread_threshold),
+            ),
+            (
+                "max_spread_threshold".to_string(),
+                decimal(self.max_spread_threshold),
+            ),
+            (
+                "volume_window_secs".to_string(),
+                self.volume_window.as_secs() as f64,
+            ),
+            ("log_frequency".to_string(), self.log_frequency as f64),
+            (
+                "order_check_interval_secs".to_string(),
+                self.order_check_interval.as_secs() as f64,
+            ),
+            (
+                "max_active_orders".to_string(),
+                self.max_active_orders as f64,
+            ),
+            (
+                "base_discount_percent".to_string(),
+                decimal(self.base_discount_percent),
+            ),
+            (
+                "discount_increment".to_string(),
+                decimal(self.discount_increment),
+            ),
+            ("tick_size".to_string(), decimal(self.tick_size)),
+            ("base_order_size".to_string(), decimal(self.base_order_size)),
+            ("max_order_value".to_string(), decimal(self.max_order_value)),
+            (
+                "auto_flatten".to_string(),
+                if self.auto_flatten { 1.0 } else { 0.0 },
+            ),
+            ("flatten_target".to_string(), decimal(self.flatten_target)),
+            (
+                "stale_order_age_secs".to_string(),
+                self.stale_order_age.as_secs() as f64,
+            ),
+            (
+                "stale_order_ticks".to_string(),
+                self.stale_order_ticks as f64,
+            ),
+        ])
+    }
+
+    /// This config with `params` applied, validated against the schema
+    pub fn with_params(&self, params: &StrategyParams) -> Result<Self> {
+        let values = validate(&Self::schema(), &self.params(), params)?;
+        let decimal = |name: &str| {
+            Decimal::from_f64(values[name])
+                .map(|d| d.round_dp(6))
+                .ok_or_else(|| anyhow!("{} is not a valid decimal", name))
+        };
+
+        let config = Self {
+            min_spread_threshold: decimal("min_spread_threshold")?,
+            max_spread_threshold: decimal("max_spread_threshold")?,
+            volume_window: Duration::from_secs(values["volume_window_secs"] as u64),
+            log_frequency: values["log_frequency"] as u32,
+            order_check_interval: Duration::from_secs(values["order_check_interval_secs"] as u64),
+            max_active_orders: values["max_active_orders"] as usize,
+            base_discount_percent: decimal("base_discount_percent")?,
+            discount_increment: decimal("discount_increment")?,
+            tick_size: decimal("tick_size")?,
+            base_order_size: decimal("base_order_size")?,
+            max_order_value: decimal("max_order_value")?,
+            auto_flatten: values["auto_flatten"] != 0.0,
+            flatten_target: decimal("flatten_target")?,
+            stale_order_age: Duration::from_secs(values["stale_order_age_secs"] as u64),
+            stale_order_ticks: values["stale_order_ticks"] as u32,
+        };
+        if config.min_spread_threshold > config.max_spread_threshold {
+            return Err(anyhow!(
+                "min_spread_threshold must not exceed max_spread_threshold"
+            ));
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_config_round_trips_through_params() {
+        let config = SimpleStrategyConfig::default();
+        let params = config.params();
+        let schema = SimpleStrategyConfig::schema();
+        assert_eq!(params.len(), schema.len());
+        // The defaults must be reachable from the editors
+        assert!(schema
+            .iter()
+            .all(|spec| spec.default >= spec.min && spec.default <= spec.max));
+
+        let rebuilt = config.with_params(&params).unwrap();
+        assert_eq!(rebuilt.params(), params);
+        assert_eq!(rebuilt.min_spread_threshold, config.min_spread_threshold);
+        assert_eq!(rebuilt.volume_window, config.volume_window);
+    }
+
+    #[test]
+    fn params_are_checked_against_the_schema() {
+        let config = SimpleStrategyConfig::default();
+        let with = |name: &str, value: f64| StrategyParams::from([(name.to_string(), value)]);
+
+        let updated = config.with_params(&with("max_active_orders", 5.0)).unwrap();
+        assert_eq!(updated.max_active_orders, 5);
+        // Unset parameters keep their current values
+        assert_eq!(updated.tick_size, config.tick_size);
+
+        assert!(config.with_params(&with("max_active_orders", 2.5)).is_err());
+        assert!(config.with_params(&with("tick_size", 5.0)).is_err());
+        assert!(config.with_params(&with("no_such_param", 1.0)).is_err());
+        assert!(config
+            .with_params(&with("min_spread_threshold", 0.09))
+            .is_err());
+    }
+}
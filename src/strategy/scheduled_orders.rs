@@ -0,0 +1,368 @@
+//! Time-triggered orders
+//!
+//! A scheduled order is a limit order to place at a given UTC time, e.g. a
+//! minute before a market closes. Orders are kept in `scheduled_orders.json`
+//! in the profile's data directory and placed by the daemon, which checks
+//! every second. An order whose time passed more than its maximum delay ago,
+//! typically because no daemon was running, is marked missed rather than
+//! placed late. Placement goes through the order manager like any other
+//! order and is attempted once; failures are recorded, not retried. A due
+//! order is marked placing in the file before it goes out, so it is never
+//! placed twice: one left placing (the daemon stopped mid-placement or
+//! couldn't save the outcome) has to be checked against the open orders.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::core::execution::route::OrderRoute;
+use crate::core::json_store::{JsonStore, Record};
+use crate::core::types::common::Side;
+use crate::data_paths::DataPaths;
+
+pub const SCHEDULED_ORDERS_FILE: &str = "scheduled_orders.json";
+
+/// How late an order may still be placed when none is given
+pub const DEFAULT_MAX_DELAY_SECS: i64 = 300;
+
+/// Parse when to place an order: an RFC 3339 timestamp, `YYYY-MM-DD HH:MM[:SS]`
+/// in UTC, or `HH:MM[:SS]` UTC for its next occurrence after `now`
+pub fn parse_execute_at(raw: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(raw, format) {
+            return Ok(at.and_utc());
+        }
+    }
+    let time = NaiveTime::parse_from_str(raw, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(raw, "%H:%M:%S"))
+        .map_err(|_| {
+            anyhow!(
+                "Invalid time '{}' (expected HH:MM, YYYY-MM-DD HH:MM or RFC 3339, in UTC)",
+                raw
+            )
+        })?;
+    let today = now.date_naive().and_time(time).and_utc();
+    Ok(if today > now {
+        today
+    } else {
+        today + Duration::days(1)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ScheduledState {
+    Pending,
+    /// Claimed for placement; the outcome wasn't saved yet
+    Placing,
+    Placed {
+        order_id: String,
+    },
+    Failed {
+        error: String,
+    },
+    /// Its time passed by more than the maximum delay before it could be placed
+    Missed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledOrder {
+    pub id: u64,
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub execute_at: DateTime<Utc>,
+    pub max_delay_secs: i64,
+    #[serde(flatten)]
+    pub state: ScheduledState,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Record for ScheduledOrder {
+    fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// A limit order to schedule
+#[derive(Debug, Clone)]
+pub struct NewScheduledOrder {
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub execute_at: DateTime<Utc>,
+    pub max_delay_secs: i64,
+}
+
+/// What to do with a pending order at a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Due {
+    Place,
+    Missed,
+}
+
+/// A pending order whose time has come
+#[derive(Debug, Clone)]
+pub struct DueOrder {
+    pub order: ScheduledOrder,
+    pub due: Due,
+}
+
+impl DueOrder {
+    /// The orders among `orders` that fall due at `now`
+    pub fn select(orders: Vec<ScheduledOrder>, now: DateTime<Utc>) -> Vec<Self> {
+        orders
+            .into_iter()
+            .filter_map(|order| order.due(now).map(|due| Self { order, due }))
+            .collect()
+    }
+}
+
+impl ScheduledOrder {
+    pub fn is_pending(&self) -> bool {
+        self.state == ScheduledState::Pending
+    }
+
+    pub fn due(&self, now: DateTime<Utc>) -> Option<Due> {
+        if !self.is_pending() || now < self.execute_at {
+            return None;
+        }
+        if now - self.execute_at > Duration::seconds(self.max_delay_secs) {
+            Some(Due::Missed)
+        } else {
+            Some(Due::Place)
+        }
+    }
+
+    pub fn state_label(&self) -> String {
+        match &self.state {
+            ScheduledState::Pending => "pending".to_string(),
+            ScheduledState::Placing => "placing (outcome not recorded)".to_string(),
+            ScheduledState::Placed { order_id } => format!("placed ({})", order_id),
+            ScheduledState::Failed { error } => format!("failed: {}", error),
+            ScheduledState::Missed => "missed".to_string(),
+            ScheduledState::Cancelled => "cancelled".to_string(),
+        }
+    }
+}
+
+/// Scheduled orders kept as JSON in the data directory
+pub struct ScheduledOrderStore {
+    file: JsonStore<Vec<ScheduledOrder>>,
+}
+
+impl ScheduledOrderStore {
+    pub fn new(data_paths: &DataPaths) -> Self {
+        Self {
+            file: JsonStore::new(data_paths.data().join(SCHEDULED_ORDERS_FILE)),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<ScheduledOrder>> {
+        self.file.load()
+    }
+
+    /// Schedule a limit order
+    pub fn add(&self, new: NewScheduledOrder) -> Result<ScheduledOrder> {
+        let now = Utc::now();
+        self.file.insert(|id| ScheduledOrder {
+            id,
+            token_id: new.token_id,
+            market_id: new.market_id,
+            side: new.side,
+            price: new.price,
+            size: new.size,
+            execute_at: new.execute_at,
+            max_delay_secs: new.max_delay_secs,
+            state: ScheduledState::Pending,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Cancel a pending order
+    pub fn cancel(&self, id: u64) -> Result<ScheduledOrder> {
+        self.file.modify(|orders| {
+            let order = orders
+                .iter_mut()
+                .find(|o| o.id == id)
+                .ok_or_else(|| anyhow!("No scheduled order {}", id))?;
+            if !order.is_pending() {
+                bail!("Scheduled order {} is already {}", id, order.state_label());
+            }
+            order.state = ScheduledState::Cancelled;
+            order.updated_at = Utc::now();
+            Ok(order.clone())
+        })
+    }
+
+    /// Claim the orders that fall due at `now`: under the file's lock, late
+    /// ones are marked missed and the rest placing, so neither a cancel nor a
+    /// later check can see them pending while they are being placed
+    pub fn claim_due(&self, now: DateTime<Utc>) -> Result<Vec<DueOrder>> {
+        self.file.modify(|orders| {
+            let due = DueOrder::select(orders.clone(), now);
+            for claimed in &due {
+                if let Some(order) = orders.iter_mut().find(|o| o.id == claimed.order.id) {
+                    order.state = match claimed.due {
+                        Due::Place => ScheduledState::Placing,
+                        Due::Missed => ScheduledState::Missed,
+                    };
+                    order.updated_at = now;
+                }
+            }
+            Ok(due)
+        })
+    }
+
+    /// Write back changed orders, keeping any added since they were loaded
+    pub fn update(&self, changed: &[ScheduledOrder]) -> Result<()> {
+        self.file.update(changed)
+    }
+}
+
+/// Outcome of handling the orders that fell due at one check
+#[derive(Debug, Default)]
+pub struct ScheduledPass {
+    /// Orders placed, failed or missed, to write back
+    pub changed: Vec<ScheduledOrder>,
+    /// One per order
+    pub messages: Vec<String>,
+}
+
+impl ScheduledPass {
+    /// Place the due orders through `route`, or mark them missed when too late
+    pub async fn run(route: &OrderRoute<'_>, due: Vec<DueOrder>, now: DateTime<Utc>) -> Self {
+        let mut pass = Self::default();
+        for DueOrder { mut order, due } in due {
+            let what = format!(
+                "scheduled order {} ({} {} @ ${} on {}, due {})",
+                order.id,
+                order.side,
+                order.size,
+                order.price,
+                order.token_id,
+                order.execute_at.format("%H:%M:%S")
+            );
+            order.state = match due {
+                Due::Missed => ScheduledState::Missed,
+                Due::Place => {
+                    let placed = route
+                        .place(order.side, &order.token_id, order.price, order.size)
+                        .await;
+                    match placed {
+                        Ok(order_id) => ScheduledState::Placed { order_id },
+                        Err(e) => ScheduledState::Failed {
+                            error: e.to_string(),
+                        },
+                    }
+                }
+            };
+            order.updated_at = now;
+            pass.messages.push(format!("{}: {}", what, order.state_label()));
+            pass.changed.push(order);
+        }
+        pass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn orders_fall_due_at_their_time_and_are_missed_when_late() {
+        let now = "2026-03-02T15:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let at = |raw: &str| parse_execute_at(raw, now).unwrap().to_rfc3339();
+        // A time of day already past today means tomorrow
+        assert_eq!(at("15:30"), "2026-03-02T15:30:00+00:00");
+        assert_eq!(at("14:59"), "2026-03-03T14:59:00+00:00");
+        assert_eq!(at("2026-03-05 09:00"), "2026-03-05T09:00:00+00:00");
+        assert_eq!(at("2026-03-05T09:00:00+02:00"), "2026-03-05T07:00:00+00:00");
+        assert!(parse_execute_at("tomorrow", now).is_err());
+
+        let order = ScheduledOrder {
+            id: 1,
+            token_id: "123".to_string(),
+            market_id: None,
+            side: Side::Buy,
+            price: dec!(0.48),
+            size: dec!(100),
+            execute_at: now,
+            max_delay_secs: DEFAULT_MAX_DELAY_SECS,
+            state: ScheduledState::Pending,
+            created_at: now,
+            updated_at: now,
+        };
+        assert_eq!(order.due(now - Duration::seconds(1)), None);
+        assert_eq!(order.due(now), Some(Due::Place));
+        assert_eq!(order.due(now + Duration::minutes(5)), Some(Due::Place));
+        assert_eq!(order.due(now + Duration::minutes(6)), Some(Due::Missed));
+
+        let cancelled = ScheduledOrder {
+            state: ScheduledState::Cancelled,
+            ..order.clone()
+        };
+        assert_eq!(cancelled.due(now), None);
+        let placing = ScheduledOrder {
+            state: ScheduledState::Placing,
+            ..order.clone()
+        };
+        assert_eq!(placing.due(now), None);
+
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json["state"], "pending");
+        assert_eq!(
+            serde_json::from_value::<ScheduledOrder>(json).unwrap(),
+            order
+        );
+    }
+
+    #[test]
+    fn claimed_orders_are_not_due_again_or_cancellable() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ScheduledOrderStore::new(&DataPaths::new(dir.path()));
+        let now = Utc::now();
+        let new = |execute_at| NewScheduledOrder {
+            token_id: "123".to_string(),
+            market_id: None,
+            side: Side::Buy,
+            price: dec!(0.48),
+            size: dec!(100),
+            execute_at,
+            max_delay_secs: DEFAULT_MAX_DELAY_SECS,
+        };
+        let due = store.add(new(now)).unwrap();
+        let late = store.add(new(now - Duration::minutes(10))).unwrap();
+        let later = store.add(new(now + Duration::hours(1))).unwrap();
+
+        let claimed = store.claim_due(now).unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert!(store.claim_due(now).unwrap().is_empty());
+
+        let stored = store.load().unwrap();
+        let state = |id: u64| stored.iter().find(|o| o.id == id).unwrap().state.clone();
+        assert_eq!(state(due.id), ScheduledState::Placing);
+        assert_eq!(state(late.id), ScheduledState::Missed);
+        assert_eq!(state(later.id), ScheduledState::Pending);
+        assert!(store.cancel(due.id).is_err());
+        store.cancel(later.id).unwrap();
+    }
+}
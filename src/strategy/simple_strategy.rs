@@ -15,6 +15,7 @@ use tracing::{info, warn, debug, error};
 use polymarket_rs_client::ClobClient;
 use owo_colors::OwoColorize;
 
+use crate::core::diagnostics::{record_event, Subsystem};
 use crate::core::execution::orders::{PolyBot, OrderPlacementResponse};
 use crate::core::types::common::Side;
 use crate::core::ws::OrderBook;
@@ -45,6 +46,14 @@ pub struct SimpleStrategyConfig {
     pub base_order_size: Decimal,
     /// Maximum total value per order in USD
     pub max_order_value: Decimal,
+    /// Let the daemon flatten this strategy's position on its flatten schedule
+    pub auto_flatten: bool,
+    /// Shares left in the position after a flatten
+    pub flatten_target: Decimal,
+    /// Resting orders on this token older than this are cancelled by the daemon (zero: daemon default)
+    pub stale_order_age: Duration,
+    /// Resting orders this many ticks from the mid are cancelled by the daemon (zero: daemon default)
+    pub stale_order_ticks: u32,
 }
 
 impl Default for SimpleStrategyConfig {
@@ -61,6 +70,10 @@ impl Default for SimpleStrategyConfig {
             tick_size: Decimal::new(1, 2), // 0.01 = cent precision
             base_order_size: Decimal::new(5, 0), // 5 shares base size
             max_order_value: Decimal::new(250, 2), // $2.50 max per order
+            auto_flatten: false,
+            flatten_target: Decimal::ZERO,
+            stale_order_age: Duration::ZERO,
+            stale_order_ticks: 0,
         }
     }
 }
@@ -361,12 +374,10 @@ impl SimpleStrategy {
             // Calculate order imbalance
             let bid_depth: Decimal = orderbook.bids.values()
                 .take(5)
-                .copied()
                 .sum();
                 
             let ask_depth: Decimal = orderbook.asks.values()
                 .take(5)
-                .copied()
                 .sum();
                 
             let total_depth = bid_depth + ask_depth;
@@ -407,6 +418,13 @@ impl SimpleStrategy {
                         last_spread,
                         spread
                     );
+                    record_event(
+                        Subsystem::Strategy,
+                        format!(
+                            "[{}] spread changed {:.1}% (${:.4} → ${:.4})",
+                            self.name, spread_change, last_spread, spread
+                        ),
+                    );
                 }
             }
         }
@@ -464,6 +482,19 @@ impl SimpleStrategy {
                 debug!("[{}] No orders to propose", name);
                 continue;
             }
+            record_event(
+                Subsystem::Strategy,
+                format!(
+                    "[{}] proposed {} orders: {}",
+                    name,
+                    proposals.len(),
+                    proposals
+                        .iter()
+                        .map(|p| format!("{:?} {} @ {}", p.side, p.size, p.price))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
             
             // Send proposals to user input handler with orderbook
             let (response_tx, response_rx) = oneshot::channel();
@@ -476,6 +507,7 @@ impl SimpleStrategy {
             match response_rx.await {
                 Ok(Some(approved_orders)) => {
                     info!("[{}] User approved {} orders", name, approved_orders.len());
+                    record_event(Subsystem::Strategy, format!("[{}] approved {} orders", name, approved_orders.len()));
                     
                     // Store approved orders in state for placement
                     let mut state_guard = state.write().await;
@@ -485,6 +517,7 @@ impl SimpleStrategy {
                 }
                 Ok(None) => {
                     info!("[{}] User rejected order proposals", name);
+                    record_event(Subsystem::Strategy, format!("[{}] proposals rejected", name));
                 }
                 Err(e) => {
                     error!("[{}] Failed to receive user response: {}", name, e);
@@ -521,15 +554,11 @@ impl SimpleStrategy {
         let levels: Vec<_> = match side {
             Side::Buy => {
                 // For buying, we hit the asks (sorted low to high)
-                let mut asks: Vec<_> = orderbook.asks.iter().collect();
-                asks.sort_by(|a, b| a.0.cmp(b.0));
-                asks.into_iter().map(|(price, size)| (*price, *size)).collect()
+                orderbook.asks.iter_from_best().collect()
             }
             Side::Sell => {
                 // For selling, we hit the bids (sorted high to low)
-                let mut bids: Vec<_> = orderbook.bids.iter().collect();
-                bids.sort_by(|a, b| b.0.cmp(a.0));
-                bids.into_iter().map(|(price, size)| (*price, *size)).collect()
+                orderbook.bids.iter_from_best().collect()
             }
         };
         
@@ -538,27 +567,27 @@ impl SimpleStrategy {
         }
         
         // Calculate total volume available
-        let total_volume: Decimal = levels.iter().map(|(_, size)| size).sum();
+        let total_volume: Decimal = levels.iter().map(|level| level.size).sum();
         let target_volume = total_volume * volume_percent / Decimal::ONE_HUNDRED;
         
         // Walk through levels until we consume target volume
         let mut remaining_volume = target_volume;
         let mut total_cost = Decimal::ZERO;
         let mut levels_consumed = Vec::new();
-        let mut last_price = levels[0].0;
+        let mut last_price = levels[0].price;
         
-        for (price, size) in &levels {
+        for level in &levels {
             if remaining_volume <= Decimal::ZERO {
                 break;
             }
             
-            let volume_at_level = (*size).min(remaining_volume);
-            total_cost += volume_at_level * price;
+            let volume_at_level = level.size.min(remaining_volume);
+            total_cost += volume_at_level * level.price;
             remaining_volume -= volume_at_level;
-            last_price = *price;
+            last_price = level.price;
             
             if volume_at_level > Decimal::ZERO {
-                levels_consumed.push((*price, volume_at_level));
+                levels_consumed.push((level.price, volume_at_level));
             }
         }
         
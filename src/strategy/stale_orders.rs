@@ -0,0 +1,226 @@
+//! Automatic cancellation of stale resting orders
+//!
+//! The daemon checks the account's resting orders on the tokens it streams
+//! and cancels those older than a maximum age or priced more than a number of
+//! ticks away from the streamed mid. A hosted strategy's own limits
+//! (`stale_order_age_secs`, `stale_order_ticks`) apply to its token instead of
+//! the daemon-wide `--cancel-stale-after` / `--cancel-drift-ticks`. The exits
+//! of open brackets rest far from the mid by design and are reported as kept,
+//! never cancelled. In dry-run mode the check only reports what it would
+//! cancel.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::portfolio::{ActiveOrder, OrderSide};
+
+/// When a resting order counts as stale; unset limits never trigger
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StaleOrderRule {
+    pub max_age: Option<Duration>,
+    pub max_drift_ticks: Option<u32>,
+}
+
+impl StaleOrderRule {
+    pub fn is_empty(&self) -> bool {
+        self.max_age.is_none() && self.max_drift_ticks.is_none()
+    }
+
+    /// Why `order` is stale, if it is; drift needs the token's current mid
+    pub fn check(
+        &self,
+        order: &ActiveOrder,
+        tick_size: Decimal,
+        mid: Option<Decimal>,
+        now: DateTime<Utc>,
+    ) -> Option<StaleReason> {
+        let age = now - order.created_at;
+        if let Some(max_age) = self.max_age.filter(|max_age| age > *max_age) {
+            return Some(StaleReason::Age { age, max_age });
+        }
+
+        let (max_ticks, mid) = (self.max_drift_ticks?, mid?);
+        if tick_size.is_zero() {
+            return None;
+        }
+        let ticks = ((order.price - mid).abs() / tick_size).round_dp(1);
+        (ticks > Decimal::from(max_ticks)).then_some(StaleReason::Drift {
+            ticks,
+            max_ticks,
+            mid,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum StaleReason {
+    Age {
+        #[serde(with = "duration_secs")]
+        age: Duration,
+        #[serde(with = "duration_secs")]
+        max_age: Duration,
+    },
+    Drift {
+        ticks: Decimal,
+        max_ticks: u32,
+        mid: Decimal,
+    },
+}
+
+impl fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaleReason::Age { age, max_age } => write!(
+                f,
+                "resting {}m (limit {}m)",
+                age.num_minutes(),
+                max_age.num_minutes()
+            ),
+            StaleReason::Drift {
+                ticks,
+                max_ticks,
+                mid,
+            } => write!(f, "{} ticks from mid ${} (limit {})", ticks, mid, max_ticks),
+        }
+    }
+}
+
+mod duration_secs {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(duration.num_seconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        i64::deserialize(deserializer).map(Duration::seconds)
+    }
+}
+
+/// One stale order found by a check, and what was done about it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleOrderReport {
+    pub at: DateTime<Utc>,
+    pub order_id: String,
+    pub token_id: String,
+    pub side: OrderSide,
+    pub price: Decimal,
+    pub remaining_size: Decimal,
+    #[serde(flatten)]
+    pub reason: StaleReason,
+    /// Strategy whose limits applied, `None` for the daemon-wide rule
+    pub strategy_id: Option<String>,
+    /// Open bracket whose exit the order is; such orders are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bracket_id: Option<u64>,
+    /// False in dry-run mode, for bracket exits and when the cancel failed
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+impl StaleOrderReport {
+    pub fn describe(&self) -> String {
+        let action = match (&self.error, self.cancelled, self.bracket_id) {
+            (Some(error), _, _) => format!("cancel failed: {}", error),
+            (None, true, _) => "cancelled".to_string(),
+            (None, false, Some(bracket_id)) => format!("kept, exit of bracket {}", bracket_id),
+            (None, false, None) => "would cancel (dry run)".to_string(),
+        };
+        format!(
+            "stale order {} ({:?} {} @ ${} on {}): {}, {}",
+            self.order_id,
+            self.side,
+            self.remaining_size,
+            self.price,
+            self.token_id,
+            self.reason,
+            action
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::portfolio::{OrderStatus, OrderType, TimeInForce};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn orders_go_stale_by_age_or_drift_from_the_mid() {
+        let now = Utc::now();
+        let order = ActiveOrder {
+            order_id: "0x1".to_string(),
+            market_id: "0xmarket".to_string(),
+            token_id: "123".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            price: dec!(0.45),
+            size: dec!(10),
+            filled_size: dec!(0),
+            remaining_size: dec!(10),
+            status: OrderStatus::Open,
+            created_at: now - Duration::minutes(20),
+            updated_at: now,
+            time_in_force: TimeInForce::GTC,
+            post_only: false,
+            reduce_only: false,
+        };
+        let tick = dec!(0.01);
+
+        assert_eq!(
+            StaleOrderRule::default().check(&order, tick, Some(dec!(0.6)), now),
+            None
+        );
+
+        let by_age = StaleOrderRule {
+            max_age: Some(Duration::minutes(15)),
+            max_drift_ticks: None,
+        };
+        assert!(matches!(
+            by_age.check(&order, tick, None, now),
+            Some(StaleReason::Age { .. })
+        ));
+
+        let by_drift = StaleOrderRule {
+            max_age: Some(Duration::hours(1)),
+            max_drift_ticks: Some(5),
+        };
+        // Five ticks below the mid is still within the limit
+        assert_eq!(by_drift.check(&order, tick, Some(dec!(0.50)), now), None);
+        assert_eq!(
+            by_drift.check(&order, tick, Some(dec!(0.515)), now),
+            Some(StaleReason::Drift {
+                ticks: dec!(6.5),
+                max_ticks: 5,
+                mid: dec!(0.515),
+            })
+        );
+        // Without a streamed mid only the age limit applies
+        assert_eq!(by_drift.check(&order, tick, None, now), None);
+
+        let report = StaleOrderReport {
+            at: now,
+            order_id: order.order_id.clone(),
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price: order.price,
+            remaining_size: order.remaining_size,
+            reason: by_age.check(&order, tick, None, now).unwrap(),
+            strategy_id: None,
+            bracket_id: None,
+            cancelled: false,
+            error: None,
+        };
+        assert!(report.describe().ends_with("would cancel (dry run)"));
+        let bracket_exit = StaleOrderReport {
+            bracket_id: Some(7),
+            ..report
+        };
+        assert!(bracket_exit.describe().ends_with("kept, exit of bracket 7"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Color theme shared by the TUI and the GUI
+//!
+//! Configuration lives in `<data_dir>/theme.yaml` and is optional. A preset
+//! picks the base palette and individual roles can be overridden with a
+//! terminal color name or a `#rrggbb` hex value:
+//!
+//! ```yaml
+//! preset: colorblind   # default | high_contrast | colorblind
+//! colors:
+//!   bid: "#0072b2"
+//!   loss: light_red
+//! ```
+//!
+//! Call [`init`] once at startup of a TUI or GUI command; rendering code reads
+//! the active palette through [`palette`].
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::data_paths::DataPaths;
+
+/// Name of the theme configuration file inside the data directory
+pub const THEME_CONFIG_FILE: &str = "theme.yaml";
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// Built-in palettes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// Green bids/profit, red asks/loss
+    #[default]
+    Default,
+    /// Saturated colors for low-contrast terminals and projectors
+    HighContrast,
+    /// Okabe-Ito blue/orange, distinguishable with red-green color blindness
+    Colorblind,
+}
+
+/// One of the 16 terminal colors, or an exact RGB value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ThemeColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    DarkGray,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl ThemeColor {
+    /// RGB used where a terminal color name has to be drawn in the GUI;
+    /// red and green match the GUI's existing sell/buy colors
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            ThemeColor::Black => (0, 0, 0),
+            ThemeColor::Red => (200, 100, 100),
+            ThemeColor::Green => (100, 200, 100),
+            ThemeColor::Yellow => (255, 200, 100),
+            ThemeColor::Blue => (100, 100, 200),
+            ThemeColor::Magenta => (200, 100, 200),
+            ThemeColor::Cyan => (100, 200, 200),
+            ThemeColor::Gray => (150, 150, 150),
+            ThemeColor::DarkGray => (90, 90, 90),
+            ThemeColor::LightRed => (255, 120, 120),
+            ThemeColor::LightGreen => (120, 255, 120),
+            ThemeColor::LightYellow => (255, 255, 120),
+            ThemeColor::LightBlue => (150, 150, 255),
+            ThemeColor::LightMagenta => (255, 150, 255),
+            ThemeColor::LightCyan => (150, 255, 255),
+            ThemeColor::White => (255, 255, 255),
+            ThemeColor::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+}
+
+impl FromStr for ThemeColor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 {
+                return Err(anyhow!("Invalid hex color '{}': expected #rrggbb", s));
+            }
+            let channel = |i: usize| {
+                u8::from_str_radix(&hex[i..i + 2], 16)
+                    .map_err(|_| anyhow!("Invalid hex color '{}'", s))
+            };
+            return Ok(ThemeColor::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        let color = match s.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "black" => ThemeColor::Black,
+            "red" => ThemeColor::Red,
+            "green" => ThemeColor::Green,
+            "yellow" => ThemeColor::Yellow,
+            "blue" => ThemeColor::Blue,
+            "magenta" => ThemeColor::Magenta,
+            "cyan" => ThemeColor::Cyan,
+            "gray" | "grey" => ThemeColor::Gray,
+            "dark_gray" | "dark_grey" => ThemeColor::DarkGray,
+            "light_red" => ThemeColor::LightRed,
+            "light_green" => ThemeColor::LightGreen,
+            "light_yellow" => ThemeColor::LightYellow,
+            "light_blue" => ThemeColor::LightBlue,
+            "light_magenta" => ThemeColor::LightMagenta,
+            "light_cyan" => ThemeColor::LightCyan,
+            "white" => ThemeColor::White,
+            _ => return Err(anyhow!("Unknown color '{}'", s)),
+        };
+        Ok(color)
+    }
+}
+
+impl TryFrom<String> for ThemeColor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(color: ThemeColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ThemeColor::Black => "black",
+            ThemeColor::Red => "red",
+            ThemeColor::Green => "green",
+            ThemeColor::Yellow => "yellow",
+            ThemeColor::Blue => "blue",
+            ThemeColor::Magenta => "magenta",
+            ThemeColor::Cyan => "cyan",
+            ThemeColor::Gray => "gray",
+            ThemeColor::DarkGray => "dark_gray",
+            ThemeColor::LightRed => "light_red",
+            ThemeColor::LightGreen => "light_green",
+            ThemeColor::LightYellow => "light_yellow",
+            ThemeColor::LightBlue => "light_blue",
+            ThemeColor::LightMagenta => "light_magenta",
+            ThemeColor::LightCyan => "light_cyan",
+            ThemeColor::White => "white",
+            ThemeColor::Rgb(r, g, b) => return write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<ThemeColor> for ratatui::style::Color {
+    fn from(color: ThemeColor) -> Self {
+        use ratatui::style::Color;
+        match color {
+            ThemeColor::Black => Color::Black,
+            ThemeColor::Red => Color::Red,
+            ThemeColor::Green => Color::Green,
+            ThemeColor::Yellow => Color::Yellow,
+            ThemeColor::Blue => Color::Blue,
+            ThemeColor::Magenta => Color::Magenta,
+            ThemeColor::Cyan => Color::Cyan,
+            ThemeColor::Gray => Color::Gray,
+            ThemeColor::DarkGray => Color::DarkGray,
+            ThemeColor::LightRed => Color::LightRed,
+            ThemeColor::LightGreen => Color::LightGreen,
+            ThemeColor::LightYellow => Color::LightYellow,
+            ThemeColor::LightBlue => Color::LightBlue,
+            ThemeColor::LightMagenta => Color::LightMagenta,
+            ThemeColor::LightCyan => Color::LightCyan,
+            ThemeColor::White => Color::White,
+            ThemeColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<ThemeColor> for egui::Color32 {
+    fn from(color: ThemeColor) -> Self {
+        let (r, g, b) = color.to_rgb();
+        egui::Color32::from_rgb(r, g, b)
+    }
+}
+
+/// Colors for the roles that carry meaning in trading views
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Palette {
+    /// Bid side of a book and buy orders
+    pub bid: ThemeColor,
+    /// Ask side of a book and sell orders
+    pub ask: ThemeColor,
+    /// Positive P&L
+    pub profit: ThemeColor,
+    /// Negative P&L
+    pub loss: ThemeColor,
+    /// Headers, selection and warnings
+    pub highlight: ThemeColor,
+    /// Secondary text
+    pub muted: ThemeColor,
+}
+
+impl Palette {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Default => Self {
+                bid: ThemeColor::Green,
+                ask: ThemeColor::Red,
+                profit: ThemeColor::Green,
+                loss: ThemeColor::Red,
+                highlight: ThemeColor::Yellow,
+                muted: ThemeColor::Gray,
+            },
+            ThemePreset::HighContrast => Self {
+                bid: ThemeColor::Rgb(0, 255, 0),
+                ask: ThemeColor::Rgb(255, 40, 40),
+                profit: ThemeColor::Rgb(0, 255, 0),
+                loss: ThemeColor::Rgb(255, 40, 40),
+                highlight: ThemeColor::Rgb(255, 255, 0),
+                muted: ThemeColor::White,
+            },
+            ThemePreset::Colorblind => Self {
+                bid: ThemeColor::Rgb(0, 114, 178),
+                ask: ThemeColor::Rgb(230, 159, 0),
+                profit: ThemeColor::Rgb(86, 180, 233),
+                loss: ThemeColor::Rgb(213, 94, 0),
+                highlight: ThemeColor::Rgb(240, 228, 66),
+                muted: ThemeColor::Gray,
+            },
+        }
+    }
+
+    /// Profit or loss color for a signed value
+    pub fn pnl(&self, positive: bool) -> ThemeColor {
+        if positive {
+            self.profit
+        } else {
+            self.loss
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::preset(ThemePreset::Default)
+    }
+}
+
+/// Per-role overrides applied on top of the preset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorOverrides {
+    pub bid: Option<ThemeColor>,
+    pub ask: Option<ThemeColor>,
+    pub profit: Option<ThemeColor>,
+    pub loss: Option<ThemeColor>,
+    pub highlight: Option<ThemeColor>,
+    pub muted: Option<ThemeColor>,
+}
+
+/// Theme configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    pub colors: ColorOverrides,
+}
+
+impl ThemeConfig {
+    /// Load `theme.yaml` from the data directory, or defaults when it doesn't exist
+    pub fn load(data_paths: &DataPaths) -> Result<Self> {
+        let path = data_paths.root().join(THEME_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid theme config {}", path.display()))
+    }
+
+    /// Preset palette with the configured overrides applied
+    pub fn palette(&self) -> Palette {
+        let mut palette = Palette::preset(self.preset);
+        let overrides = &self.colors;
+        palette.bid = overrides.bid.unwrap_or(palette.bid);
+        palette.ask = overrides.ask.unwrap_or(palette.ask);
+        palette.profit = overrides.profit.unwrap_or(palette.profit);
+        palette.loss = overrides.loss.unwrap_or(palette.loss);
+        palette.highlight = overrides.highlight.unwrap_or(palette.highlight);
+        palette.muted = overrides.muted.unwrap_or(palette.muted);
+        palette
+    }
+}
+
+/// Load the theme from the data directory and make it the active palette.
+/// `preset` (from a command line flag) replaces the configured preset but
+/// keeps the color overrides. An invalid file is reported and the default
+/// palette is used instead. Only the first call has an effect.
+pub fn init(data_paths: &DataPaths, preset: Option<ThemePreset>) -> Palette {
+    let mut config = ThemeConfig::load(data_paths).unwrap_or_else(|e| {
+        warn!("Using default theme: {:#}", e);
+        ThemeConfig::default()
+    });
+    if let Some(preset) = preset {
+        config.preset = preset;
+    }
+    let palette = config.palette();
+    *PALETTE.get_or_init(|| palette)
+}
+
+/// Active palette; the default preset until [`init`] is called
+pub fn palette() -> &'static Palette {
+    PALETTE.get_or_init(Palette::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_hex() {
+        assert_eq!("light_red".parse::<ThemeColor>().unwrap(), ThemeColor::LightRed);
+        assert_eq!("Dark-Grey".parse::<ThemeColor>().unwrap(), ThemeColor::DarkGray);
+        assert_eq!("#0072b2".parse::<ThemeColor>().unwrap(), ThemeColor::Rgb(0, 114, 178));
+        assert!("#12345".parse::<ThemeColor>().is_err());
+        assert!("chartreuse".parse::<ThemeColor>().is_err());
+    }
+
+    #[test]
+    fn overrides_apply_on_top_of_preset() {
+        let config: ThemeConfig = serde_yaml::from_str(
+            "preset: colorblind\ncolors:\n  loss: light_red\n",
+        )
+        .unwrap();
+        let palette = config.palette();
+        assert_eq!(palette.loss, ThemeColor::LightRed);
+        assert_eq!(palette.bid, Palette::preset(ThemePreset::Colorblind).bid);
+    }
+
+    #[test]
+    fn colors_round_trip_through_yaml() {
+        let palette = Palette::preset(ThemePreset::HighContrast);
+        let yaml = serde_yaml::to_string(&palette).unwrap();
+        assert_eq!(serde_yaml::from_str::<Palette>(&yaml).unwrap(), palette);
+    }
+}
@@ -2,11 +2,16 @@ use crate::data_paths::DataPaths;
 use crate::core::types::market::PriceLevel;
 use crate::core::types::common::Side;
 use crate::core::ws::PolyEvent;
+use crate::core::execution::book_view::{BookView, DepthDisplay, PriceAggregation};
 use crate::core::execution::orders::{EnhancedOrder, OrderManager};
-use crate::core::portfolio::controller::PortfolioManager;
+use crate::core::execution::synthetic::{SyntheticMonitor, SyntheticStore};
+use crate::core::portfolio::{ActiveOrder, PortfolioManager};
 use crate::core::services::Streamer;
+use crate::markets::snapshot_diff::{MarketSnapshot, SnapshotDiff, SnapshotHistory};
 use crate::tui::navigation::Navigation;
-use crate::tui::pages::{MarketsPage, OrdersPage, PortfolioPage, StreamPage, TokensPage};
+use crate::tui::pages::{
+    DatasetsPage, GammaPage, MarketsPage, OrdersPage, PortfolioPage, StreamPage, TokensPage,
+};
 use rust_decimal::Decimal;
 use serde_json;
 use std::collections::HashMap;
@@ -17,6 +22,9 @@ use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 use clipboard::{ClipboardContext, ClipboardProvider};
 
+/// Price points kept per synthetic instrument
+const SYNTHETIC_SERIES_LEN: usize = 500;
+
 #[derive(Debug, Clone)]
 pub struct TokenActivity {
     pub token_id: String,
@@ -49,6 +57,8 @@ pub struct App {
     pub tokens_page: TokensPage,
     pub markets_page: MarketsPage,
     pub portfolio_page: PortfolioPage,
+    pub datasets_page: DatasetsPage,
+    pub gamma_page: GammaPage,
 
     // Portfolio and order management
     pub portfolio_manager: Arc<PortfolioManager>,
@@ -71,6 +81,12 @@ pub struct App {
     // Order book scroll state
     pub orderbook_scroll: usize,
 
+    // Price bin width for the order book view
+    pub orderbook_aggregation: PriceAggregation,
+
+    // Ladder or cumulative depth chart
+    pub orderbook_display: DepthDisplay,
+
     // Event log scroll state
     pub event_log_scroll: usize,
 
@@ -80,6 +96,12 @@ pub struct App {
     
     // Clipboard notification
     pub clipboard_notification: Option<(String, Instant)>,
+
+    // Sampled books of the streamed tokens, for the markets page's recent change card
+    pub market_history: SnapshotHistory,
+
+    // Books and bound alerts of the synthetic instruments, for the stream page
+    pub synthetics: SyntheticMonitor,
 }
 
 impl App {
@@ -99,6 +121,8 @@ impl App {
             tokens_page: TokensPage::new(),
             markets_page: MarketsPage::new(),
             portfolio_page: PortfolioPage::new(),
+            datasets_page: DatasetsPage::new(),
+            gamma_page: GammaPage::new(),
 
             // Initialize portfolio manager
             portfolio_manager: Arc::new(PortfolioManager::new()),
@@ -117,10 +141,14 @@ impl App {
             current_asks: Vec::new(),
             current_token_id: None,
             orderbook_scroll: 0,
+            orderbook_aggregation: PriceAggregation::default(),
+            orderbook_display: DepthDisplay::default(),
             event_log_scroll: 0,
             total_events_received: 0,
             start_time: Instant::now(),
             clipboard_notification: None,
+            market_history: SnapshotHistory::default(),
+            synthetics: SyntheticMonitor::new(Vec::new(), 1, SYNTHETIC_SERIES_LEN),
         }
     }
 
@@ -278,6 +306,47 @@ impl App {
             }
             _ => {}
         }
+
+        // Sample the token's book now and then for the markets page
+        let sampled = match &event {
+            PolyEvent::PriceChange { asset_id, .. }
+            | PolyEvent::Book { asset_id, .. }
+            | PolyEvent::Trade { asset_id, .. } => Some(asset_id),
+            _ => None,
+        };
+        if let Some(asset_id) = sampled {
+            if self.market_history.is_due(asset_id, chrono::Utc::now()) {
+                if let Some(snapshot) = self.market_snapshot(asset_id) {
+                    self.market_history.record(asset_id, snapshot);
+                }
+            }
+        }
+
+        if let PolyEvent::PriceChange { asset_id, .. } | PolyEvent::Book { asset_id, .. } = &event {
+            self.update_synthetics(asset_id);
+        }
+    }
+
+    /// Current book of `token_id` with its streamed volume, if it is streamed
+    pub fn market_snapshot(&self, token_id: &str) -> Option<MarketSnapshot> {
+        let book = self.streamer.get_order_book(token_id)?;
+        let volume = self
+            .token_activities
+            .try_read()
+            .ok()
+            .and_then(|activities| activities.get(token_id).map(|a| a.total_volume));
+        Some(MarketSnapshot::from_order_book(&book, volume, chrono::Utc::now()))
+    }
+
+    /// How the market of `token_id` moved over the last `minutes` of sampled books
+    pub fn recent_market_change(&self, token_id: &str, minutes: i64) -> Option<SnapshotDiff> {
+        let current = self.market_snapshot(token_id)?;
+        self.market_history.diff(
+            token_id,
+            format!("Last {}m", minutes),
+            &current,
+            chrono::Duration::minutes(minutes),
+        )
     }
 
     pub fn get_all_active_tokens(&self) -> Vec<TokenActivity> {
@@ -334,9 +403,26 @@ impl App {
         self.orderbook_scroll = self.orderbook_scroll.saturating_sub(1);
     }
 
+    /// Current book as displayed, after price aggregation
+    pub fn orderbook_view(&self) -> BookView {
+        BookView::new(&self.current_bids, &self.current_asks, self.orderbook_aggregation)
+    }
+
+    /// Switch to the next price bin width and re-centre on the mid
+    pub fn cycle_orderbook_aggregation(&mut self) {
+        self.orderbook_aggregation = self.orderbook_aggregation.next();
+        self.reset_orderbook_scroll();
+    }
+
+    /// Switch the order book between the level ladder and the depth chart
+    pub fn toggle_orderbook_display(&mut self) {
+        self.orderbook_display = self.orderbook_display.toggle();
+    }
+
     pub fn scroll_orderbook_down(&mut self) {
         // Calculate the maximum scroll position based on current orderbook data
-        let total_levels = self.current_bids.len() + self.current_asks.len() + 1; // +1 for mid
+        let view = self.orderbook_view();
+        let total_levels = view.bids.len() + view.asks.len() + 1; // +1 for mid
         let display_height = 25; // Reasonable estimate for display area
         
         if total_levels > display_height {
@@ -360,10 +446,11 @@ impl App {
     pub fn reset_orderbook_scroll(&mut self) {
         // Center the orderbook view on the mid-point
         // Calculate based on current orderbook data
-        let total_levels = self.current_bids.len() + self.current_asks.len() + 1; // +1 for mid
+        let view = self.orderbook_view();
+        let total_levels = view.bids.len() + view.asks.len() + 1; // +1 for mid
         
         // Find approximate mid position (asks are first, then mid, then bids)
-        let mid_index = self.current_asks.len(); // Mid is right after asks
+        let mid_index = view.asks.len(); // Mid is right after asks
         
         // Assume a reasonable display height
         let display_height = 25;
@@ -460,10 +547,33 @@ impl App {
 
     /// Configure the app with data paths and host for API calls
     pub fn configure_data_access(&mut self, data_paths: DataPaths, host: String) {
+        match SyntheticStore::new(&data_paths).load() {
+            Ok(instruments) => {
+                self.synthetics = SyntheticMonitor::new(instruments, 1, SYNTHETIC_SERIES_LEN)
+            }
+            Err(e) => warn!("Synthetic instruments not loaded: {}", e),
+        }
         self.data_paths = Some(data_paths);
         self.host = Some(host);
     }
 
+    /// Rebuild the synthetic books after a leg's book changed, logging bound alerts
+    fn update_synthetics(&mut self, asset_id: &str) {
+        let tokens = self.synthetics.tokens();
+        if !tokens.contains(asset_id) {
+            return;
+        }
+        let books = tokens
+            .iter()
+            .filter_map(|token| Some((token.clone(), self.streamer.get_order_book(token)?)))
+            .collect();
+        let update = self.synthetics.update(&books, chrono::Utc::now());
+        for alert in update.alerts {
+            let icon = if alert.raised { "🚨" } else { "✅" };
+            self.event_log.push(format!("{} Synthetic {}", icon, alert));
+        }
+    }
+
     /// Check if we should fetch fresh orders data
     pub fn should_fetch_orders(&self) -> bool {
         if self.is_fetching_orders {
@@ -576,28 +686,17 @@ impl App {
 
         info!("Updated orders cache with {} orders", fetched_orders.len());
 
-        // Convert to ActiveOrder for portfolio manager compatibility
-        let active_orders: Result<Vec<crate::core::portfolio::types::ActiveOrder>, _> = fetched_orders
-            .iter()
-            .map(|enhanced_order| Self::convert_enhanced_to_active_order(enhanced_order))
-            .collect();
-
-        match active_orders {
-            Ok(orders) => {
-                // Update portfolio manager with fetched orders
-                let mut active_orders_map = portfolio_manager.active_orders().write().await;
-                active_orders_map.clear();
-                for order in orders {
-                    active_orders_map.insert(order.order_id.clone(), order);
-                }
-                info!(
-                    "Updated portfolio manager with {} active orders",
-                    active_orders_map.len()
-                );
-            }
-            Err(e) => {
-                warn!("Failed to convert orders for portfolio manager: {}", e);
+        // Update portfolio manager with fetched orders
+        {
+            let mut active_orders_map = portfolio_manager.active_orders().write().await;
+            active_orders_map.clear();
+            for order in fetched_orders.iter().map(ActiveOrder::from) {
+                active_orders_map.insert(order.order_id.clone(), order);
             }
+            info!(
+                "Updated portfolio manager with {} active orders",
+                active_orders_map.len()
+            );
         }
 
         Ok(fetched_orders.len())
@@ -635,76 +734,6 @@ impl App {
             }
         }
     }
-
-    /// Convert EnhancedOrder to ActiveOrder for portfolio manager compatibility
-    fn convert_enhanced_to_active_order(
-        enhanced: &EnhancedOrder,
-    ) -> Result<crate::core::portfolio::types::ActiveOrder, anyhow::Error> {
-        use crate::core::portfolio::types::{
-            ActiveOrder, OrderSide, OrderStatus, OrderType, TimeInForce,
-        };
-        use chrono::Utc;
-        use rust_decimal::prelude::FromPrimitive;
-        use rust_decimal::Decimal;
-
-        // Convert side
-        let side = match enhanced.side {
-            crate::core::execution::orders::OrderSide::Buy => OrderSide::Buy,
-            crate::core::execution::orders::OrderSide::Sell => OrderSide::Sell,
-        };
-
-        // Convert status
-        let status = match enhanced.status {
-            crate::core::execution::orders::OrderStatus::Open => OrderStatus::Open,
-            crate::core::execution::orders::OrderStatus::Filled => OrderStatus::Filled,
-            crate::core::execution::orders::OrderStatus::Cancelled => OrderStatus::Cancelled,
-            crate::core::execution::orders::OrderStatus::PartiallyFilled => OrderStatus::PartiallyFilled,
-            crate::core::execution::orders::OrderStatus::Rejected => OrderStatus::Rejected,
-            crate::core::execution::orders::OrderStatus::Pending => OrderStatus::Pending,
-        };
-
-        // Extract outcome from additional fields or market info
-        let outcome = enhanced
-            .market_info
-            .as_ref()
-            .and_then(|info| info.token_outcome.clone())
-            .or_else(|| {
-                enhanced
-                    .additional_fields
-                    .get("outcome")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-            })
-            .unwrap_or_else(|| "YES".to_string());
-
-        // Extract market_id from additional fields or use asset_id as fallback
-        let market_id = enhanced
-            .additional_fields
-            .get("market")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| enhanced.asset_id.clone());
-
-        Ok(ActiveOrder {
-            order_id: enhanced.id.clone(),
-            market_id,
-            token_id: enhanced.asset_id.clone(),
-            outcome,
-            side,
-            order_type: OrderType::Limit, // Default to limit, API doesn't always provide this
-            price: Decimal::from_f64(enhanced.price).unwrap_or_default(),
-            size: Decimal::from_f64(enhanced.original_size).unwrap_or_default(),
-            filled_size: Decimal::from_f64(enhanced.filled_size).unwrap_or_default(),
-            remaining_size: Decimal::from_f64(enhanced.remaining_size).unwrap_or_default(),
-            status,
-            created_at: enhanced.created_at,
-            updated_at: enhanced.updated_at.unwrap_or_else(|| Utc::now()),
-            time_in_force: TimeInForce::GTC, // Default to Good Till Cancelled
-            post_only: false,                // Default, not provided by API
-            reduce_only: false,              // Default, not provided by API
-        })
-    }
-
 }
 
 fn format_event(event: &PolyEvent) -> String {
@@ -0,0 +1,125 @@
+//! Full-screen progress view for long multi-task jobs
+//!
+//! Draws one gauge per `core::progress` task, in the order the tasks start,
+//! from the snapshots of a `ProgressReporter::channel()`. `q`, Esc or Ctrl-C
+//! cancel the reporter; the view stays up until the job returns, so work in
+//! flight can stop cleanly. Used by `polybot gamma import-session`.
+
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph},
+    Frame, Terminal,
+};
+use std::future::Future;
+use std::io;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::core::progress::{ProgressReporter, ProgressSnapshot};
+
+/// Run `job` while drawing the tasks reported through `snapshots`
+pub async fn run<T>(
+    title: &str,
+    reporter: &ProgressReporter,
+    mut snapshots: mpsc::UnboundedReceiver<ProgressSnapshot>,
+    job: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let started = Instant::now();
+    let mut tasks: Vec<ProgressSnapshot> = Vec::new();
+    let mut tick = tokio::time::interval(Duration::from_millis(100));
+    tokio::pin!(job);
+    let result = loop {
+        tokio::select! {
+            result = &mut job => break result,
+            _ = tick.tick() => {}
+        }
+        while let Ok(snapshot) = snapshots.try_recv() {
+            match tasks.iter_mut().find(|task| task.task == snapshot.task) {
+                Some(task) => *task = snapshot,
+                None => tasks.push(snapshot),
+            }
+        }
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c;
+                if key.kind == KeyEventKind::Press && quit {
+                    reporter.cancel();
+                }
+            }
+        }
+        terminal.draw(|f| draw(f, title, &tasks, started.elapsed(), reporter.is_cancelled()))?;
+    };
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn draw(f: &mut Frame, title: &str, tasks: &[ProgressSnapshot], elapsed: Duration, stopping: bool) {
+    let mut constraints = vec![Constraint::Length(3)];
+    constraints.extend(tasks.iter().map(|_| Constraint::Length(3)));
+    constraints.push(Constraint::Min(0));
+    constraints.push(Constraint::Length(1));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(f.area());
+
+    let header = Paragraph::new(Line::from(vec![
+        Span::styled(
+            title.to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("  ·  {}s elapsed", elapsed.as_secs())),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, rows[0]);
+
+    for (task, area) in tasks.iter().zip(rows.iter().skip(1)) {
+        let color = if task.finished {
+            Color::Green
+        } else {
+            Color::Cyan
+        };
+        let percent = task.fraction().map(|f| (f * 100.0) as u16).unwrap_or(0);
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .title(task.task.clone())
+                    .borders(Borders::ALL),
+            )
+            .gauge_style(Style::default().fg(color).bg(Color::Black))
+            .percent(percent)
+            .label(task.label());
+        f.render_widget(gauge, *area);
+    }
+
+    let footer = if stopping {
+        Span::styled(
+            "Stopping after the work in progress...",
+            Style::default().fg(Color::Yellow),
+        )
+    } else {
+        Span::styled("q / Esc: stop", Style::default().fg(Color::DarkGray))
+    };
+    f.render_widget(Paragraph::new(Line::from(footer)), rows[rows.len() - 1]);
+}
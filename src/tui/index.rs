@@ -22,7 +22,10 @@ use tokio::sync::mpsc;
 use tracing::error;
 
 use crate::cli::commands::index::{IndexArgs, IndexCommand};
+use crate::core::progress::{ProgressReporter, ProgressSnapshot};
 use crate::data_paths::DataPaths;
+use crate::tui::index_browser::IndexBrowser;
+use crate::typed_store::freshness;
 
 pub struct IndexTui {
     data_paths: DataPaths,
@@ -36,6 +39,12 @@ pub struct IndexTui {
     is_indexing: bool,
     indexing_progress: Arc<Mutex<crate::tui::IndexingProgress>>,
     progress_receiver: Option<mpsc::UnboundedReceiver<crate::tui::ProgressUpdate>>,
+    // Bar progress (with ETA) of the running index, and its cancel handle
+    reporter: Option<ProgressReporter>,
+    snapshot_receiver: Option<mpsc::UnboundedReceiver<ProgressSnapshot>>,
+    files_snapshot: Option<ProgressSnapshot>,
+    // Search and drill-down into the indexed markets, while open
+    browser: Option<IndexBrowser>,
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +82,10 @@ impl IndexTui {
             is_indexing: false,
             indexing_progress: Arc::new(Mutex::new(crate::tui::IndexingProgress::default())),
             progress_receiver: None,
+            reporter: None,
+            snapshot_receiver: None,
+            files_snapshot: None,
+            browser: None,
         };
 
         // Set initial status message
@@ -138,6 +151,13 @@ impl IndexTui {
             for update in updates_to_process {
                 self.handle_progress_update(update);
             }
+            if let Some(receiver) = &mut self.snapshot_receiver {
+                while let Ok(snapshot) = receiver.try_recv() {
+                    if snapshot.task == "Chunk files" {
+                        self.files_snapshot = Some(snapshot);
+                    }
+                }
+            }
 
             // Clear old status messages
             if let Some(last_time) = self.last_status_time {
@@ -154,16 +174,27 @@ impl IndexTui {
                         if self.is_indexing {
                             match key.code {
                                 KeyCode::Char('q') | KeyCode::Esc => {
-                                    // TODO: Implement cancellation
+                                    if let Some(reporter) = &self.reporter {
+                                        reporter.cancel();
+                                    }
                                     self.set_status_message(
-                                        "⚠️ Indexing in progress, cannot quit yet".to_string(),
+                                        "⏹ Cancelling after the files in progress...".to_string(),
                                     );
                                 }
                                 _ => {}
                             }
+                        } else if let Some(browser) = &mut self.browser {
+                            if !browser.handle_key(key.code) {
+                                self.browser = None;
+                            }
                         } else {
                             match key.code {
                                 KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                                KeyCode::Char('/') => {
+                                    self.browser = Some(IndexBrowser::new(
+                                        freshness::default_db_path(&self.data_paths),
+                                    ));
+                                }
                                 KeyCode::Char('h') | KeyCode::F(1) => {
                                     self.show_help = !self.show_help;
                                 }
@@ -216,6 +247,9 @@ impl IndexTui {
     fn ui(&mut self, f: &mut Frame) {
         if self.is_indexing {
             self.render_indexing_progress(f);
+        } else if let Some(browser) = &mut self.browser {
+            let area = f.area();
+            browser.render(f, area);
         } else {
             self.render_file_selection(f);
         }
@@ -277,7 +311,7 @@ impl IndexTui {
 
         // Instructions
         let instructions = if self.chunk_files.is_empty() {
-            Paragraph::new("Press 'q' to quit, 'r' to refresh")
+            Paragraph::new("Press 'q' to quit, 'r' to refresh, '/' to browse the index")
         } else {
             Paragraph::new(vec![
                 Line::from(vec![
@@ -289,6 +323,7 @@ impl IndexTui {
                 Line::from(vec![
                     Span::raw("Enter: Start Index  "),
                     Span::raw("r: Refresh  "),
+                    Span::raw("/: Browse Index  "),
                     Span::raw("h: Help  "),
                     Span::raw("q: Quit"),
                 ]),
@@ -368,6 +403,23 @@ impl IndexTui {
             0
         };
 
+        let overall_label = match &self.files_snapshot {
+            Some(snapshot) => format!(
+                "Files {} - {} markets indexed",
+                snapshot.label(),
+                progress.total_markets_indexed
+            ),
+            None => format!(
+                "File {}/{} - {} markets indexed",
+                progress.current_file, progress.total_files, progress.total_markets_indexed
+            ),
+        };
+        let overall_percent = self
+            .files_snapshot
+            .as_ref()
+            .and_then(ProgressSnapshot::fraction)
+            .map(|fraction| (fraction * 100.0) as u16)
+            .unwrap_or(overall_percent);
         let overall_gauge = Gauge::default()
             .block(
                 Block::default()
@@ -376,10 +428,7 @@ impl IndexTui {
             )
             .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
             .percent(overall_percent)
-            .label(format!(
-                "File {}/{} - {} markets indexed",
-                progress.current_file, progress.total_files, progress.total_markets_indexed
-            ));
+            .label(overall_label);
         f.render_widget(overall_gauge, progress_chunks[0]);
 
         // File progress
@@ -558,8 +607,9 @@ impl IndexTui {
             Line::from("Actions:"),
             Line::from("  Enter       - Start indexing selected files"),
             Line::from("  r           - Refresh file list from disk"),
+            Line::from("  /           - Search and browse the indexed markets"),
             Line::from("  h or F1     - Toggle this help"),
-            Line::from("  q or Esc    - Quit without indexing"),
+            Line::from("  q or Esc    - Quit without indexing (cancel while indexing)"),
             Line::from(""),
             Line::from("The indexer will:"),
             Line::from("• Convert JSON markets to typed RocksDB entries"),
@@ -664,13 +714,20 @@ impl IndexTui {
             db_path: None,         // Use default
             use_file_store: false, // Don't use file-based storage
             rocksdb: true,         // Use RocksDB storage
+            legacy_store: false,
+            from_file_store: None,
             source_dir: None,
             chunk_files: Some(chunk_files_str),
             clear: false,
+            bulk_load: false,
             skip_duplicates: true,
             batch_size: 1000,
+            file_layout: Default::default(),
+            fsync: Default::default(),
             detailed: true,
             threads: 0, // Auto-detect optimal thread count
+            memory_limit: None,
+            action: None,
         };
 
         // Create progress channel
@@ -687,8 +744,15 @@ impl IndexTui {
         self.is_indexing = true;
         self.set_status_message("🚀 Starting indexing process...".to_string());
 
+        let (reporter, snapshot_receiver) = ProgressReporter::channel();
+        self.reporter = Some(reporter.clone());
+        self.snapshot_receiver = Some(snapshot_receiver);
+        self.files_snapshot = None;
+
         // Spawn the indexing task
-        let command = IndexCommand::new(args).with_progress_sender(progress_sender);
+        let command = IndexCommand::new(args)
+            .with_progress_sender(progress_sender)
+            .with_progress(reporter);
         let data_paths = self.data_paths.clone();
         tokio::spawn(async move {
             let result = command.execute_internal(&data_paths).await;
@@ -0,0 +1,358 @@
+//! Browser of the RocksDB index, opened from the index TUI with `/`
+//!
+//! Searches the store through `typed_store::inspect` and shows the decoded
+//! rows of the picked market in every column family. Its tokens and its
+//! condition are links: Enter opens one in place and Backspace goes back.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::path::PathBuf;
+
+use crate::number_format;
+use crate::typed_store::inspect::{self, Inspection, SearchHit, Target};
+
+/// Most markets a text search lists
+const SEARCH_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Search,
+    Results,
+    Records,
+}
+
+pub struct IndexBrowser {
+    db_path: PathBuf,
+    query: String,
+    focus: Focus,
+    hits: Vec<SearchHit>,
+    hit_state: ListState,
+    inspection: Option<Inspection>,
+    /// Selected link of the inspection
+    link_index: usize,
+    scroll: u16,
+    /// Targets left by following links, newest last
+    back: Vec<Target>,
+    status: Option<String>,
+}
+
+impl IndexBrowser {
+    pub fn new(db_path: PathBuf) -> Self {
+        let status =
+            (!db_path.exists()).then(|| format!("⚠️ No index at {} yet", db_path.display()));
+        Self {
+            db_path,
+            query: String::new(),
+            focus: Focus::Search,
+            hits: Vec::new(),
+            hit_state: ListState::default(),
+            inspection: None,
+            link_index: 0,
+            scroll: 0,
+            back: Vec::new(),
+            status,
+        }
+    }
+
+    /// Handle a key; false once the browser should close
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        match self.focus {
+            Focus::Search => match key {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Enter => self.run_search(),
+                KeyCode::Esc if self.hits.is_empty() => return false,
+                KeyCode::Esc | KeyCode::Down => self.focus = Focus::Results,
+                _ => {}
+            },
+            Focus::Results => match key {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Char('/') => self.focus = Focus::Search,
+                KeyCode::Up | KeyCode::Char('k') => self.move_hit(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_hit(1),
+                KeyCode::Enter | KeyCode::Right => {
+                    if let Some(hit) = self.hit_state.selected().and_then(|i| self.hits.get(i)) {
+                        let target = Target::Condition(hit.condition_id.clone());
+                        self.back.clear();
+                        self.open(target);
+                    }
+                }
+                _ => {}
+            },
+            Focus::Records => match key {
+                KeyCode::Char('q') => return false,
+                KeyCode::Esc => self.focus = Focus::Results,
+                KeyCode::Char('/') => self.focus = Focus::Search,
+                KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+                KeyCode::Tab | KeyCode::Right => self.move_link(1),
+                KeyCode::BackTab | KeyCode::Left => self.move_link(-1),
+                KeyCode::Enter => self.follow_link(),
+                KeyCode::Backspace => {
+                    if let Some(target) = self.back.pop() {
+                        self.open(target);
+                    }
+                }
+                _ => {}
+            },
+        }
+        true
+    }
+
+    fn run_search(&mut self) {
+        match inspect::search(&self.db_path, &self.query, SEARCH_LIMIT) {
+            Ok(hits) => {
+                self.status = Some(match hits.len() {
+                    0 => format!("No markets match '{}'", self.query.trim()),
+                    SEARCH_LIMIT => format!("First {} matching markets", SEARCH_LIMIT),
+                    n => format!("{} matching markets", n),
+                });
+                self.hit_state.select((!hits.is_empty()).then_some(0));
+                if !hits.is_empty() {
+                    self.focus = Focus::Results;
+                }
+                self.hits = hits;
+            }
+            Err(e) => self.status = Some(format!("❌ Search failed: {}", e)),
+        }
+    }
+
+    fn move_hit(&mut self, step: isize) {
+        if self.hits.is_empty() {
+            return;
+        }
+        let current = self.hit_state.selected().unwrap_or(0) as isize;
+        let next = (current + step).rem_euclid(self.hits.len() as isize);
+        self.hit_state.select(Some(next as usize));
+    }
+
+    fn move_link(&mut self, step: isize) {
+        let Some(inspection) = &self.inspection else {
+            return;
+        };
+        if inspection.links.is_empty() {
+            return;
+        }
+        let next = (self.link_index as isize + step).rem_euclid(inspection.links.len() as isize);
+        self.link_index = next as usize;
+    }
+
+    fn follow_link(&mut self) {
+        let Some(inspection) = &self.inspection else {
+            return;
+        };
+        let Some(link) = inspection.links.get(self.link_index) else {
+            return;
+        };
+        let (current, target) = (inspection.target.clone(), link.target.clone());
+        if self.open(target) {
+            self.back.push(current);
+        }
+    }
+
+    /// Show the rows of `target`; false if there are none
+    fn open(&mut self, target: Target) -> bool {
+        match inspect::inspect(&self.db_path, &target) {
+            Ok(Some(inspection)) => {
+                self.status = Some(format!(
+                    "{} rows for {}",
+                    inspection.records.len(),
+                    target.id()
+                ));
+                self.inspection = Some(inspection);
+                self.link_index = 0;
+                self.scroll = 0;
+                self.focus = Focus::Records;
+                true
+            }
+            Ok(None) => {
+                self.status = Some(format!("No rows for {}", target.id()));
+                false
+            }
+            Err(e) => {
+                self.status = Some(format!("❌ Failed to read {}: {}", target.id(), e));
+                false
+            }
+        }
+    }
+
+    fn border(&self, focus: Focus) -> Style {
+        if self.focus == focus {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Gray)
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Search
+                Constraint::Min(10),   // Results and records
+                Constraint::Length(3), // Controls
+            ])
+            .split(area);
+
+        let cursor = if self.focus == Focus::Search {
+            "▏"
+        } else {
+            ""
+        };
+        let search = Paragraph::new(format!("{}{}", self.query, cursor)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.border(Focus::Search))
+                .title("🔎 Search the index (question, category, condition ID or token ID)"),
+        );
+        f.render_widget(search, chunks[0]);
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[1]);
+        self.render_hits(f, body[0]);
+        self.render_inspection(f, body[1]);
+
+        let help = match self.focus {
+            Focus::Search => "Type to search • Enter: Search • Esc: Results/Back",
+            Focus::Results => "↑/↓: Navigate • Enter: Open market • /: Search • q/Esc: Back to files",
+            Focus::Records => {
+                "↑/↓ PgUp/PgDn: Scroll • Tab/←/→: Pick link • Enter: Open link • Backspace: Previous • /: Search • Esc: Results"
+            }
+        };
+        let footer = Paragraph::new(vec![
+            Line::from(Span::styled(
+                self.status.clone().unwrap_or_default(),
+                Style::default().fg(Color::Green),
+            )),
+            Line::from(Span::styled(help, Style::default().fg(Color::Gray))),
+        ])
+        .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(footer, chunks[2]);
+    }
+
+    fn render_hits(&mut self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .map(|hit| {
+                let (marker, color) = match (hit.active, hit.closed) {
+                    (_, true) => ("✗", Color::DarkGray),
+                    (true, false) => ("●", Color::Green),
+                    (false, false) => ("○", Color::Yellow),
+                };
+                let volume = hit
+                    .volume
+                    .map(|v| format!("${}", number_format::integer(v)))
+                    .unwrap_or_else(|| "-".to_string());
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(format!("{} ", marker), Style::default().fg(color)),
+                        Span::raw(hit.question.clone()),
+                    ]),
+                    Line::from(Span::styled(
+                        format!("  {} · vol {}", hit.condition_id, volume),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.border(Focus::Results))
+                    .title(format!("Markets ({})", self.hits.len())),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        f.render_stateful_widget(list, area, &mut self.hit_state);
+    }
+
+    fn render_inspection(&self, f: &mut Frame, area: Rect) {
+        let Some(inspection) = &self.inspection else {
+            let empty = Paragraph::new("Open a market to see its rows in every column family")
+                .style(Style::default().fg(Color::Gray))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.border(Focus::Records))
+                        .title("Records"),
+                );
+            f.render_widget(empty, area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let mut spans = Vec::new();
+        for (i, link) in inspection.links.iter().enumerate() {
+            let style = if i == self.link_index {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            spans.push(Span::styled(format!("[{}]", link.label), style));
+            spans.push(Span::raw(" "));
+        }
+        let links = Paragraph::new(Line::from(spans)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.border(Focus::Records))
+                .title(format!("Related ({} back)", self.back.len())),
+        );
+        f.render_widget(links, chunks[0]);
+
+        let mut lines = Vec::new();
+        for record in &inspection.records {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    record.cf,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {}", record.key),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            lines.extend(
+                record
+                    .value
+                    .lines()
+                    .map(|line| Line::from(line.to_string())),
+            );
+            lines.push(Line::from(""));
+        }
+        let records = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.border(Focus::Records))
+                    .title(inspection.title.clone()),
+            );
+        f.render_widget(records, chunks[1]);
+    }
+}
@@ -19,6 +19,7 @@ use std::{
 use tracing::info;
 
 use crate::data_paths::DataPaths;
+use crate::number_format;
 use crate::typed_store::{
     models::{Condition, ConditionTable, MarketTable, RocksDbMarket, Token, TokenTable},
     TypedStore,
@@ -312,7 +313,7 @@ impl MarketsTui {
 
                 let volume_str = market
                     .volume
-                    .map(|v| format!("${:.0}", v))
+                    .map(|v| format!("${}", number_format::integer(v)))
                     .unwrap_or_else(|| "N/A".to_string());
 
                 let line = Line::from(vec![
@@ -364,7 +365,7 @@ impl MarketsTui {
                             .fg(Color::White)
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::raw(format!(" (${:.3})", token.current_price)),
+                    Span::raw(format!(" (${})", number_format::price(token.current_price))),
                     Span::styled(
                         format!(" - {}", truncate_text(&token.id, 20)),
                         Style::default().fg(Color::Gray),
@@ -10,6 +10,8 @@ pub enum Page {
     Tokens,
     Markets,
     Portfolio,
+    Datasets,
+    Gamma,
 }
 
 impl Page {
@@ -20,9 +22,17 @@ impl Page {
             Page::Tokens,
             Page::Markets,
             Page::Portfolio,
+            Page::Datasets,
+            Page::Gamma,
         ]
     }
 
+    /// Page for a number key, counting from 1 in tab bar order
+    pub fn from_digit(c: char) -> Option<Page> {
+        let index = c.to_digit(10)?.checked_sub(1)? as usize;
+        Self::all().get(index).cloned()
+    }
+
     pub fn title(&self) -> &'static str {
         match self {
             Page::Stream => "Stream",
@@ -30,6 +40,8 @@ impl Page {
             Page::Tokens => "Tokens",
             Page::Markets => "Markets",
             Page::Portfolio => "Portfolio",
+            Page::Datasets => "Datasets",
+            Page::Gamma => "Gamma",
         }
     }
 
@@ -79,10 +91,8 @@ impl Navigation {
         let pages = Page::all();
         let titles: Vec<Line> = pages
             .iter()
-            .map(|page| {
-                let title = page.title();
-                Line::from(title)
-            })
+            .enumerate()
+            .map(|(i, page)| Line::from(format!("{} {}", i + 1, page.title())))
             .collect();
 
         let current_index = pages
@@ -0,0 +1,228 @@
+use super::{spawn_load, LoadSlot, Loadable};
+use crate::markets::datasets::{DatasetInfo, DatasetManager, DatasetManagerConfig};
+use crate::tui::App;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+use std::sync::{Arc, Mutex};
+
+pub struct DatasetsPage {
+    pub selected_dataset: usize,
+    datasets: LoadSlot<Vec<DatasetInfo>>,
+}
+
+impl DatasetsPage {
+    pub fn new() -> Self {
+        Self {
+            selected_dataset: 0,
+            datasets: Arc::new(Mutex::new(Loadable::Idle)),
+        }
+    }
+
+    /// Rescan the data directory's datasets in the background
+    fn scan(&self, app: &App) {
+        let Some(data_paths) = app.data_paths.clone() else {
+            return;
+        };
+        spawn_load(&self.datasets, move || {
+            let mut manager = DatasetManager::new(DatasetManagerConfig {
+                base_dir: data_paths.datasets(),
+                ..Default::default()
+            });
+            manager.scan_datasets()?;
+            Ok(manager.get_datasets().to_vec())
+        });
+    }
+
+    fn render_list(&self, frame: &mut Frame, area: Rect, datasets: &[DatasetInfo]) {
+        let header = Row::new(vec!["", "Name", "Type", "Size", "Files", "Age"]).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        // Keep the selected row on screen
+        let visible = area.height.saturating_sub(3) as usize;
+        let offset = self.selected_dataset.saturating_sub(visible.saturating_sub(1));
+
+        let rows: Vec<Row> = datasets
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(i, dataset)| {
+                let style = if i == self.selected_dataset {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Row::new(vec![
+                    Cell::from(dataset.status_icon()),
+                    Cell::from(dataset.name.clone()),
+                    Cell::from(format!(
+                        "{} {}",
+                        dataset.dataset_type.icon(),
+                        dataset.dataset_type.display_name()
+                    )),
+                    Cell::from(dataset.formatted_size()),
+                    Cell::from(dataset.file_count.to_string()),
+                    Cell::from(dataset.age()),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Percentage(30),
+                Constraint::Percentage(35),
+                Constraint::Length(10),
+                Constraint::Length(6),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Datasets ({}) - ↑↓ select, R to rescan", datasets.len())),
+        );
+
+        frame.render_widget(table, area);
+    }
+
+    fn render_details(&self, frame: &mut Frame, area: Rect, dataset: Option<&DatasetInfo>) {
+        let block = Block::default().borders(Borders::ALL).title("Dataset Details");
+        let Some(dataset) = dataset else {
+            frame.render_widget(Paragraph::new("No dataset selected").block(block), area);
+            return;
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(Color::Gray)),
+                Span::styled(dataset.name.clone(), Style::default().fg(Color::Cyan)),
+            ]),
+            Line::from(format!("Path: {}", dataset.path.display())),
+            Line::from(format!("Type: {}", dataset.dataset_type.display_name())),
+            Line::from(format!(
+                "Health: {} {:?}",
+                dataset.status_icon(),
+                dataset.health_status
+            )),
+            Line::from(format!(
+                "Size: {} in {} files",
+                dataset.formatted_size(),
+                dataset.file_count
+            )),
+        ];
+        if let Some(modified) = dataset.modified_at {
+            lines.push(Line::from(format!(
+                "Modified: {}",
+                modified.format("%Y-%m-%d %H:%M:%S UTC")
+            )));
+        }
+
+        if !dataset.warnings.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Warnings ({})", dataset.warnings.len()),
+                Style::default().fg(Color::Yellow),
+            )));
+            for warning in dataset.warnings.iter().take(5) {
+                lines.push(Line::from(format!("  • {}", warning.message)));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Files",
+            Style::default().fg(Color::Gray),
+        )));
+        for file in dataset.files.iter().take(area.height.saturating_sub(lines.len() as u16 + 3) as usize) {
+            lines.push(Line::from(format!(
+                "  {} {} ({})",
+                file.file_type.icon(),
+                file.relative_path.display(),
+                crate::markets::datasets::format_bytes(file.size_bytes)
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl super::Page for DatasetsPage {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        if matches!(self.datasets.lock().as_deref(), Ok(Loadable::Idle)) {
+            self.scan(app);
+        }
+        let Ok(state) = self.datasets.lock() else {
+            return;
+        };
+        let message = match &*state {
+            Loadable::Idle | Loadable::Loading => "Scanning datasets...",
+            Loadable::Failed(e) => {
+                let error = Paragraph::new(format!("Failed to scan datasets: {}\n\nPress R to retry", e))
+                    .style(Style::default().fg(Color::Red))
+                    .block(Block::default().borders(Borders::ALL).title("Datasets"));
+                frame.render_widget(error, area);
+                return;
+            }
+            Loadable::Ready(datasets) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .split(area);
+                self.render_list(frame, chunks[0], datasets);
+                self.render_details(frame, chunks[1], datasets.get(self.selected_dataset));
+                return;
+            }
+        };
+
+        let loading = Paragraph::new(message)
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::default().borders(Borders::ALL).title("Datasets"));
+        frame.render_widget(loading, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut App) -> bool {
+        let count = match self.datasets.lock().as_deref() {
+            Ok(Loadable::Ready(datasets)) => datasets.len(),
+            _ => 0,
+        };
+        match key.code {
+            KeyCode::Up => {
+                self.selected_dataset = self.selected_dataset.saturating_sub(1);
+                true
+            }
+            KeyCode::Down => {
+                if count > 0 {
+                    self.selected_dataset = (self.selected_dataset + 1).min(count - 1);
+                }
+                true
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                self.selected_dataset = 0;
+                self.scan(app);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for DatasetsPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,132 @@
+use super::{spawn_load, LoadSlot, Loadable};
+use crate::markets::gamma::tui::MarketsBrowser;
+use crate::markets::gamma::{GammaMarket, GammaSearchEngine, GammaStorage};
+use crate::tui::App;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use std::sync::{Arc, Mutex};
+
+/// Number of markets loaded from the local Gamma database
+const MARKET_LIMIT: usize = 5000;
+
+pub struct GammaPage {
+    markets: LoadSlot<Vec<GammaMarket>>,
+    /// Built from the loaded markets; `MarketsBrowser::render` needs `&mut`
+    browser: Mutex<Option<MarketsBrowser>>,
+}
+
+impl GammaPage {
+    pub fn new() -> Self {
+        Self {
+            markets: Arc::new(Mutex::new(Loadable::Idle)),
+            browser: Mutex::new(None),
+        }
+    }
+
+    /// Load the top markets by volume from the local Gamma database
+    fn load(&self, app: &App) {
+        let Some(data_paths) = app.data_paths.clone() else {
+            return;
+        };
+        spawn_load(&self.markets, move || {
+            let storage = GammaStorage::new(data_paths.root().join("database/gamma"))?;
+            GammaSearchEngine::new(storage).get_top_markets_by_volume(MARKET_LIMIT)
+        });
+    }
+
+    /// Move freshly loaded markets into the browser
+    fn take_loaded(&self) {
+        let Ok(mut state) = self.markets.lock() else {
+            return;
+        };
+        if let Loadable::Ready(markets) = &mut *state {
+            let markets = std::mem::take(markets);
+            if let Ok(mut browser) = self.browser.lock() {
+                *browser = Some(MarketsBrowser::new(markets));
+            }
+            // Keep the slot out of Idle so the load is not repeated
+            *state = Loadable::Ready(Vec::new());
+        }
+    }
+
+    fn is_searching(&self) -> bool {
+        self.browser
+            .lock()
+            .map(|b| b.as_ref().is_some_and(|b| b.is_searching()))
+            .unwrap_or(false)
+    }
+}
+
+impl super::Page for GammaPage {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        if matches!(self.markets.lock().as_deref(), Ok(Loadable::Idle)) {
+            self.load(app);
+        }
+        self.take_loaded();
+
+        if let Ok(mut browser) = self.browser.lock() {
+            if let Some(browser) = browser.as_mut() {
+                browser.render(frame, area);
+                return;
+            }
+        }
+
+        let (message, color) = match self.markets.lock().as_deref() {
+            Ok(Loadable::Failed(e)) => (
+                format!(
+                    "Failed to load Gamma markets: {}\n\nRun `polybot gamma markets` to populate the database, then press R to retry",
+                    e
+                ),
+                Color::Red,
+            ),
+            _ => ("Loading Gamma markets...".to_string(), Color::Yellow),
+        };
+        let paragraph = Paragraph::new(message)
+            .style(Style::default().fg(color))
+            .block(Block::default().borders(Borders::ALL).title("Gamma Markets"));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, app: &mut App) -> bool {
+        let Ok(mut guard) = self.browser.lock() else {
+            return false;
+        };
+        let Some(browser) = guard.as_mut() else {
+            drop(guard);
+            if matches!(key.code, KeyCode::Char('r') | KeyCode::Char('R')) {
+                self.load(app);
+                return true;
+            }
+            return false;
+        };
+
+        match key.code {
+            KeyCode::Esc if browser.is_searching() => browser.cancel_search(),
+            KeyCode::Char('/') if !browser.is_searching() => browser.start_search(),
+            KeyCode::Enter => browser.handle_enter(),
+            KeyCode::Up => browser.previous(),
+            KeyCode::Down => browser.next(),
+            KeyCode::PageUp => browser.page_up(),
+            KeyCode::PageDown => browser.page_down(),
+            KeyCode::Home => browser.go_to_top(),
+            KeyCode::End => browser.go_to_bottom(),
+            KeyCode::Char(c) if browser.is_searching() => browser.handle_char(c),
+            KeyCode::Backspace if browser.is_searching() => browser.handle_backspace(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn captures_input(&self) -> bool {
+        self.is_searching()
+    }
+}
+
+impl Default for GammaPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,5 @@
+use crate::number_format;
+use crate::tui::widgets::snapshot_diff::{card_height, render_diff_card};
 use crate::tui::App;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -39,16 +41,16 @@ impl MarketsPage {
 
                 let bid_str = activity
                     .last_bid
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 let ask_str = activity
                     .last_ask
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 let volume_str = if activity.total_volume > rust_decimal::Decimal::ZERO {
-                    format!("${:.0}", activity.total_volume)
+                    format!("${}", number_format::integer(activity.total_volume))
                 } else {
                     "-".to_string()
                 };
@@ -86,6 +88,22 @@ impl MarketsPage {
     fn render_market_details(&self, frame: &mut Frame, area: Rect, app: &App) {
         let tokens = app.get_all_active_tokens();
 
+        // How the selected token moved lately, under its details
+        let change = tokens
+            .get(self.selected_market)
+            .and_then(|activity| app.recent_market_change(&activity.token_id, 15));
+        let area = match &change {
+            Some(diff) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(card_height(diff))])
+                    .split(area);
+                render_diff_card(frame, chunks[1], diff);
+                chunks[0]
+            }
+            None => area,
+        };
+
         let content = if let Some(activity) = tokens.get(self.selected_market) {
             let elapsed = activity
                 .last_update
@@ -95,26 +113,25 @@ impl MarketsPage {
             let spread = match (activity.last_bid, activity.last_ask) {
                 (Some(bid), Some(ask)) if ask > bid => {
                     format!(
-                        "${:.4} ({:.2}%)",
-                        ask - bid,
-                        ((ask - bid) / bid * rust_decimal::Decimal::from(100))
-                            .to_string()
-                            .parse::<f64>()
-                            .unwrap_or(0.0)
+                        "${} ({})",
+                        number_format::price(ask - bid),
+                        number_format::percent(
+                            (ask - bid) / bid * rust_decimal::Decimal::from(100)
+                        )
                     )
                 }
                 _ => "N/A".to_string(),
             };
 
             format!(
-                "Token Details:\n\nToken ID: {}\n\nActivity:\n• Events: {}\n• Trades: {}\n• Volume: ${:.2}\n• Event Rate: {:.2}/min\n\nPricing:\n• Last Bid: {}\n• Last Ask: {}\n• Spread: {}\n\nTiming:\n• Last Update: {}s ago\n\nControls:\nEnter - View in Stream\nT - View in Tokens page\nS - Subscribe",
+                "Token Details:\n\nToken ID: {}\n\nActivity:\n• Events: {}\n• Trades: {}\n• Volume: {}\n• Event Rate: {:.2}/min\n\nPricing:\n• Last Bid: {}\n• Last Ask: {}\n• Spread: {}\n\nTiming:\n• Last Update: {}s ago\n\nControls:\nEnter - View in Stream\nT - View in Tokens page\nS - Subscribe",
                 activity.token_id,
                 activity.event_count,
                 activity.trade_count,
-                activity.total_volume,
+                number_format::usd(activity.total_volume),
                 if elapsed > 0 { activity.event_count as f64 / (elapsed as f64 / 60.0) } else { 0.0 },
-                activity.last_bid.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string()),
-                activity.last_ask.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string()),
+                activity.last_bid.map(|p| format!("${}", number_format::price(p))).unwrap_or_else(|| "N/A".to_string()),
+                activity.last_ask.map(|p| format!("${}", number_format::price(p))).unwrap_or_else(|| "N/A".to_string()),
                 spread,
                 elapsed
             )
@@ -2,12 +2,16 @@ use crate::tui::App;
 use crossterm::event::KeyEvent;
 use ratatui::prelude::*;
 
+pub mod datasets;
+pub mod gamma;
 pub mod markets;
 pub mod orders;
 pub mod portfolio;
 pub mod stream;
 pub mod tokens;
 
+pub use datasets::DatasetsPage;
+pub use gamma::GammaPage;
 pub use markets::MarketsPage;
 pub use orders::OrdersPage;
 pub use portfolio::PortfolioPage;
@@ -17,4 +21,41 @@ pub use tokens::TokensPage;
 pub trait Page {
     fn render(&self, frame: &mut Frame, area: Rect, app: &App);
     fn handle_key(&mut self, key: KeyEvent, app: &mut App) -> bool;
+
+    /// True while the page is taking text input, so global keys (q, Tab, 1-7) go to the page
+    fn captures_input(&self) -> bool {
+        false
+    }
+}
+
+/// Data a page loads off the UI thread the first time it is shown
+pub enum Loadable<T> {
+    Idle,
+    Loading,
+    Ready(T),
+    Failed(String),
+}
+
+/// Shared slot a background load writes into and the page renders from
+pub type LoadSlot<T> = std::sync::Arc<std::sync::Mutex<Loadable<T>>>;
+
+/// Run a blocking loader on the blocking pool and store its result in `slot`
+pub fn spawn_load<T, F>(slot: &LoadSlot<T>, load: F)
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    if let Ok(mut state) = slot.lock() {
+        *state = Loadable::Loading;
+    }
+    let slot = slot.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = match load() {
+            Ok(value) => Loadable::Ready(value),
+            Err(e) => Loadable::Failed(e.to_string()),
+        };
+        if let Ok(mut state) = slot.lock() {
+            *state = result;
+        }
+    });
 }
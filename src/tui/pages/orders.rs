@@ -1,4 +1,5 @@
-use crate::core::portfolio::types::{ActiveOrder, OrderSide, OrderStatus, OrderType};
+use crate::core::portfolio::{ActiveOrder, OrderSide, OrderStatus, OrderType};
+use crate::number_format;
 use crate::tui::App;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -89,8 +90,8 @@ impl OrdersPage {
                     Row::new(vec![
                         Cell::from(&order.order_id[..8]), // Show first 8 chars
                         Cell::from(side_str),
-                        Cell::from(format!("{:.2}", order.remaining_size)),
-                        Cell::from(format!("${:.4}", order.price)),
+                        Cell::from(number_format::size(order.remaining_size)),
+                        Cell::from(format!("${}", number_format::price(order.price))),
                         Cell::from(status_str),
                     ])
                     .style(style)
@@ -139,16 +140,16 @@ impl OrdersPage {
                 };
 
                 format!(
-                    "Order Details:\n\nOrder ID: {}\nToken: {}\nOutcome: {}\nSide: {}\nType: {}\nSize: {:.2}\nPrice: ${:.4}\nFilled: {:.2}\nRemaining: {:.2}\nStatus: {}\nCreated: {}\nPost Only: {}\nReduce Only: {}\n\nControls:\nC - Cancel Order\nM - Modify Order\nR - Refresh",
+                    "Order Details:\n\nOrder ID: {}\nToken: {}\nOutcome: {}\nSide: {}\nType: {}\nSize: {}\nPrice: ${}\nFilled: {}\nRemaining: {}\nStatus: {}\nCreated: {}\nPost Only: {}\nReduce Only: {}\n\nControls:\nC - Cancel Order\nM - Modify Order\nR - Refresh",
                     order.order_id,
                     &order.token_id[..16],
                     order.outcome,
                     side_str,
                     order_type_str,
-                    order.size,
-                    order.price,
-                    order.filled_size,
-                    order.remaining_size,
+                    number_format::size(order.size),
+                    number_format::price(order.price),
+                    number_format::size(order.filled_size),
+                    number_format::size(order.remaining_size),
                     status_str,
                     order.created_at.format("%Y-%m-%d %H:%M:%S"),
                     order.post_only,
@@ -1,4 +1,5 @@
-use crate::core::portfolio::types::{Position, PositionSide, PositionStatus};
+use crate::core::portfolio::{Position, PositionSide, PositionStatus};
+use crate::number_format;
 use crate::tui::App;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -21,17 +22,17 @@ impl PortfolioPage {
         let content = if let Ok(stats_lock) = app.portfolio_manager.stats().try_read() {
             let stats = &*stats_lock;
             format!(
-                "Portfolio Summary:\n\nTotal Balance: ${:.2}\nAvailable: ${:.2}\nLocked: ${:.2}\n\nPositions: {} total, {} open\nRealized P&L: ${:.2}\nUnrealized P&L: ${:.2}\nTotal P&L: ${:.2}\n\nFees Paid: ${:.2}\nWin Rate: {}\nLast Updated: {}",
-                stats.total_balance,
-                stats.available_balance,
-                stats.locked_balance,
+                "Portfolio Summary:\n\nTotal Balance: {}\nAvailable: {}\nLocked: {}\n\nPositions: {} total, {} open\nRealized P&L: {}\nUnrealized P&L: {}\nTotal P&L: {}\n\nFees Paid: {}\nWin Rate: {}\nLast Updated: {}",
+                number_format::usd(stats.total_balance),
+                number_format::usd(stats.available_balance),
+                number_format::usd(stats.locked_balance),
                 stats.total_positions,
                 stats.open_positions,
-                stats.total_realized_pnl,
-                stats.total_unrealized_pnl,
-                stats.total_pnl(),
-                stats.total_fees_paid,
-                stats.win_rate.map(|w| format!("{:.1}%", w)).unwrap_or_else(|| "N/A".to_string()),
+                number_format::usd(stats.total_realized_pnl),
+                number_format::usd(stats.total_unrealized_pnl),
+                number_format::usd(stats.total_pnl()),
+                number_format::usd(stats.total_fees_paid),
+                stats.win_rate.map(number_format::percent).unwrap_or_else(|| "N/A".to_string()),
                 stats.last_updated.format("%Y-%m-%d %H:%M:%S")
             )
         } else {
@@ -88,35 +89,26 @@ impl PortfolioPage {
 
                 let current_price_str = position
                     .current_price
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "N/A".to_string());
 
                 let total_pnl = position.total_pnl();
                 let pnl_pct = position.pnl_percentage().unwrap_or_default();
 
-                let pnl_style = if total_pnl >= rust_decimal::Decimal::ZERO {
-                    Style::default().fg(Color::Green)
-                } else {
-                    Style::default().fg(Color::Red)
-                };
+                let pnl_style = Style::default().fg(
+                    crate::theme::palette()
+                        .pnl(total_pnl >= rust_decimal::Decimal::ZERO)
+                        .into(),
+                );
 
-                let pnl_str = if total_pnl >= rust_decimal::Decimal::ZERO {
-                    format!("+${:.2}", total_pnl)
-                } else {
-                    format!("-${:.2}", total_pnl.abs())
-                };
-
-                let pnl_pct_str = if pnl_pct >= rust_decimal::Decimal::ZERO {
-                    format!("+{:.1}%", pnl_pct)
-                } else {
-                    format!("{:.1}%", pnl_pct)
-                };
+                let pnl_str = number_format::signed_usd(total_pnl);
+                let pnl_pct_str = number_format::signed_percent(pnl_pct);
 
                 Row::new(vec![
                     Cell::from(&position.token_id[..12]),
                     Cell::from(side_str),
-                    Cell::from(format!("{:.2}", position.size)),
-                    Cell::from(format!("${:.4}", position.average_price)),
+                    Cell::from(number_format::size(position.size)),
+                    Cell::from(format!("${}", number_format::price(position.average_price))),
                     Cell::from(current_price_str),
                     Cell::from(pnl_str).style(pnl_style),
                     Cell::from(pnl_pct_str).style(pnl_style),
@@ -169,24 +161,24 @@ impl PortfolioPage {
                 let cost_basis = position.size * position.average_price;
 
                 format!(
-                    "Position Details:\n\nToken: {}\nOutcome: {}\nMarket: {}\nSide: {}\nStatus: {}\nSize: {:.2}\nAverage Price: ${:.4}\nCurrent Price: {}\nCost Basis: ${:.2}\nMarket Value: ${:.2}\n\nP&L Analysis:\n• Realized P&L: ${:.2}\n• Unrealized P&L: {}\n• Total P&L: ${:.2}\n• P&L %: {}\n\nTiming:\n• Opened: {}\n• Updated: {}\n• Fees Paid: ${:.2}\n\nControls:\nS - Sell Position\nT - Trade More\nI - Position Info",
+                    "Position Details:\n\nToken: {}\nOutcome: {}\nMarket: {}\nSide: {}\nStatus: {}\nSize: {}\nAverage Price: ${}\nCurrent Price: {}\nCost Basis: {}\nMarket Value: {}\n\nP&L Analysis:\n• Realized P&L: {}\n• Unrealized P&L: {}\n• Total P&L: {}\n• P&L %: {}\n\nTiming:\n• Opened: {}\n• Updated: {}\n• Fees Paid: {}\n\nControls:\nS - Sell Position\nT - Trade More\nI - Position Info",
                     &position.token_id[..16],
                     position.outcome,
                     position.market_question.as_deref().unwrap_or("N/A"),
                     side_str,
                     status_str,
-                    position.size,
-                    position.average_price,
-                    position.current_price.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string()),
-                    cost_basis,
-                    market_value,
-                    position.realized_pnl,
-                    position.unrealized_pnl.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "N/A".to_string()),
-                    position.total_pnl(),
-                    position.pnl_percentage().map(|p| format!("{:.2}%", p)).unwrap_or_else(|| "N/A".to_string()),
+                    number_format::size(position.size),
+                    number_format::price(position.average_price),
+                    position.current_price.map(|p| format!("${}", number_format::price(p))).unwrap_or_else(|| "N/A".to_string()),
+                    number_format::usd(cost_basis),
+                    number_format::usd(market_value),
+                    number_format::usd(position.realized_pnl),
+                    position.unrealized_pnl.map(number_format::usd).unwrap_or_else(|| "N/A".to_string()),
+                    number_format::usd(position.total_pnl()),
+                    position.pnl_percentage().map(number_format::percent).unwrap_or_else(|| "N/A".to_string()),
                     position.opened_at.format("%Y-%m-%d %H:%M:%S"),
                     position.updated_at.format("%Y-%m-%d %H:%M:%S"),
-                    position.fees_paid
+                    number_format::usd(position.fees_paid)
                 )
             } else {
                 "No position selected".to_string()
@@ -1,4 +1,9 @@
-use crate::tui::widgets::order_book::render_order_book;
+use crate::core::execution::cross_rate::CrossRate;
+use crate::tui::widgets::cross_rate::render_cross_rates;
+use crate::tui::widgets::synthetic::render_synthetics;
+use crate::core::execution::book_view::DepthDisplay;
+use crate::tui::widgets::order_book::{render_depth_chart, render_order_book};
+use crate::number_format;
 use crate::tui::{App, AppState};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -10,6 +15,28 @@ use ratatui::{
 enum StreamTab {
     ActiveTokens,
     RecentEvents,
+    CrossRates,
+    Synthetics,
+}
+
+impl StreamTab {
+    fn next(self) -> Self {
+        match self {
+            StreamTab::ActiveTokens => StreamTab::RecentEvents,
+            StreamTab::RecentEvents => StreamTab::CrossRates,
+            StreamTab::CrossRates => StreamTab::Synthetics,
+            StreamTab::Synthetics => StreamTab::Synthetics,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            StreamTab::ActiveTokens => StreamTab::ActiveTokens,
+            StreamTab::RecentEvents => StreamTab::ActiveTokens,
+            StreamTab::CrossRates => StreamTab::RecentEvents,
+            StreamTab::Synthetics => StreamTab::CrossRates,
+        }
+    }
 }
 
 pub struct StreamPage {
@@ -31,10 +58,12 @@ impl StreamPage {
             .split(area);
 
         // Render tabs
-        let tab_titles = vec!["Active Tokens", "Recent Events"];
+        let tab_titles = vec!["Active Tokens", "Recent Events", "Cross Rates", "Synthetics"];
         let selected_tab = match self.current_tab {
             StreamTab::ActiveTokens => 0,
             StreamTab::RecentEvents => 1,
+            StreamTab::CrossRates => 2,
+            StreamTab::Synthetics => 3,
         };
         let tabs = Tabs::new(tab_titles)
             .block(Block::default().borders(Borders::ALL).title("Stream View"))
@@ -74,6 +103,18 @@ impl StreamPage {
                 self.render_full_event_log(frame, chunks[0], app);
                 self.render_metrics(frame, chunks[1], app);
             }
+            StreamTab::CrossRates => {
+                let books: Vec<_> = app
+                    .streamer
+                    .get_all_order_books()
+                    .into_iter()
+                    .map(|entry| entry.order_book)
+                    .collect();
+                render_cross_rates(frame, main_chunks[1], &CrossRate::from_books(&books));
+            }
+            StreamTab::Synthetics => {
+                render_synthetics(frame, main_chunks[1], &app.synthetics);
+            }
         }
     }
 
@@ -95,12 +136,12 @@ impl StreamPage {
 
                 let bid_str = activity
                     .last_bid
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 let ask_str = activity
                     .last_ask
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 let elapsed = activity
@@ -166,6 +207,15 @@ impl StreamPage {
             rate,
             app.get_all_active_tokens().len()
         );
+        let content = match app.streamer.replay() {
+            Some(replay) if replay.is_finished() => format!("{}\nReplay: finished", content),
+            Some(replay) => format!(
+                "{}\nReplay: at {}",
+                content,
+                replay.position().with_timezone(&chrono::Local).format("%H:%M:%S")
+            ),
+            None => content,
+        };
 
         let paragraph =
             Paragraph::new(content).block(Block::default().borders(Borders::ALL).title("Metrics"));
@@ -240,14 +290,25 @@ impl StreamPage {
             .split(vertical_chunks[1]);
 
         // Left side: Order book
-        render_order_book(
-            frame,
-            chunks[0],
-            &app.current_bids,
-            &app.current_asks,
-            token_id,
-            app.orderbook_scroll,
-        );
+        match app.orderbook_display {
+            DepthDisplay::Ladder => render_order_book(
+                frame,
+                chunks[0],
+                &app.current_bids,
+                &app.current_asks,
+                token_id,
+                app.orderbook_scroll,
+                app.orderbook_aggregation,
+            ),
+            DepthDisplay::Chart => render_depth_chart(
+                frame,
+                chunks[0],
+                &app.current_bids,
+                &app.current_asks,
+                token_id,
+                app.orderbook_aggregation,
+            ),
+        }
 
         // Right side: Token info and controls
         self.render_token_info(frame, chunks[1], app, token_id);
@@ -258,11 +319,13 @@ impl StreamPage {
 
         // Show full token ID on separate line for easy copying
         let content = format!(
-            "Token ID:\n{}\n\nEvents Received: {}\n\nBids: {}\nAsks: {}\n\nControls:\n↑↓ - Scroll\nSpace - Center\nc - Copy Token ID\nBackspace - Back",
+            "Token ID:\n{}\n\nEvents Received: {}\n\nBids: {}\nAsks: {}\nBins: {}\nView: {}\n\nControls:\n↑↓ - Scroll\nSpace - Center\na - Cycle price bins\nd - Ladder / depth chart\nc - Copy Token ID\nBackspace - Back",
             token_id,
             event_count,
             app.current_bids.len(),
-            app.current_asks.len()
+            app.current_asks.len(),
+            app.orderbook_aggregation.label(),
+            app.orderbook_display.label()
         );
 
         let paragraph = Paragraph::new(content).block(
@@ -291,11 +354,11 @@ impl super::Page for StreamPage {
         match &app.state {
             AppState::Overview => match key.code {
                 KeyCode::Left => {
-                    self.current_tab = StreamTab::ActiveTokens;
+                    self.current_tab = self.current_tab.previous();
                     true
                 }
                 KeyCode::Right => {
-                    self.current_tab = StreamTab::RecentEvents;
+                    self.current_tab = self.current_tab.next();
                     // Bound the scroll position to valid range when switching tabs
                     let estimated_display_height = 25;
                     let max_scroll = if app.event_log.len() > estimated_display_height {
@@ -310,6 +373,7 @@ impl super::Page for StreamPage {
                     match self.current_tab {
                         StreamTab::ActiveTokens => app.select_previous(),
                         StreamTab::RecentEvents => app.scroll_event_log_up(),
+                        StreamTab::CrossRates | StreamTab::Synthetics => {}
                     }
                     true
                 }
@@ -317,6 +381,7 @@ impl super::Page for StreamPage {
                     match self.current_tab {
                         StreamTab::ActiveTokens => app.select_next(),
                         StreamTab::RecentEvents => app.scroll_event_log_down(),
+                        StreamTab::CrossRates | StreamTab::Synthetics => {}
                     }
                     true
                 }
@@ -372,6 +437,14 @@ impl super::Page for StreamPage {
                         app.reset_orderbook_scroll();
                         true
                     }
+                    KeyCode::Char('a') | KeyCode::Char('A') => {
+                        app.cycle_orderbook_aggregation();
+                        true
+                    }
+                    KeyCode::Char('d') | KeyCode::Char('D') => {
+                        app.toggle_orderbook_display();
+                        true
+                    }
                     KeyCode::Char('c') | KeyCode::Char('C') => {
                         // Copy token ID to clipboard
                         let _ = app.copy_token_to_clipboard(&token_id_clone);
@@ -1,3 +1,4 @@
+use crate::number_format;
 use crate::tui::App;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -40,12 +41,12 @@ impl TokensPage {
 
                 let bid_str = activity
                     .last_bid
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 let ask_str = activity
                     .last_ask
-                    .map(|p| format!("${:.4}", p))
+                    .map(|p| format!("${}", number_format::price(p)))
                     .unwrap_or_else(|| "-".to_string());
 
                 Row::new(vec![
@@ -53,7 +54,7 @@ impl TokensPage {
                     Cell::from(activity.event_count.to_string()),
                     Cell::from(bid_str),
                     Cell::from(ask_str),
-                    Cell::from(format!("${:.2}", activity.total_volume)),
+                    Cell::from(number_format::usd(activity.total_volume)),
                     Cell::from(activity.trade_count.to_string()),
                 ])
                 .style(style)
@@ -92,25 +93,24 @@ impl TokensPage {
             let spread = match (activity.last_bid, activity.last_ask) {
                 (Some(bid), Some(ask)) if ask > bid => {
                     format!(
-                        "${:.4} ({:.2}%)",
-                        ask - bid,
-                        ((ask - bid) / bid * rust_decimal::Decimal::from(100))
-                            .to_string()
-                            .parse::<f64>()
-                            .unwrap_or(0.0)
+                        "${} ({})",
+                        number_format::price(ask - bid),
+                        number_format::percent(
+                            (ask - bid) / bid * rust_decimal::Decimal::from(100)
+                        )
                     )
                 }
                 _ => "N/A".to_string(),
             };
 
             format!(
-                "Token Details:\n\nToken ID: {}\n\nActivity:\n• Events: {}\n• Trades: {}\n• Volume: ${:.2}\n\nPricing:\n• Last Bid: {}\n• Last Ask: {}\n• Spread: {}\n\nTiming:\n• Last Update: {}s ago\n\nControls:\nEnter - View in Stream\nS - Subscribe/Unsubscribe\nI - Token Info",
+                "Token Details:\n\nToken ID: {}\n\nActivity:\n• Events: {}\n• Trades: {}\n• Volume: {}\n\nPricing:\n• Last Bid: {}\n• Last Ask: {}\n• Spread: {}\n\nTiming:\n• Last Update: {}s ago\n\nControls:\nEnter - View in Stream\nS - Subscribe/Unsubscribe\nI - Token Info",
                 activity.token_id,
                 activity.event_count,
                 activity.trade_count,
-                activity.total_volume,
-                activity.last_bid.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string()),
-                activity.last_ask.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "N/A".to_string()),
+                number_format::usd(activity.total_volume),
+                activity.last_bid.map(|p| format!("${}", number_format::price(p))).unwrap_or_else(|| "N/A".to_string()),
+                activity.last_ask.map(|p| format!("${}", number_format::price(p))).unwrap_or_else(|| "N/A".to_string()),
                 spread,
                 elapsed
             )
@@ -134,10 +134,10 @@ impl TokensPage {
         let total_trades: usize = tokens.iter().map(|t| t.trade_count).sum();
 
         let content = format!(
-            "Overall Statistics:\n\nActive Tokens: {}\nTotal Events: {}\nTotal Volume: ${:.2}\nTotal Trades: {}\n\nMost Active Token:\n{}",
+            "Overall Statistics:\n\nActive Tokens: {}\nTotal Events: {}\nTotal Volume: {}\nTotal Trades: {}\n\nMost Active Token:\n{}",
             tokens.len(),
             total_events,
-            total_volume,
+            number_format::usd(total_volume),
             total_trades,
             tokens.first()
                 .map(|t| format!("{} ({} events)", &t.token_id[..16], t.event_count))
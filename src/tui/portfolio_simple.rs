@@ -1,6 +1,7 @@
 //! Simple portfolio display without full TUI (for environments where TUI doesn't work)
 
-use crate::core::portfolio::api::orders::PolymarketOrder;
+use crate::core::portfolio::PolymarketOrder;
+use crate::number_format;
 use anyhow::Result;
 use comfy_table::{presets::UTF8_FULL, ContentArrangement, Table};
 use owo_colors::OwoColorize;
@@ -40,8 +41,8 @@ pub fn display_portfolio_simple(user_address: &str, orders: &[PolymarketOrder])
     println!("{}", "─".repeat(50).bright_black());
     println!("📊 Open Orders: {}", open_orders.to_string().bright_green());
     println!(
-        "💰 Total Order Value: ${:.2} USDC",
-        total_order_value.to_string().bright_green()
+        "💰 Total Order Value: {} USDC",
+        number_format::usd(total_order_value).bright_green()
     );
     println!(
         "✅ Filled Orders: {}",
@@ -95,9 +96,9 @@ pub fn display_portfolio_simple(user_address: &str, orders: &[PolymarketOrder])
                 id_short,
                 market_short,
                 side_display,
-                format!("${:.4}", order.price),
-                format!("{:.2}", order.size_structured),
-                format!("{:.2}", filled_decimal),
+                format!("${}", number_format::price(order.price)),
+                number_format::size(order.size_structured),
+                number_format::size(filled_decimal),
                 status_display,
                 order.outcome.clone(),
             ]);
@@ -0,0 +1,266 @@
+//! Background work for the dashboard: refreshes, price history and orders
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tokio::sync::{mpsc, OnceCell};
+
+use super::keys::DashboardCommand;
+use super::{DashboardData, DashboardMessage};
+use crate::client::PolybotClient;
+use crate::core::funds::{DepositAddresses, FundsClient};
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::display::MarketLabels;
+use crate::core::portfolio::hedge::HedgePlan;
+use crate::core::portfolio::{ActiveOrder, Position, PositionSide};
+use crate::core::risk::{RiskConfig, RiskJournal};
+use crate::core::types::common::ShortId;
+use crate::data_paths::DataPaths;
+use crate::markets::gamma::{ClobTokenId, GammaClient, PriceHistoryQuery};
+
+/// Large order threshold and the journal recording confirmations
+pub(super) struct DashboardRisk {
+    pub(super) config: RiskConfig,
+    pub(super) journal: RiskJournal,
+}
+
+impl DashboardRisk {
+    pub(super) fn load(data_paths: &DataPaths) -> Self {
+        Self {
+            config: RiskConfig::load(data_paths).unwrap_or_default(),
+            journal: RiskJournal::new(data_paths),
+        }
+    }
+}
+
+/// Order client connected on the first order action, so browsing the
+/// dashboard never needs the signer; every action shares its order manager
+#[derive(Clone)]
+struct TradingClient {
+    host: String,
+    data_paths: DataPaths,
+    client: Arc<OnceCell<PolybotClient>>,
+}
+
+impl TradingClient {
+    async fn get(&self) -> Result<&PolybotClient> {
+        self.client
+            .get_or_try_init(|| PolybotClient::connect_to(&self.host, self.data_paths.clone()))
+            .await
+    }
+}
+
+/// Everything background tasks need to talk to the portfolio service and the exchange
+pub(super) struct DashboardContext {
+    handlers: Arc<PortfolioCommandHandlers>,
+    data_paths: DataPaths,
+    pub(super) risk: DashboardRisk,
+    /// Show market and token ids instead of market questions
+    raw_ids: bool,
+    trading: TradingClient,
+    /// Bridge deposit addresses, requested once: the bridge call that returns
+    /// them is a POST, while the balance and deposit status reads are safe to poll
+    deposit_addresses: Arc<OnceCell<DepositAddresses>>,
+}
+
+impl DashboardContext {
+    pub(super) fn new(
+        handlers: Arc<PortfolioCommandHandlers>,
+        host: String,
+        data_paths: DataPaths,
+        risk: DashboardRisk,
+        raw_ids: bool,
+    ) -> Self {
+        let trading = TradingClient {
+            host,
+            data_paths: data_paths.clone(),
+            client: Arc::new(OnceCell::new()),
+        };
+        Self {
+            handlers,
+            data_paths,
+            risk,
+            raw_ids,
+            trading,
+            deposit_addresses: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Start the background work a key press or message asked for
+    pub(super) fn run(&self, command: DashboardCommand, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        match command {
+            DashboardCommand::Refresh => self.refresh(tx),
+            DashboardCommand::LoadPriceHistory(token_id) => self.load_price_history(token_id, tx),
+            DashboardCommand::CancelOrder(order) => self.cancel_order(order, tx),
+            DashboardCommand::Flatten(position) => self.flatten_position(position, tx),
+            DashboardCommand::PlanHedge { market_id, positions } => self.plan_hedge(market_id, positions, tx),
+            DashboardCommand::SubmitHedge(plan) => self.submit_hedge(plan, tx),
+        }
+    }
+
+    pub(super) fn refresh(&self, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let handlers = self.handlers.clone();
+        let data_paths = (!self.raw_ids).then(|| self.data_paths.clone());
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                if let Err(e) = handlers.refresh_data().await {
+                    tracing::warn!("Portfolio refresh failed, showing cached state: {}", e);
+                }
+                let state = handlers.get_portfolio_state().await?;
+                let trades = handlers.get_trade_history(None, None).await.unwrap_or_default();
+                anyhow::Ok(DashboardData {
+                    portfolio: state,
+                    trades,
+                })
+            }
+            .await
+            .map_err(|e| e.to_string());
+            if let (Some(data_paths), Ok(data)) = (data_paths, &result) {
+                let token_ids: Vec<String> = data
+                    .portfolio
+                    .active_orders
+                    .iter()
+                    .map(|o| o.token_id.clone())
+                    .chain(data.trades.iter().map(|t| t.token_id.clone()))
+                    .collect();
+                let labels = tokio::task::spawn_blocking(move || {
+                    MarketLabels::load(&data_paths, token_ids.iter().map(String::as_str))
+                })
+                .await;
+                if let Ok(labels) = labels {
+                    let _ = tx.send(DashboardMessage::Labels(labels));
+                }
+            }
+            let _ = tx.send(DashboardMessage::Refreshed(result));
+        });
+        self.load_funding(tx);
+    }
+
+    /// On-chain wallet balance and pending bridge deposits for the Balances tab
+    fn load_funding(&self, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let wallet = self.handlers.get_address().to_string();
+        let deposit_addresses = self.deposit_addresses.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let client = FundsClient::new(None)?;
+                let addresses = deposit_addresses
+                    .get_or_try_init(|| client.deposit_addresses(&wallet))
+                    .await?;
+                client.funding_status(&wallet, addresses).await
+            }
+            .await
+            .map_err(|e| e.to_string());
+            let _ = tx.send(DashboardMessage::Funding(result));
+        });
+    }
+
+    fn load_price_history(&self, token_id: String, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let query = PriceHistoryQuery {
+                market: ClobTokenId(token_id.clone()),
+                start_ts: None,
+                end_ts: None,
+                interval: Some("1w".to_string()),
+                fidelity: Some(60),
+            };
+            let result = GammaClient::new()
+                .fetch_price_history(&query)
+                .await
+                .map(|history| history.history.into_iter().map(|p| p.price).collect())
+                .map_err(|e| e.to_string());
+            let _ = tx.send(DashboardMessage::PriceHistory { token_id, result });
+        });
+    }
+
+    /// Cancel through the order manager; only a cancel the exchange confirmed counts
+    fn cancel_order(&self, order: ActiveOrder, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let trading = self.trading.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                trading.get().await?.cancel(&order.order_id).await?.confirmed()?;
+                anyhow::Ok(format!("Cancelled order {}", ShortId(&order.order_id)))
+            }
+            .await
+            .map_err(|e| format!("Cancel failed: {}", e));
+            let _ = tx.send(DashboardMessage::ActionFinished(result));
+        });
+    }
+
+    /// Close the position at the touch: sell longs into the best bid, buy back shorts at the best ask
+    fn flatten_position(&self, position: Position, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let trading = self.trading.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let client = trading.get().await?;
+                let book = client.order_book(&position.token_id).await?;
+                let response = match position.side {
+                    PositionSide::Long => {
+                        let price = book
+                            .best_bid()
+                            .ok_or_else(|| anyhow::anyhow!("No bids to sell into"))?
+                            .price;
+                        client.sell(&position.token_id, price, position.size).await?
+                    }
+                    PositionSide::Short => {
+                        let price = book
+                            .best_ask()
+                            .ok_or_else(|| anyhow::anyhow!("No asks to buy from"))?
+                            .price;
+                        client.buy(&position.token_id, price, position.size).await?
+                    }
+                };
+                let action = match position.side {
+                    PositionSide::Long => "Selling",
+                    PositionSide::Short => "Buying",
+                };
+                let order_id = response.into_order_id()?;
+                anyhow::Ok(format!(
+                    "{} {} to flatten ({})",
+                    action,
+                    position.size,
+                    ShortId(&order_id)
+                ))
+            }
+            .await
+            .map_err(|e| format!("Flatten failed: {}", e));
+            let _ = tx.send(DashboardMessage::ActionFinished(result));
+        });
+    }
+
+    /// Price the orders that bring the market of these positions to zero exposure
+    fn plan_hedge(
+        &self,
+        market_id: String,
+        positions: Vec<Position>,
+        tx: &mpsc::UnboundedSender<DashboardMessage>,
+    ) {
+        let trading = self.trading.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let client = trading.get().await?;
+                HedgePlan::prepare(client, &market_id, &positions, Decimal::ZERO).await
+            }
+            .await
+            .map_err(|e| format!("Hedge failed: {}", e));
+            let _ = tx.send(DashboardMessage::HedgePlanned(result));
+        });
+    }
+
+    fn submit_hedge(&self, plan: HedgePlan, tx: &mpsc::UnboundedSender<DashboardMessage>) {
+        let trading = self.trading.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let result = async { plan.submit(trading.get().await?).await }
+                .await
+                .map(|order_ids| format!("Placed {} hedge order(s)", order_ids.len()))
+                .map_err(|e| format!("Hedge failed: {}", e));
+            let _ = tx.send(DashboardMessage::ActionFinished(result));
+        });
+    }
+}
@@ -0,0 +1,518 @@
+//! Key presses and background results applied to the dashboard state
+//!
+//! Handlers only change state and return the [`DashboardCommand`] to run, so
+//! the dashboard's key handling can be exercised without a terminal or network.
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::time::Instant;
+
+use super::context::DashboardRisk;
+use super::{
+    market_label, DashboardMessage, DashboardTab, DrillDown, PendingAction, PortfolioViewState,
+    StatusLine,
+};
+use crate::core::portfolio::hedge::HedgePlan;
+use crate::core::portfolio::{ActiveOrder, Position, PositionSide, PositionStatus};
+use crate::core::risk::RiskJournalEvent;
+use crate::core::types::common::{ShortId, Side};
+
+/// Background work requested by a key press or a finished task
+#[derive(Debug)]
+pub(super) enum DashboardCommand {
+    Refresh,
+    LoadPriceHistory(String),
+    CancelOrder(ActiveOrder),
+    Flatten(Position),
+    PlanHedge {
+        market_id: String,
+        positions: Vec<Position>,
+    },
+    SubmitHedge(HedgePlan),
+}
+
+impl PortfolioViewState {
+    /// Apply a background result; returns the follow-up work it needs
+    pub(super) fn handle_message(
+        &mut self,
+        message: DashboardMessage,
+        risk: &DashboardRisk,
+    ) -> Option<DashboardCommand> {
+        match message {
+            DashboardMessage::Refreshed(Ok(data)) => self.apply_refresh(data),
+            DashboardMessage::Labels(labels) => self.labels = labels,
+            DashboardMessage::Refreshed(Err(e)) => {
+                self.loading = false;
+                self.last_refresh = Some(Instant::now());
+                self.status = Some(StatusLine::error(format!("Refresh failed: {}", e)));
+            }
+            DashboardMessage::PriceHistory { token_id, result } => {
+                if let Some(drill_down) = &mut self.drill_down {
+                    if drill_down.position.token_id == token_id {
+                        drill_down.history = Some(result);
+                    }
+                }
+            }
+            DashboardMessage::ActionFinished(result) => {
+                self.busy = false;
+                self.status = Some(match result {
+                    Ok(message) => StatusLine::info(message),
+                    Err(message) => StatusLine::error(message),
+                });
+                self.loading = true;
+                return Some(DashboardCommand::Refresh);
+            }
+            DashboardMessage::HedgePlanned(Ok(plan)) => {
+                self.busy = false;
+                if plan.orders.is_empty() {
+                    self.status = Some(StatusLine::info("Market is already hedged"));
+                    return None;
+                }
+                let large = plan.orders.iter().find_map(|order| {
+                    risk.config
+                        .large_orders
+                        .check(&order.token_id, Side::Buy, order.price?, order.size)
+                });
+                self.status = None;
+                self.pending = Some(PendingAction::Hedge {
+                    plan,
+                    large,
+                    confirmed_once: false,
+                });
+            }
+            DashboardMessage::HedgePlanned(Err(e)) => {
+                self.busy = false;
+                self.status = Some(StatusLine::error(e));
+            }
+            DashboardMessage::Funding(result) => self.funding = Some(result),
+        }
+        None
+    }
+
+    /// Apply a key press; returns the background work it started, if any
+    pub(super) fn handle_key(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        risk: &DashboardRisk,
+    ) -> Option<DashboardCommand> {
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            self.should_quit = true;
+            return None;
+        }
+
+        // A pending confirmation captures all keys
+        if let Some(pending) = self.pending.take() {
+            let confirmed = matches!(code, KeyCode::Char('y') | KeyCode::Char('Y'));
+            return self.resolve_pending(pending, confirmed, risk);
+        }
+
+        match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Esc if self.drill_down.is_some() => self.drill_down = None,
+            KeyCode::Esc => self.should_quit = true,
+            KeyCode::Tab | KeyCode::Right => self.next_tab(),
+            KeyCode::BackTab | KeyCode::Left => self.previous_tab(),
+            KeyCode::Char(c @ '1'..='4') => {
+                let index = c as usize - '1' as usize;
+                self.select_tab(DashboardTab::ALL[index]);
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Char('s') => self.cycle_sort(),
+            KeyCode::Char('S') => self.reverse_sort(),
+            KeyCode::Char('r') if !self.loading => {
+                self.loading = true;
+                return Some(DashboardCommand::Refresh);
+            }
+            KeyCode::Enter if self.tab == DashboardTab::Positions => {
+                let position = self.selected_position()?;
+                let token_id = position.token_id.clone();
+                self.drill_down = Some(DrillDown {
+                    position,
+                    history: None,
+                });
+                return Some(DashboardCommand::LoadPriceHistory(token_id));
+            }
+            KeyCode::Char('c') | KeyCode::Delete if self.tab == DashboardTab::Orders && !self.busy => {
+                if let Some(order) = self.selected_order() {
+                    self.pending = Some(PendingAction::CancelOrder(order));
+                }
+            }
+            KeyCode::Char('f') if self.tab == DashboardTab::Positions && !self.busy => {
+                match self.focused_position() {
+                    Some(position) if position.status == PositionStatus::Open && !position.size.is_zero() => {
+                        let side = match position.side {
+                            PositionSide::Long => Side::Sell,
+                            PositionSide::Short => Side::Buy,
+                        };
+                        // The fill price is only known once the book is fetched; estimate with the mark
+                        let estimate = position.current_price.unwrap_or(position.average_price);
+                        let large = risk
+                            .config
+                            .large_orders
+                            .check(&position.token_id, side, estimate, position.size);
+                        self.pending = Some(PendingAction::FlattenPosition {
+                            position,
+                            large,
+                            confirmed_once: false,
+                        });
+                    }
+                    Some(_) => self.status = Some(StatusLine::error("Position is already closed")),
+                    None => {}
+                }
+            }
+            KeyCode::Char('h') if self.tab == DashboardTab::Positions && !self.busy => {
+                let position = self.focused_position()?;
+                // Every position in the market counts, not just the selected token
+                let positions: Vec<Position> = self
+                    .state
+                    .as_ref()
+                    .map(|s| {
+                        s.positions
+                            .iter()
+                            .filter(|p| p.market_id == position.market_id)
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.busy = true;
+                self.status = Some(StatusLine::info(format!(
+                    "Planning hedge for {}...",
+                    market_label(&position)
+                )));
+                return Some(DashboardCommand::PlanHedge {
+                    market_id: position.market_id,
+                    positions,
+                });
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// The drilled-down position, else the selected row
+    fn focused_position(&self) -> Option<Position> {
+        self.drill_down
+            .as_ref()
+            .map(|d| d.position.clone())
+            .or_else(|| self.selected_position())
+    }
+
+    fn resolve_pending(
+        &mut self,
+        pending: PendingAction,
+        confirmed: bool,
+        risk: &DashboardRisk,
+    ) -> Option<DashboardCommand> {
+        match pending {
+            PendingAction::CancelOrder(order) if confirmed => {
+                self.busy = true;
+                self.status = Some(StatusLine::info(format!(
+                    "Cancelling {}...",
+                    ShortId(&order.order_id)
+                )));
+                Some(DashboardCommand::CancelOrder(order))
+            }
+            PendingAction::CancelOrder(_) => {
+                self.status = Some(StatusLine::info("Cancel aborted"));
+                None
+            }
+            PendingAction::FlattenPosition {
+                position,
+                large: Some(large),
+                confirmed_once: false,
+            } if confirmed => {
+                // Large flattens ask twice, matching the CLI and GUI order tickets
+                self.pending = Some(PendingAction::FlattenPosition {
+                    position,
+                    large: Some(large),
+                    confirmed_once: true,
+                });
+                None
+            }
+            PendingAction::FlattenPosition { position, large, .. } => {
+                if let Some(large) = &large {
+                    let event = if confirmed {
+                        RiskJournalEvent::LargeOrderConfirmed
+                    } else {
+                        RiskJournalEvent::LargeOrderDeclined
+                    };
+                    risk.journal.record(&large.journal_entry(event, "tui"));
+                }
+                if !confirmed {
+                    self.status = Some(StatusLine::info("Flatten aborted"));
+                    return None;
+                }
+                self.busy = true;
+                self.status = Some(StatusLine::info(format!(
+                    "Flattening {}...",
+                    market_label(&position)
+                )));
+                Some(DashboardCommand::Flatten(position))
+            }
+            PendingAction::Hedge {
+                plan,
+                large: Some(large),
+                confirmed_once: false,
+            } if confirmed => {
+                self.pending = Some(PendingAction::Hedge {
+                    plan,
+                    large: Some(large),
+                    confirmed_once: true,
+                });
+                None
+            }
+            PendingAction::Hedge { plan, large, .. } => {
+                if let Some(large) = &large {
+                    let event = if confirmed {
+                        RiskJournalEvent::LargeOrderConfirmed
+                    } else {
+                        RiskJournalEvent::LargeOrderDeclined
+                    };
+                    risk.journal.record(&large.journal_entry(event, "tui"));
+                }
+                if !confirmed {
+                    self.status = Some(StatusLine::info("Hedge aborted"));
+                    return None;
+                }
+                self.busy = true;
+                self.status = Some(StatusLine::info("Placing hedge..."));
+                Some(DashboardCommand::SubmitHedge(plan))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::portfolio_view::DashboardData;
+    use crate::core::portfolio::api::PortfolioState;
+    use crate::core::portfolio::{
+        AccountBalances, OrderSide, OrderStatus, OrderType, PortfolioStats, TimeInForce,
+    };
+    use crate::core::risk::{LargeOrderConfig, RiskConfig, RiskJournal};
+    use crate::data_paths::DataPaths;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn position(token_id: &str, size: Decimal, status: PositionStatus) -> Position {
+        Position::test(token_id, size)
+            .with_market("market-1")
+            .with_status(status)
+            .with_current_price(dec!(0.5))
+    }
+
+    fn order(order_id: &str) -> ActiveOrder {
+        ActiveOrder {
+            order_id: order_id.to_string(),
+            market_id: "market-1".to_string(),
+            token_id: "token-a".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            price: dec!(0.4),
+            size: dec!(10),
+            filled_size: Decimal::ZERO,
+            remaining_size: dec!(10),
+            status: OrderStatus::Open,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            time_in_force: TimeInForce::GTC,
+            post_only: false,
+            reduce_only: false,
+        }
+    }
+
+    fn portfolio(positions: Vec<Position>, active_orders: Vec<ActiveOrder>) -> PortfolioState {
+        PortfolioState {
+            positions,
+            active_orders,
+            stats: PortfolioStats {
+                total_balance: Decimal::ZERO,
+                available_balance: Decimal::ZERO,
+                locked_balance: Decimal::ZERO,
+                total_positions: 0,
+                open_positions: 0,
+                total_realized_pnl: Decimal::ZERO,
+                total_unrealized_pnl: Decimal::ZERO,
+                total_fees_paid: Decimal::ZERO,
+                win_rate: None,
+                average_win: None,
+                average_loss: None,
+                sharpe_ratio: None,
+                last_updated: Utc::now(),
+            },
+            balances: AccountBalances::default(),
+            last_updated: Utc::now(),
+            is_synced: true,
+        }
+    }
+
+    fn dashboard() -> PortfolioViewState {
+        let mut state = PortfolioViewState::new("0xabc".to_string(), None, None);
+        state.apply_refresh(DashboardData {
+            portfolio: portfolio(
+                vec![
+                    position("token-a", dec!(10), PositionStatus::Open),
+                    position("token-b", dec!(2000), PositionStatus::Open),
+                    position("token-c", dec!(5), PositionStatus::Closed),
+                ],
+                vec![order("order-1"), order("order-2")],
+            ),
+            trades: Vec::new(),
+        });
+        state
+    }
+
+    fn risk(dir: &tempfile::TempDir) -> DashboardRisk {
+        DashboardRisk {
+            config: RiskConfig {
+                large_orders: LargeOrderConfig {
+                    enabled: true,
+                    notional_threshold: dec!(100),
+                },
+                ..RiskConfig::default()
+            },
+            journal: RiskJournal::new(&DataPaths::new(dir.path())),
+        }
+    }
+
+    fn press(state: &mut PortfolioViewState, risk: &DashboardRisk, code: KeyCode) -> Option<DashboardCommand> {
+        state.handle_key(code, KeyModifiers::NONE, risk)
+    }
+
+    #[test]
+    fn keys_switch_tabs_move_and_sort() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+
+        assert!(press(&mut state, &risk, KeyCode::Tab).is_none());
+        assert_eq!(state.tab, DashboardTab::Orders);
+        press(&mut state, &risk, KeyCode::BackTab);
+        assert_eq!(state.tab, DashboardTab::Positions);
+        press(&mut state, &risk, KeyCode::Char('4'));
+        assert_eq!(state.tab, DashboardTab::Balances);
+        press(&mut state, &risk, KeyCode::Char('1'));
+
+        assert_eq!(state.selected(), Some(0));
+        press(&mut state, &risk, KeyCode::Char('j'));
+        press(&mut state, &risk, KeyCode::PageDown);
+        assert_eq!(state.selected(), Some(2));
+        press(&mut state, &risk, KeyCode::Char('k'));
+        assert_eq!(state.selected(), Some(1));
+
+        press(&mut state, &risk, KeyCode::Char('s'));
+        press(&mut state, &risk, KeyCode::Char('S'));
+        let sort = state.sort[DashboardTab::Positions.index()];
+        assert_eq!(sort.column, 1);
+        assert!(sort.descending);
+        assert_eq!(state.positions()[0].token_id, "token-b");
+    }
+
+    #[test]
+    fn cancel_needs_a_yes() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+        press(&mut state, &risk, KeyCode::Char('2'));
+
+        press(&mut state, &risk, KeyCode::Char('c'));
+        assert!(matches!(state.pending, Some(PendingAction::CancelOrder(_))));
+        assert!(press(&mut state, &risk, KeyCode::Char('n')).is_none());
+        assert!(state.pending.is_none());
+        assert_eq!(state.status, Some(StatusLine::info("Cancel aborted")));
+
+        press(&mut state, &risk, KeyCode::Char('c'));
+        let command = press(&mut state, &risk, KeyCode::Char('y'));
+        assert!(matches!(command, Some(DashboardCommand::CancelOrder(order)) if order.order_id == "order-1"));
+        assert!(state.busy);
+
+        // No second cancel while the first is in flight
+        press(&mut state, &risk, KeyCode::Char('c'));
+        assert!(state.pending.is_none());
+    }
+
+    #[test]
+    fn large_flatten_asks_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+        press(&mut state, &risk, KeyCode::Char('s'));
+        press(&mut state, &risk, KeyCode::Char('S'));
+
+        press(&mut state, &risk, KeyCode::Char('f'));
+        assert!(matches!(
+            &state.pending,
+            Some(PendingAction::FlattenPosition { position, large: Some(_), confirmed_once: false })
+                if position.token_id == "token-b"
+        ));
+        assert!(press(&mut state, &risk, KeyCode::Char('y')).is_none());
+        assert!(matches!(
+            state.pending,
+            Some(PendingAction::FlattenPosition { confirmed_once: true, .. })
+        ));
+        let command = press(&mut state, &risk, KeyCode::Char('Y'));
+        assert!(matches!(command, Some(DashboardCommand::Flatten(position)) if position.token_id == "token-b"));
+        assert!(state.busy);
+    }
+
+    #[test]
+    fn flatten_skips_closed_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+        press(&mut state, &risk, KeyCode::PageDown);
+
+        assert!(press(&mut state, &risk, KeyCode::Char('f')).is_none());
+        assert!(state.pending.is_none());
+        assert_eq!(state.status, Some(StatusLine::error("Position is already closed")));
+    }
+
+    #[test]
+    fn drill_down_loads_history_and_escape_backs_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+
+        let command = press(&mut state, &risk, KeyCode::Enter);
+        assert!(matches!(command, Some(DashboardCommand::LoadPriceHistory(token)) if token == "token-a"));
+        assert!(state.drill_down.is_some());
+
+        press(&mut state, &risk, KeyCode::Esc);
+        assert!(state.drill_down.is_none());
+        assert!(!state.should_quit);
+        press(&mut state, &risk, KeyCode::Esc);
+        assert!(state.should_quit);
+    }
+
+    #[test]
+    fn refresh_waits_for_the_previous_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+
+        assert!(matches!(press(&mut state, &risk, KeyCode::Char('r')), Some(DashboardCommand::Refresh)));
+        assert!(press(&mut state, &risk, KeyCode::Char('r')).is_none());
+
+        let command = state.handle_message(DashboardMessage::ActionFinished(Err("rejected".to_string())), &risk);
+        assert!(matches!(command, Some(DashboardCommand::Refresh)));
+        assert_eq!(state.status, Some(StatusLine::error("rejected")));
+    }
+
+    #[test]
+    fn ctrl_c_quits_even_while_confirming() {
+        let dir = tempfile::tempdir().unwrap();
+        let risk = risk(&dir);
+        let mut state = dashboard();
+        press(&mut state, &risk, KeyCode::Char('f'));
+        assert!(state.pending.is_some());
+
+        state.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL, &risk);
+        assert!(state.should_quit);
+    }
+}
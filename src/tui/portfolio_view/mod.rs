@@ -0,0 +1,518 @@
+//! Interactive portfolio dashboard
+//!
+//! Tabs for positions, open orders, trades and balances backed by the portfolio
+//! service. Every table can be sorted, positions can be drilled into for a mini
+//! price chart, and orders or positions can be cancelled, flattened or hedged
+//! from the keyboard after a y/n prompt.
+
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, widgets::TableState, Terminal};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::core::funds::FundingStatus;
+use crate::core::portfolio::api::PortfolioState;
+use crate::core::portfolio::cli::PortfolioCommandHandlers;
+use crate::core::portfolio::display::MarketLabels;
+use crate::core::portfolio::hedge::HedgePlan;
+use crate::core::portfolio::{ActiveOrder, OrderSide, Position, TradeExecution};
+use crate::core::risk::LargeOrder;
+use crate::data_paths::DataPaths;
+use crate::theme;
+
+mod context;
+mod keys;
+mod render;
+
+use context::{DashboardContext, DashboardRisk};
+
+/// How often the dashboard re-syncs with the portfolio service
+const AUTO_REFRESH: Duration = Duration::from_secs(30);
+
+/// Dashboard tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardTab {
+    Positions,
+    Orders,
+    Trades,
+    Balances,
+}
+
+impl DashboardTab {
+    const ALL: [DashboardTab; 4] = [
+        DashboardTab::Positions,
+        DashboardTab::Orders,
+        DashboardTab::Trades,
+        DashboardTab::Balances,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|t| *t == self).unwrap_or(0)
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            DashboardTab::Positions => "Positions",
+            DashboardTab::Orders => "Open Orders",
+            DashboardTab::Trades => "Trades",
+            DashboardTab::Balances => "Balances",
+        }
+    }
+
+    /// Columns the tab can be sorted by
+    fn sort_columns(self) -> &'static [&'static str] {
+        match self {
+            DashboardTab::Positions => &["Market", "Size", "Avg Price", "Value", "P&L", "P&L %"],
+            DashboardTab::Orders => &["Created", "Market", "Side", "Price", "Size", "Filled"],
+            DashboardTab::Trades => &["Time", "Market", "Side", "Price", "Size", "Fee"],
+            DashboardTab::Balances => &[],
+        }
+    }
+}
+
+/// Current sort column and direction for a tab
+#[derive(Debug, Clone, Copy, Default)]
+struct SortState {
+    column: usize,
+    descending: bool,
+}
+
+/// Portfolio state and trade history from one refresh
+struct DashboardData {
+    portfolio: PortfolioState,
+    trades: Vec<TradeExecution>,
+}
+
+/// Messages from background tasks back to the UI loop
+enum DashboardMessage {
+    Refreshed(Result<DashboardData, String>),
+    /// Market questions of the refreshed orders and trades
+    Labels(MarketLabels),
+    PriceHistory {
+        token_id: String,
+        result: Result<Vec<Decimal>, String>,
+    },
+    ActionFinished(Result<String, String>),
+    HedgePlanned(Result<HedgePlan, String>),
+    Funding(Result<FundingStatus, String>),
+}
+
+/// Action waiting for a y/n answer
+#[derive(Debug, Clone)]
+enum PendingAction {
+    CancelOrder(ActiveOrder),
+    FlattenPosition {
+        position: Position,
+        /// Set when the estimated notional crosses the large order threshold
+        large: Option<LargeOrder>,
+        /// Large orders need a second 'y'
+        confirmed_once: bool,
+    },
+    /// Offsetting order(s) bringing a market to zero net exposure
+    Hedge {
+        plan: HedgePlan,
+        large: Option<LargeOrder>,
+        confirmed_once: bool,
+    },
+}
+
+/// Footer message shown in place of the key help
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StatusLine {
+    message: String,
+    is_error: bool,
+}
+
+impl StatusLine {
+    fn info(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            is_error: false,
+        }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            is_error: true,
+        }
+    }
+}
+
+/// Position drill-down with its price history
+struct DrillDown {
+    position: Position,
+    history: Option<Result<Vec<Decimal>, String>>,
+}
+
+/// Dashboard state
+pub struct PortfolioViewState {
+    pub user_address: String,
+    state: Option<PortfolioState>,
+    trades: Vec<TradeExecution>,
+    labels: MarketLabels,
+    funding: Option<Result<FundingStatus, String>>,
+    market_filter: Option<String>,
+    asset_filter: Option<String>,
+    tab: DashboardTab,
+    table_states: [TableState; 4],
+    sort: [SortState; 4],
+    drill_down: Option<DrillDown>,
+    pending: Option<PendingAction>,
+    status: Option<StatusLine>,
+    loading: bool,
+    busy: bool,
+    last_refresh: Option<Instant>,
+    pub should_quit: bool,
+}
+
+impl PortfolioViewState {
+    pub fn new(
+        user_address: String,
+        market_filter: Option<String>,
+        asset_filter: Option<String>,
+    ) -> Self {
+        Self {
+            user_address,
+            state: None,
+            trades: Vec::new(),
+            labels: MarketLabels::default(),
+            funding: None,
+            market_filter,
+            asset_filter,
+            tab: DashboardTab::Positions,
+            table_states: Default::default(),
+            sort: Default::default(),
+            drill_down: None,
+            pending: None,
+            status: None,
+            loading: false,
+            busy: false,
+            last_refresh: None,
+            should_quit: false,
+        }
+    }
+
+    fn matches_filters(&self, market_id: &str, token_id: &str, outcome: &str) -> bool {
+        if let Some(market) = &self.market_filter {
+            if !market_id.contains(market.as_str()) {
+                return false;
+            }
+        }
+        if let Some(asset) = &self.asset_filter {
+            if !token_id.contains(asset.as_str()) && !outcome.contains(asset.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Positions after filtering and sorting, in display order
+    fn positions(&self) -> Vec<&Position> {
+        let Some(state) = &self.state else {
+            return Vec::new();
+        };
+        let mut positions: Vec<&Position> = state
+            .positions
+            .iter()
+            .filter(|p| self.matches_filters(&p.market_id, &p.token_id, &p.outcome))
+            .collect();
+
+        let sort = self.sort[DashboardTab::Positions.index()];
+        positions.sort_by(|a, b| {
+            let ordering = match sort.column {
+                0 => market_label(a).cmp(&market_label(b)),
+                1 => a.size.cmp(&b.size),
+                2 => a.average_price.cmp(&b.average_price),
+                3 => position_value(a).cmp(&position_value(b)),
+                4 => a.total_pnl().cmp(&b.total_pnl()),
+                _ => cmp_option(a.pnl_percentage(), b.pnl_percentage()),
+            };
+            directed(ordering, sort.descending)
+        });
+        positions
+    }
+
+    /// Open orders after filtering and sorting, in display order
+    fn orders(&self) -> Vec<&ActiveOrder> {
+        let Some(state) = &self.state else {
+            return Vec::new();
+        };
+        let mut orders: Vec<&ActiveOrder> = state
+            .active_orders
+            .iter()
+            .filter(|o| self.matches_filters(&o.market_id, &o.token_id, &o.outcome))
+            .collect();
+
+        let sort = self.sort[DashboardTab::Orders.index()];
+        orders.sort_by(|a, b| {
+            let ordering = match sort.column {
+                0 => a.created_at.cmp(&b.created_at),
+                1 => a.market_id.cmp(&b.market_id),
+                2 => side_label(a.side).cmp(side_label(b.side)),
+                3 => a.price.cmp(&b.price),
+                4 => a.size.cmp(&b.size),
+                _ => a.filled_size.cmp(&b.filled_size),
+            };
+            directed(ordering, sort.descending)
+        });
+        orders
+    }
+
+    /// Trades after filtering and sorting, in display order
+    fn trades(&self) -> Vec<&TradeExecution> {
+        let mut trades: Vec<&TradeExecution> = self
+            .trades
+            .iter()
+            .filter(|t| self.matches_filters(&t.market_id, &t.token_id, ""))
+            .collect();
+
+        let sort = self.sort[DashboardTab::Trades.index()];
+        trades.sort_by(|a, b| {
+            let ordering = match sort.column {
+                0 => a.timestamp.cmp(&b.timestamp),
+                1 => a.market_id.cmp(&b.market_id),
+                2 => side_label(a.side).cmp(side_label(b.side)),
+                3 => a.price.cmp(&b.price),
+                4 => a.size.cmp(&b.size),
+                _ => a.fee.cmp(&b.fee),
+            };
+            directed(ordering, sort.descending)
+        });
+        trades
+    }
+
+    fn row_count(&self) -> usize {
+        match self.tab {
+            DashboardTab::Positions => self.positions().len(),
+            DashboardTab::Orders => self.orders().len(),
+            DashboardTab::Trades => self.trades().len(),
+            DashboardTab::Balances => 0,
+        }
+    }
+
+    fn selected(&self) -> Option<usize> {
+        self.table_states[self.tab.index()].selected()
+    }
+
+    fn select_tab(&mut self, tab: DashboardTab) {
+        self.tab = tab;
+        self.drill_down = None;
+        self.clamp_selection();
+    }
+
+    fn next_tab(&mut self) {
+        let next = (self.tab.index() + 1) % DashboardTab::ALL.len();
+        self.select_tab(DashboardTab::ALL[next]);
+    }
+
+    fn previous_tab(&mut self) {
+        let len = DashboardTab::ALL.len();
+        let previous = (self.tab.index() + len - 1) % len;
+        self.select_tab(DashboardTab::ALL[previous]);
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let rows = self.row_count();
+        if rows == 0 {
+            return;
+        }
+        let current = self.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, rows as isize - 1) as usize;
+        self.table_states[self.tab.index()].select(Some(next));
+    }
+
+    /// Keep every tab's selection inside its (possibly shrunk) row count
+    fn clamp_selection(&mut self) {
+        let counts = [
+            self.positions().len(),
+            self.orders().len(),
+            self.trades().len(),
+            0,
+        ];
+        for (state, rows) in self.table_states.iter_mut().zip(counts) {
+            match (state.selected(), rows) {
+                (_, 0) => state.select(None),
+                (None, _) => state.select(Some(0)),
+                (Some(i), rows) if i >= rows => state.select(Some(rows - 1)),
+                _ => {}
+            }
+        }
+    }
+
+    fn cycle_sort(&mut self) {
+        let columns = self.tab.sort_columns().len();
+        if columns == 0 {
+            return;
+        }
+        let sort = &mut self.sort[self.tab.index()];
+        sort.column = (sort.column + 1) % columns;
+    }
+
+    fn reverse_sort(&mut self) {
+        let sort = &mut self.sort[self.tab.index()];
+        sort.descending = !sort.descending;
+    }
+
+    fn selected_position(&self) -> Option<Position> {
+        let index = self.table_states[DashboardTab::Positions.index()].selected()?;
+        self.positions().get(index).map(|p| (*p).clone())
+    }
+
+    fn selected_order(&self) -> Option<ActiveOrder> {
+        let index = self.table_states[DashboardTab::Orders.index()].selected()?;
+        self.orders().get(index).map(|o| (*o).clone())
+    }
+
+    fn apply_refresh(&mut self, data: DashboardData) {
+        let DashboardData { portfolio: state, trades } = data;
+        // Keep the drill-down pointing at the refreshed copy of its position
+        if let Some(drill_down) = &mut self.drill_down {
+            if let Some(position) = state
+                .positions
+                .iter()
+                .find(|p| p.token_id == drill_down.position.token_id)
+            {
+                drill_down.position = position.clone();
+            }
+        }
+        self.state = Some(state);
+        self.trades = trades;
+        self.loading = false;
+        self.last_refresh = Some(Instant::now());
+        self.clamp_selection();
+    }
+}
+
+/// Run the dashboard until the user quits
+pub async fn run_portfolio_tui(
+    handlers: Arc<PortfolioCommandHandlers>,
+    host: String,
+    data_paths: DataPaths,
+    market_filter: Option<String>,
+    asset_filter: Option<String>,
+    raw_ids: bool,
+) -> Result<()> {
+    theme::init(&data_paths, None);
+    let display_paths = data_paths.clone();
+    let user_address = handlers.get_address().to_string();
+    tokio::spawn(async move {
+        crate::address_book::address_display(&display_paths)
+            .await
+            .resolve([user_address.as_str()])
+            .await;
+    });
+    let state = PortfolioViewState::new(
+        handlers.get_address().to_string(),
+        market_filter,
+        asset_filter,
+    );
+    let risk = DashboardRisk::load(&data_paths);
+    let context = DashboardContext::new(handlers, host, data_paths, risk, raw_ids);
+
+    // Restore the terminal even if drawing panics
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+        original_hook(panic);
+    }));
+
+    let result = run_tui_loop(state, context).await;
+
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+
+    result
+}
+
+async fn run_tui_loop(mut state: PortfolioViewState, context: DashboardContext) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    terminal.clear()?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    state.loading = true;
+    context.refresh(&tx);
+
+    while !state.should_quit {
+        while let Ok(message) = rx.try_recv() {
+            if let Some(command) = state.handle_message(message, &context.risk) {
+                context.run(command, &tx);
+            }
+        }
+
+        let due = state
+            .last_refresh
+            .is_some_and(|t| t.elapsed() >= AUTO_REFRESH);
+        if due && !state.loading {
+            state.loading = true;
+            context.refresh(&tx);
+        }
+
+        terminal.draw(|f| render::draw_ui(f, &mut state))?;
+
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    if let Some(command) = state.handle_key(key.code, key.modifiers, &context.risk) {
+                        context.run(command, &tx);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn market_label(position: &Position) -> String {
+    position
+        .market_question
+        .clone()
+        .unwrap_or_else(|| position.market_id.clone())
+}
+
+fn position_value(position: &Position) -> Decimal {
+    position.size * position.current_price.unwrap_or(position.average_price)
+}
+
+fn side_label(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "BUY",
+        OrderSide::Sell => "SELL",
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        let cut: String = text.chars().take(max.saturating_sub(3)).collect();
+        format!("{}...", cut)
+    } else {
+        text.to_string()
+    }
+}
+
+fn directed(ordering: Ordering, descending: bool) -> Ordering {
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+fn cmp_option(a: Option<Decimal>, b: Option<Decimal>) -> Ordering {
+    a.unwrap_or(Decimal::MIN).cmp(&b.unwrap_or(Decimal::MIN))
+}
@@ -0,0 +1,694 @@
+//! Drawing the dashboard: header, tabs, tables, drill-down and prompts
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, Tabs},
+    Frame,
+};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::{
+    market_label, position_value, side_label, truncate, DashboardTab, PendingAction,
+    PortfolioViewState, SortState,
+};
+use crate::core::portfolio::units::{self, OutcomeExposure};
+use crate::core::portfolio::{OrderSide, PositionSide};
+use crate::core::types::common::ShortId;
+use crate::number_format;
+use crate::theme;
+
+pub(super) fn draw_ui(f: &mut Frame, state: &mut PortfolioViewState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Header
+            Constraint::Length(3), // Tabs
+            Constraint::Min(10),   // Content
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    draw_header(f, chunks[0], state);
+
+    let titles: Vec<String> = DashboardTab::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| format!("{} {}", i + 1, tab.title()))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL))
+        .select(state.tab.index())
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(tabs, chunks[1]);
+
+    if state.state.is_none() {
+        let text = if state.loading {
+            "Loading portfolio..."
+        } else {
+            "Portfolio unavailable, press r to retry"
+        };
+        let loading = Paragraph::new(text)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(loading, chunks[2]);
+    } else {
+        match state.tab {
+            DashboardTab::Positions if state.drill_down.is_some() => {
+                let split = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[2]);
+                draw_positions_table(f, split[0], state);
+                draw_drill_down(f, split[1], state);
+            }
+            DashboardTab::Positions => draw_positions_table(f, chunks[2], state),
+            DashboardTab::Orders => draw_orders_table(f, chunks[2], state),
+            DashboardTab::Trades => draw_trades_table(f, chunks[2], state),
+            DashboardTab::Balances => draw_balances(f, chunks[2], state),
+        }
+    }
+
+    draw_footer(f, chunks[3], state);
+
+    if let Some(pending) = &state.pending {
+        draw_confirmation(f, pending);
+    }
+}
+
+fn draw_header(f: &mut Frame, area: Rect, state: &PortfolioViewState) {
+    let header_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let sync = match state.last_refresh {
+        _ if state.loading => Span::styled("syncing...", Style::default().fg(Color::Yellow)),
+        Some(t) => Span::styled(
+            format!("synced {}s ago", t.elapsed().as_secs()),
+            Style::default().fg(Color::DarkGray),
+        ),
+        None => Span::styled("not synced", Style::default().fg(Color::DarkGray)),
+    };
+    let user_info = vec![
+        Line::from(vec![
+            Span::raw("👤 User: "),
+            Span::styled(
+                crate::address_book::display_address(&state.user_address),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+        Line::from(vec![Span::raw("🔄 "), sync]),
+    ];
+    let header_left = Paragraph::new(user_info).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Portfolio Overview"),
+    );
+    f.render_widget(header_left, header_chunks[0]);
+
+    let summary = match &state.state {
+        Some(portfolio) => {
+            let pnl = portfolio.stats.total_pnl();
+            vec![
+                Line::from(vec![
+                    Span::raw("💰 Value: "),
+                    Span::styled(
+                        number_format::usd(portfolio.balances.total_value),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::raw("  Cash: "),
+                    Span::styled(
+                        number_format::usd(portfolio.balances.available_cash),
+                        Style::default().fg(Color::White),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("💹 P&L: "),
+                    Span::styled(format_pnl(pnl), pnl_style(pnl)),
+                    Span::raw(format!(
+                        "  📊 {} positions · {} orders",
+                        portfolio.stats.open_positions,
+                        portfolio.active_orders.len()
+                    )),
+                ]),
+            ]
+        }
+        None => vec![Line::from("—")],
+    };
+    let header_right = Paragraph::new(summary).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Account Summary"),
+    );
+    f.render_widget(header_right, header_chunks[1]);
+}
+
+/// Table title with the active sort column and direction
+fn table_title(tab: DashboardTab, sort: SortState, count: usize) -> String {
+    match tab.sort_columns().get(sort.column) {
+        Some(column) => format!(
+            " {} ({}) · sorted by {} {} ",
+            tab.title(),
+            count,
+            column,
+            if sort.descending { "↓" } else { "↑" }
+        ),
+        None => format!(" {} ({}) ", tab.title(), count),
+    }
+}
+
+fn header_row(columns: &[&str]) -> Row<'static> {
+    Row::new(
+        columns
+            .iter()
+            .map(|c| Cell::from(c.to_string()).style(Style::default().fg(Color::Gray)))
+            .collect::<Vec<_>>(),
+    )
+    .height(1)
+}
+
+fn draw_positions_table(f: &mut Frame, area: Rect, state: &mut PortfolioViewState) {
+    let tab = DashboardTab::Positions;
+    let rows: Vec<Row> = state
+        .positions()
+        .into_iter()
+        .map(|pos| {
+            let pnl = pos.total_pnl();
+            let exposure = OutcomeExposure::from_position(pos);
+            let side_cell = match pos.side {
+                PositionSide::Long => Cell::from("LONG").style(Style::default().fg(theme::palette().bid.into())),
+                PositionSide::Short => Cell::from("SHORT").style(Style::default().fg(theme::palette().ask.into())),
+            };
+            Row::new(vec![
+                Cell::from(truncate(&market_label(pos), 36)),
+                Cell::from(pos.outcome.clone()),
+                side_cell,
+                Cell::from(number_format::size(pos.size)),
+                Cell::from(format!("${}", number_format::price(pos.average_price))),
+                Cell::from(
+                    pos.current_price
+                        .map(|p| format!("${}", number_format::price(p)))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::from(units::format_usdc(exposure.value_usdc)),
+                Cell::from(format_pnl(pnl)).style(pnl_style(pnl)),
+                Cell::from(
+                    exposure
+                        .pnl_shares
+                        .map(units::format_signed_shares)
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(pnl_style(pnl)),
+                Cell::from(
+                    pos.pnl_percentage()
+                        .map(number_format::signed_percent)
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(pnl_style(pnl)),
+            ])
+        })
+        .collect();
+
+    if rows.is_empty() {
+        draw_empty(f, area, tab, "No positions. Positions will appear here once orders are filled.");
+        return;
+    }
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(11),
+        Constraint::Length(11),
+        Constraint::Length(13),
+        Constraint::Length(9),
+    ];
+    let count = rows.len();
+    let table = Table::new(rows, widths)
+        .header(header_row(&[
+            "Market", "Outcome", "Side", "Shares", "Avg", "Mark", "Value", "P&L", "P&L sh", "P&L %",
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(table_title(tab, state.sort[tab.index()], count)),
+        )
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(table, area, &mut state.table_states[tab.index()]);
+}
+
+fn draw_orders_table(f: &mut Frame, area: Rect, state: &mut PortfolioViewState) {
+    let tab = DashboardTab::Orders;
+    let labels = &state.labels;
+    let rows: Vec<Row> = state
+        .orders()
+        .into_iter()
+        .map(|order| {
+            let market = match labels.question(&order.token_id) {
+                Some(question) => truncate(question, 60),
+                None => truncate(&order.market_id, 20),
+            };
+            let outcome = labels.outcome(&order.token_id).unwrap_or(&order.outcome);
+            Row::new(vec![
+                Cell::from(order.created_at.format("%m-%d %H:%M").to_string()),
+                Cell::from(ShortId(&order.order_id).to_string()),
+                Cell::from(market),
+                Cell::from(outcome.to_string()),
+                Cell::from(side_label(order.side)).style(side_style(order.side)),
+                Cell::from(format!("${}", number_format::price(order.price))),
+                Cell::from(number_format::size(order.size)),
+                Cell::from(number_format::size(order.filled_size)),
+                Cell::from(format!("{:?}", order.status)),
+            ])
+        })
+        .collect();
+
+    if rows.is_empty() {
+        draw_empty(f, area, tab, "No open orders.");
+        return;
+    }
+
+    let widths = [
+        Constraint::Length(12),
+        Constraint::Length(14),
+        Constraint::Min(16),
+        Constraint::Length(10),
+        Constraint::Length(5),
+        Constraint::Length(9),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(16),
+    ];
+    let count = rows.len();
+    let table = Table::new(rows, widths)
+        .header(header_row(&[
+            "Created", "Order", "Market", "Outcome", "Side", "Price", "Size", "Filled", "Status",
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(table_title(tab, state.sort[tab.index()], count)),
+        )
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(table, area, &mut state.table_states[tab.index()]);
+}
+
+fn draw_trades_table(f: &mut Frame, area: Rect, state: &mut PortfolioViewState) {
+    let tab = DashboardTab::Trades;
+    let labels = &state.labels;
+    let rows: Vec<Row> = state
+        .trades()
+        .into_iter()
+        .map(|trade| {
+            let market = labels
+                .label(&trade.token_id, 60)
+                .unwrap_or_else(|| truncate(&trade.market_id, 20));
+            Row::new(vec![
+                Cell::from(trade.timestamp.format("%Y-%m-%d %H:%M").to_string()),
+                Cell::from(ShortId(&trade.trade_id).to_string()),
+                Cell::from(market),
+                Cell::from(side_label(trade.side)).style(side_style(trade.side)),
+                Cell::from(format!("${}", number_format::price(trade.price))),
+                Cell::from(number_format::size(trade.size)),
+                Cell::from(format!("${}", number_format::price(trade.fee))),
+                Cell::from(if trade.is_maker { "maker" } else { "taker" }),
+            ])
+        })
+        .collect();
+
+    if rows.is_empty() {
+        draw_empty(f, area, tab, "No trade history.");
+        return;
+    }
+
+    let widths = [
+        Constraint::Length(17),
+        Constraint::Length(14),
+        Constraint::Min(16),
+        Constraint::Length(5),
+        Constraint::Length(9),
+        Constraint::Length(10),
+        Constraint::Length(9),
+        Constraint::Length(6),
+    ];
+    let count = rows.len();
+    let table = Table::new(rows, widths)
+        .header(header_row(&[
+            "Time", "Trade", "Market", "Side", "Price", "Size", "Fee", "Role",
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(table_title(tab, state.sort[tab.index()], count)),
+        )
+        .row_highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(table, area, &mut state.table_states[tab.index()]);
+}
+
+fn draw_balances(f: &mut Frame, area: Rect, state: &PortfolioViewState) {
+    let Some(portfolio) = &state.state else {
+        return;
+    };
+    let balances = &portfolio.balances;
+    let stats = &portfolio.stats;
+    let label = Style::default().fg(Color::Gray);
+    let value = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+    let line = |name: &str, text: String, style: Style| {
+        Line::from(vec![Span::styled(format!("{:<20}", name), label), Span::styled(text, style)])
+    };
+
+    let mut lines = vec![
+        line("Total value", number_format::usd(balances.total_value), value.fg(Color::Green)),
+        line("Available cash", number_format::usd(balances.available_cash), value),
+        line("Locked in orders", number_format::usd(balances.locked_in_orders), value),
+        line("Position value", number_format::usd(balances.position_value), value),
+        Line::from(""),
+        line("Realized P&L", format_pnl(stats.total_realized_pnl), pnl_style(stats.total_realized_pnl)),
+        line(
+            "Unrealized P&L",
+            format_pnl(stats.total_unrealized_pnl),
+            pnl_style(stats.total_unrealized_pnl),
+        ),
+        line("Fees paid", number_format::usd(stats.total_fees_paid), value),
+        line(
+            "Positions",
+            format!("{} open / {} total", stats.open_positions, stats.total_positions),
+            value,
+        ),
+    ];
+    if let Some(win_rate) = stats.win_rate {
+        lines.push(line("Win rate", number_format::percent(win_rate), value));
+    }
+    lines.push(Line::from(""));
+    match &state.funding {
+        Some(Ok(funding)) => {
+            lines.push(line("Wallet USDC.e", number_format::usd(funding.wallet_balance), value));
+            let pending_style = if funding.pending_deposits.is_empty() {
+                label
+            } else {
+                value.fg(Color::Yellow)
+            };
+            lines.push(line(
+                "Pending deposits",
+                funding.pending_deposits.len().to_string(),
+                pending_style,
+            ));
+        }
+        Some(Err(e)) => lines.push(line("Wallet USDC.e", format!("unavailable ({})", e), label)),
+        None => lines.push(line("Wallet USDC.e", "loading...".to_string(), label)),
+    }
+    lines.push(line(
+        "Balances updated",
+        balances.last_updated.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        label,
+    ));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Balances "),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn draw_drill_down(f: &mut Frame, area: Rect, state: &PortfolioViewState) {
+    let Some(drill_down) = &state.drill_down else {
+        return;
+    };
+    let position = &drill_down.position;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} · {} ", truncate(&market_label(position), 50), position.outcome));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(34), Constraint::Min(20)])
+        .split(inner);
+
+    let pnl = position.total_pnl();
+    let exposure = OutcomeExposure::from_position(position);
+    let details = vec![
+        Line::from(format!("Token   {}", ShortId(&position.token_id))),
+        Line::from(format!("Size    {}", units::format_shares(exposure.shares))),
+        Line::from(format!("Avg     ${}", number_format::price(position.average_price))),
+        Line::from(format!(
+            "Mark    {}",
+            position
+                .current_price
+                .map(|p| format!("${}", number_format::price(p)))
+                .unwrap_or_else(|| "-".to_string())
+        )),
+        Line::from(format!("Value   {}", units::format_usdc(exposure.value_usdc))),
+        Line::from(vec![
+            Span::raw("P&L     "),
+            Span::styled(exposure.format_pnl(), pnl_style(pnl)),
+        ]),
+        Line::from(format!("Fees    {}", number_format::usd(position.fees_paid))),
+        Line::from(format!("Opened  {}", position.opened_at.format("%Y-%m-%d %H:%M"))),
+    ];
+    f.render_widget(Paragraph::new(details), chunks[0]);
+
+    match &drill_down.history {
+        None => {
+            let loading = Paragraph::new("Loading price history...")
+                .style(Style::default().fg(Color::Yellow));
+            f.render_widget(loading, chunks[1]);
+        }
+        Some(Err(e)) => {
+            let error = Paragraph::new(format!("Price history unavailable: {}", e))
+                .style(Style::default().fg(Color::Red));
+            f.render_widget(error, chunks[1]);
+        }
+        Some(Ok(prices)) if prices.is_empty() => {
+            f.render_widget(Paragraph::new("No price history"), chunks[1]);
+        }
+        Some(Ok(prices)) => {
+            let chart = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(chunks[1]);
+
+            // Keep the most recent points that fit the width
+            let width = chart[1].width as usize;
+            let recent = &prices[prices.len().saturating_sub(width)..];
+            let low = recent.iter().min().copied().unwrap_or_default();
+            let high = recent.iter().max().copied().unwrap_or_default();
+            let last = recent.last().copied().unwrap_or_default();
+            let first = recent.first().copied().unwrap_or_default();
+            let color = Color::from(theme::palette().pnl(last >= first));
+
+            let legend = Line::from(vec![
+                Span::styled("1w  ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("last ${}", number_format::price(last)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!(
+                        "  low ${}  high ${}",
+                        number_format::price(low),
+                        number_format::price(high)
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            f.render_widget(Paragraph::new(legend), chart[0]);
+
+            // Sparkline bars are relative, so plot the distance above the window low
+            let data: Vec<u64> = recent
+                .iter()
+                .map(|p| ((*p - low) * Decimal::from(10_000)).to_u64().unwrap_or(0) + 1)
+                .collect();
+            let sparkline = Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(color));
+            f.render_widget(sparkline, chart[1]);
+        }
+    }
+}
+
+fn draw_empty(f: &mut Frame, area: Rect, tab: DashboardTab, message: &str) {
+    let paragraph = Paragraph::new(message)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} ", tab.title())));
+    f.render_widget(paragraph, area);
+}
+
+fn draw_footer(f: &mut Frame, area: Rect, state: &PortfolioViewState) {
+    let key = Style::default().fg(Color::Yellow);
+    let line = match &state.status {
+        Some(status) => Line::from(Span::styled(
+            status.message.clone(),
+            Style::default().fg(if status.is_error { Color::Red } else { Color::Green }),
+        )),
+        None => {
+            let mut spans = vec![
+                Span::styled("↑↓/jk", key),
+                Span::raw(" move  "),
+                Span::styled("Tab/1-4", key),
+                Span::raw(" tabs  "),
+                Span::styled("s/S", key),
+                Span::raw(" sort/reverse  "),
+                Span::styled("r", key),
+                Span::raw(" refresh  "),
+            ];
+            match state.tab {
+                DashboardTab::Positions => {
+                    spans.push(Span::styled("Enter", key));
+                    spans.push(Span::raw(" chart  "));
+                    spans.push(Span::styled("f", key));
+                    spans.push(Span::raw(" flatten  "));
+                    spans.push(Span::styled("h", key));
+                    spans.push(Span::raw(" hedge  "));
+                }
+                DashboardTab::Orders => {
+                    spans.push(Span::styled("c", key));
+                    spans.push(Span::raw(" cancel  "));
+                }
+                _ => {}
+            }
+            spans.push(Span::styled("q", key));
+            spans.push(Span::raw(" quit"));
+            Line::from(spans)
+        }
+    };
+
+    let footer = Paragraph::new(line)
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, area);
+}
+
+fn draw_confirmation(f: &mut Frame, pending: &PendingAction) {
+    let (title, lines) = match pending {
+        PendingAction::CancelOrder(order) => (
+            " Cancel Order ",
+            vec![
+                Line::from(format!("Cancel order {}?", ShortId(&order.order_id))),
+                Line::from(format!(
+                    "{} {} @ ${} ({} filled)",
+                    side_label(order.side),
+                    number_format::size(order.size),
+                    number_format::price(order.price),
+                    number_format::size(order.filled_size)
+                )),
+            ],
+        ),
+        PendingAction::FlattenPosition {
+            position,
+            large,
+            confirmed_once,
+        } => {
+            let action = match position.side {
+                PositionSide::Long => "Sell",
+                PositionSide::Short => "Buy back",
+            };
+            let mut lines = vec![
+                Line::from(format!(
+                    "{} {} {} at the touch?",
+                    action,
+                    number_format::size(position.size),
+                    position.outcome
+                )),
+                Line::from(truncate(&market_label(position), 44)),
+            ];
+            if let Some(large) = large {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "⚠️  ~${} exceeds the large order threshold of ${}",
+                        large.notional.round_dp(2),
+                        large.threshold.round_dp(2)
+                    ),
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                )));
+                if *confirmed_once {
+                    lines.push(Line::from("Press y again to confirm the large order"));
+                }
+            }
+            (" Flatten Position ", lines)
+        }
+        PendingAction::Hedge {
+            plan,
+            large,
+            confirmed_once,
+        } => {
+            let mut lines: Vec<Line> = plan.summary().lines().map(|l| Line::from(l.to_string())).collect();
+            if let Some(large) = large {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "⚠️  ~${} exceeds the large order threshold of ${}",
+                        large.notional.round_dp(2),
+                        large.threshold.round_dp(2)
+                    ),
+                    Style::default().fg(Color::Rgb(255, 165, 0)),
+                )));
+                if *confirmed_once {
+                    lines.push(Line::from("Press y again to confirm the large order"));
+                }
+            }
+            (" Hedge Market ", lines)
+        }
+    };
+
+    let mut lines = lines;
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("y", Style::default().fg(Color::Yellow)),
+        Span::raw(" confirm   any other key aborts"),
+    ]));
+
+    let area = centered_rect(f.area(), 56, lines.len() as u16 + 2);
+    f.render_widget(Clear, area);
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(title),
+    );
+    f.render_widget(popup, area);
+}
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn side_style(side: OrderSide) -> Style {
+    match side {
+        OrderSide::Buy => Style::default().fg(theme::palette().bid.into()),
+        OrderSide::Sell => Style::default().fg(theme::palette().ask.into()),
+    }
+}
+
+fn format_pnl(pnl: Decimal) -> String {
+    units::format_signed_usdc(pnl)
+}
+
+fn pnl_style(pnl: Decimal) -> Style {
+    Style::default().fg(theme::palette().pnl(pnl >= Decimal::ZERO).into())
+}
@@ -0,0 +1,372 @@
+//! Main loop for the multi-page streaming TUI
+//!
+//! Shared by `polybot stream` and `polybot tui`: both start a single
+//! `Streamer` and hand it to [`run_app`], which owns the terminal, routes keys
+//! to the global navigation or the current page, and feeds streamer events
+//! into the `App` until the user quits.
+
+use anyhow::Result;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::FutureExt;
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::{self, Stdout};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::core::services::Streamer;
+use crate::data_paths::DataPaths;
+use crate::keybindings::{self, Action, KeyChord, Keymap, Surface};
+use crate::theme::{self, ThemePreset};
+use crate::tui::navigation::Page;
+use crate::tui::pages::Page as PageTrait;
+use crate::tui::{events, ui, App, EventHandler};
+
+/// Options for [`run_app`]
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Page shown when the TUI opens
+    pub initial_page: Option<Page>,
+    /// Debug: directly open orderbook view for the first token
+    pub debug_orderbook: bool,
+    /// Palette overriding the preset in theme.yaml
+    pub theme: Option<ThemePreset>,
+}
+
+/// Run the TUI over an already started streamer, restoring the terminal on
+/// exit or panic and stopping the streamer afterwards
+pub async fn run_app(
+    host: &str,
+    data_paths: DataPaths,
+    streamer: Streamer,
+    options: RunOptions,
+) -> Result<()> {
+    // Set up panic hook for proper terminal cleanup
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        // Try to restore terminal on panic
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+
+        // Call original panic hook
+        original_hook(panic_info);
+    }));
+
+    // Wrap the entire TUI execution in a catch_unwind for additional safety
+    let result = std::panic::AssertUnwindSafe(run_app_core(host, data_paths, streamer, options))
+        .catch_unwind()
+        .await;
+
+    // Restore original panic hook
+    let _ = std::panic::take_hook();
+
+    match result {
+        Ok(Ok(res)) => Ok(res),
+        Ok(Err(e)) => Err(e),
+        Err(panic) => {
+            let panic_msg = if let Some(s) = panic.downcast_ref::<String>() {
+                s.clone()
+            } else if let Some(s) = panic.downcast_ref::<&str>() {
+                s.to_string()
+            } else {
+                "Unknown panic occurred".to_string()
+            };
+            Err(anyhow::anyhow!("TUI panicked: {}", panic_msg))
+        }
+    }
+}
+
+async fn run_app_core(
+    host: &str,
+    data_paths: DataPaths,
+    streamer: Streamer,
+    options: RunOptions,
+) -> Result<()> {
+    let streamer_arc = Arc::new(streamer);
+    theme::init(&data_paths, options.theme);
+    let keymap = keybindings::load_keymap(&data_paths, Surface::Tui);
+
+    // Setup terminal with error handling
+    let mut terminal = match setup_terminal() {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            error!("Failed to setup terminal for TUI: {}", e);
+
+            // Check if this is a terminal device error
+            if e.to_string().contains("Device not configured")
+                || e.to_string().contains("not a terminal")
+                || e.to_string().contains("Inappropriate ioctl")
+            {
+                return Err(anyhow::anyhow!("Terminal not available: {}", e));
+            } else {
+                return Err(anyhow::anyhow!("Failed to setup terminal: {}", e));
+            }
+        }
+    };
+
+    // Create app and configure data access
+    let mut app = App::new(streamer_arc.clone());
+    app.configure_data_access(data_paths.clone(), host.to_string());
+
+    if let Some(page) = options.initial_page {
+        go_to_page(&mut app, page);
+        app.handle_orders_refresh_request();
+    }
+
+    // Debug mode: directly open orderbook view
+    if options.debug_orderbook {
+        info!("Debug mode: directly opening orderbook view");
+        app.event_log.push("🔧 Debug mode: orderbook view".to_string());
+
+        // For debugging, we'll wait a moment for data and then select the first token
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    // Create event handler with balanced tick rate for UI responsiveness
+    let mut event_handler = EventHandler::new(Duration::from_millis(50));
+
+    // Get event stream
+    let mut events = streamer_arc.events();
+
+    info!("Starting TUI main loop");
+
+    // Main loop with comprehensive error handling
+    let result = loop {
+        // Draw UI with error handling
+        match terminal.draw(|f| {
+            if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                ui::draw(f, &mut app);
+            })) {
+                error!("UI drawing panicked: {:?}", e);
+            }
+        }) {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Terminal drawing error: {}", e);
+                break Err(anyhow::anyhow!("Terminal drawing failed: {}", e));
+            }
+        }
+
+        // Handle events with balanced timeout for UI responsiveness
+        let event_timeout = Duration::from_millis(25);
+
+        tokio::select! {
+            // Handle keyboard events first (higher priority for UI responsiveness)
+            ui_event_opt = event_handler.next() => {
+                match ui_event_opt {
+                    Some(ui_event) => {
+                        match ui_event {
+                            events::Event::Key(key) => {
+                                // Pages taking text input get every key
+                                let handled = !current_page_captures_input(&app)
+                                    && handle_global_key(&mut app, &keymap, key);
+
+                                // If not handled by global navigation, delegate to the current page
+                                if !handled {
+                                    let page_handled = delegate_to_page(&mut app, key);
+
+                                    // If page didn't handle it, try some common fallbacks
+                                    if !page_handled {
+                                        if let KeyCode::Char('r') = key.code {
+                                            info!("User requested refresh");
+                                            events = streamer_arc.events();
+                                            app.event_log.push("🔄 Refreshed event stream".to_string());
+                                        }
+                                    }
+                                }
+
+                                // Check if refresh was requested after key handling
+                                app.handle_orders_refresh_request();
+                            }
+                            events::Event::Tick => {
+                                // Regular tick for UI updates
+                                app.update_fetching_status();
+                            }
+                            events::Event::Error(error_msg) => {
+                                error!("Event handler error: {}", error_msg);
+                                app.event_log.push(format!("❌ Input error: {}", error_msg));
+                            }
+                        }
+                    }
+                    None => {
+                        warn!("Event handler channel closed");
+                        app.event_log.push("⚠️ Input handler stopped".to_string());
+                        break Err(anyhow::anyhow!("Input event handler stopped unexpectedly"));
+                    }
+                }
+            }
+
+            // Handle websocket events (lower priority)
+            ws_event = events.recv() => {
+                match ws_event {
+                    Ok(event) => {
+                        if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            app.handle_event(event);
+                        })) {
+                            error!("Event handling panicked: {:?}", e);
+                            app.event_log.push("⚠️ Event processing error occurred".to_string());
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Event channel lagged by {} messages, resubscribing", n);
+                        events = streamer_arc.events();
+                        app.event_log.push(format!("⚠️ Missed {} events due to lag", n));
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        error!("Event channel closed, stopping TUI");
+                        app.event_log.push("❌ Event stream closed".to_string());
+                        break Err(anyhow::anyhow!("WebSocket event stream closed unexpectedly"));
+                    }
+                }
+            }
+
+            // Timeout to ensure we don't hang
+            _ = tokio::time::sleep(event_timeout) => {
+                // Regular timeout, continue loop
+            }
+        }
+
+        if app.should_quit {
+            info!("Exiting TUI main loop");
+            break Ok(());
+        }
+    };
+
+    // Restore terminal with error handling
+    if let Err(e) = restore_terminal(&mut terminal) {
+        error!("Failed to restore terminal: {}", e);
+        // Don't fail the entire operation for terminal restore errors
+    }
+
+    // Stop streamer with error handling
+    info!("Stopping streamer...");
+    match Arc::try_unwrap(streamer_arc) {
+        Ok(mut streamer) => streamer.stop().await,
+        Err(_) => {
+            warn!("Could not unwrap streamer Arc, there are still references");
+            // Streamer will be dropped when Arc goes out of scope
+        }
+    }
+
+    result
+}
+
+/// Quit and page switching keys shared by every page; quit and page cycling
+/// follow the `tui` section of keybindings.yaml
+fn handle_global_key(app: &mut App, keymap: &Keymap, key: KeyEvent) -> bool {
+    let action = KeyChord::from_crossterm(&key).and_then(|chord| keymap.action_for_typed(&chord));
+    match action {
+        Some(Action::Quit) => {
+            info!("User requested quit");
+            app.should_quit = true;
+        }
+        Some(Action::FocusNextPane) => {
+            app.navigation.next_page();
+            fetch_orders_if_needed(app);
+        }
+        Some(Action::FocusPreviousPane) => {
+            app.navigation.previous_page();
+            fetch_orders_if_needed(app);
+        }
+        _ => match key.code {
+            KeyCode::Char(c) => match Page::from_digit(c) {
+                Some(page) => go_to_page(app, page),
+                None => return false,
+            },
+            _ => return false,
+        },
+    }
+    true
+}
+
+fn go_to_page(app: &mut App, page: Page) {
+    app.navigation.go_to_page(page);
+    fetch_orders_if_needed(app);
+}
+
+/// Orders and Portfolio pages show account data fetched on demand
+fn fetch_orders_if_needed(app: &mut App) {
+    let needs_orders = matches!(
+        app.navigation.current_page,
+        Page::Orders | Page::Portfolio
+    );
+    if needs_orders && app.should_fetch_orders() {
+        app.request_orders_refresh();
+    }
+}
+
+fn current_page_captures_input(app: &App) -> bool {
+    match app.navigation.current_page {
+        Page::Stream => app.stream_page.captures_input(),
+        Page::Orders => app.orders_page.captures_input(),
+        Page::Tokens => app.tokens_page.captures_input(),
+        Page::Markets => app.markets_page.captures_input(),
+        Page::Portfolio => app.portfolio_page.captures_input(),
+        Page::Datasets => app.datasets_page.captures_input(),
+        Page::Gamma => app.gamma_page.captures_input(),
+    }
+}
+
+/// Hand a key to the current page; pages are taken out of the app while they
+/// handle it so they can mutate both themselves and the app
+fn delegate_to_page(app: &mut App, key: KeyEvent) -> bool {
+    macro_rules! with_page {
+        ($field:ident) => {{
+            let mut page = std::mem::take(&mut app.$field);
+            let result = page.handle_key(key, app);
+            app.$field = page;
+            result
+        }};
+    }
+
+    match app.navigation.current_page {
+        Page::Stream => with_page!(stream_page),
+        Page::Orders => with_page!(orders_page),
+        Page::Tokens => with_page!(tokens_page),
+        Page::Markets => with_page!(markets_page),
+        Page::Portfolio => with_page!(portfolio_page),
+        Page::Datasets => with_page!(datasets_page),
+        Page::Gamma => with_page!(gamma_page),
+    }
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
+    use std::io::IsTerminal;
+
+    // Check if stdout is a terminal
+    if !io::stdout().is_terminal() {
+        return Err(anyhow::anyhow!("stdout is not a terminal"));
+    }
+
+    // Check if stderr is a terminal (for user interaction)
+    if !io::stderr().is_terminal() {
+        return Err(anyhow::anyhow!("stderr is not a terminal"));
+    }
+
+    enable_raw_mode().map_err(|e| anyhow::anyhow!("Failed to enable raw mode: {}", e))?;
+
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+        .map_err(|e| anyhow::anyhow!("Failed to setup terminal screen: {}", e))?;
+
+    let backend = CrosstermBackend::new(stdout);
+    let terminal =
+        Terminal::new(backend).map_err(|e| anyhow::anyhow!("Failed to create terminal: {}", e))?;
+
+    Ok(terminal)
+}
+
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
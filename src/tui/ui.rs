@@ -29,6 +29,8 @@ pub fn draw(frame: &mut Frame<'_>, app: &mut App) {
         Page::Tokens => app.tokens_page.render(frame, chunks[1], app),
         Page::Markets => app.markets_page.render(frame, chunks[1], app),
         Page::Portfolio => app.portfolio_page.render(frame, chunks[1], app),
+        Page::Datasets => app.datasets_page.render(frame, chunks[1], app),
+        Page::Gamma => app.gamma_page.render(frame, chunks[1], app),
     }
     
     // Render clipboard notification overlay if present
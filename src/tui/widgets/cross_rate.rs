@@ -0,0 +1,109 @@
+//! Cross-rate table for the two outcome tokens of each streamed market
+
+use crate::core::execution::cross_rate::CrossRate;
+use crate::core::types::common::ShortId;
+use crate::number_format;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use rust_decimal::Decimal;
+
+fn price(value: Option<Decimal>) -> String {
+    value
+        .map(number_format::price)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Edge cell colored by sign: profitable edges use the profit color
+fn edge_cell(edge: Option<Decimal>) -> Cell<'static> {
+    let palette = theme::palette();
+    match edge {
+        Some(edge) => {
+            let style = if edge > Decimal::ZERO {
+                Style::default()
+                    .fg(palette.profit.into())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(palette.muted.into())
+            };
+            Cell::from(number_format::price_change(edge)).style(style)
+        }
+        None => Cell::from("-"),
+    }
+}
+
+/// Render one row per market with both tokens' top of book, the ask and bid
+/// sums, and the edge from buying or selling both sides
+pub fn render_cross_rates(f: &mut Frame, area: Rect, rates: &[CrossRate]) {
+    let title = format!(
+        "Cross Rates ({} markets, {} with edge)",
+        rates.len(),
+        rates.iter().filter(|rate| rate.has_arbitrage()).count()
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if rates.is_empty() {
+        let empty = Paragraph::new(
+            "No market has both outcome tokens streaming.\nStream both tokens of a condition to see cross rates.",
+        )
+        .style(Style::default().fg(Color::Gray))
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let palette = theme::palette();
+    let bid_style = Style::default().fg(palette.bid.into());
+    let ask_style = Style::default().fg(palette.ask.into());
+
+    let header = Row::new(vec![
+        "Market", "Token A", "A Bid", "A Ask", "Token B", "B Bid", "B Ask", "Ask Sum",
+        "Bid Sum", "Buy Both", "Sell Both",
+    ])
+    .style(
+        Style::default()
+            .fg(palette.highlight.into())
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows: Vec<Row> = rates
+        .iter()
+        .map(|rate| {
+            let [a, b] = &rate.tokens;
+            Row::new(vec![
+                Cell::from(ShortId(&rate.market).to_string()),
+                Cell::from(ShortId(&a.asset_id).to_string()),
+                Cell::from(price(a.best_bid)).style(bid_style),
+                Cell::from(price(a.best_ask)).style(ask_style),
+                Cell::from(ShortId(&b.asset_id).to_string()),
+                Cell::from(price(b.best_bid)).style(bid_style),
+                Cell::from(price(b.best_ask)).style(ask_style),
+                Cell::from(price(rate.ask_sum())),
+                Cell::from(price(rate.bid_sum())),
+                edge_cell(rate.buy_both_edge()),
+                edge_cell(rate.sell_both_edge()),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(15),
+        Constraint::Length(15),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(15),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(9),
+    ];
+
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+}
@@ -7,14 +7,20 @@
 //! Current widgets:
 //! - Order book widget with price-first layout and cumulative totals
 //! - Portfolio widget with positions, orders, and P&L tracking
+//! - Cross-rate table pairing the outcome tokens of each market
+//! - Synthetic instrument table with bounds status
+//! - Before/after card of what changed in a market
 //! - Interactive scrolling and navigation
 //! - Error state visualization for crossed markets
 //! - Real-time data synchronization
 //!
 //! For comprehensive documentation, see [README.md](./README.md)
 
+pub mod cross_rate;
 pub mod order_book;
 pub mod portfolio;
+pub mod snapshot_diff;
+pub mod synthetic;
 
 // pub use order_book::draw_order_book;
 // pub use portfolio::{PortfolioWidget, PortfolioTab};
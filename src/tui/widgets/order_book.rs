@@ -1,8 +1,11 @@
+use crate::core::execution::book_view::{BookView, DepthPoint, PriceAggregation};
 use crate::core::types::market::PriceLevel;
+use crate::number_format;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Row, Table},
+    symbols::Marker,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row, Table},
     Frame,
 };
 use rust_decimal::Decimal;
@@ -48,10 +51,12 @@ pub fn render_order_book(
     asks: &[PriceLevel],
     token_id: &str,
     scroll: usize,
+    aggregation: PriceAggregation,
 ) {
-    // Create temporary app-like structure for compatibility
-    let mut temp_bids = bids.to_vec();
-    let mut temp_asks = asks.to_vec();
+    // Bin levels first so totals and the mid row use the displayed prices
+    let view = BookView::new(bids, asks, aggregation);
+    let mut temp_bids = view.bids;
+    let mut temp_asks = view.asks;
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -63,7 +68,11 @@ pub fn render_order_book(
 
     // Title
     // Show full token ID for easy copying
-    let title = Paragraph::new(format!("Order Book | Token ID: {}", token_id))
+    let title = Paragraph::new(format!(
+        "Order Book | Token ID: {} | Bins: {}",
+        token_id,
+        aggregation.label()
+    ))
         .style(
             Style::default()
                 .fg(Color::Cyan)
@@ -153,6 +162,7 @@ pub fn render_order_book(
     let start_idx = actual_scroll;
     let end_idx = (start_idx + available_height).min(levels.len());
     let visible_levels = &levels[start_idx..end_idx];
+    let palette = crate::theme::palette();
 
     let rows: Vec<Row> = visible_levels
         .iter()
@@ -169,44 +179,44 @@ pub fn render_order_book(
                 } else {
                     // Normal mid-point row
                     Row::new(vec![
-                        format!("--- MID ${:.4} ---", level.price),
+                        format!("--- MID ${} ---", number_format::price(level.price)),
                         "".to_string(),
                         "".to_string(),
                     ])
                     .style(
                         Style::default()
-                            .fg(Color::Yellow)
+                            .fg(palette.highlight.into())
                             .add_modifier(Modifier::BOLD),
                     )
                 }
             } else {
                 // Regular order level
                 let (size, total, style) = if let Some(bid_size) = level.bid_size {
-                    // Bid level (green)
+                    // Bid level
                     let cumulative_total = level.cumulative_bid_total.unwrap_or(Decimal::ZERO);
                     (
-                        format!("{:.2}", bid_size),
-                        format!("${:.2}", cumulative_total),
-                        Style::default().fg(Color::Green),
+                        number_format::size(bid_size),
+                        number_format::usd(cumulative_total),
+                        Style::default().fg(palette.bid.into()),
                     )
                 } else if let Some(ask_size) = level.ask_size {
-                    // Ask level (red)
+                    // Ask level
                     let cumulative_total = level.cumulative_ask_total.unwrap_or(Decimal::ZERO);
                     (
-                        format!("{:.2}", ask_size),
-                        format!("${:.2}", cumulative_total),
-                        Style::default().fg(Color::Red),
+                        number_format::size(ask_size),
+                        number_format::usd(cumulative_total),
+                        Style::default().fg(palette.ask.into()),
                     )
                 } else {
                     // Empty level
                     (
                         "".to_string(),
                         "".to_string(),
-                        Style::default().fg(Color::Gray),
+                        Style::default().fg(palette.muted.into()),
                     )
                 };
 
-                let price_str = format!("${:.4}", level.price);
+                let price_str = format!("${}", number_format::price(level.price));
 
                 Row::new(vec![price_str, size, total]).style(style)
             }
@@ -221,8 +231,110 @@ pub fn render_order_book(
             Constraint::Percentage(40), // Total USD
         ],
     )
-    .header(Row::new(vec!["Price", "Size", "Total USD"]).style(Style::default().fg(Color::Yellow)))
+    .header(Row::new(vec!["Price", "Size", "Total USD"]).style(Style::default().fg(palette.highlight.into())))
     .block(Block::default().borders(Borders::ALL).title("Order Book"));
 
     frame.render_widget(table, chunks[1]);
-}
\ No newline at end of file
+}
+
+/// Cumulative depth of both sides as stepped lines, from the same binned view as the ladder
+pub fn render_depth_chart(
+    frame: &mut Frame,
+    area: ratatui::layout::Rect,
+    bids: &[PriceLevel],
+    asks: &[PriceLevel],
+    token_id: &str,
+    aggregation: PriceAggregation,
+) {
+    let view = BookView::new(bids, asks, aggregation);
+    // The chart takes `(x, y)` pairs
+    let chart_data = |steps: Vec<DepthPoint>| -> Vec<(f64, f64)> {
+        steps
+            .into_iter()
+            .map(|point| (point.price, point.cumulative_size))
+            .collect()
+    };
+    let bid_steps = chart_data(view.bid_steps());
+    let ask_steps = chart_data(view.ask_steps());
+    let palette = crate::theme::palette();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let title = Paragraph::new(format!(
+        "Depth Chart | Token ID: {} | Bins: {}",
+        token_id,
+        aggregation.label()
+    ))
+    .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(title, chunks[0]);
+
+    let block = Block::default().borders(Borders::ALL).title("Depth Chart");
+    if bid_steps.is_empty() && ask_steps.is_empty() {
+        let empty = Paragraph::new("Book is empty")
+            .style(Style::default().fg(palette.muted.into()))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(empty, chunks[1]);
+        return;
+    }
+
+    let prices = bid_steps.iter().chain(&ask_steps).map(|(price, _)| *price);
+    let (min_price, max_price) = prices.fold((f64::MAX, f64::MIN), |(lo, hi), p| {
+        (lo.min(p), hi.max(p))
+    });
+    let max_size = bid_steps
+        .iter()
+        .chain(&ask_steps)
+        .map(|(_, size)| *size)
+        .fold(0.0, f64::max);
+    // Keep a single-level book from collapsing the axis
+    let (min_price, max_price) = if max_price > min_price {
+        (min_price, max_price)
+    } else {
+        (min_price - 0.01, max_price + 0.01)
+    };
+    let mid_price = (min_price + max_price) / 2.0;
+
+    let datasets = vec![
+        Dataset::default()
+            .name("Bids")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(palette.bid.into()))
+            .data(&bid_steps),
+        Dataset::default()
+            .name("Asks")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(palette.ask.into()))
+            .data(&ask_steps),
+    ];
+
+    let axis_style = Style::default().fg(palette.muted.into());
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .title("Price")
+                .style(axis_style)
+                .bounds([min_price, max_price])
+                .labels(vec![
+                    number_format::price(min_price),
+                    number_format::price(mid_price),
+                    number_format::price(max_price),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Size")
+                .style(axis_style)
+                .bounds([0.0, max_size * 1.05])
+                .labels(vec!["0".to_string(), number_format::integer(max_size)]),
+        );
+    frame.render_widget(chart, chunks[1]);
+}
@@ -14,6 +14,8 @@ use crate::core::portfolio::{
     ActiveOrder, MarketPositionSummary, OrderSide, OrderStatus, PortfolioStats, Position,
     PositionStatus,
 };
+use crate::number_format;
+use crate::theme;
 
 /// Portfolio widget state
 pub struct PortfolioWidget<'a> {
@@ -112,17 +114,13 @@ impl<'a> PortfolioWidget<'a> {
 
         let portfolio_value = self.stats.total_portfolio_value();
         let total_pnl = self.stats.total_pnl();
-        let pnl_color = if total_pnl >= Decimal::ZERO {
-            Color::Green
-        } else {
-            Color::Red
-        };
+        let pnl_color = Color::from(theme::palette().pnl(total_pnl >= Decimal::ZERO));
 
         let stats_text = vec![
             Line::from(vec![
                 Span::raw("Total Value: "),
                 Span::styled(
-                    format!("${:.2}", portfolio_value),
+                    number_format::usd(portfolio_value),
                     Style::default()
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD),
@@ -131,12 +129,12 @@ impl<'a> PortfolioWidget<'a> {
             Line::from(vec![
                 Span::raw("Available: "),
                 Span::styled(
-                    format!("${:.2}", self.stats.available_balance),
+                    number_format::usd(self.stats.available_balance),
                     Style::default().fg(Color::Green),
                 ),
                 Span::raw(" | Locked: "),
                 Span::styled(
-                    format!("${:.2}", self.stats.locked_balance),
+                    number_format::usd(self.stats.locked_balance),
                     Style::default().fg(Color::Gray),
                 ),
             ]),
@@ -144,11 +142,13 @@ impl<'a> PortfolioWidget<'a> {
                 Span::raw("Total P&L: "),
                 Span::styled(
                     format!(
-                        "${:.2} ({:+.2}%)",
-                        total_pnl,
-                        self.stats
-                            .pnl_percentage(Decimal::from(10000))
-                            .unwrap_or(Decimal::ZERO)
+                        "{} ({})",
+                        number_format::usd(total_pnl),
+                        number_format::signed_percent(
+                            self.stats
+                                .pnl_percentage(Decimal::from(10000))
+                                .unwrap_or(Decimal::ZERO)
+                        )
                     ),
                     Style::default().fg(pnl_color).add_modifier(Modifier::BOLD),
                 ),
@@ -156,12 +156,12 @@ impl<'a> PortfolioWidget<'a> {
             Line::from(vec![
                 Span::raw("Realized: "),
                 Span::styled(
-                    format!("${:.2}", self.stats.total_realized_pnl),
+                    number_format::usd(self.stats.total_realized_pnl),
                     Style::default().fg(Color::White),
                 ),
                 Span::raw(" | Unrealized: "),
                 Span::styled(
-                    format!("${:.2}", self.stats.total_unrealized_pnl),
+                    number_format::usd(self.stats.total_unrealized_pnl),
                     Style::default().fg(Color::Gray),
                 ),
             ]),
@@ -178,7 +178,7 @@ impl<'a> PortfolioWidget<'a> {
                 Span::styled(
                     self.stats
                         .win_rate
-                        .map(|w| format!("{:.1}%", w))
+                        .map(number_format::percent)
                         .unwrap_or_else(|| "N/A".to_string()),
                     Style::default().fg(Color::Green),
                 ),
@@ -188,17 +188,17 @@ impl<'a> PortfolioWidget<'a> {
                 Span::styled(
                     self.stats
                         .average_win
-                        .map(|w| format!("${:.2}", w))
+                        .map(number_format::usd)
                         .unwrap_or_else(|| "N/A".to_string()),
-                    Style::default().fg(Color::Green),
+                    Style::default().fg(theme::palette().profit.into()),
                 ),
                 Span::raw(" | Avg Loss: "),
                 Span::styled(
                     self.stats
                         .average_loss
-                        .map(|l| format!("${:.2}", l))
+                        .map(number_format::usd)
                         .unwrap_or_else(|| "N/A".to_string()),
-                    Style::default().fg(Color::Red),
+                    Style::default().fg(theme::palette().loss.into()),
                 ),
             ]),
         ];
@@ -220,19 +220,15 @@ impl<'a> PortfolioWidget<'a> {
         // Add recent positions
         for position in self.positions.iter().take(5) {
             let pnl = position.total_pnl();
-            let pnl_color = if pnl >= Decimal::ZERO {
-                Color::Green
-            } else {
-                Color::Red
-            };
+            let pnl_color = Color::from(theme::palette().pnl(pnl >= Decimal::ZERO));
 
             activity_rows.push(Row::new(vec![
                 Cell::from("Position"),
                 Cell::from(format!("{:?}", position.side)),
                 Cell::from(format!("{:.8}", position.token_id)),
-                Cell::from(format!("{:.2}", position.size)),
-                Cell::from(format!("${:.4}", position.average_price)),
-                Cell::from(format!("${:.2}", pnl)).style(Style::default().fg(pnl_color)),
+                Cell::from(number_format::size(position.size)),
+                Cell::from(format!("${}", number_format::price(position.average_price))),
+                Cell::from(number_format::usd(pnl)).style(Style::default().fg(pnl_color)),
             ]));
         }
 
@@ -251,8 +247,8 @@ impl<'a> PortfolioWidget<'a> {
                 Cell::from("Order"),
                 Cell::from(format!("{:?}", order.side)),
                 Cell::from(format!("{:.8}", order.token_id)),
-                Cell::from(format!("{:.2}", order.size)),
-                Cell::from(format!("${:.4}", order.price)),
+                Cell::from(number_format::size(order.size)),
+                Cell::from(format!("${}", number_format::price(order.price))),
                 Cell::from(format!("{:?}", order.status)).style(Style::default().fg(status_color)),
             ]));
         }
@@ -309,11 +305,7 @@ impl<'a> PortfolioWidget<'a> {
             .map(|position| {
                 let pnl = position.total_pnl();
                 let pnl_pct = position.pnl_percentage().unwrap_or(Decimal::ZERO);
-                let pnl_color = if pnl >= Decimal::ZERO {
-                    Color::Green
-                } else {
-                    Color::Red
-                };
+                let pnl_color = Color::from(theme::palette().pnl(pnl >= Decimal::ZERO));
 
                 let status_color = match position.status {
                     PositionStatus::Open => Color::Green,
@@ -324,16 +316,17 @@ impl<'a> PortfolioWidget<'a> {
                 Row::new(vec![
                     Cell::from(format!("{:.8}", position.token_id)),
                     Cell::from(format!("{:?}", position.side)),
-                    Cell::from(format!("{:.2}", position.size)),
-                    Cell::from(format!("${:.4}", position.average_price)),
+                    Cell::from(number_format::size(position.size)),
+                    Cell::from(format!("${}", number_format::price(position.average_price))),
                     Cell::from(
                         position
                             .current_price
-                            .map(|p| format!("${:.4}", p))
+                            .map(|p| format!("${}", number_format::price(p)))
                             .unwrap_or_else(|| "N/A".to_string()),
                     ),
-                    Cell::from(format!("${:.2}", pnl)).style(Style::default().fg(pnl_color)),
-                    Cell::from(format!("{:+.2}%", pnl_pct)).style(Style::default().fg(pnl_color)),
+                    Cell::from(number_format::usd(pnl)).style(Style::default().fg(pnl_color)),
+                    Cell::from(number_format::signed_percent(pnl_pct))
+                        .style(Style::default().fg(pnl_color)),
                     Cell::from(format!("{:?}", position.status))
                         .style(Style::default().fg(status_color)),
                 ])
@@ -387,10 +380,10 @@ impl<'a> PortfolioWidget<'a> {
                     _ => Color::White,
                 };
 
-                let side_color = match order.side {
-                    OrderSide::Buy => Color::Green,
-                    OrderSide::Sell => Color::Red,
-                };
+                let side_color = Color::from(match order.side {
+                    OrderSide::Buy => theme::palette().bid,
+                    OrderSide::Sell => theme::palette().ask,
+                });
 
                 let age = Utc::now().signed_duration_since(order.created_at);
                 let age_str = if age.num_hours() > 0 {
@@ -406,9 +399,9 @@ impl<'a> PortfolioWidget<'a> {
                     Cell::from(format!("{:.8}", order.token_id)),
                     Cell::from(format!("{:?}", order.side)).style(Style::default().fg(side_color)),
                     Cell::from(format!("{:?}", order.order_type)),
-                    Cell::from(format!("${:.4}", order.price)),
-                    Cell::from(format!("{:.2}", order.size)),
-                    Cell::from(format!("{:.2}", order.filled_size)),
+                    Cell::from(format!("${}", number_format::price(order.price))),
+                    Cell::from(number_format::size(order.size)),
+                    Cell::from(number_format::size(order.filled_size)),
                     Cell::from(format!("{:?}", order.status))
                         .style(Style::default().fg(status_color)),
                     Cell::from(age_str),
@@ -460,24 +453,16 @@ impl<'a> PortfolioWidget<'a> {
             .market_summaries
             .iter()
             .map(|summary| {
-                let pnl_color = if summary.total_pnl >= Decimal::ZERO {
-                    Color::Green
-                } else {
-                    Color::Red
-                };
-                let net_color = if summary.net_position >= Decimal::ZERO {
-                    Color::Green
-                } else {
-                    Color::Red
-                };
+                let pnl_color = Color::from(theme::palette().pnl(summary.total_pnl >= Decimal::ZERO));
+                let net_color = Color::from(theme::palette().pnl(summary.net_position >= Decimal::ZERO));
 
                 Row::new(vec![
                     Cell::from(format!("{:.40}", summary.market_question)),
                     Cell::from(format!("{}", summary.positions.len())),
-                    Cell::from(format!("{:+.2}", summary.net_position))
+                    Cell::from(number_format::signed_size(summary.net_position))
                         .style(Style::default().fg(net_color)),
-                    Cell::from(format!("${:.2}", summary.total_exposure)),
-                    Cell::from(format!("${:.2}", summary.total_pnl))
+                    Cell::from(number_format::usd(summary.total_exposure)),
+                    Cell::from(number_format::usd(summary.total_pnl))
                         .style(Style::default().fg(pnl_color)),
                     Cell::from(format!("{}", summary.open_order_count)),
                 ])
@@ -0,0 +1,54 @@
+//! Compact before/after card of a market, rendering `markets::snapshot_diff`
+
+use crate::markets::snapshot_diff::{SnapshotDiff, Trend};
+use crate::theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Color of a change: up and new in the profit color, down and gone in the loss color
+fn trend_style(trend: Option<Trend>) -> Style {
+    let palette = theme::palette();
+    let color = match trend {
+        Some(Trend::Up | Trend::Added) => palette.profit,
+        Some(Trend::Down | Trend::Removed) => palette.loss,
+        Some(Trend::Flat) | None => palette.muted,
+    };
+    Style::default().fg(color.into())
+}
+
+/// Period line and one line per row of the card
+pub fn diff_card_lines(diff: &SnapshotDiff) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(theme::palette().muted.into());
+    let mut lines = vec![Line::from(Span::styled(diff.period(), muted))];
+    for row in diff.rows() {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<11}", row.label),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{:>9} → {:<9} ", row.before, row.after)),
+            Span::styled(row.delta, trend_style(row.trend)),
+        ]));
+    }
+    lines
+}
+
+/// Rows a card needs, borders included
+pub fn card_height(diff: &SnapshotDiff) -> u16 {
+    diff.rows().len() as u16 + 3
+}
+
+/// Draw `diff` as a bordered card titled with the market
+pub fn render_diff_card(f: &mut Frame, area: Rect, diff: &SnapshotDiff) {
+    let card = Paragraph::new(diff_card_lines(diff)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(diff.title.clone()),
+    );
+    f.render_widget(card, area);
+}
@@ -0,0 +1,108 @@
+//! Synthetic instrument table with each instrument's top of book and bounds
+
+use crate::core::execution::synthetic::SyntheticMonitor;
+use crate::number_format;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+use rust_decimal::Decimal;
+
+fn price(value: Option<Decimal>) -> String {
+    value
+        .map(number_format::price)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Render one row per instrument with its synthetic bid, ask and mid, how far
+/// the mid moved since the first point of its series, and its bound status
+pub fn render_synthetics(f: &mut Frame, area: Rect, monitor: &SyntheticMonitor) {
+    let breached = monitor
+        .instruments()
+        .iter()
+        .filter(|instrument| monitor.breach(&instrument.name).is_some())
+        .count();
+    let title = format!(
+        "Synthetics ({} instruments, {} out of bounds)",
+        monitor.instruments().len(),
+        breached
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if monitor.instruments().is_empty() {
+        let empty = Paragraph::new(
+            "No synthetic instruments defined.\nAdd one with `polybot synthetic add <name> --leg <token> --leg <token>:-1`.",
+        )
+        .style(Style::default().fg(Color::Gray))
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let palette = theme::palette();
+    let bid_style = Style::default().fg(palette.bid.into());
+    let ask_style = Style::default().fg(palette.ask.into());
+
+    let header = Row::new(vec![
+        "Name", "Legs", "Bid", "Ask", "Mid", "Change", "Bounds", "Status",
+    ])
+    .style(
+        Style::default()
+            .fg(palette.highlight.into())
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let rows: Vec<Row> = monitor
+        .instruments()
+        .iter()
+        .map(|instrument| {
+            let latest = monitor.latest(&instrument.name);
+            let first_mid = monitor.series(&instrument.name).find_map(|point| point.mid);
+            let change = match (first_mid, latest.and_then(|point| point.mid)) {
+                (Some(first), Some(mid)) => number_format::price_change(mid - first),
+                _ => "-".to_string(),
+            };
+            let missing = monitor
+                .book(&instrument.name)
+                .is_none_or(|book| !book.missing.is_empty());
+            let status = match monitor.breach(&instrument.name) {
+                Some(breach) => Cell::from(format!("{} {}", breach.direction, breach.price)).style(
+                    Style::default()
+                        .fg(palette.loss.into())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                None if missing => {
+                    Cell::from("waiting for legs").style(Style::default().fg(palette.muted.into()))
+                }
+                None => Cell::from("ok").style(Style::default().fg(palette.profit.into())),
+            };
+            Row::new(vec![
+                Cell::from(instrument.name.clone()),
+                Cell::from(instrument.formula()),
+                Cell::from(price(latest.and_then(|point| point.bid))).style(bid_style),
+                Cell::from(price(latest.and_then(|point| point.ask))).style(ask_style),
+                Cell::from(price(latest.and_then(|point| point.mid))),
+                Cell::from(change),
+                Cell::from(instrument.bounds_label()),
+                status,
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(18),
+        Constraint::Min(24),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(20),
+        Constraint::Length(18),
+    ];
+
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+}
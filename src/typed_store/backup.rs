@@ -0,0 +1,303 @@
+//! Backups of RocksDB stores
+//!
+//! `polybot index backup` snapshots a store (the typed market index, or the
+//! typed gamma database) with RocksDB's backup engine before upgrades or
+//! risky re-indexes. Backups are incremental: SST files that didn't change
+//! since an earlier backup in the same directory are shared, not copied again.
+//!
+//! `polybot index restore` brings back a whole store, keeping the replaced
+//! one next to it, or only some column families: those are replaced in the
+//! live store and the others are left alone.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+use rocksdb::{Env, IngestExternalFileOptions, IteratorMode, Options, SstFileWriter, DB};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A backup in a backup directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupInfo {
+    pub id: u32,
+    pub created_at: DateTime<Utc>,
+    /// Size of all files of the backup, shared ones included
+    pub size: u64,
+    pub files: u32,
+}
+
+/// What a column family restore replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestoredColumnFamily {
+    pub name: String,
+    pub rows: usize,
+}
+
+/// A store and the directory its backups go into
+#[derive(Debug, Clone)]
+pub struct StoreBackups {
+    pub db_path: PathBuf,
+    pub backup_dir: PathBuf,
+}
+
+impl StoreBackups {
+    pub fn new(db_path: impl Into<PathBuf>, backup_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            db_path: db_path.into(),
+            backup_dir: backup_dir.into(),
+        }
+    }
+
+    /// Back up the store, then keep only the newest `keep` backups if given;
+    /// returns the new backup
+    pub fn create(&self, keep: Option<usize>) -> Result<BackupInfo> {
+        let db = open_store(&self.db_path)?;
+        let mut engine = self.engine()?;
+        engine
+            .create_new_backup_flush(&db, true)
+            .with_context(|| format!("Failed to back up {}", self.db_path.display()))?;
+        if let Some(keep) = keep {
+            engine.purge_old_backups(keep.max(1))?;
+        }
+        Self::infos(&engine)
+            .pop()
+            .context("The backup engine reported no backups")
+    }
+
+    /// Backups in the backup directory, oldest first
+    pub fn list(&self) -> Result<Vec<BackupInfo>> {
+        if !self.backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(Self::infos(&self.engine()?))
+    }
+
+    /// Replace the whole store with a backup (default: the newest); the
+    /// replaced store is moved to `<db_path>.pre-restore` (replacing an
+    /// earlier one), which is returned
+    pub fn restore_store(&self, backup_id: Option<u32>) -> Result<Option<PathBuf>> {
+        let db_path = &self.db_path;
+        let staging = sibling(db_path, "restoring");
+        self.restore_into(backup_id, &staging)?;
+
+        let previous = db_path.exists().then(|| sibling(db_path, "pre-restore"));
+        if let Some(previous) = &previous {
+            if previous.exists() {
+                fs::remove_dir_all(previous)?;
+            }
+            fs::rename(db_path, previous)
+                .with_context(|| format!("Failed to move {} aside", db_path.display()))?;
+        } else if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&staging, db_path).with_context(|| {
+            format!("Failed to move the restored store to {}", db_path.display())
+        })?;
+        Ok(previous)
+    }
+
+    /// Replace only the `column_families` of the store with their contents in
+    /// a backup (default: the newest)
+    pub fn restore_column_families(
+        &self,
+        backup_id: Option<u32>,
+        column_families: &[String],
+    ) -> Result<Vec<RestoredColumnFamily>> {
+        let staging = sibling(&self.db_path, "restoring");
+        self.restore_into(backup_id, &staging)?;
+        let result = copy_column_families(&staging, &self.db_path, column_families);
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    fn engine(&self) -> Result<BackupEngine> {
+        let backup_dir = &self.backup_dir;
+        fs::create_dir_all(backup_dir)
+            .with_context(|| format!("Failed to create {}", backup_dir.display()))?;
+        let options = BackupEngineOptions::new(backup_dir)?;
+        let env = Env::new()?;
+        BackupEngine::open(&options, &env)
+            .with_context(|| format!("Failed to open backups in {}", backup_dir.display()))
+    }
+
+    fn infos(engine: &BackupEngine) -> Vec<BackupInfo> {
+        let mut backups: Vec<BackupInfo> = engine
+            .get_backup_info()
+            .into_iter()
+            .map(|info| BackupInfo {
+                id: info.backup_id,
+                created_at: Utc
+                    .timestamp_opt(info.timestamp, 0)
+                    .single()
+                    .unwrap_or_default(),
+                size: info.size,
+                files: info.num_files,
+            })
+            .collect();
+        backups.sort_by_key(|backup| backup.id);
+        backups
+    }
+
+    /// Restore backup `backup_id` (default: the newest) into a new directory
+    /// `target`
+    fn restore_into(&self, backup_id: Option<u32>, target: &Path) -> Result<()> {
+        let backup_dir = &self.backup_dir;
+        let mut engine = self.engine()?;
+        let backups = Self::infos(&engine);
+        let id = match backup_id {
+            Some(id) if backups.iter().any(|backup| backup.id == id) => id,
+            Some(id) => bail!("No backup {} in {}", id, backup_dir.display()),
+            None => match backups.last() {
+                Some(backup) => backup.id,
+                None => bail!("No backups in {}", backup_dir.display()),
+            },
+        };
+        engine
+            .verify_backup(id)
+            .with_context(|| format!("Backup {} is damaged", id))?;
+        if target.exists() {
+            fs::remove_dir_all(target)?;
+        }
+        engine
+            .restore_from_backup(target, target, &RestoreOptions::default(), id)
+            .with_context(|| format!("Failed to restore backup {}", id))
+    }
+}
+
+/// Open the store at `db_path` with all its column families
+fn open_store(db_path: &Path) -> Result<DB> {
+    if !db_path.exists() {
+        bail!("No store at {}", db_path.display());
+    }
+    let mut opts = Options::default();
+    opts.create_missing_column_families(true);
+    let column_families = DB::list_cf(&opts, db_path)?;
+    DB::open_cf(&opts, db_path, &column_families).with_context(|| {
+        format!(
+            "Failed to open {}. Stop the processes using it (indexing, enrich, the TUIs) and retry",
+            db_path.display()
+        )
+    })
+}
+
+fn copy_column_families(
+    from: &Path,
+    db_path: &Path,
+    column_families: &[String],
+) -> Result<Vec<RestoredColumnFamily>> {
+    let opts = Options::default();
+    let available = DB::list_cf(&opts, from)?;
+    if let Some(missing) = column_families.iter().find(|cf| !available.contains(cf)) {
+        bail!(
+            "The backup has no column family '{}' (it has: {})",
+            missing,
+            available.join(", ")
+        );
+    }
+    let source = DB::open_cf_for_read_only(&opts, from, &available, false)?;
+    let mut target = open_store(db_path)?;
+
+    let mut restored = Vec::new();
+    for name in column_families {
+        // Dropping and recreating empties the column family in one step
+        if target.cf_handle(name).is_some() {
+            target.drop_cf(name)?;
+        }
+        target.create_cf(name, &Options::default())?;
+
+        let sst_path = db_path.join(format!("restore-{}.sst", name));
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&sst_path)?;
+        let mut rows = 0;
+        let source_cf = source
+            .cf_handle(name)
+            .with_context(|| format!("Column family '{}' vanished", name))?;
+        // Iteration is in key order, as SST files need
+        for item in source.iterator_cf(&source_cf, IteratorMode::Start) {
+            let (key, value) = item?;
+            writer.put(key, value)?;
+            rows += 1;
+        }
+        if rows > 0 {
+            writer.finish()?;
+            let mut ingest_opts = IngestExternalFileOptions::default();
+            ingest_opts.set_move_files(true);
+            let target_cf = target
+                .cf_handle(name)
+                .with_context(|| format!("Failed to recreate column family '{}'", name))?;
+            target.ingest_external_file_cf_opts(&target_cf, &ingest_opts, vec![&sst_path])?;
+        }
+        let _ = fs::remove_file(&sst_path);
+        restored.push(RestoredColumnFamily {
+            name: name.clone(),
+            rows,
+        });
+    }
+    Ok(restored)
+}
+
+/// `<db_path>.<suffix>` next to the store
+fn sibling(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(format!(".{}", suffix));
+    db_path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed_store::context::TypedDbContext;
+    use crate::typed_store::models::{
+        EnrichmentFreshness, EnrichmentFreshnessCf, ALL_COLUMN_FAMILIES,
+    };
+    use crate::typed_store::table::TypedCf;
+
+    #[test]
+    fn backups_restore_whole_stores_and_single_column_families() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        let backups = StoreBackups::new(&db_path, dir.path().join("backups"));
+        let put = |token: &str| {
+            let ctx = TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+            let entry = EnrichmentFreshness {
+                token_id: token.to_string(),
+                condition_id: None,
+                refreshed_at: Utc::now(),
+                dataset: "enriched".to_string(),
+                has_orderbook: true,
+            };
+            ctx.put::<EnrichmentFreshnessCf>(&entry.token_id, &entry)
+                .unwrap();
+        };
+        let tokens = || {
+            let ctx = TypedDbContext::open_read_only(&db_path).unwrap();
+            let rows = ctx.scan::<EnrichmentFreshnessCf>().unwrap();
+            rows.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
+        };
+
+        put("a");
+        let first = backups.create(None).unwrap();
+        put("b");
+        let second = backups.create(None).unwrap();
+        assert_eq!(backups.list().unwrap().len(), 2);
+        assert!(second.id > first.id);
+
+        put("c");
+        let previous = backups.restore_store(Some(first.id)).unwrap();
+        assert_eq!(tokens(), ["a"]);
+        assert!(previous.unwrap().exists());
+
+        put("d");
+        let restored = backups
+            .restore_column_families(None, &[EnrichmentFreshnessCf::NAME.to_string()])
+            .unwrap();
+        assert_eq!(restored[0].rows, 2);
+        assert_eq!(tokens(), ["a", "b"]);
+
+        let unknown = backups.restore_column_families(None, &["nope".to_string()]);
+        assert!(unknown.is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Scheduled order book snapshots
+//!
+//! `book-snapshots run` captures full books on a fixed interval and stores
+//! them here, keyed by token and capture time, so liquidity analysis can
+//! replay how a book looked over days without having streamed it.
+
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+use crate::typed_store::codec::{BincodeDbCodec, DbCodec};
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{BookSnapshot, BookSnapshotCf, ALL_COLUMN_FAMILIES};
+
+/// Key of a snapshot; zero-padded so a token's snapshots sort by time
+pub fn snapshot_key(token_id: &str, captured_at: DateTime<Utc>) -> String {
+    format!("{}:{:020}", token_id, captured_at.timestamp_millis())
+}
+
+/// Encoded prefix shared by every key of `token_id`
+fn token_prefix(token_id: &str) -> Result<Vec<u8>, DbContextError> {
+    let mut prefix = BincodeDbCodec::encode(&format!("{}:", token_id))?;
+    // Keys are encoded as JSON strings; drop the closing quote
+    prefix.pop();
+    Ok(prefix)
+}
+
+/// Store one capture round in a single batch
+pub fn record(db_path: &Path, snapshots: &[BookSnapshot]) -> Result<(), DbContextError> {
+    let ctx = TypedDbContext::open(db_path, ALL_COLUMN_FAMILIES.to_vec())?;
+    ctx.batch_write(|batch| {
+        for snapshot in snapshots {
+            let key = snapshot_key(&snapshot.token_id, snapshot.captured_at);
+            batch.put::<BookSnapshotCf>(&key, snapshot)?;
+        }
+        Ok(())
+    })
+}
+
+/// Snapshots of `token_id` captured at or after `since`, oldest first
+///
+/// A store that doesn't exist or predates snapshots has none.
+pub fn load(
+    db_path: &Path,
+    token_id: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<BookSnapshot>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let snapshots = match ctx.scan_prefix::<BookSnapshotCf>(&token_prefix(token_id)?) {
+        Ok(entries) => entries.into_iter().map(|(_, snapshot)| snapshot),
+        Err(DbContextError::ColumnFamilyNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(snapshots
+        .filter(|snapshot| since.map_or(true, |since| snapshot.captured_at >= since))
+        .collect())
+}
+
+/// How many snapshots of a token are stored, and over which period
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenSnapshots {
+    pub token_id: String,
+    pub count: usize,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+/// Stored snapshots per token, ordered by token id
+pub fn summary(db_path: &Path) -> Result<Vec<TokenSnapshots>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let entries = match ctx.scan::<BookSnapshotCf>() {
+        Ok(entries) => entries,
+        Err(DbContextError::ColumnFamilyNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut tokens: Vec<TokenSnapshots> = Vec::new();
+    // Entries come sorted by key, so each token's snapshots are adjacent
+    for (_, snapshot) in entries {
+        match tokens.last_mut() {
+            Some(token) if token.token_id == snapshot.token_id => {
+                token.count += 1;
+                token.last = snapshot.captured_at;
+            }
+            _ => tokens.push(TokenSnapshots {
+                token_id: snapshot.token_id,
+                count: 1,
+                first: snapshot.captured_at,
+                last: snapshot.captured_at,
+            }),
+        }
+    }
+    tokens.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed_store::models::BookSnapshotLevel;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn snapshots_load_per_token_in_time_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        assert!(load(&db_path, "1", None).unwrap().is_empty());
+
+        let start = Utc::now();
+        let snapshot = |token_id: &str, minutes: i64| BookSnapshot {
+            token_id: token_id.to_string(),
+            condition_id: Some("0xmarket".to_string()),
+            captured_at: start + Duration::minutes(minutes),
+            bids: vec![BookSnapshotLevel {
+                price: dec!(0.48),
+                size: dec!(100),
+            }],
+            asks: vec![BookSnapshotLevel {
+                price: dec!(0.52),
+                size: dec!(80),
+            }],
+        };
+        record(&db_path, &[snapshot("1", 5), snapshot("12", 0)]).unwrap();
+        record(&db_path, &[snapshot("1", 0), snapshot("1", 10)]).unwrap();
+
+        // "1" must not pick up the snapshots of "12"
+        let books = load(&db_path, "1", None).unwrap();
+        let minutes: Vec<i64> = books
+            .iter()
+            .map(|b| (b.captured_at - start).num_minutes())
+            .collect();
+        assert_eq!(minutes, [0, 5, 10]);
+        assert_eq!(books[0].spread(), Some(dec!(0.04)));
+
+        let recent = load(&db_path, "1", Some(start + Duration::minutes(5))).unwrap();
+        assert_eq!(recent.len(), 2);
+
+        let counts: Vec<(String, usize)> = summary(&db_path)
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.token_id, token.count))
+            .collect();
+        assert_eq!(counts, [("1".to_string(), 3), ("12".to_string(), 1)]);
+    }
+}
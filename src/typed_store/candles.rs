@@ -0,0 +1,275 @@
+//! OHLCV candles and their rolling statistics
+//!
+//! Candles are built from the trades of stream captures, when a capture stops
+//! recording or is imported (see `markets::datasets::backfill`), and stored
+//! here keyed by token, interval and start time. Captures of overlapping
+//! periods merge into the same candles, so a token's history runs on across
+//! live and imported recordings; a candle keeps what each capture saw of it,
+//! so captures of separate parts of its interval add their volume up. Each
+//! candle also gets the statistics of the `ROLLING_WINDOW` candles ending with
+//! it, recomputed whenever earlier candles change.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::typed_store::codec::{BincodeDbCodec, DbCodec};
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{
+    Candle, CandleCf, CandleInterval, CandleStats, CandleStatsCf, ALL_COLUMN_FAMILIES,
+};
+
+/// Candles the rolling statistics cover
+pub const ROLLING_WINDOW: usize = 20;
+
+/// Key of a candle and of its statistics; zero-padded so a series sorts by time
+pub fn candle_key(token_id: &str, interval: CandleInterval, start: DateTime<Utc>) -> String {
+    format!("{}:{}:{:020}", token_id, interval, start.timestamp_millis())
+}
+
+/// Encoded prefix shared by every key of one token's series
+fn series_prefix(token_id: &str, interval: CandleInterval) -> Result<Vec<u8>, DbContextError> {
+    let mut prefix = BincodeDbCodec::encode(&format!("{}:{}:", token_id, interval))?;
+    // Keys are encoded as JSON strings; drop the closing quote
+    prefix.pop();
+    Ok(prefix)
+}
+
+/// What a backfill wrote
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackfillCounts {
+    /// Series (token and interval) touched
+    pub series: usize,
+    /// Candles new or changed by the merge
+    pub candles: usize,
+    /// Statistics recomputed
+    pub stats: usize,
+}
+
+/// Merge `candles` into the stored series and recompute the rolling
+/// statistics from the first changed candle on
+pub fn backfill(db_path: &Path, candles: Vec<Candle>) -> Result<BackfillCounts, DbContextError> {
+    let mut incoming: BTreeMap<(String, CandleInterval), Vec<Candle>> = BTreeMap::new();
+    for candle in candles {
+        incoming
+            .entry((candle.token_id.clone(), candle.interval))
+            .or_default()
+            .push(candle);
+    }
+
+    let ctx = TypedDbContext::open(db_path, ALL_COLUMN_FAMILIES.to_vec())?;
+    let mut counts = BackfillCounts::default();
+    for ((token_id, interval), candles) in incoming {
+        let mut series: BTreeMap<DateTime<Utc>, Candle> = ctx
+            .scan_prefix::<CandleCf>(&series_prefix(&token_id, interval)?)?
+            .into_iter()
+            .map(|(_, candle)| (candle.start, candle))
+            .collect();
+        let mut changed = BTreeSet::new();
+        for candle in candles {
+            match series.get_mut(&candle.start) {
+                Some(stored) => {
+                    let before = stored.clone();
+                    stored.merge(&candle);
+                    if *stored != before {
+                        changed.insert(candle.start);
+                    }
+                }
+                None => {
+                    changed.insert(candle.start);
+                    series.insert(candle.start, candle);
+                }
+            }
+        }
+        let Some(first_changed) = changed.first().copied() else {
+            continue;
+        };
+
+        let series: Vec<Candle> = series.into_values().collect();
+        let from = series.partition_point(|candle| candle.start < first_changed);
+        let stats = rolling_stats(&series, from);
+        ctx.batch_write(|batch| {
+            for candle in series.iter().filter(|c| changed.contains(&c.start)) {
+                batch.put::<CandleCf>(&candle_key(&token_id, interval, candle.start), candle)?;
+            }
+            for stat in &stats {
+                batch.put::<CandleStatsCf>(&candle_key(&token_id, interval, stat.start), stat)?;
+            }
+            Ok(())
+        })?;
+        counts.series += 1;
+        counts.candles += changed.len();
+        counts.stats += stats.len();
+    }
+    Ok(counts)
+}
+
+/// Statistics of each candle of `series` from index `from` on, over the
+/// `ROLLING_WINDOW` candles ending with it
+pub fn rolling_stats(series: &[Candle], from: usize) -> Vec<CandleStats> {
+    (from..series.len())
+        .map(|index| {
+            let window = &series[(index + 1).saturating_sub(ROLLING_WINDOW)..=index];
+            let candle = &series[index];
+            let count = Decimal::from(window.len());
+            let volume: Decimal = window.iter().map(|c| c.volume).sum();
+            let notional: Decimal = window.iter().map(|c| c.notional).sum();
+            let returns: Vec<f64> = window
+                .windows(2)
+                .filter_map(|pair| {
+                    let (previous, close) = (pair[0].close.to_f64()?, pair[1].close.to_f64()?);
+                    (previous > 0.0).then(|| close / previous - 1.0)
+                })
+                .collect();
+            let volatility = (returns.len() >= 2).then(|| {
+                let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+                let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+                    / (returns.len() - 1) as f64;
+                variance.sqrt()
+            });
+            CandleStats {
+                token_id: candle.token_id.clone(),
+                interval: candle.interval,
+                start: candle.start,
+                candles: window.len(),
+                sma: window.iter().map(|c| c.close).sum::<Decimal>() / count,
+                vwap: (!volume.is_zero()).then(|| notional / volume),
+                volume,
+                volatility,
+            }
+        })
+        .collect()
+}
+
+/// Candles of `token_id` starting in `[since, until)`, oldest first, each with
+/// its rolling statistics
+///
+/// A store that doesn't exist or predates candles has none.
+pub fn load(
+    db_path: &Path,
+    token_id: &str,
+    interval: CandleInterval,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Result<Vec<(Candle, Option<CandleStats>)>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let prefix = series_prefix(token_id, interval)?;
+    let candles = match ctx.scan_prefix::<CandleCf>(&prefix) {
+        Ok(entries) => entries,
+        Err(DbContextError::ColumnFamilyNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut stats: BTreeMap<String, CandleStats> = BTreeMap::new();
+    match ctx.scan_prefix::<CandleStatsCf>(&prefix) {
+        Ok(entries) => stats.extend(entries),
+        Err(DbContextError::ColumnFamilyNotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+    Ok(candles
+        .into_iter()
+        .filter(|(_, candle)| {
+            since.is_none_or(|since| candle.start >= since)
+                && until.is_none_or(|until| candle.start < until)
+        })
+        .map(|(key, candle)| (candle, stats.remove(&key)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn overlapping_backfills_merge_and_restate_later_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        let interval = CandleInterval::OneMinute;
+        assert!(load(&db_path, "1", interval, None, None)
+            .unwrap()
+            .is_empty());
+
+        let start = interval.bucket(Utc::now());
+        let at =
+            |minutes: i64, secs: i64| start + Duration::minutes(minutes) + Duration::seconds(secs);
+        let candle = |minutes: i64, price, size| {
+            Candle::new("1", interval, at(minutes, 10), price, Some(size))
+        };
+
+        // Live recording of minutes 0-2
+        let counts = backfill(
+            &db_path,
+            vec![
+                candle(0, dec!(0.50), dec!(10)),
+                candle(1, dec!(0.55), dec!(10)),
+                candle(2, dec!(0.60), dec!(10)),
+            ],
+        )
+        .unwrap();
+        assert_eq!((counts.series, counts.candles, counts.stats), (1, 3, 3));
+
+        // An imported recording saw minute 1 from earlier on, with more trades
+        let mut earlier = Candle::new("1", interval, at(1, 2), dec!(0.52), Some(dec!(5)));
+        earlier.update(at(1, 10), dec!(0.55), Some(dec!(10)));
+        let counts = backfill(&db_path, vec![earlier.clone()]).unwrap();
+        assert_eq!((counts.candles, counts.stats), (1, 2));
+        // Backfilling the same recording again changes nothing
+        assert_eq!(backfill(&db_path, vec![earlier]).unwrap().candles, 0);
+
+        let series = load(&db_path, "1", interval, None, None).unwrap();
+        assert_eq!(series.len(), 3);
+        let (minute, stats) = &series[1];
+        assert_eq!((minute.open, minute.close), (dec!(0.52), dec!(0.55)));
+        assert_eq!((minute.volume, minute.trades), (dec!(15), 2));
+        let stats = stats.as_ref().unwrap();
+        assert_eq!(stats.candles, 2);
+        assert_eq!(stats.volume, dec!(25));
+        let last = series[2].1.as_ref().unwrap();
+        assert_eq!(last.sma, dec!(0.55));
+        assert!(last.volatility.is_some());
+
+        let since = load(&db_path, "1", interval, Some(at(1, 0)), Some(at(2, 0))).unwrap();
+        assert_eq!(since.len(), 1);
+        assert!(load(&db_path, "1", CandleInterval::OneHour, None, None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn captures_of_separate_parts_of_an_interval_add_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        let interval = CandleInterval::FiveMinutes;
+        let start = interval.bucket(Utc::now());
+        let at = |secs: i64| start + Duration::seconds(secs);
+
+        // A stream stopped a minute in and restarted three minutes later
+        let mut before = Candle::new("1", interval, at(10), dec!(0.40), Some(dec!(10)));
+        before.update(at(50), dec!(0.41), Some(dec!(5)));
+        before.covering(at(0) - Duration::minutes(30), at(60));
+        let mut after = Candle::new("1", interval, at(200), dec!(0.45), Some(dec!(20)));
+        after.covering(at(180), at(600));
+        backfill(&db_path, vec![before.clone()]).unwrap();
+        backfill(&db_path, vec![after.clone()]).unwrap();
+
+        let series = load(&db_path, "1", interval, None, None).unwrap();
+        let candle = &series[0].0;
+        assert_eq!((candle.volume, candle.trades), (dec!(35), 3));
+        assert_eq!((candle.open, candle.close), (dec!(0.40), dec!(0.45)));
+        assert_eq!(candle.recordings.len(), 2);
+        assert_eq!(candle.recordings[0].from, start);
+        assert_eq!(candle.recordings[1].to, start + Duration::minutes(5));
+
+        // Either capture again, or one spanning both with fewer trades, changes nothing
+        assert_eq!(backfill(&db_path, vec![after]).unwrap().candles, 0);
+        let mut spanning = Candle::new("1", interval, at(30), dec!(0.40), Some(dec!(1)));
+        spanning.covering(at(0), at(300));
+        assert_eq!(backfill(&db_path, vec![spanning]).unwrap().candles, 0);
+    }
+}
@@ -0,0 +1,340 @@
+//! Compatibility with the JSON file store hierarchy
+//!
+//! Market data used to be written twice: as a JSON hierarchy under
+//! `<db>/markets/{condition,market,token}/` by `markets::file_store`, and
+//! into RocksDB by the typed indexer. RocksDB is now the single source of
+//! truth. This module imports an existing hierarchy (split or consolidated
+//! layout) into the column families, and serves reads addressed by the old
+//! relative paths from RocksDB so tools that still think in files keep working.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::markets::clob::fetcher::Market;
+use crate::markets::file_store::{render_market_files, FileLayout};
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{
+    Condition, ConditionCf, MarketByConditionCf, MarketCf, RocksDbMarket, Token, TokenIndexCf,
+    TokensByConditionCf,
+};
+
+/// Markets written per RocksDB batch during an import
+const IMPORT_BATCH: usize = 1000;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("I/O error reading file store: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Database error: {0}")]
+    Db(#[from] DbContextError),
+    #[error("Not a file store (no markets/condition directory): {0}")]
+    NotAFileStore(PathBuf),
+}
+
+/// What an import wrote
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub markets: usize,
+    pub conditions: usize,
+    pub tokens: usize,
+    pub duplicates: usize,
+    /// Condition directories whose `market.json` could not be parsed
+    pub unreadable: Vec<PathBuf>,
+}
+
+/// Import every market under `base/markets/condition/` into `ctx`
+///
+/// Each condition directory's `market.json` holds either the market itself
+/// (split layout) or a `{market, metadata, tokens}` document (consolidated
+/// layout); the derived views (`metadata.json`, `token/`, `market/`) are
+/// rebuilt from it rather than read. With `skip_duplicates`, markets whose id
+/// is already in [`MarketCf`] are left untouched.
+pub fn import_file_store(
+    base: &Path,
+    ctx: &TypedDbContext,
+    skip_duplicates: bool,
+) -> Result<ImportReport, ImportError> {
+    let conditions_dir = base.join("markets").join("condition");
+    if !conditions_dir.is_dir() {
+        return Err(ImportError::NotAFileStore(base.to_path_buf()));
+    }
+
+    let mut report = ImportReport::default();
+    let mut conditions: HashMap<String, Condition> = HashMap::new();
+    let mut tokens_by_condition: HashMap<String, Vec<Token>> = HashMap::new();
+    let mut batch: Vec<(String, RocksDbMarket)> = Vec::with_capacity(IMPORT_BATCH);
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(&conditions_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    entries.sort();
+
+    for dir in entries {
+        let path = dir.join("market.json");
+        let market = match read_market(&path) {
+            Some(market) => market,
+            None => {
+                warn!("⚠️ Skipping unreadable {}", path.display());
+                report.unreadable.push(path);
+                continue;
+            }
+        };
+
+        let Some(condition_id) = market
+            .condition_id
+            .clone()
+            .filter(|id| !id.trim().is_empty())
+        else {
+            report.unreadable.push(path);
+            continue;
+        };
+        let market_id = match &market.id {
+            Some(id) if !id.trim().is_empty() => id.clone(),
+            _ => format!("market_{}", condition_id),
+        };
+
+        if skip_duplicates && ctx.exists::<MarketCf>(&market_id)? {
+            debug!("Skipping duplicate market {}", market_id);
+            report.duplicates += 1;
+            continue;
+        }
+
+        let market = RocksDbMarket::from(market);
+        if let Some(condition) = market.extract_condition() {
+            conditions
+                .entry(condition_id.clone())
+                .and_modify(|existing| existing.market_count += 1)
+                .or_insert(condition);
+        }
+        tokens_by_condition
+            .entry(condition_id)
+            .or_default()
+            .extend(market.extract_tokens());
+
+        batch.push((market_id, market));
+        if batch.len() >= IMPORT_BATCH {
+            report.markets += write_markets(ctx, &mut batch)?;
+        }
+    }
+    report.markets += write_markets(ctx, &mut batch)?;
+
+    ctx.batch_write(|batch| {
+        for condition in conditions.values() {
+            batch.put::<ConditionCf>(&condition.id, condition)?;
+        }
+        for (condition_id, tokens) in &tokens_by_condition {
+            batch.put::<TokensByConditionCf>(condition_id, tokens)?;
+        }
+        Ok(())
+    })?;
+    report.conditions = conditions.len();
+    report.tokens = tokens_by_condition.values().map(Vec::len).sum();
+
+    Ok(report)
+}
+
+fn read_market(path: &Path) -> Option<Market> {
+    let contents = fs::read(path).ok()?;
+    let mut value: serde_json::Value = serde_json::from_slice(&contents).ok()?;
+    // Consolidated layout nests the market next to its derived views
+    if value.get("market").is_some_and(|m| m.is_object()) && value.get("metadata").is_some() {
+        value = value["market"].take();
+    }
+    Market::from_value(value).ok()
+}
+
+fn write_markets(
+    ctx: &TypedDbContext,
+    batch: &mut Vec<(String, RocksDbMarket)>,
+) -> Result<usize, DbContextError> {
+    if batch.is_empty() {
+        return Ok(0);
+    }
+    ctx.batch_write(|writer| {
+        for (market_id, market) in batch.iter() {
+            market.put_into(writer, market_id)?;
+        }
+        Ok(())
+    })?;
+    let written = batch.len();
+    batch.clear();
+    Ok(written)
+}
+
+/// Serves file store paths from RocksDB
+///
+/// Paths are relative to the old store's base directory, e.g.
+/// `markets/condition/<condition_id>/metadata.json` or
+/// `markets/token/<token_id>/data.json`. Contents are rendered the way the
+/// file store wrote them (split layout).
+pub struct LegacyPathReader<'a> {
+    ctx: &'a TypedDbContext,
+}
+
+impl<'a> LegacyPathReader<'a> {
+    pub fn new(ctx: &'a TypedDbContext) -> Self {
+        Self { ctx }
+    }
+
+    /// Contents of the file at `path`, or `None` if the store has no such file
+    pub fn read(&self, path: impl AsRef<Path>) -> anyhow::Result<Option<Vec<u8>>> {
+        let parts: Vec<&str> = path
+            .as_ref()
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        let market = match parts.as_slice() {
+            ["markets", "condition", condition_id, _] => self
+                .ctx
+                .get::<MarketByConditionCf>(&condition_id.to_string())?,
+            ["markets", "market", market_id, _] => {
+                self.ctx.get::<MarketCf>(&market_id.to_string())?
+            }
+            ["markets", "token", token_id, _] => {
+                match self.ctx.get::<TokenIndexCf>(&token_id.to_string())? {
+                    Some(condition_id) => self.ctx.get::<MarketByConditionCf>(&condition_id)?,
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+        let Some(market) = market else {
+            return Ok(None);
+        };
+
+        let wanted: PathBuf = parts.iter().collect();
+        let files = render_market_files(&Market::from(market), FileLayout::Split)?;
+        Ok(files
+            .into_iter()
+            .find(|file| file.path == wanted)
+            .map(|file| file.contents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::file_store::{FileStore, FileStoreOptions};
+    use crate::typed_store::models::{ConditionIndexCf, ALL_COLUMN_FAMILIES};
+
+    fn fixture_markets() -> Vec<Market> {
+        let page: serde_json::Value = serde_json::from_str(include_str!(
+            "../../tests/fixtures/rest/markets_page_1.json"
+        ))
+        .unwrap();
+        page["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| Market::from_value(m.clone()).unwrap())
+            .collect()
+    }
+
+    #[allow(deprecated)]
+    fn write_store(dir: &Path, layout: FileLayout, markets: &[Market]) {
+        let options = FileStoreOptions {
+            layout,
+            ..FileStoreOptions::default()
+        };
+        let store = FileStore::with_options(dir.to_path_buf(), options).unwrap();
+        for market in markets {
+            store.store_market(market).unwrap();
+        }
+    }
+
+    #[test]
+    fn import_fills_column_families_from_either_layout() {
+        let markets = fixture_markets();
+        for layout in [FileLayout::Split, FileLayout::Consolidated] {
+            let files = tempfile::tempdir().unwrap();
+            let db = tempfile::tempdir().unwrap();
+            write_store(files.path(), layout, &markets);
+
+            let ctx = TypedDbContext::open(db.path(), ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+            let report = import_file_store(files.path(), &ctx, true).unwrap();
+            assert_eq!(report.markets, markets.len());
+            assert!(report.unreadable.is_empty());
+
+            for market in &markets {
+                // CLOB markets carry no id; the indexer keys them by condition
+                let condition_id = market.condition_id.clone().unwrap();
+                let stored = ctx
+                    .get::<MarketCf>(&format!("market_{}", condition_id))
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(stored.question, market.question);
+                assert!(ctx.exists::<ConditionCf>(&condition_id).unwrap());
+                assert_eq!(
+                    ctx.get::<ConditionIndexCf>(&condition_id)
+                        .unwrap()
+                        .map(|ids| ids.len()),
+                    Some(market.tokens.len())
+                );
+                for token in &market.tokens {
+                    assert_eq!(
+                        ctx.get::<TokenIndexCf>(&token.token_id).unwrap(),
+                        Some(condition_id.clone())
+                    );
+                }
+            }
+
+            // A second import finds everything already present
+            let again = import_file_store(files.path(), &ctx, true).unwrap();
+            assert_eq!((again.markets, again.duplicates), (0, markets.len()));
+        }
+    }
+
+    #[test]
+    fn legacy_paths_read_back_what_the_file_store_wrote() {
+        let markets = fixture_markets();
+        let files = tempfile::tempdir().unwrap();
+        let db = tempfile::tempdir().unwrap();
+        write_store(files.path(), FileLayout::Split, &markets);
+
+        let ctx = TypedDbContext::open(db.path(), ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+        import_file_store(files.path(), &ctx, true).unwrap();
+        let reader = LegacyPathReader::new(&ctx);
+
+        let mut served_files = 0;
+        let mut dirs = vec![files.path().to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let relative = path.strip_prefix(files.path()).unwrap();
+                let on_disk: serde_json::Value =
+                    serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+                let served: serde_json::Value =
+                    serde_json::from_slice(&reader.read(relative).unwrap().unwrap()).unwrap();
+                assert_eq!(served, on_disk, "{}", relative.display());
+                served_files += 1;
+            }
+        }
+        assert!(served_files > markets.len());
+
+        let condition = format!(
+            "markets/condition/{}",
+            markets[0].condition_id.as_ref().unwrap()
+        );
+        assert!(reader
+            .read(format!("{}/unknown.json", condition))
+            .unwrap()
+            .is_none());
+        assert!(reader
+            .read("markets/condition/0xmissing/market.json")
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,9 +1,19 @@
 //! TypedDbContext implementation for type-safe RocksDB operations
 //!
 //! Provides type-safe database operations with column families and codecs
-
-use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, WriteBatch, DB};
+//!
+//! Full re-indexes open the store with [`TypedDbContext::open_bulk_load`]:
+//! compactions wait until the load is done, batches skip the write-ahead log,
+//! and whole column families can be written as one SST file with
+//! [`TypedDbContext::ingest`]. Nothing written that way is durable until
+//! [`TypedDbContext::finish_bulk_load`] flushes it.
+
+use rocksdb::{
+    ColumnFamilyDescriptor, IngestExternalFileOptions, IteratorMode, Options, SstFileWriter,
+    WriteBatch, WriteOptions, DB,
+};
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -22,9 +32,17 @@ pub enum DbContextError {
     ColumnFamilyNotFound(String),
 }
 
+/// Rows per write batch for bulk loads
+pub const BULK_BATCH_SIZE: usize = 10_000;
+
 /// Database context providing type-safe operations with column families
 pub struct TypedDbContext {
     db: Arc<DB>,
+    column_families: Vec<String>,
+    /// Opened with [`TypedDbContext::open_bulk_load`]
+    bulk_load: bool,
+    /// SST files written by [`TypedDbContext::ingest`], for unique names
+    ingested: AtomicUsize,
 }
 
 impl TypedDbContext {
@@ -32,6 +50,26 @@ impl TypedDbContext {
     pub fn open<P: AsRef<Path>>(
         path: P,
         column_families: Vec<&'static str>,
+    ) -> Result<Self, DbContextError> {
+        Self::open_with(path, column_families, false)
+    }
+
+    /// Open database for a bulk load: auto compactions are off, batches skip
+    /// the write-ahead log; call [`TypedDbContext::finish_bulk_load`] when done
+    ///
+    /// Other processes can't open the database meanwhile, so this is meant
+    /// for full re-indexes and imports.
+    pub fn open_bulk_load<P: AsRef<Path>>(
+        path: P,
+        column_families: Vec<&'static str>,
+    ) -> Result<Self, DbContextError> {
+        Self::open_with(path, column_families, true)
+    }
+
+    fn open_with<P: AsRef<Path>>(
+        path: P,
+        column_families: Vec<&'static str>,
+        bulk_load: bool,
     ) -> Result<Self, DbContextError> {
         let mut opts = Options::default();
         opts.create_if_missing(true);
@@ -40,6 +78,9 @@ impl TypedDbContext {
         opts.set_use_fsync(false);
         opts.set_bytes_per_sync(8388608);
         opts.optimize_for_point_lookup(1024);
+        if bulk_load {
+            opts.prepare_for_bulk_load();
+        }
 
         // Create column family descriptors
         let cf_descriptors: Vec<ColumnFamilyDescriptor> = column_families
@@ -47,13 +88,47 @@ impl TypedDbContext {
             .map(|&name| {
                 let mut cf_opts = Options::default();
                 cf_opts.optimize_for_point_lookup(1024);
+                if bulk_load {
+                    cf_opts.prepare_for_bulk_load();
+                }
                 ColumnFamilyDescriptor::new(name, cf_opts)
             })
             .collect();
 
         let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)?;
 
-        Ok(Self { db: Arc::new(db) })
+        Ok(Self {
+            db: Arc::new(db),
+            column_families: column_families
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            bulk_load,
+            ingested: AtomicUsize::new(0),
+        })
+    }
+
+    /// Open an existing database for reading with whichever column families it has
+    ///
+    /// Read-only handles don't take the database lock, so this works while
+    /// another process (e.g. a running `enrich`) has the store open. Column
+    /// families the database doesn't have yet report [`DbContextError::ColumnFamilyNotFound`].
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, DbContextError> {
+        let opts = Options::default();
+        let column_families = DB::list_cf(&opts, &path)?;
+        let db = DB::open_cf_for_read_only(&opts, &path, &column_families, false)?;
+
+        Ok(Self {
+            db: Arc::new(db),
+            column_families,
+            bulk_load: false,
+            ingested: AtomicUsize::new(0),
+        })
+    }
+
+    /// Whether the database was opened for a bulk load
+    pub fn is_bulk_load(&self) -> bool {
+        self.bulk_load
     }
 
     /// Put a key-value pair in the specified column family
@@ -116,7 +191,6 @@ impl TypedDbContext {
     }
 
     /// Scan with key prefix in the specified column family
-    #[allow(dead_code)]
     pub fn scan_prefix<CF: TypedCf>(
         &self,
         prefix: &[u8],
@@ -143,6 +217,31 @@ impl TypedDbContext {
         Ok(results)
     }
 
+    /// Visit the column family in key order, skipping the first `skip` entries
+    /// without decoding them; stops early when `visit` returns false
+    pub fn scan_while<CF: TypedCf>(
+        &self,
+        skip: usize,
+        mut visit: impl FnMut(CF::Key, CF::Value) -> bool,
+    ) -> Result<(), DbContextError> {
+        let cf = self.get_cf_handle::<CF>()?;
+        let iter = self.db.iterator_cf(&cf, IteratorMode::Start);
+
+        for (index, item) in iter.enumerate() {
+            let (key_bytes, value_bytes) = item?;
+            if index < skip {
+                continue;
+            }
+            let key = CF::KeyCodec::decode(&key_bytes)?;
+            let value = CF::ValueCodec::decode(&value_bytes)?;
+            if !visit(key, value) {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Perform a batch write operation
     pub fn batch_write<F>(&self, f: F) -> Result<(), DbContextError>
     where
@@ -151,7 +250,76 @@ impl TypedDbContext {
         let mut batch = WriteBatch::default();
         let mut writer = TypedBatchWriter::new(&mut batch, &self.db);
         f(&mut writer)?;
-        self.db.write(batch)?;
+        if self.bulk_load {
+            let mut write_opts = WriteOptions::default();
+            write_opts.disable_wal(true);
+            self.db.write_opt(batch, &write_opts)?;
+        } else {
+            self.db.write(batch)?;
+        }
+        Ok(())
+    }
+
+    /// Write `rows` into the column family as one SST file and ingest it,
+    /// bypassing memtables; rows replace stored ones with the same key, and
+    /// for repeated keys the last row wins. Returns the rows written
+    pub fn ingest<CF: TypedCf>(
+        &self,
+        rows: &[(CF::Key, CF::Value)],
+    ) -> Result<usize, DbContextError> {
+        let cf = self.get_cf_handle::<CF>()?;
+        let mut encoded = rows
+            .iter()
+            .map(|(key, value)| Ok((CF::KeyCodec::encode(key)?, CF::ValueCodec::encode(value)?)))
+            .collect::<Result<Vec<_>, CodecError>>()?;
+        // SST files need strictly increasing keys; the stable sort keeps the
+        // rows of a key in order, so the last one survives the dedup
+        encoded.sort_by(|a, b| a.0.cmp(&b.0));
+        encoded.reverse();
+        encoded.dedup_by(|later, earlier| later.0 == earlier.0);
+        encoded.reverse();
+        if encoded.is_empty() {
+            return Ok(0);
+        }
+
+        let number = self.ingested.fetch_add(1, Ordering::Relaxed);
+        let sst_path = self
+            .db
+            .path()
+            .join(format!("bulk-{}-{}.sst", CF::NAME, number));
+        let opts = Options::default();
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&sst_path)?;
+        for (key, value) in &encoded {
+            writer.put(key, value)?;
+        }
+        writer.finish()?;
+
+        let mut ingest_opts = IngestExternalFileOptions::default();
+        ingest_opts.set_move_files(true);
+        let result = self
+            .db
+            .ingest_external_file_cf_opts(&cf, &ingest_opts, vec![&sst_path]);
+        // Moved into the database unless ingestion failed
+        let _ = std::fs::remove_file(&sst_path);
+        result?;
+        Ok(encoded.len())
+    }
+
+    /// Flush what a bulk load wrote and compact it; does nothing unless the
+    /// database was opened with [`TypedDbContext::open_bulk_load`]
+    pub fn finish_bulk_load(&self) -> Result<(), DbContextError> {
+        if !self.bulk_load {
+            return Ok(());
+        }
+        for name in &self.column_families {
+            let cf = self
+                .db
+                .cf_handle(name)
+                .ok_or_else(|| DbContextError::ColumnFamilyNotFound(name.clone()))?;
+            self.db.flush_cf(&cf)?;
+            self.db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        }
         Ok(())
     }
 
@@ -206,3 +374,50 @@ impl<'a> TypedBatchWriter<'a> {
 }
 
 // Tests are moved to test/ directory following project conventions
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed_store::models::{Condition, ConditionCf, ALL_COLUMN_FAMILIES};
+
+    fn condition(id: &str, market_count: usize) -> Condition {
+        Condition {
+            id: id.to_string(),
+            question: format!("Question {}", id),
+            description: None,
+            category: None,
+            tags: None,
+            outcomes: None,
+            creator: None,
+            created_at: None,
+            market_count,
+        }
+    }
+
+    #[test]
+    fn bulk_loads_ingest_rows_and_survive_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let ctx =
+                TypedDbContext::open_bulk_load(dir.path(), ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+            ctx.batch_write(|batch| batch.put::<ConditionCf>(&"c".to_string(), &condition("c", 1)))
+                .unwrap();
+            // Unsorted, with a repeated key whose last row wins
+            let rows = vec![
+                ("b".to_string(), condition("b", 1)),
+                ("a".to_string(), condition("a", 1)),
+                ("b".to_string(), condition("b", 2)),
+            ];
+            assert_eq!(ctx.ingest::<ConditionCf>(&rows).unwrap(), 2);
+            ctx.finish_bulk_load().unwrap();
+        }
+
+        let ctx = TypedDbContext::open_read_only(dir.path()).unwrap();
+        let stored = ctx.scan::<ConditionCf>().unwrap();
+        let counts: Vec<_> = stored
+            .iter()
+            .map(|(id, condition)| (id.as_str(), condition.market_count))
+            .collect();
+        assert_eq!(counts, [("a", 1), ("b", 2), ("c", 1)]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Enrichment freshness tracking
+//!
+//! `enrich` records per token when its order book and liquidity snapshot was
+//! last taken. `enrich --stale-only`, `analyze --max-enrichment-age` and the
+//! canvas' Tokens pane read it back so decisions aren't made on stale liquidity.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::data_paths::DataPaths;
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{EnrichmentFreshness, EnrichmentFreshnessCf, ALL_COLUMN_FAMILIES};
+
+/// Age after which enrichment data counts as stale unless told otherwise
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The profile's typed RocksDB store
+pub fn default_db_path(data_paths: &DataPaths) -> PathBuf {
+    data_paths.root().join("database").join("rocksdb")
+}
+
+/// Store `entries`, replacing earlier refreshes of the same tokens
+pub fn record(db_path: &Path, entries: &[EnrichmentFreshness]) -> Result<(), DbContextError> {
+    let ctx = TypedDbContext::open(db_path, ALL_COLUMN_FAMILIES.to_vec())?;
+    ctx.batch_write(|batch| {
+        for entry in entries {
+            batch.put::<EnrichmentFreshnessCf>(&entry.token_id, entry)?;
+        }
+        Ok(())
+    })
+}
+
+/// Last refresh of every tracked token
+///
+/// A store that doesn't exist or predates freshness tracking has no entries.
+pub fn load(db_path: &Path) -> Result<HashMap<String, EnrichmentFreshness>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    match ctx.scan::<EnrichmentFreshnessCf>() {
+        Ok(entries) => Ok(entries.into_iter().collect()),
+        Err(DbContextError::ColumnFamilyNotFound(_)) => Ok(HashMap::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether a token last refreshed at `entry` is older than `max_age`; never refreshed counts as stale
+pub fn is_stale(
+    entry: Option<&EnrichmentFreshness>,
+    max_age: Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(entry) = entry else {
+        return true;
+    };
+    let max_age = chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+    now - entry.refreshed_at > max_age
+}
+
+/// Short age like `45s`, `12m`, `3h` or `2d`
+pub fn format_age(age: chrono::Duration) -> String {
+    let secs = age.num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_refreshes_decide_staleness() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        assert!(load(&db_path).unwrap().is_empty());
+
+        let now = Utc::now();
+        let entry = |token_id: &str, hours: i64| EnrichmentFreshness {
+            token_id: token_id.to_string(),
+            condition_id: Some("0xabc".to_string()),
+            refreshed_at: now - chrono::Duration::hours(hours),
+            dataset: "enriched".to_string(),
+            has_orderbook: true,
+        };
+        record(&db_path, &[entry("fresh", 1), entry("old", 8)]).unwrap();
+        // A later refresh replaces the earlier one
+        record(&db_path, &[entry("old", 7)]).unwrap();
+
+        let freshness = load(&db_path).unwrap();
+        assert_eq!(freshness.len(), 2);
+        assert_eq!(freshness["old"], entry("old", 7));
+        assert!(!is_stale(freshness.get("fresh"), DEFAULT_MAX_AGE, now));
+        assert!(is_stale(freshness.get("old"), DEFAULT_MAX_AGE, now));
+        assert!(is_stale(freshness.get("missing"), DEFAULT_MAX_AGE, now));
+        assert_eq!(format_age(chrono::Duration::minutes(90)), "1h");
+    }
+}
@@ -0,0 +1,399 @@
+//! Read-only browsing of the typed store
+//!
+//! The index TUI's browser finds markets by question text, condition ID or
+//! token ID, then shows the decoded row each column family holds for the
+//! market or token, with links to the related conditions and tokens.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::typed_store::book_snapshots;
+use crate::typed_store::candles;
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{
+    BookSnapshotCf, CandleCf, CandleInterval, CandleStatsCf, ConditionCf, ConditionIndexCf,
+    EnrichmentFreshnessCf, MarketByConditionCf, MarketCf, MarketIndexCf, RocksDbMarket, TokenCf,
+    TokenIndexCf, TokensByConditionCf,
+};
+use crate::typed_store::table::TypedCf;
+use crate::typed_store::token_info::missing_cf_as_none;
+
+/// Something the browser can show the rows of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Condition(String),
+    Token(String),
+}
+
+impl Target {
+    pub fn id(&self) -> &str {
+        match self {
+            Target::Condition(id) | Target::Token(id) => id,
+        }
+    }
+}
+
+/// How a search hit matched the query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    ConditionId,
+    TokenId,
+    /// Question, category or tag text
+    Text,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub condition_id: String,
+    pub question: String,
+    pub active: bool,
+    pub closed: bool,
+    pub volume: Option<f64>,
+    pub matched: MatchKind,
+}
+
+impl SearchHit {
+    fn new(market: &RocksDbMarket, condition_id: String, matched: MatchKind) -> Self {
+        Self {
+            condition_id,
+            question: market.question.clone(),
+            active: market.active,
+            closed: market.closed,
+            volume: market.volume,
+            matched,
+        }
+    }
+}
+
+/// One decoded row
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfRecord {
+    /// Column family name
+    pub cf: &'static str,
+    pub key: String,
+    /// The value as pretty JSON
+    pub value: String,
+}
+
+impl CfRecord {
+    fn new<CF: TypedCf>(key: &str, value: &impl Serialize) -> Self {
+        Self {
+            cf: CF::NAME,
+            key: key.to_string(),
+            value: serde_json::to_string_pretty(value)
+                .unwrap_or_else(|e| format!("<not printable: {}>", e)),
+        }
+    }
+}
+
+/// A related condition or token to jump to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub target: Target,
+    pub label: String,
+}
+
+/// Every row the store holds for a target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub target: Target,
+    pub title: String,
+    pub records: Vec<CfRecord>,
+    pub links: Vec<Link>,
+}
+
+/// Markets matching `query`, at most `limit`
+///
+/// A query that is a condition or token ID finds just that market. Otherwise
+/// markets whose question, category or tags contain it are returned, open
+/// markets first, then by volume.
+pub fn search(db_path: &Path, query: &str, limit: usize) -> Result<Vec<SearchHit>, DbContextError> {
+    let query = query.trim();
+    if query.is_empty() || !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let key = query.to_string();
+
+    if let Some(market) = missing_cf_as_none(ctx.get::<MarketByConditionCf>(&key))? {
+        return Ok(vec![SearchHit::new(&market, key, MatchKind::ConditionId)]);
+    }
+    if let Some(condition_id) = missing_cf_as_none(ctx.get::<TokenIndexCf>(&key))? {
+        if let Some(market) = missing_cf_as_none(ctx.get::<MarketByConditionCf>(&condition_id))? {
+            return Ok(vec![SearchHit::new(
+                &market,
+                condition_id,
+                MatchKind::TokenId,
+            )]);
+        }
+    }
+
+    let needle = query.to_lowercase();
+    let mut matches: Vec<_> = match ctx.scan::<MarketIndexCf>() {
+        Ok(entries) => entries.into_iter().map(|(_, index)| index).collect(),
+        Err(DbContextError::ColumnFamilyNotFound(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    matches.retain(|index| {
+        index.question_lower.contains(&needle)
+            || index
+                .category_lower
+                .as_ref()
+                .is_some_and(|category| category.contains(&needle))
+            || index
+                .tags_lower
+                .iter()
+                .flatten()
+                .any(|tag| tag.contains(&needle))
+    });
+    matches.sort_by(|a, b| {
+        a.closed.cmp(&b.closed).then(
+            b.volume
+                .unwrap_or_default()
+                .total_cmp(&a.volume.unwrap_or_default()),
+        )
+    });
+
+    let mut seen = HashSet::new();
+    let mut hits = Vec::new();
+    for index in matches {
+        if hits.len() >= limit {
+            break;
+        }
+        if !seen.insert(index.condition_id.clone()) {
+            continue;
+        }
+        let hit = match missing_cf_as_none(ctx.get::<MarketByConditionCf>(&index.condition_id))? {
+            Some(market) => SearchHit::new(&market, index.condition_id, MatchKind::Text),
+            None => SearchHit {
+                question: index.question_lower,
+                condition_id: index.condition_id,
+                active: index.active,
+                closed: index.closed,
+                volume: index.volume,
+                matched: MatchKind::Text,
+            },
+        };
+        hits.push(hit);
+    }
+    Ok(hits)
+}
+
+/// The rows of `target` in every column family, or `None` when no column family has it
+pub fn inspect(db_path: &Path, target: &Target) -> Result<Option<Inspection>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let inspection = match target {
+        Target::Condition(condition_id) => inspect_condition(&ctx, condition_id)?,
+        Target::Token(token_id) => inspect_token(&ctx, db_path, token_id)?,
+    };
+    Ok((!inspection.records.is_empty()).then_some(inspection))
+}
+
+fn inspect_condition(
+    ctx: &TypedDbContext,
+    condition_id: &str,
+) -> Result<Inspection, DbContextError> {
+    let key = condition_id.to_string();
+    let mut records = Vec::new();
+    let mut links = Vec::new();
+
+    let market = missing_cf_as_none(ctx.get::<MarketByConditionCf>(&key))?;
+    if let Some(market) = &market {
+        records.push(CfRecord::new::<MarketByConditionCf>(&key, market));
+        if let Some(market_id) = &market.id {
+            if let Some(row) = missing_cf_as_none(ctx.get::<MarketCf>(market_id))? {
+                records.push(CfRecord::new::<MarketCf>(market_id, &row));
+            }
+            if let Some(index) = missing_cf_as_none(ctx.get::<MarketIndexCf>(market_id))? {
+                records.push(CfRecord::new::<MarketIndexCf>(market_id, &index));
+            }
+        }
+        links.extend(market.tokens.iter().map(|token| Link {
+            target: Target::Token(token.token_id.clone()),
+            label: format!("{} token", token.outcome),
+        }));
+    }
+    if let Some(condition) = missing_cf_as_none(ctx.get::<ConditionCf>(&key))? {
+        records.push(CfRecord::new::<ConditionCf>(&key, &condition));
+    }
+    if let Some(token_ids) = missing_cf_as_none(ctx.get::<ConditionIndexCf>(&key))? {
+        records.push(CfRecord::new::<ConditionIndexCf>(&key, &token_ids));
+        // Tokens the market row doesn't list, e.g. from an older index
+        for token_id in token_ids {
+            if !links.iter().any(|link| link.target.id() == token_id) {
+                links.push(Link {
+                    label: "Token".to_string(),
+                    target: Target::Token(token_id),
+                });
+            }
+        }
+    }
+    if let Some(tokens) = missing_cf_as_none(ctx.get::<TokensByConditionCf>(&key))? {
+        records.push(CfRecord::new::<TokensByConditionCf>(&key, &tokens));
+    }
+
+    Ok(Inspection {
+        target: Target::Condition(key.clone()),
+        title: market.map(|m| m.question).unwrap_or(key),
+        records,
+        links,
+    })
+}
+
+fn inspect_token(
+    ctx: &TypedDbContext,
+    db_path: &Path,
+    token_id: &str,
+) -> Result<Inspection, DbContextError> {
+    let key = token_id.to_string();
+    let mut records = Vec::new();
+    let mut links = Vec::new();
+
+    let token = missing_cf_as_none(ctx.get::<TokenCf>(&key))?;
+    if let Some(token) = &token {
+        records.push(CfRecord::new::<TokenCf>(&key, token));
+    }
+    let indexed = missing_cf_as_none(ctx.get::<TokenIndexCf>(&key))?;
+    if let Some(condition_id) = &indexed {
+        records.push(CfRecord::new::<TokenIndexCf>(&key, condition_id));
+    }
+    if let Some(freshness) = missing_cf_as_none(ctx.get::<EnrichmentFreshnessCf>(&key))? {
+        records.push(CfRecord::new::<EnrichmentFreshnessCf>(&key, &freshness));
+    }
+    let snapshots = book_snapshots::load(db_path, token_id, None)?;
+    if let Some(latest) = snapshots.last() {
+        let key = format!(
+            "{} (latest of {})",
+            book_snapshots::snapshot_key(token_id, latest.captured_at),
+            snapshots.len()
+        );
+        records.push(CfRecord::new::<BookSnapshotCf>(&key, latest));
+    }
+    let series = candles::load(db_path, token_id, CandleInterval::OneMinute, None, None)?;
+    if let Some((latest, stats)) = series.last() {
+        let key = format!(
+            "{} (latest of {})",
+            candles::candle_key(token_id, latest.interval, latest.start),
+            series.len()
+        );
+        records.push(CfRecord::new::<CandleCf>(&key, latest));
+        if let Some(stats) = stats {
+            records.push(CfRecord::new::<CandleStatsCf>(&key, stats));
+        }
+    }
+
+    let condition_id = indexed.or_else(|| token.as_ref().and_then(|t| t.condition_id.clone()));
+    let market = match &condition_id {
+        Some(condition_id) => missing_cf_as_none(ctx.get::<MarketByConditionCf>(condition_id))?,
+        None => None,
+    };
+    if let Some(condition_id) = &condition_id {
+        links.push(Link {
+            target: Target::Condition(condition_id.clone()),
+            label: "Market".to_string(),
+        });
+    }
+    let mut title = key.clone();
+    if let Some(market) = &market {
+        for other in &market.tokens {
+            if other.token_id == token_id {
+                title = format!("{} — {}", other.outcome, market.question);
+            } else {
+                links.push(Link {
+                    target: Target::Token(other.token_id.clone()),
+                    label: format!("{} token", other.outcome),
+                });
+            }
+        }
+    }
+
+    Ok(Inspection {
+        target: Target::Token(key),
+        title,
+        records,
+        links,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::clob::fetcher::Market;
+    use crate::typed_store::models::ALL_COLUMN_FAMILIES;
+
+    #[test]
+    fn finds_markets_and_links_their_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        assert!(search(&db_path, "rain", 10).unwrap().is_empty());
+
+        let market = Market::from_value(serde_json::json!({
+            "id": "42",
+            "condition_id": "0xabc",
+            "question": "Will it rain in Paris?",
+            "category": "Weather",
+            "active": true,
+            "closed": false,
+            "accepting_orders": true,
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes", "price": 0.4},
+                {"token_id": "2", "outcome": "No", "price": 0.6}
+            ]
+        }))
+        .unwrap();
+        let market = RocksDbMarket::from(market);
+        {
+            let ctx = TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+            ctx.batch_write(|batch| market.put_into(batch, &"42".to_string()))
+                .unwrap();
+        }
+
+        let hits = search(&db_path, "RAIN", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].question, "Will it rain in Paris?");
+        assert_eq!(hits[0].matched, MatchKind::Text);
+        assert_eq!(search(&db_path, "weather", 10).unwrap().len(), 1);
+        assert_eq!(
+            search(&db_path, "0xabc", 10).unwrap()[0].matched,
+            MatchKind::ConditionId
+        );
+        assert_eq!(
+            search(&db_path, "2", 10).unwrap()[0].matched,
+            MatchKind::TokenId
+        );
+        assert!(search(&db_path, "snow", 10).unwrap().is_empty());
+
+        let condition = inspect(&db_path, &Target::Condition("0xabc".to_string()))
+            .unwrap()
+            .unwrap();
+        let cfs: Vec<_> = condition.records.iter().map(|r| r.cf).collect();
+        assert_eq!(
+            cfs,
+            [
+                "markets_by_condition",
+                "markets",
+                "market_index",
+                "condition_index"
+            ]
+        );
+        assert_eq!(condition.links.len(), 2);
+        assert_eq!(condition.links[1].target, Target::Token("2".to_string()));
+
+        let token = inspect(&db_path, &Target::Token("2".to_string()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.title, "No — Will it rain in Paris?");
+        assert_eq!(
+            token.links[0].target,
+            Target::Condition("0xabc".to_string())
+        );
+        assert_eq!(token.links[1].target, Target::Token("1".to_string()));
+        assert!(inspect(&db_path, &Target::Token("9".to_string()))
+            .unwrap()
+            .is_none());
+    }
+}
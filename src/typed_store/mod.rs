@@ -3,11 +3,19 @@
 //! This module provides type-safe, polymorphic storage on a single RocksDB instance.
 //! Each logical "table" gets its own prefix byte and strongly-typed key/value pairs.
 
+pub mod backup;
+pub mod book_snapshots;
+pub mod candles;
 pub mod codec;
+pub mod compat;
 pub mod context;
+pub mod freshness;
+pub mod inspect;
 pub mod models;
+pub mod pipeline_runs;
 pub mod store;
 pub mod table;
+pub mod token_info;
 
 pub use context::TypedDbContext;
 pub use store::TypedStore;
@@ -16,8 +24,8 @@ pub use store::TypedStore;
 // Note: These are used in the binary commands but may appear unused in lib compilation
 #[allow(unused_imports)]
 pub use models::{
-    Condition, ConditionCf, ConditionIndexCf, ConditionTable, MarketByConditionCf,
-    MarketByConditionTable, MarketCf, MarketIndex, MarketIndexCf, MarketIndexTable, MarketTable,
-    RocksDbMarket, Token, TokenCf, TokenIndexCf, TokenTable, TokensByConditionCf,
-    TokensByConditionTable, ALL_COLUMN_FAMILIES,
+    Condition, ConditionCf, ConditionIndexCf, ConditionTable, EnrichmentFreshness,
+    EnrichmentFreshnessCf, MarketByConditionCf, MarketByConditionTable, MarketCf, MarketIndex,
+    MarketIndexCf, MarketIndexTable, MarketTable, RocksDbMarket, Token, TokenCf, TokenIndexCf,
+    TokenTable, TokensByConditionCf, TokensByConditionTable, ALL_COLUMN_FAMILIES,
 };
@@ -1,9 +1,13 @@
 use crate::define_typed_cf;
 use crate::markets::clob::fetcher::{Market as FetchedMarket, MarketToken as FetchedMarketToken};
 use crate::typed_store::codec::{CodecError, RocksDbValue};
+use crate::typed_store::context::{DbContextError, TypedBatchWriter};
 use crate::typed_store::table::{Table, TypedCf};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// RocksDB-optimized market structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +98,335 @@ pub struct MarketIndex {
     pub created_at: Option<String>,
 }
 
+/// When a token's enrichment data (order book and liquidity snapshot) was last refreshed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnrichmentFreshness {
+    pub token_id: String,
+    pub condition_id: Option<String>,
+    pub refreshed_at: DateTime<Utc>,
+    /// Dataset the refresh was written to
+    pub dataset: String,
+    pub has_orderbook: bool,
+}
+
+/// One recorded pipeline run, written by the pipeline runner when the run ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRunRecord {
+    /// Name of the run's output directory, e.g. `pipeline_daily_monitor_20260301_080000`
+    pub run_id: String,
+    pub pipeline: String,
+    /// Resolved parameters, including the date variables
+    pub parameters: BTreeMap<String, String>,
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: f64,
+    pub status: PipelineRunStatus,
+    pub steps: Vec<PipelineStepRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineRunStatus {
+    Succeeded,
+    /// Finished, but some steps failed and were continued past or skipped
+    Partial,
+    Failed,
+    /// Stopped from the run monitor
+    Aborted,
+}
+
+/// How one step of a recorded run went
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStepRecord {
+    pub name: String,
+    /// CLI command, or `publish`/`notify`/`validate`
+    pub kind: String,
+    pub status: PipelineStepStatus,
+    /// Exit code of a command step; `None` for built-in steps and unstarted commands
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub duration_secs: f64,
+    /// Datasets the step wrote to
+    pub outputs: Vec<PipelineOutput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStepStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+    NotRun,
+}
+
+/// Size of a dataset a step produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineOutput {
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Full order book of a token at one moment, captured by the snapshot scheduler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    pub token_id: String,
+    pub condition_id: Option<String>,
+    pub captured_at: DateTime<Utc>,
+    /// Best (highest) bid first
+    pub bids: Vec<BookSnapshotLevel>,
+    /// Best (lowest) ask first
+    pub asks: Vec<BookSnapshotLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookSnapshotLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl BookSnapshot {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+}
+
+/// Width of a candle
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+pub enum CandleInterval {
+    #[value(name = "1m")]
+    OneMinute,
+    #[value(name = "5m")]
+    FiveMinutes,
+    #[value(name = "1h")]
+    OneHour,
+    #[value(name = "1d")]
+    OneDay,
+}
+
+impl CandleInterval {
+    pub fn seconds(self) -> i64 {
+        match self {
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 5 * 60,
+            CandleInterval::OneHour => 60 * 60,
+            CandleInterval::OneDay => 24 * 60 * 60,
+        }
+    }
+
+    /// Start of the candle `at` falls in
+    pub fn bucket(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = self.seconds();
+        let start = at.timestamp().div_euclid(seconds) * seconds;
+        DateTime::from_timestamp(start, 0).unwrap_or(at)
+    }
+}
+
+impl fmt::Display for CandleInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::OneHour => "1h",
+            CandleInterval::OneDay => "1d",
+        })
+    }
+}
+
+/// Trades of a token over one interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub token_id: String,
+    pub interval: CandleInterval,
+    pub start: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Shares traded
+    pub volume: Decimal,
+    /// Sum of price × size, for the VWAP
+    pub notional: Decimal,
+    pub trades: u32,
+    /// When the open and close prices were seen, so merged candles keep them right
+    pub first_at: DateTime<Utc>,
+    pub last_at: DateTime<Utc>,
+    /// What each recording merged into the candle saw, oldest first; empty in
+    /// candles stored before recordings were kept
+    #[serde(default)]
+    pub recordings: Vec<CandleRecording>,
+}
+
+/// The trades one recording saw of a candle, and the part of the interval it covered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleRecording {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub volume: Decimal,
+    pub notional: Decimal,
+    pub trades: u32,
+}
+
+impl CandleRecording {
+    pub fn overlaps(&self, other: &CandleRecording) -> bool {
+        self.from <= other.to && other.from <= self.to
+    }
+}
+
+impl Candle {
+    /// Candle of a single price; `size` is `None` for a price without a trade
+    /// (a last trade price update)
+    pub fn new(
+        token_id: &str,
+        interval: CandleInterval,
+        at: DateTime<Utc>,
+        price: Decimal,
+        size: Option<Decimal>,
+    ) -> Self {
+        Self {
+            token_id: token_id.to_string(),
+            interval,
+            start: interval.bucket(at),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: size.unwrap_or_default(),
+            notional: size.map(|size| price * size).unwrap_or_default(),
+            trades: u32::from(size.is_some()),
+            first_at: at,
+            last_at: at,
+            recordings: vec![CandleRecording {
+                from: at,
+                to: at,
+                volume: size.unwrap_or_default(),
+                notional: size.map(|size| price * size).unwrap_or_default(),
+                trades: u32::from(size.is_some()),
+            }],
+        }
+    }
+
+    /// Add a later price of the same interval
+    pub fn update(&mut self, at: DateTime<Utc>, price: Decimal, size: Option<Decimal>) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        if at >= self.last_at {
+            self.close = price;
+            self.last_at = at;
+        }
+        if let Some(size) = size {
+            self.volume += size;
+            self.notional += price * size;
+            self.trades += 1;
+        }
+        if let Some(recording) = self.recordings.last_mut() {
+            recording.from = recording.from.min(at);
+            recording.to = recording.to.max(at);
+            recording.volume = self.volume;
+            recording.notional = self.notional;
+            recording.trades = self.trades;
+        }
+    }
+
+    /// Mark the candle's recording as covering `from` to `to`, such as the
+    /// whole capture it was built from, within the candle's interval
+    pub fn covering(&mut self, from: DateTime<Utc>, to: DateTime<Utc>) {
+        let end = self.start + chrono::Duration::seconds(self.interval.seconds());
+        for recording in &mut self.recordings {
+            recording.from = recording.from.min(from.max(self.start));
+            recording.to = recording.to.max(to.min(end));
+        }
+    }
+
+    /// The recordings of the candle, one spanning its trades when it was
+    /// stored without them
+    fn recorded(&self) -> Vec<CandleRecording> {
+        if !self.recordings.is_empty() {
+            return self.recordings.clone();
+        }
+        vec![CandleRecording {
+            from: self.first_at,
+            to: self.last_at,
+            volume: self.volume,
+            notional: self.notional,
+            trades: self.trades,
+        }]
+    }
+
+    /// Combine with a candle of the same interval seen by other recordings
+    ///
+    /// Recordings that cover overlapping parts of the interval saw the same
+    /// trades, so only the one that saw more counts; recordings of separate
+    /// parts, like a stream stopped and restarted within the hour, add up.
+    /// Merging a candle again changes nothing.
+    pub fn merge(&mut self, other: &Candle) {
+        self.high = self.high.max(other.high);
+        self.low = self.low.min(other.low);
+        if other.first_at < self.first_at {
+            self.open = other.open;
+            self.first_at = other.first_at;
+        }
+        if other.last_at > self.last_at {
+            self.close = other.close;
+            self.last_at = other.last_at;
+        }
+
+        let mut recordings = self.recorded();
+        for incoming in other.recorded() {
+            let overlapping = recordings.iter().filter(|seen| seen.overlaps(&incoming));
+            let seen_trades: Option<u32> = overlapping.map(|seen| seen.trades).reduce(|a, b| a + b);
+            if seen_trades.is_none_or(|seen| incoming.trades > seen) {
+                recordings.retain(|seen| !seen.overlaps(&incoming));
+                recordings.push(incoming);
+            }
+        }
+        recordings.sort_by_key(|recording| recording.from);
+        self.volume = recordings.iter().map(|recording| recording.volume).sum();
+        self.notional = recordings.iter().map(|recording| recording.notional).sum();
+        self.trades = recordings.iter().map(|recording| recording.trades).sum();
+        self.recordings = recordings;
+    }
+
+    pub fn vwap(&self) -> Option<Decimal> {
+        (!self.volume.is_zero()).then(|| self.notional / self.volume)
+    }
+}
+
+/// Statistics of the candles up to and including one candle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleStats {
+    pub token_id: String,
+    pub interval: CandleInterval,
+    pub start: DateTime<Utc>,
+    /// Candles the statistics cover, at most the rolling window
+    pub candles: usize,
+    /// Mean close
+    pub sma: Decimal,
+    pub vwap: Option<Decimal>,
+    pub volume: Decimal,
+    /// Standard deviation of close-to-close returns
+    pub volatility: Option<f64>,
+}
+
 // Conversion implementations
 
 impl From<FetchedMarket> for RocksDbMarket {
@@ -148,7 +481,88 @@ impl From<FetchedMarketToken> for RocksDbMarketToken {
     }
 }
 
+impl From<RocksDbMarket> for FetchedMarket {
+    fn from(market: RocksDbMarket) -> Self {
+        Self {
+            id: market.id,
+            condition_id: market.condition_id,
+            question: market.question,
+            description: market.description,
+            category: market.category,
+            tags: market.tags,
+            tokens: market
+                .tokens
+                .into_iter()
+                .map(FetchedMarketToken::from)
+                .collect(),
+            active: market.active,
+            closed: market.closed,
+            archived: market.archived,
+            accepting_orders: market.accepting_orders,
+            minimum_order_size: market.minimum_order_size,
+            minimum_tick_size: market.minimum_tick_size,
+            end_date_iso: market.end_date_iso,
+            created_at: market.created_at,
+            updated_at: market.updated_at,
+            volume: market.volume,
+            volume_24hr: market.volume_24hr,
+            liquidity: market.liquidity,
+            outcomes: market.outcomes,
+            outcome_prices: market.outcome_prices,
+            market_slug: market.market_slug,
+            creator: market.creator,
+            fee_rate: market.fee_rate,
+            additional_fields: market.additional_fields,
+        }
+    }
+}
+
+impl From<RocksDbMarketToken> for FetchedMarketToken {
+    fn from(token: RocksDbMarketToken) -> Self {
+        Self {
+            token_id: token.token_id,
+            outcome: token.outcome,
+            price: token.price,
+            winner: token.winner,
+            volume: token.volume,
+            volume_24hr: token.volume_24hr,
+            supply: token.supply,
+            market_cap: token.market_cap,
+            additional_fields: token.additional_fields,
+        }
+    }
+}
+
 impl RocksDbMarket {
+    /// Queue every per-market row: the market by id and by condition, the
+    /// token and condition lookups, and the search index entry
+    ///
+    /// Condition and token-list rows aggregate over all markets of a
+    /// condition, so callers write those once they have seen every market.
+    pub fn put_into(
+        &self,
+        batch: &mut TypedBatchWriter,
+        market_id: &String,
+    ) -> Result<(), DbContextError> {
+        batch.put::<MarketCf>(market_id, self)?;
+
+        if let Some(ref condition_id) = self.condition_id {
+            batch.put::<MarketByConditionCf>(condition_id, self)?;
+
+            let token_ids: Vec<String> = self.tokens.iter().map(|t| t.token_id.clone()).collect();
+            for token in &self.tokens {
+                batch.put::<TokenIndexCf>(&token.token_id, condition_id)?;
+            }
+            batch.put::<ConditionIndexCf>(condition_id, &token_ids)?;
+        }
+
+        if let Some(index) = self.create_index() {
+            batch.put::<MarketIndexCf>(market_id, &index)?;
+        }
+
+        Ok(())
+    }
+
     /// Extract condition data from market
     pub fn extract_condition(&self) -> Option<Condition> {
         let condition_id = self.condition_id.as_ref()?.clone();
@@ -348,6 +762,23 @@ define_typed_cf!(
     "condition_index",
     0x08
 ); // condition_id -> [token_ids]
+define_typed_cf!(
+    EnrichmentFreshnessCf,
+    String,
+    EnrichmentFreshness,
+    "enrichment_freshness",
+    0x09
+); // token_id -> last enrichment
+define_typed_cf!(
+    PipelineRunCf,
+    String,
+    PipelineRunRecord,
+    "pipeline_runs",
+    0x0A
+); // run_id -> run record
+define_typed_cf!(BookSnapshotCf, String, BookSnapshot, "book_snapshots", 0x0B); // "<token_id>:<captured_at ms>" -> book
+define_typed_cf!(CandleCf, String, Candle, "candles", 0x0C); // "<token_id>:<interval>:<start ms>" -> candle
+define_typed_cf!(CandleStatsCf, String, CandleStats, "candle_stats", 0x0D); // same key -> rolling stats
 
 /// All column family names for database initialization
 pub const ALL_COLUMN_FAMILIES: &[&str] = &[
@@ -359,4 +790,9 @@ pub const ALL_COLUMN_FAMILIES: &[&str] = &[
     MarketIndexCf::NAME,
     TokenIndexCf::NAME,
     ConditionIndexCf::NAME,
+    EnrichmentFreshnessCf::NAME,
+    PipelineRunCf::NAME,
+    BookSnapshotCf::NAME,
+    CandleCf::NAME,
+    CandleStatsCf::NAME,
 ];
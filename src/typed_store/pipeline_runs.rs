@@ -0,0 +1,63 @@
+//! Pipeline run history
+//!
+//! The pipeline runner records every finished run (parameters, step durations
+//! and exit statuses, produced datasets) so `pipeline history` can list them
+//! and `pipeline compare` can put two runs side by side.
+
+use std::path::Path;
+
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{PipelineRunCf, PipelineRunRecord, ALL_COLUMN_FAMILIES};
+
+/// Store a run, replacing an earlier record with the same id
+pub fn record(db_path: &Path, run: &PipelineRunRecord) -> Result<(), DbContextError> {
+    let ctx = TypedDbContext::open(db_path, ALL_COLUMN_FAMILIES.to_vec())?;
+    ctx.put::<PipelineRunCf>(&run.run_id, run)
+}
+
+/// Every recorded run, newest first
+///
+/// A store that doesn't exist or predates run history has no runs.
+pub fn load(db_path: &Path) -> Result<Vec<PipelineRunRecord>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(Vec::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let mut runs: Vec<PipelineRunRecord> = match ctx.scan::<PipelineRunCf>() {
+        Ok(entries) => entries.into_iter().map(|(_, run)| run).collect(),
+        Err(DbContextError::ColumnFamilyNotFound(_)) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typed_store::models::PipelineRunStatus;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn runs_load_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        assert!(load(&db_path).unwrap().is_empty());
+
+        let run = |run_id: &str, hours_ago: i64| PipelineRunRecord {
+            run_id: run_id.to_string(),
+            pipeline: "Daily Market Monitor".to_string(),
+            parameters: Default::default(),
+            started_at: Utc::now() - Duration::hours(hours_ago),
+            duration_secs: 12.5,
+            status: PipelineRunStatus::Succeeded,
+            steps: Vec::new(),
+        };
+        record(&db_path, &run("pipeline_a", 2)).unwrap();
+        record(&db_path, &run("pipeline_b", 1)).unwrap();
+
+        let runs = load(&db_path).unwrap();
+        let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, ["pipeline_b", "pipeline_a"]);
+    }
+}
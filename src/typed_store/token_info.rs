@@ -0,0 +1,187 @@
+//! Market metadata of a single token
+//!
+//! Resolves a token id to its market through the token index, so panes that
+//! are bound to a token can show the question and outcome instead of the id.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::typed_store::context::{DbContextError, TypedDbContext};
+use crate::typed_store::models::{MarketByConditionCf, TokenCf, TokenIndexCf};
+
+/// What the store knows about a token and its market
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    pub token_id: String,
+    pub outcome: Option<String>,
+    pub condition_id: Option<String>,
+    pub question: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub end_date_iso: Option<String>,
+    pub volume: Option<f64>,
+    pub volume_24hr: Option<f64>,
+    pub liquidity: Option<f64>,
+    pub market_slug: Option<String>,
+    pub creator: Option<String>,
+    /// The market's other outcome tokens
+    pub complements: Vec<ComplementToken>,
+}
+
+/// Another outcome token of the same market
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplementToken {
+    pub token_id: String,
+    pub outcome: String,
+}
+
+/// Look up `token_id` in the store at `db_path`
+///
+/// Returns `None` when neither the token index nor the token table knows the
+/// token, including when the store doesn't exist yet.
+pub fn lookup(db_path: &Path, token_id: &str) -> Result<Option<TokenInfo>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    lookup_in(&ctx, token_id)
+}
+
+/// Look up each of `token_ids` with one open store; tokens the store doesn't
+/// know are left out
+pub fn lookup_all<'a>(
+    db_path: &Path,
+    token_ids: impl IntoIterator<Item = &'a str>,
+) -> Result<HashMap<String, TokenInfo>, DbContextError> {
+    if !db_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let ctx = TypedDbContext::open_read_only(db_path)?;
+    let mut infos = HashMap::new();
+    for token_id in token_ids {
+        if let Some(info) = lookup_in(&ctx, token_id)? {
+            infos.insert(token_id.to_string(), info);
+        }
+    }
+    Ok(infos)
+}
+
+fn lookup_in(ctx: &TypedDbContext, token_id: &str) -> Result<Option<TokenInfo>, DbContextError> {
+    let key = token_id.to_string();
+
+    let token = missing_cf_as_none(ctx.get::<TokenCf>(&key))?;
+    let condition_id = match missing_cf_as_none(ctx.get::<TokenIndexCf>(&key))? {
+        Some(condition_id) => Some(condition_id),
+        None => token.as_ref().and_then(|t| t.condition_id.clone()),
+    };
+    let market = match &condition_id {
+        Some(condition_id) => missing_cf_as_none(ctx.get::<MarketByConditionCf>(condition_id))?,
+        None => None,
+    };
+    if token.is_none() && market.is_none() {
+        return Ok(None);
+    }
+
+    let market_token = market
+        .as_ref()
+        .and_then(|m| m.tokens.iter().find(|t| t.token_id == token_id));
+    let outcome = market_token
+        .map(|t| t.outcome.clone())
+        .or_else(|| token.as_ref().map(|t| t.outcome.clone()));
+    let complements = market
+        .as_ref()
+        .map(|m| {
+            m.tokens
+                .iter()
+                .filter(|t| t.token_id != token_id)
+                .map(|t| ComplementToken {
+                    token_id: t.token_id.clone(),
+                    outcome: t.outcome.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Some(TokenInfo {
+        token_id: key,
+        outcome,
+        condition_id,
+        question: market.as_ref().map(|m| m.question.clone()),
+        category: market.as_ref().and_then(|m| m.category.clone()),
+        tags: market
+            .as_ref()
+            .and_then(|m| m.tags.clone())
+            .unwrap_or_default(),
+        end_date_iso: market.as_ref().and_then(|m| m.end_date_iso.clone()),
+        volume: market
+            .as_ref()
+            .and_then(|m| m.volume)
+            .or_else(|| token.as_ref().and_then(|t| t.volume)),
+        volume_24hr: market
+            .as_ref()
+            .and_then(|m| m.volume_24hr)
+            .or_else(|| token.as_ref().and_then(|t| t.volume_24hr)),
+        liquidity: market.as_ref().and_then(|m| m.liquidity),
+        market_slug: market.as_ref().and_then(|m| m.market_slug.clone()),
+        creator: market.as_ref().and_then(|m| m.creator.clone()),
+        complements,
+    }))
+}
+
+/// Treat a column family the store doesn't have yet like a missing key
+pub(crate) fn missing_cf_as_none<T>(
+    result: Result<Option<T>, DbContextError>,
+) -> Result<Option<T>, DbContextError> {
+    match result {
+        Err(DbContextError::ColumnFamilyNotFound(_)) => Ok(None),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::clob::fetcher::Market;
+    use crate::typed_store::models::{RocksDbMarket, ALL_COLUMN_FAMILIES};
+
+    #[test]
+    fn lookup_resolves_market_and_complement() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("rocksdb");
+        assert_eq!(lookup(&db_path, "1").unwrap(), None);
+
+        let market = Market::from_value(serde_json::json!({
+            "condition_id": "0xabc",
+            "question": "Will it rain?",
+            "end_date_iso": "2026-12-31T00:00:00Z",
+            "active": true,
+            "closed": false,
+            "accepting_orders": true,
+            "tokens": [
+                {"token_id": "1", "outcome": "Yes", "price": 0.4},
+                {"token_id": "2", "outcome": "No", "price": 0.6}
+            ]
+        }))
+        .unwrap();
+        let market = RocksDbMarket::from(market);
+        {
+            let ctx = TypedDbContext::open(&db_path, ALL_COLUMN_FAMILIES.to_vec()).unwrap();
+            ctx.batch_write(|batch| market.put_into(batch, &"market_0xabc".to_string()))
+                .unwrap();
+        }
+
+        let info = lookup(&db_path, "1").unwrap().unwrap();
+        assert_eq!(info.question.as_deref(), Some("Will it rain?"));
+        assert_eq!(info.outcome.as_deref(), Some("Yes"));
+        assert_eq!(info.condition_id.as_deref(), Some("0xabc"));
+        assert_eq!(info.end_date_iso.as_deref(), Some("2026-12-31T00:00:00Z"));
+        assert_eq!(
+            info.complements,
+            [ComplementToken {
+                token_id: "2".to_string(),
+                outcome: "No".to_string(),
+            }]
+        );
+        assert_eq!(lookup(&db_path, "unknown").unwrap(), None);
+    }
+}
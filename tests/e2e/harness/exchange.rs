@@ -0,0 +1,152 @@
+//! Mock exchange: CLOB REST endpoints on a wiremock server and a market
+//! WebSocket that replays a recording to every subscriber
+
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use wiremock::matchers::{header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::fixtures;
+
+pub struct MockExchange {
+    rest: MockServer,
+    ws_addr: SocketAddr,
+    ws_task: JoinHandle<()>,
+    subscriptions: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockExchange {
+    /// Start the exchange; the market feed replays `frames` after each
+    /// client's first (subscription) message
+    pub async fn start(frames: Vec<String>) -> Self {
+        let rest = MockServer::start().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock WebSocket");
+        let ws_addr = listener.local_addr().unwrap();
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+
+        let frames = Arc::new(frames);
+        let seen = subscriptions.clone();
+        let ws_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let frames = frames.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    let (mut write, mut read) = ws.split();
+
+                    // Replay only once the client has subscribed, like the real feed
+                    while let Some(Ok(msg)) = read.next().await {
+                        if let Message::Text(text) = msg {
+                            if let Ok(subscription) = serde_json::from_str(&text) {
+                                seen.lock().unwrap().push(subscription);
+                            }
+                            break;
+                        }
+                    }
+                    for frame in frames.iter() {
+                        if write.send(Message::Text(frame.clone().into())).await.is_err() {
+                            return;
+                        }
+                    }
+                    // Hold the connection open until the client leaves
+                    while let Some(Ok(msg)) = read.next().await {
+                        if msg.is_close() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Self {
+            rest,
+            ws_addr,
+            ws_task,
+            subscriptions,
+        }
+    }
+
+    /// Start with the frames of `tests/fixtures/ws/<recording>.jsonl`
+    pub async fn with_recording(recording: &str) -> Self {
+        Self::start(fixtures::ws_frames(recording)).await
+    }
+
+    /// Base URL of the REST API, passed as the command `host`
+    pub fn host(&self) -> String {
+        self.rest.uri()
+    }
+
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/ws/market", self.ws_addr)
+    }
+
+    /// Subscription messages received by the market feed so far
+    pub fn subscriptions(&self) -> Vec<Value> {
+        self.subscriptions.lock().unwrap().clone()
+    }
+
+    /// Serve `GET /markets` from REST fixtures, chained by their `next_cursor`
+    pub async fn mount_markets(&self, pages: &[&str]) {
+        let mut cursor: Option<String> = None;
+        for page in pages {
+            let body = fixtures::rest(page);
+            let next = body["next_cursor"].as_str().map(str::to_string);
+            let mock = Mock::given(method("GET")).and(path("/markets"));
+            match &cursor {
+                // Cursor-specific pages take precedence over the first page
+                Some(cursor) => mock
+                    .and(query_param("next_cursor", cursor.as_str()))
+                    .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                    .with_priority(1)
+                    .mount(&self.rest)
+                    .await,
+                None => mock
+                    .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                    .with_priority(10)
+                    .mount(&self.rest)
+                    .await,
+            }
+            cursor = next;
+        }
+    }
+
+    /// Serve `GET /book?token_id=<token>` from a REST fixture
+    pub async fn mount_book(&self, token: &str, fixture: &str) {
+        Mock::given(method("GET"))
+            .and(path("/book"))
+            .and(query_param("token_id", token))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixtures::rest(fixture)))
+            .mount(&self.rest)
+            .await;
+    }
+
+    /// Serve `GET /data/orders` from a REST fixture, to authenticated requests only
+    pub async fn mount_orders(&self, fixture: &str) {
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(header_exists("poly_api_key"))
+            .and(header_exists("poly_signature"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixtures::rest(fixture)))
+            .mount(&self.rest)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+            .with_priority(10)
+            .mount(&self.rest)
+            .await;
+    }
+}
+
+impl Drop for MockExchange {
+    fn drop(&mut self) {
+        self.ws_task.abort();
+    }
+}
@@ -0,0 +1,39 @@
+//! Recorded fixtures under `tests/fixtures`
+//!
+//! - `rest/*.json`: REST response bodies, served verbatim by the mock exchange
+//! - `ws/*.jsonl`: raw market WebSocket frames, one per line, in the same
+//!   format `simulate-fill` records (a JSON message or an array of messages)
+
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub fn fixture_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(relative)
+}
+
+/// A REST response body from `tests/fixtures/rest/<name>.json`
+pub fn rest(name: &str) -> Value {
+    let path = fixture_path(&format!("rest/{}.json", name));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing fixture {}: {}", path.display(), e));
+    serde_json::from_str(&content).unwrap_or_else(|e| panic!("invalid fixture {}: {}", path.display(), e))
+}
+
+/// The frames of a WebSocket recording from `tests/fixtures/ws/<name>.jsonl`
+pub fn ws_frames(name: &str) -> Vec<String> {
+    let path = ws_recording(name);
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing fixture {}: {}", path.display(), e));
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn ws_recording(name: &str) -> PathBuf {
+    fixture_path(&format!("ws/{}.jsonl", name))
+}
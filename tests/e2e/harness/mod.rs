@@ -0,0 +1,64 @@
+//! Shared harness for the end-to-end tests: a mock exchange, recorded
+//! fixtures and a throwaway profile to run commands in
+
+pub mod exchange;
+pub mod fixtures;
+
+pub use exchange::MockExchange;
+
+use polybot::auth::SignerBackend;
+use polybot::data_paths::DataPaths;
+use polymarket_rs_client::ApiCreds;
+use tempfile::TempDir;
+
+/// Address the test profiles act as
+pub const TEST_ADDRESS: &str = "0x1111111111111111111111111111111111111111";
+
+/// Outcome tokens of the first market in the fixtures; the recorded market
+/// session streams both
+pub const YES_TOKEN: &str =
+    "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+pub const NO_TOKEN: &str =
+    "48331043336612883890938759509493159234755048973500640148014422747788308965732";
+
+/// Passphrase the stored test credentials are encrypted with
+const TEST_PASSPHRASE: &str = "e2e-harness";
+
+/// A data directory that lives as long as the test
+pub struct TestEnv {
+    _dir: TempDir,
+    pub data_paths: DataPaths,
+}
+
+impl TestEnv {
+    /// Profile without credentials: public endpoints only
+    pub fn read_only() -> Self {
+        let env = Self::empty();
+        SignerBackend::read_only(TEST_ADDRESS)
+            .unwrap()
+            .save(&env.data_paths)
+            .unwrap();
+        env
+    }
+
+    /// Profile with stored API credentials, for authenticated endpoints
+    pub async fn with_credentials() -> Self {
+        let env = Self::empty();
+        // Every test uses the same value, so concurrent tests can't disagree
+        std::env::set_var("POLYBOT_PASSPHRASE", TEST_PASSPHRASE);
+        let creds = ApiCreds {
+            api_key: "6f0c1f5e-8f4a-4a35-9d0b-2b7f3c1d9e42".to_string(),
+            secret: "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string(),
+            passphrase: "harness-passphrase".to_string(),
+        };
+        polybot::config::save_credentials(&env.data_paths, &creds).await.unwrap();
+        env
+    }
+
+    fn empty() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let data_paths = DataPaths::new(dir.path());
+        data_paths.ensure_directories().unwrap();
+        Self { _dir: dir, data_paths }
+    }
+}
@@ -0,0 +1,12 @@
+//! End-to-end tests: commands run through their Command structs against a
+//! mock exchange serving recorded fixtures, asserting on what they leave in
+//! the data directory. `polybot selftest` runs a subset of the same path
+//! against the sandbox.
+
+mod harness;
+
+mod markets;
+mod orders;
+mod selftest;
+mod simulate_fill;
+mod streaming;
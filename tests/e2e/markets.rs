@@ -0,0 +1,58 @@
+use polybot::cli::commands::fetch_all_markets::{FetchAllMarketsArgs, FetchAllMarketsCommand};
+use serde_json::Value;
+
+use crate::harness::{fixtures, MockExchange, TestEnv};
+
+fn fetch_args(dataset_name: &str) -> FetchAllMarketsArgs {
+    FetchAllMarketsArgs {
+        dataset_name: Some(dataset_name.to_string()),
+        clear_state: false,
+        chunk_size_mb: 100.0,
+        use_gamma: false,
+        cache_resolution: "hours".to_string(),
+        cache_duration: 1,
+        force_refresh: false,
+    }
+}
+
+#[tokio::test]
+async fn fetch_all_markets_follows_cursors_into_a_dataset() {
+    let exchange = MockExchange::start(Vec::new()).await;
+    exchange.mount_markets(&["markets_page_1", "markets_page_2"]).await;
+    let env = TestEnv::read_only();
+
+    FetchAllMarketsCommand::new(fetch_args("e2e_markets"))
+        .execute(&exchange.host(), env.data_paths.clone(), false)
+        .await
+        .unwrap();
+
+    let dataset = env.data_paths.datasets().join("e2e_markets");
+    assert!(dataset.join("dataset.yaml").exists());
+
+    let mut chunks: Vec<_> = std::fs::read_dir(&dataset)
+        .unwrap()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().unwrap().to_string_lossy();
+            name.starts_with("markets_chunk_") && name.ends_with(".json")
+        })
+        .collect();
+    chunks.sort();
+    assert!(!chunks.is_empty());
+
+    let markets: Vec<Value> = chunks
+        .iter()
+        .flat_map(|chunk| {
+            serde_json::from_str::<Vec<Value>>(&std::fs::read_to_string(chunk).unwrap()).unwrap()
+        })
+        .collect();
+    let expected: Vec<Value> = ["markets_page_1", "markets_page_2"]
+        .iter()
+        .flat_map(|page| fixtures::rest(page)["data"].as_array().unwrap().clone())
+        .map(|market| market["condition_id"].clone())
+        .collect();
+    let fetched: Vec<Value> = markets.iter().map(|m| m["condition_id"].clone()).collect();
+    assert_eq!(expected.len(), 3);
+    assert_eq!(fetched, expected);
+}
@@ -0,0 +1,38 @@
+use polybot::core::execution::orders::{OrderManager, OrderSide, OrderStatus};
+
+use crate::harness::{MockExchange, TestEnv, TEST_ADDRESS, YES_TOKEN};
+
+#[tokio::test]
+async fn fetch_orders_signs_the_request_and_converts_the_page() {
+    let exchange = MockExchange::start(Vec::new()).await;
+    exchange.mount_orders("orders").await;
+    let env = TestEnv::with_credentials().await;
+
+    let orders = OrderManager::new()
+        .fetch_orders(&exchange.host(), &env.data_paths, TEST_ADDRESS)
+        .await
+        .unwrap();
+
+    assert_eq!(orders.len(), 2);
+    let open = &orders[0];
+    assert_eq!(open.asset_id, YES_TOKEN);
+    assert_eq!(open.side, OrderSide::Buy);
+    assert_eq!(open.status, OrderStatus::Open);
+    assert_eq!(open.original_size, 50.0);
+    assert_eq!(open.filled_size, 20.0);
+    assert_eq!(open.remaining_size, 30.0);
+    assert_eq!(orders[1].status, OrderStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn fetch_orders_without_credentials_fails_before_calling_the_api() {
+    let exchange = MockExchange::start(Vec::new()).await;
+    exchange.mount_orders("orders").await;
+    let env = TestEnv::read_only();
+
+    let result = OrderManager::new()
+        .fetch_orders(&exchange.host(), &env.data_paths, TEST_ADDRESS)
+        .await;
+
+    assert!(result.unwrap_err().to_string().contains("No credentials found"));
+}
@@ -0,0 +1,43 @@
+use polybot::cli::commands::selftest::{run_selftest, SelftestTarget};
+use std::time::Duration;
+
+use crate::harness::{MockExchange, YES_TOKEN};
+
+fn target(exchange: &MockExchange) -> SelftestTarget {
+    SelftestTarget {
+        host: exchange.host(),
+        ws_url: exchange.ws_url(),
+        token: None,
+        timeout: Duration::from_secs(10),
+        skip_ws: false,
+    }
+}
+
+#[tokio::test]
+async fn selftest_passes_against_the_mock_exchange() {
+    let exchange = MockExchange::with_recording("market_session").await;
+    exchange.mount_markets(&["markets_page_1"]).await;
+    exchange.mount_book(YES_TOKEN, "book_yes").await;
+
+    let steps = run_selftest(&target(&exchange)).await;
+
+    let names: Vec<_> = steps.iter().map(|s| s.name).collect();
+    assert_eq!(names, vec!["Markets", "Order book", "Market feed"]);
+    for step in &steps {
+        assert!(step.passed(), "{} failed: {:?}", step.name, step.outcome);
+    }
+    // The first market accepting orders supplies the token
+    assert_eq!(exchange.subscriptions()[0]["assets_ids"], serde_json::json!([YES_TOKEN]));
+}
+
+#[tokio::test]
+async fn selftest_stops_after_a_failed_step() {
+    let exchange = MockExchange::start(Vec::new()).await;
+    exchange.mount_markets(&["markets_page_1"]).await;
+
+    let steps = run_selftest(&target(&exchange)).await;
+
+    assert_eq!(steps.len(), 2);
+    assert!(steps[0].passed());
+    assert!(!steps[1].passed());
+}
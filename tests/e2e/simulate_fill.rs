@@ -0,0 +1,50 @@
+use polybot::cli::commands::simulate_fill::{load_recording, SimulateFillArgs, SimulateFillCommand};
+use polybot::core::execution::fill_model::{FillModel, FillModelConfig, RestingOrder};
+use polybot::core::types::common::Side;
+use rust_decimal_macros::dec;
+use std::time::Duration;
+
+use crate::harness::{fixtures, TestEnv, YES_TOKEN};
+
+#[tokio::test]
+async fn simulate_fill_replays_a_recorded_session() {
+    let recording = fixtures::ws_recording("market_session");
+    let env = TestEnv::read_only();
+
+    let args = SimulateFillArgs {
+        token: YES_TOKEN.to_string(),
+        side: "buy".to_string(),
+        price: dec!(0.48),
+        size: dec!(20),
+        window: 40,
+        step: 10,
+        recording: Some(recording.clone()),
+        record_secs: 0,
+        trials: true,
+    };
+    SimulateFillCommand::new(args)
+        .execute("http://unused", env.data_paths.clone())
+        .await
+        .unwrap();
+
+    // Behind 100 shares at 0.48: the first trade eats 60 of them, the second
+    // the remaining 40 and then the order
+    let events = load_recording(&recording, YES_TOKEN).unwrap();
+    let order = RestingOrder {
+        side: Side::Buy,
+        price: dec!(0.48),
+        size: dec!(20),
+    };
+    let config = FillModelConfig {
+        window: Duration::from_secs(40),
+        step: Duration::from_secs(10),
+    };
+    let estimate = FillModel::new(events).estimate(&order, &config);
+
+    assert_eq!(estimate.trials.len(), 1);
+    let trial = &estimate.trials[0];
+    assert_eq!(trial.initial_queue_ahead, dec!(100));
+    assert_eq!(trial.filled, dec!(20));
+    assert_eq!(trial.time_to_full_fill, Some(Duration::from_secs(40)));
+    assert_eq!(estimate.fill_probability, 1.0);
+}
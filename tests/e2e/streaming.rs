@@ -0,0 +1,76 @@
+use polybot::core::ws::{parse_message, OrderBook, PolyEvent, WsClient, WsConfig};
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::harness::{MockExchange, NO_TOKEN, YES_TOKEN};
+
+#[tokio::test]
+async fn market_feed_replay_builds_the_recorded_books() {
+    let exchange = MockExchange::with_recording("market_session").await;
+    let config = WsConfig {
+        market_url: exchange.ws_url(),
+        max_reconnection_attempts: 1,
+        ..Default::default()
+    };
+    let client = WsClient::new_market(config).await.unwrap();
+    let mut messages = client.messages();
+    client
+        .subscribe_market(vec![YES_TOKEN.to_string(), NO_TOKEN.to_string()])
+        .unwrap();
+
+    let mut books: HashMap<String, OrderBook> = HashMap::new();
+    let mut last_trade: HashMap<String, rust_decimal::Decimal> = HashMap::new();
+    // The recording ends with the last trade of the No token
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !last_trade.contains_key(NO_TOKEN) {
+            let msg = messages.recv().await.unwrap();
+            for event in parse_message(&msg).unwrap() {
+                match event {
+                    PolyEvent::Book {
+                        asset_id,
+                        market,
+                        timestamp,
+                        bids,
+                        asks,
+                        ..
+                    } => books
+                        .entry(asset_id.clone())
+                        .or_insert_with(|| OrderBook::new(asset_id))
+                        .replace_with_snapshot_no_hash(market, timestamp, bids, asks),
+                    PolyEvent::PriceChange {
+                        asset_id,
+                        side,
+                        price,
+                        size,
+                        ..
+                    } => books
+                        .get_mut(&asset_id)
+                        .expect("price change before snapshot")
+                        .apply_price_change_no_hash(side, price, size),
+                    PolyEvent::LastTradePrice { asset_id, price, .. } => {
+                        last_trade.insert(asset_id, price);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    })
+    .await
+    .expect("recording was not fully replayed");
+    client.disconnect().unwrap();
+
+    let subscriptions = exchange.subscriptions();
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0]["assets_ids"], serde_json::json!([YES_TOKEN, NO_TOKEN]));
+
+    // 0.48 was traded away and removed; 0.49 joined during the session
+    let book = &books[YES_TOKEN];
+    assert_eq!(book.best_bid().unwrap().price, dec!(0.49));
+    assert_eq!(book.best_ask().unwrap().price, dec!(0.52));
+    assert_eq!(book.get_bids().len(), 2);
+    assert_eq!(last_trade[YES_TOKEN], dec!(0.48));
+
+    assert_eq!(books[NO_TOKEN].best_bid().unwrap().price, dec!(0.50));
+    assert_eq!(last_trade[NO_TOKEN], dec!(0.55));
+}